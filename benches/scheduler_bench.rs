@@ -5,6 +5,7 @@
 //! - Query performance (list, filter, lookup)
 //! - Job submission throughput
 //! - Scheduling decision performance
+//! - State persistence cost per submission
 
 use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion, Throughput};
 use gflow::core::job::{DependencyMode, Job, JobBuilder, JobState};
@@ -508,7 +509,11 @@ fn bench_query_by_state(c: &mut Criterion) {
             |b, scheduler| {
                 b.iter(|| {
                     let mut sum = 0u32;
-                    for &job_id in scheduler.job_ids_by_state(JobState::Queued).unwrap_or(&[]) {
+                    for &job_id in scheduler
+                        .job_ids_by_state(JobState::Queued)
+                        .into_iter()
+                        .flatten()
+                    {
                         sum = sum.wrapping_add(job_id);
                     }
                     hint_black_box(sum)
@@ -663,6 +668,8 @@ fn bench_refresh_available_memory(c: &mut Criterion) {
                             }
                         }
                     }
+                    // Running jobs are found through the state index.
+                    scheduler.rebuild_user_jobs_index();
                     scheduler
                 },
                 |mut scheduler| {
@@ -776,6 +783,8 @@ criterion_main!(
     scheduling_flow_benches,
     auto_cancel_benches,
     reservation_benches,
+    mixed_workload_benches,
+    persistence_benches,
 );
 
 // ============================================================================
@@ -979,12 +988,76 @@ fn bench_hold_release_job(c: &mut Criterion) {
     group.finish();
 }
 
+/// Cancel 1k jobs the way the daemon does for one request per job (owner
+/// check under a read lock, then the change under a write lock, every time)
+/// against `POST /jobs/actions`, which takes the write lock once.
+fn bench_bulk_cancel(c: &mut Criterion) {
+    let mut group = c.benchmark_group("state_transitions/bulk_cancel");
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap();
+    let ids: Vec<u32> = (1..=1000).collect();
+    let setup = || {
+        let mut scheduler = create_test_scheduler();
+        populate_scheduler(&mut scheduler, 10_000);
+        tokio::sync::RwLock::new(scheduler)
+    };
+    group.throughput(Throughput::Elements(ids.len() as u64));
+
+    group.bench_function("per_job/1000", |b| {
+        b.iter_batched(
+            setup,
+            |scheduler| {
+                runtime.block_on(async {
+                    for &id in &ids {
+                        hint_black_box(scheduler.read().await.get_job(id));
+                        let mut scheduler = scheduler.write().await;
+                        if scheduler
+                            .check_transition(id, JobState::Cancelled, None)
+                            .is_ok()
+                        {
+                            hint_black_box(scheduler.cancel_job(id, None));
+                        }
+                        hint_black_box(scheduler.take_transitions());
+                    }
+                })
+            },
+            BatchSize::SmallInput,
+        );
+    });
+
+    group.bench_function("batch/1000", |b| {
+        b.iter_batched(
+            setup,
+            |scheduler| {
+                runtime.block_on(async {
+                    let mut scheduler = scheduler.write().await;
+                    for &id in &ids {
+                        hint_black_box(scheduler.get_job(id));
+                        if scheduler
+                            .check_transition(id, JobState::Cancelled, None)
+                            .is_ok()
+                        {
+                            hint_black_box(scheduler.cancel_job(id, None));
+                        }
+                    }
+                    hint_black_box(scheduler.take_transitions());
+                })
+            },
+            BatchSize::SmallInput,
+        );
+    });
+
+    group.finish();
+}
+
 criterion_group!(
     state_transition_benches,
     bench_state_transitions,
     bench_fail_job,
     bench_cancel_job,
     bench_hold_release_job,
+    bench_bulk_cancel,
 );
 
 // ============================================================================
@@ -1511,3 +1584,113 @@ criterion_group!(
     bench_update_reservation_statuses,
     bench_scheduling_with_reservations,
 );
+
+// ============================================================================
+// Mixed Workload Benchmarks
+// ============================================================================
+
+/// Submissions, starts, finishes and cancellations interleaved on a busy
+/// queue, the way a daemon sees them. Every mutation keeps the user, state
+/// and group indexes up to date, so none of them should cost O(queue).
+fn bench_mixed_workload(c: &mut Criterion) {
+    let mut group = c.benchmark_group("mixed_workload/submit_finish_cancel");
+    group.sample_size(10);
+    let rounds = 1_000u32;
+
+    for size in [10_000, 50_000, 100_000] {
+        group.throughput(Throughput::Elements(rounds as u64 * 4));
+        group.bench_with_input(BenchmarkId::new("jobs", size), &size, |b, &size| {
+            b.iter_batched(
+                || {
+                    let mut scheduler = create_test_scheduler();
+                    populate_scheduler_with_groups(&mut scheduler, size, 100);
+                    let jobs: Vec<Job> = (0..rounds)
+                        .map(|i| create_test_job(size as u32 + i + 1))
+                        .collect();
+                    (scheduler, jobs)
+                },
+                |(mut scheduler, jobs)| {
+                    for (i, job) in (0..rounds).zip(jobs) {
+                        hint_black_box(scheduler.submit_job(job));
+                        // Work through the middle of the queue.
+                        let started = size as u32 / 2 + 2 * i + 1;
+                        hint_black_box(scheduler.try_transition(started, JobState::Running, None))
+                            .ok();
+                        hint_black_box(scheduler.finish_job(started));
+                        hint_black_box(scheduler.cancel_job(started + 1, None));
+                    }
+                    // Dropped outside the measurement, which would otherwise
+                    // be dominated by freeing the whole queue.
+                    scheduler
+                },
+                BatchSize::LargeInput,
+            );
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(mixed_workload_benches, bench_mixed_workload);
+
+// ============================================================================
+// State Persistence Benchmarks
+// ============================================================================
+
+/// Write the scheduler the way gflowd does: MessagePack to a temp file, then rename.
+fn write_state(scheduler: &Scheduler, dir: &std::path::Path) {
+    let bytes = rmp_serde::to_vec_named(scheduler).unwrap();
+    let tmp_path = dir.join("state.msgpack.tmp");
+    std::fs::write(&tmp_path, &bytes).unwrap();
+    std::fs::rename(&tmp_path, dir.join("state.msgpack")).unwrap();
+}
+
+/// Submission throughput when every submission writes the state (the old
+/// behaviour) versus one write for the whole batch (debounced persistence).
+fn bench_submission_persistence(c: &mut Criterion) {
+    let mut group = c.benchmark_group("persistence/submission");
+    group.sample_size(10);
+    let dir = tempfile::tempdir().unwrap();
+    let submissions = 100u32;
+
+    for existing_jobs in [1_000, 10_000] {
+        group.throughput(Throughput::Elements(submissions as u64));
+        for per_submit in [true, false] {
+            let name = if per_submit {
+                "save_per_submit"
+            } else {
+                "save_per_batch"
+            };
+            group.bench_with_input(
+                BenchmarkId::new(name, existing_jobs),
+                &existing_jobs,
+                |b, &existing_jobs| {
+                    b.iter_batched(
+                        || {
+                            let mut scheduler = create_test_scheduler();
+                            populate_scheduler(&mut scheduler, existing_jobs);
+                            scheduler
+                        },
+                        |mut scheduler| {
+                            for i in 0..submissions {
+                                let job = create_test_job(existing_jobs as u32 + i + 1);
+                                hint_black_box(scheduler.submit_job(job));
+                                if per_submit {
+                                    write_state(&scheduler, dir.path());
+                                }
+                            }
+                            if !per_submit {
+                                write_state(&scheduler, dir.path());
+                            }
+                        },
+                        BatchSize::LargeInput,
+                    );
+                },
+            );
+        }
+    }
+
+    group.finish();
+}
+
+criterion_group!(persistence_benches, bench_submission_persistence);
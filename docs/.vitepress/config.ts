@@ -130,6 +130,10 @@ export default defineConfig({
                                 text: "gstats Reference",
                                 link: "/reference/gstats-reference",
                             },
+                            {
+                                text: "gtop Reference",
+                                link: "/reference/gtop-reference",
+                            },
                         ],
                     },
                 ],
@@ -268,6 +272,10 @@ export default defineConfig({
                                 text: "gstats 参考",
                                 link: "/zh-CN/reference/gstats-reference",
                             },
+                            {
+                                text: "gtop 参考",
+                                link: "/zh-CN/reference/gtop-reference",
+                            },
                         ],
                     },
                 ],
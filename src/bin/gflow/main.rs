@@ -8,7 +8,7 @@ async fn main() -> ExitCode {
     match real_main().await {
         Ok(()) => ExitCode::SUCCESS,
         Err(err) => {
-            eprintln!("{err:?}");
+            gflow::client::print_error(&err);
             ExitCode::from(1)
         }
     }
@@ -16,7 +16,16 @@ async fn main() -> ExitCode {
 
 async fn real_main() -> anyhow::Result<()> {
     let mut it = std::env::args_os();
-    let _program = it.next();
+    let program = it.next();
+
+    // Called through a link named after a tool, e.g. `gbatch -> gflow`.
+    if let Some(tool) = program
+        .as_deref()
+        .and_then(gflow::multicall::tool_for_program)
+    {
+        let argv = argv_with_program_name(OsString::from(tool), it.collect());
+        return gflow::multicall::dispatch(argv).await;
+    }
 
     let Some(first) = it.next() else {
         gflow::multicall::print_top_level_help();
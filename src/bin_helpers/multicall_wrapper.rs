@@ -68,11 +68,13 @@ fn dispatch_in_process(command_name: &str, rest: Vec<OsString>) -> Result<ExitCo
         .build()
         .map_err(|e| format!("Failed to build tokio runtime: {e}"))?;
 
-    runtime
-        .block_on(gflow::multicall::dispatch(argv))
-        .map_err(|e| format!("{e:?}"))?;
-
-    Ok(ExitCode::SUCCESS)
+    match runtime.block_on(gflow::multicall::dispatch(argv)) {
+        Ok(()) => Ok(ExitCode::SUCCESS),
+        Err(err) => {
+            gflow::client::print_error(&err);
+            Ok(ExitCode::from(1))
+        }
+    }
 }
 
 fn exec_binary(gflow_path: PathBuf, args: Vec<OsString>) -> Result<ExitCode, String> {
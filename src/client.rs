@@ -1,28 +1,128 @@
-use crate::core::info::{IgnoredGpuProcess, SchedulerInfo};
-use crate::core::job::{DependencyMode, Job, JobNotifications};
+use crate::config::ListenAddr;
+use crate::core::api_error::{ApiError, ApiErrorKind};
+use crate::core::info::{
+    ConfigReloadReport, IgnoredGpuProcess, SchedulerInfo, SchedulerStats, StateRestoreReport,
+    VersionInfo, WebhookTargetStatus,
+};
+use crate::core::job::{
+    DependencyMode, Job, JobArtifact, JobGroup, JobNotifications, JobSignal, JobState,
+};
+use crate::core::report::{ReportQuery, UsageReport};
+use crate::core::runtime_stats::{RuntimeStatsQuery, RuntimeSummary};
+use crate::core::scheduler::StartEstimate;
+use crate::core::version::{Compatibility, Version, CLIENT_VERSION_HEADER, SKIP_VERSION_CHECK_ENV};
 use anyhow::{anyhow, Context};
+use reqwest::header::{HeaderMap, HeaderValue, AUTHORIZATION, ETAG, IF_NONE_MATCH};
 use reqwest::{Client as ReqwestClient, StatusCode};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use std::path::PathBuf;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use std::time::Duration;
 
-/// Checks if an error is a connection error and returns a user-friendly message
-fn connection_error_context(err: reqwest::Error) -> anyhow::Error {
-    if err.is_connect() {
-        anyhow!(
-            "Could not connect to gflowd server. Is the server running?\n\
-             Hint: Start the server with 'gflowd up'"
-        )
-    } else {
-        err.into()
+mod cache;
+mod errors;
+mod scope;
+
+use cache::{CachedResponse, ResponseCache};
+pub use errors::{print_error, render_error, ClientError};
+pub use scope::UserScope;
+
+/// The daemon refused a request, as opposed to not answering at all.
+///
+/// Find it with `error.downcast_ref::<DaemonRejected>()` and match on
+/// `rejected.error.kind` to tell e.g. a missing job from an invalid transition.
+#[derive(Debug)]
+pub struct DaemonRejected {
+    /// What was attempted, e.g. `cancel job`
+    pub action: String,
+    pub status: StatusCode,
+    pub error: ApiError,
+    /// The daemon's `X-Request-Id` for the request, to quote in bug reports
+    pub request_id: Option<String>,
+}
+
+impl std::fmt::Display for DaemonRejected {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.error.message.trim().is_empty() {
+            write!(f, "Failed to {}: {}", self.action, self.status)
+        } else {
+            write!(f, "Failed to {}: {}", self.action, self.error.message)
+        }
     }
 }
 
+impl std::error::Error for DaemonRejected {}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct JobSubmitResponse {
     pub id: u32,
     pub run_name: String,
+    /// When the job is expected to start, as of submission.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub estimate: Option<StartEstimate>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub group_id: Option<uuid::Uuid>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub priority: Option<u8>,
+    /// The job's dependencies and their states, as of submission.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub dependencies: Vec<SubmittedDependency>,
+    /// 1-based place among queued jobs whose dependencies are met; `None`
+    /// while the job waits on dependencies, or from daemons that predate it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub queue_position: Option<usize>,
+    /// GPUs free at submission time; `None` from daemons that predate it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub available_gpus: Option<Vec<u32>>,
+    /// Another active job submitted with the same `--name`, outside this
+    /// job's group.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub run_name_shared_with: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubmittedDependency {
+    pub id: u32,
+    /// `None` if the dependency was purged before the reply was built.
+    pub state: Option<JobState>,
+}
+
+/// Filters the daemon applies in [`Client::find_jobs`]; `None` matches every job.
+#[derive(Debug, Clone, Default)]
+pub struct JobFilter {
+    /// Comma-separated states, e.g. `Queued,Running`
+    pub states: Option<String>,
+    /// Comma-separated submitters
+    pub user: Option<String>,
+    /// Only jobs submitted at or after this Unix timestamp
+    pub created_after: Option<i64>,
+    /// Terms that must all appear in the command, script path, run name or parameter values
+    pub search: Option<String>,
+    /// Comma-separated glob patterns, see [`crate::utils::name_glob`]
+    pub name_glob: Option<String>,
+    /// Comma-separated tags, all of which a job must have
+    pub tag: Option<String>,
+    /// `asc` or `desc`; the daemon lists in job ID order, newest first with `search`
+    pub order: Option<String>,
+}
+
+impl JobFilter {
+    fn query_params(&self) -> Vec<(&'static str, String)> {
+        let params = [
+            ("state", self.states.clone()),
+            ("user", self.user.clone()),
+            ("created_after", self.created_after.map(|t| t.to_string())),
+            ("search", self.search.clone()),
+            ("name_glob", self.name_glob.clone()),
+            ("tag", self.tag.clone()),
+            ("order", self.order.clone()),
+        ];
+        params
+            .into_iter()
+            .filter_map(|(key, value)| Some((key, value?)))
+            .collect()
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -73,6 +173,75 @@ pub struct UpdateJobResponse {
     pub updated_fields: Vec<String>,
 }
 
+/// A state change `POST /jobs/actions` applies to many jobs at once.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobAction {
+    Cancel,
+    Hold,
+    Release,
+    Finish,
+    Fail,
+}
+
+impl JobAction {
+    /// The state the action moves a job to.
+    pub fn target_state(self) -> JobState {
+        match self {
+            Self::Cancel => JobState::Cancelled,
+            Self::Hold => JobState::Hold,
+            Self::Release => JobState::Queued,
+            Self::Finish => JobState::Finished,
+            Self::Fail => JobState::Failed,
+        }
+    }
+
+    /// The verb for messages, e.g. `cancel`.
+    pub fn verb(self) -> &'static str {
+        match self {
+            Self::Cancel => "cancel",
+            Self::Hold => "hold",
+            Self::Release => "release",
+            Self::Finish => "finish",
+            Self::Fail => "fail",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobActionRequest {
+    pub op: JobAction,
+    pub ids: Vec<u32>,
+}
+
+/// What became of one job of a [`JobActionRequest`]. A job already in the
+/// target state counts as changed, as with the single-job endpoints.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobActionResult {
+    pub id: u32,
+    /// Why the job was left alone, e.g. `job_not_found` or `invalid_transition`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<ApiError>,
+}
+
+impl JobActionResult {
+    /// The job's ID, or why it was left alone as a [`DaemonRejected`] error,
+    /// as the single-job request for `action` would have failed.
+    pub fn into_result(self, action: JobAction) -> anyhow::Result<u32> {
+        let Some(error) = self.error else {
+            return Ok(self.id);
+        };
+        Err(DaemonRejected {
+            action: format!("{} job", action.verb()),
+            status: StatusCode::from_u16(error.status())
+                .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR),
+            error,
+            request_id: None,
+        }
+        .into())
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TopJob {
     pub id: u32,
@@ -102,56 +271,443 @@ pub struct UsageStats {
     pub top_jobs: Vec<TopJob>,
 }
 
+/// Final-state events a webhook attached to a job can be sent for.
+pub const JOB_WEBHOOK_EVENTS: [&str; 4] = [
+    "job_completed",
+    "job_failed",
+    "job_cancelled",
+    "job_timeout",
+];
+
+/// Body of `POST /jobs/{id}/notifications`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobWebhookRequest {
+    pub url: String,
+    /// Some of [`JOB_WEBHOOK_EVENTS`]; empty means all of them
+    #[serde(default)]
+    pub events: Vec<String>,
+    /// Who asks; an authenticated user takes precedence
+    pub user: String,
+}
+
+/// Body of `POST /jobs/{id}/artifacts`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobArtifactRequest {
+    /// Absolute, or relative to the job's run directory
+    pub path: PathBuf,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+}
+
+/// Body of `POST /jobs/{id}/tags`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct JobTagsRequest {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub add: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub remove: Vec<String>,
+}
+
+/// Body of `POST /jobs/{id}/signal`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobSignalRequest {
+    pub signal: JobSignal,
+    /// Who asks; an authenticated user takes precedence
+    pub user: String,
+}
+
+/// Answer to `DELETE /jobs/{id}/notifications`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RemovedJobWebhooks {
+    pub removed: usize,
+}
+
+/// Answer to `DELETE /jobs/{id}/dir`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RemovedJobDir {
+    pub path: PathBuf,
+    /// Size of the files removed
+    pub bytes: u64,
+}
+
+/// Answer to `GET /log-level` and `POST /log-level`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogLevel {
+    /// Default level of the daemon's logs
+    pub level: String,
+    /// Default level it started with, from `-v`/`-q` or `daemon.logging.level`
+    pub configured: String,
+    /// The whole filter, with the levels of single modules
+    pub filter: String,
+}
+
+/// Body of `POST /log-level`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LogLevelRequest {
+    /// New default level; unset goes back to the configured one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub level: Option<String>,
+}
+
+/// Answer to `GET /rate-limits`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RateLimits {
+    /// `[rate_limits]` of the running daemon
+    pub config: crate::config::RateLimitsConfig,
+    /// Per-user limits set with `gctl rate-limit set`, until the daemon restarts
+    #[serde(default)]
+    pub overrides: BTreeMap<String, crate::config::UserRateLimit>,
+}
+
+/// Header carrying a per-submission key, so the daemon can recognize a
+/// retried `POST /jobs` and return the original result instead of a duplicate.
+pub const IDEMPOTENCY_KEY_HEADER: &str = "idempotency-key";
+
+/// Header on `GET /jobs` with the number of jobs the filters match, before
+/// `limit` and `offset`.
+pub const TOTAL_COUNT_HEADER: &str = "x-total-count";
+
+/// Jobs per request when [`Client::find_jobs`] pages through a listing.
+const JOB_PAGE_SIZE: usize = 1000;
+
+/// Most job IDs one `POST /jobs/actions` request may name.
+pub const MAX_JOB_ACTION_IDS: usize = 1000;
+
+/// Which failed requests [`Client::send`] may retry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Retry {
+    Never,
+    /// Reads, and submissions (deduplicated by [`IDEMPOTENCY_KEY_HEADER`]).
+    Always,
+    /// finish/fail/cancel/hold/release, only with `client.retry_state_changes`.
+    StateChange,
+}
+
+#[derive(Debug, Clone)]
+struct RetryPolicy {
+    max_retries: u32,
+    base_delay: Duration,
+    request_timeout: Duration,
+    retry_state_changes: bool,
+}
+
+impl RetryPolicy {
+    fn from_config(config: &crate::config::ClientConfig) -> Self {
+        Self {
+            max_retries: config.max_retries,
+            base_delay: Duration::from_millis(config.retry_base_ms),
+            request_timeout: Duration::from_secs(config.request_timeout_secs.max(1)),
+            retry_state_changes: config.retry_state_changes,
+        }
+    }
+
+    fn max_retries(&self, retry: Retry) -> u32 {
+        match retry {
+            Retry::Always => self.max_retries,
+            Retry::StateChange if self.retry_state_changes => self.max_retries,
+            _ => 0,
+        }
+    }
+
+    /// Exponential backoff with up to 50% random jitter, capped at 10s.
+    fn delay(&self, attempt: u32) -> Duration {
+        let delay = self
+            .base_delay
+            .saturating_mul(1 << attempt.min(16))
+            .min(Duration::from_secs(10));
+        let jitter = getrandom::u64().unwrap_or(0) % (delay.as_millis() as u64 / 2 + 1);
+        delay + Duration::from_millis(jitter)
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Client {
     client: ReqwestClient,
     base_url: String,
+    /// Where the daemon is, for error messages: `base_url` or the Unix socket
+    daemon_url: String,
+    retry: RetryPolicy,
+    responses: Arc<ResponseCache>,
+    /// Whether to check the daemon's version before the first request
+    check_version: bool,
+    /// The daemon's `/version`, `None` if it predates the endpoint
+    daemon_version: Arc<tokio::sync::OnceCell<Option<VersionInfo>>>,
 }
 
 impl Client {
+    /// Build a client for the daemon in `config`, authenticating with the
+    /// first of `GFLOW_TOKEN`, `daemon.token` or `GFLOW_JOB_TOKEN` that is set.
     pub fn build(config: &crate::config::Config) -> anyhow::Result<Self> {
+        let env_token = |name: &str| std::env::var(name).ok().filter(|token| !token.is_empty());
+        let token = env_token("GFLOW_TOKEN")
+            .or_else(|| config.daemon.token.clone())
+            // Set by the daemon inside job sessions so jobs can report their own outcome.
+            .or_else(|| env_token("GFLOW_JOB_TOKEN"));
+        Self::build_with_token(config, token.as_deref())
+    }
+
+    /// Like [`Client::build`] but with an explicit API token.
+    pub fn build_with_token(
+        config: &crate::config::Config,
+        token: Option<&str>,
+    ) -> anyhow::Result<Self> {
         crate::tls::ensure_rustls_provider_installed();
-        let host = &config.daemon.host;
-        let port = config.daemon.port;
-        let base_url = format!("http://{host}:{port}");
-        let client = ReqwestClient::new();
-        Ok(Self { client, base_url })
+        let mut headers = HeaderMap::from_iter([(
+            reqwest::header::HeaderName::from_static(CLIENT_VERSION_HEADER),
+            HeaderValue::from_static(env!("CARGO_PKG_VERSION")),
+        )]);
+        if let Some(token) = token {
+            let mut value =
+                HeaderValue::from_str(&format!("Bearer {token}")).context("Invalid API token")?;
+            value.set_sensitive(true);
+            headers.insert(AUTHORIZATION, value);
+        }
+        let mut builder = ReqwestClient::builder().default_headers(headers);
+
+        let mut daemon_url = None;
+        let base_url = match config.daemon.listen_addr() {
+            ListenAddr::Tcp { host, port } if config.daemon.client_uses_tls() => {
+                // On the daemon host, trust its own (possibly self-signed) certificate.
+                let ca = [
+                    ("tls_ca", &config.daemon.tls_ca),
+                    ("tls_cert", &config.daemon.tls_cert),
+                ]
+                .into_iter()
+                .find_map(|(key, path)| Some((key, path.as_ref()?)));
+                if let Some((key, ca_path)) = ca {
+                    let pem = std::fs::read(ca_path).with_context(|| {
+                        format!("Failed to read daemon.{key} {}", ca_path.display())
+                    })?;
+                    for cert in reqwest::Certificate::from_pem_bundle(&pem).with_context(|| {
+                        format!("Invalid PEM in daemon.{key} {}", ca_path.display())
+                    })? {
+                        builder = builder.add_root_certificate(cert);
+                    }
+                }
+                if config.daemon.insecure_skip_verify {
+                    builder = builder.danger_accept_invalid_certs(true);
+                }
+                format!("https://{host}:{port}")
+            }
+            ListenAddr::Tcp { host, port } => format!("http://{host}:{port}"),
+            ListenAddr::Unix(path) => {
+                daemon_url = Some(format!("unix:{}", path.display()));
+                builder = builder.unix_socket(path);
+                // The host part is ignored when connecting over a Unix socket.
+                "http://localhost".to_string()
+            }
+        };
+        // One pooled client per `Client`; clones share its connections.
+        let client = builder.build().context("Failed to build HTTP client")?;
+        Ok(Self {
+            client,
+            daemon_url: daemon_url.unwrap_or_else(|| base_url.clone()),
+            base_url,
+            retry: RetryPolicy::from_config(&config.client),
+            responses: Arc::default(),
+            check_version: std::env::var(SKIP_VERSION_CHECK_ENV)
+                .map_or(true, |skip| skip.is_empty() || skip == "0"),
+            daemon_version: Arc::default(),
+        })
+    }
+
+    /// Skip [`Client::check_version`], for callers that compare versions
+    /// themselves.
+    pub fn without_version_check(mut self) -> Self {
+        self.check_version = false;
+        self
+    }
+
+    /// Ask the daemon for its version once, warn if it is a different minor
+    /// version, and refuse to go on if it declares this client too old.
+    ///
+    /// An unreachable daemon is left to the request itself to report.
+    async fn check_version(&self) -> anyhow::Result<()> {
+        if !self.check_version {
+            return Ok(());
+        }
+        let daemon = self
+            .daemon_version
+            .get_or_try_init(|| async {
+                let daemon = self.get_version().await?;
+                Self::warn_about_version(daemon.as_ref());
+                anyhow::Ok(daemon)
+            })
+            .await;
+        match daemon.map(|daemon| daemon.as_ref().map(VersionInfo::compatibility)) {
+            Ok(Some(Compatibility::ClientTooOld { client, minimum })) => {
+                Err(ClientError::ClientTooOld { client, minimum }.into())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn warn_about_version(daemon: Option<&VersionInfo>) {
+        let client = Version::current();
+        match daemon.map(VersionInfo::compatibility) {
+            Some(Compatibility::Skewed { daemon, .. }) => {
+                crate::utils::output::print_warning(format_args!(
+                    "gflowd is {daemon} but this client is {client}; upgrade the older one if requests fail"
+                ))
+            }
+            None => crate::utils::output::print_warning(format_args!(
+                "gflowd is older than this client ({client}); upgrade it if requests fail"
+            )),
+            _ => {}
+        }
+    }
+
+    /// Send `request` after [`Client::check_version`].
+    async fn send(
+        &self,
+        request: reqwest::RequestBuilder,
+        retry: Retry,
+    ) -> anyhow::Result<reqwest::Response> {
+        self.check_version().await?;
+        self.send_unchecked(request, retry).await
+    }
+
+    /// Send `request`, retrying connection failures with exponential backoff
+    /// as allowed by `retry`. All attempts together are bounded by
+    /// `client.request_timeout_secs`.
+    async fn send_unchecked(
+        &self,
+        request: reqwest::RequestBuilder,
+        retry: Retry,
+    ) -> anyhow::Result<reqwest::Response> {
+        let deadline = std::time::Instant::now() + self.retry.request_timeout;
+        let max_retries = self.retry.max_retries(retry);
+        let mut attempt = 0;
+        loop {
+            let remaining = deadline.saturating_duration_since(std::time::Instant::now());
+            let Some(this_attempt) = request.try_clone() else {
+                // Streaming bodies cannot be replayed.
+                return request
+                    .timeout(remaining)
+                    .send()
+                    .await
+                    .map_err(|error| self.connection_error(error));
+            };
+            let error = match this_attempt.timeout(remaining).send().await {
+                Ok(response) => return Ok(response),
+                Err(error) => error,
+            };
+
+            let delay = self.retry.delay(attempt);
+            if attempt >= max_retries || !error.is_connect() || delay >= remaining {
+                return Err(self.connection_error(error));
+            }
+            attempt += 1;
+            tracing::debug!(
+                attempt,
+                max_retries,
+                delay_ms = delay.as_millis() as u64,
+                error = %error,
+                "Could not reach gflowd; retrying"
+            );
+            tokio::time::sleep(delay).await;
+        }
+    }
+
+    /// GET `request` with the `ETag` of the last response from the same URL,
+    /// and reuse that response's body if the daemon answers it has not
+    /// changed.
+    async fn get_polled(
+        &self,
+        request: reqwest::RequestBuilder,
+        action: &str,
+    ) -> anyhow::Result<CachedResponse> {
+        let (client, request) = request.build_split();
+        let request = request.context("Failed to build request")?;
+        let url = request.url().to_string();
+        let cached = self.responses.get(&url);
+        let mut request = reqwest::RequestBuilder::from_parts(client, request);
+        if let Some(cached) = &cached {
+            request = request.header(IF_NONE_MATCH, &cached.etag);
+        }
+
+        let response = self.send(request, Retry::Always).await?;
+        if let (StatusCode::NOT_MODIFIED, Some(cached)) = (response.status(), cached) {
+            return Ok(cached);
+        }
+        if !response.status().is_success() {
+            return Err(Self::rejection(response, action).await);
+        }
+        let header = |name| {
+            response
+                .headers()
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string)
+        };
+        let etag = header(ETAG);
+        let total = header(reqwest::header::HeaderName::from_static(TOTAL_COUNT_HEADER))
+            .and_then(|total| total.parse().ok());
+        let fresh = CachedResponse {
+            etag: etag.clone().unwrap_or_default(),
+            body: Arc::from(response.text().await?),
+            total,
+        };
+        if etag.is_some() {
+            self.responses.insert(url, fresh.clone());
+        }
+        Ok(fresh)
+    }
+
+    fn connection_error(&self, error: reqwest::Error) -> anyhow::Error {
+        if error.is_connect() {
+            ClientError::ConnectionRefused {
+                url: self.daemon_url.clone(),
+            }
+            .into()
+        } else if error.is_timeout() {
+            ClientError::Timeout.into()
+        } else {
+            error.into()
+        }
     }
 
-    /// Helper to extract error message from response
-    async fn extract_error_message(response: reqwest::Response) -> String {
-        let error_body = response
+    /// The error for a response that failed while trying to `action`.
+    async fn rejection(response: reqwest::Response, action: impl Into<String>) -> anyhow::Error {
+        let status = response.status();
+        let request_id = response
+            .headers()
+            .get("x-request-id")
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_owned);
+        let body = response
             .text()
             .await
             .unwrap_or_else(|_| String::from("Unknown error"));
 
-        // Try to parse as JSON with error field
-        if let Ok(json_error) = serde_json::from_str::<serde_json::Value>(&error_body) {
-            if let Some(error_msg) = json_error.get("error").and_then(|e| e.as_str()) {
-                return error_msg.to_string();
-            }
+        let error = serde_json::from_str::<ApiError>(&body).unwrap_or_else(|_| {
+            // Daemons older than error codes only send `{"error": "..."}`,
+            // and some failures have no JSON body at all.
+            let message = serde_json::from_str::<serde_json::Value>(&body)
+                .ok()
+                .and_then(|json| {
+                    let message = json.get("message").or_else(|| json.get("error"))?;
+                    message.as_str().map(str::to_owned)
+                })
+                .unwrap_or(body);
+            ApiError::new(ApiErrorKind::Unknown, message)
+        });
+        DaemonRejected {
+            action: action.into(),
+            status,
+            error,
+            request_id,
         }
-
-        error_body
+        .into()
     }
 
     async fn post_expect_success(&self, path: String, action: &str) -> anyhow::Result<()> {
         let response = self
-            .client
-            .post(path)
-            .send()
-            .await
-            .map_err(connection_error_context)?;
+            .send(self.client.post(path), Retry::StateChange)
+            .await?;
 
         if !response.status().is_success() {
-            let status = response.status();
-            let error_msg = Self::extract_error_message(response).await;
-            let detail = if error_msg.trim().is_empty() {
-                status.to_string()
-            } else {
-                format!("{status}: {error_msg}")
-            };
-            return Err(anyhow!("Failed to {action}: {detail}"));
+            return Err(Self::rejection(response, action).await);
         }
 
         Ok(())
@@ -162,16 +718,7 @@ impl Client {
     /// If no parameters are provided, returns jobs from memory (active jobs only).
     /// If parameters are provided, queries from database with pagination support.
     pub async fn list_jobs(&self) -> anyhow::Result<Vec<Job>> {
-        let jobs = self
-            .client
-            .get(format!("{}/jobs", self.base_url))
-            .send()
-            .await
-            .map_err(connection_error_context)?
-            .json::<Vec<Job>>()
-            .await
-            .context("Failed to parse jobs from response")?;
-        Ok(jobs)
+        self.query_jobs(Vec::new()).await
     }
 
     /// List jobs with query parameters for database queries.
@@ -193,8 +740,6 @@ impl Client {
         created_after: Option<i64>,
         order: Option<String>,
     ) -> anyhow::Result<Vec<Job>> {
-        let mut request = self.client.get(format!("{}/jobs", self.base_url));
-
         // Add query parameters if provided
         let mut params = vec![];
         if let Some(s) = states {
@@ -216,33 +761,93 @@ impl Client {
             params.push(("order", order));
         }
 
+        self.query_jobs(params).await
+    }
+
+    /// Jobs matching `filter`, in job ID order or newest first with `filter.search`.
+    ///
+    /// Fetched a page at a time so a long history is not one giant response.
+    /// A job that shifts into an earlier page between requests is listed once.
+    pub async fn find_jobs(&self, filter: &JobFilter) -> anyhow::Result<Vec<Job>> {
+        let mut jobs: Vec<Job> = Vec::new();
+        let mut seen = std::collections::HashSet::new();
+        let mut offset = 0;
+        loop {
+            let (page, total) = self.find_jobs_page(filter, JOB_PAGE_SIZE, offset).await?;
+            let fetched = page.len();
+            jobs.extend(page.into_iter().filter(|job| seen.insert(job.id)));
+            offset += fetched;
+            if fetched < JOB_PAGE_SIZE || offset >= total {
+                return Ok(jobs);
+            }
+        }
+    }
+
+    /// Up to `limit` of the jobs matching `filter`, skipping the first
+    /// `offset`, and how many match in all.
+    ///
+    /// Daemons that predate [`TOTAL_COUNT_HEADER`] are asked for every match
+    /// instead, and the page is cut from that.
+    pub async fn find_jobs_page(
+        &self,
+        filter: &JobFilter,
+        limit: usize,
+        offset: usize,
+    ) -> anyhow::Result<(Vec<Job>, usize)> {
+        let mut params = filter.query_params();
+        params.push(("limit", limit.to_string()));
+        params.push(("offset", offset.to_string()));
+        let (jobs, total) = self.query_jobs_page(params).await?;
+        if let Some(total) = total {
+            return Ok((jobs, total));
+        }
+
+        let (all, _) = self.query_jobs_page(filter.query_params()).await?;
+        let total = all.len();
+        Ok((all.into_iter().skip(offset).take(limit).collect(), total))
+    }
+
+    async fn query_jobs(&self, params: Vec<(&str, String)>) -> anyhow::Result<Vec<Job>> {
+        Ok(self.query_jobs_page(params).await?.0)
+    }
+
+    /// The jobs in the response, and the total from [`TOTAL_COUNT_HEADER`]
+    /// or a paginated body if the daemon sent one.
+    async fn query_jobs_page(
+        &self,
+        params: Vec<(&str, String)>,
+    ) -> anyhow::Result<(Vec<Job>, Option<usize>)> {
+        let mut request = self.client.get(format!("{}/jobs", self.base_url));
         if !params.is_empty() {
             request = request.query(&params);
         }
 
-        let response = request.send().await.map_err(connection_error_context)?;
+        let response = self.get_polled(request, "list jobs").await?;
+        let total = response.total;
 
         // Handle both direct Vec<Job> and paginated response
-        let response_text = response.text().await?;
+        let response_text = &*response.body;
 
         // Try to parse as PaginatedJobsResponse first
-        if let Ok(paginated) = serde_json::from_str::<PaginatedJobsResponse>(&response_text) {
-            Ok(paginated.jobs)
+        if let Ok(paginated) = serde_json::from_str::<PaginatedJobsResponse>(response_text) {
+            Ok((paginated.jobs, total.or(Some(paginated.total))))
         } else {
             // Fall back to direct Vec<Job> for backward compatibility
-            serde_json::from_str::<Vec<Job>>(&response_text)
-                .context("Failed to parse jobs from response")
+            let jobs = serde_json::from_str::<Vec<Job>>(response_text)
+                .context("Failed to parse jobs from response")?;
+            Ok((jobs, total))
         }
     }
 
     pub async fn get_job(&self, job_id: u32) -> anyhow::Result<Option<Job>> {
         tracing::debug!("Getting job {job_id}");
         let response = self
-            .client
-            .get(format!("{}/jobs/{}", self.base_url, job_id))
-            .send()
-            .await
-            .map_err(connection_error_context)?;
+            .send(
+                self.client
+                    .get(format!("{}/jobs/{}", self.base_url, job_id)),
+                Retry::Always,
+            )
+            .await?;
 
         if response.status() == StatusCode::NOT_FOUND {
             return Ok(None);
@@ -258,17 +863,18 @@ impl Client {
     pub async fn add_job(&self, job: Job) -> anyhow::Result<JobSubmitResponse> {
         tracing::debug!("Adding job: {job:?}");
         let response = self
-            .client
-            .post(format!("{}/jobs", self.base_url))
-            .json(&job)
-            .send()
-            .await
-            .map_err(connection_error_context)?;
+            .send(
+                self.client
+                    .post(format!("{}/jobs", self.base_url))
+                    .header(IDEMPOTENCY_KEY_HEADER, uuid::Uuid::new_v4().to_string())
+                    .json(&job),
+                Retry::Always,
+            )
+            .await?;
 
         // Check if the response is successful
         if !response.status().is_success() {
-            let error_msg = Self::extract_error_message(response).await;
-            return Err(anyhow::anyhow!("Failed to add job: {}", error_msg));
+            return Err(Self::rejection(response, "add job").await);
         }
 
         let job_response: JobSubmitResponse = response
@@ -286,17 +892,18 @@ impl Client {
 
         tracing::debug!("Adding {} jobs in batch", jobs.len());
         let response = self
-            .client
-            .post(format!("{}/jobs/batch", self.base_url))
-            .json(&jobs)
-            .send()
-            .await
-            .map_err(connection_error_context)?;
+            .send(
+                self.client
+                    .post(format!("{}/jobs/batch", self.base_url))
+                    .header(IDEMPOTENCY_KEY_HEADER, uuid::Uuid::new_v4().to_string())
+                    .json(&jobs),
+                Retry::Always,
+            )
+            .await?;
 
         // Check if the response is successful
         if !response.status().is_success() {
-            let error_msg = Self::extract_error_message(response).await;
-            return Err(anyhow::anyhow!("Failed to add batch jobs: {}", error_msg));
+            return Err(Self::rejection(response, "add batch jobs").await);
         }
 
         let job_responses: Vec<JobSubmitResponse> = response
@@ -351,112 +958,499 @@ impl Client {
         .await
     }
 
-    pub async fn update_job(
+    /// Send `signal` to the processes of running job `job_id`, which stays
+    /// running as far as the daemon is concerned.
+    pub async fn signal_job(&self, job_id: u32, signal: JobSignal) -> anyhow::Result<()> {
+        tracing::debug!("Sending {signal} to job {job_id}");
+        let request = JobSignalRequest {
+            signal,
+            user: crate::platform::get_current_username(),
+        };
+        let response = self
+            .send(
+                self.client
+                    .post(format!("{}/jobs/{}/signal", self.base_url, job_id))
+                    .json(&request),
+                // A retry could deliver the signal twice.
+                Retry::Never,
+            )
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(Self::rejection(response, "signal job").await);
+        }
+        Ok(())
+    }
+
+    /// Have the daemon POST to `url` once, when job `job_id` ends with one of
+    /// `events` (all of [`JOB_WEBHOOK_EVENTS`] if empty).
+    pub async fn add_job_webhook(
         &self,
         job_id: u32,
-        request: UpdateJobRequest,
-    ) -> anyhow::Result<UpdateJobResponse> {
-        tracing::debug!("Updating job {job_id}");
-
+        url: &str,
+        events: &[String],
+    ) -> anyhow::Result<()> {
+        tracing::debug!("Adding a webhook to job {job_id}");
+        let request = JobWebhookRequest {
+            url: url.to_string(),
+            events: events.to_vec(),
+            user: crate::platform::get_current_username(),
+        };
         let response = self
-            .client
-            .patch(format!("{}/jobs/{}", self.base_url, job_id))
-            .json(&request)
-            .send()
-            .await
-            .map_err(connection_error_context)?;
+            .send(
+                self.client
+                    .post(format!("{}/jobs/{}/notifications", self.base_url, job_id))
+                    .json(&request),
+                Retry::StateChange,
+            )
+            .await?;
 
         if !response.status().is_success() {
-            let error_msg = Self::extract_error_message(response).await;
-            return Err(anyhow!("Failed to update job: {}", error_msg));
+            return Err(Self::rejection(response, "add webhook to job").await);
         }
+        Ok(())
+    }
 
-        let result: UpdateJobResponse = response
-            .json()
-            .await
-            .context("Failed to parse update job response")?;
+    /// Remove the webhooks attached to job `job_id`; returns how many there were.
+    pub async fn remove_job_webhooks(&self, job_id: u32) -> anyhow::Result<usize> {
+        tracing::debug!("Removing webhooks from job {job_id}");
+        let response = self
+            .send(
+                self.client
+                    .delete(format!("{}/jobs/{}/notifications", self.base_url, job_id)),
+                Retry::StateChange,
+            )
+            .await?;
 
-        Ok(result)
+        if !response.status().is_success() {
+            return Err(Self::rejection(response, "remove webhooks from job").await);
+        }
+        Ok(response
+            .json::<RemovedJobWebhooks>()
+            .await
+            .context("Failed to parse removed webhooks from response")?
+            .removed)
     }
 
-    pub async fn get_job_log_path(&self, job_id: u32) -> anyhow::Result<Option<String>> {
-        tracing::debug!("Getting log path for job {job_id}");
+    /// Register `path` as an output of job `job_id`. The daemon checks that
+    /// it exists on its machine.
+    pub async fn register_job_artifact(
+        &self,
+        job_id: u32,
+        path: &Path,
+        label: Option<&str>,
+    ) -> anyhow::Result<JobArtifact> {
+        tracing::debug!("Registering an artifact of job {job_id}");
+        let request = JobArtifactRequest {
+            path: path.to_path_buf(),
+            label: label.map(str::to_owned),
+        };
         let response = self
-            .client
-            .get(format!("{}/jobs/{}/log", self.base_url, job_id))
-            .send()
-            .await
-            .map_err(connection_error_context)?;
-        let status = response.status();
-        if status == StatusCode::OK {
-            response
-                .json::<Option<String>>()
-                .await
-                .context("Failed to parse log path from response")
-        } else if status == StatusCode::NOT_FOUND {
-            Ok(None)
-        } else {
-            let body = response
-                .text()
-                .await
-                .unwrap_or_else(|_| String::from("<failed to read body>"));
-            Err(anyhow!(
-                "Failed to get log path for job {} (status {}): {}",
-                job_id,
-                status,
-                body
-            ))
+            .send(
+                self.client
+                    .post(format!("{}/jobs/{}/artifacts", self.base_url, job_id))
+                    .json(&request),
+                Retry::StateChange,
+            )
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(Self::rejection(response, "register artifact").await);
         }
+        response
+            .json::<JobArtifact>()
+            .await
+            .context("Failed to parse artifact from response")
     }
 
-    pub async fn get_stats(
+    /// Add and remove tags of job `job_id`; returns its tags afterwards.
+    pub async fn update_job_tags(
         &self,
-        user: Option<&str>,
-        since: Option<i64>,
-    ) -> anyhow::Result<UsageStats> {
-        let mut params = vec![];
-        if let Some(u) = user {
-            params.push(("user", u.to_string()));
-        }
-        if let Some(s) = since {
-            params.push(("since", s.to_string()));
-        }
-        let mut request = self.client.get(format!("{}/stats", self.base_url));
-        if !params.is_empty() {
-            request = request.query(&params);
+        job_id: u32,
+        add: &[String],
+        remove: &[String],
+    ) -> anyhow::Result<Vec<String>> {
+        tracing::debug!("Updating the tags of job {job_id}");
+        let request = JobTagsRequest {
+            add: add.to_vec(),
+            remove: remove.to_vec(),
+        };
+        let response = self
+            .send(
+                self.client
+                    .post(format!("{}/jobs/{}/tags", self.base_url, job_id))
+                    .json(&request),
+                Retry::StateChange,
+            )
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(Self::rejection(response, "update job tags").await);
         }
-        let stats = request
-            .send()
-            .await
-            .map_err(connection_error_context)?
-            .json::<UsageStats>()
+        response
+            .json::<Vec<String>>()
             .await
-            .context("Failed to parse stats from response")?;
-        Ok(stats)
+            .context("Failed to parse tags from response")
     }
 
-    pub async fn get_info(&self) -> anyhow::Result<SchedulerInfo> {
-        tracing::debug!("Getting scheduler info");
-        let info = self
-            .client
-            .get(format!("{}/info", self.base_url))
-            .send()
+    /// Remove the scratch directory of finished job `job_id`.
+    pub async fn remove_job_dir(&self, job_id: u32) -> anyhow::Result<RemovedJobDir> {
+        tracing::debug!("Removing the directory of job {job_id}");
+        let response = self
+            .send(
+                self.client
+                    .delete(format!("{}/jobs/{}/dir", self.base_url, job_id)),
+                Retry::StateChange,
+            )
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(Self::rejection(response, "remove job directory").await);
+        }
+        response
+            .json::<RemovedJobDir>()
+            .await
+            .context("Failed to parse removed directory from response")
+    }
+
+    /// The artifacts job `job_id` registered, oldest first.
+    pub async fn get_job_artifacts(&self, job_id: u32) -> anyhow::Result<Vec<JobArtifact>> {
+        let response = self
+            .send(
+                self.client
+                    .get(format!("{}/jobs/{}/artifacts", self.base_url, job_id)),
+                Retry::Always,
+            )
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(Self::rejection(response, "get job artifacts").await);
+        }
+        response
+            .json::<Vec<JobArtifact>>()
+            .await
+            .context("Failed to parse artifacts from response")
+    }
+
+    pub async fn get_rate_limits(&self) -> anyhow::Result<RateLimits> {
+        let response = self
+            .send(
+                self.client.get(format!("{}/rate-limits", self.base_url)),
+                Retry::Always,
+            )
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(Self::rejection(response, "get rate limits").await);
+        }
+        response
+            .json()
+            .await
+            .context("Failed to parse rate limits from response")
+    }
+
+    /// Override `user`'s submission limits until the daemon restarts.
+    pub async fn set_rate_limit(
+        &self,
+        user: &str,
+        limit: &crate::config::UserRateLimit,
+    ) -> anyhow::Result<()> {
+        tracing::debug!("Setting rate limits of {user} to {limit:?}");
+        let response = self
+            .send(
+                self.client
+                    .post(format!("{}/rate-limits/{}", self.base_url, user))
+                    .json(limit),
+                Retry::Never,
+            )
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(Self::rejection(response, "set rate limit").await);
+        }
+        Ok(())
+    }
+
+    /// Drop `user`'s override; returns false if there was none.
+    pub async fn reset_rate_limit(&self, user: &str) -> anyhow::Result<bool> {
+        tracing::debug!("Resetting rate limits of {user}");
+        let response = self
+            .send(
+                self.client
+                    .delete(format!("{}/rate-limits/{}", self.base_url, user)),
+                Retry::Never,
+            )
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(Self::rejection(response, "reset rate limit").await);
+        }
+        let body: serde_json::Value = response
+            .json()
+            .await
+            .context("Failed to parse response json")?;
+        Ok(body["removed"].as_bool().unwrap_or(false))
+    }
+
+    /// Apply `action` to every job in `job_ids` under one daemon lock per
+    /// [`MAX_JOB_ACTION_IDS`] jobs, and report what became of each, in order.
+    /// Daemons that predate `POST /jobs/actions` are sent one request per job.
+    pub async fn apply_job_action(
+        &self,
+        action: JobAction,
+        job_ids: &[u32],
+    ) -> anyhow::Result<Vec<JobActionResult>> {
+        tracing::debug!("Applying {action:?} to {} jobs", job_ids.len());
+        let mut results = Vec::with_capacity(job_ids.len());
+        for chunk in job_ids.chunks(MAX_JOB_ACTION_IDS) {
+            let request = JobActionRequest {
+                op: action,
+                ids: chunk.to_vec(),
+            };
+            let response = self
+                .send(
+                    self.client
+                        .post(format!("{}/jobs/actions", self.base_url))
+                        .json(&request),
+                    Retry::StateChange,
+                )
+                .await?;
+
+            if response.status() == StatusCode::NOT_FOUND {
+                results.extend(self.apply_job_action_per_job(action, chunk).await?);
+                continue;
+            }
+            if !response.status().is_success() {
+                let what = format!("{} jobs", action.verb());
+                return Err(Self::rejection(response, what).await);
+            }
+            let chunk_results: Vec<JobActionResult> = response
+                .json()
+                .await
+                .context("Failed to parse job action response json")?;
+            results.extend(chunk_results);
+        }
+        Ok(results)
+    }
+
+    async fn apply_job_action_per_job(
+        &self,
+        action: JobAction,
+        job_ids: &[u32],
+    ) -> anyhow::Result<Vec<JobActionResult>> {
+        let mut results = Vec::with_capacity(job_ids.len());
+        for &id in job_ids {
+            let outcome = match action {
+                JobAction::Cancel => self.cancel_job(id).await,
+                JobAction::Hold => self.hold_job(id).await,
+                JobAction::Release => self.release_job(id).await,
+                JobAction::Finish => self.finish_job(id).await,
+                JobAction::Fail => self.fail_job(id).await,
+            };
+            let error = match outcome {
+                Ok(()) => None,
+                Err(error) => Some(error.downcast::<DaemonRejected>()?.error),
+            };
+            results.push(JobActionResult { id, error });
+        }
+        Ok(results)
+    }
+
+    pub async fn update_job(
+        &self,
+        job_id: u32,
+        request: UpdateJobRequest,
+    ) -> anyhow::Result<UpdateJobResponse> {
+        tracing::debug!("Updating job {job_id}");
+
+        let response = self
+            .send(
+                self.client
+                    .patch(format!("{}/jobs/{}", self.base_url, job_id))
+                    .json(&request),
+                Retry::Never,
+            )
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(Self::rejection(response, "update job").await);
+        }
+
+        let result: UpdateJobResponse = response
+            .json()
+            .await
+            .context("Failed to parse update job response")?;
+
+        Ok(result)
+    }
+
+    pub async fn get_job_log_path(&self, job_id: u32) -> anyhow::Result<Option<String>> {
+        tracing::debug!("Getting log path for job {job_id}");
+        let response = self
+            .send(
+                self.client
+                    .get(format!("{}/jobs/{}/log", self.base_url, job_id)),
+                Retry::Always,
+            )
+            .await?;
+        let status = response.status();
+        if status == StatusCode::OK {
+            response
+                .json::<Option<String>>()
+                .await
+                .context("Failed to parse log path from response")
+        } else if status == StatusCode::NOT_FOUND {
+            Ok(None)
+        } else {
+            let body = response
+                .text()
+                .await
+                .unwrap_or_else(|_| String::from("<failed to read body>"));
+            Err(anyhow!(
+                "Failed to get log path for job {} (status {}): {}",
+                job_id,
+                status,
+                body
+            ))
+        }
+    }
+
+    pub async fn get_stats(
+        &self,
+        user: Option<&str>,
+        since: Option<i64>,
+    ) -> anyhow::Result<UsageStats> {
+        let mut params = vec![];
+        if let Some(u) = user {
+            params.push(("user", u.to_string()));
+        }
+        if let Some(s) = since {
+            params.push(("since", s.to_string()));
+        }
+        let mut request = self.client.get(format!("{}/stats", self.base_url));
+        if !params.is_empty() {
+            request = request.query(&params);
+        }
+        let response = self.get_polled(request, "get stats").await?;
+        serde_json::from_str(&response.body).context("Failed to parse stats from response")
+    }
+
+    pub async fn get_report(&self, query: &ReportQuery) -> anyhow::Result<UsageReport> {
+        let request = self
+            .client
+            .get(format!("{}/report", self.base_url))
+            .query(query);
+        self.send(request, Retry::Always)
+            .await?
+            .json::<UsageReport>()
             .await
-            .map_err(connection_error_context)?
-            .json::<SchedulerInfo>()
+            .context("Failed to parse report from response")
+    }
+
+    pub async fn get_runtime_stats(
+        &self,
+        query: &RuntimeStatsQuery,
+    ) -> anyhow::Result<Vec<RuntimeSummary>> {
+        let request = self
+            .client
+            .get(format!("{}/stats/runtimes", self.base_url))
+            .query(query);
+        self.send(request, Retry::Always)
+            .await?
+            .json::<Vec<RuntimeSummary>>()
             .await
-            .context("Failed to parse info from response")?;
-        Ok(info)
+            .context("Failed to parse runtime stats from response")
+    }
+
+    /// Start estimates for every queued job, keyed by job ID.
+    pub async fn get_start_estimates(&self) -> anyhow::Result<BTreeMap<u32, StartEstimate>> {
+        self.send(
+            self.client.get(format!("{}/jobs/estimates", self.base_url)),
+            Retry::Always,
+        )
+        .await?
+        .json()
+        .await
+        .context("Failed to parse start estimates from response")
+    }
+
+    pub async fn get_info(&self) -> anyhow::Result<SchedulerInfo> {
+        tracing::debug!("Getting scheduler info");
+        let response = self
+            .get_polled(
+                self.client.get(format!("{}/info", self.base_url)),
+                "get info",
+            )
+            .await?;
+        serde_json::from_str(&response.body).context("Failed to parse info from response")
+    }
+
+    pub async fn get_scheduler_stats(&self) -> anyhow::Result<SchedulerStats> {
+        tracing::debug!("Getting scheduler stats snapshot");
+        let stats = self
+            .send(
+                self.client.get(format!("{}/info/stats", self.base_url)),
+                Retry::Always,
+            )
+            .await?
+            .json::<SchedulerStats>()
+            .await
+            .context("Failed to parse scheduler stats from response")?;
+        Ok(stats)
+    }
+
+    /// Delivery health of each webhook the daemon is configured with.
+    pub async fn list_webhook_targets(&self) -> anyhow::Result<Vec<WebhookTargetStatus>> {
+        tracing::debug!("Listing webhook targets");
+        let response = self
+            .send(
+                self.client
+                    .get(format!("{}/notifications/webhooks", self.base_url)),
+                Retry::Always,
+            )
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(Self::rejection(response, "list webhook targets").await);
+        }
+
+        response
+            .json::<Vec<WebhookTargetStatus>>()
+            .await
+            .context("Failed to parse webhook targets from response")
+    }
+
+    /// The daemon's version and the oldest client it accepts; `None` if it
+    /// predates `/version`.
+    pub async fn get_version(&self) -> anyhow::Result<Option<VersionInfo>> {
+        let response = self
+            .send_unchecked(
+                self.client.get(format!("{}/version", self.base_url)),
+                Retry::Never,
+            )
+            .await?;
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if !response.status().is_success() {
+            return Err(Self::rejection(response, "get daemon version").await);
+        }
+        Ok(Some(
+            response
+                .json()
+                .await
+                .context("Failed to parse version response")?,
+        ))
     }
 
     pub async fn get_health(&self) -> anyhow::Result<StatusCode> {
         tracing::debug!("Getting health status");
         let health = self
-            .client
-            .get(format!("{}/health", self.base_url))
-            .send()
-            .await
-            .map_err(connection_error_context)?
+            .send_unchecked(
+                self.client.get(format!("{}/health", self.base_url)),
+                Retry::Never,
+            )
+            .await?
             .status();
         Ok(health)
     }
@@ -464,11 +1458,11 @@ impl Client {
     pub async fn get_health_with_pid(&self) -> anyhow::Result<Option<u32>> {
         tracing::debug!("Getting health status with PID");
         let response = self
-            .client
-            .get(format!("{}/health", self.base_url))
-            .send()
-            .await
-            .map_err(connection_error_context)?;
+            .send_unchecked(
+                self.client.get(format!("{}/health", self.base_url)),
+                Retry::Never,
+            )
+            .await?;
 
         if !response.status().is_success() {
             return Ok(None);
@@ -494,19 +1488,16 @@ impl Client {
             username
         );
         let response = self
-            .client
-            .get(format!("{}/jobs/resolve-dependency", self.base_url))
-            .query(&[("username", username), ("shorthand", shorthand)])
-            .send()
-            .await
-            .map_err(connection_error_context)?;
+            .send(
+                self.client
+                    .get(format!("{}/jobs/resolve-dependency", self.base_url))
+                    .query(&[("username", username), ("shorthand", shorthand)]),
+                Retry::Always,
+            )
+            .await?;
 
         if !response.status().is_success() {
-            let error_msg = Self::extract_error_message(response).await;
-            return Err(anyhow::anyhow!(
-                "Failed to resolve dependency: {}",
-                error_msg
-            ));
+            return Err(Self::rejection(response, "resolve dependency").await);
         }
 
         let result: serde_json::Value = response
@@ -531,36 +1522,144 @@ impl Client {
         });
 
         let response = self
-            .client
-            .post(format!("{}/gpus", self.base_url))
-            .json(&request_body)
-            .send()
-            .await
-            .map_err(connection_error_context)?;
+            .send(
+                self.client
+                    .post(format!("{}/gpus", self.base_url))
+                    .json(&request_body),
+                Retry::Never,
+            )
+            .await?;
 
         if !response.status().is_success() {
-            let error_msg = Self::extract_error_message(response).await;
-            return Err(anyhow!("Failed to set GPU configuration: {}", error_msg));
+            return Err(Self::rejection(response, "set GPU configuration").await);
         }
 
         Ok(())
     }
 
+    pub async fn reload_config(&self) -> anyhow::Result<ConfigReloadReport> {
+        tracing::debug!("Requesting daemon config reload");
+        let response = self
+            .send(
+                self.client.post(format!("{}/config/reload", self.base_url)),
+                Retry::Never,
+            )
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(Self::rejection(response, "reload config").await);
+        }
+
+        response
+            .json::<ConfigReloadReport>()
+            .await
+            .context("Failed to parse config reload report from response")
+    }
+
+    pub async fn get_log_level(&self) -> anyhow::Result<LogLevel> {
+        let response = self
+            .send(
+                self.client.get(format!("{}/log-level", self.base_url)),
+                Retry::Always,
+            )
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(Self::rejection(response, "get log level").await);
+        }
+        response
+            .json()
+            .await
+            .context("Failed to parse log level from response")
+    }
+
+    /// Change the daemon's default log level until it restarts, or go back
+    /// to the configured one if `level` is `None`.
+    pub async fn set_log_level(&self, level: Option<&str>) -> anyhow::Result<LogLevel> {
+        tracing::debug!("Setting daemon log level to {level:?}");
+        let request = LogLevelRequest {
+            level: level.map(str::to_owned),
+        };
+        let response = self
+            .send(
+                self.client
+                    .post(format!("{}/log-level", self.base_url))
+                    .json(&request),
+                Retry::Never,
+            )
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(Self::rejection(response, "set log level").await);
+        }
+        response
+            .json()
+            .await
+            .context("Failed to parse log level from response")
+    }
+
+    /// Have the daemon write a backup of its current state; returns the backup path.
+    pub async fn backup_state(&self) -> anyhow::Result<PathBuf> {
+        tracing::debug!("Requesting state backup");
+        let response = self
+            .send(
+                self.client.post(format!("{}/state/backups", self.base_url)),
+                Retry::Never,
+            )
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(Self::rejection(response, "back up state").await);
+        }
+
+        #[derive(Deserialize)]
+        struct BackupResponse {
+            path: PathBuf,
+        }
+        let body: BackupResponse = response
+            .json()
+            .await
+            .context("Failed to parse backup response")?;
+        Ok(body.path)
+    }
+
+    /// Replace the daemon's state with a backup file on the daemon host.
+    pub async fn restore_state(
+        &self,
+        path: &std::path::Path,
+        force: bool,
+    ) -> anyhow::Result<StateRestoreReport> {
+        tracing::debug!("Restoring state from {}", path.display());
+        let response = self
+            .send(
+                self.client
+                    .post(format!("{}/state/restore", self.base_url))
+                    .json(&serde_json::json!({ "path": path, "force": force })),
+                Retry::Never,
+            )
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(Self::rejection(response, "restore state").await);
+        }
+
+        response
+            .json::<StateRestoreReport>()
+            .await
+            .context("Failed to parse restore report from response")
+    }
+
     pub async fn list_ignored_gpu_processes(&self) -> anyhow::Result<Vec<IgnoredGpuProcess>> {
         tracing::debug!("Listing ignored GPU processes");
         let response = self
-            .client
-            .get(format!("{}/gpu-processes", self.base_url))
-            .send()
-            .await
-            .map_err(connection_error_context)?;
+            .send(
+                self.client.get(format!("{}/gpu-processes", self.base_url)),
+                Retry::Always,
+            )
+            .await?;
 
         if !response.status().is_success() {
-            let error_msg = Self::extract_error_message(response).await;
-            return Err(anyhow!(
-                "Failed to list ignored GPU processes: {}",
-                error_msg
-            ));
+            return Err(Self::rejection(response, "list ignored GPU processes").await);
         }
 
         let processes = response
@@ -597,16 +1696,16 @@ impl Client {
         });
 
         let response = self
-            .client
-            .post(format!("{}/gpu-processes/{}", self.base_url, action))
-            .json(&request_body)
-            .send()
-            .await
-            .map_err(connection_error_context)?;
+            .send(
+                self.client
+                    .post(format!("{}/gpu-processes/{}", self.base_url, action))
+                    .json(&request_body),
+                Retry::Never,
+            )
+            .await?;
 
         if !response.status().is_success() {
-            let error_msg = Self::extract_error_message(response).await;
-            return Err(anyhow!("Failed to {} GPU process: {}", action, error_msg));
+            return Err(Self::rejection(response, format!("{action} GPU process")).await);
         }
 
         Ok(())
@@ -628,22 +1727,19 @@ impl Client {
         });
 
         let response = self
-            .client
-            .post(format!(
-                "{}/groups/{}/max-concurrency",
-                self.base_url, group_id
-            ))
-            .json(&request_body)
-            .send()
-            .await
-            .map_err(connection_error_context)?;
+            .send(
+                self.client
+                    .post(format!(
+                        "{}/groups/{}/max-concurrency",
+                        self.base_url, group_id
+                    ))
+                    .json(&request_body),
+                Retry::Never,
+            )
+            .await?;
 
         if !response.status().is_success() {
-            let error_msg = Self::extract_error_message(response).await;
-            return Err(anyhow!(
-                "Failed to set group max_concurrency: {}",
-                error_msg
-            ));
+            return Err(Self::rejection(response, "set group max_concurrency").await);
         }
 
         let result: serde_json::Value = response
@@ -660,6 +1756,79 @@ impl Client {
         Ok(updated_jobs)
     }
 
+    /// List job groups, optionally only those of the comma-separated `user`s
+    pub async fn list_groups(&self, user: Option<String>) -> anyhow::Result<Vec<JobGroup>> {
+        let mut url = format!("{}/groups", self.base_url);
+        if let Some(user) = user {
+            url.push_str(&format!("?user={}", user));
+        }
+
+        let response = self.send(self.client.get(&url), Retry::Always).await?;
+
+        if !response.status().is_success() {
+            return Err(Self::rejection(response, "list groups").await);
+        }
+
+        let groups = response
+            .json()
+            .await
+            .context("Failed to parse response json")?;
+
+        Ok(groups)
+    }
+
+    /// Get a job group's rollup, or `None` for an unknown group
+    pub async fn get_group(&self, group_id: &str) -> anyhow::Result<Option<JobGroup>> {
+        let response = self
+            .send(
+                self.client
+                    .get(format!("{}/groups/{}", self.base_url, group_id)),
+                Retry::Always,
+            )
+            .await?;
+
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+
+        if !response.status().is_success() {
+            return Err(Self::rejection(response, "get group").await);
+        }
+
+        let group = response
+            .json()
+            .await
+            .context("Failed to parse response json")?;
+
+        Ok(Some(group))
+    }
+
+    /// Get a job group's jobs in job ID order, or `None` for an unknown group
+    pub async fn list_group_jobs(&self, group_id: &str) -> anyhow::Result<Option<Vec<Job>>> {
+        let response = self
+            .send(
+                self.client
+                    .get(format!("{}/groups/{}/jobs", self.base_url, group_id)),
+                Retry::Always,
+            )
+            .await?;
+
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+
+        if !response.status().is_success() {
+            return Err(Self::rejection(response, "list group jobs").await);
+        }
+
+        let jobs = response
+            .json()
+            .await
+            .context("Failed to parse response json")?;
+
+        Ok(Some(jobs))
+    }
+
     /// Create a GPU reservation
     pub async fn create_reservation(
         &self,
@@ -667,36 +1836,56 @@ impl Client {
         gpu_spec: crate::core::reservation::GpuSpec,
         start_time: std::time::SystemTime,
         duration_secs: u64,
+    ) -> anyhow::Result<u32> {
+        self.create_reservation_with(
+            user,
+            Some(gpu_spec),
+            start_time,
+            duration_secs,
+            &Default::default(),
+        )
+        .await
+    }
+
+    /// Create a recurring reservation or maintenance window. Without a GPU
+    /// spec, a maintenance window covers the whole node.
+    pub async fn create_reservation_with(
+        &self,
+        user: String,
+        gpu_spec: Option<crate::core::reservation::GpuSpec>,
+        start_time: std::time::SystemTime,
+        duration_secs: u64,
+        options: &crate::core::reservation::ReservationOptions,
     ) -> anyhow::Result<u32> {
         use crate::core::reservation::GpuSpec;
 
-        let mut request_body = serde_json::json!({
-            "user": user,
-            "start_time": start_time,
-            "duration_secs": duration_secs,
-        });
+        let mut request_body = serde_json::to_value(options)?;
+        request_body["user"] = serde_json::json!(user);
+        request_body["start_time"] = serde_json::json!(start_time);
+        request_body["duration_secs"] = serde_json::json!(duration_secs);
 
         // Add gpu_count or gpu_indices based on spec type
         match gpu_spec {
-            GpuSpec::Count(count) => {
+            Some(GpuSpec::Count(count)) => {
                 request_body["gpu_count"] = serde_json::json!(count);
             }
-            GpuSpec::Indices(indices) => {
+            Some(GpuSpec::Indices(indices)) => {
                 request_body["gpu_indices"] = serde_json::json!(indices);
             }
+            None => {}
         }
 
         let response = self
-            .client
-            .post(format!("{}/reservations", self.base_url))
-            .json(&request_body)
-            .send()
-            .await
-            .map_err(connection_error_context)?;
+            .send(
+                self.client
+                    .post(format!("{}/reservations", self.base_url))
+                    .json(&request_body),
+                Retry::Never,
+            )
+            .await?;
 
         if !response.status().is_success() {
-            let error_msg = Self::extract_error_message(response).await;
-            return Err(anyhow!("Failed to create reservation: {}", error_msg));
+            return Err(Self::rejection(response, "create reservation").await);
         }
 
         let result: serde_json::Value = response
@@ -738,16 +1927,10 @@ impl Client {
             url.push_str(&query_params.join("&"));
         }
 
-        let response = self
-            .client
-            .get(&url)
-            .send()
-            .await
-            .map_err(connection_error_context)?;
+        let response = self.send(self.client.get(&url), Retry::Always).await?;
 
         if !response.status().is_success() {
-            let error_msg = Self::extract_error_message(response).await;
-            return Err(anyhow!("Failed to list reservations: {}", error_msg));
+            return Err(Self::rejection(response, "list reservations").await);
         }
 
         let reservations = response
@@ -764,19 +1947,19 @@ impl Client {
         id: u32,
     ) -> anyhow::Result<Option<crate::core::reservation::GpuReservation>> {
         let response = self
-            .client
-            .get(format!("{}/reservations/{}", self.base_url, id))
-            .send()
-            .await
-            .map_err(connection_error_context)?;
+            .send(
+                self.client
+                    .get(format!("{}/reservations/{}", self.base_url, id)),
+                Retry::Always,
+            )
+            .await?;
 
         if response.status() == StatusCode::NOT_FOUND {
             return Ok(None);
         }
 
         if !response.status().is_success() {
-            let error_msg = Self::extract_error_message(response).await;
-            return Err(anyhow!("Failed to get reservation: {}", error_msg));
+            return Err(Self::rejection(response, "get reservation").await);
         }
 
         let reservation = response
@@ -790,30 +1973,105 @@ impl Client {
     /// Cancel a GPU reservation
     pub async fn cancel_reservation(&self, id: u32) -> anyhow::Result<()> {
         let response = self
-            .client
-            .delete(format!("{}/reservations/{}", self.base_url, id))
-            .send()
-            .await
-            .map_err(connection_error_context)?;
+            .send(
+                self.client
+                    .delete(format!("{}/reservations/{}", self.base_url, id)),
+                Retry::Never,
+            )
+            .await?;
 
         if !response.status().is_success() {
-            let error_msg = Self::extract_error_message(response).await;
-            return Err(anyhow!("Failed to cancel reservation: {}", error_msg));
+            return Err(Self::rejection(response, "cancel reservation").await);
         }
 
         Ok(())
     }
+
+    /// Register a remote node with the daemon; agents repeat this as their heartbeat.
+    pub async fn register_node(
+        &self,
+        registration: &crate::core::node::NodeRegistration,
+    ) -> anyhow::Result<()> {
+        let response = self
+            .send(
+                self.client
+                    .post(format!("{}/nodes", self.base_url))
+                    .json(registration),
+                Retry::Never,
+            )
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(Self::rejection(response, "register node").await);
+        }
+
+        Ok(())
+    }
+
+    /// List remote nodes, by name
+    pub async fn list_nodes(&self) -> anyhow::Result<Vec<crate::core::node::Node>> {
+        let response = self
+            .send(
+                self.client.get(format!("{}/nodes", self.base_url)),
+                Retry::Always,
+            )
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(Self::rejection(response, "list nodes").await);
+        }
+
+        response
+            .json()
+            .await
+            .context("Failed to parse response json")
+    }
+
+    /// Set and remove labels of this machine, one of its GPUs, or a node, and
+    /// return the labels afterwards
+    pub async fn update_labels(
+        &self,
+        gpu: Option<u32>,
+        node: Option<&str>,
+        set: &crate::utils::constraint::Labels,
+        remove: &[String],
+    ) -> anyhow::Result<crate::utils::constraint::Labels> {
+        let request_body = serde_json::json!({
+            "gpu": gpu,
+            "node": node,
+            "set": set,
+            "remove": remove,
+        });
+        let response = self
+            .send(
+                self.client
+                    .post(format!("{}/labels", self.base_url))
+                    .json(&request_body),
+                Retry::Never,
+            )
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(Self::rejection(response, "update labels").await);
+        }
+
+        let body: serde_json::Value = response
+            .json()
+            .await
+            .context("Failed to parse response json")?;
+        serde_json::from_value(body["labels"].clone()).context("Invalid response format: labels")
+    }
 }
 
-/// Helper function to get a job and print a warning if not found.
-/// Returns Ok(Some(job)) if found, Ok(None) if not found (with warning printed).
+/// Helper function to get a job and print an error if not found.
+/// Returns Ok(Some(job)) if found, Ok(None) if not found (with the error printed).
 ///
 /// This is a convenience function to reduce boilerplate in CLI tools.
 pub async fn get_job_or_warn(client: &Client, job_id: u32) -> anyhow::Result<Option<Job>> {
     match client.get_job(job_id).await? {
         Some(job) => Ok(Some(job)),
         None => {
-            eprintln!("Error: Job {} not found", job_id);
+            print_error(&ClientError::NotFound { job_id }.into());
             Ok(None)
         }
     }
@@ -828,7 +2086,9 @@ mod tests {
     use crate::core::reservation::GpuSpec;
     use compact_str::CompactString;
     use std::time::SystemTime;
-    use wiremock::matchers::{method, path, query_param};
+    use wiremock::matchers::{
+        body_json, header, header_exists, method, path, query_param, query_param_is_missing,
+    };
     use wiremock::{Mock, MockServer, ResponseTemplate};
 
     /// Build a `Client` pointed at the given mock server.
@@ -875,89 +2135,312 @@ mod tests {
     // ── list_jobs ──────────────────────────────────────────────────────────
 
     #[tokio::test]
-    async fn list_jobs_returns_vec_on_success() {
+    async fn list_jobs_returns_vec_on_success() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/jobs"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(vec![job_json(1, "Queued"), job_json(2, "Running")]),
+            )
+            .mount(&server)
+            .await;
+
+        let client = client_for(&server);
+        let jobs = client.list_jobs().await.expect("should list jobs");
+        assert_eq!(jobs.len(), 2);
+        assert_eq!(jobs[0].id, 1);
+        assert_eq!(jobs[1].id, 2);
+    }
+
+    #[tokio::test]
+    async fn list_jobs_propagates_connection_error_with_friendly_message() {
+        let mut config = Config::default();
+        config.daemon.host = "127.0.0.1".to_string();
+        // Use a port that is almost certainly closed.
+        config.daemon.port = 1;
+        let client = Client::build(&config).expect("failed to build client");
+
+        let err = client.list_jobs().await.unwrap_err();
+        assert!(err.to_string().contains("Could not connect to gflowd"));
+    }
+
+    #[test]
+    fn build_reports_unreadable_tls_ca() {
+        let mut config = Config::default();
+        config.daemon.tls_ca = Some("/nonexistent/gflow-ca.pem".into());
+        let err = Client::build(&config).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Failed to read daemon.tls_ca /nonexistent/gflow-ca.pem"));
+    }
+
+    #[tokio::test]
+    async fn list_jobs_over_unix_socket() {
+        let dir = tempfile::tempdir().unwrap();
+        let socket_path = dir.path().join("gflow.sock");
+        let mut config = Config::default();
+        config.daemon.listen = Some(ListenAddr::Unix(socket_path.clone()));
+        let client = Client::build(&config).expect("failed to build client");
+
+        let err = client.list_jobs().await.unwrap_err();
+        assert!(err.to_string().contains("Could not connect to gflowd"));
+
+        let listener = tokio::net::UnixListener::bind(&socket_path).unwrap();
+        let app = axum::Router::new().route(
+            "/jobs",
+            axum::routing::get(|| async { axum::Json(vec![job_json(7, "Queued")]) }),
+        );
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        let jobs = client.list_jobs().await.expect("should list jobs");
+        assert_eq!(jobs[0].id, 7);
+    }
+
+    #[tokio::test]
+    async fn get_requests_retry_until_daemon_is_back() {
+        let dir = tempfile::tempdir().unwrap();
+        let socket_path = dir.path().join("gflow.sock");
+        let mut config = Config::default();
+        config.daemon.listen = Some(ListenAddr::Unix(socket_path.clone()));
+        config.client.retry_base_ms = 100;
+        config.client.max_retries = 5;
+        let client = Client::build(&config).expect("failed to build client");
+
+        // Simulate a daemon restart: nothing listens for the first few hundred ms.
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(300)).await;
+            let listener = tokio::net::UnixListener::bind(&socket_path).unwrap();
+            let app = axum::Router::new().route(
+                "/jobs",
+                axum::routing::get(|| async { axum::Json(vec![job_json(7, "Queued")]) }),
+            );
+            axum::serve(listener, app).await
+        });
+
+        let jobs = client.list_jobs().await.expect("should retry until up");
+        assert_eq!(jobs[0].id, 7);
+    }
+
+    #[tokio::test]
+    async fn state_changes_are_not_retried_by_default() {
+        let mut config = Config::default();
+        config.daemon.host = "127.0.0.1".to_string();
+        config.daemon.port = 1;
+        config.client.retry_base_ms = 60_000;
+        let client = Client::build(&config).expect("failed to build client");
+
+        // A retry would sleep for a minute; the call must fail straight away.
+        let started = std::time::Instant::now();
+        let err = client.cancel_job(1).await.unwrap_err();
+        assert!(err.to_string().contains("Could not connect to gflowd"));
+        assert!(started.elapsed() < Duration::from_secs(10));
+    }
+
+    // ── list_jobs_with_query ───────────────────────────────────────────────
+
+    #[tokio::test]
+    async fn list_jobs_with_query_accepts_paginated_response() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/jobs"))
+            .and(query_param("state", "Running"))
+            .and(query_param("limit", "10"))
+            .and(query_param("offset", "5"))
+            .and(query_param("order", "desc"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "jobs": [job_json(7, "Running")],
+                "total": 42,
+                "limit": 10,
+                "offset": 5,
+            })))
+            .mount(&server)
+            .await;
+
+        let client = client_for(&server);
+        let jobs = client
+            .list_jobs_with_query(
+                Some("Running".into()),
+                None,
+                Some(10),
+                Some(5),
+                None,
+                Some("desc".into()),
+            )
+            .await
+            .expect("should list jobs");
+
+        assert_eq!(jobs.len(), 1);
+        assert_eq!(jobs[0].id, 7);
+    }
+
+    #[tokio::test]
+    async fn find_jobs_sends_only_the_filters_that_are_set() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/jobs"))
+            .and(query_param("search", "cosine --lr 3e-4"))
+            .and(query_param("user", "alice"))
+            .and(query_param("name_glob", "train-*"))
+            .and(query_param_is_missing("state"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(vec![job_json(9, "Finished")]))
+            .mount(&server)
+            .await;
+
+        let client = client_for(&server);
+        let jobs = client
+            .find_jobs(&JobFilter {
+                user: Some("alice".into()),
+                search: Some("cosine --lr 3e-4".into()),
+                name_glob: Some("train-*".into()),
+                ..JobFilter::default()
+            })
+            .await
+            .expect("should find jobs");
+        assert_eq!(jobs[0].id, 9);
+    }
+
+    #[tokio::test]
+    async fn list_jobs_with_query_falls_back_to_plain_vec() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/jobs"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(vec![job_json(1, "Queued")]))
+            .mount(&server)
+            .await;
+
+        let client = client_for(&server);
+        let jobs = client
+            .list_jobs_with_query(None, None, None, None, None, None)
+            .await
+            .expect("should list jobs");
+
+        assert_eq!(jobs.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn find_jobs_page_reads_the_total_count_header() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/jobs"))
+            .and(query_param("user", "alice"))
+            .and(query_param("limit", "1"))
+            .and(query_param("offset", "2"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header(TOTAL_COUNT_HEADER, "7")
+                    .set_body_json(vec![job_json(3, "Queued")]),
+            )
+            .mount(&server)
+            .await;
+
+        let client = client_for(&server);
+        let filter = JobFilter {
+            user: Some("alice".into()),
+            ..JobFilter::default()
+        };
+        let (jobs, total) = client.find_jobs_page(&filter, 1, 2).await.unwrap();
+        assert_eq!(jobs[0].id, 3);
+        assert_eq!(total, 7);
+    }
+
+    #[tokio::test]
+    async fn find_jobs_page_cuts_the_page_itself_without_a_total() {
         let server = MockServer::start().await;
         Mock::given(method("GET"))
             .and(path("/jobs"))
+            .and(query_param_is_missing("limit"))
             .respond_with(
                 ResponseTemplate::new(200)
-                    .set_body_json(vec![job_json(1, "Queued"), job_json(2, "Running")]),
+                    .set_body_json((1..=5).map(|id| job_json(id, "Queued")).collect::<Vec<_>>()),
             )
             .mount(&server)
             .await;
+        Mock::given(method("GET"))
+            .and(path("/jobs"))
+            .and(query_param("limit", "2"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(vec![job_json(4, "Queued")]))
+            .mount(&server)
+            .await;
 
         let client = client_for(&server);
-        let jobs = client.list_jobs().await.expect("should list jobs");
-        assert_eq!(jobs.len(), 2);
-        assert_eq!(jobs[0].id, 1);
-        assert_eq!(jobs[1].id, 2);
-    }
-
-    #[tokio::test]
-    async fn list_jobs_propagates_connection_error_with_friendly_message() {
-        let mut config = Config::default();
-        config.daemon.host = "127.0.0.1".to_string();
-        // Use a port that is almost certainly closed.
-        config.daemon.port = 1;
-        let client = Client::build(&config).expect("failed to build client");
-
-        let err = client.list_jobs().await.unwrap_err();
-        assert!(err.to_string().contains("Could not connect to gflowd"));
+        let (jobs, total) = client
+            .find_jobs_page(&JobFilter::default(), 2, 3)
+            .await
+            .unwrap();
+        assert_eq!(jobs.iter().map(|job| job.id).collect::<Vec<_>>(), [4, 5]);
+        assert_eq!(total, 5);
     }
 
-    // ── list_jobs_with_query ───────────────────────────────────────────────
-
     #[tokio::test]
-    async fn list_jobs_with_query_accepts_paginated_response() {
+    async fn find_jobs_fetches_every_page() {
         let server = MockServer::start().await;
+        let total = JOB_PAGE_SIZE + 1;
         Mock::given(method("GET"))
             .and(path("/jobs"))
-            .and(query_param("state", "Running"))
-            .and(query_param("limit", "10"))
-            .and(query_param("offset", "5"))
-            .and(query_param("order", "desc"))
-            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
-                "jobs": [job_json(7, "Running")],
-                "total": 42,
-                "limit": 10,
-                "offset": 5,
-            })))
+            .and(query_param("offset", "0"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header(TOTAL_COUNT_HEADER, total.to_string())
+                    .set_body_json(
+                        (1..=JOB_PAGE_SIZE as u32)
+                            .map(|id| job_json(id, "Finished"))
+                            .collect::<Vec<_>>(),
+                    ),
+            )
+            .expect(1)
             .mount(&server)
             .await;
-
-        let client = client_for(&server);
-        let jobs = client
-            .list_jobs_with_query(
-                Some("Running".into()),
-                None,
-                Some(10),
-                Some(5),
-                None,
-                Some("desc".into()),
+        Mock::given(method("GET"))
+            .and(path("/jobs"))
+            .and(query_param("offset", JOB_PAGE_SIZE.to_string()))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header(TOTAL_COUNT_HEADER, total.to_string())
+                    // A job that moved up a page between the requests is listed once.
+                    .set_body_json(vec![
+                        job_json(JOB_PAGE_SIZE as u32, "Finished"),
+                        job_json(total as u32, "Finished"),
+                    ]),
             )
-            .await
-            .expect("should list jobs");
+            .expect(1)
+            .mount(&server)
+            .await;
 
-        assert_eq!(jobs.len(), 1);
-        assert_eq!(jobs[0].id, 7);
+        let client = client_for(&server);
+        let jobs = client.find_jobs(&JobFilter::default()).await.unwrap();
+        assert_eq!(jobs.len(), total);
+        assert_eq!(jobs.last().unwrap().id, total as u32);
     }
 
     #[tokio::test]
-    async fn list_jobs_with_query_falls_back_to_plain_vec() {
+    async fn unchanged_listings_are_served_from_the_cache() {
         let server = MockServer::start().await;
         Mock::given(method("GET"))
             .and(path("/jobs"))
-            .respond_with(ResponseTemplate::new(200).set_body_json(vec![job_json(1, "Queued")]))
+            .and(header("if-none-match", "\"a-1\""))
+            .respond_with(ResponseTemplate::new(304).insert_header("etag", "\"a-1\""))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/jobs"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("etag", "\"a-1\"")
+                    .insert_header(TOTAL_COUNT_HEADER, "1")
+                    .set_body_json(vec![job_json(1, "Queued")]),
+            )
+            .expect(1)
             .mount(&server)
             .await;
 
         let client = client_for(&server);
-        let jobs = client
-            .list_jobs_with_query(None, None, None, None, None, None)
-            .await
-            .expect("should list jobs");
-
-        assert_eq!(jobs.len(), 1);
+        let filter = JobFilter::default();
+        let fresh = client.find_jobs_page(&filter, 10, 0).await.unwrap();
+        let cached = client.clone().find_jobs_page(&filter, 10, 0).await.unwrap();
+        assert_eq!(cached.0[0].id, fresh.0[0].id);
+        assert_eq!(cached.1, 1);
     }
 
     // ── get_job ────────────────────────────────────────────────────────────
@@ -991,6 +2474,32 @@ mod tests {
         assert!(job.is_none());
     }
 
+    #[tokio::test]
+    async fn get_job_in_scope_refuses_other_users_jobs() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/jobs/3"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(job_json(3, "Queued")))
+            .mount(&server)
+            .await;
+
+        let client = client_for(&server);
+        let mine = UserScope::Users(vec!["tester".to_string()]);
+        assert_eq!(client.get_job_in_scope(3, &mine).await.unwrap().id, 3);
+
+        let others = UserScope::Current("alice".to_string());
+        let err = client.get_job_in_scope(3, &others).await.unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<ClientError>(),
+            Some(&ClientError::NotOwner {
+                job_id: 3,
+                owner: "tester".to_string(),
+            })
+        );
+        assert!(render_error(&err, false)
+            .ends_with("hint: act on another user's jobs with `--user tester` or `--all-users`"));
+    }
+
     // ── add_job / add_jobs ─────────────────────────────────────────────────
 
     #[tokio::test]
@@ -998,6 +2507,7 @@ mod tests {
         let server = MockServer::start().await;
         Mock::given(method("POST"))
             .and(path("/jobs"))
+            .and(header_exists(IDEMPOTENCY_KEY_HEADER))
             .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
                 "id": 42,
                 "run_name": "gjob-42"
@@ -1116,6 +2626,113 @@ mod tests {
         assert!(err.to_string().contains("job is not running"));
     }
 
+    #[tokio::test]
+    async fn job_action_error_carries_the_error_code() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/jobs/5/hold"))
+            .respond_with(
+                ResponseTemplate::new(409)
+                    .set_body_json(serde_json::json!({
+                        "code": "invalid_transition",
+                        "details": {"id": 5, "from": "Finished", "to": "Hold"},
+                        "message": "Job 5 cannot go from Finished to Hold",
+                        "error": "Job 5 cannot go from Finished to Hold"
+                    }))
+                    .insert_header("x-request-id", "5f0c6a1e"),
+            )
+            .mount(&server)
+            .await;
+
+        let client = client_for(&server);
+        let err = client.hold_job(5).await.unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Failed to hold job: Job 5 cannot go from Finished to Hold"
+        );
+        let rejected = err.downcast_ref::<DaemonRejected>().unwrap();
+        assert_eq!(rejected.status, StatusCode::CONFLICT);
+        assert_eq!(rejected.request_id.as_deref(), Some("5f0c6a1e"));
+        assert_eq!(
+            rejected.error.kind,
+            ApiErrorKind::InvalidTransition {
+                id: 5,
+                from: crate::core::job::JobState::Finished,
+                to: crate::core::job::JobState::Hold,
+                allowed: vec![],
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn apply_job_action_reports_each_job() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/jobs/actions"))
+            .and(body_json(
+                serde_json::json!({"op": "cancel", "ids": [1, 2]}),
+            ))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([
+                {"id": 1},
+                {"id": 2, "error": {
+                    "code": "job_not_found",
+                    "details": {"id": 2},
+                    "message": "Job 2 not found"
+                }}
+            ])))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = client_for(&server);
+        let mut results = client
+            .apply_job_action(JobAction::Cancel, &[1, 2])
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|result| result.into_result(JobAction::Cancel));
+        assert_eq!(results.next().unwrap().unwrap(), 1);
+        let err = results.next().unwrap().unwrap_err();
+        assert_eq!(err.to_string(), "Failed to cancel job: Job 2 not found");
+        let rejected = err.downcast_ref::<DaemonRejected>().unwrap();
+        assert_eq!(rejected.status, StatusCode::NOT_FOUND);
+        assert_eq!(rejected.error.kind, ApiErrorKind::JobNotFound { id: 2 });
+    }
+
+    #[tokio::test]
+    async fn apply_job_action_falls_back_to_one_request_per_job() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/jobs/1/hold"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/jobs/2/hold"))
+            .respond_with(ResponseTemplate::new(409).set_body_json(serde_json::json!({
+                "code": "invalid_transition",
+                "details": {"id": 2, "from": "Running", "to": "Hold"},
+                "message": "Job 2 cannot go from Running to Hold"
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        // A daemon without `/jobs/actions` answers 404 for it.
+        let client = client_for(&server);
+        let results = client
+            .apply_job_action(JobAction::Hold, &[1, 2])
+            .await
+            .unwrap();
+        assert_eq!(results.len(), 2);
+        assert!(results[0].error.is_none());
+        assert_eq!(
+            results[1].error.as_ref().unwrap().kind.code(),
+            "invalid_transition"
+        );
+    }
+
     // ── update_job ─────────────────────────────────────────────────────────
 
     #[tokio::test]
@@ -1255,6 +2872,98 @@ mod tests {
         assert_eq!(stats.success_rate, 0.8);
     }
 
+    #[tokio::test]
+    async fn get_report_sends_the_query() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/report"))
+            .and(query_param("group_by", "day"))
+            .and(query_param("since", "1000"))
+            .and(query_param_is_missing("until"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "group_by": "day",
+                "since": 1000,
+                "until": null,
+                "rows": [],
+                "total": { "key": "TOTAL", "jobs": 0, "completed": 0, "failed": 0,
+                           "cancelled": 0, "gpu_hours": 0.0, "success_rate": null,
+                           "avg_wait_secs": null }
+            })))
+            .mount(&server)
+            .await;
+
+        let client = client_for(&server);
+        let report = client
+            .get_report(&ReportQuery {
+                group_by: crate::core::report::ReportGroupBy::Day,
+                since: Some(1000),
+                ..Default::default()
+            })
+            .await
+            .expect("should get report");
+        assert!(report.rows.is_empty());
+        assert_eq!(report.total.key, "TOTAL");
+    }
+
+    #[tokio::test]
+    async fn get_start_estimates_reads_the_map() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/jobs/estimates"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "3": { "kind": "at", "at": 1700000000, "jobs_ahead": 2 },
+                "4": { "kind": "unknown" },
+                "5": { "kind": "never", "reason": "requests 9 GPU(s) but the scheduler can use 8" }
+            })))
+            .mount(&server)
+            .await;
+
+        let estimates = client_for(&server)
+            .get_start_estimates()
+            .await
+            .expect("should get estimates");
+        assert_eq!(
+            estimates[&3],
+            StartEstimate::At {
+                at: 1_700_000_000,
+                jobs_ahead: 2
+            }
+        );
+        assert_eq!(estimates[&4], StartEstimate::Unknown);
+        assert!(matches!(estimates[&5], StartEstimate::Never { .. }));
+    }
+
+    #[tokio::test]
+    async fn get_runtime_stats_sends_filters() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/stats/runtimes"))
+            .and(query_param("name", "train-*"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!([{
+                    "user": "alice",
+                    "name": "train",
+                    "count": 12,
+                    "mean_secs": 600,
+                    "p50_secs": 540,
+                    "p95_secs": 900,
+                    "last_seen": 1700000000
+                }])),
+            )
+            .mount(&server)
+            .await;
+
+        let rows = client_for(&server)
+            .get_runtime_stats(&RuntimeStatsQuery {
+                name: Some("train-*".to_string()),
+                user: None,
+            })
+            .await
+            .expect("should get runtime stats");
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].p95_secs, 900);
+    }
+
     #[tokio::test]
     async fn get_info_returns_scheduler_info() {
         let server = MockServer::start().await;
@@ -1282,6 +2991,120 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn get_scheduler_stats_returns_snapshot() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/info/stats"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "version": "0.4.17",
+                "pid": 42,
+                "uptime_secs": 120,
+                "jobs_by_state": {"Queued": 3, "Running": 1},
+                "total_gpus": 2,
+                "available_gpus": 1,
+                "gpus": [
+                    {"uuid": "gpu-0", "index": 0, "available": true},
+                    {"uuid": "gpu-1", "index": 1, "available": false, "reason": "busy"}
+                ],
+                "total_memory_mb": 16384,
+                "available_memory_mb": 8192,
+                "active_reservations": [],
+                "persistence": {
+                    "mode": "state",
+                    "state_writable": true,
+                    "journal_writable": false
+                }
+            })))
+            .mount(&server)
+            .await;
+
+        let client = client_for(&server);
+        let stats = client
+            .get_scheduler_stats()
+            .await
+            .expect("should get scheduler stats");
+        assert_eq!(stats.queue_summary(), "3 queued / 1 running / 0 hold");
+        assert_eq!(stats.available_gpus, 1);
+        assert_eq!(stats.persistence.mode, "state");
+    }
+
+    // ── version check ───────────────────────────────────────────────────────
+
+    fn version_json(version: &str, min_client_version: &str) -> serde_json::Value {
+        serde_json::json!({"version": version, "min_client_version": min_client_version})
+    }
+
+    #[tokio::test]
+    async fn requests_carry_the_client_version_and_check_the_daemon_once() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/version"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(version_json("99.0.0", "0.1.0")))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/info"))
+            .and(header(CLIENT_VERSION_HEADER, env!("CARGO_PKG_VERSION")))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "gpus": [],
+                "allowed_gpu_indices": null,
+                "gpu_allocation_strategy": "sequential"
+            })))
+            .expect(2)
+            .mount(&server)
+            .await;
+
+        let client = client_for(&server);
+        client.get_info().await.expect("skewed versions only warn");
+        client.get_info().await.expect("skewed versions only warn");
+    }
+
+    #[tokio::test]
+    async fn daemon_declaring_the_client_too_old_stops_requests() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/version"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(version_json("99.0.0", "99.0.0")),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/jobs/1/cancel"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = client_for(&server);
+        let error = client.cancel_job(1).await.unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<ClientError>(),
+            Some(ClientError::ClientTooOld { minimum, .. }) if minimum.major == 99
+        ));
+        assert!(render_error(&error, false).contains("--skip-version-check"));
+
+        let mut client = client_for(&server);
+        client.check_version = false;
+        client.cancel_job(1).await.expect("the check was skipped");
+    }
+
+    #[tokio::test]
+    async fn daemon_without_version_endpoint_is_still_used() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/health"))
+            .respond_with(ResponseTemplate::new(200))
+            .mount(&server)
+            .await;
+
+        let client = client_for(&server);
+        assert_eq!(client.get_version().await.unwrap(), None);
+        assert!(client.get_health().await.unwrap().is_success());
+    }
+
     #[tokio::test]
     async fn get_health_returns_status_code() {
         let server = MockServer::start().await;
@@ -1468,6 +3291,45 @@ mod tests {
         assert_eq!(count, 3);
     }
 
+    #[tokio::test]
+    async fn list_groups_filters_by_owner_and_unknown_groups_are_none() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/groups"))
+            .and(query_param("user", "alice"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!([{
+                    "id": "1b4e28ba-2fa1-41d2-883f-0016d3cca427",
+                    "owner": "alice",
+                    "jobs_by_state": {"Running": 2, "Queued": 3},
+                    "max_concurrent": 2,
+                    "first_submitted_at": null,
+                    "last_activity_at": null
+                }])),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/groups/abc-123/jobs"))
+            .respond_with(ResponseTemplate::new(404))
+            .mount(&server)
+            .await;
+
+        let client = client_for(&server);
+        let groups = client
+            .list_groups(Some("alice".into()))
+            .await
+            .expect("should list groups");
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].count(crate::core::job::JobState::Queued), 3);
+        assert_eq!(groups[0].active(), 5);
+        assert!(client
+            .list_group_jobs("abc-123")
+            .await
+            .expect("404 is not an error")
+            .is_none());
+    }
+
     // ── reservations ───────────────────────────────────────────────────────
 
     #[tokio::test]
@@ -0,0 +1,85 @@
+//! The last body of each polled URL, kept with its `ETag`: [`ResponseCache`].
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+/// URLs remembered at once; the least recently used is dropped beyond this.
+const MAX_ENTRIES: usize = 32;
+
+/// Responses to `GET /jobs`, `/info` and `/stats` by URL, so that polling an
+/// unchanged resource costs the daemon a `304 Not Modified` rather than the
+/// whole body. Clones of a [`Client`](super::Client) share one cache.
+#[derive(Debug, Default)]
+pub(super) struct ResponseCache {
+    inner: Mutex<Entries>,
+}
+
+#[derive(Debug, Default)]
+struct Entries {
+    by_url: HashMap<String, (u64, CachedResponse)>,
+    /// Bumped on every use, to find the least recently used entry
+    clock: u64,
+}
+
+#[derive(Debug, Clone)]
+pub(super) struct CachedResponse {
+    pub etag: String,
+    pub body: Arc<str>,
+    /// `X-Total-Count` of a job listing
+    pub total: Option<usize>,
+}
+
+impl ResponseCache {
+    pub(super) fn get(&self, url: &str) -> Option<CachedResponse> {
+        let mut entries = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        entries.clock += 1;
+        let now = entries.clock;
+        let (used, response) = entries.by_url.get_mut(url)?;
+        *used = now;
+        Some(response.clone())
+    }
+
+    pub(super) fn insert(&self, url: String, response: CachedResponse) {
+        let mut entries = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        entries.clock += 1;
+        let now = entries.clock;
+        if entries.by_url.len() >= MAX_ENTRIES && !entries.by_url.contains_key(&url) {
+            let oldest = entries
+                .by_url
+                .iter()
+                .min_by_key(|(_, (used, _))| *used)
+                .map(|(url, _)| url.clone());
+            if let Some(oldest) = oldest {
+                entries.by_url.remove(&oldest);
+            }
+        }
+        entries.by_url.insert(url, (now, response));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn response(etag: &str) -> CachedResponse {
+        CachedResponse {
+            etag: etag.to_string(),
+            body: Arc::from("[]"),
+            total: None,
+        }
+    }
+
+    #[test]
+    fn drops_the_least_recently_used_url_when_full() {
+        let cache = ResponseCache::default();
+        for i in 0..MAX_ENTRIES {
+            cache.insert(format!("/jobs?offset={i}"), response(&i.to_string()));
+        }
+        assert!(cache.get("/jobs?offset=0").is_some());
+
+        cache.insert("/info".to_string(), response("info"));
+        assert!(cache.get("/jobs?offset=0").is_some());
+        assert!(cache.get("/jobs?offset=1").is_none());
+        assert_eq!(cache.get("/info").unwrap().etag, "info");
+    }
+}
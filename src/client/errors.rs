@@ -0,0 +1,327 @@
+//! Errors explained to the user: [`ClientError`] and [`render_error`], which
+//! adds what to do next.
+
+use super::DaemonRejected;
+use crate::core::api_error::ApiErrorKind;
+use crate::core::job::JobState;
+use crate::core::version::{Version, SKIP_VERSION_CHECK_ENV};
+use crate::utils::output::OutputStyle;
+use owo_colors::Style;
+
+/// A failure the CLI knows how to explain.
+///
+/// Find it with `error.downcast_ref::<ClientError>()`; it survives added context.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ClientError {
+    /// Nothing answered at `url`, the daemon's address or Unix socket
+    ConnectionRefused {
+        url: String,
+    },
+    /// The daemon did not answer within `client.request_timeout_secs`
+    Timeout,
+    NotFound {
+        job_id: u32,
+    },
+    /// Job `job_id` belongs to `owner`, outside the command's [`UserScope`](super::UserScope)
+    NotOwner {
+        job_id: u32,
+        owner: String,
+    },
+    /// `field` could not be parsed; `example` lists what it accepts
+    InvalidInput {
+        field: String,
+        example: String,
+    },
+    /// Job `job_id` is in state `from`, which cannot go to `to`
+    InvalidTransition {
+        job_id: u32,
+        from: JobState,
+        to: JobState,
+        /// Where a job in `from` can go; empty if `from` is final
+        allowed: Vec<JobState>,
+        message: String,
+    },
+    /// The daemon declares clients older than `minimum` unsupported
+    ClientTooOld {
+        client: Version,
+        minimum: Version,
+    },
+    /// The daemon refused the request with error `code`
+    ServerError {
+        code: String,
+        message: String,
+    },
+}
+
+impl ClientError {
+    /// Invalid `--time`, with the formats `parse_time_limit` accepts.
+    pub fn invalid_time_limit() -> Self {
+        Self::InvalidInput {
+            field: "--time".to_string(),
+            example: "HH:MM:SS, MM:SS or minutes, e.g. 2:30:00, 90:00 or 90".to_string(),
+        }
+    }
+
+    /// Invalid `field` taking a point in time, with the formats `parse_since_time` accepts.
+    pub fn invalid_since_time(field: &str) -> Self {
+        Self::InvalidInput {
+            field: field.to_string(),
+            example: "1h, 2d, 3w, today, yesterday or a Unix timestamp".to_string(),
+        }
+    }
+
+    fn hints(&self) -> Vec<String> {
+        match self {
+            Self::ConnectionRefused { .. } => {
+                let config = crate::config::config_file_path(None)
+                    .map(|(path, _)| path.display().to_string())
+                    .unwrap_or_else(|_| "gflow.toml".to_string());
+                vec![
+                    "check the daemon with `gflowd status`, or start it with `gflowd up`"
+                        .to_string(),
+                    format!("the address comes from [daemon] in {config}"),
+                ]
+            }
+            Self::Timeout => vec![
+                "the daemon may be busy; retry, or raise `client.request_timeout_secs`".to_string(),
+            ],
+            Self::NotFound { .. } => vec![
+                "finished jobs are hidden by default; list every job with `gqueue --all`"
+                    .to_string(),
+            ],
+            Self::NotOwner { owner, .. } => vec![format!(
+                "act on another user's jobs with `--user {owner}` or `--all-users`"
+            )],
+            Self::InvalidInput { field, example } => vec![format!("{field} accepts {example}")],
+            Self::ClientTooOld { minimum, .. } => vec![
+                format!("upgrade gflow on this machine to {minimum} or later"),
+                format!("run anyway with `--skip-version-check` or {SKIP_VERSION_CHECK_ENV}=1"),
+            ],
+            Self::InvalidTransition {
+                job_id,
+                from,
+                allowed,
+                ..
+            } => {
+                let next = if allowed.is_empty() {
+                    format!("{from} is a final state; job {job_id} cannot change any more")
+                } else {
+                    let allowed: Vec<String> = allowed.iter().map(ToString::to_string).collect();
+                    format!("a {from} job can only go to {}", allowed.join(", "))
+                };
+                vec![
+                    next,
+                    format!("see the job's state with `gjob show {job_id}`"),
+                ]
+            }
+            Self::ServerError { code, .. } => match code.as_str() {
+                "unauthorized" => {
+                    vec!["set GFLOW_TOKEN or `daemon.token` in the config".to_string()]
+                }
+                "job_not_updatable" => {
+                    vec!["see the job's state with `gjob show <id>`".to_string()]
+                }
+                "read_only" => vec!["see why with `gflowd status`".to_string()],
+                "submission_rate_exceeded" => vec![
+                    "wait and submit again, or ask an admin to raise the limit with `gctl rate-limit set`"
+                        .to_string(),
+                ],
+                "exceeds_capacity" => vec![
+                    "see what the cluster has with `ginfo`".to_string(),
+                    "submit with `--force-queue` to queue it anyway, e.g. for hardware yet to arrive"
+                        .to_string(),
+                ],
+                "too_many_queued_jobs" => vec![
+                    "list your queued jobs with `gqueue -s Queued,Hold` and cancel some with `gcancel`"
+                        .to_string(),
+                ],
+                _ => Vec::new(),
+            },
+        }
+    }
+}
+
+impl std::fmt::Display for ClientError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::ConnectionRefused { url } => {
+                write!(
+                    f,
+                    "Could not connect to gflowd at {url}. Is the server running?"
+                )
+            }
+            Self::Timeout => f.write_str("Timed out waiting for gflowd"),
+            Self::NotFound { job_id } => write!(f, "Job {job_id} not found"),
+            Self::NotOwner { job_id, owner } => write!(f, "Job {job_id} belongs to {owner}"),
+            Self::InvalidInput { field, .. } => write!(f, "Invalid {field}"),
+            Self::ClientTooOld { client, minimum } => write!(
+                f,
+                "This client ({client}) is older than gflowd supports (at least {minimum})"
+            ),
+            Self::InvalidTransition { message, .. } | Self::ServerError { message, .. } => {
+                f.write_str(message)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ClientError {}
+
+/// A missing job is reported as [`ClientError::NotFound`], a refused state
+/// change as [`ClientError::InvalidTransition`], other refusals by their code.
+impl From<&DaemonRejected> for ClientError {
+    fn from(rejected: &DaemonRejected) -> Self {
+        match &rejected.error.kind {
+            ApiErrorKind::JobNotFound { id } => Self::NotFound { job_id: *id },
+            ApiErrorKind::InvalidTransition {
+                id,
+                from,
+                to,
+                allowed,
+            } => Self::InvalidTransition {
+                job_id: *id,
+                from: *from,
+                to: *to,
+                allowed: allowed.clone(),
+                message: rejected.error.message.clone(),
+            },
+            kind => Self::ServerError {
+                code: kind.code(),
+                message: rejected.error.message.clone(),
+            },
+        }
+    }
+}
+
+/// `error` as the CLI shows it: the message, its causes, hints for the
+/// failures [`ClientError`] knows and the daemon's request ID, colored if
+/// `color`.
+pub fn render_error(error: &anyhow::Error, color: bool) -> String {
+    let style = OutputStyle {
+        color,
+        ..OutputStyle::default()
+    };
+    let error_label = style.error_label();
+    let hint_label = style.paint("hint:", Style::new().cyan());
+
+    let mut rendered = format!("{error_label} {error}");
+    for cause in error.chain().skip(1) {
+        rendered.push_str(&format!("\n  caused by: {cause}"));
+    }
+
+    let rejected = error.downcast_ref::<DaemonRejected>();
+    let known = error
+        .downcast_ref::<ClientError>()
+        .cloned()
+        .or_else(|| rejected.map(ClientError::from));
+    for hint in known.iter().flat_map(ClientError::hints) {
+        rendered.push_str(&format!("\n  {hint_label} {hint}"));
+    }
+    if let Some(request_id) = rejected.and_then(|rejected| rejected.request_id.as_deref()) {
+        rendered.push_str(&format!("\n  request ID: {request_id}"));
+    }
+    rendered
+}
+
+/// Print `error` to stderr with [`render_error`], colored as [`OutputStyle::stderr`] says.
+pub fn print_error(error: &anyhow::Error) {
+    eprintln!("{}", render_error(error, OutputStyle::stderr().color));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::api_error::ApiError;
+    use anyhow::Context;
+    use reqwest::StatusCode;
+
+    #[test]
+    fn connection_refused_suggests_gflowd_status() {
+        let error = anyhow::Error::from(ClientError::ConnectionRefused {
+            url: "http://127.0.0.1:59000".to_string(),
+        });
+        let rendered = render_error(&error, false);
+        assert!(rendered.starts_with(
+            "error: Could not connect to gflowd at http://127.0.0.1:59000. Is the server running?"
+        ));
+        assert!(rendered.contains("hint: check the daemon with `gflowd status`"));
+        assert!(rendered.contains("hint: the address comes from [daemon] in "));
+    }
+
+    #[test]
+    fn missing_job_suggests_gqueue_all() {
+        let rejected = DaemonRejected {
+            action: "cancel job".to_string(),
+            status: StatusCode::NOT_FOUND,
+            error: ApiError::from(ApiErrorKind::JobNotFound { id: 42 }),
+            request_id: Some("5f0c6a1e".to_string()),
+        };
+        assert_eq!(
+            render_error(&rejected.into(), false),
+            "error: Failed to cancel job: Job 42 not found\n  \
+             hint: finished jobs are hidden by default; list every job with `gqueue --all`\n  \
+             request ID: 5f0c6a1e"
+        );
+    }
+
+    #[test]
+    fn invalid_time_shows_accepted_formats() {
+        let error = crate::utils::parse_time_limit("1:2:3:4")
+            .context(ClientError::invalid_time_limit())
+            .unwrap_err();
+        assert_eq!(
+            render_error(&error, false),
+            "error: Invalid --time\n  \
+             caused by: Invalid time format. Expected formats: HH:MM:SS, MM:SS, or MM\n  \
+             hint: --time accepts HH:MM:SS, MM:SS or minutes, e.g. 2:30:00, 90:00 or 90"
+        );
+    }
+
+    #[test]
+    fn invalid_transition_lists_the_allowed_states() {
+        let rejected = |from| DaemonRejected {
+            action: "finish job".to_string(),
+            status: StatusCode::CONFLICT,
+            error: ApiError::from(ApiErrorKind::invalid_transition(
+                3,
+                from,
+                JobState::Finished,
+            )),
+            request_id: None,
+        };
+        assert_eq!(
+            render_error(&rejected(JobState::Queued).into(), false),
+            "error: Failed to finish job: Job 3 cannot go from Queued to Finished\n  \
+             hint: a Queued job can only go to Hold, Running, Cancelled\n  \
+             hint: see the job's state with `gjob show 3`"
+        );
+        assert!(render_error(&rejected(JobState::Cancelled).into(), false)
+            .contains("hint: Cancelled is a final state; job 3 cannot change any more"));
+    }
+
+    #[test]
+    fn server_errors_keep_their_code_and_other_errors_their_causes() {
+        let rejected = DaemonRejected {
+            action: "submit job".to_string(),
+            status: StatusCode::UNAUTHORIZED,
+            error: ApiError::from(ApiErrorKind::Unauthorized),
+            request_id: None,
+        };
+        assert_eq!(
+            ClientError::from(&rejected),
+            ClientError::ServerError {
+                code: "unauthorized".to_string(),
+                message: "Missing or invalid API token".to_string(),
+            }
+        );
+        assert!(render_error(&rejected.into(), false)
+            .ends_with("hint: set GFLOW_TOKEN or `daemon.token` in the config"));
+
+        let plain = anyhow::anyhow!("disk full").context("Failed to save state");
+        assert_eq!(
+            render_error(&plain, false),
+            "error: Failed to save state\n  caused by: disk full"
+        );
+        assert!(render_error(&plain, true).contains("\u{1b}["));
+    }
+}
@@ -0,0 +1,115 @@
+//! Whose jobs a CLI command acts on: [`UserScope`].
+
+use super::{Client, ClientError};
+use crate::core::job::Job;
+
+/// The users whose jobs a command may list or change.
+///
+/// By default that is the invoking user, like `squeue -u $USER`; `--user`
+/// names someone else and `--all-users` lifts the limit. The daemon still
+/// enforces ownership itself when it requires tokens.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UserScope {
+    /// The invoking user, by the name `gbatch` submits under
+    Current(String),
+    /// The users named with `--user`
+    Users(Vec<String>),
+    All,
+}
+
+impl UserScope {
+    /// The scope `--user` and `--all-users` ask for. Without either, only the
+    /// invoking user's jobs are in scope if `default_user_filter` is set
+    /// (`[gqueue] default_user_filter`), and everyone's otherwise.
+    pub fn from_args(user: Option<&str>, all_users: bool, default_user_filter: bool) -> Self {
+        if all_users {
+            return Self::All;
+        }
+        match user.map(str::trim) {
+            Some("all") | Some("*") => Self::All,
+            Some(users) if !users.is_empty() => Self::Users(
+                users
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|user| !user.is_empty())
+                    .map(str::to_string)
+                    .collect(),
+            ),
+            _ if default_user_filter => Self::current(),
+            _ => Self::All,
+        }
+    }
+
+    pub fn current() -> Self {
+        Self::Current(crate::platform::get_current_username())
+    }
+
+    pub fn allows(&self, owner: &str) -> bool {
+        match self {
+            Self::Current(user) => user == owner,
+            Self::Users(users) => users.iter().any(|user| user == owner),
+            Self::All => true,
+        }
+    }
+
+    /// The comma-separated `user` filter for listing jobs; `None` for everyone.
+    pub fn user_filter(&self) -> Option<String> {
+        match self {
+            Self::Current(user) => Some(user.clone()),
+            Self::Users(users) => Some(users.join(",")),
+            Self::All => None,
+        }
+    }
+}
+
+impl Client {
+    /// Job `job_id`, if `scope` allows acting on it.
+    ///
+    /// Fails with [`ClientError::NotFound`] for a missing job and
+    /// [`ClientError::NotOwner`] for another user's.
+    pub async fn get_job_in_scope(&self, job_id: u32, scope: &UserScope) -> anyhow::Result<Job> {
+        let job = self
+            .get_job(job_id)
+            .await?
+            .ok_or(ClientError::NotFound { job_id })?;
+        if !scope.allows(&job.submitted_by) {
+            return Err(ClientError::NotOwner {
+                job_id,
+                owner: job.submitted_by.to_string(),
+            }
+            .into());
+        }
+        Ok(job)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flags_widen_the_default_scope() {
+        let me = crate::platform::get_current_username();
+        assert_eq!(
+            UserScope::from_args(None, false, true),
+            UserScope::Current(me.clone())
+        );
+        assert_eq!(UserScope::from_args(None, false, false), UserScope::All);
+        assert_eq!(UserScope::from_args(None, true, true), UserScope::All);
+        assert_eq!(
+            UserScope::from_args(Some("all"), false, true),
+            UserScope::All
+        );
+        assert_eq!(
+            UserScope::from_args(Some(" "), false, true),
+            UserScope::Current(me.clone())
+        );
+
+        let scope = UserScope::from_args(Some("alice, bob"), false, true);
+        assert_eq!(scope.user_filter().as_deref(), Some("alice,bob"));
+        assert!(scope.allows("bob"));
+        assert!(!scope.allows(&format!("{me}-other")));
+        assert!(UserScope::All.allows("anyone"));
+        assert_eq!(UserScope::All.user_filter(), None);
+    }
+}
@@ -0,0 +1,330 @@
+//! Errors the daemon sends back when it refuses a request.
+//!
+//! On the wire an error is `{"code": "...", "message": "...", "details": {...}}`:
+//! `code` and `details` come from [`ApiErrorKind`] so clients can match on them,
+//! and `message` is the text to show a person.
+
+use crate::core::info::Capacity;
+use crate::core::job::JobState;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// A refused request: what went wrong, and a message for the user.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ApiError {
+    #[serde(flatten)]
+    pub kind: ApiErrorKind,
+    pub message: String,
+}
+
+/// Machine-readable reason for an [`ApiError`], serialized as its `code` and `details`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "code", content = "details", rename_all = "snake_case")]
+pub enum ApiErrorKind {
+    JobNotFound {
+        id: u32,
+    },
+    /// The job exists but its state does not allow the requested change
+    InvalidTransition {
+        id: u32,
+        from: JobState,
+        to: JobState,
+        /// Where a job in `from` can go instead; empty if `from` is final
+        #[serde(default)]
+        allowed: Vec<JobState>,
+    },
+    /// Only queued or held jobs can be updated
+    JobNotUpdatable {
+        id: u32,
+        state: JobState,
+    },
+    /// Only running jobs can be signalled
+    JobNotRunning {
+        id: u32,
+        state: JobState,
+    },
+    DependencyMissing {
+        id: u32,
+    },
+    /// The job does not say which user submitted it
+    MissingUser,
+    /// `id` would depend on `dependency`, which already depends on `id`
+    CircularDependency {
+        id: u32,
+        dependency: u32,
+    },
+    /// A `submission_id` was sent again with a different job
+    SubmissionConflict {
+        submission_id: Uuid,
+        job_id: u32,
+    },
+    /// `duplicate_run_names = "reject"` and an active job has the name
+    RunNameInUse {
+        name: String,
+        job_id: u32,
+    },
+    BatchTooLarge {
+        max: usize,
+    },
+    /// The daemon could not save the batch, so none of its jobs were created
+    BatchNotSaved {
+        count: usize,
+    },
+    ReservationNotFound {
+        id: u32,
+    },
+    /// The job asks for a higher priority than its user may use
+    PriorityTooHigh {
+        priority: u8,
+        max: u8,
+        /// Configured priority classes at or below `max`
+        #[serde(default)]
+        allowed: Vec<String>,
+    },
+    /// The user submitted more jobs in the last minute than allowed
+    SubmissionRateExceeded {
+        per_minute: u32,
+        /// Seconds until the submission would be accepted
+        retry_after_secs: u64,
+    },
+    /// The user already has as many queued and held jobs as allowed
+    TooManyQueuedJobs {
+        queued: usize,
+        max: usize,
+    },
+    /// The job asks for more than any machine could ever give it
+    ExceedsCapacity {
+        requested: Capacity,
+        capacity: Capacity,
+    },
+    /// Malformed or invalid request; the message says what is wrong
+    InvalidRequest,
+    /// Something other than a job or reservation does not exist
+    NotFound,
+    /// The request clashes with the current state, e.g. GPUs already reserved
+    Conflict,
+    Unauthorized,
+    Forbidden,
+    ReadOnly,
+    Internal,
+    /// A code this client does not know, from a newer daemon
+    #[serde(other)]
+    Unknown,
+}
+
+impl ApiError {
+    pub fn new(kind: ApiErrorKind, message: impl Into<String>) -> Self {
+        Self {
+            kind,
+            message: message.into(),
+        }
+    }
+
+    pub fn invalid_request(message: impl Into<String>) -> Self {
+        Self::new(ApiErrorKind::InvalidRequest, message)
+    }
+
+    pub fn not_found(message: impl Into<String>) -> Self {
+        Self::new(ApiErrorKind::NotFound, message)
+    }
+
+    pub fn conflict(message: impl Into<String>) -> Self {
+        Self::new(ApiErrorKind::Conflict, message)
+    }
+
+    pub fn forbidden(message: impl Into<String>) -> Self {
+        Self::new(ApiErrorKind::Forbidden, message)
+    }
+
+    pub fn internal(message: impl Into<String>) -> Self {
+        Self::new(ApiErrorKind::Internal, message)
+    }
+
+    /// HTTP status the daemon answers with.
+    pub fn status(&self) -> u16 {
+        match self.kind {
+            ApiErrorKind::InvalidRequest
+            | ApiErrorKind::ExceedsCapacity { .. }
+            | ApiErrorKind::DependencyMissing { .. }
+            | ApiErrorKind::MissingUser
+            | ApiErrorKind::CircularDependency { .. } => 400,
+            ApiErrorKind::Unauthorized => 401,
+            ApiErrorKind::Forbidden | ApiErrorKind::PriorityTooHigh { .. } => 403,
+            ApiErrorKind::JobNotFound { .. }
+            | ApiErrorKind::ReservationNotFound { .. }
+            | ApiErrorKind::NotFound => 404,
+            ApiErrorKind::InvalidTransition { .. }
+            | ApiErrorKind::JobNotUpdatable { .. }
+            | ApiErrorKind::JobNotRunning { .. }
+            | ApiErrorKind::SubmissionConflict { .. }
+            | ApiErrorKind::RunNameInUse { .. }
+            | ApiErrorKind::Conflict => 409,
+            ApiErrorKind::BatchTooLarge { .. } => 413,
+            ApiErrorKind::SubmissionRateExceeded { .. }
+            | ApiErrorKind::TooManyQueuedJobs { .. } => 429,
+            ApiErrorKind::ReadOnly => 503,
+            ApiErrorKind::BatchNotSaved { .. } | ApiErrorKind::Internal | ApiErrorKind::Unknown => {
+                500
+            }
+        }
+    }
+
+    /// Seconds a client should wait before retrying, sent as `Retry-After`.
+    /// Queued jobs free up as they start, so a full queue is worth checking
+    /// again after a minute.
+    pub fn retry_after_secs(&self) -> Option<u64> {
+        match self.kind {
+            ApiErrorKind::SubmissionRateExceeded {
+                retry_after_secs, ..
+            } => Some(retry_after_secs),
+            ApiErrorKind::TooManyQueuedJobs { .. } => Some(60),
+            _ => None,
+        }
+    }
+}
+
+impl ApiErrorKind {
+    /// Job `id` cannot go from `from` to `to`, with the states it could go to.
+    pub fn invalid_transition(id: u32, from: JobState, to: JobState) -> Self {
+        Self::InvalidTransition {
+            id,
+            from,
+            to,
+            allowed: from.allowed_transitions(),
+        }
+    }
+
+    /// The `code` sent on the wire, e.g. `job_not_found`.
+    pub fn code(&self) -> String {
+        serde_json::to_value(self)
+            .ok()
+            .and_then(|json| json.get("code")?.as_str().map(str::to_owned))
+            .unwrap_or_else(|| "unknown".to_string())
+    }
+}
+
+/// The error with its default message.
+impl From<ApiErrorKind> for ApiError {
+    fn from(kind: ApiErrorKind) -> Self {
+        let message = match &kind {
+            ApiErrorKind::JobNotFound { id } => format!("Job {id} not found"),
+            ApiErrorKind::InvalidTransition { id, from, to, .. } => {
+                format!("Job {id} cannot go from {from} to {to}")
+            }
+            ApiErrorKind::JobNotUpdatable { id, state } => format!(
+                "Job {id} is in state '{state}' and cannot be updated. Only queued or held jobs can be updated."
+            ),
+            ApiErrorKind::JobNotRunning { id, state } => {
+                format!("Job {id} is {state}, not Running; only running jobs can be signalled")
+            }
+            ApiErrorKind::DependencyMissing { id } => format!("Dependency job {id} does not exist"),
+            ApiErrorKind::MissingUser => {
+                "The job has no submitted_by user; set USER or upgrade the client".to_string()
+            }
+            ApiErrorKind::CircularDependency { id, dependency } => format!(
+                "Circular dependency detected: Job {id} depends on Job {dependency}, \
+                 which has a path back to Job {id}"
+            ),
+            ApiErrorKind::SubmissionConflict {
+                submission_id,
+                job_id,
+            } => format!(
+                "Submission ID {submission_id} was already used for job {job_id} with a different payload"
+            ),
+            ApiErrorKind::RunNameInUse { name, job_id } => format!(
+                "Job {job_id} is already active under the name '{name}'; pick another --name"
+            ),
+            ApiErrorKind::BatchTooLarge { max } => {
+                format!("Batch size exceeds maximum of {max} jobs")
+            }
+            ApiErrorKind::BatchNotSaved { count } => format!(
+                "The daemon could not save the batch; none of its {count} jobs were created"
+            ),
+            ApiErrorKind::ReservationNotFound { id } => format!("Reservation {id} not found"),
+            ApiErrorKind::PriorityTooHigh {
+                priority,
+                max,
+                allowed,
+            } => {
+                let mut message = format!("Priority {priority} is above your limit of {max}");
+                if !allowed.is_empty() {
+                    message.push_str(&format!("; allowed classes: {}", allowed.join(", ")));
+                }
+                message
+            }
+            ApiErrorKind::SubmissionRateExceeded {
+                per_minute,
+                retry_after_secs,
+            } => format!(
+                "Too many submissions: the limit is {per_minute} jobs per minute; retry in {retry_after_secs}s"
+            ),
+            ApiErrorKind::TooManyQueuedJobs { queued, max } => format!(
+                "You have {queued} queued jobs and the limit is {max}; cancel some or wait for them to start"
+            ),
+            ApiErrorKind::ExceedsCapacity {
+                requested,
+                capacity,
+            } => format!(
+                "The job asks for more than any machine can ever give it: {}",
+                capacity.shortfalls(requested).join(", ")
+            ),
+            ApiErrorKind::InvalidRequest => "Invalid request".to_string(),
+            ApiErrorKind::NotFound => "Not found".to_string(),
+            ApiErrorKind::Conflict => "Conflict".to_string(),
+            ApiErrorKind::Unauthorized => "Missing or invalid API token".to_string(),
+            ApiErrorKind::Forbidden => "Forbidden".to_string(),
+            ApiErrorKind::ReadOnly => "Daemon is read-only".to_string(),
+            ApiErrorKind::Internal | ApiErrorKind::Unknown => "Internal error".to_string(),
+        };
+        Self { kind, message }
+    }
+}
+
+impl std::fmt::Display for ApiError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for ApiError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn serializes_as_code_message_and_details() {
+        let error = ApiError::from(ApiErrorKind::invalid_transition(
+            7,
+            JobState::Finished,
+            JobState::Cancelled,
+        ));
+        assert_eq!(error.status(), 409);
+        assert_eq!(
+            serde_json::to_value(&error).unwrap(),
+            json!({
+                "code": "invalid_transition",
+                "details": {"id": 7, "from": "Finished", "to": "Cancelled", "allowed": []},
+                "message": "Job 7 cannot go from Finished to Cancelled",
+            })
+        );
+
+        let read_only = serde_json::to_value(ApiError::from(ApiErrorKind::ReadOnly)).unwrap();
+        assert_eq!(read_only["code"], "read_only");
+        assert_eq!(ApiErrorKind::ReadOnly.code(), "read_only");
+        assert!(read_only.get("details").is_none());
+    }
+
+    #[test]
+    fn round_trips_and_tolerates_unknown_codes() {
+        let error = ApiError::from(ApiErrorKind::JobNotFound { id: 3 });
+        let json = serde_json::to_string(&error).unwrap();
+        assert_eq!(serde_json::from_str::<ApiError>(&json).unwrap(), error);
+
+        let newer: ApiError =
+            serde_json::from_value(json!({"code": "something_new", "message": "no"})).unwrap();
+        assert_eq!(newer.kind, ApiErrorKind::Unknown);
+        assert_eq!(newer.message, "no");
+    }
+}
@@ -226,6 +226,7 @@ pub fn check_reservation_conflict(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::core::reservation::ReservationKind;
     use std::time::Duration;
 
     fn create_test_reservation(
@@ -243,6 +244,8 @@ mod tests {
             status: ReservationStatus::Pending,
             created_at: SystemTime::UNIX_EPOCH,
             cancelled_at: None,
+            kind: ReservationKind::User,
+            recurrence: None,
         }
     }
 
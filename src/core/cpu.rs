@@ -0,0 +1,96 @@
+//! CPU cores: how many the machine has, and which ones a job is pinned to.
+
+use crate::core::job::CpuIds;
+
+/// Number of logical CPUs, from the `processor` entries of /proc/cpuinfo,
+/// falling back to what the standard library reports. Returns 0 if unknown.
+pub fn total_cpus() -> u32 {
+    if let Ok(content) = std::fs::read_to_string("/proc/cpuinfo") {
+        let processors = content
+            .lines()
+            .filter(|line| {
+                line.split(':')
+                    .next()
+                    .is_some_and(|key| key.trim() == "processor")
+            })
+            .count();
+        if processors > 0 {
+            return processors as u32;
+        }
+    }
+    std::thread::available_parallelism()
+        .map(|count| count.get() as u32)
+        .unwrap_or(0)
+}
+
+/// Pick `count` of the `free` cores: the lowest run of consecutive cores if
+/// there is one long enough, otherwise the lowest free cores.
+///
+/// `free` must be sorted. Returns `None` if fewer than `count` are free.
+pub fn pick_cpus(free: &[u32], count: u32) -> Option<CpuIds> {
+    let count = count as usize;
+    if count == 0 || free.len() < count {
+        return None;
+    }
+    let mut run_start = 0;
+    for i in 0..free.len() {
+        if i > 0 && free[i] != free[i - 1] + 1 {
+            run_start = i;
+        }
+        if i + 1 - run_start == count {
+            return Some(free[run_start..=i].iter().copied().collect());
+        }
+    }
+    Some(free[..count].iter().copied().collect())
+}
+
+/// Format cores the way `taskset -c` takes them, e.g. `0-3,8`.
+pub fn format_cpu_list(cpus: &[u32]) -> String {
+    let mut ranges: Vec<(u32, u32)> = Vec::new();
+    for &cpu in cpus {
+        match ranges.last_mut() {
+            Some((_, end)) if cpu == *end + 1 => *end = cpu,
+            _ => ranges.push((cpu, cpu)),
+        }
+    }
+    ranges
+        .into_iter()
+        .map(|(start, end)| {
+            if start == end {
+                start.to_string()
+            } else {
+                format!("{start}-{end}")
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pick_cpus_prefers_a_contiguous_run() {
+        // 0 and 2-3 are taken by other jobs
+        let free = [1, 4, 5, 6, 7];
+        assert_eq!(pick_cpus(&free, 3).unwrap().as_slice(), &[4, 5, 6]);
+        assert_eq!(pick_cpus(&free, 1).unwrap().as_slice(), &[1]);
+    }
+
+    #[test]
+    fn pick_cpus_falls_back_to_the_lowest_free_cores() {
+        let free = [0, 2, 4, 6];
+        assert_eq!(pick_cpus(&free, 2).unwrap().as_slice(), &[0, 2]);
+        assert_eq!(pick_cpus(&free, 5), None);
+        assert_eq!(pick_cpus(&free, 0), None);
+    }
+
+    #[test]
+    fn format_cpu_list_collapses_runs() {
+        assert_eq!(format_cpu_list(&[0, 1, 2, 3, 8]), "0-3,8");
+        assert_eq!(format_cpu_list(&[5]), "5");
+        assert_eq!(format_cpu_list(&[1, 3, 4]), "1,3-4");
+        assert_eq!(format_cpu_list(&[]), "");
+    }
+}
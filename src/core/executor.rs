@@ -1,6 +1,82 @@
-use crate::core::job::Job;
-use anyhow::Result;
+use crate::core::job::{Job, JobSignal};
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumString};
+
+/// How gflowd starts a job's command.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Default,
+    Serialize,
+    Deserialize,
+    EnumString,
+    Display,
+    schemars::JsonSchema,
+    utoipa::ToSchema,
+)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case", ascii_case_insensitive)]
+pub enum ExecutorKind {
+    /// In a tmux session per job, which `gjob attach` can join.
+    #[default]
+    Tmux,
+    /// As a child process group of the daemon; no tmux needed.
+    Direct,
+}
+
+/// Memory a job used, as its executor measured it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MemoryUsage {
+    /// Most memory the job's processes used at once, in MB.
+    pub peak_mb: Option<u64>,
+    /// Whether the kernel killed one of them for exceeding the job's limit.
+    pub oom_killed: bool,
+}
 
 pub trait Executor: Send + Sync {
     fn execute(&self, job: &Job) -> Result<()>;
+
+    /// Ask a running job to stop, as Ctrl-C in its terminal would.
+    fn interrupt(&self, _job: &Job) -> Result<()> {
+        Ok(())
+    }
+
+    /// Send `signal` to the processes of a running job, leaving its state to
+    /// whatever they do about it.
+    fn signal(&self, job: &Job, signal: JobSignal) -> Result<()> {
+        bail!(
+            "Cannot send {signal} to job {}: its executor does not support signals",
+            job.id
+        )
+    }
+
+    /// Make sure an interrupted job stops, e.g. stop its container. May block
+    /// for a while, so callers run it off the scheduler loop.
+    fn stop(&self, _job: &Job) -> Result<()> {
+        Ok(())
+    }
+
+    /// Whether the job's workload is still running. `None` means unknown.
+    fn is_running(&self, _job: &Job) -> Option<bool> {
+        None
+    }
+
+    /// Release what the executor keeps for a job that is no longer running.
+    /// With `close`, also close the tmux session left open for inspection.
+    fn release(&self, _job: &Job, _close: bool) {}
+
+    /// PID of the process the job's command runs under, so that the job is
+    /// its process tree. `None` if unknown.
+    fn pid(&self, _job: &Job) -> Option<u32> {
+        None
+    }
+
+    /// Memory the job used so far, if the executor tracks it.
+    fn memory_usage(&self, _job: &Job) -> Option<MemoryUsage> {
+        None
+    }
 }
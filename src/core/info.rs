@@ -1,6 +1,12 @@
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::time::SystemTime;
 
 use super::gpu_allocation::GpuAllocationStrategy;
+use super::job::JobState;
+use super::reservation::GpuReservation;
+use crate::utils::constraint::Labels;
+use crate::utils::disk::DiskUsage;
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct IgnoredGpuProcess {
@@ -8,7 +14,7 @@ pub struct IgnoredGpuProcess {
     pub pid: u32,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
 pub struct GpuInfo {
     pub uuid: String,
     pub index: u32,
@@ -16,13 +22,370 @@ pub struct GpuInfo {
     /// Reason why GPU is unavailable (e.g., occupied by non-gflow process)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub reason: Option<String>,
+    /// The machine's labels plus the GPU's own (`daemon.gpu_labels`)
+    #[serde(default, skip_serializing_if = "Labels::is_empty")]
+    pub labels: Labels,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
 pub struct SchedulerInfo {
     pub gpus: Vec<GpuInfo>,
     /// GPU indices that scheduler is configured to use (None = all GPUs)
     pub allowed_gpu_indices: Option<Vec<u32>>,
     /// Strategy used when allocating GPUs for new jobs.
     pub gpu_allocation_strategy: GpuAllocationStrategy,
+    /// Labels of the daemon's machine (`daemon.labels`)
+    #[serde(default, skip_serializing_if = "Labels::is_empty")]
+    pub labels: Labels,
+}
+
+/// GPUs, host memory and CPU cores, as asked for by a job or as the most
+/// any one machine of the cluster has.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Capacity {
+    pub gpus: u32,
+    /// In MB; `None` if not asked for, or unknown
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memory_mb: Option<u64>,
+    /// `None` if not asked for, or not limited
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cpus: Option<u32>,
+}
+
+impl Capacity {
+    /// What of `requested` goes beyond this capacity, e.g. `16 GPUs (at most 8)`.
+    pub fn shortfalls(&self, requested: &Capacity) -> Vec<String> {
+        let mut shortfalls = Vec::new();
+        if requested.gpus > self.gpus {
+            shortfalls.push(format!("{} GPUs (at most {})", requested.gpus, self.gpus));
+        }
+        if let (Some(requested), Some(max)) = (requested.memory_mb, self.memory_mb) {
+            if requested > max {
+                shortfalls.push(format!(
+                    "{} memory (at most {})",
+                    crate::utils::format_memory(requested),
+                    crate::utils::format_memory(max)
+                ));
+            }
+        }
+        if let (Some(requested), Some(max)) = (requested.cpus, self.cpus) {
+            if requested > max {
+                shortfalls.push(format!("{requested} CPU cores (at most {max})"));
+            }
+        }
+        shortfalls
+    }
+}
+
+/// Persistence health as seen by the daemon (mirrors the `/health` payload).
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct PersistenceStatus {
+    /// One of `state`, `journal`, or `read_only`.
+    pub mode: String,
+    pub state_writable: bool,
+    pub journal_writable: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub state_load_error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub journal_error: Option<String>,
+}
+
+/// Point-in-time snapshot of the scheduler, served by `GET /info/stats`.
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct SchedulerStats {
+    pub version: String,
+    pub pid: u32,
+    pub uptime_secs: u64,
+    /// Number of jobs in each state (states with no jobs are omitted).
+    pub jobs_by_state: BTreeMap<JobState, usize>,
+    pub total_gpus: usize,
+    pub available_gpus: usize,
+    pub gpus: Vec<GpuInfo>,
+    pub total_memory_mb: u64,
+    /// Memory held by jobs running on this machine.
+    #[serde(default)]
+    pub used_memory_mb: u64,
+    pub available_memory_mb: u64,
+    /// Logical CPUs of this machine (0 if unknown).
+    #[serde(default)]
+    pub total_cpus: u32,
+    /// Cores jobs running on this machine are pinned to.
+    #[serde(default)]
+    pub used_cpus: u32,
+    pub active_reservations: Vec<GpuReservation>,
+    pub persistence: PersistenceStatus,
+    /// Webhook deliveries waiting in the outbox for a retry.
+    #[serde(default)]
+    pub pending_webhook_deliveries: usize,
+    /// Free space of the data dir and of the filesystems holding the run_dirs
+    /// of queued and running jobs, one entry per filesystem.
+    #[serde(default)]
+    pub disks: Vec<DiskUsage>,
+    /// Requests from clients older than the daemon.
+    #[serde(default)]
+    pub clients: ClientVersionStats,
+    /// GPU demand, longest wait and expected all-clear of the queue.
+    #[serde(default)]
+    pub queue: crate::core::scheduler::QueueSummary,
+}
+
+/// Requests since startup by clients older than the daemon, which tell
+/// admins when `daemon.min_client_version` can be raised.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct ClientVersionStats {
+    /// Requests from gflow clients older than the daemon.
+    pub outdated_requests: u64,
+    /// `outdated_requests` by client version.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub outdated_by_version: BTreeMap<String, u64>,
+    /// Requests without a client version: gflow clients that predate it,
+    /// scripts, and the web UI.
+    #[serde(default)]
+    pub unversioned_requests: u64,
+}
+
+/// The daemon's version and the oldest client it accepts, served by
+/// `GET /version`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct VersionInfo {
+    #[schema(value_type = String, example = "0.4.17")]
+    pub version: crate::core::version::Version,
+    /// Clients older than this are refused by the CLI.
+    #[schema(value_type = String, example = "0.4.0")]
+    pub min_client_version: crate::core::version::Version,
+}
+
+impl VersionInfo {
+    /// How a client of this build fits the daemon.
+    pub fn compatibility(&self) -> crate::core::version::Compatibility {
+        crate::core::version::Compatibility::of(
+            crate::core::version::Version::current(),
+            self.version,
+            self.min_client_version,
+        )
+    }
+}
+
+impl SchedulerStats {
+    /// Number of jobs currently in `state`.
+    pub fn count(&self, state: JobState) -> usize {
+        self.jobs_by_state.get(&state).copied().unwrap_or(0)
+    }
+
+    /// One-line summary of the active queue, e.g. `12 queued / 4 running / 2 hold`.
+    pub fn queue_summary(&self) -> String {
+        format!(
+            "{} queued / {} running / {} hold",
+            self.count(JobState::Queued),
+            self.count(JobState::Running),
+            self.count(JobState::Hold)
+        )
+    }
+
+    /// The line `gqueue` prints above its table, e.g. `4 running (7 GPUs),
+    /// 12 queued (9 GPUs), 2 hold, 1/8 GPUs free, oldest queued 3h, est.
+    /// all-clear ~22:40`. `now` is a Unix timestamp.
+    pub fn queue_overview(&self, now: i64) -> String {
+        let running = self.count(JobState::Running);
+        let queued = self.count(JobState::Queued);
+        let mut parts = vec![
+            format!("{running} running ({} GPUs)", self.queue.running_gpus),
+            format!("{queued} queued ({} GPUs)", self.queue.queued_gpus),
+        ];
+        let held = self.count(JobState::Hold);
+        if held > 0 {
+            parts.push(format!("{held} hold"));
+        }
+        if self.total_gpus > 0 {
+            parts.push(format!(
+                "{}/{} GPUs free",
+                self.available_gpus, self.total_gpus
+            ));
+        }
+        if let Some(oldest) = self.queue.oldest_queued_at {
+            let waited = std::time::Duration::from_secs(now.saturating_sub(oldest).max(0) as u64);
+            parts.push(format!(
+                "oldest queued {}",
+                crate::utils::format_duration_compact(waited)
+            ));
+        }
+        if running + queued > 0 {
+            let all_clear = match self.queue.all_clear_at {
+                Some(at) => crate::utils::format_start_estimate(
+                    &crate::core::scheduler::StartEstimate::At { at, jobs_ahead: 0 },
+                    now,
+                ),
+                None => "unknown".to_string(),
+            };
+            parts.push(format!("est. all-clear {all_clear}"));
+        }
+        parts.join(", ")
+    }
+
+    /// One-line summary of host memory, e.g. `24G used / 64G total (40G available)`.
+    pub fn memory_summary(&self) -> String {
+        format!(
+            "{} used / {} total ({} available)",
+            crate::utils::format_memory(self.used_memory_mb),
+            crate::utils::format_memory(self.total_memory_mb),
+            crate::utils::format_memory(self.available_memory_mb)
+        )
+    }
+
+    /// One-line summary of CPU cores, e.g. `6 pinned / 32 total (26 free)`.
+    pub fn cpu_summary(&self) -> String {
+        format!(
+            "{} pinned / {} total ({} free)",
+            self.used_cpus,
+            self.total_cpus,
+            self.total_cpus.saturating_sub(self.used_cpus)
+        )
+    }
+}
+
+/// How the daemon's startup went, served by `GET /startupz`.
+/// Delivery health of one configured webhook, served by
+/// `GET /notifications/webhooks`.
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct WebhookTargetStatus {
+    /// Position in `[[notifications.webhooks]]`, starting at 0.
+    pub index: usize,
+    /// Scheme, host and port of the URL. The path is left out because hook
+    /// URLs often carry a secret.
+    pub url: String,
+    /// Deliveries waiting in the outbox for a retry.
+    pub pending: usize,
+    #[schema(value_type = Option<crate::core::schema::SystemTimeSchema>)]
+    pub last_success_at: Option<SystemTime>,
+    #[schema(value_type = Option<crate::core::schema::SystemTimeSchema>)]
+    pub last_error_at: Option<SystemTime>,
+    pub last_error: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StartupInfo {
+    /// Number of jobs restored from state.json or the journal.
+    pub jobs_loaded: usize,
+    /// Time spent loading state and opening the journal.
+    pub load_duration_ms: u64,
+    /// Whether NVML initialized (false on machines without NVIDIA GPUs).
+    pub nvml_initialized: bool,
+    /// Whether state was restored from a journal snapshot rather than state.json.
+    pub journal_applied: bool,
+}
+
+/// Outcome of the daemon re-reading its config file (SIGHUP or `POST /config/reload`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigReloadReport {
+    /// What triggered the reload: `signal` or `api`.
+    pub trigger: String,
+    /// RFC 3339 timestamp of the reload attempt.
+    pub reloaded_at: String,
+    /// Config keys whose new values are now in effect.
+    pub applied: Vec<String>,
+    /// Config keys that changed but were not applied, as `key: reason`.
+    pub rejected: Vec<String>,
+    /// Set when the config file could not be loaded; nothing was applied.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Outcome of `POST /state/restore`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StateRestoreReport {
+    /// Backup file the state was restored from.
+    pub restored_from: String,
+    /// Number of jobs in the restored state.
+    pub jobs: usize,
+    /// ID the next submitted job receives.
+    pub next_job_id: u32,
+    /// Backup of the state that was replaced.
+    pub replaced_state_backup: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn queue_summary_defaults_missing_states_to_zero() {
+        let stats = SchedulerStats {
+            version: "0.0.0".to_string(),
+            pid: 1,
+            uptime_secs: 0,
+            jobs_by_state: BTreeMap::from([(JobState::Queued, 12), (JobState::Running, 4)]),
+            total_gpus: 0,
+            available_gpus: 0,
+            gpus: vec![],
+            total_memory_mb: 64 * 1024,
+            used_memory_mb: 24 * 1024,
+            available_memory_mb: 40 * 1024,
+            total_cpus: 32,
+            used_cpus: 6,
+            active_reservations: vec![],
+            persistence: PersistenceStatus {
+                mode: "state".to_string(),
+                state_writable: true,
+                journal_writable: false,
+                state_load_error: None,
+                journal_error: None,
+            },
+            pending_webhook_deliveries: 0,
+            disks: vec![],
+            clients: ClientVersionStats::default(),
+            queue: Default::default(),
+        };
+
+        assert_eq!(stats.queue_summary(), "12 queued / 4 running / 0 hold");
+        assert_eq!(stats.count(JobState::Finished), 0);
+        assert_eq!(
+            stats.memory_summary(),
+            "24G used / 64G total (40G available)"
+        );
+        assert_eq!(stats.cpu_summary(), "6 pinned / 32 total (26 free)");
+    }
+
+    #[test]
+    fn queue_overview_leaves_out_what_is_unknown_or_empty() {
+        let mut stats: SchedulerStats = serde_json::from_value(serde_json::json!({
+            "version": "0.0.0",
+            "pid": 1,
+            "uptime_secs": 0,
+            "jobs_by_state": { "Queued": 12, "Running": 4, "Hold": 2 },
+            "total_gpus": 8,
+            "available_gpus": 1,
+            "gpus": [],
+            "total_memory_mb": 0,
+            "available_memory_mb": 0,
+            "active_reservations": [],
+            "persistence": {
+                "mode": "state",
+                "state_writable": true,
+                "journal_writable": false,
+                "state_load_error": null,
+                "journal_error": null
+            },
+            "queue": {
+                "running_gpus": 7,
+                "queued_gpus": 9,
+                "oldest_queued_at": 1000,
+                "all_clear_at": null
+            }
+        }))
+        .unwrap();
+
+        assert_eq!(
+            stats.queue_overview(1000 + 3 * 3600),
+            "4 running (7 GPUs), 12 queued (9 GPUs), 2 hold, 1/8 GPUs free, \
+             oldest queued 3h, est. all-clear unknown"
+        );
+
+        stats.jobs_by_state.clear();
+        stats.total_gpus = 0;
+        stats.queue = Default::default();
+        assert_eq!(
+            stats.queue_overview(1000),
+            "0 running (0 GPUs), 0 queued (0 GPUs)"
+        );
+    }
 }
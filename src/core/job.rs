@@ -1,10 +1,24 @@
+mod artifact;
+mod environment;
+mod group;
+mod interned;
 mod model;
 mod parameters;
+mod progress;
+mod signal;
 mod state;
+mod tags;
 
+pub use artifact::{JobArtifact, MAX_ARTIFACTS_PER_JOB};
+pub use environment::EnvSpec;
+pub use group::JobGroup;
+pub use interned::{InternedStr, StringPool};
 pub use model::{Job, JobBuilder, JobNotifications, JobRuntime, JobSpec, JobView};
-pub use parameters::{DependencyIds, GpuIds, Parameters};
+pub use parameters::{CpuIds, DependencyIds, GpuIds, Parameters};
+pub use progress::{JobProgress, PROGRESS_STALE_AFTER};
+pub use signal::JobSignal;
 pub use state::{DependencyMode, GpuSharingMode, JobError, JobState, JobStateReason};
+pub use tags::{validate_tag, Tags, MAX_TAGS_PER_JOB};
 
 use serde::{Deserialize, Deserializer, Serializer};
 use uuid::Uuid;
@@ -42,6 +56,40 @@ mod tests {
     use std::collections::HashMap;
     use uuid::Uuid;
 
+    #[test]
+    fn test_job_structs_stay_compact() {
+        // A queue holds 100k of these; grow them only on purpose.
+        assert!(std::mem::size_of::<Job>() <= 768);
+        assert!(std::mem::size_of::<JobSpec>() <= 392);
+        assert_eq!(std::mem::size_of::<Parameters>(), 16);
+        assert_eq!(std::mem::size_of::<InternedStr>(), 16);
+    }
+
+    #[test]
+    fn test_interned_fields_serialize_like_strings() {
+        let mut params = HashMap::new();
+        params.insert("lr".to_string(), "0.01".to_string());
+        let job = JobBuilder::new()
+            .submitted_by("alice")
+            .conda_env(Some("torch".to_string()))
+            .parameters(params)
+            .build();
+        let json = serde_json::to_value(&job).unwrap();
+        assert_eq!(json["submitted_by"], "alice");
+        assert_eq!(json["conda_env"], "torch");
+        assert_eq!(json["environment"]["conda"], "torch");
+        assert_eq!(json["parameters"]["lr"], "0.01");
+
+        let bare = serde_json::to_value(JobBuilder::new().build()).unwrap();
+        assert_eq!(bare["parameters"], serde_json::json!({}));
+
+        let back: Job = serde_json::from_value(json.clone()).unwrap();
+        assert_eq!(serde_json::to_value(&back).unwrap(), json);
+        let bytes = rmp_serde::to_vec_named(&job).unwrap();
+        let back: Job = rmp_serde::from_slice(&bytes).unwrap();
+        assert_eq!(serde_json::to_value(&back).unwrap(), json);
+    }
+
     #[test]
     fn test_backward_compatibility_missing_auto_close_tmux() {
         // Simulate an old state.json that doesn't have auto_close_tmux field
@@ -293,6 +341,73 @@ mod tests {
         assert_eq!(deserialized.group_id, Some(uuid));
     }
 
+    #[test]
+    fn search_matches_every_term_across_command_script_name_and_parameters() {
+        let (spec, _) = Job::builder()
+            .command("python train.py --lr {lr} --schedule Cosine")
+            .run_name(Some("sweep-7".to_string()))
+            .parameters(HashMap::from([("lr".to_string(), "3e-4".to_string())]))
+            .build()
+            .into_parts();
+
+        assert!(spec.matches_search("cosine --lr 3e-4"));
+        assert!(spec.matches_search("SWEEP-7"));
+        assert!(spec.matches_search(""));
+        assert!(!spec.matches_search("cosine 1e-3"));
+
+        let (script_spec, _) = Job::builder()
+            .script("/runs/ablation.sh")
+            .build()
+            .into_parts();
+        assert!(script_spec.matches_search("ablation"));
+        assert!(!script_spec.matches_search("cosine"));
+    }
+
+    #[test]
+    fn test_wait_time_counts_from_the_last_queueing() {
+        use std::time::{Duration, SystemTime};
+        let at = |secs| SystemTime::UNIX_EPOCH + Duration::from_secs(secs);
+
+        let mut job = Job {
+            submitted_at: Some(at(100)),
+            ..Default::default()
+        };
+        assert_eq!(job.wait_time(), None);
+        assert_eq!(job.wait_time_as_of(at(130)), Some(Duration::from_secs(30)));
+
+        // Released from hold at 200, then started at 260.
+        job.queued_at = Some(at(200));
+        job.state = JobState::Running;
+        job.started_at = Some(at(260));
+        assert_eq!(job.wait_time(), Some(Duration::from_secs(60)));
+        assert_eq!(job.wait_time_as_of(at(900)), Some(Duration::from_secs(60)));
+
+        job.state = JobState::Hold;
+        job.started_at = None;
+        assert_eq!(job.wait_time_as_of(at(900)), None);
+    }
+
+    #[test]
+    fn test_memory_utilization_compares_peaks_with_requests() {
+        let mut job = Job::builder()
+            .command("python train.py")
+            .gpus(2)
+            .memory_limit_mb(Some(8192))
+            .gpu_memory_limit_mb(Some(10240))
+            .build();
+        assert_eq!(job.memory_utilization(), None);
+
+        job.peak_rss_mb = Some(2048);
+        job.peak_gpu_mb = Some(15360);
+        assert_eq!(job.memory_utilization(), Some(25));
+        assert_eq!(job.requested_gpu_memory_mb(), Some(20480));
+        assert_eq!(job.gpu_memory_utilization(), Some(75));
+
+        // Whole GPUs: no request to compare with
+        job.gpu_memory_limit_mb = None;
+        assert_eq!(job.gpu_memory_utilization(), None);
+    }
+
     #[test]
     fn test_group_id_backward_compatibility() {
         // Test that old JSON with string group_id can be deserialized to UUID
@@ -0,0 +1,107 @@
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// Most artifacts one job may register; later ones are refused.
+pub const MAX_ARTIFACTS_PER_JOB: usize = 1000;
+
+/// Longest label accepted.
+const MAX_LABEL_CHARS: usize = 64;
+
+/// An output a job registered so tools can find it later, through
+/// `$GFLOW_ARTIFACTS_FILE` or `POST /jobs/{id}/artifacts`. gflow only
+/// records it: the file is never moved or deleted.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct JobArtifact {
+    /// Absolute path on the machine the job ran on
+    #[schema(value_type = String)]
+    pub path: PathBuf,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    /// Size at registration; for a directory, of the files in it
+    pub size_bytes: u64,
+    #[schema(value_type = crate::core::schema::SystemTimeSchema)]
+    pub registered_at: SystemTime,
+}
+
+impl JobArtifact {
+    /// Describe the file or directory at `path`, resolved against `run_dir`
+    /// when relative. Fails unless it exists and the label is short and on
+    /// one line.
+    pub fn register(path: &Path, label: Option<&str>, run_dir: &Path) -> Result<Self, String> {
+        let label = label.map(str::trim).filter(|label| !label.is_empty());
+        if let Some(label) = label {
+            if label.chars().count() > MAX_LABEL_CHARS || label.contains(char::is_control) {
+                return Err(format!(
+                    "Artifact labels must be one line of at most {MAX_LABEL_CHARS} characters"
+                ));
+            }
+        }
+        if path.as_os_str().is_empty() {
+            return Err("Artifact path is empty".to_string());
+        }
+        let path = run_dir.join(path);
+        let metadata = std::fs::metadata(&path)
+            .map_err(|e| format!("Cannot register '{}': {e}", path.display()))?;
+        let size_bytes = if metadata.is_dir() {
+            dir_size(&path)
+        } else {
+            metadata.len()
+        };
+        Ok(Self {
+            path,
+            label: label.map(str::to_owned),
+            size_bytes,
+            registered_at: SystemTime::now(),
+        })
+    }
+}
+
+/// Total size of the files under `dir`, skipping what cannot be read.
+/// Symlinks are not followed.
+fn dir_size(dir: &Path) -> u64 {
+    let mut size = 0;
+    let mut pending = vec![dir.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            match entry.metadata() {
+                Ok(metadata) if metadata.is_dir() => pending.push(entry.path()),
+                Ok(metadata) if metadata.is_file() => size += metadata.len(),
+                _ => {}
+            }
+        }
+    }
+    size
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn artifacts_must_exist_and_are_resolved_against_the_run_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("ckpt")).unwrap();
+        std::fs::write(dir.path().join("ckpt/a.pt"), [0u8; 10]).unwrap();
+        std::fs::write(dir.path().join("ckpt/b.pt"), [0u8; 5]).unwrap();
+
+        let file =
+            JobArtifact::register(Path::new("ckpt/a.pt"), Some(" best "), dir.path()).unwrap();
+        assert_eq!(file.path, dir.path().join("ckpt/a.pt"));
+        assert_eq!(file.label.as_deref(), Some("best"));
+        assert_eq!(file.size_bytes, 10);
+
+        let absolute = dir.path().join("ckpt");
+        let tree = JobArtifact::register(&absolute, Some(""), Path::new("/elsewhere")).unwrap();
+        assert_eq!(
+            (tree.path, tree.label, tree.size_bytes),
+            (absolute, None, 15)
+        );
+
+        assert!(JobArtifact::register(Path::new("missing.pt"), None, dir.path()).is_err());
+        assert!(JobArtifact::register(Path::new("ckpt"), Some("a\nb"), dir.path()).is_err());
+    }
+}
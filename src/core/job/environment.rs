@@ -0,0 +1,135 @@
+use super::InternedStr;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// The environment a job's command runs in.
+///
+/// Supersedes the legacy `conda_env` field, which is kept in sync for
+/// `Conda` so older clients and state files keep working.
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq, Eq, utoipa::ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum EnvSpec {
+    #[default]
+    None,
+    /// `conda activate <name>`
+    #[schema(value_type = String)]
+    Conda(InternedStr),
+    /// `source <path>/bin/activate`
+    #[schema(value_type = String)]
+    Venv(PathBuf),
+    /// Run the command through `uv run`
+    Uv,
+}
+
+impl EnvSpec {
+    pub fn is_none(&self) -> bool {
+        matches!(self, EnvSpec::None)
+    }
+
+    pub fn conda_name(&self) -> Option<&str> {
+        self.conda().map(InternedStr::as_str)
+    }
+
+    fn conda(&self) -> Option<&InternedStr> {
+        match self {
+            EnvSpec::Conda(name) => Some(name),
+            _ => None,
+        }
+    }
+
+    /// Activation script of a virtualenv rooted at `venv`.
+    pub fn venv_activate_script(venv: &Path) -> PathBuf {
+        venv.join("bin").join("activate")
+    }
+}
+
+impl fmt::Display for EnvSpec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EnvSpec::None => write!(f, "none"),
+            EnvSpec::Conda(name) => write!(f, "conda:{name}"),
+            EnvSpec::Venv(path) => write!(f, "venv:{}", path.display()),
+            EnvSpec::Uv => write!(f, "uv"),
+        }
+    }
+}
+
+/// Reconcile `environment` with the legacy `conda_env` field.
+///
+/// A job that only has `conda_env` (old clients, old state files) gets a
+/// `Conda` environment; afterwards `conda_env` mirrors the environment.
+pub(super) fn sync_legacy_conda_env(
+    environment: &mut EnvSpec,
+    conda_env: &mut Option<InternedStr>,
+) {
+    if environment.is_none() {
+        if let Some(name) = conda_env.as_ref() {
+            *environment = EnvSpec::Conda(name.clone());
+        }
+    }
+    *conda_env = environment.conda().cloned();
+}
+
+/// Apply an update of the legacy `conda_env` field to `environment`.
+///
+/// Clearing the conda env leaves a venv or uv environment in place.
+pub(super) fn update_conda_env(
+    environment: &mut EnvSpec,
+    conda_env: &mut Option<InternedStr>,
+    update: Option<String>,
+) {
+    match update {
+        Some(name) => *environment = EnvSpec::Conda(name.into()),
+        None if environment.conda_name().is_some() => *environment = EnvSpec::None,
+        None => {}
+    }
+    *conda_env = environment.conda().cloned();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn legacy_conda_env_maps_to_conda_environment() {
+        let mut environment = EnvSpec::None;
+        let mut conda_env = Some(InternedStr::from("torch"));
+        sync_legacy_conda_env(&mut environment, &mut conda_env);
+        assert_eq!(environment, EnvSpec::Conda("torch".into()));
+        assert_eq!(conda_env.as_deref(), Some("torch"));
+
+        let mut environment = EnvSpec::Uv;
+        let mut conda_env = Some(InternedStr::from("stale"));
+        sync_legacy_conda_env(&mut environment, &mut conda_env);
+        assert_eq!(environment, EnvSpec::Uv);
+        assert_eq!(conda_env, None);
+    }
+
+    #[test]
+    fn clearing_conda_env_keeps_other_environments() {
+        let mut environment = EnvSpec::Venv(PathBuf::from("/opt/venv"));
+        let mut conda_env = None;
+        update_conda_env(&mut environment, &mut conda_env, None);
+        assert_eq!(environment, EnvSpec::Venv(PathBuf::from("/opt/venv")));
+
+        update_conda_env(&mut environment, &mut conda_env, Some("ml".to_string()));
+        assert_eq!(environment, EnvSpec::Conda("ml".into()));
+        assert_eq!(conda_env.as_deref(), Some("ml"));
+
+        update_conda_env(&mut environment, &mut conda_env, None);
+        assert_eq!(environment, EnvSpec::None);
+        assert_eq!(conda_env, None);
+    }
+
+    #[test]
+    fn serializes_as_tagged_variants() {
+        assert_eq!(serde_json::to_string(&EnvSpec::Uv).unwrap(), r#""uv""#);
+        assert_eq!(
+            serde_json::to_string(&EnvSpec::Venv(PathBuf::from("/opt/venv"))).unwrap(),
+            r#"{"venv":"/opt/venv"}"#
+        );
+        let conda: EnvSpec = serde_json::from_str(r#"{"conda":"ml"}"#).unwrap();
+        assert_eq!(conda, EnvSpec::Conda("ml".into()));
+    }
+}
@@ -0,0 +1,117 @@
+use super::{JobRuntime, JobSpec, JobState};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::time::SystemTime;
+use uuid::Uuid;
+
+/// The jobs of one batch submission (`gbatch --param`, `--param-file` or
+/// `--array`), as served by `GET /groups`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct JobGroup {
+    pub id: Uuid,
+    /// Who submitted the group's first job
+    pub owner: String,
+    /// Number of member jobs in each state (states with no jobs are omitted)
+    pub jobs_by_state: BTreeMap<JobState, usize>,
+    /// Limit on running members, if any (`gctl set-limit`)
+    pub max_concurrent: Option<usize>,
+    /// When the first member was submitted
+    #[schema(value_type = Option<crate::core::schema::SystemTimeSchema>)]
+    pub first_submitted_at: Option<SystemTime>,
+    /// When a member was last submitted, started or finished
+    #[schema(value_type = Option<crate::core::schema::SystemTimeSchema>)]
+    pub last_activity_at: Option<SystemTime>,
+}
+
+impl JobGroup {
+    /// The rollup of the member jobs `members`, in job ID order, or `None`
+    /// without any.
+    pub fn from_members<'a>(
+        id: Uuid,
+        members: impl IntoIterator<Item = (&'a JobSpec, &'a JobRuntime)>,
+    ) -> Option<Self> {
+        let mut members = members.into_iter();
+        let (first_spec, first_rt) = members.next()?;
+        let mut group = Self {
+            id,
+            owner: first_spec.submitted_by.to_string(),
+            jobs_by_state: BTreeMap::new(),
+            max_concurrent: first_rt.max_concurrent,
+            first_submitted_at: None,
+            last_activity_at: None,
+        };
+        for (spec, rt) in std::iter::once((first_spec, first_rt)).chain(members) {
+            *group.jobs_by_state.entry(rt.state).or_default() += 1;
+            group.first_submitted_at = [group.first_submitted_at, spec.submitted_at]
+                .into_iter()
+                .flatten()
+                .min();
+            group.last_activity_at = [
+                group.last_activity_at,
+                spec.submitted_at,
+                rt.started_at,
+                rt.finished_at,
+            ]
+            .into_iter()
+            .flatten()
+            .max();
+        }
+        Some(group)
+    }
+
+    /// Number of member jobs in `state`.
+    pub fn count(&self, state: JobState) -> usize {
+        self.jobs_by_state.get(&state).copied().unwrap_or(0)
+    }
+
+    pub fn total(&self) -> usize {
+        self.jobs_by_state.values().sum()
+    }
+
+    /// Number of members that have not reached a final state.
+    pub fn active(&self) -> usize {
+        self.jobs_by_state
+            .iter()
+            .filter(|(state, _)| !state.is_final())
+            .map(|(_, count)| count)
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::job::Job;
+    use std::time::Duration;
+
+    #[test]
+    fn rollup_counts_states_and_spans_timestamps() {
+        let at = |secs| Some(SystemTime::UNIX_EPOCH + Duration::from_secs(secs));
+        let job = |state, submitted, finished: Option<u64>| Job {
+            state,
+            submitted_by: "alice".into(),
+            submitted_at: at(submitted),
+            finished_at: finished.and_then(at),
+            max_concurrent: Some(2),
+            ..Job::default()
+        };
+        let members = [
+            job(JobState::Finished, 10, Some(40)),
+            job(JobState::Running, 10, None),
+            job(JobState::Queued, 20, None),
+        ]
+        .map(Job::into_parts);
+
+        let group =
+            JobGroup::from_members(Uuid::nil(), members.iter().map(|(spec, rt)| (spec, rt)))
+                .unwrap();
+        assert_eq!(group.owner, "alice");
+        assert_eq!(group.max_concurrent, Some(2));
+        assert_eq!(group.count(JobState::Running), 1);
+        assert_eq!(group.count(JobState::Failed), 0);
+        assert_eq!((group.total(), group.active()), (3, 2));
+        assert_eq!(group.first_submitted_at, at(10));
+        assert_eq!(group.last_activity_at, at(40));
+        assert_eq!(JobGroup::from_members(Uuid::nil(), []), None);
+    }
+}
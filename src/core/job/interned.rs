@@ -0,0 +1,191 @@
+use compact_str::CompactString;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::borrow::Borrow;
+use std::collections::HashSet;
+use std::fmt;
+use std::ops::Deref;
+use std::sync::Arc;
+
+/// An immutable string that is cheap to clone and, once interned in a
+/// [`StringPool`], shared by every job with the same value.
+///
+/// Used for fields that repeat across tens of thousands of jobs, such as user
+/// names and conda environments. Serializes as a plain string.
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct InternedStr(Arc<str>);
+
+impl InternedStr {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Default for InternedStr {
+    fn default() -> Self {
+        Self::from("")
+    }
+}
+
+impl Deref for InternedStr {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl AsRef<str> for InternedStr {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Borrow<str> for InternedStr {
+    fn borrow(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Debug for InternedStr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&*self.0, f)
+    }
+}
+
+impl fmt::Display for InternedStr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&*self.0, f)
+    }
+}
+
+impl From<&str> for InternedStr {
+    fn from(value: &str) -> Self {
+        Self(Arc::from(value))
+    }
+}
+
+impl From<String> for InternedStr {
+    fn from(value: String) -> Self {
+        Self(Arc::from(value))
+    }
+}
+
+impl From<CompactString> for InternedStr {
+    fn from(value: CompactString) -> Self {
+        Self::from(value.as_str())
+    }
+}
+
+impl From<&InternedStr> for String {
+    fn from(value: &InternedStr) -> Self {
+        value.as_str().to_owned()
+    }
+}
+
+impl PartialEq<str> for InternedStr {
+    fn eq(&self, other: &str) -> bool {
+        &*self.0 == other
+    }
+}
+
+impl PartialEq<&str> for InternedStr {
+    fn eq(&self, other: &&str) -> bool {
+        &*self.0 == *other
+    }
+}
+
+impl PartialEq<String> for InternedStr {
+    fn eq(&self, other: &String) -> bool {
+        &*self.0 == other.as_str()
+    }
+}
+
+impl PartialEq<InternedStr> for str {
+    fn eq(&self, other: &InternedStr) -> bool {
+        self == &*other.0
+    }
+}
+
+impl PartialEq<InternedStr> for &str {
+    fn eq(&self, other: &InternedStr) -> bool {
+        *self == &*other.0
+    }
+}
+
+impl PartialEq<InternedStr> for String {
+    fn eq(&self, other: &InternedStr) -> bool {
+        self.as_str() == &*other.0
+    }
+}
+
+impl Serialize for InternedStr {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for InternedStr {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        CompactString::deserialize(deserializer).map(Self::from)
+    }
+}
+
+/// The distinct values of the [`InternedStr`] fields of the jobs a scheduler
+/// holds. Values are never dropped: there are only as many as there are users
+/// and environments.
+#[derive(Debug, Default)]
+pub struct StringPool(HashSet<InternedStr>);
+
+impl StringPool {
+    /// Swap `value` for the pooled copy of the same string, pooling it first
+    /// if it is new.
+    pub fn intern(&mut self, value: &mut InternedStr) {
+        match self.0.get(value.as_str()) {
+            Some(pooled) => *value = pooled.clone(),
+            None => {
+                self.0.insert(value.clone());
+            }
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn interned_strings_share_one_allocation() {
+        let mut pool = StringPool::default();
+        let mut first = InternedStr::from("alice");
+        let mut second = InternedStr::from(String::from("alice"));
+        pool.intern(&mut first);
+        pool.intern(&mut second);
+        assert!(Arc::ptr_eq(&first.0, &second.0));
+        assert_eq!(pool.len(), 1);
+        assert_eq!(second, "alice");
+    }
+
+    #[test]
+    fn serializes_as_a_plain_string() {
+        let value = InternedStr::from("alice");
+        assert_eq!(serde_json::to_string(&value).unwrap(), r#""alice""#);
+        let back: InternedStr = serde_json::from_str(r#""alice""#).unwrap();
+        assert_eq!(back, value);
+        let bytes = rmp_serde::to_vec(&value).unwrap();
+        assert_eq!(bytes, rmp_serde::to_vec("alice").unwrap());
+    }
+}
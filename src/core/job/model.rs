@@ -1,7 +1,11 @@
+use super::environment::{sync_legacy_conda_env, update_conda_env};
 use super::{
-    deserialize_group_id, serialize_group_id, DependencyIds, DependencyMode, GpuIds,
-    GpuSharingMode, JobError, JobState, JobStateReason, Parameters,
+    deserialize_group_id, serialize_group_id, CpuIds, DependencyIds, DependencyMode, EnvSpec,
+    GpuIds, GpuSharingMode, InternedStr, JobError, JobProgress, JobState, JobStateReason,
+    Parameters, StringPool, Tags,
 };
+use crate::core::executor::ExecutorKind;
+use crate::utils::constraint::Constraint;
 use compact_str::CompactString;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -17,13 +21,13 @@ pub struct JobSpec {
     // Execution config (cold - accessed only at execution time)
     pub script: Option<Box<PathBuf>>,
     pub command: Option<CompactString>,
-    pub conda_env: Option<CompactString>,
+    pub conda_env: Option<InternedStr>,
     pub run_dir: PathBuf,
     #[serde(default)]
     pub parameters: Parameters,
 
     // Metadata (cold - rarely accessed)
-    pub submitted_by: CompactString,
+    pub submitted_by: InternedStr,
     pub submitted_at: Option<SystemTime>,
     pub task_id: Option<u32>,
     pub redone_from: Option<u32>,
@@ -50,6 +54,51 @@ pub struct JobSpec {
     pub dependency_mode: Option<DependencyMode>,
     #[serde(default)]
     pub auto_cancel_on_dependency_failure: bool,
+
+    // Supersedes `conda_env`, which mirrors it for `EnvSpec::Conda`
+    #[serde(default)]
+    #[serde(skip_serializing_if = "EnvSpec::is_none")]
+    pub environment: EnvSpec,
+
+    // Image to run the job in instead of directly on the host
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub container_image: Option<String>,
+
+    // Backend that starts the job; `None` uses `[daemon] executor`
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub executor: Option<ExecutorKind>,
+
+    // A shell the submitter attaches to (`gbatch --interactive`)
+    #[serde(default)]
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub interactive: bool,
+
+    // May be stopped and requeued for a high-priority job (`gbatch --preemptible`)
+    #[serde(default)]
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub preemptible: bool,
+
+    // Labels the job's GPUs or node must have (`gbatch --constraint`)
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub constraint: Option<Constraint>,
+
+    // Free space the run_dir and log dir need before the job starts (`gbatch --min-free-disk`)
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_free_disk_mb: Option<u64>,
+
+    // Freeform labels for organizing jobs (`gbatch --tag`, `gjob tag`)
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Tags::is_empty")]
+    pub tags: Tags,
+
+    // Never remove the job's GFLOW_JOB_DIR automatically (`gbatch --keep-job-dir`)
+    #[serde(default)]
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub keep_job_dir: bool,
 }
 
 impl Default for JobSpec {
@@ -60,7 +109,7 @@ impl Default for JobSpec {
             conda_env: None,
             run_dir: PathBuf::from("."),
             parameters: Parameters::new(),
-            submitted_by: CompactString::const_new("unknown"),
+            submitted_by: InternedStr::from("unknown"),
             submitted_at: None,
             task_id: None,
             redone_from: None,
@@ -74,10 +123,63 @@ impl Default for JobSpec {
             depends_on_ids: DependencyIds::new(),
             dependency_mode: None,
             auto_cancel_on_dependency_failure: true,
+            environment: EnvSpec::None,
+            container_image: None,
+            executor: None,
+            interactive: false,
+            preemptible: false,
+            constraint: None,
+            min_free_disk_mb: None,
+            tags: Tags::default(),
+            keep_job_dir: false,
         }
     }
 }
 
+impl JobSpec {
+    /// Fill `environment` from the legacy `conda_env` field and keep the two in sync.
+    pub fn normalize_environment(&mut self) {
+        sync_legacy_conda_env(&mut self.environment, &mut self.conda_env);
+    }
+
+    /// Share the user name and conda environment with other jobs through `pool`.
+    pub fn intern_strings(&mut self, pool: &mut StringPool) {
+        pool.intern(&mut self.submitted_by);
+        if let Some(conda_env) = self.conda_env.as_mut() {
+            pool.intern(conda_env);
+        }
+        if let EnvSpec::Conda(name) = &mut self.environment {
+            pool.intern(name);
+        }
+    }
+
+    /// Set or clear the conda environment, as requested through the legacy field.
+    pub fn set_conda_env(&mut self, conda_env: Option<String>) {
+        update_conda_env(&mut self.environment, &mut self.conda_env, conda_env);
+    }
+
+    /// Whether every whitespace-separated term of `query` appears, ignoring case,
+    /// in the command, script path, run name or a parameter value.
+    pub fn matches_search(&self, query: &str) -> bool {
+        let script = self
+            .script
+            .as_ref()
+            .map(|script| script.to_string_lossy().to_lowercase());
+        let fields: Vec<String> = self
+            .command
+            .iter()
+            .chain(self.run_name.iter())
+            .chain(self.parameters.iter().map(|(_, value)| value))
+            .map(|field| field.as_str().to_lowercase())
+            .chain(script)
+            .collect();
+        query.split_whitespace().all(|term| {
+            let term = term.to_lowercase();
+            fields.iter().any(|field| field.contains(&term))
+        })
+    }
+}
+
 /// JobRuntime contains mutable runtime state (hot data).
 /// This data is frequently accessed during scheduling and should fit in CPU cache.
 #[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
@@ -118,6 +220,39 @@ pub struct JobRuntime {
     // Failure reason (cold - only set on failure)
     #[serde(default)]
     pub reason: Option<Box<JobStateReason>>,
+
+    // Times the job was stopped and requeued for a higher-priority job
+    #[serde(default)]
+    pub preemptions: u32,
+
+    // Remote node the job was placed on (None = the head daemon's machine)
+    #[serde(default)]
+    pub node: Option<String>,
+
+    // When the job last entered the queue: on submission, release or preemption
+    #[serde(default)]
+    pub queued_at: Option<SystemTime>,
+
+    // CPU cores requested (`gbatch --cpus`) and the cores the job is pinned to
+    #[serde(default)]
+    pub cpus: Option<u32>,
+    #[serde(default)]
+    pub cpu_ids: Option<CpuIds>,
+
+    // Most host and GPU memory the job's processes used at once, in MB
+    #[serde(default)]
+    pub peak_rss_mb: Option<u64>,
+    #[serde(default)]
+    pub peak_gpu_mb: Option<u64>,
+
+    // Scratch directory created for the job when it started (`GFLOW_JOB_DIR`),
+    // until it is removed
+    #[serde(default)]
+    pub job_dir: Option<Box<PathBuf>>,
+
+    // Latest progress the running job reported; not persisted
+    #[serde(skip)]
+    pub progress: Option<Box<JobProgress>>,
 }
 
 impl Default for JobRuntime {
@@ -137,6 +272,15 @@ impl Default for JobRuntime {
             started_at: None,
             finished_at: None,
             reason: None,
+            preemptions: 0,
+            node: None,
+            queued_at: None,
+            cpus: None,
+            cpu_ids: None,
+            peak_rss_mb: None,
+            peak_gpu_mb: None,
+            job_dir: None,
+            progress: None,
         }
     }
 }
@@ -171,19 +315,24 @@ impl JobView {
     }
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone, utoipa::ToSchema)]
 #[serde(default)]
 pub struct Job {
     /// Required fields at submission time
     pub id: u32,
+    #[schema(value_type = Option<String>)]
     pub script: Option<Box<PathBuf>>,
+    #[schema(value_type = Option<String>)]
     pub command: Option<CompactString>,
     pub gpus: u32,
-    pub conda_env: Option<CompactString>,
+    #[schema(value_type = Option<String>)]
+    pub conda_env: Option<InternedStr>,
+    #[schema(value_type = String)]
     pub run_dir: PathBuf,
     pub priority: u8,
     pub depends_on: Option<u32>, // Legacy single dependency (for backward compatibility)
     #[serde(default)]
+    #[schema(value_type = Vec<u32>)]
     pub depends_on_ids: DependencyIds, // New multi-dependency field
     #[serde(default)]
     pub dependency_mode: Option<DependencyMode>, // AND or OR logic
@@ -194,9 +343,11 @@ pub struct Job {
     pub gpu_sharing_mode: GpuSharingMode,
     #[serde(default)]
     pub gpu_memory_limit_mb: Option<u64>, // Per-GPU memory limit in MB (None = no limit)
+    #[schema(value_type = Option<crate::core::schema::DurationSchema>)]
     pub time_limit: Option<Duration>, // Maximum runtime in seconds (None = no limit)
     pub memory_limit_mb: Option<u64>, // Maximum memory in MB (None = no limit)
-    pub submitted_by: CompactString,
+    #[schema(value_type = String)]
+    pub submitted_by: InternedStr,
     pub redone_from: Option<u32>, // The job ID this job was redone from
     #[serde(default)]
     pub retried_from: Option<u32>, // The job ID this job was automatically retried from
@@ -204,6 +355,7 @@ pub struct Job {
     pub max_retries: u32, // Maximum automatic retries after failure/timeout
     pub auto_close_tmux: bool,    // Whether to automatically close tmux on successful completion
     #[serde(default)]
+    #[schema(value_type = BTreeMap<String, String>)]
     pub parameters: Parameters, // Parameter values for template substitution
     #[serde(
         default,
@@ -214,20 +366,109 @@ pub struct Job {
     pub max_concurrent: Option<usize>, // Max concurrent jobs in this group
 
     /// Optional fields that get populated by gflowd
+    #[schema(value_type = Option<String>)]
     pub run_name: Option<CompactString>, // tmux session name
     #[serde(default)]
+    #[schema(value_type = Option<String>)]
     pub project: Option<CompactString>, // Project code for tracking (normalized, immutable)
     pub state: JobState,
-    pub gpu_ids: Option<GpuIds>,          // GPU IDs assigned to this job
+    #[schema(value_type = Option<Vec<u32>>)]
+    pub gpu_ids: Option<GpuIds>, // GPU IDs assigned to this job
+    #[schema(value_type = Option<crate::core::schema::SystemTimeSchema>)]
     pub submitted_at: Option<SystemTime>, // When the job was submitted
-    pub started_at: Option<SystemTime>,   // When the job started running
-    pub finished_at: Option<SystemTime>,  // When the job finished or failed
+    #[schema(value_type = Option<crate::core::schema::SystemTimeSchema>)]
+    pub started_at: Option<SystemTime>, // When the job started running
+    #[schema(value_type = Option<crate::core::schema::SystemTimeSchema>)]
+    pub finished_at: Option<SystemTime>, // When the job finished or failed
     #[serde(default)]
+    #[schema(value_type = Option<JobStateReason>)]
     pub reason: Option<Box<JobStateReason>>, // Reason for cancellation/failure
     // Append-only for backward compatibility with legacy msgpack array layout.
     #[serde(default)]
     #[serde(skip_serializing_if = "JobNotifications::is_empty")]
     pub notifications: JobNotifications,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "EnvSpec::is_none")]
+    pub environment: EnvSpec, // Supersedes conda_env, which mirrors EnvSpec::Conda
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub container_image: Option<String>, // Run inside this container image instead of directly on the host
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub executor: Option<ExecutorKind>, // tmux or direct; None uses `[daemon] executor`
+    #[serde(default)]
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub interactive: bool, // A shell the submitter attaches to (`gbatch --interactive`)
+    #[serde(default)]
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub preemptible: bool, // May be requeued for a high-priority job (`gbatch --preemptible`)
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_zero")]
+    pub preemptions: u32, // Times the job was requeued for a higher-priority job
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub node: Option<String>, // Remote node running the job; None = the head daemon's machine
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<String>)]
+    pub constraint: Option<Constraint>, // Labels the job's GPUs or node must have (`gbatch --constraint`)
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_free_disk_mb: Option<u64>, // Free space the run_dir and log dir need before starting (`gbatch --min-free-disk`)
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub script_content: Option<String>, // Submission only: script body the daemon saves and runs instead of a path
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub submission_id: Option<Uuid>, // Submission only: client-chosen ID that makes resending the job safe
+    #[serde(default)]
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub force_queue: bool, // Submission only: queue the job even if no machine could ever run it (`gbatch --force-queue`)
+    #[serde(default)]
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub as_user: bool, // Submission only: submit for `submitted_by` rather than the caller (`gbatch --as-user`)
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<crate::core::schema::SystemTimeSchema>)]
+    pub queued_at: Option<SystemTime>, // When the job last entered the queue (submission, release, preemption)
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub batch_depends_on: Vec<usize>, // Submission only: positions of earlier jobs in the same batch this job depends on
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cpus: Option<u32>, // CPU cores the job is limited to (`gbatch --cpus`)
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<Vec<u32>>)]
+    pub cpu_ids: Option<CpuIds>, // CPU cores assigned to this job
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub peak_rss_mb: Option<u64>, // Most host memory the job's processes used at once
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub peak_gpu_mb: Option<u64>, // Most GPU memory the job's processes used at once, over all its GPUs
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub progress: Option<Box<JobProgress>>, // Latest progress the running job reported
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Tags::is_empty")]
+    #[schema(value_type = Vec<String>)]
+    pub tags: Tags, // Freeform labels for organizing jobs (`gbatch --tag`, `gjob tag`)
+    #[serde(default)]
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub keep_job_dir: bool, // Never remove GFLOW_JOB_DIR automatically (`gbatch --keep-job-dir`)
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<String>)]
+    pub job_dir: Option<Box<PathBuf>>, // Scratch directory created when the job started (`GFLOW_JOB_DIR`)
+}
+
+fn utilization(peak_mb: u64, requested_mb: u64) -> Option<u64> {
+    (requested_mb > 0).then(|| peak_mb * 100 / requested_mb)
+}
+
+fn is_zero(value: &u32) -> bool {
+    *value == 0
 }
 
 #[derive(Default)]
@@ -235,7 +476,7 @@ pub struct JobBuilder {
     script: Option<PathBuf>,
     command: Option<CompactString>,
     gpus: Option<u32>,
-    conda_env: Option<CompactString>,
+    conda_env: Option<InternedStr>,
     run_dir: Option<PathBuf>,
     priority: Option<u8>,
     depends_on: Option<u32>,
@@ -246,7 +487,7 @@ pub struct JobBuilder {
     time_limit: Option<Duration>,
     gpu_memory_limit_mb: Option<u64>,
     memory_limit_mb: Option<u64>,
-    submitted_by: Option<CompactString>,
+    submitted_by: Option<InternedStr>,
     run_name: Option<CompactString>,
     redone_from: Option<u32>,
     retried_from: Option<u32>,
@@ -258,14 +499,26 @@ pub struct JobBuilder {
     project: Option<CompactString>,
     notifications: Option<JobNotifications>,
     gpu_sharing_mode: Option<GpuSharingMode>,
+    environment: Option<EnvSpec>,
+    container_image: Option<String>,
+    executor: Option<ExecutorKind>,
+    interactive: Option<bool>,
+    preemptible: Option<bool>,
+    keep_job_dir: Option<bool>,
+    constraint: Option<Constraint>,
+    min_free_disk_mb: Option<u64>,
+    cpus: Option<u32>,
+    tags: Tags,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, Default)]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, Default, utoipa::ToSchema)]
 #[serde(default)]
 pub struct JobNotifications {
     #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[schema(value_type = Vec<String>)]
     pub emails: Vec<CompactString>,
     #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[schema(value_type = Vec<String>)]
     pub events: Vec<CompactString>,
 }
 
@@ -319,7 +572,43 @@ impl JobBuilder {
     }
 
     pub fn conda_env(mut self, conda_env: Option<String>) -> Self {
-        self.conda_env = conda_env.map(CompactString::from);
+        self.conda_env = conda_env.map(InternedStr::from);
+        self
+    }
+
+    /// Takes precedence over `conda_env`.
+    pub fn environment(mut self, environment: EnvSpec) -> Self {
+        self.environment = Some(environment);
+        self
+    }
+
+    pub fn container_image(mut self, container_image: Option<String>) -> Self {
+        self.container_image = container_image;
+        self
+    }
+
+    pub fn executor(mut self, executor: Option<ExecutorKind>) -> Self {
+        self.executor = executor;
+        self
+    }
+
+    pub fn interactive(mut self, interactive: bool) -> Self {
+        self.interactive = Some(interactive);
+        self
+    }
+
+    pub fn preemptible(mut self, preemptible: bool) -> Self {
+        self.preemptible = Some(preemptible);
+        self
+    }
+
+    pub fn keep_job_dir(mut self, keep_job_dir: bool) -> Self {
+        self.keep_job_dir = Some(keep_job_dir);
+        self
+    }
+
+    pub fn constraint(mut self, constraint: Option<Constraint>) -> Self {
+        self.constraint = constraint;
         self
     }
 
@@ -387,8 +676,18 @@ impl JobBuilder {
         self
     }
 
+    pub fn min_free_disk_mb(mut self, min_free_disk_mb: impl Into<Option<u64>>) -> Self {
+        self.min_free_disk_mb = min_free_disk_mb.into();
+        self
+    }
+
+    pub fn cpus(mut self, cpus: impl Into<Option<u32>>) -> Self {
+        self.cpus = cpus.into();
+        self
+    }
+
     pub fn submitted_by(mut self, submitted_by: impl Into<String>) -> Self {
-        self.submitted_by = Some(CompactString::from(submitted_by.into()));
+        self.submitted_by = Some(InternedStr::from(submitted_by.into()));
         self
     }
 
@@ -457,8 +756,13 @@ impl JobBuilder {
         self
     }
 
+    pub fn tags(mut self, tags: Tags) -> Self {
+        self.tags = tags;
+        self
+    }
+
     pub fn build(self) -> Job {
-        Job {
+        let mut job = Job {
             id: 0,
             script: self.script.map(Box::new),
             command: self.command,
@@ -478,7 +782,7 @@ impl JobBuilder {
             memory_limit_mb: self.memory_limit_mb,
             submitted_by: self
                 .submitted_by
-                .unwrap_or_else(|| CompactString::const_new("unknown")),
+                .unwrap_or_else(|| InternedStr::from("unknown")),
             redone_from: self.redone_from,
             retried_from: self.retried_from,
             max_retries: self.max_retries.unwrap_or(0),
@@ -489,6 +793,29 @@ impl JobBuilder {
             run_name: self.run_name,
             project: self.project,
             notifications: self.notifications.unwrap_or_default(),
+            environment: self.environment.unwrap_or_default(),
+            container_image: self.container_image,
+            executor: self.executor,
+            interactive: self.interactive.unwrap_or(false),
+            preemptible: self.preemptible.unwrap_or(false),
+            preemptions: 0,
+            node: None,
+            constraint: self.constraint,
+            min_free_disk_mb: self.min_free_disk_mb,
+            script_content: None,
+            submission_id: None,
+            force_queue: false,
+            as_user: false,
+            queued_at: None,
+            batch_depends_on: Vec::new(),
+            cpus: self.cpus,
+            cpu_ids: None,
+            peak_rss_mb: None,
+            peak_gpu_mb: None,
+            progress: None,
+            tags: self.tags,
+            keep_job_dir: self.keep_job_dir.unwrap_or(false),
+            job_dir: None,
             state: JobState::Queued,
             gpu_ids: None,
             run_dir: self.run_dir.unwrap_or_else(|| ".".into()),
@@ -496,7 +823,9 @@ impl JobBuilder {
             started_at: None,
             finished_at: None,
             reason: None,
-        }
+        };
+        job.normalize_environment();
+        job
     }
 }
 
@@ -519,7 +848,7 @@ impl Default for Job {
             gpu_memory_limit_mb: None,
             time_limit: None,
             memory_limit_mb: None,
-            submitted_by: CompactString::const_new("unknown"),
+            submitted_by: InternedStr::from("unknown"),
             redone_from: None,
             retried_from: None,
             max_retries: 0,
@@ -530,6 +859,29 @@ impl Default for Job {
             run_name: None,
             project: None,
             notifications: JobNotifications::default(),
+            environment: EnvSpec::None,
+            container_image: None,
+            executor: None,
+            interactive: false,
+            preemptible: false,
+            preemptions: 0,
+            node: None,
+            constraint: None,
+            min_free_disk_mb: None,
+            script_content: None,
+            submission_id: None,
+            force_queue: false,
+            as_user: false,
+            queued_at: None,
+            batch_depends_on: Vec::new(),
+            cpus: None,
+            cpu_ids: None,
+            peak_rss_mb: None,
+            peak_gpu_mb: None,
+            progress: None,
+            tags: Tags::default(),
+            keep_job_dir: false,
+            job_dir: None,
             state: JobState::Queued,
             gpu_ids: None,
             submitted_at: None,
@@ -575,12 +927,35 @@ impl Job {
             run_name: spec.run_name,
             project: spec.project,
             notifications: spec.notifications,
+            environment: spec.environment,
+            container_image: spec.container_image,
+            executor: spec.executor,
+            interactive: spec.interactive,
+            preemptible: spec.preemptible,
+            preemptions: runtime.preemptions,
+            node: runtime.node,
+            constraint: spec.constraint,
+            min_free_disk_mb: spec.min_free_disk_mb,
+            script_content: None,
+            submission_id: None,
+            force_queue: false,
+            as_user: false,
             state: runtime.state,
             gpu_ids: runtime.gpu_ids,
             submitted_at: spec.submitted_at,
             started_at: runtime.started_at,
             finished_at: runtime.finished_at,
             reason: runtime.reason,
+            queued_at: runtime.queued_at,
+            batch_depends_on: Vec::new(),
+            cpus: runtime.cpus,
+            cpu_ids: runtime.cpu_ids,
+            peak_rss_mb: runtime.peak_rss_mb,
+            peak_gpu_mb: runtime.peak_gpu_mb,
+            progress: runtime.progress,
+            tags: spec.tags,
+            keep_job_dir: spec.keep_job_dir,
+            job_dir: runtime.job_dir,
         }
     }
 
@@ -606,6 +981,15 @@ impl Job {
             depends_on_ids: self.depends_on_ids,
             dependency_mode: self.dependency_mode,
             auto_cancel_on_dependency_failure: self.auto_cancel_on_dependency_failure,
+            environment: self.environment,
+            container_image: self.container_image,
+            executor: self.executor,
+            interactive: self.interactive,
+            preemptible: self.preemptible,
+            constraint: self.constraint,
+            min_free_disk_mb: self.min_free_disk_mb,
+            tags: self.tags,
+            keep_job_dir: self.keep_job_dir,
         };
 
         let runtime = JobRuntime {
@@ -623,6 +1007,15 @@ impl Job {
             started_at: self.started_at,
             finished_at: self.finished_at,
             reason: self.reason,
+            preemptions: self.preemptions,
+            node: self.node,
+            queued_at: self.queued_at,
+            cpus: self.cpus,
+            cpu_ids: self.cpu_ids,
+            peak_rss_mb: self.peak_rss_mb,
+            peak_gpu_mb: self.peak_gpu_mb,
+            job_dir: self.job_dir,
+            progress: self.progress,
         };
 
         (spec, runtime)
@@ -652,17 +1045,28 @@ impl Job {
     }
 
     /// Returns true if this job has no dependencies.
+    /// Fill `environment` from the legacy `conda_env` field and keep the two in sync.
+    pub fn normalize_environment(&mut self) {
+        sync_legacy_conda_env(&mut self.environment, &mut self.conda_env);
+    }
+
+    /// Set or clear the conda environment, as requested through the legacy field.
+    pub fn set_conda_env(&mut self, conda_env: Option<String>) {
+        update_conda_env(&mut self.environment, &mut self.conda_env, conda_env);
+    }
+
     pub fn has_no_dependencies(&self) -> bool {
         self.depends_on.is_none() && self.depends_on_ids.is_empty()
     }
 
     fn update_timestamps(&mut self, next: &JobState) {
         match next {
+            JobState::Queued => self.queued_at = Some(SystemTime::now()),
             JobState::Running => self.started_at = Some(SystemTime::now()),
             JobState::Finished | JobState::Failed | JobState::Cancelled | JobState::Timeout => {
                 self.finished_at = Some(SystemTime::now())
             }
-            _ => {}
+            JobState::Hold => {}
         }
     }
 
@@ -722,14 +1126,25 @@ impl Job {
         false
     }
 
-    /// Calculate wait time (time from submission to start)
+    /// Calculate wait time (time from entering the queue to start). The queue is
+    /// entered on submission and again on release from hold or preemption, so
+    /// time on hold does not count.
     pub fn wait_time(&self) -> Option<Duration> {
-        match (self.submitted_at, self.started_at) {
-            (Some(submitted), Some(started)) => started.duration_since(submitted).ok(),
+        match (self.queued_at.or(self.submitted_at), self.started_at) {
+            (Some(queued), Some(started)) => started.duration_since(queued).ok(),
             _ => None,
         }
     }
 
+    /// Like [`Job::wait_time`], but a job still in the queue has waited until `now`.
+    pub fn wait_time_as_of(&self, now: SystemTime) -> Option<Duration> {
+        if self.state != JobState::Queued {
+            return self.wait_time();
+        }
+        let queued = self.queued_at.or(self.submitted_at)?;
+        Some(now.duration_since(queued).unwrap_or_default())
+    }
+
     /// Calculate runtime (time from start to finish, or current elapsed time if still running)
     pub fn runtime(&self) -> Option<Duration> {
         match (self.started_at, self.finished_at) {
@@ -741,6 +1156,24 @@ impl Job {
         }
     }
 
+    /// GPU memory the job asked for over all its GPUs, in MB. Unknown for
+    /// jobs that take whole GPUs without `--gpu-memory`.
+    pub fn requested_gpu_memory_mb(&self) -> Option<u64> {
+        self.gpu_memory_limit_mb
+            .filter(|_| self.gpus > 0)
+            .map(|per_gpu| per_gpu * u64::from(self.gpus))
+    }
+
+    /// Peak host memory as a percentage of `--memory`, once both are known.
+    pub fn memory_utilization(&self) -> Option<u64> {
+        utilization(self.peak_rss_mb?, self.memory_limit_mb?)
+    }
+
+    /// Peak GPU memory as a percentage of what the job asked for.
+    pub fn gpu_memory_utilization(&self) -> Option<u64> {
+        utilization(self.peak_gpu_mb?, self.requested_gpu_memory_mb()?)
+    }
+
     #[cfg(test)]
     pub fn with_id(mut self, id: u32) -> Self {
         self.id = id;
@@ -10,21 +10,25 @@ pub type DependencyIds = smallvec::SmallVec<[u32; 2]>;
 /// Most jobs use 1-4 GPUs, so inline storage of 4 elements eliminates heap allocation
 pub type GpuIds = smallvec::SmallVec<[u32; 4]>;
 
+/// Type alias for the CPU cores a job is pinned to (`gbatch --cpus`)
+pub type CpuIds = smallvec::SmallVec<[u32; 8]>;
+
 /// Job parameters stored as a small Vec of key-value pairs.
 ///
 /// This keeps `Job`'s inline size small (unlike `SmallVec` with large inline tuples)
 /// while avoiding `HashMap` hashing overhead for typical small parameter counts.
-#[derive(Debug, Clone)]
-pub struct Parameters(Vec<(CompactString, CompactString)>);
+/// Most jobs have none, so the pairs are boxed and an empty set costs no allocation.
+#[derive(Debug, Clone, Default)]
+pub struct Parameters(Option<Box<[(CompactString, CompactString)]>>);
 
 impl PartialEq for Parameters {
     fn eq(&self, other: &Self) -> bool {
-        if self.0.len() != other.0.len() {
+        if self.len() != other.len() {
             return false;
         }
-        self.0
+        self.entries()
             .iter()
-            .all(|(k, v)| other.0.iter().any(|(ok, ov)| k == ok && v == ov))
+            .all(|(k, v)| other.entries().iter().any(|(ok, ov)| k == ok && v == ov))
     }
 }
 
@@ -32,50 +36,46 @@ impl Eq for Parameters {}
 
 impl Parameters {
     pub fn new() -> Self {
-        Self(Vec::new())
+        Self(None)
+    }
+
+    fn entries(&self) -> &[(CompactString, CompactString)] {
+        self.0.as_deref().unwrap_or_default()
     }
 
     pub fn get(&self, key: &str) -> Option<&CompactString> {
-        self.0
+        self.entries()
             .iter()
             .find(|(k, _)| k.as_str() == key)
             .map(|(_, v)| v)
     }
 
     pub fn insert(&mut self, key: CompactString, value: CompactString) {
-        if let Some((_k, v)) = self.0.iter_mut().find(|(k, _)| k == key) {
-            *v = value;
-        } else {
-            self.0.push((key, value));
-        }
+        let mut entries = self.0.take().map(Vec::from).unwrap_or_default();
+        insert_entry(&mut entries, key, value);
+        self.0 = Some(entries.into_boxed_slice());
     }
 
     pub fn iter(&self) -> impl Iterator<Item = (&CompactString, &CompactString)> {
-        self.0.iter().map(|(k, v)| (k, v))
+        self.entries().iter().map(|(k, v)| (k, v))
     }
 
     pub fn is_empty(&self) -> bool {
-        self.0.is_empty()
+        self.entries().is_empty()
     }
 
     pub fn len(&self) -> usize {
-        self.0.len()
-    }
-}
-
-impl Default for Parameters {
-    fn default() -> Self {
-        Self::new()
+        self.entries().len()
     }
 }
 
 impl FromIterator<(CompactString, CompactString)> for Parameters {
     fn from_iter<T: IntoIterator<Item = (CompactString, CompactString)>>(iter: T) -> Self {
-        let mut params = Self::new();
+        let mut entries = Vec::new();
         for (k, v) in iter {
-            params.insert(k, v);
+            insert_entry(&mut entries, k, v);
         }
-        params
+        Self((!entries.is_empty()).then(|| entries.into_boxed_slice()))
     }
 }
 
@@ -87,7 +87,7 @@ impl<'a> IntoIterator for &'a Parameters {
     >;
 
     fn into_iter(self) -> Self::IntoIter {
-        self.0.iter().map(|(k, v)| (k, v))
+        self.entries().iter().map(|(k, v)| (k, v))
     }
 }
 
@@ -97,8 +97,8 @@ impl Serialize for Parameters {
         S: Serializer,
     {
         use serde::ser::SerializeMap;
-        let mut map = serializer.serialize_map(Some(self.0.len()))?;
-        for (k, v) in &self.0 {
+        let mut map = serializer.serialize_map(Some(self.len()))?;
+        for (k, v) in self.entries() {
             map.serialize_entry(k, v)?;
         }
         map.end()
@@ -111,7 +111,19 @@ impl<'de> Deserialize<'de> for Parameters {
         D: Deserializer<'de>,
     {
         let map: HashMap<CompactString, CompactString> = HashMap::deserialize(deserializer)?;
-        Ok(Self(map.into_iter().collect()))
+        Ok(map.into_iter().collect())
+    }
+}
+
+fn insert_entry(
+    entries: &mut Vec<(CompactString, CompactString)>,
+    key: CompactString,
+    value: CompactString,
+) {
+    if let Some((_k, v)) = entries.iter_mut().find(|(k, _)| *k == key) {
+        *v = value;
+    } else {
+        entries.push((key, value));
     }
 }
 
@@ -0,0 +1,97 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::time::{Duration, SystemTime};
+
+/// Progress older than this is shown as stale.
+pub const PROGRESS_STALE_AFTER: Duration = Duration::from_secs(10 * 60);
+
+/// Longest message kept; the rest is cut off.
+const MAX_MESSAGE_CHARS: usize = 120;
+
+/// What a running job last reported about its progress, by writing a line
+/// to `$GFLOW_PROGRESS_FILE`. Kept only while the job runs.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct JobProgress {
+    /// 0 to 100
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub percent: Option<u8>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    #[schema(value_type = crate::core::schema::SystemTimeSchema)]
+    pub updated_at: SystemTime,
+}
+
+impl JobProgress {
+    /// Parse a progress line: a percentage, a message, or a percentage
+    /// followed by a message, e.g. `42 epoch 42/100` or `42.5%`. `None` for
+    /// a blank line.
+    pub fn parse(line: &str, updated_at: SystemTime) -> Option<Self> {
+        let line = line.lines().next().unwrap_or_default().trim();
+        if line.is_empty() {
+            return None;
+        }
+        let (first, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+        let percent = first
+            .strip_suffix('%')
+            .unwrap_or(first)
+            .parse::<f64>()
+            .ok()
+            .filter(|percent| percent.is_finite())
+            .map(|percent| percent.clamp(0.0, 100.0) as u8);
+        let message = if percent.is_some() { rest.trim() } else { line };
+        Some(Self {
+            percent,
+            message: (!message.is_empty())
+                .then(|| message.chars().take(MAX_MESSAGE_CHARS).collect()),
+            updated_at,
+        })
+    }
+
+    /// Whether the job has not reported for [`PROGRESS_STALE_AFTER`].
+    pub fn is_stale(&self, now: SystemTime) -> bool {
+        now.duration_since(self.updated_at)
+            .is_ok_and(|age| age > PROGRESS_STALE_AFTER)
+    }
+}
+
+impl fmt::Display for JobProgress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.percent, &self.message) {
+            (Some(percent), Some(message)) => write!(f, "{percent}% {message}"),
+            (Some(percent), None) => write!(f, "{percent}%"),
+            (None, Some(message)) => f.write_str(message),
+            (None, None) => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_a_percentage_a_message_or_both() {
+        let at = SystemTime::UNIX_EPOCH;
+        let progress = JobProgress::parse("42 epoch 42/100\n", at).unwrap();
+        assert_eq!(progress.percent, Some(42));
+        assert_eq!(progress.message.as_deref(), Some("epoch 42/100"));
+        assert_eq!(progress.to_string(), "42% epoch 42/100");
+
+        assert_eq!(JobProgress::parse("99.9%", at).unwrap().to_string(), "99%");
+        assert_eq!(JobProgress::parse("250", at).unwrap().percent, Some(100));
+        let message = JobProgress::parse("warming up", at).unwrap();
+        assert_eq!(
+            (message.percent, message.to_string()),
+            (None, "warming up".into())
+        );
+        assert_eq!(JobProgress::parse("  \n", at), None);
+    }
+
+    #[test]
+    fn progress_goes_stale_after_ten_minutes() {
+        let progress = JobProgress::parse("1", SystemTime::UNIX_EPOCH).unwrap();
+        assert!(!progress.is_stale(SystemTime::UNIX_EPOCH + PROGRESS_STALE_AFTER));
+        assert!(progress
+            .is_stale(SystemTime::UNIX_EPOCH + PROGRESS_STALE_AFTER + Duration::from_secs(1)));
+    }
+}
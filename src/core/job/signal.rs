@@ -0,0 +1,128 @@
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::str::FromStr;
+
+/// A signal `gcancel --signal` may send to a running job. Only these, so a
+/// job cannot be stopped in a way the scheduler would not notice.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JobSignal {
+    Hup,
+    Int,
+    Quit,
+    Usr1,
+    Usr2,
+    Term,
+    Kill,
+    Cont,
+    Winch,
+}
+
+impl JobSignal {
+    pub const ALL: [Self; 9] = [
+        Self::Hup,
+        Self::Int,
+        Self::Quit,
+        Self::Usr1,
+        Self::Usr2,
+        Self::Term,
+        Self::Kill,
+        Self::Cont,
+        Self::Winch,
+    ];
+
+    /// Name without the `SIG` prefix, e.g. `USR1`.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Hup => "HUP",
+            Self::Int => "INT",
+            Self::Quit => "QUIT",
+            Self::Usr1 => "USR1",
+            Self::Usr2 => "USR2",
+            Self::Term => "TERM",
+            Self::Kill => "KILL",
+            Self::Cont => "CONT",
+            Self::Winch => "WINCH",
+        }
+    }
+
+    pub fn number(self) -> libc::c_int {
+        match self {
+            Self::Hup => libc::SIGHUP,
+            Self::Int => libc::SIGINT,
+            Self::Quit => libc::SIGQUIT,
+            Self::Usr1 => libc::SIGUSR1,
+            Self::Usr2 => libc::SIGUSR2,
+            Self::Term => libc::SIGTERM,
+            Self::Kill => libc::SIGKILL,
+            Self::Cont => libc::SIGCONT,
+            Self::Winch => libc::SIGWINCH,
+        }
+    }
+}
+
+impl std::fmt::Display for JobSignal {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "SIG{}", self.name())
+    }
+}
+
+/// Accepts `USR1`, `SIGUSR1` and `sigusr1` alike.
+impl FromStr for JobSignal {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let upper = s.trim().to_ascii_uppercase();
+        let name = upper.strip_prefix("SIG").unwrap_or(&upper);
+        Self::ALL
+            .into_iter()
+            .find(|signal| signal.name() == name)
+            .ok_or_else(|| {
+                let names: Vec<_> = Self::ALL.iter().map(|signal| signal.name()).collect();
+                format!(
+                    "Signal '{}' cannot be sent to jobs; use one of {}",
+                    s.trim(),
+                    names.join(", ")
+                )
+            })
+    }
+}
+
+/// As its name without the `SIG` prefix, e.g. `"USR1"`.
+impl Serialize for JobSignal {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.name())
+    }
+}
+
+impl<'de> Deserialize<'de> for JobSignal {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn signals_parse_with_or_without_the_sig_prefix() {
+        for name in ["USR1", "SIGUSR1", "sigusr1", " usr1 "] {
+            assert_eq!(name.parse::<JobSignal>(), Ok(JobSignal::Usr1), "{name}");
+        }
+        assert_eq!(JobSignal::Usr1.to_string(), "SIGUSR1");
+        assert_eq!(JobSignal::Term.number(), libc::SIGTERM);
+
+        for disallowed in ["STOP", "SIGSEGV", "9", "", "SIG"] {
+            assert!(disallowed.parse::<JobSignal>().is_err(), "{disallowed}");
+        }
+
+        let json = serde_json::to_string(&JobSignal::Usr2).unwrap();
+        assert_eq!(json, r#""USR2""#);
+        assert_eq!(
+            serde_json::from_str::<JobSignal>(&json).unwrap(),
+            JobSignal::Usr2
+        );
+        assert!(serde_json::from_str::<JobSignal>(r#""SIGSTOP""#).is_err());
+    }
+}
@@ -1,7 +1,7 @@
 use compact_str::CompactString;
 use serde::{Deserialize, Serialize};
 use std::fmt;
-use strum::{Display, EnumIter, EnumString, FromRepr};
+use strum::{Display, EnumIter, EnumString, FromRepr, IntoEnumIterator};
 
 #[derive(Debug)]
 pub enum JobError {
@@ -39,6 +39,7 @@ impl fmt::Display for JobError {
     Hash,
     Ord,
     PartialOrd,
+    utoipa::ToSchema,
 )]
 pub enum JobState {
     #[strum(to_string = "Queued", serialize = "PD", serialize = "pd")]
@@ -57,20 +58,20 @@ pub enum JobState {
     Timeout,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, utoipa::ToSchema)]
 pub enum DependencyMode {
     All,
     Any,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Default)]
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Default, utoipa::ToSchema)]
 pub enum GpuSharingMode {
     #[default]
     Exclusive,
     Shared,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, utoipa::ToSchema)]
 pub enum JobStateReason {
     JobHeldUser,
     WaitingForDependency,
@@ -79,7 +80,101 @@ pub enum JobStateReason {
     WaitingForMemory,
     CancelledByUser,
     DependencyFailed(u32),
+    #[schema(value_type = String)]
     SystemError(CompactString),
+    /// Held back by another user's active GPU reservation.
+    #[schema(value_type = String)]
+    ReservedForUser(CompactString),
+    /// A maintenance window is on, or would start before the time limit ends.
+    Maintenance,
+    /// Stopped and requeued so the given higher-priority job could start.
+    PreemptedBy(u32),
+    /// No GPU or node has the labels the job's `--constraint` asks for.
+    NoMatchingResources,
+    /// Fewer CPU cores are free than the job's `--cpus` asks for.
+    WaitingForCpu,
+    /// Killed for going over its `--memory` limit; the peak usage in MB.
+    MemoryLimitExceeded(Option<u64>),
+    /// The job's run_dir or the log dir has less free space than `--min-free-disk`.
+    InsufficientDisk {
+        /// `run_dir` or `log_dir`
+        #[schema(value_type = String)]
+        dir: CompactString,
+        free_mb: u64,
+        required_mb: u64,
+    },
+    /// Dependencies that have not ended yet. Replaces `WaitingForDependency`,
+    /// which only states from older versions still carry.
+    Dependency {
+        unfinished: u32,
+    },
+    /// Fewer usable GPUs are free than the job asks for. Replaces
+    /// `WaitingForGpu` for jobs the scheduling pass looked at.
+    WaitingForGpus {
+        need: u32,
+        free: u32,
+    },
+    /// The job's group already runs its `--max-concurrent` jobs.
+    GroupLimit {
+        running: u32,
+        max: u32,
+    },
+}
+
+impl JobStateReason {
+    /// The reason's name without its details, for counting jobs by reason
+    pub fn label(&self) -> &'static str {
+        match self {
+            JobStateReason::JobHeldUser => "JobHeldUser",
+            JobStateReason::WaitingForDependency | JobStateReason::Dependency { .. } => {
+                "Dependency"
+            }
+            JobStateReason::WaitingForResources => "Resources",
+            JobStateReason::WaitingForGpu | JobStateReason::WaitingForGpus { .. } => {
+                "WaitingForGPUs"
+            }
+            JobStateReason::WaitingForMemory => "WaitingForMemory",
+            JobStateReason::WaitingForCpu => "WaitingForCPUs",
+            JobStateReason::CancelledByUser => "CancelledByUser",
+            JobStateReason::DependencyFailed(_) => "DependencyFailed",
+            JobStateReason::SystemError(_) => "SystemError",
+            JobStateReason::ReservedForUser(_) => "ReservedForUser",
+            JobStateReason::Maintenance => "Maintenance",
+            JobStateReason::PreemptedBy(_) => "PreemptedBy",
+            JobStateReason::NoMatchingResources => "NoMatchingResources",
+            JobStateReason::MemoryLimitExceeded(_) => "MemoryLimitExceeded",
+            JobStateReason::InsufficientDisk { .. } => "InsufficientDisk",
+            JobStateReason::GroupLimit { .. } => "GroupLimit",
+        }
+    }
+
+    /// Waiting for GPUs, memory, CPU cores or disk space to free up, rather
+    /// than on other jobs, a limit, a reservation or a person.
+    pub fn is_resource_wait(&self) -> bool {
+        matches!(
+            self,
+            JobStateReason::WaitingForResources
+                | JobStateReason::WaitingForGpu
+                | JobStateReason::WaitingForGpus { .. }
+                | JobStateReason::WaitingForMemory
+                | JobStateReason::WaitingForCpu
+                | JobStateReason::InsufficientDisk { .. }
+        )
+    }
+
+    pub fn is_gpu_wait(&self) -> bool {
+        matches!(
+            self,
+            JobStateReason::WaitingForGpu | JobStateReason::WaitingForGpus { .. }
+        )
+    }
+
+    pub fn is_dependency_wait(&self) -> bool {
+        matches!(
+            self,
+            JobStateReason::WaitingForDependency | JobStateReason::Dependency { .. }
+        )
+    }
 }
 
 impl fmt::Display for JobStateReason {
@@ -88,13 +183,44 @@ impl fmt::Display for JobStateReason {
             JobStateReason::JobHeldUser => write!(f, "JobHeldUser"),
             JobStateReason::WaitingForDependency => write!(f, "Dependency"),
             JobStateReason::WaitingForResources => write!(f, "Resources"),
-            JobStateReason::WaitingForGpu => write!(f, "Resources"),
-            JobStateReason::WaitingForMemory => write!(f, "Resources"),
+            JobStateReason::WaitingForGpu => write!(f, "WaitingForGPUs"),
+            JobStateReason::WaitingForMemory => write!(f, "WaitingForMemory"),
             JobStateReason::CancelledByUser => write!(f, "CancelledByUser"),
             JobStateReason::DependencyFailed(job_id) => {
                 write!(f, "DependencyFailed:{}", job_id)
             }
             JobStateReason::SystemError(msg) => write!(f, "SystemError:{}", msg),
+            JobStateReason::ReservedForUser(user) => write!(f, "ReservedForUser({})", user),
+            JobStateReason::Maintenance => write!(f, "Maintenance"),
+            JobStateReason::PreemptedBy(job_id) => write!(f, "PreemptedBy:{}", job_id),
+            JobStateReason::NoMatchingResources => write!(f, "NoMatchingResources"),
+            JobStateReason::WaitingForCpu => write!(f, "WaitingForCPUs"),
+            JobStateReason::MemoryLimitExceeded(Some(peak_mb)) => {
+                write!(f, "exceeded memory limit (OOM-killed, peak {} MB)", peak_mb)
+            }
+            JobStateReason::MemoryLimitExceeded(None) => {
+                write!(f, "exceeded memory limit (OOM-killed)")
+            }
+            JobStateReason::InsufficientDisk {
+                dir,
+                free_mb,
+                required_mb,
+            } => write!(
+                f,
+                "InsufficientDisk({} {} free < {})",
+                dir,
+                crate::utils::format_memory(*free_mb),
+                crate::utils::format_memory(*required_mb)
+            ),
+            JobStateReason::Dependency { unfinished } => {
+                write!(f, "Dependency({} unfinished)", unfinished)
+            }
+            JobStateReason::WaitingForGpus { need, free } => {
+                write!(f, "WaitingForGPUs(need {}, have {})", need, free)
+            }
+            JobStateReason::GroupLimit { running, max } => {
+                write!(f, "GroupLimit({}/{} running)", running, max)
+            }
         }
     }
 }
@@ -128,6 +254,14 @@ impl JobState {
         )
     }
 
+    /// Every state a job in `self` may move to, in declaration order. Empty
+    /// for final states.
+    pub fn allowed_transitions(self) -> Vec<JobState> {
+        JobState::iter()
+            .filter(|&next| self.can_transition_to(next))
+            .collect()
+    }
+
     pub fn is_final(&self) -> bool {
         Self::COMPLETED.contains(self)
     }
@@ -156,12 +290,66 @@ impl JobState {
 
 #[cfg(test)]
 mod tests {
-    use super::JobStateReason;
+    use super::{JobState, JobStateReason};
+    use strum::IntoEnumIterator;
+
+    #[test]
+    fn transition_matrix() {
+        use JobState::*;
+        let allowed = [
+            (Queued, vec![Hold, Running, Cancelled]),
+            (Hold, vec![Queued, Cancelled]),
+            (Running, vec![Finished, Failed, Cancelled, Timeout]),
+            (Finished, vec![]),
+            (Failed, vec![]),
+            (Cancelled, vec![]),
+            (Timeout, vec![]),
+        ];
+        assert_eq!(allowed.len(), JobState::iter().count());
+
+        for (from, to) in allowed {
+            for next in JobState::iter() {
+                assert_eq!(
+                    from.can_transition_to(next),
+                    to.contains(&next),
+                    "{from} -> {next}"
+                );
+            }
+            assert_eq!(from.allowed_transitions(), to, "from {from}");
+            assert_eq!(from.allowed_transitions().is_empty(), from.is_final());
+        }
+    }
+
+    #[test]
+    fn pending_reasons_say_what_the_job_waits_for() {
+        let gpus = JobStateReason::WaitingForGpus { need: 4, free: 1 };
+        assert_eq!(gpus.to_string(), "WaitingForGPUs(need 4, have 1)");
+        assert_eq!(gpus.label(), JobStateReason::WaitingForGpu.label());
+        let dependency = JobStateReason::Dependency { unfinished: 4 };
+        assert_eq!(dependency.to_string(), "Dependency(4 unfinished)");
+        assert!(dependency.is_dependency_wait() && !dependency.is_resource_wait());
+        let group = JobStateReason::GroupLimit { running: 2, max: 2 };
+        assert_eq!(group.to_string(), "GroupLimit(2/2 running)");
+        assert!(!group.is_resource_wait());
+        assert_eq!(
+            JobStateReason::WaitingForMemory.to_string(),
+            "WaitingForMemory"
+        );
+        assert_eq!(JobStateReason::WaitingForCpu.to_string(), "WaitingForCPUs");
+        assert!(JobStateReason::WaitingForResources.is_resource_wait());
+        assert!(!JobStateReason::ReservedForUser("bob".into()).is_resource_wait());
+    }
 
     #[test]
-    fn resource_wait_reasons_share_the_same_display_label() {
-        assert_eq!(JobStateReason::WaitingForResources.to_string(), "Resources");
-        assert_eq!(JobStateReason::WaitingForGpu.to_string(), "Resources");
-        assert_eq!(JobStateReason::WaitingForMemory.to_string(), "Resources");
+    fn insufficient_disk_shows_the_dir_and_both_sizes() {
+        let reason = JobStateReason::InsufficientDisk {
+            dir: "run_dir".into(),
+            free_mb: 12 * 1024,
+            required_mb: 50 * 1024,
+        };
+        assert_eq!(
+            reason.to_string(),
+            "InsufficientDisk(run_dir 12G free < 50G)"
+        );
     }
 }
@@ -0,0 +1,116 @@
+use compact_str::CompactString;
+use serde::{Deserialize, Deserializer, Serialize};
+
+/// Most tags one job may carry.
+pub const MAX_TAGS_PER_JOB: usize = 20;
+
+/// Longest tag accepted.
+const MAX_TAG_CHARS: usize = 64;
+
+/// Freeform labels for organizing jobs (`gbatch --tag`), unlike parameters
+/// never substituted into the command. Kept in the order first given,
+/// without repeats; most jobs have none, which costs no allocation.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct Tags(Box<[CompactString]>);
+
+impl Tags {
+    /// Check and deduplicate `tags`: each is lowercase letters, digits and
+    /// dashes, and there are at most [`MAX_TAGS_PER_JOB`].
+    pub fn new<S: AsRef<str>>(tags: impl IntoIterator<Item = S>) -> Result<Self, String> {
+        Self::default().with_changes(tags, std::iter::empty::<&str>())
+    }
+
+    /// These tags with `add` appended and `remove` taken out, checked as by
+    /// [`Tags::new`]. Removing a tag the job does not have is not an error.
+    pub fn with_changes<A: AsRef<str>, R: AsRef<str>>(
+        &self,
+        add: impl IntoIterator<Item = A>,
+        remove: impl IntoIterator<Item = R>,
+    ) -> Result<Self, String> {
+        let mut tags = self.0.to_vec();
+        for tag in add {
+            let tag = tag.as_ref();
+            validate_tag(tag)?;
+            if !tags.iter().any(|t| t == tag) {
+                tags.push(CompactString::from(tag));
+            }
+        }
+        for tag in remove {
+            tags.retain(|t| t != tag.as_ref());
+        }
+        if tags.len() > MAX_TAGS_PER_JOB {
+            return Err(format!(
+                "A job can have at most {MAX_TAGS_PER_JOB} tags, not {}",
+                tags.len()
+            ));
+        }
+        Ok(Self(tags.into_boxed_slice()))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &str> + Clone {
+        self.0.iter().map(CompactString::as_str)
+    }
+
+    pub fn contains(&self, tag: &str) -> bool {
+        self.iter().any(|t| t == tag)
+    }
+}
+
+/// Accepted as given, so a state saved before the rules changed still loads;
+/// submissions are checked with [`Tags::new`].
+impl<'de> Deserialize<'de> for Tags {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Vec::<CompactString>::deserialize(deserializer).map(|tags| Self(tags.into_boxed_slice()))
+    }
+}
+
+/// Fails unless `tag` is 1 to 64 lowercase letters, digits and dashes.
+pub fn validate_tag(tag: &str) -> Result<(), String> {
+    let valid = !tag.is_empty()
+        && tag.len() <= MAX_TAG_CHARS
+        && tag
+            .bytes()
+            .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'-');
+    if valid {
+        Ok(())
+    } else {
+        Err(format!(
+            "Invalid tag '{tag}': use up to {MAX_TAG_CHARS} lowercase letters, digits and dashes"
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tags_are_checked_deduplicated_and_capped() {
+        let tags = Tags::new(["exp42", "baseline", "exp42"]).unwrap();
+        assert_eq!(tags.iter().collect::<Vec<_>>(), ["exp42", "baseline"]);
+
+        let changed = tags
+            .with_changes(["lr-sweep"], ["exp42", "missing"])
+            .unwrap();
+        assert_eq!(changed.iter().collect::<Vec<_>>(), ["baseline", "lr-sweep"]);
+
+        for invalid in ["", "Exp42", "a_b", "two words", &"x".repeat(65)] {
+            assert!(Tags::new([invalid]).is_err(), "{invalid:?} was accepted");
+        }
+        let many: Vec<String> = (0..=MAX_TAGS_PER_JOB).map(|i| format!("t{i}")).collect();
+        assert!(Tags::new(&many).is_err());
+        assert_eq!(Tags::new(&many[1..]).unwrap().len(), MAX_TAGS_PER_JOB);
+
+        let json = serde_json::to_value(&tags).unwrap();
+        assert_eq!(json, serde_json::json!(["exp42", "baseline"]));
+        assert_eq!(serde_json::from_value::<Tags>(json).unwrap(), tags);
+    }
+}
@@ -1,7 +1,7 @@
 use super::scheduler::Scheduler;
 use anyhow::{anyhow, Result};
 
-pub const CURRENT_VERSION: u32 = 4;
+pub const CURRENT_VERSION: u32 = 5;
 
 /// Migrate state from any version to the current version
 pub fn migrate_state(mut scheduler: Scheduler) -> Result<Scheduler> {
@@ -55,6 +55,9 @@ pub fn migrate_state(mut scheduler: Scheduler) -> Result<Scheduler> {
     if from_version < 4 {
         scheduler = migrate_v3_to_v4(scheduler)?;
     }
+    if from_version < 5 {
+        scheduler = migrate_v4_to_v5(scheduler)?;
+    }
 
     scheduler.version = CURRENT_VERSION;
     tracing::debug!(
@@ -119,6 +122,26 @@ fn migrate_v3_to_v4(mut scheduler: Scheduler) -> Result<Scheduler> {
     Ok(scheduler)
 }
 
+/// Migrate from version 4 to version 5 (job `environment` superseding `conda_env`)
+fn migrate_v4_to_v5(mut scheduler: Scheduler) -> Result<Scheduler> {
+    tracing::info!("Migrating from v4 to v5: mapping conda_env to job environments");
+    let mut mapped = 0;
+    for spec in scheduler.job_specs.iter_mut() {
+        if spec.environment.is_none() && spec.conda_env.is_some() {
+            mapped += 1;
+        }
+        spec.normalize_environment();
+    }
+    scheduler.version = 5;
+
+    tracing::info!(
+        "Migration v4→v5 complete: mapped conda_env of {} jobs",
+        mapped
+    );
+
+    Ok(scheduler)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -341,4 +364,28 @@ mod tests {
         assert_eq!(scheduler.get_job(2).unwrap().state, JobState::Queued);
         assert_eq!(scheduler.next_job_id(), 3);
     }
+
+    #[test]
+    fn test_v4_to_v5_maps_conda_env_to_environment() {
+        use crate::core::job::{EnvSpec, Job};
+
+        let old_json = serde_json::json!({
+            "version": 4,
+            "next_job_id": 3,
+            "jobs": [
+                Job { id: 1, conda_env: Some("torch".into()), ..Default::default() },
+                Job { id: 2, ..Default::default() },
+            ],
+        })
+        .to_string();
+
+        let scheduler: Scheduler = serde_json::from_str(&old_json).unwrap();
+        let result = migrate_state(scheduler).unwrap();
+        assert_eq!(result.version, CURRENT_VERSION);
+
+        let job = result.get_job(1).unwrap();
+        assert_eq!(job.environment, EnvSpec::Conda("torch".into()));
+        assert_eq!(job.conda_env.as_deref(), Some("torch"));
+        assert_eq!(result.get_job(2).unwrap().environment, EnvSpec::None);
+    }
 }
@@ -1,4 +1,6 @@
+pub mod api_error;
 pub mod conflict;
+pub mod cpu;
 pub mod executor;
 pub mod gpu;
 pub mod gpu_allocation;
@@ -6,7 +8,12 @@ pub mod info;
 pub mod job;
 pub mod macros;
 pub mod migrations;
+pub mod node;
+pub mod report;
 pub mod reservation;
+pub mod runtime_stats;
 pub mod scheduler;
+pub mod schema;
+pub mod version;
 
 pub use gpu::{GPUSlot, GpuUuid};
@@ -0,0 +1,93 @@
+//! Remote machines running `gflowd agent`, which the head daemon can place
+//! jobs on. The head's own GPUs are not a node; jobs placed there have no
+//! `node` set.
+
+use crate::utils::constraint::Labels;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::time::{Duration, SystemTime};
+
+/// How often an agent re-registers with the head daemon.
+pub const NODE_HEARTBEAT_INTERVAL: Duration = Duration::from_secs(10);
+
+/// A node with no heartbeat for this long is marked down.
+pub const NODE_HEARTBEAT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Whether the head daemon still hears from a node
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, strum::Display)]
+pub enum NodeStatus {
+    Up,
+    /// Missed its heartbeats; nothing is placed on it until it registers again
+    Down,
+}
+
+/// What an agent sends when it joins, and again with every heartbeat.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NodeRegistration {
+    /// Unique node name, the agent's hostname unless overridden
+    pub name: String,
+    /// Base URL the head daemon forwards jobs to
+    pub url: String,
+    /// Number of GPUs, indexed from 0 on the node
+    pub gpus: u32,
+    pub total_memory_mb: u64,
+    /// Secret the head daemon presents to the agent
+    pub token: String,
+    /// The agent's `daemon.labels`, shared by all its GPUs
+    #[serde(default)]
+    pub labels: Labels,
+}
+
+/// A registered node
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Node {
+    pub name: String,
+    pub url: String,
+    pub gpus: u32,
+    pub total_memory_mb: u64,
+    /// Never shown by `GET /nodes`
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub token: String,
+    pub status: NodeStatus,
+    pub last_heartbeat: SystemTime,
+    /// The agent's labels with `label_overrides` applied
+    #[serde(default)]
+    pub labels: Labels,
+    /// Set (`Some`) or removed (`None`) on the head with `gctl set-label --node`;
+    /// kept when the agent registers again
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub label_overrides: BTreeMap<String, Option<String>>,
+}
+
+impl Node {
+    pub fn from_registration(registration: NodeRegistration, now: SystemTime) -> Self {
+        Self {
+            name: registration.name,
+            url: registration.url,
+            gpus: registration.gpus,
+            total_memory_mb: registration.total_memory_mb,
+            token: registration.token,
+            status: NodeStatus::Up,
+            last_heartbeat: now,
+            labels: registration.labels,
+            label_overrides: BTreeMap::new(),
+        }
+    }
+
+    /// Record label changes made on the head and apply them to `labels`.
+    pub fn override_labels(&mut self, overrides: BTreeMap<String, Option<String>>) {
+        self.label_overrides.extend(overrides);
+        for (key, value) in &self.label_overrides {
+            match value {
+                Some(value) => self.labels.insert(key.clone(), value.clone()),
+                None => self.labels.remove(key),
+            };
+        }
+    }
+
+    /// Whether the last heartbeat is older than [`NODE_HEARTBEAT_TIMEOUT`].
+    pub fn is_stale(&self, now: SystemTime) -> bool {
+        now.duration_since(self.last_heartbeat)
+            .is_ok_and(|elapsed| elapsed > NODE_HEARTBEAT_TIMEOUT)
+    }
+}
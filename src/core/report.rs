@@ -0,0 +1,232 @@
+//! Usage accounting served by `GET /report`.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use super::job::{Job, JobState};
+
+/// How [`UsageReport`] rows are keyed.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    strum::Display,
+    strum::EnumString,
+)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum ReportGroupBy {
+    #[default]
+    User,
+    Project,
+    /// UTC day the job was submitted.
+    Day,
+}
+
+/// Query parameters of `GET /report`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ReportQuery {
+    #[serde(default)]
+    pub group_by: ReportGroupBy,
+    /// Only jobs submitted at or after this Unix timestamp.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub since: Option<i64>,
+    /// Only jobs submitted before this Unix timestamp.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub until: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ReportRow {
+    /// User, project (`-` for none), or day (`YYYY-MM-DD`).
+    pub key: String,
+    pub jobs: usize,
+    pub completed: usize,
+    /// Failed and timed-out jobs.
+    pub failed: usize,
+    pub cancelled: usize,
+    /// GPUs times runtime, counting running jobs up to now.
+    pub gpu_hours: f64,
+    /// Completed jobs as a percentage of those that ended.
+    pub success_rate: Option<f64>,
+    /// Mean time from submission to start of the jobs that started.
+    pub avg_wait_secs: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UsageReport {
+    pub group_by: ReportGroupBy,
+    pub since: Option<i64>,
+    pub until: Option<i64>,
+    /// Days in order; users and projects by GPU-hours, most first.
+    pub rows: Vec<ReportRow>,
+    pub total: ReportRow,
+}
+
+#[derive(Default)]
+struct Tally {
+    row: ReportRow,
+    wait_secs: f64,
+    started: usize,
+}
+
+impl Tally {
+    fn add(&mut self, job: &Job) {
+        self.row.jobs += 1;
+        match job.state {
+            JobState::Finished => self.row.completed += 1,
+            JobState::Failed | JobState::Timeout => self.row.failed += 1,
+            JobState::Cancelled => self.row.cancelled += 1,
+            _ => {}
+        }
+        if let Some(runtime) = job.runtime() {
+            self.row.gpu_hours += job.gpus as f64 * runtime.as_secs_f64() / 3600.0;
+        }
+        if let Some(wait) = job.wait_time() {
+            self.wait_secs += wait.as_secs_f64();
+            self.started += 1;
+        }
+    }
+
+    fn finish(mut self, key: String) -> ReportRow {
+        let ended = self.row.completed + self.row.failed + self.row.cancelled;
+        self.row.key = key;
+        self.row.success_rate =
+            (ended > 0).then(|| self.row.completed as f64 / ended as f64 * 100.0);
+        self.row.avg_wait_secs = (self.started > 0).then(|| self.wait_secs / self.started as f64);
+        self.row
+    }
+}
+
+fn timestamp(secs: i64) -> SystemTime {
+    UNIX_EPOCH + Duration::from_secs(secs.max(0) as u64)
+}
+
+impl UsageReport {
+    /// Aggregate the jobs submitted within the query's window.
+    pub fn build<'a>(jobs: impl IntoIterator<Item = &'a Job>, query: &ReportQuery) -> Self {
+        let since = query.since.map(timestamp);
+        let until = query.until.map(timestamp);
+
+        let mut groups: BTreeMap<String, Tally> = BTreeMap::new();
+        let mut total = Tally::default();
+        for job in jobs {
+            if query
+                .user
+                .as_deref()
+                .is_some_and(|user| job.submitted_by != user)
+            {
+                continue;
+            }
+            let Some(submitted) = job.submitted_at else {
+                continue;
+            };
+            if since.is_some_and(|since| submitted < since)
+                || until.is_some_and(|until| submitted >= until)
+            {
+                continue;
+            }
+
+            let key = match query.group_by {
+                ReportGroupBy::User => job.submitted_by.to_string(),
+                ReportGroupBy::Project => job.project.as_deref().unwrap_or("-").to_string(),
+                ReportGroupBy::Day => chrono::DateTime::<chrono::Utc>::from(submitted)
+                    .format("%Y-%m-%d")
+                    .to_string(),
+            };
+            groups.entry(key).or_default().add(job);
+            total.add(job);
+        }
+
+        let mut rows: Vec<ReportRow> = groups
+            .into_iter()
+            .map(|(key, tally)| tally.finish(key))
+            .collect();
+        if query.group_by != ReportGroupBy::Day {
+            rows.sort_by(|a, b| b.gpu_hours.total_cmp(&a.gpu_hours));
+        }
+
+        Self {
+            group_by: query.group_by,
+            since: query.since,
+            until: query.until,
+            rows,
+            total: total.finish("TOTAL".to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::job::JobBuilder;
+
+    const DAY: u64 = 86_400;
+
+    fn job(user: &str, gpus: u32, submitted_day: u64, state: JobState, hours: u64) -> Job {
+        let mut job = JobBuilder::new()
+            .command("true")
+            .submitted_by(user)
+            .gpus(gpus)
+            .build();
+        let submitted = UNIX_EPOCH + Duration::from_secs(submitted_day * DAY);
+        let started = submitted + Duration::from_secs(60);
+        job.state = state;
+        job.submitted_at = Some(submitted);
+        job.started_at = Some(started);
+        job.finished_at = Some(started + Duration::from_secs(hours * 3600));
+        job
+    }
+
+    #[test]
+    fn groups_by_user_with_the_heaviest_first() {
+        let jobs = [
+            job("alice", 1, 1, JobState::Finished, 2),
+            job("bob", 4, 1, JobState::Finished, 3),
+            job("bob", 2, 2, JobState::Failed, 1),
+            job("alice", 1, 2, JobState::Cancelled, 0),
+        ];
+        let report = UsageReport::build(&jobs, &ReportQuery::default());
+
+        let keys: Vec<&str> = report.rows.iter().map(|row| row.key.as_str()).collect();
+        assert_eq!(keys, ["bob", "alice"]);
+        let bob = &report.rows[0];
+        assert_eq!((bob.jobs, bob.completed, bob.failed), (2, 1, 1));
+        assert_eq!(bob.gpu_hours, 14.0);
+        assert_eq!(bob.success_rate, Some(50.0));
+        assert_eq!(bob.avg_wait_secs, Some(60.0));
+        assert_eq!(report.total.jobs, 4);
+        assert_eq!(report.total.gpu_hours, 16.0);
+    }
+
+    #[test]
+    fn buckets_by_day_within_the_window() {
+        let jobs = [
+            job("alice", 1, 1, JobState::Finished, 1),
+            job("alice", 1, 2, JobState::Finished, 1),
+            job("alice", 1, 2, JobState::Running, 0),
+            job("alice", 1, 3, JobState::Finished, 1),
+        ];
+        let query = ReportQuery {
+            group_by: ReportGroupBy::Day,
+            since: Some(2 * DAY as i64),
+            until: Some(3 * DAY as i64),
+            user: None,
+        };
+        let report = UsageReport::build(&jobs, &query);
+
+        assert_eq!(report.rows.len(), 1);
+        assert_eq!(report.rows[0].key, "1970-01-03");
+        assert_eq!(report.rows[0].jobs, 2);
+        // The running job has not ended, so it does not count against the rate.
+        assert_eq!(report.rows[0].success_rate, Some(100.0));
+    }
+}
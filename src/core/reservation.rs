@@ -1,9 +1,12 @@
+use chrono::Weekday;
 use compact_str::CompactString;
 use serde::{Deserialize, Serialize};
-use std::time::{Duration, SystemTime};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const DAY_SECS: u64 = 24 * 3600;
 
 /// Status of a GPU reservation
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
 pub enum ReservationStatus {
     /// Scheduled but not yet active
     Pending,
@@ -16,7 +19,7 @@ pub enum ReservationStatus {
 }
 
 /// GPU specification for a reservation
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum GpuSpec {
     /// Reserve a specific number of GPUs (scheduler will allocate dynamically)
@@ -43,25 +46,167 @@ impl GpuSpec {
     }
 }
 
+/// What a reservation is for
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+pub enum ReservationKind {
+    /// GPUs set aside for the reserving user
+    #[default]
+    User,
+    /// Nothing starts during the window, and jobs whose time limit would
+    /// run into it wait until it is over
+    Maintenance,
+}
+
+/// Weekly repetition of a reservation. Computed in UTC, so an occurrence
+/// always starts at the UTC time of day of the first one.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct Recurrence {
+    /// Days an occurrence starts on (UTC)
+    #[serde(with = "weekday_names")]
+    #[schema(value_type = Vec<String>, example = json!(["Mon", "Thu"]))]
+    pub weekdays: Vec<Weekday>,
+    /// No occurrence starts at or after this time; repeats forever if unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<crate::core::schema::SystemTimeSchema>)]
+    pub until: Option<SystemTime>,
+}
+
+impl Recurrence {
+    /// The first occurrence starting at or after `after`, at the UTC time of
+    /// day of `anchor`. Looks at most eight days ahead.
+    pub fn next_start(&self, anchor: SystemTime, after: SystemTime) -> Option<SystemTime> {
+        let time_of_day = unix_secs(anchor) % DAY_SECS;
+        let after = unix_secs_ceil(after.max(anchor));
+        (after / DAY_SECS..=after / DAY_SECS + 7)
+            .filter(|&day| self.repeats_on(day))
+            .map(|day| day * DAY_SECS + time_of_day)
+            .find(|&start| start >= after)
+            .map(|start| UNIX_EPOCH + Duration::from_secs(start))
+            .filter(|&start| self.until.is_none_or(|until| start < until))
+    }
+
+    /// Shortest time between two consecutive occurrences; occurrences
+    /// longer than this would overlap each other.
+    pub fn min_gap(&self) -> Duration {
+        let mut days: Vec<u32> = self
+            .weekdays
+            .iter()
+            .map(|w| w.num_days_from_monday())
+            .collect();
+        days.sort_unstable();
+        days.dedup();
+        let gap_days = match (days.first(), days.last()) {
+            (Some(first), Some(last)) => days
+                .windows(2)
+                .map(|pair| pair[1] - pair[0])
+                .chain([first + 7 - last])
+                .min()
+                .unwrap_or(7),
+            _ => 7,
+        };
+        Duration::from_secs(u64::from(gap_days) * DAY_SECS)
+    }
+
+    /// Whether day `day` since the Unix epoch is one of `weekdays`.
+    fn repeats_on(&self, day: u64) -> bool {
+        // 1970-01-01 was a Thursday.
+        let weekday = (day + 3) % 7;
+        self.weekdays
+            .iter()
+            .any(|w| u64::from(w.num_days_from_monday()) == weekday)
+    }
+}
+
+impl std::fmt::Display for Recurrence {
+    /// E.g. `weekly Tue,Thu until 2026-06-30 (UTC)`.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let days: Vec<String> = self.weekdays.iter().map(ToString::to_string).collect();
+        write!(f, "weekly {}", days.join(","))?;
+        if let Some(until) = self.until {
+            // The last day an occurrence can start on.
+            let last = chrono::DateTime::<chrono::Utc>::from(until - Duration::from_secs(1));
+            write!(f, " until {}", last.format("%Y-%m-%d"))?;
+        }
+        write!(f, " (UTC)")
+    }
+}
+
+fn unix_secs(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs())
+}
+
+fn unix_secs_ceil(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH).map_or(0, |elapsed| {
+        elapsed.as_secs() + u64::from(elapsed.subsec_nanos() > 0)
+    })
+}
+
+/// Weekdays as their short English names (`"Tue"`).
+mod weekday_names {
+    use chrono::Weekday;
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(
+        weekdays: &[Weekday],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(weekdays.iter().map(ToString::to_string))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<Weekday>, D::Error> {
+        Vec::<String>::deserialize(deserializer)?
+            .iter()
+            .map(|name| {
+                name.parse()
+                    .map_err(|_| D::Error::custom(format!("invalid weekday '{name}'")))
+            })
+            .collect()
+    }
+}
+
+/// Kind and recurrence of a new reservation; the defaults give a one-off
+/// user reservation.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct ReservationOptions {
+    #[serde(default)]
+    pub kind: ReservationKind,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub recurrence: Option<Recurrence>,
+}
+
 /// A GPU reservation for a specific user
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
 pub struct GpuReservation {
     /// Unique reservation ID
     pub id: u32,
     /// Username who created the reservation
+    #[schema(value_type = String)]
     pub user: CompactString,
     /// GPU specification (count or specific indices)
     pub gpu_spec: GpuSpec,
     /// When reservation starts
+    #[schema(value_type = crate::core::schema::SystemTimeSchema)]
     pub start_time: SystemTime,
     /// How long reservation lasts
+    #[schema(value_type = crate::core::schema::DurationSchema)]
     pub duration: Duration,
     /// Current status
     pub status: ReservationStatus,
     /// Creation timestamp
+    #[schema(value_type = crate::core::schema::SystemTimeSchema)]
     pub created_at: SystemTime,
     /// Cancellation timestamp
+    #[schema(value_type = Option<crate::core::schema::SystemTimeSchema>)]
     pub cancelled_at: Option<SystemTime>,
+    #[serde(default)]
+    pub kind: ReservationKind,
+    /// For recurring reservations, `start_time` is the current or next
+    /// occurrence and moves on as occurrences end
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub recurrence: Option<Recurrence>,
 }
 
 impl GpuReservation {
@@ -71,7 +216,18 @@ impl GpuReservation {
             return false;
         }
 
-        now >= self.start_time && now < self.end_time()
+        self.occurrence_ending_after(now)
+            .is_some_and(|start| start <= now)
+    }
+
+    /// Start of the earliest occurrence still running after `time`, if any.
+    pub fn occurrence_ending_after(&self, time: SystemTime) -> Option<SystemTime> {
+        if self.end_time() > time {
+            return Some(self.start_time);
+        }
+        // Occurrences starting later than `time - duration` end after `time`.
+        let after = time.checked_sub(self.duration).unwrap_or(UNIX_EPOCH) + Duration::from_nanos(1);
+        self.recurrence.as_ref()?.next_start(self.start_time, after)
     }
 
     /// Calculate the end time of the reservation
@@ -79,30 +235,55 @@ impl GpuReservation {
         self.start_time + self.duration
     }
 
+    /// Starts of the occurrences overlapping `[start, end)`.
+    pub fn occurrences_between(&self, start: SystemTime, end: SystemTime) -> Vec<SystemTime> {
+        std::iter::successors(self.occurrence_ending_after(start), |&previous| {
+            self.recurrence
+                .as_ref()?
+                .next_start(self.start_time, previous + Duration::from_secs(1))
+        })
+        .take_while(|&occurrence| occurrence < end)
+        .collect()
+    }
+
+    /// Who the GPUs are held for: the user, or `maintenance`.
+    pub fn holder(&self) -> &str {
+        match self.kind {
+            ReservationKind::User => &self.user,
+            ReservationKind::Maintenance => "maintenance",
+        }
+    }
+
     /// Check if this reservation overlaps with a given time range
     pub fn overlaps_with(&self, start: SystemTime, end: SystemTime) -> bool {
         // Two ranges overlap if: start1 < end2 AND start2 < end1
-        self.start_time < end && start < self.end_time()
+        self.occurrence_ending_after(start)
+            .is_some_and(|occurrence| occurrence < end)
     }
 
     /// Update status based on current time
+    ///
+    /// A recurring reservation whose occurrence has ended moves on to its
+    /// next occurrence instead of completing.
     pub fn update_status(&mut self, now: SystemTime) {
-        match self.status {
-            ReservationStatus::Pending => {
-                if now >= self.start_time && now < self.end_time() {
-                    self.status = ReservationStatus::Active;
-                } else if now >= self.end_time() {
-                    self.status = ReservationStatus::Completed;
-                }
-            }
-            ReservationStatus::Active => {
-                if now >= self.end_time() {
-                    self.status = ReservationStatus::Completed;
-                }
-            }
-            ReservationStatus::Completed | ReservationStatus::Cancelled => {
-                // Terminal states, no change
+        if matches!(
+            self.status,
+            ReservationStatus::Completed | ReservationStatus::Cancelled
+        ) {
+            // Terminal states, no change
+            return;
+        }
+
+        match self.occurrence_ending_after(now) {
+            Some(start) => {
+                self.start_time = start;
+                self.status = if now >= start {
+                    ReservationStatus::Active
+                } else {
+                    ReservationStatus::Pending
+                };
             }
+            None => self.status = ReservationStatus::Completed,
         }
     }
 
@@ -157,6 +338,8 @@ mod tests {
             status: ReservationStatus::Pending,
             created_at: SystemTime::UNIX_EPOCH,
             cancelled_at: None,
+            kind: ReservationKind::User,
+            recurrence: None,
         };
 
         // Before start time
@@ -197,6 +380,8 @@ mod tests {
             status: ReservationStatus::Pending,
             created_at: SystemTime::UNIX_EPOCH,
             cancelled_at: None,
+            kind: ReservationKind::User,
+            recurrence: None,
         };
 
         assert_eq!(reservation.end_time(), start + duration);
@@ -216,6 +401,8 @@ mod tests {
             status: ReservationStatus::Pending,
             created_at: SystemTime::UNIX_EPOCH,
             cancelled_at: None,
+            kind: ReservationKind::User,
+            recurrence: None,
         };
 
         let end = start + duration;
@@ -269,6 +456,8 @@ mod tests {
             status: ReservationStatus::Pending,
             created_at: SystemTime::UNIX_EPOCH,
             cancelled_at: None,
+            kind: ReservationKind::User,
+            recurrence: None,
         };
 
         // Before start: stays Pending
@@ -315,6 +504,8 @@ mod tests {
             status: ReservationStatus::Pending,
             created_at: SystemTime::UNIX_EPOCH,
             cancelled_at: None,
+            kind: ReservationKind::User,
+            recurrence: None,
         };
 
         // If we check after end time while still Pending, it should go to Completed
@@ -337,6 +528,8 @@ mod tests {
             status: ReservationStatus::Pending,
             created_at: now,
             cancelled_at: None,
+            kind: ReservationKind::User,
+            recurrence: None,
         };
 
         // Should return start_time for pending reservation
@@ -363,6 +556,8 @@ mod tests {
             status: ReservationStatus::Active,
             created_at: now - Duration::from_secs(2000),
             cancelled_at: None,
+            kind: ReservationKind::User,
+            recurrence: None,
         };
 
         // Should return end_time for active reservation
@@ -387,6 +582,8 @@ mod tests {
             status: ReservationStatus::Completed,
             created_at: now - Duration::from_secs(8000),
             cancelled_at: None,
+            kind: ReservationKind::User,
+            recurrence: None,
         };
 
         // Completed reservation should return None
@@ -450,6 +647,8 @@ mod tests {
                     status: ReservationStatus::Pending,
                     created_at: SystemTime::UNIX_EPOCH,
                     cancelled_at: None,
+                    kind: ReservationKind::User,
+                    recurrence: None,
                 };
 
                 let end2 = start2 + dur2;
@@ -465,6 +664,8 @@ mod tests {
                     status: ReservationStatus::Pending,
                     created_at: SystemTime::UNIX_EPOCH,
                     cancelled_at: None,
+                    kind: ReservationKind::User,
+                    recurrence: None,
                 };
 
                 let end1 = start1 + dur1;
@@ -489,6 +690,8 @@ mod tests {
                     status: ReservationStatus::Pending,
                     created_at: SystemTime::UNIX_EPOCH,
                     cancelled_at: None,
+                    kind: ReservationKind::User,
+                    recurrence: None,
                 };
 
                 let end = start + dur;
@@ -514,6 +717,8 @@ mod tests {
                     status: ReservationStatus::Pending,
                     created_at: SystemTime::UNIX_EPOCH,
                     cancelled_at: None,
+                    kind: ReservationKind::User,
+                    recurrence: None,
                 };
 
                 let end = start + dur;
@@ -538,6 +743,8 @@ mod tests {
                     status: ReservationStatus::Pending,
                     created_at: SystemTime::UNIX_EPOCH,
                     cancelled_at: None,
+                    kind: ReservationKind::User,
+                    recurrence: None,
                 };
 
                 let end = start + dur;
@@ -575,6 +782,8 @@ mod tests {
                     status: ReservationStatus::Pending,
                     created_at: SystemTime::UNIX_EPOCH,
                     cancelled_at: None,
+                    kind: ReservationKind::User,
+                    recurrence: None,
                 };
 
                 prop_assert_eq!(reservation.end_time(), start + dur);
@@ -611,6 +820,8 @@ mod tests {
                     status: ReservationStatus::Cancelled,
                     created_at: SystemTime::UNIX_EPOCH,
                     cancelled_at: Some(SystemTime::UNIX_EPOCH),
+                    kind: ReservationKind::User,
+                    recurrence: None,
                 };
 
                 let check = SystemTime::UNIX_EPOCH + Duration::from_secs(check_time);
@@ -622,4 +833,101 @@ mod tests {
             }
         }
     }
+
+    fn utc(y: i32, m: u32, d: u32, h: u32) -> SystemTime {
+        use chrono::TimeZone;
+        chrono::Utc
+            .with_ymd_and_hms(y, m, d, h, 0, 0)
+            .unwrap()
+            .into()
+    }
+
+    fn weekly(weekdays: &[Weekday], until: Option<SystemTime>) -> Recurrence {
+        Recurrence {
+            weekdays: weekdays.to_vec(),
+            until,
+        }
+    }
+
+    #[test]
+    fn test_recurrence_steps_through_weekdays_in_utc() {
+        // 2026-01-27 is a Tuesday.
+        let anchor = utc(2026, 1, 27, 14);
+        let recurrence = weekly(&[Weekday::Tue, Weekday::Thu], None);
+
+        assert_eq!(recurrence.next_start(anchor, anchor), Some(anchor));
+        assert_eq!(
+            recurrence.next_start(anchor, anchor + Duration::from_secs(1)),
+            Some(utc(2026, 1, 29, 14))
+        );
+        assert_eq!(
+            recurrence.next_start(anchor, utc(2026, 1, 29, 15)),
+            Some(utc(2026, 2, 3, 14))
+        );
+        // Across a year boundary.
+        assert_eq!(
+            recurrence.next_start(anchor, utc(2026, 12, 30, 0)),
+            Some(utc(2026, 12, 31, 14))
+        );
+        assert_eq!(
+            recurrence.next_start(anchor, utc(2026, 12, 31, 15)),
+            Some(utc(2027, 1, 5, 14))
+        );
+
+        let until_new_year = weekly(&[Weekday::Tue, Weekday::Thu], Some(utc(2027, 1, 1, 0)));
+        assert_eq!(
+            until_new_year.next_start(anchor, utc(2026, 12, 31, 15)),
+            None
+        );
+        assert_eq!(weekly(&[], None).next_start(anchor, anchor), None);
+        assert_eq!(
+            until_new_year.to_string(),
+            "weekly Tue,Thu until 2026-12-31 (UTC)"
+        );
+    }
+
+    #[test]
+    fn test_recurrence_min_gap() {
+        let day = Duration::from_secs(DAY_SECS);
+        assert_eq!(weekly(&[Weekday::Tue], None).min_gap(), 7 * day);
+        assert_eq!(
+            weekly(&[Weekday::Tue, Weekday::Thu], None).min_gap(),
+            2 * day
+        );
+        assert_eq!(weekly(&[Weekday::Mon, Weekday::Sun], None).min_gap(), day);
+    }
+
+    #[test]
+    fn test_recurring_reservation_moves_to_next_occurrence() {
+        let mut reservation = GpuReservation {
+            id: 1,
+            user: "class".into(),
+            gpu_spec: GpuSpec::Indices(vec![0, 1, 2, 3]),
+            start_time: utc(2026, 1, 27, 14),
+            duration: Duration::from_secs(4 * 3600),
+            status: ReservationStatus::Pending,
+            created_at: SystemTime::UNIX_EPOCH,
+            cancelled_at: None,
+            kind: ReservationKind::User,
+            recurrence: Some(weekly(&[Weekday::Tue], Some(utc(2026, 2, 4, 0)))),
+        };
+
+        reservation.update_status(utc(2026, 1, 27, 17));
+        assert_eq!(reservation.status, ReservationStatus::Active);
+
+        // The first occurrence ends at 18:00; the next Tuesday is pending.
+        reservation.update_status(utc(2026, 1, 27, 18));
+        assert_eq!(reservation.status, ReservationStatus::Pending);
+        assert_eq!(reservation.start_time, utc(2026, 2, 3, 14));
+        assert!(reservation.overlaps_with(utc(2026, 2, 3, 17), utc(2026, 2, 3, 20)));
+        assert!(!reservation.overlaps_with(utc(2026, 2, 4, 0), utc(2026, 2, 9, 0)));
+        assert_eq!(
+            reservation.occurrences_between(utc(2026, 1, 1, 0), utc(2026, 3, 1, 0)),
+            vec![utc(2026, 2, 3, 14)]
+        );
+
+        // No occurrence starts after `until`.
+        reservation.update_status(utc(2026, 2, 3, 18));
+        assert_eq!(reservation.status, ReservationStatus::Completed);
+    }
 }
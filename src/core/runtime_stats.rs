@@ -0,0 +1,242 @@
+//! Runtimes of finished jobs by user and run-name prefix, served by
+//! `GET /stats/runtimes` and used to estimate jobs without a time limit.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Recent runtimes kept per name for the percentiles.
+pub const RUNTIME_SAMPLES: usize = 100;
+
+/// Names with no finished run for this long are dropped.
+pub const RUNTIME_STATS_RETENTION: Duration = Duration::from_secs(90 * 24 * 3600);
+
+/// What daily reruns share: the run name without trailing numbered segments,
+/// so `train-42` and `nightly_20261017` count as `train` and `nightly`.
+pub fn name_prefix(run_name: &str) -> &str {
+    strip_segments(run_name, |segment| {
+        segment.bytes().all(|b| b.is_ascii_digit())
+    })
+}
+
+fn strip_segments(name: &str, strippable: impl Fn(&str) -> bool) -> &str {
+    let mut prefix = name;
+    while let Some(pos) = prefix.rfind(['-', '_']) {
+        let segment = &prefix[pos + 1..];
+        if pos == 0 || segment.is_empty() || !strippable(segment) {
+            break;
+        }
+        prefix = &prefix[..pos];
+    }
+    prefix
+}
+
+/// Whether `prefix` is selected by `pattern`: a run name (`train-42`), a
+/// prefix (`train`), or a glob over prefixes (`train-*`, `tr*`).
+pub fn prefix_matches(pattern: &str, prefix: &str) -> bool {
+    let pattern = strip_segments(pattern, |segment| {
+        segment == "*" || segment.bytes().all(|b| b.is_ascii_digit())
+    });
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = prefix.strip_prefix(first) else {
+        return false;
+    };
+    let mut parts: Vec<&str> = parts.collect();
+    let Some(last) = parts.pop() else {
+        return rest.is_empty();
+    };
+    for part in parts {
+        match rest.find(part) {
+            Some(pos) => rest = &rest[pos + part.len()..],
+            None => return false,
+        }
+    }
+    rest.ends_with(last)
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+struct RuntimeEntry {
+    count: u64,
+    total_secs: u64,
+    recent_secs: VecDeque<u64>,
+    /// Unix timestamp of the latest finish.
+    last_seen: i64,
+}
+
+impl RuntimeEntry {
+    fn percentile(&self, pct: usize) -> u64 {
+        let mut sorted: Vec<u64> = self.recent_secs.iter().copied().collect();
+        sorted.sort_unstable();
+        // Nearest rank.
+        let rank = (pct * sorted.len()).div_ceil(100).max(1);
+        sorted.get(rank - 1).copied().unwrap_or_default()
+    }
+
+    fn summary(&self, user: &str, name: &str) -> RuntimeSummary {
+        RuntimeSummary {
+            user: user.to_string(),
+            name: name.to_string(),
+            count: self.count,
+            mean_secs: self.total_secs / self.count.max(1),
+            p50_secs: self.percentile(50),
+            p95_secs: self.percentile(95),
+            last_seen: self.last_seen,
+        }
+    }
+}
+
+/// Query parameters of `GET /stats/runtimes`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RuntimeStatsQuery {
+    /// See [`prefix_matches`]; all names without one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RuntimeSummary {
+    pub user: String,
+    /// Run-name prefix, see [`name_prefix`].
+    pub name: String,
+    /// Finished runs ever recorded.
+    pub count: u64,
+    pub mean_secs: u64,
+    /// Percentiles of the last [`RUNTIME_SAMPLES`] runs.
+    pub p50_secs: u64,
+    pub p95_secs: u64,
+    pub last_seen: i64,
+}
+
+/// Per user, per run-name prefix. Kept in the scheduler state, so it outlives
+/// the jobs it was learned from.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct RuntimeStats(BTreeMap<String, BTreeMap<String, RuntimeEntry>>);
+
+fn unix_secs(time: SystemTime) -> i64 {
+    time.duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs() as i64)
+}
+
+impl RuntimeStats {
+    /// Add a successful run and drop names past [`RUNTIME_STATS_RETENTION`].
+    pub fn record(
+        &mut self,
+        user: &str,
+        run_name: &str,
+        runtime: Duration,
+        finished_at: SystemTime,
+    ) {
+        let entry = self
+            .0
+            .entry(user.to_string())
+            .or_default()
+            .entry(name_prefix(run_name).to_string())
+            .or_default();
+        let secs = runtime.as_secs();
+        entry.count += 1;
+        entry.total_secs = entry.total_secs.saturating_add(secs);
+        if entry.recent_secs.len() == RUNTIME_SAMPLES {
+            entry.recent_secs.pop_front();
+        }
+        entry.recent_secs.push_back(secs);
+        entry.last_seen = entry.last_seen.max(unix_secs(finished_at));
+
+        self.prune(finished_at);
+    }
+
+    pub fn prune(&mut self, now: SystemTime) {
+        let cutoff = unix_secs(now) - RUNTIME_STATS_RETENTION.as_secs() as i64;
+        for names in self.0.values_mut() {
+            names.retain(|_, entry| entry.last_seen >= cutoff);
+        }
+        self.0.retain(|_, names| !names.is_empty());
+    }
+
+    /// The 95th-percentile runtime of earlier runs like this one.
+    pub fn p95(&self, user: &str, run_name: &str) -> Option<Duration> {
+        self.0
+            .get(user)?
+            .get(name_prefix(run_name))
+            .map(|entry| Duration::from_secs(entry.percentile(95)))
+    }
+
+    pub fn query(&self, query: &RuntimeStatsQuery) -> Vec<RuntimeSummary> {
+        self.0
+            .iter()
+            .filter(|(user, _)| query.user.as_deref().is_none_or(|u| u == user.as_str()))
+            .flat_map(|(user, names)| {
+                names
+                    .iter()
+                    .filter(|(name, _)| {
+                        query
+                            .name
+                            .as_deref()
+                            .is_none_or(|pattern| prefix_matches(pattern, name))
+                    })
+                    .map(|(name, entry)| entry.summary(user, name))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn numbered_suffixes_share_a_prefix() {
+        assert_eq!(name_prefix("train-42"), "train");
+        assert_eq!(name_prefix("nightly-eval_20261017"), "nightly-eval");
+        assert_eq!(name_prefix("gjob-3-1"), "gjob");
+        assert_eq!(name_prefix("resnet50"), "resnet50");
+        assert_eq!(name_prefix("2026-10-17"), "2026");
+
+        assert!(prefix_matches("train-*", "train"));
+        assert!(prefix_matches("train-7", "train"));
+        assert!(prefix_matches("tr*", "train"));
+        assert!(prefix_matches("*eval", "nightly-eval"));
+        assert!(!prefix_matches("train", "train-big"));
+        assert!(!prefix_matches("eval*", "nightly-eval"));
+    }
+
+    #[test]
+    fn keeps_percentiles_of_recent_runs_and_prunes_stale_names() {
+        let day = Duration::from_secs(24 * 3600);
+        let start = UNIX_EPOCH + 1000 * day;
+        let mut stats = RuntimeStats::default();
+        for minutes in 1..=20 {
+            stats.record(
+                "alice",
+                &format!("train-{minutes}"),
+                Duration::from_secs(minutes * 60),
+                start,
+            );
+        }
+        stats.record("alice", "probe", Duration::from_secs(5), start);
+
+        let query = RuntimeStatsQuery {
+            name: Some("train-*".to_string()),
+            user: None,
+        };
+        let rows = stats.query(&query);
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].count, 20);
+        assert_eq!(rows[0].p50_secs, 10 * 60);
+        assert_eq!(rows[0].p95_secs, 19 * 60);
+        assert_eq!(
+            stats.p95("alice", "train-99"),
+            Some(Duration::from_secs(19 * 60))
+        );
+        assert_eq!(stats.p95("bob", "train-99"), None);
+
+        // Only `probe` runs again, 91 days later.
+        stats.record("alice", "probe", Duration::from_secs(5), start + 91 * day);
+        assert!(stats.query(&query).is_empty());
+        assert_eq!(stats.query(&RuntimeStatsQuery::default()).len(), 1);
+    }
+}
@@ -3,31 +3,68 @@ use crate::core::gpu::{GPUSlot, GpuUuid};
 use crate::core::gpu_allocation::GpuAllocationStrategy;
 use crate::core::info::{GpuInfo, SchedulerInfo};
 use crate::core::job::{
-    DependencyMode, GpuIds, GpuSharingMode, Job, JobRuntime, JobSpec, JobState, JobStateReason,
-    JobView,
+    DependencyMode, GpuIds, GpuSharingMode, InternedStr, Job, JobArtifact, JobRuntime, JobSpec,
+    JobState, JobStateReason, JobView, StringPool, Tags,
 };
+use crate::core::node::{Node, NodeRegistration, NodeStatus};
 use crate::core::reservation::{GpuReservation, ReservationStatus};
+use crate::core::runtime_stats::RuntimeStats;
+use crate::utils::constraint::Labels;
 use compact_str::{format_compact, CompactString};
 use serde::{Deserialize, Deserializer, Serialize};
 use std::cmp::Ordering;
-use std::collections::{BinaryHeap, HashMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, BinaryHeap, HashMap, HashSet};
 use std::path::PathBuf;
 use std::time::Duration;
 
 #[path = "scheduler/access.rs"]
 mod access;
+#[path = "scheduler/artifacts.rs"]
+mod artifacts;
 #[path = "scheduler/builder.rs"]
 mod builder;
+#[path = "scheduler/capacity.rs"]
+mod capacity;
+#[path = "scheduler/cpus.rs"]
+mod cpus;
+#[path = "scheduler/disk.rs"]
+mod disk;
+#[path = "scheduler/estimation.rs"]
+mod estimation;
+#[path = "scheduler/gpu_claims.rs"]
+mod gpu_claims;
+#[path = "scheduler/groups.rs"]
+mod groups;
+#[path = "scheduler/indexes.rs"]
+mod indexes;
+#[path = "scheduler/labels.rs"]
+mod labels;
+#[path = "scheduler/nodes.rs"]
+mod nodes;
 #[path = "scheduler/persistence.rs"]
 mod persistence;
+#[path = "scheduler/preemption.rs"]
+mod preemption;
 #[path = "scheduler/reservations.rs"]
 mod reservations;
 #[path = "scheduler/scheduling.rs"]
 mod scheduling;
+#[path = "scheduler/submissions.rs"]
+mod submissions;
+#[path = "scheduler/tags.rs"]
+mod tags;
 #[path = "scheduler/transitions.rs"]
 mod transitions;
 
 pub use builder::SchedulerBuilder;
+use disk::DiskGuard;
+pub use estimation::{QueueSummary, StartEstimate, ESTIMATE_HORIZON};
+pub use gpu_claims::GpuClaim;
+pub use labels::LabelTarget;
+pub use preemption::Preemption;
+pub use scheduling::SchedulingPass;
+pub use submissions::{submission_digest, PriorSubmission, SubmissionRecord, SUBMISSION_ID_TTL};
+pub use transitions::StateTransition;
 
 #[derive(Debug, Clone, Default)]
 pub(crate) struct DependencyRuntime {
@@ -87,10 +124,16 @@ pub struct Scheduler {
     pub(crate) executor: Option<Box<dyn Executor>>,
     #[serde(skip)]
     pub(crate) gpu_slots: HashMap<GpuUuid, GPUSlot>,
+    /// GPUs held by local jobs, by job id, from assignment until they stop running
+    #[serde(skip)]
+    pub(crate) gpu_claims: BTreeMap<u32, GpuClaim>,
     #[serde(skip)]
     pub(crate) total_memory_mb: u64,
     #[serde(skip)]
     pub(crate) available_memory_mb: u64,
+    /// Logical CPUs of this machine (0 = unknown, jobs are not pinned)
+    #[serde(skip)]
+    pub(crate) total_cpus: u32,
     pub(crate) state_path: PathBuf,
     pub(crate) next_job_id: u32,
     /// GPU indices that scheduler is allowed to use (None = all GPUs)
@@ -104,15 +147,20 @@ pub struct Scheduler {
     /// Index of job IDs by username for fast dependency resolution
     /// Maps username -> sorted list of job IDs (ascending order)
     #[serde(skip)]
-    pub(crate) user_jobs_index: HashMap<CompactString, Vec<u32>>,
+    pub(crate) user_jobs_index: HashMap<InternedStr, Vec<u32>>,
     /// Index of job IDs by state for faster state filtering.
-    /// Maps state -> sorted list of job IDs (ascending order)
+    /// Maps state -> set of job IDs; a set, since jobs leave their state from
+    /// anywhere in a long queue.
     #[serde(skip)]
-    pub(crate) state_jobs_index: HashMap<JobState, Vec<u32>>,
+    pub(crate) state_jobs_index: HashMap<JobState, BTreeSet<u32>>,
     /// Index of job IDs by project for fast project filtering.
     /// Maps project -> sorted list of job IDs (ascending order)
     #[serde(skip)]
     pub(crate) project_jobs_index: HashMap<CompactString, Vec<u32>>,
+    /// Index of job IDs by tag for fast tag filtering.
+    /// Maps tag -> sorted list of job IDs (ascending order)
+    #[serde(skip)]
+    pub(crate) tag_jobs_index: HashMap<CompactString, Vec<u32>>,
     /// Reverse dependency graph for fast dependent lookup
     /// Maps dependency job ID -> sorted list of dependent job IDs
     #[serde(skip)]
@@ -127,15 +175,58 @@ pub struct Scheduler {
     /// Maps group_id -> count of running jobs in that group
     #[serde(skip)]
     pub(crate) group_running_count: HashMap<uuid::Uuid, usize>,
+    /// Index of job IDs by group_id for listing groups without scanning all jobs.
+    /// Maps group_id -> sorted list of job IDs (ascending order)
+    #[serde(skip)]
+    pub(crate) group_jobs_index: HashMap<uuid::Uuid, Vec<u32>>,
+    /// One shared copy of each user name and conda environment across jobs.
+    #[serde(skip)]
+    pub(crate) string_pool: StringPool,
+    /// Counts changes to what the API shows of jobs and GPUs, see [`Scheduler::revision`].
+    #[serde(skip)]
+    pub(crate) revision: u64,
     /// GPU reservations
     pub reservations: Vec<GpuReservation>,
     /// Next reservation ID
     pub next_reservation_id: u32,
+    /// Runtimes learned from finished jobs
+    #[serde(default)]
+    pub(crate) runtime_stats: RuntimeStats,
+    /// Remote nodes that joined with `gflowd agent`, by name
+    #[serde(default)]
+    pub(crate) nodes: BTreeMap<String, Node>,
+    /// Recent client-chosen submission IDs, to deduplicate resent jobs
+    #[serde(default)]
+    pub(crate) submissions: HashMap<uuid::Uuid, SubmissionRecord>,
+    /// Outputs jobs registered, by job id (`gjob artifacts`)
+    #[serde(default)]
+    pub(crate) artifacts: BTreeMap<u32, Vec<JobArtifact>>,
+    /// Labels of this machine, shared by all its GPUs (`daemon.labels`)
+    #[serde(skip)]
+    pub(crate) labels: Labels,
+    /// Extra labels of single GPUs, by index (`daemon.gpu_labels`)
+    #[serde(skip)]
+    pub(crate) gpu_labels: BTreeMap<u32, Labels>,
+    /// State changes not yet taken by [`Scheduler::take_transitions`]
+    #[serde(skip)]
+    pub(crate) transitions: Vec<StateTransition>,
+    /// Free-space check before jobs start (`--min-free-disk`)
+    #[serde(skip)]
+    pub(crate) disk_guard: DiskGuard,
+    /// Longest a scheduling pass may spend on queued jobs (`scheduler.pass_budget_ms`)
+    #[serde(skip)]
+    pub(crate) pass_budget: Option<Duration>,
+    /// Queued jobs the last pass ran out of time for; the next one starts with them
+    #[serde(skip)]
+    pub(crate) pass_backlog: Vec<u32>,
+    #[serde(skip)]
+    pub(crate) last_pass: SchedulingPass,
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::core::info::Capacity;
     use crate::core::job::JobBuilder;
     use serde::Serialize;
     use std::collections::HashMap;
@@ -264,6 +355,26 @@ mod tests {
         assert_eq!(scheduler.get_job(1).unwrap().state, JobState::Queued);
     }
 
+    #[test]
+    fn test_release_restarts_the_queue_wait() {
+        let mut scheduler = create_test_scheduler();
+        let (job_id, _) = scheduler.submit_job(create_test_job("held"));
+        let submitted = scheduler.get_job(job_id).unwrap().submitted_at;
+        assert_eq!(scheduler.get_job(job_id).unwrap().queued_at, submitted);
+
+        let long_ago = std::time::SystemTime::UNIX_EPOCH;
+        scheduler.get_job_runtime_mut(job_id).unwrap().queued_at = Some(long_ago);
+        assert!(scheduler.hold_job(job_id));
+        assert_eq!(scheduler.get_job(job_id).unwrap().queued_at, Some(long_ago));
+        assert!(scheduler.release_job(job_id));
+
+        let job = scheduler.get_job(job_id).unwrap();
+        assert!(job.queued_at.unwrap() >= submitted.unwrap());
+        assert!(
+            job.wait_time_as_of(std::time::SystemTime::now()).unwrap() < Duration::from_secs(60)
+        );
+    }
+
     #[test]
     fn test_submit_job_sets_waiting_for_dependency_reason() {
         let mut scheduler = create_test_scheduler();
@@ -282,7 +393,7 @@ mod tests {
             scheduler
                 .get_job(child_id)
                 .and_then(|j| j.reason.map(|r| *r)),
-            Some(JobStateReason::WaitingForDependency)
+            Some(JobStateReason::Dependency { unfinished: 1 })
         );
     }
     #[test]
@@ -333,7 +444,7 @@ mod tests {
             scheduler
                 .get_job(job_c_id)
                 .and_then(|j| j.reason.map(|r| *r)),
-            Some(JobStateReason::WaitingForDependency)
+            Some(JobStateReason::Dependency { unfinished: 1 })
         );
     }
 
@@ -524,7 +635,7 @@ mod tests {
             scheduler
                 .get_job(exclusive_job_id)
                 .and_then(|j| j.reason.map(|r| *r)),
-            Some(JobStateReason::WaitingForGpu)
+            Some(JobStateReason::WaitingForGpus { need: 1, free: 0 })
         );
 
         scheduler.finish_job(shared_job_id);
@@ -534,6 +645,64 @@ mod tests {
         assert_eq!(scheduler.get_job(exclusive_job_id).unwrap().reason, None);
     }
 
+    #[test]
+    fn test_gpu_claims_hold_gpus_until_the_job_stops_running() {
+        let mut scheduler = create_test_scheduler();
+        scheduler.gpu_slots.insert(
+            "GPU-0".to_string(),
+            GPUSlot {
+                index: 0,
+                available: true,
+                total_memory_mb: None,
+                reason: None,
+            },
+        );
+        let gpu_job = || {
+            JobBuilder::new()
+                .submitted_by("alice")
+                .run_dir("/tmp")
+                .gpus(1)
+                .build()
+        };
+        let (first, _) = scheduler.submit_job(gpu_job());
+        let (second, _) = scheduler.submit_job(gpu_job());
+
+        assert_eq!(scheduler.prepare_jobs_for_execution().len(), 1);
+        let claim = scheduler.gpu_claim(first).unwrap();
+        assert_eq!(claim.gpus.as_slice(), &[0]);
+        assert!(!claim.confirmed);
+
+        // Losing the GPU ids does not free the GPU: the claim still holds it.
+        scheduler.get_job_runtime_mut(first).unwrap().gpu_ids = None;
+        assert!(scheduler.prepare_jobs_for_execution().is_empty());
+
+        // Not launched yet, so it can go back to the queue.
+        assert_eq!(scheduler.requeue_unlaunched_job(first), Ok(true));
+        assert!(scheduler.gpu_claim(first).is_none());
+        let first_job = scheduler.get_job(first).unwrap();
+        assert_eq!(first_job.state, JobState::Queued);
+        assert_eq!(
+            first_job.reason.map(|r| *r),
+            Some(JobStateReason::WaitingForGpu)
+        );
+
+        assert_eq!(scheduler.prepare_jobs_for_execution().len(), 1);
+        assert!(scheduler.confirm_gpu_claim(first));
+        assert!(scheduler
+            .requeue_unlaunched_job(first)
+            .is_err_and(|e| matches!(
+                e.kind,
+                crate::core::api_error::ApiErrorKind::InvalidTransition { .. }
+            )));
+        assert!(scheduler.prepare_jobs_for_execution().is_empty());
+
+        assert!(scheduler.fail_job(first));
+        assert!(scheduler.gpu_claim(first).is_none());
+        let prepared = scheduler.prepare_jobs_for_execution();
+        assert_eq!(prepared.len(), 1);
+        assert_eq!(prepared[0].id, second);
+    }
+
     #[test]
     fn test_shared_job_can_still_schedule_after_one_shared_job_finishes() {
         let mut scheduler = create_test_scheduler();
@@ -640,6 +809,66 @@ mod tests {
         assert_eq!(info.gpu_allocation_strategy, GpuAllocationStrategy::Random);
     }
 
+    #[test]
+    fn test_pending_reasons_follow_dependencies_and_group_limits() {
+        let mut scheduler = create_test_scheduler();
+        let reason = |scheduler: &Scheduler, id| {
+            scheduler
+                .get_job_runtime(id)
+                .and_then(|rt| rt.reason.as_deref().cloned())
+        };
+
+        let parents: Vec<u32> = (0..2)
+            .map(|_| scheduler.submit_job(create_test_job("test")).0)
+            .collect();
+        let child = JobBuilder::new()
+            .submitted_by("test")
+            .run_dir("/tmp")
+            .depends_on_ids(parents.clone())
+            .build();
+        let (child_id, _) = scheduler.submit_job(child);
+        assert_eq!(
+            reason(&scheduler, child_id),
+            Some(JobStateReason::Dependency { unfinished: 2 })
+        );
+        for (i, &parent) in parents.iter().enumerate() {
+            scheduler.transition_job_state(parent, JobState::Running, None);
+            scheduler.finish_job(parent).unwrap();
+            let unfinished = 1 - i as u32;
+            let expected = (unfinished > 0).then_some(JobStateReason::Dependency { unfinished });
+            assert_eq!(reason(&scheduler, child_id), expected);
+        }
+
+        let group_id = Uuid::new_v4();
+        let grouped: Vec<u32> = (0..2)
+            .map(|_| {
+                let job = JobBuilder::new()
+                    .submitted_by("test")
+                    .run_dir("/tmp")
+                    .group_id_uuid(Some(group_id))
+                    .max_concurrent(Some(1))
+                    .build();
+                scheduler.submit_job(job).0
+            })
+            .collect();
+        let started: Vec<u32> = scheduler
+            .prepare_jobs_for_execution()
+            .iter()
+            .map(|job| job.id)
+            .collect();
+        assert_eq!(started, [child_id, grouped[0]]);
+        assert_eq!(reason(&scheduler, child_id), None);
+        assert_eq!(
+            reason(&scheduler, grouped[1]),
+            Some(JobStateReason::GroupLimit { running: 1, max: 1 })
+        );
+
+        // Cleared once the limit no longer holds the job back.
+        scheduler.finish_job(grouped[0]).unwrap();
+        assert_eq!(scheduler.prepare_jobs_for_execution().len(), 1);
+        assert_eq!(reason(&scheduler, grouped[1]), None);
+    }
+
     #[test]
     fn test_group_running_count_updates_on_run_and_finish() {
         let mut scheduler = create_test_scheduler();
@@ -670,6 +899,32 @@ mod tests {
             .is_some_and(|v| v.contains(&job_id)));
     }
 
+    #[test]
+    fn test_group_index_survives_rebuild() {
+        let mut scheduler = create_test_scheduler();
+        let (first, second) = (Uuid::new_v4(), Uuid::new_v4());
+
+        for group_id in [Some(second), None, Some(first), Some(second)] {
+            let job = JobBuilder::new()
+                .submitted_by("test")
+                .run_dir("/tmp")
+                .group_id_uuid(group_id)
+                .build();
+            scheduler.submit_job(job);
+        }
+        scheduler.rebuild_user_jobs_index();
+
+        assert_eq!(scheduler.job_ids_by_group(&second), Some(&[1, 4][..]));
+        assert_eq!(scheduler.job_ids_by_group(&Uuid::new_v4()), None);
+        let groups = scheduler.job_groups();
+        assert_eq!(
+            groups.iter().map(|group| group.id).collect::<Vec<_>>(),
+            [second, first]
+        );
+        assert_eq!(groups[0].total(), 2);
+        assert_eq!(scheduler.job_group(&first).unwrap().owner, "test");
+    }
+
     #[test]
     fn test_resolve_dependency_most_recent() {
         let mut scheduler = create_test_scheduler();
@@ -960,7 +1215,7 @@ mod tests {
 
         assert_eq!(
             scheduler.state_jobs_index.get(&JobState::Queued).unwrap(),
-            &vec![job_id]
+            &BTreeSet::from([job_id])
         );
 
         assert!(scheduler
@@ -994,6 +1249,260 @@ mod tests {
             .is_some_and(|v| v.contains(&job_id)));
     }
 
+    #[test]
+    fn test_submitted_jobs_share_user_and_conda_env_strings() {
+        let mut scheduler = create_test_scheduler();
+        let job = || {
+            JobBuilder::new()
+                .submitted_by("alice")
+                .conda_env(Some("torch".to_string()))
+                .build()
+        };
+        let (first, _) = scheduler.submit_job(job());
+        let (second, _) = scheduler.submit_job(job());
+        let first = scheduler.get_job_spec(first).unwrap();
+        let second = scheduler.get_job_spec(second).unwrap();
+        assert_eq!(first.submitted_by.as_ptr(), second.submitted_by.as_ptr());
+        let conda_env = |spec: &JobSpec| spec.conda_env.as_ref().unwrap().as_ptr();
+        assert_eq!(conda_env(first), conda_env(second));
+        assert_eq!(
+            first.environment.conda_name().unwrap().as_ptr(),
+            conda_env(second)
+        );
+        assert_eq!(scheduler.string_pool.len(), 2);
+    }
+
+    #[test]
+    fn test_revision_moves_only_when_something_visible_changes() {
+        let mut scheduler = create_test_scheduler();
+        let start = scheduler.revision();
+        let (job_id, _) = scheduler.submit_job(JobBuilder::new().gpus(64).build());
+        let submitted = scheduler.revision();
+        assert!(submitted > start);
+
+        // The job cannot start; the first pass records why, later ones change nothing.
+        scheduler.prepare_jobs_for_execution();
+        let waiting = scheduler.revision();
+        scheduler.prepare_jobs_for_execution();
+        scheduler.update_gpu_slots(scheduler.gpu_slots.clone());
+        assert_eq!(scheduler.revision(), waiting);
+
+        assert!(scheduler.hold_job(job_id));
+        assert!(scheduler.revision() > waiting);
+    }
+
+    #[test]
+    fn test_indexes_stay_consistent_through_a_mixed_workload() {
+        let mut scheduler = create_test_scheduler();
+        let groups = [Uuid::new_v4(), Uuid::new_v4()];
+        for i in 0..40u32 {
+            let job = JobBuilder::new()
+                .submitted_by(format!("user{}", i % 3))
+                .run_dir("/tmp")
+                .project((i % 4 == 0).then(|| "vision".to_string()))
+                .group_id_uuid((i % 5 != 0).then_some(groups[i as usize % 2]))
+                .tags(Tags::new(["exp42"].iter().take(i as usize % 2)).unwrap())
+                .build();
+            let (job_id, _) = scheduler.submit_job(job);
+            if i % 3 == 0 {
+                let add = ["baseline".to_string()];
+                let remove = ["exp42".to_string()];
+                scheduler.update_job_tags(job_id, &add, &remove).unwrap();
+            }
+            // Submissions, starts, finishes, holds and cancellations interleaved;
+            // every transition checks the indexes against a rebuild.
+            match i % 4 {
+                0 => {
+                    scheduler.transition_job_state(job_id, JobState::Running, None);
+                }
+                1 => {
+                    scheduler.transition_job_state(job_id - 1, JobState::Finished, None);
+                }
+                2 => {
+                    scheduler.hold_job(job_id);
+                }
+                _ => {
+                    scheduler.cancel_job(job_id - 1, None);
+                    scheduler.release_job(job_id - 1);
+                }
+            }
+        }
+        scheduler.assert_job_indexes_consistent();
+        assert_eq!(
+            scheduler
+                .job_ids_by_state(JobState::Finished)
+                .unwrap()
+                .len(),
+            10
+        );
+        assert_eq!(
+            scheduler
+                .job_ids_by_state(JobState::Cancelled)
+                .unwrap()
+                .len(),
+            10
+        );
+        assert_eq!(scheduler.job_ids_by_tag("exp42").unwrap().len(), 13);
+        assert_eq!(scheduler.job_ids_by_tag("baseline").unwrap().len(), 14);
+    }
+
+    #[test]
+    fn test_try_transition_rejects_invalid_moves_and_records_applied_ones() {
+        let mut scheduler = create_test_scheduler();
+        let (job_id, _) = scheduler.submit_job(create_test_job("test"));
+
+        let error = scheduler
+            .try_transition(job_id, JobState::Finished, None)
+            .unwrap_err();
+        assert_eq!(
+            error.kind,
+            crate::core::api_error::ApiErrorKind::InvalidTransition {
+                id: job_id,
+                from: JobState::Queued,
+                to: JobState::Finished,
+                allowed: vec![JobState::Hold, JobState::Running, JobState::Cancelled],
+            }
+        );
+        assert_eq!(scheduler.get_job(job_id).unwrap().state, JobState::Queued);
+        assert!(scheduler.take_transitions().is_empty());
+
+        assert_eq!(
+            scheduler.try_transition(job_id, JobState::Hold, None),
+            Ok(true)
+        );
+        assert_eq!(
+            scheduler.try_transition(job_id, JobState::Hold, None),
+            Ok(false)
+        );
+        assert!(scheduler.release_job(job_id));
+        assert!(!scheduler.release_job(job_id));
+        assert!(scheduler.cancel_job(job_id, None).is_some());
+        assert!(!scheduler.fail_job(job_id));
+
+        let transitions: Vec<_> = scheduler
+            .take_transitions()
+            .into_iter()
+            .map(|t| (t.job_id, t.from, t.to))
+            .collect();
+        assert_eq!(
+            transitions,
+            vec![
+                (job_id, JobState::Queued, JobState::Hold),
+                (job_id, JobState::Hold, JobState::Queued),
+                (job_id, JobState::Queued, JobState::Cancelled),
+            ]
+        );
+        assert!(scheduler.take_transitions().is_empty());
+    }
+
+    #[test]
+    fn test_every_way_out_of_running_returns_memory() {
+        type End = fn(&mut Scheduler, u32) -> bool;
+        let ends: [(&str, End); 4] = [
+            ("finish", |s, id| s.finish_job(id).is_some()),
+            ("fail", |s, id| s.fail_job(id)),
+            ("cancel", |s, id| s.cancel_job(id, None).is_some()),
+            ("timeout", |s, id| s.timeout_job(id)),
+        ];
+
+        let mut scheduler = create_test_scheduler();
+        let total = scheduler.total_memory_mb();
+        for (name, end) in ends {
+            let job = JobBuilder::new()
+                .submitted_by("alice")
+                .run_dir("/tmp")
+                .memory_limit_mb(4096)
+                .build();
+            let (job_id, _) = scheduler.submit_job(job);
+            assert_eq!(scheduler.prepare_jobs_for_execution().len(), 1);
+            assert_eq!(scheduler.available_memory_mb(), total - 4096, "{name}");
+            assert_eq!(scheduler.used_memory_mb(), 4096, "{name}");
+
+            assert!(end(&mut scheduler, job_id), "{name}");
+            assert_eq!(scheduler.available_memory_mb(), total, "{name}");
+            assert_eq!(scheduler.used_memory_mb(), 0, "{name}");
+        }
+        assert_eq!(scheduler.reconcile_memory(), 0);
+    }
+
+    #[test]
+    fn test_reconcile_memory_corrects_drift() {
+        let mut scheduler = create_test_scheduler();
+        let job = JobBuilder::new()
+            .submitted_by("alice")
+            .run_dir("/tmp")
+            .memory_limit_mb(1024)
+            .build();
+        scheduler.submit_job(job);
+        scheduler.prepare_jobs_for_execution();
+        let expected = scheduler.total_memory_mb() - 1024;
+        assert_eq!(scheduler.available_memory_mb(), expected);
+
+        scheduler.available_memory_mb = 0;
+        assert_eq!(scheduler.reconcile_memory(), expected as i64);
+        assert_eq!(scheduler.available_memory_mb(), expected);
+        assert_eq!(scheduler.reconcile_memory(), 0);
+    }
+
+    #[test]
+    fn test_cpu_jobs_are_pinned_to_free_cores_until_they_stop_running() {
+        let mut scheduler = create_test_scheduler();
+        scheduler.update_cpus(8);
+        let submit = |scheduler: &mut Scheduler, cpus: Option<u32>| {
+            let job = JobBuilder::new()
+                .submitted_by("alice")
+                .run_dir("/tmp")
+                .cpus(cpus)
+                .build();
+            scheduler.submit_job(job).0
+        };
+        let cpu_ids = |scheduler: &Scheduler, id: u32| {
+            scheduler
+                .get_job_runtime(id)
+                .and_then(|rt| rt.cpu_ids.clone())
+                .map(|ids| ids.to_vec())
+        };
+
+        let a = submit(&mut scheduler, Some(2));
+        let b = submit(&mut scheduler, Some(3));
+        let c = submit(&mut scheduler, Some(4));
+        let unpinned = submit(&mut scheduler, None);
+        assert_eq!(scheduler.prepare_jobs_for_execution().len(), 3);
+        assert_eq!(cpu_ids(&scheduler, a), Some(vec![0, 1]));
+        assert_eq!(cpu_ids(&scheduler, b), Some(vec![2, 3, 4]));
+        assert_eq!(cpu_ids(&scheduler, unpinned), None);
+        let waiting = scheduler.get_job_runtime(c).unwrap();
+        assert_eq!(waiting.state, JobState::Queued);
+        assert_eq!(
+            waiting.reason.as_deref(),
+            Some(&JobStateReason::WaitingForCpu)
+        );
+        assert_eq!(scheduler.used_cpus(), 5);
+
+        // No four free cores are contiguous now, so the lowest ones are taken.
+        assert!(scheduler.finish_job(a).is_some());
+        assert_eq!(scheduler.prepare_jobs_for_execution().len(), 1);
+        assert_eq!(cpu_ids(&scheduler, c), Some(vec![0, 1, 5, 6]));
+
+        assert!(scheduler.fail_job(b));
+        assert!(scheduler.cancel_job(c, None).is_some());
+        assert!(scheduler.finish_job(unpinned).is_some());
+        assert_eq!(scheduler.used_cpus(), 0);
+    }
+
+    #[test]
+    fn test_cpu_jobs_run_unpinned_when_the_core_count_is_unknown() {
+        let mut scheduler = create_test_scheduler();
+        let job = JobBuilder::new()
+            .submitted_by("alice")
+            .run_dir("/tmp")
+            .cpus(4)
+            .build();
+        let (job_id, _) = scheduler.submit_job(job);
+        assert_eq!(scheduler.prepare_jobs_for_execution().len(), 1);
+        assert_eq!(scheduler.get_job_runtime(job_id).unwrap().cpu_ids, None);
+    }
+
     #[test]
     #[allow(deprecated)]
     fn test_schedule_jobs_without_executor_does_not_mutate_state() {
@@ -1294,6 +1803,79 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_legacy_depends_on_counts_with_depends_on_ids() {
+        let mut scheduler = create_test_scheduler();
+
+        let (job_a_id, _) = scheduler.submit_job(create_test_job("test"));
+        let (job_b_id, _) = scheduler.submit_job(create_test_job("test"));
+
+        // All mode waits for the legacy dependency as well as the list.
+        let job_c = JobBuilder::new()
+            .submitted_by("test")
+            .run_dir("/tmp")
+            .depends_on(Some(job_a_id))
+            .depends_on_ids(vec![job_b_id])
+            .build();
+        let (job_c_id, _) = scheduler.submit_job(job_c);
+        assert_eq!(
+            scheduler.dependency_runtime(job_c_id).map(|rt| rt.total),
+            Some(2)
+        );
+
+        scheduler.transition_job_state(job_b_id, JobState::Running, None);
+        scheduler.finish_job(job_b_id);
+        assert!(
+            !scheduler
+                .dependency_runtime(job_c_id)
+                .unwrap()
+                .deps_satisfied
+        );
+
+        scheduler.transition_job_state(job_a_id, JobState::Running, None);
+        scheduler.finish_job(job_a_id);
+        assert!(
+            scheduler
+                .dependency_runtime(job_c_id)
+                .unwrap()
+                .deps_satisfied
+        );
+    }
+
+    #[test]
+    fn test_any_mode_runs_once_one_dependency_finishes_after_another_failed() {
+        let mut scheduler = create_test_scheduler();
+
+        let (job_a_id, _) = scheduler.submit_job(create_test_job("test"));
+        let (job_b_id, _) = scheduler.submit_job(create_test_job("test"));
+        let (job_c_id, _) = scheduler.submit_job(create_test_job("test"));
+
+        let job_d = JobBuilder::new()
+            .submitted_by("test")
+            .run_dir("/tmp")
+            .depends_on_ids(vec![job_a_id, job_b_id, job_c_id])
+            .dependency_mode(Some(DependencyMode::Any))
+            .auto_cancel_on_dependency_failure(true)
+            .build();
+        let (job_d_id, _) = scheduler.submit_job(job_d);
+
+        scheduler.transition_job_state(job_a_id, JobState::Running, None);
+        scheduler.fail_job(job_a_id);
+        scheduler.transition_job_state(job_b_id, JobState::Cancelled, None);
+        assert_eq!(scheduler.get_job(job_d_id).unwrap().state, JobState::Queued);
+        assert!(
+            !scheduler
+                .dependency_runtime(job_d_id)
+                .unwrap()
+                .deps_satisfied
+        );
+
+        scheduler.transition_job_state(job_c_id, JobState::Running, None);
+        scheduler.finish_job(job_c_id);
+        let prepared = scheduler.prepare_jobs_for_execution();
+        assert!(prepared.iter().any(|job| job.id == job_d_id));
+    }
+
     #[test]
     fn test_create_reservation_with_indices() {
         use crate::core::reservation::GpuSpec;
@@ -1470,7 +2052,867 @@ mod tests {
             .contains("GPU index 3 is out of range"));
     }
 
-    // Property-based tests for GPU allocation invariants
+    #[test]
+    fn test_job_blocked_by_reservation_names_its_owner() {
+        use crate::core::reservation::GpuSpec;
+        let mut scheduler = scheduler_with_one_gpu();
+
+        let reservation_id = scheduler
+            .create_reservation(
+                "bob".into(),
+                GpuSpec::Count(1),
+                std::time::SystemTime::now() + std::time::Duration::from_secs(60),
+                std::time::Duration::from_secs(3600),
+            )
+            .unwrap();
+        // Move the reservation into its window.
+        scheduler
+            .get_reservation_mut(reservation_id)
+            .unwrap()
+            .start_time = std::time::SystemTime::now() - std::time::Duration::from_secs(1);
+
+        let job_id = submit_gpu_job(&mut scheduler, 1, None);
+        assert!(scheduler.prepare_jobs_for_execution().is_empty());
+        assert_eq!(
+            scheduler.get_job(job_id).and_then(|j| j.reason.map(|r| *r)),
+            Some(JobStateReason::ReservedForUser("bob".into()))
+        );
+    }
+
+    #[test]
+    fn test_maintenance_window_holds_jobs_that_would_overlap_it() {
+        use crate::core::reservation::{GpuSpec, ReservationKind, ReservationOptions};
+        let mut scheduler = scheduler_with_one_gpu();
+        let maintenance = ReservationOptions {
+            kind: ReservationKind::Maintenance,
+            recurrence: None,
+        };
+        let reservation_id = scheduler
+            .create_reservation_with(
+                "admin".into(),
+                GpuSpec::Count(1),
+                std::time::SystemTime::now() + Duration::from_secs(3600),
+                Duration::from_secs(3600),
+                maintenance,
+            )
+            .unwrap();
+
+        let long_job = submit_gpu_job(&mut scheduler, 1, Some(2 * 3600));
+        let prepared = scheduler.prepare_jobs_for_execution();
+        assert!(prepared.is_empty());
+        assert_eq!(
+            scheduler
+                .get_job(long_job)
+                .and_then(|j| j.reason.map(|r| *r)),
+            Some(JobStateReason::Maintenance)
+        );
+
+        // Done well before the window opens.
+        scheduler.cancel_job(long_job, None);
+        let short_job = submit_gpu_job(&mut scheduler, 1, Some(600));
+        let prepared = scheduler.prepare_jobs_for_execution();
+        assert_eq!(prepared.len(), 1);
+        assert_eq!(prepared[0].id, short_job);
+        scheduler.finish_job(short_job);
+
+        // Once the window is open nothing starts, limit or not.
+        scheduler
+            .get_reservation_mut(reservation_id)
+            .unwrap()
+            .start_time = std::time::SystemTime::now() - Duration::from_secs(1);
+        let unlimited_job = submit_gpu_job(&mut scheduler, 0, None);
+        assert!(scheduler.prepare_jobs_for_execution().is_empty());
+        assert_eq!(
+            scheduler
+                .get_job(unlimited_job)
+                .and_then(|j| j.reason.map(|r| *r)),
+            Some(JobStateReason::Maintenance)
+        );
+    }
+
+    #[test]
+    fn test_recurring_reservation_conflicts_with_later_occurrence() {
+        use crate::core::reservation::{GpuSpec, Recurrence, ReservationOptions};
+        use chrono::{TimeZone, Weekday};
+        let mut scheduler = scheduler_with_one_gpu();
+        let weekly_tuesday = Recurrence {
+            weekdays: vec![Weekday::Tue],
+            until: None,
+        };
+        // 2026-01-27 is a Tuesday; this is the next Tuesday 14:00 UTC.
+        let tuesday: std::time::SystemTime = chrono::Utc
+            .with_ymd_and_hms(2026, 1, 27, 14, 0, 0)
+            .unwrap()
+            .into();
+        let next_tuesday = weekly_tuesday
+            .next_start(
+                tuesday,
+                std::time::SystemTime::now() + Duration::from_secs(60),
+            )
+            .unwrap();
+
+        scheduler
+            .create_reservation_with(
+                "class".into(),
+                GpuSpec::Count(1),
+                next_tuesday,
+                Duration::from_secs(4 * 3600),
+                ReservationOptions {
+                    recurrence: Some(weekly_tuesday.clone()),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+
+        // Two weeks on, inside the class's slot.
+        let later = next_tuesday + Duration::from_secs(14 * 24 * 3600 + 3600);
+        let err = scheduler
+            .create_reservation(
+                "alice".into(),
+                GpuSpec::Count(1),
+                later,
+                Duration::from_secs(3600),
+            )
+            .unwrap_err();
+        assert!(err
+            .downcast_ref::<crate::core::conflict::ConflictError>()
+            .is_some());
+
+        // The other way round: a recurrence hitting an existing one-off.
+        let mut scheduler = scheduler_with_one_gpu();
+        scheduler
+            .create_reservation(
+                "alice".into(),
+                GpuSpec::Count(1),
+                later,
+                Duration::from_secs(3600),
+            )
+            .unwrap();
+        assert!(scheduler
+            .create_reservation_with(
+                "class".into(),
+                GpuSpec::Count(1),
+                next_tuesday,
+                Duration::from_secs(4 * 3600),
+                ReservationOptions {
+                    recurrence: Some(weekly_tuesday),
+                    ..Default::default()
+                },
+            )
+            .is_err());
+    }
+
+    fn scheduler_with_one_gpu() -> Scheduler {
+        let mut scheduler = create_test_scheduler();
+        scheduler.gpu_slots.insert(
+            "GPU-0".to_string(),
+            GPUSlot {
+                index: 0,
+                available: true,
+                total_memory_mb: None,
+                reason: None,
+            },
+        );
+        scheduler
+    }
+
+    fn submit_gpu_job(scheduler: &mut Scheduler, gpus: u32, time_limit: Option<u64>) -> u32 {
+        let mut builder = JobBuilder::new()
+            .submitted_by("alice")
+            .run_dir("/tmp")
+            .gpus(gpus);
+        if let Some(secs) = time_limit {
+            builder = builder.time_limit(Some(Duration::from_secs(secs)));
+        }
+        scheduler.submit_job(builder.build()).0
+    }
+
+    #[test]
+    fn test_pass_budget_yields_and_resumes_where_it_stopped() {
+        let mut scheduler = scheduler_with_one_gpu();
+        let jobs: Vec<u32> = (0..4)
+            .map(|_| submit_gpu_job(&mut scheduler, 1, None))
+            .collect();
+        let reason = |scheduler: &Scheduler, id: u32| {
+            scheduler
+                .get_job_runtime(id)
+                .unwrap()
+                .reason
+                .as_deref()
+                .cloned()
+        };
+
+        // Out of time after every job: each pass looks at exactly one.
+        scheduler.set_pass_budget(Some(Duration::ZERO));
+        assert_eq!(scheduler.prepare_jobs_for_execution().len(), 1);
+        assert_eq!(
+            *scheduler.last_pass(),
+            SchedulingPass {
+                duration: scheduler.last_pass().duration,
+                examined: 1,
+                started: 1,
+                yielded: true,
+            }
+        );
+        assert_eq!(reason(&scheduler, jobs[1]), None);
+
+        // Later passes go on with the jobs the earlier ones did not reach.
+        for &id in &jobs[1..] {
+            assert!(scheduler.prepare_jobs_for_execution().is_empty());
+            assert_eq!(
+                reason(&scheduler, id),
+                Some(JobStateReason::WaitingForGpus { need: 1, free: 0 })
+            );
+        }
+        assert!(scheduler.last_pass().yielded);
+
+        scheduler.set_pass_budget(None);
+        scheduler.prepare_jobs_for_execution();
+        assert_eq!(scheduler.last_pass().examined, 3);
+        assert!(!scheduler.last_pass().yielded);
+    }
+
+    #[test]
+    fn test_pass_budget_cuts_a_long_queue_short() {
+        let mut scheduler = scheduler_with_one_gpu();
+        for _ in 0..1_000 {
+            submit_gpu_job(&mut scheduler, 1, None);
+        }
+
+        scheduler.set_pass_budget(Some(Duration::from_millis(1)));
+        scheduler.prepare_jobs_for_execution();
+        let pass = *scheduler.last_pass();
+        assert!(pass.yielded);
+        assert!(pass.examined < 1_000, "examined {}", pass.examined);
+        let queued = scheduler
+            .job_runtimes
+            .iter()
+            .filter(|rt| rt.state == JobState::Queued)
+            .count();
+        assert_eq!(queued, 1_000 - pass.started);
+
+        // Nothing was lost: without a budget the next pass sees all of them.
+        scheduler.set_pass_budget(None);
+        scheduler.prepare_jobs_for_execution();
+        assert_eq!(scheduler.last_pass().examined, queued);
+    }
+
+    #[test]
+    fn test_estimates_follow_gpu_releases_in_priority_order() {
+        let mut scheduler = scheduler_with_one_gpu();
+        let running = submit_gpu_job(&mut scheduler, 1, Some(3600));
+        assert_eq!(scheduler.prepare_jobs_for_execution().len(), 1);
+        let now = std::time::SystemTime::now();
+        scheduler.get_job_runtime_mut(running).unwrap().started_at =
+            Some(now - Duration::from_secs(1800));
+
+        let first = submit_gpu_job(&mut scheduler, 1, Some(7200));
+        let second = submit_gpu_job(&mut scheduler, 1, Some(600));
+        let too_big = submit_gpu_job(&mut scheduler, 2, None);
+
+        let at = |offset: u64| {
+            (now + Duration::from_secs(offset))
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_secs() as i64
+        };
+        let estimates = scheduler.estimate_start_times(now);
+        // The shorter job has the larger time bonus, so it goes first.
+        assert_eq!(
+            estimates[&second],
+            StartEstimate::At {
+                at: at(1800),
+                jobs_ahead: 0
+            }
+        );
+        assert_eq!(
+            estimates[&first],
+            StartEstimate::At {
+                at: at(2400),
+                jobs_ahead: 1
+            }
+        );
+        assert!(matches!(estimates[&too_big], StartEstimate::Never { .. }));
+        assert!(!estimates.contains_key(&running));
+    }
+
+    #[test]
+    fn test_progress_is_cleared_when_the_job_stops_running() {
+        let mut scheduler = scheduler_with_one_gpu();
+        let job_id = submit_gpu_job(&mut scheduler, 1, None);
+        assert_eq!(scheduler.prepare_jobs_for_execution().len(), 1);
+        scheduler.get_job_runtime_mut(job_id).unwrap().progress =
+            crate::core::job::JobProgress::parse("50", std::time::SystemTime::now()).map(Box::new);
+        assert!(scheduler.get_job(job_id).unwrap().progress.is_some());
+
+        scheduler.finish_job(job_id);
+        assert_eq!(scheduler.get_job(job_id).unwrap().progress, None);
+    }
+
+    #[test]
+    fn test_queue_summary_adds_up_gpus_and_waits_for_the_last_job() {
+        let mut scheduler = scheduler_with_one_gpu();
+        let running = submit_gpu_job(&mut scheduler, 1, Some(3600));
+        assert_eq!(scheduler.prepare_jobs_for_execution().len(), 1);
+        let now = std::time::SystemTime::now();
+        scheduler.get_job_runtime_mut(running).unwrap().started_at =
+            Some(now - Duration::from_secs(1800));
+
+        let first = submit_gpu_job(&mut scheduler, 1, Some(7200));
+        let second = submit_gpu_job(&mut scheduler, 1, Some(600));
+        submit_gpu_job(&mut scheduler, 2, None);
+        scheduler.get_job_runtime_mut(first).unwrap().queued_at =
+            Some(now - Duration::from_secs(600));
+        scheduler.get_job_runtime_mut(second).unwrap().queued_at =
+            Some(now - Duration::from_secs(60));
+
+        let at = |offset: u64| {
+            (now + Duration::from_secs(offset))
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_secs() as i64
+        };
+        let summary = scheduler.queue_summary(now);
+        assert_eq!(summary.running_gpus, 1);
+        assert_eq!(summary.queued_gpus, 4);
+        assert_eq!(summary.oldest_queued_at, Some(at(0) - 600));
+        // The job too big to ever start does not hold up the all-clear.
+        assert_eq!(summary.all_clear_at, Some(at(2400 + 7200)));
+
+        // Without a time limit the running job has no expected end.
+        scheduler.get_job_runtime_mut(running).unwrap().time_limit = None;
+        assert_eq!(scheduler.queue_summary(now).all_clear_at, None);
+    }
+
+    #[test]
+    fn test_estimates_fall_back_to_runtime_history() {
+        let mut scheduler = scheduler_with_one_gpu();
+        let now = std::time::SystemTime::now();
+
+        let finished = submit_gpu_job(&mut scheduler, 1, None);
+        scheduler.prepare_jobs_for_execution();
+        scheduler.get_job_runtime_mut(finished).unwrap().started_at =
+            Some(now - Duration::from_secs(3600));
+        scheduler.finish_job(finished);
+        assert_eq!(
+            scheduler.runtime_stats().p95("alice", "gjob-7"),
+            Some(Duration::from_secs(3600))
+        );
+
+        let running = submit_gpu_job(&mut scheduler, 1, None);
+        assert_eq!(scheduler.prepare_jobs_for_execution().len(), 1);
+        scheduler.get_job_runtime_mut(running).unwrap().started_at = Some(now);
+        let queued = submit_gpu_job(&mut scheduler, 1, None);
+
+        let estimates = scheduler.estimate_start_times(now);
+        let expected = (now + Duration::from_secs(3600))
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs() as i64;
+        assert_eq!(
+            estimates[&queued],
+            StartEstimate::At {
+                at: expected,
+                jobs_ahead: 0
+            }
+        );
+
+        // With no history the running job may hold its GPU forever.
+        for id in [running, queued] {
+            scheduler.job_specs[(id - 1) as usize].submitted_by = "bob".into();
+        }
+        let estimates = scheduler.estimate_start_times(now);
+        assert_eq!(estimates[&queued], StartEstimate::Unknown);
+    }
+
+    #[test]
+    fn test_queue_positions_skip_jobs_waiting_on_dependencies() {
+        let mut scheduler = scheduler_with_one_gpu();
+        let running = submit_gpu_job(&mut scheduler, 1, None);
+        assert_eq!(scheduler.prepare_jobs_for_execution().len(), 1);
+
+        let long = submit_gpu_job(&mut scheduler, 1, Some(7200));
+        let short = submit_gpu_job(&mut scheduler, 1, Some(600));
+        let waiting = scheduler
+            .submit_job(
+                JobBuilder::new()
+                    .submitted_by("alice")
+                    .run_dir("/tmp")
+                    .depends_on(Some(running))
+                    .build(),
+            )
+            .0;
+
+        let positions = scheduler.queue_positions();
+        assert_eq!(positions.get(&short), Some(&1));
+        assert_eq!(positions.get(&long), Some(&2));
+        assert!(!positions.contains_key(&waiting));
+        assert!(!positions.contains_key(&running));
+    }
+
+    // Property-based tests for GPU allocation invariants
+    fn scheduler_with_gpu_count(count: u32) -> Scheduler {
+        let mut scheduler = create_test_scheduler();
+        for index in 0..count {
+            scheduler.gpu_slots.insert(
+                format!("GPU-{index}"),
+                GPUSlot {
+                    index,
+                    available: true,
+                    total_memory_mb: None,
+                    reason: None,
+                },
+            );
+        }
+        scheduler
+    }
+
+    fn start_job(
+        scheduler: &mut Scheduler,
+        priority: u8,
+        preemptible: bool,
+        minutes_ago: u64,
+    ) -> u32 {
+        let job = JobBuilder::new()
+            .submitted_by("alice")
+            .run_dir("/tmp")
+            .gpus(1)
+            .priority(priority)
+            .preemptible(preemptible)
+            .build();
+        let job_id = scheduler.submit_job(job).0;
+        assert_eq!(scheduler.prepare_jobs_for_execution().len(), 1);
+        scheduler.get_job_runtime_mut(job_id).unwrap().started_at =
+            Some(std::time::SystemTime::now() - Duration::from_secs(minutes_ago * 60));
+        job_id
+    }
+
+    fn submit_urgent_job(scheduler: &mut Scheduler, gpus: u32, priority: u8) -> u32 {
+        let job = JobBuilder::new()
+            .submitted_by("bob")
+            .run_dir("/tmp")
+            .gpus(gpus)
+            .priority(priority)
+            .build();
+        scheduler.submit_job(job).0
+    }
+
+    #[test]
+    fn test_preemption_requeues_lowest_priority_most_recent_job() {
+        let mut scheduler = scheduler_with_gpu_count(3);
+        let older = start_job(&mut scheduler, 1, true, 120);
+        let newer = start_job(&mut scheduler, 1, true, 10);
+        let higher = start_job(&mut scheduler, 5, true, 5);
+        let urgent = submit_urgent_job(&mut scheduler, 1, 50);
+
+        let (jobs, preemptions) = scheduler.prepare_jobs_with_preemption(50);
+
+        assert_eq!(
+            preemptions,
+            vec![Preemption {
+                victim: newer,
+                by: urgent
+            }]
+        );
+        assert_eq!(
+            jobs.iter().map(|job| job.id).collect::<Vec<_>>(),
+            vec![urgent]
+        );
+        assert_eq!(jobs[0].gpu_ids.as_deref(), Some(&[1][..]));
+
+        let victim = scheduler.get_job_runtime(newer).unwrap();
+        assert_eq!(victim.state, JobState::Queued);
+        assert_eq!(victim.gpu_ids, None);
+        assert_eq!(victim.preemptions, 1);
+        assert_eq!(
+            victim.reason.as_deref(),
+            Some(&JobStateReason::PreemptedBy(urgent))
+        );
+        for job_id in [older, higher] {
+            assert_eq!(
+                scheduler.get_job_runtime(job_id).unwrap().state,
+                JobState::Running
+            );
+        }
+
+        // The victim waits in the queue for a free GPU like any other job.
+        assert!(scheduler.prepare_jobs_with_preemption(50).0.is_empty());
+        scheduler.finish_job(older);
+        let jobs = scheduler.prepare_jobs_for_execution();
+        assert_eq!(
+            jobs.iter().map(|job| job.id).collect::<Vec<_>>(),
+            vec![newer]
+        );
+    }
+
+    #[test]
+    fn test_preemption_only_when_it_frees_enough_gpus() {
+        let mut scheduler = scheduler_with_gpu_count(2);
+        let preemptible = start_job(&mut scheduler, 1, true, 10);
+        let pinned = start_job(&mut scheduler, 1, false, 10);
+
+        // Freeing the preemptible job's GPU alone is not enough.
+        let wide = submit_urgent_job(&mut scheduler, 2, 50);
+        let (jobs, preemptions) = scheduler.prepare_jobs_with_preemption(50);
+        assert!(jobs.is_empty());
+        assert!(preemptions.is_empty());
+        assert_eq!(
+            scheduler.get_job_runtime(wide).unwrap().reason.as_deref(),
+            Some(&JobStateReason::WaitingForGpus { need: 2, free: 0 })
+        );
+        scheduler.cancel_job(wide, None);
+
+        // Below the threshold nothing is preempted.
+        submit_urgent_job(&mut scheduler, 1, 40);
+        let (jobs, preemptions) = scheduler.prepare_jobs_with_preemption(50);
+        assert!(jobs.is_empty());
+        assert!(preemptions.is_empty());
+
+        for job_id in [preemptible, pinned] {
+            let rt = scheduler.get_job_runtime(job_id).unwrap();
+            assert_eq!(rt.state, JobState::Running);
+            assert_eq!(rt.preemptions, 0);
+        }
+    }
+
+    #[test]
+    fn test_preemption_never_touches_equal_or_non_preemptible_jobs() {
+        let mut scheduler = scheduler_with_gpu_count(2);
+        start_job(&mut scheduler, 1, false, 10);
+        start_job(&mut scheduler, 50, true, 10);
+        submit_urgent_job(&mut scheduler, 1, 50);
+
+        let (jobs, preemptions) = scheduler.prepare_jobs_with_preemption(50);
+        assert!(jobs.is_empty());
+        assert!(preemptions.is_empty());
+    }
+
+    fn register_test_node(scheduler: &mut Scheduler, name: &str, gpus: u32) {
+        let registration = NodeRegistration {
+            name: name.to_string(),
+            url: format!("http://{name}:59001"),
+            gpus,
+            total_memory_mb: 64 * 1024,
+            token: "secret".to_string(),
+            labels: Labels::new(),
+        };
+        assert!(scheduler.register_node(registration, std::time::SystemTime::now()));
+    }
+
+    #[test]
+    fn test_capacity_rejects_jobs_no_machine_can_run() {
+        let mut scheduler = scheduler_with_one_gpu();
+        scheduler.total_cpus = 8;
+        let job = |gpus, memory_mb, cpus| {
+            let mut job = JobBuilder::new().gpus(gpus).build();
+            job.memory_limit_mb = memory_mb;
+            job.cpus = cpus;
+            job
+        };
+
+        assert!(scheduler
+            .validate_capacity(&job(1, Some(1024), Some(8)))
+            .is_ok());
+        let error = scheduler
+            .validate_capacity(&job(2, Some(32 * 1024), Some(16)))
+            .unwrap_err();
+        assert_eq!(error.status(), 400);
+        assert_eq!(
+            error.to_string(),
+            "The job asks for more than any machine can ever give it: \
+             2 GPUs (at most 1), 32G memory (at most 16G), 16 CPU cores (at most 8)"
+        );
+
+        // Hidden GPUs do not count, a node with more GPUs and memory does.
+        scheduler.allowed_gpu_indices = Some(vec![]);
+        assert!(scheduler.validate_capacity(&job(1, None, None)).is_err());
+        register_test_node(&mut scheduler, "node-a", 4);
+        assert_eq!(
+            scheduler.capacity(),
+            Capacity {
+                gpus: 4,
+                memory_mb: Some(64 * 1024),
+                cpus: None,
+            }
+        );
+        assert!(scheduler
+            .validate_capacity(&job(4, Some(32 * 1024), Some(16)))
+            .is_ok());
+    }
+
+    #[test]
+    fn test_jobs_spill_onto_nodes_when_local_gpus_are_busy() {
+        let mut scheduler = scheduler_with_one_gpu();
+        register_test_node(&mut scheduler, "node-b", 2);
+        register_test_node(&mut scheduler, "node-a", 1);
+
+        let local = submit_gpu_job(&mut scheduler, 1, None);
+        let on_a = submit_gpu_job(&mut scheduler, 1, None);
+        let on_b = submit_gpu_job(&mut scheduler, 2, None);
+        let waiting = submit_gpu_job(&mut scheduler, 1, None);
+        assert_eq!(scheduler.prepare_jobs_for_execution().len(), 3);
+
+        let rt = |scheduler: &Scheduler, id| scheduler.get_job_runtime(id).unwrap().clone();
+        assert_eq!(rt(&scheduler, local).node, None);
+        assert_eq!(rt(&scheduler, on_a).node.as_deref(), Some("node-a"));
+        assert_eq!(rt(&scheduler, on_a).gpu_ids.as_deref(), Some(&[0][..]));
+        assert_eq!(rt(&scheduler, on_b).node.as_deref(), Some("node-b"));
+        assert_eq!(rt(&scheduler, on_b).gpu_ids.as_deref(), Some(&[0, 1][..]));
+        assert_eq!(rt(&scheduler, waiting).state, JobState::Queued);
+
+        // Remote GPU 0 does not occupy the head's GPU 0.
+        scheduler.finish_job(local);
+        assert_eq!(scheduler.prepare_jobs_for_execution().len(), 1);
+        assert_eq!(rt(&scheduler, waiting).node, None);
+        assert_eq!(rt(&scheduler, waiting).gpu_ids.as_deref(), Some(&[0][..]));
+    }
+
+    #[test]
+    fn test_stale_nodes_go_down_and_report_their_jobs() {
+        let mut scheduler = scheduler_with_gpu_count(0);
+        register_test_node(&mut scheduler, "node-a", 1);
+        let job_id = submit_gpu_job(&mut scheduler, 1, None);
+        assert_eq!(scheduler.prepare_jobs_for_execution().len(), 1);
+
+        let now = std::time::SystemTime::now();
+        assert!(scheduler.mark_stale_nodes_down(now).is_empty());
+        assert!(scheduler.jobs_on_down_nodes().is_empty());
+
+        let later = now + crate::core::node::NODE_HEARTBEAT_TIMEOUT * 2;
+        assert_eq!(scheduler.mark_stale_nodes_down(later), vec!["node-a"]);
+        assert_eq!(scheduler.jobs_on_down_nodes(), vec![job_id]);
+
+        // Nothing is placed on a down node until it registers again.
+        let queued = submit_gpu_job(&mut scheduler, 1, None);
+        scheduler.finish_job(job_id);
+        assert!(scheduler.prepare_jobs_for_execution().is_empty());
+        register_test_node(&mut scheduler, "node-a", 1);
+        assert_eq!(scheduler.prepare_jobs_for_execution().len(), 1);
+        assert_eq!(
+            scheduler.get_job_runtime(queued).unwrap().node.as_deref(),
+            Some("node-a")
+        );
+    }
+
+    #[test]
+    fn test_submission_ids_tell_resends_from_conflicts_until_they_expire() {
+        let mut scheduler = create_test_scheduler();
+        let submission_id = uuid::Uuid::new_v4();
+        let job = JobBuilder::new()
+            .submitted_by("alice")
+            .run_dir("/tmp")
+            .command("echo hi")
+            .build();
+        let digest = submission_digest(&job);
+        let now = std::time::SystemTime::now();
+        assert_eq!(
+            scheduler.prior_submission(submission_id, &digest, now),
+            PriorSubmission::None
+        );
+
+        let (job_id, _) = scheduler.submit_job(job);
+        scheduler.record_submission(
+            submission_id,
+            SubmissionRecord {
+                job_id,
+                digest: digest.clone(),
+                at: now,
+            },
+        );
+        assert_eq!(
+            scheduler.prior_submission(submission_id, &digest, now),
+            PriorSubmission::Same(job_id)
+        );
+        assert_eq!(
+            scheduler.prior_submission(submission_id, "other", now),
+            PriorSubmission::Different(job_id)
+        );
+
+        // Remembered across restarts, forgotten after the TTL.
+        let bytes = rmp_serde::to_vec_named(&scheduler).unwrap();
+        let reloaded: Scheduler = rmp_serde::from_slice(&bytes).unwrap();
+        assert_eq!(
+            reloaded.prior_submission(submission_id, &digest, now),
+            PriorSubmission::Same(job_id)
+        );
+        let later = now + SUBMISSION_ID_TTL * 2;
+        assert_eq!(
+            reloaded.prior_submission(submission_id, &digest, later),
+            PriorSubmission::None
+        );
+    }
+
+    #[test]
+    fn test_artifacts_replace_by_path_and_survive_restarts() {
+        let mut scheduler = create_test_scheduler();
+        let artifact = |path: &str, label: &str| JobArtifact {
+            path: path.into(),
+            label: Some(label.to_string()),
+            size_bytes: 1,
+            registered_at: std::time::SystemTime::UNIX_EPOCH,
+        };
+        scheduler
+            .register_artifact(1, artifact("/out/a.pt", "first"))
+            .unwrap();
+        scheduler
+            .register_artifact(1, artifact("/out/b.pt", "other"))
+            .unwrap();
+        scheduler
+            .register_artifact(1, artifact("/out/a.pt", "best"))
+            .unwrap();
+        assert!(scheduler.job_artifacts(2).is_empty());
+
+        let bytes = rmp_serde::to_vec_named(&scheduler).unwrap();
+        let reloaded: Scheduler = rmp_serde::from_slice(&bytes).unwrap();
+        let labels: Vec<_> = reloaded
+            .job_artifacts(1)
+            .iter()
+            .map(|a| a.label.as_deref().unwrap())
+            .collect();
+        assert_eq!(labels, ["best", "other"]);
+    }
+
+    fn labels(pairs: &[(&str, &str)]) -> Labels {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    fn submit_constrained_job(scheduler: &mut Scheduler, gpus: u32, constraint: &str) -> u32 {
+        let job = JobBuilder::new()
+            .submitted_by("alice")
+            .run_dir("/tmp")
+            .gpus(gpus)
+            .constraint(Some(constraint.parse().unwrap()))
+            .build();
+        scheduler.submit_job(job).0
+    }
+
+    #[test]
+    fn test_constraint_picks_matching_gpus_or_waits() {
+        let mut scheduler = scheduler_with_gpu_count(3);
+        scheduler.set_labels(
+            labels(&[("arch", "ampere")]),
+            BTreeMap::from([(2, labels(&[("vram", "48g")]))]),
+        );
+
+        let big = submit_constrained_job(&mut scheduler, 1, "vram=48g,arch=ampere");
+        let hopper = submit_constrained_job(&mut scheduler, 1, "arch=hopper");
+        let cpu = submit_constrained_job(&mut scheduler, 0, "arch=ampere|hopper");
+        assert_eq!(scheduler.prepare_jobs_for_execution().len(), 2);
+
+        let rt = |scheduler: &Scheduler, id| scheduler.get_job_runtime(id).unwrap().clone();
+        assert_eq!(rt(&scheduler, big).gpu_ids.as_deref(), Some(&[2][..]));
+        assert_eq!(rt(&scheduler, cpu).state, JobState::Running);
+        assert_eq!(rt(&scheduler, hopper).state, JobState::Queued);
+        assert_eq!(
+            rt(&scheduler, hopper).reason.as_deref(),
+            Some(&JobStateReason::NoMatchingResources)
+        );
+
+        // Only GPU 2 has 48g, and it is busy.
+        let second_big = submit_constrained_job(&mut scheduler, 1, "vram=48g");
+        assert!(scheduler.prepare_jobs_for_execution().is_empty());
+        assert_eq!(
+            rt(&scheduler, second_big).reason.as_deref(),
+            Some(&JobStateReason::WaitingForGpus { need: 1, free: 0 })
+        );
+
+        scheduler
+            .update_labels(&LabelTarget::Gpu(1), labels(&[("arch", "hopper")]), &[])
+            .unwrap();
+        assert_eq!(scheduler.prepare_jobs_for_execution().len(), 1);
+        assert_eq!(rt(&scheduler, hopper).gpu_ids.as_deref(), Some(&[1][..]));
+    }
+
+    fn submit_disk_job(scheduler: &mut Scheduler, run_dir: &str, min_free_mb: Option<u64>) -> u32 {
+        let job = JobBuilder::new()
+            .submitted_by("alice")
+            .run_dir(run_dir)
+            .min_free_disk_mb(min_free_mb)
+            .build();
+        scheduler.submit_job(job).0
+    }
+
+    #[test]
+    fn test_jobs_wait_for_free_disk_space() {
+        let mut scheduler = create_test_scheduler();
+        scheduler.disk_guard.probe = |path| match path.to_str() {
+            Some("/full") => Some(12 * 1024),
+            Some("/logs") => Some(30 * 1024),
+            Some("/nfs") => None,
+            _ => Some(500 * 1024),
+        };
+        scheduler.set_log_dir(Some(PathBuf::from("/logs")));
+        scheduler.set_min_free_disk(Some(20 * 1024));
+
+        let full = submit_disk_job(&mut scheduler, "/full", Some(50 * 1024));
+        let logs = submit_disk_job(&mut scheduler, "/scratch", Some(40 * 1024));
+        let nfs = submit_disk_job(&mut scheduler, "/nfs", Some(25 * 1024));
+        let default = submit_disk_job(&mut scheduler, "/scratch", None);
+        let started: Vec<u32> = scheduler
+            .prepare_jobs_for_execution()
+            .iter()
+            .map(|job| job.id)
+            .collect();
+        assert_eq!(started, vec![nfs, default]);
+
+        let reason = |scheduler: &Scheduler, id| {
+            let rt = scheduler.get_job_runtime(id).unwrap();
+            assert_eq!(rt.state, JobState::Queued);
+            rt.reason.as_deref().unwrap().to_string()
+        };
+        assert_eq!(
+            reason(&scheduler, full),
+            "InsufficientDisk(run_dir 12G free < 50G)"
+        );
+        assert_eq!(
+            reason(&scheduler, logs),
+            "InsufficientDisk(log_dir 30G free < 40G)"
+        );
+
+        // Checked again on every pass.
+        scheduler.disk_guard.probe = |_| Some(500 * 1024);
+        assert_eq!(scheduler.prepare_jobs_for_execution().len(), 2);
+    }
+
+    #[test]
+    fn test_constraint_places_jobs_on_labelled_nodes() {
+        let mut scheduler = scheduler_with_one_gpu();
+        register_test_node(&mut scheduler, "node-a", 1);
+        let mut registration = NodeRegistration {
+            name: "node-b".to_string(),
+            url: "http://node-b:59001".to_string(),
+            gpus: 1,
+            total_memory_mb: 64 * 1024,
+            token: "secret".to_string(),
+            labels: labels(&[("arch", "hopper")]),
+        };
+        scheduler.register_node(registration.clone(), std::time::SystemTime::now());
+
+        // The head's GPU is idle, but only node-b is labelled hopper.
+        let job_id = submit_constrained_job(&mut scheduler, 1, "arch=hopper");
+        assert_eq!(scheduler.prepare_jobs_for_execution().len(), 1);
+        let rt = scheduler.get_job_runtime(job_id).unwrap();
+        assert_eq!(rt.node.as_deref(), Some("node-b"));
+
+        // Labels set on the head survive the agent registering again.
+        scheduler
+            .update_labels(
+                &LabelTarget::Node("node-b".to_string()),
+                labels(&[("nvlink", "true")]),
+                &["arch".to_string()],
+            )
+            .unwrap();
+        registration.labels = labels(&[("arch", "hopper"), ("vram", "80g")]);
+        scheduler.register_node(registration, std::time::SystemTime::now());
+        assert_eq!(
+            scheduler.get_node("node-b").unwrap().labels,
+            labels(&[("nvlink", "true"), ("vram", "80g")])
+        );
+    }
+
     mod proptests {
         use super::*;
         use crate::core::reservation::GpuSpec;
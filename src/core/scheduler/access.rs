@@ -1,75 +1,6 @@
 use super::*;
 
 impl Scheduler {
-    /// Update the cached state->job_ids index.
-    ///
-    /// This maintains sorted job IDs per state so API handlers can iterate in stable ID order
-    /// without scanning all jobs.
-    pub(super) fn update_state_jobs_index(
-        &mut self,
-        job_id: u32,
-        old_state: JobState,
-        new_state: JobState,
-    ) {
-        if old_state == new_state {
-            return;
-        }
-
-        if let Some(v) = self.state_jobs_index.get_mut(&old_state) {
-            if let Ok(pos) = v.binary_search(&job_id) {
-                v.remove(pos);
-                if v.is_empty() {
-                    self.state_jobs_index.remove(&old_state);
-                }
-            }
-        }
-
-        let entry = self.state_jobs_index.entry(new_state).or_default();
-        match entry.binary_search(&job_id) {
-            Ok(_) => {} // already present
-            Err(pos) => entry.insert(pos, job_id),
-        }
-    }
-
-    /// Update the cached project->job_ids index.
-    ///
-    /// This maintains sorted job IDs per project so API handlers can iterate in stable ID order
-    /// without scanning all jobs. Uses binary search to maintain sort order.
-    ///
-    /// Note: Projects are immutable after job submission. This method is designed for initial
-    /// indexing and rebuild operations, not for updating existing job projects.
-    pub(super) fn update_project_jobs_index(
-        &mut self,
-        job_id: u32,
-        old_project: Option<&CompactString>,
-        new_project: Option<&CompactString>,
-    ) {
-        if old_project == new_project {
-            return;
-        }
-
-        // Remove from old project
-        if let Some(old_proj) = old_project {
-            if let Some(v) = self.project_jobs_index.get_mut(old_proj) {
-                if let Ok(pos) = v.binary_search(&job_id) {
-                    v.remove(pos);
-                    if v.is_empty() {
-                        self.project_jobs_index.remove(old_proj);
-                    }
-                }
-            }
-        }
-
-        // Add to new project
-        if let Some(new_proj) = new_project {
-            let entry = self.project_jobs_index.entry(new_proj.clone()).or_default();
-            match entry.binary_search(&job_id) {
-                Ok(_) => {} // already present
-                Err(pos) => entry.insert(pos, job_id),
-            }
-        }
-    }
-
     /// Get job IDs by project for fast filtering.
     ///
     /// Returns a sorted list of job IDs (ascending order) for the given project,
@@ -78,6 +9,11 @@ impl Scheduler {
         self.project_jobs_index.get(project)
     }
 
+    /// Job IDs carrying `tag`, in ascending order, or None if no job has it.
+    pub fn job_ids_by_tag(&self, tag: &str) -> Option<&[u32]> {
+        self.tag_jobs_index.get(tag).map(Vec::as_slice)
+    }
+
     /// Get a JobSpec by ID (job IDs start at 1, so we subtract 1 for the index)
     #[inline]
     pub fn get_job_spec(&self, job_id: u32) -> Option<&JobSpec> {
@@ -105,6 +41,18 @@ impl Scheduler {
         self.job_runtimes.get_mut((job_id - 1) as usize)
     }
 
+    /// Share job `job_id`'s user name and conda environment with the other
+    /// jobs again, after an update replaced them.
+    pub fn intern_job_strings(&mut self, job_id: u32) {
+        let Some(spec) = job_id
+            .checked_sub(1)
+            .and_then(|idx| self.job_specs.get_mut(idx as usize))
+        else {
+            return;
+        };
+        spec.intern_strings(&mut self.string_pool);
+    }
+
     /// Get a JobView combining spec and runtime
     pub fn get_job_view(&self, job_id: u32) -> Option<JobView> {
         let spec = self.get_job_spec(job_id)?;
@@ -141,6 +89,8 @@ impl Scheduler {
                 || self.dependency_runtimes.len() == self.job_runtimes.len(),
             "dependency_runtimes must be empty or aligned with job_runtimes"
         );
+        #[cfg(test)]
+        self.assert_job_indexes_consistent();
     }
 
     /// Total jobs stored in the scheduler.
@@ -181,6 +131,11 @@ impl Scheduler {
             .collect()
     }
 
+    /// Runtimes learned from finished jobs
+    pub fn runtime_stats(&self) -> &RuntimeStats {
+        &self.runtime_stats
+    }
+
     /// Check if a job exists
     #[inline]
     pub fn job_exists(&self, job_id: u32) -> bool {
@@ -216,6 +171,7 @@ impl Scheduler {
                 index: slot.index,
                 available: slot.available,
                 reason: slot.reason.clone(),
+                labels: self.gpu_labels(slot.index),
             })
             .collect();
         // Sort by index for stable output
@@ -224,6 +180,7 @@ impl Scheduler {
             gpus,
             allowed_gpu_indices: self.allowed_gpu_indices.clone(),
             gpu_allocation_strategy: self.gpu_allocation_strategy,
+            labels: self.labels.clone(),
         }
     }
 
@@ -240,6 +197,7 @@ impl Scheduler {
     /// Set GPU restrictions
     pub fn set_allowed_gpu_indices(&mut self, indices: Option<Vec<u32>>) {
         self.allowed_gpu_indices = indices;
+        self.bump_revision();
     }
 
     /// Get GPU restrictions
@@ -250,6 +208,20 @@ impl Scheduler {
     /// Set GPU allocation strategy.
     pub fn set_gpu_allocation_strategy(&mut self, strategy: GpuAllocationStrategy) {
         self.gpu_allocation_strategy = strategy;
+        self.bump_revision();
+    }
+
+    /// A number that grows whenever jobs, GPU availability or the settings
+    /// in [`Scheduler::info`] change, so pollers can tell nothing has.
+    /// Starts from 0 on every load.
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    /// Record a change [`Scheduler::revision`] covers that was made from
+    /// outside, e.g. editing a job through [`Scheduler::get_job_runtime_mut`].
+    pub fn bump_revision(&mut self) {
+        self.revision += 1;
     }
 
     /// Get current GPU allocation strategy.
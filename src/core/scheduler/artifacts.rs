@@ -0,0 +1,31 @@
+use super::*;
+use crate::core::job::MAX_ARTIFACTS_PER_JOB;
+
+impl Scheduler {
+    /// Every job's artifacts, by job id
+    pub fn artifacts(&self) -> &BTreeMap<u32, Vec<JobArtifact>> {
+        &self.artifacts
+    }
+
+    /// What job `job_id` registered, oldest first.
+    pub fn job_artifacts(&self, job_id: u32) -> &[JobArtifact] {
+        self.artifacts.get(&job_id).map_or(&[], Vec::as_slice)
+    }
+
+    /// Record an artifact of `job_id`, replacing an earlier one with the same
+    /// path. Fails once the job has [`MAX_ARTIFACTS_PER_JOB`].
+    pub fn register_artifact(&mut self, job_id: u32, artifact: JobArtifact) -> Result<(), String> {
+        let artifacts = self.artifacts.entry(job_id).or_default();
+        if let Some(earlier) = artifacts.iter_mut().find(|a| a.path == artifact.path) {
+            *earlier = artifact;
+            return Ok(());
+        }
+        if artifacts.len() >= MAX_ARTIFACTS_PER_JOB {
+            return Err(format!(
+                "Job {job_id} already has {MAX_ARTIFACTS_PER_JOB} artifacts"
+            ));
+        }
+        artifacts.push(artifact);
+        Ok(())
+    }
+}
@@ -6,6 +6,7 @@ pub struct SchedulerBuilder {
     gpu_slots: HashMap<GpuUuid, GPUSlot>,
     state_path: PathBuf,
     total_memory_mb: u64,
+    total_cpus: u32,
     allowed_gpu_indices: Option<Vec<u32>>,
     gpu_allocation_strategy: GpuAllocationStrategy,
     unified_memory: bool,
@@ -18,6 +19,7 @@ impl SchedulerBuilder {
             gpu_slots: HashMap::new(),
             state_path: PathBuf::from("state.json"),
             total_memory_mb: 16 * 1024,
+            total_cpus: 0,
             allowed_gpu_indices: None,
             gpu_allocation_strategy: GpuAllocationStrategy::default(),
             unified_memory: false,
@@ -44,6 +46,11 @@ impl SchedulerBuilder {
         self
     }
 
+    pub fn with_total_cpus(mut self, total_cpus: u32) -> Self {
+        self.total_cpus = total_cpus;
+        self
+    }
+
     pub fn with_allowed_gpu_indices(mut self, indices: Option<Vec<u32>>) -> Self {
         self.allowed_gpu_indices = indices;
         self
@@ -68,6 +75,7 @@ impl SchedulerBuilder {
             gpu_slots: self.gpu_slots,
             total_memory_mb: self.total_memory_mb,
             available_memory_mb: self.total_memory_mb,
+            total_cpus: self.total_cpus,
             state_path: self.state_path,
             next_job_id: 1,
             allowed_gpu_indices: self.allowed_gpu_indices,
@@ -76,12 +84,28 @@ impl SchedulerBuilder {
             user_jobs_index: HashMap::new(),
             state_jobs_index: HashMap::new(),
             project_jobs_index: HashMap::new(),
+            tag_jobs_index: HashMap::new(),
             dependents_graph: HashMap::new(),
             dependency_runtimes: Vec::new(),
             ready_heap: std::collections::BinaryHeap::new(),
             group_running_count: HashMap::new(),
+            group_jobs_index: HashMap::new(),
+            string_pool: StringPool::default(),
+            revision: 0,
             reservations: Vec::new(),
             next_reservation_id: 1,
+            runtime_stats: Default::default(),
+            nodes: BTreeMap::new(),
+            submissions: HashMap::new(),
+            artifacts: BTreeMap::new(),
+            labels: Labels::new(),
+            gpu_labels: BTreeMap::new(),
+            transitions: Vec::new(),
+            disk_guard: DiskGuard::default(),
+            pass_budget: None,
+            pass_backlog: Vec::new(),
+            last_pass: SchedulingPass::default(),
+            gpu_claims: BTreeMap::new(),
         }
     }
 }
@@ -0,0 +1,57 @@
+use super::*;
+use crate::core::api_error::{ApiError, ApiErrorKind};
+use crate::core::info::Capacity;
+
+impl Scheduler {
+    /// The most of each resource a single job could ever get: this machine's
+    /// allowed GPUs, memory and cores, or a registered node's if it has more.
+    ///
+    /// Memory is `None` while unknown. CPU cores are only a limit without
+    /// nodes, since jobs on nodes are not pinned.
+    pub fn capacity(&self) -> Capacity {
+        let local_gpus = self
+            .gpu_slots
+            .values()
+            .filter(|slot| {
+                self.allowed_gpu_indices
+                    .as_ref()
+                    .is_none_or(|allowed| allowed.contains(&slot.index))
+            })
+            .count() as u32;
+        let gpus = self
+            .nodes
+            .values()
+            .map(|node| node.gpus)
+            .fold(local_gpus, u32::max);
+        let memory_mb = self
+            .nodes
+            .values()
+            .map(|node| node.total_memory_mb)
+            .fold(self.total_memory_mb, u64::max);
+        Capacity {
+            gpus,
+            memory_mb: (memory_mb > 0).then_some(memory_mb),
+            cpus: (self.total_cpus > 0 && self.nodes.is_empty()).then_some(self.total_cpus),
+        }
+    }
+
+    /// Refuse a job that asks for more than [`Scheduler::capacity`]; one that
+    /// only has to wait for resources to free up passes.
+    pub fn validate_capacity(&self, job: &Job) -> Result<(), ApiError> {
+        let requested = Capacity {
+            gpus: job.gpus,
+            memory_mb: job.memory_limit_mb,
+            cpus: job.cpus,
+        };
+        let capacity = self.capacity();
+        if capacity.shortfalls(&requested).is_empty() {
+            Ok(())
+        } else {
+            Err(ApiErrorKind::ExceedsCapacity {
+                requested,
+                capacity,
+            }
+            .into())
+        }
+    }
+}
@@ -0,0 +1,49 @@
+use super::*;
+use crate::core::cpu::pick_cpus;
+use crate::core::job::CpuIds;
+use std::collections::BTreeSet;
+
+impl Scheduler {
+    /// Logical CPUs of this machine; 0 if unknown, in which case jobs are
+    /// not pinned.
+    pub fn total_cpus(&self) -> u32 {
+        self.total_cpus
+    }
+
+    pub fn update_cpus(&mut self, total_cpus: u32) {
+        self.total_cpus = total_cpus;
+    }
+
+    /// Cores held by jobs running on this machine, counted afresh. A job
+    /// gives its cores back by leaving Running, whichever way it leaves.
+    pub fn used_cpu_ids(&self) -> BTreeSet<u32> {
+        self.runtimes_in_state(JobState::Running)
+            .filter(|rt| rt.node.is_none())
+            .filter_map(|rt| rt.cpu_ids.as_ref())
+            .flatten()
+            .copied()
+            .collect()
+    }
+
+    pub fn used_cpus(&self) -> u32 {
+        self.used_cpu_ids().len() as u32
+    }
+
+    /// Cores no running job is pinned to, in ascending order.
+    pub(super) fn free_cpu_ids(&self) -> Vec<u32> {
+        let used = self.used_cpu_ids();
+        (0..self.total_cpus)
+            .filter(|cpu| !used.contains(cpu))
+            .collect()
+    }
+
+    /// Cores for a job asking for `cpus` of them out of `free`, which this
+    /// pass has not handed out yet: `Some(None)` if the job is not pinned,
+    /// `None` if not enough are free.
+    pub(super) fn assign_cpus(&self, cpus: Option<u32>, free: &[u32]) -> Option<Option<CpuIds>> {
+        match cpus {
+            Some(count) if count > 0 && self.total_cpus > 0 => pick_cpus(free, count).map(Some),
+            _ => Some(None),
+        }
+    }
+}
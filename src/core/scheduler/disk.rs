@@ -0,0 +1,70 @@
+use super::*;
+use crate::utils::disk::disk_space;
+use std::path::Path;
+
+/// Free-space check made right before a job starts (`--min-free-disk`)
+#[derive(Debug, Clone)]
+pub(crate) struct DiskGuard {
+    /// Threshold for jobs that do not set their own (`daemon.min_free_disk`)
+    pub default_min_free_mb: Option<u64>,
+    /// Directory job logs are written to
+    pub log_dir: Option<PathBuf>,
+    /// Free MB of the filesystem holding a path; `None` if unknown
+    pub probe: fn(&Path) -> Option<u64>,
+}
+
+impl Default for DiskGuard {
+    fn default() -> Self {
+        Self {
+            default_min_free_mb: None,
+            log_dir: None,
+            probe: |path| disk_space(path).map(|space| space.free_mb),
+        }
+    }
+}
+
+impl Scheduler {
+    /// Free space jobs without `--min-free-disk` need (`daemon.min_free_disk`).
+    pub fn set_min_free_disk(&mut self, min_free_mb: Option<u64>) {
+        self.disk_guard.default_min_free_mb = min_free_mb;
+    }
+
+    /// Directory job logs are written to, checked along with each run_dir.
+    pub fn set_log_dir(&mut self, log_dir: Option<PathBuf>) {
+        self.disk_guard.log_dir = log_dir;
+    }
+
+    /// Why job `idx` must not start yet for lack of disk space, if it must not.
+    ///
+    /// Filesystems are asked once per scheduling pass through `free_by_dir`.
+    /// A filesystem that cannot be asked does not hold the job back.
+    pub(super) fn insufficient_disk(
+        &self,
+        idx: usize,
+        free_by_dir: &mut HashMap<PathBuf, Option<u64>>,
+    ) -> Option<JobStateReason> {
+        let spec = self.job_specs.get(idx)?;
+        let required_mb = spec
+            .min_free_disk_mb
+            .or(self.disk_guard.default_min_free_mb)?;
+        let dirs = std::iter::once(("run_dir", spec.run_dir.as_path())).chain(
+            self.disk_guard
+                .log_dir
+                .as_deref()
+                .map(|dir| ("log_dir", dir)),
+        );
+        for (name, dir) in dirs {
+            let free_mb = *free_by_dir
+                .entry(dir.to_path_buf())
+                .or_insert_with(|| (self.disk_guard.probe)(dir));
+            if let Some(free_mb) = free_mb.filter(|&free_mb| free_mb < required_mb) {
+                return Some(JobStateReason::InsufficientDisk {
+                    dir: name.into(),
+                    free_mb,
+                    required_mb,
+                });
+            }
+        }
+        None
+    }
+}
@@ -0,0 +1,289 @@
+use super::*;
+use std::collections::BTreeMap;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// How far ahead start times are simulated; later starts are reported as unknown.
+pub const ESTIMATE_HORIZON: Duration = Duration::from_secs(7 * 24 * 3600);
+
+/// When a queued job is expected to start, from [`Scheduler::estimate_start_times`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum StartEstimate {
+    /// Expected start as a Unix timestamp; `jobs_ahead` queued jobs go first.
+    At { at: i64, jobs_ahead: usize },
+    /// Not within [`ESTIMATE_HORIZON`], or waiting on a job with no known end.
+    Unknown,
+    /// The job asks for more than this scheduler has, so it will never start.
+    Never { reason: String },
+}
+
+/// Running and queued jobs at a glance, from [`Scheduler::queue_summary`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct QueueSummary {
+    /// GPUs held by running jobs.
+    pub running_gpus: u32,
+    /// GPUs asked for by queued jobs.
+    pub queued_gpus: u32,
+    /// When the job waiting longest entered the queue, as a Unix timestamp.
+    pub oldest_queued_at: Option<i64>,
+    /// When every running and queued job is expected to have ended, as a
+    /// Unix timestamp; `None` when any of them has no expected end.
+    pub all_clear_at: Option<i64>,
+}
+
+fn unix_secs(time: SystemTime) -> i64 {
+    time.duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs() as i64)
+}
+
+impl Scheduler {
+    /// Sort key putting queued jobs in the order the scheduler starts them.
+    fn scheduling_order(rt: &JobRuntime) -> (std::cmp::Reverse<u8>, std::cmp::Reverse<u32>, u32) {
+        (
+            std::cmp::Reverse(rt.priority),
+            std::cmp::Reverse(Self::calculate_time_bonus(&rt.time_limit)),
+            rt.id,
+        )
+    }
+
+    /// The place of every queued job whose dependencies are met in the order
+    /// jobs are started, from 1 for the next. Jobs still waiting on
+    /// dependencies have no place.
+    pub fn queue_positions(&self) -> BTreeMap<u32, usize> {
+        let mut runnable: Vec<&JobRuntime> = self
+            .job_runtimes
+            .iter()
+            .filter(|rt| {
+                rt.state == JobState::Queued
+                    && self
+                        .dependency_runtime(rt.id)
+                        .is_none_or(|dep_rt| dep_rt.deps_satisfied)
+            })
+            .collect();
+        runnable.sort_by_key(|rt| Self::scheduling_order(rt));
+        runnable
+            .into_iter()
+            .enumerate()
+            .map(|(index, rt)| (rt.id, index + 1))
+            .collect()
+    }
+
+    /// Best-effort start times for every queued job.
+    ///
+    /// Running jobs release their GPUs at the end of their time limit, or else
+    /// after the p95 runtime of the submitter's earlier runs with the same
+    /// run-name prefix (see [`RuntimeStats`]). Queued jobs then take the
+    /// earliest freed GPUs with the labels they ask for, in scheduling order.
+    /// Shared GPUs, group limits, reservations and remote nodes are not modelled. Each queued job looks
+    /// at every GPU once, so the cost is O(queued × GPUs).
+    pub fn estimate_start_times(&self, now: SystemTime) -> BTreeMap<u32, StartEstimate> {
+        self.simulate_queue(now).0
+    }
+
+    /// Aggregates over running and queued jobs, for a one-line overview.
+    pub fn queue_summary(&self, now: SystemTime) -> QueueSummary {
+        let (estimates, ends) = self.simulate_queue(now);
+        let mut summary = QueueSummary::default();
+        // Jobs that can never start are left out: they would never clear.
+        let mut all_clear = Some(now);
+        for rt in &self.job_runtimes {
+            match rt.state {
+                JobState::Running => summary.running_gpus += rt.gpus,
+                JobState::Queued => {
+                    summary.queued_gpus += rt.gpus;
+                    if let Some(queued_at) = rt.queued_at {
+                        let oldest = summary.oldest_queued_at.get_or_insert(unix_secs(queued_at));
+                        *oldest = (*oldest).min(unix_secs(queued_at));
+                    }
+                    if matches!(estimates.get(&rt.id), Some(StartEstimate::Never { .. })) {
+                        continue;
+                    }
+                }
+                _ => continue,
+            }
+            let end = ends.get(&rt.id).copied().flatten();
+            all_clear = all_clear.zip(end).map(|(a, b)| a.max(b));
+        }
+        summary.all_clear_at = all_clear.map(unix_secs);
+        summary
+    }
+
+    /// Start estimates for queued jobs, and the expected end of every job
+    /// placed in the simulation (`None` when unknown).
+    fn simulate_queue(
+        &self,
+        now: SystemTime,
+    ) -> (
+        BTreeMap<u32, StartEstimate>,
+        HashMap<u32, Option<SystemTime>>,
+    ) {
+        let horizon = now + ESTIMATE_HORIZON;
+
+        // The p95 is only an estimate; time limits alone end jobs.
+        let expected_runtime = |spec: &JobSpec, rt: &JobRuntime| -> Option<Duration> {
+            rt.time_limit.or_else(|| {
+                self.runtime_stats
+                    .p95(&spec.submitted_by, spec.run_name.as_deref()?)
+            })
+        };
+
+        // `None` means "not before the horizon": no known end, or busy outside gflow.
+        let mut ends: HashMap<u32, Option<SystemTime>> = HashMap::new();
+        let mut held: HashMap<u32, Option<SystemTime>> = HashMap::new();
+        for (spec, rt) in self.job_specs.iter().zip(&self.job_runtimes) {
+            if rt.state != JobState::Running || rt.node.is_some() {
+                continue;
+            }
+            let end = rt
+                .started_at
+                .zip(expected_runtime(spec, rt))
+                .map(|(started, runtime)| (started + runtime).max(now));
+            ends.insert(rt.id, end);
+            for &gpu in rt.gpu_ids.iter().flatten() {
+                let free_at = held.entry(gpu).or_insert(end);
+                *free_at = free_at.zip(end).map(|(a, b)| a.max(b));
+            }
+        }
+
+        let mut free_at: Vec<(u32, Option<SystemTime>)> = self
+            .gpu_slots
+            .values()
+            .filter(|slot| {
+                self.allowed_gpu_indices
+                    .as_ref()
+                    .is_none_or(|allowed| allowed.contains(&slot.index))
+            })
+            .map(|slot| {
+                let at = match held.get(&slot.index) {
+                    Some(end) => *end,
+                    None if slot.available => Some(now),
+                    None => None,
+                };
+                (slot.index, at)
+            })
+            .collect();
+        let usable_gpus = free_at.len();
+
+        let mut queued: Vec<&JobRuntime> = self
+            .job_runtimes
+            .iter()
+            .filter(|rt| rt.state == JobState::Queued)
+            .collect();
+        queued.sort_by_key(|rt| Self::scheduling_order(rt));
+
+        let mut estimates = BTreeMap::new();
+        let mut jobs_ahead = 0;
+        for rt in queued {
+            let Some(spec) = self.get_job_spec(rt.id) else {
+                continue;
+            };
+
+            let required_memory_mb = Self::effective_host_memory_mb(
+                self.unified_memory,
+                rt.memory_limit_mb,
+                rt.gpu_memory_limit_mb,
+                rt.gpus,
+            );
+            let never = if rt.gpus as usize > usable_gpus {
+                Some(format!(
+                    "requests {} GPU(s) but the scheduler can use {}",
+                    rt.gpus, usable_gpus
+                ))
+            } else if self.total_memory_mb > 0 && required_memory_mb > self.total_memory_mb {
+                Some(format!(
+                    "requests {} of memory but the host has {}",
+                    crate::utils::format_memory(required_memory_mb),
+                    crate::utils::format_memory(self.total_memory_mb)
+                ))
+            } else {
+                rt.cpus
+                    .filter(|&cpus| self.total_cpus > 0 && cpus > self.total_cpus)
+                    .map(|cpus| {
+                        format!(
+                            "requests {} CPU core(s) but the host has {}",
+                            cpus, self.total_cpus
+                        )
+                    })
+            };
+            if let Some(reason) = never {
+                estimates.insert(rt.id, StartEstimate::Never { reason });
+                continue;
+            }
+
+            let ahead = jobs_ahead;
+            jobs_ahead += 1;
+
+            let Some(ready) = self.estimated_ready_time(rt.id, spec, &ends, now) else {
+                estimates.insert(rt.id, StartEstimate::Unknown);
+                continue;
+            };
+
+            // Only GPUs with the labels the job asks for count.
+            let matches = |labels: &Labels| {
+                spec.constraint
+                    .as_ref()
+                    .is_none_or(|constraint| constraint.matches(labels))
+            };
+            let needed = rt.gpus as usize;
+            let start = if needed == 0 {
+                matches(&self.labels).then_some(ready)
+            } else {
+                // Earliest-freed matching GPUs first, with those never freed last.
+                free_at.sort_by_key(|&(index, at)| {
+                    (!matches(&self.gpu_labels(index)), at.is_none(), at, index)
+                });
+                let (index, at) = free_at[needed - 1];
+                at.filter(|_| matches(&self.gpu_labels(index)))
+                    .map(|at| at.max(ready))
+            };
+            let Some(start) = start.filter(|start| *start <= horizon) else {
+                estimates.insert(rt.id, StartEstimate::Unknown);
+                continue;
+            };
+
+            let end = expected_runtime(spec, rt).map(|runtime| start + runtime);
+            for slot in free_at.iter_mut().take(needed) {
+                slot.1 = end;
+            }
+            ends.insert(rt.id, end);
+            estimates.insert(
+                rt.id,
+                StartEstimate::At {
+                    at: unix_secs(start),
+                    jobs_ahead: ahead,
+                },
+            );
+        }
+        (estimates, ends)
+    }
+
+    /// When a queued job's dependencies are expected to let it run.
+    fn estimated_ready_time(
+        &self,
+        job_id: u32,
+        spec: &JobSpec,
+        ends: &HashMap<u32, Option<SystemTime>>,
+        now: SystemTime,
+    ) -> Option<SystemTime> {
+        let dep_rt = self.dependency_runtime(job_id)?;
+        if dep_rt.deps_satisfied {
+            return Some(now);
+        }
+        if dep_rt.impossible {
+            return None;
+        }
+
+        let dep_ends = Self::normalized_dependency_ids(spec)
+            .into_iter()
+            .map(|dep| match self.get_job_runtime(dep).map(|rt| rt.state) {
+                Some(state) if state.is_final() => Some(now),
+                _ => ends.get(&dep).copied().flatten(),
+            });
+        match Self::dependency_mode(spec) {
+            DependencyMode::All => dep_ends
+                .collect::<Option<Vec<_>>>()
+                .map(|ends| ends.into_iter().max().unwrap_or(now)),
+            DependencyMode::Any => dep_ends.flatten().min(),
+        }
+    }
+}
@@ -0,0 +1,115 @@
+use super::*;
+use crate::core::api_error::ApiError;
+
+/// GPUs a scheduling pass assigned to a local job.
+///
+/// The claim is taken when the GPUs are assigned and held until the job stops
+/// running, whatever NVML reports meanwhile, so no later pass can hand the
+/// same GPUs out. It stays unconfirmed until the job was launched; until then
+/// the job can still go back to the queue.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GpuClaim {
+    pub gpus: GpuIds,
+    pub sharing_mode: GpuSharingMode,
+    /// Whether the job was launched on its GPUs
+    pub confirmed: bool,
+}
+
+impl Scheduler {
+    pub fn gpu_claim(&self, job_id: u32) -> Option<&GpuClaim> {
+        self.gpu_claims.get(&job_id)
+    }
+
+    /// Record that job `job_id` was launched on its claimed GPUs.
+    pub fn confirm_gpu_claim(&mut self, job_id: u32) -> bool {
+        let Some(claim) = self.gpu_claims.get_mut(&job_id) else {
+            return false;
+        };
+        claim.confirmed = true;
+        true
+    }
+
+    /// Local jobs holding GPUs, with the GPUs and how they share them: every
+    /// claim, and running jobs with GPUs but no claim.
+    pub fn gpu_holders(&self) -> Vec<(u32, &GpuIds, GpuSharingMode)> {
+        let mut holders: Vec<_> = self
+            .gpu_claims
+            .iter()
+            .map(|(&job_id, claim)| (job_id, &claim.gpus, claim.sharing_mode))
+            .collect();
+        holders.extend(
+            self.job_runtimes
+                .iter()
+                .filter(|rt| {
+                    rt.state == JobState::Running
+                        && rt.node.is_none()
+                        && !self.gpu_claims.contains_key(&rt.id)
+                })
+                .filter_map(|rt| Some((rt.id, rt.gpu_ids.as_ref()?, rt.gpu_sharing_mode))),
+        );
+        holders
+    }
+
+    /// Send job `job_id` back to the queue before it was launched, e.g.
+    /// because something outside gflow took one of its GPUs meanwhile.
+    /// Launched jobs cannot be requeued this way.
+    pub fn requeue_unlaunched_job(&mut self, job_id: u32) -> Result<bool, ApiError> {
+        let requeued = self.try_transition(
+            job_id,
+            JobState::Queued,
+            Some(JobStateReason::WaitingForGpu),
+        )?;
+        if requeued {
+            if let Some(rt) = self.get_job_runtime_mut(job_id) {
+                rt.gpu_ids = None;
+                rt.cpu_ids = None;
+                rt.started_at = None;
+            }
+        }
+        Ok(requeued)
+    }
+
+    /// Whether job `job_id` holds GPUs it was not launched on yet.
+    pub(super) fn is_unlaunched(&self, job_id: u32) -> bool {
+        self.gpu_claims
+            .get(&job_id)
+            .is_some_and(|claim| !claim.confirmed)
+    }
+
+    pub(super) fn claim_gpus(&mut self, job_id: u32, gpus: GpuIds, sharing_mode: GpuSharingMode) {
+        if gpus.is_empty() {
+            return;
+        }
+        self.gpu_claims.insert(
+            job_id,
+            GpuClaim {
+                gpus,
+                sharing_mode,
+                confirmed: false,
+            },
+        );
+    }
+
+    pub(super) fn release_gpu_claim(&mut self, job_id: u32) {
+        self.gpu_claims.remove(&job_id);
+    }
+
+    /// Claims of the running jobs in a loaded state, which were launched
+    /// before the restart.
+    pub(super) fn rebuild_gpu_claims(&mut self) {
+        self.gpu_claims = self
+            .job_runtimes
+            .iter()
+            .filter(|rt| rt.state == JobState::Running && rt.node.is_none())
+            .filter_map(|rt| {
+                let gpus = rt.gpu_ids.clone().filter(|gpus| !gpus.is_empty())?;
+                let claim = GpuClaim {
+                    gpus,
+                    sharing_mode: rt.gpu_sharing_mode,
+                    confirmed: true,
+                };
+                Some((rt.id, claim))
+            })
+            .collect();
+    }
+}
@@ -0,0 +1,41 @@
+use super::*;
+use crate::core::job::JobGroup;
+use uuid::Uuid;
+
+impl Scheduler {
+    /// Get the sorted list of job IDs in a group.
+    ///
+    /// This is primarily intended for API/query paths to avoid scanning all jobs.
+    pub fn job_ids_by_group(&self, group_id: &Uuid) -> Option<&[u32]> {
+        self.group_jobs_index.get(group_id).map(|v| v.as_slice())
+    }
+
+    /// The rollup of a group's jobs, or `None` for an unknown group.
+    pub fn job_group(&self, group_id: &Uuid) -> Option<JobGroup> {
+        let job_ids = self.job_ids_by_group(group_id)?;
+        JobGroup::from_members(*group_id, self.group_members(job_ids))
+    }
+
+    /// The rollup of every group, oldest (lowest first job ID) first.
+    pub fn job_groups(&self) -> Vec<JobGroup> {
+        let mut groups: Vec<(u32, JobGroup)> = self
+            .group_jobs_index
+            .iter()
+            .filter_map(|(group_id, job_ids)| {
+                let group = JobGroup::from_members(*group_id, self.group_members(job_ids))?;
+                Some((job_ids[0], group))
+            })
+            .collect();
+        groups.sort_unstable_by_key(|(first_job_id, _)| *first_job_id);
+        groups.into_iter().map(|(_, group)| group).collect()
+    }
+
+    fn group_members<'a>(
+        &'a self,
+        job_ids: &'a [u32],
+    ) -> impl Iterator<Item = (&'a JobSpec, &'a JobRuntime)> + 'a {
+        job_ids
+            .iter()
+            .filter_map(|&id| Some((self.get_job_spec(id)?, self.get_job_runtime(id)?)))
+    }
+}
@@ -0,0 +1,227 @@
+//! Job indexes by user, state, project, tag and group.
+//!
+//! Submissions and transitions keep them up to date one job at a time, so no
+//! mutation costs a pass over the queue. Building them from scratch is only
+//! for a state loaded from disk, and for checking the incremental updates in
+//! tests.
+
+use super::*;
+use std::hash::Hash;
+
+/// The indexes derived from the jobs alone, as a from-scratch build sees them.
+struct JobIndexes {
+    user_jobs: HashMap<InternedStr, Vec<u32>>,
+    state_jobs: HashMap<JobState, BTreeSet<u32>>,
+    project_jobs: HashMap<CompactString, Vec<u32>>,
+    tag_jobs: HashMap<CompactString, Vec<u32>>,
+    group_jobs: HashMap<uuid::Uuid, Vec<u32>>,
+    group_running_count: HashMap<uuid::Uuid, usize>,
+}
+
+impl Scheduler {
+    /// Update the cached state->job_ids index.
+    ///
+    /// This keeps job IDs per state in ID order so API handlers can iterate them stably
+    /// without scanning all jobs. Moving a job costs O(log n) however long the queue is.
+    pub(super) fn update_state_jobs_index(
+        &mut self,
+        job_id: u32,
+        old_state: JobState,
+        new_state: JobState,
+    ) {
+        if old_state == new_state {
+            return;
+        }
+
+        if let Some(ids) = self.state_jobs_index.get_mut(&old_state) {
+            if ids.remove(&job_id) && ids.is_empty() {
+                self.state_jobs_index.remove(&old_state);
+            }
+        }
+
+        self.state_jobs_index
+            .entry(new_state)
+            .or_default()
+            .insert(job_id);
+    }
+
+    /// Update the cached project->job_ids index.
+    ///
+    /// This maintains sorted job IDs per project so API handlers can iterate in stable ID order
+    /// without scanning all jobs. Uses binary search to maintain sort order.
+    ///
+    /// Note: Projects are immutable after job submission, so this is only called when a job
+    /// is added.
+    pub(super) fn update_project_jobs_index(
+        &mut self,
+        job_id: u32,
+        old_project: Option<&CompactString>,
+        new_project: Option<&CompactString>,
+    ) {
+        if old_project == new_project {
+            return;
+        }
+
+        // Remove from old project
+        if let Some(old_proj) = old_project {
+            if let Some(v) = self.project_jobs_index.get_mut(old_proj) {
+                if let Ok(pos) = v.binary_search(&job_id) {
+                    v.remove(pos);
+                    if v.is_empty() {
+                        self.project_jobs_index.remove(old_proj);
+                    }
+                }
+            }
+        }
+
+        // Add to new project
+        if let Some(new_proj) = new_project {
+            let entry = self.project_jobs_index.entry(new_proj.clone()).or_default();
+            match entry.binary_search(&job_id) {
+                Ok(_) => {} // already present
+                Err(pos) => entry.insert(pos, job_id),
+            }
+        }
+    }
+
+    /// Update the cached tag->job_ids index for a job whose tags went from
+    /// `old_tags` to `new_tags`, keeping each list in ID order.
+    pub(super) fn update_tag_jobs_index(&mut self, job_id: u32, old_tags: &Tags, new_tags: &Tags) {
+        for tag in old_tags.iter().filter(|tag| !new_tags.contains(tag)) {
+            if let Some(v) = self.tag_jobs_index.get_mut(tag) {
+                if let Ok(pos) = v.binary_search(&job_id) {
+                    v.remove(pos);
+                }
+                if v.is_empty() {
+                    self.tag_jobs_index.remove(tag);
+                }
+            }
+        }
+        for tag in new_tags.iter().filter(|tag| !old_tags.contains(tag)) {
+            let entry = self.tag_jobs_index.entry(tag.into()).or_default();
+            if let Err(pos) = entry.binary_search(&job_id) {
+                entry.insert(pos, job_id);
+            }
+        }
+    }
+
+    pub(super) fn update_group_running_count(
+        &mut self,
+        group_id: Option<uuid::Uuid>,
+        old_state: JobState,
+        new_state: JobState,
+    ) {
+        let entering_running = new_state == JobState::Running && old_state != JobState::Running;
+        let leaving_running = old_state == JobState::Running && new_state != JobState::Running;
+
+        if !entering_running && !leaving_running {
+            return;
+        }
+
+        if let Some(group_id) = group_id {
+            if entering_running {
+                *self.group_running_count.entry(group_id).or_insert(0) += 1;
+            } else if leaving_running {
+                if let Some(count) = self.group_running_count.get_mut(&group_id) {
+                    *count = count.saturating_sub(1);
+                    if *count == 0 {
+                        self.group_running_count.remove(&group_id);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Runtimes of the jobs in `state`, in ID order, found through the state
+    /// index rather than a pass over every job.
+    pub(super) fn runtimes_in_state(&self, state: JobState) -> impl Iterator<Item = &JobRuntime> {
+        self.state_jobs_index
+            .get(&state)
+            .into_iter()
+            .flatten()
+            .filter_map(|&job_id| self.get_job_runtime(job_id))
+    }
+
+    /// Replace the indexes with ones built from the jobs. Only for a state
+    /// loaded from disk, or one whose runtimes were changed behind the
+    /// scheduler's back.
+    pub(super) fn rebuild_job_indexes(&mut self) {
+        let indexes = self.build_job_indexes();
+        self.user_jobs_index = indexes.user_jobs;
+        self.state_jobs_index = indexes.state_jobs;
+        self.project_jobs_index = indexes.project_jobs;
+        self.tag_jobs_index = indexes.tag_jobs;
+        self.group_jobs_index = indexes.group_jobs;
+        self.group_running_count = indexes.group_running_count;
+    }
+
+    fn build_job_indexes(&self) -> JobIndexes {
+        let jobs = || self.job_specs.iter().zip(&self.job_runtimes);
+        let mut state_jobs: HashMap<JobState, BTreeSet<u32>> = HashMap::new();
+        let mut group_running_count = HashMap::new();
+        for rt in &self.job_runtimes {
+            state_jobs.entry(rt.state).or_default().insert(rt.id);
+            if let (Some(group_id), JobState::Running) = (rt.group_id, rt.state) {
+                *group_running_count.entry(group_id).or_insert(0) += 1;
+            }
+        }
+        JobIndexes {
+            user_jobs: index_by(jobs().map(|(spec, rt)| (spec.submitted_by.clone(), rt.id))),
+            state_jobs,
+            project_jobs: index_by(
+                jobs().filter_map(|(spec, rt)| Some((spec.project.clone()?, rt.id))),
+            ),
+            tag_jobs: index_by(jobs().flat_map(|(spec, rt)| {
+                spec.tags
+                    .iter()
+                    .map(move |tag| (CompactString::from(tag), rt.id))
+            })),
+            group_jobs: index_by(jobs().filter_map(|(_, rt)| Some((rt.group_id?, rt.id)))),
+            group_running_count,
+        }
+    }
+
+    /// Panic if the incrementally maintained indexes differ from a fresh
+    /// build.
+    #[cfg(test)]
+    pub(super) fn assert_job_indexes_consistent(&self) {
+        let fresh = self.build_job_indexes();
+        assert_eq!(self.user_jobs_index, fresh.user_jobs, "user index drifted");
+        assert_eq!(
+            self.state_jobs_index, fresh.state_jobs,
+            "state index drifted"
+        );
+        assert_eq!(
+            self.project_jobs_index, fresh.project_jobs,
+            "project index drifted"
+        );
+        assert_eq!(self.tag_jobs_index, fresh.tag_jobs, "tag index drifted");
+        assert_eq!(
+            self.group_jobs_index, fresh.group_jobs,
+            "group index drifted"
+        );
+        assert_eq!(
+            self.group_running_count, fresh.group_running_count,
+            "group running counts drifted"
+        );
+    }
+}
+
+/// Job IDs per key, in the order given (ascending job ID). Keys are counted
+/// first so every list is allocated once, at its final size.
+fn index_by<K: Hash + Eq>(entries: impl Iterator<Item = (K, u32)> + Clone) -> HashMap<K, Vec<u32>> {
+    let mut counts: HashMap<K, usize> = HashMap::new();
+    for (key, _) in entries.clone() {
+        *counts.entry(key).or_insert(0) += 1;
+    }
+    let mut index: HashMap<K, Vec<u32>> = counts
+        .into_iter()
+        .map(|(key, count)| (key, Vec::with_capacity(count)))
+        .collect();
+    for (key, job_id) in entries {
+        if let Some(job_ids) = index.get_mut(&key) {
+            job_ids.push(job_id);
+        }
+    }
+    index
+}
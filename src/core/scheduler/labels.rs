@@ -0,0 +1,112 @@
+use super::*;
+use crate::utils::constraint::{validate_label, Constraint, Labels};
+
+/// Whose labels `gctl set-label` changes
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LabelTarget {
+    /// This machine, and so all of its GPUs
+    Machine,
+    /// One of this machine's GPUs, by index
+    Gpu(u32),
+    /// A remote node, by name
+    Node(String),
+}
+
+impl Scheduler {
+    /// Replace this machine's labels (`daemon.labels`) and the extra labels
+    /// of single GPUs (`daemon.gpu_labels`).
+    pub fn set_labels(&mut self, labels: Labels, gpu_labels: BTreeMap<u32, Labels>) {
+        self.labels = labels;
+        self.gpu_labels = gpu_labels;
+        self.bump_revision();
+    }
+
+    /// This machine's labels
+    pub fn labels(&self) -> &Labels {
+        &self.labels
+    }
+
+    /// A local GPU's labels: the machine's, plus its own on top.
+    pub fn gpu_labels(&self, index: u32) -> Labels {
+        let mut labels = self.labels.clone();
+        if let Some(own) = self.gpu_labels.get(&index) {
+            labels.extend(own.iter().map(|(k, v)| (k.clone(), v.clone())));
+        }
+        labels
+    }
+
+    /// Set and remove labels of `target`, and return its labels afterwards.
+    ///
+    /// Changes to a node are kept when its agent registers again; changes to
+    /// this machine last until the config is reloaded or the daemon restarts.
+    pub fn update_labels(
+        &mut self,
+        target: &LabelTarget,
+        set: Labels,
+        remove: &[String],
+    ) -> anyhow::Result<Labels> {
+        for (key, value) in &set {
+            validate_label(key, value)?;
+        }
+        self.bump_revision();
+        match target {
+            LabelTarget::Machine => {
+                self.labels.extend(set);
+                for key in remove {
+                    self.labels.remove(key);
+                }
+                Ok(self.labels.clone())
+            }
+            LabelTarget::Gpu(index) => {
+                if !self.has_gpu_index(*index) {
+                    anyhow::bail!("GPU {index} does not exist");
+                }
+                let own = self.gpu_labels.entry(*index).or_default();
+                own.extend(set);
+                for key in remove {
+                    own.remove(key);
+                }
+                if own.is_empty() {
+                    self.gpu_labels.remove(index);
+                }
+                Ok(self.gpu_labels(*index))
+            }
+            LabelTarget::Node(name) => {
+                let Some(node) = self.nodes.get_mut(name) else {
+                    anyhow::bail!("Node '{name}' is not registered");
+                };
+                let overrides = set
+                    .into_iter()
+                    .map(|(key, value)| (key, Some(value)))
+                    .chain(remove.iter().map(|key| (key.clone(), None)))
+                    .collect();
+                node.override_labels(overrides);
+                Ok(node.labels.clone())
+            }
+        }
+    }
+
+    /// Whether `gpus` GPUs matching `constraint` exist at all, on this
+    /// machine or on a node that is up, whether or not they are busy.
+    pub(super) fn has_matching_resources(&self, constraint: &Constraint, gpus: u32) -> bool {
+        let local = if gpus == 0 {
+            constraint.matches(&self.labels) as u32
+        } else {
+            self.gpu_slots
+                .values()
+                .filter(|slot| {
+                    self.allowed_gpu_indices
+                        .as_ref()
+                        .is_none_or(|allowed| allowed.contains(&slot.index))
+                })
+                .filter(|slot| constraint.matches(&self.gpu_labels(slot.index)))
+                .count() as u32
+        };
+        local >= gpus.max(1)
+            || self.nodes.values().any(|node| {
+                node.status == NodeStatus::Up
+                    && node.gpus >= gpus
+                    && constraint.matches(&node.labels)
+            })
+    }
+}
@@ -0,0 +1,144 @@
+use super::*;
+use std::time::SystemTime;
+
+/// What running jobs take on one remote node.
+#[derive(Debug, Default)]
+pub(super) struct NodeUsage {
+    gpus: HashSet<u32>,
+    memory_mb: u64,
+}
+
+impl Scheduler {
+    /// Add a node, or refresh one registering again as its heartbeat.
+    ///
+    /// Returns `true` when the node was not up before, so queued jobs may now
+    /// fit on it.
+    pub fn register_node(&mut self, registration: NodeRegistration, now: SystemTime) -> bool {
+        let previous = self.nodes.get(&registration.name);
+        let was_up = previous.is_some_and(|node| node.status == NodeStatus::Up);
+        let overrides = previous
+            .map(|node| node.label_overrides.clone())
+            .unwrap_or_default();
+        let mut node = Node::from_registration(registration, now);
+        node.override_labels(overrides);
+        self.nodes.insert(node.name.clone(), node);
+        !was_up
+    }
+
+    /// Mark up nodes whose heartbeat is overdue as down, and return their names.
+    pub fn mark_stale_nodes_down(&mut self, now: SystemTime) -> Vec<String> {
+        self.nodes
+            .values_mut()
+            .filter(|node| node.status == NodeStatus::Up && node.is_stale(now))
+            .map(|node| {
+                node.status = NodeStatus::Down;
+                node.name.clone()
+            })
+            .collect()
+    }
+
+    pub fn nodes(&self) -> &BTreeMap<String, Node> {
+        &self.nodes
+    }
+
+    pub fn get_node(&self, name: &str) -> Option<&Node> {
+        self.nodes.get(name)
+    }
+
+    /// Running jobs placed on nodes that are down.
+    pub fn jobs_on_down_nodes(&self) -> Vec<u32> {
+        self.job_runtimes
+            .iter()
+            .filter(|rt| rt.state == JobState::Running)
+            .filter(|rt| {
+                rt.node
+                    .as_deref()
+                    .and_then(|name| self.nodes.get(name))
+                    .is_some_and(|node| node.status == NodeStatus::Down)
+            })
+            .map(|rt| rt.id)
+            .collect()
+    }
+
+    pub(super) fn node_usage(&self) -> HashMap<String, NodeUsage> {
+        let mut usage: HashMap<String, NodeUsage> = HashMap::new();
+        if self.nodes.is_empty() {
+            return usage;
+        }
+        for rt in self
+            .job_runtimes
+            .iter()
+            .filter(|rt| rt.state == JobState::Running)
+        {
+            let Some(node) = rt.node.as_ref() else {
+                continue;
+            };
+            let entry = usage.entry(node.clone()).or_default();
+            entry.gpus.extend(rt.gpu_ids.iter().flatten().copied());
+            entry.memory_mb += rt.memory_limit_mb.unwrap_or(0);
+        }
+        usage
+    }
+
+    /// Start `job_id` on the first up node, by name, with the labels its
+    /// constraint asks for and enough idle GPUs and memory, and mark it Running.
+    ///
+    /// Remote jobs always hold their GPUs exclusively, and the head's GPU
+    /// reservations and `allowed_gpu_indices` do not apply to nodes.
+    pub(super) fn place_on_node(
+        &mut self,
+        job_id: u32,
+        usage: &mut HashMap<String, NodeUsage>,
+    ) -> bool {
+        let Some(rt) = self.get_job_runtime(job_id) else {
+            return false;
+        };
+        let needed_gpus = rt.gpus as usize;
+        let needed_memory = rt.memory_limit_mb.unwrap_or(0);
+        let constraint = self
+            .get_job_spec(job_id)
+            .and_then(|spec| spec.constraint.as_ref());
+
+        let placement = self
+            .nodes
+            .values()
+            .filter(|node| node.status == NodeStatus::Up)
+            .filter(|node| constraint.is_none_or(|constraint| constraint.matches(&node.labels)))
+            .find_map(|node| {
+                let used = usage.get(&node.name);
+                let used_memory = used.map_or(0, |used| used.memory_mb);
+                if used_memory + needed_memory > node.total_memory_mb {
+                    return None;
+                }
+                let idle: GpuIds = (0..node.gpus)
+                    .filter(|gpu| used.is_none_or(|used| !used.gpus.contains(gpu)))
+                    .take(needed_gpus)
+                    .collect();
+                (idle.len() == needed_gpus).then(|| (node.name.clone(), idle))
+            });
+        let Some((name, gpus)) = placement else {
+            return false;
+        };
+
+        if let Some(rt) = self.get_job_runtime_mut(job_id) {
+            rt.gpu_ids = Some(gpus.clone());
+            rt.cpu_ids = None;
+            rt.node = Some(name.clone());
+        }
+        let transitioned = self
+            .transition_job_state(job_id, JobState::Running, None)
+            .unwrap_or(false);
+        if !transitioned {
+            if let Some(rt) = self.get_job_runtime_mut(job_id) {
+                rt.gpu_ids = None;
+                rt.node = None;
+            }
+            return false;
+        }
+
+        let entry = usage.entry(name).or_default();
+        entry.gpus.extend(gpus);
+        entry.memory_mb += needed_memory;
+        true
+    }
+}
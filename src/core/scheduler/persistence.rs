@@ -71,6 +71,10 @@ struct SchedulerSerde {
     pub(crate) allowed_gpu_indices: Option<Vec<u32>>,
     pub reservations: Vec<GpuReservation>,
     pub next_reservation_id: u32,
+    pub runtime_stats: RuntimeStats,
+    pub nodes: BTreeMap<String, Node>,
+    pub submissions: HashMap<uuid::Uuid, SubmissionRecord>,
+    pub artifacts: BTreeMap<u32, Vec<JobArtifact>>,
 }
 
 #[derive(Deserialize)]
@@ -95,6 +99,10 @@ impl Default for SchedulerSerde {
             allowed_gpu_indices: None,
             reservations: Vec::new(),
             next_reservation_id: 1,
+            runtime_stats: RuntimeStats::default(),
+            nodes: BTreeMap::new(),
+            submissions: HashMap::new(),
+            artifacts: BTreeMap::new(),
         }
     }
 }
@@ -109,6 +117,7 @@ impl Default for Scheduler {
             gpu_slots: HashMap::new(),
             total_memory_mb: 16 * 1024,
             available_memory_mb: 16 * 1024,
+            total_cpus: 0,
             state_path: PathBuf::from("state.json"),
             next_job_id: 1,
             allowed_gpu_indices: None,
@@ -117,12 +126,28 @@ impl Default for Scheduler {
             user_jobs_index: HashMap::new(),
             state_jobs_index: HashMap::new(),
             project_jobs_index: HashMap::new(),
+            tag_jobs_index: HashMap::new(),
             dependents_graph: HashMap::new(),
             dependency_runtimes: Vec::new(),
             ready_heap: std::collections::BinaryHeap::new(),
             group_running_count: HashMap::new(),
+            group_jobs_index: HashMap::new(),
+            string_pool: StringPool::default(),
+            revision: 0,
             reservations: Vec::new(),
             next_reservation_id: 1,
+            runtime_stats: RuntimeStats::default(),
+            nodes: BTreeMap::new(),
+            submissions: HashMap::new(),
+            artifacts: BTreeMap::new(),
+            labels: Labels::new(),
+            gpu_labels: BTreeMap::new(),
+            transitions: Vec::new(),
+            disk_guard: DiskGuard::default(),
+            pass_budget: None,
+            pass_backlog: Vec::new(),
+            last_pass: SchedulingPass::default(),
+            gpu_claims: BTreeMap::new(),
         }
     }
 }
@@ -189,6 +214,7 @@ impl<'de> Deserialize<'de> for Scheduler {
             gpu_slots: HashMap::new(),
             total_memory_mb: 16 * 1024,
             available_memory_mb: 16 * 1024,
+            total_cpus: 0,
             state_path: persisted.state_path,
             next_job_id: persisted.next_job_id,
             allowed_gpu_indices: persisted.allowed_gpu_indices,
@@ -197,12 +223,28 @@ impl<'de> Deserialize<'de> for Scheduler {
             user_jobs_index: HashMap::new(),
             state_jobs_index: HashMap::new(),
             project_jobs_index: HashMap::new(),
+            tag_jobs_index: HashMap::new(),
             dependents_graph: HashMap::new(),
             dependency_runtimes: Vec::new(),
             ready_heap: std::collections::BinaryHeap::new(),
             group_running_count: HashMap::new(),
+            group_jobs_index: HashMap::new(),
+            string_pool: StringPool::default(),
+            revision: 0,
             reservations: persisted.reservations,
             next_reservation_id: persisted.next_reservation_id,
+            runtime_stats: persisted.runtime_stats,
+            nodes: persisted.nodes,
+            submissions: persisted.submissions,
+            artifacts: persisted.artifacts,
+            labels: Labels::new(),
+            gpu_labels: BTreeMap::new(),
+            transitions: Vec::new(),
+            disk_guard: DiskGuard::default(),
+            pass_budget: None,
+            pass_backlog: Vec::new(),
+            last_pass: SchedulingPass::default(),
+            gpu_claims: BTreeMap::new(),
         };
 
         Ok(scheduler)
@@ -224,7 +266,19 @@ impl Scheduler {
         self.allowed_gpu_indices = loaded.allowed_gpu_indices;
         self.reservations = std::mem::take(&mut loaded.reservations);
         self.next_reservation_id = loaded.next_reservation_id;
+        self.runtime_stats = std::mem::take(&mut loaded.runtime_stats);
+        // Agents kept running while the daemon was away; give them a full
+        // timeout to check in again before their jobs are given up on.
+        let now = std::time::SystemTime::now();
+        self.nodes = std::mem::take(&mut loaded.nodes);
+        for node in self.nodes.values_mut() {
+            node.last_heartbeat = now;
+        }
+        self.submissions = std::mem::take(&mut loaded.submissions);
+        self.artifacts = std::mem::take(&mut loaded.artifacts);
 
         self.state_path = state_path;
+        // The revision carries on from this scheduler's rather than the loaded one's.
+        self.bump_revision();
     }
 }
@@ -0,0 +1,160 @@
+use super::*;
+
+/// A running job requeued so a higher-priority job could have its GPUs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Preemption {
+    pub victim: u32,
+    pub by: u32,
+}
+
+impl Scheduler {
+    /// Like [`Scheduler::prepare_jobs_for_execution`], but queued jobs with
+    /// at least `threshold` priority that are still waiting for GPUs may
+    /// preempt preemptible running jobs of lower priority.
+    ///
+    /// Victims are already back in Queued when this returns; the caller must
+    /// stop their workloads before executing the returned jobs. They are kept
+    /// out of this pass, so they cannot restart until the next one.
+    pub fn prepare_jobs_with_preemption(&mut self, threshold: u8) -> (Vec<Job>, Vec<Preemption>) {
+        let mut jobs = self.prepare_jobs_for_execution();
+
+        let mut urgent: Vec<(u8, u32)> = self
+            .job_runtimes
+            .iter()
+            .filter(|rt| {
+                rt.state == JobState::Queued
+                    && rt.priority >= threshold
+                    && rt.gpus > 0
+                    && rt
+                        .reason
+                        .as_deref()
+                        .is_some_and(JobStateReason::is_gpu_wait)
+            })
+            .map(|rt| (rt.priority, rt.id))
+            .collect();
+        if urgent.is_empty() {
+            return (jobs, Vec::new());
+        }
+        urgent.sort_unstable_by_key(|&(priority, id)| (std::cmp::Reverse(priority), id));
+
+        let mut preemptions = Vec::new();
+        let mut claimed = HashSet::new();
+        for (_, job_id) in urgent {
+            let Some((victims, gpus)) = self.select_preemption_victims(job_id, &claimed) else {
+                continue;
+            };
+            claimed.extend(gpus);
+            for victim in victims {
+                if self.preempt_job(victim, job_id) {
+                    tracing::info!(
+                        job_id = victim,
+                        by = job_id,
+                        "Job preempted by job {}",
+                        job_id
+                    );
+                    preemptions.push(Preemption { victim, by: job_id });
+                }
+            }
+        }
+
+        if !preemptions.is_empty() {
+            jobs.extend(self.prepare_jobs_for_execution());
+            for preemption in &preemptions {
+                self.enqueue_if_ready(preemption.victim);
+            }
+        }
+        (jobs, preemptions)
+    }
+
+    /// Running jobs to requeue so `job_id` can start, and the GPUs that frees.
+    ///
+    /// Only preemptible jobs with a strictly lower priority qualify. The
+    /// lowest priority goes first and, among equals, the most recently
+    /// started, so the least work is lost. GPUs in `claimed` are promised to
+    /// another job. Returns `None` if all candidates together are not enough.
+    pub(super) fn select_preemption_victims(
+        &self,
+        job_id: u32,
+        claimed: &HashSet<u32>,
+    ) -> Option<(Vec<u32>, Vec<u32>)> {
+        let (spec, rt) = self.get_job_parts(job_id)?;
+        let needed = rt.gpus as usize;
+        let user = spec.submitted_by.clone();
+
+        let running: Vec<&JobRuntime> = self
+            .job_runtimes
+            .iter()
+            .filter(|rt| rt.state == JobState::Running && rt.node.is_none() && rt.gpu_ids.is_some())
+            .collect();
+        let occupied: HashSet<u32> = running
+            .iter()
+            .flat_map(|rt| rt.gpu_ids.iter().flatten().copied())
+            .collect();
+
+        let allowed = |gpu: &u32| {
+            !claimed.contains(gpu)
+                && self
+                    .allowed_gpu_indices
+                    .as_ref()
+                    .is_none_or(|allowed| allowed.contains(gpu))
+                && spec
+                    .constraint
+                    .as_ref()
+                    .is_none_or(|constraint| constraint.matches(&self.gpu_labels(*gpu)))
+        };
+
+        let mut candidates: Vec<&JobRuntime> = running
+            .iter()
+            .copied()
+            .filter(|victim| {
+                victim.priority < rt.priority
+                    && self
+                        .get_job_spec(victim.id)
+                        .is_some_and(|spec| spec.preemptible)
+                    && victim.gpu_ids.iter().flatten().any(allowed)
+            })
+            .collect();
+        candidates.sort_by_key(|victim| {
+            (
+                victim.priority,
+                std::cmp::Reverse(victim.started_at),
+                std::cmp::Reverse(victim.id),
+            )
+        });
+
+        let idle: Vec<u32> = self
+            .get_available_gpu_slots()
+            .into_iter()
+            .filter(|gpu| !occupied.contains(gpu) && allowed(gpu))
+            .collect();
+
+        let mut victims = Vec::new();
+        for candidate in candidates {
+            victims.push(candidate.id);
+
+            // A GPU is only freed once no remaining running job holds it.
+            let freed: HashSet<u32> = running
+                .iter()
+                .filter(|rt| victims.contains(&rt.id))
+                .flat_map(|rt| rt.gpu_ids.iter().flatten().copied())
+                .filter(|gpu| {
+                    allowed(gpu)
+                        && !running.iter().any(|rt| {
+                            !victims.contains(&rt.id)
+                                && rt.gpu_ids.as_ref().is_some_and(|ids| ids.contains(gpu))
+                        })
+                })
+                .collect();
+
+            let mut free = idle.clone();
+            free.extend(freed);
+            free.sort_unstable();
+            let usable = self.filter_usable_gpus(&user, &free);
+            if usable.len() >= needed && self.check_job_respects_reservations(&user, rt.gpus, &free)
+            {
+                return Some((victims, usable.into_iter().take(needed).collect()));
+            }
+        }
+        None
+    }
+}
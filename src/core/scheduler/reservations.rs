@@ -1,5 +1,9 @@
 use super::*;
 
+/// How far past the latest existing reservation a recurring reservation is
+/// checked for conflicts; every weekly pattern repeats within it.
+const RECURRENCE_CHECK_SPAN: std::time::Duration = std::time::Duration::from_secs(8 * 24 * 3600);
+
 impl Scheduler {
     pub fn create_reservation(
         &mut self,
@@ -7,15 +11,28 @@ impl Scheduler {
         gpu_spec: crate::core::reservation::GpuSpec,
         start_time: std::time::SystemTime,
         duration: std::time::Duration,
+    ) -> anyhow::Result<u32> {
+        self.create_reservation_with(user, gpu_spec, start_time, duration, Default::default())
+    }
+
+    /// Create a reservation that may recur or be a maintenance window.
+    pub fn create_reservation_with(
+        &mut self,
+        user: CompactString,
+        gpu_spec: crate::core::reservation::GpuSpec,
+        mut start_time: std::time::SystemTime,
+        duration: std::time::Duration,
+        options: crate::core::reservation::ReservationOptions,
     ) -> anyhow::Result<u32> {
         use crate::core::conflict;
-        use crate::core::reservation::{GpuReservation, ReservationStatus};
+        use crate::core::reservation::{GpuReservation, ReservationKind};
 
         // Validate GPU spec
         let total_gpus = self.gpu_slots_count() as u32;
         let gpu_count = gpu_spec.count();
 
-        if gpu_count == 0 {
+        // A maintenance window still applies on a node without GPUs.
+        if gpu_count == 0 && options.kind != ReservationKind::Maintenance {
             anyhow::bail!("GPU count must be greater than 0");
         }
         if gpu_count > total_gpus {
@@ -45,10 +62,49 @@ impl Scheduler {
             anyhow::bail!("Start time cannot be in the past");
         }
 
-        // Check for conflicts using pure functions
-        let end_time = start_time + duration;
-        let state = conflict::collect_reservation_state(&self.reservations, start_time, end_time);
-        conflict::check_reservation_conflict(&gpu_spec, &state, total_gpus)?;
+        // Check for conflicts using pure functions, once per occurrence
+        let occurrences = match &options.recurrence {
+            None => vec![start_time],
+            Some(recurrence) => {
+                if recurrence.weekdays.is_empty() {
+                    anyhow::bail!("A recurring reservation needs at least one weekday");
+                }
+                if duration > recurrence.min_gap() {
+                    anyhow::bail!(
+                        "Occurrences would overlap: a reservation repeating on {} can last at most {}h",
+                        recurrence,
+                        recurrence.min_gap().as_secs() / 3600
+                    );
+                }
+                start_time = recurrence
+                    .next_start(start_time, start_time)
+                    .ok_or_else(|| {
+                        anyhow::anyhow!("The recurrence ends before its first occurrence")
+                    })?;
+
+                let check_until = recurrence.until.unwrap_or_else(|| {
+                    let latest = self
+                        .reservations
+                        .iter()
+                        .map(|r| r.start_time)
+                        .fold(start_time, std::cmp::max);
+                    latest + RECURRENCE_CHECK_SPAN
+                });
+                std::iter::successors(Some(start_time), |&previous| {
+                    recurrence.next_start(start_time, previous + std::time::Duration::from_secs(1))
+                })
+                .take_while(|&occurrence| occurrence < check_until)
+                .collect()
+            }
+        };
+        for occurrence in occurrences {
+            let state = conflict::collect_reservation_state(
+                &self.reservations,
+                occurrence,
+                occurrence + duration,
+            );
+            conflict::check_reservation_conflict(&gpu_spec, &state, total_gpus)?;
+        }
 
         // Create reservation
         let id = self.next_reservation_id;
@@ -65,6 +121,8 @@ impl Scheduler {
             status: ReservationStatus::Pending,
             created_at: now,
             cancelled_at: None,
+            kind: options.kind,
+            recurrence: options.recurrence,
         };
 
         self.reservations.push(reservation);
@@ -254,6 +312,40 @@ impl Scheduler {
         job_gpu_count <= available_for_unreserved && job_gpu_count <= usable_gpus.len() as u32
     }
 
+    /// Start of the current or next maintenance window, if any is scheduled.
+    pub(super) fn next_maintenance_start(
+        &self,
+        now: std::time::SystemTime,
+    ) -> Option<std::time::SystemTime> {
+        use crate::core::reservation::ReservationKind;
+
+        self.reservations
+            .iter()
+            .filter(|r| {
+                r.kind == ReservationKind::Maintenance
+                    && matches!(
+                        r.status,
+                        ReservationStatus::Pending | ReservationStatus::Active
+                    )
+            })
+            .filter_map(|r| r.occurrence_ending_after(now))
+            .min()
+    }
+
+    /// Owner of the reservation keeping a job of `job_user` waiting: the other
+    /// user whose active reservation ends first. `None` when `job_user` holds
+    /// an active reservation, since then their own reservation is too small.
+    pub(super) fn blocking_reservation_owner(&self, job_user: &str) -> Option<CompactString> {
+        let active_reservations = self.get_active_reservations();
+        if active_reservations.iter().any(|r| r.user == job_user) {
+            return None;
+        }
+        active_reservations
+            .into_iter()
+            .min_by_key(|r| r.end_time())
+            .map(|r| r.user.clone())
+    }
+
     /// Filter available GPUs to only include those usable by the given user
     /// considering active reservations
     pub(super) fn filter_usable_gpus(&self, job_user: &str, available_gpus: &[u32]) -> Vec<u32> {
@@ -1,5 +1,17 @@
 use super::*;
 
+/// What one [`Scheduler::prepare_jobs_for_execution`] pass did
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SchedulingPass {
+    pub duration: Duration,
+    /// Queued jobs looked at
+    pub examined: usize,
+    /// Jobs it picked to start
+    pub started: usize,
+    /// Whether it ran out of time before looking at every queued job
+    pub yielded: bool,
+}
+
 impl Scheduler {
     pub fn calculate_time_bonus(time_limit: &Option<Duration>) -> u32 {
         match time_limit {
@@ -24,7 +36,7 @@ impl Scheduler {
     /// from the same physical pool as `memory_limit_mb`, so we combine them.
     /// In split-memory mode (NVIDIA), they are tracked separately and only
     /// `memory_limit_mb` counts against the host pool.
-    fn effective_host_memory_mb(
+    pub(super) fn effective_host_memory_mb(
         unified_memory: bool,
         memory_limit_mb: Option<u64>,
         gpu_memory_limit_mb: Option<u64>,
@@ -38,24 +50,47 @@ impl Scheduler {
         }
     }
 
+    /// Host memory held by jobs running on this machine.
+    pub(super) fn job_host_memory_mb(&self, rt: &JobRuntime) -> u64 {
+        if rt.node.is_some() {
+            return 0;
+        }
+        Self::effective_host_memory_mb(
+            self.unified_memory,
+            rt.memory_limit_mb,
+            rt.gpu_memory_limit_mb,
+            rt.gpus,
+        )
+    }
+
+    /// Memory used by running jobs, counted afresh.
+    pub fn used_memory_mb(&self) -> u64 {
+        self.runtimes_in_state(JobState::Running)
+            .map(|rt| self.job_host_memory_mb(rt))
+            .sum()
+    }
+
     /// Refresh available memory by calculating memory used by running jobs
     pub fn refresh_available_memory(&mut self) {
-        let unified = self.unified_memory;
-        let memory_used: u64 = self
-            .job_runtimes
-            .iter()
-            .filter(|rt| rt.state == JobState::Running)
-            .map(|rt| {
-                Self::effective_host_memory_mb(
-                    unified,
-                    rt.memory_limit_mb,
-                    rt.gpu_memory_limit_mb,
-                    rt.gpus,
-                )
-            })
-            .sum();
+        self.available_memory_mb = self.total_memory_mb.saturating_sub(self.used_memory_mb());
+    }
 
-        self.available_memory_mb = self.total_memory_mb.saturating_sub(memory_used);
+    /// Recompute available memory from the running jobs, and log if the
+    /// count kept by job transitions had drifted from it. Returns the drift
+    /// in MB: how much more memory is available than was counted.
+    pub fn reconcile_memory(&mut self) -> i64 {
+        let counted = self.available_memory_mb;
+        self.refresh_available_memory();
+        let drift = self.available_memory_mb as i64 - counted as i64;
+        if drift != 0 {
+            tracing::warn!(
+                counted_mb = counted,
+                actual_mb = self.available_memory_mb,
+                drift_mb = drift,
+                "Available memory had drifted from the running jobs; corrected"
+            );
+        }
+        drift
     }
 
     fn current_gpu_occupancy(&self) -> (HashMap<u32, usize>, HashSet<u32>, HashMap<u32, u64>) {
@@ -63,20 +98,15 @@ impl Scheduler {
         let mut exclusive_gpu_occupancy = HashSet::new();
         let mut shared_gpu_memory_usage_mb = HashMap::new();
 
-        for rt in self
-            .job_runtimes
-            .iter()
-            .filter(|rt| rt.state == JobState::Running)
-        {
-            let Some(gpu_ids) = rt.gpu_ids.as_ref() else {
-                continue;
-            };
-
-            match rt.gpu_sharing_mode {
+        for (job_id, gpu_ids, sharing_mode) in self.gpu_holders() {
+            match sharing_mode {
                 GpuSharingMode::Shared => {
+                    let gpu_memory_limit_mb = self
+                        .get_job_runtime(job_id)
+                        .and_then(|rt| rt.gpu_memory_limit_mb);
                     for &gpu in gpu_ids {
                         *shared_gpu_occupancy.entry(gpu).or_insert(0) += 1;
-                        if let Some(limit_mb) = rt.gpu_memory_limit_mb {
+                        if let Some(limit_mb) = gpu_memory_limit_mb {
                             *shared_gpu_memory_usage_mb.entry(gpu).or_insert(0) += limit_mb;
                         }
                     }
@@ -97,8 +127,12 @@ impl Scheduler {
     }
 
     fn set_job_reason(&mut self, job_id: u32, reason: Option<JobStateReason>) {
-        if let Some(rt) = self.get_job_runtime_mut(job_id) {
+        let Some(rt) = self.get_job_runtime_mut(job_id) else {
+            return;
+        };
+        if rt.reason.as_deref() != reason.as_ref() {
             rt.reason = reason.map(Box::new);
+            self.bump_revision();
         }
     }
 
@@ -126,11 +160,12 @@ impl Scheduler {
     /// scheduler.handle_execution_failures(&results);
     /// ```
     pub fn prepare_jobs_for_execution(&mut self) -> Vec<Job> {
+        let pass_started = std::time::Instant::now();
         // Update reservation statuses first
         self.update_reservation_statuses();
-        // Recompute host RAM from currently running jobs before making new decisions.
-        // Without this, finished/cancelled jobs can leave stale memory pressure behind.
-        self.refresh_available_memory();
+        // Check the memory count against the running jobs before making new
+        // decisions; every pass, including the periodic one, reconciles it.
+        self.reconcile_memory();
 
         let mut job_ids_to_execute = Vec::new();
         let available_gpus = self.get_available_gpu_slots();
@@ -142,6 +177,8 @@ impl Scheduler {
             .filter_map(|slot| slot.total_memory_mb.map(|total_mb| (slot.index, total_mb)))
             .collect();
 
+        let mut node_usage = self.node_usage();
+
         let mut runnable_jobs = Vec::new();
         let mut seen_ready_jobs = HashSet::new();
         while let Some(entry) = self.ready_heap.pop() {
@@ -165,18 +202,75 @@ impl Scheduler {
 
             runnable_jobs.push(entry.job_id);
         }
+        self.put_backlog_first(&mut runnable_jobs);
 
         // Allocate resources for runnable jobs
         let mut available_memory = self.available_memory_mb;
-        for job_id in runnable_jobs {
+        let mut free_cpus = self.free_cpu_ids();
+        let now = std::time::SystemTime::now();
+        let next_maintenance = self.next_maintenance_start(now);
+        let mut free_disk_by_dir = HashMap::new();
+        let mut examined = 0;
+        let mut runnable_jobs = runnable_jobs.into_iter();
+        while let Some(job_id) = runnable_jobs.next() {
+            // Out of time: leave the rest queued for the next pass, which
+            // looks at them first. At least one job is looked at per pass.
+            if examined > 0
+                && self
+                    .pass_budget
+                    .is_some_and(|budget| pass_started.elapsed() >= budget)
+            {
+                let backlog: Vec<u32> = std::iter::once(job_id)
+                    .chain(runnable_jobs.by_ref())
+                    .collect();
+                for &job_id in &backlog {
+                    self.enqueue_if_ready(job_id);
+                }
+                self.pass_backlog = backlog;
+                break;
+            }
+            examined += 1;
+
             let Some(idx) = job_id.checked_sub(1).map(|x| x as usize) else {
                 continue;
             };
 
+            // Nothing starts during maintenance, and a job with a time limit
+            // only starts if it would be done before the window opens.
+            if let Some(window) = next_maintenance {
+                let time_limit = self.job_runtimes.get(idx).and_then(|rt| rt.time_limit);
+                if window <= now || time_limit.is_some_and(|limit| now + limit > window) {
+                    self.set_job_reason(job_id, Some(JobStateReason::Maintenance));
+                    self.enqueue_if_ready(job_id);
+                    continue;
+                }
+            }
+
+            // A job whose constraint nothing could ever satisfy just waits.
+            let constraint = self
+                .job_specs
+                .get(idx)
+                .and_then(|spec| spec.constraint.clone());
+            if let Some(ref constraint) = constraint {
+                let gpus = self.job_runtimes.get(idx).map_or(0, |rt| rt.gpus);
+                if !self.has_matching_resources(constraint, gpus) {
+                    self.set_job_reason(job_id, Some(JobStateReason::NoMatchingResources));
+                    self.enqueue_if_ready(job_id);
+                    continue;
+                }
+            }
+
+            // Keep the job waiting rather than let it fill up a disk.
+            if let Some(reason) = self.insufficient_disk(idx, &mut free_disk_by_dir) {
+                self.set_job_reason(job_id, Some(reason));
+                self.enqueue_if_ready(job_id);
+                continue;
+            }
+
             // First, do immutable checks using only runtime (hot data)
             let (
                 has_enough_memory,
-                within_group_limit,
+                group_limit,
                 respects_reservations,
                 required_memory,
                 job_user,
@@ -204,7 +298,7 @@ impl Scheduler {
                     self.check_job_respects_reservations(&job_user, rt.gpus, &available_gpus);
 
                 // Check group concurrency limit using runtime data only
-                let within_group_limit = if let Some(ref group_id) = rt.group_id {
+                let group_limit = if let Some(ref group_id) = rt.group_id {
                     if let Some(max_concurrent) = rt.max_concurrent {
                         // Use O(1) index lookup
                         let running_in_group =
@@ -218,20 +312,23 @@ impl Scheduler {
                                 running_in_group,
                                 max_concurrent
                             );
-                            false
+                            Some(JobStateReason::GroupLimit {
+                                running: running_in_group as u32,
+                                max: max_concurrent as u32,
+                            })
                         } else {
-                            true
+                            None
                         }
                     } else {
-                        true // No limit specified
+                        None // No limit specified
                     }
                 } else {
-                    true // Not part of a group
+                    None // Not part of a group
                 };
 
                 (
                     has_enough_memory,
-                    within_group_limit,
+                    group_limit,
                     respects_reservations,
                     required_memory,
                     job_user,
@@ -242,9 +339,13 @@ impl Scheduler {
             } else {
                 continue;
             };
+            let requested_cpus = self.job_runtimes.get(idx).and_then(|rt| rt.cpus);
+            let cpus_for_job = self.assign_cpus(requested_cpus, &free_cpus);
+            let has_enough_cpus = cpus_for_job.is_some();
+            let within_group_limit = group_limit.is_none();
 
             // Now allocate resources if all checks pass
-            if has_enough_memory && within_group_limit && respects_reservations {
+            if has_enough_memory && has_enough_cpus && within_group_limit && respects_reservations {
                 // Filter out GPUs that are reserved by other users
                 let mut usable_gpus = self.filter_usable_gpus(&job_user, &available_gpus);
                 self.reorder_usable_gpus(job_id, &mut usable_gpus);
@@ -277,11 +378,30 @@ impl Scheduler {
                                 && shared_gpu_occupancy.get(gpu).copied().unwrap_or(0) == 0
                         }
                     })
+                    .filter(|&gpu| {
+                        constraint
+                            .as_ref()
+                            .is_none_or(|constraint| constraint.matches(&self.gpu_labels(gpu)))
+                    })
                     .collect();
-                let has_enough_gpus = requested_gpu_count as usize <= compatible_gpus.len();
+                // A CPU-only job is matched against the machine's labels.
+                let fits_here = requested_gpu_count > 0
+                    || constraint
+                        .as_ref()
+                        .is_none_or(|constraint| constraint.matches(&self.labels));
+                let has_enough_gpus =
+                    fits_here && requested_gpu_count as usize <= compatible_gpus.len();
 
                 if !has_enough_gpus {
-                    self.set_job_reason(job_id, Some(JobStateReason::WaitingForGpu));
+                    if self.place_on_node(job_id, &mut node_usage) {
+                        job_ids_to_execute.push(job_id);
+                        continue;
+                    }
+                    let reason = JobStateReason::WaitingForGpus {
+                        need: requested_gpu_count,
+                        free: compatible_gpus.len() as u32,
+                    };
+                    self.set_job_reason(job_id, Some(reason));
                     self.enqueue_if_ready(job_id);
                     continue;
                 }
@@ -293,7 +413,10 @@ impl Scheduler {
                 let mut allocated_gpus = None;
                 if let Some(rt) = self.job_runtimes.get_mut(idx) {
                     rt.gpu_ids = Some(gpus_for_job.clone());
-                    allocated_gpus = Some(gpus_for_job);
+                    rt.cpu_ids = cpus_for_job.clone().flatten();
+                    rt.node = None;
+                    allocated_gpus = Some(gpus_for_job.clone());
+                    self.claim_gpus(job_id, gpus_for_job, gpu_sharing_mode);
                 }
 
                 if let Some(ref allocated) = allocated_gpus {
@@ -323,10 +446,11 @@ impl Scheduler {
                     // Collect job ID instead of cloning immediately
                     job_ids_to_execute.push(job_id);
 
-                    // Update memory tracking after releasing the borrow
+                    // The transition took the memory; keep this pass's count in step.
                     available_memory = available_memory.saturating_sub(required_memory);
-                    self.available_memory_mb =
-                        self.available_memory_mb.saturating_sub(required_memory);
+                    if let Some(Some(cpus)) = &cpus_for_job {
+                        free_cpus.retain(|cpu| !cpus.contains(cpu));
+                    }
                 } else {
                     // Roll back provisional GPU allocation if we couldn't transition to Running.
                     if let Some(allocated) = allocated_gpus {
@@ -361,10 +485,15 @@ impl Scheduler {
                     }
                     if let Some(rt) = self.job_runtimes.get_mut(idx) {
                         rt.gpu_ids = None;
+                        rt.cpu_ids = None;
                     }
+                    self.release_gpu_claim(job_id);
                     self.set_job_reason(job_id, Some(JobStateReason::WaitingForResources));
                     self.enqueue_if_ready(job_id);
                 }
+            } else if within_group_limit && self.place_on_node(job_id, &mut node_usage) {
+                // Short on memory or reserved GPUs here, but a node has room.
+                job_ids_to_execute.push(job_id);
             } else if !has_enough_memory {
                 self.set_job_reason(job_id, Some(JobStateReason::WaitingForMemory));
                 self.enqueue_if_ready(job_id);
@@ -376,11 +505,27 @@ impl Scheduler {
                         available_memory
                     );
                 }
-            } else if !within_group_limit {
-                self.set_job_reason(job_id, Some(JobStateReason::WaitingForResources));
+            } else if !has_enough_cpus {
+                self.set_job_reason(job_id, Some(JobStateReason::WaitingForCpu));
+                self.enqueue_if_ready(job_id);
+                tracing::debug!(
+                    "Job {} waiting for CPU cores: needs {}, {} free",
+                    job_id,
+                    requested_cpus.unwrap_or(0),
+                    free_cpus.len()
+                );
+            } else if let Some(reason) = group_limit {
+                self.set_job_reason(job_id, Some(reason));
                 self.enqueue_if_ready(job_id);
             } else if !respects_reservations {
-                self.set_job_reason(job_id, Some(JobStateReason::WaitingForGpu));
+                let reason = match self.blocking_reservation_owner(&job_user) {
+                    Some(owner) => JobStateReason::ReservedForUser(owner),
+                    None => JobStateReason::WaitingForGpus {
+                        need: requested_gpu_count,
+                        free: self.filter_usable_gpus(&job_user, &available_gpus).len() as u32,
+                    },
+                };
+                self.set_job_reason(job_id, Some(reason));
                 self.enqueue_if_ready(job_id);
                 if let Some(rt) = self.job_runtimes.get(idx) {
                     tracing::debug!(
@@ -393,6 +538,13 @@ impl Scheduler {
             }
         }
 
+        self.last_pass = SchedulingPass {
+            duration: pass_started.elapsed(),
+            examined,
+            started: job_ids_to_execute.len(),
+            yielded: !self.pass_backlog.is_empty(),
+        };
+
         // Clone jobs only once after all allocations are done
         job_ids_to_execute
             .into_iter()
@@ -400,6 +552,33 @@ impl Scheduler {
             .collect()
     }
 
+    /// What the last scheduling pass did.
+    pub fn last_pass(&self) -> &SchedulingPass {
+        &self.last_pass
+    }
+
+    /// Limit how long one pass may spend on queued jobs; `None` for no limit.
+    pub fn set_pass_budget(&mut self, budget: Option<Duration>) {
+        self.pass_budget = budget;
+    }
+
+    /// Move the jobs the last pass ran out of time for to the front of
+    /// `runnable_jobs`, in the order they were left, so a long queue is
+    /// covered over several passes instead of only its head. The sort is
+    /// stable: the other jobs keep their priority order.
+    fn put_backlog_first(&mut self, runnable_jobs: &mut [u32]) {
+        if self.pass_backlog.is_empty() {
+            return;
+        }
+        let backlog: HashMap<u32, usize> = self
+            .pass_backlog
+            .drain(..)
+            .enumerate()
+            .map(|(position, job_id)| (job_id, position))
+            .collect();
+        runnable_jobs.sort_by_key(|job_id| backlog.get(job_id).copied().unwrap_or(usize::MAX));
+    }
+
     /// Phase 2: Execute jobs (call executor - can be done WITHOUT holding lock)
     /// This is separated so the caller can release locks before doing I/O
     /// Returns execution results WITHOUT modifying state
@@ -433,31 +612,14 @@ impl Scheduler {
     pub fn handle_execution_failures(&mut self, results: &[(u32, Result<(), String>)]) {
         for (job_id, result) in results {
             if result.is_err() {
-                let Some((had_gpus, required_memory)) = (|| {
-                    let unified = self.unified_memory;
-                    let rt = self.get_job_runtime_mut(*job_id)?;
-                    let had_gpus = rt.gpu_ids.take().is_some();
-                    let required_memory = Self::effective_host_memory_mb(
-                        unified,
-                        rt.memory_limit_mb,
-                        rt.gpu_memory_limit_mb,
-                        rt.gpus,
-                    );
-                    Some((had_gpus, required_memory))
-                })() else {
+                let Some(rt) = self.get_job_runtime_mut(*job_id) else {
                     continue;
                 };
+                rt.gpu_ids = None;
+                rt.cpu_ids = None;
 
-                // Keep previous behavior: return `true` (job exists) even if transition isn't valid,
-                // and always release resources when they were allocated.
+                // Leaving Running gives back the job's memory, GPUs and cores.
                 self.transition_job_state(*job_id, JobState::Failed, None);
-
-                // Return memory if we had allocated GPUs (i.e. we were running).
-                if had_gpus {
-                    self.available_memory_mb =
-                        self.available_memory_mb.saturating_add(required_memory);
-                    // Note: GPUs will be returned in next refresh cycle.
-                }
             }
         }
     }
@@ -481,6 +643,24 @@ impl Scheduler {
 
     /// Update GPU slot availability
     pub fn update_gpu_slots(&mut self, new_slots: HashMap<GpuUuid, GPUSlot>) {
+        let visible = |slots: &HashMap<GpuUuid, GPUSlot>| {
+            let mut visible: Vec<_> = slots
+                .iter()
+                .map(|(uuid, slot)| {
+                    (
+                        uuid.clone(),
+                        slot.index,
+                        slot.available,
+                        slot.reason.clone(),
+                    )
+                })
+                .collect();
+            visible.sort_unstable();
+            visible
+        };
+        if visible(&self.gpu_slots) != visible(&new_slots) {
+            self.bump_revision();
+        }
         self.gpu_slots = new_slots;
     }
 
@@ -520,50 +700,21 @@ impl Scheduler {
         self.next_job_id = id;
     }
 
-    /// Rebuild user jobs index from current jobs
-    /// Should be called after loading state from disk
+    /// Rebuild every index from the jobs: the user, state, project and group
+    /// indexes, dependencies and the ready queue. Only for a state loaded from
+    /// disk, or to repair one; submissions and transitions keep them current.
     pub fn rebuild_user_jobs_index(&mut self) {
-        self.user_jobs_index.clear();
-        self.state_jobs_index.clear();
-        self.project_jobs_index.clear();
         self.dependents_graph.clear();
         self.dependency_runtimes = vec![DependencyRuntime::default(); self.job_specs.len()];
         self.ready_heap.clear();
-        self.group_running_count.clear();
+        self.rebuild_gpu_claims();
+        for spec in &mut self.job_specs {
+            spec.intern_strings(&mut self.string_pool);
+        }
+        self.rebuild_job_indexes();
 
         self.check_invariant();
 
-        for (idx, spec) in self.job_specs.iter().enumerate() {
-            let rt = &self.job_runtimes[idx];
-
-            // Rebuild user index.
-            self.user_jobs_index
-                .entry(spec.submitted_by.clone())
-                .or_default()
-                .push(rt.id);
-
-            // Rebuild state index.
-            self.state_jobs_index
-                .entry(rt.state)
-                .or_default()
-                .push(rt.id);
-
-            // Rebuild project index.
-            if let Some(ref project) = spec.project {
-                self.project_jobs_index
-                    .entry(project.clone())
-                    .or_default()
-                    .push(rt.id);
-            }
-
-            // Rebuild group running count index.
-            if rt.state == JobState::Running {
-                if let Some(group_id) = rt.group_id {
-                    *self.group_running_count.entry(group_id).or_insert(0) += 1;
-                }
-            }
-        }
-
         let job_ids: Vec<u32> = self.job_runtimes.iter().map(|rt| rt.id).collect();
         for job_id in &job_ids {
             let Some(spec) = self.get_job_spec(*job_id) else {
@@ -581,11 +732,11 @@ impl Scheduler {
         }
     }
 
-    /// Get the sorted list of job IDs for a state.
+    /// Get the job IDs in a state, in ascending order.
     ///
     /// This is primarily intended for API/query paths to avoid scanning all jobs.
-    pub fn job_ids_by_state(&self, state: JobState) -> Option<&[u32]> {
-        self.state_jobs_index.get(&state).map(|v| v.as_slice())
+    pub fn job_ids_by_state(&self, state: JobState) -> Option<&BTreeSet<u32>> {
+        self.state_jobs_index.get(&state)
     }
 
     /// Get count of jobs by state for monitoring
@@ -0,0 +1,69 @@
+use super::*;
+use sha2::{Digest, Sha256};
+use std::time::SystemTime;
+
+/// How long a submission ID is remembered. Covers spooled submissions sent
+/// after the daemon was down for a while.
+pub const SUBMISSION_ID_TTL: Duration = Duration::from_secs(24 * 3600);
+
+/// A job accepted with a client-chosen submission ID.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SubmissionRecord {
+    pub job_id: u32,
+    /// [`submission_digest`] of the payload the job was created from
+    pub digest: String,
+    pub at: SystemTime,
+}
+
+/// What an earlier submission with the same ID means for a new one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PriorSubmission {
+    /// Not seen within [`SUBMISSION_ID_TTL`]: create the job.
+    None,
+    /// The same payload was already turned into this job.
+    Same(u32),
+    /// The ID was already used for this job with a different payload.
+    Different(u32),
+}
+
+/// SHA-256 of a job as the client submitted it.
+pub fn submission_digest(job: &Job) -> String {
+    let payload = serde_json::to_vec(job).expect("jobs always serialize");
+    Sha256::digest(payload)
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect()
+}
+
+impl Scheduler {
+    pub fn prior_submission(
+        &self,
+        submission_id: uuid::Uuid,
+        digest: &str,
+        now: SystemTime,
+    ) -> PriorSubmission {
+        match self.submissions.get(&submission_id) {
+            Some(record) if !is_expired(record, now) => {
+                if record.digest == digest {
+                    PriorSubmission::Same(record.job_id)
+                } else {
+                    PriorSubmission::Different(record.job_id)
+                }
+            }
+            _ => PriorSubmission::None,
+        }
+    }
+
+    /// Remember a submission ID, forgetting the ones past their TTL.
+    pub fn record_submission(&mut self, submission_id: uuid::Uuid, record: SubmissionRecord) {
+        let now = record.at;
+        self.submissions
+            .retain(|_, record| !is_expired(record, now));
+        self.submissions.insert(submission_id, record);
+    }
+}
+
+fn is_expired(record: &SubmissionRecord, now: SystemTime) -> bool {
+    now.duration_since(record.at)
+        .is_ok_and(|age| age >= SUBMISSION_ID_TTL)
+}
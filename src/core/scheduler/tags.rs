@@ -0,0 +1,28 @@
+use super::*;
+
+impl Scheduler {
+    /// Add and remove tags of `job_id`, whatever its state, and return its
+    /// tags afterwards. Fails if a tag is invalid or the job would have too
+    /// many; the job is then left as it was.
+    pub fn update_job_tags(
+        &mut self,
+        job_id: u32,
+        add: &[String],
+        remove: &[String],
+    ) -> Result<Tags, String> {
+        let spec = self
+            .get_job_spec(job_id)
+            .ok_or_else(|| format!("Job {job_id} not found"))?;
+        let old_tags = spec.tags.clone();
+        let new_tags = old_tags.with_changes(add, remove)?;
+        if new_tags == old_tags {
+            return Ok(new_tags);
+        }
+        self.update_tag_jobs_index(job_id, &old_tags, &new_tags);
+        if let Some((spec, _)) = self.get_job_parts_mut(job_id) {
+            spec.tags = new_tags.clone();
+        }
+        self.bump_revision();
+        Ok(new_tags)
+    }
+}
@@ -1,6 +1,16 @@
 use super::*;
+use crate::core::api_error::{ApiError, ApiErrorKind};
 use std::collections::VecDeque;
 
+/// A job state change, as applied by [`Scheduler::try_transition`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct StateTransition {
+    pub job_id: u32,
+    pub from: JobState,
+    pub to: JobState,
+    pub reason: Option<JobStateReason>,
+}
+
 impl Scheduler {
     pub(super) fn normalized_dependency_ids(spec: &JobSpec) -> Vec<u32> {
         let mut deps: Vec<u32> = spec.depends_on_ids.iter().copied().collect();
@@ -18,7 +28,7 @@ impl Scheduler {
             .unwrap_or_default()
     }
 
-    fn dependency_mode(spec: &JobSpec) -> DependencyMode {
+    pub(super) fn dependency_mode(spec: &JobSpec) -> DependencyMode {
         spec.dependency_mode.unwrap_or(DependencyMode::All)
     }
 
@@ -159,13 +169,14 @@ impl Scheduler {
             return None;
         }
 
-        if self
-            .dependency_runtime(job_id)
-            .is_some_and(|dep_rt| dep_rt.deps_satisfied)
-        {
-            None
-        } else {
-            Some(JobStateReason::WaitingForDependency)
+        match self.dependency_runtime(job_id) {
+            Some(dep_rt) if dep_rt.deps_satisfied => None,
+            Some(dep_rt) => Some(JobStateReason::Dependency {
+                unfinished: dep_rt
+                    .total
+                    .saturating_sub(dep_rt.success + dep_rt.terminal_non_success),
+            }),
+            None => Some(JobStateReason::WaitingForDependency),
         }
     }
 
@@ -176,18 +187,18 @@ impl Scheduler {
             .and_then(|rt| rt.reason.as_deref().cloned());
 
         let should_update = match desired_reason {
-            Some(JobStateReason::WaitingForDependency) => true,
-            None => {
-                current_reason.is_none()
-                    || matches!(current_reason, Some(JobStateReason::WaitingForDependency))
-            }
+            Some(ref reason) if reason.is_dependency_wait() => true,
+            None => current_reason
+                .as_ref()
+                .is_none_or(JobStateReason::is_dependency_wait),
             Some(_) => false,
         };
 
-        if should_update {
+        if should_update && current_reason != desired_reason {
             if let Some(rt) = self.get_job_runtime_mut(job_id) {
                 rt.reason = desired_reason.map(Box::new);
             }
+            self.bump_revision();
         }
     }
 
@@ -403,13 +414,20 @@ impl Scheduler {
 
         spec.run_name = Some(run_name.clone());
         spec.submitted_at = Some(submitted_at);
+        // Older clients only send the legacy `conda_env` field.
+        spec.normalize_environment();
+        spec.intern_strings(&mut self.string_pool);
 
         runtime.id = job_id;
         runtime.state = JobState::Queued;
         runtime.gpu_ids = None;
+        runtime.cpu_ids = None;
         runtime.started_at = None;
         runtime.finished_at = None;
         runtime.reason = None;
+        runtime.preemptions = 0;
+        runtime.node = None;
+        runtime.queued_at = Some(submitted_at);
 
         self.user_jobs_index
             .entry(spec.submitted_by.clone())
@@ -418,8 +436,15 @@ impl Scheduler {
         self.state_jobs_index
             .entry(runtime.state)
             .or_default()
-            .push(job_id);
+            .insert(job_id);
         self.update_project_jobs_index(job_id, None, spec.project.as_ref());
+        self.update_tag_jobs_index(job_id, &Tags::default(), &spec.tags);
+        if let Some(group_id) = runtime.group_id {
+            self.group_jobs_index
+                .entry(group_id)
+                .or_default()
+                .push(job_id);
+        }
 
         self.job_specs.push(spec);
         self.job_runtimes.push(runtime);
@@ -427,11 +452,66 @@ impl Scheduler {
 
         self.insert_job_dependencies_index(job_id, &deps);
         self.refresh_job_readiness(job_id);
+        self.bump_revision();
         self.check_invariant();
 
         (job_id, run_name.into())
     }
 
+    /// Add failed placeholders for ids from `next_job_id` up to (not including)
+    /// `up_to`: ids handed out for submissions that were lost before the state
+    /// was saved. Keeps them from being handed out again.
+    pub fn record_lost_jobs(&mut self, up_to: u32, reason: &str) -> Vec<u32> {
+        let now = std::time::SystemTime::now();
+        let lost: Vec<u32> = (self.next_job_id..up_to).collect();
+        for &job_id in &lost {
+            let (mut spec, mut runtime) = Job::builder()
+                .submitted_by("unknown")
+                .run_name(Some(format!("gjob-{job_id}")))
+                .build()
+                .into_parts();
+            spec.submitted_at = Some(now);
+            spec.intern_strings(&mut self.string_pool);
+            runtime.id = job_id;
+            runtime.state = JobState::Failed;
+            runtime.finished_at = Some(now);
+            runtime.reason = Some(Box::new(JobStateReason::SystemError(reason.into())));
+
+            self.user_jobs_index
+                .entry(spec.submitted_by.clone())
+                .or_default()
+                .push(job_id);
+            self.state_jobs_index
+                .entry(runtime.state)
+                .or_default()
+                .insert(job_id);
+            self.job_specs.push(spec);
+            self.job_runtimes.push(runtime);
+            self.dependency_runtimes.push(DependencyRuntime::default());
+        }
+        self.next_job_id = self.next_job_id.max(up_to);
+        self.bump_revision();
+        self.check_invariant();
+        lost
+    }
+
+    /// Take back the jobs from `first_id` on, as if they had never been
+    /// submitted, and hand their ids out again: for a batch that could not be
+    /// saved. Only for the newest jobs, which nothing has run or refers to yet.
+    pub fn discard_jobs_from(&mut self, first_id: u32) {
+        let keep = first_id.saturating_sub(1) as usize;
+        if keep >= self.job_specs.len() {
+            return;
+        }
+        self.job_specs.truncate(keep);
+        self.job_runtimes.truncate(keep);
+        self.transitions
+            .retain(|transition| transition.job_id < first_id);
+        self.next_job_id = first_id;
+        self.rebuild_user_jobs_index();
+        self.bump_revision();
+    }
+
     pub fn replace_job_dependencies(
         &mut self,
         job_id: u32,
@@ -486,104 +566,176 @@ impl Scheduler {
         updated_jobs
     }
 
-    pub(super) fn update_group_running_count(
+    /// Whether job `job_id` may move to `next`: `Ok(false)` if it already is
+    /// in `next`, the reason it may not otherwise. Running → Queued is
+    /// reserved for preemption and for jobs not launched yet.
+    pub fn check_transition(
+        &self,
+        job_id: u32,
+        next: JobState,
+        reason: Option<&JobStateReason>,
+    ) -> Result<bool, ApiError> {
+        let from = self
+            .get_job_runtime(job_id)
+            .ok_or(ApiErrorKind::JobNotFound { id: job_id })?
+            .state;
+        if from == next {
+            return Ok(false);
+        }
+        let requeued = from == JobState::Running
+            && next == JobState::Queued
+            && match reason {
+                Some(JobStateReason::PreemptedBy(_)) => true,
+                Some(JobStateReason::WaitingForGpu) => self.is_unlaunched(job_id),
+                _ => false,
+            };
+        if from.can_transition_to(next) || requeued {
+            Ok(true)
+        } else {
+            Err(ApiErrorKind::invalid_transition(job_id, from, next).into())
+        }
+    }
+
+    /// Move job `job_id` to `next`. Every job state change goes through here;
+    /// each one is kept for [`Self::take_transitions`]. Returns `Ok(false)` if
+    /// the job already is in `next`.
+    pub fn try_transition(
         &mut self,
-        group_id: Option<uuid::Uuid>,
-        old_state: JobState,
-        new_state: JobState,
-    ) {
-        let entering_running = new_state == JobState::Running && old_state != JobState::Running;
-        let leaving_running = old_state == JobState::Running && new_state != JobState::Running;
+        job_id: u32,
+        next: JobState,
+        reason: Option<JobStateReason>,
+    ) -> Result<bool, ApiError> {
+        self.apply_transition(job_id, next, reason, true)
+    }
 
-        if !entering_running && !leaving_running {
-            return;
-        }
+    /// [`Self::try_transition`] for a job whose dependents were already moved
+    /// to its retry, so they are left alone.
+    pub fn try_transition_without_propagation(
+        &mut self,
+        job_id: u32,
+        next: JobState,
+        reason: Option<JobStateReason>,
+    ) -> Result<bool, ApiError> {
+        self.apply_transition(job_id, next, reason, false)
+    }
 
-        if let Some(group_id) = group_id {
-            if entering_running {
-                *self.group_running_count.entry(group_id).or_insert(0) += 1;
-            } else if leaving_running {
-                if let Some(count) = self.group_running_count.get_mut(&group_id) {
-                    *count = count.saturating_sub(1);
-                    if *count == 0 {
-                        self.group_running_count.remove(&group_id);
-                    }
-                }
-            }
-        }
+    /// The state changes since the last call, oldest first.
+    pub fn take_transitions(&mut self) -> Vec<StateTransition> {
+        std::mem::take(&mut self.transitions)
     }
 
-    fn transition_job_state_internal(
+    fn apply_transition(
         &mut self,
         job_id: u32,
         next: JobState,
         reason: Option<JobStateReason>,
         propagate_terminal_state: bool,
-    ) -> Option<bool> {
-        let (group_id, old_state, transitioned) = (|| {
-            let rt = self.get_job_runtime_mut(job_id)?;
-            let group_id = rt.group_id;
-            let old_state = rt.state;
-
-            if old_state == next {
-                tracing::warn!(
-                    "Job {} already in state {}, ignoring transition",
-                    job_id,
-                    next
-                );
-                return Some((group_id, old_state, false));
-            }
+    ) -> Result<bool, ApiError> {
+        if !self.check_transition(job_id, next, reason.as_ref())? {
+            tracing::warn!(
+                "Job {} already in state {}, ignoring transition",
+                job_id,
+                next
+            );
+            return Ok(false);
+        }
 
-            if !old_state.can_transition_to(next) {
-                tracing::error!(
-                    "Job {} invalid transition: {} → {}",
-                    job_id,
-                    old_state,
-                    next
-                );
-                return Some((group_id, old_state, false));
+        let memory_mb = self
+            .get_job_runtime(job_id)
+            .map_or(0, |rt| self.job_host_memory_mb(rt));
+        let rt = self
+            .get_job_runtime_mut(job_id)
+            .expect("checked by check_transition");
+        let group_id = rt.group_id;
+        let old_state = rt.state;
+        if old_state == JobState::Running {
+            // Progress belongs to the run that reported it.
+            rt.progress = None;
+        }
+        match next {
+            JobState::Queued => rt.queued_at = Some(std::time::SystemTime::now()),
+            JobState::Running => rt.started_at = Some(std::time::SystemTime::now()),
+            JobState::Finished | JobState::Failed | JobState::Cancelled | JobState::Timeout => {
+                rt.finished_at = Some(std::time::SystemTime::now())
             }
+            JobState::Hold => {}
+        }
+        rt.reason = reason.clone().map(Box::new);
+        rt.state = next;
+        tracing::debug!("Job {} transitioned to {}", job_id, next);
+        self.transitions.push(StateTransition {
+            job_id,
+            from: old_state,
+            to: next,
+            reason,
+        });
 
-            match next {
-                JobState::Running => rt.started_at = Some(std::time::SystemTime::now()),
-                JobState::Finished | JobState::Failed | JobState::Cancelled | JobState::Timeout => {
-                    rt.finished_at = Some(std::time::SystemTime::now())
-                }
-                _ => {}
-            }
+        // A running job holds its GPUs and memory; every way out of Running
+        // gives them back.
+        if next == JobState::Running {
+            self.available_memory_mb = self.available_memory_mb.saturating_sub(memory_mb);
+        } else if old_state == JobState::Running {
+            self.release_gpu_claim(job_id);
+            self.available_memory_mb = self
+                .available_memory_mb
+                .saturating_add(memory_mb)
+                .min(self.total_memory_mb);
+        }
+        self.update_group_running_count(group_id, old_state, next);
+        self.update_state_jobs_index(job_id, old_state, next);
+        self.bump_ready_epoch(job_id);
 
-            rt.reason = reason.map(Box::new);
-            rt.state = next;
-            tracing::debug!("Job {} transitioned to {}", job_id, next);
-            Some((group_id, old_state, true))
-        })()?;
-
-        if transitioned {
-            self.update_group_running_count(group_id, old_state, next);
-            self.update_state_jobs_index(job_id, old_state, next);
-            self.bump_ready_epoch(job_id);
-
-            match next {
-                JobState::Queued => self.refresh_job_readiness(job_id),
-                JobState::Finished | JobState::Failed | JobState::Cancelled | JobState::Timeout => {
-                    if propagate_terminal_state {
-                        self.propagate_terminal_state_to_dependents(job_id, next);
-                    }
+        if next == JobState::Finished {
+            self.record_runtime(job_id);
+        }
+        match next {
+            JobState::Queued => self.refresh_job_readiness(job_id),
+            JobState::Finished | JobState::Failed | JobState::Cancelled | JobState::Timeout => {
+                if propagate_terminal_state {
+                    self.propagate_terminal_state_to_dependents(job_id, next);
                 }
-                JobState::Hold | JobState::Running => {}
             }
+            JobState::Hold | JobState::Running => {}
         }
+        self.bump_revision();
+        self.check_invariant();
 
-        Some(transitioned)
+        Ok(true)
     }
 
+    /// Add a successful run to the runtime statistics.
+    fn record_runtime(&mut self, job_id: u32) {
+        let Some((spec, rt)) = self.get_job_parts(job_id) else {
+            return;
+        };
+        let (Some(run_name), Some(started), Some(finished)) =
+            (spec.run_name.clone(), rt.started_at, rt.finished_at)
+        else {
+            return;
+        };
+        let Ok(runtime) = finished.duration_since(started) else {
+            return;
+        };
+        let user = spec.submitted_by.clone();
+        self.runtime_stats
+            .record(&user, &run_name, runtime, finished);
+    }
+
+    /// [`Self::try_transition`] for callers that only log a refused change:
+    /// `Some(true)` if the job moved, `Some(false)` if it already was in `next`.
     pub(super) fn transition_job_state(
         &mut self,
         job_id: u32,
         next: JobState,
         reason: Option<JobStateReason>,
     ) -> Option<bool> {
-        self.transition_job_state_internal(job_id, next, reason, true)
+        match self.try_transition(job_id, next, reason) {
+            Ok(transitioned) => Some(transitioned),
+            Err(error) => {
+                tracing::error!(job_id, %error, "Refused job state change");
+                None
+            }
+        }
     }
 
     pub fn finish_job(&mut self, job_id: u32) -> Option<(bool, Option<String>)> {
@@ -591,29 +743,24 @@ impl Scheduler {
         let should_close_tmux = spec.auto_close_tmux;
         let run_name = spec.run_name.as_ref().map(|s| s.to_string());
 
-        self.transition_job_state(job_id, JobState::Finished, None)?;
+        if !self.transition_job_state(job_id, JobState::Finished, None)? {
+            return None;
+        }
 
         Some((should_close_tmux, run_name))
     }
 
     pub fn fail_job(&mut self, job_id: u32) -> bool {
-        self.transition_job_state(job_id, JobState::Failed, None)
-            .is_some()
+        self.fail_job_because(job_id, None)
     }
 
-    pub fn fail_job_without_propagation(&mut self, job_id: u32) -> bool {
-        self.transition_job_state_internal(job_id, JobState::Failed, None, false)
-            .is_some()
+    /// [`Self::fail_job`], recording why the job failed.
+    pub fn fail_job_because(&mut self, job_id: u32, reason: Option<JobStateReason>) -> bool {
+        self.transition_job_state(job_id, JobState::Failed, reason) == Some(true)
     }
 
     pub fn timeout_job(&mut self, job_id: u32) -> bool {
-        self.transition_job_state(job_id, JobState::Timeout, None)
-            .is_some()
-    }
-
-    pub fn timeout_job_without_propagation(&mut self, job_id: u32) -> bool {
-        self.transition_job_state_internal(job_id, JobState::Timeout, None, false)
-            .is_some()
+        self.transition_job_state(job_id, JobState::Timeout, None) == Some(true)
     }
 
     pub fn cancel_job(
@@ -629,19 +776,65 @@ impl Scheduler {
             .map(|s| s.to_string());
 
         let reason = reason.unwrap_or(JobStateReason::CancelledByUser);
-        self.transition_job_state(job_id, JobState::Cancelled, Some(reason))?;
+        if !self.transition_job_state(job_id, JobState::Cancelled, Some(reason))? {
+            return None;
+        }
 
         Some((was_running, run_name))
     }
 
+    /// Requeue a running job so job `by` can have its GPUs.
+    ///
+    /// The job is left out of the ready queue until `enqueue_if_ready` is
+    /// called for it, so it cannot restart in the same scheduling pass.
+    pub(super) fn preempt_job(&mut self, job_id: u32, by: u32) -> bool {
+        // A remote job's GPUs are the node's, not the head's slots. Its node
+        // stays set so its workload can still be stopped there.
+        let gpu_ids = self
+            .get_job_runtime(job_id)
+            .filter(|rt| rt.node.is_none())
+            .and_then(|rt| rt.gpu_ids.clone())
+            .unwrap_or_default();
+        let transitioned = self
+            .transition_job_state(
+                job_id,
+                JobState::Queued,
+                Some(JobStateReason::PreemptedBy(by)),
+            )
+            .unwrap_or(false);
+        if !transitioned {
+            return false;
+        }
+
+        if let Some(rt) = self.get_job_runtime_mut(job_id) {
+            rt.gpu_ids = None;
+            rt.cpu_ids = None;
+            rt.started_at = None;
+            rt.preemptions += 1;
+        }
+        self.bump_ready_epoch(job_id);
+
+        // The GPU monitor would only notice once the job has exited.
+        for gpu in gpu_ids {
+            let still_held = self.runtimes_in_state(JobState::Running).any(|rt| {
+                rt.node.is_none() && rt.gpu_ids.as_ref().is_some_and(|ids| ids.contains(&gpu))
+            });
+            if still_held {
+                continue;
+            }
+            if let Some(slot) = self.gpu_slots.values_mut().find(|slot| slot.index == gpu) {
+                slot.available = true;
+            }
+        }
+        true
+    }
+
     pub fn hold_job(&mut self, job_id: u32) -> bool {
-        self.transition_job_state(job_id, JobState::Hold, None)
-            .is_some()
+        self.transition_job_state(job_id, JobState::Hold, None) == Some(true)
     }
 
     pub fn release_job(&mut self, job_id: u32) -> bool {
-        self.transition_job_state(job_id, JobState::Queued, None)
-            .is_some()
+        self.transition_job_state(job_id, JobState::Queued, None) == Some(true)
     }
 
     pub fn resolve_dependency(&self, username: &str, shorthand: &str) -> Option<u32> {
@@ -677,16 +870,16 @@ impl Scheduler {
         &self,
         new_job_id: u32,
         dependency_ids: &[u32],
-    ) -> Result<(), String> {
+    ) -> Result<(), ApiError> {
         use std::collections::HashSet;
 
         for &dep_id in dependency_ids {
             if self.has_path_dfs(dep_id, new_job_id, &mut HashSet::new()) {
-                return Err(format!(
-                    "Circular dependency detected: Job {} depends on Job {}, \
-                     which has a path back to Job {}",
-                    new_job_id, dep_id, new_job_id
-                ));
+                return Err(ApiErrorKind::CircularDependency {
+                    id: new_job_id,
+                    dependency: dep_id,
+                }
+                .into());
             }
         }
 
@@ -766,22 +959,27 @@ impl Scheduler {
         cancelled
     }
 
-    pub fn validate_job_update(&self, job_id: u32, new_deps: Option<&[u32]>) -> Result<(), String> {
+    pub fn validate_job_update(
+        &self,
+        job_id: u32,
+        new_deps: Option<&[u32]>,
+    ) -> Result<(), ApiError> {
         let rt = self
             .get_job_runtime(job_id)
-            .ok_or_else(|| format!("Job {} not found", job_id))?;
+            .ok_or(ApiErrorKind::JobNotFound { id: job_id })?;
 
         if rt.state != JobState::Queued && rt.state != JobState::Hold {
-            return Err(format!(
-                "Job {} is in state '{}' and cannot be updated. Only queued or held jobs can be updated.",
-                job_id, rt.state
-            ));
+            return Err(ApiErrorKind::JobNotUpdatable {
+                id: job_id,
+                state: rt.state,
+            }
+            .into());
         }
 
         if let Some(deps) = new_deps {
             for &dep_id in deps {
                 if !self.job_exists(dep_id) {
-                    return Err(format!("Dependency job {} does not exist", dep_id));
+                    return Err(ApiErrorKind::DependencyMissing { id: dep_id }.into());
                 }
             }
 
@@ -0,0 +1,19 @@
+//! OpenAPI schemas for std types that serde writes as plain structs.
+
+use utoipa::ToSchema;
+
+/// `SystemTime` as serialized by serde: time since the Unix epoch.
+#[derive(ToSchema)]
+#[schema(as = SystemTime)]
+pub struct SystemTimeSchema {
+    pub secs_since_epoch: u64,
+    pub nanos_since_epoch: u32,
+}
+
+/// `Duration` as serialized by serde.
+#[derive(ToSchema)]
+#[schema(as = Duration)]
+pub struct DurationSchema {
+    pub secs: u64,
+    pub nanos: u32,
+}
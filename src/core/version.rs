@@ -0,0 +1,146 @@
+//! Versions of gflow clients and daemons, and whether the two fit together.
+//!
+//! Clients send their version in [`CLIENT_VERSION_HEADER`]; the daemon serves
+//! its own and the oldest client it accepts at `GET /version`.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// Header every [`crate::Client`] request carries its version in.
+pub const CLIENT_VERSION_HEADER: &str = "x-gflow-client-version";
+
+/// Set to `1` to skip the check, as `--skip-version-check` does.
+pub const SKIP_VERSION_CHECK_ENV: &str = "GFLOW_SKIP_VERSION_CHECK";
+
+/// Oldest client a daemon accepts unless `daemon.min_client_version` says
+/// otherwise.
+pub const MIN_CLIENT_VERSION: Version = Version::new(0, 4, 0);
+
+/// A `MAJOR.MINOR.PATCH` version; pre-release and build suffixes are ignored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Version {
+    pub major: u64,
+    pub minor: u64,
+    pub patch: u64,
+}
+
+impl Version {
+    pub const fn new(major: u64, minor: u64, patch: u64) -> Self {
+        Self {
+            major,
+            minor,
+            patch,
+        }
+    }
+
+    /// The version of this build.
+    pub fn current() -> Self {
+        env!("CARGO_PKG_VERSION")
+            .parse()
+            .expect("CARGO_PKG_VERSION is a valid version")
+    }
+
+    /// Whether `self` and `other` are the same release series, e.g. 0.4.x.
+    pub fn same_minor(&self, other: &Version) -> bool {
+        (self.major, self.minor) == (other.major, other.minor)
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+impl FromStr for Version {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let core = s.trim().trim_start_matches('v');
+        let core = core.split(['-', '+']).next().unwrap_or_default();
+        let mut parts = core.split('.').map(str::parse::<u64>);
+        match (parts.next(), parts.next(), parts.next(), parts.next()) {
+            (Some(Ok(major)), Some(Ok(minor)), Some(Ok(patch)), None) => {
+                Ok(Self::new(major, minor, patch))
+            }
+            _ => anyhow::bail!("'{s}' is not a version like 0.4.17"),
+        }
+    }
+}
+
+impl Serialize for Version {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Version {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+/// What a client should make of the daemon it talks to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Compatibility {
+    /// Same release series
+    Compatible,
+    /// Different minor or major versions the daemon still accepts; requests
+    /// may lack fields one side expects
+    Skewed { client: Version, daemon: Version },
+    /// The daemon declares the client older than it accepts
+    ClientTooOld { client: Version, minimum: Version },
+}
+
+impl Compatibility {
+    pub fn of(client: Version, daemon: Version, minimum: Version) -> Self {
+        if client < minimum {
+            Self::ClientTooOld { client, minimum }
+        } else if client.same_minor(&daemon) {
+            Self::Compatible
+        } else {
+            Self::Skewed { client, daemon }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn versions_parse_and_order_numerically() {
+        let v: Version = "0.4.17".parse().unwrap();
+        assert_eq!(v, Version::new(0, 4, 17));
+        assert_eq!(
+            "v1.2.3-rc.1+abc".parse::<Version>().unwrap(),
+            Version::new(1, 2, 3)
+        );
+        assert!(Version::new(0, 4, 10) > Version::new(0, 4, 9));
+        assert!("0.4".parse::<Version>().is_err());
+        assert!("0.4.x".parse::<Version>().is_err());
+        assert_eq!(v.to_string(), "0.4.17");
+        assert_eq!(serde_json::to_string(&v).unwrap(), "\"0.4.17\"");
+        let _ = Version::current();
+    }
+
+    #[test]
+    fn compatibility_warns_on_minor_skew_and_refuses_old_clients() {
+        let v = Version::new;
+        assert_eq!(
+            Compatibility::of(v(0, 4, 2), v(0, 4, 17), v(0, 4, 0)),
+            Compatibility::Compatible
+        );
+        assert!(matches!(
+            Compatibility::of(v(0, 5, 0), v(0, 4, 17), v(0, 4, 0)),
+            Compatibility::Skewed { .. }
+        ));
+        assert!(matches!(
+            Compatibility::of(v(0, 4, 1), v(0, 5, 0), v(0, 4, 5)),
+            Compatibility::ClientTooOld { .. }
+        ));
+    }
+}
@@ -27,7 +27,8 @@ pub mod utils;
 pub use client::Client;
 pub use config::Config;
 
-/// Creates a client from the config file path.
+/// Creates a client from the config file path, for the remote selected with
+/// `--remote` or `GFLOW_REMOTE` if any.
 /// This is a convenience function to reduce boilerplate in CLI tools.
 ///
 /// # Example
@@ -42,7 +43,8 @@ pub use config::Config;
 /// # }
 /// ```
 pub fn create_client(config_path: &Option<std::path::PathBuf>) -> anyhow::Result<Client> {
-    let config = config::load_config(config_path.as_ref())?;
+    let mut config = config::load_config(config_path.as_ref())?;
+    config.use_selected_remote()?;
     Client::build(&config)
 }
 
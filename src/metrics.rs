@@ -100,6 +100,19 @@ lazy_static! {
         vec![0.001, 0.005, 0.01, 0.05, 0.1, 0.5, 1.0, 2.0, 5.0]
     )
     .unwrap();
+    pub static ref SCHEDULER_PASS_JOBS: HistogramVec = register_histogram_vec!(
+        "gflow_scheduler_pass_jobs",
+        "Queued jobs each scheduling pass examined or started",
+        &["outcome"],
+        vec![0.0, 1.0, 10.0, 100.0, 1000.0, 10000.0, 100000.0]
+    )
+    .unwrap();
+    pub static ref SCHEDULER_PASSES_YIELDED: CounterVec = register_counter_vec!(
+        "gflow_scheduler_passes_yielded_total",
+        "Scheduling passes that ran out of time before examining every queued job",
+        &[]
+    )
+    .unwrap();
 }
 
 #[cfg(feature = "metrics")]
@@ -219,3 +232,24 @@ pub fn observe_scheduler_latency(operation: &str, duration: Duration) {
 pub fn observe_scheduler_latency(_operation: &str, _duration: std::time::Duration) {
     // No-op when metrics feature is disabled
 }
+
+#[cfg(feature = "metrics")]
+pub fn observe_scheduling_pass(pass: &crate::core::scheduler::SchedulingPass) {
+    observe_scheduler_latency("scheduling_pass", pass.duration);
+    SCHEDULER_PASS_JOBS
+        .with_label_values(&["examined"])
+        .observe(pass.examined as f64);
+    SCHEDULER_PASS_JOBS
+        .with_label_values(&["started"])
+        .observe(pass.started as f64);
+    if pass.yielded {
+        SCHEDULER_PASSES_YIELDED
+            .with_label_values(&[] as &[&str])
+            .inc();
+    }
+}
+
+#[cfg(not(feature = "metrics"))]
+pub fn observe_scheduling_pass(_pass: &crate::core::scheduler::SchedulingPass) {
+    // No-op when metrics feature is disabled
+}
@@ -1,5 +1,19 @@
 use anyhow::Result;
+use clap::Parser;
+use clap_complete::Shell;
+use gflow::core::job::Job;
+use std::ffi::OsString;
+use std::fmt::Write as _;
 use std::io::Write;
+use std::time::Duration;
+
+/// Upper bound on how long `__complete` waits for the daemon. Completion runs
+/// inside the user's shell, so a slow or missing daemon must yield nothing
+/// rather than a hang.
+const DYNAMIC_COMPLETION_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// Number of recent jobs offered by [`DynamicKind::Recent`] and [`DynamicKind::Names`].
+const RECENT_JOB_LIMIT: usize = 50;
 
 pub fn generate_to_stdout(
     shell: clap_complete::Shell,
@@ -8,8 +22,11 @@ pub fn generate_to_stdout(
 ) -> Result<()> {
     let mut buf = Vec::<u8>::new();
     clap_complete::generate(shell, cmd, bin_name, &mut buf);
+    write_stdout(&buf)
+}
 
-    match std::io::stdout().write_all(&buf) {
+fn write_stdout(buf: &[u8]) -> Result<()> {
+    match std::io::stdout().write_all(buf) {
         Ok(()) => Ok(()),
         Err(e) if e.kind() == std::io::ErrorKind::BrokenPipe => Ok(()),
         Err(e) => Err(e.into()),
@@ -26,3 +43,365 @@ pub fn handle_completion(
 ) -> Result<()> {
     generate_to_stdout(shell, &mut cmd, bin_name)
 }
+
+/// Values the hidden `__complete` helper can list.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum DynamicKind {
+    /// IDs of queued, held, and running jobs
+    Active,
+    /// IDs of the most recent jobs in any state
+    Recent,
+    /// Run names of the most recent jobs
+    Names,
+}
+
+impl DynamicKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            DynamicKind::Active => "active",
+            DynamicKind::Recent => "recent",
+            DynamicKind::Names => "names",
+        }
+    }
+}
+
+/// An option whose value is completed through `__complete`.
+pub struct DynamicOption {
+    pub long: &'static str,
+    pub short: Option<char>,
+    pub kind: DynamicKind,
+}
+
+/// Describes where a binary accepts dynamically completed values.
+pub struct DynamicSpec {
+    /// Kind completed for the binary's first positional argument.
+    pub positional: Option<DynamicKind>,
+    /// Subcommand names (aliases included) whose first positional argument is
+    /// completed with the given kind.
+    pub subcommands: &'static [(&'static [&'static str], DynamicKind)],
+    pub options: &'static [DynamicOption],
+}
+
+/// Like [`handle_completion`], but extends the bash/zsh/fish scripts so job ID
+/// and run name positions are completed by calling `<bin> __complete <kind>`.
+pub fn handle_dynamic_completion(
+    shell: Shell,
+    mut cmd: clap::Command,
+    bin_name: &str,
+    spec: &DynamicSpec,
+) -> Result<()> {
+    let mut buf = Vec::<u8>::new();
+    clap_complete::generate(shell, &mut cmd, bin_name, &mut buf);
+    let script = String::from_utf8(buf)?;
+    write_stdout(dynamic_script(shell, bin_name, spec, script).as_bytes())
+}
+
+/// Hidden first argument that turns any multicall binary into a completion
+/// helper: `<bin> __complete <kind> [--config <path>]`. It is intercepted in
+/// [`super::dispatch`] before clap parsing so it never shows up in help or in the
+/// generated scripts.
+pub const DYNAMIC_COMPLETE_ARG: &str = "__complete";
+
+#[derive(Debug, clap::Parser)]
+#[command(no_binary_name = true)]
+struct DynamicCompleteArgs {
+    #[arg(value_enum)]
+    kind: DynamicKind,
+
+    #[arg(long)]
+    config: Option<std::path::PathBuf>,
+}
+
+/// Handle `<bin> __complete ...`: print one candidate per line. Any failure
+/// (bad arguments, missing config, daemon down, timeout) prints nothing and
+/// succeeds so the calling shell never sees an error.
+pub async fn run_dynamic_complete(args: &[OsString]) -> Result<()> {
+    let Ok(args) = DynamicCompleteArgs::try_parse_from(args) else {
+        return Ok(());
+    };
+    let Ok(client) = gflow::create_client_or_default(&args.config) else {
+        return Ok(());
+    };
+    let fetch = async {
+        match args.kind {
+            DynamicKind::Active => client.list_jobs().await,
+            DynamicKind::Recent | DynamicKind::Names => {
+                client
+                    .list_jobs_with_query(
+                        None,
+                        None,
+                        Some(RECENT_JOB_LIMIT),
+                        None,
+                        None,
+                        Some("desc".to_string()),
+                    )
+                    .await
+            }
+        }
+    };
+    let Ok(Ok(jobs)) = tokio::time::timeout(DYNAMIC_COMPLETION_TIMEOUT, fetch).await else {
+        return Ok(());
+    };
+
+    let mut out = String::new();
+    for candidate in candidates(args.kind, &jobs) {
+        out.push_str(&candidate);
+        out.push('\n');
+    }
+    write_stdout(out.as_bytes())
+}
+
+fn candidates(kind: DynamicKind, jobs: &[Job]) -> Vec<String> {
+    let mut values: Vec<String> = match kind {
+        DynamicKind::Active => jobs
+            .iter()
+            .filter(|job| !job.state.is_final())
+            .map(|job| job.id.to_string())
+            .collect(),
+        DynamicKind::Recent => jobs.iter().map(|job| job.id.to_string()).collect(),
+        DynamicKind::Names => jobs
+            .iter()
+            .filter_map(|job| job.run_name.as_ref().map(|name| name.to_string()))
+            .collect(),
+    };
+    let mut seen = std::collections::HashSet::new();
+    values.retain(|value| seen.insert(value.clone()));
+    values
+}
+
+fn dynamic_script(shell: Shell, bin: &str, spec: &DynamicSpec, script: String) -> String {
+    match shell {
+        Shell::Bash => script + &bash_wrapper(bin, spec),
+        Shell::Zsh => zsh_wrap(bin, spec, script),
+        Shell::Fish => script + &fish_lines(bin, spec),
+        _ => script,
+    }
+}
+
+/// Shell `case` arms matching the option that precedes the word under the cursor.
+fn option_cases(spec: &DynamicSpec) -> String {
+    let mut cases = String::new();
+    for option in spec.options {
+        let mut patterns = format!("--{}", option.long);
+        if let Some(short) = option.short {
+            let _ = write!(patterns, "|-{short}");
+        }
+        let _ = writeln!(
+            cases,
+            "        {patterns}) kind={} ;;",
+            option.kind.as_str()
+        );
+    }
+    cases
+}
+
+/// Shell snippet that sets `kind` when the word under the cursor (`$cword`) is
+/// the first positional argument of the binary or of a listed subcommand.
+fn position_checks(spec: &DynamicSpec) -> String {
+    let mut checks = String::new();
+    if let Some(kind) = spec.positional {
+        let _ = writeln!(
+            checks,
+            "        if _gflow_first_positional 1 \"$cword\"; then kind={}; fi",
+            kind.as_str()
+        );
+    }
+    if !spec.subcommands.is_empty() {
+        checks.push_str("        case \"$(_gflow_word 1)\" in\n");
+        for (names, kind) in spec.subcommands {
+            let _ = writeln!(
+                checks,
+                "            {}) _gflow_first_positional 2 \"$cword\" && kind={} ;;",
+                names.join("|"),
+                kind.as_str()
+            );
+        }
+        checks.push_str("        esac\n");
+    }
+    checks
+}
+
+/// Body shared by the bash and zsh wrappers; expects `cword`, `cur`, `prev`,
+/// and a `_gflow_word <index>` helper to be defined by the caller.
+fn detect_kind(spec: &DynamicSpec) -> String {
+    let mut body = String::from("    local kind=\"\"\n");
+    if !spec.options.is_empty() {
+        let _ = write!(
+            body,
+            "    case \"$prev\" in\n{}    esac\n",
+            option_cases(spec)
+        );
+    }
+    let checks = position_checks(spec);
+    if !checks.is_empty() {
+        let _ = write!(
+            body,
+            "    if [[ -z \"$kind\" && \"$cur\" != -* ]]; then\n{checks}    fi\n"
+        );
+    }
+    body
+}
+
+fn bash_wrapper(bin: &str, spec: &DynamicSpec) -> String {
+    format!(
+        r#"
+_{bin}_dynamic() {{
+    local cword=$COMP_CWORD
+    local cur="${{COMP_WORDS[COMP_CWORD]}}"
+    local prev="${{COMP_WORDS[COMP_CWORD-1]}}"
+    _gflow_word() {{ printf '%s' "${{COMP_WORDS[$1]}}"; }}
+    _gflow_first_positional() {{
+        local i
+        for (( i = $1; i < $2; i++ )); do
+            [[ "$(_gflow_word "$i")" == -* ]] || return 1
+        done
+    }}
+{detect}    if [[ -n "$kind" ]]; then
+        COMPREPLY=( $(compgen -W "$({bin} __complete "$kind" 2>/dev/null)" -- "$cur") )
+        return 0
+    fi
+    _{bin} "$@"
+}}
+
+complete -F _{bin}_dynamic -o bashdefault -o default {bin}
+"#,
+        detect = detect_kind(spec)
+    )
+}
+
+/// zsh scripts are autoloaded as the `_<bin>` function, so rename clap's entry
+/// point and install a wrapper under the original name.
+fn zsh_wrap(bin: &str, spec: &DynamicSpec, script: String) -> String {
+    let entry = format!("_{bin}() {{");
+    let dispatch = format!("if [ \"$funcstack[1]\" = \"_{bin}\" ]; then");
+    let Some(dispatch_at) = script.rfind(&dispatch) else {
+        return script;
+    };
+    let wrapper = format!(
+        r#"_{bin}() {{
+    local cword=$CURRENT
+    local cur="${{words[CURRENT]}}"
+    local prev="${{words[CURRENT-1]}}"
+    _gflow_word() {{ printf '%s' "${{words[$(( $1 + 1 ))]}}"; }}
+    _gflow_first_positional() {{
+        local i
+        for (( i = $1; i < $2 - 1; i++ )); do
+            [[ "$(_gflow_word "$i")" == -* ]] || return 1
+        done
+    }}
+{detect}    if [[ -n "$kind" ]]; then
+        local -a candidates
+        candidates=(${{(f)"$({bin} __complete "$kind" 2>/dev/null)"}})
+        compadd -a candidates
+        return
+    fi
+    _{bin}_static "$@"
+}}
+
+"#,
+        detect = detect_kind(spec)
+    );
+
+    let (head, tail) = script.split_at(dispatch_at);
+    let head = head.replacen(&entry, &format!("_{bin}_static() {{"), 1);
+    format!("{head}{wrapper}{tail}")
+}
+
+fn fish_lines(bin: &str, spec: &DynamicSpec) -> String {
+    let mut lines = String::from("\n");
+    let source = |kind: DynamicKind| format!("({bin} __complete {} 2>/dev/null)", kind.as_str());
+    if let Some(kind) = spec.positional {
+        let _ = writeln!(
+            lines,
+            "complete -c {bin} -n \"__fish_is_first_arg\" -f -a \"{}\"",
+            source(kind)
+        );
+    }
+    for (names, kind) in spec.subcommands {
+        let _ = writeln!(
+            lines,
+            "complete -c {bin} -n \"__fish_seen_subcommand_from {}\" -f -a \"{}\"",
+            names.join(" "),
+            source(*kind)
+        );
+    }
+    for option in spec.options {
+        let short = option.short.map(|s| format!(" -s {s}")).unwrap_or_default();
+        let _ = writeln!(
+            lines,
+            "complete -c {bin}{short} -l {} -x -a \"{}\"",
+            option.long,
+            source(option.kind)
+        );
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gflow::core::job::{JobBuilder, JobState};
+
+    const SPEC: DynamicSpec = DynamicSpec {
+        positional: Some(DynamicKind::Active),
+        subcommands: &[(&["log", "l"], DynamicKind::Recent)],
+        options: &[DynamicOption {
+            long: "names",
+            short: Some('N'),
+            kind: DynamicKind::Names,
+        }],
+    };
+
+    fn job(id: u32, name: &str, state: JobState) -> Job {
+        let mut job = JobBuilder::new().run_name(Some(name.to_string())).build();
+        job.id = id;
+        job.state = state;
+        job
+    }
+
+    #[test]
+    fn active_candidates_skip_final_states() {
+        let jobs = vec![
+            job(1, "a", JobState::Running),
+            job(2, "b", JobState::Finished),
+            job(3, "a", JobState::Queued),
+        ];
+        assert_eq!(candidates(DynamicKind::Active, &jobs), vec!["1", "3"]);
+        assert_eq!(candidates(DynamicKind::Recent, &jobs), vec!["1", "2", "3"]);
+        assert_eq!(candidates(DynamicKind::Names, &jobs), vec!["a", "b"]);
+    }
+
+    #[test]
+    fn bash_script_registers_dynamic_wrapper() {
+        let script = dynamic_script(Shell::Bash, "gjob", &SPEC, "_gjob() { :; }\n".into());
+        assert!(script.contains("complete -F _gjob_dynamic -o bashdefault -o default gjob"));
+        assert!(script.contains("--names|-N) kind=names ;;"));
+        assert!(script.contains("log|l) _gflow_first_positional 2 \"$cword\" && kind=recent ;;"));
+        assert!(script.contains("gjob __complete \"$kind\""));
+    }
+
+    #[test]
+    fn zsh_script_renames_clap_entry_point() {
+        let mut cmd = clap::Command::new("gjob").arg(clap::Arg::new("job"));
+        let mut buf = Vec::new();
+        clap_complete::generate(Shell::Zsh, &mut cmd, "gjob", &mut buf);
+        let script = dynamic_script(Shell::Zsh, "gjob", &SPEC, String::from_utf8(buf).unwrap());
+
+        assert!(script.contains("_gjob_static() {"));
+        assert!(script.contains("_gjob_static \"$@\""));
+        let wrapper_at = script.find("\n_gjob() {").expect("wrapper defined");
+        let dispatch_at = script
+            .rfind("if [ \"$funcstack[1]\" = \"_gjob\" ]")
+            .unwrap();
+        assert!(wrapper_at < dispatch_at);
+    }
+
+    #[test]
+    fn fish_lines_call_hidden_helper() {
+        let lines = fish_lines("gqueue", &SPEC);
+        assert!(lines.contains(
+            "complete -c gqueue -s N -l names -x -a \"(gqueue __complete names 2>/dev/null)\""
+        ));
+        assert!(lines.contains("__fish_seen_subcommand_from log l"));
+    }
+}
@@ -0,0 +1,47 @@
+use clap::Parser;
+use std::path::PathBuf;
+
+#[derive(Debug, Parser)]
+#[command(
+    name = "config",
+    bin_name = "gflow config",
+    author,
+    version=gflow::build_info::version(),
+    about = "Inspects, validates, and edits the gflow configuration file."
+)]
+#[command(styles=gflow::utils::STYLES)]
+pub struct ConfigCli {
+    #[command(subcommand)]
+    pub command: Commands,
+
+    #[arg(long, global = true, help = "Path to the config file", hide = true)]
+    pub config: Option<PathBuf>,
+}
+
+#[derive(Debug, Parser)]
+pub enum Commands {
+    /// Check a config file for syntax errors, unknown keys, and invalid values
+    Validate {
+        /// Config file to check (default: the active config file)
+        #[arg(value_hint = clap::ValueHint::FilePath)]
+        path: Option<PathBuf>,
+    },
+    /// Print the effective value of a key, e.g. `daemon.gpus`
+    Get {
+        /// Dotted key path
+        key: String,
+    },
+    /// Set a key in the config file, keeping comments and formatting
+    Set {
+        /// Dotted key path, e.g. `daemon.port`
+        key: String,
+        /// TOML value (`4`, `true`, `[0, 1]`); anything else is stored as a string
+        value: String,
+    },
+    /// Print the config file, or the merged configuration with --effective
+    Show {
+        /// Print defaults + config file + GFLOW_* environment overrides as loaded
+        #[arg(long)]
+        effective: bool,
+    },
+}
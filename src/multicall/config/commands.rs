@@ -0,0 +1,122 @@
+use anyhow::{Context, Result};
+use gflow::config::{
+    check_value_key, config_file_path, load_config, set_config_value, validate_config_file,
+    ConfigError,
+};
+use std::path::{Path, PathBuf};
+
+fn target_path(config_path: &Option<PathBuf>) -> Result<PathBuf> {
+    config_file_path(config_path.as_ref()).map(|(path, _)| path)
+}
+
+fn report(errors: &[ConfigError]) {
+    for error in errors {
+        eprintln!("error: {error}");
+    }
+}
+
+pub(super) fn handle_validate(config_path: &Option<PathBuf>, path: Option<PathBuf>) -> Result<()> {
+    let path = match path {
+        Some(path) => path,
+        None => target_path(config_path)?,
+    };
+
+    match validate_config_file(&path) {
+        Ok(_) => {
+            println!("{} is valid", path.display());
+            Ok(())
+        }
+        Err(errors) => {
+            report(&errors);
+            anyhow::bail!("{} error(s) found in {}", errors.len(), path.display())
+        }
+    }
+}
+
+pub(super) fn handle_get(config_path: &Option<PathBuf>, key: &str) -> Result<()> {
+    check_value_key(key)?;
+    let config = load_config(config_path.as_ref())?;
+    let table = config
+        .to_effective_toml()
+        .context("Failed to serialize config")?;
+
+    let mut value = None;
+    let mut current = &table;
+    let segments: Vec<&str> = key.split('.').collect();
+    for (i, segment) in segments.iter().enumerate() {
+        match current.get(*segment) {
+            Some(toml::Value::Table(next)) if i + 1 < segments.len() => current = next,
+            Some(found) if i + 1 == segments.len() => value = Some(found),
+            _ => break,
+        }
+    }
+
+    match value {
+        Some(toml::Value::String(s)) => println!("{s}"),
+        Some(other) => println!("{other}"),
+        None => anyhow::bail!("'{key}' is not set"),
+    }
+    Ok(())
+}
+
+pub(super) fn handle_set(config_path: &Option<PathBuf>, key: &str, value: &str) -> Result<()> {
+    let path = target_path(config_path)?;
+    let content = if path.exists() {
+        std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?
+    } else {
+        String::new()
+    };
+
+    let updated = match set_config_value(&path, &content, key, value) {
+        Ok(updated) => updated,
+        Err(errors) => {
+            report(&errors);
+            anyhow::bail!("Refusing to write {}", path.display());
+        }
+    };
+    write_atomically(&path, &updated)?;
+    println!("Set {key} in {}", path.display());
+    Ok(())
+}
+
+pub(super) fn handle_show(config_path: &Option<PathBuf>, effective: bool) -> Result<()> {
+    if effective {
+        let config = load_config(config_path.as_ref())?;
+        let table = config
+            .to_effective_toml()
+            .context("Failed to serialize config")?;
+        print!(
+            "{}",
+            toml::to_string_pretty(&table).context("Failed to render config")?
+        );
+        return Ok(());
+    }
+
+    let path = target_path(config_path)?;
+    let content = std::fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    eprintln!("# {}", path.display());
+    print!("{content}");
+    Ok(())
+}
+
+pub(crate) fn write_atomically(path: &Path, content: &str) -> Result<()> {
+    let parent = path
+        .parent()
+        .filter(|p| !p.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    std::fs::create_dir_all(parent)
+        .with_context(|| format!("Failed to create config directory {}", parent.display()))?;
+
+    let tmp = parent.join(format!(".gflow.toml.tmp.{}", std::process::id()));
+    std::fs::write(&tmp, content)
+        .with_context(|| format!("Failed to write temp file {}", tmp.display()))?;
+    std::fs::rename(&tmp, path).with_context(|| {
+        format!(
+            "Failed to move temp file {} to {}",
+            tmp.display(),
+            path.display()
+        )
+    })
+}
@@ -0,0 +1,19 @@
+mod cli;
+mod commands;
+
+pub(crate) use commands::write_atomically;
+
+use anyhow::Result;
+use clap::Parser;
+use std::ffi::OsString;
+
+pub async fn run(argv: Vec<OsString>) -> Result<()> {
+    let args = cli::ConfigCli::parse_from(argv);
+
+    match args.command {
+        cli::Commands::Validate { path } => commands::handle_validate(&args.config, path),
+        cli::Commands::Get { key } => commands::handle_get(&args.config, &key),
+        cli::Commands::Set { key, value } => commands::handle_set(&args.config, &key, &value),
+        cli::Commands::Show { effective } => commands::handle_show(&args.config, effective),
+    }
+}
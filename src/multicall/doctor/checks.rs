@@ -0,0 +1,605 @@
+//! The checks `gflow doctor` runs, one function each
+//!
+//! Checks get what they look at as arguments (paths, a config, the output of
+//! `tmux -V`), so they can be tested without the real environment.
+
+use gflow::config::{Config, ConfigSource, ListenAddr};
+use gflow::core::version::Version;
+use serde::Serialize;
+use std::io::ErrorKind;
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+use crate::multicall::gflowd::pidfile;
+use crate::multicall::gflowd::scheduler_runtime::serialization;
+
+/// tmux releases gflow is developed against.
+const MIN_TMUX: (u32, u32) = (3, 0);
+/// How far in the future a state file may have been written before the
+/// clock is suspected of having gone back.
+const CLOCK_SKEW: Duration = Duration::from_secs(5 * 60);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub(super) enum Status {
+    Pass,
+    Warn,
+    Fail,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub(super) struct Check {
+    pub name: &'static str,
+    pub status: Status,
+    pub message: String,
+    /// What to do about a warning or failure
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hint: Option<String>,
+}
+
+impl Check {
+    fn pass(name: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: Status::Pass,
+            message: message.into(),
+            hint: None,
+        }
+    }
+
+    fn warn(name: &'static str, message: impl Into<String>, hint: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: Status::Warn,
+            message: message.into(),
+            hint: Some(hint.into()),
+        }
+    }
+
+    fn fail(name: &'static str, message: impl Into<String>, hint: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: Status::Fail,
+            message: message.into(),
+            hint: Some(hint.into()),
+        }
+    }
+}
+
+/// The config file at `path` parses and only has known keys with valid values.
+/// A missing file is fine unless it was asked for with `--config` or
+/// `GFLOW_CONFIG`.
+pub(super) fn config_file(path: &Path, source: ConfigSource) -> Check {
+    const NAME: &str = "config";
+    if !path.exists() {
+        return if source == ConfigSource::Default {
+            Check::pass(
+                NAME,
+                format!("no config file at {}; using defaults", path.display()),
+            )
+        } else {
+            Check::fail(
+                NAME,
+                format!("{} (from {source}) does not exist", path.display()),
+                format!("check the {source} path"),
+            )
+        };
+    }
+    match gflow::config::validate_config_file(path) {
+        Ok(_) => Check::pass(NAME, format!("{} (from {source}) is valid", path.display())),
+        Err(errors) => Check::fail(
+            NAME,
+            format!(
+                "{} (from {source}) has {} error(s), first: {}",
+                path.display(),
+                errors.len(),
+                errors[0]
+            ),
+            format!(
+                "see all of them with `gflow config validate {}`",
+                path.display()
+            ),
+        ),
+    }
+}
+
+/// The effective config (file plus `GFLOW_*` variables) cannot be loaded.
+pub(super) fn config_load_failed(error: &gflow::config::ConfigError) -> Check {
+    Check::fail(
+        "config",
+        format!("the config cannot be loaded: {error}"),
+        "check the GFLOW_* environment variables and `gflow config show`",
+    )
+}
+
+/// How the daemon answered, as far as the checks need to know.
+pub(super) enum DaemonProbe {
+    /// `/info/stats` answered with the daemon's version
+    Up {
+        version: String,
+        pid: u32,
+        persistence: gflow::core::info::PersistenceStatus,
+        /// From `/version`; `None` if the daemon predates it
+        min_client_version: Option<Version>,
+    },
+    /// `/health` answered but `/info/stats` did not
+    Limited(String),
+    Unreachable(String),
+}
+
+pub(super) async fn probe_daemon(config: &Config) -> DaemonProbe {
+    let client = match gflow::Client::build(config) {
+        Ok(client) => client.without_version_check(),
+        Err(e) => return DaemonProbe::Unreachable(format!("{e:#}")),
+    };
+    if let Err(e) = client.get_health().await {
+        return DaemonProbe::Unreachable(format!("{e:#}"));
+    }
+    let min_client_version = client
+        .get_version()
+        .await
+        .ok()
+        .flatten()
+        .map(|info| info.min_client_version);
+    match client.get_scheduler_stats().await {
+        Ok(stats) => DaemonProbe::Up {
+            version: stats.version,
+            pid: stats.pid,
+            persistence: stats.persistence,
+            min_client_version,
+        },
+        Err(e) => DaemonProbe::Limited(format!("{e:#}")),
+    }
+}
+
+pub(super) fn describe_addr(addr: &ListenAddr) -> String {
+    match addr {
+        ListenAddr::Tcp { host, port } => format!("{host}:{port}"),
+        ListenAddr::Unix(path) => format!("unix:{}", path.display()),
+    }
+}
+
+/// The daemon answers, accepts this client, and runs the same version.
+pub(super) fn daemon(probe: &DaemonProbe, addr: &ListenAddr, client_version: Version) -> Check {
+    const NAME: &str = "daemon";
+    let addr = describe_addr(addr);
+    match probe {
+        DaemonProbe::Up {
+            min_client_version: Some(minimum),
+            version,
+            ..
+        } if client_version < *minimum => Check::fail(
+            NAME,
+            format!("gflowd {version} at {addr} accepts clients from {minimum}, but this client is {client_version}"),
+            format!("upgrade gflow on this machine to {minimum} or later"),
+        ),
+        DaemonProbe::Up { version, pid, .. } if *version == client_version.to_string() => Check::pass(
+            NAME,
+            format!("gflowd {version} (PID {pid}) answers at {addr}"),
+        ),
+        DaemonProbe::Up { version, pid, .. } => Check::warn(
+            NAME,
+            format!("gflowd at {addr} (PID {pid}) runs {version}, but this client is {client_version}"),
+            "restart the daemon with `gflowd restart` after upgrading, or install the same version on both sides",
+        ),
+        DaemonProbe::Limited(e) => Check::warn(
+            NAME,
+            format!("gflowd answers at {addr}, but refused to report its version: {e}"),
+            "if it requires auth, set `daemon.token` or GFLOW_TOKEN",
+        ),
+        DaemonProbe::Unreachable(e) => Check::fail(
+            NAME,
+            format!("cannot reach gflowd at {addr}: {e}"),
+            "start it with `gflowd up`, or check `daemon.host` and `daemon.port`",
+        ),
+    }
+}
+
+/// The PID file, if any, belongs to a running process.
+pub(super) fn pid_file(path: &Path) -> Check {
+    const NAME: &str = "pidfile";
+    if !path.exists() {
+        return Check::pass(NAME, format!("no PID file at {}", path.display()));
+    }
+    match pidfile::read_pid(path) {
+        Some(pid) if pidfile::is_process_running(pid) => {
+            Check::pass(NAME, format!("{} holds running PID {pid}", path.display()))
+        }
+        Some(pid) => Check::warn(
+            NAME,
+            format!("{} is stale: PID {pid} is not running", path.display()),
+            format!("remove it with `rm {}`", path.display()),
+        ),
+        None => Check::warn(
+            NAME,
+            format!("{} does not hold a PID", path.display()),
+            format!("remove it with `rm {}`", path.display()),
+        ),
+    }
+}
+
+/// Nothing but gflowd listens on the daemon's address.
+pub(super) fn listen_addr(addr: &ListenAddr, daemon_reachable: bool) -> Check {
+    const NAME: &str = "port";
+    let described = describe_addr(addr);
+    if daemon_reachable {
+        return Check::pass(NAME, format!("{described} is served by gflowd"));
+    }
+    if !addr.is_local() {
+        return Check::pass(NAME, format!("{described} is not on this host"));
+    }
+    match addr {
+        ListenAddr::Tcp { host, port } => {
+            let host = match host.as_str() {
+                "localhost" => "127.0.0.1",
+                other => other,
+            };
+            match std::net::TcpListener::bind((host, *port)) {
+                Ok(_) => Check::pass(NAME, format!("{described} is free for gflowd")),
+                Err(e) if e.kind() == ErrorKind::AddrInUse => Check::fail(
+                    NAME,
+                    format!("{described} is in use by another program"),
+                    format!("find it with `ss -ltnp 'sport = :{port}'`, or move gflowd with `gflow config set daemon.port <port>`"),
+                ),
+                Err(e) => Check::warn(
+                    NAME,
+                    format!("cannot bind {described}: {e}"),
+                    "check `daemon.host`",
+                ),
+            }
+        }
+        ListenAddr::Unix(path) if path.exists() => Check::warn(
+            NAME,
+            format!("{} exists but gflowd does not answer on it", path.display()),
+            format!(
+                "remove the stale socket with `rm {}` if gflowd is not running",
+                path.display()
+            ),
+        ),
+        ListenAddr::Unix(_) => Check::pass(NAME, format!("{described} is free for gflowd")),
+    }
+}
+
+/// `tmux -V` printed a recent enough version; `None` if tmux did not run.
+pub(super) fn tmux(version_output: Option<&str>) -> Check {
+    const NAME: &str = "tmux";
+    let Some(output) = version_output else {
+        return Check::fail(
+            NAME,
+            "tmux is not installed or not on PATH",
+            "install it, e.g. `sudo apt install tmux`; gflowd runs every job in a tmux session",
+        );
+    };
+    let output = output.trim();
+    let version = output
+        .strip_prefix("tmux ")
+        .map(|v| v.trim_start_matches("next-"))
+        .and_then(|v| {
+            let (major, rest) = v.split_once('.')?;
+            let minor: String = rest.chars().take_while(char::is_ascii_digit).collect();
+            Some((major.parse::<u32>().ok()?, minor.parse::<u32>().ok()?))
+        });
+    match version {
+        Some(version) if version >= MIN_TMUX => Check::pass(NAME, output),
+        Some(_) => Check::warn(
+            NAME,
+            format!("{output} is older than {}.{}", MIN_TMUX.0, MIN_TMUX.1),
+            "upgrade tmux if jobs fail to start or lose output",
+        ),
+        None => Check::warn(
+            NAME,
+            format!("cannot tell the tmux version from {output:?}"),
+            "check that `tmux` is the real tmux",
+        ),
+    }
+}
+
+/// NVML loaded and found GPUs; without it only CPU jobs can run.
+pub(super) fn gpus(nvml: Result<u32, String>) -> Check {
+    const NAME: &str = "gpus";
+    const HINT: &str =
+        "install the NVIDIA driver and check `nvidia-smi`; without GPUs only CPU jobs can run";
+    match nvml {
+        Ok(0) => Check::warn(NAME, "NVML loaded but found no GPUs", HINT),
+        Ok(count) => Check::pass(NAME, format!("NVML found {count} GPU(s)")),
+        Err(e) => Check::warn(NAME, format!("NVML cannot be loaded: {e}"), HINT),
+    }
+}
+
+pub(super) fn nvml_gpu_count() -> Result<u32, String> {
+    let nvml = nvml_wrapper::Nvml::init().map_err(|e| e.to_string())?;
+    nvml.device_count().map_err(|e| e.to_string())
+}
+
+/// `dir` exists or can be created, and files can be written in it.
+pub(super) fn writable_dir(name: &'static str, dir: &Path) -> Check {
+    let probe = dir.join(format!(".gflow-doctor.{}", std::process::id()));
+    let result = std::fs::create_dir_all(dir)
+        .and_then(|_| std::fs::write(&probe, b""))
+        .and_then(|_| std::fs::remove_file(&probe));
+    match result {
+        Ok(()) => Check::pass(name, format!("{} is writable", dir.display())),
+        Err(e) => Check::fail(
+            name,
+            format!("cannot write to {}: {e}", dir.display()),
+            format!(
+                "make it writable for this user, e.g. `sudo chown -R $USER {}`",
+                dir.display()
+            ),
+        ),
+    }
+}
+
+/// The job state is healthy: as the running daemon reports it, or else as
+/// the state file in `state_dir` loads.
+pub(super) fn state(probe: &DaemonProbe, state_dir: &Path) -> Check {
+    const NAME: &str = "state";
+    if let DaemonProbe::Up { persistence, .. } = probe {
+        return match persistence.mode.as_str() {
+            "state" => Check::pass(NAME, "gflowd saves its state normally"),
+            mode => Check::fail(
+                NAME,
+                format!(
+                    "gflowd is in {mode} mode: {}",
+                    persistence
+                        .state_load_error
+                        .as_deref()
+                        .or(persistence.journal_error.as_deref())
+                        .unwrap_or("the state file cannot be written")
+                ),
+                "see `gflowd status` and `gflowd journal inspect`",
+            ),
+        };
+    }
+    match serialization::load_state_auto(state_dir) {
+        Ok(None) => Check::pass(
+            NAME,
+            format!("no state file in {} yet", state_dir.display()),
+        ),
+        Ok(Some(scheduler)) => match gflow::core::migrations::migrate_state(scheduler) {
+            Ok(scheduler) => Check::pass(
+                NAME,
+                format!(
+                    "the state file in {} loads ({} jobs)",
+                    state_dir.display(),
+                    scheduler.jobs_len()
+                ),
+            ),
+            Err(e) => Check::fail(
+                NAME,
+                format!("the state file cannot be migrated: {e:#}"),
+                "upgrade gflow, or restore an older state with `gflowd restore`",
+            ),
+        },
+        Err(e) => Check::fail(
+            NAME,
+            format!("the state file cannot be loaded: {e:#}"),
+            "inspect it with `gflowd journal inspect`, or restore a backup with `gflowd restore`",
+        ),
+    }
+}
+
+/// The clock is not before this gflow was built, and the state was not
+/// written in the future.
+pub(super) fn clock(
+    now: SystemTime,
+    built_at: Option<SystemTime>,
+    state_written_at: Option<SystemTime>,
+) -> Check {
+    const NAME: &str = "clock";
+    const HINT: &str = "fix the system clock, e.g. enable NTP with `timedatectl set-ntp true`";
+    let now_text = gflow::utils::format_system_time(now);
+    if let Some(built_at) = built_at.filter(|&built_at| now < built_at) {
+        return Check::fail(
+            NAME,
+            format!(
+                "the clock says {now_text}, before this gflow was built ({})",
+                gflow::utils::format_system_time(built_at)
+            ),
+            HINT,
+        );
+    }
+    if let Some(ahead) = state_written_at.and_then(|at| at.duration_since(now).ok()) {
+        if ahead > CLOCK_SKEW {
+            return Check::warn(
+                NAME,
+                format!(
+                    "the state file was written {} in the future; the clock went back",
+                    gflow::utils::format_duration(ahead)
+                ),
+                HINT,
+            );
+        }
+    }
+    Check::pass(NAME, format!("the clock says {now_text}"))
+}
+
+/// When this binary was built.
+pub(super) fn build_time() -> Option<SystemTime> {
+    chrono::DateTime::parse_from_rfc3339(env!("VERGEN_BUILD_TIMESTAMP"))
+        .ok()
+        .map(SystemTime::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gflow::core::info::PersistenceStatus;
+
+    fn up(version: &str, mode: &str) -> DaemonProbe {
+        DaemonProbe::Up {
+            version: version.to_string(),
+            pid: 42,
+            min_client_version: Some(Version::new(0, 4, 0)),
+            persistence: PersistenceStatus {
+                mode: mode.to_string(),
+                state_writable: mode == "state",
+                journal_writable: true,
+                state_load_error: (mode != "state").then(|| "disk full".to_string()),
+                journal_error: None,
+            },
+        }
+    }
+
+    fn tcp(port: u16) -> ListenAddr {
+        ListenAddr::Tcp {
+            host: "127.0.0.1".to_string(),
+            port,
+        }
+    }
+
+    #[test]
+    fn config_file_reports_missing_and_invalid_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("gflow.toml");
+        assert_eq!(
+            config_file(&path, ConfigSource::Default).status,
+            Status::Pass
+        );
+        let check = config_file(&path, ConfigSource::Env);
+        assert_eq!(check.status, Status::Fail);
+        assert!(
+            check.message.contains("from GFLOW_CONFIG"),
+            "{}",
+            check.message
+        );
+
+        std::fs::write(&path, "[daemon]\nport = 59001\n").unwrap();
+        assert_eq!(config_file(&path, ConfigSource::Flag).status, Status::Pass);
+
+        std::fs::write(&path, "[daemon]\nprot = 59001\n").unwrap();
+        let check = config_file(&path, ConfigSource::Default);
+        assert_eq!(check.status, Status::Fail);
+        assert!(check.message.contains("prot"), "{}", check.message);
+        assert!(check.hint.unwrap().contains("gflow config validate"));
+    }
+
+    #[test]
+    fn daemon_reports_version_mismatch_and_unreachable_daemon() {
+        let addr = tcp(59000);
+        let v = Version::new;
+        assert_eq!(
+            daemon(&up("0.4.0", "state"), &addr, v(0, 4, 0)).status,
+            Status::Pass
+        );
+        let check = daemon(&up("0.4.9", "state"), &addr, v(0, 4, 1));
+        assert_eq!(check.status, Status::Warn);
+        assert!(check.message.contains("0.4.9"), "{}", check.message);
+        let check = daemon(&up("0.5.0", "state"), &addr, v(0, 3, 9));
+        assert_eq!(check.status, Status::Fail);
+        assert!(check.hint.unwrap().contains("0.4.0"));
+        let unreachable = DaemonProbe::Unreachable("connection refused".to_string());
+        assert_eq!(daemon(&unreachable, &addr, v(0, 4, 0)).status, Status::Fail);
+    }
+
+    #[tokio::test]
+    async fn probe_daemon_finds_nothing_on_a_closed_port() {
+        let port = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let mut config = Config::default();
+        config.daemon.host = "127.0.0.1".to_string();
+        config.daemon.port = port;
+        assert!(matches!(
+            probe_daemon(&config).await,
+            DaemonProbe::Unreachable(_)
+        ));
+    }
+
+    #[test]
+    fn pid_file_flags_stale_and_garbled_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("gflowd.pid");
+        assert_eq!(pid_file(&path).status, Status::Pass);
+
+        std::fs::write(&path, format!("{}\n", std::process::id())).unwrap();
+        assert_eq!(pid_file(&path).status, Status::Pass);
+        std::fs::write(&path, format!("{}\n", i32::MAX)).unwrap();
+        let check = pid_file(&path);
+        assert_eq!(check.status, Status::Warn);
+        assert!(check.message.contains("stale"), "{}", check.message);
+        std::fs::write(&path, "garbage").unwrap();
+        assert_eq!(pid_file(&path).status, Status::Warn);
+    }
+
+    #[test]
+    fn listen_addr_fails_when_another_program_holds_the_port() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = tcp(listener.local_addr().unwrap().port());
+        assert_eq!(listen_addr(&addr, false).status, Status::Fail);
+        assert_eq!(listen_addr(&addr, true).status, Status::Pass);
+        drop(listener);
+        assert_eq!(listen_addr(&addr, false).status, Status::Pass);
+
+        let dir = tempfile::tempdir().unwrap();
+        let socket = ListenAddr::Unix(dir.path().join("gflowd.sock"));
+        assert_eq!(listen_addr(&socket, false).status, Status::Pass);
+        std::fs::write(dir.path().join("gflowd.sock"), "").unwrap();
+        assert_eq!(listen_addr(&socket, false).status, Status::Warn);
+    }
+
+    #[test]
+    fn tmux_needs_a_recent_version() {
+        assert_eq!(tmux(Some("tmux 3.3a\n")).status, Status::Pass);
+        assert_eq!(tmux(Some("tmux next-3.5")).status, Status::Pass);
+        assert_eq!(tmux(Some("tmux 2.6")).status, Status::Warn);
+        assert_eq!(tmux(Some("screen")).status, Status::Warn);
+        assert_eq!(tmux(None).status, Status::Fail);
+    }
+
+    #[test]
+    fn gpus_warns_without_nvml() {
+        assert_eq!(gpus(Ok(8)).status, Status::Pass);
+        assert_eq!(gpus(Ok(0)).status, Status::Warn);
+        assert_eq!(
+            gpus(Err("libnvidia-ml.so not found".into())).status,
+            Status::Warn
+        );
+    }
+
+    #[test]
+    fn writable_dir_creates_missing_dirs_and_fails_under_a_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let data = dir.path().join("share/gflow");
+        assert_eq!(writable_dir("data_dir", &data).status, Status::Pass);
+        assert!(data.is_dir());
+        assert_eq!(std::fs::read_dir(&data).unwrap().count(), 0);
+
+        std::fs::write(dir.path().join("file"), "").unwrap();
+        let check = writable_dir("data_dir", &dir.path().join("file/gflow"));
+        assert_eq!(check.status, Status::Fail);
+    }
+
+    #[test]
+    fn state_uses_the_daemon_report_or_the_state_file() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(
+            state(&up("0.4.0", "state"), dir.path()).status,
+            Status::Pass
+        );
+        let check = state(&up("0.4.0", "journal"), dir.path());
+        assert_eq!(check.status, Status::Fail);
+        assert!(check.message.contains("disk full"), "{}", check.message);
+
+        let offline = DaemonProbe::Unreachable(String::new());
+        assert_eq!(state(&offline, dir.path()).status, Status::Pass);
+        std::fs::write(dir.path().join("state.msgpack"), b"\xc1garbage").unwrap();
+        assert_eq!(state(&offline, dir.path()).status, Status::Fail);
+    }
+
+    #[test]
+    fn clock_catches_times_before_the_build_or_behind_the_state() {
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(1_800_000_000);
+        let hour = Duration::from_secs(3600);
+        assert_eq!(clock(now, Some(now - hour), Some(now)).status, Status::Pass);
+        assert_eq!(clock(now, Some(now + hour), None).status, Status::Fail);
+        assert_eq!(clock(now, None, Some(now + hour)).status, Status::Warn);
+        assert_eq!(
+            clock(now, None, Some(now + Duration::from_secs(60))).status,
+            Status::Pass
+        );
+        assert!(build_time().is_some());
+    }
+}
@@ -0,0 +1,21 @@
+use clap::Parser;
+use std::path::PathBuf;
+
+#[derive(Debug, Parser)]
+#[command(
+    name = "doctor",
+    bin_name = "gflow doctor",
+    author,
+    version=gflow::build_info::version(),
+    about = "Checks the gflow installation, configuration, and daemon for common problems.",
+    after_help = "Exit status: 0 when no check failed (warnings allowed), 1 otherwise."
+)]
+#[command(styles=gflow::utils::STYLES)]
+pub struct DoctorCli {
+    /// Print the results as JSON
+    #[arg(long)]
+    pub json: bool,
+
+    #[arg(long, help = "Path to the config file", hide = true)]
+    pub config: Option<PathBuf>,
+}
@@ -0,0 +1,119 @@
+mod checks;
+mod cli;
+
+use anyhow::Result;
+use checks::{Check, DaemonProbe, Status};
+use clap::Parser;
+use gflow::utils::output::OutputStyle;
+use owo_colors::Style;
+use std::ffi::OsString;
+use std::time::SystemTime;
+
+pub async fn run(argv: Vec<OsString>) -> Result<()> {
+    let args = cli::DoctorCli::parse_from(argv);
+    let results = run_checks(&args).await;
+
+    if args.json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&serde_json::json!({ "checks": results }))?
+        );
+    } else {
+        print_report(&results);
+    }
+
+    let failed = results.iter().filter(|c| c.status == Status::Fail).count();
+    if failed > 0 {
+        anyhow::bail!("{failed} check(s) failed");
+    }
+    Ok(())
+}
+
+async fn run_checks(args: &cli::DoctorCli) -> Vec<Check> {
+    let mut results = Vec::new();
+
+    if let Ok((path, source)) = gflow::config::config_file_path(args.config.as_ref()) {
+        results.push(checks::config_file(&path, source));
+    }
+    let config = match gflow::config::load_config(args.config.as_ref()) {
+        Ok(config) => config,
+        Err(e) => {
+            // The file check above already explains a broken file.
+            if results.iter().all(|c| c.status != Status::Fail) {
+                results.push(checks::config_load_failed(&e));
+            }
+            gflow::config::Config::default()
+        }
+    };
+
+    let addr = config.daemon.listen_addr();
+    let probe = checks::probe_daemon(&config).await;
+    results.push(checks::daemon(
+        &probe,
+        &addr,
+        gflow::core::version::Version::current(),
+    ));
+    if let Ok(path) = gflow::paths::get_pid_file_path() {
+        results.push(checks::pid_file(&path));
+    }
+    results.push(checks::listen_addr(
+        &addr,
+        !matches!(probe, DaemonProbe::Unreachable(_)),
+    ));
+
+    let tmux = std::process::Command::new("tmux")
+        .arg("-V")
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| String::from_utf8_lossy(&output.stdout).into_owned());
+    results.push(checks::tmux(tmux.as_deref()));
+    results.push(checks::gpus(checks::nvml_gpu_count()));
+
+    let data_dir = gflow::paths::get_data_dir();
+    if let Ok(dir) = &data_dir {
+        results.push(checks::writable_dir("data_dir", dir));
+    }
+    if let Ok(dir) = gflow::paths::get_runtime_dir() {
+        results.push(checks::writable_dir("runtime_dir", &dir));
+    }
+
+    let state_written_at = data_dir.as_ref().ok().and_then(|dir| {
+        ["state.msgpack", "state.json"]
+            .iter()
+            .find_map(|name| std::fs::metadata(dir.join(name)).ok()?.modified().ok())
+    });
+    if let Ok(dir) = &data_dir {
+        results.push(checks::state(&probe, dir));
+    }
+    results.push(checks::clock(
+        SystemTime::now(),
+        checks::build_time(),
+        state_written_at,
+    ));
+    results
+}
+
+fn print_report(results: &[Check]) {
+    let style = OutputStyle::stdout();
+    let width = results.iter().map(|c| c.name.len()).max().unwrap_or(0);
+    for check in results {
+        let label = match check.status {
+            Status::Pass => style.paint("[ OK ]", Style::new().green()),
+            Status::Warn => style.paint("[WARN]", Style::new().yellow().bold()),
+            Status::Fail => style.paint("[FAIL]", Style::new().red().bold()),
+        };
+        println!("{label} {:width$}  {}", check.name, check.message);
+        if let Some(hint) = &check.hint {
+            println!("       {:width$}  hint: {hint}", "");
+        }
+    }
+
+    let count = |status| results.iter().filter(|c| c.status == status).count();
+    println!(
+        "\n{} passed, {} warning(s), {} failed",
+        count(Status::Pass),
+        count(Status::Warn),
+        count(Status::Fail)
+    );
+}
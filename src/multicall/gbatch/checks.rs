@@ -0,0 +1,263 @@
+//! Submission-time checks for job scripts and job environments
+//!
+//! These catch problems that would otherwise only show up as a job failing
+//! instantly inside its tmux session.
+
+use anyhow::{bail, Context, Result};
+use gflow::core::job::EnvSpec;
+use gflow::utils::output::print_warning;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+/// Extensions that mark a lone argument as a script rather than a command.
+const SCRIPT_EXTENSIONS: &[&str] = &["sh", "bash", "zsh"];
+
+pub(crate) struct ScriptChecks<'a> {
+    /// Add the execute bit when it is missing (`--no-chmod` turns this off).
+    pub(crate) chmod: bool,
+    /// Accept CRLF line endings (`--allow-crlf`).
+    pub(crate) allow_crlf: bool,
+    /// Shell gflowd runs the script with if it has no shebang.
+    pub(crate) default_shell: &'a str,
+}
+
+/// Check the single positional argument of a submission.
+///
+/// An argument that names an existing file is validated as a script. One that
+/// looks like a script path but does not exist is an error, rather than being
+/// submitted as a command that fails as soon as it runs.
+pub(crate) fn check_script_argument(argument: &str, checks: &ScriptChecks) -> Result<()> {
+    let path = Path::new(argument);
+    if path.exists() {
+        return check_script(path, checks);
+    }
+    let looks_like_script = path
+        .extension()
+        .is_some_and(|ext| SCRIPT_EXTENSIONS.iter().any(|known| ext == *known));
+    if looks_like_script && !argument.contains(char::is_whitespace) {
+        bail!("Script not found: {argument}");
+    }
+    Ok(())
+}
+
+pub(crate) fn check_script(path: &Path, checks: &ScriptChecks) -> Result<()> {
+    let metadata =
+        fs::metadata(path).with_context(|| format!("Cannot access script {}", path.display()))?;
+    if !metadata.is_file() {
+        bail!("Script {} is not a regular file", path.display());
+    }
+    let content =
+        fs::read(path).with_context(|| format!("Script {} is not readable", path.display()))?;
+
+    check_line_endings(&path.display().to_string(), &content, checks.allow_crlf)?;
+    check_execute_bit(path, &metadata, checks.chmod);
+    if !content.starts_with(b"#!") {
+        eprintln!(
+            "Note: {} has no shebang line; gflowd will run it with `{}`",
+            path.display(),
+            checks.default_shell
+        );
+    }
+    Ok(())
+}
+
+fn check_line_endings(name: &str, content: &[u8], allow_crlf: bool) -> Result<()> {
+    if allow_crlf || !content.windows(2).any(|pair| pair == b"\r\n") {
+        return Ok(());
+    }
+    bail!(
+        "Script {name} has Windows (CRLF) line endings, which make the shell fail with \
+         errors like `$'\\r': command not found`.\n\
+         Convert it with `dos2unix {name}` or `sed -i 's/\\r$//' {name}`, \
+         or pass --allow-crlf to submit it anyway."
+    )
+}
+
+#[cfg(unix)]
+fn check_execute_bit(path: &Path, metadata: &fs::Metadata, chmod: bool) {
+    use std::os::unix::fs::PermissionsExt;
+
+    let mode = metadata.permissions().mode();
+    if mode & 0o111 != 0 {
+        return;
+    }
+    if !chmod {
+        print_warning(format_args!(
+            "{} is not executable; gflowd will run it with its default shell",
+            path.display()
+        ));
+        return;
+    }
+
+    // Grant execute wherever read is granted, like `chmod +x` under a typical umask.
+    let new_mode = mode | ((mode & 0o444) >> 2);
+    match fs::set_permissions(path, fs::Permissions::from_mode(new_mode)) {
+        Ok(()) => print_warning(format_args!(
+            "{} was not executable; added execute permission (use --no-chmod to skip)",
+            path.display()
+        )),
+        Err(error) => print_warning(format_args!(
+            "{} is not executable and could not be made so: {error}",
+            path.display()
+        )),
+    }
+}
+
+#[cfg(not(unix))]
+fn check_execute_bit(_path: &Path, _metadata: &fs::Metadata, _chmod: bool) {}
+
+/// Check that `name` is a conda environment, if conda is available to ask.
+pub(crate) fn check_conda_env(name: &str) -> Result<()> {
+    let output = match Command::new("conda").args(["env", "list"]).output() {
+        Ok(output) if output.status.success() => output,
+        Ok(output) => {
+            print_warning(format_args!(
+                "`conda env list` failed ({}); cannot verify conda environment '{name}'",
+                output.status
+            ));
+            return Ok(());
+        }
+        Err(_) => {
+            print_warning(format_args!(
+                "conda not found on PATH; cannot verify conda environment '{name}'"
+            ));
+            return Ok(());
+        }
+    };
+
+    let listing = String::from_utf8_lossy(&output.stdout);
+    if !conda_env_listed(&listing, name) {
+        bail!("Conda environment '{name}' does not exist (see `conda env list`)");
+    }
+    Ok(())
+}
+
+/// Warn when `venv` has no activation script yet.
+///
+/// Only a warning: an earlier job may create the venv. gflowd fails the job
+/// if the script is still missing when it is scheduled.
+pub(crate) fn check_venv(venv: &Path) {
+    let activate = EnvSpec::venv_activate_script(venv);
+    if !activate.is_file() {
+        print_warning(format_args!(
+            "{} does not exist; the job will fail unless the venv is created before it starts",
+            activate.display()
+        ));
+    }
+}
+
+/// Whether `conda env list` output contains `name`, as a name or a prefix path.
+fn conda_env_listed(listing: &str, name: &str) -> bool {
+    listing
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .any(|line| {
+            // Unnamed environments are listed by path only; the path is always last.
+            line.split_whitespace().next() == Some(name)
+                || line.split_whitespace().last() == Some(name)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn checks() -> ScriptChecks<'static> {
+        ScriptChecks {
+            chmod: true,
+            allow_crlf: false,
+            default_shell: "bash",
+        }
+    }
+
+    #[test]
+    fn missing_script_is_an_error() {
+        let err = check_script_argument("/nonexistent/train.sh", &checks()).unwrap_err();
+        assert_eq!(err.to_string(), "Script not found: /nonexistent/train.sh");
+
+        // Commands are left alone.
+        check_script_argument("nvidia-smi", &checks()).unwrap();
+        check_script_argument("python train.py", &checks()).unwrap();
+    }
+
+    #[test]
+    fn directory_is_not_a_script() {
+        let dir = tempfile::tempdir().unwrap();
+        let err = check_script(dir.path(), &checks()).unwrap_err();
+        assert!(err.to_string().contains("is not a regular file"), "{err}");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn unreadable_script_is_an_error() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let script = dir.path().join("job.sh");
+        fs::write(&script, "#!/bin/bash\necho hi\n").unwrap();
+        fs::set_permissions(&script, fs::Permissions::from_mode(0o000)).unwrap();
+        if fs::read(&script).is_ok() {
+            // Running as root; permissions are not enforced.
+            return;
+        }
+        let err = check_script(&script, &checks()).unwrap_err();
+        assert!(err.to_string().contains("is not readable"), "{err}");
+    }
+
+    #[test]
+    fn crlf_is_refused_unless_allowed() {
+        let dir = tempfile::tempdir().unwrap();
+        let script = dir.path().join("job.sh");
+        fs::write(&script, "#!/bin/bash\r\necho hi\r\n").unwrap();
+
+        let err = check_script(&script, &checks()).unwrap_err();
+        assert!(err.to_string().contains("CRLF"), "{err}");
+        assert!(err.to_string().contains("--allow-crlf"), "{err}");
+
+        let allow = ScriptChecks {
+            allow_crlf: true,
+            ..checks()
+        };
+        check_script(&script, &allow).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn missing_execute_bit_is_fixed_unless_opted_out() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let script = dir.path().join("job.sh");
+        fs::write(&script, "#!/bin/bash\necho hi\n").unwrap();
+        let mode = |path: &Path| fs::metadata(path).unwrap().permissions().mode() & 0o777;
+
+        fs::set_permissions(&script, fs::Permissions::from_mode(0o644)).unwrap();
+        let no_chmod = ScriptChecks {
+            chmod: false,
+            ..checks()
+        };
+        check_script(&script, &no_chmod).unwrap();
+        assert_eq!(mode(&script), 0o644);
+
+        check_script(&script, &checks()).unwrap();
+        assert_eq!(mode(&script), 0o755);
+    }
+
+    #[test]
+    fn conda_env_list_parsing() {
+        let listing = "\
+# conda environments:
+#
+base                  *  /opt/conda
+torch                    /opt/conda/envs/torch
+                         /home/alice/project/.env
+";
+        assert!(conda_env_listed(listing, "base"));
+        assert!(conda_env_listed(listing, "torch"));
+        assert!(conda_env_listed(listing, "/home/alice/project/.env"));
+        assert!(!conda_env_listed(listing, "tensorflow"));
+        assert!(!conda_env_listed(listing, "*"));
+    }
+}
@@ -14,12 +14,22 @@ pub struct GBatch {
 
     #[arg(long, global = true, help = "Path to the config file", hide = true)]
     pub config: Option<std::path::PathBuf>,
+
+    /// Talk to the daemon of this `[remotes.<name>]` entry (or set GFLOW_REMOTE)
+    #[arg(long, global = true, value_name = "NAME")]
+    pub remote: Option<String>,
+
+    /// Run even if gflowd declares this client too old (or set GFLOW_SKIP_VERSION_CHECK=1)
+    #[arg(long, global = true)]
+    pub skip_version_check: bool,
 }
 
 #[derive(Debug, Parser)]
 pub enum Commands {
     /// Create a new job script template
     New(NewArgs),
+    /// Submit jobs spooled while the daemon was unreachable
+    FlushSpool,
     /// Generate shell completion scripts
     Completion {
         /// The shell to generate completions for
@@ -40,21 +50,81 @@ pub struct AddArgs {
     #[arg(trailing_var_arg = true, allow_hyphen_values = true, value_hint = clap::ValueHint::CommandWithArguments)]
     pub script_or_command: Vec<String>,
 
+    /// Read the job script from standard input (same as passing "-")
+    #[arg(long, conflicts_with = "script_or_command")]
+    pub stdin: bool,
+
+    /// Start a shell as a job, wait for it to run, and attach to it
+    #[arg(
+        short = 'i',
+        long,
+        conflicts_with_all = [
+            "script_or_command", "stdin", "array", "param", "param_file",
+            "container", "executor", "dry_run", "spool",
+        ]
+    )]
+    pub interactive: bool,
+
+    /// Submit the steps of a pipeline file (YAML or TOML) as one batch
+    #[arg(
+        long,
+        value_name = "FILE",
+        value_hint = clap::ValueHint::FilePath,
+        conflicts_with_all = [
+            "script_or_command", "stdin", "interactive", "array", "param", "param_file",
+            "name_template", "name", "depends_on", "depends_on_all", "depends_on_any",
+        ]
+    )]
+    pub pipeline: Option<std::path::PathBuf>,
+
     /// The conda environment to use
     #[arg(short, long, value_hint = clap::ValueHint::Other)]
     pub conda_env: Option<String>,
 
+    /// Activate the virtualenv at this path before running the job
+    #[arg(long, conflicts_with_all = ["conda_env", "uv"], value_hint = clap::ValueHint::DirPath)]
+    pub venv: Option<std::path::PathBuf>,
+
+    /// Run the job through `uv run`
+    #[arg(long, conflicts_with = "conda_env")]
+    pub uv: bool,
+
+    /// Run the job inside this container image (see `[container]` in the config)
+    #[arg(long, value_name = "IMAGE", conflicts_with_all = ["conda_env", "venv", "uv"], value_hint = clap::ValueHint::Other)]
+    pub container: Option<String>,
+
+    /// Start the job in a tmux session (`tmux`) or as a plain process (`direct`);
+    /// defaults to `[daemon] executor`
+    #[arg(long, value_name = "tmux|direct")]
+    pub executor: Option<gflow::core::executor::ExecutorKind>,
+
     /// The GPU count to request
     #[arg(short, long, visible_alias = "gres", name = "NUMS")]
     pub gpus: Option<u32>,
 
+    /// Limit the job to this many CPU cores, pinned for it by the scheduler
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u32).range(1..))]
+    pub cpus: Option<u32>,
+
     /// Allow this job to share allocated GPU(s) with other shared jobs
     #[arg(long)]
     pub shared: bool,
 
-    /// The priority of the job
-    #[arg(short = 'p', long, visible_alias = "nice")]
-    pub priority: Option<u8>,
+    /// The priority of the job: 0-255 (higher runs first) or a class from `[priorities]`
+    #[arg(short = 'p', long, visible_alias = "nice", value_name = "PRIORITY")]
+    pub priority: Option<String>,
+
+    /// Allow a higher-priority job to stop and requeue this one
+    #[arg(long, conflicts_with = "interactive")]
+    pub preemptible: bool,
+
+    /// Never remove this job's GFLOW_JOB_DIR automatically (`daemon.job_dir_retention_secs`)
+    #[arg(long)]
+    pub keep_job_dir: bool,
+
+    /// Only run on GPUs or nodes with these labels, e.g. "vram=48g,arch=ampere|hopper"
+    #[arg(long, value_name = "LABELS", value_hint = clap::ValueHint::Other)]
+    pub constraint: Option<String>,
 
     /// Job dependency; accepts a job ID or shorthand like "@" / "@~N"
     #[arg(short = 'd', long, visible_alias = "dependency", value_hint = clap::ValueHint::Other)]
@@ -104,6 +174,21 @@ pub struct AddArgs {
     )]
     pub gpu_memory: Option<String>,
 
+    /// Keep the job queued until its run_dir and the log dir have this much
+    /// free space (formats: "50G", "1024M"); defaults to `[daemon] min_free_disk`
+    #[arg(long, value_name = "SIZE", value_hint = clap::ValueHint::Other)]
+    pub min_free_disk: Option<String>,
+
+    /// Queue the job even if it asks for more GPUs, memory or CPU cores than
+    /// any machine has, e.g. for hardware that is yet to arrive
+    #[arg(long)]
+    pub force_queue: bool,
+
+    /// Submit for another user: needs an admin token, or
+    /// `daemon.allow_as_user` on daemons without authentication
+    #[arg(long, value_name = "USER", value_hint = clap::ValueHint::Username)]
+    pub as_user: Option<String>,
+
     /// Custom run name for the job (used as tmux session name)
     #[arg(
         short = 'n',
@@ -127,6 +212,38 @@ pub struct AddArgs {
     #[arg(long)]
     pub dry_run: bool,
 
+    /// After submitting, also show the group, priority, dependencies, queue position and free GPUs
+    #[arg(short = 'v', long, conflicts_with = "quiet")]
+    pub verbose: bool,
+
+    /// Print only the IDs of the submitted jobs, one per line
+    #[arg(short = 'q', long)]
+    pub quiet: bool,
+
+    /// Print the defaults that apply here and where each comes from, then exit
+    #[arg(long)]
+    pub show_effective_config: bool,
+
+    /// `[gbatch]` defaults from the user config and the project's `.gflow.toml`
+    #[arg(skip)]
+    pub defaults: gflow::config::GbatchConfig,
+
+    /// Named priorities from `[priorities]` that `--priority` accepts
+    #[arg(skip)]
+    pub priority_classes: std::collections::BTreeMap<String, u8>,
+
+    /// If the daemon is unreachable, save the submission locally and send it later
+    #[arg(long)]
+    pub spool: bool,
+
+    /// Do not add the execute permission to a script that lacks it
+    #[arg(long)]
+    pub no_chmod: bool,
+
+    /// Submit scripts with Windows (CRLF) line endings anyway
+    #[arg(long)]
+    pub allow_crlf: bool,
+
     /// Maximum number of jobs from this submission that can run concurrently
     #[arg(long, value_hint = clap::ValueHint::Other)]
     pub max_concurrent: Option<usize>,
@@ -148,6 +265,10 @@ pub struct AddArgs {
     #[arg(short = 'P', long, value_hint = clap::ValueHint::Other)]
     pub project: Option<String>,
 
+    /// Tag for filtering with `gqueue --tag` (comma-separated or repeated)
+    #[arg(long, value_delimiter = ',', value_hint = clap::ValueHint::Other)]
+    pub tag: Vec<String>,
+
     /// Additional email recipient for this job's notifications
     #[arg(long = "notify-email", value_hint = clap::ValueHint::EmailAddress)]
     pub notify_email: Vec<String>,
@@ -180,7 +301,7 @@ mod tests {
         .expect("should parse SLURM-compatible aliases");
 
         assert_eq!(args.add_args.time.as_deref(), Some("2:00:00"));
-        assert_eq!(args.add_args.priority, Some(10));
+        assert_eq!(args.add_args.priority.as_deref(), Some("10"));
         assert_eq!(args.add_args.name.as_deref(), Some("train"));
         assert_eq!(args.add_args.gpus, Some(2));
         assert!(!args.add_args.shared);
@@ -191,6 +312,76 @@ mod tests {
         );
     }
 
+    #[test]
+    fn parses_spool_flag_and_flush_command() {
+        let args = GBatch::try_parse_from(["gbatch", "--spool", "script.sh"])
+            .expect("should parse --spool flag");
+        assert!(args.add_args.spool);
+
+        let args = GBatch::try_parse_from(["gbatch", "flush-spool"])
+            .expect("should parse flush-spool command");
+        assert!(matches!(args.commands, Some(Commands::FlushSpool)));
+    }
+
+    #[test]
+    fn stdin_flag_conflicts_with_command() {
+        let args = GBatch::try_parse_from(["gbatch", "--gpus", "1", "--stdin"])
+            .expect("should parse --stdin flag");
+        assert!(args.add_args.stdin);
+        assert!(args.add_args.script_or_command.is_empty());
+
+        assert!(GBatch::try_parse_from(["gbatch", "--stdin", "script.sh"]).is_err());
+    }
+
+    #[test]
+    fn interactive_flag_takes_no_command() {
+        let args = GBatch::try_parse_from(["gbatch", "--interactive", "--gpus", "2"])
+            .expect("should parse --interactive flag");
+        assert!(args.add_args.interactive);
+        assert_eq!(args.add_args.gpus, Some(2));
+
+        assert!(GBatch::try_parse_from(["gbatch", "-i", "python", "train.py"]).is_err());
+        assert!(GBatch::try_parse_from(["gbatch", "-i", "--executor", "direct"]).is_err());
+    }
+
+    #[test]
+    fn preemptible_flag_conflicts_with_interactive() {
+        let args = GBatch::try_parse_from(["gbatch", "--preemptible", "script.sh"])
+            .expect("should parse --preemptible flag");
+        assert!(args.add_args.preemptible);
+
+        assert!(GBatch::try_parse_from(["gbatch", "-i", "--preemptible"]).is_err());
+    }
+
+    #[test]
+    fn pipeline_flag_replaces_the_command() {
+        let args = GBatch::try_parse_from(["gbatch", "--pipeline", "flow.yaml", "--gpus", "1"])
+            .expect("should parse --pipeline flag");
+        assert_eq!(
+            args.add_args.pipeline.as_deref(),
+            Some(std::path::Path::new("flow.yaml"))
+        );
+
+        assert!(GBatch::try_parse_from(["gbatch", "--pipeline", "flow.yaml", "train.sh"]).is_err());
+        assert!(
+            GBatch::try_parse_from(["gbatch", "--pipeline", "flow.yaml", "--depends-on", "3"])
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn verbose_and_quiet_conflict() {
+        let args = GBatch::try_parse_from(["gbatch", "-q", "python", "train.py", "-v"]).unwrap();
+        assert!(args.add_args.quiet);
+        assert!(!args.add_args.verbose);
+        assert_eq!(
+            args.add_args.script_or_command,
+            ["python", "train.py", "-v"]
+        );
+
+        assert!(GBatch::try_parse_from(["gbatch", "-v", "-q", "python", "train.py"]).is_err());
+    }
+
     #[test]
     fn parses_shared_flag() {
         let args = GBatch::try_parse_from(["gbatch", "--shared", "script.sh"])
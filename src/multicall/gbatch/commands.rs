@@ -2,11 +2,18 @@ use super::cli::Commands;
 use clap::CommandFactory;
 
 pub mod add;
+mod flush_spool;
+pub mod interactive;
 mod new;
+pub mod pipeline;
 
-pub async fn handle_commands(_: &gflow::config::Config, commands: Commands) -> anyhow::Result<()> {
+pub async fn handle_commands(
+    config: &gflow::config::Config,
+    commands: Commands,
+) -> anyhow::Result<()> {
     match commands {
         Commands::New(new_args) => new::handle_new(new_args),
+        Commands::FlushSpool => flush_spool::handle_flush_spool(config).await,
         Commands::Completion { shell } => {
             crate::multicall::completion::handle_completion(
                 shell,
@@ -1,15 +1,21 @@
+use crate::multicall::gbatch::checks::{self, ScriptChecks};
 use crate::multicall::gbatch::cli;
+use crate::multicall::gbatch::spool::{self, Spool};
 use anyhow::{anyhow, Context, Result};
 use clap::Parser;
-use gflow::client::Client;
-use gflow::core::job::{GpuSharingMode, Job, JobNotifications};
+use gflow::client::{Client, ClientError, JobSubmitResponse};
+use gflow::config::RemoteConfig;
+use gflow::core::job::{EnvSpec, GpuSharingMode, Job, JobNotifications, JobState, Tags};
+use gflow::core::scheduler::{StartEstimate, ESTIMATE_HORIZON};
+use gflow::utils::constraint::Constraint;
+use gflow::utils::interpolation;
 use gflow::utils::parsers::parse_array_spec;
 use gflow::utils::{generate_param_combinations, parse_param_spec};
 use lettre::message::Mailbox;
-use std::{collections::HashMap, env, fs, io::Read, path::PathBuf};
+use std::{collections::HashMap, env, fs, io::Read, path::PathBuf, time::Duration};
 
 /// Validate project against configuration requirements
-fn validate_project(job: &mut Job, config: &gflow::config::Config) -> Result<()> {
+pub(super) fn validate_project(job: &mut Job, config: &gflow::config::Config) -> Result<()> {
     let normalized =
         gflow::utils::validate_project_policy(job.project.as_deref(), &config.projects)?;
     job.project = normalized.map(|s| s.into());
@@ -23,6 +29,55 @@ fn resolve_project(args: &cli::AddArgs, script_args: Option<&cli::AddArgs>) -> O
         .or_else(|| script_args.and_then(|s| s.project.clone()))
 }
 
+/// Script tags followed by those given on the command line
+fn resolve_tags(args: &cli::AddArgs, script_args: Option<&cli::AddArgs>) -> Result<Tags> {
+    let script_tags = script_args.map_or(&[][..], |s| &s.tag);
+    Tags::new(script_tags.iter().chain(&args.tag)).map_err(|e| anyhow!(e))
+}
+
+/// CLI constraint takes precedence over the script's.
+fn resolve_constraint(
+    args: &cli::AddArgs,
+    script_args: Option<&cli::AddArgs>,
+) -> Result<Option<Constraint>> {
+    args.constraint
+        .as_deref()
+        .or(script_args.and_then(|script_args| script_args.constraint.as_deref()))
+        .map(|constraint| {
+            constraint
+                .parse()
+                .with_context(|| format!("Invalid --constraint '{constraint}'"))
+        })
+        .transpose()
+}
+
+/// CLI `--min-free-disk` takes precedence over the script's, in MB.
+fn resolve_min_free_disk(
+    args: &cli::AddArgs,
+    script_args: Option<&cli::AddArgs>,
+) -> Result<Option<u64>> {
+    args.min_free_disk
+        .as_deref()
+        .or(script_args.and_then(|script_args| script_args.min_free_disk.as_deref()))
+        .map(|size| {
+            gflow::utils::parse_memory_limit(size)
+                .with_context(|| format!("Invalid --min-free-disk '{size}'"))
+        })
+        .transpose()
+}
+
+/// `--priority`, else the script's directive, else the `[gbatch]` default.
+fn resolve_priority(args: &cli::AddArgs, script_args: Option<&cli::AddArgs>) -> Result<u8> {
+    let priority = args
+        .priority
+        .as_deref()
+        .or(script_args.and_then(|script| script.priority.as_deref()));
+    match priority {
+        Some(priority) => gflow::utils::parse_priority(priority, &args.priority_classes),
+        None => Ok(args.defaults.priority.unwrap_or(10)),
+    }
+}
+
 fn default_per_job_notification_events() -> Vec<String> {
     vec![
         "job_completed".to_string(),
@@ -81,14 +136,44 @@ fn validate_shared_requires_gpu_memory(job: &Job) -> Result<()> {
     Ok(())
 }
 
-/// Substitute {param_name} patterns in command with actual values (for preview only)
-fn preview_substitute(command: &str, parameters: &HashMap<String, String>) -> String {
-    let mut result = command.to_string();
-    for (param_name, value) in parameters {
-        let pattern = format!("{{{}}}", param_name);
-        result = result.replace(&pattern, value);
-    }
-    result
+/// `command` with its parameters filled in, for a dry run; `{job_id}` and
+/// other placeholders known only once submitted are shown as written.
+pub(super) fn preview_substitute(command: &str, parameters: &HashMap<String, String>) -> String {
+    interpolation::expand(command, |name| {
+        Some(
+            parameters
+                .get(name)
+                .cloned()
+                .unwrap_or_else(|| format!("{{{name}}}")),
+        )
+    })
+    .unwrap_or_else(|_| command.to_string())
+}
+
+/// `command` with `{env:VAR}` filled in from this shell. Fails on a
+/// placeholder that is neither in `parameters` nor filled in by the daemon.
+pub(super) fn interpolate_command(
+    command: &str,
+    parameters: &HashMap<String, String>,
+) -> Result<String> {
+    let command = interpolation::expand_env(command, |var| env::var(var).ok())?;
+    interpolation::check_placeholders(&command, |name| parameters.contains_key(name))?;
+    Ok(command)
+}
+
+/// The command line given to gbatch, shell-quoted, with `{env:VAR}` filled
+/// in before quoting so that values cannot break out of their argument.
+fn command_from_args(args: &[String], parameters: &HashMap<String, String>) -> Result<String> {
+    let command = args
+        .iter()
+        .map(|arg| {
+            let arg = interpolation::expand_env(arg, |var| env::var(var).ok())?;
+            Ok(shell_escape::escape(arg.into()).into_owned())
+        })
+        .collect::<Result<Vec<_>>>()?
+        .join(" ");
+    interpolation::check_placeholders(&command, |name| parameters.contains_key(name))?;
+    Ok(command)
 }
 
 /// Substitute {param_name} patterns in template with actual values
@@ -144,9 +229,15 @@ pub(crate) async fn handle_add(
     use_stdin: bool,
 ) -> Result<()> {
     let client = Client::build(config).context("Failed to build client")?;
+    let transfer = ScriptTransfer::for_remote(config.selected_remote()?.map(|(_, remote)| remote));
+    let spool = if add_args.dry_run {
+        None
+    } else {
+        flush_spool_first(config, &add_args, &client).await?
+    };
 
     // Read stdin content if needed
-    let stdin_content = if use_stdin {
+    let stdin_script = if use_stdin {
         let mut buffer = String::new();
         std::io::stdin()
             .read_to_string(&mut buffer)
@@ -154,11 +245,38 @@ pub(crate) async fn handle_add(
         if buffer.trim().is_empty() {
             anyhow::bail!("No content provided via stdin");
         }
-        Some(buffer)
+        // A dry run previews the jobs without leaving a script behind, and
+        // an uploaded script is saved by the daemon instead.
+        let path = if add_args.dry_run || transfer == ScriptTransfer::Content {
+            PathBuf::from("<stdin>")
+        } else {
+            gflow::paths::save_script("stdin", &buffer)?
+        };
+        Some(StdinScript {
+            content: buffer,
+            path,
+        })
     } else {
         None
     };
 
+    if stdin_script.is_none() {
+        if let [argument] = add_args.script_or_command.as_slice() {
+            let checks = ScriptChecks {
+                chmod: !add_args.no_chmod,
+                allow_crlf: add_args.allow_crlf,
+                default_shell: &config.daemon.default_shell,
+            };
+            checks::check_script_argument(argument, &checks)?;
+        }
+    }
+    if let Some(conda_env) = &add_args.conda_env {
+        checks::check_conda_env(conda_env)?;
+    }
+    if let Some(venv) = &add_args.venv {
+        checks::check_venv(venv);
+    }
+
     // Validation: --param and --array are mutually exclusive
     if !add_args.param.is_empty() && add_args.array.is_some() {
         anyhow::bail!("Cannot use both --param and --array together");
@@ -193,18 +311,14 @@ pub(crate) async fn handle_add(
             param_combinations = merged;
         }
 
-        // Generate group_id if max_concurrent is specified
-        let group_id = if add_args.max_concurrent.is_some() {
-            Some(uuid::Uuid::new_v4())
-        } else {
-            None
-        };
+        // Every job of a sweep shares a group, so it can be listed and cancelled together
+        let group_id = uuid::Uuid::new_v4();
 
         // Dry-run mode: preview without submitting
         if add_args.dry_run {
             println!("Would submit {} batch job(s):", param_combinations.len());
             for (idx, params) in param_combinations.iter().enumerate() {
-                let job = build_job_with_params(&add_args, params, &client, stdin_content.as_ref())
+                let job = build_job_with_params(&add_args, params, &client, stdin_script.as_ref())
                     .await?;
 
                 // Show preview
@@ -226,39 +340,26 @@ pub(crate) async fn handle_add(
         let mut jobs = Vec::with_capacity(param_combinations.len());
         for params in &param_combinations {
             let mut job =
-                build_job_with_params(&add_args, params, &client, stdin_content.as_ref()).await?;
+                build_job_with_params(&add_args, params, &client, stdin_script.as_ref()).await?;
             // Validate project
             validate_project(&mut job, config)?;
+            transfer.apply(&mut job, stdin_script.as_ref())?;
             // Assign group_id and max_concurrent if needed
-            job.group_id = group_id;
+            job.group_id = Some(group_id);
             job.max_concurrent = add_args.max_concurrent;
             jobs.push(job);
         }
 
         // Submit in batch
-        let responses = client
-            .add_jobs(jobs)
-            .await
-            .context("Failed to add batch jobs")?;
+        let Some(responses) = submit(&client, spool.as_ref(), jobs).await? else {
+            return Ok(());
+        };
 
-        // Show group_id if jobs are part of a group
-        if let Some(ref gid) = group_id {
-            println!(
-                "Submitted {} batch jobs with group_id: {}",
-                responses.len(),
-                gid
-            );
-            println!(
-                "  (You can update the limit with: gctl set-limit {} <N>)",
-                responses[0].id
-            );
+        for response in &responses {
+            print_submitted(response, &add_args);
         }
-
-        for response in responses {
-            println!(
-                "Submitted batch job {} ({})",
-                response.id, response.run_name
-            );
+        if !add_args.quiet {
+            print_group(group_id, &responses, add_args.max_concurrent.is_some());
         }
 
         return Ok(());
@@ -275,18 +376,14 @@ pub(crate) async fn handle_add(
         // Generate cartesian product
         let param_combinations = generate_param_combinations(&param_specs);
 
-        // Generate group_id if max_concurrent is specified
-        let group_id = if add_args.max_concurrent.is_some() {
-            Some(uuid::Uuid::new_v4())
-        } else {
-            None
-        };
+        // Every job of a sweep shares a group, so it can be listed and cancelled together
+        let group_id = uuid::Uuid::new_v4();
 
         // Dry-run mode: preview without submitting
         if add_args.dry_run {
             println!("Would submit {} batch job(s):", param_combinations.len());
             for (idx, params) in param_combinations.iter().enumerate() {
-                let job = build_job_with_params(&add_args, params, &client, stdin_content.as_ref())
+                let job = build_job_with_params(&add_args, params, &client, stdin_script.as_ref())
                     .await?;
 
                 // Show preview
@@ -308,39 +405,26 @@ pub(crate) async fn handle_add(
         let mut jobs = Vec::with_capacity(param_combinations.len());
         for params in &param_combinations {
             let mut job =
-                build_job_with_params(&add_args, params, &client, stdin_content.as_ref()).await?;
+                build_job_with_params(&add_args, params, &client, stdin_script.as_ref()).await?;
             // Validate project
             validate_project(&mut job, config)?;
+            transfer.apply(&mut job, stdin_script.as_ref())?;
             // Assign group_id and max_concurrent if needed
-            job.group_id = group_id;
+            job.group_id = Some(group_id);
             job.max_concurrent = add_args.max_concurrent;
             jobs.push(job);
         }
 
         // Submit in batch
-        let responses = client
-            .add_jobs(jobs)
-            .await
-            .context("Failed to add batch jobs")?;
+        let Some(responses) = submit(&client, spool.as_ref(), jobs).await? else {
+            return Ok(());
+        };
 
-        // Show group_id if jobs are part of a group
-        if let Some(ref gid) = group_id {
-            println!(
-                "Submitted {} batch jobs with group_id: {}",
-                responses.len(),
-                gid
-            );
-            println!(
-                "  (You can update the limit with: gctl set-limit {} <N>)",
-                responses[0].id
-            );
+        for response in &responses {
+            print_submitted(response, &add_args);
         }
-
-        for response in responses {
-            println!(
-                "Submitted batch job {} ({})",
-                response.id, response.run_name
-            );
+        if !add_args.quiet {
+            print_group(group_id, &responses, add_args.max_concurrent.is_some());
         }
 
         return Ok(());
@@ -350,19 +434,15 @@ pub(crate) async fn handle_add(
     if let Some(array_spec) = &add_args.array {
         let task_ids = parse_array_spec(array_spec)?;
 
-        // Generate group_id if max_concurrent is specified
-        let group_id = if add_args.max_concurrent.is_some() {
-            Some(uuid::Uuid::new_v4())
-        } else {
-            None
-        };
+        // Every job of a sweep shares a group, so it can be listed and cancelled together
+        let group_id = uuid::Uuid::new_v4();
 
         // Dry-run mode for array jobs
         if add_args.dry_run {
             println!("Would submit {} array job(s):", task_ids.len());
             for (idx, task_id) in task_ids.iter().enumerate() {
                 let job =
-                    build_job(&add_args, Some(*task_id), &client, stdin_content.as_ref()).await?;
+                    build_job(&add_args, Some(*task_id), &client, stdin_script.as_ref()).await?;
 
                 let cmd = if let Some(c) = &job.command {
                     c.to_string()
@@ -386,46 +466,33 @@ pub(crate) async fn handle_add(
         let mut jobs = Vec::with_capacity(task_ids.len());
         for task_id in task_ids {
             let mut job =
-                build_job(&add_args, Some(task_id), &client, stdin_content.as_ref()).await?;
+                build_job(&add_args, Some(task_id), &client, stdin_script.as_ref()).await?;
             // Validate project
             validate_project(&mut job, config)?;
+            transfer.apply(&mut job, stdin_script.as_ref())?;
             // Assign group_id and max_concurrent if needed
-            job.group_id = group_id;
+            job.group_id = Some(group_id);
             job.max_concurrent = add_args.max_concurrent;
             jobs.push(job);
         }
 
         // Submit in batch
-        let responses = client
-            .add_jobs(jobs)
-            .await
-            .context("Failed to add batch jobs")?;
+        let Some(responses) = submit(&client, spool.as_ref(), jobs).await? else {
+            return Ok(());
+        };
 
-        // Show group_id if jobs are part of a group
-        if let Some(ref gid) = group_id {
-            println!(
-                "Submitted {} batch jobs with group_id: {}",
-                responses.len(),
-                gid
-            );
-            println!(
-                "  (You can update the limit with: gctl set-limit {} <N>)",
-                responses[0].id
-            );
+        for response in &responses {
+            print_submitted(response, &add_args);
         }
-
-        for response in responses {
-            println!(
-                "Submitted batch job {} ({})",
-                response.id, response.run_name
-            );
+        if !add_args.quiet {
+            print_group(group_id, &responses, add_args.max_concurrent.is_some());
         }
         return Ok(());
     }
 
     // Dry-run for non-param, non-array jobs
     if add_args.dry_run {
-        let job = build_job(&add_args, None, &client, stdin_content.as_ref()).await?;
+        let job = build_job(&add_args, None, &client, stdin_script.as_ref()).await?;
         println!("Would submit 1 batch job:");
         let cmd = if let Some(c) = &job.command {
             c.to_string()
@@ -439,15 +506,200 @@ pub(crate) async fn handle_add(
     }
 
     // Single job submission (existing logic)
-    let mut job = build_job(&add_args, None, &client, stdin_content.as_ref()).await?;
+    let mut job = build_job(&add_args, None, &client, stdin_script.as_ref()).await?;
     validate_project(&mut job, config)?;
-    let response = client.add_job(job).await.context("Failed to add job")?;
+    transfer.apply(&mut job, stdin_script.as_ref())?;
+    let Some(responses) = submit(&client, spool.as_ref(), vec![job]).await? else {
+        return Ok(());
+    };
+    for response in &responses {
+        print_submitted(response, &add_args);
+    }
+
+    Ok(())
+}
+
+/// Print the group of a sweep last, where it stays visible after many jobs.
+pub(super) fn print_group(group_id: uuid::Uuid, responses: &[JobSubmitResponse], limited: bool) {
+    let style = gflow::utils::output::OutputStyle::stdout();
+    println!(
+        "Submitted {} jobs as group {}",
+        responses.len(),
+        style.paint(group_id, owo_colors::Style::new().bold())
+    );
+    println!("  List them:   gjob group {group_id}");
+    println!("  Cancel them: gcancel --group {group_id}");
+    if limited {
+        if let Some(first) = responses.first() {
+            println!("  Change the limit: gctl set-limit {} <N>", first.id);
+        }
+    }
+}
+
+/// Report a submitted job and, when it has to wait, when it should start.
+fn print_submitted(response: &JobSubmitResponse, args: &cli::AddArgs) {
+    if args.quiet {
+        println!("{}", response.id);
+        return;
+    }
     println!(
         "Submitted batch job {} ({})",
         response.id, response.run_name
     );
+    if let (Some(other), Some(name)) = (response.run_name_shared_with, &args.name) {
+        gflow::utils::output::print_warning(format_args!(
+            "job {other} is also active under the name '{name}'; tell them apart by job ID"
+        ));
+    }
+    print_estimate(response);
+    if args.verbose {
+        print_details(response);
+    }
+}
 
-    Ok(())
+fn print_estimate(response: &JobSubmitResponse) {
+    let now = chrono::Utc::now().timestamp();
+    match &response.estimate {
+        Some(estimate @ StartEstimate::At { at, jobs_ahead }) if *at > now => {
+            let ahead = match jobs_ahead {
+                0 => "no jobs ahead".to_string(),
+                1 => "1 job ahead".to_string(),
+                n => format!("{n} jobs ahead"),
+            };
+            println!(
+                "  Estimated start {}, {ahead}",
+                gflow::utils::format_start_estimate(estimate, now)
+            );
+        }
+        Some(StartEstimate::Unknown) => println!(
+            "  No start estimate within {} days",
+            ESTIMATE_HORIZON.as_secs() / 86_400
+        ),
+        Some(StartEstimate::Never { reason }) => eprintln!(
+            "  Warning: job {} can never start: it {reason}",
+            response.id
+        ),
+        _ => {}
+    }
+}
+
+/// What the daemon reported about the job's place in the queue (`--verbose`).
+pub(super) fn print_details(response: &JobSubmitResponse) {
+    if let Some(group_id) = response.group_id {
+        println!("  Group:          {group_id}");
+    }
+    if let Some(priority) = response.priority {
+        println!("  Priority:       {priority}");
+    }
+    if !response.dependencies.is_empty() {
+        let dependencies: Vec<String> = response
+            .dependencies
+            .iter()
+            .map(|dependency| match dependency.state {
+                Some(state) => format!("{} ({state})", dependency.id),
+                None => format!("{} (purged)", dependency.id),
+            })
+            .collect();
+        println!("  Depends on:     {}", dependencies.join(", "));
+    }
+    match response.queue_position {
+        Some(position) => println!("  Queue position: {position}"),
+        None if response
+            .dependencies
+            .iter()
+            .any(|dependency| dependency.state != Some(JobState::Finished)) =>
+        {
+            println!("  Queue position: waiting on dependencies")
+        }
+        None => {}
+    }
+    if let Some(gpus) = &response.available_gpus {
+        let gpus = if gpus.is_empty() {
+            "none".to_string()
+        } else {
+            gpus.iter()
+                .map(u32::to_string)
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        println!("  Free GPUs:      {gpus}");
+    }
+}
+
+/// Send earlier spooled submissions before anything new, so order is kept.
+///
+/// Returns the spool to fall back on when `--spool` (or `client.spool`) is on.
+pub(super) async fn flush_spool_first(
+    config: &gflow::config::Config,
+    add_args: &cli::AddArgs,
+    client: &Client,
+) -> Result<Option<PendingSpool>> {
+    let spool = Spool::open()?;
+    let pending = match spool.flush(client).await {
+        Ok(Some(report)) => report.pending > 0,
+        // Another gbatch is flushing; assume entries are still queued.
+        Ok(None) => true,
+        Err(error) => {
+            gflow::utils::output::print_warning(format_args!(
+                "could not flush spooled submissions: {error:#}"
+            ));
+            !spool.pending()?.is_empty()
+        }
+    };
+
+    if add_args.spool || config.client.spool {
+        Ok(Some(PendingSpool { spool, pending }))
+    } else {
+        if pending {
+            eprintln!("Note: earlier submissions are still spooled; run `gbatch flush-spool` once gflowd is reachable");
+        }
+        Ok(None)
+    }
+}
+
+pub(super) struct PendingSpool {
+    spool: Spool,
+    /// Earlier submissions are still waiting, so new ones must queue behind them.
+    pending: bool,
+}
+
+/// Submit `jobs`, or spool them if spooling is on and the daemon is unreachable.
+///
+/// Returns `None` when the jobs were spooled instead.
+pub(super) async fn submit(
+    client: &Client,
+    spool: Option<&PendingSpool>,
+    mut jobs: Vec<Job>,
+) -> Result<Option<Vec<JobSubmitResponse>>> {
+    // Resending a job with the same ID (a retried request, a spool flush)
+    // cannot create it twice.
+    for job in &mut jobs {
+        job.submission_id.get_or_insert_with(uuid::Uuid::new_v4);
+    }
+    let Some(PendingSpool { spool, pending }) = spool else {
+        return spool::submit(client, jobs).await.map(Some);
+    };
+
+    if !pending {
+        match spool::submit(client, jobs.clone()).await {
+            Ok(responses) => return Ok(Some(responses)),
+            Err(error)
+                if matches!(
+                    error.downcast_ref(),
+                    Some(ClientError::ConnectionRefused { .. })
+                ) => {}
+            Err(error) => return Err(error),
+        }
+    }
+
+    let path = spool.push(&jobs)?;
+    println!(
+        "gflowd is unreachable; spooled {} job(s) to {}",
+        jobs.len(),
+        path.display()
+    );
+    println!("  (They are submitted by the next gbatch run or `gbatch flush-spool`)");
+    Ok(None)
 }
 
 /// Detects the currently active conda environment from the environment variables
@@ -457,19 +709,66 @@ fn detect_current_conda_env() -> Option<String> {
         .filter(|env_name| !env_name.is_empty())
 }
 
-async fn build_job(
+fn environment_from_args(args: &cli::AddArgs) -> Result<Option<EnvSpec>> {
+    if let Some(venv) = &args.venv {
+        return Ok(Some(EnvSpec::Venv(make_absolute_path(venv.clone())?)));
+    }
+    if args.uv {
+        return Ok(Some(EnvSpec::Uv));
+    }
+    Ok(args
+        .conda_env
+        .clone()
+        .map(|name| EnvSpec::Conda(name.into())))
+}
+
+/// The job's environment: CLI flags, then script directives, then the
+/// `[gbatch]` default. Commands without any of these inherit the active conda
+/// environment.
+fn resolve_environment(args: &cli::AddArgs, script_args: Option<&cli::AddArgs>) -> Result<EnvSpec> {
+    if let Some(environment) = environment_from_args(args)? {
+        return Ok(environment);
+    }
+    let default = args
+        .defaults
+        .conda_env
+        .clone()
+        .map(|name| EnvSpec::Conda(name.into()));
+    match script_args {
+        Some(script_args) => Ok(environment_from_args(script_args)?
+            .or(default)
+            .unwrap_or_default()),
+        None => Ok(default
+            .or_else(|| detect_current_conda_env().map(|name| EnvSpec::Conda(name.into())))
+            .unwrap_or_default()),
+    }
+}
+
+/// The `[gbatch]` time limit, for jobs whose flags and directives set none.
+fn default_time_limit(args: &cli::AddArgs) -> Result<Option<Duration>> {
+    args.defaults
+        .time
+        .as_deref()
+        .map(|time| gflow::utils::parse_time_limit(time).context(ClientError::invalid_time_limit()))
+        .transpose()
+}
+
+pub(super) async fn build_job(
     args: &cli::AddArgs,
     task_id: Option<u32>,
     client: &Client,
-    stdin_content: Option<&String>,
+    stdin_script: Option<&StdinScript>,
 ) -> Result<Job> {
     let mut builder = Job::builder();
     let run_dir = std::env::current_dir().context("Failed to get current directory")?;
     builder = builder.run_dir(run_dir);
     builder = builder.task_id(task_id);
 
-    // Get the username of the submitter
-    let username = gflow::platform::get_current_username();
+    // The submitter, unless submitting for another user
+    let username = args
+        .as_user
+        .clone()
+        .unwrap_or_else(gflow::platform::get_current_username);
     builder = builder.submitted_by(username);
 
     // Set custom run name if provided
@@ -477,7 +776,7 @@ async fn build_job(
 
     // Parse time limit if provided
     let time_limit = if let Some(time_str) = &args.time {
-        Some(gflow::utils::parse_time_limit(time_str)?)
+        Some(gflow::utils::parse_time_limit(time_str).context(ClientError::invalid_time_limit())?)
     } else {
         None
     };
@@ -523,26 +822,41 @@ async fn build_job(
     builder = builder.max_retries(args.max_retries.unwrap_or(0));
     builder = builder.notifications(JobNotifications::default());
 
-    if let Some(content) = stdin_content {
-        // Stdin mode - save content to a temporary script file
-        let script_args = parse_script_content_for_args(content)?;
-        let temp_script = save_stdin_to_temp_file(content)?;
+    if let Some(stdin) = stdin_script {
+        // Stdin mode - run the copy saved under the managed scripts directory
+        let script_args = parse_script_content_for_args(&stdin.content)?;
 
-        builder = builder.script(temp_script);
-        builder = builder.gpus(args.gpus.or(script_args.gpus).unwrap_or(0));
+        builder = builder.script(stdin.path.clone());
+        builder = builder.gpus(
+            args.gpus
+                .or(script_args.gpus)
+                .or(args.defaults.gpus)
+                .unwrap_or(0),
+        );
+        builder = builder.cpus(args.cpus.or(script_args.cpus));
         builder = builder.shared(args.shared || script_args.shared);
-        builder = builder.priority(args.priority.or(script_args.priority).unwrap_or(10));
+        builder = builder.preemptible(args.preemptible || script_args.preemptible);
+        builder = builder.keep_job_dir(args.keep_job_dir || script_args.keep_job_dir);
+        builder = builder.constraint(resolve_constraint(args, Some(&script_args))?);
+        builder = builder.min_free_disk_mb(resolve_min_free_disk(args, Some(&script_args))?);
+        builder = builder.priority(resolve_priority(args, Some(&script_args))?);
         builder = builder.project(resolve_project(args, Some(&script_args)));
+        builder = builder.tags(resolve_tags(args, Some(&script_args))?);
         builder = builder.notifications(resolve_job_notifications(args, Some(&script_args))?);
-        builder = builder.conda_env(args.conda_env.clone().or(script_args.conda_env));
+        builder = builder.environment(resolve_environment(args, Some(&script_args))?);
+        builder = builder.container_image(args.container.clone().or(script_args.container));
+        builder = builder.executor(args.executor.or(script_args.executor));
 
         // CLI time limit takes precedence over script time limit
         let final_time_limit = if time_limit.is_some() {
             time_limit
         } else if let Some(script_time_str) = &script_args.time {
-            Some(gflow::utils::parse_time_limit(script_time_str)?)
+            Some(
+                gflow::utils::parse_time_limit(script_time_str)
+                    .context(ClientError::invalid_time_limit())?,
+            )
         } else {
-            None
+            default_time_limit(args)?
         };
         builder = builder.time_limit(final_time_limit);
 
@@ -566,8 +880,9 @@ async fn build_job(
         builder = builder.gpu_memory_limit_mb(final_gpu_memory_limit);
     } else {
         // Determine if it's a script or command
-        let is_script =
-            args.script_or_command.len() == 1 && PathBuf::from(&args.script_or_command[0]).exists();
+        let is_script = !args.interactive
+            && args.script_or_command.len() == 1
+            && PathBuf::from(&args.script_or_command[0]).exists();
 
         if is_script {
             // Script mode
@@ -575,11 +890,24 @@ async fn build_job(
             let script_args = parse_script_for_args(&script_path)?;
 
             builder = builder.script(script_path);
-            builder = builder.gpus(args.gpus.or(script_args.gpus).unwrap_or(0));
+            builder = builder.gpus(
+                args.gpus
+                    .or(script_args.gpus)
+                    .or(args.defaults.gpus)
+                    .unwrap_or(0),
+            );
+            builder = builder.cpus(args.cpus.or(script_args.cpus));
             builder = builder.shared(args.shared || script_args.shared);
-            builder = builder.priority(args.priority.or(script_args.priority).unwrap_or(10));
+            builder = builder.preemptible(args.preemptible || script_args.preemptible);
+            builder = builder.keep_job_dir(args.keep_job_dir || script_args.keep_job_dir);
+            builder = builder.constraint(resolve_constraint(args, Some(&script_args))?);
+            builder = builder.min_free_disk_mb(resolve_min_free_disk(args, Some(&script_args))?);
+            builder = builder.priority(resolve_priority(args, Some(&script_args))?);
             builder = builder.notifications(resolve_job_notifications(args, Some(&script_args))?);
-            builder = builder.conda_env(args.conda_env.clone().or(script_args.conda_env));
+            builder = builder.tags(resolve_tags(args, Some(&script_args))?);
+            builder = builder.environment(resolve_environment(args, Some(&script_args))?);
+            builder = builder.container_image(args.container.clone().or(script_args.container));
+            builder = builder.executor(args.executor.or(script_args.executor));
 
             // CLI project takes precedence over script project
             let final_project = args.project.clone().or(script_args.project);
@@ -589,9 +917,12 @@ async fn build_job(
             let final_time_limit = if time_limit.is_some() {
                 time_limit
             } else if let Some(script_time_str) = &script_args.time {
-                Some(gflow::utils::parse_time_limit(script_time_str)?)
+                Some(
+                    gflow::utils::parse_time_limit(script_time_str)
+                        .context(ClientError::invalid_time_limit())?,
+                )
             } else {
-                None
+                default_time_limit(args)?
             };
             builder = builder.time_limit(final_time_limit);
 
@@ -615,24 +946,25 @@ async fn build_job(
             builder = builder.gpu_memory_limit_mb(final_gpu_memory_limit);
         } else {
             // Command mode
-            let command = args
-                .script_or_command
-                .iter()
-                .map(|arg| shell_escape::escape(arg.into()))
-                .collect::<Vec<_>>()
-                .join(" ");
+            let command = command_from_args(&args.script_or_command, &HashMap::new())?;
             builder = builder.command(command);
-            builder = builder.gpus(args.gpus.unwrap_or(0));
+            builder = builder.gpus(args.gpus.or(args.defaults.gpus).unwrap_or(0));
+            builder = builder.cpus(args.cpus);
             builder = builder.shared(args.shared);
-            builder = builder.priority(args.priority.unwrap_or(10));
-
-            // Auto-detect conda environment if not specified
-            let conda_env = args.conda_env.clone().or_else(detect_current_conda_env);
-            builder = builder.conda_env(conda_env);
+            builder = builder.preemptible(args.preemptible);
+            builder = builder.keep_job_dir(args.keep_job_dir);
+            builder = builder.constraint(resolve_constraint(args, None)?);
+            builder = builder.min_free_disk_mb(resolve_min_free_disk(args, None)?);
+            builder = builder.priority(resolve_priority(args, None)?);
+
+            builder = builder.environment(resolve_environment(args, None)?);
+            builder = builder.container_image(args.container.clone());
+            builder = builder.executor(args.executor);
             builder = builder.project(resolve_project(args, None));
+            builder = builder.tags(resolve_tags(args, None)?);
             builder = builder.notifications(resolve_job_notifications(args, None)?);
 
-            builder = builder.time_limit(time_limit);
+            builder = builder.time_limit(time_limit.or(default_time_limit(args)?));
             builder = builder.memory_limit_mb(memory_limit_mb);
             builder = builder.gpu_memory_limit_mb(gpu_memory_limit_mb);
         }
@@ -640,17 +972,20 @@ async fn build_job(
 
     // Set auto-close tmux flag
     builder = builder.auto_close_tmux(args.auto_close);
+    builder = builder.interactive(args.interactive);
 
-    let job = builder.build();
+    let mut job = builder.build();
+    job.force_queue = args.force_queue;
+    job.as_user = args.as_user.is_some();
     validate_shared_requires_gpu_memory(&job)?;
     Ok(job)
 }
 
-async fn build_job_with_params(
+pub(super) async fn build_job_with_params(
     args: &cli::AddArgs,
     parameters: &HashMap<String, String>,
     client: &Client,
-    stdin_content: Option<&String>,
+    stdin_script: Option<&StdinScript>,
 ) -> Result<Job> {
     let mut builder = Job::builder();
     let run_dir = std::env::current_dir().context("Failed to get current directory")?;
@@ -658,8 +993,11 @@ async fn build_job_with_params(
     // Parameters are for array-like submissions but without task_id
     builder = builder.task_id(None);
 
-    // Get the username of the submitter
-    let username = gflow::platform::get_current_username();
+    // The submitter, unless submitting for another user
+    let username = args
+        .as_user
+        .clone()
+        .unwrap_or_else(gflow::platform::get_current_username);
     builder = builder.submitted_by(username);
 
     // Apply name template if provided, otherwise use custom name if provided
@@ -675,7 +1013,7 @@ async fn build_job_with_params(
 
     // Parse time limit if provided
     let time_limit = if let Some(time_str) = &args.time {
-        Some(gflow::utils::parse_time_limit(time_str)?)
+        Some(gflow::utils::parse_time_limit(time_str).context(ClientError::invalid_time_limit())?)
     } else {
         None
     };
@@ -721,26 +1059,41 @@ async fn build_job_with_params(
     builder = builder.max_retries(args.max_retries.unwrap_or(0));
     builder = builder.notifications(JobNotifications::default());
 
-    if let Some(content) = stdin_content {
-        // Stdin mode - save content to a temporary script file
-        let script_args = parse_script_content_for_args(content)?;
-        let temp_script = save_stdin_to_temp_file(content)?;
+    if let Some(stdin) = stdin_script {
+        // Stdin mode - run the copy saved under the managed scripts directory
+        let script_args = parse_script_content_for_args(&stdin.content)?;
 
-        builder = builder.script(temp_script);
-        builder = builder.gpus(args.gpus.or(script_args.gpus).unwrap_or(0));
+        builder = builder.script(stdin.path.clone());
+        builder = builder.gpus(
+            args.gpus
+                .or(script_args.gpus)
+                .or(args.defaults.gpus)
+                .unwrap_or(0),
+        );
+        builder = builder.cpus(args.cpus.or(script_args.cpus));
         builder = builder.shared(args.shared || script_args.shared);
-        builder = builder.priority(args.priority.or(script_args.priority).unwrap_or(10));
+        builder = builder.preemptible(args.preemptible || script_args.preemptible);
+        builder = builder.keep_job_dir(args.keep_job_dir || script_args.keep_job_dir);
+        builder = builder.constraint(resolve_constraint(args, Some(&script_args))?);
+        builder = builder.min_free_disk_mb(resolve_min_free_disk(args, Some(&script_args))?);
+        builder = builder.priority(resolve_priority(args, Some(&script_args))?);
         builder = builder.project(resolve_project(args, Some(&script_args)));
+        builder = builder.tags(resolve_tags(args, Some(&script_args))?);
         builder = builder.notifications(resolve_job_notifications(args, Some(&script_args))?);
-        builder = builder.conda_env(args.conda_env.clone().or(script_args.conda_env));
+        builder = builder.environment(resolve_environment(args, Some(&script_args))?);
+        builder = builder.container_image(args.container.clone().or(script_args.container));
+        builder = builder.executor(args.executor.or(script_args.executor));
 
         // CLI time limit takes precedence over script time limit
         let final_time_limit = if time_limit.is_some() {
             time_limit
         } else if let Some(script_time_str) = &script_args.time {
-            Some(gflow::utils::parse_time_limit(script_time_str)?)
+            Some(
+                gflow::utils::parse_time_limit(script_time_str)
+                    .context(ClientError::invalid_time_limit())?,
+            )
         } else {
-            None
+            default_time_limit(args)?
         };
         builder = builder.time_limit(final_time_limit);
 
@@ -773,11 +1126,24 @@ async fn build_job_with_params(
             let script_args = parse_script_for_args(&script_path)?;
 
             builder = builder.script(script_path);
-            builder = builder.gpus(args.gpus.or(script_args.gpus).unwrap_or(0));
+            builder = builder.gpus(
+                args.gpus
+                    .or(script_args.gpus)
+                    .or(args.defaults.gpus)
+                    .unwrap_or(0),
+            );
+            builder = builder.cpus(args.cpus.or(script_args.cpus));
             builder = builder.shared(args.shared || script_args.shared);
-            builder = builder.priority(args.priority.or(script_args.priority).unwrap_or(10));
+            builder = builder.preemptible(args.preemptible || script_args.preemptible);
+            builder = builder.keep_job_dir(args.keep_job_dir || script_args.keep_job_dir);
+            builder = builder.constraint(resolve_constraint(args, Some(&script_args))?);
+            builder = builder.min_free_disk_mb(resolve_min_free_disk(args, Some(&script_args))?);
+            builder = builder.priority(resolve_priority(args, Some(&script_args))?);
             builder = builder.notifications(resolve_job_notifications(args, Some(&script_args))?);
-            builder = builder.conda_env(args.conda_env.clone().or(script_args.conda_env));
+            builder = builder.tags(resolve_tags(args, Some(&script_args))?);
+            builder = builder.environment(resolve_environment(args, Some(&script_args))?);
+            builder = builder.container_image(args.container.clone().or(script_args.container));
+            builder = builder.executor(args.executor.or(script_args.executor));
 
             // CLI project takes precedence over script project
             let final_project = args.project.clone().or(script_args.project);
@@ -787,9 +1153,12 @@ async fn build_job_with_params(
             let final_time_limit = if time_limit.is_some() {
                 time_limit
             } else if let Some(script_time_str) = &script_args.time {
-                Some(gflow::utils::parse_time_limit(script_time_str)?)
+                Some(
+                    gflow::utils::parse_time_limit(script_time_str)
+                        .context(ClientError::invalid_time_limit())?,
+                )
             } else {
-                None
+                default_time_limit(args)?
             };
             builder = builder.time_limit(final_time_limit);
 
@@ -813,24 +1182,25 @@ async fn build_job_with_params(
             builder = builder.gpu_memory_limit_mb(final_gpu_memory_limit);
         } else {
             // Command mode
-            let command = args
-                .script_or_command
-                .iter()
-                .map(|arg| shell_escape::escape(arg.into()))
-                .collect::<Vec<_>>()
-                .join(" ");
+            let command = command_from_args(&args.script_or_command, parameters)?;
             builder = builder.command(command);
-            builder = builder.gpus(args.gpus.unwrap_or(0));
+            builder = builder.gpus(args.gpus.or(args.defaults.gpus).unwrap_or(0));
+            builder = builder.cpus(args.cpus);
             builder = builder.shared(args.shared);
-            builder = builder.priority(args.priority.unwrap_or(10));
-
-            // Auto-detect conda environment if not specified
-            let conda_env = args.conda_env.clone().or_else(detect_current_conda_env);
-            builder = builder.conda_env(conda_env);
+            builder = builder.preemptible(args.preemptible);
+            builder = builder.keep_job_dir(args.keep_job_dir);
+            builder = builder.constraint(resolve_constraint(args, None)?);
+            builder = builder.min_free_disk_mb(resolve_min_free_disk(args, None)?);
+            builder = builder.priority(resolve_priority(args, None)?);
+
+            builder = builder.environment(resolve_environment(args, None)?);
+            builder = builder.container_image(args.container.clone());
+            builder = builder.executor(args.executor);
             builder = builder.project(resolve_project(args, None));
+            builder = builder.tags(resolve_tags(args, None)?);
             builder = builder.notifications(resolve_job_notifications(args, None)?);
 
-            builder = builder.time_limit(time_limit);
+            builder = builder.time_limit(time_limit.or(default_time_limit(args)?));
             builder = builder.memory_limit_mb(memory_limit_mb);
             builder = builder.gpu_memory_limit_mb(gpu_memory_limit_mb);
         }
@@ -839,7 +1209,9 @@ async fn build_job_with_params(
     // Set auto-close tmux flag
     builder = builder.auto_close_tmux(args.auto_close);
 
-    let job = builder.build();
+    let mut job = builder.build();
+    job.force_queue = args.force_queue;
+    job.as_user = args.as_user.is_some();
     validate_shared_requires_gpu_memory(&job)?;
     Ok(job)
 }
@@ -860,7 +1232,12 @@ fn parse_script_content_for_args(content: &str) -> Result<cli::AddArgs> {
         return Ok(cli::AddArgs {
             script_or_command: vec![],
             conda_env: None,
+            venv: None,
+            uv: false,
+            container: None,
+            executor: None,
             gpus: None,
+            cpus: None,
             shared: false,
             priority: None,
             depends_on: None,
@@ -875,11 +1252,29 @@ fn parse_script_content_for_args(content: &str) -> Result<cli::AddArgs> {
             auto_close: false,
             param: vec![],
             dry_run: false,
+            verbose: false,
+            quiet: false,
+            spool: false,
+            no_chmod: false,
+            allow_crlf: false,
+            stdin: false,
+            interactive: false,
+            pipeline: None,
+            show_effective_config: false,
+            defaults: Default::default(),
+            priority_classes: Default::default(),
+            preemptible: false,
+            keep_job_dir: false,
+            constraint: None,
+            min_free_disk: None,
+            force_queue: false,
+            as_user: None,
             max_concurrent: None,
             max_retries: None,
             param_file: None,
             name_template: None,
             project: None,
+            tag: vec![],
             notify_email: vec![],
             notify_on: vec![],
         });
@@ -892,32 +1287,47 @@ fn parse_script_content_for_args(content: &str) -> Result<cli::AddArgs> {
     Ok(parsed.add_args)
 }
 
-fn save_stdin_to_temp_file(content: &str) -> Result<PathBuf> {
-    use std::io::Write;
-
-    // Create a temporary file in the system temp directory
-    let temp_dir = std::env::temp_dir();
-    let timestamp = std::time::SystemTime::now()
-        .duration_since(std::time::UNIX_EPOCH)
-        .unwrap()
-        .as_micros();
-    let temp_path = temp_dir.join(format!("gflow_stdin_{}.sh", timestamp));
-
-    let mut file =
-        fs::File::create(&temp_path).context("Failed to create temporary script file")?;
-    file.write_all(content.as_bytes())
-        .context("Failed to write to temporary script file")?;
-
-    // Make the file executable
-    #[cfg(unix)]
-    {
-        use std::os::unix::fs::PermissionsExt;
-        let mut perms = file.metadata()?.permissions();
-        perms.set_mode(0o755);
-        fs::set_permissions(&temp_path, perms)?;
+/// How a job's script reaches the daemon.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum ScriptTransfer {
+    /// As a path, for a daemon that sees this machine's files: a local one,
+    /// or a remote with `shared_fs`.
+    Path,
+    /// As its content, which the daemon saves in its own scripts directory.
+    Content,
+}
+
+impl ScriptTransfer {
+    pub(super) fn for_remote(remote: Option<&RemoteConfig>) -> Self {
+        match remote {
+            Some(remote) if !remote.shared_fs => Self::Content,
+            _ => Self::Path,
+        }
+    }
+
+    /// For `Content`, replace the script path of `job` with the script
+    /// itself: `stdin`'s content, or the file the path names.
+    pub(super) fn apply(self, job: &mut Job, stdin: Option<&StdinScript>) -> Result<()> {
+        if self == Self::Path {
+            return Ok(());
+        }
+        let Some(script) = job.script.take() else {
+            return Ok(());
+        };
+        let content = match stdin {
+            Some(stdin) => stdin.content.clone(),
+            None => fs::read_to_string(&*script)
+                .with_context(|| format!("Failed to read script {}", script.display()))?,
+        };
+        job.script_content = Some(content);
+        Ok(())
     }
+}
 
-    Ok(temp_path)
+/// Script body read from standard input, and where it was saved.
+pub(super) struct StdinScript {
+    content: String,
+    path: PathBuf,
 }
 
 fn make_absolute_path(path: PathBuf) -> Result<PathBuf> {
@@ -1002,7 +1412,12 @@ mod tests {
         let args = cli::AddArgs {
             script_or_command: vec!["python".to_string(), "train.py".to_string()],
             conda_env: None,
+            venv: None,
+            uv: false,
+            container: None,
+            executor: None,
             gpus: None,
+            cpus: None,
             shared: false,
             priority: None,
             depends_on: None,
@@ -1017,11 +1432,29 @@ mod tests {
             auto_close: false,
             param: vec![],
             dry_run: false,
+            verbose: false,
+            quiet: false,
+            spool: false,
+            no_chmod: false,
+            allow_crlf: false,
+            stdin: false,
+            interactive: false,
+            pipeline: None,
+            show_effective_config: false,
+            defaults: Default::default(),
+            priority_classes: Default::default(),
+            preemptible: false,
+            keep_job_dir: false,
+            constraint: None,
+            min_free_disk: None,
+            force_queue: false,
+            as_user: None,
             max_concurrent: None,
             max_retries: None,
             param_file: None,
             name_template: None,
             project: None,
+            tag: vec![],
             notify_email: vec!["alice@example.com".to_string()],
             notify_on: vec![],
         };
@@ -1069,4 +1502,187 @@ python train.py
             vec!["job_failed".to_string(), "job_timeout".to_string()]
         );
     }
+
+    #[test]
+    fn scripts_go_by_content_only_to_remotes_without_shared_fs() {
+        let mut remote = RemoteConfig {
+            host: "http://gpu1:59000".to_string(),
+            token: None,
+            tls_ca: None,
+            insecure_skip_verify: false,
+            shared_fs: false,
+        };
+        assert_eq!(ScriptTransfer::for_remote(None), ScriptTransfer::Path);
+        assert_eq!(
+            ScriptTransfer::for_remote(Some(&remote)),
+            ScriptTransfer::Content
+        );
+        remote.shared_fs = true;
+        assert_eq!(
+            ScriptTransfer::for_remote(Some(&remote)),
+            ScriptTransfer::Path
+        );
+    }
+
+    #[test]
+    fn script_transfer_sends_either_path_or_content() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("train.sh");
+        fs::write(&path, "#!/bin/bash\necho train\n").unwrap();
+        let job = Job::builder().script(path.clone()).build();
+
+        let mut by_path = job.clone();
+        ScriptTransfer::Path.apply(&mut by_path, None).unwrap();
+        assert_eq!(by_path.script.as_deref(), Some(&path));
+        assert_eq!(by_path.script_content, None);
+
+        let mut by_content = job.clone();
+        ScriptTransfer::Content
+            .apply(&mut by_content, None)
+            .unwrap();
+        assert_eq!(by_content.script, None);
+        assert_eq!(
+            by_content.script_content.as_deref(),
+            Some("#!/bin/bash\necho train\n")
+        );
+
+        let stdin = StdinScript {
+            content: "echo from-stdin\n".to_string(),
+            path: PathBuf::from("<stdin>"),
+        };
+        let mut from_stdin = Job::builder().script(stdin.path.clone()).build();
+        ScriptTransfer::Content
+            .apply(&mut from_stdin, Some(&stdin))
+            .unwrap();
+        assert_eq!(from_stdin.script, None);
+        assert_eq!(
+            from_stdin.script_content.as_deref(),
+            Some("echo from-stdin\n")
+        );
+
+        let mut command = Job::builder().command("echo hi").build();
+        ScriptTransfer::Content.apply(&mut command, None).unwrap();
+        assert_eq!(command.script_content, None);
+        assert_eq!(command.command.as_deref(), Some("echo hi"));
+    }
+
+    #[test]
+    fn resolve_environment_prefers_cli_over_script_directives() {
+        let script_args = parse_script_content_for_args(
+            r#"#!/bin/bash
+# GFLOW --venv=/opt/venvs/train
+python train.py
+"#,
+        )
+        .unwrap();
+        let mut args = script_args.clone();
+        args.venv = None;
+
+        assert_eq!(
+            resolve_environment(&args, Some(&script_args)).unwrap(),
+            EnvSpec::Venv(PathBuf::from("/opt/venvs/train"))
+        );
+
+        args.uv = true;
+        assert_eq!(
+            resolve_environment(&args, Some(&script_args)).unwrap(),
+            EnvSpec::Uv
+        );
+
+        args.uv = false;
+        args.venv = Some(PathBuf::from(".venv"));
+        assert_eq!(
+            resolve_environment(&args, Some(&script_args)).unwrap(),
+            EnvSpec::Venv(std::env::current_dir().unwrap().join(".venv"))
+        );
+    }
+
+    #[test]
+    fn resolve_priority_accepts_numbers_and_configured_classes() {
+        let script_args = parse_script_content_for_args(
+            r#"#!/bin/bash
+# GFLOW --priority=high
+python train.py
+"#,
+        )
+        .unwrap();
+        let mut args = script_args.clone();
+        args.priority = None;
+        args.priority_classes = std::collections::BTreeMap::from([("high".to_string(), 50)]);
+
+        assert_eq!(resolve_priority(&args, Some(&script_args)).unwrap(), 50);
+        assert_eq!(resolve_priority(&args, None).unwrap(), 10);
+
+        args.priority = Some("200".to_string());
+        assert_eq!(resolve_priority(&args, Some(&script_args)).unwrap(), 200);
+
+        args.priority = Some("urgent".to_string());
+        let err = resolve_priority(&args, None).unwrap_err().to_string();
+        assert!(err.contains("high (50)"), "{err}");
+    }
+    #[test]
+    fn resolve_min_free_disk_prefers_the_cli_over_the_script() {
+        let script_args = parse_script_content_for_args(
+            r#"#!/bin/bash
+# GFLOW --min-free-disk=50G
+python train.py
+"#,
+        )
+        .unwrap();
+        let mut args = script_args.clone();
+        args.min_free_disk = None;
+        assert_eq!(
+            resolve_min_free_disk(&args, Some(&script_args)).unwrap(),
+            Some(50 * 1024)
+        );
+        assert_eq!(resolve_min_free_disk(&args, None).unwrap(), None);
+
+        args.min_free_disk = Some("512M".to_string());
+        assert_eq!(
+            resolve_min_free_disk(&args, Some(&script_args)).unwrap(),
+            Some(512)
+        );
+
+        args.min_free_disk = Some("plenty".to_string());
+        assert!(resolve_min_free_disk(&args, None).is_err());
+    }
+
+    #[test]
+    fn resolve_tags_adds_cli_tags_to_the_script_ones() {
+        let script_args = parse_script_content_for_args(
+            r#"#!/bin/bash
+# GFLOW --tag exp42,baseline
+python train.py
+"#,
+        )
+        .unwrap();
+        let mut args = script_args.clone();
+        args.tag = vec!["lr-sweep".to_string(), "exp42".to_string()];
+        let tags = resolve_tags(&args, Some(&script_args)).unwrap();
+        assert_eq!(
+            tags.iter().collect::<Vec<_>>(),
+            ["exp42", "baseline", "lr-sweep"]
+        );
+
+        args.tag = vec!["Exp42".to_string()];
+        assert!(resolve_tags(&args, None).is_err());
+    }
+
+    #[test]
+    fn command_placeholders_are_checked_and_env_quoted_per_argument() {
+        let args = |args: &[&str]| args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>();
+        let parameters = HashMap::from([("lr".to_string(), "0.1".to_string())]);
+
+        let command = command_from_args(
+            &args(&["echo", "{env:GFLOW_TEST_UNSET:-it's here}", "{lr}/{job_id}"]),
+            &parameters,
+        )
+        .unwrap();
+        assert_eq!(command, r#"echo 'it'\''s here' '{lr}/{job_id}'"#);
+
+        let error = command_from_args(&args(&["echo", "{seed}"]), &parameters).unwrap_err();
+        assert!(error.to_string().contains("{seed}"), "{error}");
+        assert!(command_from_args(&args(&["echo", "{{seed}}"]), &parameters).is_ok());
+        assert!(interpolate_command("echo {lr} {env:GFLOW_TEST_UNSET}", &parameters).is_err());
+    }
 }
@@ -0,0 +1,23 @@
+use crate::multicall::gbatch::spool::Spool;
+use anyhow::{Context, Result};
+use gflow::client::Client;
+
+pub(super) async fn handle_flush_spool(config: &gflow::config::Config) -> Result<()> {
+    let client = Client::build(config).context("Failed to build client")?;
+    let spool = Spool::open()?;
+
+    let Some(report) = spool.flush(&client).await? else {
+        anyhow::bail!("Another gbatch is already flushing the spool; try again shortly");
+    };
+
+    if report.submitted == 0 && report.moved_aside == 0 && report.pending == 0 {
+        println!("No spooled submissions");
+    }
+    if report.pending > 0 {
+        anyhow::bail!(
+            "Could not connect to gflowd; {} submission(s) remain spooled",
+            report.pending
+        );
+    }
+    Ok(())
+}
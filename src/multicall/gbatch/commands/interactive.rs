@@ -0,0 +1,294 @@
+use super::add::{build_job, validate_project};
+use crate::multicall::gbatch::{checks, cli};
+use anyhow::{bail, Context, Result};
+use gflow::core::executor::ExecutorKind;
+use gflow::core::job::{Job, JobState};
+use gflow::core::scheduler::Scheduler;
+use gflow::tmux::{attach_and_wait, is_session_exist};
+use gflow::Client;
+use std::cmp::Reverse;
+use std::io::IsTerminal;
+use std::time::{Duration, SystemTime};
+
+/// How often the job's state is polled while waiting for it to start.
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+/// How long to wait for a running job's tmux session to appear.
+const SESSION_TIMEOUT: Duration = Duration::from_secs(15);
+
+pub(crate) async fn handle_interactive(
+    config: &gflow::config::Config,
+    mut add_args: cli::AddArgs,
+) -> Result<()> {
+    if !std::io::stdin().is_terminal() || !std::io::stdout().is_terminal() {
+        bail!("--interactive needs a terminal");
+    }
+    if !config.daemon.listen_addr().is_local() {
+        bail!(
+            "--interactive needs gflowd on this host, since it attaches to the job's tmux session"
+        );
+    }
+    if let Some(conda_env) = &add_args.conda_env {
+        checks::check_conda_env(conda_env)?;
+    }
+    if let Some(venv) = &add_args.venv {
+        checks::check_venv(venv);
+    }
+
+    let client = Client::build(config).context("Failed to build client")?;
+    add_args.script_or_command = vec![interactive_shell(&config.daemon.default_shell)];
+    add_args.executor = Some(ExecutorKind::Tmux);
+    add_args.auto_close = true;
+    let mut job = build_job(&add_args, None, &client, None).await?;
+    validate_project(&mut job, config)?;
+
+    let response = client.add_job(job).await?;
+    let job_id = response.id;
+    println!(
+        "Submitted interactive job {} ({})",
+        job_id, response.run_name
+    );
+
+    let job = tokio::select! {
+        job = wait_until_running(&client, job_id) => job?,
+        _ = tokio::signal::ctrl_c() => {
+            client.cancel_job(job_id).await?;
+            println!("\nCancelled job {job_id}");
+            return Ok(());
+        }
+    };
+    let session = job
+        .run_name
+        .as_ref()
+        .map(ToString::to_string)
+        .context("Running job has no tmux session")?;
+    wait_for_session(&session).await?;
+
+    println!("Job {job_id} is running; leave the shell to end it.");
+    let attach_session = session.clone();
+    tokio::task::spawn_blocking(move || attach_and_wait(&attach_session)).await??;
+
+    if is_session_exist(&session) {
+        println!("Detached from job {job_id}; it keeps running.");
+        println!("  Reattach with `gjob attach {job_id}`, or end it with `gcancel {job_id}`.");
+        return Ok(());
+    }
+    report_end(&client, job_id).await
+}
+
+/// The shell to run: the submitter's login shell, else `[daemon] default_shell`.
+fn interactive_shell(default_shell: &str) -> String {
+    std::env::var("SHELL")
+        .ok()
+        .filter(|shell| !shell.is_empty())
+        .unwrap_or_else(|| default_shell.to_string())
+}
+
+/// Poll the job until it runs, printing where it stands in the queue.
+async fn wait_until_running(client: &Client, job_id: u32) -> Result<Job> {
+    let mut last_status = String::new();
+    loop {
+        let job = client
+            .get_job(job_id)
+            .await?
+            .with_context(|| format!("Job {job_id} no longer exists"))?;
+        let status = match job.state {
+            JobState::Running => return Ok(job),
+            state if state.is_final() => {
+                bail!("Job {job_id} ended as {state} before it started")
+            }
+            JobState::Hold => {
+                format!("Job {job_id} is held; release it with `gjob release {job_id}`")
+            }
+            _ => queue_status(client, &job).await?,
+        };
+        if status != last_status {
+            println!("{status}");
+            last_status = status;
+        }
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+}
+
+async fn queue_status(client: &Client, job: &Job) -> Result<String> {
+    let queued = client
+        .list_jobs_with_query(Some("Queued".to_string()), None, None, None, None, None)
+        .await?;
+    let running = client
+        .list_jobs_with_query(Some("Running".to_string()), None, None, None, None, None)
+        .await?;
+    let info = client.get_info().await?;
+    let free_gpus = info
+        .gpus
+        .iter()
+        .filter(|gpu| gpu.available)
+        .filter(|gpu| {
+            info.allowed_gpu_indices
+                .as_ref()
+                .is_none_or(|allowed| allowed.contains(&gpu.index))
+        })
+        .count() as u32;
+
+    let position = queue_position(job, &queued, &running, free_gpus, SystemTime::now());
+    let ahead = match position.ahead {
+        0 => "no jobs ahead of you".to_string(),
+        1 => "1 job ahead of you".to_string(),
+        n => format!("{n} jobs ahead of you"),
+    };
+    Ok(match position.estimate {
+        Some(wait) => format!(
+            "Waiting for job {}: {ahead}, est. {} by time limits",
+            job.id,
+            gflow::utils::format_duration_compact(wait)
+        ),
+        None => format!("Waiting for job {}: {ahead}", job.id),
+    })
+}
+
+/// Where a queued job stands.
+#[derive(Debug, PartialEq, Eq)]
+struct QueuePosition {
+    /// Queued jobs the scheduler would start first.
+    ahead: usize,
+    /// When enough GPUs free up for those jobs and this one, going by the
+    /// running jobs' time limits. `None` when that cannot be told.
+    estimate: Option<Duration>,
+}
+
+fn queue_position(
+    job: &Job,
+    queued: &[Job],
+    running: &[Job],
+    free_gpus: u32,
+    now: SystemTime,
+) -> QueuePosition {
+    // The order the scheduler picks ready jobs in.
+    let key = |job: &Job| {
+        (
+            job.priority,
+            Scheduler::calculate_time_bonus(&job.time_limit),
+            Reverse(job.id),
+        )
+    };
+    let ahead: Vec<&Job> = queued
+        .iter()
+        .filter(|other| other.id != job.id && key(other) > key(job))
+        .collect();
+
+    let needed = job.gpus + ahead.iter().map(|other| other.gpus).sum::<u32>();
+    let estimate = if job.gpus == 0 || needed <= free_gpus {
+        None
+    } else {
+        let mut releases: Vec<(Duration, u32)> = running
+            .iter()
+            .filter_map(|other| {
+                let elapsed = now.duration_since(other.started_at?).unwrap_or_default();
+                Some((other.time_limit?.saturating_sub(elapsed), other.gpus))
+            })
+            .collect();
+        releases.sort();
+        let mut free = free_gpus;
+        releases.into_iter().find_map(|(remaining, gpus)| {
+            free += gpus;
+            (free >= needed).then_some(remaining)
+        })
+    };
+
+    QueuePosition {
+        ahead: ahead.len(),
+        estimate,
+    }
+}
+
+/// The session can show up shortly after the job is marked running.
+async fn wait_for_session(session: &str) -> Result<()> {
+    let deadline = tokio::time::Instant::now() + SESSION_TIMEOUT;
+    while !is_session_exist(session) {
+        if tokio::time::Instant::now() >= deadline {
+            bail!("Tmux session '{session}' did not appear on this host");
+        }
+        tokio::time::sleep(Duration::from_millis(200)).await;
+    }
+    Ok(())
+}
+
+/// Tell the user how the job ended, finishing it if the shell is gone but the
+/// job has not reported yet, so its GPUs free up right away.
+async fn report_end(client: &Client, job_id: u32) -> Result<()> {
+    let Some(job) = client.get_job(job_id).await? else {
+        return Ok(());
+    };
+    match job.state {
+        JobState::Running => {
+            client.finish_job(job_id).await?;
+            println!("Job {job_id} finished; its GPUs are released.");
+        }
+        JobState::Finished => println!("Job {job_id} finished; its GPUs are released."),
+        JobState::Timeout => {
+            println!("Job {job_id} reached its time limit; its session was closed.")
+        }
+        state => println!("Job {job_id} ended as {state}."),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn job(id: u32, gpus: u32, priority: u8) -> Job {
+        Job {
+            id,
+            gpus,
+            priority,
+            ..Default::default()
+        }
+    }
+
+    fn running(id: u32, gpus: u32, limit_mins: u64, elapsed_mins: u64, now: SystemTime) -> Job {
+        Job {
+            state: JobState::Running,
+            time_limit: Some(Duration::from_secs(limit_mins * 60)),
+            started_at: Some(now - Duration::from_secs(elapsed_mins * 60)),
+            ..job(id, gpus, 10)
+        }
+    }
+
+    #[test]
+    fn counts_jobs_the_scheduler_picks_first() {
+        let me = job(5, 1, 10);
+        let queued = [
+            job(3, 1, 10),
+            job(4, 1, 5),
+            job(6, 1, 10),
+            job(7, 1, 20),
+            me.clone(),
+        ];
+        let position = queue_position(&me, &queued, &[], 4, SystemTime::now());
+        // Job 3 is older at the same priority and job 7 has a higher one.
+        assert_eq!(position.ahead, 2);
+        assert_eq!(position.estimate, None, "enough GPUs are free");
+    }
+
+    #[test]
+    fn estimates_the_wait_from_time_limits() {
+        let now = SystemTime::now();
+        let me = job(5, 2, 10);
+        let queued = [job(3, 1, 10), me.clone()];
+        let busy = [
+            running(1, 1, 60, 30, now),
+            running(2, 2, 120, 30, now),
+            running(4, 1, 45, 30, now),
+        ];
+        // Three GPUs are needed and none is free: jobs 4 and 1 free one each
+        // within 30m, job 2 the last two after 90m.
+        let position = queue_position(&me, &queued, &busy, 0, now);
+        assert_eq!(position.ahead, 1);
+        assert_eq!(position.estimate, Some(Duration::from_secs(90 * 60)));
+
+        // Without a time limit on job 2, there is no telling.
+        let mut unlimited = busy.clone();
+        unlimited[1].time_limit = None;
+        let position = queue_position(&me, &queued, &unlimited, 0, now);
+        assert_eq!(position.estimate, None);
+    }
+}
@@ -0,0 +1,375 @@
+//! `gbatch --pipeline`: submit the steps of a workflow file as one batch.
+//!
+//! ```yaml
+//! params:
+//!   dataset: cifar10
+//! steps:
+//!   prepare:
+//!     command: python prep.py --data {dataset}
+//!   train:
+//!     command: python train.py --data {dataset}
+//!     gpus: 1
+//!     depends_on: [prepare]
+//! ```
+//!
+//! Steps are submitted in dependency order, each depending on the batch
+//! positions of its `depends_on` steps, so the daemon creates all or none.
+
+use super::add::{
+    build_job_with_params, flush_spool_first, interpolate_command, preview_substitute,
+    print_details, print_group, submit, validate_project, ScriptTransfer,
+};
+use crate::multicall::gbatch::checks::{self, ScriptChecks};
+use crate::multicall::gbatch::cli;
+use anyhow::{anyhow, bail, Context, Result};
+use gflow::client::Client;
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use tabled::{builder::Builder, settings::style::Style};
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Pipeline {
+    /// Values for `{name}` placeholders in the steps' commands
+    #[serde(default)]
+    params: BTreeMap<String, Scalar>,
+    steps: BTreeMap<String, Step>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Step {
+    command: Option<String>,
+    script: Option<PathBuf>,
+    /// Names of the steps that must finish first
+    #[serde(default)]
+    depends_on: Vec<String>,
+    /// Run name; the step's name by default
+    name: Option<String>,
+    gpus: Option<u32>,
+    cpus: Option<u32>,
+    priority: Option<u8>,
+    time: Option<Scalar>,
+    memory: Option<Scalar>,
+    gpu_memory: Option<Scalar>,
+    conda_env: Option<String>,
+}
+
+/// A value written as a string or a bare number, e.g. `time: 30` or `lr: 0.01`.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum Scalar {
+    String(String),
+    Integer(i64),
+    Float(f64),
+    Bool(bool),
+}
+
+impl fmt::Display for Scalar {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::String(value) => f.write_str(value),
+            Self::Integer(value) => write!(f, "{value}"),
+            Self::Float(value) => write!(f, "{value}"),
+            Self::Bool(value) => write!(f, "{value}"),
+        }
+    }
+}
+
+impl Pipeline {
+    /// Read a pipeline from TOML (`.toml`) or YAML (anything else).
+    fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read pipeline file {}", path.display()))?;
+        let pipeline: Self = if path.extension().is_some_and(|ext| ext == "toml") {
+            toml::from_str(&content).map_err(anyhow::Error::from)
+        } else {
+            serde_yaml::from_str(&content).map_err(anyhow::Error::from)
+        }
+        .with_context(|| format!("Invalid pipeline file {}", path.display()))?;
+        pipeline.validate()?;
+        Ok(pipeline)
+    }
+
+    fn validate(&self) -> Result<()> {
+        if self.steps.is_empty() {
+            bail!("The pipeline has no steps");
+        }
+        for (name, step) in &self.steps {
+            match (&step.command, &step.script) {
+                (Some(_), None) | (None, Some(_)) => {}
+                _ => bail!("Step '{name}' needs either a command or a script"),
+            }
+            if let Some(dependency) = step
+                .depends_on
+                .iter()
+                .find(|dependency| !self.steps.contains_key(dependency.as_str()))
+            {
+                bail!("Step '{name}' depends on unknown step '{dependency}'");
+            }
+        }
+        Ok(())
+    }
+
+    /// Step names with every step after the steps it depends on; steps that
+    /// could go in either order keep name order.
+    fn submission_order(&self) -> Result<Vec<&str>> {
+        let mut waiting_on: BTreeMap<&str, usize> = self
+            .steps
+            .iter()
+            .map(|(name, step)| (name.as_str(), step.depends_on.len()))
+            .collect();
+        let mut order = Vec::with_capacity(self.steps.len());
+
+        while let Some(next) = waiting_on
+            .iter()
+            .find(|(_, waiting)| **waiting == 0)
+            .map(|(name, _)| *name)
+        {
+            waiting_on.remove(next);
+            order.push(next);
+            for (name, step) in &self.steps {
+                if let Some(waiting) = waiting_on.get_mut(name.as_str()) {
+                    *waiting -= step.depends_on.iter().filter(|dep| *dep == next).count();
+                }
+            }
+        }
+
+        if !waiting_on.is_empty() {
+            let cycle: Vec<&str> = waiting_on.into_keys().collect();
+            bail!(
+                "Pipeline steps depend on each other in a cycle: {}",
+                cycle.join(", ")
+            );
+        }
+        Ok(order)
+    }
+}
+
+pub(crate) async fn handle_pipeline(
+    config: &gflow::config::Config,
+    add_args: cli::AddArgs,
+    path: &Path,
+) -> Result<()> {
+    let pipeline = Pipeline::load(path)?;
+    let order = pipeline.submission_order()?;
+    let params: HashMap<String, String> = pipeline
+        .params
+        .iter()
+        .map(|(name, value)| (name.clone(), value.to_string()))
+        .collect();
+    // Scripts are found next to the pipeline file.
+    let base_dir = path.parent().unwrap_or(Path::new("."));
+
+    let client = Client::build(config).context("Failed to build client")?;
+    let transfer = ScriptTransfer::for_remote(config.selected_remote()?.map(|(_, remote)| remote));
+    let group_id = uuid::Uuid::new_v4();
+
+    let mut jobs = Vec::with_capacity(order.len());
+    for name in &order {
+        let step = &pipeline.steps[*name];
+        let args = step_args(&add_args, name, step, base_dir, config)?;
+        let mut job = build_job_with_params(&args, &params, &client, None)
+            .await
+            .with_context(|| format!("Step '{name}'"))?;
+        if let Some(command) = &step.command {
+            let command =
+                interpolate_command(command, &params).with_context(|| format!("Step '{name}'"))?;
+            job.command = Some(command.into());
+        }
+        validate_project(&mut job, config)?;
+        transfer.apply(&mut job, None)?;
+        job.batch_depends_on = step
+            .depends_on
+            .iter()
+            .filter_map(|dependency| order.iter().position(|step| step == dependency))
+            .collect();
+        job.group_id = Some(group_id);
+        job.max_concurrent = add_args.max_concurrent;
+        jobs.push(job);
+    }
+
+    if add_args.dry_run {
+        println!("Would submit {} pipeline step(s):", jobs.len());
+        let mut builder = Builder::default();
+        builder.push_record(["STEP", "DEPENDS ON", "COMMAND"]);
+        for (name, job) in order.iter().zip(&jobs) {
+            let command = match (&job.command, &job.script) {
+                (Some(command), _) => preview_substitute(command, &params),
+                (None, Some(script)) => script.display().to_string(),
+                (None, None) => String::new(),
+            };
+            builder.push_record([
+                name.to_string(),
+                depends_on_cell(&pipeline.steps[*name].depends_on),
+                command,
+            ]);
+        }
+        println!("{}", builder.build().with(Style::blank()));
+        return Ok(());
+    }
+
+    let spool = flush_spool_first(config, &add_args, &client).await?;
+    let Some(responses) = submit(&client, spool.as_ref(), jobs).await? else {
+        return Ok(());
+    };
+
+    if add_args.quiet {
+        for response in &responses {
+            println!("{}", response.id);
+        }
+        return Ok(());
+    }
+
+    let mut builder = Builder::default();
+    builder.push_record(["STEP", "JOBID", "RUN NAME", "DEPENDS ON"]);
+    for (name, response) in order.iter().zip(&responses) {
+        builder.push_record([
+            name.to_string(),
+            response.id.to_string(),
+            response.run_name.clone(),
+            depends_on_cell(&pipeline.steps[*name].depends_on),
+        ]);
+    }
+    println!("{}", builder.build().with(Style::blank()));
+    if add_args.verbose {
+        for (name, response) in order.iter().zip(&responses) {
+            println!("Step {name} (job {}):", response.id);
+            print_details(response);
+        }
+    }
+    print_group(group_id, &responses, add_args.max_concurrent.is_some());
+    Ok(())
+}
+
+/// The `gbatch` arguments for one step: the command line's, with the step's
+/// own settings on top.
+fn step_args(
+    add_args: &cli::AddArgs,
+    name: &str,
+    step: &Step,
+    base_dir: &Path,
+    config: &gflow::config::Config,
+) -> Result<cli::AddArgs> {
+    let mut args = add_args.clone();
+    args.name = Some(step.name.clone().unwrap_or_else(|| name.to_string()));
+    args.gpus = step.gpus.or(args.gpus);
+    args.cpus = step.cpus.or(args.cpus);
+    args.priority = step.priority.map(|p| p.to_string()).or(args.priority);
+    args.time = step.time.as_ref().map(Scalar::to_string).or(args.time);
+    args.memory = step.memory.as_ref().map(Scalar::to_string).or(args.memory);
+    args.gpu_memory = step
+        .gpu_memory
+        .as_ref()
+        .map(Scalar::to_string)
+        .or(args.gpu_memory);
+    if let Some(conda_env) = &step.conda_env {
+        checks::check_conda_env(conda_env)?;
+        args.conda_env = Some(conda_env.clone());
+    }
+
+    // A command is set on the built job as written, so its placeholders and
+    // quoting survive.
+    args.script_or_command = match &step.script {
+        Some(script) => {
+            let script = base_dir.join(script);
+            if !script.is_file() {
+                return Err(anyhow!(
+                    "Step '{name}': script {} does not exist",
+                    script.display()
+                ));
+            }
+            let script = script.to_string_lossy().into_owned();
+            let checks = ScriptChecks {
+                chmod: !args.no_chmod,
+                allow_crlf: args.allow_crlf,
+                default_shell: &config.daemon.default_shell,
+            };
+            checks::check_script_argument(&script, &checks)?;
+            vec![script]
+        }
+        None => Vec::new(),
+    };
+    Ok(args)
+}
+
+fn depends_on_cell(depends_on: &[String]) -> String {
+    if depends_on.is_empty() {
+        "-".to_string()
+    } else {
+        depends_on.join(",")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_yaml(content: &str) -> Result<Pipeline> {
+        let pipeline: Pipeline = serde_yaml::from_str(content)?;
+        pipeline.validate()?;
+        Ok(pipeline)
+    }
+
+    #[test]
+    fn steps_follow_their_dependencies() {
+        let pipeline = parse_yaml(
+            "params:\n  lr: 0.01\nsteps:\n  \
+             eval:\n    command: python eval.py\n    depends_on: [train, baseline]\n  \
+             train:\n    command: python train.py --lr {lr}\n    depends_on: [prepare]\n    time: 30\n  \
+             prepare:\n    command: python prep.py\n  \
+             baseline:\n    script: baseline.sh\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            pipeline.submission_order().unwrap(),
+            ["baseline", "prepare", "train", "eval"]
+        );
+        assert_eq!(pipeline.params["lr"].to_string(), "0.01");
+        assert_eq!(
+            pipeline.steps["train"].time.as_ref().map(Scalar::to_string),
+            Some("30".to_string())
+        );
+    }
+
+    #[test]
+    fn toml_pipelines_parse_the_same() {
+        let pipeline: Pipeline = toml::from_str(
+            "[steps.prepare]\ncommand = \"python prep.py\"\n\n\
+             [steps.train]\ncommand = \"python train.py\"\ndepends_on = [\"prepare\"]\ngpus = 2\n",
+        )
+        .unwrap();
+        assert_eq!(pipeline.submission_order().unwrap(), ["prepare", "train"]);
+        assert_eq!(pipeline.steps["train"].gpus, Some(2));
+    }
+
+    #[test]
+    fn invalid_pipelines_are_refused() {
+        let cycle = parse_yaml(
+            "steps:\n  a:\n    command: x\n    depends_on: [b]\n  \
+             b:\n    command: y\n    depends_on: [a]\n  c:\n    command: z\n",
+        )
+        .unwrap();
+        assert_eq!(
+            cycle.submission_order().unwrap_err().to_string(),
+            "Pipeline steps depend on each other in a cycle: a, b"
+        );
+
+        let unknown = parse_yaml("steps:\n  a:\n    command: x\n    depends_on: [b]\n");
+        assert_eq!(
+            unknown.unwrap_err().to_string(),
+            "Step 'a' depends on unknown step 'b'"
+        );
+
+        let both = parse_yaml("steps:\n  a:\n    command: x\n    script: a.sh\n");
+        assert_eq!(
+            both.unwrap_err().to_string(),
+            "Step 'a' needs either a command or a script"
+        );
+        assert!(parse_yaml("steps:\n  a:\n    command: x\n    gpu: 1\n").is_err());
+        assert!(parse_yaml("steps: {}\n").is_err());
+    }
+}
@@ -0,0 +1,177 @@
+//! `gbatch` defaults, lowest first: builtin values, the `[gbatch]` table of the
+//! user config, the project's `.gflow.toml`, and flags on the command line.
+//! Script directives sit between the defaults and the flags.
+
+use crate::multicall::gbatch::cli;
+use anyhow::{anyhow, Context, Result};
+use gflow::config::{find_project_config, load_project_config, Config, GbatchConfig};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use tabled::{builder::Builder, settings::style::Style};
+
+pub(crate) struct Defaults {
+    user: GbatchConfig,
+    project: Option<(PathBuf, GbatchConfig)>,
+}
+
+#[derive(Debug, PartialEq)]
+enum Source<'a> {
+    Builtin,
+    UserConfig,
+    ProjectFile(&'a Path),
+    CommandLine,
+}
+
+impl fmt::Display for Source<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Builtin => f.write_str("builtin"),
+            Self::UserConfig => f.write_str("user config"),
+            Self::ProjectFile(path) => write!(f, "project file ({})", path.display()),
+            Self::CommandLine => f.write_str("command line"),
+        }
+    }
+}
+
+impl Defaults {
+    /// The user config's defaults and those of the project file found from
+    /// the working directory.
+    pub(crate) fn load(config: &Config) -> Result<Self> {
+        config
+            .gbatch
+            .validate()
+            .map_err(|e| anyhow!("Invalid user config: {e}"))?;
+        let dir = std::env::current_dir().context("Failed to get current directory")?;
+        let project = match find_project_config(&dir) {
+            Some(path) => {
+                let project = load_project_config(&path)?;
+                Some((path, project))
+            }
+            None => None,
+        };
+        Ok(Self {
+            user: config.gbatch.clone(),
+            project,
+        })
+    }
+
+    /// The defaults that apply, the project file's winning over the user config's.
+    pub(crate) fn merged(&self) -> GbatchConfig {
+        match &self.project {
+            Some((_, project)) => self.user.overridden_by(project),
+            None => self.user.clone(),
+        }
+    }
+
+    /// Each setting's value and where it came from (`--show-effective-config`).
+    pub(crate) fn print(&self, args: &cli::AddArgs) {
+        let rows = [
+            (
+                "gpus",
+                self.resolve(args.gpus.map(|v| v.to_string()), "0", |c| {
+                    c.gpus.map(|v| v.to_string())
+                }),
+            ),
+            (
+                "conda_env",
+                self.resolve(args.conda_env.clone(), "-", |c| c.conda_env.clone()),
+            ),
+            (
+                "priority",
+                self.resolve(args.priority.clone(), "10", |c| {
+                    c.priority.map(|v| v.to_string())
+                }),
+            ),
+            (
+                "time",
+                self.resolve(args.time.clone(), "-", |c| c.time.clone()),
+            ),
+            (
+                "max_concurrent",
+                self.resolve(args.max_concurrent.map(|v| v.to_string()), "-", |c| {
+                    c.max_concurrent.map(|v| v.to_string())
+                }),
+            ),
+        ];
+
+        let mut builder = Builder::default();
+        builder.push_record(["KEY", "VALUE", "SOURCE"]);
+        for (key, (value, source)) in rows {
+            builder.push_record([key.to_string(), value, source.to_string()]);
+        }
+        println!("{}", builder.build().with(Style::blank()));
+        if self.project.is_none() {
+            println!("No .gflow.toml found in this directory or its parents.");
+        }
+    }
+
+    fn resolve(
+        &self,
+        command_line: Option<String>,
+        builtin: &str,
+        value: impl Fn(&GbatchConfig) -> Option<String>,
+    ) -> (String, Source<'_>) {
+        if let Some(v) = command_line {
+            return (v, Source::CommandLine);
+        }
+        if let Some((path, project)) = &self.project {
+            if let Some(v) = value(project) {
+                return (v, Source::ProjectFile(path));
+            }
+        }
+        match value(&self.user) {
+            Some(v) => (v, Source::UserConfig),
+            None => (builtin.to_string(), Source::Builtin),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn each_value_comes_from_the_highest_layer_that_sets_it() {
+        let defaults = Defaults {
+            user: GbatchConfig {
+                gpus: Some(1),
+                priority: Some(5),
+                ..Default::default()
+            },
+            project: Some((
+                PathBuf::from("/work/.gflow.toml"),
+                GbatchConfig {
+                    gpus: Some(2),
+                    time: Some("1:00:00".to_string()),
+                    ..Default::default()
+                },
+            )),
+        };
+
+        let merged = defaults.merged();
+        assert_eq!(merged.gpus, Some(2));
+        assert_eq!(merged.priority, Some(5));
+        assert_eq!(merged.time.as_deref(), Some("1:00:00"));
+
+        let gpus = |c: &GbatchConfig| c.gpus.map(|v| v.to_string());
+        assert_eq!(
+            defaults.resolve(None, "0", gpus),
+            (
+                "2".to_string(),
+                Source::ProjectFile(Path::new("/work/.gflow.toml"))
+            )
+        );
+        assert_eq!(
+            defaults.resolve(Some("4".to_string()), "0", gpus),
+            ("4".to_string(), Source::CommandLine)
+        );
+        assert_eq!(
+            defaults.resolve(None, "10", |c| c.priority.map(|v| v.to_string())),
+            ("5".to_string(), Source::UserConfig)
+        );
+        assert_eq!(
+            defaults.resolve(None, "-", |c| c.conda_env.clone()),
+            ("-".to_string(), Source::Builtin)
+        );
+    }
+}
@@ -5,33 +5,59 @@ use gflow::config::load_config;
 use std::ffi::OsString;
 use std::io::{self, IsTerminal};
 
+mod checks;
 mod cli;
 mod commands;
+mod defaults;
+mod spool;
 
 pub async fn run(argv: Vec<OsString>) -> Result<()> {
-    let args = cli::GBatch::parse_from(argv);
-    let config = load_config(args.config.as_ref())?;
+    let mut args = cli::GBatch::parse_from(argv);
+    crate::multicall::select_remote(args.remote.as_deref());
+    crate::multicall::skip_version_check(args.skip_version_check);
+    let mut config = load_config(args.config.as_ref())?;
+    config.use_selected_remote()?;
 
     if let Some(commands) = args.commands {
-        handle_commands(&config, commands).await
+        return handle_commands(&config, commands).await;
+    }
+
+    let defaults = defaults::Defaults::load(&config)?;
+    if args.add_args.show_effective_config {
+        defaults.print(&args.add_args);
+        return Ok(());
+    }
+    args.add_args.defaults = defaults.merged();
+    args.add_args.priority_classes = config.priorities.clone();
+    args.add_args.max_concurrent = args
+        .add_args
+        .max_concurrent
+        .or(args.add_args.defaults.max_concurrent);
+
+    if args.add_args.interactive {
+        commands::interactive::handle_interactive(&config, args.add_args).await
+    } else if let Some(pipeline) = args.add_args.pipeline.clone() {
+        commands::pipeline::handle_pipeline(&config, args.add_args, &pipeline).await
     } else {
         // Check if stdin is available (not a terminal)
         let stdin_available = !io::stdin().is_terminal();
 
-        // Check if user explicitly requested stdin with "-"
-        let explicit_stdin =
-            args.add_args.script_or_command.len() == 1 && args.add_args.script_or_command[0] == "-";
+        // Check if user explicitly requested stdin with "--stdin" or "-"
+        let explicit_stdin = args.add_args.stdin
+            || (args.add_args.script_or_command.len() == 1
+                && args.add_args.script_or_command[0] == "-");
 
         // Validate that either we have args, stdin is available, "--param" is specified, or "-" was specified
         if args.add_args.script_or_command.is_empty()
             && !stdin_available
+            && !explicit_stdin
             && args.add_args.param.is_empty()
         {
-            anyhow::bail!("The following required arguments were not provided:\n  <SCRIPT_OR_COMMAND>...\n\nUsage: gbatch <SCRIPT_OR_COMMAND>...\n       gbatch < script.sh\n       gbatch -\n\nFor more information, try 'gbatch --help'");
+            anyhow::bail!("The following required arguments were not provided:\n  <SCRIPT_OR_COMMAND>...\n\nUsage: gbatch <SCRIPT_OR_COMMAND>...\n       gbatch < script.sh\n       gbatch --stdin\n       gbatch -\n\nFor more information, try 'gbatch --help'");
         }
 
         // Use stdin only if:
-        // 1. Explicitly requested with "-", OR
+        // 1. Explicitly requested with "--stdin" or "-", OR
         // 2. stdin is available AND no command args provided (or only "-")
         let use_stdin =
             explicit_stdin || (stdin_available && args.add_args.script_or_command.is_empty());
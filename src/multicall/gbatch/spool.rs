@@ -0,0 +1,311 @@
+//! Local spool for submissions made while gflowd is unreachable
+//!
+//! Each undelivered submission is one JSON file holding the complete job
+//! payloads, named so that lexical order is submission order. Flushing sends
+//! them oldest first and stops at the first one the daemon cannot take yet,
+//! so later submissions never overtake earlier ones. Files that cannot be
+//! parsed, or that the daemon rejects, are renamed aside instead of blocking
+//! the rest of the spool.
+
+use anyhow::{Context, Result};
+use gflow::client::{Client, ClientError, JobSubmitResponse};
+use gflow::core::job::Job;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const LOCK_FILE: &str = ".lock";
+
+#[derive(Serialize, Deserialize)]
+struct SpoolEntry {
+    jobs: Vec<Job>,
+}
+
+pub(crate) struct Spool {
+    dir: PathBuf,
+}
+
+/// Outcome of [`Spool::flush`].
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct FlushReport {
+    pub(crate) submitted: usize,
+    pub(crate) moved_aside: usize,
+    /// Submissions left in the spool because the daemon is still unreachable.
+    pub(crate) pending: usize,
+}
+
+impl Spool {
+    pub(crate) fn open() -> Result<Self> {
+        Ok(Self::at(gflow::paths::get_spool_dir()?))
+    }
+
+    pub(crate) fn at(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    /// Store `jobs` as one pending submission and return the file written.
+    pub(crate) fn push(&self, jobs: &[Job]) -> Result<PathBuf> {
+        fs::create_dir_all(&self.dir)
+            .with_context(|| format!("Failed to create spool directory {}", self.dir.display()))?;
+
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos();
+        let name = format!("{nanos:020}-{}.json", uuid::Uuid::new_v4().simple());
+        let path = self.dir.join(&name);
+        let tmp = self.dir.join(format!(".{name}.tmp"));
+
+        let entry = SpoolEntry {
+            jobs: jobs.to_vec(),
+        };
+        let contents = serde_json::to_vec_pretty(&entry).context("Failed to serialize jobs")?;
+        fs::write(&tmp, contents)
+            .with_context(|| format!("Failed to write spool file {}", tmp.display()))?;
+        fs::rename(&tmp, &path)
+            .with_context(|| format!("Failed to write spool file {}", path.display()))?;
+        Ok(path)
+    }
+
+    /// Pending submission files, oldest first.
+    pub(crate) fn pending(&self) -> Result<Vec<PathBuf>> {
+        let entries = match fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(error) => {
+                return Err(error).with_context(|| {
+                    format!("Failed to read spool directory {}", self.dir.display())
+                })
+            }
+        };
+
+        let mut files: Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| {
+                path.extension().is_some_and(|ext| ext == "json")
+                    && !path
+                        .file_name()
+                        .is_some_and(|name| name.to_string_lossy().starts_with('.'))
+            })
+            .collect();
+        files.sort();
+        Ok(files)
+    }
+
+    /// Submit pending entries in order, printing the ids the daemon assigns.
+    ///
+    /// Returns `None` if another `gbatch` is already flushing this spool.
+    pub(crate) async fn flush(&self, client: &Client) -> Result<Option<FlushReport>> {
+        let files = self.pending()?;
+        if files.is_empty() {
+            return Ok(Some(FlushReport::default()));
+        }
+
+        let lock_path = self.dir.join(LOCK_FILE);
+        let lock = fs::File::create(&lock_path)
+            .with_context(|| format!("Failed to open {}", lock_path.display()))?;
+        if lock.try_lock().is_err() {
+            return Ok(None);
+        }
+        // Another gbatch may have flushed just before we took the lock.
+        let files = self.pending()?;
+
+        let mut report = FlushReport::default();
+        for (index, path) in files.iter().enumerate() {
+            let entry = match read_entry(path) {
+                Ok(entry) => entry,
+                Err(error) => {
+                    let aside = move_aside(path, "corrupt")?;
+                    gflow::utils::output::print_warning(format_args!(
+                        "moved unreadable spool file to {}: {error:#}",
+                        aside.display()
+                    ));
+                    report.moved_aside += 1;
+                    continue;
+                }
+            };
+
+            match submit(client, entry.jobs).await {
+                Ok(responses) => {
+                    for response in responses {
+                        println!(
+                            "Submitted spooled batch job {} ({})",
+                            response.id, response.run_name
+                        );
+                    }
+                    fs::remove_file(path)
+                        .with_context(|| format!("Failed to remove {}", path.display()))?;
+                    report.submitted += 1;
+                }
+                Err(error)
+                    if matches!(
+                        error.downcast_ref(),
+                        Some(ClientError::ConnectionRefused { .. })
+                    ) =>
+                {
+                    report.pending = files.len() - index;
+                    break;
+                }
+                Err(error) => {
+                    let aside = move_aside(path, "rejected")?;
+                    gflow::utils::output::print_warning(format_args!(
+                        "gflowd rejected spooled submission, moved to {}: {error:#}",
+                        aside.display()
+                    ));
+                    report.moved_aside += 1;
+                }
+            }
+        }
+        Ok(Some(report))
+    }
+}
+
+/// Submit `jobs` as one request, as a plain `gbatch` run would.
+pub(crate) async fn submit(client: &Client, jobs: Vec<Job>) -> Result<Vec<JobSubmitResponse>> {
+    if let [job] = jobs.as_slice() {
+        let response = client
+            .add_job(job.clone())
+            .await
+            .context("Failed to add job")?;
+        Ok(vec![response])
+    } else {
+        client
+            .add_jobs(jobs)
+            .await
+            .context("Failed to add batch jobs")
+    }
+}
+
+fn read_entry(path: &Path) -> Result<SpoolEntry> {
+    let contents = fs::read(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let entry: SpoolEntry = serde_json::from_slice(&contents).context("Invalid spool file")?;
+    if entry.jobs.is_empty() {
+        anyhow::bail!("Spool file contains no jobs");
+    }
+    Ok(entry)
+}
+
+fn move_aside(path: &Path, suffix: &str) -> Result<PathBuf> {
+    let mut aside = path.as_os_str().to_owned();
+    aside.push(format!(".{suffix}"));
+    let aside = PathBuf::from(aside);
+    fs::rename(path, &aside).with_context(|| format!("Failed to move {} aside", path.display()))?;
+    Ok(aside)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gflow::config::Config;
+    use gflow::core::job::JobBuilder;
+    use std::collections::HashMap;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn job(command: &str) -> Job {
+        JobBuilder::new()
+            .command(command)
+            .submitted_by("tester")
+            .run_dir("/work/project")
+            .parameters(HashMap::from([("lr".to_string(), "0.1".to_string())]))
+            .build()
+    }
+
+    fn client_for(server: &MockServer) -> Client {
+        let mut config = Config::default();
+        config.daemon.host = "127.0.0.1".to_string();
+        config.daemon.port = server.address().port();
+        Client::build(&config).unwrap()
+    }
+
+    #[test]
+    fn push_keeps_full_payload_in_submission_order() {
+        let dir = tempfile::tempdir().unwrap();
+        let spool = Spool::at(dir.path().join("spool"));
+        assert!(spool.pending().unwrap().is_empty());
+
+        let first = spool.push(&[job("echo first")]).unwrap();
+        let second = spool
+            .push(&[job("echo second"), job("echo third")])
+            .unwrap();
+        assert_eq!(spool.pending().unwrap(), vec![first.clone(), second]);
+
+        let entry = read_entry(&first).unwrap();
+        assert_eq!(entry.jobs[0].command.as_deref(), Some("echo first"));
+        assert_eq!(
+            entry.jobs[0].run_dir,
+            std::path::PathBuf::from("/work/project")
+        );
+        assert_eq!(
+            entry.jobs[0].parameters.get("lr").map(|v| v.as_str()),
+            Some("0.1")
+        );
+    }
+
+    #[tokio::test]
+    async fn flush_submits_in_order_and_moves_bad_files_aside() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/jobs"))
+            .respond_with(ResponseTemplate::new(201).set_body_json(serde_json::json!({
+                "id": 1,
+                "run_name": "gjob-1"
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/jobs/batch"))
+            .respond_with(ResponseTemplate::new(201).set_body_json(serde_json::json!([
+                { "id": 2, "run_name": "gjob-2" },
+                { "id": 3, "run_name": "gjob-3" }
+            ])))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let dir = tempfile::tempdir().unwrap();
+        let spool = Spool::at(dir.path().to_path_buf());
+        spool.push(&[job("echo first")]).unwrap();
+        let corrupt = dir.path().join("00000000000000000001-corrupt.json");
+        fs::write(&corrupt, "{ not json").unwrap();
+        spool
+            .push(&[job("echo second"), job("echo third")])
+            .unwrap();
+
+        let report = spool.flush(&client_for(&server)).await.unwrap().unwrap();
+        assert_eq!(
+            report,
+            FlushReport {
+                submitted: 2,
+                moved_aside: 1,
+                pending: 0
+            }
+        );
+        assert!(spool.pending().unwrap().is_empty());
+        assert!(dir
+            .path()
+            .join("00000000000000000001-corrupt.json.corrupt")
+            .exists());
+    }
+
+    #[tokio::test]
+    async fn flush_keeps_entries_while_daemon_is_down() {
+        let dir = tempfile::tempdir().unwrap();
+        let spool = Spool::at(dir.path().to_path_buf());
+        spool.push(&[job("echo first")]).unwrap();
+        spool.push(&[job("echo second")]).unwrap();
+
+        let mut config = Config::default();
+        config.daemon.host = "127.0.0.1".to_string();
+        config.daemon.port = 1;
+        config.client.max_retries = 0;
+        let client = Client::build(&config).unwrap();
+
+        let report = spool.flush(&client).await.unwrap().unwrap();
+        assert_eq!(report.pending, 2);
+        assert_eq!(report.submitted, 0);
+        assert_eq!(spool.pending().unwrap().len(), 2);
+    }
+}
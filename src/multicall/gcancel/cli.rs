@@ -1,6 +1,7 @@
 use clap::Parser;
 use clap_complete::Shell;
 use gflow::build_info::version;
+use gflow::core::job::JobSignal;
 
 #[derive(Debug, Parser)]
 #[command(
@@ -19,6 +20,18 @@ pub struct GCancel {
 
     #[arg(long, global = true, help = "Path to the config file", hide = true)]
     pub config: Option<std::path::PathBuf>,
+
+    /// Talk to the daemon of this `[remotes.<name>]` entry (or set GFLOW_REMOTE)
+    #[arg(long, global = true, value_name = "NAME")]
+    pub remote: Option<String>,
+
+    /// Run even if gflowd declares this client too old (or set GFLOW_SKIP_VERSION_CHECK=1)
+    #[arg(long, global = true)]
+    pub skip_version_check: bool,
+
+    /// When to color output: auto (terminals, unless NO_COLOR is set), always or never
+    #[arg(long, global = true, value_enum, value_name = "WHEN", default_value_t)]
+    pub color: gflow::utils::output::ColorChoice,
 }
 
 #[derive(Debug, Parser)]
@@ -49,16 +62,59 @@ pub struct CancelArgs {
     #[arg(value_hint = clap::ValueHint::Other)]
     pub ids: Option<String>,
 
+    /// Cancel every queued, held or running job of this job group (UUID)
+    #[arg(long, value_name = "UUID", conflicts_with = "ids", value_hint = clap::ValueHint::Other)]
+    pub group: Option<String>,
+
+    /// Send this signal (e.g. USR1 or SIGTERM) to running jobs instead of cancelling them
+    #[arg(
+        long,
+        value_name = "SIGNAL",
+        requires = "ids",
+        conflicts_with = "dry_run",
+        value_hint = clap::ValueHint::Other
+    )]
+    pub signal: Option<JobSignal>,
+
     /// If set, the job will not be cancelled, but the action will be printed
     #[arg(long)]
     pub dry_run: bool,
+
+    /// Allow cancelling jobs of these users (comma-separated) instead of only your own
+    #[arg(long, short = 'u', value_hint = clap::ValueHint::Other)]
+    pub user: Option<String>,
+
+    /// Allow cancelling any user's jobs
+    #[arg(long, conflicts_with = "user")]
+    pub all_users: bool,
 }
 
 #[derive(Debug)]
 pub enum CancelCommand {
-    Cancel { ids: String, dry_run: bool },
-    Finish { id: u32 },
-    Fail { id: u32 },
+    Cancel {
+        ids: String,
+        dry_run: bool,
+        user: Option<String>,
+        all_users: bool,
+    },
+    CancelGroup {
+        group: String,
+        dry_run: bool,
+        user: Option<String>,
+        all_users: bool,
+    },
+    Signal {
+        ids: String,
+        signal: JobSignal,
+        user: Option<String>,
+        all_users: bool,
+    },
+    Finish {
+        id: u32,
+    },
+    Fail {
+        id: u32,
+    },
 }
 
 impl CancelArgs {
@@ -67,13 +123,29 @@ impl CancelArgs {
             Ok(CancelCommand::Finish { id: job_id })
         } else if let Some(job_id) = self.fail {
             Ok(CancelCommand::Fail { id: job_id })
+        } else if let (Some(ids), Some(signal)) = (&self.ids, self.signal) {
+            Ok(CancelCommand::Signal {
+                ids: ids.clone(),
+                signal,
+                user: self.user.clone(),
+                all_users: self.all_users,
+            })
         } else if let Some(ref ids) = self.ids {
             Ok(CancelCommand::Cancel {
                 ids: ids.clone(),
                 dry_run: self.dry_run,
+                user: self.user.clone(),
+                all_users: self.all_users,
+            })
+        } else if let Some(ref group) = self.group {
+            Ok(CancelCommand::CancelGroup {
+                group: group.clone(),
+                dry_run: self.dry_run,
+                user: self.user.clone(),
+                all_users: self.all_users,
             })
         } else {
-            anyhow::bail!("No command specified. Use --finish <id>, --fail <id>, or provide job IDs to cancel")
+            anyhow::bail!("No command specified. Use --finish <id>, --fail <id>, --group <uuid>, or provide job IDs to cancel")
         }
     }
 }
@@ -90,4 +162,33 @@ mod tests {
         assert!(args.cancel_args.cancel);
         assert_eq!(args.cancel_args.ids.as_deref(), Some("1,2,3"));
     }
+
+    #[test]
+    fn group_conflicts_with_ids() {
+        let args = GCancel::try_parse_from(["gcancel", "--group", "abc", "--dry-run"])
+            .expect("should parse --group");
+        assert!(matches!(
+            args.cancel_args.get_command(),
+            Ok(CancelCommand::CancelGroup { ref group, dry_run: true, .. }) if group == "abc"
+        ));
+
+        assert!(GCancel::try_parse_from(["gcancel", "--group", "abc", "1"]).is_err());
+    }
+
+    #[test]
+    fn signal_takes_names_with_or_without_sig() {
+        let args = GCancel::try_parse_from(["gcancel", "--signal", "USR1", "4,5"]).unwrap();
+        assert!(matches!(
+            args.cancel_args.get_command(),
+            Ok(CancelCommand::Signal { ref ids, signal: JobSignal::Usr1, .. }) if ids == "4,5"
+        ));
+        let args = GCancel::try_parse_from(["gcancel", "--signal", "SIGTERM", "4"]).unwrap();
+        assert_eq!(args.cancel_args.signal, Some(JobSignal::Term));
+
+        assert!(GCancel::try_parse_from(["gcancel", "--signal", "STOP", "4"]).is_err());
+        assert!(GCancel::try_parse_from(["gcancel", "--signal", "USR1"]).is_err());
+        assert!(
+            GCancel::try_parse_from(["gcancel", "--signal", "USR1", "--dry-run", "4"]).is_err()
+        );
+    }
 }
@@ -1,19 +1,59 @@
 use super::cli::CancelCommand;
+use gflow::client::UserScope;
 use std::path::PathBuf;
 
 pub mod cancel;
 pub mod fail;
 pub mod finish;
+pub mod signal;
 
 pub async fn handle_commands(
     config_path: &Option<PathBuf>,
     command: CancelCommand,
 ) -> anyhow::Result<()> {
-    let client = gflow::create_client(config_path)?;
+    let mut config = gflow::config::load_config(config_path.as_ref())?;
+    config.use_selected_remote()?;
+    let client = gflow::Client::build(&config)?;
 
     match command {
-        CancelCommand::Cancel { ids, dry_run } => {
-            cancel::handle_cancel(&client, &ids, dry_run).await?;
+        CancelCommand::Cancel {
+            ids,
+            dry_run,
+            user,
+            all_users,
+        } => {
+            let scope = UserScope::from_args(
+                user.as_deref(),
+                all_users,
+                config.gqueue.default_user_filter(),
+            );
+            cancel::handle_cancel(&client, &ids, dry_run, &scope).await?;
+        }
+        CancelCommand::CancelGroup {
+            group,
+            dry_run,
+            user,
+            all_users,
+        } => {
+            let scope = UserScope::from_args(
+                user.as_deref(),
+                all_users,
+                config.gqueue.default_user_filter(),
+            );
+            cancel::handle_cancel_group(&client, &group, dry_run, &scope).await?;
+        }
+        CancelCommand::Signal {
+            ids,
+            signal,
+            user,
+            all_users,
+        } => {
+            let scope = UserScope::from_args(
+                user.as_deref(),
+                all_users,
+                config.gqueue.default_user_filter(),
+            );
+            signal::handle_signal(&client, &ids, signal, &scope).await?;
         }
         CancelCommand::Finish { id } => {
             finish::handle_finish(&client, id).await?;
@@ -1,27 +1,87 @@
-use anyhow::{Context, Result};
-use gflow::{client::Client, core::job::JobState, utils::parse_job_ids};
+use anyhow::{anyhow, bail, Result};
+use gflow::{
+    client::{print_error, Client, JobAction, UserScope},
+    core::job::JobState,
+    utils::parse_job_ids,
+};
 
-pub async fn handle_cancel(client: &Client, ids: &str, dry_run: bool) -> Result<()> {
+pub async fn handle_cancel(
+    client: &Client,
+    ids: &str,
+    dry_run: bool,
+    scope: &UserScope,
+) -> Result<()> {
     let job_ids = parse_job_ids(ids)?;
+    cancel_jobs(client, &job_ids, dry_run, scope).await
+}
+
+/// Cancel the jobs of group `group` that have not finished yet.
+pub async fn handle_cancel_group(
+    client: &Client,
+    group: &str,
+    dry_run: bool,
+    scope: &UserScope,
+) -> Result<()> {
+    let jobs = client
+        .list_group_jobs(group)
+        .await?
+        .ok_or_else(|| anyhow!("No jobs found with group_id '{}'", group))?;
+    let job_ids: Vec<u32> = jobs
+        .iter()
+        .filter(|job| !job.state.is_final())
+        .map(|job| job.id)
+        .collect();
+
+    if job_ids.is_empty() {
+        println!("No active jobs in group {}.", group);
+        return Ok(());
+    }
+    cancel_jobs(client, &job_ids, dry_run, scope).await
+}
 
+async fn cancel_jobs(
+    client: &Client,
+    job_ids: &[u32],
+    dry_run: bool,
+    scope: &UserScope,
+) -> Result<()> {
     if dry_run {
-        perform_dry_run(client, &job_ids).await?;
+        perform_dry_run(client, job_ids, scope).await?;
     } else {
-        for job_id in &job_ids {
+        // Check every job first so a list with someone else's job cancels nothing.
+        if *scope != UserScope::All {
+            for &job_id in job_ids {
+                client.get_job_in_scope(job_id, scope).await?;
+            }
+        }
+        if let [job_id] = job_ids {
             client.cancel_job(*job_id).await?;
             println!("Job {} cancelled.", job_id);
+            return Ok(());
+        }
+
+        let results = client.apply_job_action(JobAction::Cancel, job_ids).await?;
+        let mut failed = 0;
+        for result in results {
+            match result.into_result(JobAction::Cancel) {
+                Ok(job_id) => println!("Job {} cancelled.", job_id),
+                Err(error) => {
+                    print_error(&error);
+                    failed += 1;
+                }
+            }
+        }
+        if failed > 0 {
+            bail!("{} of {} jobs were not cancelled", failed, job_ids.len());
         }
     }
 
     Ok(())
 }
 
-async fn perform_dry_run(client: &Client, job_ids: &[u32]) -> Result<()> {
+async fn perform_dry_run(client: &Client, job_ids: &[u32], scope: &UserScope) -> Result<()> {
     for &job_id in job_ids {
-        let job = client
-            .get_job(job_id)
-            .await?
-            .context(format!("Job {} not found", job_id))?;
+        let job = client.get_job_in_scope(job_id, scope).await?;
 
         let can_cancel = job.state.can_transition_to(JobState::Cancelled);
 
@@ -0,0 +1,38 @@
+use anyhow::{bail, Result};
+use gflow::{
+    client::{print_error, Client, UserScope},
+    core::job::JobSignal,
+    utils::parse_job_ids,
+};
+
+/// Send `signal` to each of the running jobs `ids`, leaving them running.
+pub async fn handle_signal(
+    client: &Client,
+    ids: &str,
+    signal: JobSignal,
+    scope: &UserScope,
+) -> Result<()> {
+    let job_ids = parse_job_ids(ids)?;
+    // Check every job first so a list with someone else's job signals nothing.
+    if *scope != UserScope::All {
+        for &job_id in &job_ids {
+            client.get_job_in_scope(job_id, scope).await?;
+        }
+    }
+
+    let mut failed = 0;
+    for &job_id in &job_ids {
+        match client.signal_job(job_id, signal).await {
+            Ok(()) => println!("Sent {} to job {}.", signal, job_id),
+            Err(error) if job_ids.len() == 1 => return Err(error),
+            Err(error) => {
+                print_error(&error);
+                failed += 1;
+            }
+        }
+    }
+    if failed > 0 {
+        bail!("{} of {} jobs were not signalled", failed, job_ids.len());
+    }
+    Ok(())
+}
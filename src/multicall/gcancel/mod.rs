@@ -1,20 +1,31 @@
 mod cli;
 mod commands;
 
+use crate::multicall::completion::{DynamicKind, DynamicSpec};
 use anyhow::Result;
 use clap::{CommandFactory, Parser};
 use std::ffi::OsString;
 
+const DYNAMIC_COMPLETION: DynamicSpec = DynamicSpec {
+    positional: Some(DynamicKind::Active),
+    subcommands: &[],
+    options: &[],
+};
+
 pub async fn run(argv: Vec<OsString>) -> Result<()> {
     let args = cli::GCancel::parse_from(argv);
+    crate::multicall::select_remote(args.remote.as_deref());
+    crate::multicall::skip_version_check(args.skip_version_check);
+    gflow::utils::output::set_color_choice(args.color);
 
     if let Some(command) = args.command {
         match command {
             cli::Commands::Completion { shell } => {
-                crate::multicall::completion::handle_completion(
+                crate::multicall::completion::handle_dynamic_completion(
                     shell,
                     cli::GCancel::command(),
                     "gcancel",
+                    &DYNAMIC_COMPLETION,
                 )?;
                 return Ok(());
             }
@@ -44,12 +44,50 @@ pub enum Commands {
         limit: usize,
     },
 
+    /// Make the daemon re-read its config file and apply changes live
+    ReloadConfig,
+
+    /// Show or change the daemon's log level until it restarts
+    LogLevel {
+        /// New default level: off, error, warn, info, debug or trace
+        #[arg(conflicts_with = "reset")]
+        level: Option<String>,
+        /// Go back to the level the daemon started with
+        #[arg(long)]
+        reset: bool,
+    },
+
+    /// Set or remove labels matched by `gbatch --constraint`
+    SetLabel {
+        /// Label a single GPU of the daemon's machine
+        #[arg(long, conflicts_with = "node")]
+        gpu: Option<u32>,
+        /// Label a remote node instead of the daemon's machine
+        #[arg(long)]
+        node: Option<String>,
+        /// `key=value` to set, or `key-` to remove
+        #[arg(required = true, value_name = "LABEL")]
+        labels: Vec<String>,
+    },
+
     /// Manage GPU reservations
     Reserve {
         #[command(subcommand)]
         command: ReserveCommands,
     },
 
+    /// Inspect webhook notification delivery
+    Webhooks {
+        #[command(subcommand)]
+        command: WebhooksCommands,
+    },
+
+    /// Show or override per-user submission limits
+    RateLimit {
+        #[command(subcommand)]
+        command: RateLimitCommands,
+    },
+
     /// Generate shell completion scripts
     Completion {
         /// The shell to generate completions for
@@ -58,6 +96,36 @@ pub enum Commands {
     },
 }
 
+#[derive(Debug, Parser)]
+pub enum WebhooksCommands {
+    /// List webhook targets with pending retries and their last success and error
+    Status,
+}
+
+#[derive(Debug, Parser)]
+pub enum RateLimitCommands {
+    /// Show the configured limits and runtime overrides
+    Show,
+
+    /// Override a user's limits until gflowd restarts; unset ones keep the config value
+    Set {
+        /// User whose limits to override
+        user: String,
+        /// Jobs the user may submit per minute
+        #[arg(long, required_unless_present = "max_queued")]
+        per_minute: Option<u32>,
+        /// Queued and held jobs the user may have at once
+        #[arg(long)]
+        max_queued: Option<usize>,
+    },
+
+    /// Drop a user's override and go back to the configured limits
+    Reset {
+        /// User whose override to drop
+        user: String,
+    },
+}
+
 #[derive(Debug, Parser)]
 pub enum GpuProcessCommands {
     /// Ignore a running GPU process for scheduling decisions
@@ -88,9 +156,9 @@ pub enum GpuProcessCommands {
 pub enum ReserveCommands {
     /// Create a GPU reservation
     Create {
-        /// Username for the reservation
+        /// Username for the reservation (default: the current user)
         #[arg(long)]
-        user: String,
+        user: Option<String>,
         /// Number of GPUs to reserve (mutually exclusive with --gpu-spec)
         #[arg(long, conflicts_with = "gpu_spec")]
         gpus: Option<u32>,
@@ -108,6 +176,16 @@ pub enum ReserveCommands {
         /// Overrides config file timezone setting
         #[arg(long)]
         timezone: Option<String>,
+        /// Repeat weekly on these days, in UTC (e.g., "tue" or "mon,wed,fri")
+        #[arg(long, value_name = "DAYS")]
+        repeat: Option<String>,
+        /// Last day an occurrence may start on (YYYY-MM-DD, UTC)
+        #[arg(long, value_name = "DATE", requires = "repeat")]
+        until: Option<String>,
+        /// Schedule a maintenance window: nothing starts during it (admin only).
+        /// Covers all GPUs unless --gpus or --gpu-spec is given
+        #[arg(long)]
+        maintenance: bool,
     },
 
     /// List GPU reservations
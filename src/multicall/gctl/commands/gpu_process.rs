@@ -2,7 +2,7 @@ use anyhow::Result;
 use gflow::client::Client;
 
 fn print_warning() {
-    eprintln!("Warning: manually ignoring a GPU process is unsafe.");
+    gflow::utils::output::print_warning(format_args!("manually ignoring a GPU process is unsafe."));
     eprintln!("gflow may schedule onto that GPU even though the process is still attached.");
     eprintln!("This override is runtime-only and will be cleared after gflowd restarts.");
 }
@@ -10,7 +10,10 @@ fn print_warning() {
 pub async fn handle_ignore_gpu_process(client: &Client, gpu: u32, pid: u32) -> Result<()> {
     print_warning();
     client.ignore_gpu_process(gpu, pid).await?;
-    eprintln!("Warning: override applied for GPU {} PID {}.", gpu, pid);
+    gflow::utils::output::print_warning(format_args!(
+        "override applied for GPU {} PID {}.",
+        gpu, pid
+    ));
     println!("Ignoring GPU process PID {} on GPU {}", pid, gpu);
     Ok(())
 }
@@ -0,0 +1,16 @@
+use anyhow::Result;
+use gflow::client::Client;
+
+pub async fn handle_log_level(client: &Client, level: Option<&str>, reset: bool) -> Result<()> {
+    let now = if level.is_some() || reset {
+        client.set_log_level(level).await?
+    } else {
+        client.get_log_level().await?
+    };
+
+    println!("Log level: {} (started with {})", now.level, now.configured);
+    if now.filter != now.level {
+        println!("Filter: {}", now.filter);
+    }
+    Ok(())
+}
@@ -4,13 +4,18 @@ use gflow::client::Client;
 use gflow::config::Config;
 
 pub mod gpu_process;
+pub mod log_level;
+pub mod rate_limit;
+pub mod reload_config;
 pub mod reserve_cancel;
 pub mod reserve_create;
 pub mod reserve_get;
 pub mod reserve_list;
 pub mod set_gpus;
 pub mod set_group_max_concurrency;
+pub mod set_label;
 pub mod show_gpus;
+pub mod webhooks;
 
 use super::cli;
 
@@ -48,6 +53,15 @@ pub async fn handle_commands(
             )
             .await?;
         }
+        cli::Commands::ReloadConfig => {
+            reload_config::handle_reload_config(client).await?;
+        }
+        cli::Commands::LogLevel { level, reset } => {
+            log_level::handle_log_level(client, level.as_deref(), reset).await?;
+        }
+        cli::Commands::SetLabel { gpu, node, labels } => {
+            set_label::handle_set_label(client, gpu, node.as_deref(), &labels).await?;
+        }
         cli::Commands::Reserve { command } => match command {
             cli::ReserveCommands::Create {
                 user,
@@ -56,17 +70,23 @@ pub async fn handle_commands(
                 start,
                 duration,
                 timezone,
+                repeat,
+                until,
+                maintenance,
             } => {
                 reserve_create::handle_reserve_create(
                     client,
                     config,
                     reserve_create::ReserveCreateParams {
-                        user: &user,
+                        user: &user.unwrap_or_else(gflow::platform::get_current_username),
                         gpus: gpus.as_ref().copied(),
                         gpu_spec: gpu_spec.as_deref(),
                         start: &start,
                         duration: &duration,
                         timezone: timezone.as_deref(),
+                        repeat: repeat.as_deref(),
+                        until: until.as_deref(),
+                        maintenance,
                     },
                 )
                 .await?;
@@ -98,6 +118,26 @@ pub async fn handle_commands(
                 reserve_cancel::handle_reserve_cancel(client, id).await?;
             }
         },
+        cli::Commands::Webhooks { command } => match command {
+            cli::WebhooksCommands::Status => {
+                webhooks::handle_webhooks_status(client).await?;
+            }
+        },
+        cli::Commands::RateLimit { command } => match command {
+            cli::RateLimitCommands::Show => {
+                rate_limit::handle_show(client).await?;
+            }
+            cli::RateLimitCommands::Set {
+                user,
+                per_minute,
+                max_queued,
+            } => {
+                rate_limit::handle_set(client, &user, per_minute, max_queued).await?;
+            }
+            cli::RateLimitCommands::Reset { user } => {
+                rate_limit::handle_reset(client, &user).await?;
+            }
+        },
         cli::Commands::Completion { shell } => {
             crate::multicall::completion::handle_completion(shell, cli::GCtl::command(), "gctl")?;
         }
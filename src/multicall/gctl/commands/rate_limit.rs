@@ -0,0 +1,63 @@
+use anyhow::Result;
+use gflow::client::Client;
+use gflow::config::UserRateLimit;
+use std::collections::BTreeSet;
+
+fn format_limit<T: ToString>(limit: Option<T>) -> String {
+    limit.map_or_else(|| "none".to_string(), |limit| limit.to_string())
+}
+
+fn print_limits(who: &str, limits: &UserRateLimit, source: &str) {
+    println!(
+        "{who}\tper_minute={}\tmax_queued={}\t{source}",
+        format_limit(limits.submissions_per_minute),
+        format_limit(limits.max_queued_jobs)
+    );
+}
+
+pub async fn handle_show(client: &Client) -> Result<()> {
+    let limits = client.get_rate_limits().await?;
+    let defaults = limits.config.limits_for("", None);
+    print_limits("default", &defaults, "config");
+
+    let users: BTreeSet<&String> = limits
+        .config
+        .users
+        .keys()
+        .chain(limits.overrides.keys())
+        .collect();
+    for user in users {
+        let overridden = limits.overrides.get(user);
+        let source = if overridden.is_some() {
+            "override"
+        } else {
+            "config"
+        };
+        print_limits(user, &limits.config.limits_for(user, overridden), source);
+    }
+    Ok(())
+}
+
+pub async fn handle_set(
+    client: &Client,
+    user: &str,
+    per_minute: Option<u32>,
+    max_queued: Option<usize>,
+) -> Result<()> {
+    let limit = UserRateLimit {
+        submissions_per_minute: per_minute,
+        max_queued_jobs: max_queued,
+    };
+    client.set_rate_limit(user, &limit).await?;
+    println!("Rate limits of {user} overridden until gflowd restarts");
+    Ok(())
+}
+
+pub async fn handle_reset(client: &Client, user: &str) -> Result<()> {
+    if client.reset_rate_limit(user).await? {
+        println!("Rate limits of {user} reset to the config");
+    } else {
+        println!("{user} has no rate limit override");
+    }
+    Ok(())
+}
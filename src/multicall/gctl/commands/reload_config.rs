@@ -0,0 +1,19 @@
+use anyhow::Result;
+use gflow::client::Client;
+
+pub async fn handle_reload_config(client: &Client) -> Result<()> {
+    let report = client.reload_config().await?;
+
+    if report.applied.is_empty() && report.rejected.is_empty() {
+        println!("Config reloaded: no changes");
+        return Ok(());
+    }
+
+    for key in &report.applied {
+        println!("Applied: {key}");
+    }
+    for entry in &report.rejected {
+        eprintln!("Not applied: {entry}");
+    }
+    Ok(())
+}
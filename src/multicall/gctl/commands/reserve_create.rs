@@ -1,9 +1,9 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
 use gflow::client::Client;
 use gflow::config::Config;
-use gflow::core::reservation::GpuSpec;
+use gflow::core::reservation::{GpuSpec, Recurrence, ReservationKind, ReservationOptions};
 use gflow::print_field;
-use gflow::utils::parsers::{parse_gpu_indices, parse_reservation_duration};
+use gflow::utils::parsers::{parse_gpu_indices, parse_reservation_duration, parse_weekdays};
 use gflow::utils::timezone::parse_reservation_time_with_tz;
 
 pub struct ReserveCreateParams<'a> {
@@ -13,6 +13,9 @@ pub struct ReserveCreateParams<'a> {
     pub start: &'a str,
     pub duration: &'a str,
     pub timezone: Option<&'a str>,
+    pub repeat: Option<&'a str>,
+    pub until: Option<&'a str>,
+    pub maintenance: bool,
 }
 
 pub async fn handle_reserve_create(
@@ -29,29 +32,70 @@ pub async fn handle_reserve_create(
 
     // Determine GPU specification
     let gpu_spec = match (params.gpus, params.gpu_spec) {
-        (Some(count), None) => GpuSpec::Count(count),
+        (Some(count), None) => Some(GpuSpec::Count(count)),
         (None, Some(spec_str)) => {
             let indices = parse_gpu_indices(spec_str)?;
             if indices.is_empty() {
                 anyhow::bail!("GPU specification cannot be empty");
             }
-            GpuSpec::Indices(indices)
+            Some(GpuSpec::Indices(indices))
         }
         (Some(_), Some(_)) => {
             anyhow::bail!("Cannot specify both --gpus and --gpu-spec");
         }
+        // The daemon reserves every GPU for maintenance.
+        (None, None) if params.maintenance => None,
         (None, None) => {
             anyhow::bail!("Must specify either --gpus or --gpu-spec");
         }
     };
 
+    let recurrence = params
+        .repeat
+        .map(|days| {
+            Ok::<_, anyhow::Error>(Recurrence {
+                weekdays: parse_weekdays(days)?,
+                until: params.until.map(parse_until).transpose()?,
+            })
+        })
+        .transpose()?;
+    let options = ReservationOptions {
+        kind: if params.maintenance {
+            ReservationKind::Maintenance
+        } else {
+            ReservationKind::User
+        },
+        recurrence,
+    };
+
     // Create reservation
     let reservation_id = client
-        .create_reservation(params.user.to_string(), gpu_spec, start_time, duration_secs)
+        .create_reservation_with(
+            params.user.to_string(),
+            gpu_spec,
+            start_time,
+            duration_secs,
+            &options,
+        )
         .await?;
 
     println!("Reservation created successfully.");
     print_field!("ReservationID", "{}", reservation_id);
+    if let Some(recurrence) = &options.recurrence {
+        print_field!("Repeats", "{}", recurrence);
+    }
 
     Ok(())
 }
+
+/// `--until` names the last day an occurrence may start on, so the recurrence
+/// ends at the following UTC midnight.
+fn parse_until(date: &str) -> Result<std::time::SystemTime> {
+    let date = chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d")
+        .with_context(|| format!("Invalid --until date '{}' (expected YYYY-MM-DD)", date))?;
+    let end = date
+        .succ_opt()
+        .and_then(|next| next.and_hms_opt(0, 0, 0))
+        .context("--until date is out of range")?;
+    Ok(end.and_utc().into())
+}
@@ -1,7 +1,7 @@
 use anyhow::Result;
 use gflow::client::Client;
 use gflow::config::Config;
-use gflow::core::reservation::{GpuSpec, ReservationStatus};
+use gflow::core::reservation::{GpuSpec, ReservationKind, ReservationStatus};
 use gflow::print_field;
 use gflow::utils::timezone::format_system_time;
 
@@ -15,6 +15,9 @@ pub async fn handle_reserve_get(client: &Client, config: &Config, id: u32) -> Re
             println!("Reservation Details:");
             print_field!("ID", "{}", r.id);
             print_field!("User", "{}", r.user);
+            if r.kind == ReservationKind::Maintenance {
+                print_field!("Kind", "Maintenance");
+            }
 
             // Display GPU specification
             match &r.gpu_spec {
@@ -46,6 +49,9 @@ pub async fn handle_reserve_get(client: &Client, config: &Config, id: u32) -> Re
                 "{}",
                 gflow::utils::format_duration_compact(r.duration)
             );
+            if let Some(recurrence) = &r.recurrence {
+                print_field!("Repeats", "{}", recurrence);
+            }
             print_field!("Status", "{}", format_status(r.status));
             print_field!(
                 "CreatedAt",
@@ -81,7 +81,7 @@ pub async fn handle_reserve_list(
 
         // Render table view
         let mut builder = Builder::default();
-        builder.push_record(["ID", "USER", "GPUS", "START", "END", "STATUS"]);
+        builder.push_record(["ID", "USER", "GPUS", "START", "END", "STATUS", "REPEATS"]);
 
         for reservation in reservations {
             let start_time = format_system_time_short(reservation.start_time, config_tz)?;
@@ -91,11 +91,15 @@ pub async fn handle_reserve_list(
 
             builder.push_record([
                 reservation.id.to_string(),
-                reservation.user.to_string(),
+                reservation.holder().to_string(),
                 gpu_spec_str,
                 start_time,
                 end_time,
                 status_str,
+                reservation
+                    .recurrence
+                    .as_ref()
+                    .map_or_else(|| "-".to_string(), ToString::to_string),
             ]);
         }
 
@@ -0,0 +1,61 @@
+use anyhow::Result;
+use gflow::client::Client;
+use gflow::utils::constraint::{format_labels, parse_label, Labels};
+
+pub async fn handle_set_label(
+    client: &Client,
+    gpu: Option<u32>,
+    node: Option<&str>,
+    labels: &[String],
+) -> Result<()> {
+    let (set, remove) = parse_label_edits(labels)?;
+    let labels = client.update_labels(gpu, node, &set, &remove).await?;
+
+    let target = match (gpu, node) {
+        (Some(gpu), _) => format!("GPU {gpu}"),
+        (None, Some(node)) => format!("Node '{node}'"),
+        (None, None) => "This machine".to_string(),
+    };
+    if labels.is_empty() {
+        println!("{target} has no labels");
+    } else {
+        println!("{target} is labelled {}", format_labels(&labels));
+    }
+    Ok(())
+}
+
+/// Split `key=value` labels to set from `key-` labels to remove.
+fn parse_label_edits(labels: &[String]) -> Result<(Labels, Vec<String>)> {
+    let mut set = Labels::new();
+    let mut remove = Vec::new();
+    for label in labels {
+        match label.strip_suffix('-') {
+            Some(key) if !label.contains('=') => remove.push(key.to_string()),
+            _ => {
+                let (key, value) = parse_label(label)?;
+                set.insert(key, value);
+            }
+        }
+    }
+    Ok((set, remove))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_label_edits_splits_sets_and_removals() {
+        let (set, remove) =
+            parse_label_edits(&["arch=ampere".to_string(), "nvlink-".to_string()]).unwrap();
+        assert_eq!(set.get("arch").map(String::as_str), Some("ampere"));
+        assert_eq!(remove, vec!["nvlink".to_string()]);
+
+        // A value may end in '-'.
+        let (set, remove) = parse_label_edits(&["tier=b-".to_string()]).unwrap();
+        assert_eq!(set.get("tier").map(String::as_str), Some("b-"));
+        assert!(remove.is_empty());
+
+        assert!(parse_label_edits(&["arch".to_string()]).is_err());
+    }
+}
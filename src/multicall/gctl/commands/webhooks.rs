@@ -0,0 +1,29 @@
+use anyhow::Result;
+use gflow::client::Client;
+use gflow::utils::format_system_time;
+
+pub async fn handle_webhooks_status(client: &Client) -> Result<()> {
+    let targets = client.list_webhook_targets().await?;
+    if targets.is_empty() {
+        println!("No webhooks configured");
+        return Ok(());
+    }
+
+    for target in targets {
+        println!("[{}] {}", target.index, target.url);
+        println!("  pending: {}", target.pending);
+        println!(
+            "  last success: {}",
+            target
+                .last_success_at
+                .map_or_else(|| "never".to_string(), format_system_time)
+        );
+        match (target.last_error_at, target.last_error) {
+            (Some(at), Some(error)) => {
+                println!("  last error: {} ({error})", format_system_time(at))
+            }
+            _ => println!("  last error: none"),
+        }
+    }
+    Ok(())
+}
@@ -226,8 +226,9 @@ fn print_reservation_bar_to_writer<W: std::io::Write>(
     let res_start = reservation.start_time;
     let res_end = reservation.end_time();
 
-    // Skip if completely outside range
-    if res_end < range_start || res_start > range_end {
+    // Recurring reservations get one segment per occurrence in range
+    let occurrences = reservation.occurrences_between(range_start, range_end);
+    if occurrences.is_empty() {
         return;
     }
 
@@ -235,12 +236,6 @@ fn print_reservation_bar_to_writer<W: std::io::Write>(
     const LABEL_WIDTH: usize = 16;
     let bar_width = width.saturating_sub(LABEL_WIDTH);
 
-    // Calculate bar position and length using the FULL width (same as axis)
-    // This ensures alignment with the time axis
-    let bar_start = time_to_position(res_start.max(range_start), range_start, range_end, width);
-    let bar_end = time_to_position(res_end.min(range_end), range_start, range_end, width);
-    let bar_length = bar_end.saturating_sub(bar_start).max(1);
-
     // Create the bar with reduced width (to account for label)
     let mut bar = vec![' '; bar_width];
 
@@ -252,17 +247,28 @@ fn print_reservation_bar_to_writer<W: std::io::Write>(
         ReservationStatus::Cancelled => '▒',
     };
 
-    #[allow(clippy::needless_range_loop)]
-    for pos in bar_start..bar_start + bar_length {
-        // Adjust position: subtract LABEL_WIDTH because the bar area starts after the label
-        if pos >= LABEL_WIDTH && pos - LABEL_WIDTH < bar_width {
-            bar[pos - LABEL_WIDTH] = bar_char;
+    for occurrence in occurrences {
+        // Calculate bar position and length using the FULL width (same as axis)
+        // This ensures alignment with the time axis
+        let occurrence_end = occurrence + reservation.duration;
+        let bar_start =
+            time_to_position(occurrence.max(range_start), range_start, range_end, width);
+        let bar_end =
+            time_to_position(occurrence_end.min(range_end), range_start, range_end, width);
+        let bar_length = bar_end.saturating_sub(bar_start).max(1);
+
+        #[allow(clippy::needless_range_loop)]
+        for pos in bar_start..bar_start + bar_length {
+            // Adjust position: subtract LABEL_WIDTH because the bar area starts after the label
+            if pos >= LABEL_WIDTH && pos - LABEL_WIDTH < bar_width {
+                bar[pos - LABEL_WIDTH] = bar_char;
+            }
         }
     }
 
     // Create label with GPU spec
     let gpu_spec_str = format_gpu_spec(&reservation.gpu_spec);
-    let label = format!("{} (GPU: {})", reservation.user, gpu_spec_str);
+    let label = format!("{} (GPU: {})", reservation.holder(), gpu_spec_str);
 
     // Print user label and bar
     let bar_str: String = bar.iter().collect();
@@ -270,12 +276,15 @@ fn print_reservation_bar_to_writer<W: std::io::Write>(
     writeln!(writer, "{:<15} {}", label, bar_str).ok();
 
     // Print status info below
-    let status_info = format!(
+    let mut status_info = format!(
         "  └─ {} ({}→{})",
         format_status(reservation.status),
         format_time_short(res_start, tz),
         format_time_short(res_end, tz)
     );
+    if let Some(recurrence) = &reservation.recurrence {
+        status_info.push_str(&format!(", repeats {}", recurrence));
+    }
     writeln!(writer, "{}", status_info).ok();
 }
 
@@ -398,7 +407,7 @@ fn datetime_to_system_time<T: chrono::TimeZone>(dt: DateTime<T>) -> SystemTime {
 mod tests {
     use super::*;
     use compact_str::CompactString;
-    use gflow::core::reservation::GpuSpec;
+    use gflow::core::reservation::{GpuSpec, ReservationKind};
 
     #[test]
     fn test_time_to_position() {
@@ -438,6 +447,8 @@ mod tests {
             status: ReservationStatus::Active,
             created_at: now,
             cancelled_at: None,
+            kind: ReservationKind::User,
+            recurrence: None,
         };
 
         let config = TimelineConfig::default();
@@ -463,6 +474,8 @@ mod tests {
             status: ReservationStatus::Active,
             created_at: base_time,
             cancelled_at: None,
+            kind: ReservationKind::User,
+            recurrence: None,
         };
 
         let config = TimelineConfig {
@@ -529,6 +542,8 @@ mod tests {
             status: ReservationStatus::Pending,
             created_at: base_time,
             cancelled_at: None,
+            kind: ReservationKind::User,
+            recurrence: None,
         };
 
         // Reservation in the middle
@@ -541,6 +556,8 @@ mod tests {
             status: ReservationStatus::Active,
             created_at: base_time,
             cancelled_at: None,
+            kind: ReservationKind::User,
+            recurrence: None,
         };
 
         let config = TimelineConfig {
@@ -580,6 +597,8 @@ mod tests {
             status: ReservationStatus::Active,
             created_at: base_time,
             cancelled_at: None,
+            kind: ReservationKind::User,
+            recurrence: None,
         };
 
         let reservation2 = GpuReservation {
@@ -591,6 +610,8 @@ mod tests {
             status: ReservationStatus::Pending,
             created_at: base_time,
             cancelled_at: None,
+            kind: ReservationKind::User,
+            recurrence: None,
         };
 
         let config = TimelineConfig {
@@ -0,0 +1,297 @@
+//! `gflowd agent`: makes this machine a node of a head daemon.
+//!
+//! The agent registers with the head and registers again every
+//! [`NODE_HEARTBEAT_INTERVAL`] as its heartbeat. The head places jobs on nodes
+//! and forwards them here; the agent starts them with the local executor.
+//! Jobs report their outcome to the head themselves with `gcancel`, so the
+//! agent keeps no job state. Run directories, scripts and logs are used as-is,
+//! so they must be on a filesystem shared with the head.
+
+use anyhow::{bail, Context, Result};
+use axum::{
+    extract::{Path, Request, State},
+    http::{header, StatusCode},
+    middleware::{self, Next},
+    response::{IntoResponse, Response},
+    routing::post,
+    Json, Router,
+};
+use gflow::config::{Config, ListenAddr};
+use gflow::core::executor::Executor;
+use gflow::core::job::{Job, JobSignal};
+use gflow::core::node::{Node, NodeRegistration, NODE_HEARTBEAT_INTERVAL};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::Duration;
+
+use super::cli::AgentArgs;
+use super::executor::JobExecutor;
+use super::scheduler_runtime::SchedulerRuntime;
+use super::tokens::{constant_time_eq, random_hex};
+
+/// How long the head waits for an agent to accept a request.
+const AGENT_REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// What the head daemon sends an agent about one of its jobs.
+#[derive(Debug, Serialize, Deserialize)]
+pub(super) struct AgentJobRequest {
+    pub(super) job: Job,
+    /// Extra environment for `execute`, such as the job's API token
+    #[serde(default)]
+    pub(super) env: Vec<(String, String)>,
+    /// For `release`: also close the job's tmux session
+    #[serde(default)]
+    pub(super) close: bool,
+    /// For `signal`: the signal to send
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(super) signal: Option<JobSignal>,
+}
+
+impl AgentJobRequest {
+    pub(super) fn new(job: Job) -> Self {
+        Self {
+            job,
+            env: Vec::new(),
+            close: false,
+            signal: None,
+        }
+    }
+}
+
+/// The head daemon's side: forwards job actions to agents.
+#[derive(Clone)]
+pub(super) struct AgentClient {
+    http: reqwest::Client,
+}
+
+impl AgentClient {
+    pub(super) fn new() -> Self {
+        gflow::tls::ensure_rustls_provider_installed();
+        Self {
+            http: reqwest::Client::new(),
+        }
+    }
+
+    /// Ask `node`'s agent to `execute`, `interrupt`, `stop` or `release` a job.
+    pub(super) async fn send(
+        &self,
+        node: &Node,
+        action: &str,
+        request: &AgentJobRequest,
+    ) -> Result<()> {
+        let url = format!("{}/agent/{action}", node.url.trim_end_matches('/'));
+        let response = self
+            .http
+            .post(&url)
+            .bearer_auth(&node.token)
+            .timeout(AGENT_REQUEST_TIMEOUT)
+            .json(request)
+            .send()
+            .await
+            .with_context(|| format!("Failed to reach node '{}' at {}", node.name, node.url))?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let message = response.text().await.unwrap_or_default();
+            bail!(
+                "Node '{}' could not {action} job {}: {status} {message}",
+                node.name,
+                request.job.id
+            );
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone)]
+struct AgentState {
+    config: Arc<Config>,
+    /// Where `gcancel` in a job finds the head daemon
+    head_listen: Arc<str>,
+    token: Arc<str>,
+}
+
+impl AgentState {
+    /// The local executor, exporting `env` to the job as well.
+    fn executor(&self, mut env: Vec<(String, String)>) -> JobExecutor {
+        env.push((
+            "GFLOW_DAEMON__LISTEN".to_string(),
+            self.head_listen.to_string(),
+        ));
+        JobExecutor::new(
+            self.config.daemon.executor,
+            &self.config.daemon.default_shell,
+            None,
+            &self.config.container,
+        )
+        .with_tmux_mode(self.config.daemon.tmux_mode)
+        .with_env(env)
+    }
+}
+
+pub(super) async fn run(config: Config, args: AgentArgs) -> Result<()> {
+    let (head_host, head_port) = parse_head_url(&args.join)?;
+    let listen = match format!("tcp:{}", args.listen).parse() {
+        Ok(ListenAddr::Tcp { host, port }) => (host, port),
+        _ => bail!("Invalid --listen '{}': expected <host>:<port>", args.listen),
+    };
+    let hostname = hostname().context("Failed to read this machine's hostname")?;
+    let (labels, _) = config.daemon.parsed_labels()?;
+
+    // Jobs call `gcancel` on this machine; point it at the head daemon.
+    let head_listen = format!("tcp:{head_host}:{head_port}");
+
+    let registration = NodeRegistration {
+        name: args.name.unwrap_or_else(|| hostname.clone()),
+        url: args
+            .advertise
+            .unwrap_or_else(|| format!("http://{hostname}:{}", listen.1)),
+        gpus: nvml_wrapper::Nvml::init()
+            .and_then(|nvml| nvml.device_count())
+            .unwrap_or(0),
+        total_memory_mb: SchedulerRuntime::get_total_system_memory_mb(),
+        token: random_hex(32)?,
+        labels,
+    };
+
+    let mut head_config = config.clone();
+    head_config.daemon.listen = None;
+    head_config.daemon.host = head_host;
+    head_config.daemon.port = head_port;
+    let head = gflow::Client::build(&head_config)?;
+    head.register_node(&registration)
+        .await
+        .with_context(|| format!("Failed to join {}", args.join))?;
+    tracing::info!(
+        node = %registration.name,
+        url = %registration.url,
+        gpus = registration.gpus,
+        memory_mb = registration.total_memory_mb,
+        "Joined {}",
+        args.join
+    );
+
+    let state = AgentState {
+        config: Arc::new(config),
+        head_listen: Arc::from(head_listen),
+        token: Arc::from(registration.token.as_str()),
+    };
+    tokio::spawn(heartbeat(head, registration));
+
+    let app = Router::new()
+        .route("/agent/{action}", post(handle_job_action))
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            require_head_token,
+        ))
+        .with_state(state);
+    let listener = tokio::net::TcpListener::bind((listen.0.as_str(), listen.1))
+        .await
+        .with_context(|| format!("Failed to listen on {}", args.listen))?;
+    tracing::info!(listen = %args.listen, "Waiting for jobs");
+    axum::serve(listener, app).await?;
+    Ok(())
+}
+
+/// Register again every interval; the head marks the node down without it.
+async fn heartbeat(head: gflow::Client, registration: NodeRegistration) {
+    let mut interval = tokio::time::interval(NODE_HEARTBEAT_INTERVAL);
+    interval.tick().await;
+    loop {
+        interval.tick().await;
+        if let Err(error) = head.register_node(&registration).await {
+            tracing::warn!(error = %error, "Heartbeat to the head daemon failed");
+        }
+    }
+}
+
+async fn require_head_token(State(state): State<AgentState>, req: Request, next: Next) -> Response {
+    let authorized = req
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .is_some_and(|token| constant_time_eq(token.trim().as_bytes(), state.token.as_bytes()));
+    if !authorized {
+        return (StatusCode::UNAUTHORIZED, "Unknown head daemon").into_response();
+    }
+    next.run(req).await
+}
+
+async fn handle_job_action(
+    State(state): State<AgentState>,
+    Path(action): Path<String>,
+    Json(request): Json<AgentJobRequest>,
+) -> Response {
+    let job_id = request.job.id;
+    // Executors block on tmux, signals and containers.
+    let result = tokio::task::spawn_blocking(move || {
+        let AgentJobRequest {
+            job,
+            env,
+            close,
+            signal,
+        } = request;
+        let executor = state.executor(env);
+        match action.as_str() {
+            "execute" => executor.execute(&job),
+            "interrupt" => executor.interrupt(&job),
+            "signal" => match signal {
+                Some(signal) => executor.signal(&job, signal),
+                None => bail!("No signal given"),
+            },
+            "stop" => executor.stop(&job),
+            "release" => {
+                executor.release(&job, close);
+                Ok(())
+            }
+            other => bail!("Unknown action '{other}'"),
+        }
+    })
+    .await;
+
+    match result {
+        Ok(Ok(())) => StatusCode::NO_CONTENT.into_response(),
+        Ok(Err(error)) => {
+            tracing::error!(job_id, error = %error, "Job action failed");
+            (StatusCode::INTERNAL_SERVER_ERROR, format!("{error:#}")).into_response()
+        }
+        Err(error) => (StatusCode::INTERNAL_SERVER_ERROR, error.to_string()).into_response(),
+    }
+}
+
+/// Host and port of `http://<host>:<port>`.
+fn parse_head_url(url: &str) -> Result<(String, u16)> {
+    let Some(rest) = url.strip_prefix("http://") else {
+        bail!("Invalid --join '{url}': expected http://<host>:<port>");
+    };
+    match format!("tcp:{}", rest.trim_end_matches('/')).parse() {
+        Ok(ListenAddr::Tcp { host, port }) => Ok((host, port)),
+        _ => bail!("Invalid --join '{url}': expected http://<host>:<port>"),
+    }
+}
+
+pub(crate) fn hostname() -> Result<String> {
+    let mut buf = [0u8; 256];
+    // SAFETY: the buffer is valid for its whole length.
+    if unsafe { libc::gethostname(buf.as_mut_ptr().cast(), buf.len()) } != 0 {
+        return Err(std::io::Error::last_os_error().into());
+    }
+    let len = buf.iter().position(|&b| b == 0).unwrap_or(buf.len());
+    Ok(String::from_utf8_lossy(&buf[..len]).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_head_url_accepts_http_only() {
+        assert_eq!(
+            parse_head_url("http://head:59000/").unwrap(),
+            ("head".to_string(), 59000)
+        );
+        assert!(parse_head_url("head:59000").is_err());
+        assert!(parse_head_url("https://head:59000").is_err());
+        assert!(parse_head_url("http://head").is_err());
+    }
+}
@@ -0,0 +1,220 @@
+//! Rotated copies of the scheduler state
+//!
+//! Backups are MessagePack state files named `state-<UTC timestamp>.msgpack`
+//! in `<data dir>/backups`, so sorting by name sorts by age. The daemon writes
+//! one every `daemon.state_backup_interval_secs` when the state has changed,
+//! and keeps the newest `daemon.state_backups`.
+
+use anyhow::{Context, Result};
+use gflow::core::scheduler::Scheduler;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use super::scheduler_runtime::serialization::{self, SerializationFormat};
+use super::scheduler_runtime::SharedState;
+
+pub(crate) const BACKUP_DIR: &str = "backups";
+
+pub(crate) struct Backup {
+    pub path: PathBuf,
+    pub size: u64,
+}
+
+impl Backup {
+    pub fn name(&self) -> String {
+        self.path
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .into_owned()
+    }
+}
+
+pub(crate) fn backup_dir(state_dir: &Path) -> PathBuf {
+    state_dir.join(BACKUP_DIR)
+}
+
+fn is_backup_name(name: &str) -> bool {
+    name.starts_with("state-") && name.ends_with(".msgpack")
+}
+
+/// Backups in `state_dir`, newest first.
+pub(crate) fn list(state_dir: &Path) -> Result<Vec<Backup>> {
+    let dir = backup_dir(state_dir);
+    let entries = match std::fs::read_dir(&dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e).with_context(|| format!("Failed to read {}", dir.display())),
+    };
+
+    let mut backups = Vec::new();
+    for entry in entries {
+        let entry = entry.with_context(|| format!("Failed to read {}", dir.display()))?;
+        if !is_backup_name(&entry.file_name().to_string_lossy()) {
+            continue;
+        }
+        let size = entry.metadata().map(|meta| meta.len()).unwrap_or_default();
+        backups.push(Backup {
+            path: entry.path(),
+            size,
+        });
+    }
+    backups.sort_by(|a, b| b.path.cmp(&a.path));
+    Ok(backups)
+}
+
+/// Write `scheduler` as a new backup, then delete all but the newest `keep`.
+pub(crate) fn create(scheduler: &Scheduler, state_dir: &Path, keep: usize) -> Result<PathBuf> {
+    let dir = backup_dir(state_dir);
+    let mut name = format!(
+        "state-{}.msgpack",
+        chrono::Utc::now().format("%Y%m%dT%H%M%S%.3fZ")
+    );
+    // A backup within the same millisecond (or after the clock stepped back)
+    // must still sort as the newest.
+    if let Some(latest) = list(state_dir)?.first().map(Backup::name) {
+        if name <= latest {
+            name = latest.replace(".msgpack", "_.msgpack");
+        }
+    }
+    let path = dir.join(name);
+    serialization::write_state_file(scheduler, &path, SerializationFormat::MessagePack)?;
+
+    for old in list(state_dir)?.into_iter().skip(keep.max(1)) {
+        if let Err(e) = std::fs::remove_file(&old.path) {
+            tracing::warn!(path = %old.path.display(), error = %e, "Failed to remove old state backup");
+        }
+    }
+    Ok(path)
+}
+
+/// Whether the newest backup already holds exactly this state.
+pub(crate) fn matches_latest(scheduler: &Scheduler, state_dir: &Path) -> bool {
+    let Some(latest) = list(state_dir)
+        .ok()
+        .and_then(|backups| backups.into_iter().next())
+    else {
+        return false;
+    };
+    let Ok(bytes) = serialization::serialize(scheduler, SerializationFormat::MessagePack) else {
+        return false;
+    };
+    std::fs::read(&latest.path).is_ok_and(|existing| existing == bytes)
+}
+
+/// `backup` as given if it names an existing file, else that name in the backup directory.
+pub(crate) fn resolve(state_dir: &Path, backup: &str) -> PathBuf {
+    let path = PathBuf::from(backup);
+    if path.exists() {
+        return path;
+    }
+    backup_dir(state_dir).join(backup)
+}
+
+/// Read a backup (or any state file) and migrate it to the current schema.
+pub(crate) fn load(path: &Path) -> Result<Scheduler> {
+    let format = SerializationFormat::from_path(path).unwrap_or(SerializationFormat::MessagePack);
+    let bytes =
+        std::fs::read(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let scheduler = serialization::deserialize(&bytes, format)
+        .with_context(|| format!("{} is not a valid state file", path.display()))?;
+    gflow::core::migrations::migrate_state(scheduler)
+        .with_context(|| format!("{} cannot be migrated to this version", path.display()))
+}
+
+/// Check that restoring `backup` does not hand out job IDs again that the
+/// current state already used, unless `force` accepts that.
+pub(crate) fn check_job_ids(
+    current_next_job_id: u32,
+    backup: &Scheduler,
+    force: bool,
+) -> Result<(), String> {
+    if force || backup.next_job_id() >= current_next_job_id {
+        return Ok(());
+    }
+    Err(format!(
+        "The backup ends before job {}, but jobs up to {} were created since; restoring it would reuse their IDs. Use --force to restore anyway.",
+        backup.next_job_id(),
+        current_next_job_id - 1
+    ))
+}
+
+/// Back up the scheduler state periodically, skipping unchanged state.
+pub(crate) async fn run(shared_state: SharedState, every: Duration) {
+    let mut interval = tokio::time::interval_at(tokio::time::Instant::now() + every, every);
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+
+    loop {
+        interval.tick().await;
+        let state = shared_state.read().await;
+        match state.backup_state(true) {
+            Ok(Some(path)) => {
+                tracing::info!(path = %path.display(), "Backed up scheduler state");
+            }
+            Ok(None) => tracing::debug!("State unchanged since the last backup"),
+            Err(e) => {
+                tracing::warn!(error = %format!("{e:#}"), "Failed to back up scheduler state")
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gflow::core::job::JobBuilder;
+    use gflow::core::scheduler::SchedulerBuilder;
+
+    fn scheduler_with_jobs(jobs: usize) -> Scheduler {
+        let mut scheduler = SchedulerBuilder::new().build();
+        for _ in 0..jobs {
+            scheduler.submit_job(JobBuilder::new().command("true").build());
+        }
+        scheduler
+    }
+
+    #[test]
+    fn keeps_the_newest_backups() {
+        let dir = tempfile::tempdir().unwrap();
+        let schedulers: Vec<Scheduler> = (0..4).map(scheduler_with_jobs).collect();
+        let paths: Vec<PathBuf> = schedulers
+            .iter()
+            .map(|scheduler| create(scheduler, dir.path(), 2).unwrap())
+            .collect();
+        // Not a backup; left alone.
+        std::fs::write(backup_dir(dir.path()).join("notes.txt"), "").unwrap();
+
+        let kept: Vec<PathBuf> = list(dir.path())
+            .unwrap()
+            .into_iter()
+            .map(|b| b.path)
+            .collect();
+        assert_eq!(kept, [paths[3].clone(), paths[2].clone()]);
+        assert_eq!(load(&kept[0]).unwrap().job_specs().len(), 3);
+
+        assert!(matches_latest(&schedulers[3], dir.path()));
+        assert!(!matches_latest(&schedulers[2], dir.path()));
+    }
+
+    #[test]
+    fn refuses_backups_that_would_reuse_job_ids_unless_forced() {
+        let backup = scheduler_with_jobs(2);
+        assert!(check_job_ids(3, &backup, false).is_ok());
+
+        let error = check_job_ids(6, &backup, false).unwrap_err();
+        assert!(error.contains("jobs up to 5"), "{error}");
+        assert!(check_job_ids(6, &backup, true).is_ok());
+    }
+
+    #[test]
+    fn rejects_files_that_are_not_state() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("state-x.msgpack");
+        std::fs::write(&path, b"\xc1garbage").unwrap();
+        assert!(load(&path).is_err());
+        assert_eq!(
+            resolve(dir.path(), "state-y.msgpack"),
+            backup_dir(dir.path()).join("state-y.msgpack")
+        );
+    }
+}
@@ -0,0 +1,233 @@
+//! cgroup v2 limits for jobs (`[daemon] enforce_limits`)
+//!
+//! Each local job runs in a cgroup of its own under the daemon's, with
+//! `memory.max` set to its `--memory` limit and `cpu.max` to its `--cpus`, so a
+//! job going over its limit is OOM-killed alone instead of exhausting the
+//! machine. This needs a cgroup v2 hierarchy delegated to the daemon, e.g.
+//! `Delegate=yes` in its systemd unit; without one, jobs run unconfined.
+
+use gflow::core::executor::MemoryUsage;
+use gflow::core::job::Job;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Where the cgroup v2 hierarchy is mounted.
+const CGROUP_ROOT: &str = "/sys/fs/cgroup";
+/// The daemon's own cgroup, next to those of its jobs.
+const DAEMON_CGROUP: &str = "gflowd";
+/// `cpu.max` period: `--cpus N` allows N times this much CPU time per period.
+const CPU_PERIOD_US: u64 = 100_000;
+
+#[derive(Debug)]
+pub(crate) struct Cgroups {
+    /// Holds the daemon's and the jobs' cgroups.
+    base: PathBuf,
+    /// Whether the cpu controller could be enabled for jobs.
+    cpu: bool,
+}
+
+impl Cgroups {
+    /// Prepare the daemon's cgroup for job cgroups. Returns `None` if this
+    /// host has no cgroup v2 hierarchy the daemon may manage.
+    pub(crate) fn detect() -> Option<Self> {
+        let own = fs::read_to_string("/proc/self/cgroup").ok()?;
+        let mut base = Path::new(CGROUP_ROOT).join(own_cgroup(&own)?.trim_start_matches('/'));
+        // A hot-reloaded daemon starts out in its predecessor's cgroup.
+        if base.file_name().is_some_and(|name| name == DAEMON_CGROUP) {
+            base.pop();
+        }
+        match Self::init(base) {
+            Ok(cgroups) => {
+                tracing::info!(cgroup = %cgroups.base.display(), cpu = cgroups.cpu, "Enforcing job limits with cgroups");
+                Some(cgroups)
+            }
+            Err(error) => {
+                tracing::info!(%error, "No delegated cgroup v2 hierarchy; job limits are not enforced");
+                None
+            }
+        }
+    }
+
+    fn init(base: PathBuf) -> io::Result<Self> {
+        let controllers = fs::read_to_string(base.join("cgroup.controllers"))?;
+        if !has_controller(&controllers, "memory") {
+            return Err(io::Error::other("the memory controller is not delegated"));
+        }
+        // Only leaf cgroups may hold processes once controllers are enabled
+        // for children, so the daemon moves into one of its own.
+        let daemon = base.join(DAEMON_CGROUP);
+        create_cgroup(&daemon)?;
+        fs::write(daemon.join("cgroup.procs"), std::process::id().to_string())?;
+        let subtree_control = base.join("cgroup.subtree_control");
+        fs::write(&subtree_control, "+memory")?;
+        let cpu =
+            has_controller(&controllers, "cpu") && fs::write(&subtree_control, "+cpu").is_ok();
+
+        let cgroups = Self { base, cpu };
+        cgroups.prune();
+        Ok(cgroups)
+    }
+
+    /// Remove cgroups left behind by jobs that have ended. Those of jobs
+    /// still running are busy and stay.
+    fn prune(&self) {
+        let Ok(entries) = fs::read_dir(&self.base) else {
+            return;
+        };
+        for entry in entries.flatten() {
+            if entry.file_name().to_string_lossy().starts_with("job-") {
+                fs::remove_dir(entry.path()).ok();
+            }
+        }
+    }
+
+    fn job_cgroup(&self, job_id: u32) -> PathBuf {
+        self.base.join(format!("job-{job_id}"))
+    }
+
+    /// Create a fresh cgroup with the job's limits. Returns its path, or
+    /// `None` if the job has to run without one.
+    pub(crate) fn create(&self, job: &Job) -> Option<PathBuf> {
+        let path = self.job_cgroup(job.id);
+        // A requeued job starts over, OOM kills included.
+        fs::remove_dir(&path).ok();
+        match self.set_up(&path, job) {
+            Ok(()) => Some(path),
+            Err(error) => {
+                tracing::warn!(job_id = job.id, %error, "Failed to set up the job's cgroup; running it without limits");
+                fs::remove_dir(&path).ok();
+                None
+            }
+        }
+    }
+
+    fn set_up(&self, path: &Path, job: &Job) -> io::Result<()> {
+        create_cgroup(path)?;
+        if let Some(limit_mb) = job.memory_limit_mb {
+            fs::write(
+                path.join("memory.max"),
+                (limit_mb * 1024 * 1024).to_string(),
+            )?;
+            // Swapping instead would only slow the job down; not every
+            // kernel has swap accounting, though.
+            fs::write(path.join("memory.swap.max"), "0").ok();
+        }
+        if let Some(cpus) = job.cpus.filter(|_| self.cpu) {
+            fs::write(path.join("cpu.max"), cpu_max(cpus))?;
+        }
+        Ok(())
+    }
+
+    /// Peak memory of the job's cgroup and whether it had an OOM kill.
+    pub(crate) fn usage(&self, job_id: u32) -> Option<MemoryUsage> {
+        let path = self.job_cgroup(job_id);
+        let events = fs::read_to_string(path.join("memory.events")).ok()?;
+        // `memory.peak` needs Linux 5.19 or later.
+        let peak_mb = fs::read_to_string(path.join("memory.peak"))
+            .ok()
+            .and_then(|peak| peak.trim().parse::<u64>().ok())
+            .filter(|&bytes| bytes > 0)
+            .map(|bytes| bytes.div_ceil(1024 * 1024));
+        Some(MemoryUsage {
+            peak_mb,
+            oom_killed: event_count(&events, "oom_kill") > 0,
+        })
+    }
+
+    /// Remove the job's cgroup once its processes are gone. One still busy
+    /// is pruned when the daemon next starts.
+    pub(crate) fn remove(&self, job_id: u32) {
+        fs::remove_dir(self.job_cgroup(job_id)).ok();
+    }
+}
+
+/// `command`, moving its shell into the cgroup at `path` first. If the shell
+/// may not join, e.g. a tmux server outside the daemon's hierarchy, the job
+/// runs unconfined.
+pub(crate) fn join_command(path: &Path, command: String) -> String {
+    let procs = path.join("cgroup.procs");
+    let procs = procs.display().to_string();
+    if procs.contains(['\'', '"', '$', '`', '\\']) {
+        return command;
+    }
+    format!(r#"sh -c '{{ echo $$ > "{procs}"; }} 2>/dev/null; exec "$@"' gflow-job {command}"#)
+}
+
+/// The daemon's cgroup path from /proc/self/cgroup: the `0::` line of the
+/// v2 hierarchy.
+fn own_cgroup(content: &str) -> Option<&str> {
+    content
+        .lines()
+        .find_map(|line| line.strip_prefix("0::"))
+        .map(str::trim)
+}
+
+fn has_controller(controllers: &str, name: &str) -> bool {
+    controllers.split_whitespace().any(|c| c == name)
+}
+
+/// A counter from a `key value` file such as `memory.events`.
+fn event_count(events: &str, key: &str) -> u64 {
+    events
+        .lines()
+        .find_map(|line| {
+            let (name, value) = line.split_once(' ')?;
+            if name == key {
+                value.trim().parse().ok()
+            } else {
+                None
+            }
+        })
+        .unwrap_or(0)
+}
+
+fn cpu_max(cpus: u32) -> String {
+    format!("{} {CPU_PERIOD_US}", u64::from(cpus) * CPU_PERIOD_US)
+}
+
+fn create_cgroup(path: &Path) -> io::Result<()> {
+    match fs::create_dir(path) {
+        Err(error) if error.kind() != io::ErrorKind::AlreadyExists => Err(error),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn own_cgroup_reads_the_v2_entry() {
+        let content = "12:cpuset:/\n0::/system.slice/gflowd.service\n";
+        assert_eq!(own_cgroup(content), Some("/system.slice/gflowd.service"));
+        assert_eq!(own_cgroup("1:name=systemd:/init.scope\n"), None);
+    }
+
+    #[test]
+    fn memory_events_count_oom_kills() {
+        let events = "low 0\nhigh 0\nmax 12\noom 1\noom_kill 1\noom_group_kill 0\n";
+        assert_eq!(event_count(events, "oom_kill"), 1);
+        assert_eq!(event_count(events, "oom"), 1);
+        assert_eq!(event_count("low 0\n", "oom_kill"), 0);
+    }
+
+    #[test]
+    fn cpu_max_allows_a_period_per_core() {
+        assert_eq!(cpu_max(1), "100000 100000");
+        assert_eq!(cpu_max(4), "400000 100000");
+    }
+
+    #[test]
+    fn join_command_moves_the_shell_before_running_the_job() {
+        let path = Path::new("/sys/fs/cgroup/gflow.service/job-7");
+        assert_eq!(
+            join_command(path, "taskset -c 0-1 bash -c \"train\"".to_string()),
+            r#"sh -c '{ echo $$ > "/sys/fs/cgroup/gflow.service/job-7/cgroup.procs"; } 2>/dev/null; exec "$@"' gflow-job taskset -c 0-1 bash -c "train""#
+        );
+        assert_eq!(
+            join_command(Path::new("/sys/fs/cgroup/it's"), "true".to_string()),
+            "true"
+        );
+    }
+}
@@ -31,6 +31,18 @@ pub struct GFlowd {
     #[arg(long, hide = true)]
     pub gpu_poll_interval_secs_internal: Option<u64>,
 
+    /// On SIGTERM/SIGINT, wait for running jobs (at most SECONDS, if given) before exiting
+    #[arg(long, value_name = "SECONDS", num_args = 0..=1, require_equals = true)]
+    pub wait_running: Option<Option<u64>>,
+
+    /// If another daemon uses the same data directory, ask it to shut down and replace it
+    #[arg(long)]
+    pub takeover: bool,
+
+    /// Started by 'gflowd reload': take over the data directory once the old instance exits
+    #[arg(long, hide = true, conflicts_with = "takeover")]
+    pub reload_internal: bool,
+
     #[command(flatten)]
     pub verbosity: Verbosity,
 }
@@ -48,6 +60,40 @@ pub struct DaemonOverrideArgs {
     /// Poll interval in seconds for GPU occupancy detection (default: 10)
     #[arg(long, value_name = "SECONDS")]
     pub gpu_poll_interval_secs: Option<u64>,
+
+    /// When stopped, wait for running jobs (at most SECONDS, if given) before exiting
+    #[arg(long, value_name = "SECONDS", num_args = 0..=1, require_equals = true)]
+    pub wait_running: Option<Option<u64>>,
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct AgentArgs {
+    /// URL of the head daemon to join (e.g., http://head:59000)
+    #[arg(long, value_name = "URL")]
+    pub join: String,
+
+    /// Node name (default: this machine's hostname)
+    #[arg(long, value_name = "NAME")]
+    pub name: Option<String>,
+
+    /// Address to accept jobs from the head daemon on
+    #[arg(long, value_name = "HOST:PORT", default_value = "0.0.0.0:59001")]
+    pub listen: String,
+
+    /// URL the head daemon reaches this agent at (default: http://<hostname>:<port>)
+    #[arg(long, value_name = "URL")]
+    pub advertise: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Default, Args)]
+pub struct ServiceScopeArgs {
+    /// Manage a per-user unit with `systemctl --user` (default)
+    #[arg(long, conflicts_with = "system")]
+    pub user: bool,
+
+    /// Manage a system-wide unit (requires root)
+    #[arg(long)]
+    pub system: bool,
 }
 
 #[derive(Debug, Parser)]
@@ -83,14 +129,92 @@ pub enum Commands {
     },
     /// Start the daemon in a tmux session
     Up(DaemonOverrideArgs),
-    /// Stop the daemon
-    Down,
+    /// Stop the daemon gracefully (saves state before exiting)
+    Down {
+        /// Seconds to wait for the daemon to exit
+        #[arg(long, value_name = "SECONDS", default_value_t = super::commands::down::DEFAULT_STOP_TIMEOUT_SECS)]
+        timeout: u64,
+    },
     /// Restart the daemon
     Restart(DaemonOverrideArgs),
     /// Reload the daemon with zero downtime
     Reload(DaemonOverrideArgs),
     /// Show the daemon status
     Status,
+    /// Install gflowd as a systemd service, then enable and start it
+    InstallService {
+        #[command(flatten)]
+        scope: ServiceScopeArgs,
+
+        #[command(flatten)]
+        daemon_overrides: DaemonOverrideArgs,
+    },
+    /// Stop, disable, and remove the gflowd systemd service
+    UninstallService {
+        #[command(flatten)]
+        scope: ServiceScopeArgs,
+    },
+    /// Show the state of the gflowd systemd service
+    ServiceStatus {
+        #[command(flatten)]
+        scope: ServiceScopeArgs,
+    },
+    /// Manage API tokens used when `daemon.require_auth` is enabled
+    Token {
+        #[command(subcommand)]
+        command: TokenCommands,
+    },
+    /// Create or list rotated backups of the job state
+    Backup {
+        #[command(subcommand)]
+        command: BackupCommands,
+    },
+    /// Replace the job state with a backup
+    Restore {
+        /// Backup name from `gflowd backup list`, or a path to a state file
+        backup: String,
+
+        /// Restore even if jobs were created after the backup was taken
+        #[arg(long)]
+        force: bool,
+    },
+    /// Package the job state and its metadata for moving gflowd to another host
+    Export {
+        /// Archive to write, e.g. `backup.tar.gz`
+        #[arg(short, long, value_name = "PATH")]
+        output: PathBuf,
+
+        /// Include job logs
+        #[arg(long)]
+        logs: bool,
+    },
+    /// Load an archive from `gflowd export` on this host (gflowd must be stopped)
+    Import {
+        /// Archive written by `gflowd export`
+        archive: PathBuf,
+
+        /// Rewrite job paths starting with OLD to start with NEW (repeatable); asked for when omitted on a terminal
+        #[arg(long = "map-path", value_name = "OLD=NEW")]
+        map_paths: Vec<String>,
+
+        /// Keep jobs that were running as Running instead of failing them
+        #[arg(long)]
+        assume_running: bool,
+
+        /// Replace the jobs already on this host
+        #[arg(long)]
+        force: bool,
+    },
+    /// Inspect or recover from the state journal written in recovery mode
+    #[command(
+        after_help = "Exit status: 0 when done or recovered, 3 when there is nothing to do, 4 when nothing can be recovered."
+    )]
+    Journal {
+        #[command(subcommand)]
+        command: JournalCommands,
+    },
+    /// Run this machine as a node of another gflowd, which forwards jobs to it
+    Agent(AgentArgs),
     /// Generate shell completion scripts
     Completion {
         /// The shell to generate completions for
@@ -98,3 +222,45 @@ pub enum Commands {
         shell: Shell,
     },
 }
+
+#[derive(Debug, Parser)]
+pub enum TokenCommands {
+    /// Create a token for a user and print it (it is not shown again)
+    Create {
+        /// Username the token authenticates as
+        username: String,
+
+        /// Allow the token to change other users' jobs and daemon settings
+        #[arg(long)]
+        admin: bool,
+    },
+    /// List tokens (without their secrets)
+    List,
+    /// Revoke a token by ID
+    Revoke {
+        /// Token ID as shown by `gflowd token list`
+        id: String,
+    },
+}
+
+#[derive(Debug, Parser)]
+pub enum BackupCommands {
+    /// Back up the current state (through the daemon when it is running)
+    Now,
+    /// List backups, newest first
+    List,
+}
+
+#[derive(Debug, Parser)]
+pub enum JournalCommands {
+    /// List journal entries, check that each one parses, and show what gflowd would load
+    Inspect,
+    /// Write a state file from the newest usable journal snapshot (gflowd must be stopped)
+    Replay {
+        /// State file to write; `.msgpack` or `.json` picks the format
+        #[arg(long, value_name = "PATH")]
+        into: PathBuf,
+    },
+    /// Save the journal snapshot as the state file, then truncate the journal (gflowd must be stopped)
+    Compact,
+}
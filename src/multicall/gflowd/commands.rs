@@ -3,10 +3,15 @@ use anyhow::{anyhow, Context, Result};
 use clap::CommandFactory;
 use clap_verbosity_flag::{Verbosity, VerbosityFilter};
 
+pub mod backup;
 pub mod down;
 pub mod init;
+pub mod journal;
+pub mod migrate;
 pub mod reload;
+pub mod service;
 pub mod status;
+pub mod token;
 pub mod up;
 
 pub static TMUX_SESSION_NAME: &str = "gflow_server";
@@ -16,6 +21,7 @@ pub struct DaemonStartOptions<'a> {
     pub gpus: Option<&'a str>,
     pub gpu_allocation_strategy: Option<&'a str>,
     pub gpu_poll_interval_secs: Option<u64>,
+    pub wait_running: Option<Option<u64>>,
     pub verbosity: Verbosity,
 }
 
@@ -25,6 +31,7 @@ impl<'a> DaemonStartOptions<'a> {
             gpus: overrides.gpus.as_deref(),
             gpu_allocation_strategy: overrides.gpu_allocation_strategy.as_deref(),
             gpu_poll_interval_secs: overrides.gpu_poll_interval_secs,
+            wait_running: overrides.wait_running,
             verbosity,
         }
     }
@@ -44,6 +51,22 @@ pub fn validate_daemon_startup_config(
             "Invalid daemon.gpu_poll_interval_secs '0'. Use a value of at least 1 second."
         ));
     }
+    if config.daemon.state_save_interval_ms == 0 {
+        return Err(anyhow!(
+            "Invalid daemon.state_save_interval_ms '0'. Use a value of at least 1 millisecond."
+        ));
+    }
+    if config.daemon.state_backup_interval_secs == 0 {
+        return Err(anyhow!(
+            "Invalid daemon.state_backup_interval_secs '0'. Use a value of at least 1 second."
+        ));
+    }
+    if config.scheduler.tick_secs == 0 {
+        return Err(anyhow!(
+            "Invalid scheduler.tick_secs '0'. Use a value of at least 1 second."
+        ));
+    }
+    super::logging::check(&config.daemon.logging)?;
 
     Ok(())
 }
@@ -92,10 +115,50 @@ pub fn daemon_start_command(options: &DaemonStartOptions<'_>) -> Result<String>
             gpu_poll_interval_secs
         ));
     }
+    match options.wait_running {
+        Some(Some(secs)) => command.push_str(&format!(" --wait-running={secs}")),
+        Some(None) => command.push_str(" --wait-running"),
+        None => {}
+    }
+
+    Ok(command)
+}
 
+/// [`daemon_start_command`] with `--config` for the config file picked by
+/// `--config` or `GFLOW_CONFIG`, which a daemon started in tmux would not
+/// otherwise see.
+pub fn daemon_start_command_with_config(
+    config_path: &Option<std::path::PathBuf>,
+    options: &DaemonStartOptions<'_>,
+) -> Result<String> {
+    let mut command = daemon_start_command(options)?;
+    if let Some(path) = daemon_config_path(config_path)? {
+        let escaped = shell_escape::escape(path.to_string_lossy());
+        command.push_str(&format!(" --config {escaped}"));
+    }
     Ok(command)
 }
 
+/// The absolute path of the config file picked by `--config` or
+/// `GFLOW_CONFIG`, which must exist; `None` for the default location.
+pub fn daemon_config_path(
+    config_path: &Option<std::path::PathBuf>,
+) -> Result<Option<std::path::PathBuf>> {
+    let (path, source) = gflow::config::config_file_path(config_path.as_ref())?;
+    if source == gflow::config::ConfigSource::Default {
+        return Ok(None);
+    }
+    if !path.exists() {
+        return Err(anyhow!(
+            "Config file not found: {} (from {source})",
+            path.display()
+        ));
+    }
+    std::path::absolute(&path)
+        .map(Some)
+        .with_context(|| format!("Failed to resolve {}", path.display()))
+}
+
 fn daemon_verbosity_flag(verbosity: Verbosity) -> Option<&'static str> {
     match verbosity.filter() {
         VerbosityFilter::Off => Some("-q"),
@@ -141,11 +204,11 @@ pub async fn handle_commands(
         Commands::Up(daemon_overrides) => {
             up::handle_up(config_path, daemon_overrides, verbosity).await?;
         }
-        Commands::Down => {
-            down::handle_down().await?;
+        Commands::Down { timeout } => {
+            down::handle_down(config_path, timeout).await?;
         }
         Commands::Restart(daemon_overrides) => {
-            down::handle_down().await?;
+            down::handle_down(config_path, down::DEFAULT_STOP_TIMEOUT_SECS).await?;
             up::handle_up(config_path, daemon_overrides, verbosity).await?;
         }
         Commands::Reload(daemon_overrides) => {
@@ -154,6 +217,45 @@ pub async fn handle_commands(
         Commands::Status => {
             status::handle_status(config_path).await?;
         }
+        Commands::InstallService {
+            scope,
+            daemon_overrides,
+        } => {
+            service::handle_install_service(config_path, scope, daemon_overrides, verbosity)?;
+        }
+        Commands::UninstallService { scope } => {
+            service::handle_uninstall_service(scope)?;
+        }
+        Commands::ServiceStatus { scope } => {
+            service::handle_service_status(scope)?;
+        }
+        Commands::Token { command } => {
+            token::handle_token(command)?;
+        }
+        Commands::Backup { command } => {
+            backup::handle_backup(config_path, command).await?;
+        }
+        Commands::Restore { backup, force } => {
+            backup::handle_restore(config_path, &backup, force).await?;
+        }
+        Commands::Export { output, logs } => {
+            migrate::handle_export(config_path, &output, logs).await?;
+        }
+        Commands::Import {
+            archive,
+            map_paths,
+            assume_running,
+            force,
+        } => {
+            migrate::handle_import(config_path, &archive, &map_paths, assume_running, force)?;
+        }
+        Commands::Journal { command } => {
+            journal::handle_journal(command)?;
+        }
+        Commands::Agent(args) => {
+            let config = gflow::config::load_config(config_path.as_ref())?;
+            super::agent::run(config, args).await?;
+        }
         Commands::Completion { shell } => {
             crate::multicall::completion::handle_completion(
                 shell,
@@ -176,6 +278,7 @@ mod tests {
             gpus: None,
             gpu_allocation_strategy: None,
             gpu_poll_interval_secs: None,
+            wait_running: None,
             verbosity: Verbosity::new(0, 0),
         })
         .unwrap();
@@ -188,6 +291,7 @@ mod tests {
             gpus: None,
             gpu_allocation_strategy: None,
             gpu_poll_interval_secs: None,
+            wait_running: None,
             verbosity: Verbosity::new(1, 0),
         })
         .unwrap();
@@ -198,6 +302,7 @@ mod tests {
             gpus: None,
             gpu_allocation_strategy: None,
             gpu_poll_interval_secs: None,
+            wait_running: None,
             verbosity: Verbosity::new(0, 1),
         })
         .unwrap();
@@ -207,6 +312,7 @@ mod tests {
             gpus: None,
             gpu_allocation_strategy: None,
             gpu_poll_interval_secs: None,
+            wait_running: None,
             verbosity: Verbosity::new(9, 0),
         })
         .unwrap();
@@ -219,18 +325,37 @@ mod tests {
             gpus: None,
             gpu_allocation_strategy: None,
             gpu_poll_interval_secs: Some(3),
+            wait_running: None,
             verbosity: Verbosity::new(0, 0),
         })
         .unwrap();
         assert!(command.contains("--gpu-poll-interval-secs-internal 3"));
     }
 
+    #[test]
+    fn daemon_start_command_passes_wait_running() {
+        let options = |wait_running| DaemonStartOptions {
+            gpus: None,
+            gpu_allocation_strategy: None,
+            gpu_poll_interval_secs: None,
+            wait_running,
+            verbosity: Verbosity::new(0, 0),
+        };
+        let command = daemon_start_command(&options(Some(Some(600)))).unwrap();
+        assert!(command.ends_with(" --wait-running=600"));
+        let command = daemon_start_command(&options(Some(None))).unwrap();
+        assert!(command.ends_with(" --wait-running"));
+        let command = daemon_start_command(&options(None)).unwrap();
+        assert!(!command.contains("--wait-running"));
+    }
+
     #[test]
     fn daemon_start_command_rejects_zero_gpu_poll_interval() {
         let error = daemon_start_command(&DaemonStartOptions {
             gpus: None,
             gpu_allocation_strategy: None,
             gpu_poll_interval_secs: Some(0),
+            wait_running: None,
             verbosity: Verbosity::new(0, 0),
         })
         .unwrap_err();
@@ -258,6 +383,7 @@ gpu_poll_interval_secs = 0
                 gpus: None,
                 gpu_allocation_strategy: None,
                 gpu_poll_interval_secs: None,
+                wait_running: None,
                 verbosity: Verbosity::new(0, 0),
             },
         )
@@ -0,0 +1,121 @@
+use anyhow::{anyhow, bail, Context, Result};
+use std::path::PathBuf;
+
+use super::super::backups;
+use super::super::cli::BackupCommands;
+use super::super::pidfile::running_daemon_pid;
+use super::super::scheduler_runtime::job_ids;
+use super::super::scheduler_runtime::journal::{self, JOURNAL_FILE};
+use super::super::scheduler_runtime::serialization::{self, SerializationFormat};
+
+pub async fn handle_backup(config_path: &Option<PathBuf>, command: BackupCommands) -> Result<()> {
+    let state_dir = gflow::paths::get_data_dir()?;
+    match command {
+        BackupCommands::Now => {
+            let config = gflow::config::load_config(config_path.as_ref())?;
+            let path = if running_daemon_pid().is_some() {
+                let client = gflow::Client::build(&config).context("Failed to build client")?;
+                client.backup_state().await?
+            } else {
+                let Some(scheduler) = serialization::load_state_auto(&state_dir)? else {
+                    bail!("No state file in {} to back up", state_dir.display());
+                };
+                backups::create(&scheduler, &state_dir, config.daemon.state_backups)?
+            };
+            println!("Backed up state to {}", path.display());
+        }
+        BackupCommands::List => {
+            let backups = backups::list(&state_dir)?;
+            if backups.is_empty() {
+                println!(
+                    "No backups in {}.",
+                    backups::backup_dir(&state_dir).display()
+                );
+                return Ok(());
+            }
+            println!("{:<40} {:>10}", "NAME", "SIZE");
+            for backup in backups {
+                println!(
+                    "{:<40} {:>10}",
+                    backup.name(),
+                    format!("{:.1} KiB", backup.size as f64 / 1024.0)
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+pub async fn handle_restore(
+    config_path: &Option<PathBuf>,
+    backup: &str,
+    force: bool,
+) -> Result<()> {
+    let state_dir = gflow::paths::get_data_dir()?;
+    let path = backups::resolve(&state_dir, backup);
+    // The daemon resolves paths from its own working directory.
+    let path = path
+        .canonicalize()
+        .with_context(|| format!("Backup {} not found", path.display()))?;
+    let config = gflow::config::load_config(config_path.as_ref())?;
+
+    if running_daemon_pid().is_some() {
+        let client = gflow::Client::build(&config).context("Failed to build client")?;
+        let report = client.restore_state(&path, force).await?;
+        println!(
+            "Restored {} jobs from {}; the next job ID is {}.",
+            report.jobs, report.restored_from, report.next_job_id
+        );
+        println!(
+            "The replaced state was backed up to {}.",
+            report.replaced_state_backup
+        );
+        return Ok(());
+    }
+
+    let restored = backups::load(&path)?;
+    let job_ids_path = job_ids::path(&state_dir);
+    let replaced = match serialization::load_state_auto(&state_dir) {
+        Ok(Some(current)) => {
+            // After a crash the high-water mark may be ahead of the state file.
+            let recorded = job_ids::load(&job_ids_path).ok().flatten().unwrap_or(0);
+            backups::check_job_ids(current.next_job_id().max(recorded), &restored, force)
+                .map_err(|message| anyhow!(message))?;
+            Some(backups::create(
+                &current,
+                &state_dir,
+                config.daemon.state_backups,
+            )?)
+        }
+        Ok(None) => None,
+        Err(e) if force => {
+            gflow::utils::output::print_warning(format_args!("replacing a state file that cannot be loaded: {e:#}"));
+            None
+        }
+        Err(e) => bail!(
+            "The current state file cannot be loaded, so its job IDs cannot be checked: {e:#}. Use --force to restore anyway."
+        ),
+    };
+
+    serialization::save_state(&restored, &state_dir, SerializationFormat::MessagePack)?;
+    job_ids::store(&job_ids_path, restored.next_job_id())
+        .with_context(|| format!("Failed to update {}", job_ids_path.display()))?;
+    // A newer journal would otherwise win over the restored state at startup.
+    let journal_path = state_dir.join(JOURNAL_FILE);
+    journal::truncate(&journal_path)
+        .with_context(|| format!("Failed to truncate {}", journal_path.display()))?;
+
+    println!(
+        "Restored {} jobs from {}; the next job ID is {}.",
+        restored.job_specs().len(),
+        path.display(),
+        restored.next_job_id()
+    );
+    if let Some(replaced) = replaced {
+        println!(
+            "The replaced state was backed up to {}.",
+            replaced.display()
+        );
+    }
+    Ok(())
+}
@@ -1,13 +1,66 @@
-use anyhow::Result;
+use anyhow::{bail, Result};
+use std::time::Duration;
 use tmux_interface::{KillSession, Tmux};
 
-pub async fn handle_down() -> Result<()> {
-    if let Err(e) =
-        Tmux::with_command(KillSession::new().target_session(super::TMUX_SESSION_NAME)).output()
-    {
-        eprintln!("Failed to stop gflowd: {e}");
-    } else {
-        println!("gflowd stopped.");
+use super::super::pidfile::{is_process_running, running_daemon_pid};
+
+/// Default time `gflowd down` waits for the daemon to save state and exit.
+pub const DEFAULT_STOP_TIMEOUT_SECS: u64 = 10;
+
+pub async fn handle_down(
+    config_path: &Option<std::path::PathBuf>,
+    timeout_secs: u64,
+) -> Result<()> {
+    let pid = match running_daemon_pid() {
+        Some(pid) => Some(pid),
+        // Daemons started before PID files existed can still be found via /health.
+        None => match gflow::create_client_or_default(config_path) {
+            Ok(client) => client.get_health_with_pid().await.ok().flatten(),
+            Err(_) => None,
+        },
+    };
+
+    if let Some(pid) = pid {
+        // SIGTERM triggers a graceful shutdown: stop accepting requests, save state, exit.
+        unsafe {
+            libc::kill(pid as libc::pid_t, libc::SIGTERM);
+        }
+        if !wait_for_exit(pid, Duration::from_secs(timeout_secs)).await {
+            bail!(
+                "gflowd (PID {pid}) did not stop within {timeout_secs}s; it may still be saving state. \
+                 Check again with `gflowd status`, or force it with `kill -9 {pid}`."
+            );
+        }
+    }
+
+    // Clean up the tmux session that hosted the daemon (no-op for systemd).
+    let session_exists = gflow::tmux::is_session_exist(super::TMUX_SESSION_NAME);
+    if session_exists {
+        if let Err(e) =
+            Tmux::with_command(KillSession::new().target_session(super::TMUX_SESSION_NAME)).output()
+        {
+            eprintln!(
+                "Failed to remove tmux session '{}': {e}",
+                super::TMUX_SESSION_NAME
+            );
+        }
+    }
+
+    match pid {
+        Some(pid) => println!("gflowd stopped (PID {pid})."),
+        None if session_exists => println!("gflowd stopped."),
+        None => println!("gflowd is not running."),
     }
     Ok(())
 }
+
+async fn wait_for_exit(pid: u32, timeout: Duration) -> bool {
+    let deadline = tokio::time::Instant::now() + timeout;
+    while is_process_running(pid) {
+        if tokio::time::Instant::now() >= deadline {
+            return false;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    true
+}
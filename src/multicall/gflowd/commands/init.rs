@@ -40,11 +40,12 @@ pub async fn handle_init(config_path: &Option<PathBuf>, args: InitArgs) -> anyho
     let detected_timezone = gflow::utils::timezone::get_local_timezone().to_string();
 
     let detected_gpus = detect_gpus().unwrap_or_else(|e| {
-        eprintln!("Warning: GPU detection failed: {e}");
+        gflow::utils::output::print_warning(format_args!("GPU detection failed: {e}"));
         Vec::new()
     });
 
     let mut cfg = gflow::config::Config::default();
+    cfg.gqueue.default_user_filter = Some(true);
     if let Some(host) = args.host.clone() {
         cfg.daemon.host = host;
     }
@@ -368,11 +369,11 @@ fn parse_and_validate_gpu_indices(
     let max = detected.len() as u32;
     let (valid, invalid): (Vec<_>, Vec<_>) = indices.into_iter().partition(|&i| i < max);
     if !invalid.is_empty() {
-        eprintln!(
-            "Warning: Ignoring invalid GPU indices {:?} (only {} GPU(s) detected).",
+        gflow::utils::output::print_warning(format_args!(
+            "Ignoring invalid GPU indices {:?} (only {} GPU(s) detected).",
             invalid,
             detected.len()
-        );
+        ));
     }
     if valid.is_empty() {
         anyhow::bail!(
@@ -0,0 +1,309 @@
+use anyhow::{bail, Context, Result};
+use gflow::core::scheduler::Scheduler;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use super::super::cli::JournalCommands;
+use super::super::pidfile::running_daemon_pid;
+use super::super::scheduler_runtime::journal::{self, JOURNAL_FILE};
+use super::super::scheduler_runtime::serialization::{self, SerializationFormat};
+
+/// Exit status when the journal holds nothing to replay or compact.
+pub const EXIT_NOTHING_TO_DO: i32 = 3;
+/// Exit status when neither the journal nor the state file can be loaded.
+pub const EXIT_UNRECOVERABLE: i32 = 4;
+
+#[derive(Debug, PartialEq, Eq)]
+enum Outcome {
+    Done,
+    NothingToDo,
+    Unrecoverable,
+}
+
+pub fn handle_journal(command: JournalCommands) -> Result<()> {
+    let state_dir = gflow::paths::get_data_dir()?;
+    let outcome = match command {
+        JournalCommands::Inspect => inspect(&state_dir)?,
+        JournalCommands::Replay { into } => {
+            ensure_daemon_stopped()?;
+            replay(&state_dir, &into)?
+        }
+        JournalCommands::Compact => {
+            ensure_daemon_stopped()?;
+            compact(&state_dir)?
+        }
+    };
+    match outcome {
+        Outcome::Done => Ok(()),
+        Outcome::NothingToDo => std::process::exit(EXIT_NOTHING_TO_DO),
+        Outcome::Unrecoverable => std::process::exit(EXIT_UNRECOVERABLE),
+    }
+}
+
+/// The daemon rewrites both files on its own schedule.
+pub(super) fn ensure_daemon_stopped() -> Result<()> {
+    if let Some(pid) = running_daemon_pid() {
+        bail!("gflowd is running (PID {pid}) and owns the state files. Stop it with `gflowd down` first.");
+    }
+    Ok(())
+}
+
+enum StateFile {
+    Missing,
+    Loaded(Box<Scheduler>),
+    Broken(String),
+}
+
+/// `state.msgpack`, or the legacy `state.json` when only that exists.
+fn state_file_path(state_dir: &Path) -> PathBuf {
+    let msgpack = state_dir.join("state.msgpack");
+    if msgpack.exists() {
+        return msgpack;
+    }
+    let json = state_dir.join("state.json");
+    if json.exists() {
+        json
+    } else {
+        msgpack
+    }
+}
+
+fn load_state_file(state_dir: &Path) -> StateFile {
+    match serialization::load_state_auto(state_dir) {
+        Ok(Some(scheduler)) => StateFile::Loaded(Box::new(scheduler)),
+        Ok(None) => StateFile::Missing,
+        Err(e) => StateFile::Broken(format!("{e:#}")),
+    }
+}
+
+/// The journal snapshot to recover from: the one gflowd would load at
+/// startup, or, when the state file cannot be loaded, any good snapshot.
+fn recovery_snapshot(state_dir: &Path, state: &StateFile) -> Option<(Scheduler, u64)> {
+    let journal_path = state_dir.join(JOURNAL_FILE);
+    let newer = journal::should_apply(&state_dir.join("state.json"), &journal_path);
+    if newer || !matches!(state, StateFile::Loaded(_)) {
+        journal::load_last_snapshot(&journal_path)
+    } else {
+        None
+    }
+}
+
+fn describe(scheduler: &Scheduler) -> String {
+    format!(
+        "{} jobs, next job ID {}",
+        scheduler.job_specs().len(),
+        scheduler.next_job_id()
+    )
+}
+
+fn format_ts(ts: u64) -> String {
+    gflow::utils::format_system_time(SystemTime::UNIX_EPOCH + Duration::from_secs(ts))
+}
+
+fn inspect(state_dir: &Path) -> Result<Outcome> {
+    let journal_path = state_dir.join(JOURNAL_FILE);
+    let entries = journal::read_entries(&journal_path)
+        .with_context(|| format!("Failed to read {}", journal_path.display()))?;
+
+    println!("Journal: {}", journal_path.display());
+    if entries.is_empty() {
+        println!("  (empty)");
+    }
+    for (line, entry) in &entries {
+        match entry {
+            Ok(entry) => println!(
+                "  line {line}: {} at {}, {} [ok]",
+                entry.kind,
+                format_ts(entry.ts),
+                describe(&entry.scheduler)
+            ),
+            Err(e) => println!("  line {line}: [invalid] {e}"),
+        }
+    }
+
+    let state_path = state_file_path(state_dir);
+    let state = load_state_file(state_dir);
+    match &state {
+        StateFile::Missing => println!("State file: {} (missing)", state_path.display()),
+        StateFile::Loaded(scheduler) => println!(
+            "State file: {} ({} [ok])",
+            state_path.display(),
+            describe(scheduler)
+        ),
+        StateFile::Broken(e) => println!("State file: {} [invalid] {e}", state_path.display()),
+    }
+
+    let newer = journal::should_apply(&state_dir.join("state.json"), &journal_path);
+    let outcome = match (
+        newer
+            .then(|| journal::load_last_snapshot(&journal_path))
+            .flatten(),
+        &state,
+    ) {
+        (Some((_, ts)), _) => {
+            println!("gflowd loads: the journal snapshot from {}", format_ts(ts));
+            Outcome::Done
+        }
+        (None, StateFile::Loaded(_)) => {
+            println!("gflowd loads: the state file");
+            Outcome::Done
+        }
+        (None, StateFile::Missing) if entries.is_empty() => {
+            println!("gflowd loads: nothing; it starts with an empty queue");
+            Outcome::NothingToDo
+        }
+        (None, _) => {
+            println!("gflowd loads: nothing usable; it starts in recovery mode");
+            Outcome::Unrecoverable
+        }
+    };
+    if outcome == Outcome::Done && entries.is_empty() {
+        return Ok(Outcome::NothingToDo);
+    }
+    Ok(outcome)
+}
+
+fn replay(state_dir: &Path, into: &Path) -> Result<Outcome> {
+    let Some(format) = SerializationFormat::from_path(into) else {
+        bail!(
+            "Cannot tell the format of {}: use a .msgpack or .json file name",
+            into.display()
+        );
+    };
+
+    let state = load_state_file(state_dir);
+    if let Some((scheduler, ts)) = recovery_snapshot(state_dir, &state) {
+        serialization::write_state_file(&scheduler, into, format)?;
+        println!(
+            "Recovered {} from the journal snapshot of {} into {}",
+            describe(&scheduler),
+            format_ts(ts),
+            into.display()
+        );
+        return Ok(Outcome::Done);
+    }
+
+    Ok(match state {
+        StateFile::Loaded(_) => {
+            println!("The journal has nothing newer than the state file; nothing to replay.");
+            Outcome::NothingToDo
+        }
+        StateFile::Missing if journal::read_entries(&state_dir.join(JOURNAL_FILE))?.is_empty() => {
+            println!("There is no journal or state file; nothing to replay.");
+            Outcome::NothingToDo
+        }
+        StateFile::Missing => {
+            eprintln!("The journal has no usable snapshot and there is no state file.");
+            Outcome::Unrecoverable
+        }
+        StateFile::Broken(e) => {
+            eprintln!(
+                "The journal has no usable snapshot and the state file cannot be loaded: {e}"
+            );
+            Outcome::Unrecoverable
+        }
+    })
+}
+
+fn compact(state_dir: &Path) -> Result<Outcome> {
+    let journal_path = state_dir.join(JOURNAL_FILE);
+    if journal::read_entries(&journal_path)
+        .with_context(|| format!("Failed to read {}", journal_path.display()))?
+        .is_empty()
+    {
+        println!("The journal is empty; nothing to compact.");
+        return Ok(Outcome::NothingToDo);
+    }
+
+    let state = load_state_file(state_dir);
+    if let Some((scheduler, ts)) = recovery_snapshot(state_dir, &state) {
+        serialization::save_state(&scheduler, state_dir, SerializationFormat::MessagePack)?;
+        println!(
+            "Saved the journal snapshot of {} ({}) to {}",
+            format_ts(ts),
+            describe(&scheduler),
+            state_dir.join("state.msgpack").display()
+        );
+    } else if let StateFile::Loaded(_) = state {
+        println!("The state file is newer than the journal.");
+    } else {
+        eprintln!("Neither the journal nor the state file can be loaded; leaving both in place.");
+        return Ok(Outcome::Unrecoverable);
+    }
+
+    journal::truncate(&journal_path)
+        .with_context(|| format!("Failed to truncate {}", journal_path.display()))?;
+    println!("Truncated {}", journal_path.display());
+    Ok(Outcome::Done)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gflow::core::job::JobBuilder;
+    use gflow::core::scheduler::SchedulerBuilder;
+
+    fn scheduler_with_jobs(jobs: usize) -> Scheduler {
+        let mut scheduler = SchedulerBuilder::new().build();
+        for _ in 0..jobs {
+            scheduler.submit_job(JobBuilder::new().command("true").build());
+        }
+        scheduler
+    }
+
+    fn write_journal(state_dir: &Path, scheduler: &Scheduler) {
+        let line = serde_json::json!({ "ts": 1, "kind": "snapshot", "scheduler": scheduler });
+        std::fs::write(state_dir.join(JOURNAL_FILE), format!("{line}\n")).unwrap();
+    }
+
+    #[test]
+    fn compact_saves_the_snapshot_over_a_corrupt_state_file() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("state.msgpack"), b"\xc1garbage").unwrap();
+        write_journal(dir.path(), &scheduler_with_jobs(3));
+
+        assert_eq!(compact(dir.path()).unwrap(), Outcome::Done);
+        let StateFile::Loaded(scheduler) = load_state_file(dir.path()) else {
+            panic!("state file should load after compacting");
+        };
+        assert_eq!(scheduler.job_specs().len(), 3);
+        assert_eq!(scheduler.next_job_id(), 4);
+        assert_eq!(
+            std::fs::metadata(dir.path().join(JOURNAL_FILE))
+                .unwrap()
+                .len(),
+            0
+        );
+
+        assert_eq!(compact(dir.path()).unwrap(), Outcome::NothingToDo);
+    }
+
+    #[test]
+    fn replay_writes_the_requested_format() {
+        let dir = tempfile::tempdir().unwrap();
+        write_journal(dir.path(), &scheduler_with_jobs(2));
+        let into = dir.path().join("recovered.json");
+
+        assert_eq!(replay(dir.path(), &into).unwrap(), Outcome::Done);
+        let bytes = std::fs::read(&into).unwrap();
+        let scheduler = serialization::deserialize(&bytes, SerializationFormat::Json).unwrap();
+        assert_eq!(scheduler.job_specs().len(), 2);
+        // The journal is left alone.
+        assert!(journal::load_last_snapshot(&dir.path().join(JOURNAL_FILE)).is_some());
+
+        assert!(replay(dir.path(), &dir.path().join("recovered.txt")).is_err());
+    }
+
+    #[test]
+    fn replay_reports_unrecoverable_and_nothing_to_do() {
+        let dir = tempfile::tempdir().unwrap();
+        let into = dir.path().join("out.msgpack");
+        assert_eq!(replay(dir.path(), &into).unwrap(), Outcome::NothingToDo);
+
+        std::fs::write(dir.path().join("state.msgpack"), b"\xc1garbage").unwrap();
+        std::fs::write(dir.path().join(JOURNAL_FILE), "{\"ts\": 1, \"kind\"\n").unwrap();
+        assert_eq!(replay(dir.path(), &into).unwrap(), Outcome::Unrecoverable);
+        assert_eq!(compact(dir.path()).unwrap(), Outcome::Unrecoverable);
+        assert!(!into.exists());
+    }
+}
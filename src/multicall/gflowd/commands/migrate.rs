@@ -0,0 +1,139 @@
+use anyhow::{bail, Context, Result};
+use dialoguer::{theme::ColorfulTheme, Input};
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
+
+use super::super::backups;
+use super::super::migration::{self, PathMap};
+use super::super::pidfile::running_daemon_pid;
+use super::super::scheduler_runtime::serialization;
+use super::journal::ensure_daemon_stopped;
+
+pub async fn handle_export(
+    config_path: &Option<PathBuf>,
+    output: &Path,
+    include_logs: bool,
+) -> Result<()> {
+    let state_dir = gflow::paths::get_data_dir()?;
+    let scheduler = if running_daemon_pid().is_some() {
+        // The daemon writes a snapshot of its state under its own lock.
+        let config = gflow::config::load_config(config_path.as_ref())?;
+        let client = gflow::Client::build(&config).context("Failed to build client")?;
+        let snapshot = client.backup_state().await?;
+        gflow::utils::output::print_warning(
+            "gflowd is running; jobs that change from now on are not in the archive. Stop it with `gflowd down` before the final export.",
+        );
+        backups::load(&snapshot)?
+    } else {
+        migration::load_current(&state_dir)?
+    };
+
+    let manifest = migration::export(&scheduler, &state_dir, output, include_logs)?;
+    println!(
+        "Exported {} jobs{} to {}.",
+        manifest.jobs,
+        if include_logs { " with their logs" } else { "" },
+        output.display()
+    );
+    Ok(())
+}
+
+pub fn handle_import(
+    config_path: &Option<PathBuf>,
+    archive: &Path,
+    map_paths: &[String],
+    assume_running: bool,
+    force: bool,
+) -> Result<()> {
+    ensure_daemon_stopped()?;
+    let state_dir = gflow::paths::get_data_dir()?;
+    let mut path_map = PathMap::parse(map_paths)?;
+
+    let replaced = match serialization::load_state_auto(&state_dir) {
+        Ok(Some(current)) if current.jobs_len() > 0 => {
+            if !force {
+                bail!(
+                    "This host already has {} jobs, which the import would replace. Use --force to import anyway; they are backed up first.",
+                    current.jobs_len()
+                );
+            }
+            let config = gflow::config::load_config(config_path.as_ref())?;
+            Some(backups::create(
+                &current,
+                &state_dir,
+                config.daemon.state_backups,
+            )?)
+        }
+        Ok(_) => None,
+        Err(e) if force => {
+            gflow::utils::output::print_warning(format_args!(
+                "replacing a state file that cannot be loaded: {e:#}"
+            ));
+            None
+        }
+        Err(e) => {
+            bail!("The current state file cannot be loaded: {e:#}. Use --force to import anyway.")
+        }
+    };
+
+    let unpacked = migration::unpack(archive, &state_dir)?;
+    let manifest = &unpacked.manifest;
+    println!(
+        "Archive from {} (gflow {}, exported {}): {} jobs{}.",
+        manifest.hostname,
+        manifest.gflow_version,
+        manifest.exported_at,
+        manifest.jobs,
+        if manifest.logs { " with logs" } else { "" }
+    );
+    if path_map.is_empty() && std::io::stdin().is_terminal() && std::io::stdout().is_terminal() {
+        ask_path_map(&unpacked.scheduler, &mut path_map)?;
+    }
+
+    let report = migration::install(unpacked, &state_dir, &path_map, assume_running)?;
+    println!(
+        "Imported {} jobs ({} with rewritten paths, {} logs); the next job ID is {}.",
+        report.jobs, report.remapped, report.logs, report.next_job_id
+    );
+    if !report.lost.is_empty() {
+        let ids: Vec<String> = report.lost.iter().map(u32::to_string).collect();
+        println!(
+            "Jobs {} were running and are now Failed ({}).",
+            ids.join(", "),
+            migration::LOST_DURING_MIGRATION
+        );
+    }
+    if let Some(replaced) = replaced {
+        println!(
+            "The replaced state was backed up to {}.",
+            replaced.display()
+        );
+    }
+    Ok(())
+}
+
+/// Ask where each directory jobs use lives on this host.
+fn ask_path_map(
+    scheduler: &gflow::core::scheduler::Scheduler,
+    path_map: &mut PathMap,
+) -> Result<()> {
+    let roots = migration::path_roots(scheduler);
+    if roots.is_empty() {
+        return Ok(());
+    }
+    println!("Where do these directories live on this host? Press Enter to keep a path.");
+    let theme = ColorfulTheme::default();
+    for (root, jobs) in roots {
+        let old = root.display().to_string();
+        let new: String = Input::with_theme(&theme)
+            .with_prompt(format!("{old} ({jobs} jobs)"))
+            .default(old.clone())
+            .interact_text()?;
+        let new = PathBuf::from(new.trim());
+        if !new.is_absolute() {
+            bail!("'{}' is not an absolute path", new.display());
+        }
+        path_map.insert(root, new);
+    }
+    Ok(())
+}
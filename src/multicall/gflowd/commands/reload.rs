@@ -6,6 +6,8 @@ use std::process::Command;
 use std::time::Duration;
 use tmux_interface::{ListPanes, RenameSession, Tmux};
 
+use super::super::pidfile::is_process_running;
+
 pub async fn handle_reload(
     config_path: &Option<std::path::PathBuf>,
     daemon_overrides: super::super::cli::DaemonOverrideArgs,
@@ -35,7 +37,10 @@ pub async fn handle_reload(
         .unwrap()
         .as_micros();
     let new_session_name = format!("gflow_server_new_{}", timestamp);
-    let command = super::daemon_start_command(&start_options)?;
+    let command = format!(
+        "{} --reload-internal",
+        super::daemon_start_command_with_config(config_path, &start_options)?
+    );
     let session = TmuxSession::create(new_session_name.clone())?;
 
     session.try_send_command(&command)?;
@@ -288,10 +293,6 @@ fn is_process_descendant_of(pid: u32, ancestor_pid: u32) -> bool {
     false
 }
 
-fn is_process_running(pid: u32) -> bool {
-    unsafe { libc::kill(pid as libc::pid_t, 0) == 0 }
-}
-
 async fn wait_for_new_daemon_pid(
     session_name: &str,
     old_pid: u32,
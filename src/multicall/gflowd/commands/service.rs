@@ -0,0 +1,293 @@
+use anyhow::{anyhow, bail, Context, Result};
+use clap_verbosity_flag::Verbosity;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use super::super::cli::{DaemonOverrideArgs, ServiceScopeArgs};
+
+const UNIT_NAME: &str = "gflowd.service";
+
+/// Unit template; `{placeholders}` are filled in by `render_unit`.
+const UNIT_TEMPLATE: &str = "\
+[Unit]
+Description=gflow job scheduler daemon
+Documentation=https://runqd.com
+After=network.target
+
+[Service]
+Type=simple
+{user}{environment}ExecStart={exec_start}
+Restart=on-failure
+RestartSec=5
+RuntimeDirectory=gflow
+# Only stop gflowd itself; jobs keep running in their tmux sessions.
+KillMode=process
+
+[Install]
+WantedBy={wanted_by}
+";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ServiceScope {
+    User,
+    System,
+}
+
+impl From<ServiceScopeArgs> for ServiceScope {
+    fn from(args: ServiceScopeArgs) -> Self {
+        if args.system {
+            ServiceScope::System
+        } else {
+            ServiceScope::User
+        }
+    }
+}
+
+impl ServiceScope {
+    fn unit_path(self) -> Result<PathBuf> {
+        match self {
+            ServiceScope::User => dirs::config_dir()
+                .map(|dir| dir.join("systemd").join("user").join(UNIT_NAME))
+                .ok_or_else(|| anyhow!("Failed to get config directory")),
+            ServiceScope::System => Ok(Path::new("/etc/systemd/system").join(UNIT_NAME)),
+        }
+    }
+
+    fn systemctl(self) -> Command {
+        let mut command = Command::new("systemctl");
+        if self == ServiceScope::User {
+            command.arg("--user");
+        }
+        command
+    }
+
+    fn flag(self) -> &'static str {
+        match self {
+            ServiceScope::User => "--user",
+            ServiceScope::System => "--system",
+        }
+    }
+}
+
+struct UnitParams<'a> {
+    scope: ServiceScope,
+    /// Daemon command line as produced by `daemon_start_command`.
+    command: &'a str,
+    config_path: Option<&'a Path>,
+    run_as: Option<&'a str>,
+}
+
+fn render_unit(params: &UnitParams<'_>) -> String {
+    // systemd expands `%` specifiers and `$VAR` references in ExecStart.
+    let mut exec_start = params.command.replace('%', "%%").replace('$', "$$");
+    let mut environment = String::new();
+    if let Some(path) = params.config_path {
+        let value = path
+            .display()
+            .to_string()
+            .replace('%', "%%")
+            .replace('\\', "\\\\")
+            .replace('"', "\\\"");
+        environment = format!("Environment=\"GFLOWD_CONFIG={value}\"\n");
+        exec_start.push_str(" --config ${GFLOWD_CONFIG}");
+    }
+
+    let (user, wanted_by) = match params.scope {
+        ServiceScope::User => (String::new(), "default.target"),
+        ServiceScope::System => (
+            params
+                .run_as
+                .map(|user| format!("User={user}\n"))
+                .unwrap_or_default(),
+            "multi-user.target",
+        ),
+    };
+
+    UNIT_TEMPLATE
+        .replace("{user}", &user)
+        .replace("{environment}", &environment)
+        .replace("{exec_start}", &exec_start)
+        .replace("{wanted_by}", wanted_by)
+}
+
+fn ensure_systemd() -> Result<()> {
+    if !cfg!(target_os = "linux") {
+        bail!("gflowd service management requires systemd, which is only available on Linux. Use `gflowd up` instead.");
+    }
+    // Same check as sd_booted(3).
+    if !Path::new("/run/systemd/system").is_dir() {
+        bail!("systemd is not running on this machine (no /run/systemd/system). Use `gflowd up` to run gflowd in tmux instead.");
+    }
+    Ok(())
+}
+
+fn run_systemctl(scope: ServiceScope, args: &[&str]) -> Result<()> {
+    let status = scope
+        .systemctl()
+        .args(args)
+        .status()
+        .context("Failed to run systemctl")?;
+    if !status.success() {
+        let user_flag = if scope == ServiceScope::User {
+            "--user "
+        } else {
+            ""
+        };
+        bail!(
+            "`systemctl {user_flag}{}` failed ({status})",
+            args.join(" ")
+        );
+    }
+    Ok(())
+}
+
+fn resolve_config_path(config_path: &Option<PathBuf>) -> Result<Option<PathBuf>> {
+    if let Some(path) = super::daemon_config_path(config_path)? {
+        return Ok(Some(path));
+    }
+
+    let default_path = gflow::config::default_config_path()?;
+    Ok(default_path.exists().then_some(default_path))
+}
+
+pub fn handle_install_service(
+    config_path: &Option<PathBuf>,
+    scope: ServiceScopeArgs,
+    daemon_overrides: DaemonOverrideArgs,
+    verbosity: Verbosity,
+) -> Result<()> {
+    let scope = ServiceScope::from(scope);
+    ensure_systemd()?;
+
+    let start_options = super::DaemonStartOptions::from_overrides(&daemon_overrides, verbosity);
+    super::validate_daemon_startup_config(config_path, &start_options)?;
+    let command = super::daemon_start_command(&start_options)?;
+    let config_path = resolve_config_path(config_path)?;
+    // `sudo gflowd install-service --system` should run the daemon as the invoking user.
+    let run_as =
+        std::env::var("SUDO_USER").unwrap_or_else(|_| gflow::platform::get_current_username());
+
+    let unit = render_unit(&UnitParams {
+        scope,
+        command: &command,
+        config_path: config_path.as_deref(),
+        run_as: Some(&run_as),
+    });
+
+    let unit_path = scope.unit_path()?;
+    if let Some(parent) = unit_path.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+    std::fs::write(&unit_path, unit)
+        .with_context(|| format!("Failed to write unit file {}", unit_path.display()))?;
+    println!("Wrote {}", unit_path.display());
+
+    run_systemctl(scope, &["daemon-reload"])?;
+    run_systemctl(scope, &["enable", "--now", UNIT_NAME])?;
+
+    println!("gflowd service enabled and started.");
+    if scope == ServiceScope::User {
+        println!(
+            "To keep it running after you log out, run: loginctl enable-linger {}",
+            gflow::platform::get_current_username()
+        );
+    }
+    println!("Check it with `gflowd service-status {}`.", scope.flag());
+    Ok(())
+}
+
+pub fn handle_uninstall_service(scope: ServiceScopeArgs) -> Result<()> {
+    let scope = ServiceScope::from(scope);
+    ensure_systemd()?;
+
+    let unit_path = scope.unit_path()?;
+    if !unit_path.exists() {
+        println!(
+            "gflowd service is not installed ({} not found).",
+            unit_path.display()
+        );
+        return Ok(());
+    }
+
+    run_systemctl(scope, &["disable", "--now", UNIT_NAME])?;
+    std::fs::remove_file(&unit_path)
+        .with_context(|| format!("Failed to remove {}", unit_path.display()))?;
+    run_systemctl(scope, &["daemon-reload"])?;
+
+    println!("gflowd service stopped and removed.");
+    Ok(())
+}
+
+pub fn handle_service_status(scope: ServiceScopeArgs) -> Result<()> {
+    let scope = ServiceScope::from(scope);
+    ensure_systemd()?;
+
+    let unit_path = scope.unit_path()?;
+    if !unit_path.exists() {
+        println!("Service: Not installed");
+        println!("Install it with `gflowd install-service {}`.", scope.flag());
+        return Ok(());
+    }
+
+    let query = |verb: &str| -> Result<String> {
+        let output = scope
+            .systemctl()
+            .args([verb, UNIT_NAME])
+            .output()
+            .context("Failed to run systemctl")?;
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    };
+
+    println!("Service: {}", unit_path.display());
+    println!("Enabled: {}", query("is-enabled")?);
+    println!("Active: {}", query("is-active")?);
+
+    // Full status (including recent log lines) for details; exits non-zero when inactive.
+    let _ = scope
+        .systemctl()
+        .args(["status", "--no-pager", "--lines=5", UNIT_NAME])
+        .status();
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn user_unit_passes_config_path_through_environment() {
+        let unit = render_unit(&UnitParams {
+            scope: ServiceScope::User,
+            command: "/usr/bin/gflow __multicall gflowd -vvv",
+            config_path: Some(Path::new("/home/alice/.config/gflow/gflow.toml")),
+            run_as: Some("alice"),
+        });
+
+        assert!(
+            unit.contains("Environment=\"GFLOWD_CONFIG=/home/alice/.config/gflow/gflow.toml\"\n")
+        );
+        assert!(unit.contains(
+            "ExecStart=/usr/bin/gflow __multicall gflowd -vvv --config ${GFLOWD_CONFIG}\n"
+        ));
+        assert!(unit.contains("Restart=on-failure\n"));
+        assert!(unit.contains("RuntimeDirectory=gflow\n"));
+        assert!(unit.contains("WantedBy=default.target\n"));
+        assert!(!unit.contains("User="));
+    }
+
+    #[test]
+    fn system_unit_runs_as_user_and_escapes_specifiers() {
+        let unit = render_unit(&UnitParams {
+            scope: ServiceScope::System,
+            command: "'/opt/100%/gflow' __multicall gflowd -vvv",
+            config_path: None,
+            run_as: Some("alice"),
+        });
+
+        assert!(unit.contains("User=alice\n"));
+        assert!(unit.contains("ExecStart='/opt/100%%/gflow' __multicall gflowd -vvv\n"));
+        assert!(unit.contains("WantedBy=multi-user.target\n"));
+        assert!(!unit.contains("GFLOWD_CONFIG"));
+    }
+}
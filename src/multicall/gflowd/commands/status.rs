@@ -1,34 +1,73 @@
 use anyhow::Result;
+use gflow::config::ListenAddr;
 use gflow::tmux::is_session_exist;
+use std::time::Duration;
+
+use super::super::pidfile::running_daemon_pid;
 
 pub async fn handle_status(config_path: &Option<std::path::PathBuf>) -> Result<()> {
+    let pid = running_daemon_pid();
     let session_exists = is_session_exist(super::TMUX_SESSION_NAME);
 
-    if !session_exists {
+    if pid.is_none() && !session_exists {
         println!("Status: Not running");
-        println!("The gflowd daemon is not running (tmux session not found).");
+        println!("The gflowd daemon is not running (no PID file or tmux session found).");
         return Ok(());
     }
 
-    let client = gflow::create_client_or_default(config_path)?;
-
-    match client.get_health().await {
-        Ok(health) => {
-            if health.is_success() {
-                println!("Status: Running");
-                println!(
-                    "The gflowd daemon is running in tmux session '{}'.",
-                    super::TMUX_SESSION_NAME
-                );
-            } else {
-                println!("Status: Unhealthy");
-                eprintln!("The gflowd daemon responded to the health check but is not healthy.");
-            }
-        }
+    let config = gflow::config::load_config(config_path.as_ref()).unwrap_or_default();
+    let client = gflow::Client::build(&config)?;
+
+    let health = match client.get_health().await {
+        Ok(health) => health,
         Err(e) => {
             println!("Status: Not Running");
+            if let Some(pid) = pid {
+                println!("PID: {pid}");
+            }
             eprintln!("Failed to connect to gflowd daemon: {e}");
+            return Ok(());
+        }
+    };
+
+    println!(
+        "Status: {}",
+        if health.is_success() {
+            "Running"
+        } else {
+            "Unhealthy"
+        }
+    );
+
+    match client.get_scheduler_stats().await {
+        Ok(stats) => {
+            println!("PID: {}", stats.pid);
+            println!(
+                "Uptime: {}",
+                gflow::utils::format_duration(Duration::from_secs(stats.uptime_secs))
+            );
+            println!("Persistence: {}", stats.persistence.mode);
+        }
+        Err(_) => {
+            if let Some(pid) = pid {
+                println!("PID: {pid}");
+            }
+        }
+    }
+    match config.daemon.listen_addr() {
+        ListenAddr::Tcp { host, port } if config.daemon.client_uses_tls() => {
+            println!("Address: https://{host}:{port}")
         }
+        ListenAddr::Tcp { host, port } => println!("Address: {host}:{port}"),
+        ListenAddr::Unix(path) => println!("Address: unix:{}", path.display()),
+    }
+    println!("Health: HTTP {}", health.as_u16());
+    if session_exists {
+        println!("tmux session: {}", super::TMUX_SESSION_NAME);
+    }
+
+    if !health.is_success() {
+        eprintln!("The gflowd daemon responded to the health check but is not healthy.");
     }
     Ok(())
 }
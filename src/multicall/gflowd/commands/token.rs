@@ -0,0 +1,45 @@
+use anyhow::{bail, Result};
+
+use super::super::cli::TokenCommands;
+use super::super::tokens::TokenStore;
+
+pub fn handle_token(command: TokenCommands) -> Result<()> {
+    let store = TokenStore::open_default()?;
+    match command {
+        TokenCommands::Create { username, admin } => {
+            let (record, token) = store.create(&username, admin)?;
+            println!(
+                "Created {}token {} for '{}'.",
+                if admin { "admin " } else { "" },
+                record.id,
+                record.username
+            );
+            println!("{token}");
+            println!("Store it now (e.g. export GFLOW_TOKEN=...); it cannot be shown again.");
+        }
+        TokenCommands::List => {
+            let tokens = store.list()?;
+            if tokens.is_empty() {
+                println!("No tokens. Create one with `gflowd token create <user>`.");
+                return Ok(());
+            }
+            println!("{:<10} {:<16} {:<6} CREATED", "ID", "USER", "ADMIN");
+            for token in tokens {
+                println!(
+                    "{:<10} {:<16} {:<6} {}",
+                    token.id,
+                    token.username,
+                    if token.admin { "yes" } else { "no" },
+                    token.created_at
+                );
+            }
+        }
+        TokenCommands::Revoke { id } => {
+            if !store.revoke(&id)? {
+                bail!("No token with ID '{id}'");
+            }
+            println!("Revoked token {id}.");
+        }
+    }
+    Ok(())
+}
@@ -1,6 +1,9 @@
 use anyhow::{bail, Result};
 use clap_verbosity_flag::Verbosity;
 use gflow::tmux::{is_session_exist, TmuxSession};
+use std::time::Duration;
+
+use super::super::pidfile::running_daemon_pid;
 
 pub async fn handle_up(
     config_path: &Option<std::path::PathBuf>,
@@ -36,17 +39,49 @@ pub async fn handle_up(
         }
     }
 
+    if let Some(pid) = running_daemon_pid() {
+        bail!(
+            "gflowd is already running outside tmux (PID {pid}), e.g. as a systemd service. \
+             Stop it first with `gflowd down`."
+        );
+    }
+
     let start_options = super::DaemonStartOptions::from_overrides(&daemon_overrides, verbosity);
     super::validate_daemon_startup_config(config_path, &start_options)?;
-    let command = super::daemon_start_command(&start_options)?;
+    let command = super::daemon_start_command_with_config(config_path, &start_options)?;
     let session = TmuxSession::create(super::TMUX_SESSION_NAME.to_string())?;
 
     session.try_send_command(&command)?;
 
-    println!("gflowd started.");
+    match wait_for_pid_file(Duration::from_secs(STARTUP_TIMEOUT_SECS)).await {
+        Some(pid) => println!("gflowd started (PID {pid})."),
+        None => {
+            println!("gflowd start command sent, but the daemon has not reported a PID yet.");
+            println!(
+                "Check `gflowd status`, or `tmux attach -t {}` for startup errors.",
+                super::TMUX_SESSION_NAME
+            );
+        }
+    }
     Ok(())
 }
 
+const STARTUP_TIMEOUT_SECS: u64 = 10;
+
+/// Wait for the new daemon to bind its listener and write the PID file.
+async fn wait_for_pid_file(timeout: Duration) -> Option<u32> {
+    let deadline = tokio::time::Instant::now() + timeout;
+    loop {
+        if let Some(pid) = running_daemon_pid() {
+            return Some(pid);
+        }
+        if tokio::time::Instant::now() >= deadline {
+            return None;
+        }
+        tokio::time::sleep(Duration::from_millis(200)).await;
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 enum ExistingDaemonState {
     NotPresent,
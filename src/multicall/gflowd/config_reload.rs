@@ -0,0 +1,486 @@
+//! Config hot-reload for the running daemon
+//!
+//! On SIGHUP or `POST /config/reload` the daemon re-reads its config, diffs it
+//! against the running config, and applies the settings that can change live.
+//! Settings baked into the listener or monitor tasks are left untouched and
+//! reported as rejected until the daemon is restarted.
+
+use super::scheduler_runtime::SharedState;
+use gflow::config::{Config, NotificationsConfig, RateLimitsConfig};
+use gflow::core::info::ConfigReloadReport;
+use gflow::core::version::{Version, MIN_CLIENT_VERSION};
+use std::path::PathBuf;
+use tokio::sync::{watch, Mutex};
+
+/// Where the daemon config comes from: the config file (plus `GFLOW_*`
+/// environment overrides) and the internal CLI overrides passed by `gflowd up`.
+/// CLI overrides keep precedence across reloads.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ConfigSource {
+    pub(crate) path: Option<PathBuf>,
+    pub(crate) gpus: Option<String>,
+    pub(crate) gpu_allocation_strategy: Option<String>,
+    pub(crate) gpu_poll_interval_secs: Option<u64>,
+}
+
+impl ConfigSource {
+    pub(crate) fn load(&self) -> anyhow::Result<Config> {
+        let mut config = gflow::config::load_config(self.path.as_ref())?;
+
+        if let Some(ref gpu_spec) = self.gpus {
+            let indices = gflow::utils::parse_gpu_indices(gpu_spec)?;
+            config.daemon.gpus = Some(indices);
+        }
+        if let Some(ref strategy) = self.gpu_allocation_strategy {
+            config.daemon.gpu_allocation_strategy = strategy.parse().map_err(|_| {
+                anyhow::anyhow!(
+                    "Invalid GPU allocation strategy '{}'. Use 'sequential' or 'random'.",
+                    strategy
+                )
+            })?;
+        }
+        if let Some(gpu_poll_interval_secs) = self.gpu_poll_interval_secs {
+            if gpu_poll_interval_secs == 0 {
+                anyhow::bail!("Invalid GPU poll interval '0'. Use a value of at least 1 second.");
+            }
+            config.daemon.gpu_poll_interval_secs = gpu_poll_interval_secs;
+        }
+
+        Ok(config)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum ReloadTrigger {
+    Signal,
+    Api,
+}
+
+impl ReloadTrigger {
+    fn as_str(self) -> &'static str {
+        match self {
+            ReloadTrigger::Signal => "signal",
+            ReloadTrigger::Api => "api",
+        }
+    }
+}
+
+/// Changed settings split into those that can be applied live and those that
+/// require a restart.
+#[derive(Debug, Default, PartialEq, Eq)]
+struct ConfigDiff {
+    gpus: bool,
+    gpu_allocation_strategy: bool,
+    preemption_priority: bool,
+    min_free_disk: bool,
+    duplicate_run_names: bool,
+    pass_budget: bool,
+    priority_limits: bool,
+    labels: bool,
+    notifications: bool,
+    projects: bool,
+    rate_limits: bool,
+    min_client_version: bool,
+    restart_required: Vec<&'static str>,
+}
+
+impl ConfigDiff {
+    fn between(running: &Config, new: &Config) -> Self {
+        let mut restart_required = Vec::new();
+        if running.daemon.host != new.daemon.host {
+            restart_required.push("daemon.host");
+        }
+        if running.daemon.port != new.daemon.port {
+            restart_required.push("daemon.port");
+        }
+        if running.daemon.listen != new.daemon.listen {
+            restart_required.push("daemon.listen");
+        }
+        if running.daemon.extra_listen != new.daemon.extra_listen {
+            restart_required.push("daemon.extra_listen");
+        }
+        if running.daemon.readonly_bind != new.daemon.readonly_bind {
+            restart_required.push("daemon.readonly_bind");
+        }
+        if running.daemon.tls_cert != new.daemon.tls_cert {
+            restart_required.push("daemon.tls_cert");
+        }
+        if running.daemon.tls_key != new.daemon.tls_key {
+            restart_required.push("daemon.tls_key");
+        }
+        if running.daemon.gpu_poll_interval_secs != new.daemon.gpu_poll_interval_secs {
+            restart_required.push("daemon.gpu_poll_interval_secs");
+        }
+        if running.daemon.state_save_interval_ms != new.daemon.state_save_interval_ms {
+            restart_required.push("daemon.state_save_interval_ms");
+        }
+        if running.daemon.state_backups != new.daemon.state_backups {
+            restart_required.push("daemon.state_backups");
+        }
+        if running.daemon.state_backup_interval_secs != new.daemon.state_backup_interval_secs {
+            restart_required.push("daemon.state_backup_interval_secs");
+        }
+        if running.daemon.require_auth != new.daemon.require_auth {
+            restart_required.push("daemon.require_auth");
+        }
+        if running.daemon.anonymous_read != new.daemon.anonymous_read {
+            restart_required.push("daemon.anonymous_read");
+        }
+        if running.daemon.allow_as_user != new.daemon.allow_as_user {
+            restart_required.push("daemon.allow_as_user");
+        }
+        if running.daemon.default_shell != new.daemon.default_shell {
+            restart_required.push("daemon.default_shell");
+        }
+        if running.daemon.executor != new.daemon.executor {
+            restart_required.push("daemon.executor");
+        }
+        if running.daemon.tmux_mode != new.daemon.tmux_mode {
+            restart_required.push("daemon.tmux_mode");
+        }
+        if running.daemon.enforce_limits != new.daemon.enforce_limits {
+            restart_required.push("daemon.enforce_limits");
+        }
+        if running.daemon.job_dir_retention_secs != new.daemon.job_dir_retention_secs {
+            restart_required.push("daemon.job_dir_retention_secs");
+        }
+        if running.daemon.logging != new.daemon.logging {
+            restart_required.push("daemon.logging");
+        }
+        if running.container != new.container {
+            restart_required.push("container");
+        }
+        if running.hooks != new.hooks {
+            restart_required.push("hooks");
+        }
+        if running.scheduler.tick_secs != new.scheduler.tick_secs {
+            restart_required.push("scheduler.tick_secs");
+        }
+
+        Self {
+            gpus: running.daemon.gpus != new.daemon.gpus,
+            gpu_allocation_strategy: running.daemon.gpu_allocation_strategy
+                != new.daemon.gpu_allocation_strategy,
+            preemption_priority: running.daemon.preemption_priority
+                != new.daemon.preemption_priority,
+            min_free_disk: running.daemon.min_free_disk != new.daemon.min_free_disk,
+            duplicate_run_names: running.daemon.duplicate_run_names
+                != new.daemon.duplicate_run_names,
+            pass_budget: running.scheduler.pass_budget_ms != new.scheduler.pass_budget_ms,
+            priority_limits: running.priority_limits != new.priority_limits
+                || running.priorities != new.priorities,
+            labels: running.daemon.labels != new.daemon.labels
+                || running.daemon.gpu_labels != new.daemon.gpu_labels,
+            notifications: running.notifications != new.notifications,
+            projects: running.projects != new.projects,
+            rate_limits: running.rate_limits != new.rate_limits,
+            min_client_version: running.daemon.min_client_version != new.daemon.min_client_version,
+            restart_required,
+        }
+    }
+}
+
+pub(crate) struct ConfigReloader {
+    source: ConfigSource,
+    running: Mutex<Config>,
+    scheduler: SharedState,
+    notifications_tx: watch::Sender<NotificationsConfig>,
+    rate_limits_tx: watch::Sender<RateLimitsConfig>,
+    /// Served by `/version`; clients older than this refuse to run.
+    min_client_version: std::sync::RwLock<Version>,
+    last_report: std::sync::RwLock<Option<ConfigReloadReport>>,
+}
+
+impl ConfigReloader {
+    pub(crate) fn new(
+        source: ConfigSource,
+        running: Config,
+        scheduler: SharedState,
+        notifications_tx: watch::Sender<NotificationsConfig>,
+        rate_limits_tx: watch::Sender<RateLimitsConfig>,
+    ) -> Self {
+        // The daemon refuses to start with an invalid value.
+        let min_client_version = running
+            .daemon
+            .min_client_version()
+            .unwrap_or(MIN_CLIENT_VERSION);
+        Self {
+            source,
+            running: Mutex::new(running),
+            min_client_version: std::sync::RwLock::new(min_client_version),
+            scheduler,
+            notifications_tx,
+            rate_limits_tx,
+            last_report: std::sync::RwLock::new(None),
+        }
+    }
+
+    /// Result of the most recent reload, if any.
+    pub(crate) fn last_report(&self) -> Option<ConfigReloadReport> {
+        self.last_report
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    pub(crate) fn min_client_version(&self) -> Version {
+        *self
+            .min_client_version
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Re-read the config and apply every change that is safe to apply live.
+    pub(crate) async fn reload(&self, trigger: ReloadTrigger) -> ConfigReloadReport {
+        let mut running = self.running.lock().await;
+        let mut report = ConfigReloadReport {
+            trigger: trigger.as_str().to_string(),
+            reloaded_at: chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+            applied: Vec::new(),
+            rejected: Vec::new(),
+            error: None,
+        };
+
+        match self.source.load() {
+            Ok(new) => self.apply(&mut running, new, &mut report).await,
+            Err(e) => {
+                tracing::error!(trigger = trigger.as_str(), error = %e, "Config reload failed");
+                report.error = Some(format!("{e:#}"));
+            }
+        }
+
+        if report.error.is_none() {
+            tracing::info!(
+                trigger = trigger.as_str(),
+                applied = ?report.applied,
+                rejected = ?report.rejected,
+                "Config reloaded"
+            );
+        }
+        *self.last_report.write().unwrap_or_else(|e| e.into_inner()) = Some(report.clone());
+        report
+    }
+
+    async fn apply(&self, running: &mut Config, new: Config, report: &mut ConfigReloadReport) {
+        let diff = ConfigDiff::between(running, &new);
+
+        for key in &diff.restart_required {
+            tracing::warn!(
+                key,
+                "Config change requires a daemon restart; keeping current value"
+            );
+            report.rejected.push(format!("{key}: requires restart"));
+        }
+
+        if diff.gpus
+            || diff.gpu_allocation_strategy
+            || diff.preemption_priority
+            || diff.min_free_disk
+            || diff.duplicate_run_names
+            || diff.pass_budget
+            || diff.priority_limits
+            || diff.labels
+            || diff.projects
+        {
+            let mut state = self.scheduler.write().await;
+
+            if diff.gpus {
+                let detected = state.gpu_slots_count() as u32;
+                let invalid: Vec<u32> = new
+                    .daemon
+                    .gpus
+                    .iter()
+                    .flatten()
+                    .copied()
+                    .filter(|&idx| idx >= detected)
+                    .collect();
+                if invalid.is_empty() {
+                    state.set_allowed_gpu_indices(new.daemon.gpus.clone());
+                    running.daemon.gpus = new.daemon.gpus.clone();
+                    report.applied.push("daemon.gpus".to_string());
+                } else {
+                    tracing::warn!(
+                        ?invalid,
+                        detected,
+                        "Ignoring daemon.gpus with invalid indices"
+                    );
+                    report.rejected.push(format!(
+                        "daemon.gpus: invalid GPU indices {invalid:?} (only {detected} GPUs detected)"
+                    ));
+                }
+            }
+
+            if diff.gpu_allocation_strategy {
+                state.set_gpu_allocation_strategy(new.daemon.gpu_allocation_strategy);
+                running.daemon.gpu_allocation_strategy = new.daemon.gpu_allocation_strategy;
+                report
+                    .applied
+                    .push("daemon.gpu_allocation_strategy".to_string());
+            }
+
+            if diff.preemption_priority {
+                state.set_preemption_priority(new.daemon.preemption_priority);
+                running.daemon.preemption_priority = new.daemon.preemption_priority;
+                report
+                    .applied
+                    .push("daemon.preemption_priority".to_string());
+            }
+
+            if diff.min_free_disk {
+                match new.daemon.min_free_disk_mb() {
+                    Ok(min_free_mb) => {
+                        state.set_min_free_disk(min_free_mb);
+                        running.daemon.min_free_disk = new.daemon.min_free_disk.clone();
+                        report.applied.push("daemon.min_free_disk".to_string());
+                    }
+                    Err(e) => {
+                        tracing::warn!(error = %e, "Ignoring invalid min_free_disk");
+                        report.rejected.push(format!("daemon.min_free_disk: {e:#}"));
+                    }
+                }
+            }
+
+            if diff.duplicate_run_names {
+                state.set_duplicate_run_names(new.daemon.duplicate_run_names);
+                running.daemon.duplicate_run_names = new.daemon.duplicate_run_names;
+                report
+                    .applied
+                    .push("daemon.duplicate_run_names".to_string());
+            }
+
+            if diff.pass_budget {
+                state.set_pass_budget(new.scheduler.pass_budget());
+                running.scheduler.pass_budget_ms = new.scheduler.pass_budget_ms;
+                report.applied.push("scheduler.pass_budget_ms".to_string());
+            }
+
+            if diff.priority_limits {
+                state.set_priority_limits(new.priority_limits.clone(), new.priorities.clone());
+                if running.priority_limits != new.priority_limits {
+                    running.priority_limits = new.priority_limits.clone();
+                    report.applied.push("priority_limits".to_string());
+                }
+                if running.priorities != new.priorities {
+                    running.priorities = new.priorities.clone();
+                    report.applied.push("priorities".to_string());
+                }
+            }
+
+            if diff.labels {
+                match new.daemon.parsed_labels() {
+                    Ok((labels, gpu_labels)) => {
+                        state.set_labels(labels, gpu_labels);
+                        running.daemon.labels = new.daemon.labels.clone();
+                        running.daemon.gpu_labels = new.daemon.gpu_labels.clone();
+                        report.applied.push("daemon.labels".to_string());
+                    }
+                    Err(e) => {
+                        tracing::warn!(error = %e, "Ignoring invalid labels");
+                        report.rejected.push(format!("daemon.labels: {e:#}"));
+                    }
+                }
+            }
+
+            if diff.projects {
+                state.set_projects_config(new.projects.clone());
+                running.projects = new.projects.clone();
+                report.applied.push("projects".to_string());
+            }
+        }
+
+        if diff.notifications {
+            self.notifications_tx
+                .send_replace(new.notifications.clone());
+            running.notifications = new.notifications;
+            report.applied.push("notifications".to_string());
+        }
+
+        if diff.rate_limits {
+            self.rate_limits_tx.send_replace(new.rate_limits.clone());
+            running.rate_limits = new.rate_limits;
+            report.applied.push("rate_limits".to_string());
+        }
+
+        if diff.min_client_version {
+            match new.daemon.min_client_version() {
+                Ok(version) => {
+                    *self
+                        .min_client_version
+                        .write()
+                        .unwrap_or_else(|e| e.into_inner()) = version;
+                    running.daemon.min_client_version = new.daemon.min_client_version.clone();
+                    report.applied.push("daemon.min_client_version".to_string());
+                }
+                Err(e) => {
+                    tracing::warn!(error = %e, "Ignoring invalid min_client_version");
+                    report
+                        .rejected
+                        .push(format!("daemon.min_client_version: {e:#}"));
+                }
+            }
+        }
+
+        // Only read by clients, so there is nothing to apply in the daemon.
+        running.timezone = new.timezone;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diff_separates_live_and_restart_only_changes() {
+        let running = Config::default();
+        let mut new = Config::default();
+        new.daemon.port = 60000;
+        new.daemon.gpus = Some(vec![0]);
+        new.notifications.enabled = true;
+        new.priorities.insert("high".to_string(), 50);
+        new.scheduler.tick_secs = 5;
+        new.scheduler.pass_budget_ms = 50;
+
+        let diff = ConfigDiff::between(&running, &new);
+        assert_eq!(
+            diff,
+            ConfigDiff {
+                gpus: true,
+                gpu_allocation_strategy: false,
+                preemption_priority: false,
+                min_free_disk: false,
+                duplicate_run_names: false,
+                pass_budget: true,
+                priority_limits: true,
+                labels: false,
+                notifications: true,
+                projects: false,
+                rate_limits: false,
+                min_client_version: false,
+                restart_required: vec!["daemon.port", "scheduler.tick_secs"],
+            }
+        );
+    }
+
+    #[test]
+    fn diff_of_identical_configs_is_empty() {
+        let config = Config::default();
+        assert_eq!(
+            ConfigDiff::between(&config, &config.clone()),
+            ConfigDiff::default()
+        );
+    }
+
+    #[test]
+    fn source_applies_cli_overrides_over_file() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("gflow.toml");
+        std::fs::write(&path, "[daemon]\ngpus = [0, 1]\n").unwrap();
+
+        let source = ConfigSource {
+            path: Some(path),
+            gpus: Some("2".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(source.load().unwrap().daemon.gpus, Some(vec![2]));
+    }
+}
@@ -592,6 +592,7 @@ mod tests {
             }),
             reservation: None,
             gpu: None,
+            request_id: None,
         };
 
         let subject = build_subject(&payload, Some("[prod]"));
@@ -627,6 +628,7 @@ mod tests {
                 cancelled_at: None,
             }),
             gpu: None,
+            request_id: None,
         };
         let gpu_payload = WebhookPayload {
             event: "gpu_available".to_string(),
@@ -642,6 +644,7 @@ mod tests {
                 index: 3,
                 available: true,
             }),
+            request_id: None,
         };
 
         assert_eq!(
@@ -688,6 +691,7 @@ mod tests {
             }),
             reservation: None,
             gpu: None,
+            request_id: None,
         };
         let job_notifications = JobNotifications::normalized(
             vec![
@@ -745,6 +749,7 @@ mod tests {
             }),
             reservation: None,
             gpu: None,
+            request_id: None,
         };
         let job_notifications = JobNotifications::normalized(
             vec!["alice@example.com".to_string()],
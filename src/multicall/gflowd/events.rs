@@ -4,11 +4,16 @@
 //! scheduler operations without polling. Events are published when state
 //! changes occur and handlers react to these events.
 
-use gflow::core::job::{GpuIds, JobState, JobStateReason};
+use gflow::core::job::{GpuIds, JobSignal, JobState, JobStateReason};
 use std::sync::Arc;
 use tokio::sync::broadcast;
 use tracing::Span;
 
+tokio::task_local! {
+    /// ID of the API request being handled, given to the events it causes.
+    pub static REQUEST_ID: String;
+}
+
 /// Events that can occur in the scheduler
 #[derive(Debug, Clone)]
 #[allow(dead_code)] // Some variants/fields are reserved for future use
@@ -27,6 +32,13 @@ pub enum SchedulerEvent {
     /// A job's parameters were updated
     JobUpdated { job_id: u32 },
 
+    /// A signal was sent to a running job's processes
+    JobSignalled {
+        job_id: u32,
+        signal: JobSignal,
+        requested_by: String,
+    },
+
     /// A job has completed (finished, failed, cancelled, or timed out)
     JobCompleted {
         job_id: u32,
@@ -55,6 +67,12 @@ pub enum SchedulerEvent {
     /// A zombie job was detected (tmux session disappeared)
     ZombieJobDetected { job_id: u32 },
 
+    /// A remote node joined, or registered again after being down
+    NodeUp { name: String },
+
+    /// A remote node missed its heartbeats
+    NodeDown { name: String },
+
     /// Periodic health check trigger
     PeriodicHealthCheck,
 
@@ -74,12 +92,15 @@ impl SchedulerEvent {
             Self::JobStateChanged { .. } => "job_state_changed",
             Self::JobSubmitted { .. } => "job_submitted",
             Self::JobUpdated { .. } => "job_updated",
+            Self::JobSignalled { .. } => "job_signalled",
             Self::JobCompleted { .. } => "job_completed",
             Self::GpuAvailabilityChanged { .. } => "gpu_availability_changed",
             Self::ManualGpuOverrideChanged { .. } => "manual_gpu_override_changed",
             Self::MemoryAvailabilityChanged { .. } => "memory_availability_changed",
             Self::JobTimedOut { .. } => "job_timed_out",
             Self::ZombieJobDetected { .. } => "zombie_job_detected",
+            Self::NodeUp { .. } => "node_up",
+            Self::NodeDown { .. } => "node_down",
             Self::PeriodicHealthCheck => "periodic_health_check",
             Self::ReservationCreated { .. } => "reservation_created",
             Self::ReservationCancelled { .. } => "reservation_cancelled",
@@ -92,6 +113,8 @@ impl SchedulerEvent {
 pub struct EventEnvelope {
     pub event: SchedulerEvent,
     pub span: Span,
+    /// The API request that caused the event, if any
+    pub request_id: Option<String>,
 }
 
 impl EventEnvelope {
@@ -105,6 +128,10 @@ impl EventEnvelope {
     }
 }
 
+fn current_request_id() -> Option<String> {
+    REQUEST_ID.try_with(Clone::clone).ok()
+}
+
 /// Event bus for publishing and subscribing to scheduler events
 #[derive(Clone)]
 pub struct EventBus {
@@ -127,6 +154,7 @@ impl EventBus {
         let envelope = EventEnvelope {
             event,
             span: Span::current(),
+            request_id: current_request_id(),
         };
         tracing::debug!(
             event_type = event_name,
@@ -137,6 +165,31 @@ impl EventBus {
         let _ = self.sender.send(envelope);
     }
 
+    /// Publish a burst of events, such as one per job of a batch action,
+    /// logging once for the lot.
+    pub fn publish_all(&self, events: impl IntoIterator<Item = SchedulerEvent>) {
+        let span = Span::current();
+        let request_id = current_request_id();
+        let mut count = 0usize;
+        for event in events {
+            let envelope = EventEnvelope {
+                event,
+                span: span.clone(),
+                request_id: request_id.clone(),
+            };
+            // Ignore send errors (no subscribers is fine)
+            let _ = self.sender.send(envelope);
+            count += 1;
+        }
+        if count > 0 {
+            tracing::debug!(
+                count,
+                subscriber_count = self.subscriber_count(),
+                "Publishing scheduler events"
+            );
+        }
+    }
+
     /// Subscribe to events
     pub fn subscribe(&self) -> broadcast::Receiver<EventEnvelope> {
         self.sender.subscribe()
@@ -194,6 +247,44 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_publish_all_delivers_in_order() {
+        let bus = EventBus::new(100);
+        let mut rx = bus.subscribe();
+
+        bus.publish_all((1..=3).map(|job_id| SchedulerEvent::JobSubmitted { job_id }));
+
+        for expected in 1..=3 {
+            match rx.recv().await.unwrap().event {
+                SchedulerEvent::JobSubmitted { job_id } => assert_eq!(job_id, expected),
+                _ => panic!("Unexpected event type"),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn events_carry_the_request_that_caused_them() {
+        let bus = EventBus::new(100);
+        let mut rx = bus.subscribe();
+
+        REQUEST_ID
+            .scope("req-1".to_string(), async {
+                bus.publish(SchedulerEvent::JobSubmitted { job_id: 1 });
+                bus.publish_all([SchedulerEvent::JobUpdated { job_id: 1 }]);
+            })
+            .await;
+        bus.publish(SchedulerEvent::PeriodicHealthCheck);
+
+        let request_ids: Vec<_> = [(); 3]
+            .iter()
+            .map(|_| rx.try_recv().unwrap().request_id)
+            .collect();
+        assert_eq!(
+            request_ids,
+            [Some("req-1".to_string()), Some("req-1".to_string()), None]
+        );
+    }
+
     #[tokio::test]
     async fn test_subscriber_count() {
         let bus = EventBus::new(100);
@@ -1,79 +1,486 @@
-use anyhow::Result;
-use gflow::core::{executor::Executor, job::Job};
-use gflow::tmux::TmuxSession;
-use gflow::utils::substitute_parameters;
+mod container;
+mod direct;
+
+use anyhow::{anyhow, bail, Result};
+use gflow::config::ContainerConfig;
+use gflow::core::cpu::format_cpu_list;
+use gflow::core::{
+    executor::{Executor, ExecutorKind, MemoryUsage},
+    job::{EnvSpec, Job, JobSignal},
+};
+use gflow::tmux::{disable_pipe_pane_for_job, job_window_name, JobTarget, TmuxMode, TmuxSession};
+use gflow::utils::substitute_job_placeholders;
 use std::fs;
+use std::io::Read;
+use std::path::Path;
+use std::sync::Arc;
+
+use super::cgroup::{join_command, Cgroups};
+use super::tokens::TokenStore;
+use container::ContainerExecutor;
+use direct::DirectExecutor;
+
+/// Starts a job's shell command, in a tmux session or as a child process.
+trait Backend: Executor {
+    /// Run `user_command` in the job's run directory after `activation`,
+    /// logging its output and reporting the outcome with `gcancel`.
+    fn launch(&self, job: &Job, activation: Option<String>, user_command: &str) -> Result<()>;
+}
 
-pub struct TmuxExecutor;
+#[derive(Clone)]
+pub struct TmuxExecutor {
+    /// Runs scripts that cannot be executed directly (`[daemon] default_shell`).
+    default_shell: String,
+    /// Set when the daemon requires auth, so jobs can report their own outcome.
+    job_tokens: Option<Arc<TokenStore>>,
+    /// Exported to every job after the usual variables.
+    env: Vec<(String, String)>,
+    /// A session per job, or windows of one shared session.
+    mode: TmuxMode,
+    /// Set with `[daemon] enforce_limits`, to run each job in its own cgroup.
+    cgroups: Option<Arc<Cgroups>>,
+}
+
+impl Default for TmuxExecutor {
+    fn default() -> Self {
+        Self::new("bash")
+    }
+}
 
 impl TmuxExecutor {
+    pub fn new(default_shell: impl Into<String>) -> Self {
+        Self {
+            default_shell: default_shell.into(),
+            job_tokens: None,
+            env: Vec::new(),
+            mode: TmuxMode::default(),
+            cgroups: None,
+        }
+    }
+
+    pub fn with_job_tokens(mut self, job_tokens: Arc<TokenStore>) -> Self {
+        self.job_tokens = Some(job_tokens);
+        self
+    }
+
+    pub fn with_env(mut self, env: Vec<(String, String)>) -> Self {
+        self.env = env;
+        self
+    }
+
+    pub fn with_mode(mut self, mode: TmuxMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    fn with_cgroups(mut self, cgroups: Arc<Cgroups>) -> Self {
+        self.cgroups = Some(cgroups);
+        self
+    }
+
+    #[cfg(test)]
     fn generate_wrapped_command(&self, job: &Job) -> Result<String> {
-        let mut user_command = String::new();
+        Ok(pin_to_cpus(
+            job,
+            wrap_command(job.id, &user_command(job, &self.default_shell)?),
+        ))
+    }
+}
 
-        if let Some(script) = &job.script {
-            if let Some(script_str) = script.to_str() {
-                user_command.push_str(&format!("bash {script_str}"));
-            }
-        } else if let Some(cmd) = &job.command {
-            // Apply parameter substitution
-            let substituted = substitute_parameters(cmd, &job.parameters)?;
-            user_command.push_str(&substituted);
-        }
-
-        // Wrap the command in bash -c to ensure && and || operators work
-        // regardless of the user's default shell (fish, zsh, etc.)
-        // Use double quotes to avoid the ugly '\'' escaping pattern
-        // Need to escape: backslash, double-quote, dollar sign, backtick
-        let escaped_command = user_command
-            .replace('\\', r"\\")
-            .replace('"', r#"\""#)
-            .replace('$', r"\$")
-            .replace('`', r"\`");
-        let wrapped_command = format!(
-            r#"bash -c "{escaped_command} && gcancel --finish {job_id} || gcancel --fail {job_id}""#,
-            job_id = job.id,
+impl Backend for TmuxExecutor {
+    fn launch(&self, job: &Job, activation: Option<String>, user_command: &str) -> Result<()> {
+        let Some(session_name) = job.run_name.as_ref() else {
+            return Ok(());
+        };
+        // Interactive jobs keep a session of their own: leaving the shell
+        // should detach the submitter, not switch them to another job's window.
+        let session = if self.mode == TmuxMode::WindowsInSharedSession && !job.interactive {
+            TmuxSession::create_window(&job_window_name(job.id, session_name))?
+        } else {
+            TmuxSession::create(session_name.to_string())?
+        };
+
+        // Enable pipe-pane to capture output to log file
+        let log_path = gflow::paths::prepare_log_file_path(job.id)?;
+        if let Some(parent) = log_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        session.enable_pipe_pane(&log_path)?;
+
+        session.try_send_command(&format!("cd {}", job.run_dir.display()))?;
+        for (key, value) in job_env(job, self.job_tokens.as_deref(), &self.env)? {
+            session.try_send_command(&format!("export {key}={value}"))?;
+        }
+
+        if let Some(activation) = activation {
+            session.try_send_command(&activation)?;
+        }
+
+        let mut command = confine(
+            self.cgroups.as_deref(),
+            job,
+            pin_to_cpus(job, wrap_command(job.id, user_command)),
         );
-        Ok(wrapped_command)
+        if job.interactive {
+            // End the session with the shell, which detaches the submitter.
+            command.push_str("; exit");
+        }
+        session.try_send_command(&command)?;
+        Ok(())
     }
 }
 
 impl Executor for TmuxExecutor {
     fn execute(&self, job: &Job) -> Result<()> {
-        if let Some(session_name) = job.run_name.as_ref() {
-            let session = TmuxSession::create(session_name.to_string())?;
+        if job.run_name.is_none() {
+            return Ok(());
+        }
+        let activation = activation_command(&job.environment)?;
+        self.launch(job, activation, &user_command(job, &self.default_shell)?)
+    }
+
+    fn interrupt(&self, job: &Job) -> Result<()> {
+        match &job.run_name {
+            Some(name) => match JobTarget::find(job.id, name) {
+                Some(target) => gflow::tmux::send_ctrl_c(target.as_str()),
+                None => gflow::tmux::send_ctrl_c(name),
+            },
+            None => Ok(()),
+        }
+    }
 
-            // Enable pipe-pane to capture output to log file
-            let log_path = gflow::paths::prepare_log_file_path(job.id)?;
-            if let Some(parent) = log_path.parent() {
-                fs::create_dir_all(parent)?;
+    fn signal(&self, job: &Job, signal: JobSignal) -> Result<()> {
+        let target = job
+            .run_name
+            .as_ref()
+            .and_then(|name| JobTarget::find(job.id, name));
+        let Some(target) = target else {
+            bail!("Job {} has no tmux session to signal", job.id);
+        };
+        match target.foreground_process_group() {
+            Some(group) => direct::signal_group(group, signal.number()),
+            None => bail!(
+                "Job {} has no command running in tmux target '{}'",
+                job.id,
+                target.as_str()
+            ),
+        }
+    }
+
+    fn is_running(&self, job: &Job) -> Option<bool> {
+        job.run_name
+            .as_ref()
+            .map(|name| JobTarget::find(job.id, name).is_some())
+    }
+
+    fn pid(&self, job: &Job) -> Option<u32> {
+        JobTarget::find(job.id, job.run_name.as_ref()?)?.pane_pid()
+    }
+
+    fn release(&self, job: &Job, close: bool) {
+        let Some(name) = &job.run_name else {
+            return;
+        };
+        let Some(target) = JobTarget::find(job.id, name) else {
+            // Gone already, and its pipe-pane with it
+            return;
+        };
+        if close {
+            // Killing the session or window also disables pipe-pane
+            tracing::info!(
+                "Auto-closing tmux target '{}' for job {}",
+                target.as_str(),
+                job.id
+            );
+            if let Err(e) = target.kill() {
+                tracing::warn!(
+                    "Failed to auto-close tmux target '{}': {}",
+                    target.as_str(),
+                    e
+                );
             }
-            session.enable_pipe_pane(&log_path)?;
-
-            session.try_send_command(&format!("cd {}", job.run_dir.display()))?;
-            session.try_send_command(&format!(
-                "export GFLOW_ARRAY_TASK_ID={}",
-                job.task_id.unwrap_or(0)
-            ))?;
-            if let Some(gpu_ids) = &job.gpu_ids {
-                session.try_send_command(&format!(
-                    "export CUDA_VISIBLE_DEVICES={}",
-                    gpu_ids
-                        .iter()
-                        .map(ToString::to_string)
-                        .collect::<Vec<_>>()
-                        .join(",")
-                ))?;
+        } else {
+            // Keep the session for inspection, but stop `cat >> logfile`
+            disable_pipe_pane_for_job(job.id, target.as_str(), false);
+        }
+    }
+}
+
+/// The job's command line, before wrapping it in `bash -c`.
+fn user_command(job: &Job, default_shell: &str) -> Result<String> {
+    let mut user_command = String::new();
+
+    if let Some(script) = &job.script {
+        if let Some(script_str) = script.to_str() {
+            if runs_directly(script) {
+                user_command.push_str(script_str);
+            } else {
+                user_command.push_str(&format!("{default_shell} {script_str}"));
             }
+        }
+    } else if let Some(cmd) = &job.command {
+        // Fill in parameters, `{job_id}` and `{run_name}`
+        let substituted = substitute_job_placeholders(job, cmd)?;
+        user_command.push_str(&substituted);
+    }
+
+    if job.environment == EnvSpec::Uv {
+        user_command.insert_str(0, "uv run ");
+    }
+    if job.interactive {
+        // Leaving the shell ends the job, whatever its last command returned.
+        user_command.push_str("; true");
+    }
+    Ok(user_command)
+}
+
+/// Environment variables every job runs with, then `extra`.
+fn job_env(
+    job: &Job,
+    job_tokens: Option<&TokenStore>,
+    extra: &[(String, String)],
+) -> Result<Vec<(String, String)>> {
+    let mut env = vec![(
+        "GFLOW_ARRAY_TASK_ID".to_string(),
+        job.task_id.unwrap_or(0).to_string(),
+    )];
+    if let Some(gpu_ids) = &job.gpu_ids {
+        env.push((
+            "CUDA_VISIBLE_DEVICES".to_string(),
+            gpu_ids
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(","),
+        ));
+    }
+    if let Some(dir) = &job.job_dir {
+        env.push(("GFLOW_JOB_DIR".to_string(), dir.display().to_string()));
+    }
+    if let Some(job_tokens) = job_tokens {
+        // Read by `gcancel --finish/--fail` in the wrapped command.
+        env.push(("GFLOW_JOB_TOKEN".to_string(), job_tokens.job_token(job.id)?));
+    }
+    // Read by the daemon after each GPU poll; a job without one just shows no progress.
+    match gflow::paths::prepare_progress_file_path(job.id) {
+        Ok(path) => env.push((
+            "GFLOW_PROGRESS_FILE".to_string(),
+            path.display().to_string(),
+        )),
+        Err(error) => tracing::warn!(job_id = job.id, %error, "No progress file for job"),
+    }
+    // Read alongside the progress file, and once more after the job ends.
+    match gflow::paths::prepare_artifacts_file_path(job.id) {
+        Ok(path) => env.push((
+            "GFLOW_ARTIFACTS_FILE".to_string(),
+            path.display().to_string(),
+        )),
+        Err(error) => tracing::warn!(job_id = job.id, %error, "No artifacts file for job"),
+    }
+    env.extend_from_slice(extra);
+    Ok(env)
+}
+
+/// Wrap the command in bash -c to ensure && and || operators work
+/// regardless of the user's default shell (fish, zsh, etc.)
+fn wrap_command(job_id: u32, user_command: &str) -> String {
+    // Use double quotes to avoid the ugly '\'' escaping pattern
+    // Need to escape: backslash, double-quote, dollar sign, backtick
+    let escaped_command = user_command
+        .replace('\\', r"\\")
+        .replace('"', r#"\""#)
+        .replace('$', r"\$")
+        .replace('`', r"\`");
+    format!(
+        r#"bash -c "{escaped_command} && gcancel --finish {job_id} || gcancel --fail {job_id}""#
+    )
+}
+
+/// Run `command` with `taskset` on the cores the scheduler assigned to the
+/// job, if any. Containers are pinned by their runtime instead.
+fn pin_to_cpus(job: &Job, command: String) -> String {
+    match job.cpu_ids.as_ref().filter(|ids| !ids.is_empty()) {
+        Some(cpu_ids) if job.container_image.is_none() => {
+            format!("taskset -c {} {command}", format_cpu_list(cpu_ids))
+        }
+        _ => command,
+    }
+}
+
+/// Run `command` in a cgroup of its own that enforces the job's limits, if
+/// the daemon manages cgroups. Containers are limited by their runtime.
+fn confine(cgroups: Option<&Cgroups>, job: &Job, command: String) -> String {
+    match cgroups {
+        Some(cgroups) if job.container_image.is_none() => match cgroups.create(job) {
+            Some(path) => join_command(&path, command),
+            None => command,
+        },
+        _ => command,
+    }
+}
+
+/// Runs each job with its backend (`[daemon] executor` unless the job picks
+/// one), inside a container when the job names an image.
+pub struct JobExecutor {
+    default_kind: ExecutorKind,
+    tmux: TmuxExecutor,
+    direct: DirectExecutor,
+    container: ContainerExecutor,
+    cgroups: Option<Arc<Cgroups>>,
+}
+
+impl JobExecutor {
+    pub fn new(
+        default_kind: ExecutorKind,
+        default_shell: &str,
+        job_tokens: Option<Arc<TokenStore>>,
+        container_config: &ContainerConfig,
+    ) -> Self {
+        let mut tmux = TmuxExecutor::new(default_shell);
+        let mut direct = DirectExecutor::new(default_shell);
+        if let Some(job_tokens) = job_tokens {
+            tmux = tmux.with_job_tokens(Arc::clone(&job_tokens));
+            direct = direct.with_job_tokens(job_tokens);
+        }
+        Self {
+            default_kind,
+            tmux,
+            direct,
+            container: ContainerExecutor::new(default_shell, container_config),
+            cgroups: None,
+        }
+    }
 
-            if let Some(conda_env) = &job.conda_env {
-                session.try_send_command(&format!("conda activate {conda_env}"))?;
+    /// Export `env` to every job, e.g. where `gcancel` finds the daemon.
+    pub fn with_env(mut self, env: Vec<(String, String)>) -> Self {
+        self.tmux = self.tmux.with_env(env.clone());
+        self.direct = self.direct.with_env(env);
+        self
+    }
+
+    /// Run tmux jobs in their own sessions or as windows of a shared one.
+    pub fn with_tmux_mode(mut self, mode: TmuxMode) -> Self {
+        self.tmux = self.tmux.with_mode(mode);
+        self
+    }
+
+    /// Run jobs in cgroups that enforce their memory and CPU limits.
+    pub(crate) fn with_cgroups(mut self, cgroups: Cgroups) -> Self {
+        let cgroups = Arc::new(cgroups);
+        self.tmux = self.tmux.with_cgroups(Arc::clone(&cgroups));
+        self.direct = self.direct.with_cgroups(Arc::clone(&cgroups));
+        self.cgroups = Some(cgroups);
+        self
+    }
+
+    fn backend(&self, job: &Job) -> &dyn Backend {
+        match job.executor.unwrap_or(self.default_kind) {
+            ExecutorKind::Tmux => &self.tmux,
+            ExecutorKind::Direct => &self.direct,
+        }
+    }
+}
+
+impl Executor for JobExecutor {
+    fn execute(&self, job: &Job) -> Result<()> {
+        if let Some(dir) = &job.job_dir {
+            // A job never starts without the directory it was promised.
+            fs::create_dir_all(dir.as_path())
+                .map_err(|e| anyhow!("Failed to create job directory {}: {e}", dir.display()))?;
+        }
+        match &job.container_image {
+            Some(image) => self.container.execute(self.backend(job), job, image),
+            None => self.backend(job).execute(job),
+        }
+    }
+
+    fn interrupt(&self, job: &Job) -> Result<()> {
+        self.backend(job).interrupt(job)
+    }
+
+    fn signal(&self, job: &Job, signal: JobSignal) -> Result<()> {
+        // `docker run` passes signals on to the container.
+        self.backend(job).signal(job, signal)
+    }
+
+    fn stop(&self, job: &Job) -> Result<()> {
+        let container = match job.container_image {
+            Some(_) => self.container.stop(job),
+            None => Ok(()),
+        };
+        self.backend(job).stop(job).and(container)
+    }
+
+    fn is_running(&self, job: &Job) -> Option<bool> {
+        match self.backend(job).is_running(job) {
+            Some(true) | None if job.container_image.is_some() => self.container.is_running(job),
+            running => running,
+        }
+    }
+
+    fn release(&self, job: &Job, close: bool) {
+        self.backend(job).release(job, close);
+        if let Some(cgroups) = &self.cgroups {
+            cgroups.remove(job.id);
+        }
+    }
+
+    fn pid(&self, job: &Job) -> Option<u32> {
+        // A container's processes are not descendants of the backend's.
+        match job.container_image {
+            Some(_) => None,
+            None => self.backend(job).pid(job),
+        }
+    }
+
+    fn memory_usage(&self, job: &Job) -> Option<MemoryUsage> {
+        self.cgroups.as_ref()?.usage(job.id)
+    }
+}
+
+/// Shell command that activates the job's environment, if it needs one.
+///
+/// Fails for a venv without an activation script, before any tmux session is
+/// created for the job.
+fn activation_command(environment: &EnvSpec) -> Result<Option<String>> {
+    match environment {
+        EnvSpec::None | EnvSpec::Uv => Ok(None),
+        EnvSpec::Conda(name) => Ok(Some(format!("conda activate {name}"))),
+        EnvSpec::Venv(venv) => {
+            let activate = EnvSpec::venv_activate_script(venv);
+            if !activate.is_file() {
+                bail!(
+                    "venv {} has no activation script at {}",
+                    venv.display(),
+                    activate.display()
+                );
             }
+            Ok(Some(format!(
+                "source {}",
+                shell_escape::escape(activate.to_string_lossy())
+            )))
+        }
+    }
+}
 
-            let wrapped_command = self.generate_wrapped_command(job)?;
-            session.try_send_command(&wrapped_command)?;
+/// Whether `script` can be exec'd, letting its shebang pick the interpreter.
+fn runs_directly(script: &Path) -> bool {
+    if !script.is_absolute() {
+        return false;
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let executable =
+            fs::metadata(script).is_ok_and(|metadata| metadata.permissions().mode() & 0o111 != 0);
+        if !executable {
+            return false;
         }
-        Ok(())
     }
+    let mut head = [0u8; 2];
+    fs::File::open(script)
+        .and_then(|mut file| file.read_exact(&mut head))
+        .is_ok_and(|()| head == *b"#!")
 }
 
 #[cfg(test)]
@@ -84,7 +491,7 @@ mod tests {
 
     #[test]
     fn test_generate_wrapped_command_basic() {
-        let executor = TmuxExecutor;
+        let executor = TmuxExecutor::default();
         let job = Job {
             id: 123,
             command: Some("echo hello".into()),
@@ -102,7 +509,7 @@ mod tests {
 
     #[test]
     fn test_generate_wrapped_command_with_quotes() {
-        let executor = TmuxExecutor;
+        let executor = TmuxExecutor::default();
         let job = Job {
             id: 456,
             command: Some("echo 'hello world'".into()),
@@ -121,7 +528,7 @@ mod tests {
 
     #[test]
     fn test_generate_wrapped_command_with_script() {
-        let executor = TmuxExecutor;
+        let executor = TmuxExecutor::default();
         let job = Job {
             id: 789,
             script: Some(Box::new(PathBuf::from("/tmp/script.sh"))),
@@ -137,9 +544,44 @@ mod tests {
         );
     }
 
+    #[test]
+    #[cfg(unix)]
+    fn test_generate_wrapped_command_execs_scripts_with_shebang() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let with_shebang = dir.path().join("train.py");
+        let without_shebang = dir.path().join("plain.sh");
+        fs::write(&with_shebang, "#!/usr/bin/env python3\nprint(1)\n").unwrap();
+        fs::write(&without_shebang, "echo plain\n").unwrap();
+        for path in [&with_shebang, &without_shebang] {
+            fs::set_permissions(path, fs::Permissions::from_mode(0o755)).unwrap();
+        }
+
+        let executor = TmuxExecutor::new("zsh");
+        let wrapped = |script: &Path| {
+            let job = Job {
+                id: 1,
+                script: Some(Box::new(script.to_path_buf())),
+                ..Default::default()
+            };
+            executor.generate_wrapped_command(&job).unwrap()
+        };
+
+        assert!(wrapped(&with_shebang)
+            .starts_with(&format!(r#"bash -c "{} &&"#, with_shebang.display())));
+        assert!(wrapped(&without_shebang)
+            .starts_with(&format!(r#"bash -c "zsh {} &&"#, without_shebang.display())));
+
+        // Without execute permission the shebang cannot be used.
+        fs::set_permissions(&with_shebang, fs::Permissions::from_mode(0o644)).unwrap();
+        assert!(wrapped(&with_shebang)
+            .starts_with(&format!(r#"bash -c "zsh {} &&"#, with_shebang.display())));
+    }
+
     #[test]
     fn test_generate_wrapped_command_with_special_chars() {
-        let executor = TmuxExecutor;
+        let executor = TmuxExecutor::default();
         let job = Job {
             id: 527,
             command: Some("lighteval vllm 'model_name=meta-llama/Llama-3.2-1B-Instruct,dtype=bfloat16' 'lighteval|gsm8k|5'".into()),
@@ -158,7 +600,7 @@ mod tests {
 
     #[test]
     fn test_generate_wrapped_command_with_double_quotes() {
-        let executor = TmuxExecutor;
+        let executor = TmuxExecutor::default();
         let job = Job {
             id: 100,
             command: Some(r#"echo "hello world""#.into()),
@@ -177,7 +619,7 @@ mod tests {
 
     #[test]
     fn test_generate_wrapped_command_with_dollar_sign() {
-        let executor = TmuxExecutor;
+        let executor = TmuxExecutor::default();
         let job = Job {
             id: 200,
             command: Some("echo $HOME".into()),
@@ -193,4 +635,71 @@ mod tests {
             r#"bash -c "echo \$HOME && gcancel --finish 200 || gcancel --fail 200""#
         );
     }
+
+    #[test]
+    fn test_generate_wrapped_command_with_uv() {
+        let executor = TmuxExecutor::default();
+        let job = Job {
+            id: 300,
+            command: Some("python train.py".into()),
+            environment: EnvSpec::Uv,
+            ..Default::default()
+        };
+
+        let wrapped = executor.generate_wrapped_command(&job).unwrap();
+        assert_eq!(
+            wrapped,
+            r#"bash -c "uv run python train.py && gcancel --finish 300 || gcancel --fail 300""#
+        );
+    }
+
+    #[test]
+    fn test_generate_wrapped_command_pins_assigned_cpus() {
+        let executor = TmuxExecutor::default();
+        let mut job = Job {
+            id: 301,
+            command: Some("python train.py".into()),
+            cpus: Some(3),
+            cpu_ids: Some([4, 5, 6].into_iter().collect()),
+            ..Default::default()
+        };
+
+        let wrapped = executor.generate_wrapped_command(&job).unwrap();
+        assert_eq!(
+            wrapped,
+            r#"taskset -c 4-6 bash -c "python train.py && gcancel --finish 301 || gcancel --fail 301""#
+        );
+
+        // Containers get `--cpuset-cpus` from the runtime instead.
+        job.container_image = Some("ubuntu:24.04".to_string());
+        assert!(!executor
+            .generate_wrapped_command(&job)
+            .unwrap()
+            .starts_with("taskset"));
+    }
+
+    #[test]
+    fn test_activation_command_per_environment() {
+        assert_eq!(activation_command(&EnvSpec::None).unwrap(), None);
+        assert_eq!(activation_command(&EnvSpec::Uv).unwrap(), None);
+        assert_eq!(
+            activation_command(&EnvSpec::Conda("ml".into())).unwrap(),
+            Some("conda activate ml".to_string())
+        );
+
+        let dir = tempfile::tempdir().unwrap();
+        let venv = dir.path().join("my venv");
+        let missing = activation_command(&EnvSpec::Venv(venv.clone())).unwrap_err();
+        assert!(
+            missing.to_string().contains("has no activation script"),
+            "{missing}"
+        );
+
+        fs::create_dir_all(venv.join("bin")).unwrap();
+        fs::write(venv.join("bin/activate"), "").unwrap();
+        assert_eq!(
+            activation_command(&EnvSpec::Venv(venv.clone())).unwrap(),
+            Some(format!("source '{}'", venv.join("bin/activate").display()))
+        );
+    }
 }
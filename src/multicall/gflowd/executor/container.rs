@@ -0,0 +1,327 @@
+//! Runs jobs inside a container image with docker or podman.
+//!
+//! The container is started by the job's backend (tmux or direct) like any
+//! other command, so logs, `gjob attach` and the `gcancel` outcome report keep
+//! working.
+
+use anyhow::{bail, Context, Result};
+use gflow::config::ContainerConfig;
+use gflow::core::cpu::format_cpu_list;
+use gflow::core::job::Job;
+use gflow::utils::substitute_job_placeholders;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+use super::{runs_directly, Backend};
+
+/// Where the job's run directory is mounted, and the container's working directory.
+const WORKSPACE: &str = "/workspace";
+/// Where a job script is mounted, read-only.
+const SCRIPT_DIR: &str = "/gflow";
+/// Seconds `stop` waits after SIGTERM before the runtime kills the container.
+const STOP_TIMEOUT_SECS: &str = "10";
+
+pub(super) struct ContainerExecutor {
+    /// Runs scripts that cannot be executed directly (`[daemon] default_shell`).
+    default_shell: String,
+    runtime: String,
+    mounts: Vec<String>,
+    extra_args: Vec<String>,
+}
+
+impl ContainerExecutor {
+    pub(super) fn new(default_shell: &str, config: &ContainerConfig) -> Self {
+        Self {
+            default_shell: default_shell.to_string(),
+            runtime: config.runtime.clone(),
+            mounts: config.mounts.clone(),
+            extra_args: config.extra_args.clone(),
+        }
+    }
+
+    /// `<runtime> run ...` for the job, as an argument list.
+    fn run_args(&self, job: &Job, image: &str, cid_file: &Path) -> Result<Vec<String>> {
+        let mut args = vec![
+            self.runtime.clone(),
+            "run".to_string(),
+            "--rm".to_string(),
+            "--name".to_string(),
+            container_name(job)?,
+            "--cidfile".to_string(),
+            cid_file.display().to_string(),
+            "--label".to_string(),
+            format!("gflow.job_id={}", job.id),
+        ];
+        if let Some(gpu_ids) = job.gpu_ids.as_ref().filter(|ids| !ids.is_empty()) {
+            let devices = gpu_ids
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(",");
+            // The inner quotes keep the runtime from splitting the device list.
+            args.extend(["--gpus".to_string(), format!("\"device={devices}\"")]);
+        }
+        if let Some(cpu_ids) = job.cpu_ids.as_ref().filter(|ids| !ids.is_empty()) {
+            args.extend(["--cpuset-cpus".to_string(), format_cpu_list(cpu_ids)]);
+        }
+        args.extend([
+            "-v".to_string(),
+            format!("{}:{WORKSPACE}", job.run_dir.display()),
+            "-w".to_string(),
+            WORKSPACE.to_string(),
+            "-e".to_string(),
+            format!("GFLOW_ARRAY_TASK_ID={}", job.task_id.unwrap_or(0)),
+        ]);
+        if let Some(dir) = &job.job_dir {
+            // Mounted where it is on the host, so the path reads the same.
+            let dir = dir.display();
+            args.extend([
+                "-v".to_string(),
+                format!("{dir}:{dir}"),
+                "-e".to_string(),
+                format!("GFLOW_JOB_DIR={dir}"),
+            ]);
+        }
+        for mount in &self.mounts {
+            args.extend(["-v".to_string(), mount.clone()]);
+        }
+
+        let shell = self.default_shell.clone();
+        let command = if let Some(script) = &job.script {
+            let file_name = script
+                .file_name()
+                .with_context(|| format!("Invalid script path {}", script.display()))?;
+            let target = Path::new(SCRIPT_DIR).join(file_name).display().to_string();
+            args.extend([
+                "-v".to_string(),
+                format!("{}:{target}:ro", script.display()),
+            ]);
+            if runs_directly(script) {
+                vec![target]
+            } else {
+                vec![shell, target]
+            }
+        } else if let Some(command) = &job.command {
+            let substituted = substitute_job_placeholders(job, command)?;
+            vec![shell, "-c".to_string(), substituted]
+        } else {
+            bail!("Job {} has neither a script nor a command", job.id);
+        };
+
+        args.extend(self.extra_args.iter().cloned());
+        args.push(image.to_string());
+        args.extend(command);
+        Ok(args)
+    }
+
+    fn runtime_command(&self, args: &[&str]) -> Result<Output> {
+        Command::new(&self.runtime)
+            .args(args)
+            .output()
+            .with_context(|| format!("Failed to run `{}`", self.runtime))
+    }
+
+    /// Start the job's container through `backend`.
+    pub(super) fn execute(&self, backend: &dyn Backend, job: &Job, image: &str) -> Result<()> {
+        if job.run_name.is_none() {
+            return Ok(());
+        }
+        if !job.environment.is_none() {
+            bail!(
+                "Environment {} cannot be combined with a container image",
+                job.environment
+            );
+        }
+
+        let cid_file = cid_file_path(job.id)?;
+        if let Some(parent) = cid_file.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        // The runtime refuses to start if the file is left over from an earlier run.
+        match fs::remove_file(&cid_file) {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e).context("Failed to remove stale container ID file"),
+        }
+
+        let user_command = self
+            .run_args(job, image, &cid_file)?
+            .iter()
+            .map(|arg| shell_escape::escape(arg.into()))
+            .collect::<Vec<_>>()
+            .join(" ");
+        backend.launch(job, None, &user_command)
+    }
+
+    pub(super) fn stop(&self, job: &Job) -> Result<()> {
+        let name = container_name(job)?;
+        let output = self.runtime_command(&["stop", "--time", STOP_TIMEOUT_SECS, &name])?;
+        if !output.status.success() && !is_missing_container(&output) {
+            bail!(
+                "`{} stop {name}` failed: {}",
+                self.runtime,
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+        if let Ok(cid_file) = cid_file_path(job.id) {
+            fs::remove_file(cid_file).ok();
+        }
+        Ok(())
+    }
+
+    pub(super) fn is_running(&self, job: &Job) -> Option<bool> {
+        // The runtime writes the ID file once the container exists. Until then
+        // the image may still be pulling, which can take minutes.
+        if !cid_file_path(job.id).ok()?.exists() {
+            return None;
+        }
+        let name = container_name(job).ok()?;
+        let output = self
+            .runtime_command(&["inspect", "--format", "{{.State.Running}}", &name])
+            .ok()?;
+        if output.status.success() {
+            Some(String::from_utf8_lossy(&output.stdout).trim() == "true")
+        } else if is_missing_container(&output) {
+            Some(false)
+        } else {
+            None
+        }
+    }
+}
+
+fn container_name(job: &Job) -> Result<String> {
+    let run_name = job
+        .run_name
+        .as_ref()
+        .context("Job has no run name to name its container")?;
+    Ok(format!("gflow-{run_name}"))
+}
+
+fn cid_file_path(job_id: u32) -> Result<PathBuf> {
+    Ok(gflow::paths::get_runtime_dir()?
+        .join("containers")
+        .join(format!("{job_id}.cid")))
+}
+
+/// docker says "No such container"/"No such object", podman "no such container".
+fn is_missing_container(output: &Output) -> bool {
+    String::from_utf8_lossy(&output.stderr)
+        .to_lowercase()
+        .contains("no such")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::TmuxExecutor;
+    use super::*;
+    use gflow::core::job::JobBuilder;
+
+    fn executor() -> ContainerExecutor {
+        ContainerExecutor::new(
+            "bash",
+            &ContainerConfig {
+                runtime: "podman".to_string(),
+                mounts: vec!["/data:/data:ro".to_string()],
+                extra_args: vec!["--shm-size=8g".to_string()],
+            },
+        )
+    }
+
+    #[test]
+    fn run_args_for_command_job() {
+        let mut job = JobBuilder::new()
+            .command("python train.py --lr {lr}")
+            .parameters([("lr".to_string(), "0.1".to_string())].into())
+            .run_dir("/home/alice/proj")
+            .run_name(Some("gjob-7".to_string()))
+            .container_image(Some("nvcr.io/nvidia/pytorch:24.01".to_string()))
+            .build();
+        job.id = 7;
+        job.gpu_ids = Some([2, 3].into_iter().collect());
+        job.cpu_ids = Some([8, 9, 10, 11].into_iter().collect());
+        job.job_dir = Some(Box::new("/var/lib/gflow/jobs/7".into()));
+
+        let args = executor()
+            .run_args(
+                &job,
+                "nvcr.io/nvidia/pytorch:24.01",
+                Path::new("/run/7.cid"),
+            )
+            .unwrap();
+        assert_eq!(
+            args,
+            [
+                "podman",
+                "run",
+                "--rm",
+                "--name",
+                "gflow-gjob-7",
+                "--cidfile",
+                "/run/7.cid",
+                "--label",
+                "gflow.job_id=7",
+                "--gpus",
+                "\"device=2,3\"",
+                "--cpuset-cpus",
+                "8-11",
+                "-v",
+                "/home/alice/proj:/workspace",
+                "-w",
+                "/workspace",
+                "-e",
+                "GFLOW_ARRAY_TASK_ID=0",
+                "-v",
+                "/var/lib/gflow/jobs/7:/var/lib/gflow/jobs/7",
+                "-e",
+                "GFLOW_JOB_DIR=/var/lib/gflow/jobs/7",
+                "-v",
+                "/data:/data:ro",
+                "--shm-size=8g",
+                "nvcr.io/nvidia/pytorch:24.01",
+                "bash",
+                "-c",
+                "python train.py --lr 0.1",
+            ]
+        );
+    }
+
+    #[test]
+    fn run_args_mount_scripts_read_only() {
+        let job = JobBuilder::new()
+            .script("/home/alice/proj/train.sh")
+            .run_dir("/home/alice/proj")
+            .run_name(Some("gjob-8".to_string()))
+            .build();
+
+        let args = executor()
+            .run_args(&job, "alpine", Path::new("/run/8.cid"))
+            .unwrap();
+        assert!(!args.iter().any(|arg| arg == "--gpus"));
+        let mount = args
+            .iter()
+            .position(|arg| arg == "/home/alice/proj/train.sh:/gflow/train.sh:ro")
+            .expect("script should be mounted");
+        assert_eq!(args[mount - 1], "-v");
+        // The script does not exist here, so it runs through the default shell.
+        assert_eq!(
+            args[args.len() - 3..],
+            ["alpine", "bash", "/gflow/train.sh"]
+        );
+    }
+
+    #[test]
+    fn environments_are_refused() {
+        let job = JobBuilder::new()
+            .command("python train.py")
+            .run_name(Some("gjob-9".to_string()))
+            .conda_env(Some("ml".to_string()))
+            .container_image(Some("alpine".to_string()))
+            .build();
+
+        let err = executor()
+            .execute(&TmuxExecutor::default(), &job, "alpine")
+            .unwrap_err();
+        assert!(err.to_string().contains("cannot be combined"), "{err}");
+    }
+}
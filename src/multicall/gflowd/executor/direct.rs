@@ -0,0 +1,253 @@
+//! Runs jobs as child processes of the daemon, for hosts without tmux.
+//!
+//! Each job gets its own process group, so signals reach everything it started.
+//! Output goes to the job's log file, and the PID is kept in a file so that a
+//! restarted daemon can still tell whether the job is alive.
+
+use anyhow::{bail, Context, Result};
+use gflow::core::{
+    executor::Executor,
+    job::{EnvSpec, Job, JobSignal},
+};
+use std::ffi::OsString;
+use std::fs::{self, OpenOptions};
+use std::os::unix::process::CommandExt;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use super::super::cgroup::Cgroups;
+use super::super::pidfile::{is_process_running, read_pid};
+use super::super::tokens::TokenStore;
+use super::{
+    activation_command, confine, job_env, pin_to_cpus, user_command, wrap_command, Backend,
+};
+
+/// How long `stop` waits for an interrupted job before sending SIGTERM.
+const STOP_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Clone)]
+pub(super) struct DirectExecutor {
+    /// Runs scripts that cannot be executed directly (`[daemon] default_shell`).
+    default_shell: String,
+    /// Set when the daemon requires auth, so jobs can report their own outcome.
+    job_tokens: Option<Arc<TokenStore>>,
+    /// Exported to every job after the usual variables.
+    env: Vec<(String, String)>,
+    /// Set with `[daemon] enforce_limits`, to run each job in its own cgroup.
+    cgroups: Option<Arc<Cgroups>>,
+}
+
+impl DirectExecutor {
+    pub(super) fn new(default_shell: impl Into<String>) -> Self {
+        Self {
+            default_shell: default_shell.into(),
+            job_tokens: None,
+            env: Vec::new(),
+            cgroups: None,
+        }
+    }
+
+    pub(super) fn with_job_tokens(mut self, job_tokens: Arc<TokenStore>) -> Self {
+        self.job_tokens = Some(job_tokens);
+        self
+    }
+
+    pub(super) fn with_env(mut self, env: Vec<(String, String)>) -> Self {
+        self.env = env;
+        self
+    }
+
+    pub(super) fn with_cgroups(mut self, cgroups: Arc<Cgroups>) -> Self {
+        self.cgroups = Some(cgroups);
+        self
+    }
+}
+
+impl Backend for DirectExecutor {
+    fn launch(&self, job: &Job, activation: Option<String>, user_command: &str) -> Result<()> {
+        let log_path = gflow::paths::prepare_log_file_path(job.id)?;
+        if let Some(parent) = log_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let log = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&log_path)
+            .with_context(|| format!("Failed to open log file {}", log_path.display()))?;
+
+        let mut script = String::new();
+        if matches!(job.environment, EnvSpec::Conda(_)) {
+            // A non-interactive shell has not run `conda init`'s hook.
+            script.push_str("eval \"$(conda shell.bash hook)\"\n");
+        }
+        if let Some(activation) = activation {
+            script.push_str(&activation);
+            script.push('\n');
+        }
+        script.push_str(&confine(
+            self.cgroups.as_deref(),
+            job,
+            pin_to_cpus(job, wrap_command(job.id, user_command)),
+        ));
+
+        let mut child = Command::new("bash")
+            .arg("-c")
+            .arg(script)
+            .current_dir(&job.run_dir)
+            .envs(job_env(job, self.job_tokens.as_deref(), &self.env)?)
+            .envs(job_path().map(|path| ("PATH", path)))
+            .stdin(Stdio::null())
+            .stdout(log.try_clone()?)
+            .stderr(log)
+            .process_group(0)
+            .spawn()
+            .with_context(|| {
+                format!(
+                    "Failed to start job {} in {}",
+                    job.id,
+                    job.run_dir.display()
+                )
+            })?;
+        let pid = child.id();
+        // Reap the child when it exits, so it does not linger as a zombie process.
+        std::thread::spawn(move || child.wait());
+
+        if let Err(error) = write_pid_file(job.id, pid) {
+            // An untracked job could neither be cancelled nor checked.
+            signal_group(pid, libc::SIGKILL).ok();
+            return Err(error);
+        }
+        Ok(())
+    }
+}
+
+impl Executor for DirectExecutor {
+    fn execute(&self, job: &Job) -> Result<()> {
+        let activation = activation_command(&job.environment)?;
+        self.launch(job, activation, &user_command(job, &self.default_shell)?)
+    }
+
+    fn interrupt(&self, job: &Job) -> Result<()> {
+        match job_pid(job.id) {
+            Some(pid) => signal_group(pid, libc::SIGINT),
+            None => Ok(()),
+        }
+    }
+
+    fn signal(&self, job: &Job, signal: JobSignal) -> Result<()> {
+        match job_pid(job.id) {
+            Some(pid) if group_exists(pid) => signal_group(pid, signal.number()),
+            _ => bail!("Job {} has no process left to signal", job.id),
+        }
+    }
+
+    fn stop(&self, job: &Job) -> Result<()> {
+        let Some(pid) = job_pid(job.id) else {
+            return Ok(());
+        };
+        let deadline = Instant::now() + STOP_TIMEOUT;
+        while group_exists(pid) {
+            if Instant::now() >= deadline {
+                tracing::info!(job_id = job.id, pid, "Job ignored SIGINT, sending SIGTERM");
+                return signal_group(pid, libc::SIGTERM);
+            }
+            std::thread::sleep(Duration::from_millis(200));
+        }
+        Ok(())
+    }
+
+    fn is_running(&self, job: &Job) -> Option<bool> {
+        Some(job_pid(job.id).is_some_and(is_process_running))
+    }
+
+    fn pid(&self, job: &Job) -> Option<u32> {
+        job_pid(job.id).filter(|&pid| is_process_running(pid))
+    }
+
+    fn release(&self, job: &Job, _close: bool) {
+        // Keep the PID while `stop` may still need to signal the group.
+        if job_pid(job.id).is_some_and(|pid| !group_exists(pid)) {
+            if let Ok(path) = pid_file_path(job.id) {
+                fs::remove_file(path).ok();
+            }
+        }
+    }
+}
+
+/// `PATH` with the daemon's own directory appended, so jobs find the `gcancel`
+/// installed next to it even under a minimal `PATH` (e.g. a systemd unit).
+fn job_path() -> Option<OsString> {
+    let exe_dir = std::env::current_exe().ok()?.parent()?.to_path_buf();
+    let mut paths: Vec<PathBuf> = std::env::var_os("PATH")
+        .map(|path| std::env::split_paths(&path).collect())
+        .unwrap_or_default();
+    if !paths.contains(&exe_dir) {
+        paths.push(exe_dir);
+    }
+    std::env::join_paths(paths).ok()
+}
+
+fn pid_file_path(job_id: u32) -> Result<PathBuf> {
+    Ok(gflow::paths::get_runtime_dir()?
+        .join("direct")
+        .join(format!("{job_id}.pid")))
+}
+
+fn write_pid_file(job_id: u32, pid: u32) -> Result<()> {
+    let path = pid_file_path(job_id)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(&path, pid.to_string())
+        .with_context(|| format!("Failed to write PID file {}", path.display()))
+}
+
+fn job_pid(job_id: u32) -> Option<u32> {
+    read_pid(&pid_file_path(job_id).ok()?)
+}
+
+/// Whether any process is left in the group the job leads.
+fn group_exists(pgid: u32) -> bool {
+    unsafe { libc::kill(-(pgid as libc::pid_t), 0) == 0 }
+}
+
+/// Send `signal` to the job's process group; a group that is gone is not an error.
+pub(super) fn signal_group(pgid: u32, signal: libc::c_int) -> Result<()> {
+    if unsafe { libc::kill(-(pgid as libc::pid_t), signal) } == 0 {
+        return Ok(());
+    }
+    let error = std::io::Error::last_os_error();
+    if error.raw_os_error() == Some(libc::ESRCH) {
+        return Ok(());
+    }
+    Err(error).with_context(|| format!("Failed to signal process group {pgid}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn signals_reach_the_whole_process_group() {
+        let mut child = Command::new("bash")
+            .args(["-c", "sleep 30 & wait"])
+            .process_group(0)
+            .spawn()
+            .unwrap();
+        let pgid = child.id();
+        assert!(group_exists(pgid));
+
+        signal_group(pgid, libc::SIGTERM).unwrap();
+        child.wait().unwrap();
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while group_exists(pgid) && Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(50));
+        }
+        assert!(!group_exists(pgid), "background sleep should be gone too");
+
+        // Signalling a group that no longer exists is fine.
+        signal_group(pgid, libc::SIGINT).unwrap();
+    }
+}
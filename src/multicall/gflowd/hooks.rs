@@ -0,0 +1,225 @@
+//! Admin hooks run around a job's life
+//!
+//! `[hooks] pre_submit` vets (and may rewrite) every submission before it is
+//! queued; `post_complete` hears about every job that reaches a final state,
+//! e.g. for accounting. Both get the job as JSON on stdin and are killed once
+//! `timeout_secs` runs out, so a hung script cannot hold up the API.
+
+use gflow::config::HooksConfig;
+use gflow::core::api_error::ApiError;
+use gflow::core::job::Job;
+use std::path::{Path, PathBuf};
+use std::process::{Output, Stdio};
+use std::time::{Duration, Instant};
+use tokio::io::AsyncWriteExt;
+use tracing::Instrument;
+
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Hooks {
+    pre_submit: Option<PathBuf>,
+    post_complete: Option<PathBuf>,
+    timeout: Duration,
+}
+
+impl Hooks {
+    pub(crate) fn new(config: &HooksConfig) -> Self {
+        Self {
+            pre_submit: config.pre_submit.clone(),
+            post_complete: config.post_complete.clone(),
+            timeout: Duration::from_secs(config.timeout_secs.max(1)),
+        }
+    }
+
+    /// Run the `pre_submit` hook on a job about to be queued. Returns the job
+    /// to submit: the hook's replacement if it printed one, else `job`.
+    pub(crate) async fn pre_submit(&self, job: Job) -> Result<Job, ApiError> {
+        let Some(path) = &self.pre_submit else {
+            return Ok(job);
+        };
+        let input = serde_json::to_vec(&job)
+            .map_err(|e| ApiError::internal(format!("Failed to encode job for hook: {e}")))?;
+
+        let started = Instant::now();
+        let output = run(path, &input, self.timeout).await.map_err(|e| {
+            tracing::error!(hook = %path.display(), error = %e, "pre_submit hook failed");
+            ApiError::internal(format!("pre_submit hook failed: {e}"))
+        })?;
+        let elapsed_ms = started.elapsed().as_millis() as u64;
+
+        if !output.status.success() {
+            let reason = message(&output);
+            tracing::info!(
+                hook = %path.display(),
+                decision = "rejected",
+                status = %output.status,
+                elapsed_ms,
+                %reason,
+                "pre_submit hook decision"
+            );
+            return Err(ApiError::invalid_request(reason));
+        }
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        if stdout.trim().is_empty() {
+            tracing::info!(
+                hook = %path.display(),
+                decision = "accepted",
+                elapsed_ms,
+                "pre_submit hook decision"
+            );
+            return Ok(job);
+        }
+        let replacement: Job = serde_json::from_str(&stdout).map_err(|e| {
+            tracing::error!(hook = %path.display(), error = %e, "pre_submit hook printed an invalid job");
+            ApiError::internal(format!("pre_submit hook printed an invalid job: {e}"))
+        })?;
+        tracing::info!(
+            hook = %path.display(),
+            decision = "modified",
+            elapsed_ms,
+            "pre_submit hook decision"
+        );
+        Ok(replacement)
+    }
+
+    /// Hand a job that reached a final state to the `post_complete` hook. It
+    /// runs in the background; failures are only logged.
+    pub(crate) fn post_complete(&self, job: &Job) {
+        let Some(path) = self.post_complete.clone() else {
+            return;
+        };
+        let job_id = job.id;
+        let input = match serde_json::to_vec(job) {
+            Ok(input) => input,
+            Err(e) => {
+                tracing::error!(job_id, error = %e, "Failed to encode job for post_complete hook");
+                return;
+            }
+        };
+        let timeout = self.timeout;
+        let span = tracing::info_span!("post_complete_hook", job_id, state = %job.state);
+        tokio::spawn(
+            async move {
+                match run(&path, &input, timeout).await {
+                    Ok(output) if output.status.success() => {
+                        tracing::info!(hook = %path.display(), "post_complete hook ran");
+                    }
+                    Ok(output) => tracing::warn!(
+                        hook = %path.display(),
+                        status = %output.status,
+                        output = %message(&output),
+                        "post_complete hook failed"
+                    ),
+                    Err(e) => tracing::warn!(
+                        hook = %path.display(),
+                        error = %e,
+                        "post_complete hook failed"
+                    ),
+                }
+            }
+            .instrument(span),
+        );
+    }
+}
+
+/// Run a hook with `input` on stdin, killing it if it outlives `timeout`.
+async fn run(path: &Path, input: &[u8], timeout: Duration) -> anyhow::Result<Output> {
+    let mut child = tokio::process::Command::new(path)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| anyhow::anyhow!("cannot run '{}': {e}", path.display()))?;
+    let mut stdin = child.stdin.take().expect("stdin is piped");
+    let write = async move {
+        // A hook may exit without reading its input; that is not an error.
+        stdin.write_all(input).await.ok();
+    };
+    match tokio::time::timeout(timeout, async {
+        tokio::join!(write, child.wait_with_output()).1
+    })
+    .await
+    {
+        Ok(output) => Ok(output?),
+        Err(_) => anyhow::bail!("timed out after {}s", timeout.as_secs()),
+    }
+}
+
+/// What a failed hook said: its stderr, else its stdout.
+fn message(output: &Output) -> String {
+    let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+    if !stderr.is_empty() {
+        return stderr;
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if !stdout.is_empty() {
+        return stdout;
+    }
+    format!("Hook exited with {}", output.status)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gflow::core::job::JobBuilder;
+    use std::os::unix::fs::PermissionsExt;
+
+    fn hooks_with(dir: &Path, script: &str, timeout_secs: u64) -> Hooks {
+        let path = dir.join("pre_submit.sh");
+        std::fs::write(&path, format!("#!/bin/sh\n{script}\n")).unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+        Hooks::new(&HooksConfig {
+            pre_submit: Some(path),
+            post_complete: None,
+            timeout_secs,
+        })
+    }
+
+    fn job() -> Job {
+        JobBuilder::new()
+            .submitted_by("alice")
+            .command("python train.py")
+            .build()
+    }
+
+    #[tokio::test]
+    async fn accepts_jobs_when_the_hook_prints_nothing() {
+        let dir = tempfile::tempdir().unwrap();
+        let hooks = hooks_with(dir.path(), "cat > /dev/null", 5);
+        let accepted = hooks.pre_submit(job()).await.unwrap();
+        assert_eq!(accepted.command.as_deref(), Some("python train.py"));
+    }
+
+    #[tokio::test]
+    async fn replaces_jobs_with_the_hook_output() {
+        let dir = tempfile::tempdir().unwrap();
+        let hooks = hooks_with(dir.path(), r#"sed 's/"priority":[0-9]*/"priority":3/'"#, 5);
+        let modified = hooks.pre_submit(job()).await.unwrap();
+        assert_eq!(modified.priority, 3);
+        assert_eq!(modified.command.as_deref(), Some("python train.py"));
+    }
+
+    #[tokio::test]
+    async fn rejects_jobs_with_the_hook_message() {
+        let dir = tempfile::tempdir().unwrap();
+        let hooks = hooks_with(
+            dir.path(),
+            "echo 'jobs must set a time limit' >&2\nexit 1",
+            5,
+        );
+        let error = hooks.pre_submit(job()).await.unwrap_err();
+        assert_eq!(error.status(), 400);
+        assert_eq!(error.message, "jobs must set a time limit");
+    }
+
+    #[tokio::test]
+    async fn kills_hooks_that_run_too_long() {
+        let dir = tempfile::tempdir().unwrap();
+        let hooks = hooks_with(dir.path(), "sleep 30", 1);
+        let started = Instant::now();
+        let error = hooks.pre_submit(job()).await.unwrap_err();
+        assert!(started.elapsed() < Duration::from_secs(10));
+        assert!(error.message.contains("timed out"), "{}", error.message);
+    }
+}
@@ -0,0 +1,226 @@
+//! Exclusive lock on the data directory
+//!
+//! Two daemons on one data directory would both write its state and run the
+//! same jobs, so the daemon holds an advisory lock on `gflowd.lock` there for
+//! as long as it runs, with its PID written in the file. The file is emptied on
+//! a clean exit; the kernel drops the lock of a daemon that crashed, and the
+//! PID it left behind tells the next one so.
+
+use anyhow::{anyhow, bail, Context, Result};
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use super::pidfile::{is_process_running, read_pid};
+
+const LOCK_FILE: &str = "gflowd.lock";
+
+/// How often a waiting daemon tries the lock again.
+const RETRY_INTERVAL: Duration = Duration::from_millis(100);
+
+/// How long `--takeover` waits for the old daemon to exit.
+const TAKEOVER_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// How a daemon that finds the lock held goes on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Mode {
+    /// Exit with an error naming the daemon holding it
+    Refuse,
+    /// Ask that daemon to shut down and take the lock once it has (`--takeover`)
+    Takeover,
+    /// Start anyway and take the lock once the old instance exits (`gflowd reload`)
+    AfterReload,
+}
+
+/// A held `gflowd.lock`; dropping it empties the file and releases the lock.
+struct LockFile {
+    file: File,
+}
+
+impl LockFile {
+    /// Take the lock in `dir`, or return the PID recorded by the daemon
+    /// holding it.
+    fn try_acquire(dir: &Path) -> Result<std::result::Result<Self, Option<u32>>> {
+        fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+        let path = lock_path(dir);
+        let mut file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(&path)
+            .with_context(|| format!("Failed to open {}", path.display()))?;
+        match file.try_lock() {
+            Ok(()) => {}
+            Err(fs::TryLockError::WouldBlock) => return Ok(Err(read_pid(&path))),
+            Err(fs::TryLockError::Error(e)) => {
+                return Err(e).with_context(|| format!("Failed to lock {}", path.display()))
+            }
+        }
+
+        if let Some(pid) = read_pid(&path).filter(|&pid| pid != std::process::id()) {
+            tracing::warn!(
+                "The gflowd with PID {pid} did not shut down cleanly; taking over {}",
+                dir.display()
+            );
+        }
+        file.set_len(0)
+            .and_then(|()| writeln!(file, "{}", std::process::id()))
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        Ok(Ok(Self { file }))
+    }
+}
+
+impl Drop for LockFile {
+    fn drop(&mut self) {
+        let _ = self.file.set_len(0);
+    }
+}
+
+/// The data directory lock for the daemon's lifetime. After a reload it is
+/// taken in the background once the old instance lets go.
+pub(crate) struct InstanceLock {
+    held: Arc<Mutex<Option<LockFile>>>,
+    waiter: Option<tokio::task::JoinHandle<()>>,
+}
+
+impl Drop for InstanceLock {
+    fn drop(&mut self) {
+        if let Some(waiter) = self.waiter.take() {
+            waiter.abort();
+        }
+        if let Ok(mut held) = self.held.lock() {
+            held.take();
+        }
+    }
+}
+
+fn lock_path(dir: &Path) -> PathBuf {
+    dir.join(LOCK_FILE)
+}
+
+/// Take the lock on `dir` for this daemon, going on as `mode` says when
+/// another daemon holds it.
+pub(crate) async fn acquire(
+    dir: &Path,
+    config: &gflow::config::Config,
+    mode: Mode,
+) -> Result<InstanceLock> {
+    let holder = match LockFile::try_acquire(dir)? {
+        Ok(lock) => return Ok(held(lock)),
+        Err(holder) => holder,
+    };
+
+    match mode {
+        Mode::Refuse => {
+            let holder = holder.map_or_else(|| "Another gflowd".to_string(), describe);
+            bail!(
+                "{holder} is already running with data directory {}; stop it with `gflowd down`, or pass --takeover to replace it",
+                dir.display()
+            )
+        }
+        Mode::AfterReload => {
+            tracing::info!("Waiting for the old instance to release {}", dir.display());
+            let slot = Arc::new(Mutex::new(None));
+            let waiter = tokio::spawn({
+                let slot = Arc::clone(&slot);
+                let dir = dir.to_path_buf();
+                async move {
+                    match wait_for(&dir, None).await {
+                        Ok(lock) => {
+                            tracing::info!("Took over the lock on {}", dir.display());
+                            if let Ok(mut slot) = slot.lock() {
+                                *slot = lock;
+                            }
+                        }
+                        Err(e) => tracing::error!("Failed to take over the lock: {e:#}"),
+                    }
+                }
+            });
+            Ok(InstanceLock {
+                held: slot,
+                waiter: Some(waiter),
+            })
+        }
+        Mode::Takeover => {
+            // Ask whoever serves the configured address, which is the daemon
+            // holding the lock unless it moved; the lock file is the fallback.
+            let health_pid = match gflow::client::Client::build(config) {
+                Ok(client) => client.get_health_with_pid().await.ok().flatten(),
+                Err(_) => None,
+            };
+            let pid = health_pid.or(holder).ok_or_else(|| {
+                anyhow!(
+                    "Cannot tell which gflowd holds {}; stop it and start again",
+                    lock_path(dir).display()
+                )
+            })?;
+            eprintln!("Asking {} to shut down...", describe(pid));
+            if is_process_running(pid) {
+                unsafe {
+                    libc::kill(pid as libc::pid_t, libc::SIGUSR2);
+                }
+            }
+            match wait_for(dir, Some(TAKEOVER_TIMEOUT)).await? {
+                Some(lock) => Ok(held(lock)),
+                None => bail!(
+                    "{} did not exit within {}s; it still holds {}",
+                    describe(pid),
+                    TAKEOVER_TIMEOUT.as_secs(),
+                    lock_path(dir).display()
+                ),
+            }
+        }
+    }
+}
+
+fn held(lock: LockFile) -> InstanceLock {
+    InstanceLock {
+        held: Arc::new(Mutex::new(Some(lock))),
+        waiter: None,
+    }
+}
+
+fn describe(pid: u32) -> String {
+    format!("gflowd (PID {pid})")
+}
+
+/// Try the lock until it is free, or `timeout` runs out.
+async fn wait_for(dir: &Path, timeout: Option<Duration>) -> Result<Option<LockFile>> {
+    let deadline = timeout.map(|timeout| tokio::time::Instant::now() + timeout);
+    loop {
+        if let Ok(lock) = LockFile::try_acquire(dir)? {
+            return Ok(Some(lock));
+        }
+        if deadline.is_some_and(|deadline| tokio::time::Instant::now() >= deadline) {
+            return Ok(None);
+        }
+        tokio::time::sleep(RETRY_INTERVAL).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_lock_is_exclusive_and_left_empty_on_release() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = lock_path(dir.path());
+
+        let lock = LockFile::try_acquire(dir.path()).unwrap().ok().unwrap();
+        assert_eq!(read_pid(&path), Some(std::process::id()));
+        let holder = LockFile::try_acquire(dir.path()).unwrap().err();
+        assert_eq!(holder, Some(Some(std::process::id())));
+
+        drop(lock);
+        assert_eq!(fs::read_to_string(&path).unwrap(), "");
+        // A PID left by a crashed daemon does not keep the lock.
+        fs::write(&path, "4194304\n").unwrap();
+        let lock = LockFile::try_acquire(dir.path()).unwrap().ok().unwrap();
+        assert_eq!(read_pid(&path), Some(std::process::id()));
+        drop(lock);
+    }
+}
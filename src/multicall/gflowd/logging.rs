@@ -0,0 +1,454 @@
+//! Where gflowd's logs go, set by `[daemon.logging]`
+//!
+//! Every sink gets the records the level filter lets through. The filter sits
+//! behind a reload handle, so `gctl log-level` can change the default level of
+//! a running daemon; the per-module levels from the config stay in place.
+
+use anyhow::Context as _;
+use clap_verbosity_flag::Verbosity;
+use gflow::client::LogLevel;
+use gflow::config::{LogFileFormat, LogSink, LoggingConfig};
+use std::collections::BTreeMap;
+use std::os::unix::net::UnixDatagram;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use tracing::field::{Field, Visit};
+use tracing::{Event, Level, Subscriber};
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_subscriber::filter::{Directive, EnvFilter, LevelFilter};
+use tracing_subscriber::layer::{Context, Layered, SubscriberExt};
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{reload, Layer, Registry};
+
+const JOURNALD_SOCKET: &str = "/run/systemd/journal/socket";
+const SYSLOG_SOCKET: &str = "/dev/log";
+
+/// Name records are logged under in journald and syslog.
+const IDENTIFIER: &str = "gflowd";
+
+type Filtered = Layered<reload::Layer<EnvFilter, Registry>, Registry>;
+
+static LEVELS: OnceLock<LogLevels> = OnceLock::new();
+
+/// The levels of the installed subscriber, once [`init`] has run.
+pub(crate) fn levels() -> Option<&'static LogLevels> {
+    LEVELS.get()
+}
+
+/// Install the subscriber for the sinks in `config`. The guard flushes the
+/// log file when dropped, so keep it until exit.
+pub(crate) fn init(
+    verbosity: Verbosity,
+    config: &LoggingConfig,
+) -> anyhow::Result<Option<WorkerGuard>> {
+    let level = match &config.level {
+        Some(level) if !verbosity.is_present() => configured_level(level)?,
+        _ => LevelFilter::from(verbosity),
+    };
+    let (filter, levels) = LogLevels::new(level, &config.levels)?;
+
+    let mut guard = None;
+    let mut sinks = Vec::new();
+    for &kind in &config.sinks {
+        sinks.push(sink(kind, config.file_format, &mut guard)?);
+    }
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(sinks)
+        .init();
+    let _ = LEVELS.set(levels);
+    Ok(guard)
+}
+
+/// Check `config` as [`init`] would, so `gflowd up` reports problems before
+/// the daemon starts.
+pub(crate) fn check(config: &LoggingConfig) -> anyhow::Result<()> {
+    if let Some(level) = &config.level {
+        configured_level(level)?;
+    }
+    LogLevels::new(LevelFilter::INFO, &config.levels)?;
+    for &kind in &config.sinks {
+        match kind {
+            LogSink::Journald => drop(journald()?),
+            LogSink::Syslog => drop(syslog()?),
+            LogSink::Stderr | LogSink::File => {}
+        }
+    }
+    Ok(())
+}
+
+fn sink(
+    kind: LogSink,
+    file_format: LogFileFormat,
+    guard: &mut Option<WorkerGuard>,
+) -> anyhow::Result<Box<dyn Layer<Filtered> + Send + Sync>> {
+    Ok(match kind {
+        LogSink::Stderr => tracing_subscriber::fmt::layer()
+            .with_writer(std::io::stderr)
+            .with_target(true)
+            .boxed(),
+        LogSink::File => {
+            let log_dir = gflow::paths::get_data_dir()?.join("logs");
+            std::fs::create_dir_all(&log_dir)?;
+            let file_appender = tracing_appender::rolling::RollingFileAppender::builder()
+                .rotation(tracing_appender::rolling::Rotation::DAILY)
+                .filename_prefix("daemon")
+                .filename_suffix("log")
+                .max_log_files(7)
+                .build(&log_dir)?;
+            let (non_blocking, file_guard) = tracing_appender::non_blocking(file_appender);
+            *guard = Some(file_guard);
+            let layer = tracing_subscriber::fmt::layer()
+                .with_ansi(false)
+                .with_writer(non_blocking);
+            match file_format {
+                LogFileFormat::Json => layer
+                    .json()
+                    .flatten_event(true)
+                    .with_current_span(true)
+                    .with_span_list(true)
+                    .boxed(),
+                LogFileFormat::Text => layer.boxed(),
+            }
+        }
+        LogSink::Journald => journald()?.boxed(),
+        LogSink::Syslog => syslog()?.boxed(),
+    })
+}
+
+fn journald() -> anyhow::Result<DatagramLayer> {
+    DatagramLayer::open(JOURNALD_SOCKET, journald_record)
+        .with_context(|| format!("Cannot log to journald through {JOURNALD_SOCKET}"))
+}
+
+fn syslog() -> anyhow::Result<DatagramLayer> {
+    DatagramLayer::open(SYSLOG_SOCKET, syslog_record)
+        .with_context(|| format!("Cannot log to syslog through {SYSLOG_SOCKET}"))
+}
+
+fn configured_level(level: &str) -> anyhow::Result<LevelFilter> {
+    parse_level(level).with_context(|| format!("Invalid daemon.logging.level '{level}'"))
+}
+
+fn parse_level(level: &str) -> anyhow::Result<LevelFilter> {
+    level
+        .parse()
+        .map_err(|_| anyhow::anyhow!("use off, error, warn, info, debug or trace"))
+}
+
+/// The running daemon's level filter, and the levels the config set.
+pub(crate) struct LogLevels {
+    handle: reload::Handle<EnvFilter, Registry>,
+    configured: LevelFilter,
+    current: Mutex<LevelFilter>,
+    modules: Vec<Directive>,
+}
+
+impl LogLevels {
+    /// A filter with `level` as the default and the per-module `levels` from
+    /// the config, and the levels to change it through.
+    fn new(
+        level: LevelFilter,
+        levels: &BTreeMap<String, String>,
+    ) -> anyhow::Result<(reload::Layer<EnvFilter, Registry>, Self)> {
+        let modules: Vec<Directive> = levels
+            .iter()
+            .map(|(module, level)| {
+                let parsed = parse_level(level).with_context(|| {
+                    format!("Invalid level '{level}' for '{module}' in daemon.logging.levels")
+                })?;
+                format!("{module}={parsed}")
+                    .parse()
+                    .with_context(|| format!("Invalid module '{module}' in daemon.logging.levels"))
+            })
+            .collect::<anyhow::Result<_>>()?;
+        let (filter, handle) = reload::Layer::new(filter(level, &modules));
+        let levels = Self {
+            handle,
+            configured: level,
+            current: Mutex::new(level),
+            modules,
+        };
+        Ok((filter, levels))
+    }
+
+    /// The default level, and the whole filter with the per-module levels.
+    pub(crate) fn current(&self) -> LogLevel {
+        let level = *self.current.lock().unwrap();
+        LogLevel {
+            level: name(level),
+            configured: name(self.configured),
+            filter: filter(level, &self.modules).to_string(),
+        }
+    }
+
+    /// Make `level` the default level, or the configured one if `None`.
+    pub(crate) fn set(&self, level: Option<&str>) -> anyhow::Result<LogLevel> {
+        let level = match level {
+            Some(level) => {
+                parse_level(level).with_context(|| format!("Invalid log level '{level}'"))?
+            }
+            None => self.configured,
+        };
+        let mut current = self.current.lock().unwrap();
+        self.handle.reload(filter(level, &self.modules))?;
+        *current = level;
+        drop(current);
+        Ok(self.current())
+    }
+}
+
+fn filter(level: LevelFilter, modules: &[Directive]) -> EnvFilter {
+    modules.iter().cloned().fold(
+        EnvFilter::default().add_directive(level.into()),
+        EnvFilter::add_directive,
+    )
+}
+
+/// `level` as config files spell it, e.g. `info`.
+fn name(level: LevelFilter) -> String {
+    level.to_string().to_lowercase()
+}
+
+/// Sends each record as one datagram to a local log daemon. Records the
+/// socket does not take, e.g. while the daemon restarts, are dropped.
+struct DatagramLayer {
+    socket: UnixDatagram,
+    path: PathBuf,
+    encode: fn(&Event<'_>) -> Vec<u8>,
+}
+
+impl DatagramLayer {
+    fn open(path: impl AsRef<Path>, encode: fn(&Event<'_>) -> Vec<u8>) -> std::io::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        // Fail at startup rather than drop every record.
+        UnixDatagram::unbound()?.connect(&path)?;
+        Ok(Self {
+            socket: UnixDatagram::unbound()?,
+            path,
+            encode,
+        })
+    }
+}
+
+impl<S: Subscriber> Layer<S> for DatagramLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let _ = self.socket.send_to(&(self.encode)(event), &self.path);
+    }
+}
+
+/// The message and other fields of an event.
+#[derive(Default)]
+struct Fields {
+    message: String,
+    fields: Vec<(&'static str, String)>,
+}
+
+impl Fields {
+    fn of(event: &Event<'_>) -> Self {
+        let mut fields = Self::default();
+        event.record(&mut fields);
+        fields
+    }
+}
+
+impl Visit for Fields {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message = value.to_string();
+        } else {
+            self.fields.push((field.name(), value.to_string()));
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "message" {
+            self.message = format!("{value:?}");
+        } else {
+            self.fields.push((field.name(), format!("{value:?}")));
+        }
+    }
+}
+
+/// Syslog severity of a level.
+fn severity(level: &Level) -> u8 {
+    match *level {
+        Level::ERROR => 3,
+        Level::WARN => 4,
+        Level::INFO => 6,
+        _ => 7,
+    }
+}
+
+/// A record in journald's native protocol: `KEY=value` lines, each field of
+/// the event as an upper-case key, so `journalctl JOB_ID=42` finds them.
+fn journald_record(event: &Event<'_>) -> Vec<u8> {
+    let metadata = event.metadata();
+    let fields = Fields::of(event);
+    let mut record = Vec::new();
+    let mut put = |key: &str, value: &str| {
+        record.extend_from_slice(key.as_bytes());
+        if value.contains('\n') {
+            // Multi-line values go as the length followed by the bytes.
+            record.push(b'\n');
+            record.extend_from_slice(&(value.len() as u64).to_le_bytes());
+        } else {
+            record.push(b'=');
+        }
+        record.extend_from_slice(value.as_bytes());
+        record.push(b'\n');
+    };
+    put("PRIORITY", &severity(metadata.level()).to_string());
+    put("SYSLOG_IDENTIFIER", IDENTIFIER);
+    put("TARGET", metadata.target());
+    if let Some(file) = metadata.file() {
+        put("CODE_FILE", file);
+    }
+    if let Some(line) = metadata.line() {
+        put("CODE_LINE", &line.to_string());
+    }
+    put("MESSAGE", &fields.message);
+    for (name, value) in &fields.fields {
+        if let Some(key) = journald_key(name) {
+            put(&key, value);
+        }
+    }
+    record
+}
+
+/// `name` as a journald key: upper-case letters, digits and underscores, not
+/// starting with an underscore, which journald reserves for trusted fields.
+fn journald_key(name: &str) -> Option<String> {
+    let key: String = name
+        .trim_start_matches(|c: char| !c.is_ascii_alphabetic())
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_uppercase()
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    (!key.is_empty()).then_some(key)
+}
+
+/// A record as `/dev/log` takes it (RFC 3164, from the daemon facility), with
+/// the fields after the message; the syslog daemon adds time and host.
+fn syslog_record(event: &Event<'_>) -> Vec<u8> {
+    const DAEMON_FACILITY: u8 = 3;
+    let metadata = event.metadata();
+    let fields = Fields::of(event);
+    let mut record = format!(
+        "<{}>{IDENTIFIER}[{}]: {}: {}",
+        DAEMON_FACILITY * 8 + severity(metadata.level()),
+        std::process::id(),
+        metadata.target(),
+        fields.message
+    );
+    for (name, value) in &fields.fields {
+        record.push_str(&format!(" {name}={value}"));
+    }
+    record.into_bytes()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    /// Log lines written by a subscriber, for inspection.
+    #[derive(Clone, Default)]
+    struct Captured(Arc<Mutex<Vec<u8>>>);
+
+    impl std::io::Write for Captured {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl Captured {
+        fn take(&self) -> String {
+            String::from_utf8(std::mem::take(&mut *self.0.lock().unwrap())).unwrap()
+        }
+    }
+
+    #[test]
+    fn set_level_changes_what_is_logged() {
+        let modules = BTreeMap::from([("noisy".to_string(), "warn".to_string())]);
+        let (filter, levels) = LogLevels::new(LevelFilter::INFO, &modules).unwrap();
+        let captured = Captured::default();
+        let writer = captured.clone();
+        let subscriber = tracing_subscriber::registry().with(filter).with(
+            tracing_subscriber::fmt::layer()
+                .with_ansi(false)
+                .with_writer(move || writer.clone()),
+        );
+
+        tracing::subscriber::with_default(subscriber, || {
+            let log_all = || {
+                tracing::debug!("scheduler detail");
+                tracing::info!(target: "noisy", "access line");
+            };
+            log_all();
+            assert_eq!(captured.take(), "");
+
+            let now = levels.set(Some("debug")).unwrap();
+            assert_eq!(
+                (now.level.as_str(), now.configured.as_str()),
+                ("debug", "info")
+            );
+            log_all();
+            let logged = captured.take();
+            assert!(logged.contains("scheduler detail"), "{logged}");
+            assert!(
+                !logged.contains("access line"),
+                "module level kept: {logged}"
+            );
+
+            assert_eq!(levels.set(None).unwrap().level, "info");
+            log_all();
+            assert_eq!(captured.take(), "");
+        });
+        assert!(levels.set(Some("loud")).is_err());
+    }
+
+    #[test]
+    fn invalid_module_levels_are_refused() {
+        let modules = BTreeMap::from([("gflow".to_string(), "chatty".to_string())]);
+        let error = LogLevels::new(LevelFilter::INFO, &modules)
+            .err()
+            .unwrap()
+            .to_string();
+        assert!(
+            error.contains("'gflow' in daemon.logging.levels"),
+            "{error}"
+        );
+    }
+
+    #[test]
+    fn journald_records_carry_the_event_fields() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("journal.socket");
+        let journal = UnixDatagram::bind(&path).unwrap();
+        let layer = DatagramLayer::open(&path, journald_record).unwrap();
+        let subscriber = tracing_subscriber::registry().with(layer);
+
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::warn!(job_id = 42, reason = "two\nlines", "Job failed");
+        });
+        let mut buf = vec![0; 4096];
+        let len = journal.recv(&mut buf).unwrap();
+        let record = String::from_utf8_lossy(&buf[..len]);
+        assert!(record.contains("PRIORITY=4\n"), "{record}");
+        assert!(record.contains("MESSAGE=Job failed\n"), "{record}");
+        assert!(record.contains("JOB_ID=42\n"), "{record}");
+        assert!(record.contains("REASON\n"), "multi-line value: {record}");
+        assert!(DatagramLayer::open(dir.path().join("missing"), journald_record).is_err());
+    }
+}
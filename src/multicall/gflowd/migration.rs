@@ -0,0 +1,595 @@
+//! Moving the job state to another host
+//!
+//! `gflowd export` writes a gzipped tar archive holding `manifest.json`, the
+//! scheduler state as `state.msgpack`, the token and webhook files of the
+//! data directory and, with `--logs`, the job logs under `logs/`.
+//!
+//! `gflowd import` unpacks the archive into a staging directory inside the
+//! data directory and checks its versions. Absolute job paths are then
+//! rewritten through a [`PathMap`], and only after that do the files replace
+//! those of the data directory.
+
+use anyhow::{bail, Context, Result};
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use gflow::core::job::{EnvSpec, JobSpec, JobState, JobStateReason};
+use gflow::core::scheduler::Scheduler;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Write};
+use std::path::{Component, Path, PathBuf};
+
+use super::backups;
+use super::scheduler_runtime::job_ids;
+use super::scheduler_runtime::journal::{self, JOURNAL_FILE};
+use super::scheduler_runtime::serialization::{self, SerializationFormat};
+
+/// Archive layout version; bumped when older gflow could not read it.
+pub(crate) const FORMAT: u32 = 1;
+const MANIFEST: &str = "manifest.json";
+const STATE: &str = "state.msgpack";
+const LOG_DIR: &str = "logs";
+/// Files of the data directory carried along unchanged.
+const DATA_FILES: &[&str] = &["tokens.json", "job_webhooks.json", "webhook_outbox.jsonl"];
+
+/// Reason of jobs that were running on the old host.
+pub(crate) const LOST_DURING_MIGRATION: &str = "lost during migration";
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub(crate) struct Manifest {
+    /// See [`FORMAT`]
+    pub format: u32,
+    /// gflow version that wrote the archive
+    pub gflow_version: String,
+    /// Schema version of the state, see `gflow::core::migrations`
+    pub state_version: u32,
+    pub hostname: String,
+    /// RFC 3339
+    pub exported_at: String,
+    pub jobs: usize,
+    /// No job ID at or above this was handed out on the old host
+    pub next_job_id: u32,
+    pub logs: bool,
+}
+
+/// Prefix rewrites for absolute paths, given as `OLD=NEW`.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub(crate) struct PathMap(Vec<(PathBuf, PathBuf)>);
+
+impl PathMap {
+    pub fn parse(rules: &[String]) -> Result<Self> {
+        let mut map = Self::default();
+        for rule in rules {
+            let Some((old, new)) = rule.split_once('=') else {
+                bail!("Invalid path mapping '{rule}': expected OLD=NEW");
+            };
+            if !Path::new(old).is_absolute() || !Path::new(new).is_absolute() {
+                bail!("Invalid path mapping '{rule}': both paths must be absolute");
+            }
+            map.insert(old.into(), new.into());
+        }
+        Ok(map)
+    }
+
+    pub fn insert(&mut self, old: PathBuf, new: PathBuf) {
+        if old != new {
+            self.0.push((old, new));
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// `path` with the longest matching prefix replaced; `None` if no rule
+    /// matches. Prefixes match whole components only.
+    pub fn apply(&self, path: &Path) -> Option<PathBuf> {
+        self.0
+            .iter()
+            .filter_map(|(old, new)| {
+                let rest = path.strip_prefix(old).ok()?;
+                Some((old.components().count(), new.join(rest)))
+            })
+            .max_by_key(|(len, _)| *len)
+            .map(|(_, path)| path)
+    }
+}
+
+/// The state gflowd would load at startup: the journal snapshot when it is
+/// newer than the state file, else the state file.
+pub(crate) fn load_current(state_dir: &Path) -> Result<Scheduler> {
+    let journal_path = state_dir.join(JOURNAL_FILE);
+    let snapshot = journal::should_apply(&state_dir.join("state.json"), &journal_path)
+        .then(|| journal::load_last_snapshot(&journal_path))
+        .flatten();
+    let scheduler = match snapshot {
+        Some((scheduler, _)) => scheduler,
+        None => serialization::load_state_auto(state_dir)?
+            .with_context(|| format!("No state file in {} to export", state_dir.display()))?,
+    };
+    gflow::core::migrations::migrate_state(scheduler)
+        .context("The state cannot be migrated to this version")
+}
+
+/// Write `scheduler` and the files of `state_dir` to the archive `output`.
+pub(crate) fn export(
+    scheduler: &Scheduler,
+    state_dir: &Path,
+    output: &Path,
+    include_logs: bool,
+) -> Result<Manifest> {
+    let recorded = job_ids::load(&job_ids::path(state_dir))
+        .ok()
+        .flatten()
+        .unwrap_or(0);
+    let manifest = Manifest {
+        format: FORMAT,
+        gflow_version: env!("CARGO_PKG_VERSION").to_string(),
+        state_version: scheduler.version,
+        hostname: super::agent::hostname().unwrap_or_else(|_| "unknown".to_string()),
+        exported_at: chrono::Utc::now().to_rfc3339(),
+        jobs: scheduler.jobs_len(),
+        next_job_id: scheduler.next_job_id().max(recorded),
+        logs: include_logs,
+    };
+
+    let mut tmp_name = output.file_name().unwrap_or_default().to_os_string();
+    tmp_name.push(".tmp");
+    let tmp_path = output.with_file_name(tmp_name);
+    let file = File::create(&tmp_path)
+        .with_context(|| format!("Failed to create {}", tmp_path.display()))?;
+    let mut archive =
+        tar::Builder::new(GzEncoder::new(BufWriter::new(file), Compression::default()));
+
+    append_bytes(
+        &mut archive,
+        MANIFEST,
+        &serde_json::to_vec_pretty(&manifest)?,
+    )?;
+    let state = serialization::serialize(scheduler, SerializationFormat::MessagePack)?;
+    append_bytes(&mut archive, STATE, &state)?;
+    for name in DATA_FILES {
+        let path = state_dir.join(name);
+        if path.exists() {
+            archive
+                .append_path_with_name(&path, name)
+                .with_context(|| format!("Failed to add {}", path.display()))?;
+        }
+    }
+    if include_logs {
+        for path in job_logs(&state_dir.join(LOG_DIR))? {
+            let name = Path::new(LOG_DIR).join(path.file_name().unwrap_or_default());
+            archive
+                .append_path_with_name(&path, name)
+                .with_context(|| format!("Failed to add {}", path.display()))?;
+        }
+    }
+
+    let mut writer = archive.into_inner()?.finish()?;
+    writer.flush()?;
+    writer
+        .into_inner()
+        .map_err(|e| e.into_error())?
+        .sync_all()?;
+    std::fs::rename(&tmp_path, output)
+        .with_context(|| format!("Failed to write {}", output.display()))?;
+    Ok(manifest)
+}
+
+fn append_bytes<W: Write>(archive: &mut tar::Builder<W>, name: &str, bytes: &[u8]) -> Result<()> {
+    let mut header = tar::Header::new_gnu();
+    header.set_size(bytes.len() as u64);
+    header.set_mode(0o600);
+    header.set_mtime(chrono::Utc::now().timestamp().max(0) as u64);
+    header.set_cksum();
+    archive
+        .append_data(&mut header, name, bytes)
+        .with_context(|| format!("Failed to add {name}"))
+}
+
+/// Job logs in `log_dir` (`<id>.log` and archived `<id>.log.old.<ts>`),
+/// leaving out the daemon's own logs.
+fn job_logs(log_dir: &Path) -> Result<Vec<PathBuf>> {
+    let entries = match std::fs::read_dir(log_dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e).with_context(|| format!("Failed to read {}", log_dir.display())),
+    };
+    let mut logs = Vec::new();
+    for entry in entries {
+        let entry = entry.with_context(|| format!("Failed to read {}", log_dir.display()))?;
+        let name = entry.file_name();
+        let is_job_log = name
+            .to_str()
+            .and_then(|name| name.split_once(".log"))
+            .is_some_and(|(id, _)| id.parse::<u32>().is_ok());
+        if is_job_log && entry.file_type().is_ok_and(|t| t.is_file()) {
+            logs.push(entry.path());
+        }
+    }
+    logs.sort();
+    Ok(logs)
+}
+
+/// A directory removed again on drop.
+struct Staging(PathBuf);
+
+impl Drop for Staging {
+    fn drop(&mut self) {
+        if let Err(e) = std::fs::remove_dir_all(&self.0) {
+            if e.kind() != std::io::ErrorKind::NotFound {
+                tracing::warn!(path = %self.0.display(), error = %e, "Failed to remove import staging directory");
+            }
+        }
+    }
+}
+
+/// An archive unpacked into a staging directory.
+pub(crate) struct Unpacked {
+    staging: Staging,
+    pub manifest: Manifest,
+    pub scheduler: Scheduler,
+}
+
+/// Unpack `archive` next to the files it will replace in `state_dir` and
+/// check that this gflow can load it.
+pub(crate) fn unpack(archive: &Path, state_dir: &Path) -> Result<Unpacked> {
+    let file =
+        File::open(archive).with_context(|| format!("Failed to open {}", archive.display()))?;
+    std::fs::create_dir_all(state_dir)
+        .with_context(|| format!("Failed to create {}", state_dir.display()))?;
+    let staging = Staging(state_dir.join(format!(".import.{}", std::process::id())));
+    if staging.0.exists() {
+        std::fs::remove_dir_all(&staging.0)
+            .with_context(|| format!("Failed to remove {}", staging.0.display()))?;
+    }
+    // Entries cannot escape the staging directory: `unpack` skips `..` and
+    // absolute paths.
+    tar::Archive::new(GzDecoder::new(BufReader::new(file)))
+        .unpack(&staging.0)
+        .with_context(|| format!("{} is not a gflowd export archive", archive.display()))?;
+
+    let manifest_path = staging.0.join(MANIFEST);
+    let manifest = std::fs::read(&manifest_path).with_context(|| {
+        format!(
+            "{} has no {MANIFEST}; was it written by `gflowd export`?",
+            archive.display()
+        )
+    })?;
+    let manifest: Manifest =
+        serde_json::from_slice(&manifest).with_context(|| format!("Invalid {MANIFEST}"))?;
+    if manifest.format > FORMAT {
+        bail!(
+            "{} was written by gflow {} in archive format {}, but this gflow only reads up to format {FORMAT}. Upgrade gflow on this host first.",
+            archive.display(),
+            manifest.gflow_version,
+            manifest.format
+        );
+    }
+    let mut scheduler = backups::load(&staging.0.join(STATE))?;
+    // Job indexes are not persisted; failing running jobs needs them.
+    scheduler.rebuild_user_jobs_index();
+    if scheduler.jobs_len() != manifest.jobs {
+        bail!(
+            "{} is damaged: its manifest lists {} jobs but the state holds {}",
+            archive.display(),
+            manifest.jobs,
+            scheduler.jobs_len()
+        );
+    }
+    Ok(Unpacked {
+        staging,
+        manifest,
+        scheduler,
+    })
+}
+
+fn job_paths(spec: &JobSpec) -> impl Iterator<Item = &Path> {
+    let venv = match &spec.environment {
+        EnvSpec::Venv(venv) => Some(venv.as_path()),
+        _ => None,
+    };
+    std::iter::once(spec.run_dir.as_path())
+        .chain(spec.script.as_deref().map(PathBuf::as_path))
+        .chain(venv)
+}
+
+fn job_paths_mut(spec: &mut JobSpec) -> impl Iterator<Item = &mut PathBuf> {
+    let venv = match &mut spec.environment {
+        EnvSpec::Venv(venv) => Some(venv),
+        _ => None,
+    };
+    std::iter::once(&mut spec.run_dir)
+        .chain(spec.script.as_deref_mut())
+        .chain(venv)
+}
+
+/// The top two directories of the absolute paths jobs use, e.g.
+/// `/home/alice`, with the number of jobs under each.
+pub(crate) fn path_roots(scheduler: &Scheduler) -> BTreeMap<PathBuf, usize> {
+    let mut roots = BTreeMap::new();
+    for spec in scheduler.job_specs() {
+        let mut seen: Vec<PathBuf> = job_paths(spec)
+            .filter(|path| path.is_absolute())
+            .map(|path| {
+                path.components()
+                    .filter(|c| !matches!(c, Component::CurDir))
+                    .take(3)
+                    .collect()
+            })
+            .collect();
+        seen.sort();
+        seen.dedup();
+        for root in seen {
+            *roots.entry(root).or_insert(0) += 1;
+        }
+    }
+    roots
+}
+
+#[derive(Debug)]
+pub(crate) struct ImportReport {
+    pub jobs: usize,
+    /// Jobs with at least one path rewritten
+    pub remapped: usize,
+    /// Jobs that were running and are now failed
+    pub lost: Vec<u32>,
+    pub logs: usize,
+    pub next_job_id: u32,
+}
+
+/// Rewrite the unpacked state for this host and move it into `state_dir`.
+///
+/// Jobs that were running are failed as [`LOST_DURING_MIGRATION`] unless
+/// `assume_running` says they still run where this daemon can see them.
+pub(crate) fn install(
+    mut unpacked: Unpacked,
+    state_dir: &Path,
+    path_map: &PathMap,
+    assume_running: bool,
+) -> Result<ImportReport> {
+    let scheduler = &mut unpacked.scheduler;
+    let mut remapped = 0;
+    let mut running = Vec::new();
+    for id in 1..=scheduler.jobs_len() as u32 {
+        let Some((spec, rt)) = scheduler.get_job_parts_mut(id) else {
+            continue;
+        };
+        let mut changed = false;
+        for path in job_paths_mut(spec) {
+            if let Some(new) = path_map.apply(path) {
+                *path = new;
+                changed = true;
+            }
+        }
+        remapped += usize::from(changed);
+        if rt.state == JobState::Running {
+            running.push(id);
+        }
+    }
+    let mut lost = Vec::new();
+    if !assume_running {
+        for id in running {
+            let reason = JobStateReason::SystemError(LOST_DURING_MIGRATION.into());
+            if scheduler.fail_job_because(id, Some(reason)) {
+                lost.push(id);
+            }
+        }
+    }
+
+    let next_job_id = scheduler.next_job_id().max(unpacked.manifest.next_job_id);
+    serialization::save_state(scheduler, state_dir, SerializationFormat::MessagePack)?;
+    let job_ids_path = job_ids::path(state_dir);
+    job_ids::store(&job_ids_path, next_job_id)
+        .with_context(|| format!("Failed to update {}", job_ids_path.display()))?;
+    // A newer journal would otherwise win over the imported state at startup.
+    let journal_path = state_dir.join(JOURNAL_FILE);
+    journal::truncate(&journal_path)
+        .with_context(|| format!("Failed to truncate {}", journal_path.display()))?;
+
+    for name in DATA_FILES {
+        let from = unpacked.staging.0.join(name);
+        if from.exists() {
+            move_file(&from, &state_dir.join(name))?;
+        }
+    }
+    let mut logs = 0;
+    let log_dir = state_dir.join(LOG_DIR);
+    for from in job_logs(&unpacked.staging.0.join(LOG_DIR))? {
+        std::fs::create_dir_all(&log_dir)
+            .with_context(|| format!("Failed to create {}", log_dir.display()))?;
+        move_file(&from, &log_dir.join(from.file_name().unwrap_or_default()))?;
+        logs += 1;
+    }
+
+    Ok(ImportReport {
+        jobs: unpacked.scheduler.jobs_len(),
+        remapped,
+        lost,
+        logs,
+        next_job_id,
+    })
+}
+
+fn move_file(from: &Path, to: &Path) -> Result<()> {
+    std::fs::rename(from, to)
+        .with_context(|| format!("Failed to move {} to {}", from.display(), to.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gflow::core::job::JobBuilder;
+    use gflow::core::scheduler::SchedulerBuilder;
+
+    /// A data directory with a running job under `/old/home`, a queued one
+    /// elsewhere, a token file, and logs of the job and the daemon.
+    fn old_host(dir: &Path) {
+        let mut scheduler = SchedulerBuilder::new().build();
+        scheduler.submit_job(
+            JobBuilder::new()
+                .script("/old/home/alice/proj/train.sh")
+                .run_dir("/old/home/alice/proj")
+                .build(),
+        );
+        scheduler.prepare_jobs_for_execution();
+        let mut queued = JobBuilder::new()
+            .command("true")
+            .run_dir("/scratch/x")
+            .gpus(64)
+            .build();
+        queued.environment = EnvSpec::Venv("/old/home/alice/venv".into());
+        scheduler.submit_job(queued);
+        serialization::save_state(&scheduler, dir, SerializationFormat::MessagePack).unwrap();
+        job_ids::store(&job_ids::path(dir), 10).unwrap();
+
+        std::fs::write(dir.join("tokens.json"), "{}").unwrap();
+        std::fs::create_dir_all(dir.join(LOG_DIR)).unwrap();
+        std::fs::write(dir.join("logs/1.log"), "epoch 1").unwrap();
+        std::fs::write(dir.join("logs/1.log.old.1700000000"), "epoch 0").unwrap();
+        std::fs::write(dir.join("logs/daemon.2026-10-18.log"), "").unwrap();
+    }
+
+    fn export_old_host(include_logs: bool) -> (tempfile::TempDir, PathBuf) {
+        let dir = tempfile::tempdir().unwrap();
+        let old = dir.path().join("old");
+        std::fs::create_dir_all(&old).unwrap();
+        old_host(&old);
+        let archive = dir.path().join("backup.tar.gz");
+        let scheduler = load_current(&old).unwrap();
+        let manifest = export(&scheduler, &old, &archive, include_logs).unwrap();
+        assert_eq!(manifest.jobs, 2);
+        assert_eq!(manifest.next_job_id, 10);
+        (dir, archive)
+    }
+
+    #[test]
+    fn round_trip_rewrites_paths_and_fails_running_jobs() {
+        let (dir, archive) = export_old_host(true);
+        let new = dir.path().join("new");
+
+        let unpacked = unpack(&archive, &new).unwrap();
+        assert_eq!(
+            path_roots(&unpacked.scheduler)
+                .into_iter()
+                .collect::<Vec<_>>(),
+            [
+                (PathBuf::from("/old/home"), 2),
+                (PathBuf::from("/scratch/x"), 1)
+            ]
+        );
+        let map = PathMap::parse(&["/old/home=/new/home".to_string()]).unwrap();
+        let report = install(unpacked, &new, &map, false).unwrap();
+        assert_eq!(report.jobs, 2);
+        assert_eq!(report.remapped, 2);
+        assert_eq!(report.lost, [1]);
+        assert_eq!(report.logs, 2);
+        assert_eq!(report.next_job_id, 10);
+
+        let imported = serialization::load_state_auto(&new).unwrap().unwrap();
+        let (spec, rt) = imported.get_job_parts(1).unwrap();
+        assert_eq!(spec.run_dir, Path::new("/new/home/alice/proj"));
+        assert_eq!(
+            spec.script.as_deref().unwrap(),
+            Path::new("/new/home/alice/proj/train.sh")
+        );
+        assert_eq!(rt.state, JobState::Failed);
+        assert_eq!(
+            rt.reason.as_deref().unwrap().to_string(),
+            format!("SystemError:{LOST_DURING_MIGRATION}")
+        );
+        let (spec, rt) = imported.get_job_parts(2).unwrap();
+        assert_eq!(spec.run_dir, Path::new("/scratch/x"));
+        assert_eq!(
+            spec.environment,
+            EnvSpec::Venv("/new/home/alice/venv".into())
+        );
+        assert_eq!(rt.state, JobState::Queued);
+
+        assert_eq!(job_ids::load(&job_ids::path(&new)).unwrap(), Some(10));
+        assert_eq!(
+            std::fs::read_to_string(new.join("tokens.json")).unwrap(),
+            "{}"
+        );
+        assert_eq!(
+            std::fs::read_to_string(new.join("logs/1.log")).unwrap(),
+            "epoch 1"
+        );
+        assert!(new.join("logs/1.log.old.1700000000").exists());
+        assert!(!new.join("logs/daemon.2026-10-18.log").exists());
+        let leftovers: Vec<_> = std::fs::read_dir(&new)
+            .unwrap()
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.file_name().to_string_lossy().starts_with(".import"))
+            .collect();
+        assert!(leftovers.is_empty());
+    }
+
+    #[test]
+    fn assume_running_keeps_running_jobs() {
+        let (dir, archive) = export_old_host(false);
+        let new = dir.path().join("new");
+
+        let unpacked = unpack(&archive, &new).unwrap();
+        assert!(!unpacked.manifest.logs);
+        let report = install(unpacked, &new, &PathMap::default(), true).unwrap();
+        assert!(report.lost.is_empty());
+        assert_eq!(report.logs, 0);
+
+        let imported = serialization::load_state_auto(&new).unwrap().unwrap();
+        let (spec, rt) = imported.get_job_parts(1).unwrap();
+        assert_eq!(spec.run_dir, Path::new("/old/home/alice/proj"));
+        assert_eq!(rt.state, JobState::Running);
+        assert!(!new.join(LOG_DIR).exists());
+    }
+
+    #[test]
+    fn refuses_archives_from_a_newer_format() {
+        let (dir, archive) = export_old_host(false);
+        let new = dir.path().join("new");
+        let mut manifest = unpack(&archive, &new).unwrap().manifest.clone();
+        manifest.format = FORMAT + 1;
+        manifest.gflow_version = "9.9.9".to_string();
+
+        let newer = dir.path().join("newer.tar.gz");
+        let file = File::create(&newer).unwrap();
+        let mut builder = tar::Builder::new(GzEncoder::new(file, Compression::default()));
+        append_bytes(
+            &mut builder,
+            MANIFEST,
+            &serde_json::to_vec(&manifest).unwrap(),
+        )
+        .unwrap();
+        builder.into_inner().unwrap().finish().unwrap();
+
+        let error = unpack(&newer, &new).err().unwrap();
+        assert!(format!("{error:#}").contains("gflow 9.9.9"), "{error:#}");
+        let error = unpack(&dir.path().join("missing.tar.gz"), &new)
+            .err()
+            .unwrap();
+        assert!(format!("{error:#}").contains("Failed to open"), "{error:#}");
+    }
+
+    #[test]
+    fn path_map_uses_the_longest_whole_component_prefix() {
+        let map = PathMap::parse(&[
+            "/data=/mnt/data".to_string(),
+            "/data/alice=/home/alice/data".to_string(),
+            "/same=/same".to_string(),
+        ])
+        .unwrap();
+        assert_eq!(
+            map.apply(Path::new("/data/bob/run")),
+            Some(PathBuf::from("/mnt/data/bob/run"))
+        );
+        assert_eq!(
+            map.apply(Path::new("/data/alice/run")),
+            Some(PathBuf::from("/home/alice/data/run"))
+        );
+        assert_eq!(map.apply(Path::new("/database")), None);
+        assert_eq!(map.apply(Path::new("/same/x")), None);
+
+        assert!(PathMap::parse(&["/data".to_string()]).is_err());
+        assert!(PathMap::parse(&["data=/mnt".to_string()]).is_err());
+    }
+}
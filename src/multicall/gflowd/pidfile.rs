@@ -0,0 +1,72 @@
+//! PID file for the running daemon
+//!
+//! The daemon writes its PID once the listener is bound and removes the file on
+//! exit. During a hot reload the new instance overwrites the file first, so the
+//! old instance only removes it if it still holds its own PID.
+
+use anyhow::Context;
+use std::path::{Path, PathBuf};
+
+/// Removes the PID file when dropped, unless another instance has taken it over.
+pub(crate) struct PidFile {
+    path: PathBuf,
+    pid: u32,
+}
+
+impl PidFile {
+    pub(crate) fn create(path: PathBuf) -> anyhow::Result<Self> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        let pid = std::process::id();
+        std::fs::write(&path, format!("{pid}\n"))
+            .with_context(|| format!("Failed to write PID file {}", path.display()))?;
+        Ok(Self { path, pid })
+    }
+}
+
+impl Drop for PidFile {
+    fn drop(&mut self) {
+        if read_pid(&self.path) == Some(self.pid) {
+            let _ = std::fs::remove_file(&self.path);
+        }
+    }
+}
+
+/// PID recorded in `path`, if the file exists and parses.
+pub(crate) fn read_pid(path: &Path) -> Option<u32> {
+    std::fs::read_to_string(path).ok()?.trim().parse().ok()
+}
+
+/// PID of the running daemon according to the PID file, ignoring stale files.
+pub(crate) fn running_daemon_pid() -> Option<u32> {
+    let path = gflow::paths::get_pid_file_path().ok()?;
+    read_pid(&path).filter(|&pid| is_process_running(pid))
+}
+
+pub(crate) fn is_process_running(pid: u32) -> bool {
+    unsafe { libc::kill(pid as libc::pid_t, 0) == 0 }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pid_file_is_removed_only_by_its_owner() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("run").join("gflowd.pid");
+
+        let pid_file = PidFile::create(path.clone()).unwrap();
+        assert_eq!(read_pid(&path), Some(std::process::id()));
+        drop(pid_file);
+        assert!(!path.exists());
+
+        let pid_file = PidFile::create(path.clone()).unwrap();
+        // A reloaded instance took over the file.
+        std::fs::write(&path, "1\n").unwrap();
+        drop(pid_file);
+        assert_eq!(read_pid(&path), Some(1));
+    }
+}
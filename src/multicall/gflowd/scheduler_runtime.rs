@@ -1,27 +1,44 @@
+mod artifacts;
 mod event_loop;
 mod gpu;
+pub(crate) mod job_dirs;
+pub(crate) mod job_ids;
 mod jobs;
+pub(crate) mod journal;
 mod monitors;
+mod nodes;
 mod persistence;
+mod progress;
 mod retry;
-mod serialization;
+pub(crate) mod serialization;
 #[cfg(test)]
 mod tests;
+mod usage;
 
 pub use event_loop::run_event_driven;
 
+use super::agent::{AgentClient, AgentJobRequest};
+use super::backups;
+use super::events::{EventBus, SchedulerEvent};
+use super::hooks::Hooks;
 use super::state_saver::StateSaverHandle;
+use super::tokens::TokenStore;
 use anyhow::{bail, Context, Result};
 use compact_str::CompactString;
-use gflow::core::executor::Executor;
+use gflow::client::JobAction;
+use gflow::config::DuplicateRunNames;
+use gflow::core::api_error::{ApiError, ApiErrorKind};
+use gflow::core::executor::{Executor, ExecutorKind, MemoryUsage};
 use gflow::core::gpu::{GPUSlot, GpuUuid};
-use gflow::core::info::IgnoredGpuProcess;
-use gflow::core::job::{GpuSharingMode, Job, JobSpec, JobState};
-use gflow::core::scheduler::{Scheduler, SchedulerBuilder};
-use gflow::tmux::disable_pipe_pane_for_job;
+use gflow::core::info::{IgnoredGpuProcess, StartupInfo};
+use gflow::core::job::{GpuSharingMode, Job, JobSignal, JobSpec, JobState, JobStateReason, Tags};
+use gflow::core::scheduler::{
+    LabelTarget, PriorSubmission, Scheduler, SchedulerBuilder, SubmissionRecord,
+};
+use gflow::utils::constraint::Labels;
 use nvml_wrapper::Nvml;
 use std::{
-    collections::{HashMap, HashSet},
+    collections::{BTreeMap, HashMap, HashSet},
     path::PathBuf,
     sync::Arc,
     time::Duration,
@@ -37,6 +54,30 @@ impl Executor for ArcExecutorWrapper {
     fn execute(&self, job: &Job) -> Result<()> {
         self.0.execute(job)
     }
+
+    fn interrupt(&self, job: &Job) -> Result<()> {
+        self.0.interrupt(job)
+    }
+
+    fn stop(&self, job: &Job) -> Result<()> {
+        self.0.stop(job)
+    }
+
+    fn is_running(&self, job: &Job) -> Option<bool> {
+        self.0.is_running(job)
+    }
+
+    fn release(&self, job: &Job, close: bool) {
+        self.0.release(job, close)
+    }
+
+    fn pid(&self, job: &Job) -> Option<u32> {
+        self.0.pid(job)
+    }
+
+    fn memory_usage(&self, job: &Job) -> Option<MemoryUsage> {
+        self.0.memory_usage(job)
+    }
 }
 
 /// Runtime adapter for Scheduler with system integration
@@ -45,6 +86,8 @@ pub struct SchedulerRuntime {
     projects_config: gflow::config::ProjectsConfig,
     nvml: Option<Nvml>,
     executor: Arc<dyn Executor>, // Shared executor for lock-free job execution
+    default_executor: ExecutorKind, // Recorded on jobs submitted without one
+    duplicate_run_names: DuplicateRunNames, // `daemon.duplicate_run_names`
     dirty: bool,                 // Tracks if state has changed since last save
     state_saver: Option<StateSaverHandle>, // Handle for async background state persistence
     state_writable: bool,        // False when state load/migration failed
@@ -54,7 +97,21 @@ pub struct SchedulerRuntime {
     journal_writable: bool,
     journal_error: Option<String>,
     journal_applied: bool,
+    job_ids_path: PathBuf,
     ignored_gpu_processes: HashSet<IgnoredGpuProcess>,
+    startup: Option<StartupInfo>, // Set once state loading and journal init have finished
+    backups_kept: usize,          // Rotated state backups to keep (`daemon.state_backups`)
+    preemption_priority: Option<u8>, // `daemon.preemption_priority`; None disables preemption
+    priority_limits: gflow::config::PriorityLimitsConfig, // `[priority_limits]`, checked on submission
+    priority_classes: BTreeMap<String, u8>, // `[priorities]`, suggested when a limit is hit
+    job_tokens: Option<Arc<TokenStore>>,    // Set when auth is required; forwarded to remote jobs
+    agents: AgentClient,                    // Forwards jobs placed on remote nodes to their agents
+    hooks: Arc<Hooks>,                      // `[hooks]` scripts run on submission and completion
+    launching_stopped: bool,                // Set on shutdown: queued jobs stay queued
+    event_bus: Option<Arc<EventBus>>,       // Receives a JobStateChanged for every state change
+    gpu_process_memory: HashMap<u32, u64>,  // GPU memory (MB) by PID, from the last NVML refresh
+    usage: usage::UsageTracker, // Peak memory of running jobs, stored on them when they end
+    artifact_offsets: HashMap<u32, u64>, // How far each job's artifacts file has been read
 }
 
 impl SchedulerRuntime {
@@ -138,12 +195,14 @@ impl SchedulerRuntime {
             Box::new(ArcExecutorWrapper(executor_arc.clone()));
 
         let state_file = state_dir.join("state.json");
-        let journal_path = state_dir.join("state.journal.jsonl");
+        let journal_path = state_dir.join(journal::JOURNAL_FILE);
+        let job_ids_path = job_ids::path(&state_dir);
         let scheduler = SchedulerBuilder::new()
             .with_executor(executor_for_scheduler)
             .with_gpu_slots(gpu_slots)
             .with_state_path(state_file)
             .with_total_memory_mb(total_memory_mb)
+            .with_total_cpus(gflow::core::cpu::total_cpus())
             .with_allowed_gpu_indices(validated_gpu_indices)
             .with_gpu_allocation_strategy(gpu_allocation_strategy)
             .with_unified_memory(unified_memory)
@@ -154,6 +213,8 @@ impl SchedulerRuntime {
             projects_config,
             nvml,
             executor: executor_arc,
+            default_executor: ExecutorKind::default(),
+            duplicate_run_names: DuplicateRunNames::default(),
             dirty: false,
             state_saver: None,
             state_writable: true,
@@ -163,10 +224,31 @@ impl SchedulerRuntime {
             journal_writable: false,
             journal_error: None,
             journal_applied: false,
+            job_ids_path,
             ignored_gpu_processes: HashSet::new(),
+            startup: None,
+            backups_kept: 0,
+            preemption_priority: None,
+            priority_limits: Default::default(),
+            priority_classes: BTreeMap::new(),
+            job_tokens: None,
+            event_bus: None,
+            agents: AgentClient::new(),
+            hooks: Arc::default(),
+            launching_stopped: false,
+            gpu_process_memory: HashMap::new(),
+            usage: usage::UsageTracker::default(),
+            artifact_offsets: HashMap::new(),
         };
+        let load_started = std::time::Instant::now();
         runtime.load_state();
         runtime.init_journal();
+        runtime.startup = Some(StartupInfo {
+            jobs_loaded: runtime.scheduler.job_specs().len(),
+            load_duration_ms: load_started.elapsed().as_millis() as u64,
+            nvml_initialized: runtime.nvml.is_some(),
+            journal_applied: runtime.journal_applied,
+        });
         Ok(runtime)
     }
 
@@ -192,6 +274,16 @@ impl SchedulerRuntime {
         self.state_writable || self.journal_writable
     }
 
+    pub fn startup_info(&self) -> Option<&StartupInfo> {
+        self.startup.as_ref()
+    }
+
+    /// Ready to serve traffic: startup finished and jobs can be persisted
+    /// (state.json or journal recovery mode).
+    pub fn is_ready(&self) -> bool {
+        self.startup.is_some() && self.can_mutate()
+    }
+
     pub fn state_load_error(&self) -> Option<&str> {
         self.state_load_error.as_deref()
     }
@@ -209,7 +301,7 @@ impl SchedulerRuntime {
     }
 
     /// Get total system memory in MB by reading /proc/meminfo (Linux)
-    fn get_total_system_memory_mb() -> u64 {
+    pub(super) fn get_total_system_memory_mb() -> u64 {
         // Try to read /proc/meminfo on Linux
         if let Ok(content) = std::fs::read_to_string("/proc/meminfo") {
             for line in content.lines() {
@@ -254,6 +346,11 @@ impl SchedulerRuntime {
         self.scheduler.info()
     }
 
+    /// See [`gflow::core::scheduler::Scheduler::revision`].
+    pub fn revision(&self) -> u64 {
+        self.scheduler.revision()
+    }
+
     pub fn gpu_slots_count(&self) -> usize {
         self.scheduler.gpu_slots_count()
     }
@@ -263,6 +360,121 @@ impl SchedulerRuntime {
         self.mark_dirty();
     }
 
+    pub fn set_labels(&mut self, labels: Labels, gpu_labels: BTreeMap<u32, Labels>) {
+        self.scheduler.set_labels(labels, gpu_labels);
+    }
+
+    /// Change labels with `gctl set-label`; returns the target's labels.
+    pub fn update_labels(
+        &mut self,
+        target: &LabelTarget,
+        set: Labels,
+        remove: &[String],
+    ) -> Result<Labels> {
+        let labels = self.scheduler.update_labels(target, set, remove)?;
+        // Node labels are saved with the node.
+        if matches!(target, LabelTarget::Node(_)) {
+            self.mark_dirty();
+        }
+        Ok(labels)
+    }
+
+    /// Executor recorded on new jobs that do not pick one, so changing
+    /// `[daemon] executor` later does not affect them.
+    pub fn set_default_executor(&mut self, executor: ExecutorKind) {
+        self.default_executor = executor;
+    }
+
+    pub fn set_duplicate_run_names(&mut self, policy: DuplicateRunNames) {
+        self.duplicate_run_names = policy;
+    }
+
+    pub fn set_backups_kept(&mut self, keep: usize) {
+        self.backups_kept = keep;
+    }
+
+    pub fn set_preemption_priority(&mut self, threshold: Option<u8>) {
+        self.preemption_priority = threshold;
+    }
+
+    pub fn set_min_free_disk(&mut self, min_free_mb: Option<u64>) {
+        self.scheduler.set_min_free_disk(min_free_mb);
+    }
+
+    pub fn set_log_dir(&mut self, log_dir: Option<PathBuf>) {
+        self.scheduler.set_log_dir(log_dir);
+    }
+
+    /// Lets jobs forwarded to remote nodes report their own outcome.
+    pub fn set_job_tokens(&mut self, job_tokens: Arc<TokenStore>) {
+        self.job_tokens = Some(job_tokens);
+    }
+
+    /// Publish every job state change on `event_bus`.
+    pub fn set_event_bus(&mut self, event_bus: Arc<EventBus>) {
+        self.event_bus = Some(event_bus);
+    }
+
+    /// Start no more jobs, e.g. while the daemon shuts down.
+    pub fn stop_launching(&mut self) {
+        self.launching_stopped = true;
+    }
+
+    pub fn set_hooks(&mut self, hooks: Hooks) {
+        self.hooks = Arc::new(hooks);
+    }
+
+    /// The `[hooks]` scripts, for running `pre_submit` without holding the lock.
+    pub fn hooks(&self) -> Arc<Hooks> {
+        Arc::clone(&self.hooks)
+    }
+
+    pub fn set_gpu_allocation_strategy(
+        &mut self,
+        strategy: gflow::core::gpu_allocation::GpuAllocationStrategy,
+    ) {
+        self.scheduler.set_gpu_allocation_strategy(strategy);
+    }
+
+    /// Limit how long one scheduling pass may spend on queued jobs
+    /// (`scheduler.pass_budget_ms`).
+    pub fn set_pass_budget(&mut self, budget: Option<Duration>) {
+        self.scheduler.set_pass_budget(budget);
+    }
+
+    pub fn set_projects_config(&mut self, projects_config: gflow::config::ProjectsConfig) {
+        self.projects_config = projects_config;
+    }
+
+    /// Limit the priorities users may submit with; `classes` are suggested
+    /// to users who go over their limit.
+    pub fn set_priority_limits(
+        &mut self,
+        limits: gflow::config::PriorityLimitsConfig,
+        classes: BTreeMap<String, u8>,
+    ) {
+        self.priority_limits = limits;
+        self.priority_classes = classes;
+    }
+
+    /// Check a job's priority against its user's `[priority_limits]`.
+    pub fn validate_priority(&self, priority: u8, user: &str) -> Result<(), ApiError> {
+        gflow::utils::validate_priority_limit(
+            priority,
+            user,
+            &self.priority_limits,
+            &self.priority_classes,
+        )
+    }
+
+    /// Refuse a job no machine could ever run, unless it asks to be queued anyway.
+    pub fn validate_capacity(&self, job: &Job) -> Result<(), ApiError> {
+        if job.force_queue {
+            return Ok(());
+        }
+        self.scheduler.validate_capacity(job)
+    }
+
     pub fn gpu_available(&self, gpu_index: u32) -> Option<bool> {
         self.scheduler
             .info()
@@ -272,6 +484,29 @@ impl SchedulerRuntime {
             .map(|gpu| gpu.available)
     }
 
+    pub fn estimate_start_times(
+        &self,
+    ) -> std::collections::BTreeMap<u32, gflow::core::scheduler::StartEstimate> {
+        self.scheduler
+            .estimate_start_times(std::time::SystemTime::now())
+    }
+
+    pub fn queue_summary(&self) -> gflow::core::scheduler::QueueSummary {
+        self.scheduler.queue_summary(std::time::SystemTime::now())
+    }
+
+    pub fn queue_positions(&self) -> std::collections::BTreeMap<u32, usize> {
+        self.scheduler.queue_positions()
+    }
+
+    pub fn available_gpu_slots(&self) -> Vec<u32> {
+        self.scheduler.get_available_gpu_slots()
+    }
+
+    pub fn runtime_stats(&self) -> &gflow::core::runtime_stats::RuntimeStats {
+        self.scheduler.runtime_stats()
+    }
+
     // Materialize all jobs for server handlers (allocates/clones).
     pub fn jobs(&self) -> Vec<Job> {
         self.scheduler.jobs_as_vec()
@@ -282,6 +517,11 @@ impl SchedulerRuntime {
         self.scheduler.get_job(job_id)
     }
 
+    /// Why job `job_id` cannot move to `next`, if it cannot.
+    pub fn transition_error(&self, job_id: u32, next: JobState) -> Option<ApiError> {
+        self.scheduler.check_transition(job_id, next, None).err()
+    }
+
     // Read-only access to hot runtimes for monitors/metrics.
     pub fn job_runtimes(&self) -> &[gflow::core::job::JobRuntime] {
         self.scheduler.job_runtimes()
@@ -296,10 +536,55 @@ impl SchedulerRuntime {
         self.scheduler.job_ids_by_user(username)
     }
 
-    pub fn job_ids_by_state(&self, state: gflow::core::job::JobState) -> Option<&[u32]> {
+    pub fn job_ids_by_tag(&self, tag: &str) -> Option<&[u32]> {
+        self.scheduler.job_ids_by_tag(tag)
+    }
+
+    /// Queued and held jobs of `username`, as capped by `max_queued_jobs`.
+    pub fn waiting_job_count(&self, username: &str) -> usize {
+        self.job_ids_by_user(username)
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|&id| self.scheduler.get_job_runtime(id))
+            .filter(|rt| matches!(rt.state, JobState::Queued | JobState::Hold))
+            .count()
+    }
+
+    /// Run directories of jobs queued or running on this machine.
+    pub fn local_run_dirs(&self) -> std::collections::BTreeSet<PathBuf> {
+        [JobState::Queued, JobState::Running]
+            .into_iter()
+            .filter_map(|state| self.scheduler.job_ids_by_state(state))
+            .flatten()
+            .filter_map(|&id| self.scheduler.get_job_parts(id))
+            .filter(|(_, rt)| rt.node.is_none())
+            .map(|(spec, _)| spec.run_dir.clone())
+            .collect()
+    }
+
+    pub fn job_ids_by_state(
+        &self,
+        state: gflow::core::job::JobState,
+    ) -> Option<&std::collections::BTreeSet<u32>> {
         self.scheduler.job_ids_by_state(state)
     }
 
+    pub fn job_ids_by_group(&self, group_id: &uuid::Uuid) -> Option<&[u32]> {
+        self.scheduler.job_ids_by_group(group_id)
+    }
+
+    pub fn job_group(&self, group_id: &uuid::Uuid) -> Option<gflow::core::job::JobGroup> {
+        self.scheduler.job_group(group_id)
+    }
+
+    pub fn job_groups(&self) -> Vec<gflow::core::job::JobGroup> {
+        self.scheduler.job_groups()
+    }
+
+    pub fn job_counts_by_state(&self) -> HashMap<JobState, usize> {
+        self.scheduler.get_job_counts_by_state()
+    }
+
     // Debug/metrics accessors
     pub fn next_job_id(&self) -> u32 {
         self.scheduler.next_job_id()
@@ -309,7 +594,7 @@ impl SchedulerRuntime {
         &self,
         new_job_id: u32,
         dependency_ids: &[u32],
-    ) -> Result<(), String> {
+    ) -> Result<(), gflow::core::api_error::ApiError> {
         self.scheduler
             .validate_no_circular_dependency(new_job_id, dependency_ids)
     }
@@ -322,6 +607,18 @@ impl SchedulerRuntime {
         self.scheduler.available_memory_mb()
     }
 
+    pub fn used_memory_mb(&self) -> u64 {
+        self.scheduler.used_memory_mb()
+    }
+
+    pub fn total_cpus(&self) -> u32 {
+        self.scheduler.total_cpus()
+    }
+
+    pub fn used_cpus(&self) -> u32 {
+        self.scheduler.used_cpus()
+    }
+
     // GPU Reservation methods
     pub fn create_reservation(
         &mut self,
@@ -329,10 +626,11 @@ impl SchedulerRuntime {
         gpu_spec: gflow::core::reservation::GpuSpec,
         start_time: std::time::SystemTime,
         duration: std::time::Duration,
+        options: gflow::core::reservation::ReservationOptions,
     ) -> anyhow::Result<u32> {
         let result = self
             .scheduler
-            .create_reservation(user, gpu_spec, start_time, duration)?;
+            .create_reservation_with(user, gpu_spec, start_time, duration, options)?;
         self.mark_dirty();
         Ok(result)
     }
@@ -341,6 +639,10 @@ impl SchedulerRuntime {
         self.scheduler.get_reservation(id)
     }
 
+    pub fn active_reservations(&self) -> Vec<&gflow::core::reservation::GpuReservation> {
+        self.scheduler.get_active_reservations()
+    }
+
     pub fn cancel_reservation(&mut self, id: u32) -> anyhow::Result<()> {
         self.scheduler.cancel_reservation(id)?;
         self.mark_dirty();
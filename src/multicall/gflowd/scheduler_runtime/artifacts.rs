@@ -0,0 +1,207 @@
+//! Artifacts running jobs register by appending lines to
+//! `$GFLOW_ARTIFACTS_FILE`: a path, optionally followed by a tab and a label.
+//! Read after each GPU refresh from where the last read stopped; once the job
+//! has ended its file is read to the end and removed.
+
+use super::*;
+use gflow::core::job::JobArtifact;
+use std::io::{Read, Seek, SeekFrom};
+
+/// Most of a file read in one poll; the rest waits for the next one.
+const MAX_READ_BYTES: u64 = 64 * 1024;
+
+/// A job's artifacts file, as found by [`poll`].
+struct ArtifactsFile {
+    job_id: u32,
+    path: PathBuf,
+    run_dir: PathBuf,
+    offset: u64,
+    ended: bool,
+}
+
+/// What [`read_new_lines`] found in one file.
+struct Registered {
+    job_id: u32,
+    artifacts: Vec<JobArtifact>,
+    offset: u64,
+    ended: bool,
+}
+
+/// Register the lines jobs appended to their artifacts files. Files are read,
+/// and artifacts looked at, without holding the lock.
+pub(super) async fn poll(state: &SharedState) {
+    let Ok(dir) = gflow::paths::get_artifacts_dir() else {
+        return;
+    };
+    let found = match tokio::task::spawn_blocking(move || list_files(&dir)).await {
+        Ok(found) if !found.is_empty() => found,
+        Ok(_) => return,
+        Err(error) => {
+            tracing::warn!(%error, "Failed to list job artifacts files");
+            return;
+        }
+    };
+
+    let files: Vec<ArtifactsFile> = {
+        let guard = state.read().await;
+        found
+            .into_iter()
+            .filter_map(|(job_id, path, len)| {
+                let offset = guard.artifact_offsets.get(&job_id).copied().unwrap_or(0);
+                let (run_dir, ended) = match guard.scheduler.get_job_parts(job_id) {
+                    Some((spec, rt)) => (spec.run_dir.clone(), rt.state != JobState::Running),
+                    // Left by a job the state no longer has.
+                    None => (PathBuf::new(), true),
+                };
+                (ended || len != offset).then_some(ArtifactsFile {
+                    job_id,
+                    path,
+                    run_dir,
+                    // Shorter than what was read: the job started over.
+                    offset: if len < offset { 0 } else { offset },
+                    ended,
+                })
+            })
+            .collect()
+    };
+    if files.is_empty() {
+        return;
+    }
+
+    let read = tokio::task::spawn_blocking(move || {
+        files.into_iter().map(read_new_lines).collect::<Vec<_>>()
+    })
+    .await;
+    match read {
+        Ok(registered) => state.write().await.record_artifacts(registered),
+        Err(error) => tracing::warn!(%error, "Failed to read job artifacts"),
+    }
+}
+
+/// The files in `dir` named by a job ID, with their lengths.
+fn list_files(dir: &std::path::Path) -> Vec<(u32, PathBuf, u64)> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .filter_map(|entry| {
+            let job_id = entry.file_name().to_str()?.parse().ok()?;
+            let len = entry.metadata().ok()?.len();
+            Some((job_id, entry.path(), len))
+        })
+        .collect()
+}
+
+/// Register the complete lines after `file.offset`. The file of an ended job
+/// is read to the end, last line included, and removed.
+fn read_new_lines(file: ArtifactsFile) -> Registered {
+    let mut registered = Registered {
+        job_id: file.job_id,
+        artifacts: Vec::new(),
+        offset: file.offset,
+        ended: false,
+    };
+    let mut content = Vec::new();
+    let read = std::fs::File::open(&file.path).and_then(|mut f| {
+        f.seek(SeekFrom::Start(file.offset))?;
+        f.take(MAX_READ_BYTES).read_to_end(&mut content)
+    });
+    if let Err(error) = read {
+        tracing::warn!(job_id = file.job_id, %error, "Failed to read artifacts file");
+        return registered;
+    }
+
+    let at_end = (content.len() as u64) < MAX_READ_BYTES;
+    let consumed = match content.iter().rposition(|&b| b == b'\n') {
+        _ if file.ended && at_end => content.len(),
+        Some(newline) => newline + 1,
+        // A line longer than a whole read is no path; skip it.
+        None if !at_end => content.len(),
+        None => 0,
+    };
+    registered.offset += consumed as u64;
+
+    if !file.run_dir.as_os_str().is_empty() {
+        for line in String::from_utf8_lossy(&content[..consumed]).lines() {
+            let (path, label) = line.split_once('\t').unwrap_or((line, ""));
+            let path = path.trim();
+            if path.is_empty() {
+                continue;
+            }
+            match JobArtifact::register(path.as_ref(), Some(label), &file.run_dir) {
+                Ok(artifact) => registered.artifacts.push(artifact),
+                Err(error) => {
+                    tracing::warn!(job_id = file.job_id, %error, "Artifact not registered")
+                }
+            }
+        }
+    }
+
+    if file.ended && at_end {
+        let _ = std::fs::remove_file(&file.path);
+        registered.ended = true;
+    }
+    registered
+}
+
+impl SchedulerRuntime {
+    pub fn job_artifacts(&self, job_id: u32) -> &[JobArtifact] {
+        self.scheduler.job_artifacts(job_id)
+    }
+
+    /// Record an artifact registered through the API.
+    pub fn register_artifact(&mut self, job_id: u32, artifact: JobArtifact) -> Result<(), String> {
+        self.scheduler.register_artifact(job_id, artifact)?;
+        self.mark_dirty();
+        Ok(())
+    }
+
+    fn record_artifacts(&mut self, registered: Vec<Registered>) {
+        for file in registered {
+            if file.ended {
+                self.artifact_offsets.remove(&file.job_id);
+            } else {
+                self.artifact_offsets.insert(file.job_id, file.offset);
+            }
+            for artifact in file.artifacts {
+                if let Err(error) = self.register_artifact(file.job_id, artifact) {
+                    tracing::warn!(job_id = file.job_id, %error, "Artifact not registered");
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lines_are_read_once_and_the_last_one_when_the_job_ends() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("model.pt"), b"weights").unwrap();
+        std::fs::write(dir.path().join("log.txt"), b"").unwrap();
+        let path = dir.path().join("7");
+        std::fs::write(&path, "model.pt\tbest\nmissing.pt\nlog.txt").unwrap();
+        let file = |offset, ended| ArtifactsFile {
+            job_id: 7,
+            path: path.clone(),
+            run_dir: dir.path().to_path_buf(),
+            offset,
+            ended,
+        };
+
+        let running = read_new_lines(file(0, false));
+        assert_eq!(running.artifacts.len(), 1, "the missing file is skipped");
+        assert_eq!(running.artifacts[0].label.as_deref(), Some("best"));
+        assert_eq!(running.artifacts[0].size_bytes, 7);
+        assert!(!running.ended);
+
+        let ended = read_new_lines(file(running.offset, true));
+        assert_eq!(ended.artifacts.len(), 1);
+        assert_eq!(ended.artifacts[0].path, dir.path().join("log.txt"));
+        assert!(ended.ended);
+        assert!(!path.exists());
+    }
+}
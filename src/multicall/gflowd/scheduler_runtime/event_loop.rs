@@ -1,5 +1,6 @@
 use super::super::events::{EventBus, EventEnvelope, SchedulerEvent};
 use super::*;
+use gflow::core::job::JobStateReason;
 use std::sync::Arc;
 use tracing::Instrument;
 
@@ -8,15 +9,18 @@ pub async fn run_event_driven(
     shared_state: SharedState,
     event_bus: Arc<EventBus>,
     gpu_poll_interval: Duration,
+    scheduler_tick: Duration,
+    job_dir_retention: Option<Duration>,
 ) {
     // Spawn all event handlers and monitors
-    let handles = vec![
+    let mut handles = vec![
         // Scheduler trigger handler with debouncing
         tokio::spawn(
             scheduler_trigger_handler_with_debounce(
                 event_bus.subscribe(),
                 Arc::clone(&shared_state),
                 Arc::clone(&event_bus),
+                scheduler_tick,
             )
             .instrument(tracing::info_span!("scheduler_trigger_task")),
         ),
@@ -43,6 +47,11 @@ pub async fn run_event_driven(
             )
             .instrument(tracing::info_span!("zombie_handler_task")),
         ),
+        // Node monitor - marks nodes without heartbeats down
+        tokio::spawn(
+            super::monitors::node_monitor_task(Arc::clone(&shared_state), Arc::clone(&event_bus))
+                .instrument(tracing::info_span!("node_monitor_task")),
+        ),
         // Timeout monitor - checks time limits every 10s
         tokio::spawn(
             super::monitors::timeout_monitor_task(
@@ -69,6 +78,11 @@ pub async fn run_event_driven(
             )
             .instrument(tracing::info_span!("reservation_monitor_task")),
         ),
+        // Stdin script cleanup - removes unreferenced `gbatch --stdin` scripts hourly
+        tokio::spawn(
+            super::monitors::stdin_script_cleanup_task(Arc::clone(&shared_state))
+                .instrument(tracing::info_span!("stdin_script_cleanup_task")),
+        ),
         // Metrics updater - updates metrics every 5s
         #[cfg(feature = "metrics")]
         tokio::spawn(
@@ -76,6 +90,13 @@ pub async fn run_event_driven(
                 .instrument(tracing::info_span!("metrics_updater_task")),
         ),
     ];
+    // Job directory cleanup - only with `daemon.job_dir_retention_secs`
+    if let Some(retention) = job_dir_retention {
+        handles.push(tokio::spawn(
+            super::job_dirs::job_dir_cleanup_task(Arc::clone(&shared_state), retention)
+                .instrument(tracing::info_span!("job_dir_cleanup_task")),
+        ));
+    }
 
     // Wait for all handlers (they run forever)
     for handle in handles {
@@ -85,14 +106,51 @@ pub async fn run_event_driven(
     }
 }
 
+/// How long to gather a burst of events (e.g. a batch submission) into one
+/// pass, and to let others at the lock after a pass that ran out of time.
+const SCHEDULE_DEBOUNCE: Duration = Duration::from_millis(100);
+
+/// How many times to look at a job's GPUs before launching it, while
+/// something else is on them (e.g. a preempted job still exiting).
+const LAUNCH_GPU_CHECKS: u32 = 5;
+const LAUNCH_GPU_CHECK_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Whether an event can let a queued job start.
+fn triggers_scheduling(event: &SchedulerEvent) -> bool {
+    matches!(
+        event,
+        SchedulerEvent::JobSubmitted { .. }
+            | SchedulerEvent::JobUpdated { .. }
+            | SchedulerEvent::JobCompleted { .. }
+            | SchedulerEvent::JobTimedOut { .. }
+            | SchedulerEvent::GpuAvailabilityChanged { .. }
+            | SchedulerEvent::ManualGpuOverrideChanged { .. }
+            | SchedulerEvent::MemoryAvailabilityChanged { .. }
+            | SchedulerEvent::ReservationCancelled { .. }
+            | SchedulerEvent::NodeUp { .. }
+            // Published when reservations start or end
+            | SchedulerEvent::PeriodicHealthCheck
+    )
+}
+
 /// Scheduler trigger handler with debouncing
-async fn scheduler_trigger_handler_with_debounce(
+///
+/// Sleeps until an event asks for a pass, waits `SCHEDULE_DEBOUNCE` for the
+/// rest of the burst, then runs one pass. Events published during a pass stay
+/// queued in the receiver and start the next round, so none is missed.
+/// Without events a pass runs every `tick` (`scheduler.tick_secs`), as a
+/// safety net for changes that publish none, and a pass that ran out of time
+/// is followed by another after `SCHEDULE_DEBOUNCE`.
+pub(super) async fn scheduler_trigger_handler_with_debounce(
     mut events: tokio::sync::broadcast::Receiver<EventEnvelope>,
     state: SharedState,
     event_bus: Arc<EventBus>,
+    tick: Duration,
 ) {
-    let mut debounce = tokio::time::interval(Duration::from_millis(100));
-    let mut pending_schedule = false;
+    let mut fallback = tokio::time::interval(tick);
+    fallback.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    fallback.reset();
+    let mut resume_at = None;
 
     loop {
         tokio::select! {
@@ -101,67 +159,124 @@ async fn scheduler_trigger_handler_with_debounce(
                     Ok(event) => {
                         let handling_span = event.handling_span("scheduler_trigger_handler");
                         let _entered = handling_span.enter();
-                        match event.event {
-                            SchedulerEvent::JobSubmitted { .. }
-                            | SchedulerEvent::JobUpdated { .. }
-                            | SchedulerEvent::JobCompleted { .. }
-                            | SchedulerEvent::JobTimedOut { .. }
-                            | SchedulerEvent::GpuAvailabilityChanged { .. }
-                            | SchedulerEvent::ManualGpuOverrideChanged { .. }
-                            | SchedulerEvent::MemoryAvailabilityChanged { .. } => {
-                                pending_schedule = true;
-                            }
-                            _ => {}
+                        if !triggers_scheduling(&event.event) {
+                            continue;
                         }
                     }
                     Err(tokio::sync::broadcast::error::RecvError::Lagged(skipped)) => {
                         tracing::warn!(skipped, "Scheduler trigger handler lagged");
-                        pending_schedule = true; // Trigger scheduling to be safe
+                        // Trigger scheduling to be safe
                     }
                     Err(tokio::sync::broadcast::error::RecvError::Closed) => {
                         tracing::info!("Event bus closed, scheduler trigger handler exiting");
                         break;
                     }
                 }
-            }
-            _ = debounce.tick() => {
-                if pending_schedule {
-                    trigger_scheduling(&state, &event_bus).await;
-                    pending_schedule = false;
+
+                // Let the rest of the burst arrive; the pass covers all of it.
+                let window = tokio::time::sleep(SCHEDULE_DEBOUNCE);
+                tokio::pin!(window);
+                loop {
+                    tokio::select! {
+                        _ = &mut window => break,
+                        result = events.recv() => {
+                            if matches!(result, Err(tokio::sync::broadcast::error::RecvError::Closed)) {
+                                break;
+                            }
+                        }
+                    }
                 }
             }
+            _ = fallback.tick() => {}
+            _ = tokio::time::sleep_until(resume_at.unwrap_or_else(tokio::time::Instant::now)),
+                if resume_at.is_some() => {}
         }
+
+        let yielded = trigger_scheduling(&state, &event_bus).await;
+        resume_at = yielded.then(|| tokio::time::Instant::now() + SCHEDULE_DEBOUNCE);
+        fallback.reset();
     }
 }
 
-/// Trigger job scheduling
-async fn trigger_scheduling(state: &SharedState, event_bus: &Arc<EventBus>) {
+/// Trigger job scheduling. Returns whether the pass ran out of time before
+/// looking at every queued job.
+pub(super) async fn trigger_scheduling(state: &SharedState, event_bus: &Arc<EventBus>) -> bool {
     let scheduling_span = tracing::info_span!("trigger_scheduling");
     let _entered = scheduling_span.enter();
     #[cfg(feature = "metrics")]
     let started_at = std::time::Instant::now();
 
     // Step 1: Prepare jobs for execution (write lock - fast, no I/O)
-    let jobs_to_execute = {
+    let (jobs_to_execute, preemptions, yielded) = {
         let mut state_guard = state.write().await;
-        let jobs = state_guard.scheduler.prepare_jobs_for_execution();
+        if state_guard.launching_stopped {
+            return false;
+        }
+        let (mut jobs, preemptions) = match state_guard.preemption_priority {
+            Some(threshold) => state_guard
+                .scheduler
+                .prepare_jobs_with_preemption(threshold),
+            None => (
+                state_guard.scheduler.prepare_jobs_for_execution(),
+                Vec::new(),
+            ),
+        };
+
+        let pass = *state_guard.scheduler.last_pass();
+        tracing::debug!(
+            duration_ms = pass.duration.as_millis() as u64,
+            examined = pass.examined,
+            started = pass.started,
+            yielded = pass.yielded,
+            "Scheduling pass"
+        );
+        gflow::metrics::observe_scheduling_pass(&pass);
+        state_guard.assign_job_dirs(&mut jobs);
+
+        // Victims are already requeued; interrupt them before their GPUs are reused
+        for preemption in &preemptions {
+            state_guard.interrupt_job(preemption.victim);
+            state_guard.stop_job_workload(preemption.victim);
+        }
 
         // CRITICAL: Immediately refresh GPU slots after allocation to prevent race condition
         // This ensures that if another scheduling trigger happens before the periodic
         // GPU monitor runs, it will see the updated GPU availability
         if !jobs.is_empty() {
             state_guard.refresh_gpu_slots();
+        }
+        if !jobs.is_empty() || !preemptions.is_empty() {
             // prepare_jobs_for_execution mutates job state/resources, so we must persist
             state_guard.mark_dirty();
         }
 
-        jobs
+        (jobs, preemptions, pass.yielded)
     }; // Lock released here
 
+    if !preemptions.is_empty() {
+        // Same ladder as cancelling: wait for a graceful exit, then close the
+        // session so the victim can start again under its run name.
+        tokio::time::sleep(Duration::from_millis(500)).await;
+        let state_guard = state.read().await;
+        for preemption in &preemptions {
+            state_guard.release_job_workload(preemption.victim, true);
+        }
+        drop(state_guard);
+
+        for preemption in preemptions {
+            tracing::info!(
+                job_id = preemption.victim,
+                by = preemption.by,
+                "Requeued job preempted by job {}",
+                preemption.by
+            );
+        }
+    }
+
     if jobs_to_execute.is_empty() {
         #[cfg(feature = "metrics")]
         gflow::metrics::observe_scheduler_latency("trigger_scheduling", started_at.elapsed());
-        return;
+        return yielded;
     }
 
     tracing::info!(
@@ -170,21 +285,22 @@ async fn trigger_scheduling(state: &SharedState, event_bus: &Arc<EventBus>) {
     );
 
     // Step 2: Execute jobs (NO LOCK - can take seconds due to tmux I/O)
-    let executor = {
+    let (executor, agents) = {
         let state_guard = state.read().await;
-        state_guard.executor.clone()
+        (state_guard.executor.clone(), state_guard.agents.clone())
     }; // Read lock released immediately
 
     let mut execution_results = Vec::new();
     for job in &jobs_to_execute {
         // Re-check job state before execution (prevents executing cancelled/held jobs)
-        let should_execute = {
+        let (should_execute, remote) = {
             let state_guard = state.read().await;
-            state_guard
+            let should_execute = state_guard
                 .scheduler
                 .get_job_runtime(job.id)
                 .map(|rt| rt.state == JobState::Running)
-                .unwrap_or(false)
+                .unwrap_or(false);
+            (should_execute, state_guard.remote_execution(job))
         };
 
         if !should_execute {
@@ -199,9 +315,21 @@ async fn trigger_scheduling(state: &SharedState, event_bus: &Arc<EventBus>) {
             continue;
         }
 
-        match executor.execute(job) {
+        if remote.is_none() && !claimed_gpus_are_free(state, job.id).await {
+            if state.write().await.requeue_unlaunched_job(job.id) {
+                event_bus.publish(SchedulerEvent::JobUpdated { job_id: job.id });
+            }
+            continue;
+        }
+
+        let result = match remote {
+            Some(Ok((node, request))) => agents.send(&node, "execute", &request).await,
+            Some(Err(error)) => Err(error),
+            None => executor.execute(job),
+        };
+        match result {
             Ok(_) => {
-                tracing::info!(job_id = job.id, "Executed job");
+                tracing::info!(job_id = job.id, node = ?job.node, "Executed job");
                 execution_results.push((job.id, Ok(())));
             }
             Err(e) => {
@@ -216,26 +344,24 @@ async fn trigger_scheduling(state: &SharedState, event_bus: &Arc<EventBus>) {
         let mut retried_jobs = Vec::new();
         let mut state_guard = state.write().await;
         for (job_id, result) in &execution_results {
-            if result.is_ok() {
+            let Err(error) = result else {
+                state_guard.scheduler.confirm_gpu_claim(*job_id);
                 continue;
-            }
+            };
 
-            let Some((had_gpus, was_running)) = (|| {
-                let rt = state_guard.scheduler.get_job_runtime_mut(*job_id)?;
-                Some((rt.gpu_ids.take().is_some(), rt.state == JobState::Running))
-            })() else {
+            let Some(rt) = state_guard.scheduler.get_job_runtime_mut(*job_id) else {
                 continue;
             };
+            rt.gpu_ids = None;
+            rt.cpu_ids = None;
 
-            if was_running {
-                if let Some(Some(new_job_id)) = state_guard.fail_job(*job_id).await {
-                    retried_jobs.push(new_job_id);
+            if rt.state == JobState::Running {
+                // Record why, e.g. a venv without an activation script.
+                let reason = JobStateReason::SystemError(error.into());
+                if let Some(retried) = state_guard.fail_job_because(*job_id, Some(reason)).await {
+                    retried_jobs.extend(retried);
                 }
             }
-
-            if had_gpus {
-                state_guard.scheduler.refresh_available_memory();
-            }
         }
         drop(state_guard);
 
@@ -246,4 +372,34 @@ async fn trigger_scheduling(state: &SharedState, event_bus: &Arc<EventBus>) {
 
     #[cfg(feature = "metrics")]
     gflow::metrics::observe_scheduler_latency("trigger_scheduling", started_at.elapsed());
+    yielded
+}
+
+/// Whether job `job_id` can be launched on the GPUs it was assigned: waits a
+/// little for other processes to leave them, since the pass that assigned
+/// them looked at an older snapshot.
+async fn claimed_gpus_are_free(state: &SharedState, job_id: u32) -> bool {
+    for check in 1..=LAUNCH_GPU_CHECKS {
+        let busy = state.read().await.busy_claimed_gpus(job_id);
+        let Some((gpu, pids)) = busy.first() else {
+            return true;
+        };
+        if check == LAUNCH_GPU_CHECKS {
+            tracing::warn!(
+                job_id,
+                gpu_index = gpu,
+                ?pids,
+                "GPU was taken before the job could start; requeueing it"
+            );
+            break;
+        }
+        tracing::debug!(
+            job_id,
+            gpu_index = gpu,
+            ?pids,
+            "Waiting for GPU to be free before launch"
+        );
+        tokio::time::sleep(LAUNCH_GPU_CHECK_INTERVAL).await;
+    }
+    false
 }
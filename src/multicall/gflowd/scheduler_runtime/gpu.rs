@@ -1,21 +1,13 @@
 use super::*;
+use nvml_wrapper::enums::device::UsedGpuMemory;
 
 impl SchedulerRuntime {
     pub(super) fn refresh_gpu_slots(&mut self) {
         let mut running_shared_gpu_indices = HashSet::new();
         let mut running_exclusive_gpu_indices = HashSet::new();
 
-        for rt in self
-            .scheduler
-            .job_runtimes()
-            .iter()
-            .filter(|rt| rt.state == JobState::Running)
-        {
-            let Some(gpu_ids) = rt.gpu_ids.as_ref() else {
-                continue;
-            };
-
-            match rt.gpu_sharing_mode {
+        for (_, gpu_ids, sharing_mode) in self.scheduler.gpu_holders() {
+            match sharing_mode {
                 GpuSharingMode::Shared => {
                     for &gpu in gpu_ids {
                         running_shared_gpu_indices.insert(gpu);
@@ -30,6 +22,7 @@ impl SchedulerRuntime {
         }
 
         if let Some(nvml) = &self.nvml {
+            let mut gpu_process_memory: HashMap<u32, u64> = HashMap::new();
             let ignored_snapshot = self.ignored_gpu_processes.clone();
             let mut active_ignored = ignored_snapshot.clone();
             if let Ok(device_count) = nvml.device_count() {
@@ -52,6 +45,12 @@ impl SchedulerRuntime {
 
                     match device.running_compute_processes() {
                         Ok(processes) => {
+                            for process in &processes {
+                                if let UsedGpuMemory::Used(bytes) = process.used_gpu_memory {
+                                    *gpu_process_memory.entry(process.pid).or_default() +=
+                                        bytes / (1024 * 1024);
+                                }
+                            }
                             let mut unmanaged_pids = processes
                                 .into_iter()
                                 .map(|proc| proc.pid)
@@ -128,6 +127,66 @@ impl SchedulerRuntime {
                 tracing::warn!("Failed to query NVML device count during GPU refresh");
             }
             self.ignored_gpu_processes = active_ignored;
+            self.gpu_process_memory = gpu_process_memory;
+        }
+    }
+
+    /// GPUs of job `job_id`, assigned but not launched on yet, on which NVML
+    /// shows processes that are not ignored, with those processes. GPUs that
+    /// other jobs hold are skipped: their processes cannot be told apart.
+    /// Empty without NVML.
+    pub(super) fn busy_claimed_gpus(&self, job_id: u32) -> Vec<(u32, Vec<u32>)> {
+        if self.nvml.is_none() {
+            return Vec::new();
+        }
+        let Some(claim) = self.scheduler.gpu_claim(job_id).filter(|c| !c.confirmed) else {
+            return Vec::new();
+        };
+        let shared: HashSet<u32> = self
+            .scheduler
+            .gpu_holders()
+            .into_iter()
+            .filter(|(holder, _, _)| *holder != job_id)
+            .flat_map(|(_, gpus, _)| gpus.iter().copied())
+            .collect();
+
+        let mut busy = Vec::new();
+        for &gpu in claim.gpus.iter().filter(|gpu| !shared.contains(gpu)) {
+            let mut pids = match self.current_compute_processes_on_gpu(gpu) {
+                Ok(pids) => pids,
+                Err(error) => {
+                    tracing::warn!(job_id, gpu_index = gpu, error = %error, "Cannot check GPU before launch");
+                    continue;
+                }
+            };
+            pids.retain(|&pid| {
+                !self.ignored_gpu_processes.contains(&IgnoredGpuProcess {
+                    gpu_index: gpu,
+                    pid,
+                })
+            });
+            if !pids.is_empty() {
+                busy.push((gpu, pids));
+            }
+        }
+        busy
+    }
+
+    /// Put job `job_id` back in the queue because its GPUs were taken before
+    /// it could be launched.
+    pub fn requeue_unlaunched_job(&mut self, job_id: u32) -> bool {
+        match self.scheduler.requeue_unlaunched_job(job_id) {
+            Ok(requeued) => {
+                if requeued {
+                    self.refresh_gpu_slots();
+                    self.mark_dirty();
+                }
+                requeued
+            }
+            Err(error) => {
+                tracing::warn!(job_id, error = %error, "Cannot requeue job");
+                false
+            }
         }
     }
 
@@ -0,0 +1,114 @@
+//! Scratch directories under `<data dir>/jobs`, one per job started on this
+//! machine and exported to it as `GFLOW_JOB_DIR`. The executor creates the
+//! directory before it starts the job; `[daemon] job_dir_retention_secs`
+//! removes those of finished jobs, and `gjob clean` one on demand.
+
+use super::*;
+use std::path::Path;
+use std::time::SystemTime;
+
+/// Longest time between two looks at finished jobs' directories.
+const CLEANUP_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+impl SchedulerRuntime {
+    /// Give each of `jobs`, just prepared to run, its scratch directory.
+    /// Jobs placed on remote nodes get none.
+    pub(super) fn assign_job_dirs(&mut self, jobs: &mut [Job]) {
+        for job in jobs.iter_mut().filter(|job| job.node.is_none()) {
+            let dir = match gflow::paths::get_job_dir(job.id) {
+                Ok(dir) => Box::new(dir),
+                Err(error) => {
+                    tracing::warn!(job_id = job.id, %error, "No job directory for job");
+                    continue;
+                }
+            };
+            if let Some(rt) = self.scheduler.get_job_runtime_mut(job.id) {
+                rt.job_dir = Some(dir.clone());
+            }
+            job.job_dir = Some(dir);
+        }
+    }
+
+    /// Directories of jobs that ended at least `retention` before `now`,
+    /// other than those submitted with `--keep-job-dir`.
+    pub(super) fn expired_job_dirs(
+        &self,
+        retention: Duration,
+        now: SystemTime,
+    ) -> Vec<(u32, PathBuf)> {
+        self.scheduler
+            .job_specs()
+            .iter()
+            .zip(self.scheduler.job_runtimes())
+            .filter(|(spec, rt)| !spec.keep_job_dir && rt.state.is_final())
+            .filter(|(_, rt)| {
+                rt.finished_at
+                    .and_then(|finished_at| now.duration_since(finished_at).ok())
+                    .is_some_and(|age| age >= retention)
+            })
+            .filter_map(|(_, rt)| Some((rt.id, rt.job_dir.as_deref()?.clone())))
+            .collect()
+    }
+
+    /// Forget job `job_id`'s directory `dir` once it has been removed.
+    pub fn forget_job_dir(&mut self, job_id: u32, dir: &Path) {
+        let Some(rt) = self.scheduler.get_job_runtime_mut(job_id) else {
+            return;
+        };
+        if rt.job_dir.as_deref().is_some_and(|job_dir| job_dir == dir) {
+            rt.job_dir = None;
+            self.mark_dirty();
+        }
+    }
+}
+
+/// Remove a job's directory; returns the bytes freed, nothing if it was
+/// already gone.
+pub(crate) fn remove_job_dir(dir: &Path) -> std::io::Result<u64> {
+    match gflow::utils::disk::remove_dir_tree(dir) {
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(0),
+        result => result,
+    }
+}
+
+/// Job directory cleanup - removes the directories of jobs that finished
+/// `retention` ago, looking hourly or every `retention` if that is sooner
+pub(super) async fn job_dir_cleanup_task(state: SharedState, retention: Duration) {
+    let period = retention.clamp(Duration::from_secs(60), CLEANUP_INTERVAL);
+    let mut interval = tokio::time::interval(period);
+
+    loop {
+        interval.tick().await;
+
+        let expired = state
+            .read()
+            .await
+            .expired_job_dirs(retention, SystemTime::now());
+        if expired.is_empty() {
+            continue;
+        }
+        let removed = tokio::task::spawn_blocking(move || remove_job_dirs(expired))
+            .await
+            .unwrap_or_default();
+
+        let mut state = state.write().await;
+        for (job_id, dir) in &removed {
+            state.forget_job_dir(*job_id, dir);
+        }
+    }
+}
+
+fn remove_job_dirs(dirs: Vec<(u32, PathBuf)>) -> Vec<(u32, PathBuf)> {
+    dirs.into_iter()
+        .filter(|(job_id, dir)| match remove_job_dir(dir) {
+            Ok(bytes) => {
+                tracing::info!(job_id, path = %dir.display(), bytes, "Removed job directory");
+                true
+            }
+            Err(error) => {
+                tracing::warn!(job_id, path = %dir.display(), %error, "Failed to remove job directory");
+                false
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,69 @@
+//! Durable job id high-water mark
+//!
+//! Job ids come from `next_job_id`, which only reaches disk with the next
+//! (background) state save. So that a crash cannot hand out an id twice, the
+//! new `next_job_id` is written to its own small file in the state directory,
+//! and synced, before a submission returns its ids. On startup, ids below the
+//! mark that the state file does not have are recorded as lost.
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// File name of the high-water mark inside the state directory.
+pub(crate) const HIGH_WATER_FILE: &str = "next_job_id";
+
+pub(crate) fn path(state_dir: &Path) -> PathBuf {
+    state_dir.join(HIGH_WATER_FILE)
+}
+
+/// The recorded high-water mark: no id at or above it was handed out.
+pub(crate) fn load(path: &Path) -> anyhow::Result<Option<u32>> {
+    let content = match std::fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+    let mark = content
+        .trim()
+        .parse()
+        .map_err(|e| anyhow::anyhow!("invalid job id high-water mark {content:?}: {e}"))?;
+    Ok(Some(mark))
+}
+
+/// Record a new high-water mark, returning only once it is on disk.
+pub(crate) fn store(path: &Path, mark: u32) -> std::io::Result<()> {
+    let tmp_path = path.with_extension("tmp");
+    let mut file = std::fs::File::create(&tmp_path)?;
+    writeln!(file, "{mark}")?;
+    file.sync_all()?;
+    std::fs::rename(&tmp_path, path)?;
+    if let Some(dir) = path.parent() {
+        // Make the rename itself durable.
+        std::fs::File::open(dir)?.sync_all()?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stores_and_loads_the_mark() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = path(dir.path());
+        assert_eq!(load(&path).unwrap(), None);
+
+        store(&path, 101).unwrap();
+        store(&path, 201).unwrap();
+        assert_eq!(load(&path).unwrap(), Some(201));
+    }
+
+    #[test]
+    fn rejects_a_garbled_mark() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = path(dir.path());
+        std::fs::write(&path, "12x\n").unwrap();
+        assert!(load(&path).is_err());
+    }
+}
@@ -1,5 +1,32 @@
 use super::*;
 
+/// Names in use when jobs are submitted: run names of active jobs and tmux
+/// sessions, and the `--name` each active job was given, with its ID and group.
+#[derive(Default)]
+struct TakenRunNames {
+    reserved: HashSet<String>,
+    requested: HashMap<String, Vec<(u32, Option<uuid::Uuid>)>>,
+}
+
+impl TakenRunNames {
+    /// The active job outside `group_id` that was given `name`.
+    fn shared_with(&self, name: &str, group_id: Option<uuid::Uuid>) -> Option<u32> {
+        self.requested
+            .get(name)?
+            .iter()
+            .find(|(_, other_group)| group_id.is_none() || *other_group != group_id)
+            .map(|&(job_id, _)| job_id)
+    }
+}
+
+/// The `--name` part of `gjob-<id>-<name>`; `None` for default run names.
+fn requested_run_name(job_id: u32, run_name: &str) -> Option<&str> {
+    run_name
+        .strip_prefix("gjob-")?
+        .strip_prefix(job_id.to_string().as_str())?
+        .strip_prefix('-')
+}
+
 impl SchedulerRuntime {
     fn normalize_and_validate_project(&self, job: &mut Job) -> Result<()> {
         let normalized =
@@ -17,17 +44,29 @@ impl SchedulerRuntime {
         Ok(())
     }
 
-    fn current_reserved_run_names(&self) -> HashSet<String> {
-        let mut reserved_names: HashSet<String> = self
+    fn current_run_names(&self) -> TakenRunNames {
+        let mut taken = TakenRunNames::default();
+        for (spec, rt) in self
             .scheduler
             .job_specs()
             .iter()
             .zip(self.scheduler.job_runtimes().iter())
             .filter(|(_, rt)| JobState::ACTIVE.contains(&rt.state))
-            .filter_map(|(spec, _)| spec.run_name.as_ref().map(|name| name.to_string()))
-            .collect();
-        reserved_names.extend(gflow::tmux::get_all_session_names());
-        reserved_names
+        {
+            let Some(run_name) = spec.run_name.as_deref() else {
+                continue;
+            };
+            if let Some(requested) = requested_run_name(rt.id, run_name) {
+                taken
+                    .requested
+                    .entry(requested.to_string())
+                    .or_default()
+                    .push((rt.id, rt.group_id));
+            }
+            taken.reserved.insert(run_name.to_string());
+        }
+        taken.reserved.extend(gflow::tmux::get_all_session_names());
+        taken
     }
 
     fn allocate_run_name(
@@ -69,9 +108,30 @@ impl SchedulerRuntime {
         }
     }
 
-    fn prepare_run_name(&self, job: &mut Job, job_id: u32, reserved_names: &mut HashSet<String>) {
+    fn prepare_run_name(
+        &self,
+        job: &mut Job,
+        job_id: u32,
+        taken: &mut TakenRunNames,
+    ) -> Result<()> {
         let requested = job.run_name.as_ref().map(|name| name.as_str());
-        let allocated = self.allocate_run_name(job_id, requested, reserved_names);
+        let normalized = requested
+            .map(gflow::tmux::normalize_session_name)
+            .filter(|name| !name.is_empty());
+        if let Some(name) = &normalized {
+            if let Some(other) = taken.shared_with(name, job.group_id) {
+                if self.duplicate_run_names == DuplicateRunNames::Reject {
+                    return Err(ApiError::from(ApiErrorKind::RunNameInUse {
+                        name: name.clone(),
+                        job_id: other,
+                    })
+                    .into());
+                }
+                tracing::info!(run_name = %name, other_job_id = other, "Active job already uses this run_name");
+            }
+        }
+
+        let allocated = self.allocate_run_name(job_id, requested, &taken.reserved);
 
         if let Some(requested_name) = requested {
             if requested_name != allocated {
@@ -83,15 +143,60 @@ impl SchedulerRuntime {
             }
         }
 
-        reserved_names.insert(allocated.clone());
+        if let Some(name) = normalized {
+            taken
+                .requested
+                .entry(name)
+                .or_default()
+                .push((job_id, job.group_id));
+        }
+        taken.reserved.insert(allocated.clone());
         job.run_name = Some(CompactString::from(allocated));
+        Ok(())
+    }
+
+    /// Another active job given the same `--name` as `job_id`, outside its
+    /// group. Group members (array tasks, sweeps) share a name on purpose.
+    pub fn run_name_shared_with(&self, job_id: u32) -> Option<u32> {
+        let job = self.scheduler.get_job(job_id)?;
+        let name = requested_run_name(job_id, job.run_name.as_deref()?)?;
+        self.scheduler
+            .job_specs()
+            .iter()
+            .zip(self.scheduler.job_runtimes().iter())
+            .filter(|(_, rt)| rt.id != job_id && JobState::ACTIVE.contains(&rt.state))
+            .filter(|(_, rt)| job.group_id.is_none() || rt.group_id != job.group_id)
+            .find(|(spec, rt)| {
+                spec.run_name
+                    .as_deref()
+                    .and_then(|run_name| requested_run_name(rt.id, run_name))
+                    == Some(name)
+            })
+            .map(|(_, rt)| rt.id)
+    }
+
+    /// Checks and defaults applied to every submitted job.
+    fn prepare_submission(
+        &self,
+        job: &mut Job,
+        job_id: u32,
+        taken: &mut TakenRunNames,
+    ) -> Result<()> {
+        self.normalize_and_validate_project(job)?;
+        job.tags = Tags::new(job.tags.iter()).map_err(anyhow::Error::msg)?;
+        Self::validate_shared_job_requirements(job)?;
+        self.prepare_run_name(job, job_id, taken)?;
+        let executor = *job.executor.get_or_insert(self.default_executor);
+        if job.interactive && executor != ExecutorKind::Tmux {
+            bail!("Interactive jobs need the tmux executor");
+        }
+        Ok(())
     }
 
     pub async fn submit_job(&mut self, mut job: Job) -> Result<(u32, String, Job)> {
-        self.normalize_and_validate_project(&mut job)?;
-        Self::validate_shared_job_requirements(&job)?;
-        let mut reserved_names = self.current_reserved_run_names();
-        self.prepare_run_name(&mut job, self.scheduler.next_job_id(), &mut reserved_names);
+        let mut taken = self.current_run_names();
+        self.prepare_submission(&mut job, self.scheduler.next_job_id(), &mut taken)?;
+        self.reserve_job_ids(1)?;
         let (job_id, run_name) = self.scheduler.submit_job(job);
         self.mark_dirty();
 
@@ -113,15 +218,15 @@ impl SchedulerRuntime {
             bail!("Batch size exceeds maximum of 1000 jobs");
         }
 
-        let mut reserved_names = self.current_reserved_run_names();
+        let mut taken = self.current_run_names();
         let mut normalized_jobs = Vec::with_capacity(batch_size);
         for (next_job_id, mut job) in (self.scheduler.next_job_id()..).zip(jobs) {
-            self.normalize_and_validate_project(&mut job)?;
-            Self::validate_shared_job_requirements(&job)?;
-            self.prepare_run_name(&mut job, next_job_id, &mut reserved_names);
+            self.prepare_submission(&mut job, next_job_id, &mut taken)?;
             normalized_jobs.push(job);
         }
+        self.reserve_job_ids(batch_size as u32)?;
 
+        let first_id = self.scheduler.next_job_id();
         let mut results = Vec::with_capacity(normalized_jobs.len());
         let mut submitted_jobs = Vec::with_capacity(normalized_jobs.len());
 
@@ -136,119 +241,287 @@ impl SchedulerRuntime {
         }
 
         self.mark_dirty();
+        // A batch is kept whole or not at all: if it cannot be saved, every
+        // job of it is taken back before anyone hears of it.
+        if let Err(error) = self.save_state_now().await {
+            self.scheduler.discard_jobs_from(first_id);
+            self.release_job_ids();
+            tracing::error!(%error, count = batch_size, "Failed to save batch; no job was created");
+            let kind = ApiErrorKind::BatchNotSaved { count: batch_size };
+            let message = format!("{} ({error:#})", ApiError::from(kind.clone()).message);
+            return Err(ApiError::new(kind, message).into());
+        }
         let next_id = self.scheduler.next_job_id();
         Ok((results, submitted_jobs, next_id))
     }
 
+    /// Look up an earlier job submitted under the same client-chosen ID.
+    pub fn prior_submission(&self, submission_id: uuid::Uuid, digest: &str) -> PriorSubmission {
+        self.scheduler
+            .prior_submission(submission_id, digest, std::time::SystemTime::now())
+    }
+
+    /// Remember that `job_id` was created for a client-chosen submission ID.
+    pub fn record_submission(&mut self, submission_id: uuid::Uuid, job_id: u32, digest: String) {
+        self.scheduler.record_submission(
+            submission_id,
+            SubmissionRecord {
+                job_id,
+                digest,
+                at: std::time::SystemTime::now(),
+            },
+        );
+        self.mark_dirty();
+    }
+
     pub async fn finish_job(&mut self, job_id: u32) -> bool {
-        if let Some((should_close_tmux, run_name)) = self.scheduler.finish_job(job_id) {
-            self.mark_dirty();
+        !self.finish_jobs(&[job_id]).is_empty()
+    }
 
-            if let Some(name) = run_name {
-                if should_close_tmux {
-                    // Close tmux session if auto_close is enabled (this also disables pipe-pane)
-                    tracing::info!("Auto-closing tmux session '{}' for job {}", name, job_id);
-                    if let Err(e) = gflow::tmux::kill_session(&name) {
-                        tracing::warn!("Failed to auto-close tmux session '{}': {}", name, e);
-                    }
-                } else {
-                    // Disable pipe-pane to prevent process leaks (keep session alive for user inspection)
-                    disable_pipe_pane_for_job(job_id, &name, false);
-                }
-            }
+    /// Apply `action` to each of `job_ids`, saving the state once for all of
+    /// them. Returns the jobs whose state changed.
+    pub async fn apply_job_action(&mut self, action: JobAction, job_ids: &[u32]) -> Vec<u32> {
+        match action {
+            JobAction::Cancel => self.cancel_jobs(job_ids).await,
+            JobAction::Hold => self.hold_jobs(job_ids),
+            JobAction::Release => self.release_jobs(job_ids),
+            JobAction::Finish => self.finish_jobs(job_ids),
+            JobAction::Fail => self.explicit_fail_jobs(job_ids),
+        }
+    }
 
-            true
-        } else {
-            false
+    fn finish_jobs(&mut self, job_ids: &[u32]) -> Vec<u32> {
+        let mut finished = Vec::new();
+        for &job_id in job_ids {
+            self.record_usage(job_id);
+            if let Some((should_close_tmux, _run_name)) = self.scheduler.finish_job(job_id) {
+                // Close the tmux session if auto_close is enabled; otherwise keep it
+                // for inspection but stop logging to prevent process leaks
+                self.release_job_workload(job_id, should_close_tmux);
+                self.run_post_complete_hook(job_id);
+                finished.push(job_id);
+            }
         }
+        if !finished.is_empty() {
+            self.mark_dirty();
+        }
+        finished
     }
 
     pub async fn fail_job(&mut self, job_id: u32) -> Option<Option<u32>> {
-        // Get run_name before modifying state (needed for PipePane cleanup)
-        let run_name = self
-            .scheduler
-            .get_job(job_id)
-            .and_then(|j| j.run_name.clone());
+        self.fail_job_because(job_id, None).await
+    }
 
-        let result = self.finalize_job_with_retry(job_id, JobState::Failed).await;
+    /// [`Self::fail_job`], recording why the job failed.
+    pub async fn fail_job_because(
+        &mut self,
+        job_id: u32,
+        reason: Option<JobStateReason>,
+    ) -> Option<Option<u32>> {
+        let oom_killed = self.record_usage(job_id);
+        let result = self
+            .finalize_job_with_retry(job_id, JobState::Failed, reason.or(oom_killed))
+            .await;
         if result.is_some() {
-            // Disable PipePane to prevent process leaks (keep session alive for user inspection)
-            if let Some(name) = run_name {
-                disable_pipe_pane_for_job(job_id, &name, false);
-            }
+            self.release_job_workload(job_id, false);
+            self.run_post_complete_hook(job_id);
         }
         result
     }
 
     pub async fn explicit_fail_job(&mut self, job_id: u32) -> bool {
-        let run_name = self
-            .scheduler
-            .get_job(job_id)
-            .and_then(|j| j.run_name.clone());
+        !self.explicit_fail_jobs(&[job_id]).is_empty()
+    }
 
-        let result = self.scheduler.fail_job(job_id);
-        if result {
-            self.mark_dirty();
-            if let Some(name) = run_name {
-                disable_pipe_pane_for_job(job_id, &name, false);
+    fn explicit_fail_jobs(&mut self, job_ids: &[u32]) -> Vec<u32> {
+        let mut failed = Vec::new();
+        for &job_id in job_ids {
+            let oom_killed = self.record_usage(job_id);
+            if self.scheduler.fail_job_because(job_id, oom_killed) {
+                self.release_job_workload(job_id, false);
+                self.run_post_complete_hook(job_id);
+                failed.push(job_id);
             }
         }
-        result
+        if !failed.is_empty() {
+            self.mark_dirty();
+        }
+        failed
     }
 
     pub async fn timeout_job(&mut self, job_id: u32) -> Option<Option<u32>> {
-        let run_name = self
-            .scheduler
-            .get_job(job_id)
-            .and_then(|j| j.run_name.clone());
-
+        self.record_usage(job_id);
         let result = self
-            .finalize_job_with_retry(job_id, JobState::Timeout)
+            .finalize_job_with_retry(job_id, JobState::Timeout, None)
             .await;
         if result.is_some() {
-            if let Some(name) = run_name {
-                disable_pipe_pane_for_job(job_id, &name, false);
-            }
+            // An interactive shell ignores Ctrl-C, so close its session to end it.
+            let interactive = self
+                .scheduler
+                .get_job(job_id)
+                .is_some_and(|job| job.interactive);
+            self.release_job_workload(job_id, interactive);
+            self.run_post_complete_hook(job_id);
         }
         result
     }
 
+    /// Interrupt a running job through its executor (Ctrl-C in tmux, SIGINT
+    /// for direct jobs), or its node's agent for a remote job.
+    pub(super) fn interrupt_job(&self, job_id: u32) {
+        let Some(job) = self.scheduler.get_job(job_id) else {
+            return;
+        };
+        if self.forward_to_node(&job, "interrupt", false) {
+            return;
+        }
+        if let Err(error) = self.executor.interrupt(&job) {
+            tracing::error!(job_id, error = %error, "Failed to interrupt job");
+        }
+    }
+
+    /// Send `signal` to a running job through its executor, or its node's
+    /// agent for a remote job, whose failures are only logged.
+    pub fn signal_job(&self, job: &Job, signal: JobSignal) -> Result<()> {
+        let request = AgentJobRequest {
+            signal: Some(signal),
+            ..AgentJobRequest::new(job.clone())
+        };
+        if self.forward_request_to_node(job, "signal", request) {
+            return Ok(());
+        }
+        self.executor.signal(job, signal)
+    }
+
+    /// Have the executor make sure an interrupted job stops, such as stopping
+    /// its container, without blocking the caller.
+    pub(super) fn stop_job_workload(&self, job_id: u32) {
+        let Some(job) = self.scheduler.get_job(job_id) else {
+            return;
+        };
+        if self.forward_to_node(&job, "stop", false) {
+            return;
+        }
+        let executor = Arc::clone(&self.executor);
+        tokio::task::spawn_blocking(move || {
+            if let Err(error) = executor.stop(&job) {
+                tracing::error!(job_id, error = %error, "Failed to stop job");
+            }
+        });
+    }
+
+    /// Store the peak memory of a job about to stop running on it. Returns
+    /// the reason to fail it with if it was OOM-killed for going over its
+    /// memory limit.
+    fn record_usage(&mut self, job_id: u32) -> Option<JobStateReason> {
+        let peaks = self.take_usage_peaks(job_id);
+        let job = self.scheduler.get_job(job_id)?;
+        if job.state != JobState::Running || job.node.is_some() {
+            return None;
+        }
+        let usage = self.executor.memory_usage(&job);
+        let rt = self.scheduler.get_job_runtime_mut(job_id)?;
+        // A cgroup also counts memory between two samples.
+        rt.peak_rss_mb = peaks.rss_mb.max(usage.and_then(|usage| usage.peak_mb));
+        rt.peak_gpu_mb = peaks.gpu_mb;
+        let usage = usage?;
+        usage
+            .oom_killed
+            .then_some(JobStateReason::MemoryLimitExceeded(usage.peak_mb))
+    }
+
+    /// Let the executor release the job's session or process handle once the
+    /// job is no longer running.
+    pub(super) fn release_job_workload(&self, job_id: u32, close: bool) {
+        if let Ok(path) = gflow::paths::get_progress_file_path(job_id) {
+            let _ = std::fs::remove_file(path);
+        }
+        if let Some(job) = self.scheduler.get_job(job_id) {
+            if !self.forward_to_node(&job, "release", close) {
+                self.executor.release(&job, close);
+            }
+        }
+    }
+
+    /// Hand a job that just reached a final state to the `post_complete` hook.
+    fn run_post_complete_hook(&self, job_id: u32) {
+        if let Some(job) = self.scheduler.get_job(job_id) {
+            self.hooks.post_complete(&job);
+        }
+    }
+
     pub async fn cancel_job(&mut self, job_id: u32) -> bool {
-        if let Some((was_running, run_name)) = self.scheduler.cancel_job(job_id, None) {
-            self.mark_dirty();
+        !self.cancel_jobs(&[job_id]).await.is_empty()
+    }
 
-            // If the job was running, send Ctrl-C to gracefully interrupt it, then disable PipePane
+    /// Cancel `job_ids`, giving the running ones one shared grace period to
+    /// shut down rather than one each.
+    async fn cancel_jobs(&mut self, job_ids: &[u32]) -> Vec<u32> {
+        let mut cancelled = Vec::new();
+        let mut interrupted = Vec::new();
+        let mut wait = false;
+        for &job_id in job_ids {
+            self.record_usage(job_id);
+            let Some((was_running, run_name)) = self.scheduler.cancel_job(job_id, None) else {
+                continue;
+            };
+            cancelled.push(job_id);
+            // If the job was running, interrupt it gracefully, then release its session
             if was_running {
-                if let Some(name) = run_name {
-                    if let Err(e) = gflow::tmux::send_ctrl_c(&name) {
-                        tracing::error!("Failed to send C-c to tmux session {}: {}", name, e);
-                    }
-
-                    // Wait a moment for graceful shutdown, then disable PipePane
-                    tokio::time::sleep(Duration::from_millis(500)).await;
-                    disable_pipe_pane_for_job(job_id, &name, false);
-                }
+                self.interrupt_job(job_id);
+                self.stop_job_workload(job_id);
+                wait |= run_name.is_some();
+                interrupted.push(job_id);
             }
-            true
-        } else {
-            false
         }
+        if cancelled.is_empty() {
+            return cancelled;
+        }
+        self.mark_dirty();
+
+        if wait {
+            // Wait a moment for graceful shutdown
+            tokio::time::sleep(Duration::from_millis(500)).await;
+        }
+        for &job_id in &interrupted {
+            self.release_job_workload(job_id, false);
+        }
+        for &job_id in &cancelled {
+            self.run_post_complete_hook(job_id);
+        }
+        cancelled
     }
 
     pub async fn hold_job(&mut self, job_id: u32) -> bool {
-        let result = self.scheduler.hold_job(job_id);
-        if result {
+        !self.hold_jobs(&[job_id]).is_empty()
+    }
+
+    fn hold_jobs(&mut self, job_ids: &[u32]) -> Vec<u32> {
+        let held: Vec<u32> = job_ids
+            .iter()
+            .copied()
+            .filter(|&job_id| self.scheduler.hold_job(job_id))
+            .collect();
+        if !held.is_empty() {
             self.mark_dirty();
         }
-        result
+        held
     }
 
     pub async fn release_job(&mut self, job_id: u32) -> bool {
-        let result = self.scheduler.release_job(job_id);
-        if result {
+        !self.release_jobs(&[job_id]).is_empty()
+    }
+
+    fn release_jobs(&mut self, job_ids: &[u32]) -> Vec<u32> {
+        let released: Vec<u32> = job_ids
+            .iter()
+            .copied()
+            .filter(|&job_id| self.scheduler.release_job(job_id))
+            .collect();
+        if !released.is_empty() {
             self.mark_dirty();
         }
-        result
+        released
     }
 
     /// Update max_concurrent for a specific job
@@ -259,13 +532,25 @@ impl SchedulerRuntime {
         self.scheduler.get_job(job_id)
     }
 
+    /// Add and remove tags of a job in any state; see [`Scheduler::update_job_tags`].
+    pub fn update_job_tags(
+        &mut self,
+        job_id: u32,
+        add: &[String],
+        remove: &[String],
+    ) -> Result<Tags, String> {
+        let tags = self.scheduler.update_job_tags(job_id, add, remove)?;
+        self.mark_dirty();
+        Ok(tags)
+    }
+
     /// Update job parameters
-    /// Returns Ok((updated_job, updated_fields)) on success, Err(error_message) on failure
+    /// Returns Ok((updated_job, updated_fields)) on success
     pub async fn update_job(
         &mut self,
         job_id: u32,
         request: crate::multicall::gflowd::server::UpdateJobRequest,
-    ) -> Result<(Job, Vec<String>), String> {
+    ) -> Result<(Job, Vec<String>), ApiError> {
         let mut updated_fields = Vec::new();
         let old_deps = self.scheduler.dependency_ids_for_job(job_id);
 
@@ -274,14 +559,16 @@ impl SchedulerRuntime {
         self.scheduler.validate_job_update(job_id, new_deps)?;
 
         // Enforce shared-job invariant before mutating state.
-        if let Some((_spec, rt)) = self.scheduler.get_job_parts(job_id) {
+        if let Some((spec, rt)) = self.scheduler.get_job_parts(job_id) {
             if rt.gpu_sharing_mode == GpuSharingMode::Shared
                 && matches!(request.gpu_memory_limit_mb, Some(None))
             {
-                return Err(
-                    "Shared jobs must keep a GPU memory limit (--gpu-memory / --max-gpu-mem)."
-                        .to_string(),
-                );
+                return Err(ApiError::invalid_request(
+                    "Shared jobs must keep a GPU memory limit (--gpu-memory / --max-gpu-mem).",
+                ));
+            }
+            if let Some(priority) = request.priority {
+                self.validate_priority(priority, &spec.submitted_by)?;
             }
         }
 
@@ -289,7 +576,7 @@ impl SchedulerRuntime {
             let (spec, rt) = self
                 .scheduler
                 .get_job_parts_mut(job_id)
-                .ok_or_else(|| format!("Job {} not found", job_id))?;
+                .ok_or(ApiErrorKind::JobNotFound { id: job_id })?;
 
             // Apply updates (spec)
             if let Some(command) = request.command {
@@ -308,7 +595,7 @@ impl SchedulerRuntime {
             }
 
             if let Some(conda_env) = request.conda_env {
-                spec.conda_env = conda_env.map(compact_str::CompactString::from);
+                spec.set_conda_env(conda_env);
                 updated_fields.push("conda_env".to_string());
             }
 
@@ -390,6 +677,9 @@ impl SchedulerRuntime {
         } else if affects_ready_queue {
             self.scheduler.refresh_job_readiness(job_id);
         }
+        if updated_fields.iter().any(|f| f == "conda_env") {
+            self.scheduler.intern_job_strings(job_id);
+        }
 
         // Mark state as dirty for persistence
         self.mark_dirty();
@@ -398,7 +688,7 @@ impl SchedulerRuntime {
         let updated_job = self
             .scheduler
             .get_job(job_id)
-            .ok_or_else(|| format!("Job {} not found", job_id))?;
+            .ok_or(ApiErrorKind::JobNotFound { id: job_id })?;
         Ok((updated_job, updated_fields))
     }
 }
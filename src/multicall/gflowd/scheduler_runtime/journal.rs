@@ -0,0 +1,138 @@
+//! Recovery journal
+//!
+//! When the state file cannot be written (recovery mode), gflowd persists to
+//! `state.journal.jsonl` instead: one JSON line per entry, each holding a full
+//! scheduler snapshot. The journal is truncated once a regular state save
+//! succeeds again.
+
+use gflow::core::scheduler::Scheduler;
+use std::path::Path;
+
+/// File name of the journal inside the state directory.
+pub(crate) const JOURNAL_FILE: &str = "state.journal.jsonl";
+
+/// A journal line that parsed.
+#[derive(serde::Deserialize)]
+pub(crate) struct JournalEntry {
+    /// Seconds since the Unix epoch when the entry was written.
+    pub ts: u64,
+    pub kind: String,
+    pub scheduler: Scheduler,
+}
+
+/// Every non-blank line of the journal with its 1-based line number, parsed
+/// or with the reason it could not be.
+pub(crate) fn read_entries(
+    journal_path: &Path,
+) -> std::io::Result<Vec<(usize, Result<JournalEntry, String>)>> {
+    let content = match std::fs::read_to_string(journal_path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+    Ok(content
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(index, line)| {
+            let entry =
+                serde_json::from_str::<JournalEntry>(line.trim()).map_err(|e| e.to_string());
+            (index + 1, entry)
+        })
+        .collect())
+}
+
+/// The newest snapshot that parses, with its timestamp.
+pub(crate) fn load_last_snapshot(journal_path: &Path) -> Option<(Scheduler, u64)> {
+    read_entries(journal_path)
+        .ok()?
+        .into_iter()
+        .filter_map(|(_, entry)| entry.ok())
+        .rfind(|entry| entry.kind == "snapshot")
+        .map(|entry| (entry.scheduler, entry.ts))
+}
+
+/// Empty the journal so that gflowd loads the state file.
+pub(crate) fn truncate(journal_path: &Path) -> std::io::Result<()> {
+    match std::fs::OpenOptions::new()
+        .write(true)
+        .truncate(true)
+        .open(journal_path)
+    {
+        Ok(_) => Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(e),
+    }
+}
+
+/// Whether the journal holds changes newer than the state file, so that gflowd
+/// loads it instead.
+pub(crate) fn should_apply(state_path: &Path, journal_path: &Path) -> bool {
+    let Ok(j_meta) = std::fs::metadata(journal_path) else {
+        return false;
+    };
+    if j_meta.len() == 0 {
+        return false;
+    }
+    let Ok(j_mtime) = j_meta.modified() else {
+        return true;
+    };
+
+    let Ok(s_meta) = std::fs::metadata(state_path) else {
+        return true;
+    };
+    let Ok(s_mtime) = s_meta.modified() else {
+        return true;
+    };
+
+    j_mtime >= s_mtime
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gflow::core::job::JobBuilder;
+    use gflow::core::scheduler::SchedulerBuilder;
+
+    fn snapshot_line(jobs: usize, ts: u64) -> String {
+        let mut scheduler = SchedulerBuilder::new().build();
+        for _ in 0..jobs {
+            scheduler.submit_job(JobBuilder::new().command("true").build());
+        }
+        serde_json::json!({ "ts": ts, "kind": "snapshot", "scheduler": scheduler }).to_string()
+    }
+
+    #[test]
+    fn last_good_snapshot_wins_over_a_torn_line() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(JOURNAL_FILE);
+        let torn = &snapshot_line(3, 30)[..40];
+        std::fs::write(
+            &path,
+            format!(
+                "{}\n\n{}\n{torn}\n",
+                snapshot_line(1, 10),
+                snapshot_line(2, 20)
+            ),
+        )
+        .unwrap();
+
+        let entries = read_entries(&path).unwrap();
+        let lines: Vec<usize> = entries.iter().map(|(line, _)| *line).collect();
+        assert_eq!(lines, [1, 3, 4]);
+        assert!(entries[2].1.is_err());
+
+        let (scheduler, ts) = load_last_snapshot(&path).unwrap();
+        assert_eq!(ts, 20);
+        assert_eq!(scheduler.job_specs().len(), 2);
+    }
+
+    #[test]
+    fn missing_journal_has_no_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(JOURNAL_FILE);
+        assert!(read_entries(&path).unwrap().is_empty());
+        assert!(load_last_snapshot(&path).is_none());
+        assert!(!should_apply(&dir.path().join("state.json"), &path));
+    }
+}
@@ -1,10 +1,13 @@
 use super::super::events::{EventBus, EventEnvelope, SchedulerEvent};
 use super::*;
-use gflow::tmux::disable_pipe_pane_for_job;
 use std::sync::Arc;
 
 const ZOMBIE_STARTUP_GRACE_PERIOD: Duration = Duration::from_secs(30);
 
+/// How long an unreferenced `gbatch --stdin` script is kept, so a submission
+/// still in flight (or spooled) does not lose its script.
+const STDIN_SCRIPT_GRACE_PERIOD: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
 fn should_check_missing_session_as_zombie(
     started_at: Option<std::time::SystemTime>,
     now: std::time::SystemTime,
@@ -52,33 +55,38 @@ pub(super) async fn gpu_monitor_task(
                 previous_gpu_states.insert(gpu_info.index, gpu_info.available);
             }
         }
+
+        // Uses the GPU memory of each process NVML just reported
+        super::usage::sample(&state).await;
+        super::progress::poll(&state).await;
+        super::artifacts::poll(&state).await;
     }
 }
 
-/// Zombie monitor task - checks tmux sessions every 10s
+/// Zombie monitor task - asks the executor every 10s whether running jobs are alive
 pub(super) async fn zombie_monitor_task(state: SharedState, event_bus: Arc<EventBus>) {
     let mut interval = tokio::time::interval(Duration::from_secs(10));
+    // Container jobs whose container was not running at the previous check.
+    // A container is gone for a moment between exiting and `gcancel` reporting
+    // the outcome, so only a second miss in a row makes the job a zombie.
+    let mut stopped_containers: HashSet<u32> = HashSet::new();
 
     loop {
         interval.tick().await;
 
         // Collect running jobs (with read lock)
-        let running_jobs = {
+        let (running_jobs, executor) = {
             let state_guard = state.read().await;
-            state_guard
+            let running_jobs = state_guard
                 .job_runtimes()
                 .iter()
                 .filter(|rt| rt.state == JobState::Running)
-                .map(|rt| {
-                    let run_name = state_guard
-                        .scheduler
-                        .get_job_spec(rt.id)
-                        .and_then(|spec| spec.run_name.clone());
-                    (rt.id, run_name, rt.started_at)
-                })
-                .collect::<Vec<_>>()
+                .filter_map(|rt| state_guard.scheduler.get_job(rt.id))
+                .collect::<Vec<_>>();
+            (running_jobs, Arc::clone(&state_guard.executor))
         };
 
+        stopped_containers.retain(|id| running_jobs.iter().any(|job| job.id == *id));
         if running_jobs.is_empty() {
             continue;
         }
@@ -87,20 +95,42 @@ pub(super) async fn zombie_monitor_task(state: SharedState, event_bus: Arc<Event
         // started during snapshot construction don't look like future starts.
         let now = std::time::SystemTime::now();
 
-        // Get all tmux sessions in a single batch call (no lock held)
-        let existing_sessions = gflow::tmux::get_all_session_names();
-
-        // Check which jobs are zombies
-        for (job_id, run_name, started_at) in running_jobs {
-            if let Some(rn) = run_name {
-                if !should_check_missing_session_as_zombie(started_at, now) {
-                    continue;
-                }
-                if !existing_sessions.contains(rn.as_str()) {
-                    tracing::warn!(job_id, run_name = %rn, "Found zombie job");
-                    event_bus.publish(SchedulerEvent::ZombieJobDetected { job_id });
-                }
+        // Check which jobs are zombies (no lock held)
+        for job in running_jobs {
+            // Remote jobs are lost with their node; see `node_monitor_task`.
+            if job.node.is_some() || !should_check_missing_session_as_zombie(job.started_at, now) {
+                continue;
+            }
+            if executor.is_running(&job) != Some(false) {
+                stopped_containers.remove(&job.id);
+                continue;
             }
+            if job.container_image.is_some() && stopped_containers.insert(job.id) {
+                continue;
+            }
+            tracing::warn!(job_id = job.id, run_name = ?job.run_name, "Found zombie job");
+            stopped_containers.remove(&job.id);
+            event_bus.publish(SchedulerEvent::ZombieJobDetected { job_id: job.id });
+        }
+    }
+}
+
+/// Node monitor task - marks nodes down once their heartbeats are overdue,
+/// and reports the jobs running on down nodes as zombies
+pub(super) async fn node_monitor_task(state: SharedState, event_bus: Arc<EventBus>) {
+    let mut interval = tokio::time::interval(gflow::core::node::NODE_HEARTBEAT_INTERVAL);
+
+    loop {
+        interval.tick().await;
+
+        let (down, lost_jobs) = state.write().await.mark_stale_nodes_down();
+        for name in down {
+            tracing::warn!(node = %name, "Node missed its heartbeats; marked down");
+            event_bus.publish(SchedulerEvent::NodeDown { name });
+        }
+        for job_id in lost_jobs {
+            tracing::warn!(job_id, "Found zombie job on a down node");
+            event_bus.publish(SchedulerEvent::ZombieJobDetected { job_id });
         }
     }
 }
@@ -119,15 +149,6 @@ pub(super) async fn zombie_handler_task(
                 let SchedulerEvent::ZombieJobDetected { job_id } = event.event else {
                     continue;
                 };
-                // Get run_name before acquiring write lock
-                let run_name = {
-                    let state_guard = state.read().await;
-                    state_guard
-                        .scheduler
-                        .get_job_spec(job_id)
-                        .and_then(|spec| spec.run_name.clone())
-                };
-
                 // Update job state (write lock)
                 let result = {
                     let mut state_guard = state.write().await;
@@ -138,12 +159,6 @@ pub(super) async fn zombie_handler_task(
                 } else if result.is_some() {
                     tracing::info!(job_id, "Marked zombie job as failed");
                 }
-
-                // Disable PipePane if session still exists (no lock held)
-                // This handles the case where the session was manually killed but PipePane might still be active
-                if let Some(rn) = run_name {
-                    disable_pipe_pane_for_job(job_id, &rn, true);
-                }
             }
             Err(tokio::sync::broadcast::error::RecvError::Lagged(skipped)) => {
                 tracing::warn!(skipped, "Zombie handler lagged");
@@ -213,19 +228,16 @@ pub(super) async fn timeout_handler_task(
             Ok(event) => {
                 let handling_span = event.handling_span("timeout_handler");
                 let _entered = handling_span.enter();
-                let SchedulerEvent::JobTimedOut { job_id, run_name } = event.event else {
+                let SchedulerEvent::JobTimedOut { job_id, .. } = event.event else {
                     continue;
                 };
-                // Send Ctrl-C to terminate the job (no lock held)
-                if let Some(rn) = &run_name {
-                    if let Err(e) = gflow::tmux::send_ctrl_c(rn) {
-                        tracing::error!(job_id, error = %e, "Failed to send Ctrl-C to timed-out job");
-                    }
-                }
+                // Interrupt the job (read lock)
+                state.read().await.interrupt_job(job_id);
 
                 // Update job state (write lock)
                 let result = {
                     let mut state_guard = state.write().await;
+                    state_guard.stop_job_workload(job_id);
                     state_guard.timeout_job(job_id).await
                 };
 
@@ -366,6 +378,72 @@ pub(super) async fn reservation_monitor_task(
     }
 }
 
+/// Script cleanup - hourly, removes stdin and uploaded scripts no known job uses
+pub(super) async fn stdin_script_cleanup_task(state: SharedState) {
+    let scripts_dir = match gflow::paths::get_scripts_dir() {
+        Ok(dir) => dir,
+        Err(error) => {
+            tracing::warn!(%error, "Stdin script cleanup disabled");
+            return;
+        }
+    };
+    let mut interval = tokio::time::interval(Duration::from_secs(60 * 60));
+
+    loop {
+        interval.tick().await;
+
+        let referenced: HashSet<PathBuf> = {
+            let state_guard = state.read().await;
+            state_guard
+                .scheduler
+                .job_specs()
+                .iter()
+                .filter_map(|spec| spec.script.as_deref())
+                .filter(|script| script.starts_with(&scripts_dir))
+                .cloned()
+                .collect()
+        };
+
+        let removed =
+            remove_unreferenced_scripts(&scripts_dir, &referenced, std::time::SystemTime::now());
+        if removed > 0 {
+            tracing::info!(removed, "Removed stdin scripts no job refers to");
+        }
+    }
+}
+
+fn remove_unreferenced_scripts(
+    scripts_dir: &std::path::Path,
+    referenced: &HashSet<PathBuf>,
+    now: std::time::SystemTime,
+) -> usize {
+    let Ok(entries) = std::fs::read_dir(scripts_dir) else {
+        return 0;
+    };
+
+    let mut removed = 0;
+    for path in entries.filter_map(|entry| entry.ok().map(|entry| entry.path())) {
+        if referenced.contains(&path) {
+            continue;
+        }
+        let old_enough = std::fs::metadata(&path)
+            .and_then(|metadata| metadata.modified())
+            .ok()
+            .and_then(|modified| now.duration_since(modified).ok())
+            .is_some_and(|age| age >= STDIN_SCRIPT_GRACE_PERIOD);
+        if !old_enough {
+            continue;
+        }
+        match std::fs::remove_file(&path) {
+            Ok(()) => removed += 1,
+            Err(error) => {
+                tracing::warn!(path = %path.display(), %error, "Failed to remove stdin script")
+            }
+        }
+    }
+    removed
+}
+
 /// Calculate the next reservation status transition time
 fn calculate_next_reservation_transition(
     reservations: &[gflow::core::reservation::GpuReservation],
@@ -381,7 +459,7 @@ fn calculate_next_reservation_transition(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use gflow::core::reservation::{GpuReservation, GpuSpec, ReservationStatus};
+    use gflow::core::reservation::{GpuReservation, GpuSpec, ReservationKind, ReservationStatus};
     use std::time::{Duration, SystemTime};
 
     #[test]
@@ -411,6 +489,28 @@ mod tests {
         assert!(should_check_missing_session_as_zombie(started_at, now));
     }
 
+    #[test]
+    fn stdin_script_cleanup_keeps_referenced_and_recent_scripts() {
+        let dir = tempfile::tempdir().unwrap();
+        let referenced_script = dir.path().join("referenced.sh");
+        let orphan_script = dir.path().join("orphan.sh");
+        std::fs::write(&referenced_script, "echo hi").unwrap();
+        std::fs::write(&orphan_script, "echo hi").unwrap();
+        let referenced = HashSet::from([referenced_script.clone()]);
+
+        let now = SystemTime::now();
+        assert_eq!(remove_unreferenced_scripts(dir.path(), &referenced, now), 0);
+        assert!(orphan_script.exists());
+
+        let later = now + STDIN_SCRIPT_GRACE_PERIOD + Duration::from_secs(60);
+        assert_eq!(
+            remove_unreferenced_scripts(dir.path(), &referenced, later),
+            1
+        );
+        assert!(!orphan_script.exists());
+        assert!(referenced_script.exists());
+    }
+
     #[test]
     fn test_calculate_next_transition_no_reservations() {
         let reservations = vec![];
@@ -432,6 +532,8 @@ mod tests {
             status: ReservationStatus::Pending,
             created_at: now,
             cancelled_at: None,
+            kind: ReservationKind::User,
+            recurrence: None,
         };
 
         let result = calculate_next_reservation_transition(&[reservation]);
@@ -454,6 +556,8 @@ mod tests {
             status: ReservationStatus::Active,
             created_at: now - Duration::from_secs(2000),
             cancelled_at: None,
+            kind: ReservationKind::User,
+            recurrence: None,
         };
 
         let result = calculate_next_reservation_transition(&[reservation.clone()]);
@@ -482,6 +586,8 @@ mod tests {
                 status: ReservationStatus::Pending,
                 created_at: now,
                 cancelled_at: None,
+                kind: ReservationKind::User,
+                recurrence: None,
             },
             GpuReservation {
                 id: 2,
@@ -492,6 +598,8 @@ mod tests {
                 status: ReservationStatus::Pending,
                 created_at: now,
                 cancelled_at: None,
+                kind: ReservationKind::User,
+                recurrence: None,
             },
             GpuReservation {
                 id: 3,
@@ -502,6 +610,8 @@ mod tests {
                 status: ReservationStatus::Pending,
                 created_at: now,
                 cancelled_at: None,
+                kind: ReservationKind::User,
+                recurrence: None,
             },
         ];
 
@@ -526,6 +636,8 @@ mod tests {
                 status: ReservationStatus::Pending,
                 created_at: now - Duration::from_secs(7200),
                 cancelled_at: None,
+                kind: ReservationKind::User,
+                recurrence: None,
             },
             GpuReservation {
                 id: 2,
@@ -536,6 +648,8 @@ mod tests {
                 status: ReservationStatus::Pending,
                 created_at: now,
                 cancelled_at: None,
+                kind: ReservationKind::User,
+                recurrence: None,
             },
         ];
 
@@ -558,6 +672,8 @@ mod tests {
             status: ReservationStatus::Cancelled,
             created_at: now,
             cancelled_at: Some(now),
+            kind: ReservationKind::User,
+            recurrence: None,
         };
 
         let result = calculate_next_reservation_transition(&[reservation]);
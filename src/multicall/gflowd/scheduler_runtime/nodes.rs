@@ -0,0 +1,105 @@
+use super::*;
+use gflow::core::node::{Node, NodeRegistration, NodeStatus};
+
+impl SchedulerRuntime {
+    /// Register a node, or record its heartbeat. Returns `true` when the
+    /// node just came up, so queued jobs may now fit on it.
+    pub fn register_node(&mut self, registration: NodeRegistration) -> bool {
+        let came_up = self
+            .scheduler
+            .register_node(registration, std::time::SystemTime::now());
+        if came_up {
+            self.mark_dirty();
+        }
+        came_up
+    }
+
+    /// Registered nodes, without the secrets agents gave us.
+    pub fn nodes(&self) -> Vec<Node> {
+        self.scheduler
+            .nodes()
+            .values()
+            .map(|node| Node {
+                token: String::new(),
+                ..node.clone()
+            })
+            .collect()
+    }
+
+    /// Mark nodes with overdue heartbeats as down. Returns their names and
+    /// the running jobs on any down node, which can no longer be reached.
+    pub(super) fn mark_stale_nodes_down(&mut self) -> (Vec<String>, Vec<u32>) {
+        let down = self
+            .scheduler
+            .mark_stale_nodes_down(std::time::SystemTime::now());
+        if !down.is_empty() {
+            self.mark_dirty();
+        }
+        (down, self.scheduler.jobs_on_down_nodes())
+    }
+
+    /// The node `job` was placed on and the request that starts it there,
+    /// or `None` for a job on this machine.
+    pub(super) fn remote_execution(&self, job: &Job) -> Option<Result<(Node, AgentJobRequest)>> {
+        let name = job.node.as_deref()?;
+        Some((|| {
+            let node = self
+                .scheduler
+                .get_node(name)
+                .with_context(|| format!("Unknown node '{name}'"))?;
+            if node.status == NodeStatus::Down {
+                bail!("Node '{name}' is down");
+            }
+            let mut request = AgentJobRequest::new(job.clone());
+            if let Some(job_tokens) = &self.job_tokens {
+                request
+                    .env
+                    .push(("GFLOW_JOB_TOKEN".to_string(), job_tokens.job_token(job.id)?));
+            }
+            Ok((node.clone(), request))
+        })())
+    }
+
+    /// Send `action` for a job placed on a remote node to its agent, in the
+    /// background. Returns `false` for a job on this machine.
+    pub(super) fn forward_to_node(&self, job: &Job, action: &'static str, close: bool) -> bool {
+        let request = AgentJobRequest {
+            close,
+            ..AgentJobRequest::new(job.clone())
+        };
+        self.forward_request_to_node(job, action, request)
+    }
+
+    /// [`Self::forward_to_node`] with a request of the caller's making.
+    pub(super) fn forward_request_to_node(
+        &self,
+        job: &Job,
+        action: &'static str,
+        request: AgentJobRequest,
+    ) -> bool {
+        let Some(name) = job.node.as_deref() else {
+            return false;
+        };
+        let Some(node) = self
+            .scheduler
+            .get_node(name)
+            .filter(|node| node.status == NodeStatus::Up)
+            .cloned()
+        else {
+            tracing::warn!(
+                job_id = job.id,
+                node = name,
+                "Node is not up; cannot {action} job"
+            );
+            return true;
+        };
+
+        let agents = self.agents.clone();
+        tokio::spawn(async move {
+            if let Err(error) = agents.send(&node, action, &request).await {
+                tracing::error!(job_id = request.job.id, error = %error, "Failed to {action} job");
+            }
+        });
+        true
+    }
+}
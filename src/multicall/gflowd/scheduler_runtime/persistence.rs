@@ -1,11 +1,21 @@
 use super::*;
 
+/// Most job ids `apply_job_id_high_water` will record as lost at startup.
+const MAX_LOST_JOBS: u32 = 10_000;
+
 impl SchedulerRuntime {
     /// Save scheduler state to disk asynchronously
     pub async fn save_state(&mut self) {
+        if let Err(e) = self.try_save_state().await {
+            tracing::error!("Failed to save scheduler state: {e:#}");
+        }
+    }
+
+    /// [`Self::save_state`], returning what went wrong. Writes a journal
+    /// snapshot instead when the state file is not writable.
+    pub(super) async fn try_save_state(&mut self) -> Result<()> {
         if !self.state_writable {
-            self.append_journal_snapshot().await;
-            return;
+            return self.append_journal_snapshot().await;
         }
 
         let state_dir = self
@@ -14,37 +24,41 @@ impl SchedulerRuntime {
             .parent()
             .unwrap_or_else(|| std::path::Path::new("."));
 
-        match serialization::save_state(
+        serialization::save_state(
             &self.scheduler,
             state_dir,
             serialization::SerializationFormat::MessagePack,
-        ) {
-            Ok(_) => {
-                if self.journal_applied {
-                    if let Err(e) = tokio::fs::OpenOptions::new()
-                        .write(true)
-                        .truncate(true)
-                        .open(&self.journal_path)
-                        .await
-                    {
-                        tracing::warn!(
-                            "Failed to truncate journal file {}: {}",
-                            self.journal_path.display(),
-                            e
-                        );
-                    } else {
-                        self.journal_applied = false;
-                    }
-                }
-            }
-            Err(e) => {
-                tracing::error!("Failed to save scheduler state: {}", e);
+        )?;
+        if self.journal_applied {
+            if let Err(e) = tokio::fs::OpenOptions::new()
+                .write(true)
+                .truncate(true)
+                .open(&self.journal_path)
+                .await
+            {
+                tracing::warn!(
+                    "Failed to truncate journal file {}: {}",
+                    self.journal_path.display(),
+                    e
+                );
+            } else {
+                self.journal_applied = false;
             }
         }
+        Ok(())
+    }
+
+    /// Save right away, failing rather than leaving it to the state saver.
+    pub(super) async fn save_state_now(&mut self) -> Result<()> {
+        self.try_save_state().await?;
+        self.dirty = false;
+        Ok(())
     }
 
     /// Mark state as dirty without saving immediately
     pub(super) fn mark_dirty(&mut self) {
+        self.scheduler.bump_revision();
+        self.publish_transitions();
         if !(self.state_writable || self.journal_writable) {
             return;
         }
@@ -54,6 +68,22 @@ impl SchedulerRuntime {
         }
     }
 
+    /// Announce the job state changes made since the last call.
+    fn publish_transitions(&mut self) {
+        let transitions = self.scheduler.take_transitions();
+        let Some(event_bus) = &self.event_bus else {
+            return;
+        };
+        for transition in transitions {
+            event_bus.publish(SchedulerEvent::JobStateChanged {
+                job_id: transition.job_id,
+                old_state: transition.from,
+                new_state: transition.to,
+                reason: transition.reason,
+            });
+        }
+    }
+
     /// Save state only if dirty flag is set, then clear flag
     pub async fn save_state_if_dirty(&mut self) {
         if self.dirty {
@@ -64,6 +94,64 @@ impl SchedulerRuntime {
         }
     }
 
+    /// Write the in-memory state to a new rotated backup. With `skip_unchanged`,
+    /// nothing is written if the newest backup already holds this state.
+    pub fn backup_state(&self, skip_unchanged: bool) -> Result<Option<PathBuf>> {
+        let state_dir = self
+            .scheduler
+            .state_path()
+            .parent()
+            .unwrap_or_else(|| std::path::Path::new("."));
+        if skip_unchanged && backups::matches_latest(&self.scheduler, state_dir) {
+            return Ok(None);
+        }
+        backups::create(&self.scheduler, state_dir, self.backups_kept).map(Some)
+    }
+
+    /// Replace jobs and reservations with a restored state and write it out
+    /// right away.
+    pub async fn restore_state(&mut self, restored: Scheduler) {
+        self.apply_loaded_scheduler(restored);
+        self.mark_dirty();
+        self.save_state_if_dirty().await;
+        // The restore was checked (or forced) against the ids in use; number
+        // on from the restored state.
+        if let Err(e) = job_ids::store(&self.job_ids_path, self.scheduler.next_job_id()) {
+            tracing::warn!(
+                "Failed to update job id high-water mark in {}: {}",
+                self.job_ids_path.display(),
+                e
+            );
+        }
+    }
+
+    /// Record that `count` more job ids are about to be handed out, before any
+    /// of them reaches a client.
+    pub(super) fn reserve_job_ids(&mut self, count: u32) -> Result<()> {
+        let mark = self.scheduler.next_job_id().saturating_add(count);
+        if let Err(e) = job_ids::store(&self.job_ids_path, mark) {
+            let message = format!(
+                "Failed to record job id high-water mark in {}: {e}",
+                self.job_ids_path.display()
+            );
+            tracing::error!("{}", message);
+            return Err(ApiError::internal(message).into());
+        }
+        Ok(())
+    }
+
+    /// Give back the ids [`Self::reserve_job_ids`] reserved past the jobs that
+    /// were created, so they are not recorded as lost at the next start.
+    pub(super) fn release_job_ids(&mut self) {
+        let mark = self.scheduler.next_job_id();
+        if let Err(e) = job_ids::store(&self.job_ids_path, mark) {
+            tracing::warn!(
+                "Failed to lower job id high-water mark in {}: {e}",
+                self.job_ids_path.display()
+            );
+        }
+    }
+
     /// Set the state saver handle for async background persistence
     pub fn set_state_saver(&mut self, saver: StateSaverHandle) {
         let should_kick = self.dirty;
@@ -156,8 +244,8 @@ impl SchedulerRuntime {
         }
 
         let legacy_json_path = state_dir.join("state.json");
-        if should_apply_journal(&legacy_json_path, &self.journal_path) {
-            if let Some((snapshot, ts)) = load_last_journal_snapshot(&self.journal_path) {
+        if journal::should_apply(&legacy_json_path, &self.journal_path) {
+            if let Some((snapshot, ts)) = journal::load_last_snapshot(&self.journal_path) {
                 tracing::warn!(
                     "Loading scheduler state from journal snapshot (ts={}) at {}",
                     ts,
@@ -174,6 +262,7 @@ impl SchedulerRuntime {
         if let Some(scheduler) = loaded {
             self.apply_loaded_scheduler(scheduler);
         }
+        self.apply_job_id_high_water();
 
         self.reinitialize_runtime_resources();
     }
@@ -209,6 +298,49 @@ impl SchedulerRuntime {
         }
     }
 
+    /// Never number on below an id that may already have been handed out.
+    /// Ids below the recorded high-water mark that the loaded state lacks
+    /// belong to submissions lost in a crash; they are kept as failed jobs.
+    fn apply_job_id_high_water(&mut self) {
+        let after_last_job = self.scheduler.jobs_len() as u32 + 1;
+        if self.scheduler.next_job_id() < after_last_job {
+            self.scheduler.set_next_job_id(after_last_job);
+        }
+
+        let recorded = match job_ids::load(&self.job_ids_path) {
+            Ok(recorded) => recorded.unwrap_or(0),
+            Err(e) => {
+                tracing::error!(
+                    "Failed to read job id high-water mark from {}: {:#}",
+                    self.job_ids_path.display(),
+                    e
+                );
+                return;
+            }
+        };
+        // Far more unsaved submissions than a crash could lose: the mark
+        // itself is wrong, and acting on it would create that many jobs.
+        if recorded.saturating_sub(self.scheduler.next_job_id()) > MAX_LOST_JOBS {
+            tracing::error!(
+                recorded,
+                next_job_id = self.scheduler.next_job_id(),
+                "Ignoring implausible job id high-water mark in {}",
+                self.job_ids_path.display()
+            );
+            return;
+        }
+        let lost = self
+            .scheduler
+            .record_lost_jobs(recorded, "Lost in a gflowd crash before it was saved");
+        if !lost.is_empty() {
+            tracing::warn!(
+                ?lost,
+                "Jobs were submitted but not saved before gflowd stopped; marked them failed"
+            );
+            self.mark_dirty();
+        }
+    }
+
     fn apply_loaded_scheduler(&mut self, loaded: Scheduler) {
         self.scheduler.apply_persisted_state(loaded);
         self.scheduler.rebuild_user_jobs_index();
@@ -233,26 +365,25 @@ impl SchedulerRuntime {
         let total_memory_mb = Self::get_total_system_memory_mb();
         self.scheduler.update_memory(total_memory_mb);
         self.scheduler.refresh_available_memory();
+        self.scheduler.update_cpus(gflow::core::cpu::total_cpus());
     }
 
-    async fn append_journal_snapshot(&mut self) {
+    async fn append_journal_snapshot(&mut self) -> Result<()> {
         if !self.journal_writable {
-            tracing::error!(
+            bail!(
                 "Refusing to persist state: state.json is not writable and journal is not writable"
             );
-            return;
         }
 
         if let Some(parent) = self.journal_path.parent() {
             if let Err(e) = tokio::fs::create_dir_all(parent).await {
-                tracing::error!(
+                self.journal_writable = false;
+                self.journal_error = Some(format!("Failed to create journal dir: {e}"));
+                bail!(
                     "Failed to create journal directory {}: {}",
                     parent.display(),
                     e
                 );
-                self.journal_writable = false;
-                self.journal_error = Some(format!("Failed to create journal dir: {e}"));
-                return;
             }
         }
 
@@ -270,6 +401,8 @@ impl SchedulerRuntime {
             allowed_gpu_indices: Option<&'a Vec<u32>>,
             reservations: &'a Vec<gflow::core::reservation::GpuReservation>,
             next_reservation_id: u32,
+            runtime_stats: &'a gflow::core::runtime_stats::RuntimeStats,
+            artifacts: &'a std::collections::BTreeMap<u32, Vec<gflow::core::job::JobArtifact>>,
         }
 
         #[derive(serde::Serialize)]
@@ -296,16 +429,12 @@ impl SchedulerRuntime {
                 allowed_gpu_indices: self.scheduler.allowed_gpu_indices(),
                 reservations: &self.scheduler.reservations,
                 next_reservation_id: self.scheduler.next_reservation_id,
+                runtime_stats: self.scheduler.runtime_stats(),
+                artifacts: self.scheduler.artifacts(),
             },
         };
 
-        let line = match serde_json::to_string(&entry) {
-            Ok(s) => s,
-            Err(e) => {
-                tracing::error!("Failed to serialize journal entry: {}", e);
-                return;
-            }
-        };
+        let line = serde_json::to_string(&entry).context("Failed to serialize journal entry")?;
 
         let tmp_path = self.journal_path.with_extension("jsonl.tmp");
         match tokio::fs::File::create(&tmp_path).await {
@@ -314,12 +443,11 @@ impl SchedulerRuntime {
                     tokio::io::AsyncWriteExt::write_all(&mut file, format!("{line}\n").as_bytes())
                         .await
                 {
-                    tracing::error!(
+                    bail!(
                         "Failed to write journal snapshot to {}: {}",
                         tmp_path.display(),
                         e
                     );
-                    return;
                 }
 
                 if let Err(e) = file.sync_all().await {
@@ -333,73 +461,33 @@ impl SchedulerRuntime {
                 if let Err(e) = tokio::fs::rename(&tmp_path, &self.journal_path).await {
                     let _ = tokio::fs::remove_file(&self.journal_path).await;
                     if let Err(e2) = tokio::fs::rename(&tmp_path, &self.journal_path).await {
-                        tracing::error!(
+                        self.journal_writable = false;
+                        self.journal_error =
+                            Some(format!("Failed to finalize journal snapshot: {e2}"));
+                        bail!(
                             "Failed to move journal snapshot from {} to {}: {} (retry error: {})",
                             tmp_path.display(),
                             self.journal_path.display(),
                             e,
                             e2
                         );
-                        self.journal_writable = false;
-                        self.journal_error =
-                            Some(format!("Failed to finalize journal snapshot: {e2}"));
                     }
                 }
             }
             Err(e) => {
-                tracing::error!(
+                self.journal_writable = false;
+                self.journal_error = Some(format!("Failed to create journal temp file: {e}"));
+                bail!(
                     "Failed to create journal temp file {}: {}",
                     tmp_path.display(),
                     e
                 );
-                self.journal_writable = false;
-                self.journal_error = Some(format!("Failed to create journal temp file: {e}"));
             }
         };
+        Ok(())
     }
 }
 
-fn should_apply_journal(state_path: &std::path::Path, journal_path: &std::path::Path) -> bool {
-    let Ok(j_meta) = std::fs::metadata(journal_path) else {
-        return false;
-    };
-    if j_meta.len() == 0 {
-        return false;
-    }
-    let Ok(j_mtime) = j_meta.modified() else {
-        return true;
-    };
-
-    let Ok(s_meta) = std::fs::metadata(state_path) else {
-        return true;
-    };
-    let Ok(s_mtime) = s_meta.modified() else {
-        return true;
-    };
-
-    j_mtime >= s_mtime
-}
-
-fn load_last_journal_snapshot(journal_path: &std::path::Path) -> Option<(Scheduler, u64)> {
-    #[derive(serde::Deserialize)]
-    struct Entry {
-        ts: u64,
-        kind: String,
-        scheduler: Scheduler,
-    }
-
-    let content = std::fs::read_to_string(journal_path).ok()?;
-    let line = content.lines().next()?.trim();
-    if line.is_empty() {
-        return None;
-    }
-    let entry = serde_json::from_str::<Entry>(line).ok()?;
-    if entry.kind != "snapshot" {
-        return None;
-    }
-    Some((entry.scheduler, entry.ts))
-}
-
 fn backup_state_file(
     path: &std::path::Path,
     kind: &str,
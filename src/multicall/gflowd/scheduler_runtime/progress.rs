@@ -0,0 +1,90 @@
+//! Progress running jobs report by writing a line to `$GFLOW_PROGRESS_FILE`.
+//! Read after each GPU refresh; a file is only read again once its
+//! modification time changes.
+
+use super::*;
+use gflow::core::job::JobProgress;
+use std::io::Read;
+use std::time::SystemTime;
+
+/// More than a progress line needs; the rest of a file is ignored.
+const MAX_PROGRESS_BYTES: u64 = 4096;
+
+/// Read the progress files of the jobs running on this machine. Files are
+/// read without holding the lock.
+pub(super) async fn poll(state: &SharedState) {
+    let jobs: Vec<(u32, Option<SystemTime>)> = {
+        let guard = state.read().await;
+        guard
+            .job_runtimes()
+            .iter()
+            .filter(|rt| rt.state == JobState::Running && rt.node.is_none())
+            .map(|rt| (rt.id, rt.progress.as_ref().map(|p| p.updated_at)))
+            .collect()
+    };
+    if jobs.is_empty() {
+        return;
+    }
+
+    let read = tokio::task::spawn_blocking(move || {
+        jobs.into_iter()
+            .filter_map(|(job_id, seen)| {
+                let path = gflow::paths::get_progress_file_path(job_id).ok()?;
+                read_progress(&path, seen).map(|progress| (job_id, progress))
+            })
+            .collect::<Vec<_>>()
+    })
+    .await;
+    match read {
+        Ok(updates) if !updates.is_empty() => state.write().await.record_progress(updates),
+        Ok(_) => {}
+        Err(error) => tracing::warn!(%error, "Failed to read job progress"),
+    }
+}
+
+/// The progress in `path`, unless it has not changed since `seen`.
+fn read_progress(path: &std::path::Path, seen: Option<SystemTime>) -> Option<JobProgress> {
+    let file = std::fs::File::open(path).ok()?;
+    let modified = file.metadata().ok()?.modified().ok()?;
+    if seen == Some(modified) {
+        return None;
+    }
+    let mut line = String::new();
+    file.take(MAX_PROGRESS_BYTES)
+        .read_to_string(&mut line)
+        .ok()?;
+    JobProgress::parse(&line, modified)
+}
+
+impl SchedulerRuntime {
+    fn record_progress(&mut self, updates: Vec<(u32, JobProgress)>) {
+        for (job_id, progress) in updates {
+            let Some(rt) = self.scheduler.get_job_runtime_mut(job_id) else {
+                continue;
+            };
+            // The job may have ended while its file was read.
+            if rt.state == JobState::Running {
+                rt.progress = Some(Box::new(progress));
+            }
+        }
+        // Not saved: progress is only kept while jobs run.
+        self.scheduler.bump_revision();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn progress_files_are_read_again_only_once_they_change() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("7");
+        assert_eq!(read_progress(&path, None), None);
+
+        std::fs::write(&path, "42 epoch 42/100\n").unwrap();
+        let progress = read_progress(&path, None).unwrap();
+        assert_eq!(progress.to_string(), "42% epoch 42/100");
+        assert_eq!(read_progress(&path, Some(progress.updated_at)), None);
+    }
+}
@@ -59,10 +59,17 @@ impl SchedulerRuntime {
         builder = builder.gpus(original_job.gpus);
         builder = builder.gpu_sharing_mode(original_job.gpu_sharing_mode);
         builder = builder.priority(original_job.priority);
-        builder = builder.conda_env(original_job.conda_env.as_ref().map(|s| s.to_string()));
+        builder = builder.environment(original_job.environment.clone());
+        builder = builder.container_image(original_job.container_image.clone());
+        builder = builder.executor(original_job.executor);
+        builder = builder.interactive(original_job.interactive);
+        builder = builder.preemptible(original_job.preemptible);
+        builder = builder.keep_job_dir(original_job.keep_job_dir);
+        builder = builder.constraint(original_job.constraint.clone());
         builder = builder.time_limit(original_job.time_limit);
         builder = builder.memory_limit_mb(original_job.memory_limit_mb);
         builder = builder.gpu_memory_limit_mb(original_job.gpu_memory_limit_mb);
+        builder = builder.cpus(original_job.cpus);
         builder = builder.depends_on_ids(depends_on_ids.clone());
         builder = builder.dependency_mode(original_job.dependency_mode);
         builder = builder
@@ -78,6 +85,7 @@ impl SchedulerRuntime {
         builder = builder.group_id_uuid(original_job.group_id);
         builder = builder.max_concurrent(original_job.max_concurrent);
         builder = builder.project(original_job.project.as_ref().map(|s| s.to_string()));
+        builder = builder.tags(original_job.tags.clone());
         builder = builder.notifications(original_job.notifications.clone());
         builder = builder.redone_from(Some(retry_root_id));
         builder = builder.retried_from(Some(original_job.id));
@@ -90,6 +98,7 @@ impl SchedulerRuntime {
         &mut self,
         job_id: u32,
         final_state: JobState,
+        reason: Option<JobStateReason>,
     ) -> Option<Option<u32>> {
         let original_job = self.scheduler.get_job(job_id)?;
 
@@ -110,12 +119,12 @@ impl SchedulerRuntime {
                 Ok((new_job_id, _run_name, _stored_job)) => {
                     self.scheduler
                         .retarget_dependents_to_retry(job_id, new_job_id);
-                    let transitioned = match final_state {
-                        JobState::Failed => self.scheduler.fail_job_without_propagation(job_id),
-                        JobState::Timeout => self.scheduler.timeout_job_without_propagation(job_id),
-                        _ => false,
-                    };
-                    if transitioned {
+                    let transitioned = self.scheduler.try_transition_without_propagation(
+                        job_id,
+                        final_state,
+                        reason.clone(),
+                    );
+                    if transitioned == Ok(true) {
                         self.mark_dirty();
                         return Some(Some(new_job_id));
                     }
@@ -131,12 +140,7 @@ impl SchedulerRuntime {
             }
         }
 
-        let transitioned = match final_state {
-            JobState::Failed => self.scheduler.fail_job(job_id),
-            JobState::Timeout => self.scheduler.timeout_job(job_id),
-            _ => false,
-        };
-        if transitioned {
+        if self.scheduler.try_transition(job_id, final_state, reason) == Ok(true) {
             self.mark_dirty();
             Some(None)
         } else {
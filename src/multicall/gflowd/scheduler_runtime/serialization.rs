@@ -68,7 +68,6 @@ impl SerializationFormat {
     }
 
     /// Detect format from file extension
-    #[allow(dead_code)]
     pub fn from_path(path: &Path) -> Option<Self> {
         path.extension()
             .and_then(|ext| ext.to_str())
@@ -167,9 +166,19 @@ pub fn save_state(
     state_dir: &Path,
     format: SerializationFormat,
 ) -> Result<()> {
-    let filename = format!("state.{}", format.extension());
-    let path = state_dir.join(&filename);
-    let tmp_path = state_dir.join(format!("{}.tmp", filename));
+    let path = state_dir.join(format!("state.{}", format.extension()));
+    write_state_file(scheduler, &path, format)
+}
+
+/// Write scheduler state to `path`, atomically like [`save_state`].
+pub fn write_state_file(
+    scheduler: &Scheduler,
+    path: &Path,
+    format: SerializationFormat,
+) -> Result<()> {
+    let mut tmp_name = path.file_name().unwrap_or_default().to_os_string();
+    tmp_name.push(".tmp");
+    let tmp_path = path.with_file_name(tmp_name);
 
     // Ensure parent directory exists
     if let Some(parent) = path.parent() {
@@ -185,7 +194,7 @@ pub fn save_state(
         .context(format!("Failed to write to {}", tmp_path.display()))?;
 
     // Atomic rename
-    std::fs::rename(&tmp_path, &path).context(format!(
+    std::fs::rename(&tmp_path, path).context(format!(
         "Failed to rename {} to {}",
         tmp_path.display(),
         path.display()
@@ -1,8 +1,8 @@
 use super::gpu::{format_manual_ignore_reason, format_unmanaged_process_reason};
 use super::*;
-use gflow::core::executor::Executor;
+use gflow::core::executor::{Executor, MemoryUsage};
 use gflow::core::info::IgnoredGpuProcess;
-use gflow::core::job::{GpuSharingMode, Job, JobState};
+use gflow::core::job::{GpuSharingMode, Job, JobState, JobStateReason};
 
 struct NoopExecutor;
 
@@ -284,6 +284,57 @@ async fn batch_submit_assigns_unique_custom_run_names() {
     assert_eq!(submitted_jobs[1].run_name.as_deref(), Some("gjob-2-demo"));
 }
 
+#[tokio::test]
+async fn duplicate_names_of_active_jobs_are_flagged_or_rejected() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut runtime = SchedulerRuntime::with_state_path(
+        Box::new(NoopExecutor),
+        dir.path().to_path_buf(),
+        None,
+        gflow::core::gpu_allocation::GpuAllocationStrategy::Sequential,
+        gflow::config::ProjectsConfig::default(),
+    )
+    .unwrap();
+    let named = |name: &str| {
+        Job::builder()
+            .command("echo test")
+            .submitted_by("alice")
+            .run_name(Some(name.to_string()))
+            .build()
+    };
+
+    runtime.submit_job(named("demo")).await.unwrap();
+    runtime.submit_job(named("demo")).await.unwrap();
+    runtime.submit_job(named("other")).await.unwrap();
+    assert_eq!(runtime.run_name_shared_with(2), Some(1));
+    assert_eq!(runtime.run_name_shared_with(3), None);
+
+    runtime.set_duplicate_run_names(gflow::config::DuplicateRunNames::Reject);
+    let error = runtime.submit_job(named("demo")).await.unwrap_err();
+    assert!(matches!(
+        error.downcast_ref::<ApiError>().map(|error| &error.kind),
+        Some(ApiErrorKind::RunNameInUse { job_id: 1, .. })
+    ));
+
+    // Finished jobs do not hold on to their names.
+    assert!(runtime.cancel_job(1).await);
+    assert!(runtime.cancel_job(2).await);
+    let (job_id, run_name, _) = runtime.submit_job(named("demo")).await.unwrap();
+    assert_eq!((job_id, run_name.as_str()), (4, "gjob-4-demo"));
+
+    // Tasks of one array share their name.
+    let group_id = Some(uuid::Uuid::new_v4());
+    let tasks = (0..2)
+        .map(|_| {
+            let mut job = named("sweep");
+            job.group_id = group_id;
+            job
+        })
+        .collect();
+    runtime.submit_jobs(tasks).await.unwrap();
+    assert_eq!(runtime.run_name_shared_with(6), None);
+}
+
 #[tokio::test]
 async fn rejects_updating_shared_job_to_clear_gpu_memory_limit() {
     let dir = tempfile::tempdir().unwrap();
@@ -326,6 +377,7 @@ async fn rejects_updating_shared_job_to_clear_gpu_memory_limit() {
     assert!(result.is_err());
     assert!(result
         .unwrap_err()
+        .message
         .contains("Shared jobs must keep a GPU memory limit"));
 
     let current = runtime.get_job(job_id).unwrap();
@@ -333,6 +385,37 @@ async fn rejects_updating_shared_job_to_clear_gpu_memory_limit() {
     assert_eq!(current.gpu_memory_limit_mb, Some(1024));
 }
 
+#[test]
+fn priority_limits_cap_users_and_suggest_allowed_classes() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut runtime = runtime_in(dir.path());
+    runtime.set_priority_limits(
+        gflow::config::PriorityLimitsConfig {
+            max: Some(50),
+            users: [("alice".to_string(), 90)].into(),
+        },
+        [
+            ("low".to_string(), 1),
+            ("high".to_string(), 50),
+            ("urgent".to_string(), 90),
+        ]
+        .into(),
+    );
+
+    assert!(runtime.validate_priority(50, "bob").is_ok());
+    assert!(runtime.validate_priority(90, "alice").is_ok());
+    let err = runtime.validate_priority(90, "bob").unwrap_err();
+    assert_eq!(
+        err.kind,
+        gflow::core::api_error::ApiErrorKind::PriorityTooHigh {
+            priority: 90,
+            max: 50,
+            allowed: vec!["low".to_string(), "high".to_string()],
+        }
+    );
+    assert!(err.message.contains("allowed classes: low, high"));
+}
+
 #[tokio::test]
 async fn updates_job_notifications() {
     let dir = tempfile::tempdir().unwrap();
@@ -467,6 +550,47 @@ async fn explicit_fail_job_does_not_spawn_retry_attempt() {
     assert!(runtime.get_job(2).is_none());
 }
 
+#[tokio::test]
+async fn job_actions_apply_to_every_job_and_wait_once_for_running_ones() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut runtime = SchedulerRuntime::with_state_path(
+        Box::new(NoopExecutor),
+        dir.path().to_path_buf(),
+        None,
+        gflow::core::gpu_allocation::GpuAllocationStrategy::Sequential,
+        gflow::config::ProjectsConfig::default(),
+    )
+    .unwrap();
+
+    let mut ids = Vec::new();
+    for i in 0..6 {
+        let job = Job::builder()
+            .command(format!("echo {i}"))
+            .submitted_by("alice")
+            .build();
+        ids.push(runtime.submit_job(job).await.unwrap().0);
+    }
+    let held = runtime
+        .apply_job_action(JobAction::Hold, &[ids[4], ids[5], 999])
+        .await;
+    assert_eq!(held, [ids[4], ids[5]]);
+    assert_eq!(runtime.get_job(ids[5]).unwrap().state, JobState::Hold);
+
+    // The rest start running; each would otherwise get its own grace period.
+    assert_eq!(runtime.scheduler.prepare_jobs_for_execution().len(), 4);
+    let started = std::time::Instant::now();
+    let cancelled = runtime.apply_job_action(JobAction::Cancel, &ids).await;
+    assert!(started.elapsed() < Duration::from_millis(1500));
+    assert_eq!(cancelled, ids);
+    for &id in &ids {
+        assert_eq!(runtime.get_job(id).unwrap().state, JobState::Cancelled);
+    }
+    assert!(runtime
+        .apply_job_action(JobAction::Release, &ids)
+        .await
+        .is_empty());
+}
+
 #[tokio::test]
 async fn manual_redo_lineage_does_not_consume_automatic_retry_budget() {
     let dir = tempfile::tempdir().unwrap();
@@ -585,6 +709,101 @@ async fn timeout_does_not_spawn_retry_attempt() {
     assert!(runtime.get_job(2).is_none());
 }
 
+/// Reports every job as OOM-killed after peaking at 2 GB.
+struct OomExecutor;
+
+impl Executor for OomExecutor {
+    fn execute(&self, _job: &Job) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    fn memory_usage(&self, _job: &Job) -> Option<MemoryUsage> {
+        Some(MemoryUsage {
+            peak_mb: Some(2048),
+            oom_killed: true,
+        })
+    }
+}
+
+#[tokio::test]
+async fn oom_killed_job_fails_with_the_memory_limit_reason_and_its_peak() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut runtime = SchedulerRuntime::with_state_path(
+        Box::new(OomExecutor),
+        dir.path().to_path_buf(),
+        None,
+        gflow::core::gpu_allocation::GpuAllocationStrategy::Sequential,
+        gflow::config::ProjectsConfig::default(),
+    )
+    .unwrap();
+
+    let job = Job::builder()
+        .command("python train.py")
+        .submitted_by("alice")
+        .memory_limit_mb(Some(1024))
+        .build();
+    let (job_id, _run_name, _job) = runtime.submit_job(job).await.unwrap();
+    assert_eq!(runtime.scheduler.prepare_jobs_for_execution().len(), 1);
+
+    assert!(runtime.explicit_fail_job(job_id).await);
+    let job = runtime.get_job(job_id).unwrap();
+    assert_eq!(job.state, JobState::Failed);
+    assert_eq!(job.peak_rss_mb, Some(2048));
+    assert_eq!(
+        job.reason.as_deref(),
+        Some(&JobStateReason::MemoryLimitExceeded(Some(2048)))
+    );
+    assert_eq!(
+        job.reason.unwrap().to_string(),
+        "exceeded memory limit (OOM-killed, peak 2048 MB)"
+    );
+}
+
+#[tokio::test]
+async fn submitted_jobs_start_within_a_second_on_an_idle_daemon() {
+    use super::super::events::{EventBus, SchedulerEvent};
+
+    let dir = tempfile::tempdir().unwrap();
+    let runtime = SchedulerRuntime::with_state_path(
+        Box::new(NoopExecutor),
+        dir.path().to_path_buf(),
+        None,
+        gflow::core::gpu_allocation::GpuAllocationStrategy::Sequential,
+        gflow::config::ProjectsConfig::default(),
+    )
+    .unwrap();
+    let state: SharedState = Arc::new(RwLock::new(runtime));
+    let event_bus = Arc::new(EventBus::new(64));
+    let handler = tokio::spawn(event_loop::scheduler_trigger_handler_with_debounce(
+        event_bus.subscribe(),
+        Arc::clone(&state),
+        Arc::clone(&event_bus),
+        Duration::from_secs(60),
+    ));
+
+    // Each submission lands on an idle handler, so each needs its own wakeup.
+    for _ in 0..3 {
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        let job = Job::builder()
+            .command("sleep 60")
+            .submitted_by("alice")
+            .build();
+        let submitted_at = std::time::Instant::now();
+        let (job_id, _run_name, _job) = state.write().await.submit_job(job).await.unwrap();
+        event_bus.publish(SchedulerEvent::JobSubmitted { job_id });
+
+        while state.read().await.get_job(job_id).unwrap().state != JobState::Running {
+            assert!(
+                submitted_at.elapsed() < Duration::from_secs(1),
+                "job {job_id} did not start within a second"
+            );
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    }
+
+    handler.abort();
+}
+
 #[tokio::test]
 async fn enters_journal_mode_and_does_not_overwrite_state_on_migration_failure() {
     let dir = tempfile::tempdir().unwrap();
@@ -759,3 +978,377 @@ async fn prefers_newer_journal_snapshot_and_truncates_after_state_save() {
         JobState::Finished
     );
 }
+
+fn write_future_version_state(dir: &std::path::Path) {
+    let state_json = serde_json::json!({
+        "version": 999,
+        "jobs": [],
+        "state_path": "state.json",
+        "next_job_id": 1,
+        "allowed_gpu_indices": null
+    });
+    std::fs::write(dir.join("state.json"), state_json.to_string()).unwrap();
+}
+
+#[test]
+fn records_startup_info_and_is_ready_on_fresh_state() {
+    let dir = tempfile::tempdir().unwrap();
+    let runtime = SchedulerRuntime::with_state_path(
+        Box::new(NoopExecutor),
+        dir.path().to_path_buf(),
+        None,
+        gflow::core::gpu_allocation::GpuAllocationStrategy::Sequential,
+        gflow::config::ProjectsConfig::default(),
+    )
+    .unwrap();
+
+    let startup = runtime.startup_info().unwrap();
+    assert_eq!(startup.jobs_loaded, 0);
+    assert!(!startup.journal_applied);
+    assert!(runtime.is_ready());
+    assert_eq!(runtime.persistence_mode(), "state");
+}
+
+#[test]
+fn is_ready_in_journal_recovery_mode() {
+    let dir = tempfile::tempdir().unwrap();
+    write_future_version_state(dir.path());
+
+    let runtime = SchedulerRuntime::with_state_path(
+        Box::new(NoopExecutor),
+        dir.path().to_path_buf(),
+        None,
+        gflow::core::gpu_allocation::GpuAllocationStrategy::Sequential,
+        gflow::config::ProjectsConfig::default(),
+    )
+    .unwrap();
+
+    assert_eq!(runtime.persistence_mode(), "journal");
+    assert!(runtime.startup_info().is_some());
+    assert!(runtime.is_ready());
+}
+
+#[test]
+fn is_not_ready_when_read_only() {
+    let dir = tempfile::tempdir().unwrap();
+    write_future_version_state(dir.path());
+    // A directory where the journal file should be makes the journal unwritable.
+    std::fs::create_dir(dir.path().join("state.journal.jsonl")).unwrap();
+
+    let runtime = SchedulerRuntime::with_state_path(
+        Box::new(NoopExecutor),
+        dir.path().to_path_buf(),
+        None,
+        gflow::core::gpu_allocation::GpuAllocationStrategy::Sequential,
+        gflow::config::ProjectsConfig::default(),
+    )
+    .unwrap();
+
+    assert_eq!(runtime.persistence_mode(), "read_only");
+    assert!(runtime.journal_error().is_some());
+    assert!(runtime.startup_info().is_some());
+    assert!(!runtime.is_ready());
+}
+
+fn runtime_in(dir: &std::path::Path) -> SchedulerRuntime {
+    SchedulerRuntime::with_state_path(
+        Box::new(NoopExecutor),
+        dir.to_path_buf(),
+        None,
+        gflow::core::gpu_allocation::GpuAllocationStrategy::Sequential,
+        gflow::config::ProjectsConfig::default(),
+    )
+    .unwrap()
+}
+
+fn test_job() -> Job {
+    Job::builder().command("true").submitted_by("alice").build()
+}
+
+#[tokio::test]
+async fn job_ids_are_not_reused_after_a_crash_before_the_state_save() {
+    let dir = tempfile::tempdir().unwrap();
+
+    let mut runtime = runtime_in(dir.path());
+    let (first, _, _) = runtime.submit_job(test_job()).await.unwrap();
+    runtime.save_state().await;
+    let (second, _, _) = runtime.submit_job(test_job()).await.unwrap();
+    let (third, _, _) = runtime.submit_job(test_job()).await.unwrap();
+    // Crash: the last submissions never reach the state file.
+    drop(runtime);
+
+    let mut runtime = runtime_in(dir.path());
+    assert_eq!(runtime.get_job(first).unwrap().state, JobState::Queued);
+    // The lost submissions stay on record, so their ids are not handed out again.
+    for job_id in [second, third] {
+        let lost = runtime.get_job(job_id).unwrap();
+        assert_eq!(lost.state, JobState::Failed);
+        assert!(matches!(
+            lost.reason.as_deref(),
+            Some(JobStateReason::SystemError(_))
+        ));
+    }
+    let (after_crash, _, _) = runtime.submit_job(test_job()).await.unwrap();
+    assert_eq!(after_crash, third + 1);
+}
+
+#[tokio::test]
+async fn batches_are_saved_before_they_are_accepted() {
+    let dir = tempfile::tempdir().unwrap();
+
+    let mut runtime = runtime_in(dir.path());
+    let (batch, _, _) = runtime
+        .submit_jobs(vec![test_job(), test_job()])
+        .await
+        .unwrap();
+    // Crash right after the reply: the batch is already on disk.
+    drop(runtime);
+
+    let runtime = runtime_in(dir.path());
+    for (job_id, _, _) in batch {
+        assert_eq!(runtime.get_job(job_id).unwrap().state, JobState::Queued);
+    }
+}
+
+#[tokio::test]
+async fn job_ids_continue_without_a_gap_after_a_clean_restart() {
+    let dir = tempfile::tempdir().unwrap();
+
+    let mut runtime = runtime_in(dir.path());
+    runtime.submit_job(test_job()).await.unwrap();
+    runtime.submit_job(test_job()).await.unwrap();
+    runtime.save_state().await;
+    drop(runtime);
+
+    let mut runtime = runtime_in(dir.path());
+    let (job_id, _, _) = runtime.submit_job(test_job()).await.unwrap();
+    assert_eq!(job_id, 3);
+}
+
+#[tokio::test]
+async fn next_job_id_starts_after_the_highest_loaded_job() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut scheduler = SchedulerBuilder::new()
+        .with_state_path(dir.path().join("state.json"))
+        .build();
+    scheduler.submit_job(test_job());
+    scheduler.submit_job(test_job());
+    scheduler.set_next_job_id(1);
+    serialization::save_state(
+        &scheduler,
+        dir.path(),
+        serialization::SerializationFormat::MessagePack,
+    )
+    .unwrap();
+
+    let runtime = runtime_in(dir.path());
+    assert_eq!(runtime.next_job_id(), 3);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn concurrent_scheduling_never_gives_a_gpu_to_two_running_jobs() {
+    const GPUS: u32 = 4;
+    const SUBMITTERS: usize = 8;
+    const JOBS_PER_SUBMITTER: usize = 10;
+
+    let dir = tempfile::tempdir().unwrap();
+    let mut runtime = SchedulerRuntime::with_state_path(
+        Box::new(NoopExecutor),
+        dir.path().to_path_buf(),
+        None,
+        gflow::core::gpu_allocation::GpuAllocationStrategy::Sequential,
+        gflow::config::ProjectsConfig::default(),
+    )
+    .unwrap();
+    runtime.scheduler.update_gpu_slots(
+        (0..GPUS)
+            .map(|index| {
+                let slot = GPUSlot {
+                    index,
+                    available: true,
+                    total_memory_mb: None,
+                    reason: None,
+                };
+                (format!("GPU-{index}"), slot)
+            })
+            .collect(),
+    );
+    let state: SharedState = Arc::new(RwLock::new(runtime));
+    let event_bus = Arc::new(EventBus::new(1000));
+
+    let assert_no_gpu_is_shared = |runtime: &SchedulerRuntime| {
+        let mut holders = HashMap::new();
+        for rt in runtime
+            .job_runtimes()
+            .iter()
+            .filter(|rt| rt.state == JobState::Running)
+        {
+            for &gpu in rt.gpu_ids.iter().flatten() {
+                if let Some(other) = holders.insert(gpu, rt.id) {
+                    panic!(
+                        "GPU {gpu} is assigned to running jobs {other} and {}",
+                        rt.id
+                    );
+                }
+            }
+        }
+    };
+
+    let mut tasks = Vec::new();
+    for submitter in 0..SUBMITTERS {
+        let state = Arc::clone(&state);
+        tasks.push(tokio::spawn(async move {
+            for _ in 0..JOBS_PER_SUBMITTER {
+                let job = Job::builder()
+                    .command("true")
+                    .submitted_by(format!("user{submitter}"))
+                    .gpus(1)
+                    .build();
+                state.write().await.submit_job(job).await.unwrap();
+                tokio::task::yield_now().await;
+            }
+        }));
+    }
+    for _ in 0..4 {
+        let state = Arc::clone(&state);
+        let event_bus = Arc::clone(&event_bus);
+        tasks.push(tokio::spawn(async move {
+            for _ in 0..100 {
+                super::event_loop::trigger_scheduling(&state, &event_bus).await;
+                tokio::task::yield_now().await;
+            }
+        }));
+    }
+    // Jobs end while passes run, so their GPUs are handed out again.
+    let finisher = {
+        let state = Arc::clone(&state);
+        tokio::spawn(async move {
+            let mut finished = 0;
+            while finished < SUBMITTERS * JOBS_PER_SUBMITTER {
+                let mut runtime = state.write().await;
+                assert_no_gpu_is_shared(&runtime);
+                let running = runtime.scheduler.job_ids_by_state(JobState::Running);
+                if let Some(&job_id) = running.and_then(|ids| ids.first()) {
+                    assert!(runtime.finish_job(job_id).await);
+                    finished += 1;
+                }
+                drop(runtime);
+                super::event_loop::trigger_scheduling(&state, &event_bus).await;
+                tokio::task::yield_now().await;
+            }
+        })
+    };
+
+    for task in tasks {
+        task.await.unwrap();
+    }
+    tokio::time::timeout(Duration::from_secs(30), finisher)
+        .await
+        .expect("not every job ran")
+        .unwrap();
+
+    let runtime = state.read().await;
+    assert_no_gpu_is_shared(&runtime);
+    assert!(runtime
+        .job_runtimes()
+        .iter()
+        .all(|rt| rt.state == JobState::Finished));
+    assert!(runtime.scheduler.gpu_holders().is_empty());
+}
+
+#[tokio::test]
+async fn zombie_jobs_return_their_memory() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut runtime = SchedulerRuntime::with_state_path(
+        Box::new(NoopExecutor),
+        dir.path().to_path_buf(),
+        None,
+        gflow::core::gpu_allocation::GpuAllocationStrategy::Sequential,
+        gflow::config::ProjectsConfig::default(),
+    )
+    .unwrap();
+    let job = Job::builder()
+        .command("sleep 60")
+        .submitted_by("alice")
+        .memory_limit_mb(1024)
+        .build();
+    let (job_id, _run_name, _job) = runtime.submit_job(job).await.unwrap();
+    let available = runtime.available_memory_mb();
+    runtime.scheduler.prepare_jobs_for_execution();
+    assert_eq!(runtime.available_memory_mb(), available - 1024);
+    assert_eq!(runtime.used_memory_mb(), 1024);
+
+    let state: SharedState = Arc::new(RwLock::new(runtime));
+    let event_bus = Arc::new(EventBus::new(16));
+    let handler = tokio::spawn(super::monitors::zombie_handler_task(
+        event_bus.subscribe(),
+        Arc::clone(&state),
+        Arc::clone(&event_bus),
+    ));
+    event_bus.publish(SchedulerEvent::ZombieJobDetected { job_id });
+
+    tokio::time::timeout(Duration::from_secs(5), async {
+        while state.read().await.get_job(job_id).unwrap().state != JobState::Failed {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .expect("zombie job was not failed");
+    handler.abort();
+
+    let runtime = state.read().await;
+    assert_eq!(runtime.available_memory_mb(), available);
+    assert_eq!(runtime.used_memory_mb(), 0);
+}
+
+#[tokio::test]
+async fn finished_jobs_lose_their_directories_unless_kept() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut runtime = SchedulerRuntime::with_state_path(
+        Box::new(NoopExecutor),
+        dir.path().to_path_buf(),
+        None,
+        gflow::core::gpu_allocation::GpuAllocationStrategy::Sequential,
+        gflow::config::ProjectsConfig::default(),
+    )
+    .unwrap();
+    let job = |keep| {
+        Job::builder()
+            .command("echo scratch")
+            .submitted_by("alice")
+            .keep_job_dir(keep)
+            .build()
+    };
+    runtime
+        .submit_jobs(vec![job(false), job(true), job(false)])
+        .await
+        .unwrap();
+
+    // Jobs placed on a remote node get no directory here.
+    let mut started: Vec<Job> = (1..=3)
+        .map(|id| runtime.scheduler.get_job(id).unwrap())
+        .collect();
+    started[2].node = Some("node-a".to_string());
+    runtime.assign_job_dirs(&mut started);
+    let job_dir = gflow::paths::get_job_dir(1).unwrap();
+    assert_eq!(started[0].job_dir.as_deref(), Some(&job_dir));
+    assert_eq!(
+        runtime.scheduler.get_job(1).unwrap().job_dir.as_deref(),
+        Some(&job_dir)
+    );
+    assert!(started[2].job_dir.is_none());
+
+    let hour = Duration::from_secs(60 * 60);
+    let now = std::time::SystemTime::now();
+    for id in 1..=3 {
+        let rt = runtime.scheduler.get_job_runtime_mut(id).unwrap();
+        rt.state = JobState::Finished;
+        rt.finished_at = Some(now - 2 * hour);
+    }
+    assert!(runtime.expired_job_dirs(3 * hour, now).is_empty());
+    assert_eq!(runtime.expired_job_dirs(hour, now), [(1, job_dir.clone())]);
+
+    runtime.forget_job_dir(1, &job_dir);
+    assert!(runtime.scheduler.get_job(1).unwrap().job_dir.is_none());
+    assert!(runtime.expired_job_dirs(hour, now).is_empty());
+}
@@ -0,0 +1,257 @@
+//! Peak host and GPU memory of running jobs, to compare with what they asked
+//! for. Sampled after each GPU refresh; a sample that fails just leaves the
+//! peaks as they were.
+
+use super::super::pidfile::is_process_running;
+use super::*;
+use std::fs;
+
+/// Peak memory of the jobs running on this machine, kept until they end.
+#[derive(Debug, Default)]
+pub(super) struct UsageTracker {
+    peaks: HashMap<u32, UsagePeaks>,
+    /// The process each job's tree hangs off, so it is only looked up once.
+    pids: HashMap<u32, u32>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(super) struct UsagePeaks {
+    pub rss_mb: Option<u64>,
+    pub gpu_mb: Option<u64>,
+}
+
+/// A job's memory at one point in time.
+#[derive(Debug)]
+struct UsageSample {
+    job_id: u32,
+    pid: u32,
+    rss_mb: u64,
+    gpu_mb: u64,
+    uses_gpus: bool,
+}
+
+/// Sample the memory of every job running on this machine. Processes are
+/// looked up without holding the lock.
+pub(super) async fn sample(state: &SharedState) {
+    let (jobs, executor, pids, gpu_memory) = {
+        let guard = state.read().await;
+        let jobs = guard
+            .job_runtimes()
+            .iter()
+            .filter(|rt| rt.state == JobState::Running && rt.node.is_none())
+            .filter_map(|rt| guard.scheduler.get_job(rt.id))
+            .collect::<Vec<_>>();
+        (
+            jobs,
+            Arc::clone(&guard.executor),
+            guard.usage.pids.clone(),
+            guard.gpu_process_memory.clone(),
+        )
+    };
+    if jobs.is_empty() {
+        return;
+    }
+
+    let sampled = tokio::task::spawn_blocking(move || {
+        take_samples(&jobs, executor.as_ref(), &pids, &gpu_memory)
+    })
+    .await;
+    match sampled {
+        Ok(samples) => state.write().await.record_usage_samples(samples),
+        Err(error) => tracing::warn!(%error, "Failed to sample job memory usage"),
+    }
+}
+
+fn take_samples(
+    jobs: &[Job],
+    executor: &dyn Executor,
+    pids: &HashMap<u32, u32>,
+    gpu_memory: &HashMap<u32, u64>,
+) -> Vec<UsageSample> {
+    let children = child_processes();
+    jobs.iter()
+        .filter_map(|job| {
+            let pid = pids
+                .get(&job.id)
+                .copied()
+                .filter(|&pid| is_process_running(pid))
+                .or_else(|| executor.pid(job))?;
+            let tree = process_tree(pid, &children);
+            Some(UsageSample {
+                job_id: job.id,
+                pid,
+                rss_mb: tree.iter().filter_map(|&pid| rss_kb(pid)).sum::<u64>() / 1024,
+                gpu_mb: tree.iter().filter_map(|pid| gpu_memory.get(pid)).sum(),
+                uses_gpus: job.gpus > 0,
+            })
+        })
+        .collect()
+}
+
+impl SchedulerRuntime {
+    fn record_usage_samples(&mut self, samples: Vec<UsageSample>) {
+        self.usage.pids.clear();
+        for sample in samples {
+            // The job may have ended while it was sampled.
+            let running = self
+                .scheduler
+                .get_job_runtime(sample.job_id)
+                .is_some_and(|rt| rt.state == JobState::Running);
+            if !running {
+                continue;
+            }
+            self.usage.pids.insert(sample.job_id, sample.pid);
+            let peaks = self.usage.peaks.entry(sample.job_id).or_default();
+            peaks.rss_mb = peaks.rss_mb.max(Some(sample.rss_mb));
+            if sample.uses_gpus || sample.gpu_mb > 0 {
+                peaks.gpu_mb = peaks.gpu_mb.max(Some(sample.gpu_mb));
+            }
+        }
+    }
+
+    /// The peaks sampled for a job that is leaving Running, which are no
+    /// longer tracked afterwards.
+    pub(super) fn take_usage_peaks(&mut self, job_id: u32) -> UsagePeaks {
+        self.usage.pids.remove(&job_id);
+        self.usage.peaks.remove(&job_id).unwrap_or_default()
+    }
+}
+
+/// Children of each process, from one pass over /proc.
+fn child_processes() -> HashMap<u32, Vec<u32>> {
+    let mut children: HashMap<u32, Vec<u32>> = HashMap::new();
+    let Ok(entries) = fs::read_dir("/proc") else {
+        return children;
+    };
+    for entry in entries.flatten() {
+        let Some(pid) = entry
+            .file_name()
+            .to_str()
+            .and_then(|name| name.parse().ok())
+        else {
+            continue;
+        };
+        let parent = fs::read_to_string(entry.path().join("stat"))
+            .ok()
+            .and_then(|stat| parent_pid(&stat));
+        if let Some(parent) = parent {
+            children.entry(parent).or_default().push(pid);
+        }
+    }
+    children
+}
+
+/// The parent PID from a /proc/<pid>/stat line. The command name before it
+/// is in parentheses and may contain spaces and parentheses itself.
+fn parent_pid(stat: &str) -> Option<u32> {
+    let (_, fields) = stat.rsplit_once(')')?;
+    fields.split_whitespace().nth(1)?.parse().ok()
+}
+
+/// `root` and all its descendants.
+fn process_tree(root: u32, children: &HashMap<u32, Vec<u32>>) -> Vec<u32> {
+    let mut tree = vec![root];
+    let mut next = 0;
+    while let Some(&pid) = tree.get(next) {
+        tree.extend(children.get(&pid).into_iter().flatten());
+        next += 1;
+    }
+    tree
+}
+
+/// Resident memory of a process in kB.
+fn rss_kb(pid: u32) -> Option<u64> {
+    parse_rss_kb(&fs::read_to_string(format!("/proc/{pid}/smaps_rollup")).ok()?)
+}
+
+fn parse_rss_kb(smaps_rollup: &str) -> Option<u64> {
+    smaps_rollup
+        .lines()
+        .find_map(|line| line.strip_prefix("Rss:"))?
+        .trim()
+        .strip_suffix("kB")?
+        .trim()
+        .parse()
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parent_pid_skips_command_names_with_spaces() {
+        assert_eq!(parent_pid("42 (bash) S 7 42 42 0 -1"), Some(7));
+        assert_eq!(parent_pid("43 (my (odd) cmd) R 42 43 42 0 -1"), Some(42));
+        assert_eq!(parent_pid("garbage"), None);
+    }
+
+    #[test]
+    fn process_tree_collects_all_descendants() {
+        let children = HashMap::from([(1, vec![2, 3]), (2, vec![4]), (9, vec![10])]);
+        let mut tree = process_tree(1, &children);
+        tree.sort_unstable();
+        assert_eq!(tree, vec![1, 2, 3, 4]);
+        assert_eq!(process_tree(5, &children), vec![5]);
+    }
+
+    #[test]
+    fn parse_rss_reads_the_rollup_total() {
+        let rollup = "00400000-7fff0000 ---p 00000000 00:00 0    [rollup]\nRss:              123456 kB\nPss:               65432 kB\n";
+        assert_eq!(parse_rss_kb(rollup), Some(123456));
+        assert_eq!(parse_rss_kb("Pss: 1 kB\n"), None);
+    }
+
+    struct NoopExecutor;
+
+    impl Executor for NoopExecutor {
+        fn execute(&self, _job: &Job) -> anyhow::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn peaks_are_stored_on_the_job_when_it_ends() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut runtime = SchedulerRuntime::with_state_path(
+            Box::new(NoopExecutor),
+            dir.path().to_path_buf(),
+            None,
+            gflow::core::gpu_allocation::GpuAllocationStrategy::Sequential,
+            gflow::config::ProjectsConfig::default(),
+        )
+        .unwrap();
+        let job = Job::builder()
+            .command("python train.py")
+            .submitted_by("alice")
+            .memory_limit_mb(Some(1024))
+            .build();
+        let (job_id, _run_name, _job) = runtime.submit_job(job).await.unwrap();
+        assert_eq!(runtime.scheduler.prepare_jobs_for_execution().len(), 1);
+
+        for rss_mb in [100, 300, 200] {
+            runtime.record_usage_samples(vec![UsageSample {
+                job_id,
+                pid: 4242,
+                rss_mb,
+                gpu_mb: 0,
+                uses_gpus: false,
+            }]);
+        }
+        assert!(runtime.finish_job(job_id).await);
+
+        let job = runtime.get_job(job_id).unwrap();
+        assert_eq!(job.peak_rss_mb, Some(300));
+        assert_eq!(job.peak_gpu_mb, None);
+        assert_eq!(job.memory_utilization(), Some(29));
+        assert!(runtime.usage.peaks.is_empty() && runtime.usage.pids.is_empty());
+    }
+
+    #[test]
+    fn samples_this_process_tree() {
+        let children = child_processes();
+        let tree = process_tree(std::process::id(), &children);
+        assert_eq!(tree[0], std::process::id());
+        assert!(rss_kb(std::process::id()).is_some_and(|kb| kb > 0));
+    }
+}
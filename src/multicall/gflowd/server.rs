@@ -2,36 +2,57 @@
 //!
 //! # Security Note
 //! The `/debug/*` endpoints expose full job details and per-user statistics without
-//! authentication. In production environments, ensure the daemon is bound to localhost
-//! only and protected by firewall rules. Consider gating these endpoints behind a
+//! authentication. In production environments, listen on a Unix socket
+//! (`[daemon] listen = "unix:..."`) or bind to localhost behind firewall rules. Consider gating these endpoints behind a
 //! feature flag or configuration option for production deployments.
 
+mod auth;
+mod client_versions;
+mod errors;
+mod etag;
 mod handlers;
+mod idempotency;
+mod rate_limit;
+mod snapshot;
 mod state;
+#[cfg(test)]
+mod test_app;
 mod web_ui;
 
 pub(crate) use handlers::UpdateJobRequest;
 
+use super::cgroup::Cgroups;
+use super::config_reload::{ConfigReloader, ConfigSource, ReloadTrigger};
 use super::events::EventBus;
-use super::executor::TmuxExecutor;
+use super::executor::JobExecutor;
 use super::scheduler_runtime;
+use super::shutdown::{Shutdown, WaitRunning};
 use super::state_saver::StateSaverHandle;
+use super::tokens::{Caller, TokenStore};
 use axum::{
     extract::Request,
     http::HeaderValue,
     middleware::{self, Next},
     response::Response,
-    routing::{get, post},
+    routing::{delete, get, patch, post},
     Router,
 };
+use gflow::config::ListenAddr;
 use socket2::{Domain, Protocol, Socket, Type};
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::Semaphore;
 use tracing::Instrument;
 
-pub async fn run(config: gflow::config::Config) -> anyhow::Result<()> {
+pub async fn run(
+    config: gflow::config::Config,
+    source: ConfigSource,
+    wait_running: WaitRunning,
+    lock_mode: super::instance_lock::Mode,
+) -> anyhow::Result<()> {
     let state_dir = gflow::paths::get_data_dir()?;
+    // Taken before any state is read, and held until it is saved for the last time.
+    let instance_lock = super::instance_lock::acquire(&state_dir, &config, lock_mode).await?;
     let allowed_gpus = config.daemon.gpus.clone();
     let gpu_allocation_strategy = config.daemon.gpu_allocation_strategy;
     let gpu_poll_interval_secs = config.daemon.gpu_poll_interval_secs;
@@ -43,15 +64,85 @@ pub async fn run(config: gflow::config::Config) -> anyhow::Result<()> {
             "Invalid daemon.gpu_poll_interval_secs '0'. Use a value of at least 1 second."
         );
     }
+    if config.daemon.state_save_interval_ms == 0 {
+        anyhow::bail!(
+            "Invalid daemon.state_save_interval_ms '0'. Use a value of at least 1 millisecond."
+        );
+    }
     let gpu_poll_interval = Duration::from_secs(gpu_poll_interval_secs);
+    if config.daemon.state_backup_interval_secs == 0 {
+        anyhow::bail!(
+            "Invalid daemon.state_backup_interval_secs '0'. Use a value of at least 1 second."
+        );
+    }
+    let state_save_interval = Duration::from_millis(config.daemon.state_save_interval_ms);
+    if config.scheduler.tick_secs == 0 {
+        anyhow::bail!("Invalid scheduler.tick_secs '0'. Use a value of at least 1 second.");
+    }
+    let scheduler_tick = Duration::from_secs(config.scheduler.tick_secs);
+    let job_dir_retention = config
+        .daemon
+        .job_dir_retention_secs
+        .map(Duration::from_secs);
+    let (labels, gpu_labels) = config.daemon.parsed_labels()?;
+    let min_free_disk_mb = config.daemon.min_free_disk_mb()?;
+    config.daemon.min_client_version()?;
+    let tls_config = super::tls::server_config(&config.daemon)?;
+    let readonly_addr = match &config.daemon.readonly_bind {
+        Some(bind) => match format!("tcp:{bind}").parse() {
+            Ok(ListenAddr::Tcp { host, port }) => Some((host, port)),
+            _ => anyhow::bail!("Invalid daemon.readonly_bind '{bind}': expected <host>:<port>"),
+        },
+        None => None,
+    };
+
+    let auth = if config.daemon.require_auth {
+        let tokens = Arc::new(TokenStore::open_default()?);
+        tokens.ensure_job_secret()?;
+        tracing::info!(
+            anonymous_read = config.daemon.anonymous_read,
+            "API token authentication enabled"
+        );
+        auth::AuthPolicy {
+            required: true,
+            anonymous_read: config.daemon.anonymous_read,
+            tokens: Some(tokens),
+            allow_as_user: config.daemon.allow_as_user,
+        }
+    } else {
+        auth::AuthPolicy {
+            allow_as_user: config.daemon.allow_as_user,
+            ..auth::AuthPolicy::disabled()
+        }
+    };
 
-    // Inject TmuxExecutor
-    let executor = Box::new(TmuxExecutor);
+    // Inject the executor: tmux or direct, in a container for jobs that name an image
+    let mut executor = JobExecutor::new(
+        config.daemon.executor,
+        &config.daemon.default_shell,
+        auth.tokens.clone(),
+        &config.container,
+    )
+    .with_tmux_mode(config.daemon.tmux_mode);
+    // Enforce memory and CPU limits where the daemon may manage cgroups
+    if let Some(cgroups) = config.daemon.enforce_limits.then(Cgroups::detect).flatten() {
+        executor = executor.with_cgroups(cgroups);
+    }
+    let executor = Box::new(executor);
 
     // Create state saver channel before initializing SchedulerRuntime
     let (state_tx, state_rx) = tokio::sync::mpsc::unbounded_channel();
     let state_saver_handle = StateSaverHandle::new(state_tx);
 
+    // Webhook deliveries that keep failing wait here, across notifier
+    // restarts on reload and across daemon restarts.
+    let webhook_outbox = Arc::new(super::webhooks::WebhookOutbox::open(
+        state_dir.join("webhook_outbox.jsonl"),
+    ));
+    let job_webhooks = Arc::new(super::webhooks::JobWebhookStore::open(
+        state_dir.join("job_webhooks.json"),
+    ));
+
     // Create SchedulerRuntime and set state saver
     let mut scheduler_runtime = scheduler_runtime::SchedulerRuntime::with_state_path(
         executor,
@@ -61,25 +152,53 @@ pub async fn run(config: gflow::config::Config) -> anyhow::Result<()> {
         config.projects.clone(),
     )?;
     scheduler_runtime.set_state_saver(state_saver_handle.clone());
-
-    let scheduler = Arc::new(tokio::sync::RwLock::new(scheduler_runtime));
-    let scheduler_clone = Arc::clone(&scheduler);
+    scheduler_runtime.set_default_executor(config.daemon.executor);
+    scheduler_runtime.set_duplicate_run_names(config.daemon.duplicate_run_names);
+    scheduler_runtime.set_backups_kept(config.daemon.state_backups);
+    scheduler_runtime.set_preemption_priority(config.daemon.preemption_priority);
+    scheduler_runtime
+        .set_priority_limits(config.priority_limits.clone(), config.priorities.clone());
+    scheduler_runtime.set_labels(labels, gpu_labels);
+    scheduler_runtime.set_min_free_disk(min_free_disk_mb);
+    scheduler_runtime.set_pass_budget(config.scheduler.pass_budget());
+    scheduler_runtime.set_log_dir(gflow::paths::get_log_dir().ok());
+    scheduler_runtime.set_hooks(super::hooks::Hooks::new(&config.hooks));
+    if let Some(tokens) = &auth.tokens {
+        scheduler_runtime.set_job_tokens(Arc::clone(tokens));
+    }
 
     // Create event bus for event-driven scheduling
     let event_bus = Arc::new(EventBus::new(1000));
     let event_bus_clone = Arc::clone(&event_bus);
+    scheduler_runtime.set_event_bus(Arc::clone(&event_bus));
 
-    // Spawn state saver task (30 second interval)
+    let scheduler = Arc::new(tokio::sync::RwLock::new(scheduler_runtime));
+    let scheduler_clone = Arc::clone(&scheduler);
+
+    // Spawn state saver task (writes changes at most once per interval)
     let scheduler_for_saver = Arc::clone(&scheduler);
     let state_saver_task = tokio::spawn(
         async move {
-            tracing::info!(interval_secs = 30u64, "Starting state saver task");
-            super::state_saver::run(scheduler_for_saver, state_rx, Duration::from_secs(30)).await;
+            tracing::info!(
+                interval_ms = state_save_interval.as_millis() as u64,
+                "Starting state saver task"
+            );
+            super::state_saver::run(scheduler_for_saver, state_rx, state_save_interval).await;
         }
         .instrument(tracing::info_span!("state_saver_task")),
     );
     state_saver_handle.set_task_handle(state_saver_task);
 
+    // Rotate backups only while the state file loaded fine: in recovery mode
+    // they would replace good backups with a partial state.
+    if config.daemon.state_backups > 0 && scheduler.read().await.state_writable() {
+        let backup_interval = Duration::from_secs(config.daemon.state_backup_interval_secs);
+        tokio::spawn(
+            super::backups::run(Arc::clone(&scheduler), backup_interval)
+                .instrument(tracing::info_span!("state_backup_task")),
+        );
+    }
+
     // Spawn event-driven scheduler task only when we can persist (state.json or journal).
     // Otherwise the daemon is read-only and should not mutate jobs.
     let can_schedule = scheduler.read().await.can_mutate();
@@ -91,6 +210,8 @@ pub async fn run(config: gflow::config::Config) -> anyhow::Result<()> {
                     scheduler_clone,
                     event_bus_clone,
                     gpu_poll_interval,
+                    scheduler_tick,
+                    job_dir_retention,
                 )
                 .await;
             }
@@ -103,61 +224,245 @@ pub async fn run(config: gflow::config::Config) -> anyhow::Result<()> {
         );
     }
 
+    // Notification targets can change on config reload; the supervisor restarts
+    // the notifiers whenever a new NotificationsConfig is published.
+    let (notifications_tx, notifications_rx) = tokio::sync::watch::channel(notifications.clone());
+    let (rate_limits_tx, rate_limits_rx) = tokio::sync::watch::channel(config.rate_limits.clone());
+    let config_reloader = Arc::new(ConfigReloader::new(
+        source,
+        config.clone(),
+        Arc::clone(&scheduler),
+        notifications_tx,
+        rate_limits_tx,
+    ));
+
+    // Stop launching jobs on SIGTERM/SIGINT/SIGUSR2, optionally wait for
+    // running ones, then close the listeners.
+    let shutdown = Shutdown::default();
+    tokio::spawn(
+        shutdown
+            .clone()
+            .run(Arc::clone(&scheduler), wait_running)
+            .instrument(tracing::info_span!("shutdown")),
+    );
+
+    // Listings read published snapshots while the scheduler holds the lock.
+    let (snapshots_tx, snapshots_rx) = tokio::sync::watch::channel(None);
+    tokio::spawn(
+        snapshot::publish(Arc::clone(&scheduler), snapshots_tx)
+            .instrument(tracing::info_span!("snapshot_publisher")),
+    );
+
     // Create server state with scheduler, event bus, and state saver
-    let server_state = state::ServerState::new(scheduler, event_bus, state_saver_handle.clone());
+    let server_state = state::ServerState::new(
+        scheduler,
+        snapshots_rx,
+        event_bus,
+        state_saver_handle.clone(),
+        Arc::clone(&config_reloader),
+        auth,
+        shutdown.clone(),
+        Arc::clone(&webhook_outbox),
+        Arc::clone(&job_webhooks),
+        rate_limit::SubmissionLimiter::new(rate_limits_rx),
+    );
 
-    // Spawn notification dispatchers (best-effort)
-    if notifications.enabled
-        && (!notifications.webhooks.is_empty() || !notifications.emails.is_empty())
-    {
-        let delivery_semaphore = Arc::new(Semaphore::new(
-            notifications.max_concurrent_deliveries.max(1),
-        ));
-        super::webhooks::spawn_webhook_notifier(
-            notifications.clone(),
-            Arc::clone(&delivery_semaphore),
+    // Spawn notification dispatchers (best-effort). They subscribe to the event
+    // bus before returning, so DaemonStarted below is not missed.
+    let notifiers = spawn_notifiers(
+        notifications.clone(),
+        &server_state.scheduler,
+        &server_state.event_bus,
+        &daemon_host,
+        &webhook_outbox,
+        &job_webhooks,
+    );
+    tokio::spawn(
+        run_notification_supervisor(
+            notifiers,
+            notifications_rx,
             Arc::clone(&server_state.scheduler),
             Arc::clone(&server_state.event_bus),
             daemon_host,
-        );
-        super::emails::spawn_email_notifier(
-            notifications,
-            delivery_semaphore,
-            Arc::clone(&server_state.scheduler),
-            Arc::clone(&server_state.event_bus),
-            config.daemon.host.clone(),
-        );
+            webhook_outbox,
+            job_webhooks,
+        )
+        .instrument(tracing::info_span!("notification_supervisor")),
+    );
+    if notifications.enabled
+        && (!notifications.webhooks.is_empty() || !notifications.emails.is_empty())
+    {
         // Notify that the daemon is online (covers both fresh start and reload).
         server_state
             .event_bus
             .publish(super::events::SchedulerEvent::DaemonStarted);
     }
 
-    let app = Router::new()
+    tokio::spawn(
+        reload_on_sighup(config_reloader).instrument(tracing::info_span!("config_reload_signal")),
+    );
+
+    let app = with_common_layers(
+        versioned(read_only_routes().merge(mutating_routes())),
+        server_state.clone(),
+    );
+    let read_only_app = with_common_layers(versioned(read_only_routes()), server_state);
+
+    let mut listen_addrs = vec![config.daemon.listen_addr()];
+    listen_addrs.extend(config.daemon.extra_listen.iter().cloned());
+    let mut servers = tokio::task::JoinSet::new();
+    let mut socket_files = Vec::new();
+    for addr in listen_addrs {
+        match addr {
+            ListenAddr::Tcp { host, port } => {
+                let listener = bind_tcp(&host, port).await?;
+                match &tls_config {
+                    Some(tls_config) => {
+                        tracing::info!(host = %host, port, "Serving HTTPS");
+                        servers.spawn(serve_tls(
+                            listener,
+                            app.clone(),
+                            Arc::clone(tls_config),
+                            shutdown.clone(),
+                        ));
+                    }
+                    None => {
+                        servers.spawn(serve(listener, app.clone(), shutdown.clone()));
+                    }
+                }
+            }
+            ListenAddr::Unix(path) => {
+                let (listener, socket_file) = super::unix_socket::bind(&path)?;
+                tracing::info!(path = %path.display(), "Listening for HTTP requests on Unix socket");
+                socket_files.push(socket_file);
+                servers.spawn(serve(listener, app.clone(), shutdown.clone()));
+            }
+        }
+    }
+
+    if let Some((host, port)) = readonly_addr {
+        let listener = bind_tcp(&host, port).await?;
+        tracing::info!(host = %host, port, "Serving read-only API");
+        match &tls_config {
+            Some(tls_config) => {
+                servers.spawn(serve_tls(
+                    listener,
+                    read_only_app,
+                    Arc::clone(tls_config),
+                    shutdown.clone(),
+                ));
+            }
+            None => {
+                servers.spawn(serve(listener, read_only_app, shutdown.clone()));
+            }
+        }
+    }
+
+    // Record our PID only once every listener is bound, so a failed start does
+    // not clobber the PID file of a running instance.
+    let pid_file = super::pidfile::PidFile::create(gflow::paths::get_pid_file_path()?)?;
+    while let Some(result) = servers.join_next().await {
+        result??;
+    }
+    drop(pid_file);
+    drop(socket_files);
+
+    tracing::info!("Saving state before shutdown");
+    if let Err(e) = state_saver_handle.shutdown_and_wait().await {
+        tracing::error!(error = %e, "Failed to save state during shutdown");
+    } else {
+        tracing::info!("State saved successfully");
+    }
+
+    drop(instance_lock);
+
+    tracing::info!("Server shutdown complete");
+    Ok(())
+}
+
+/// Mount point of the versioned API.
+const API_PREFIX: &str = "/api/v1";
+
+/// Serve `api` under [`API_PREFIX`], plus the web UI. The unversioned API
+/// paths stay as deprecated aliases for one release.
+fn versioned(api: Router<state::ServerState>) -> Router<state::ServerState> {
+    Router::new()
         .route("/", get(|| async { "Hello, World!" }))
         .route("/ui", get(web_ui::serve_index))
         .route("/ui/", get(web_ui::serve_index))
         .route("/ui/{*path}", get(web_ui::serve_asset))
-        .route("/jobs", get(handlers::list_jobs).post(handlers::create_job))
-        .route("/jobs/batch", post(handlers::create_jobs_batch))
+        .nest(API_PREFIX, api.clone())
+        .merge(api)
+}
+
+/// GET endpoints safe to expose to dashboards; also served on `readonly_bind`.
+fn read_only_routes() -> Router<state::ServerState> {
+    Router::new()
+        .route("/openapi.json", get(handlers::get_openapi))
+        .route("/jobs", get(handlers::list_jobs))
         .route(
             "/jobs/resolve-dependency",
             get(handlers::resolve_dependency),
         )
-        .route(
-            "/jobs/{id}",
-            get(handlers::get_job).patch(handlers::update_job),
-        )
+        .route("/jobs/estimates", get(handlers::get_start_estimates))
+        .route("/jobs/{id}", get(handlers::get_job))
+        .route("/jobs/{id}/log", get(handlers::get_job_log))
+        .route("/jobs/{id}/artifacts", get(handlers::list_job_artifacts))
+        .route("/info", get(handlers::info))
+        .route("/info/stats", get(handlers::get_scheduler_stats))
+        .route("/health", get(handlers::get_health))
+        .route("/version", get(handlers::get_version))
+        .route("/healthz", get(handlers::healthz))
+        .route("/readyz", get(handlers::readyz))
+        .route("/startupz", get(handlers::startupz))
+        .route("/gpu-processes", get(handlers::list_ignored_gpu_processes))
+        .route("/nodes", get(handlers::list_nodes))
+        .route("/groups", get(handlers::list_groups))
+        .route("/groups/{group_id}", get(handlers::get_group))
+        .route("/groups/{group_id}/jobs", get(handlers::list_group_jobs))
+        .route("/reservations", get(handlers::list_reservations))
+        .route("/reservations/{id}", get(handlers::get_reservation))
+        .route("/stats", get(handlers::get_stats))
+        .route("/stats/runtimes", get(handlers::get_runtime_stats))
+        .route("/report", get(handlers::get_report))
+        .route("/metrics", get(handlers::get_metrics))
+}
+
+/// Endpoints that change state or expose internals; never on `readonly_bind`.
+fn mutating_routes() -> Router<state::ServerState> {
+    Router::new()
+        .route("/jobs", post(handlers::create_job))
+        .route("/jobs/batch", post(handlers::create_jobs_batch))
+        .route("/jobs/actions", post(handlers::apply_job_action))
+        .route("/jobs/{id}", patch(handlers::update_job))
         .route("/jobs/{id}/finish", post(handlers::finish_job))
         .route("/jobs/{id}/fail", post(handlers::fail_job))
         .route("/jobs/{id}/cancel", post(handlers::cancel_job))
         .route("/jobs/{id}/hold", post(handlers::hold_job))
         .route("/jobs/{id}/release", post(handlers::release_job))
-        .route("/jobs/{id}/log", get(handlers::get_job_log))
-        .route("/info", get(handlers::info))
-        .route("/health", get(handlers::get_health))
+        .route(
+            "/jobs/{id}/artifacts",
+            post(handlers::register_job_artifact),
+        )
+        .route("/jobs/{id}/dir", delete(handlers::remove_job_dir))
+        .route("/jobs/{id}/tags", post(handlers::update_job_tags))
+        .route("/jobs/{id}/signal", post(handlers::signal_job))
+        .route(
+            "/jobs/{id}/notifications",
+            post(handlers::add_job_webhook).delete(handlers::remove_job_webhooks),
+        )
+        .route("/config/reload", post(handlers::reload_config))
+        .route(
+            "/log-level",
+            get(handlers::get_log_level).post(handlers::set_log_level),
+        )
+        .route(
+            "/notifications/webhooks",
+            get(handlers::list_webhook_targets),
+        )
+        .route("/state/backups", post(handlers::create_backup))
+        .route("/state/restore", post(handlers::restore_backup))
         .route("/gpus", post(handlers::set_allowed_gpus))
-        .route("/gpu-processes", get(handlers::list_ignored_gpu_processes))
         .route("/gpu-processes/ignore", post(handlers::ignore_gpu_process))
         .route(
             "/gpu-processes/unignore",
@@ -167,26 +472,39 @@ pub async fn run(config: gflow::config::Config) -> anyhow::Result<()> {
             "/groups/{group_id}/max-concurrency",
             post(handlers::set_group_max_concurrency),
         )
+        .route("/labels", post(handlers::update_labels))
+        .route("/rate-limits", get(handlers::get_rate_limits))
         .route(
-            "/reservations",
-            get(handlers::list_reservations).post(handlers::create_reservation),
-        )
-        .route(
-            "/reservations/{id}",
-            get(handlers::get_reservation).delete(handlers::cancel_reservation),
+            "/rate-limits/{user}",
+            post(handlers::set_rate_limit).delete(handlers::reset_rate_limit),
         )
-        .route("/stats", get(handlers::get_stats))
-        .route("/metrics", get(handlers::get_metrics))
+        .route("/nodes", post(handlers::register_node))
+        .route("/reservations", post(handlers::create_reservation))
+        .route("/reservations/{id}", delete(handlers::cancel_reservation))
         .route("/debug/state", get(handlers::debug_state))
         .route("/debug/jobs/{id}", get(handlers::debug_job))
         .route("/debug/metrics", get(handlers::debug_metrics))
-        .layer(middleware::from_fn(request_tracing_middleware))
-        .with_state(server_state);
+}
 
-    // Create socket with SO_REUSEPORT for hot reload support
-    let host = &config.daemon.host;
-    let port = config.daemon.port;
+fn with_common_layers(
+    routes: Router<state::ServerState>,
+    server_state: state::ServerState,
+) -> Router {
+    routes
+        .layer(middleware::from_fn_with_state(
+            server_state.clone(),
+            auth::require_auth,
+        ))
+        .layer(middleware::from_fn_with_state(
+            server_state.clone(),
+            client_versions::track,
+        ))
+        .layer(middleware::from_fn(request_tracing_middleware))
+        .with_state(server_state)
+}
 
+/// Bind a TCP listener with SO_REUSEPORT so a reloaded instance can take over the port.
+async fn bind_tcp(host: &str, port: u16) -> anyhow::Result<tokio::net::TcpListener> {
     // Handle IPv6 literal addresses (e.g., "::1" -> "[::1]")
     let bind_addr = if host.contains(':') && !host.starts_with('[') {
         // IPv6 literal without brackets
@@ -221,19 +539,126 @@ pub async fn run(config: gflow::config::Config) -> anyhow::Result<()> {
     let listener = tokio::net::TcpListener::from_std(std_listener)?;
 
     tracing::info!(%addr, reuse_port = true, "Listening for HTTP requests");
+    Ok(listener)
+}
 
-    // Create shutdown signal handler with state saver for graceful shutdown
-    let shutdown_signal = create_shutdown_signal(state_saver_handle);
-
-    // Start Axum server with graceful shutdown
+async fn serve<L>(listener: L, app: Router, shutdown: Shutdown) -> anyhow::Result<()>
+where
+    L: axum::serve::Listener,
+    L::Addr: std::fmt::Debug,
+{
+    // Stop accepting connections once shutting down and let in-flight
+    // requests finish before the final save.
     axum::serve(listener, app)
-        .with_graceful_shutdown(shutdown_signal)
+        .with_graceful_shutdown(async move { shutdown.stopping().await })
         .await?;
+    Ok(())
+}
 
-    tracing::info!("Server shutdown complete");
+async fn serve_tls(
+    listener: tokio::net::TcpListener,
+    app: Router,
+    tls_config: Arc<rustls::ServerConfig>,
+    shutdown: Shutdown,
+) -> anyhow::Result<()> {
+    let handle = axum_server::Handle::new();
+    let shutdown_handle = handle.clone();
+    tokio::spawn(async move {
+        shutdown.stopping().await;
+        shutdown_handle.graceful_shutdown(None);
+    });
+
+    axum_server::from_tcp_rustls(
+        listener.into_std()?,
+        axum_server::tls_rustls::RustlsConfig::from_config(tls_config),
+    )
+    .handle(handle)
+    .serve(app.into_make_service())
+    .await?;
     Ok(())
 }
 
+fn spawn_notifiers(
+    notifications: gflow::config::NotificationsConfig,
+    scheduler: &scheduler_runtime::SharedState,
+    event_bus: &Arc<EventBus>,
+    daemon_host: &str,
+    webhook_outbox: &Arc<super::webhooks::WebhookOutbox>,
+    job_webhooks: &Arc<super::webhooks::JobWebhookStore>,
+) -> Vec<tokio::task::JoinHandle<()>> {
+    let delivery_semaphore = Arc::new(Semaphore::new(
+        notifications.max_concurrent_deliveries.max(1),
+    ));
+    [
+        super::webhooks::spawn_webhook_notifier(
+            notifications.clone(),
+            Arc::clone(&delivery_semaphore),
+            Arc::clone(scheduler),
+            Arc::clone(event_bus),
+            daemon_host.to_string(),
+            Arc::clone(webhook_outbox),
+            Arc::clone(job_webhooks),
+        ),
+        super::emails::spawn_email_notifier(
+            notifications,
+            delivery_semaphore,
+            Arc::clone(scheduler),
+            Arc::clone(event_bus),
+            daemon_host.to_string(),
+        ),
+    ]
+    .into_iter()
+    .flatten()
+    .collect()
+}
+
+/// Restart the webhook and email notifiers whenever a reload publishes a new
+/// notifications config. In-flight deliveries run in their own tasks and finish.
+async fn run_notification_supervisor(
+    mut notifiers: Vec<tokio::task::JoinHandle<()>>,
+    mut notifications_rx: tokio::sync::watch::Receiver<gflow::config::NotificationsConfig>,
+    scheduler: scheduler_runtime::SharedState,
+    event_bus: Arc<EventBus>,
+    daemon_host: String,
+    webhook_outbox: Arc<super::webhooks::WebhookOutbox>,
+    job_webhooks: Arc<super::webhooks::JobWebhookStore>,
+) {
+    while notifications_rx.changed().await.is_ok() {
+        for handle in notifiers.drain(..) {
+            handle.abort();
+        }
+        tracing::info!("Restarting notifiers with reloaded config");
+        let notifications = notifications_rx.borrow_and_update().clone();
+        notifiers = spawn_notifiers(
+            notifications,
+            &scheduler,
+            &event_bus,
+            &daemon_host,
+            &webhook_outbox,
+            &job_webhooks,
+        );
+    }
+}
+
+async fn reload_on_sighup(config_reloader: Arc<ConfigReloader>) {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    let mut sighup = match signal(SignalKind::hangup()) {
+        Ok(sighup) => sighup,
+        Err(e) => {
+            tracing::error!(error = %e, "Failed to register SIGHUP handler; config reload via signal disabled");
+            return;
+        }
+    };
+
+    while sighup.recv().await.is_some() {
+        tracing::info!(signal = "SIGHUP", "Reloading config");
+        config_reloader.reload(ReloadTrigger::Signal).await;
+    }
+}
+
+/// Give each request an ID, returned as `X-Request-Id` and carried by the
+/// events it causes, and log it with its outcome.
 async fn request_tracing_middleware(req: Request, next: Next) -> Response {
     let request_id = uuid::Uuid::new_v4().to_string();
     let method = req.method().clone();
@@ -250,13 +675,25 @@ async fn request_tracing_middleware(req: Request, next: Next) -> Response {
     async move {
         let started_at = std::time::Instant::now();
         tracing::info!("Request received");
-        let mut response = next.run(req).await;
+        let mut response = super::events::REQUEST_ID
+            .scope(request_id.clone(), next.run(req))
+            .await;
         if let Ok(header_value) = HeaderValue::from_str(&request_id) {
             response.headers_mut().insert("x-request-id", header_value);
         }
+        let user = response
+            .extensions()
+            .get::<Caller>()
+            .map(|caller| match caller {
+                Caller::User { username, .. } => username.clone(),
+                Caller::Job(job_id) => format!("job {job_id}"),
+            });
         tracing::info!(
+            method = %method,
+            path = %route,
             status = response.status().as_u16(),
             latency_ms = started_at.elapsed().as_millis() as u64,
+            user,
             "Request completed"
         );
         response
@@ -264,32 +701,3 @@ async fn request_tracing_middleware(req: Request, next: Next) -> Response {
     .instrument(span)
     .await
 }
-
-async fn create_shutdown_signal(state_saver: StateSaverHandle) {
-    use tokio::signal::unix::{signal, SignalKind};
-
-    let mut sigterm = signal(SignalKind::terminate()).expect("Failed to register SIGTERM handler");
-    let mut sigint = signal(SignalKind::interrupt()).expect("Failed to register SIGINT handler");
-    let mut sigusr2 =
-        signal(SignalKind::user_defined2()).expect("Failed to register SIGUSR2 handler");
-
-    tokio::select! {
-        _ = sigterm.recv() => {
-            tracing::info!(signal = "SIGTERM", "Initiating graceful shutdown");
-        }
-        _ = sigint.recv() => {
-            tracing::info!(signal = "SIGINT", "Initiating graceful shutdown");
-        }
-        _ = sigusr2.recv() => {
-            tracing::info!(signal = "SIGUSR2", reload = true, "Initiating graceful shutdown");
-        }
-    }
-
-    // Save state before exiting
-    tracing::info!("Saving state before shutdown");
-    if let Err(e) = state_saver.shutdown_and_wait().await {
-        tracing::error!(error = %e, "Failed to save state during shutdown");
-    } else {
-        tracing::info!("State saved successfully");
-    }
-}
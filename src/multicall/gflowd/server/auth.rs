@@ -0,0 +1,284 @@
+use super::state::ServerState;
+use crate::multicall::gflowd::tokens::{Caller, TokenStore};
+use axum::{
+    extract::{Request, State},
+    http::{header, Method},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use gflow::core::api_error::{ApiError, ApiErrorKind};
+use gflow::core::job::Job;
+use std::sync::Arc;
+
+/// Authentication settings from `[daemon]`, fixed for the daemon's lifetime.
+pub(in crate::multicall::gflowd) struct AuthPolicy {
+    pub(in crate::multicall::gflowd) required: bool,
+    pub(in crate::multicall::gflowd) anonymous_read: bool,
+    pub(in crate::multicall::gflowd) tokens: Option<Arc<TokenStore>>,
+    /// `daemon.allow_as_user`: accept `--as-user` without authentication
+    pub(in crate::multicall::gflowd) allow_as_user: bool,
+}
+
+impl AuthPolicy {
+    pub(in crate::multicall::gflowd) fn disabled() -> Self {
+        Self {
+            required: false,
+            anonymous_read: true,
+            tokens: None,
+            allow_as_user: false,
+        }
+    }
+}
+
+/// Probes and the web UI shell stay reachable without a token.
+fn is_public_path(path: &str) -> bool {
+    matches!(
+        path,
+        "/" | "/health"
+            | "/version"
+            | "/healthz"
+            | "/readyz"
+            | "/startupz"
+            | "/openapi.json"
+            | "/ui"
+            | "/ui/"
+    ) || path.starts_with("/ui/")
+}
+
+/// Daemon-wide settings that only admin tokens may change.
+fn is_admin_path(path: &str) -> bool {
+    matches!(
+        path,
+        "/config/reload" | "/log-level" | "/gpus" | "/labels" | "/nodes"
+    ) || path.starts_with("/gpu-processes/")
+        || path.starts_with("/state/")
+        || path.starts_with("/rate-limits/")
+}
+
+/// Job tokens may only report their own job's outcome and artifacts.
+fn job_token_allows(job_id: u32, method: &Method, path: &str) -> bool {
+    *method == Method::POST
+        && path
+            .strip_prefix(&format!("/jobs/{job_id}/"))
+            .is_some_and(|action| matches!(action, "finish" | "fail" | "artifacts"))
+}
+
+fn unauthorized(message: &str) -> Response {
+    let error = ApiError::new(ApiErrorKind::Unauthorized, message);
+    (
+        [(header::WWW_AUTHENTICATE, "Bearer")],
+        error.into_response(),
+    )
+        .into_response()
+}
+
+fn forbidden(message: String) -> Response {
+    ApiError::forbidden(message).into_response()
+}
+
+/// Authenticate the bearer token and attach the [`Caller`] to the request.
+pub(super) async fn require_auth(
+    State(server_state): State<ServerState>,
+    mut req: Request,
+    next: Next,
+) -> Response {
+    let policy = &server_state.auth;
+    let Some(tokens) = policy.tokens.as_ref().filter(|_| policy.required) else {
+        return next.run(req).await;
+    };
+
+    let method = req.method().clone();
+    // `/api/v1/jobs` and its legacy alias `/jobs` get the same checks.
+    let path = req.uri().path();
+    let path = path
+        .strip_prefix(super::API_PREFIX)
+        .filter(|rest| rest.starts_with('/'))
+        .unwrap_or(path)
+        .to_string();
+    let is_read = method == Method::GET || method == Method::HEAD;
+    let open = is_read && (policy.anonymous_read || is_public_path(&path));
+
+    let bearer = req
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(str::trim);
+    let Some(bearer) = bearer else {
+        if open {
+            return next.run(req).await;
+        }
+        return unauthorized(
+            "Authentication required. Set GFLOW_TOKEN or daemon.token to a token from `gflowd token create`.",
+        );
+    };
+
+    let Some(caller) = tokens.authenticate(bearer) else {
+        return unauthorized("Invalid or revoked API token");
+    };
+    match &caller {
+        Caller::Job(job_id) if !open && !job_token_allows(*job_id, &method, &path) => {
+            return forbidden(format!(
+                "Job token for job {job_id} can only finish or fail that job or register its artifacts"
+            ));
+        }
+        Caller::User {
+            username,
+            admin: false,
+        } if !is_read && is_admin_path(&path) => {
+            return forbidden(format!(
+                "User '{username}' is not an admin; changing daemon settings requires an admin token"
+            ));
+        }
+        _ => {}
+    }
+
+    req.extensions_mut().insert(caller.clone());
+    let mut response = next.run(req).await;
+    // For the access log.
+    response.extensions_mut().insert(caller);
+    response
+}
+
+/// The authenticated username, which overrides any user the client claims.
+pub(super) fn authenticated_user(caller: Option<&Caller>) -> Option<&str> {
+    match caller {
+        Some(Caller::User { username, .. }) => Some(username),
+        _ => None,
+    }
+}
+
+/// Settle who a submitted job belongs to. The authenticated username
+/// overrides any user the client claims, unless an admin submits with
+/// `--as-user`; without authentication `--as-user` needs `allow_as_user`.
+pub(super) fn resolve_submitter(
+    policy: &AuthPolicy,
+    caller: Option<&Caller>,
+    job: &mut Job,
+) -> Result<(), ApiError> {
+    match caller {
+        Some(Caller::User { admin: true, .. }) if job.as_user => {}
+        Some(Caller::User { username, .. }) if job.as_user => {
+            return Err(ApiError::forbidden(format!(
+                "User '{username}' is not an admin; --as-user requires an admin token"
+            )));
+        }
+        Some(Caller::User { username, .. }) => job.submitted_by = username.as_str().into(),
+        _ if job.as_user && !policy.allow_as_user => {
+            return Err(ApiError::forbidden(
+                "--as-user requires an admin token, or daemon.allow_as_user on daemons without require_auth",
+            ));
+        }
+        _ => {}
+    }
+    job.as_user = false;
+    if job.submitted_by.trim().is_empty() {
+        return Err(ApiErrorKind::MissingUser.into());
+    }
+    Ok(())
+}
+
+/// Reject changes to another user's job. Admins and unauthenticated daemons
+/// (no [`Caller`]) are not restricted; unknown jobs fall through to the
+/// handler's 404.
+pub(super) async fn reject_if_not_owner(
+    server_state: &ServerState,
+    caller: Option<&Caller>,
+    job_id: u32,
+) -> Option<Response> {
+    if !matches!(caller, Some(Caller::User { admin: false, .. })) {
+        return None;
+    }
+
+    let state = server_state.scheduler.read().await;
+    let owner = state.get_job(job_id)?.submitted_by;
+    ownership_error(caller, job_id, &owner).map(IntoResponse::into_response)
+}
+
+/// Why `caller` may not change job `job_id` of `owner`, by the same rule as
+/// [`reject_if_not_owner`], for handlers that already hold the lock.
+pub(super) fn ownership_error(
+    caller: Option<&Caller>,
+    job_id: u32,
+    owner: &str,
+) -> Option<ApiError> {
+    let Some(Caller::User {
+        username,
+        admin: false,
+    }) = caller
+    else {
+        return None;
+    };
+    (owner != username.as_str()).then(|| {
+        ApiError::forbidden(format!(
+            "Job {job_id} belongs to '{owner}'; only its owner or an admin can change it"
+        ))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn job_tokens_only_report_on_their_own_job() {
+        assert!(job_token_allows(7, &Method::POST, "/jobs/7/finish"));
+        assert!(job_token_allows(7, &Method::POST, "/jobs/7/artifacts"));
+        assert!(!job_token_allows(7, &Method::POST, "/jobs/70/artifacts"));
+        assert!(!job_token_allows(7, &Method::POST, "/jobs/7/cancel"));
+        assert!(!job_token_allows(7, &Method::GET, "/jobs/7/artifacts"));
+    }
+
+    fn resolve(
+        policy: &AuthPolicy,
+        caller: Option<&Caller>,
+        user: &str,
+        as_user: bool,
+    ) -> Result<String, u16> {
+        let mut job = Job::builder().submitted_by(user).build();
+        job.as_user = as_user;
+        resolve_submitter(policy, caller, &mut job)
+            .map(|()| job.submitted_by.to_string())
+            .map_err(|error| error.status())
+    }
+
+    #[test]
+    fn only_admins_or_allow_as_user_may_submit_for_someone_else() {
+        let user = |admin| Caller::User {
+            username: "alice".to_string(),
+            admin,
+        };
+        let disabled = AuthPolicy::disabled();
+        let allowing = AuthPolicy {
+            allow_as_user: true,
+            ..AuthPolicy::disabled()
+        };
+
+        // The token's user wins over the one the client claims...
+        assert_eq!(
+            resolve(&disabled, Some(&user(false)), "root", false),
+            Ok("alice".into())
+        );
+        // ...unless an admin submits for someone else.
+        assert_eq!(
+            resolve(&disabled, Some(&user(true)), "bob", true),
+            Ok("bob".into())
+        );
+        assert_eq!(
+            resolve(&disabled, Some(&user(false)), "bob", true),
+            Err(403)
+        );
+
+        assert_eq!(resolve(&disabled, None, "bob", false), Ok("bob".into()));
+        assert_eq!(resolve(&disabled, None, "bob", true), Err(403));
+        assert_eq!(resolve(&allowing, None, "bob", true), Ok("bob".into()));
+    }
+
+    #[test]
+    fn jobs_without_a_user_are_rejected() {
+        let mut job = Job::builder().submitted_by(" ").build();
+        let error = resolve_submitter(&AuthPolicy::disabled(), None, &mut job).unwrap_err();
+        assert_eq!(error.kind, ApiErrorKind::MissingUser);
+        assert_eq!(error.kind.code(), "missing_user");
+    }
+}
@@ -0,0 +1,87 @@
+//! Counting requests by the client version they carry, for `/info/stats`.
+
+use super::state::ServerState;
+use axum::{extract::Request, extract::State, middleware::Next, response::Response};
+use gflow::core::info::ClientVersionStats;
+use gflow::core::version::{Version, CLIENT_VERSION_HEADER};
+use std::sync::Mutex;
+
+#[derive(Debug, Default)]
+pub(super) struct ClientVersions {
+    stats: Mutex<ClientVersionStats>,
+}
+
+impl ClientVersions {
+    /// Count a request whose client version header was `header`.
+    fn record(&self, header: Option<&str>, daemon: Version) {
+        let mut stats = self.stats.lock().unwrap_or_else(|e| e.into_inner());
+        let Some(header) = header else {
+            stats.unversioned_requests += 1;
+            return;
+        };
+        // An unparseable version is as good as none.
+        let Ok(client) = header.parse::<Version>() else {
+            stats.unversioned_requests += 1;
+            return;
+        };
+        if client >= daemon {
+            return;
+        }
+        stats.outdated_requests += 1;
+        let count = stats
+            .outdated_by_version
+            .entry(client.to_string())
+            .or_default();
+        *count += 1;
+        if *count == 1 {
+            tracing::warn!(
+                client_version = %client,
+                daemon_version = %daemon,
+                "Request from an outdated client"
+            );
+        }
+    }
+
+    pub(super) fn snapshot(&self) -> ClientVersionStats {
+        self.stats.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+}
+
+pub(super) async fn track(
+    State(server_state): State<ServerState>,
+    req: Request,
+    next: Next,
+) -> Response {
+    let header = req
+        .headers()
+        .get(CLIENT_VERSION_HEADER)
+        .and_then(|value| value.to_str().ok());
+    server_state
+        .client_versions
+        .record(header, Version::current());
+    next.run(req).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_older_clients_count_as_outdated() {
+        let versions = ClientVersions::default();
+        let daemon = Version::new(0, 5, 0);
+        versions.record(Some("0.5.0"), daemon);
+        versions.record(Some("0.6.1"), daemon);
+        versions.record(Some("0.4.17"), daemon);
+        versions.record(Some("0.4.17"), daemon);
+        versions.record(Some("0.4.2"), daemon);
+        versions.record(None, daemon);
+        versions.record(Some("garbage"), daemon);
+
+        let stats = versions.snapshot();
+        assert_eq!(stats.outdated_requests, 3);
+        assert_eq!(stats.outdated_by_version["0.4.17"], 2);
+        assert_eq!(stats.outdated_by_version["0.4.2"], 1);
+        assert_eq!(stats.unversioned_requests, 2);
+    }
+}
@@ -0,0 +1,30 @@
+//! Error responses: [`ApiError`] as JSON with its HTTP status.
+
+use axum::{
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use gflow::core::api_error::ApiError;
+
+/// JSON body of `error`. It repeats the message as `error`, the only field
+/// clients older than error codes read.
+pub(super) fn body(error: &ApiError) -> serde_json::Value {
+    let mut body = serde_json::to_value(error).unwrap_or_default();
+    body["error"] = error.message.clone().into();
+    body
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let status =
+            StatusCode::from_u16(self.status()).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+        let mut response = (status, Json(body(&self))).into_response();
+        if let Some(secs) = self.retry_after_secs() {
+            response
+                .headers_mut()
+                .insert(header::RETRY_AFTER, secs.into());
+        }
+        response
+    }
+}
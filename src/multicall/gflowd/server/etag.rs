@@ -0,0 +1,75 @@
+//! Entity tags for the endpoints dashboards poll
+//!
+//! A tag is the scheduler's revision, prefixed with a number picked when the
+//! daemon starts so that a tag from before a restart never matches. A request
+//! whose `If-None-Match` holds the current tag gets `304 Not Modified` and no
+//! body. Handlers take the tag from the same read lock or snapshot they build
+//! the body from, so the two always agree.
+
+use axum::http::{header, HeaderMap, HeaderValue, StatusCode};
+use axum::response::{IntoResponse, Response};
+use std::sync::LazyLock;
+
+static INSTANCE: LazyLock<u64> = LazyLock::new(|| {
+    getrandom::u64().unwrap_or_else(|_| {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default();
+        now.as_nanos() as u64 ^ u64::from(std::process::id())
+    })
+});
+
+/// The tag of everything built at scheduler revision `revision`.
+pub(super) fn for_revision(revision: u64) -> HeaderValue {
+    HeaderValue::from_str(&format!("\"{:x}-{revision}\"", *INSTANCE))
+        .expect("an entity tag is printable ASCII")
+}
+
+/// `304 Not Modified` if the request's `If-None-Match` lists `etag` or `*`.
+pub(super) fn not_modified(headers: &HeaderMap, etag: &HeaderValue) -> Option<Response> {
+    let etag = etag.to_str().ok()?;
+    let matches = headers
+        .get_all(header::IF_NONE_MATCH)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(str::trim)
+        // `If-None-Match` compares tags weakly.
+        .any(|tag| tag == "*" || tag.trim_start_matches("W/") == etag);
+    matches.then(|| (StatusCode::NOT_MODIFIED, [(header::ETAG, etag)]).into_response())
+}
+
+/// `response` with `etag`, if there is one.
+pub(super) fn tagged(etag: Option<HeaderValue>, response: impl IntoResponse) -> Response {
+    let mut response = response.into_response();
+    if let Some(etag) = etag {
+        response.headers_mut().insert(header::ETAG, etag);
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn if_none_match_matches_listed_and_weak_tags() {
+        let etag = for_revision(7);
+        let tag = etag.to_str().unwrap().to_string();
+        let request = |value: &str| {
+            let mut headers = HeaderMap::new();
+            headers.insert(header::IF_NONE_MATCH, HeaderValue::from_str(value).unwrap());
+            headers
+        };
+
+        assert!(not_modified(&request(&tag), &etag).is_some());
+        assert!(not_modified(&request(&format!("\"other\", W/{tag}")), &etag).is_some());
+        assert!(not_modified(&request("*"), &etag).is_some());
+        assert!(not_modified(&request(for_revision(8).to_str().unwrap()), &etag).is_none());
+        assert!(not_modified(&HeaderMap::new(), &etag).is_none());
+        assert_eq!(
+            not_modified(&request(&tag), &etag).unwrap().status(),
+            StatusCode::NOT_MODIFIED
+        );
+    }
+}
@@ -0,0 +1,99 @@
+use super::super::auth::ownership_error;
+use super::super::state::{reject_if_read_only, ServerState};
+use super::openapi::ErrorBody;
+use crate::multicall::gflowd::tokens::Caller;
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Extension, Json,
+};
+use gflow::client::JobArtifactRequest;
+use gflow::core::api_error::{ApiError, ApiErrorKind};
+use gflow::core::job::JobArtifact;
+
+#[utoipa::path(
+    get,
+    path = "/jobs/{id}/artifacts",
+    tag = "jobs",
+    summary = "List the artifacts a job registered",
+    params(("id" = u32, Path, description = "Job ID")),
+    responses(
+        (status = 200, description = "Artifacts, oldest first", body = [JobArtifact]),
+        (status = 404, description = "No such job", body = ErrorBody),
+    )
+)]
+#[axum::debug_handler]
+pub(in crate::multicall::gflowd::server) async fn list_job_artifacts(
+    State(server_state): State<ServerState>,
+    Path(id): Path<u32>,
+) -> Response {
+    let state = server_state.scheduler.read().await;
+    if state.get_job(id).is_none() {
+        return ApiError::from(ApiErrorKind::JobNotFound { id }).into_response();
+    }
+    (StatusCode::OK, Json(state.job_artifacts(id).to_vec())).into_response()
+}
+
+#[utoipa::path(
+    post,
+    path = "/jobs/{id}/artifacts",
+    tag = "jobs",
+    summary = "Register an output of a job",
+    params(("id" = u32, Path, description = "Job ID")),
+    request_body = super::openapi::JobArtifactRequest,
+    responses(
+        (status = 200, description = "Artifact registered, or updated if its path was already", body = JobArtifact),
+        (status = 400, description = "The path does not exist, or the label is invalid", body = ErrorBody),
+        (status = 403, description = "Job belongs to another user", body = ErrorBody),
+        (status = 404, description = "No such job", body = ErrorBody),
+        (status = 409, description = "The job ran on a remote node, or has too many artifacts", body = ErrorBody),
+        (status = 503, description = "Daemon is read-only", body = ErrorBody),
+    )
+)]
+#[axum::debug_handler]
+pub(in crate::multicall::gflowd::server) async fn register_job_artifact(
+    State(server_state): State<ServerState>,
+    caller: Option<Extension<Caller>>,
+    Path(id): Path<u32>,
+    Json(request): Json<JobArtifactRequest>,
+) -> Response {
+    if let Some(resp) = reject_if_read_only(&server_state).await {
+        return resp;
+    }
+
+    let run_dir = {
+        let state = server_state.scheduler.read().await;
+        let Some(job) = state.get_job(id) else {
+            return ApiError::from(ApiErrorKind::JobNotFound { id }).into_response();
+        };
+        if let Some(error) = ownership_error(caller.as_deref(), id, &job.submitted_by) {
+            return error.into_response();
+        }
+        if let Some(node) = &job.node {
+            return ApiError::conflict(format!(
+                "Job {id} ran on node '{node}'; its artifacts cannot be checked from here"
+            ))
+            .into_response();
+        }
+        job.run_dir
+    };
+
+    // Sizing a directory can take a while; do it without the lock.
+    let registered = tokio::task::spawn_blocking(move || {
+        JobArtifact::register(&request.path, request.label.as_deref(), &run_dir)
+    })
+    .await;
+    let artifact = match registered {
+        Ok(Ok(artifact)) => artifact,
+        Ok(Err(message)) => return ApiError::invalid_request(message).into_response(),
+        Err(error) => return ApiError::internal(error.to_string()).into_response(),
+    };
+
+    let mut state = server_state.scheduler.write().await;
+    if let Err(message) = state.register_artifact(id, artifact.clone()) {
+        return ApiError::conflict(message).into_response();
+    }
+    tracing::info!(job_id = id, path = %artifact.path.display(), "Registered artifact");
+    (StatusCode::OK, Json(artifact)).into_response()
+}
@@ -0,0 +1,91 @@
+use super::super::state::{reject_if_read_only, ServerState};
+use crate::multicall::gflowd::backups;
+use crate::multicall::gflowd::events::SchedulerEvent;
+use axum::{
+    extract::State,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use gflow::core::api_error::ApiError;
+use gflow::core::info::StateRestoreReport;
+use std::path::PathBuf;
+
+#[derive(serde::Deserialize)]
+pub(in crate::multicall::gflowd::server) struct RestoreRequest {
+    path: PathBuf,
+    #[serde(default)]
+    force: bool,
+}
+
+#[axum::debug_handler]
+pub(in crate::multicall::gflowd::server) async fn create_backup(
+    State(server_state): State<ServerState>,
+) -> Response {
+    let state = server_state.scheduler.read().await;
+    match state.backup_state(false) {
+        Ok(path) => (
+            StatusCode::CREATED,
+            Json(serde_json::json!({ "path": path })),
+        )
+            .into_response(),
+        Err(e) => ApiError::internal(format!("{e:#}")).into_response(),
+    }
+}
+
+#[axum::debug_handler]
+pub(in crate::multicall::gflowd::server) async fn restore_backup(
+    State(server_state): State<ServerState>,
+    Json(request): Json<RestoreRequest>,
+) -> Response {
+    if let Some(resp) = reject_if_read_only(&server_state).await {
+        return resp;
+    }
+
+    let restored = match backups::load(&request.path) {
+        Ok(restored) => restored,
+        Err(e) => return ApiError::invalid_request(format!("{e:#}")).into_response(),
+    };
+
+    let report = {
+        // Holding the write lock keeps every other request from changing jobs
+        // until the restored state is in place and saved.
+        let mut state = server_state.scheduler.write().await;
+        if let Err(message) = backups::check_job_ids(state.next_job_id(), &restored, request.force)
+        {
+            return ApiError::conflict(message).into_response();
+        }
+        let replaced = match state.backup_state(false) {
+            Ok(path) => path.unwrap_or_default(),
+            Err(e) => {
+                return ApiError::internal(format!(
+                    "Not restoring: failed to back up the current state first: {e:#}"
+                ))
+                .into_response()
+            }
+        };
+
+        state.restore_state(restored).await;
+        let report = StateRestoreReport {
+            restored_from: request.path.display().to_string(),
+            jobs: state.job_specs().len(),
+            next_job_id: state.next_job_id(),
+            replaced_state_backup: replaced.display().to_string(),
+        };
+        tracing::warn!(
+            backup = %report.restored_from,
+            jobs = report.jobs,
+            next_job_id = report.next_job_id,
+            forced = request.force,
+            replaced_state_backup = %report.replaced_state_backup,
+            "Restored scheduler state from backup"
+        );
+        report
+    };
+
+    // Let the scheduler pick up the restored queue right away.
+    server_state
+        .event_bus
+        .publish(SchedulerEvent::PeriodicHealthCheck);
+    (StatusCode::OK, Json(report)).into_response()
+}
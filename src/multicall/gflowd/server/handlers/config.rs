@@ -0,0 +1,50 @@
+use super::super::state::ServerState;
+use crate::multicall::gflowd::config_reload::ReloadTrigger;
+use crate::multicall::gflowd::logging;
+use axum::{
+    extract::State,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use gflow::client::LogLevelRequest;
+use gflow::core::api_error::ApiError;
+
+#[axum::debug_handler]
+pub(in crate::multicall::gflowd::server) async fn reload_config(
+    State(server_state): State<ServerState>,
+) -> Response {
+    let report = server_state
+        .config_reloader
+        .reload(ReloadTrigger::Api)
+        .await;
+
+    match report.error {
+        Some(error) => ApiError::invalid_request(error).into_response(),
+        None => (StatusCode::OK, Json(serde_json::json!(report))).into_response(),
+    }
+}
+
+#[axum::debug_handler]
+pub(in crate::multicall::gflowd::server) async fn get_log_level() -> Response {
+    match logging::levels() {
+        Some(levels) => (StatusCode::OK, Json(levels.current())).into_response(),
+        None => ApiError::conflict("This daemon's log levels cannot be changed").into_response(),
+    }
+}
+
+#[axum::debug_handler]
+pub(in crate::multicall::gflowd::server) async fn set_log_level(
+    Json(request): Json<LogLevelRequest>,
+) -> Response {
+    let Some(levels) = logging::levels() else {
+        return ApiError::conflict("This daemon's log levels cannot be changed").into_response();
+    };
+    match levels.set(request.level.as_deref()) {
+        Ok(now) => {
+            tracing::info!(level = now.level, "Log level changed");
+            (StatusCode::OK, Json(now)).into_response()
+        }
+        Err(error) => ApiError::invalid_request(format!("{error:#}")).into_response(),
+    }
+}
@@ -0,0 +1,109 @@
+use super::super::state::ServerState;
+use super::openapi::ErrorBody;
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+use serde::Deserialize;
+use uuid::Uuid;
+
+use gflow::core::api_error::ApiError;
+use gflow::core::job::{Job, JobGroup};
+
+#[derive(Debug, Deserialize, utoipa::IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ListGroupsQuery {
+    /// Comma-separated owners
+    pub user: Option<String>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/groups",
+    tag = "jobs",
+    params(ListGroupsQuery),
+    responses(
+        (status = 200, description = "Job groups, oldest first", body = Vec<JobGroup>),
+    )
+)]
+pub async fn list_groups(
+    State(server_state): State<ServerState>,
+    Query(params): Query<ListGroupsQuery>,
+) -> Json<Vec<JobGroup>> {
+    let users: Option<Vec<&str>> = params.user.as_deref().map(|users| {
+        users
+            .split(',')
+            .map(str::trim)
+            .filter(|user| !user.is_empty())
+            .collect()
+    });
+
+    let state = server_state.scheduler.read().await;
+    let groups = state
+        .job_groups()
+        .into_iter()
+        .filter(|group| {
+            users
+                .as_ref()
+                .is_none_or(|users| users.contains(&group.owner.as_str()))
+        })
+        .collect();
+    Json(groups)
+}
+
+#[utoipa::path(
+    get,
+    path = "/groups/{group_id}",
+    tag = "jobs",
+    params(("group_id" = String, Path, description = "Group UUID")),
+    responses(
+        (status = 200, description = "The group", body = JobGroup),
+        (status = 400, description = "Invalid UUID", body = ErrorBody),
+        (status = 404, description = "No such group", body = ErrorBody),
+    )
+)]
+pub async fn get_group(
+    State(server_state): State<ServerState>,
+    Path(group_id): Path<String>,
+) -> Result<Json<JobGroup>, ApiError> {
+    let group_uuid = parse_group_id(&group_id)?;
+    let state = server_state.scheduler.read().await;
+    state
+        .job_group(&group_uuid)
+        .map(Json)
+        .ok_or_else(|| group_not_found(&group_id))
+}
+
+#[utoipa::path(
+    get,
+    path = "/groups/{group_id}/jobs",
+    tag = "jobs",
+    params(("group_id" = String, Path, description = "Group UUID")),
+    responses(
+        (status = 200, description = "The group's jobs in job ID order", body = Vec<Job>),
+        (status = 400, description = "Invalid UUID", body = ErrorBody),
+        (status = 404, description = "No such group", body = ErrorBody),
+    )
+)]
+pub async fn list_group_jobs(
+    State(server_state): State<ServerState>,
+    Path(group_id): Path<String>,
+) -> Result<Json<Vec<Job>>, ApiError> {
+    let group_uuid = parse_group_id(&group_id)?;
+    let state = server_state.scheduler.read().await;
+    let job_ids = state
+        .job_ids_by_group(&group_uuid)
+        .ok_or_else(|| group_not_found(&group_id))?;
+    Ok(Json(
+        job_ids.iter().filter_map(|&id| state.get_job(id)).collect(),
+    ))
+}
+
+fn parse_group_id(group_id: &str) -> Result<Uuid, ApiError> {
+    Uuid::parse_str(group_id)
+        .map_err(|_| ApiError::invalid_request(format!("Invalid UUID format: '{}'", group_id)))
+}
+
+fn group_not_found(group_id: &str) -> ApiError {
+    ApiError::not_found(format!("No jobs found with group_id '{}'", group_id))
+}
@@ -0,0 +1,190 @@
+use super::super::state::ServerState;
+use super::openapi::{Health, Liveness, Readiness, Startup};
+use axum::{extract::State, http::StatusCode, response::IntoResponse, Json};
+use gflow::core::info::VersionInfo;
+use gflow::core::version::Version;
+
+/// Liveness: the HTTP server is up and answering requests.
+#[utoipa::path(
+    get,
+    path = "/healthz",
+    tag = "health",
+    responses((status = 200, description = "Server is up", body = Liveness))
+)]
+#[axum::debug_handler]
+pub(in crate::multicall::gflowd::server) async fn healthz() -> impl IntoResponse {
+    (
+        StatusCode::OK,
+        Json(serde_json::json!({
+            "status": "ok",
+            "pid": std::process::id(),
+        })),
+    )
+}
+
+/// Readiness: state is loaded, NVML init was attempted, and jobs can be
+/// persisted (state.json or journal recovery mode).
+#[utoipa::path(
+    get,
+    path = "/readyz",
+    tag = "health",
+    responses(
+        (status = 200, description = "Ready", body = Readiness),
+        (status = 503, description = "Starting, or persistence is read-only", body = Readiness),
+    )
+)]
+#[axum::debug_handler]
+pub(in crate::multicall::gflowd::server) async fn readyz(
+    State(server_state): State<ServerState>,
+) -> impl IntoResponse {
+    let state = server_state.scheduler.read().await;
+    let mode = state.persistence_mode();
+    if state.is_ready() {
+        return (
+            StatusCode::OK,
+            Json(serde_json::json!({
+                "status": "ready",
+                "mode": mode,
+            })),
+        );
+    }
+
+    let reason = if state.startup_info().is_none() {
+        "startup in progress"
+    } else {
+        "persistence is read-only"
+    };
+    (
+        StatusCode::SERVICE_UNAVAILABLE,
+        Json(serde_json::json!({
+            "status": "not_ready",
+            "mode": mode,
+            "reason": reason,
+            "detail": state.state_load_error(),
+            "journal_error": state.journal_error(),
+        })),
+    )
+}
+
+/// Startup probe: how loading state went.
+#[utoipa::path(
+    get,
+    path = "/startupz",
+    tag = "health",
+    responses(
+        (status = 200, description = "State loaded", body = Startup),
+        (status = 503, description = "Still starting", body = Startup),
+    )
+)]
+#[axum::debug_handler]
+pub(in crate::multicall::gflowd::server) async fn startupz(
+    State(server_state): State<ServerState>,
+) -> impl IntoResponse {
+    let state = server_state.scheduler.read().await;
+    match state.startup_info() {
+        Some(startup) => (
+            StatusCode::OK,
+            Json(serde_json::json!({
+                "status": "complete",
+                "jobs_loaded": startup.jobs_loaded,
+                "load_duration_ms": startup.load_duration_ms,
+                "nvml_initialized": startup.nvml_initialized,
+                "journal_applied": startup.journal_applied,
+                "mode": state.persistence_mode(),
+            })),
+        ),
+        None => (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(serde_json::json!({ "status": "starting" })),
+        ),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/health",
+    tag = "health",
+    responses(
+        (status = 200, description = "`ok`, or `recovery` when only the journal is writable", body = Health),
+        (status = 503, description = "`read_only`: nothing can be persisted", body = Health),
+    )
+)]
+#[axum::debug_handler]
+pub(in crate::multicall::gflowd::server) async fn get_health(
+    State(server_state): State<ServerState>,
+) -> impl IntoResponse {
+    let pid = std::process::id();
+    let config_reload = server_state.config_reloader.last_report();
+    let shutdown = server_state.shutdown.phase();
+
+    let state = server_state.scheduler.read().await;
+    let state_writable = state.state_writable();
+    let journal_writable = state.journal_writable();
+    let mode = state.persistence_mode();
+    if state_writable {
+        return (
+            StatusCode::OK,
+            Json(serde_json::json!({
+                "status": "ok",
+                "pid": pid,
+                "config_reload": config_reload,
+                "shutdown": shutdown,
+            })),
+        );
+    }
+
+    let backup_path = state.state_backup_path().map(|p| p.display().to_string());
+    let journal_path = state.journal_path().display().to_string();
+
+    if journal_writable {
+        return (
+            StatusCode::OK,
+            Json(serde_json::json!({
+                "status": "recovery",
+                "mode": mode,
+                "pid": pid,
+                "detail": state.state_load_error(),
+                "state_backup": backup_path,
+                "journal": journal_path,
+                "journal_error": state.journal_error(),
+                "config_reload": config_reload,
+                "shutdown": shutdown,
+            })),
+        );
+    }
+
+    (
+        StatusCode::SERVICE_UNAVAILABLE,
+        Json(serde_json::json!({
+            "status": "read_only",
+            "pid": pid,
+            "detail": state.state_load_error(),
+            "state_backup": backup_path,
+            "journal": journal_path,
+            "journal_error": state.journal_error(),
+            "config_reload": config_reload,
+            "shutdown": shutdown,
+        })),
+    )
+}
+
+/// The daemon's version and the oldest client it accepts, for clients to
+/// check before their first request.
+#[utoipa::path(
+    get,
+    path = "/version",
+    tag = "health",
+    responses((status = 200, description = "Daemon version", body = VersionInfo))
+)]
+#[axum::debug_handler]
+pub(in crate::multicall::gflowd::server) async fn get_version(
+    State(server_state): State<ServerState>,
+) -> impl IntoResponse {
+    (
+        StatusCode::OK,
+        Json(VersionInfo {
+            version: Version::current(),
+            min_client_version: server_state.config_reloader.min_client_version(),
+        }),
+    )
+}
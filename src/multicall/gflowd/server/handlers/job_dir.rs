@@ -0,0 +1,84 @@
+use super::super::auth::ownership_error;
+use super::super::state::{reject_if_read_only, ServerState};
+use super::openapi::ErrorBody;
+use crate::multicall::gflowd::scheduler_runtime::job_dirs;
+use crate::multicall::gflowd::tokens::Caller;
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Extension, Json,
+};
+use gflow::client::RemovedJobDir;
+use gflow::core::api_error::{ApiError, ApiErrorKind};
+
+#[utoipa::path(
+    delete,
+    path = "/jobs/{id}/dir",
+    tag = "jobs",
+    summary = "Remove a finished job's GFLOW_JOB_DIR",
+    params(("id" = u32, Path, description = "Job ID")),
+    responses(
+        (status = 200, description = "Directory removed, with the size of its files", body = super::openapi::RemovedJobDir),
+        (status = 403, description = "Job belongs to another user", body = ErrorBody),
+        (status = 404, description = "No such job", body = ErrorBody),
+        (status = 409, description = "The job has not finished, or has no directory", body = ErrorBody),
+        (status = 503, description = "Daemon is read-only", body = ErrorBody),
+    )
+)]
+#[axum::debug_handler]
+pub(in crate::multicall::gflowd::server) async fn remove_job_dir(
+    State(server_state): State<ServerState>,
+    caller: Option<Extension<Caller>>,
+    Path(id): Path<u32>,
+) -> Response {
+    if let Some(resp) = reject_if_read_only(&server_state).await {
+        return resp;
+    }
+
+    let dir = {
+        let state = server_state.scheduler.read().await;
+        let Some(job) = state.get_job(id) else {
+            return ApiError::from(ApiErrorKind::JobNotFound { id }).into_response();
+        };
+        if let Some(error) = ownership_error(caller.as_deref(), id, &job.submitted_by) {
+            return error.into_response();
+        }
+        if !job.state.is_final() {
+            return ApiError::conflict(format!(
+                "Job {id} is {}; its directory can be removed once it has finished",
+                job.state
+            ))
+            .into_response();
+        }
+        match job.job_dir {
+            Some(dir) => *dir,
+            None => {
+                return ApiError::conflict(format!("Job {id} has no job directory")).into_response()
+            }
+        }
+    };
+
+    // A large directory can take a while; remove it without the lock.
+    let removed = tokio::task::spawn_blocking({
+        let dir = dir.clone();
+        move || job_dirs::remove_job_dir(&dir)
+    })
+    .await;
+    let bytes = match removed {
+        Ok(Ok(bytes)) => bytes,
+        Ok(Err(error)) => {
+            return ApiError::internal(format!("Failed to remove {}: {error}", dir.display()))
+                .into_response()
+        }
+        Err(error) => return ApiError::internal(error.to_string()).into_response(),
+    };
+
+    server_state
+        .scheduler
+        .write()
+        .await
+        .forget_job_dir(id, &dir);
+    tracing::info!(job_id = id, path = %dir.display(), bytes, "Removed job directory");
+    (StatusCode::OK, Json(RemovedJobDir { path: dir, bytes })).into_response()
+}
@@ -1,21 +1,49 @@
+use super::super::auth::{ownership_error, reject_if_not_owner, resolve_submitter};
+use super::super::etag;
+use super::super::idempotency;
+use super::super::rate_limit;
 use super::super::state::{reject_if_read_only, ServerState};
+use super::openapi::{self, ErrorBody, SubmittedJob};
 use crate::multicall::gflowd::events::SchedulerEvent;
+use crate::multicall::gflowd::scheduler_runtime::SchedulerRuntime;
+use crate::multicall::gflowd::tokens::Caller;
 use axum::{
     extract::{Path, State},
-    http::StatusCode,
+    http::{HeaderMap, HeaderValue, StatusCode},
     response::{IntoResponse, Response},
-    Json,
+    Extension, Json,
 };
-use gflow::core::job::{Job, JobRuntime, JobSpec, JobState};
-use std::collections::HashMap;
-
+use gflow::client::{
+    JobAction, JobActionRequest, JobActionResult, MAX_JOB_ACTION_IDS, TOTAL_COUNT_HEADER,
+};
+use gflow::core::api_error::{ApiError, ApiErrorKind};
+use gflow::core::info::SchedulerInfo;
+use gflow::core::job::{DependencyMode, Job, JobRuntime, JobSpec, JobState};
+use gflow::core::scheduler::{submission_digest, PriorSubmission, StartEstimate};
+use gflow::utils::name_glob::NameGlobs;
+use std::collections::{BTreeMap, HashMap};
+
+#[utoipa::path(
+    get,
+    path = "/info",
+    tag = "gpus",
+    params(("If-None-Match" = Option<String>, Header, description = "`ETag` of an earlier response")),
+    responses(
+        (status = 200, description = "GPUs and scheduler settings", body = SchedulerInfo),
+        (status = 304, description = "Unchanged since the response with the `ETag` in `If-None-Match`")
+    )
+)]
 #[axum::debug_handler]
 pub(in crate::multicall::gflowd::server) async fn info(
     State(server_state): State<ServerState>,
-) -> impl IntoResponse {
-    let state = server_state.scheduler.read().await;
-    let info = state.info();
-    (StatusCode::OK, Json(info))
+    headers: HeaderMap,
+) -> Response {
+    let state = server_state.jobs().await;
+    let etag = etag::for_revision(state.revision());
+    if let Some(response) = etag::not_modified(&headers, &etag) {
+        return response;
+    }
+    etag::tagged(Some(etag), (StatusCode::OK, Json(state.info())))
 }
 
 #[axum::debug_handler]
@@ -27,22 +55,60 @@ pub(in crate::multicall::gflowd::server) async fn list_ignored_gpu_processes(
     (StatusCode::OK, Json(processes))
 }
 
-#[derive(serde::Deserialize)]
+#[derive(serde::Deserialize, utoipa::IntoParams)]
+#[into_params(parameter_in = Query)]
 pub(in crate::multicall::gflowd::server) struct ListJobsQuery {
+    /// Comma-separated states, e.g. `Queued,Running`
     state: Option<String>,
+    /// Comma-separated submitters
     user: Option<String>,
     limit: Option<usize>,
     offset: Option<usize>,
+    /// Only jobs submitted at or after this Unix timestamp
     created_after: Option<i64>,
+    /// `desc` for newest first; the default with `search`
     order: Option<String>,
+    /// Whitespace-separated terms that must all appear, ignoring case, in the
+    /// command, script path, run name or a parameter value
+    search: Option<String>,
+    /// Comma-separated glob patterns, e.g. `train-*`; the run name must match one
+    name_glob: Option<String>,
+    /// Comma-separated tags; the job must have every one
+    tag: Option<String>,
 }
 
+#[utoipa::path(
+    get,
+    path = "/jobs",
+    tag = "jobs",
+    params(
+        ListJobsQuery,
+        ("If-None-Match" = Option<String>, Header, description = "`ETag` of an earlier response")
+    ),
+    responses(
+        (status = 200, description = "Matching jobs in job ID order, newest first with `search`", body = Vec<Job>,
+            headers(("X-Total-Count" = usize, description = "Number of matching jobs before `limit` and `offset`"))),
+        (status = 304, description = "No job changed since the response with the `ETag` in `If-None-Match`"),
+        (status = 400, description = "Invalid `name_glob`", body = ErrorBody)
+    )
+)]
 #[axum::debug_handler]
 pub(in crate::multicall::gflowd::server) async fn list_jobs(
     State(server_state): State<ServerState>,
     axum::extract::Query(params): axum::extract::Query<ListJobsQuery>,
-) -> impl IntoResponse {
-    let state = server_state.scheduler.read().await;
+    headers: HeaderMap,
+) -> Result<Response, ApiError> {
+    let name_globs = params
+        .name_glob
+        .as_deref()
+        .map(str::parse::<NameGlobs>)
+        .transpose()
+        .map_err(|error| ApiError::invalid_request(error.to_string()))?;
+    let state = server_state.jobs().await;
+    let etag = etag::for_revision(state.revision());
+    if let Some(response) = etag::not_modified(&headers, &etag) {
+        return Ok(response);
+    }
 
     // Parse filters once before iteration
     let state_filter: Option<Vec<JobState>> = params.state.as_ref().map(|states_str| {
@@ -57,6 +123,14 @@ pub(in crate::multicall::gflowd::server) async fn list_jobs(
         .as_ref()
         .map(|users_str| users_str.split(',').map(|s| s.trim().to_string()).collect());
 
+    let tag_filter: Option<Vec<&str>> = params.tag.as_deref().map(|tags_str| {
+        tags_str
+            .split(',')
+            .map(str::trim)
+            .filter(|tag| !tag.is_empty())
+            .collect()
+    });
+
     let time_filter = params.created_after.and_then(|secs| {
         use std::time::{Duration, UNIX_EPOCH};
         UNIX_EPOCH.checked_add(Duration::from_secs(secs.max(0) as u64))
@@ -65,16 +139,32 @@ pub(in crate::multicall::gflowd::server) async fn list_jobs(
     // Stream over split storage in the requested ID order and materialize only the requested page.
     let offset = params.offset.unwrap_or(0);
     let limit = params.limit.unwrap_or(usize::MAX);
-    let descending = params
-        .order
+    let search = params
+        .search
         .as_deref()
-        .is_some_and(|order| order.eq_ignore_ascii_case("desc"));
+        .filter(|query| !query.trim().is_empty());
+    let descending = match params.order.as_deref() {
+        Some(order) => order.eq_ignore_ascii_case("desc"),
+        None => search.is_some(),
+    };
 
     let mut matched = 0usize;
     let mut jobs = Vec::new();
 
     let users = user_filter.as_ref().filter(|u| !u.is_empty());
     let states = state_filter.as_ref().filter(|s| !s.is_empty());
+    let tags = tag_filter.as_ref().filter(|t| !t.is_empty());
+
+    // Every candidate matches when the index picked below is the only filter, so the total is
+    // the number of candidates and the scan can stop at the end of the page. Otherwise it keeps
+    // counting matches past the page.
+    let counts_matches = (users.is_some() as u8 + states.is_some() as u8 + tags.is_some() as u8)
+        > 1
+        || tags.is_some_and(|tags| tags.len() > 1)
+        || time_filter.is_some()
+        || search.is_some()
+        || name_globs.is_some();
+    let candidates;
 
     let matches_filters = |spec: &JobSpec, rt: &JobRuntime| -> bool {
         if let Some(ref states) = state_filter {
@@ -89,12 +179,32 @@ pub(in crate::multicall::gflowd::server) async fn list_jobs(
             }
         }
 
+        if let Some(tags) = tags {
+            if !tags.iter().all(|tag| spec.tags.contains(tag)) {
+                return false;
+            }
+        }
+
         if let Some(created_after) = time_filter {
             if spec.submitted_at.is_none_or(|ts| ts < created_after) {
                 return false;
             }
         }
 
+        if search.is_some_and(|query| !spec.matches_search(query)) {
+            return false;
+        }
+
+        if let Some(ref globs) = name_globs {
+            if !spec
+                .run_name
+                .as_ref()
+                .is_some_and(|name| globs.matches(name))
+            {
+                return false;
+            }
+        }
+
         true
     };
 
@@ -108,20 +218,23 @@ pub(in crate::multicall::gflowd::server) async fn list_jobs(
         }
         matched += 1;
 
-        jobs.len() >= limit
+        jobs.len() >= limit && !counts_matches
     };
 
-    // Choose the most selective index (user or state) when both filters are present.
+    // Choose the most selective index (user or state) when both filters are present; a tag
+    // filter, usually the narrowest, goes through the tag index.
     //
     // This keeps the hot path O(k) where k is the number of candidate jobs, instead of O(n)
     // scanning all jobs.
     enum CandidateSource {
+        Tag,
         User,
         State,
         ScanAll,
     }
 
     let source = match (users, states) {
+        _ if tags.is_some() => CandidateSource::Tag,
         (Some(users), Some(states)) => {
             let user_count: usize = if users.len() == 1 {
                 state
@@ -159,15 +272,53 @@ pub(in crate::multicall::gflowd::server) async fn list_jobs(
     };
 
     match source {
+        CandidateSource::Tag => {
+            // The jobs of the rarest tag; the filters check the others.
+            let job_ids = tags
+                .into_iter()
+                .flatten()
+                .map(|tag| state.job_ids_by_tag(tag).unwrap_or_default())
+                .min_by_key(|job_ids| job_ids.len())
+                .unwrap_or_default();
+            candidates = job_ids.len();
+
+            let mut visit_job_id = |job_id: u32| -> bool {
+                let idx = match job_id.checked_sub(1) {
+                    Some(v) => v as usize,
+                    None => return false,
+                };
+                let (Some(spec), Some(rt)) =
+                    (state.job_specs().get(idx), state.job_runtimes().get(idx))
+                else {
+                    return false;
+                };
+                collect_job(spec, rt)
+            };
+
+            if descending {
+                for &job_id in job_ids.iter().rev() {
+                    if visit_job_id(job_id) {
+                        break;
+                    }
+                }
+            } else {
+                for &job_id in job_ids {
+                    if visit_job_id(job_id) {
+                        break;
+                    }
+                }
+            }
+        }
         CandidateSource::User => {
             let Some(users) = users else {
-                return (StatusCode::OK, Json(jobs));
+                return Ok(job_page(jobs, 0, etag));
             };
 
             if users.len() == 1 {
                 let Some(job_ids) = state.job_ids_by_user(&users[0]) else {
-                    return (StatusCode::OK, Json(jobs));
+                    return Ok(job_page(jobs, 0, etag));
                 };
+                candidates = job_ids.len();
 
                 let mut visit_job_id = |job_id: u32| -> bool {
                     let idx = match job_id.checked_sub(1) {
@@ -206,6 +357,7 @@ pub(in crate::multicall::gflowd::server) async fn list_jobs(
 
                 job_ids.sort_unstable();
                 job_ids.dedup();
+                candidates = job_ids.len();
 
                 let mut visit_job_id = |job_id: u32| -> bool {
                     let idx = match job_id.checked_sub(1) {
@@ -237,13 +389,14 @@ pub(in crate::multicall::gflowd::server) async fn list_jobs(
         }
         CandidateSource::State => {
             let Some(states) = states else {
-                return (StatusCode::OK, Json(jobs));
+                return Ok(job_page(jobs, 0, etag));
             };
 
             if states.len() == 1 {
                 let Some(job_ids) = state.job_ids_by_state(states[0]) else {
-                    return (StatusCode::OK, Json(jobs));
+                    return Ok(job_page(jobs, 0, etag));
                 };
+                candidates = job_ids.len();
 
                 let mut visit_job_id = |job_id: u32| -> bool {
                     let idx = match job_id.checked_sub(1) {
@@ -276,12 +429,13 @@ pub(in crate::multicall::gflowd::server) async fn list_jobs(
                 let mut job_ids = Vec::new();
                 for state_name in states {
                     if let Some(state_ids) = state.job_ids_by_state(*state_name) {
-                        job_ids.extend_from_slice(state_ids);
+                        job_ids.extend(state_ids);
                     }
                 }
 
                 job_ids.sort_unstable();
                 job_ids.dedup();
+                candidates = job_ids.len();
 
                 let mut visit_job_id = |job_id: u32| -> bool {
                     let idx = match job_id.checked_sub(1) {
@@ -312,6 +466,7 @@ pub(in crate::multicall::gflowd::server) async fn list_jobs(
             }
         }
         CandidateSource::ScanAll => {
+            candidates = state.job_specs().len();
             if descending {
                 for (spec, rt) in state
                     .job_specs()
@@ -333,17 +488,64 @@ pub(in crate::multicall::gflowd::server) async fn list_jobs(
         }
     }
 
-    (StatusCode::OK, Json(jobs))
+    let total = if counts_matches { matched } else { candidates };
+    Ok(job_page(jobs, total, etag))
 }
 
+/// A page of `GET /jobs`, still a bare array for older clients, with the
+/// number of matching jobs in a header.
+fn job_page(jobs: Vec<Job>, total: usize, etag: HeaderValue) -> Response {
+    etag::tagged(
+        Some(etag),
+        (
+            StatusCode::OK,
+            [(TOTAL_COUNT_HEADER, total.to_string())],
+            Json(jobs),
+        ),
+    )
+}
+
+#[utoipa::path(
+    post,
+    path = "/jobs",
+    tag = "jobs",
+    request_body = Job,
+    params(("Idempotency-Key" = Option<String>, Header, description = "Repeats within 10 minutes return the original result")),
+    responses(
+        (status = 201, description = "Job queued", body = SubmittedJob),
+        (status = 200, description = "Job already created for this `submission_id`", body = SubmittedJob),
+        (status = 400, description = "Invalid job or dependency, no `submitted_by` user, more resources than any machine has (unless `force_queue`), or rejected by the `pre_submit` hook", body = ErrorBody),
+        (status = 403, description = "`as_user` without an admin token or `daemon.allow_as_user`", body = ErrorBody),
+        (status = 409, description = "`submission_id` already used for a different job", body = ErrorBody),
+        (status = 429, description = "Over the user's submission rate or queued-job limit; see `Retry-After`", body = ErrorBody),
+        (status = 503, description = "Daemon is read-only", body = ErrorBody),
+    )
+)]
 #[axum::debug_handler]
 pub(in crate::multicall::gflowd::server) async fn create_job(
     State(server_state): State<ServerState>,
-    Json(input): Json<Job>,
+    caller: Option<Extension<Caller>>,
+    headers: HeaderMap,
+    Json(mut input): Json<Job>,
 ) -> Response {
     if let Some(resp) = reject_if_read_only(&server_state).await {
         return resp;
     }
+    if let Err(error) = resolve_submitter(&server_state.auth, caller.as_deref(), &mut input) {
+        return error.into_response();
+    }
+    if !input.batch_depends_on.is_empty() {
+        return ApiError::invalid_request("batch_depends_on is only allowed in /jobs/batch")
+            .into_response();
+    }
+    if let Err(error) = server_state.rate_limiter.take(&input.submitted_by, 1) {
+        tracing::warn!(user = %input.submitted_by, %error, "Job submission rate limited");
+        return error.into_response();
+    }
+    // Digest the job as sent, before uploaded scripts are saved and rewritten.
+    let submission = input
+        .submission_id
+        .map(|submission_id| (submission_id, submission_digest(&input)));
     tracing::info!(
         user = %input.submitted_by,
         gpus = input.gpus,
@@ -352,10 +554,37 @@ pub(in crate::multicall::gflowd::server) async fn create_job(
         "Received job submission"
     );
 
+    let mut input = match run_pre_submit_hook(&server_state, vec![input]).await {
+        Ok(mut vetted) => vetted.remove(0),
+        Err(error) => return error.into_response(),
+    };
+
+    let idempotency_key = idempotency::key(&headers);
+
     // Validate dependency and submit job
-    let (job_id, run_name) = {
+    let (job_id, run_name, response) = {
         let mut state = server_state.scheduler.write().await;
 
+        if let Some(previous) = idempotency_key
+            .as_deref()
+            .and_then(|key| server_state.submissions.get(key))
+        {
+            tracing::info!(response = %previous, "Duplicate submission; returning original result");
+            return (StatusCode::CREATED, Json(previous)).into_response();
+        }
+
+        if let Some((submission_id, digest)) = &submission {
+            match prior_submission(&state, *submission_id, digest) {
+                Ok(Some((job_id, run_name))) => {
+                    tracing::info!(%submission_id, job_id, "Resent submission; returning original job");
+                    let response = SubmissionReport::new(&state).job(&state, job_id, &run_name);
+                    return (StatusCode::OK, Json(response)).into_response();
+                }
+                Ok(None) => {}
+                Err(error) => return error.into_response(),
+            }
+        }
+
         // Collect all dependencies (legacy + new)
         let mut all_deps = input.depends_on_ids.clone();
         if let Some(dep) = input.depends_on {
@@ -371,43 +600,58 @@ pub(in crate::multicall::gflowd::server) async fn create_job(
                     dep_id = dep_id,
                     "Job submission failed: dependency job does not exist"
                 );
-                return (
-                    StatusCode::BAD_REQUEST,
-                    Json(serde_json::json!({
-                        "error": format!("Dependency job {} does not exist", dep_id)
-                    })),
-                )
+                return ApiError::from(ApiErrorKind::DependencyMissing { id: *dep_id })
                     .into_response();
             }
         }
 
         // Check for circular dependencies
         let next_id = state.next_job_id();
-        if let Err(cycle_msg) = state.validate_no_circular_dependency(next_id, &all_deps) {
-            tracing::warn!("Circular dependency detected: {}", cycle_msg);
-            return (
-                StatusCode::BAD_REQUEST,
-                Json(serde_json::json!({
-                    "error": cycle_msg
-                })),
-            )
-                .into_response();
+        if let Err(error) = state.validate_no_circular_dependency(next_id, &all_deps) {
+            tracing::warn!("Circular dependency detected: {}", error);
+            return error.into_response();
+        }
+
+        if let Err(error) = state.validate_priority(input.priority, &input.submitted_by) {
+            tracing::warn!(%error, "Job submission failed: priority above the user's limit");
+            return error.into_response();
+        }
+
+        if let Err(error) = state.validate_capacity(&input) {
+            tracing::warn!(%error, "Job submission failed: more resources than the cluster has");
+            return error.into_response();
         }
 
+        let waiting = state.waiting_job_count(&input.submitted_by);
+        if let Err(error) = server_state
+            .rate_limiter
+            .check_queued(&input.submitted_by, waiting, 1)
+        {
+            tracing::warn!(user = %input.submitted_by, %error, "Job submission failed: too many queued jobs");
+            return error.into_response();
+        }
+
+        if let Some(resp) = save_uploaded_scripts(std::slice::from_mut(&mut input)) {
+            return resp;
+        }
         let (job_id, run_name, _job_clone) = match state.submit_job(input).await {
             Ok(result) => result,
             Err(error) => {
+                if let Some(error) = error.downcast_ref::<ApiError>() {
+                    return error.clone().into_response();
+                }
                 tracing::warn!(%error, "Job submission failed: project policy validation");
-                return (
-                    StatusCode::BAD_REQUEST,
-                    Json(serde_json::json!({
-                        "error": error.to_string()
-                    })),
-                )
-                    .into_response();
+                return ApiError::invalid_request(error.to_string()).into_response();
             }
         };
-        (job_id, run_name)
+        if let Some((submission_id, digest)) = submission {
+            state.record_submission(submission_id, job_id, digest);
+        }
+        let response = SubmissionReport::new(&state).job(&state, job_id, &run_name);
+        if let Some(key) = idempotency_key {
+            server_state.submissions.insert(key, response.clone());
+        }
+        (job_id, run_name, response)
     }; // Lock released here
 
     // Publish JobSubmitted event to trigger scheduling
@@ -428,45 +672,129 @@ pub(in crate::multicall::gflowd::server) async fn create_job(
 
     tracing::info!(job_id = job_id, run_name = %run_name, "Job created");
 
-    (
-        StatusCode::CREATED,
-        Json(serde_json::json!({ "id": job_id, "run_name": run_name })),
-    )
-        .into_response()
+    (StatusCode::CREATED, Json(response)).into_response()
 }
 
+#[utoipa::path(
+    post,
+    path = "/jobs/batch",
+    tag = "jobs",
+    request_body = Vec<Job>,
+    params(("Idempotency-Key" = Option<String>, Header, description = "Repeats within 10 minutes return the original result")),
+    responses(
+        (status = 201, description = "Jobs queued, in submission order", body = Vec<SubmittedJob>),
+        (status = 200, description = "Every job was already created for its `submission_id`", body = Vec<SubmittedJob>),
+        (status = 400, description = "Empty batch, a repeated `submission_id`, an invalid job or dependency, a job without a `submitted_by` user, or a job needing more resources than any machine has", body = ErrorBody),
+        (status = 403, description = "`as_user` without an admin token or `daemon.allow_as_user`", body = ErrorBody),
+        (status = 409, description = "A `submission_id` was already used for a different job", body = ErrorBody),
+        (status = 413, description = "Batch too large", body = ErrorBody),
+        (status = 429, description = "Over a user's submission rate or queued-job limit; see `Retry-After`", body = ErrorBody),
+        (status = 500, description = "The batch could not be saved; none of its jobs were created", body = ErrorBody),
+        (status = 503, description = "Daemon is read-only", body = ErrorBody),
+    )
+)]
 #[axum::debug_handler]
 pub(in crate::multicall::gflowd::server) async fn create_jobs_batch(
     State(server_state): State<ServerState>,
-    Json(input): Json<Vec<Job>>,
+    caller: Option<Extension<Caller>>,
+    headers: HeaderMap,
+    Json(mut input): Json<Vec<Job>>,
 ) -> Response {
     if let Some(resp) = reject_if_read_only(&server_state).await {
         return resp;
     }
+    for job in &mut input {
+        if let Err(error) = resolve_submitter(&server_state.auth, caller.as_deref(), job) {
+            return error.into_response();
+        }
+    }
     if input.is_empty() {
-        return (
-            StatusCode::BAD_REQUEST,
-            Json(serde_json::json!({"error": "Batch must contain at least one job"})),
-        )
-            .into_response();
+        return ApiError::invalid_request("Batch must contain at least one job").into_response();
     }
 
     if input.len() > 1000 {
-        return (
-            StatusCode::PAYLOAD_TOO_LARGE,
-            Json(serde_json::json!({"error": "Batch size exceeds maximum of 1000 jobs"})),
-        )
-            .into_response();
+        return ApiError::from(ApiErrorKind::BatchTooLarge { max: 1000 }).into_response();
+    }
+    let submissions: Vec<_> = input
+        .iter()
+        .map(|job| {
+            job.submission_id
+                .map(|submission_id| (submission_id, submission_digest(job)))
+        })
+        .collect();
+    let mut seen = std::collections::HashSet::new();
+    if let Some((submission_id, _)) = submissions
+        .iter()
+        .flatten()
+        .find(|(submission_id, _)| !seen.insert(*submission_id))
+    {
+        return ApiError::invalid_request(format!(
+            "Submission ID {submission_id} appears more than once in the batch"
+        ))
+        .into_response();
+    }
+
+    for (user, count) in rate_limit::count_by_user(&input) {
+        if let Err(error) = server_state.rate_limiter.take(user, count) {
+            tracing::warn!(user, count, %error, "Batch job submission rate limited");
+            return error.into_response();
+        }
     }
 
     tracing::info!(count = input.len(), "Received batch job submission");
 
+    let mut input = match run_pre_submit_hook(&server_state, input).await {
+        Ok(vetted) => vetted,
+        Err(error) => return error.into_response(),
+    };
+
+    let idempotency_key = idempotency::key(&headers);
+
     // Validate and submit jobs
-    let (results, _jobs_to_save, _next_job_id) = {
+    let ((results, _jobs_to_save, _next_job_id), response) = {
         let mut state = server_state.scheduler.write().await;
 
+        if let Some(previous) = idempotency_key
+            .as_deref()
+            .and_then(|key| server_state.submissions.get(key))
+        {
+            tracing::info!("Duplicate batch submission; returning original result");
+            return (StatusCode::CREATED, Json(previous)).into_response();
+        }
+
+        // Jobs already created by an earlier send of the same submission ID
+        // are answered from the original job; only the rest are submitted.
+        let mut previous = Vec::with_capacity(input.len());
+        for submission in &submissions {
+            previous.push(match submission {
+                Some((submission_id, digest)) => {
+                    match prior_submission(&state, *submission_id, digest) {
+                        Ok(previous) => previous,
+                        Err(error) => return error.into_response(),
+                    }
+                }
+                None => None,
+            });
+        }
+        let batch_ids = batch_job_ids(&previous, state.next_job_id());
+        if let Err(error) = resolve_batch_dependencies(&mut input, &batch_ids) {
+            return error.into_response();
+        }
+        let new_count = previous
+            .iter()
+            .filter(|previous| previous.is_none())
+            .count();
+        let new_ids = state.next_job_id()..state.next_job_id() + new_count as u32;
+        let (mut input, submissions): (Vec<_>, Vec<_>) = input
+            .into_iter()
+            .zip(submissions)
+            .zip(&previous)
+            .filter(|(_, previous)| previous.is_none())
+            .map(|(new, _)| new)
+            .unzip();
+
         // Validate all dependencies exist before submitting any (fail-fast)
-        for job in &input {
+        for (job, job_id) in input.iter().zip(new_ids.clone()) {
             // Collect all dependencies (legacy + new)
             let mut all_deps = job.depends_on_ids.clone();
             if let Some(dep) = job.depends_on {
@@ -475,50 +803,83 @@ pub(in crate::multicall::gflowd::server) async fn create_jobs_batch(
                 }
             }
 
-            // Validate all dependencies exist
+            // Validate all dependencies exist; earlier jobs of the batch are created first
             for dep_id in &all_deps {
-                if state.get_job(*dep_id).is_none() {
+                if !new_ids.contains(dep_id) && state.get_job(*dep_id).is_none() {
                     tracing::warn!(
                         dep_id = dep_id,
                         "Batch job submission failed: dependency job does not exist"
                     );
-                    return (
-                        StatusCode::BAD_REQUEST,
-                        Json(serde_json::json!({
-                            "error": format!("Dependency job {} does not exist", dep_id)
-                        })),
-                    )
+                    return ApiError::from(ApiErrorKind::DependencyMissing { id: *dep_id })
                         .into_response();
                 }
             }
 
             // Check for circular dependencies
-            let next_id = state.next_job_id();
-            if let Err(cycle_msg) = state.validate_no_circular_dependency(next_id, &all_deps) {
-                tracing::warn!("Circular dependency detected: {}", cycle_msg);
-                return (
-                    StatusCode::BAD_REQUEST,
-                    Json(serde_json::json!({
-                        "error": cycle_msg
-                    })),
-                )
-                    .into_response();
+            if let Err(error) = state.validate_no_circular_dependency(job_id, &all_deps) {
+                tracing::warn!("Circular dependency detected: {}", error);
+                return error.into_response();
+            }
+        }
+
+        for job in &input {
+            if let Err(error) = state.validate_priority(job.priority, &job.submitted_by) {
+                tracing::warn!(%error, "Batch job submission failed: priority above the user's limit");
+                return error.into_response();
+            }
+            if let Err(error) = state.validate_capacity(job) {
+                tracing::warn!(%error, "Batch job submission failed: more resources than the cluster has");
+                return error.into_response();
+            }
+        }
+
+        for (user, count) in rate_limit::count_by_user(&input) {
+            let waiting = state.waiting_job_count(user);
+            if let Err(error) = server_state.rate_limiter.check_queued(user, waiting, count) {
+                tracing::warn!(user, %error, "Batch job submission failed: too many queued jobs");
+                return error.into_response();
             }
         }
 
-        match state.submit_jobs(input).await {
+        if let Some(resp) = save_uploaded_scripts(&mut input) {
+            return resp;
+        }
+        let submitted = match state.submit_jobs(input).await {
             Ok(result) => result,
             Err(error) => {
+                if let Some(error) = error.downcast_ref::<ApiError>() {
+                    return error.clone().into_response();
+                }
                 tracing::warn!(%error, "Batch job submission failed: project policy validation");
-                return (
-                    StatusCode::BAD_REQUEST,
-                    Json(serde_json::json!({
-                        "error": error.to_string()
-                    })),
-                )
-                    .into_response();
+                return ApiError::invalid_request(error.to_string()).into_response();
+            }
+        };
+        for ((job_id, _, _), submission) in submitted.0.iter().zip(submissions) {
+            if let Some((submission_id, digest)) = submission {
+                state.record_submission(submission_id, *job_id, digest);
             }
         }
+        let mut created = submitted
+            .0
+            .iter()
+            .map(|(job_id, run_name, _)| (*job_id, run_name.clone()));
+        let entries: Vec<_> = previous
+            .into_iter()
+            .map(|previous| previous.or_else(|| created.next()))
+            .collect::<Option<_>>()
+            .expect("one submitted job per new entry");
+        let report = SubmissionReport::new(&state);
+        let response: serde_json::Value = entries
+            .iter()
+            .map(|(job_id, run_name)| report.job(&state, *job_id, run_name))
+            .collect();
+        if let Some(key) = idempotency_key {
+            server_state.submissions.insert(key, response.clone());
+        }
+        // The jobs were saved on submission; write the submission IDs
+        // recorded for them too before replying.
+        state.save_state_if_dirty().await;
+        (submitted, response)
     }; // Lock released here
 
     // Publish JobSubmitted events for all submitted jobs
@@ -538,40 +899,246 @@ pub(in crate::multicall::gflowd::server) async fn create_jobs_batch(
 
     tracing::info!(count = results.len(), "Batch jobs created");
 
-    let response: Vec<_> = results
-        .into_iter()
-        .map(|(job_id, run_name, _)| {
-            serde_json::json!({
-                "id": job_id,
-                "run_name": run_name
+    let status = if results.is_empty() {
+        StatusCode::OK
+    } else {
+        StatusCode::CREATED
+    };
+    (status, Json(response)).into_response()
+}
+
+/// The ID each job of a batch gets: the job an earlier send already created,
+/// or the next free ID, in batch order.
+/// Pass each job through the `pre_submit` hook, outside the scheduler lock.
+/// The hook may rewrite a job but not change who submitted it.
+async fn run_pre_submit_hook(
+    server_state: &ServerState,
+    jobs: Vec<Job>,
+) -> Result<Vec<Job>, ApiError> {
+    let hooks = server_state.scheduler.read().await.hooks();
+    let batch = jobs.len() > 1;
+    let mut vetted = Vec::with_capacity(jobs.len());
+    for (index, job) in jobs.into_iter().enumerate() {
+        let submitted_by = job.submitted_by.clone();
+        let mut job = hooks.pre_submit(job).await.map_err(|mut error| {
+            if batch {
+                error.message = format!("Job {} of the batch: {}", index + 1, error.message);
+            }
+            error
+        })?;
+        job.submitted_by = submitted_by;
+        vetted.push(job);
+    }
+    Ok(vetted)
+}
+
+fn batch_job_ids(previous: &[Option<(u32, String)>], next_job_id: u32) -> Vec<u32> {
+    let mut next_job_id = next_job_id;
+    previous
+        .iter()
+        .map(|previous| match previous {
+            Some((job_id, _)) => *job_id,
+            None => {
+                next_job_id += 1;
+                next_job_id - 1
+            }
+        })
+        .collect()
+}
+
+/// Turn each job's `batch_depends_on` positions into dependencies on the
+/// IDs in `batch_ids`. A job may only depend on jobs before it, so a batch
+/// cannot contain a cycle.
+fn resolve_batch_dependencies(jobs: &mut [Job], batch_ids: &[u32]) -> Result<(), ApiError> {
+    for (index, job) in jobs.iter_mut().enumerate() {
+        for position in std::mem::take(&mut job.batch_depends_on) {
+            if position >= index {
+                return Err(ApiError::invalid_request(format!(
+                    "Job {} of the batch depends on job {}, which does not come before it",
+                    index, position
+                )));
+            }
+            let dep_id = batch_ids[position];
+            if !job.depends_on_ids.contains(&dep_id) {
+                job.depends_on_ids.push(dep_id);
+            }
+        }
+        if job.depends_on_ids.len() == 1 && job.depends_on.is_none() {
+            job.depends_on = job.depends_on_ids.first().copied();
+        }
+        if !job.depends_on_ids.is_empty() && job.dependency_mode.is_none() {
+            job.dependency_mode = Some(DependencyMode::All);
+        }
+    }
+    Ok(())
+}
+
+/// Save scripts submitted as content (`gbatch` to a remote without a shared
+/// filesystem) in the scripts directory and point their jobs at the copies.
+/// Returns the error response when a job is malformed or a script cannot be saved.
+fn save_uploaded_scripts(jobs: &mut [Job]) -> Option<Response> {
+    for job in jobs {
+        let Some(content) = job.script_content.take() else {
+            continue;
+        };
+        if job.script.is_some() || job.command.is_some() {
+            return Some(
+                ApiError::invalid_request(
+                    "A job has either script content, a script path, or a command",
+                )
+                .into_response(),
+            );
+        }
+        let path = match gflow::paths::save_script("upload", &content) {
+            Ok(path) => path,
+            Err(error) => {
+                tracing::error!(error = %format!("{error:#}"), "Failed to save uploaded script");
+                return Some(ApiError::internal(format!("{error:#}")).into_response());
+            }
+        };
+        job.script = Some(Box::new(path));
+        // The submitter's directory is usually on their own machine.
+        if !job.run_dir.is_dir() {
+            if let Some(home) = dirs::home_dir() {
+                job.run_dir = home;
+            }
+        }
+    }
+    None
+}
+
+/// The job an earlier send of `submission_id` created, as `(id, run_name)`,
+/// or the conflict when that send carried a different job.
+fn prior_submission(
+    state: &SchedulerRuntime,
+    submission_id: uuid::Uuid,
+    digest: &str,
+) -> Result<Option<(u32, String)>, ApiError> {
+    match state.prior_submission(submission_id, digest) {
+        PriorSubmission::None => Ok(None),
+        PriorSubmission::Same(job_id) => Ok(state
+            .get_job(job_id)
+            .map(|job| (job_id, job.run_name.unwrap_or_default().to_string()))),
+        PriorSubmission::Different(job_id) => {
+            tracing::warn!(%submission_id, job_id, "Submission ID reused with a different job");
+            Err(ApiErrorKind::SubmissionConflict {
+                submission_id,
+                job_id,
+            }
+            .into())
+        }
+    }
+}
+
+/// Why job `id` cannot move to `to`: it does not exist, or its state forbids it.
+/// A job already in `to` is accepted, so repeating a request is harmless.
+fn transition_error(state: &SchedulerRuntime, id: u32, to: JobState) -> Option<ApiError> {
+    state.transition_error(id, to)
+}
+
+/// What a submitter is told about each job it created, taken under the same
+/// lock as the submission so queue positions match the queue it joined.
+struct SubmissionReport {
+    estimates: BTreeMap<u32, StartEstimate>,
+    queue_positions: BTreeMap<u32, usize>,
+    available_gpus: Vec<u32>,
+}
+
+impl SubmissionReport {
+    fn new(state: &SchedulerRuntime) -> Self {
+        Self {
+            estimates: state.estimate_start_times(),
+            queue_positions: state.queue_positions(),
+            available_gpus: state.available_gpu_slots(),
+        }
+    }
+
+    fn job(&self, state: &SchedulerRuntime, job_id: u32, run_name: &str) -> serde_json::Value {
+        let job = state.get_job(job_id);
+        let dependencies: Vec<_> = job
+            .iter()
+            .flat_map(|job| job.dependency_ids_iter())
+            .map(|id| {
+                serde_json::json!({
+                    "id": id,
+                    "state": state.get_job(id).map(|dependency| dependency.state)
+                })
             })
+            .collect();
+        serde_json::json!({
+            "id": job_id,
+            "run_name": run_name,
+            "estimate": self.estimates.get(&job_id),
+            "group_id": job.as_ref().and_then(|job| job.group_id),
+            "priority": job.as_ref().map(|job| job.priority),
+            "dependencies": dependencies,
+            "queue_position": self.queue_positions.get(&job_id),
+            "available_gpus": self.available_gpus,
+            "run_name_shared_with": state.run_name_shared_with(job_id),
         })
-        .collect();
+    }
+}
 
-    (
-        StatusCode::CREATED,
-        Json(serde_json::Value::Array(response)),
-    )
-        .into_response()
+/// Start estimates for every queued job, keyed by job ID.
+#[utoipa::path(
+    get,
+    path = "/jobs/estimates",
+    tag = "jobs",
+    responses((status = 200, description = "Start estimate of every queued job, keyed by job ID", body = BTreeMap<String, StartEstimate>))
+)]
+#[axum::debug_handler]
+pub(in crate::multicall::gflowd::server) async fn get_start_estimates(
+    State(server_state): State<ServerState>,
+) -> Json<BTreeMap<u32, StartEstimate>> {
+    let state = server_state.scheduler.read().await;
+    Json(state.estimate_start_times())
 }
 
+#[utoipa::path(
+    get,
+    path = "/jobs/{id}",
+    tag = "jobs",
+    params(("id" = u32, Path, description = "Job ID")),
+    responses(
+        (status = 200, description = "The job", body = Job),
+        (status = 404, description = "No such job"),
+    )
+)]
 #[axum::debug_handler]
 pub(in crate::multicall::gflowd::server) async fn get_job(
     State(server_state): State<ServerState>,
     Path(id): Path<u32>,
 ) -> Result<Json<Job>, StatusCode> {
-    let state = server_state.scheduler.read().await;
+    let state = server_state.jobs().await;
     state.get_job(id).map(Json).ok_or(StatusCode::NOT_FOUND)
 }
 
+#[utoipa::path(
+    post,
+    path = "/jobs/{id}/finish",
+    tag = "jobs",
+    summary = "Mark a running job finished",
+    params(("id" = u32, Path, description = "Job ID")),
+    responses(
+        (status = 200, description = "State changed"),
+        (status = 403, description = "Job belongs to another user", body = ErrorBody),
+        (status = 404, description = "No such job", body = ErrorBody),
+        (status = 409, description = "The job's state does not allow this", body = ErrorBody),
+        (status = 503, description = "Daemon is read-only", body = ErrorBody),
+    )
+)]
 #[axum::debug_handler]
 pub(in crate::multicall::gflowd::server) async fn finish_job(
     State(server_state): State<ServerState>,
+    caller: Option<Extension<Caller>>,
     Path(id): Path<u32>,
 ) -> Response {
     if let Some(resp) = reject_if_read_only(&server_state).await {
         return resp;
     }
+    if let Some(resp) = reject_if_not_owner(&server_state, caller.as_deref(), id).await {
+        return resp;
+    }
     tracing::info!(job_id = id, "Finishing job");
 
     // Get job info before finishing (for metrics and events)
@@ -581,46 +1148,37 @@ pub(in crate::multicall::gflowd::server) async fn finish_job(
         state.get_job(id).map(|j| j.submitted_by.clone())
     };
 
-    let (success, gpu_ids, memory_mb) = {
+    let (gpu_ids, memory_mb) = {
         let mut state = server_state.scheduler.write().await;
         let job_info = state
             .get_job(id)
             .map(|j| (j.gpu_ids.clone(), j.memory_limit_mb));
-        let success = state.finish_job(id).await;
-        if let Some((gpu_ids, memory_mb)) = job_info {
-            (success, gpu_ids, memory_mb)
-        } else {
-            (success, None, None)
+        if let Some(error) = transition_error(&state, id, JobState::Finished) {
+            return error.into_response();
         }
+        state.finish_job(id).await;
+        job_info.unwrap_or_default()
     }; // Lock released here
 
-    if success {
-        // Publish JobCompleted event to trigger scheduling and cascade
-        server_state
-            .event_bus
-            .publish(SchedulerEvent::JobCompleted {
-                job_id: id,
-                final_state: JobState::Finished,
-                gpu_ids,
-                memory_mb,
-            });
-    }
+    // Publish JobCompleted event to trigger scheduling and cascade
+    server_state
+        .event_bus
+        .publish(SchedulerEvent::JobCompleted {
+            job_id: id,
+            final_state: JobState::Finished,
+            gpu_ids,
+            memory_mb,
+        });
 
     // Record metrics only on successful transition
     #[cfg(feature = "metrics")]
-    if success {
-        if let Some(submitted_by) = user {
-            gflow::metrics::JOB_FINISHED
-                .with_label_values(&[&submitted_by])
-                .inc();
-        }
+    if let Some(submitted_by) = user {
+        gflow::metrics::JOB_FINISHED
+            .with_label_values(&[&submitted_by])
+            .inc();
     }
 
-    if success {
-        (StatusCode::OK, Json(())).into_response()
-    } else {
-        (StatusCode::NOT_FOUND, Json(())).into_response()
-    }
+    (StatusCode::OK, Json(())).into_response()
 }
 
 #[axum::debug_handler]
@@ -653,14 +1211,32 @@ pub(in crate::multicall::gflowd::server) async fn get_job_log(
     }
 }
 
+#[utoipa::path(
+    post,
+    path = "/jobs/{id}/fail",
+    tag = "jobs",
+    summary = "Mark a running job failed",
+    params(("id" = u32, Path, description = "Job ID")),
+    responses(
+        (status = 200, description = "State changed"),
+        (status = 403, description = "Job belongs to another user", body = ErrorBody),
+        (status = 404, description = "No such job", body = ErrorBody),
+        (status = 409, description = "The job's state does not allow this", body = ErrorBody),
+        (status = 503, description = "Daemon is read-only", body = ErrorBody),
+    )
+)]
 #[axum::debug_handler]
 pub(in crate::multicall::gflowd::server) async fn fail_job(
     State(server_state): State<ServerState>,
+    caller: Option<Extension<Caller>>,
     Path(id): Path<u32>,
 ) -> Response {
     if let Some(resp) = reject_if_read_only(&server_state).await {
         return resp;
     }
+    if let Some(resp) = reject_if_not_owner(&server_state, caller.as_deref(), id).await {
+        return resp;
+    }
     tracing::info!(job_id = id, "Failing job");
 
     // Get user and job info before failing (for metrics and events)
@@ -679,43 +1255,60 @@ pub(in crate::multicall::gflowd::server) async fn fail_job(
         }
     };
 
-    let result = {
+    {
         let mut state = server_state.scheduler.write().await;
-        state.explicit_fail_job(id).await
-    }; // Lock released here
-
-    if result {
-        // Publish JobCompleted event to trigger cascade cancellation
-        server_state
-            .event_bus
-            .publish(SchedulerEvent::JobCompleted {
-                job_id: id,
-                final_state: JobState::Failed,
-                gpu_ids,
-                memory_mb,
-            });
-
-        #[cfg(feature = "metrics")]
-        if let Some(submitted_by) = user {
-            gflow::metrics::JOB_FAILED
-                .with_label_values(&[&submitted_by])
-                .inc();
+        if let Some(error) = transition_error(&state, id, JobState::Failed) {
+            return error.into_response();
         }
+        state.explicit_fail_job(id).await;
+    } // Lock released here
 
-        (StatusCode::OK, Json(())).into_response()
-    } else {
-        (StatusCode::NOT_FOUND, Json(())).into_response()
+    // Publish JobCompleted event to trigger cascade cancellation
+    server_state
+        .event_bus
+        .publish(SchedulerEvent::JobCompleted {
+            job_id: id,
+            final_state: JobState::Failed,
+            gpu_ids,
+            memory_mb,
+        });
+
+    #[cfg(feature = "metrics")]
+    if let Some(submitted_by) = user {
+        gflow::metrics::JOB_FAILED
+            .with_label_values(&[&submitted_by])
+            .inc();
     }
+
+    (StatusCode::OK, Json(())).into_response()
 }
 
+#[utoipa::path(
+    post,
+    path = "/jobs/{id}/cancel",
+    tag = "jobs",
+    summary = "Cancel a queued, held or running job",
+    params(("id" = u32, Path, description = "Job ID")),
+    responses(
+        (status = 200, description = "State changed"),
+        (status = 403, description = "Job belongs to another user", body = ErrorBody),
+        (status = 404, description = "No such job", body = ErrorBody),
+        (status = 409, description = "The job's state does not allow this", body = ErrorBody),
+        (status = 503, description = "Daemon is read-only", body = ErrorBody),
+    )
+)]
 #[axum::debug_handler]
 pub(in crate::multicall::gflowd::server) async fn cancel_job(
     State(server_state): State<ServerState>,
+    caller: Option<Extension<Caller>>,
     Path(id): Path<u32>,
 ) -> Response {
     if let Some(resp) = reject_if_read_only(&server_state).await {
         return resp;
     }
+    if let Some(resp) = reject_if_not_owner(&server_state, caller.as_deref(), id).await {
+        return resp;
+    }
     tracing::info!(job_id = id, "Cancelling job");
 
     // Get user and job info before cancelling (for metrics and events)
@@ -734,100 +1327,218 @@ pub(in crate::multicall::gflowd::server) async fn cancel_job(
         }
     };
 
-    let result = {
+    {
         let mut state = server_state.scheduler.write().await;
-        state.cancel_job(id).await
-    }; // Lock released here
+        if let Some(error) = transition_error(&state, id, JobState::Cancelled) {
+            return error.into_response();
+        }
+        state.cancel_job(id).await;
+    } // Lock released here
 
-    if result {
-        // Publish JobCompleted event to trigger cascade cancellation
-        server_state
-            .event_bus
-            .publish(SchedulerEvent::JobCompleted {
-                job_id: id,
-                final_state: JobState::Cancelled,
-                gpu_ids,
-                memory_mb,
-            });
-    }
+    // Publish JobCompleted event to trigger cascade cancellation
+    server_state
+        .event_bus
+        .publish(SchedulerEvent::JobCompleted {
+            job_id: id,
+            final_state: JobState::Cancelled,
+            gpu_ids,
+            memory_mb,
+        });
 
     // Record metrics only on successful transition
     #[cfg(feature = "metrics")]
-    if result {
-        if let Some(submitted_by) = user {
-            gflow::metrics::JOB_CANCELLED
-                .with_label_values(&[&submitted_by])
-                .inc();
-        }
+    if let Some(submitted_by) = user {
+        gflow::metrics::JOB_CANCELLED
+            .with_label_values(&[&submitted_by])
+            .inc();
     }
 
-    if result {
-        (StatusCode::OK, Json(())).into_response()
-    } else {
-        (StatusCode::NOT_FOUND, Json(())).into_response()
-    }
+    (StatusCode::OK, Json(())).into_response()
 }
 
+#[utoipa::path(
+    post,
+    path = "/jobs/{id}/hold",
+    tag = "jobs",
+    summary = "Hold a queued job",
+    params(("id" = u32, Path, description = "Job ID")),
+    responses(
+        (status = 200, description = "State changed"),
+        (status = 403, description = "Job belongs to another user", body = ErrorBody),
+        (status = 404, description = "No such job", body = ErrorBody),
+        (status = 409, description = "The job's state does not allow this", body = ErrorBody),
+        (status = 503, description = "Daemon is read-only", body = ErrorBody),
+    )
+)]
 #[axum::debug_handler]
 pub(in crate::multicall::gflowd::server) async fn hold_job(
     State(server_state): State<ServerState>,
+    caller: Option<Extension<Caller>>,
     Path(id): Path<u32>,
 ) -> Response {
     if let Some(resp) = reject_if_read_only(&server_state).await {
         return resp;
     }
+    if let Some(resp) = reject_if_not_owner(&server_state, caller.as_deref(), id).await {
+        return resp;
+    }
     tracing::info!(job_id = id, "Holding job");
 
-    let success = {
+    {
         let mut state = server_state.scheduler.write().await;
-        state.hold_job(id).await
-    }; // Lock released here
+        if let Some(error) = transition_error(&state, id, JobState::Hold) {
+            return error.into_response();
+        }
+        state.hold_job(id).await;
+    } // Lock released here
 
-    if success {
-        (StatusCode::OK, Json(())).into_response()
-    } else {
-        (StatusCode::NOT_FOUND, Json(())).into_response()
-    }
+    (StatusCode::OK, Json(())).into_response()
 }
 
+#[utoipa::path(
+    post,
+    path = "/jobs/{id}/release",
+    tag = "jobs",
+    summary = "Release a held job",
+    params(("id" = u32, Path, description = "Job ID")),
+    responses(
+        (status = 200, description = "State changed"),
+        (status = 403, description = "Job belongs to another user", body = ErrorBody),
+        (status = 404, description = "No such job", body = ErrorBody),
+        (status = 409, description = "The job's state does not allow this", body = ErrorBody),
+        (status = 503, description = "Daemon is read-only", body = ErrorBody),
+    )
+)]
 #[axum::debug_handler]
 pub(in crate::multicall::gflowd::server) async fn release_job(
     State(server_state): State<ServerState>,
+    caller: Option<Extension<Caller>>,
     Path(id): Path<u32>,
 ) -> Response {
     if let Some(resp) = reject_if_read_only(&server_state).await {
         return resp;
     }
+    if let Some(resp) = reject_if_not_owner(&server_state, caller.as_deref(), id).await {
+        return resp;
+    }
     tracing::info!(job_id = id, "Releasing job");
 
-    let success = {
+    {
         let mut state = server_state.scheduler.write().await;
-        state.release_job(id).await
-    }; // Lock released here
+        if let Some(error) = transition_error(&state, id, JobState::Queued) {
+            return error.into_response();
+        }
+        state.release_job(id).await;
+    } // Lock released here
 
-    if success {
-        // Publish JobSubmitted event since released job may be ready to run
-        server_state
-            .event_bus
-            .publish(SchedulerEvent::JobSubmitted { job_id: id });
+    // Publish JobSubmitted event since released job may be ready to run
+    server_state
+        .event_bus
+        .publish(SchedulerEvent::JobSubmitted { job_id: id });
+
+    (StatusCode::OK, Json(())).into_response()
+}
+
+#[utoipa::path(
+    post,
+    path = "/jobs/actions",
+    tag = "jobs",
+    summary = "Cancel, hold, release, finish or fail many jobs at once",
+    description = "Checks and changes every job under one scheduler lock and saves the \
+        state once. Jobs that cannot be changed are reported in the results \
+        instead of failing the request.",
+    request_body = openapi::JobActionRequest,
+    responses(
+        (status = 200, description = "What became of each job, in request order", body = [openapi::JobActionResult]),
+        (status = 413, description = "More job IDs than one request may name", body = ErrorBody),
+        (status = 503, description = "Daemon is read-only", body = ErrorBody),
+    )
+)]
+#[axum::debug_handler]
+pub(in crate::multicall::gflowd::server) async fn apply_job_action(
+    State(server_state): State<ServerState>,
+    caller: Option<Extension<Caller>>,
+    Json(request): Json<JobActionRequest>,
+) -> Response {
+    if let Some(resp) = reject_if_read_only(&server_state).await {
+        return resp;
+    }
+    if request.ids.len() > MAX_JOB_ACTION_IDS {
+        return ApiError::from(ApiErrorKind::BatchTooLarge {
+            max: MAX_JOB_ACTION_IDS,
+        })
+        .into_response();
     }
+    let action = request.op;
+    let target = action.target_state();
+    tracing::info!(?action, jobs = request.ids.len(), "Applying job action");
 
-    if success {
-        (StatusCode::OK, Json(())).into_response()
-    } else {
-        (StatusCode::NOT_FOUND, Json(())).into_response()
+    let (results, completed) = {
+        let mut state = server_state.scheduler.write().await;
+        let mut results = Vec::with_capacity(request.ids.len());
+        let mut accepted = Vec::new();
+        // Taken before the change, for the JobCompleted events and metrics
+        let mut before = HashMap::new();
+        for &id in &request.ids {
+            let job = state.get_job(id);
+            let error = match &job {
+                None => Some(ApiErrorKind::JobNotFound { id }.into()),
+                Some(job) => ownership_error(caller.as_deref(), id, &job.submitted_by)
+                    .or_else(|| transition_error(&state, id, target)),
+            };
+            if let (None, Some(job)) = (&error, job) {
+                if before.insert(id, job).is_none() {
+                    accepted.push(id);
+                }
+            }
+            results.push(JobActionResult { id, error });
+        }
+        let changed = state.apply_job_action(action, &accepted).await;
+        let completed: Vec<Job> = changed.iter().filter_map(|id| before.remove(id)).collect();
+        (results, completed)
+    }; // Lock released here
+
+    let events = completed.iter().map(|job| match action {
+        JobAction::Release => Some(SchedulerEvent::JobSubmitted { job_id: job.id }),
+        JobAction::Hold => None,
+        JobAction::Cancel | JobAction::Finish | JobAction::Fail => {
+            Some(SchedulerEvent::JobCompleted {
+                job_id: job.id,
+                final_state: target,
+                gpu_ids: job.gpu_ids.clone(),
+                memory_mb: job.memory_limit_mb,
+            })
+        }
+    });
+    server_state.event_bus.publish_all(events.flatten());
+
+    #[cfg(feature = "metrics")]
+    for job in &completed {
+        let counter = match action {
+            JobAction::Cancel => &*gflow::metrics::JOB_CANCELLED,
+            JobAction::Finish => &*gflow::metrics::JOB_FINISHED,
+            JobAction::Fail => &*gflow::metrics::JOB_FAILED,
+            JobAction::Hold | JobAction::Release => continue,
+        };
+        counter.with_label_values(&[&job.submitted_by]).inc();
     }
+
+    (StatusCode::OK, Json(results)).into_response()
 }
 
 #[axum::debug_handler]
 pub(in crate::multicall::gflowd::server) async fn update_job(
     State(server_state): State<ServerState>,
+    caller: Option<Extension<Caller>>,
     Path(id): Path<u32>,
     Json(request): Json<UpdateJobRequest>,
 ) -> Response {
     if let Some(resp) = reject_if_read_only(&server_state).await {
         return resp;
     }
+    if let Some(resp) = reject_if_not_owner(&server_state, caller.as_deref(), id).await {
+        return resp;
+    }
     tracing::info!(job_id = id, "Updating job parameters");
 
     let result = {
@@ -859,13 +1570,7 @@ pub(in crate::multicall::gflowd::server) async fn update_job(
         }
         Err(error) => {
             tracing::error!(job_id = id, error = %error, "Failed to update job");
-            (
-                StatusCode::BAD_REQUEST,
-                Json(serde_json::json!({
-                    "error": error,
-                })),
-            )
-                .into_response()
+            error.into_response()
         }
     }
 }
@@ -874,7 +1579,7 @@ pub(in crate::multicall::gflowd::server) async fn update_job(
 pub(in crate::multicall::gflowd::server) async fn resolve_dependency(
     State(server_state): State<ServerState>,
     axum::extract::Query(params): axum::extract::Query<ResolveDependencyQuery>,
-) -> impl IntoResponse {
+) -> Response {
     let state = server_state.scheduler.read().await;
 
     if let Some(resolved_id) = state.resolve_dependency(&params.username, &params.shorthand) {
@@ -882,13 +1587,13 @@ pub(in crate::multicall::gflowd::server) async fn resolve_dependency(
             StatusCode::OK,
             Json(serde_json::json!({ "job_id": resolved_id })),
         )
+            .into_response()
     } else {
-        (
-            StatusCode::BAD_REQUEST,
-            Json(serde_json::json!({
-                "error": format!("Cannot resolve dependency '{}' for user '{}'", params.shorthand, params.username)
-            })),
-        )
+        ApiError::invalid_request(format!(
+            "Cannot resolve dependency '{}' for user '{}'",
+            params.shorthand, params.username
+        ))
+        .into_response()
     }
 }
 
@@ -898,55 +1603,8 @@ pub(in crate::multicall::gflowd::server) struct ResolveDependencyQuery {
     shorthand: String,
 }
 
-#[axum::debug_handler]
-pub(in crate::multicall::gflowd::server) async fn get_health(
-    State(server_state): State<ServerState>,
-) -> impl IntoResponse {
-    let pid = std::process::id();
-
-    let state = server_state.scheduler.read().await;
-    let state_writable = state.state_writable();
-    let journal_writable = state.journal_writable();
-    let mode = state.persistence_mode();
-    if state_writable {
-        return (
-            StatusCode::OK,
-            Json(serde_json::json!({ "status": "ok", "pid": pid })),
-        );
-    }
-
-    let backup_path = state.state_backup_path().map(|p| p.display().to_string());
-    let journal_path = state.journal_path().display().to_string();
-
-    if journal_writable {
-        return (
-            StatusCode::OK,
-            Json(serde_json::json!({
-                "status": "recovery",
-                "mode": mode,
-                "pid": pid,
-                "detail": state.state_load_error(),
-                "state_backup": backup_path,
-                "journal": journal_path,
-                "journal_error": state.journal_error(),
-            })),
-        );
-    }
-
-    (
-        StatusCode::SERVICE_UNAVAILABLE,
-        Json(serde_json::json!({
-            "status": "read_only",
-            "pid": pid,
-            "detail": state.state_load_error(),
-            "state_backup": backup_path,
-            "journal": journal_path,
-            "journal_error": state.journal_error(),
-        })),
-    )
-}
-
-#[derive(serde::Deserialize)]
+/// GPUs the scheduler may use; `null` allows all of them.
+#[derive(serde::Deserialize, utoipa::ToSchema)]
 pub(in crate::multicall::gflowd::server) struct SetGpusRequest {
     allowed_indices: Option<Vec<u32>>,
 }
@@ -957,6 +1615,18 @@ pub(in crate::multicall::gflowd::server) struct GpuProcessOverrideRequest {
     pid: u32,
 }
 
+#[utoipa::path(
+    post,
+    path = "/gpus",
+    tag = "gpus",
+    request_body = SetGpusRequest,
+    responses(
+        (status = 200, description = "GPUs the scheduler may now use", body = SetGpusRequest),
+        (status = 400, description = "Unknown GPU index", body = ErrorBody),
+        (status = 403, description = "Not an admin", body = ErrorBody),
+        (status = 503, description = "Daemon is read-only", body = ErrorBody),
+    )
+)]
 #[axum::debug_handler]
 pub(in crate::multicall::gflowd::server) async fn set_allowed_gpus(
     State(server_state): State<ServerState>,
@@ -977,16 +1647,11 @@ pub(in crate::multicall::gflowd::server) async fn set_allowed_gpus(
             .collect();
 
         if !invalid.is_empty() {
-            return (
-                StatusCode::BAD_REQUEST,
-                Json(serde_json::json!({
-                    "error": format!(
-                        "Invalid GPU indices {:?} (only {} GPUs detected)",
-                        invalid, detected_count
-                    )
-                })),
-            )
-                .into_response();
+            return ApiError::invalid_request(format!(
+                "Invalid GPU indices {:?} (only {} GPUs detected)",
+                invalid, detected_count
+            ))
+            .into_response();
         }
     }
 
@@ -1047,13 +1712,7 @@ pub(in crate::multicall::gflowd::server) async fn ignore_gpu_process(
             )
                 .into_response()
         }
-        Err(error) => (
-            StatusCode::BAD_REQUEST,
-            Json(serde_json::json!({
-                "error": error.to_string()
-            })),
-        )
-            .into_response(),
+        Err(error) => ApiError::invalid_request(error.to_string()).into_response(),
     }
 }
 
@@ -1073,16 +1732,11 @@ pub(in crate::multicall::gflowd::server) async fn unignore_gpu_process(
     drop(state);
 
     if !removed {
-        return (
-            StatusCode::NOT_FOUND,
-            Json(serde_json::json!({
-                "error": format!(
-                    "Ignore override for PID {} on GPU {} was not found",
-                    request.pid, request.gpu_index
-                )
-            })),
-        )
-            .into_response();
+        return ApiError::not_found(format!(
+            "Ignore override for PID {} on GPU {} was not found",
+            request.pid, request.gpu_index
+        ))
+        .into_response();
     }
 
     tracing::info!(
@@ -1156,12 +1810,7 @@ pub(in crate::multicall::gflowd::server) async fn set_group_max_concurrency(
     let group_uuid = match uuid::Uuid::parse_str(&group_id) {
         Ok(uuid) => uuid,
         Err(_) => {
-            return (
-                StatusCode::BAD_REQUEST,
-                Json(serde_json::json!({
-                    "error": format!("Invalid UUID format: '{}'", group_id)
-                })),
-            )
+            return ApiError::invalid_request(format!("Invalid UUID format: '{}'", group_id))
                 .into_response();
         }
     };
@@ -1178,12 +1827,7 @@ pub(in crate::multicall::gflowd::server) async fn set_group_max_concurrency(
             .collect();
 
         if job_ids.is_empty() {
-            return (
-                StatusCode::NOT_FOUND,
-                Json(serde_json::json!({
-                    "error": format!("No jobs found with group_id '{}'", group_id)
-                })),
-            )
+            return ApiError::not_found(format!("No jobs found with group_id '{}'", group_id))
                 .into_response();
         }
 
@@ -1214,3 +1858,82 @@ pub(in crate::multicall::gflowd::server) async fn set_group_max_concurrency(
     )
         .into_response()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::super::super::test_app::{app, code, get, post};
+    use axum::http::StatusCode;
+    use gflow::core::job::Job;
+
+    fn gpu_job(gpus: u32, force_queue: bool) -> serde_json::Value {
+        let mut job = Job::builder()
+            .command("python train.py")
+            .submitted_by("alice")
+            .gpus(gpus)
+            .build();
+        job.force_queue = force_queue;
+        serde_json::to_value(job).unwrap()
+    }
+
+    #[tokio::test]
+    async fn jobs_no_machine_can_run_are_rejected_unless_forced() {
+        let dir = tempfile::tempdir().unwrap();
+        // No GPU is allowed, whatever this host has.
+        let app = app(dir.path(), Default::default(), Some(vec![]));
+
+        let response = post(&app, "/jobs", gpu_job(1, false)).await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(code(response).await, "exceeds_capacity");
+        let batch = serde_json::json!([gpu_job(0, false), gpu_job(2, false)]);
+        let response = post(&app, "/jobs/batch", batch).await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(code(response).await, "exceeds_capacity");
+
+        assert_eq!(
+            post(&app, "/jobs", gpu_job(1, true)).await.status(),
+            StatusCode::CREATED
+        );
+        let batch = serde_json::json!([gpu_job(0, false), gpu_job(2, true)]);
+        assert_eq!(
+            post(&app, "/jobs/batch", batch).await.status(),
+            StatusCode::CREATED
+        );
+    }
+
+    #[tokio::test]
+    async fn a_batch_that_cannot_be_saved_is_not_created_at_all() {
+        let dir = tempfile::tempdir().unwrap();
+        let app = app(dir.path(), Default::default(), None);
+        let job_ids = |jobs: serde_json::Value| -> Vec<u64> {
+            jobs.as_array()
+                .unwrap()
+                .iter()
+                .map(|job| job["id"].as_u64().unwrap())
+                .collect()
+        };
+        assert_eq!(
+            post(&app, "/jobs", gpu_job(0, false)).await.status(),
+            StatusCode::CREATED
+        );
+
+        // The state file cannot be replaced while a directory is in its way.
+        let state_file = dir.path().join("state.msgpack");
+        std::fs::create_dir_all(state_file.join("in-the-way")).unwrap();
+        let batch = serde_json::json!([gpu_job(0, false), gpu_job(0, false), gpu_job(0, false)]);
+        let response = post(&app, "/jobs/batch", batch.clone()).await;
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["code"], "batch_not_saved");
+        assert_eq!(body["details"]["count"], 3);
+        assert_eq!(job_ids(get(&app, "/jobs").await), [1]);
+
+        // Once saving works again the batch gets the ids it would have had.
+        std::fs::remove_dir_all(&state_file).unwrap();
+        let response = post(&app, "/jobs/batch", batch).await;
+        assert_eq!(response.status(), StatusCode::CREATED);
+        assert_eq!(job_ids(get(&app, "/jobs").await), [1, 2, 3, 4]);
+    }
+}
@@ -1,20 +1,44 @@
 pub(crate) use jobs::UpdateJobRequest;
 
+pub(super) use artifacts::{list_job_artifacts, register_job_artifact};
+pub(super) use backups::{create_backup, restore_backup};
+pub(super) use config::{get_log_level, reload_config, set_log_level};
 pub(super) use debug::{debug_job, debug_metrics, debug_state};
+pub(super) use groups::{get_group, list_group_jobs, list_groups};
+pub(super) use health::{get_health, get_version, healthz, readyz, startupz};
+pub(super) use job_dir::remove_job_dir;
 pub(super) use jobs::{
-    cancel_job, create_job, create_jobs_batch, fail_job, finish_job, get_health, get_job,
-    get_job_log, hold_job, ignore_gpu_process, info, list_ignored_gpu_processes, list_jobs,
-    release_job, resolve_dependency, set_allowed_gpus, set_group_max_concurrency,
-    unignore_gpu_process, update_job,
+    apply_job_action, cancel_job, create_job, create_jobs_batch, fail_job, finish_job, get_job,
+    get_job_log, get_start_estimates, hold_job, ignore_gpu_process, info,
+    list_ignored_gpu_processes, list_jobs, release_job, resolve_dependency, set_allowed_gpus,
+    set_group_max_concurrency, unignore_gpu_process, update_job,
 };
 pub(super) use metrics::get_metrics;
+pub(super) use nodes::{list_nodes, register_node, update_labels};
+pub(super) use notifications::{add_job_webhook, list_webhook_targets, remove_job_webhooks};
+pub(super) use openapi::get_openapi;
+pub(super) use rate_limits::{get_rate_limits, reset_rate_limit, set_rate_limit};
 pub(super) use reservations::{
     cancel_reservation, create_reservation, get_reservation, list_reservations,
 };
-pub(super) use stats::get_stats;
+pub(super) use signals::signal_job;
+pub(super) use stats::{get_report, get_runtime_stats, get_scheduler_stats, get_stats};
+pub(super) use tags::update_job_tags;
 
+mod artifacts;
+mod backups;
+mod config;
 mod debug;
+mod groups;
+mod health;
+mod job_dir;
 mod jobs;
 mod metrics;
+mod nodes;
+mod notifications;
+mod openapi;
+mod rate_limits;
 mod reservations;
+mod signals;
 mod stats;
+mod tags;
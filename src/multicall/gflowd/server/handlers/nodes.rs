@@ -0,0 +1,104 @@
+use super::super::state::{reject_if_read_only, ServerState};
+use crate::multicall::gflowd::events::SchedulerEvent;
+use axum::{
+    extract::State,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use gflow::core::api_error::ApiError;
+use gflow::core::node::NodeRegistration;
+use gflow::core::scheduler::LabelTarget;
+use gflow::utils::constraint::Labels;
+
+#[derive(serde::Deserialize)]
+pub(in crate::multicall::gflowd::server) struct UpdateLabelsRequest {
+    /// A GPU of this machine; with neither this nor `node`, the machine itself
+    #[serde(default)]
+    gpu: Option<u32>,
+    #[serde(default)]
+    node: Option<String>,
+    #[serde(default)]
+    set: Labels,
+    #[serde(default)]
+    remove: Vec<String>,
+}
+
+/// Register a node, or record the heartbeat of one that is already known.
+#[axum::debug_handler]
+pub(in crate::multicall::gflowd::server) async fn register_node(
+    State(server_state): State<ServerState>,
+    Json(registration): Json<NodeRegistration>,
+) -> Response {
+    if let Some(resp) = reject_if_read_only(&server_state).await {
+        return resp;
+    }
+    if registration.name.is_empty() || registration.token.is_empty() {
+        return ApiError::invalid_request("Node name and token must not be empty").into_response();
+    }
+    if !registration.url.starts_with("http://") {
+        return ApiError::invalid_request(format!(
+            "Invalid node URL '{}': expected http://<host>:<port>",
+            registration.url
+        ))
+        .into_response();
+    }
+
+    let name = registration.name.clone();
+    let came_up = server_state
+        .scheduler
+        .write()
+        .await
+        .register_node(registration);
+    if came_up {
+        tracing::info!(node = %name, "Node is up");
+        server_state
+            .event_bus
+            .publish(SchedulerEvent::NodeUp { name });
+    }
+    StatusCode::NO_CONTENT.into_response()
+}
+
+#[axum::debug_handler]
+pub(in crate::multicall::gflowd::server) async fn list_nodes(
+    State(server_state): State<ServerState>,
+) -> Response {
+    let nodes = server_state.scheduler.read().await.nodes();
+    Json(nodes).into_response()
+}
+
+#[axum::debug_handler]
+pub(in crate::multicall::gflowd::server) async fn update_labels(
+    State(server_state): State<ServerState>,
+    Json(request): Json<UpdateLabelsRequest>,
+) -> Response {
+    if let Some(resp) = reject_if_read_only(&server_state).await {
+        return resp;
+    }
+    let target = match (request.gpu, request.node) {
+        (None, None) => LabelTarget::Machine,
+        (Some(gpu), None) => LabelTarget::Gpu(gpu),
+        (None, Some(node)) => LabelTarget::Node(node),
+        (Some(_), Some(_)) => {
+            return ApiError::invalid_request("Specify a GPU or a node, not both").into_response();
+        }
+    };
+
+    let result =
+        server_state
+            .scheduler
+            .write()
+            .await
+            .update_labels(&target, request.set, &request.remove);
+    match result {
+        Ok(labels) => {
+            tracing::info!(?target, ?labels, "Labels updated");
+            // Queued jobs may match now.
+            server_state
+                .event_bus
+                .publish(SchedulerEvent::PeriodicHealthCheck);
+            Json(serde_json::json!({ "labels": labels })).into_response()
+        }
+        Err(error) => ApiError::invalid_request(format!("{error:#}")).into_response(),
+    }
+}
@@ -0,0 +1,160 @@
+use super::super::auth::{authenticated_user, ownership_error};
+use super::super::state::{reject_if_read_only, ServerState};
+use super::openapi::ErrorBody;
+use crate::multicall::gflowd::tokens::Caller;
+use crate::multicall::gflowd::webhooks::JobWebhook;
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Extension, Json,
+};
+use gflow::client::{JobWebhookRequest, RemovedJobWebhooks, JOB_WEBHOOK_EVENTS};
+use gflow::core::api_error::{ApiError, ApiErrorKind};
+use gflow::core::info::WebhookTargetStatus;
+use std::time::SystemTime;
+
+#[utoipa::path(
+    get,
+    path = "/notifications/webhooks",
+    tag = "notifications",
+    responses((
+        status = 200,
+        description = "Delivery health of each configured webhook",
+        body = [WebhookTargetStatus]
+    ))
+)]
+#[axum::debug_handler]
+pub(in crate::multicall::gflowd::server) async fn list_webhook_targets(
+    State(server_state): State<ServerState>,
+) -> impl IntoResponse {
+    (StatusCode::OK, Json(server_state.webhook_outbox.status()))
+}
+
+#[utoipa::path(
+    post,
+    path = "/jobs/{id}/notifications",
+    tag = "notifications",
+    summary = "Send a webhook once when a job ends",
+    params(("id" = u32, Path, description = "Job ID")),
+    request_body = super::openapi::JobWebhookRequest,
+    responses(
+        (status = 200, description = "Webhook attached"),
+        (status = 400, description = "Invalid URL or event", body = ErrorBody),
+        (status = 403, description = "Job belongs to another user", body = ErrorBody),
+        (status = 404, description = "No such job", body = ErrorBody),
+        (status = 409, description = "The job has ended, or notifications are disabled", body = ErrorBody),
+        (status = 503, description = "Daemon is read-only", body = ErrorBody),
+    )
+)]
+#[axum::debug_handler]
+pub(in crate::multicall::gflowd::server) async fn add_job_webhook(
+    State(server_state): State<ServerState>,
+    caller: Option<Extension<Caller>>,
+    Path(id): Path<u32>,
+    Json(request): Json<JobWebhookRequest>,
+) -> Response {
+    if let Some(resp) = reject_if_read_only(&server_state).await {
+        return resp;
+    }
+    if !server_state.job_webhooks.deliverable() {
+        return ApiError::conflict(
+            "Webhook notifications are disabled on this daemon; an admin can enable them with `[notifications] enabled = true`",
+        )
+        .into_response();
+    }
+    if let Err(message) = validate_job_webhook(&request) {
+        return ApiError::invalid_request(message).into_response();
+    }
+    let requested_by = authenticated_user(caller.as_deref()).unwrap_or(&request.user);
+
+    // Hold the lock while attaching, so the job cannot end in between and
+    // leave a webhook that never fires.
+    let state = server_state.scheduler.read().await;
+    let Some(job) = state.get_job(id) else {
+        return ApiError::from(ApiErrorKind::JobNotFound { id }).into_response();
+    };
+    if let Some(error) = ownership_error(caller.as_deref(), id, &job.submitted_by) {
+        return error.into_response();
+    }
+    if job.state.is_final() {
+        return ApiError::conflict(format!("Job {id} has already ended ({})", job.state))
+            .into_response();
+    }
+
+    tracing::info!(
+        job_id = id,
+        requested_by,
+        events = ?request.events,
+        "Attaching webhook to job"
+    );
+    server_state.job_webhooks.add(
+        id,
+        JobWebhook {
+            url: request.url.trim().to_string(),
+            events: request.events,
+            requested_by: requested_by.to_string(),
+            created_at: SystemTime::now(),
+        },
+    );
+    drop(state);
+
+    (StatusCode::OK, Json(())).into_response()
+}
+
+#[utoipa::path(
+    delete,
+    path = "/jobs/{id}/notifications",
+    tag = "notifications",
+    summary = "Remove the webhooks attached to a job",
+    params(("id" = u32, Path, description = "Job ID")),
+    responses(
+        (status = 200, description = "Number of webhooks removed", body = super::openapi::RemovedJobWebhooks),
+        (status = 403, description = "Job belongs to another user", body = ErrorBody),
+        (status = 404, description = "No such job", body = ErrorBody),
+        (status = 503, description = "Daemon is read-only", body = ErrorBody),
+    )
+)]
+#[axum::debug_handler]
+pub(in crate::multicall::gflowd::server) async fn remove_job_webhooks(
+    State(server_state): State<ServerState>,
+    caller: Option<Extension<Caller>>,
+    Path(id): Path<u32>,
+) -> Response {
+    if let Some(resp) = reject_if_read_only(&server_state).await {
+        return resp;
+    }
+
+    {
+        let state = server_state.scheduler.read().await;
+        let Some(job) = state.get_job(id) else {
+            return ApiError::from(ApiErrorKind::JobNotFound { id }).into_response();
+        };
+        if let Some(error) = ownership_error(caller.as_deref(), id, &job.submitted_by) {
+            return error.into_response();
+        }
+    }
+
+    let removed = server_state.job_webhooks.remove(id);
+    tracing::info!(job_id = id, removed, "Removed webhooks from job");
+    (StatusCode::OK, Json(RemovedJobWebhooks { removed })).into_response()
+}
+
+fn validate_job_webhook(request: &JobWebhookRequest) -> Result<(), String> {
+    let url =
+        reqwest::Url::parse(request.url.trim()).map_err(|e| format!("Invalid webhook URL: {e}"))?;
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err("Webhook URL must use http or https".to_string());
+    }
+    if let Some(event) = request
+        .events
+        .iter()
+        .find(|event| !JOB_WEBHOOK_EVENTS.contains(&event.as_str()))
+    {
+        return Err(format!(
+            "Unknown event '{event}'; expected one of {}",
+            JOB_WEBHOOK_EVENTS.join(", ")
+        ));
+    }
+    Ok(())
+}
@@ -0,0 +1,386 @@
+//! OpenAPI description of the HTTP API, served at `/api/v1/openapi.json`.
+//!
+//! Handlers that build their bodies with `json!` are described by the
+//! schema-only structs below; keep them in step with the handlers.
+#![allow(dead_code)]
+
+use super::jobs::SetGpusRequest;
+use super::reservations::{CreateReservationRequest, CreateReservationResponse};
+use super::stats::{TopJob, UsageStats};
+use axum::Json;
+use gflow::core::info::{
+    ClientVersionStats, GpuInfo, PersistenceStatus, SchedulerInfo, SchedulerStats, VersionInfo,
+    WebhookTargetStatus,
+};
+use gflow::core::job::{Job, JobArtifact, JobGroup, JobProgress, JobState, JobStateReason};
+use gflow::core::reservation::GpuReservation;
+use gflow::core::scheduler::{QueueSummary, StartEstimate};
+use gflow::utils::disk::DiskUsage;
+use utoipa::{OpenApi, ToSchema};
+
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "gflowd",
+        description = "HTTP API of the gflow scheduler daemon. The unversioned paths \
+            (`/jobs` instead of `/api/v1/jobs`) are deprecated aliases."
+    ),
+    servers((url = "/api/v1")),
+    paths(
+        super::jobs::list_jobs,
+        super::jobs::create_job,
+        super::jobs::create_jobs_batch,
+        super::jobs::get_start_estimates,
+        super::jobs::get_job,
+        super::jobs::finish_job,
+        super::jobs::fail_job,
+        super::jobs::cancel_job,
+        super::jobs::hold_job,
+        super::jobs::release_job,
+        super::jobs::apply_job_action,
+        super::jobs::info,
+        super::jobs::set_allowed_gpus,
+        super::artifacts::list_job_artifacts,
+        super::artifacts::register_job_artifact,
+        super::job_dir::remove_job_dir,
+        super::tags::update_job_tags,
+        super::signals::signal_job,
+        super::groups::list_groups,
+        super::groups::get_group,
+        super::groups::list_group_jobs,
+        super::reservations::create_reservation,
+        super::reservations::list_reservations,
+        super::reservations::get_reservation,
+        super::reservations::cancel_reservation,
+        super::health::get_health,
+        super::health::healthz,
+        super::health::readyz,
+        super::health::startupz,
+        super::health::get_version,
+        super::stats::get_stats,
+        super::stats::get_scheduler_stats,
+        super::notifications::list_webhook_targets,
+        super::notifications::add_job_webhook,
+        super::notifications::remove_job_webhooks,
+    ),
+    components(schemas(
+        Job,
+        JobGroup,
+        JobState,
+        JobStateReason,
+        JobProgress,
+        JobArtifact,
+        JobArtifactRequest,
+        JobTagsRequest,
+        JobSignalRequest,
+        StartEstimate,
+        SchedulerInfo,
+        GpuInfo,
+        SchedulerStats,
+        PersistenceStatus,
+        DiskUsage,
+        ClientVersionStats,
+        QueueSummary,
+        VersionInfo,
+        WebhookTargetStatus,
+        GpuReservation,
+        CreateReservationRequest,
+        CreateReservationResponse,
+        SetGpusRequest,
+        UsageStats,
+        TopJob,
+        SubmittedJob,
+        SubmittedDependency,
+        JobActionRequest,
+        JobActionResult,
+        JobActionError,
+        JobWebhookRequest,
+        RemovedJobWebhooks,
+        RemovedJobDir,
+        ErrorBody,
+        Health,
+        Liveness,
+        Readiness,
+        Startup,
+    )),
+    tags(
+        (name = "jobs", description = "Submit, inspect and change jobs"),
+        (name = "gpus", description = "GPUs and the GPUs the scheduler may use"),
+        (name = "reservations", description = "GPU reservations and maintenance windows"),
+        (name = "health", description = "Liveness, readiness and persistence health"),
+        (name = "stats", description = "Usage and scheduler statistics"),
+        (name = "notifications", description = "Webhook delivery and webhooks attached to jobs"),
+    )
+)]
+pub(super) struct ApiDoc;
+
+#[axum::debug_handler]
+pub(in crate::multicall::gflowd::server) async fn get_openapi() -> Json<utoipa::openapi::OpenApi> {
+    Json(ApiDoc::openapi())
+}
+
+/// Error body of the JSON endpoints; some errors add fields such as `hint`.
+#[derive(ToSchema)]
+pub(super) struct ErrorBody {
+    /// Machine-readable error code, e.g. `job_not_found` or `invalid_transition`
+    pub code: String,
+    pub message: String,
+    /// Code-specific fields, e.g. `{"id": 7, "from": "Finished", "to": "Cancelled"}`
+    #[schema(value_type = Option<Object>, required = false)]
+    pub details: Option<serde_json::Value>,
+    /// Same as `message`, for clients that predate error codes
+    pub error: String,
+}
+
+/// One accepted submission.
+#[derive(ToSchema)]
+pub(super) struct SubmittedJob {
+    pub id: u32,
+    pub run_name: String,
+    /// `null` when the job does not start queued, e.g. it is held
+    pub estimate: Option<StartEstimate>,
+    pub group_id: Option<uuid::Uuid>,
+    pub priority: u8,
+    pub dependencies: Vec<SubmittedDependency>,
+    /// 1-based place among queued jobs whose dependencies are met; `null`
+    /// while the job waits on dependencies or is not queued
+    pub queue_position: Option<usize>,
+    /// GPUs free at submission time
+    pub available_gpus: Vec<u32>,
+}
+
+/// A dependency of a submitted job and its state at submission time.
+#[derive(ToSchema)]
+pub(super) struct SubmittedDependency {
+    pub id: u32,
+    /// `null` if the dependency has since been purged
+    pub state: Option<JobState>,
+}
+
+/// A state change for many jobs.
+#[derive(ToSchema)]
+pub(super) struct JobActionRequest {
+    /// `cancel`, `hold`, `release`, `finish` or `fail`
+    pub op: String,
+    /// At most 1000 job IDs
+    pub ids: Vec<u32>,
+}
+
+/// What became of one job of a [`JobActionRequest`].
+#[derive(ToSchema)]
+pub(super) struct JobActionResult {
+    pub id: u32,
+    /// Why the job was left alone, e.g. `job_not_found`, `forbidden` or
+    /// `invalid_transition`; absent if it changed or was already in the target state
+    #[schema(required = false)]
+    pub error: Option<JobActionError>,
+}
+
+/// The error of one job in a [`JobActionResult`]: an [`ErrorBody`] without
+/// the legacy `error` field.
+#[derive(ToSchema)]
+pub(super) struct JobActionError {
+    pub code: String,
+    pub message: String,
+    #[schema(value_type = Option<Object>, required = false)]
+    pub details: Option<serde_json::Value>,
+}
+
+/// A webhook sent once, when a job ends.
+#[derive(ToSchema)]
+pub(super) struct JobWebhookRequest {
+    /// `http` or `https` URL to POST the job payload to
+    pub url: String,
+    /// Some of `job_completed`, `job_failed`, `job_cancelled` and
+    /// `job_timeout`; empty or absent means all of them
+    #[schema(required = false)]
+    pub events: Vec<String>,
+    /// Who asks; ignored for authenticated callers
+    pub user: String,
+}
+
+/// An output to register for a job.
+#[derive(ToSchema)]
+pub(super) struct JobArtifactRequest {
+    /// Absolute, or relative to the job's run directory; must exist on the
+    /// daemon's machine
+    pub path: String,
+    /// Short name such as `best-checkpoint`
+    #[schema(required = false)]
+    pub label: Option<String>,
+}
+
+/// A signal for a running job, and who sends it.
+#[derive(ToSchema)]
+pub(super) struct JobSignalRequest {
+    /// One of HUP, INT, QUIT, USR1, USR2, TERM, KILL, CONT and WINCH, with
+    /// or without the `SIG` prefix
+    #[schema(example = "USR1")]
+    pub signal: String,
+    /// Who asks; ignored for authenticated callers
+    pub user: String,
+}
+
+/// Tags to add to and remove from a job.
+#[derive(ToSchema)]
+pub(super) struct JobTagsRequest {
+    /// Lowercase letters, digits and dashes; a job has at most 20 tags
+    #[schema(required = false)]
+    pub add: Vec<String>,
+    /// Tags the job does not have are ignored
+    #[schema(required = false)]
+    pub remove: Vec<String>,
+}
+
+#[derive(ToSchema)]
+pub(super) struct RemovedJobWebhooks {
+    pub removed: usize,
+}
+
+#[derive(ToSchema)]
+pub(super) struct RemovedJobDir {
+    pub path: String,
+    /// Size of the files removed
+    pub bytes: u64,
+}
+
+#[derive(ToSchema)]
+pub(super) struct Liveness {
+    /// Always `ok`
+    pub status: String,
+    pub pid: u32,
+}
+
+#[derive(ToSchema)]
+pub(super) struct Readiness {
+    /// `ready` or `not_ready`
+    pub status: String,
+    /// Persistence mode: `state`, `journal` or `read_only`
+    pub mode: String,
+    #[schema(required = false)]
+    pub reason: Option<String>,
+    #[schema(required = false)]
+    pub detail: Option<String>,
+    #[schema(required = false)]
+    pub journal_error: Option<String>,
+}
+
+#[derive(ToSchema)]
+pub(super) struct Startup {
+    /// `complete` or `starting`; the other fields are only set once complete
+    pub status: String,
+    #[schema(required = false)]
+    pub jobs_loaded: Option<usize>,
+    #[schema(required = false)]
+    pub load_duration_ms: Option<u64>,
+    #[schema(required = false)]
+    pub nvml_initialized: Option<bool>,
+    #[schema(required = false)]
+    pub journal_applied: Option<bool>,
+    #[schema(required = false)]
+    pub mode: Option<String>,
+}
+
+#[derive(ToSchema)]
+pub(super) struct Health {
+    /// `ok`, `recovery` or `read_only`; the other fields except `pid`,
+    /// `config_reload` and `shutdown` are only set when not `ok`
+    pub status: String,
+    pub pid: u32,
+    #[schema(required = false)]
+    pub mode: Option<String>,
+    #[schema(required = false)]
+    pub detail: Option<String>,
+    #[schema(required = false)]
+    pub state_backup: Option<String>,
+    #[schema(required = false)]
+    pub journal: Option<String>,
+    #[schema(required = false)]
+    pub journal_error: Option<String>,
+    /// Outcome of the last config reload, if any
+    #[schema(value_type = Option<Object>)]
+    pub config_reload: Option<serde_json::Value>,
+    /// `draining` while waiting for running jobs before shutting down
+    /// (`--wait-running`), `stopping` once listeners are closing; null otherwise
+    pub shutdown: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::Value;
+
+    #[test]
+    fn spec_lists_the_api_and_its_schemas() {
+        let spec: Value = serde_json::to_value(ApiDoc::openapi()).unwrap();
+
+        assert_eq!(spec["servers"][0]["url"], "/api/v1");
+        for path in [
+            "/jobs",
+            "/jobs/batch",
+            "/jobs/{id}",
+            "/jobs/{id}/finish",
+            "/jobs/{id}/fail",
+            "/jobs/{id}/cancel",
+            "/jobs/{id}/hold",
+            "/jobs/{id}/release",
+            "/jobs/actions",
+            "/info",
+            "/gpus",
+            "/reservations",
+            "/reservations/{id}",
+            "/health",
+            "/healthz",
+            "/stats",
+            "/info/stats",
+            "/notifications/webhooks",
+            "/jobs/{id}/notifications",
+            "/jobs/{id}/artifacts",
+            "/jobs/{id}/dir",
+            "/jobs/{id}/tags",
+        ] {
+            assert!(spec["paths"][path].is_object(), "missing path {path}");
+        }
+        assert!(spec["paths"]["/jobs"]["get"].is_object());
+        assert!(spec["paths"]["/jobs"]["post"].is_object());
+        assert!(spec["paths"]["/reservations/{id}"]["delete"].is_object());
+
+        let schemas = &spec["components"]["schemas"];
+        for name in ["Job", "JobState", "JobStateReason", "SchedulerInfo"] {
+            assert!(schemas[name].is_object(), "missing schema {name}");
+        }
+        assert_eq!(schemas["JobState"]["enum"][0], "Queued");
+    }
+
+    #[test]
+    fn job_schema_matches_the_wire_format() {
+        let spec: Value = serde_json::to_value(ApiDoc::openapi()).unwrap();
+        let job = &spec["components"]["schemas"]["Job"];
+
+        // `#[serde(default)]` makes every field optional on submission.
+        assert!(job["required"].as_array().is_none_or(Vec::is_empty));
+        let properties = job["properties"].as_object().unwrap();
+        for field in [
+            "id",
+            "script",
+            "script_content",
+            "constraint",
+            "state",
+            "time_limit",
+        ] {
+            assert!(properties.contains_key(field), "missing field {field}");
+        }
+        assert_eq!(properties["run_dir"]["type"], "string");
+        assert_eq!(properties["group_id"]["format"], "uuid");
+        let time_limit = properties["time_limit"].to_string();
+        assert!(
+            time_limit.contains("#/components/schemas/Duration"),
+            "{time_limit}"
+        );
+        let submitted_at = properties["submitted_at"].to_string();
+        assert!(
+            submitted_at.contains("#/components/schemas/SystemTime"),
+            "{submitted_at}"
+        );
+    }
+}
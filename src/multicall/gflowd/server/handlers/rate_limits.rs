@@ -0,0 +1,63 @@
+use super::super::state::ServerState;
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use gflow::client::RateLimits;
+use gflow::config::UserRateLimit;
+use gflow::core::api_error::ApiError;
+
+#[axum::debug_handler]
+pub(in crate::multicall::gflowd::server) async fn get_rate_limits(
+    State(server_state): State<ServerState>,
+) -> impl IntoResponse {
+    let limits = RateLimits {
+        config: server_state.rate_limiter.config(),
+        overrides: server_state.rate_limiter.overrides(),
+    };
+    (StatusCode::OK, Json(limits))
+}
+
+#[axum::debug_handler]
+pub(in crate::multicall::gflowd::server) async fn set_rate_limit(
+    State(server_state): State<ServerState>,
+    Path(user): Path<String>,
+    Json(limit): Json<UserRateLimit>,
+) -> Response {
+    if limit == UserRateLimit::default() {
+        return ApiError::invalid_request(
+            "Set submissions_per_minute, max_queued_jobs or both; reset the user to drop an override",
+        )
+        .into_response();
+    }
+    if limit.submissions_per_minute == Some(0) {
+        return ApiError::invalid_request("submissions_per_minute must be at least 1")
+            .into_response();
+    }
+
+    tracing::info!(user, ?limit, "Rate limit override set");
+    server_state.rate_limiter.set_override(user.clone(), limit);
+    let effective = server_state.rate_limiter.limits_for(&user);
+    (
+        StatusCode::OK,
+        Json(serde_json::json!({ "user": user, "limits": effective })),
+    )
+        .into_response()
+}
+
+#[axum::debug_handler]
+pub(in crate::multicall::gflowd::server) async fn reset_rate_limit(
+    State(server_state): State<ServerState>,
+    Path(user): Path<String>,
+) -> impl IntoResponse {
+    let removed = server_state.rate_limiter.remove_override(&user);
+    if removed {
+        tracing::info!(user, "Rate limit override removed");
+    }
+    (
+        StatusCode::OK,
+        Json(serde_json::json!({ "removed": removed })),
+    )
+}
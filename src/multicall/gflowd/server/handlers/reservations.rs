@@ -1,77 +1,116 @@
+use super::super::auth::authenticated_user;
 use super::super::state::ServerState;
+use super::openapi::ErrorBody;
 use crate::multicall::gflowd::events::SchedulerEvent;
+use crate::multicall::gflowd::tokens::Caller;
 use axum::{
     extract::{Path, Query, State},
     http::StatusCode,
-    Json,
+    Extension, Json,
 };
 use compact_str::CompactString;
 use serde::{Deserialize, Serialize};
 use std::time::{Duration, SystemTime};
 
-use gflow::core::reservation::{GpuReservation, GpuSpec, ReservationStatus};
+use gflow::core::api_error::{ApiError, ApiErrorKind};
+use gflow::core::conflict::ConflictError;
+use gflow::core::reservation::{
+    GpuReservation, GpuSpec, ReservationKind, ReservationOptions, ReservationStatus,
+};
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, utoipa::ToSchema)]
 pub struct CreateReservationRequest {
     pub user: String,
     /// GPU count (for count-based reservations)
     pub gpu_count: Option<u32>,
     /// GPU indices (for index-based reservations, e.g., [0, 2, 3])
     pub gpu_indices: Option<Vec<u32>>,
+    #[schema(value_type = gflow::core::schema::SystemTimeSchema)]
     pub start_time: SystemTime,
     pub duration_secs: u64,
+    #[serde(flatten)]
+    pub options: ReservationOptions,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, utoipa::ToSchema)]
 pub struct CreateReservationResponse {
     pub reservation_id: u32,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, utoipa::IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct ListReservationsQuery {
     pub user: Option<String>,
+    /// `pending`, `active`, `completed` or `cancelled`
     pub status: Option<String>,
     pub active_only: Option<bool>,
 }
 
+#[utoipa::path(
+    post,
+    path = "/reservations",
+    tag = "reservations",
+    request_body = CreateReservationRequest,
+    responses(
+        (status = 200, description = "Reservation created", body = CreateReservationResponse),
+        (status = 400, description = "Invalid request", body = ErrorBody),
+        (status = 403, description = "Maintenance windows need an admin", body = ErrorBody),
+        (status = 409, description = "Not enough GPUs free at that time", body = ErrorBody),
+    )
+)]
 pub async fn create_reservation(
     State(server_state): State<ServerState>,
+    caller: Option<Extension<Caller>>,
     Json(req): Json<CreateReservationRequest>,
-) -> Result<Json<CreateReservationResponse>, (StatusCode, String)> {
+) -> Result<Json<CreateReservationResponse>, ApiError> {
+    if req.options.kind == ReservationKind::Maintenance {
+        if let Some(Caller::User { admin: false, .. }) = caller.as_deref() {
+            return Err(ApiError::forbidden(
+                "Only an admin can schedule a maintenance window",
+            ));
+        }
+    }
+
     let mut state = server_state.scheduler.write().await;
 
     let duration = Duration::from_secs(req.duration_secs);
-    let user = CompactString::from(req.user);
+    let user = CompactString::from(authenticated_user(caller.as_deref()).unwrap_or(&req.user));
 
     // Validate that exactly one of gpu_count or gpu_indices is provided
     let gpu_spec = match (req.gpu_count, req.gpu_indices) {
         (Some(count), None) => GpuSpec::Count(count),
         (None, Some(indices)) => {
             if indices.is_empty() {
-                return Err((
-                    StatusCode::BAD_REQUEST,
-                    "gpu_indices cannot be empty".to_string(),
-                ));
+                return Err(ApiError::invalid_request("gpu_indices cannot be empty"));
             }
             GpuSpec::Indices(indices)
         }
         (Some(_), Some(_)) => {
-            return Err((
-                StatusCode::BAD_REQUEST,
-                "Cannot specify both gpu_count and gpu_indices".to_string(),
+            return Err(ApiError::invalid_request(
+                "Cannot specify both gpu_count and gpu_indices",
             ));
         }
+        // Maintenance covers the whole node unless told otherwise.
+        (None, None) if req.options.kind == ReservationKind::Maintenance => {
+            GpuSpec::Count(state.gpu_slots_count() as u32)
+        }
         (None, None) => {
-            return Err((
-                StatusCode::BAD_REQUEST,
-                "Must specify either gpu_count or gpu_indices".to_string(),
+            return Err(ApiError::invalid_request(
+                "Must specify either gpu_count or gpu_indices",
             ));
         }
     };
 
     let reservation_id = state
-        .create_reservation(user, gpu_spec, req.start_time, duration)
-        .map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()))?;
+        .create_reservation(user, gpu_spec, req.start_time, duration, req.options)
+        .map_err(|e| {
+            // Not enough GPUs left at that time, as opposed to a malformed request.
+            if e.downcast_ref::<ConflictError>().is_some() {
+                ApiError::conflict(e.to_string())
+            } else {
+                ApiError::invalid_request(e.to_string())
+            }
+        })?;
 
     // Publish event
     server_state
@@ -81,10 +120,20 @@ pub async fn create_reservation(
     Ok(Json(CreateReservationResponse { reservation_id }))
 }
 
+#[utoipa::path(
+    get,
+    path = "/reservations",
+    tag = "reservations",
+    params(ListReservationsQuery),
+    responses(
+        (status = 200, description = "Matching reservations", body = Vec<GpuReservation>),
+        (status = 400, description = "Invalid status filter", body = ErrorBody),
+    )
+)]
 pub async fn list_reservations(
     State(server_state): State<ServerState>,
     Query(query): Query<ListReservationsQuery>,
-) -> Result<Json<Vec<GpuReservation>>, (StatusCode, String)> {
+) -> Result<Json<Vec<GpuReservation>>, ApiError> {
     let state = server_state.scheduler.read().await;
 
     // Parse status filter
@@ -95,10 +144,10 @@ pub async fn list_reservations(
             "completed" => Some(ReservationStatus::Completed),
             "cancelled" => Some(ReservationStatus::Cancelled),
             _ => {
-                return Err((
-                    StatusCode::BAD_REQUEST,
-                    format!("Invalid status: {}", status_str),
-                ))
+                return Err(ApiError::invalid_request(format!(
+                    "Invalid status: {}",
+                    status_str
+                )))
             }
         }
     } else {
@@ -117,31 +166,69 @@ pub async fn list_reservations(
     Ok(Json(reservations))
 }
 
+#[utoipa::path(
+    get,
+    path = "/reservations/{id}",
+    tag = "reservations",
+    params(("id" = u32, Path, description = "Reservation ID")),
+    responses(
+        (status = 200, description = "The reservation", body = GpuReservation),
+        (status = 404, description = "No such reservation", body = ErrorBody),
+    )
+)]
 pub async fn get_reservation(
     State(server_state): State<ServerState>,
     Path(id): Path<u32>,
-) -> Result<Json<GpuReservation>, (StatusCode, String)> {
+) -> Result<Json<GpuReservation>, ApiError> {
     let state = server_state.scheduler.read().await;
 
-    let reservation = state.get_reservation(id).ok_or_else(|| {
-        (
-            StatusCode::NOT_FOUND,
-            format!("Reservation {} not found", id),
-        )
-    })?;
+    let reservation = state
+        .get_reservation(id)
+        .ok_or(ApiErrorKind::ReservationNotFound { id })?;
 
     Ok(Json(reservation.clone()))
 }
 
+#[utoipa::path(
+    delete,
+    path = "/reservations/{id}",
+    tag = "reservations",
+    params(("id" = u32, Path, description = "Reservation ID")),
+    responses(
+        (status = 200, description = "Reservation cancelled"),
+        (status = 400, description = "Reservation already finished", body = ErrorBody),
+        (status = 403, description = "Reservation belongs to another user", body = ErrorBody),
+        (status = 404, description = "No such reservation", body = ErrorBody),
+    )
+)]
 pub async fn cancel_reservation(
     State(server_state): State<ServerState>,
+    caller: Option<Extension<Caller>>,
     Path(id): Path<u32>,
-) -> Result<StatusCode, (StatusCode, String)> {
+) -> Result<StatusCode, ApiError> {
     let mut state = server_state.scheduler.write().await;
 
+    if let Some(Caller::User {
+        username,
+        admin: false,
+    }) = caller.as_deref()
+    {
+        if let Some(reservation) = state.get_reservation(id) {
+            if reservation.user != username.as_str() {
+                return Err(ApiError::forbidden(format!(
+                    "Reservation {id} belongs to '{}'; only its owner or an admin can cancel it",
+                    reservation.user
+                )));
+            }
+        }
+    }
+
+    if state.get_reservation(id).is_none() {
+        return Err(ApiErrorKind::ReservationNotFound { id }.into());
+    }
     state
         .cancel_reservation(id)
-        .map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()))?;
+        .map_err(|e| ApiError::invalid_request(e.to_string()))?;
 
     // Publish event
     server_state
@@ -0,0 +1,104 @@
+use super::super::auth::{authenticated_user, ownership_error};
+use super::super::state::{reject_if_read_only, ServerState};
+use super::openapi::ErrorBody;
+use crate::multicall::gflowd::events::SchedulerEvent;
+use crate::multicall::gflowd::tokens::Caller;
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Extension, Json,
+};
+use gflow::client::JobSignalRequest;
+use gflow::core::api_error::{ApiError, ApiErrorKind};
+use gflow::core::job::JobState;
+
+#[utoipa::path(
+    post,
+    path = "/jobs/{id}/signal",
+    tag = "jobs",
+    summary = "Send a signal to a running job's processes",
+    description = "The job keeps its state; what happens next is up to the job, \
+        e.g. saving a checkpoint on SIGUSR1 or exiting on SIGTERM.",
+    params(("id" = u32, Path, description = "Job ID")),
+    request_body = super::openapi::JobSignalRequest,
+    responses(
+        (status = 200, description = "Signal sent"),
+        (status = 403, description = "Job belongs to another user", body = ErrorBody),
+        (status = 404, description = "No such job", body = ErrorBody),
+        (status = 409, description = "The job is not running", body = ErrorBody),
+        (status = 500, description = "The job's processes could not be signalled", body = ErrorBody),
+        (status = 503, description = "Daemon is read-only", body = ErrorBody),
+    )
+)]
+#[axum::debug_handler]
+pub(in crate::multicall::gflowd::server) async fn signal_job(
+    State(server_state): State<ServerState>,
+    caller: Option<Extension<Caller>>,
+    Path(id): Path<u32>,
+    Json(request): Json<JobSignalRequest>,
+) -> Response {
+    if let Some(resp) = reject_if_read_only(&server_state).await {
+        return resp;
+    }
+    let requested_by = authenticated_user(caller.as_deref()).unwrap_or(&request.user);
+    let signal = request.signal;
+
+    {
+        let state = server_state.scheduler.read().await;
+        let Some(job) = state.get_job(id) else {
+            return ApiError::from(ApiErrorKind::JobNotFound { id }).into_response();
+        };
+        if let Some(error) = ownership_error(caller.as_deref(), id, &job.submitted_by) {
+            return error.into_response();
+        }
+        if job.state != JobState::Running {
+            return ApiError::from(ApiErrorKind::JobNotRunning {
+                id,
+                state: job.state,
+            })
+            .into_response();
+        }
+        tracing::info!(job_id = id, %signal, requested_by, "Signalling job");
+        if let Err(error) = state.signal_job(&job, signal) {
+            return ApiError::internal(format!("{error:#}")).into_response();
+        }
+    } // Lock released here
+
+    server_state
+        .event_bus
+        .publish(SchedulerEvent::JobSignalled {
+            job_id: id,
+            signal,
+            requested_by: requested_by.to_string(),
+        });
+    (StatusCode::OK, Json(())).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::super::test_app::{app, code, post};
+    use axum::http::StatusCode;
+    use gflow::core::job::Job;
+
+    #[tokio::test]
+    async fn only_running_jobs_take_signals_from_the_whitelist() {
+        let dir = tempfile::tempdir().unwrap();
+        let app = app(dir.path(), Default::default(), None);
+        let job = Job::builder().command("true").submitted_by("alice").build();
+        let response = post(&app, "/jobs", serde_json::to_value(&job).unwrap()).await;
+        assert_eq!(response.status(), StatusCode::CREATED);
+
+        let usr1 = serde_json::json!({"signal": "USR1", "user": "alice"});
+        let response = post(&app, "/jobs/1/signal", usr1.clone()).await;
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+        assert_eq!(code(response).await, "job_not_running");
+
+        let response = post(&app, "/jobs/9/signal", usr1).await;
+        assert_eq!(code(response).await, "job_not_found");
+
+        let stop = serde_json::json!({"signal": "STOP", "user": "alice"});
+        let response = post(&app, "/jobs/1/signal", stop).await;
+        assert!(response.status().is_client_error());
+    }
+}
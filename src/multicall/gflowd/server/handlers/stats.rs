@@ -1,21 +1,30 @@
+use super::super::etag;
 use super::super::state::ServerState;
 use axum::{
     extract::{Query, State},
-    http::StatusCode,
-    response::IntoResponse,
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
     Json,
 };
+use gflow::core::info::{PersistenceStatus, SchedulerStats};
 use gflow::core::job::JobState;
+use gflow::core::report::{ReportQuery, UsageReport};
+use gflow::core::runtime_stats::RuntimeStatsQuery;
+use gflow::utils::disk::DiskUsage;
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashSet};
+use std::os::unix::fs::MetadataExt;
+use std::path::PathBuf;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, utoipa::IntoParams)]
+#[into_params(parameter_in = Query)]
 pub(in crate::multicall::gflowd::server) struct StatsQuery {
     user: Option<String>,
     since: Option<i64>, // Unix timestamp
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, utoipa::ToSchema)]
 pub(in crate::multicall::gflowd::server) struct UsageStats {
     pub user: Option<String>,
     pub since: Option<u64>,
@@ -46,7 +55,7 @@ pub(in crate::multicall::gflowd::server) struct UsageStats {
     pub top_jobs: Vec<TopJob>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, utoipa::ToSchema)]
 pub(in crate::multicall::gflowd::server) struct TopJob {
     pub id: u32,
     pub name: Option<String>,
@@ -54,12 +63,38 @@ pub(in crate::multicall::gflowd::server) struct TopJob {
     pub gpus: u32,
 }
 
+#[utoipa::path(
+    get,
+    path = "/stats",
+    tag = "stats",
+    params(
+        StatsQuery,
+        ("If-None-Match" = Option<String>, Header, description = "`ETag` of an earlier response")
+    ),
+    responses(
+        (status = 200, description = "Usage statistics; no `ETag` while jobs run, as their runtimes keep growing", body = UsageStats),
+        (status = 304, description = "Unchanged since the response with the `ETag` in `If-None-Match`")
+    )
+)]
 #[axum::debug_handler]
 pub(in crate::multicall::gflowd::server) async fn get_stats(
     State(server_state): State<ServerState>,
     Query(params): Query<StatsQuery>,
-) -> impl IntoResponse {
+    headers: HeaderMap,
+) -> Response {
     let scheduler = server_state.scheduler.read().await;
+    // Running jobs count towards the runtimes up to now, so the stats change
+    // even when nothing else does.
+    let etag = scheduler
+        .job_ids_by_state(JobState::Running)
+        .is_none_or(|ids| ids.is_empty())
+        .then(|| etag::for_revision(scheduler.revision()));
+    if let Some(response) = etag
+        .as_ref()
+        .and_then(|etag| etag::not_modified(&headers, etag))
+    {
+        return response;
+    }
     let jobs = scheduler.jobs();
 
     let since_time: Option<SystemTime> = params
@@ -207,5 +242,95 @@ pub(in crate::multicall::gflowd::server) async fn get_stats(
         top_jobs,
     };
 
+    etag::tagged(etag, (StatusCode::OK, Json(stats)))
+}
+
+#[axum::debug_handler]
+pub(in crate::multicall::gflowd::server) async fn get_report(
+    State(server_state): State<ServerState>,
+    Query(query): Query<ReportQuery>,
+) -> impl IntoResponse {
+    let scheduler = server_state.scheduler.read().await;
+    let report = UsageReport::build(&scheduler.jobs(), &query);
+    (StatusCode::OK, Json(report))
+}
+
+#[axum::debug_handler]
+pub(in crate::multicall::gflowd::server) async fn get_runtime_stats(
+    State(server_state): State<ServerState>,
+    Query(query): Query<RuntimeStatsQuery>,
+) -> impl IntoResponse {
+    let scheduler = server_state.scheduler.read().await;
+    let rows = scheduler.runtime_stats().query(&query);
+    (StatusCode::OK, Json(rows))
+}
+
+#[utoipa::path(
+    get,
+    path = "/info/stats",
+    tag = "stats",
+    responses((status = 200, description = "Scheduler snapshot", body = SchedulerStats))
+)]
+#[axum::debug_handler]
+pub(in crate::multicall::gflowd::server) async fn get_scheduler_stats(
+    State(server_state): State<ServerState>,
+) -> impl IntoResponse {
+    let state = server_state.scheduler.read().await;
+    let info = state.info();
+    let run_dirs = state.local_run_dirs();
+
+    let mut stats = SchedulerStats {
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        pid: std::process::id(),
+        uptime_secs: server_state.started_at.elapsed().as_secs(),
+        jobs_by_state: state
+            .job_counts_by_state()
+            .into_iter()
+            .filter(|(_, count)| *count > 0)
+            .collect(),
+        total_gpus: info.gpus.len(),
+        available_gpus: info.gpus.iter().filter(|g| g.available).count(),
+        gpus: info.gpus,
+        total_memory_mb: state.total_memory_mb(),
+        used_memory_mb: state.used_memory_mb(),
+        available_memory_mb: state.available_memory_mb(),
+        total_cpus: state.total_cpus(),
+        used_cpus: state.used_cpus(),
+        active_reservations: state.active_reservations().into_iter().cloned().collect(),
+        persistence: PersistenceStatus {
+            mode: state.persistence_mode().to_string(),
+            state_writable: state.state_writable(),
+            journal_writable: state.journal_writable(),
+            state_load_error: state.state_load_error().map(str::to_string),
+            journal_error: state.journal_error().map(str::to_string),
+        },
+        pending_webhook_deliveries: server_state.webhook_outbox.pending(),
+        disks: Vec::new(),
+        clients: server_state.client_versions.snapshot(),
+        queue: state.queue_summary(),
+    };
+    drop(state);
+    stats.disks = disk_usage(run_dirs).await;
+
     (StatusCode::OK, Json(stats))
 }
+
+/// Free space of the data dir and of each filesystem holding a run_dir in `run_dirs`.
+async fn disk_usage(run_dirs: BTreeSet<PathBuf>) -> Vec<DiskUsage> {
+    // statvfs can block for a long time on a network filesystem.
+    tokio::task::spawn_blocking(move || {
+        let data_dir = gflow::paths::get_data_dir().ok();
+        let mut seen_devices = HashSet::new();
+        data_dir
+            .iter()
+            .map(|dir| ("data_dir", dir))
+            .chain(run_dirs.iter().map(|dir| ("run_dir", dir)))
+            .filter(|(_, dir)| {
+                std::fs::metadata(dir).map_or(true, |meta| seen_devices.insert(meta.dev()))
+            })
+            .map(|(kind, dir)| DiskUsage::of(kind, dir))
+            .collect()
+    })
+    .await
+    .unwrap_or_default()
+}
@@ -0,0 +1,121 @@
+use super::super::auth::ownership_error;
+use super::super::state::{reject_if_read_only, ServerState};
+use super::openapi::ErrorBody;
+use crate::multicall::gflowd::events::SchedulerEvent;
+use crate::multicall::gflowd::tokens::Caller;
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Extension, Json,
+};
+use gflow::client::JobTagsRequest;
+use gflow::core::api_error::{ApiError, ApiErrorKind};
+
+#[utoipa::path(
+    post,
+    path = "/jobs/{id}/tags",
+    tag = "jobs",
+    summary = "Add and remove tags of a job in any state",
+    params(("id" = u32, Path, description = "Job ID")),
+    request_body = super::openapi::JobTagsRequest,
+    responses(
+        (status = 200, description = "The job's tags afterwards", body = [String]),
+        (status = 400, description = "An invalid tag, or more than 20 tags", body = ErrorBody),
+        (status = 403, description = "Job belongs to another user", body = ErrorBody),
+        (status = 404, description = "No such job", body = ErrorBody),
+        (status = 503, description = "Daemon is read-only", body = ErrorBody),
+    )
+)]
+#[axum::debug_handler]
+pub(in crate::multicall::gflowd::server) async fn update_job_tags(
+    State(server_state): State<ServerState>,
+    caller: Option<Extension<Caller>>,
+    Path(id): Path<u32>,
+    Json(request): Json<JobTagsRequest>,
+) -> Response {
+    if let Some(resp) = reject_if_read_only(&server_state).await {
+        return resp;
+    }
+
+    let tags = {
+        let mut state = server_state.scheduler.write().await;
+        let Some(job) = state.get_job(id) else {
+            return ApiError::from(ApiErrorKind::JobNotFound { id }).into_response();
+        };
+        if let Some(error) = ownership_error(caller.as_deref(), id, &job.submitted_by) {
+            return error.into_response();
+        }
+        match state.update_job_tags(id, &request.add, &request.remove) {
+            Ok(tags) => tags,
+            Err(message) => return ApiError::invalid_request(message).into_response(),
+        }
+    };
+
+    server_state
+        .event_bus
+        .publish(SchedulerEvent::JobUpdated { job_id: id });
+    tracing::info!(job_id = id, add = ?request.add, remove = ?request.remove, "Updated job tags");
+    (StatusCode::OK, Json(tags)).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::super::test_app::{app, code, post};
+    use axum::body::Body;
+    use axum::http::{Request, StatusCode};
+    use gflow::core::job::Job;
+    use tower::ServiceExt;
+
+    fn tagged_job(tags: &[&str]) -> serde_json::Value {
+        let mut job =
+            serde_json::to_value(Job::builder().command("true").submitted_by("alice").build())
+                .unwrap();
+        job["tags"] = serde_json::json!(tags);
+        job
+    }
+
+    async fn ids_tagged(app: &axum::Router, tags: &str) -> Vec<u64> {
+        let request = Request::builder()
+            .uri(format!("/jobs?tag={tags}"))
+            .body(Body::empty())
+            .unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let jobs: Vec<serde_json::Value> = serde_json::from_slice(&body).unwrap();
+        jobs.iter().map(|job| job["id"].as_u64().unwrap()).collect()
+    }
+
+    #[tokio::test]
+    async fn jobs_are_listed_by_tag_and_tags_change_after_submission() {
+        let dir = tempfile::tempdir().unwrap();
+        let app = app(dir.path(), Default::default(), None);
+        for tags in [&["exp42", "baseline"][..], &["exp42"], &[]] {
+            let response = post(&app, "/jobs", tagged_job(tags)).await;
+            assert_eq!(response.status(), StatusCode::CREATED);
+        }
+        let response = post(&app, "/jobs", tagged_job(&["Exp42"])).await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        assert_eq!(ids_tagged(&app, "exp42").await, [1, 2]);
+        assert_eq!(ids_tagged(&app, "exp42,baseline").await, [1]);
+
+        let change = serde_json::json!({"add": ["baseline"], "remove": ["exp42"]});
+        let response = post(&app, "/jobs/2/tags", change).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert_eq!(body, r#"["baseline"]"#);
+        assert_eq!(ids_tagged(&app, "exp42").await, [1]);
+        assert_eq!(ids_tagged(&app, "baseline").await, [1, 2]);
+
+        let invalid = serde_json::json!({"add": ["no spaces"]});
+        let response = post(&app, "/jobs/2/tags", invalid).await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let response = post(&app, "/jobs/9/tags", serde_json::json!({})).await;
+        assert_eq!(code(response).await, "job_not_found");
+    }
+}
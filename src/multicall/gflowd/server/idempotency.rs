@@ -0,0 +1,48 @@
+//! Deduplication of retried job submissions
+//!
+//! Clients send a fresh `Idempotency-Key` with each `POST /jobs` or
+//! `POST /jobs/batch` and reuse it when retrying. If a submission with the
+//! same key succeeded within [`IDEMPOTENCY_WINDOW`], the daemon returns the
+//! original response instead of creating the jobs again.
+//!
+//! Lookups and inserts happen while the scheduler write lock is held, so two
+//! concurrent requests with the same key cannot both submit.
+
+use axum::http::HeaderMap;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+pub(super) const IDEMPOTENCY_WINDOW: Duration = Duration::from_secs(10 * 60);
+
+#[derive(Default)]
+pub(super) struct SubmissionCache {
+    entries: Mutex<HashMap<String, (Instant, serde_json::Value)>>,
+}
+
+impl SubmissionCache {
+    /// Response of an earlier submission with `key`, if still in the window.
+    pub(super) fn get(&self, key: &str) -> Option<serde_json::Value> {
+        let entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        entries
+            .get(key)
+            .filter(|(at, _)| at.elapsed() < IDEMPOTENCY_WINDOW)
+            .map(|(_, response)| response.clone())
+    }
+
+    pub(super) fn insert(&self, key: String, response: serde_json::Value) {
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        entries.retain(|_, (at, _)| at.elapsed() < IDEMPOTENCY_WINDOW);
+        entries.insert(key, (Instant::now(), response));
+    }
+}
+
+/// The request's idempotency key, if it sent a usable one.
+pub(super) fn key(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get(gflow::client::IDEMPOTENCY_KEY_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .map(str::trim)
+        .filter(|key| !key.is_empty() && key.len() <= 128)
+        .map(str::to_string)
+}
@@ -0,0 +1,284 @@
+//! Per-user submission limits
+//!
+//! `[rate_limits]` caps how fast each user may submit jobs (a token bucket
+//! refilled at `submissions_per_minute`) and how many queued or held jobs
+//! they may have at once. Admins can override a user's limits at runtime
+//! with `gctl rate-limit set`; overrides and buckets live in memory and are
+//! reset when the daemon restarts.
+//!
+//! Buckets are charged before the `pre_submit` hook and the scheduler lock,
+//! so a runaway submission loop is turned away cheaply. Every job of a batch
+//! counts as one submission.
+
+use gflow::config::{RateLimitsConfig, UserRateLimit};
+use gflow::core::api_error::{ApiError, ApiErrorKind};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+use std::time::Instant;
+use tokio::sync::watch;
+
+/// Submissions a user may still make, refilled continuously.
+struct Bucket {
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+pub(super) struct SubmissionLimiter {
+    /// `[rate_limits]`, replaced on config reload.
+    config: watch::Receiver<RateLimitsConfig>,
+    overrides: Mutex<BTreeMap<String, UserRateLimit>>,
+    buckets: Mutex<HashMap<String, Bucket>>,
+}
+
+impl SubmissionLimiter {
+    pub(super) fn new(config: watch::Receiver<RateLimitsConfig>) -> Self {
+        Self {
+            config,
+            overrides: Mutex::default(),
+            buckets: Mutex::default(),
+        }
+    }
+
+    pub(super) fn config(&self) -> RateLimitsConfig {
+        self.config.borrow().clone()
+    }
+
+    /// Limits set with `gctl rate-limit set`, by user.
+    pub(super) fn overrides(&self) -> BTreeMap<String, UserRateLimit> {
+        self.overrides
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// The limits that apply to `user` right now.
+    pub(super) fn limits_for(&self, user: &str) -> UserRateLimit {
+        let overrides = self.overrides.lock().unwrap_or_else(|e| e.into_inner());
+        self.config.borrow().limits_for(user, overrides.get(user))
+    }
+
+    pub(super) fn set_override(&self, user: String, limit: UserRateLimit) {
+        let mut overrides = self.overrides.lock().unwrap_or_else(|e| e.into_inner());
+        overrides.insert(user, limit);
+    }
+
+    /// Drop `user`'s override; false if there was none.
+    pub(super) fn remove_override(&self, user: &str) -> bool {
+        let mut overrides = self.overrides.lock().unwrap_or_else(|e| e.into_inner());
+        overrides.remove(user).is_some()
+    }
+
+    /// Charge `count` submissions to `user`, or say when to retry.
+    pub(super) fn take(&self, user: &str, count: usize) -> Result<(), ApiError> {
+        self.take_at(user, count, Instant::now())
+    }
+
+    fn take_at(&self, user: &str, count: usize, now: Instant) -> Result<(), ApiError> {
+        let Some(per_minute) = self.limits_for(user).submissions_per_minute else {
+            return Ok(());
+        };
+        if count > per_minute as usize {
+            return Err(ApiError::invalid_request(format!(
+                "A batch of {count} jobs is more than the {per_minute} jobs per minute you may submit; split it up"
+            )));
+        }
+
+        let capacity = f64::from(per_minute);
+        let per_second = capacity / 60.0;
+        let mut buckets = self.buckets.lock().unwrap_or_else(|e| e.into_inner());
+        let bucket = buckets.entry(user.to_string()).or_insert(Bucket {
+            tokens: capacity,
+            refilled_at: now,
+        });
+        let elapsed = now.saturating_duration_since(bucket.refilled_at);
+        bucket.tokens = (bucket.tokens + elapsed.as_secs_f64() * per_second).min(capacity);
+        bucket.refilled_at = now;
+
+        let missing = count as f64 - bucket.tokens;
+        if missing <= 0.0 {
+            bucket.tokens -= count as f64;
+            return Ok(());
+        }
+        Err(ApiErrorKind::SubmissionRateExceeded {
+            per_minute,
+            retry_after_secs: ((missing / per_second).ceil() as u64).max(1),
+        }
+        .into())
+    }
+
+    /// Whether `user`, who has `queued` queued or held jobs, may add `incoming` more.
+    pub(super) fn check_queued(
+        &self,
+        user: &str,
+        queued: usize,
+        incoming: usize,
+    ) -> Result<(), ApiError> {
+        match self.limits_for(user).max_queued_jobs {
+            Some(max) if queued + incoming > max => {
+                Err(ApiErrorKind::TooManyQueuedJobs { queued, max }.into())
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Jobs per submitting user, to charge a batch to each of them.
+pub(super) fn count_by_user(jobs: &[gflow::core::job::Job]) -> BTreeMap<&str, usize> {
+    let mut counts = BTreeMap::new();
+    for job in jobs {
+        *counts.entry(job.submitted_by.as_str()).or_default() += 1;
+    }
+    counts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_app::{code, post};
+    use super::*;
+    use axum::http::{header, StatusCode};
+    use gflow::core::job::Job;
+    use std::time::Duration;
+
+    /// The mutating API of a daemon with `config` as `[rate_limits]`.
+    fn app(dir: &std::path::Path, config: RateLimitsConfig) -> axum::Router {
+        super::super::test_app::app(dir, config, None)
+    }
+
+    fn job(user: &str) -> serde_json::Value {
+        let job = Job::builder().command("echo hi").submitted_by(user).build();
+        serde_json::to_value(job).unwrap()
+    }
+
+    #[tokio::test]
+    async fn submissions_over_the_rate_get_429_with_retry_after() {
+        let dir = tempfile::tempdir().unwrap();
+        let app = app(
+            dir.path(),
+            RateLimitsConfig {
+                submissions_per_minute: Some(5),
+                ..Default::default()
+            },
+        );
+
+        for _ in 0..5 {
+            let response = post(&app, "/jobs", job("alice")).await;
+            assert_eq!(response.status(), StatusCode::CREATED);
+        }
+        let response = post(&app, "/jobs", job("alice")).await;
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        let retry_after: u64 = response.headers()[header::RETRY_AFTER]
+            .to_str()
+            .unwrap()
+            .parse()
+            .unwrap();
+        assert!((1..=12).contains(&retry_after), "{retry_after}");
+        assert_eq!(code(response).await, "submission_rate_exceeded");
+
+        // Each job of a batch counts, and other users are not affected.
+        let batch = serde_json::json!([job("bob"), job("bob"), job("bob")]);
+        assert_eq!(
+            post(&app, "/jobs/batch", batch.clone()).await.status(),
+            StatusCode::CREATED
+        );
+        let response = post(&app, "/jobs/batch", batch).await;
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+    }
+
+    #[tokio::test]
+    async fn queued_job_cap_counts_batches_and_follows_gctl_overrides() {
+        let dir = tempfile::tempdir().unwrap();
+        let app = app(
+            dir.path(),
+            RateLimitsConfig {
+                max_queued_jobs: Some(3),
+                ..Default::default()
+            },
+        );
+
+        let batch = serde_json::json!([job("alice"), job("alice"), job("alice"), job("alice")]);
+        let response = post(&app, "/jobs/batch", batch.clone()).await;
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert!(response.headers().contains_key(header::RETRY_AFTER));
+        assert_eq!(code(response).await, "too_many_queued_jobs");
+
+        let override_body = serde_json::json!({"max_queued_jobs": 5});
+        let response = post(&app, "/rate-limits/alice", override_body).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            post(&app, "/jobs/batch", batch).await.status(),
+            StatusCode::CREATED
+        );
+        assert_eq!(
+            post(&app, "/jobs", job("alice")).await.status(),
+            StatusCode::CREATED
+        );
+        let response = post(&app, "/jobs", job("alice")).await;
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(code(response).await, "too_many_queued_jobs");
+    }
+
+    fn limiter(config: RateLimitsConfig) -> SubmissionLimiter {
+        SubmissionLimiter::new(watch::channel(config).1)
+    }
+
+    #[test]
+    fn bucket_refills_at_the_configured_rate() {
+        let limiter = limiter(RateLimitsConfig {
+            submissions_per_minute: Some(60),
+            ..Default::default()
+        });
+        let start = Instant::now();
+
+        limiter.take_at("alice", 60, start).unwrap();
+        let error = limiter.take_at("alice", 1, start).unwrap_err();
+        assert_eq!(
+            error.kind,
+            ApiErrorKind::SubmissionRateExceeded {
+                per_minute: 60,
+                retry_after_secs: 1,
+            }
+        );
+        assert_eq!(error.status(), 429);
+        // Other users have their own bucket.
+        limiter.take_at("bob", 1, start).unwrap();
+
+        let later = start + Duration::from_secs(5);
+        limiter.take_at("alice", 5, later).unwrap();
+        assert!(limiter.take_at("alice", 1, later).is_err());
+        assert!(limiter.take_at("alice", 61, later).is_err());
+    }
+
+    #[test]
+    fn overrides_take_precedence_over_config() {
+        let limiter = limiter(RateLimitsConfig {
+            max_queued_jobs: Some(10),
+            users: [(
+                "ci".to_string(),
+                UserRateLimit {
+                    max_queued_jobs: Some(100),
+                    ..Default::default()
+                },
+            )]
+            .into(),
+            ..Default::default()
+        });
+
+        assert!(limiter.check_queued("alice", 9, 1).is_ok());
+        assert_eq!(
+            limiter.check_queued("alice", 9, 2).unwrap_err().kind,
+            ApiErrorKind::TooManyQueuedJobs { queued: 9, max: 10 }
+        );
+        assert!(limiter.check_queued("ci", 50, 50).is_ok());
+
+        limiter.set_override(
+            "ci".to_string(),
+            UserRateLimit {
+                max_queued_jobs: Some(20),
+                ..Default::default()
+            },
+        );
+        assert!(limiter.check_queued("ci", 50, 1).is_err());
+        assert!(limiter.remove_override("ci"));
+        assert!(limiter.check_queued("ci", 50, 1).is_ok());
+    }
+}
@@ -0,0 +1,285 @@
+//! Read model for the listings clients poll
+//!
+//! A task copies what `GET /jobs`, `GET /jobs/{id}` and `GET /info` show into
+//! an immutable [`Snapshot`] whenever the scheduler's revision changes,
+//! checking every [`PUBLISH_INTERVAL`]. Those handlers read the live state
+//! when the lock is free, and the snapshot while someone holds or waits for
+//! the write lock, e.g. during a long scheduling pass, so listings never wait
+//! for it. A snapshot misses at most the changes of the last interval, plus
+//! those made while the lock was taken.
+
+use super::super::scheduler_runtime::{SchedulerRuntime, SharedState};
+use super::state::ServerState;
+use gflow::core::info::SchedulerInfo;
+use gflow::core::job::{Job, JobRuntime, JobSpec, JobState};
+use std::collections::{BTreeSet, HashMap};
+use std::ops::Deref;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{watch, RwLockReadGuard};
+
+/// How often the publisher looks for changes.
+pub(super) const PUBLISH_INTERVAL: Duration = Duration::from_millis(250);
+
+/// The latest snapshot; `None` until the first one is published.
+pub(super) type Snapshots = watch::Receiver<Option<Arc<Snapshot>>>;
+
+/// What the listing handlers read, from the live state or a [`Snapshot`].
+pub(super) trait JobsView {
+    fn revision(&self) -> u64;
+    fn info(&self) -> SchedulerInfo;
+    fn job_specs(&self) -> &[JobSpec];
+    fn job_runtimes(&self) -> &[JobRuntime];
+    fn job_ids_by_user(&self, user: &str) -> Option<&[u32]>;
+    fn job_ids_by_tag(&self, tag: &str) -> Option<&[u32]>;
+    fn job_ids_by_state(&self, state: JobState) -> Option<&BTreeSet<u32>>;
+
+    fn get_job(&self, id: u32) -> Option<Job> {
+        let idx = id.checked_sub(1)? as usize;
+        let spec = self.job_specs().get(idx)?;
+        let rt = self.job_runtimes().get(idx)?;
+        Some(Job::from_parts(spec.clone(), rt.clone()))
+    }
+}
+
+impl JobsView for SchedulerRuntime {
+    fn revision(&self) -> u64 {
+        SchedulerRuntime::revision(self)
+    }
+
+    fn info(&self) -> SchedulerInfo {
+        SchedulerRuntime::info(self)
+    }
+
+    fn job_specs(&self) -> &[JobSpec] {
+        SchedulerRuntime::job_specs(self)
+    }
+
+    fn job_runtimes(&self) -> &[JobRuntime] {
+        SchedulerRuntime::job_runtimes(self)
+    }
+
+    fn job_ids_by_user(&self, user: &str) -> Option<&[u32]> {
+        SchedulerRuntime::job_ids_by_user(self, user)
+    }
+
+    fn job_ids_by_tag(&self, tag: &str) -> Option<&[u32]> {
+        SchedulerRuntime::job_ids_by_tag(self, tag)
+    }
+
+    fn job_ids_by_state(&self, state: JobState) -> Option<&BTreeSet<u32>> {
+        SchedulerRuntime::job_ids_by_state(self, state)
+    }
+
+    fn get_job(&self, id: u32) -> Option<Job> {
+        SchedulerRuntime::get_job(self, id)
+    }
+}
+
+/// Jobs, their indexes and GPU info as of one revision
+pub(super) struct Snapshot {
+    revision: u64,
+    info: SchedulerInfo,
+    job_specs: Vec<JobSpec>,
+    job_runtimes: Vec<JobRuntime>,
+    user_jobs: HashMap<String, Vec<u32>>,
+    tag_jobs: HashMap<String, Vec<u32>>,
+    state_jobs: HashMap<JobState, BTreeSet<u32>>,
+}
+
+impl Snapshot {
+    fn of(state: &SchedulerRuntime) -> Self {
+        let job_specs = state.job_specs().to_vec();
+        let job_runtimes = state.job_runtimes().to_vec();
+        let mut user_jobs: HashMap<String, Vec<u32>> = HashMap::new();
+        let mut tag_jobs: HashMap<String, Vec<u32>> = HashMap::new();
+        for (id, spec) in (1..).zip(&job_specs) {
+            user_jobs
+                .entry(spec.submitted_by.to_string())
+                .or_default()
+                .push(id);
+            for tag in spec.tags.iter() {
+                tag_jobs.entry(tag.to_string()).or_default().push(id);
+            }
+        }
+        let mut state_jobs: HashMap<JobState, BTreeSet<u32>> = HashMap::new();
+        for rt in &job_runtimes {
+            state_jobs.entry(rt.state).or_default().insert(rt.id);
+        }
+        Self {
+            revision: state.revision(),
+            info: state.info(),
+            job_specs,
+            job_runtimes,
+            user_jobs,
+            tag_jobs,
+            state_jobs,
+        }
+    }
+}
+
+impl JobsView for Snapshot {
+    fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    fn info(&self) -> SchedulerInfo {
+        self.info.clone()
+    }
+
+    fn job_specs(&self) -> &[JobSpec] {
+        &self.job_specs
+    }
+
+    fn job_runtimes(&self) -> &[JobRuntime] {
+        &self.job_runtimes
+    }
+
+    fn job_ids_by_user(&self, user: &str) -> Option<&[u32]> {
+        self.user_jobs.get(user).map(Vec::as_slice)
+    }
+
+    fn job_ids_by_tag(&self, tag: &str) -> Option<&[u32]> {
+        self.tag_jobs.get(tag).map(Vec::as_slice)
+    }
+
+    fn job_ids_by_state(&self, state: JobState) -> Option<&BTreeSet<u32>> {
+        self.state_jobs.get(&state)
+    }
+}
+
+/// The live state under a read lock, or the latest snapshot.
+pub(super) enum Jobs<'a> {
+    Live(RwLockReadGuard<'a, SchedulerRuntime>),
+    Published(Arc<Snapshot>),
+}
+
+impl Deref for Jobs<'_> {
+    type Target = dyn JobsView;
+
+    fn deref(&self) -> &Self::Target {
+        match self {
+            Jobs::Live(guard) => &**guard,
+            Jobs::Published(snapshot) => &**snapshot,
+        }
+    }
+}
+
+impl ServerState {
+    /// The live state if the lock is free right now, else the latest
+    /// snapshot. Waits for the lock only before the first snapshot.
+    pub(super) async fn jobs(&self) -> Jobs<'_> {
+        if let Ok(guard) = self.scheduler.try_read() {
+            return Jobs::Live(guard);
+        }
+        let published = self.snapshots.borrow().clone();
+        match published {
+            Some(snapshot) => Jobs::Published(snapshot),
+            None => Jobs::Live(self.scheduler.read().await),
+        }
+    }
+}
+
+/// Publish a snapshot whenever the revision has changed, until no handler
+/// is left to read them.
+pub(super) async fn publish(state: SharedState, snapshots: watch::Sender<Option<Arc<Snapshot>>>) {
+    let mut published = None;
+    let mut interval = tokio::time::interval(PUBLISH_INTERVAL);
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    while !snapshots.is_closed() {
+        interval.tick().await;
+        let snapshot = {
+            let guard = state.read().await;
+            if published == Some(guard.revision()) {
+                continue;
+            }
+            Snapshot::of(&guard)
+        };
+        published = Some(snapshot.revision);
+        snapshots.send_replace(Some(Arc::new(snapshot)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_app;
+    use super::*;
+    use axum::body::Body;
+    use axum::http::{Request, StatusCode};
+    use std::time::Instant;
+    use tower::ServiceExt;
+
+    async fn list_jobs(app: &axum::Router) -> (Duration, Vec<Job>) {
+        let started = Instant::now();
+        let request = Request::builder().uri("/jobs").body(Body::empty()).unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (started.elapsed(), serde_json::from_slice(&body).unwrap())
+    }
+
+    async fn submit(app: &axum::Router) {
+        let job = Job::builder().command("true").submitted_by("alice").build();
+        let response = test_app::post(app, "/jobs", serde_json::to_value(job).unwrap()).await;
+        assert_eq!(response.status(), StatusCode::CREATED);
+    }
+
+    #[tokio::test]
+    async fn listings_do_not_wait_for_a_held_write_lock() {
+        let dir = tempfile::tempdir().unwrap();
+        let (app, scheduler) = test_app::app_with_scheduler(dir.path(), Default::default(), None);
+        for _ in 0..3 {
+            submit(&app).await;
+        }
+        tokio::time::sleep(PUBLISH_INTERVAL * 2).await;
+        let (unlocked, jobs) = list_jobs(&app).await;
+        assert_eq!(jobs.len(), 3);
+
+        // A long scheduling pass, with submissions and a cancellation
+        // queued up behind it.
+        let pass = {
+            let scheduler = Arc::clone(&scheduler);
+            tokio::spawn(async move {
+                let _guard = scheduler.write().await;
+                tokio::time::sleep(Duration::from_secs(2)).await;
+            })
+        };
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        let writers: Vec<_> = (0..3)
+            .map(|_| {
+                let app = app.clone();
+                tokio::spawn(async move { submit(&app).await })
+            })
+            .chain(std::iter::once({
+                let app = app.clone();
+                tokio::spawn(async move {
+                    let response =
+                        test_app::post(&app, "/jobs/1/cancel", serde_json::json!({})).await;
+                    assert_eq!(response.status(), StatusCode::OK);
+                })
+            }))
+            .collect();
+
+        let mut slowest = Duration::ZERO;
+        for _ in 0..20 {
+            let (latency, jobs) = list_jobs(&app).await;
+            assert_eq!(jobs.len(), 3, "served from the snapshot");
+            slowest = slowest.max(latency);
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+        assert!(!pass.is_finished(), "the lock was held throughout");
+        assert!(
+            slowest < unlocked + Duration::from_millis(250),
+            "listing took {slowest:?} behind the lock"
+        );
+
+        for writer in writers {
+            writer.await.unwrap();
+        }
+        let (_, jobs) = list_jobs(&app).await;
+        assert_eq!(jobs.len(), 6);
+        assert_eq!(jobs[0].state, JobState::Cancelled);
+    }
+}
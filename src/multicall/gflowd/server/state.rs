@@ -1,31 +1,78 @@
+use super::super::config_reload::ConfigReloader;
 use super::super::events::EventBus;
 use super::super::scheduler_runtime::SharedState;
+use super::super::shutdown::Shutdown;
 use super::super::state_saver::StateSaverHandle;
+use super::super::webhooks::{JobWebhookStore, WebhookOutbox};
+use super::auth::AuthPolicy;
+use super::client_versions::ClientVersions;
+use super::errors;
+use super::idempotency::SubmissionCache;
+use super::rate_limit::SubmissionLimiter;
+use super::snapshot::Snapshots;
 use axum::{
     http::StatusCode,
     response::{IntoResponse, Response},
     Json,
 };
+use gflow::core::api_error::{ApiError, ApiErrorKind};
 use std::sync::Arc;
+use std::time::Instant;
 
 /// Server state that includes both the scheduler and the event bus
 #[derive(Clone)]
 pub(super) struct ServerState {
     pub(super) scheduler: SharedState,
+    /// What listings read while the scheduler lock is taken.
+    pub(super) snapshots: Snapshots,
     pub(super) event_bus: Arc<EventBus>,
     pub(super) _state_saver: StateSaverHandle,
+    /// When this server instance started (used to report uptime).
+    pub(super) started_at: Instant,
+    pub(super) config_reloader: Arc<ConfigReloader>,
+    pub(super) auth: Arc<AuthPolicy>,
+    /// Recent submissions by idempotency key.
+    pub(super) submissions: Arc<SubmissionCache>,
+    /// Shutdown phase, reported by `/health`.
+    pub(super) shutdown: Shutdown,
+    /// Webhook deliveries waiting for a retry.
+    pub(super) webhook_outbox: Arc<WebhookOutbox>,
+    /// Webhooks users attached to their jobs.
+    pub(super) job_webhooks: Arc<JobWebhookStore>,
+    /// Per-user submission rate and queue limits.
+    pub(super) rate_limiter: Arc<SubmissionLimiter>,
+    /// Requests by outdated clients, reported by `/info/stats`.
+    pub(super) client_versions: Arc<ClientVersions>,
 }
 
 impl ServerState {
+    #[allow(clippy::too_many_arguments)]
     pub(super) fn new(
         scheduler: SharedState,
+        snapshots: Snapshots,
         event_bus: Arc<EventBus>,
         state_saver: StateSaverHandle,
+        config_reloader: Arc<ConfigReloader>,
+        auth: AuthPolicy,
+        shutdown: Shutdown,
+        webhook_outbox: Arc<WebhookOutbox>,
+        job_webhooks: Arc<JobWebhookStore>,
+        rate_limiter: SubmissionLimiter,
     ) -> Self {
         Self {
             scheduler,
+            snapshots,
             event_bus,
             _state_saver: state_saver,
+            started_at: Instant::now(),
+            config_reloader,
+            auth: Arc::new(auth),
+            submissions: Arc::default(),
+            shutdown,
+            webhook_outbox,
+            job_webhooks,
+            rate_limiter: Arc::new(rate_limiter),
+            client_versions: Arc::default(),
         }
     }
 }
@@ -39,18 +86,15 @@ pub(super) async fn reject_if_read_only(server_state: &ServerState) -> Option<Re
     let backup_path = state.state_backup_path().map(|p| p.display().to_string());
     let journal_path = state.journal_path().display().to_string();
 
-    Some(
-        (
-            StatusCode::SERVICE_UNAVAILABLE,
-            Json(serde_json::json!({
-                "error": "gflowd is in read-only mode (no persistence available)",
-                "detail": state.state_load_error(),
-                "state_backup": backup_path,
-                "journal": journal_path,
-                "journal_error": state.journal_error(),
-                "hint": "Fix/upgrade the version that can migrate your state.json, or restore from the backup file. If the journal path is unwritable, fix permissions."
-            })),
-        )
-            .into_response(),
-    )
+    let error = ApiError::new(
+        ApiErrorKind::ReadOnly,
+        "gflowd is in read-only mode (no persistence available)",
+    );
+    let mut body = errors::body(&error);
+    body["detail"] = state.state_load_error().into();
+    body["state_backup"] = backup_path.into();
+    body["journal"] = journal_path.into();
+    body["journal_error"] = state.journal_error().into();
+    body["hint"] = "Fix/upgrade the version that can migrate your state.json, or restore from the backup file. If the journal path is unwritable, fix permissions.".into();
+    Some((StatusCode::SERVICE_UNAVAILABLE, Json(body)).into_response())
 }
@@ -0,0 +1,119 @@
+//! An in-process daemon for handler tests, with no GPUs probed and state in
+//! a temporary directory.
+
+use super::auth::AuthPolicy;
+use super::rate_limit::SubmissionLimiter;
+use super::state::ServerState;
+use crate::multicall::gflowd::config_reload::{ConfigReloader, ConfigSource};
+use crate::multicall::gflowd::events::EventBus;
+use crate::multicall::gflowd::scheduler_runtime::{SchedulerRuntime, SharedState};
+use crate::multicall::gflowd::state_saver::StateSaverHandle;
+use crate::multicall::gflowd::webhooks::{JobWebhookStore, WebhookOutbox};
+use axum::body::Body;
+use axum::http::{header, Method, Request};
+use gflow::config::RateLimitsConfig;
+use gflow::core::executor::Executor;
+use gflow::core::job::Job;
+use std::path::Path;
+use std::sync::Arc;
+use tokio::sync::watch;
+use tower::ServiceExt;
+
+struct NoopExecutor;
+
+impl Executor for NoopExecutor {
+    fn execute(&self, _job: &Job) -> anyhow::Result<()> {
+        Ok(())
+    }
+}
+
+/// The API of a daemon with `rate_limits` as `[rate_limits]` and only
+/// `allowed_gpus` visible, if given.
+pub(super) fn app(
+    dir: &Path,
+    rate_limits: RateLimitsConfig,
+    allowed_gpus: Option<Vec<u32>>,
+) -> axum::Router {
+    app_with_scheduler(dir, rate_limits, allowed_gpus).0
+}
+
+/// [`app`], and its scheduler state for tests that take the lock themselves.
+/// Snapshots are published as by the daemon, so this needs a runtime.
+pub(super) fn app_with_scheduler(
+    dir: &Path,
+    rate_limits: RateLimitsConfig,
+    allowed_gpus: Option<Vec<u32>>,
+) -> (axum::Router, SharedState) {
+    let runtime = SchedulerRuntime::with_state_path(
+        Box::new(NoopExecutor),
+        dir.to_path_buf(),
+        allowed_gpus,
+        gflow::core::gpu_allocation::GpuAllocationStrategy::Sequential,
+        gflow::config::ProjectsConfig::default(),
+    )
+    .unwrap();
+    let scheduler = Arc::new(tokio::sync::RwLock::new(runtime));
+    let (rate_limits_tx, rate_limits_rx) = watch::channel(rate_limits);
+    let reloader = ConfigReloader::new(
+        ConfigSource::default(),
+        gflow::config::Config::default(),
+        Arc::clone(&scheduler),
+        watch::channel(Default::default()).0,
+        rate_limits_tx,
+    );
+    let (snapshots_tx, snapshots_rx) = watch::channel(None);
+    tokio::spawn(super::snapshot::publish(
+        Arc::clone(&scheduler),
+        snapshots_tx,
+    ));
+    let state = ServerState::new(
+        Arc::clone(&scheduler),
+        snapshots_rx,
+        Arc::new(EventBus::new(100)),
+        StateSaverHandle::new(tokio::sync::mpsc::unbounded_channel().0),
+        Arc::new(reloader),
+        AuthPolicy::disabled(),
+        Default::default(),
+        Arc::new(WebhookOutbox::open(dir.join("webhook_outbox.jsonl"))),
+        Arc::new(JobWebhookStore::open(dir.join("job_webhooks.json"))),
+        SubmissionLimiter::new(rate_limits_rx),
+    );
+    let routes = super::read_only_routes().merge(super::mutating_routes());
+    (super::with_common_layers(routes, state), scheduler)
+}
+
+pub(super) async fn post(
+    app: &axum::Router,
+    path: &str,
+    body: serde_json::Value,
+) -> axum::response::Response {
+    let request = Request::builder()
+        .method(Method::POST)
+        .uri(path)
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(body.to_string()))
+        .unwrap();
+    app.clone().oneshot(request).await.unwrap()
+}
+
+pub(super) async fn get(app: &axum::Router, path: &str) -> serde_json::Value {
+    let request = Request::builder()
+        .method(Method::GET)
+        .uri(path)
+        .body(Body::empty())
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    serde_json::from_slice(&body).unwrap()
+}
+
+/// The `code` of an error response body.
+pub(super) async fn code(response: axum::response::Response) -> String {
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    body["code"].as_str().unwrap_or_default().to_string()
+}
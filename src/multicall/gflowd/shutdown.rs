@@ -0,0 +1,202 @@
+//! Graceful shutdown of the daemon
+//!
+//! On SIGTERM/SIGINT the daemon stops launching jobs, then stops its listeners
+//! and saves state. With `--wait-running` it first keeps serving (so jobs can
+//! still report their outcome) until the running jobs are done or the limit
+//! runs out. SIGUSR2 hands over to a reloaded instance at once.
+
+use super::scheduler_runtime::SharedState;
+use gflow::core::job::JobState;
+use serde::Serialize;
+use std::time::Duration;
+use tokio::signal::unix::{signal, Signal, SignalKind};
+use tokio::sync::watch;
+
+/// How often a draining daemon checks on running jobs and saves state.
+const DRAIN_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// How long SIGTERM/SIGINT wait for running jobs (`--wait-running`).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) enum WaitRunning {
+    /// Exit right away; jobs keep running in their tmux sessions
+    #[default]
+    No,
+    /// Wait until every running job is done
+    Unlimited,
+    /// Wait at most this long
+    Limit(Duration),
+}
+
+impl WaitRunning {
+    /// From `--wait-running[=SECONDS]`; no value means no limit.
+    pub(crate) fn from_arg(arg: Option<Option<u64>>) -> Self {
+        match arg {
+            None => Self::No,
+            Some(None) => Self::Unlimited,
+            Some(Some(secs)) => Self::Limit(Duration::from_secs(secs)),
+        }
+    }
+}
+
+/// Where the daemon is in shutting down, as reported by `/health`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum ShutdownPhase {
+    /// Not launching jobs; waiting for running ones before stopping
+    Draining,
+    /// Listeners are closing and state is being saved
+    Stopping,
+}
+
+/// Shared view of the shutdown phase; `None` while running normally.
+#[derive(Clone)]
+pub(crate) struct Shutdown {
+    phase: watch::Sender<Option<ShutdownPhase>>,
+}
+
+impl Default for Shutdown {
+    fn default() -> Self {
+        Self {
+            phase: watch::Sender::new(None),
+        }
+    }
+}
+
+impl Shutdown {
+    pub(crate) fn phase(&self) -> Option<ShutdownPhase> {
+        *self.phase.borrow()
+    }
+
+    fn set(&self, phase: ShutdownPhase) {
+        self.phase.send_replace(Some(phase));
+    }
+
+    /// Resolves once listeners should stop accepting connections.
+    pub(crate) async fn stopping(&self) {
+        let mut phase = self.phase.subscribe();
+        // The sender lives as long as `self`, so this cannot fail.
+        let _ = phase
+            .wait_for(|phase| *phase == Some(ShutdownPhase::Stopping))
+            .await;
+    }
+
+    /// Wait for a shutdown signal and walk through the phases.
+    pub(crate) async fn run(self, scheduler: SharedState, wait_running: WaitRunning) {
+        let mut signals = Signals::new();
+        let reload = signals.recv().await;
+
+        scheduler.write().await.stop_launching();
+        if !reload && wait_running != WaitRunning::No {
+            self.set(ShutdownPhase::Draining);
+            tokio::select! {
+                _ = drain(&scheduler, wait_running) => {}
+                _ = signals.recv() => {
+                    tracing::warn!("Second shutdown signal; no longer waiting for running jobs");
+                }
+            }
+        }
+        self.set(ShutdownPhase::Stopping);
+    }
+}
+
+/// Wait for running jobs to end, saving state along the way.
+async fn drain(scheduler: &SharedState, wait_running: WaitRunning) {
+    let deadline = match wait_running {
+        WaitRunning::Limit(limit) => Some(tokio::time::Instant::now() + limit),
+        WaitRunning::No | WaitRunning::Unlimited => None,
+    };
+    let mut poll = tokio::time::interval(DRAIN_POLL_INTERVAL);
+    loop {
+        poll.tick().await;
+        let running = {
+            let mut state = scheduler.write().await;
+            state.save_state_if_dirty().await;
+            state
+                .job_ids_by_state(JobState::Running)
+                .map_or(0, |ids| ids.len())
+        };
+        if running == 0 {
+            tracing::info!("No jobs running; shutting down");
+            return;
+        }
+        if deadline.is_some_and(|deadline| tokio::time::Instant::now() >= deadline) {
+            tracing::warn!(
+                running,
+                "Gave up waiting for running jobs; shutting down while they run"
+            );
+            return;
+        }
+        tracing::info!(running, "Waiting for running jobs before shutting down");
+    }
+}
+
+struct Signals {
+    sigterm: Signal,
+    sigint: Signal,
+    sigusr2: Signal,
+}
+
+impl Signals {
+    fn new() -> Self {
+        Self {
+            sigterm: signal(SignalKind::terminate()).expect("Failed to register SIGTERM handler"),
+            sigint: signal(SignalKind::interrupt()).expect("Failed to register SIGINT handler"),
+            sigusr2: signal(SignalKind::user_defined2())
+                .expect("Failed to register SIGUSR2 handler"),
+        }
+    }
+
+    /// Wait for the next signal; `true` for SIGUSR2, i.e. a hot reload.
+    async fn recv(&mut self) -> bool {
+        tokio::select! {
+            _ = self.sigterm.recv() => {
+                tracing::info!(signal = "SIGTERM", "Initiating graceful shutdown");
+                false
+            }
+            _ = self.sigint.recv() => {
+                tracing::info!(signal = "SIGINT", "Initiating graceful shutdown");
+                false
+            }
+            _ = self.sigusr2.recv() => {
+                tracing::info!(signal = "SIGUSR2", reload = true, "Initiating graceful shutdown");
+                true
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_wait_running_argument() {
+        assert_eq!(WaitRunning::from_arg(None), WaitRunning::No);
+        assert_eq!(WaitRunning::from_arg(Some(None)), WaitRunning::Unlimited);
+        assert_eq!(
+            WaitRunning::from_arg(Some(Some(600))),
+            WaitRunning::Limit(Duration::from_secs(600))
+        );
+    }
+
+    #[tokio::test]
+    async fn stopping_resolves_only_in_the_stopping_phase() {
+        let shutdown = Shutdown::default();
+        assert_eq!(shutdown.phase(), None);
+
+        let waiter = tokio::spawn({
+            let shutdown = shutdown.clone();
+            async move { shutdown.stopping().await }
+        });
+        shutdown.set(ShutdownPhase::Draining);
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(!waiter.is_finished());
+
+        shutdown.set(ShutdownPhase::Stopping);
+        tokio::time::timeout(Duration::from_secs(1), waiter)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(shutdown.phase(), Some(ShutdownPhase::Stopping));
+    }
+}
@@ -97,8 +97,8 @@ pub async fn run(
     save_interval: Duration,
 ) {
     tracing::info!(
-        "State saver started with save interval: {}s",
-        save_interval.as_secs()
+        "State saver started with save interval: {}ms",
+        save_interval.as_millis()
     );
 
     let mut interval = tokio::time::interval(save_interval);
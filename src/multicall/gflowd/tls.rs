@@ -0,0 +1,122 @@
+//! HTTPS for TCP listeners when `[daemon] tls_cert` and `tls_key` are set
+//!
+//! The certificate and key are loaded once at startup; any problem with them
+//! aborts startup with the offending path and config key in the message.
+//! Unix socket listeners always serve plain HTTP.
+
+use anyhow::{bail, Context, Result};
+use rustls::pki_types::pem::PemObject;
+use rustls::pki_types::{CertificateDer, PrivateKeyDer};
+use std::path::Path;
+use std::sync::Arc;
+
+/// Build the rustls server config from `[daemon]`, or `None` when TLS is off.
+pub(crate) fn server_config(
+    daemon: &gflow::config::DaemonConfig,
+) -> Result<Option<Arc<rustls::ServerConfig>>> {
+    let (cert_path, key_path) = match (&daemon.tls_cert, &daemon.tls_key) {
+        (None, None) => return Ok(None),
+        (Some(cert), Some(key)) => (cert, key),
+        (Some(_), None) => bail!("daemon.tls_cert is set but daemon.tls_key is missing"),
+        (None, Some(_)) => bail!("daemon.tls_key is set but daemon.tls_cert is missing"),
+    };
+    load(cert_path, key_path).map(Some)
+}
+
+fn load(cert_path: &Path, key_path: &Path) -> Result<Arc<rustls::ServerConfig>> {
+    gflow::tls::ensure_rustls_provider_installed();
+
+    let cert_pem = std::fs::read(cert_path)
+        .with_context(|| format!("Failed to read daemon.tls_cert {}", cert_path.display()))?;
+    let certs = CertificateDer::pem_slice_iter(&cert_pem)
+        .collect::<Result<Vec<_>, _>>()
+        .with_context(|| format!("Invalid PEM in daemon.tls_cert {}", cert_path.display()))?;
+    if certs.is_empty() {
+        bail!(
+            "daemon.tls_cert {} contains no certificates",
+            cert_path.display()
+        );
+    }
+
+    let key_pem = std::fs::read(key_path)
+        .with_context(|| format!("Failed to read daemon.tls_key {}", key_path.display()))?;
+    let key = PrivateKeyDer::from_pem_slice(&key_pem).with_context(|| {
+        format!(
+            "No private key found in daemon.tls_key {}",
+            key_path.display()
+        )
+    })?;
+
+    let mut config = rustls::ServerConfig::builder()
+        .with_no_client_auth()
+        .with_single_cert(certs, key)
+        .with_context(|| {
+            format!(
+                "daemon.tls_key {} does not match daemon.tls_cert {}",
+                key_path.display(),
+                cert_path.display()
+            )
+        })?;
+    config.alpn_protocols = vec![b"http/1.1".to_vec()];
+    Ok(Arc::new(config))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gflow::config::DaemonConfig;
+
+    fn write_self_signed(dir: &Path) -> (std::path::PathBuf, std::path::PathBuf) {
+        let cert = rcgen::generate_simple_self_signed(vec!["localhost".to_string()]).unwrap();
+        let cert_path = dir.join("cert.pem");
+        let key_path = dir.join("key.pem");
+        std::fs::write(&cert_path, cert.cert.pem()).unwrap();
+        std::fs::write(&key_path, cert.signing_key.serialize_pem()).unwrap();
+        (cert_path, key_path)
+    }
+
+    #[test]
+    fn loads_matching_cert_and_key() {
+        let dir = tempfile::tempdir().unwrap();
+        let (cert, key) = write_self_signed(dir.path());
+        let daemon = DaemonConfig {
+            tls_cert: Some(cert),
+            tls_key: Some(key),
+            ..Default::default()
+        };
+        assert!(server_config(&daemon).unwrap().is_some());
+        assert!(server_config(&DaemonConfig::default()).unwrap().is_none());
+    }
+
+    #[test]
+    fn reports_which_file_is_wrong() {
+        let dir = tempfile::tempdir().unwrap();
+        let (cert, key) = write_self_signed(dir.path());
+        let missing = dir.path().join("missing.pem");
+        let error = |tls_cert, tls_key| {
+            let daemon = DaemonConfig {
+                tls_cert,
+                tls_key,
+                ..Default::default()
+            };
+            format!("{:#}", server_config(&daemon).unwrap_err())
+        };
+
+        let message = error(Some(cert.clone()), Some(missing.clone()));
+        assert!(
+            message.contains("Failed to read daemon.tls_key"),
+            "{message}"
+        );
+        assert!(message.contains("missing.pem"), "{message}");
+
+        std::fs::write(&missing, "not pem").unwrap();
+        let message = error(Some(cert.clone()), Some(missing));
+        assert!(message.contains("No private key found"), "{message}");
+
+        let message = error(Some(key.clone()), Some(key.clone()));
+        assert!(message.contains("contains no certificates"), "{message}");
+
+        let message = error(Some(cert), None);
+        assert!(message.contains("daemon.tls_key is missing"), "{message}");
+    }
+}
@@ -0,0 +1,288 @@
+//! API tokens for `[daemon] require_auth = true`
+//!
+//! Tokens are created with `gflowd token create <user>` and stored as salted
+//! SHA-256 hashes in `tokens.json` in the data directory (mode 0600). The
+//! daemon re-reads the file when it changes, so new and revoked tokens take
+//! effect without a restart.
+//!
+//! The file also holds a secret used to derive job tokens: each job gets a
+//! token that can only mark that job finished or failed, which the executor
+//! exports as `GFLOW_JOB_TOKEN` for the `gcancel --finish/--fail` callback.
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+const USER_TOKEN_PREFIX: &str = "gflow_";
+const JOB_TOKEN_PREFIX: &str = "gflowjob_";
+
+/// Who made an authenticated request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Caller {
+    User {
+        username: String,
+        admin: bool,
+    },
+    /// A job reporting its own completion.
+    Job(u32),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct TokenRecord {
+    pub(crate) id: String,
+    pub(crate) username: String,
+    #[serde(default)]
+    pub(crate) admin: bool,
+    pub(crate) created_at: String,
+    salt: String,
+    hash: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct TokenFile {
+    #[serde(default)]
+    job_secret: Option<String>,
+    #[serde(default)]
+    tokens: Vec<TokenRecord>,
+}
+
+#[derive(Default)]
+struct Cached {
+    modified: Option<SystemTime>,
+    file: TokenFile,
+}
+
+pub(crate) struct TokenStore {
+    path: PathBuf,
+    cache: Mutex<Cached>,
+}
+
+impl TokenStore {
+    pub(crate) fn open(path: PathBuf) -> Self {
+        Self {
+            path,
+            cache: Mutex::new(Cached::default()),
+        }
+    }
+
+    pub(crate) fn open_default() -> Result<Self> {
+        Ok(Self::open(
+            gflow::paths::get_data_dir()?.join("tokens.json"),
+        ))
+    }
+
+    /// Create a token for `username` and return its record and the plaintext
+    /// token, which is not stored anywhere.
+    pub(crate) fn create(&self, username: &str, admin: bool) -> Result<(TokenRecord, String)> {
+        let username = username.trim();
+        if username.is_empty() {
+            bail!("Username must not be empty");
+        }
+
+        let mut file = self.read_file()?;
+        let id = loop {
+            let id = random_hex(4)?;
+            if !file.tokens.iter().any(|t| t.id == id) {
+                break id;
+            }
+        };
+        let secret = random_hex(32)?;
+        let salt = random_hex(16)?;
+        let record = TokenRecord {
+            id: id.clone(),
+            username: username.to_string(),
+            admin,
+            created_at: chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+            hash: hash_secret(&salt, &secret),
+            salt,
+        };
+        file.tokens.push(record.clone());
+        self.write_file(&file)?;
+
+        Ok((record, format!("{USER_TOKEN_PREFIX}{id}_{secret}")))
+    }
+
+    pub(crate) fn list(&self) -> Result<Vec<TokenRecord>> {
+        Ok(self.read_file()?.tokens)
+    }
+
+    /// Remove the token with `id`. Returns false if there is no such token.
+    pub(crate) fn revoke(&self, id: &str) -> Result<bool> {
+        let mut file = self.read_file()?;
+        let before = file.tokens.len();
+        file.tokens.retain(|t| t.id != id);
+        if file.tokens.len() == before {
+            return Ok(false);
+        }
+        self.write_file(&file)?;
+        Ok(true)
+    }
+
+    /// Make sure a job token secret exists, creating the file if needed.
+    pub(crate) fn ensure_job_secret(&self) -> Result<()> {
+        let mut file = self.read_file()?;
+        if file.job_secret.is_none() {
+            file.job_secret = Some(random_hex(32)?);
+            self.write_file(&file)?;
+        }
+        Ok(())
+    }
+
+    /// Token that only allows job `job_id` to report its own completion.
+    pub(crate) fn job_token(&self, job_id: u32) -> Result<String> {
+        let secret = self
+            .with_current(|file| file.job_secret.clone())?
+            .context("No job token secret; the daemon was started without require_auth")?;
+        Ok(format!(
+            "{JOB_TOKEN_PREFIX}{job_id}_{}",
+            hash_secret(&secret, &job_id.to_string())
+        ))
+    }
+
+    /// Resolve a bearer token to its caller, or `None` if it is not valid.
+    pub(crate) fn authenticate(&self, token: &str) -> Option<Caller> {
+        if let Some(rest) = token.strip_prefix(JOB_TOKEN_PREFIX) {
+            let (job_id, _) = rest.split_once('_')?;
+            let job_id: u32 = job_id.parse().ok()?;
+            let expected = self.job_token(job_id).ok()?;
+            return constant_time_eq(token.as_bytes(), expected.as_bytes())
+                .then_some(Caller::Job(job_id));
+        }
+
+        let (id, secret) = token.strip_prefix(USER_TOKEN_PREFIX)?.split_once('_')?;
+        self.with_current(|file| {
+            file.tokens
+                .iter()
+                .find(|t| t.id == id)
+                .filter(|t| {
+                    constant_time_eq(hash_secret(&t.salt, secret).as_bytes(), t.hash.as_bytes())
+                })
+                .map(|t| Caller::User {
+                    username: t.username.clone(),
+                    admin: t.admin,
+                })
+        })
+        .ok()
+        .flatten()
+    }
+
+    /// Run `f` on the cached file contents, re-reading the file if it changed.
+    fn with_current<T>(&self, f: impl FnOnce(&TokenFile) -> T) -> Result<T> {
+        let modified = std::fs::metadata(&self.path)
+            .and_then(|m| m.modified())
+            .ok();
+        let mut cache = self.cache.lock().unwrap_or_else(|e| e.into_inner());
+        if cache.modified != modified || modified.is_none() {
+            cache.file = self.read_file()?;
+            cache.modified = modified;
+        }
+        Ok(f(&cache.file))
+    }
+
+    fn read_file(&self) -> Result<TokenFile> {
+        match std::fs::read_to_string(&self.path) {
+            Ok(content) => serde_json::from_str(&content)
+                .with_context(|| format!("Failed to parse {}", self.path.display())),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(TokenFile::default()),
+            Err(e) => Err(e).with_context(|| format!("Failed to read {}", self.path.display())),
+        }
+    }
+
+    fn write_file(&self, file: &TokenFile) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        let tmp_path = self.path.with_extension("json.tmp");
+        write_private(&tmp_path, &serde_json::to_vec_pretty(file)?)?;
+        std::fs::rename(&tmp_path, &self.path)
+            .with_context(|| format!("Failed to write {}", self.path.display()))
+    }
+}
+
+fn write_private(path: &Path, content: &[u8]) -> Result<()> {
+    use std::io::Write;
+    let _ = std::fs::remove_file(path);
+    let mut file = std::fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .mode(0o600)
+        .open(path)
+        .with_context(|| format!("Failed to create {}", path.display()))?;
+    file.write_all(content)
+        .with_context(|| format!("Failed to write {}", path.display()))
+}
+
+pub(super) fn random_hex(len: usize) -> Result<String> {
+    let mut bytes = vec![0u8; len];
+    getrandom::fill(&mut bytes)
+        .map_err(|e| anyhow::anyhow!("Failed to generate random bytes: {e}"))?;
+    Ok(to_hex(&bytes))
+}
+
+fn hash_secret(salt: &str, secret: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(salt.as_bytes());
+    hasher.update(b":");
+    hasher.update(secret.as_bytes());
+    to_hex(&hasher.finalize())
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+pub(super) fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::PermissionsExt;
+
+    #[test]
+    fn created_tokens_authenticate_until_revoked() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("tokens.json");
+        let store = TokenStore::open(path.clone());
+
+        let (record, token) = store.create("alice", false).unwrap();
+        assert_eq!(
+            store.authenticate(&token),
+            Some(Caller::User {
+                username: "alice".to_string(),
+                admin: false
+            })
+        );
+        assert_eq!(store.authenticate(&format!("{token}x")), None);
+        assert_eq!(store.authenticate("gflow_nope_nope"), None);
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(!content.contains(token.rsplit('_').next().unwrap()));
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+
+        // A separate process (`gflowd token revoke`) edits the same file.
+        assert!(TokenStore::open(path).revoke(&record.id).unwrap());
+        assert_eq!(store.authenticate(&token), None);
+    }
+
+    #[test]
+    fn job_tokens_are_scoped_to_their_job() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = TokenStore::open(dir.path().join("tokens.json"));
+        assert!(store.job_token(1).is_err());
+
+        store.ensure_job_secret().unwrap();
+        let token = store.job_token(7).unwrap();
+        assert_eq!(store.authenticate(&token), Some(Caller::Job(7)));
+
+        let forged = token.replacen("gflowjob_7_", "gflowjob_8_", 1);
+        assert_eq!(store.authenticate(&forged), None);
+    }
+}
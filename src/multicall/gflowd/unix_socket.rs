@@ -0,0 +1,112 @@
+//! Unix domain socket listener for `[daemon] listen = "unix:..."`
+//!
+//! The socket file is made owner-only (0600), so only the user running gflowd
+//! can submit or cancel jobs. If the socket's directory does not exist it is
+//! created with 0700; an existing directory is left as is.
+//!
+//! During a hot reload the new instance unlinks the old socket file and binds a
+//! fresh one, while the old instance keeps serving connections it already
+//! accepted. On exit an instance only removes the socket file if it is still
+//! the one it bound.
+
+use anyhow::Context;
+use std::os::unix::fs::{DirBuilderExt, FileTypeExt, MetadataExt, PermissionsExt};
+use std::path::{Path, PathBuf};
+use tokio::net::UnixListener;
+
+/// Removes the socket file when dropped, unless another instance has replaced it.
+pub(crate) struct UnixSocketFile {
+    path: PathBuf,
+    ino: u64,
+}
+
+impl Drop for UnixSocketFile {
+    fn drop(&mut self) {
+        if socket_inode(&self.path) == Some(self.ino) {
+            let _ = std::fs::remove_file(&self.path);
+        }
+    }
+}
+
+fn socket_inode(path: &Path) -> Option<u64> {
+    std::fs::symlink_metadata(path).ok().map(|m| m.ino())
+}
+
+pub(crate) fn bind(path: &Path) -> anyhow::Result<(UnixListener, UnixSocketFile)> {
+    if let Some(parent) = path.parent() {
+        if !parent.exists() {
+            std::fs::DirBuilder::new()
+                .recursive(true)
+                .mode(0o700)
+                .create(parent)
+                .with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+    }
+
+    match std::fs::symlink_metadata(path) {
+        Ok(metadata) if metadata.file_type().is_socket() => {
+            // Left behind by a crashed daemon, or the instance being hot-reloaded.
+            std::fs::remove_file(path)
+                .with_context(|| format!("Failed to remove stale socket {}", path.display()))?;
+        }
+        Ok(_) => anyhow::bail!(
+            "Cannot listen on {}: path exists and is not a socket",
+            path.display()
+        ),
+        Err(_) => {}
+    }
+
+    let listener = UnixListener::bind(path)
+        .with_context(|| format!("Failed to bind Unix socket {}", path.display()))?;
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))
+        .with_context(|| format!("Failed to set permissions on {}", path.display()))?;
+    let ino = socket_inode(path)
+        .with_context(|| format!("Socket {} disappeared after bind", path.display()))?;
+
+    Ok((
+        listener,
+        UnixSocketFile {
+            path: path.to_path_buf(),
+            ino,
+        },
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn binds_owner_only_socket_in_new_private_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("run").join("gflow.sock");
+
+        let (_listener, socket_file) = bind(&path).unwrap();
+        let dir_mode = std::fs::metadata(path.parent().unwrap())
+            .unwrap()
+            .permissions()
+            .mode();
+        assert_eq!(dir_mode & 0o777, 0o700);
+        let socket_mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(socket_mode & 0o777, 0o600);
+
+        drop(socket_file);
+        assert!(!path.exists());
+    }
+
+    #[tokio::test]
+    async fn rebinding_replaces_socket_and_keeps_it_on_old_drop() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("gflow.sock");
+
+        let (_old_listener, old_file) = bind(&path).unwrap();
+        let (_new_listener, new_file) = bind(&path).unwrap();
+        drop(old_file);
+        assert!(path.exists(), "old instance must not remove the new socket");
+        drop(new_file);
+        assert!(!path.exists());
+
+        std::fs::write(&path, "not a socket").unwrap();
+        assert!(bind(&path).is_err());
+    }
+}
@@ -1,5 +1,9 @@
+mod job_hooks;
+mod outbox;
+
 use super::events::{EventBus, EventEnvelope, SchedulerEvent};
 use super::scheduler_runtime::SchedulerRuntime;
+use gflow::client::JOB_WEBHOOK_EVENTS;
 use gflow::config::{NotificationsConfig, WebhookConfig};
 use gflow::core::job::{Job, JobState};
 use gflow::core::reservation::GpuReservation;
@@ -10,14 +14,26 @@ use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use tokio::sync::{RwLock, Semaphore};
 use tracing::Instrument;
 
+pub(crate) use job_hooks::{JobWebhook, JobWebhookStore};
+pub(crate) use outbox::WebhookOutbox;
+
+/// How often the notifier looks for outbox deliveries that are due.
+const OUTBOX_DRAIN_INTERVAL: Duration = Duration::from_secs(5);
+
 pub(crate) fn spawn_webhook_notifier(
     notifications: NotificationsConfig,
     semaphore: Arc<Semaphore>,
     scheduler: Arc<RwLock<SchedulerRuntime>>,
     event_bus: Arc<EventBus>,
     scheduler_host: String,
+    outbox: Arc<WebhookOutbox>,
+    job_hooks: Arc<JobWebhookStore>,
 ) -> Option<tokio::task::JoinHandle<()>> {
-    if !notifications.enabled || notifications.webhooks.is_empty() {
+    // Runs without configured targets too, to deliver webhooks users attach
+    // to their jobs.
+    job_hooks.set_deliverable(false);
+    if !notifications.enabled {
+        outbox.set_targets(vec![]);
         return None;
     }
 
@@ -27,9 +43,11 @@ pub(crate) fn spawn_webhook_notifier(
         Ok(t) => t,
         Err(e) => {
             tracing::error!("Webhook notifier disabled due to invalid config: {e}");
+            outbox.set_targets(vec![]);
             return None;
         }
     };
+    outbox.set_targets(targets.iter().map(|t| t.url.clone()).collect());
 
     let concurrency = notifications.max_concurrent_deliveries.max(1);
 
@@ -49,15 +67,45 @@ pub(crate) fn spawn_webhook_notifier(
         targets.len(),
         concurrency
     );
+    job_hooks.set_deliverable(true);
 
     // Subscribe before spawning so no events are missed (e.g. DaemonStarted).
     let rx = event_bus.subscribe();
 
+    let limits = OutboxLimits {
+        max_entries: notifications.outbox_max_entries,
+        max_age: Duration::from_secs(notifications.outbox_max_age_secs),
+    };
+    let notifier = WebhookNotifier {
+        targets,
+        client,
+        semaphore,
+        outbox,
+        job_hooks,
+        limits,
+    };
     Some(tokio::spawn(async move {
-        run_webhook_notifier(targets, client, semaphore, scheduler, rx, scheduler_host).await;
+        run_webhook_notifier(notifier, scheduler, rx, scheduler_host).await;
     }))
 }
 
+#[derive(Clone, Copy)]
+struct OutboxLimits {
+    max_entries: usize,
+    max_age: Duration,
+}
+
+/// Everything a delivery task needs, cheap to clone.
+#[derive(Clone)]
+struct WebhookNotifier {
+    targets: WebhookTargets,
+    client: Arc<reqwest::Client>,
+    semaphore: Arc<Semaphore>,
+    outbox: Arc<WebhookOutbox>,
+    job_hooks: Arc<JobWebhookStore>,
+    limits: OutboxLimits,
+}
+
 #[derive(Clone)]
 struct WebhookTarget {
     url: String,
@@ -76,6 +124,10 @@ impl WebhookTargets {
         self.0.len()
     }
 
+    fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
     fn iter(&self) -> impl Iterator<Item = &WebhookTarget> {
         self.0.iter()
     }
@@ -151,15 +203,47 @@ impl EventMatcher {
 }
 
 async fn run_webhook_notifier(
-    targets: WebhookTargets,
-    client: Arc<reqwest::Client>,
-    semaphore: Arc<Semaphore>,
+    notifier: WebhookNotifier,
     scheduler: Arc<RwLock<SchedulerRuntime>>,
     mut rx: tokio::sync::broadcast::Receiver<EventEnvelope>,
     scheduler_host: String,
 ) {
+    let mut drain_tick = tokio::time::interval(OUTBOX_DRAIN_INTERVAL);
+    drain_tick.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    let mut drain: Option<tokio::task::JoinHandle<()>> = None;
+
+    // Webhooks of jobs that ended while no notifier was running can never fire.
+    let job_ids = notifier.job_hooks.job_ids();
+    if !job_ids.is_empty() {
+        let state = scheduler.read().await;
+        let ended: HashSet<u32> = job_ids
+            .into_iter()
+            .filter(|&id| state.get_job(id).is_none_or(|job| job.state.is_final()))
+            .collect();
+        drop(state);
+        notifier.job_hooks.retain_jobs(|id| !ended.contains(&id));
+    }
+
     loop {
-        let event = match rx.recv().await {
+        let received = tokio::select! {
+            received = rx.recv() => received,
+            _ = drain_tick.tick() => {
+                // Like immediate deliveries, a running drain finishes even if
+                // the notifier is restarted; never start a second one.
+                if drain.as_ref().is_none_or(|d| d.is_finished())
+                    && notifier.outbox.pending() > 0
+                {
+                    let notifier = notifier.clone();
+                    drain = Some(tokio::spawn(
+                        async move { drain_outbox(&notifier, SystemTime::now()).await }
+                            .instrument(tracing::info_span!("webhook_outbox_drain")),
+                    ));
+                }
+                continue;
+            }
+        };
+
+        let event = match received {
             Ok(e) => e,
             Err(tokio::sync::broadcast::error::RecvError::Lagged(skipped)) => {
                 tracing::warn!(skipped, "Webhook notifier lagged behind");
@@ -171,15 +255,28 @@ async fn run_webhook_notifier(
             }
         };
 
+        if notifier.targets.is_empty()
+            && !final_event_job(&event.event).is_some_and(|id| notifier.job_hooks.has(id))
+        {
+            continue;
+        }
+
         let handling_span = event.handling_span("webhook_notifier");
         let _entered = handling_span.enter();
-        let payloads = build_payloads(&scheduler, &scheduler_host, &event.event).await;
+        let mut payloads = build_payloads(&scheduler, &scheduler_host, &event.event).await;
         if payloads.is_empty() {
             continue;
         }
+        for payload in &mut payloads {
+            payload.request_id.clone_from(&event.request_id);
+        }
 
         for payload in &payloads {
-            for target in targets.iter() {
+            if deliver_job_webhooks(&notifier, payload).await.is_err() {
+                return;
+            }
+
+            for target in notifier.targets.iter() {
                 if !target.matcher.matches(&payload.event) {
                     continue;
                 }
@@ -193,12 +290,19 @@ async fn run_webhook_notifier(
                     }
                 }
 
-                let permit = match semaphore.clone().acquire_owned().await {
+                // Keep events in order behind deliveries already waiting for
+                // this target, and avoid hammering a receiver that is down.
+                if notifier.outbox.has_pending(&target.url) {
+                    enqueue_payload(&notifier, target, payload);
+                    continue;
+                }
+
+                let permit = match notifier.semaphore.clone().acquire_owned().await {
                     Ok(p) => p,
                     Err(_) => return,
                 };
 
-                let client = Arc::clone(&client);
+                let notifier = notifier.clone();
                 let target = target.clone();
                 let payload = payload.clone();
                 let delivery_span = tracing::info_span!(
@@ -209,13 +313,20 @@ async fn run_webhook_notifier(
                 tokio::spawn(
                     async move {
                         let _permit = permit;
-                        if let Err(e) = deliver_with_retries(client, &target, &payload).await {
-                            tracing::warn!(
-                                event = %payload.event,
-                                target_url = %target.url,
-                                error = %e,
-                                "Webhook delivery failed"
-                            );
+                        match deliver_with_retries(&notifier.client, &target, &payload).await {
+                            Ok(()) => notifier.outbox.record_success(&target.url),
+                            Err(e) => {
+                                tracing::warn!(
+                                    event = %payload.event,
+                                    target_url = %target.url,
+                                    error = %e,
+                                    "Webhook delivery failed"
+                                );
+                                notifier.outbox.record_error(&target.url, &e.to_string());
+                                if matches!(e, DeliveryError::Failed(_)) {
+                                    enqueue_payload(&notifier, &target, &payload);
+                                }
+                            }
                         }
                     }
                     .instrument(delivery_span),
@@ -225,19 +336,194 @@ async fn run_webhook_notifier(
     }
 }
 
+/// The job an event reports the end of, if any.
+fn final_event_job(event: &SchedulerEvent) -> Option<u32> {
+    match event {
+        SchedulerEvent::JobCompleted { job_id, .. }
+        | SchedulerEvent::JobTimedOut { job_id, .. } => Some(*job_id),
+        _ => None,
+    }
+}
+
+/// Send the webhooks attached to the job `payload` reports the end of, with
+/// the default timeout and retries, and detach them. Errs once the delivery
+/// semaphore is closed.
+async fn deliver_job_webhooks(
+    notifier: &WebhookNotifier,
+    payload: &WebhookPayload,
+) -> Result<(), tokio::sync::AcquireError> {
+    let Some(job) = payload.job.as_ref() else {
+        return Ok(());
+    };
+    if !JOB_WEBHOOK_EVENTS.contains(&payload.event.as_str()) {
+        return Ok(());
+    }
+
+    for hook in notifier.job_hooks.take(job.id) {
+        let target_url = outbox::redact_url(&hook.url);
+        if !hook.wants(&payload.event) {
+            tracing::info!(
+                job_id = job.id,
+                event = %payload.event,
+                target_url = %target_url,
+                requested_by = %hook.requested_by,
+                "Job ended with an event its webhook does not want; dropping the webhook"
+            );
+            continue;
+        }
+        let target = match WebhookTargets::try_from_config(&[WebhookConfig::new(&hook.url)]) {
+            Ok(targets) => targets.0.into_iter().next().expect("one target per config"),
+            Err(e) => {
+                tracing::warn!(job_id = job.id, error = %e, "Skipping invalid job webhook");
+                continue;
+            }
+        };
+
+        tracing::info!(
+            job_id = job.id,
+            event = %payload.event,
+            target_url = %target_url,
+            requested_by = %hook.requested_by,
+            "Sending job webhook"
+        );
+        let permit = notifier.semaphore.clone().acquire_owned().await?;
+        let client = Arc::clone(&notifier.client);
+        let payload = job_webhook_payload(payload);
+        let delivery_span = tracing::info_span!(
+            "job_webhook_delivery",
+            job_id = job.id,
+            event = %payload.event,
+            target_url = %target_url
+        );
+        tokio::spawn(
+            async move {
+                let _permit = permit;
+                match deliver_with_retries(&client, &target, &payload).await {
+                    Ok(()) => tracing::info!("Job webhook delivered"),
+                    Err(e) => tracing::warn!(error = %e, "Job webhook delivery failed"),
+                }
+            }
+            .instrument(delivery_span),
+        );
+    }
+    Ok(())
+}
+
+/// `payload` with a one-line summary, which chat services such as Slack show.
+fn job_webhook_payload(payload: &WebhookPayload) -> WebhookPayload {
+    let mut payload = payload.clone();
+    if let Some(job) = &payload.job {
+        let outcome = match payload.event.as_str() {
+            "job_completed" => "finished",
+            "job_failed" => "failed",
+            "job_cancelled" => "was cancelled",
+            _ => "timed out",
+        };
+        let name = job
+            .name
+            .as_deref()
+            .map(|name| format!(" ({name})"))
+            .unwrap_or_default();
+        payload.text = Some(format!(
+            "gflow job {}{name} {outcome} on {}",
+            job.id, payload.scheduler.host
+        ));
+    }
+    payload
+}
+
+fn enqueue_payload(notifier: &WebhookNotifier, target: &WebhookTarget, payload: &WebhookPayload) {
+    match serde_json::to_value(payload) {
+        Ok(value) => notifier.outbox.enqueue(
+            &target.url,
+            &payload.event,
+            value,
+            notifier.limits.max_entries,
+        ),
+        Err(e) => tracing::error!(error = %e, "Failed to serialize webhook payload"),
+    }
+}
+
+/// Retry the outbox: drop expired deliveries, then send each target's due
+/// deliveries oldest first, stopping at the first one that fails again.
+async fn drain_outbox(notifier: &WebhookNotifier, now: SystemTime) {
+    notifier.outbox.expire(now, notifier.limits.max_age);
+
+    let mut per_target = tokio::task::JoinSet::new();
+    for target in notifier.targets.iter() {
+        if notifier.outbox.due_head(&target.url, now).is_none() {
+            continue;
+        }
+        let notifier = notifier.clone();
+        let target = target.clone();
+        per_target.spawn(async move {
+            let mut next = notifier.outbox.due_head(&target.url, now);
+            while let Some(delivery) = next {
+                let Ok(_permit) = notifier.semaphore.acquire().await else {
+                    return;
+                };
+                match deliver_once(&notifier.client, &target, &delivery.payload).await {
+                    Ok(()) => {
+                        notifier.outbox.remove(delivery.id);
+                        notifier.outbox.record_success(&target.url);
+                    }
+                    Err(e) => {
+                        tracing::warn!(
+                            event = %delivery.event,
+                            target_url = %target.url,
+                            attempts = delivery.attempts + 1,
+                            error = %e,
+                            "Webhook outbox retry failed"
+                        );
+                        notifier.outbox.record_error(&target.url, &e.to_string());
+                        match e {
+                            DeliveryError::Rejected(_) => notifier.outbox.remove(delivery.id),
+                            DeliveryError::Failed(_) => {
+                                notifier.outbox.reschedule(delivery.id, SystemTime::now());
+                                return;
+                            }
+                        }
+                    }
+                }
+                // The receiver is answering again, so the rest need not wait
+                // for their own retry time.
+                next = notifier.outbox.head(&target.url);
+            }
+        });
+    }
+    while per_target.join_next().await.is_some() {}
+}
+
+#[derive(Debug)]
+enum DeliveryError {
+    /// The receiver refused the payload (a 4xx other than 429), or the target
+    /// config cannot produce a valid request. Retrying will not help.
+    Rejected(String),
+    /// Network error, timeout, 429 or 5xx.
+    Failed(String),
+}
+
+impl std::fmt::Display for DeliveryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Rejected(e) | Self::Failed(e) => f.write_str(e),
+        }
+    }
+}
+
 async fn deliver_with_retries(
-    client: Arc<reqwest::Client>,
+    client: &reqwest::Client,
     target: &WebhookTarget,
     payload: &WebhookPayload,
-) -> anyhow::Result<()> {
+) -> Result<(), DeliveryError> {
     let mut attempt: u32 = 0;
     let max_attempts = 1u32.saturating_add(target.max_retries);
 
     loop {
         attempt += 1;
-        match deliver_once(&client, target, payload).await {
+        match deliver_once(client, target, payload).await {
             Ok(()) => return Ok(()),
-            Err(e) if attempt < max_attempts => {
+            Err(DeliveryError::Failed(e)) if attempt < max_attempts => {
                 let delay = backoff_delay(attempt);
                 tracing::debug!(
                     attempt,
@@ -261,8 +547,8 @@ fn backoff_delay(attempt: u32) -> Duration {
 async fn deliver_once(
     client: &reqwest::Client,
     target: &WebhookTarget,
-    payload: &WebhookPayload,
-) -> anyhow::Result<()> {
+    payload: &impl Serialize,
+) -> Result<(), DeliveryError> {
     let mut req = client
         .post(&target.url)
         .json(payload)
@@ -270,26 +556,29 @@ async fn deliver_once(
 
     for (k, v) in &target.headers {
         let name = reqwest::header::HeaderName::from_bytes(k.as_bytes())
-            .map_err(|_| anyhow::anyhow!("invalid header name: {k}"))?;
+            .map_err(|_| DeliveryError::Rejected(format!("invalid header name: {k}")))?;
         let value = reqwest::header::HeaderValue::from_str(v)
-            .map_err(|_| anyhow::anyhow!("invalid header value for {k}"))?;
+            .map_err(|_| DeliveryError::Rejected(format!("invalid header value for {k}")))?;
         req = req.header(name, value);
     }
 
-    let resp = req.send().await?;
+    // The error text is shown by `gctl webhooks status`, so leave out the URL.
+    let resp = req.send().await.map_err(|e| {
+        DeliveryError::Failed(format!("{:#}", anyhow::Error::from(e.without_url())))
+    })?;
     let status = resp.status();
     if status.is_success() {
         return Ok(());
     }
 
-    // Avoid retrying for most 4xx errors (auth/config problems).
+    let body = resp.text().await.unwrap_or_default();
+    // Most 4xx mean the payload or auth is wrong; resending will not fix it.
     if status.is_client_error() && status != reqwest::StatusCode::TOO_MANY_REQUESTS {
-        let body = resp.text().await.unwrap_or_default();
-        anyhow::bail!("HTTP {status} (non-retriable): {body}");
+        return Err(DeliveryError::Rejected(format!(
+            "HTTP {status} (non-retriable): {body}"
+        )));
     }
-
-    let body = resp.text().await.unwrap_or_default();
-    anyhow::bail!("HTTP {status}: {body}");
+    Err(DeliveryError::Failed(format!("HTTP {status}: {body}")))
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -306,6 +595,9 @@ pub(crate) struct WebhookPayload {
     pub(crate) reservation: Option<ReservationPayload>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub(crate) gpu: Option<GpuPayload>,
+    /// ID of the API request that caused the event, as in `X-Request-Id`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) request_id: Option<String>,
 }
 
 impl WebhookPayload {
@@ -393,6 +685,7 @@ pub(crate) async fn build_payloads(
                 job: Some(job_payload(*job_id, job)),
                 reservation: None,
                 gpu: None,
+                request_id: None,
             }]
         }
         SchedulerEvent::JobUpdated { job_id } => {
@@ -405,6 +698,24 @@ pub(crate) async fn build_payloads(
                 job: Some(job_payload(*job_id, job)),
                 reservation: None,
                 gpu: None,
+                request_id: None,
+            }]
+        }
+        SchedulerEvent::JobSignalled {
+            job_id,
+            signal,
+            requested_by,
+        } => {
+            let job = scheduler.read().await.get_job(*job_id);
+            vec![WebhookPayload {
+                event: "job_signalled".to_string(),
+                timestamp: now,
+                scheduler: scheduler_info,
+                text: Some(format!("Job {job_id} was sent {signal} by {requested_by}")),
+                job: Some(job_payload(*job_id, job)),
+                reservation: None,
+                gpu: None,
+                request_id: None,
             }]
         }
         SchedulerEvent::JobStateChanged {
@@ -425,6 +736,7 @@ pub(crate) async fn build_payloads(
                 job: Some(job_payload(*job_id, job)),
                 reservation: None,
                 gpu: None,
+                request_id: None,
             }]
         }
         SchedulerEvent::JobCompleted {
@@ -444,6 +756,7 @@ pub(crate) async fn build_payloads(
                 job: Some(job_payload(*job_id, job)),
                 reservation: None,
                 gpu: None,
+                request_id: None,
             }]
         }
         SchedulerEvent::JobTimedOut { job_id, .. } => {
@@ -456,6 +769,7 @@ pub(crate) async fn build_payloads(
                 job: Some(job_payload(*job_id, job)),
                 reservation: None,
                 gpu: None,
+                request_id: None,
             }]
         }
         SchedulerEvent::ReservationCreated { reservation_id } => {
@@ -475,6 +789,7 @@ pub(crate) async fn build_payloads(
                 job: None,
                 reservation: Some(reservation_payload(&reservation)),
                 gpu: None,
+                request_id: None,
             }]
         }
         SchedulerEvent::ReservationCancelled { reservation_id } => {
@@ -494,6 +809,7 @@ pub(crate) async fn build_payloads(
                 job: None,
                 reservation: Some(reservation_payload(&reservation)),
                 gpu: None,
+                request_id: None,
             }]
         }
         SchedulerEvent::GpuAvailabilityChanged {
@@ -514,11 +830,14 @@ pub(crate) async fn build_payloads(
                     index: *gpu_index,
                     available: *available,
                 }),
+                request_id: None,
             }]
         }
         SchedulerEvent::ManualGpuOverrideChanged { .. } => vec![],
         SchedulerEvent::MemoryAvailabilityChanged { .. }
         | SchedulerEvent::ZombieJobDetected { .. }
+        | SchedulerEvent::NodeUp { .. }
+        | SchedulerEvent::NodeDown { .. }
         | SchedulerEvent::PeriodicHealthCheck => vec![],
 
         SchedulerEvent::DaemonStarted => {
@@ -533,6 +852,7 @@ pub(crate) async fn build_payloads(
                 job: None,
                 reservation: None,
                 gpu: None,
+                request_id: None,
             }]
         }
     }
@@ -637,11 +957,17 @@ fn system_time_to_rfc3339(time: SystemTime) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use axum::{extract::State, http::HeaderMap, routing::post, Json, Router};
+    use axum::{
+        extract::State,
+        http::{HeaderMap, StatusCode},
+        routing::post,
+        Json, Router,
+    };
     use gflow::core::executor::Executor;
     use gflow::core::job::Job;
     use serde_json::Value;
     use std::net::SocketAddr;
+    use std::sync::atomic::{AtomicU16, Ordering};
     use std::sync::Mutex;
     use tempfile::tempdir;
     use tokio::time::timeout;
@@ -658,24 +984,32 @@ mod tests {
     #[derive(Clone)]
     struct ReceiverState {
         received: Received,
+        status: Arc<AtomicU16>,
     }
 
     async fn webhook_receiver(
         State(state): State<ReceiverState>,
         headers: HeaderMap,
         Json(body): Json<Value>,
-    ) {
+    ) -> StatusCode {
         let auth = headers
             .get("authorization")
             .and_then(|v| v.to_str().ok())
             .map(|s| s.to_string());
         state.received.lock().unwrap().push((body, auth));
+        StatusCode::from_u16(state.status.load(Ordering::SeqCst)).unwrap()
     }
 
     async fn start_receiver() -> (String, Received) {
+        start_receiver_with_status(Arc::new(AtomicU16::new(200))).await
+    }
+
+    /// A receiver that answers with whatever status `status` holds.
+    async fn start_receiver_with_status(status: Arc<AtomicU16>) -> (String, Received) {
         let received: Received = Arc::new(Mutex::new(vec![]));
         let state = ReceiverState {
             received: Arc::clone(&received),
+            status,
         };
 
         let app = Router::new()
@@ -757,6 +1091,7 @@ mod tests {
             }],
             emails: vec![],
             max_concurrent_deliveries: 4,
+            ..Default::default()
         };
 
         let _handle = spawn_webhook_notifier(
@@ -765,6 +1100,8 @@ mod tests {
             Arc::clone(&scheduler),
             Arc::clone(&event_bus),
             "localhost".to_string(),
+            Arc::new(WebhookOutbox::open(dir.path().join("webhook_outbox.jsonl"))),
+            Arc::new(JobWebhookStore::open(dir.path().join("job_webhooks.json"))),
         )
         .unwrap();
 
@@ -791,4 +1128,210 @@ mod tests {
         assert_eq!(payload["job"]["user"], "alice");
         assert_eq!(auth.as_deref(), Some("Bearer token123"));
     }
+
+    #[tokio::test]
+    async fn test_job_webhook_is_sent_once_when_the_job_ends() {
+        let (url, received) = start_receiver().await;
+
+        let dir = tempdir().unwrap();
+        let mut runtime = SchedulerRuntime::with_state_path(
+            Box::new(NoopExecutor),
+            dir.path().to_path_buf(),
+            None,
+            gflow::core::gpu_allocation::GpuAllocationStrategy::Sequential,
+            gflow::config::ProjectsConfig::default(),
+        )
+        .unwrap();
+        let job = Job::builder()
+            .command("echo test")
+            .submitted_by("alice")
+            .build();
+        let (job_id, _run_name, _job_clone) = runtime.submit_job(job).await.unwrap();
+
+        let job_hooks = Arc::new(JobWebhookStore::open(dir.path().join("job_webhooks.json")));
+        job_hooks.add(
+            job_id,
+            JobWebhook {
+                url,
+                events: vec!["job_completed".to_string()],
+                requested_by: "alice".to_string(),
+                created_at: SystemTime::now(),
+            },
+        );
+
+        // No global webhooks: the notifier still runs for the job's webhook.
+        let event_bus = Arc::new(EventBus::new(16));
+        let notifications = NotificationsConfig {
+            enabled: true,
+            ..Default::default()
+        };
+        spawn_webhook_notifier(
+            notifications,
+            Arc::new(Semaphore::new(4)),
+            Arc::new(RwLock::new(runtime)),
+            Arc::clone(&event_bus),
+            "localhost".to_string(),
+            Arc::new(WebhookOutbox::open(dir.path().join("webhook_outbox.jsonl"))),
+            Arc::clone(&job_hooks),
+        )
+        .unwrap();
+        assert!(job_hooks.deliverable());
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        for _ in 0..2 {
+            event_bus.publish(SchedulerEvent::JobCompleted {
+                job_id,
+                final_state: JobState::Finished,
+                gpu_ids: None,
+                memory_mb: None,
+            });
+        }
+        wait_until(|| !received.lock().unwrap().is_empty()).await;
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        let guard = received.lock().unwrap();
+        assert_eq!(guard.len(), 1, "a job webhook fires only once");
+        let (payload, _auth) = &guard[0];
+        assert_eq!(payload["event"], "job_completed");
+        assert_eq!(payload["job"]["id"], job_id);
+        assert!(payload["text"]
+            .as_str()
+            .unwrap()
+            .contains(&format!("job {job_id}")));
+        assert!(!job_hooks.has(job_id));
+    }
+
+    async fn wait_until(mut done: impl FnMut() -> bool) {
+        timeout(Duration::from_secs(5), async {
+            while !done() {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .unwrap();
+    }
+
+    async fn notifier_with_outbox(
+        status: Arc<AtomicU16>,
+        outbox_dir: &std::path::Path,
+    ) -> (
+        String,
+        Arc<EventBus>,
+        Received,
+        Arc<WebhookOutbox>,
+        u32,
+        tempfile::TempDir,
+    ) {
+        let (url, received) = start_receiver_with_status(status).await;
+
+        let dir = tempdir().unwrap();
+        let mut runtime = SchedulerRuntime::with_state_path(
+            Box::new(NoopExecutor),
+            dir.path().to_path_buf(),
+            None,
+            gflow::core::gpu_allocation::GpuAllocationStrategy::Sequential,
+            gflow::config::ProjectsConfig::default(),
+        )
+        .unwrap();
+        let job = Job::builder()
+            .command("echo test")
+            .submitted_by("alice")
+            .build();
+        let (job_id, _run_name, _job_clone) = runtime.submit_job(job).await.unwrap();
+
+        let event_bus = Arc::new(EventBus::new(16));
+        let outbox = Arc::new(WebhookOutbox::open(outbox_dir.join("webhook_outbox.jsonl")));
+        let notifications = NotificationsConfig {
+            enabled: true,
+            webhooks: vec![WebhookConfig {
+                url: url.clone(),
+                events: vec!["job_submitted".to_string()],
+                filter_users: None,
+                headers: HashMap::new(),
+                timeout_secs: 5,
+                max_retries: 0,
+            }],
+            ..Default::default()
+        };
+        spawn_webhook_notifier(
+            notifications,
+            Arc::new(Semaphore::new(4)),
+            Arc::new(RwLock::new(runtime)),
+            Arc::clone(&event_bus),
+            "localhost".to_string(),
+            Arc::clone(&outbox),
+            Arc::new(JobWebhookStore::open(outbox_dir.join("job_webhooks.json"))),
+        )
+        .unwrap();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        (url, event_bus, received, outbox, job_id, dir)
+    }
+
+    #[tokio::test]
+    async fn test_rejected_webhook_payload_is_dropped_without_retry() {
+        let outbox_dir = tempdir().unwrap();
+        let (_url, event_bus, received, outbox, job_id, _dir) =
+            notifier_with_outbox(Arc::new(AtomicU16::new(400)), outbox_dir.path()).await;
+
+        event_bus.publish(SchedulerEvent::JobSubmitted { job_id });
+        wait_until(|| outbox.status()[0].last_error.is_some()).await;
+
+        assert_eq!(received.lock().unwrap().len(), 1);
+        assert_eq!(outbox.pending(), 0);
+        assert!(outbox.status()[0]
+            .last_error
+            .as_deref()
+            .unwrap()
+            .contains("400"));
+    }
+
+    #[tokio::test]
+    async fn test_failed_webhook_deliveries_wait_in_outbox_until_receiver_recovers() {
+        let status = Arc::new(AtomicU16::new(503));
+        let outbox_dir = tempdir().unwrap();
+        let (url, event_bus, received, outbox, job_id, _dir) =
+            notifier_with_outbox(Arc::clone(&status), outbox_dir.path()).await;
+
+        event_bus.publish(SchedulerEvent::JobSubmitted { job_id });
+        wait_until(|| outbox.pending() == 1).await;
+
+        // With a delivery already waiting, later events queue behind it
+        // instead of hitting the receiver.
+        event_bus.publish(SchedulerEvent::JobSubmitted { job_id });
+        wait_until(|| outbox.pending() == 2).await;
+        assert_eq!(received.lock().unwrap().len(), 1);
+
+        // The queue survives a restart.
+        let reopened = WebhookOutbox::open(outbox_dir.path().join("webhook_outbox.jsonl"));
+        assert_eq!(reopened.pending(), 2);
+
+        status.store(200, Ordering::SeqCst);
+        let notifier = WebhookNotifier {
+            targets: WebhookTargets::try_from_config(&[WebhookConfig {
+                url,
+                events: vec![],
+                filter_users: None,
+                headers: HashMap::new(),
+                timeout_secs: 5,
+                max_retries: 0,
+            }])
+            .unwrap(),
+            client: Arc::new(reqwest::Client::new()),
+            semaphore: Arc::new(Semaphore::new(4)),
+            outbox: Arc::clone(&outbox),
+            job_hooks: Arc::new(JobWebhookStore::open(
+                outbox_dir.path().join("job_webhooks.json"),
+            )),
+            limits: OutboxLimits {
+                max_entries: 10,
+                max_age: Duration::from_secs(3600),
+            },
+        };
+        drain_outbox(&notifier, SystemTime::now() + Duration::from_secs(60)).await;
+
+        assert_eq!(outbox.pending(), 0);
+        assert_eq!(received.lock().unwrap().len(), 3);
+        assert!(outbox.status()[0].last_success_at.is_some());
+    }
 }
@@ -0,0 +1,208 @@
+//! One-shot webhooks users attach to their own jobs (`gjob notify`)
+//!
+//! They live in `job_webhooks.json` in the data directory rather than on the
+//! job, because job details are readable by every user and hook URLs usually
+//! carry a secret. The notifier takes a job's webhooks when the job reaches a
+//! final state, delivers the ones whose events match, and forgets the rest.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub(crate) struct JobWebhook {
+    pub(crate) url: String,
+    /// Final-state events to deliver (`job_completed`, ...); empty means all.
+    #[serde(default)]
+    pub(crate) events: Vec<String>,
+    /// Who registered the webhook.
+    pub(crate) requested_by: String,
+    pub(crate) created_at: SystemTime,
+}
+
+impl JobWebhook {
+    pub(super) fn wants(&self, event: &str) -> bool {
+        self.events.is_empty() || self.events.iter().any(|e| e == event)
+    }
+}
+
+#[derive(Default)]
+struct StoreState {
+    hooks: BTreeMap<u32, Vec<JobWebhook>>,
+    /// Whether a webhook notifier is running to deliver them.
+    deliverable: bool,
+}
+
+pub(crate) struct JobWebhookStore {
+    path: PathBuf,
+    state: Mutex<StoreState>,
+}
+
+impl JobWebhookStore {
+    pub(crate) fn open(path: PathBuf) -> Self {
+        let hooks = match std::fs::read_to_string(&path) {
+            Ok(content) => serde_json::from_str(&content).unwrap_or_else(|e| {
+                tracing::warn!(
+                    path = %path.display(),
+                    error = %e,
+                    "Failed to parse job webhooks; starting empty"
+                );
+                BTreeMap::new()
+            }),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => BTreeMap::new(),
+            Err(e) => {
+                tracing::warn!(
+                    path = %path.display(),
+                    error = %e,
+                    "Failed to read job webhooks; starting empty"
+                );
+                BTreeMap::new()
+            }
+        };
+
+        Self {
+            path,
+            state: Mutex::new(StoreState {
+                hooks,
+                deliverable: false,
+            }),
+        }
+    }
+
+    /// Whether webhooks attached now would be delivered.
+    pub(crate) fn deliverable(&self) -> bool {
+        self.state.lock().unwrap().deliverable
+    }
+
+    pub(super) fn set_deliverable(&self, deliverable: bool) {
+        self.state.lock().unwrap().deliverable = deliverable;
+    }
+
+    /// Attach `hook` to `job_id`, replacing an earlier one with the same URL.
+    pub(crate) fn add(&self, job_id: u32, hook: JobWebhook) {
+        let mut state = self.state.lock().unwrap();
+        let hooks = state.hooks.entry(job_id).or_default();
+        hooks.retain(|h| h.url != hook.url);
+        hooks.push(hook);
+        self.persist(&state);
+    }
+
+    /// Detach every webhook from `job_id`; returns how many there were.
+    pub(crate) fn remove(&self, job_id: u32) -> usize {
+        let mut state = self.state.lock().unwrap();
+        let removed = state.hooks.remove(&job_id).map_or(0, |h| h.len());
+        if removed > 0 {
+            self.persist(&state);
+        }
+        removed
+    }
+
+    pub(super) fn has(&self, job_id: u32) -> bool {
+        self.state.lock().unwrap().hooks.contains_key(&job_id)
+    }
+
+    /// Detach and return `job_id`'s webhooks for delivery.
+    pub(super) fn take(&self, job_id: u32) -> Vec<JobWebhook> {
+        let mut state = self.state.lock().unwrap();
+        let hooks = state.hooks.remove(&job_id).unwrap_or_default();
+        if !hooks.is_empty() {
+            self.persist(&state);
+        }
+        hooks
+    }
+
+    /// Drop the webhooks of jobs `keep` rejects, e.g. jobs that ended while
+    /// no notifier was running.
+    pub(super) fn retain_jobs(&self, mut keep: impl FnMut(u32) -> bool) {
+        let mut state = self.state.lock().unwrap();
+        let before = state.hooks.len();
+        state.hooks.retain(|&job_id, _| keep(job_id));
+        let dropped = before - state.hooks.len();
+        if dropped > 0 {
+            tracing::info!(
+                jobs = dropped,
+                "Dropped webhooks of jobs that ended without delivering them"
+            );
+            self.persist(&state);
+        }
+    }
+
+    pub(super) fn job_ids(&self) -> Vec<u32> {
+        self.state.lock().unwrap().hooks.keys().copied().collect()
+    }
+
+    fn persist(&self, state: &StoreState) {
+        if let Err(e) = self.write_file(&state.hooks) {
+            tracing::error!(
+                path = %self.path.display(),
+                error = %e,
+                "Failed to write job webhooks"
+            );
+        }
+    }
+
+    fn write_file(&self, hooks: &BTreeMap<u32, Vec<JobWebhook>>) -> anyhow::Result<()> {
+        use std::io::Write;
+
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let tmp_path = self.path.with_extension("json.tmp");
+        let _ = std::fs::remove_file(&tmp_path);
+        let mut file = std::fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .mode(0o600)
+            .open(&tmp_path)?;
+        file.write_all(&serde_json::to_vec_pretty(hooks)?)?;
+        std::fs::rename(&tmp_path, &self.path)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn hook(url: &str, events: &[&str]) -> JobWebhook {
+        JobWebhook {
+            url: url.to_string(),
+            events: events.iter().map(|e| e.to_string()).collect(),
+            requested_by: "alice".to_string(),
+            created_at: SystemTime::now(),
+        }
+    }
+
+    #[test]
+    fn job_webhooks_persist_until_taken_or_removed() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("job_webhooks.json");
+
+        let store = JobWebhookStore::open(path.clone());
+        store.add(1, hook("http://a.example/x", &["job_failed"]));
+        store.add(1, hook("http://a.example/x", &[]));
+        store.add(2, hook("http://b.example/y", &[]));
+
+        let reopened = JobWebhookStore::open(path);
+        assert!(reopened.has(1));
+        let taken = reopened.take(1);
+        assert_eq!(taken.len(), 1, "same URL replaces the earlier webhook");
+        assert!(taken[0].wants("job_completed"));
+        assert!(!reopened.has(1));
+
+        assert_eq!(reopened.remove(2), 1);
+        assert_eq!(reopened.remove(2), 0);
+        assert!(reopened.job_ids().is_empty());
+    }
+
+    #[test]
+    fn job_webhook_events_filter_deliveries() {
+        let failed_only = hook("http://a.example/x", &["job_failed", "job_timeout"]);
+        assert!(failed_only.wants("job_timeout"));
+        assert!(!failed_only.wants("job_completed"));
+    }
+}
@@ -0,0 +1,384 @@
+//! Durable queue of webhook deliveries waiting for a retry
+//!
+//! A delivery that still fails after its in-memory retries is appended to
+//! `webhook_outbox.jsonl` in the data directory, one delivery per line, so a
+//! receiver outage or a daemon restart does not lose notifications. The
+//! notifier retries each target's entries in order with exponential backoff
+//! until they succeed, the receiver rejects them with a 4xx, or they are older
+//! than `notifications.outbox_max_age_secs`.
+
+use gflow::core::info::WebhookTargetStatus;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+
+const FIRST_RETRY_DELAY: Duration = Duration::from_secs(30);
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(60 * 60);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(super) struct QueuedDelivery {
+    pub(super) id: u64,
+    pub(super) url: String,
+    pub(super) event: String,
+    pub(super) payload: serde_json::Value,
+    pub(super) queued_at: SystemTime,
+    /// Attempts made from the outbox (the first in-memory ones are not counted).
+    pub(super) attempts: u32,
+    pub(super) next_attempt_at: SystemTime,
+}
+
+#[derive(Debug, Default, Clone)]
+struct TargetHealth {
+    last_success_at: Option<SystemTime>,
+    last_error_at: Option<SystemTime>,
+    last_error: Option<String>,
+}
+
+#[derive(Default)]
+struct OutboxState {
+    queue: VecDeque<QueuedDelivery>,
+    next_id: u64,
+    /// URLs of the configured targets, in config order.
+    targets: Vec<String>,
+    /// Kept in memory only; resets when the daemon restarts.
+    health: HashMap<String, TargetHealth>,
+}
+
+pub(crate) struct WebhookOutbox {
+    path: PathBuf,
+    state: Mutex<OutboxState>,
+}
+
+impl WebhookOutbox {
+    /// Load the outbox at `path`, skipping lines that do not parse.
+    pub(crate) fn open(path: PathBuf) -> Self {
+        let mut state = OutboxState::default();
+        match std::fs::read_to_string(&path) {
+            Ok(content) => {
+                for (line_no, line) in content.lines().enumerate() {
+                    if line.trim().is_empty() {
+                        continue;
+                    }
+                    match serde_json::from_str::<QueuedDelivery>(line) {
+                        Ok(delivery) => state.queue.push_back(delivery),
+                        Err(e) => tracing::warn!(
+                            path = %path.display(),
+                            line = line_no + 1,
+                            error = %e,
+                            "Skipping unreadable webhook outbox entry"
+                        ),
+                    }
+                }
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => tracing::warn!(
+                path = %path.display(),
+                error = %e,
+                "Failed to read webhook outbox; starting empty"
+            ),
+        }
+        state.next_id = state.queue.iter().map(|d| d.id + 1).max().unwrap_or(0);
+        if !state.queue.is_empty() {
+            tracing::info!(
+                pending = state.queue.len(),
+                "Loaded pending webhook deliveries"
+            );
+        }
+
+        Self {
+            path,
+            state: Mutex::new(state),
+        }
+    }
+
+    /// Number of deliveries waiting for a retry.
+    pub(crate) fn pending(&self) -> usize {
+        self.state.lock().unwrap().queue.len()
+    }
+
+    pub(super) fn has_pending(&self, url: &str) -> bool {
+        self.state
+            .lock()
+            .unwrap()
+            .queue
+            .iter()
+            .any(|d| d.url == url)
+    }
+
+    /// Queue a delivery for its first retry. When the outbox holds more than
+    /// `max_entries`, the oldest deliveries are dropped.
+    pub(super) fn enqueue(
+        &self,
+        url: &str,
+        event: &str,
+        payload: serde_json::Value,
+        max_entries: usize,
+    ) {
+        if max_entries == 0 {
+            tracing::warn!(event, "Webhook outbox disabled; dropping failed delivery");
+            return;
+        }
+
+        let now = SystemTime::now();
+        let mut state = self.state.lock().unwrap();
+        let id = state.next_id;
+        state.next_id += 1;
+        state.queue.push_back(QueuedDelivery {
+            id,
+            url: url.to_string(),
+            event: event.to_string(),
+            payload,
+            queued_at: now,
+            attempts: 0,
+            next_attempt_at: now + retry_delay(0),
+        });
+
+        let overflow = state.queue.len().saturating_sub(max_entries);
+        if overflow > 0 {
+            state.queue.drain(..overflow);
+            tracing::warn!(
+                dropped = overflow,
+                max_entries,
+                "Webhook outbox full; dropped the oldest deliveries"
+            );
+        }
+        self.persist(&state);
+    }
+
+    /// Record the configured target URLs, dropping queued deliveries for
+    /// targets that are no longer configured.
+    pub(super) fn set_targets(&self, urls: Vec<String>) {
+        let mut state = self.state.lock().unwrap();
+        let before = state.queue.len();
+        state.queue.retain(|d| urls.contains(&d.url));
+        state.health.retain(|url, _| urls.contains(url));
+        state.targets = urls;
+
+        let dropped = before - state.queue.len();
+        if dropped > 0 {
+            tracing::warn!(
+                dropped,
+                "Dropped pending webhook deliveries for targets no longer configured"
+            );
+            self.persist(&state);
+        }
+    }
+
+    /// Drop deliveries first queued more than `max_age` before `now`.
+    pub(super) fn expire(&self, now: SystemTime, max_age: Duration) {
+        let mut state = self.state.lock().unwrap();
+        let before = state.queue.len();
+        state.queue.retain(|d| {
+            now.duration_since(d.queued_at)
+                .map(|age| age < max_age)
+                .unwrap_or(true)
+        });
+
+        let dropped = before - state.queue.len();
+        if dropped > 0 {
+            tracing::warn!(
+                dropped,
+                max_age_secs = max_age.as_secs(),
+                "Gave up on webhook deliveries older than the outbox max age"
+            );
+            self.persist(&state);
+        }
+    }
+
+    /// The oldest delivery for `url`, if its retry is due. Later deliveries
+    /// wait behind it so a target sees events in order.
+    pub(super) fn due_head(&self, url: &str, now: SystemTime) -> Option<QueuedDelivery> {
+        self.head(url).filter(|head| head.next_attempt_at <= now)
+    }
+
+    /// The oldest delivery for `url`, due or not.
+    pub(super) fn head(&self, url: &str) -> Option<QueuedDelivery> {
+        let state = self.state.lock().unwrap();
+        state.queue.iter().find(|d| d.url == url).cloned()
+    }
+
+    /// Remove a delivery that succeeded or was rejected.
+    pub(super) fn remove(&self, id: u64) {
+        let mut state = self.state.lock().unwrap();
+        let before = state.queue.len();
+        state.queue.retain(|d| d.id != id);
+        if state.queue.len() != before {
+            self.persist(&state);
+        }
+    }
+
+    /// Push a delivery's next attempt back after another failure.
+    pub(super) fn reschedule(&self, id: u64, now: SystemTime) {
+        let mut state = self.state.lock().unwrap();
+        let Some(delivery) = state.queue.iter_mut().find(|d| d.id == id) else {
+            return;
+        };
+        delivery.attempts = delivery.attempts.saturating_add(1);
+        delivery.next_attempt_at = now + retry_delay(delivery.attempts);
+        self.persist(&state);
+    }
+
+    pub(super) fn record_success(&self, url: &str) {
+        let mut state = self.state.lock().unwrap();
+        state
+            .health
+            .entry(url.to_string())
+            .or_default()
+            .last_success_at = Some(SystemTime::now());
+    }
+
+    pub(super) fn record_error(&self, url: &str, error: &str) {
+        let mut state = self.state.lock().unwrap();
+        let health = state.health.entry(url.to_string()).or_default();
+        health.last_error_at = Some(SystemTime::now());
+        health.last_error = Some(error.to_string());
+    }
+
+    /// Per-target delivery health, in config order.
+    pub(crate) fn status(&self) -> Vec<WebhookTargetStatus> {
+        let state = self.state.lock().unwrap();
+        state
+            .targets
+            .iter()
+            .enumerate()
+            .map(|(index, url)| {
+                let health = state.health.get(url).cloned().unwrap_or_default();
+                WebhookTargetStatus {
+                    index,
+                    url: redact_url(url),
+                    pending: state.queue.iter().filter(|d| &d.url == url).count(),
+                    last_success_at: health.last_success_at,
+                    last_error_at: health.last_error_at,
+                    last_error: health.last_error,
+                }
+            })
+            .collect()
+    }
+
+    fn persist(&self, state: &OutboxState) {
+        if let Err(e) = self.write_file(&state.queue) {
+            tracing::error!(
+                path = %self.path.display(),
+                error = %e,
+                "Failed to write webhook outbox"
+            );
+        }
+    }
+
+    fn write_file(&self, queue: &VecDeque<QueuedDelivery>) -> anyhow::Result<()> {
+        use std::io::Write;
+
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut content = Vec::new();
+        for delivery in queue {
+            serde_json::to_writer(&mut content, delivery)?;
+            content.push(b'\n');
+        }
+
+        // Payloads can include job commands, so keep the file private.
+        let tmp_path = self.path.with_extension("jsonl.tmp");
+        let _ = std::fs::remove_file(&tmp_path);
+        let mut file = std::fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .mode(0o600)
+            .open(&tmp_path)?;
+        file.write_all(&content)?;
+        std::fs::rename(&tmp_path, &self.path)?;
+        Ok(())
+    }
+}
+
+fn retry_delay(attempts: u32) -> Duration {
+    FIRST_RETRY_DELAY
+        .saturating_mul(2u32.saturating_pow(attempts.min(16)))
+        .min(MAX_RETRY_DELAY)
+}
+
+/// Keep only scheme, host and port; chat hook URLs carry their secret in the path.
+pub(super) fn redact_url(url: &str) -> String {
+    match reqwest::Url::parse(url) {
+        Ok(parsed) if parsed.path() == "/" && parsed.query().is_none() => {
+            parsed.origin().ascii_serialization()
+        }
+        Ok(parsed) => format!("{}/…", parsed.origin().ascii_serialization()),
+        Err(_) => "<invalid url>".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    const URL: &str = "http://hooks.example.com/services/secret";
+
+    #[test]
+    fn outbox_survives_reopen_and_drops_oldest_when_full() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("webhook_outbox.jsonl");
+
+        let outbox = WebhookOutbox::open(path.clone());
+        outbox.set_targets(vec![URL.to_string()]);
+        for n in 0..3 {
+            outbox.enqueue(URL, "job_failed", serde_json::json!({ "n": n }), 2);
+        }
+        assert_eq!(outbox.pending(), 2);
+
+        let reopened = WebhookOutbox::open(path);
+        assert_eq!(reopened.pending(), 2);
+        let later = SystemTime::now() + Duration::from_secs(60);
+        let head = reopened.due_head(URL, later).unwrap();
+        assert_eq!(head.payload["n"], 1);
+
+        // A new entry must not reuse an id that is still queued.
+        reopened.enqueue(URL, "job_failed", serde_json::json!({ "n": 3 }), 10);
+        reopened.remove(head.id);
+        assert_eq!(reopened.due_head(URL, later).unwrap().payload["n"], 2);
+    }
+
+    #[test]
+    fn outbox_backs_off_and_expires_old_deliveries() {
+        let dir = tempdir().unwrap();
+        let outbox = WebhookOutbox::open(dir.path().join("webhook_outbox.jsonl"));
+        outbox.set_targets(vec![URL.to_string()]);
+        outbox.enqueue(URL, "job_failed", serde_json::json!({}), 10);
+
+        let now = SystemTime::now();
+        assert!(outbox.due_head(URL, now).is_none());
+        let head = outbox.due_head(URL, now + FIRST_RETRY_DELAY).unwrap();
+
+        outbox.reschedule(head.id, now);
+        assert!(outbox.due_head(URL, now + FIRST_RETRY_DELAY).is_none());
+        assert!(outbox.due_head(URL, now + FIRST_RETRY_DELAY * 2).is_some());
+        assert_eq!(retry_delay(30), MAX_RETRY_DELAY);
+
+        outbox.expire(now + Duration::from_secs(120), Duration::from_secs(60));
+        assert_eq!(outbox.pending(), 0);
+    }
+
+    #[test]
+    fn status_hides_url_paths_and_drops_removed_targets() {
+        let dir = tempdir().unwrap();
+        let outbox = WebhookOutbox::open(dir.path().join("webhook_outbox.jsonl"));
+        outbox.set_targets(vec![URL.to_string(), "https://ci.example.com/".to_string()]);
+        outbox.enqueue(URL, "job_failed", serde_json::json!({}), 10);
+        outbox.record_error(URL, "HTTP 503");
+
+        let status = outbox.status();
+        assert_eq!(status[0].url, "http://hooks.example.com/…");
+        assert_eq!(status[0].pending, 1);
+        assert_eq!(status[0].last_error.as_deref(), Some("HTTP 503"));
+        assert_eq!(status[1].url, "https://ci.example.com");
+        assert_eq!(status[1].pending, 0);
+
+        outbox.set_targets(vec!["https://ci.example.com/".to_string()]);
+        assert_eq!(outbox.pending(), 0);
+        assert_eq!(outbox.status().len(), 1);
+    }
+}
@@ -14,11 +14,19 @@ pub struct GInfoCli {
     #[command(subcommand)]
     pub command: Option<Commands>,
 
+    /// Print a machine-readable scheduler snapshot as JSON
+    #[arg(long)]
+    pub json: bool,
+
     #[command(flatten)]
     pub verbosity: Verbosity,
 
     #[arg(long, global = true, help = "Path to the config file", hide = true)]
     pub config: Option<std::path::PathBuf>,
+
+    /// When to color output: auto (terminals, unless NO_COLOR is set), always or never
+    #[arg(long, global = true, value_enum, value_name = "WHEN", default_value_t)]
+    pub color: gflow::utils::output::ColorChoice,
 }
 
 #[derive(Debug, Parser)]
@@ -1,11 +1,43 @@
 use anyhow::Result;
 use gflow::client::Client;
 
-pub async fn handle_info(config_path: &Option<std::path::PathBuf>) -> Result<()> {
+pub async fn handle_info(config_path: &Option<std::path::PathBuf>, json: bool) -> Result<()> {
     let client = gflow::create_client_or_default(config_path)?;
 
+    let stats = client.get_scheduler_stats().await?;
+    if json {
+        println!("{}", serde_json::to_string_pretty(&stats)?);
+        return Ok(());
+    }
+
     let (info, jobs) = fetch_info_and_jobs(&client).await?;
+    if let Ok((path, source)) = gflow::config::config_file_path(config_path.as_ref()) {
+        if path.exists() {
+            println!("Config file: {} (from {source})", path.display());
+        } else {
+            println!("Config file: none, using defaults");
+        }
+    }
+    println!("Queue summary: {}", stats.queue_summary());
+    println!("Memory: {}", stats.memory_summary());
+    if stats.total_cpus > 0 {
+        println!("CPU cores: {}", stats.cpu_summary());
+    }
+    if stats.pending_webhook_deliveries > 0 {
+        println!(
+            "Webhook deliveries pending retry: {} (see `gctl webhooks status`)",
+            stats.pending_webhook_deliveries
+        );
+    }
     print_gpu_allocation(&info, &jobs);
+    print_labels(&info);
+    print_disks(&stats.disks);
+
+    let reservations = client.list_reservations(None, None, true).await?;
+    print_active_reservations(&reservations)?;
+
+    let nodes = client.list_nodes().await?;
+    print_nodes(&nodes)?;
     Ok(())
 }
 
@@ -24,7 +56,10 @@ fn print_gpu_allocation(info: &gflow::core::info::SchedulerInfo, jobs: &[gflow::
 
     // Build a reverse index: gpu_index -> Option<(job_id, run_name)>
     let mut usage: HashMap<u32, (u32, String)> = HashMap::new();
-    for j in jobs.iter().filter(|j| j.state == JobState::Running) {
+    for j in jobs
+        .iter()
+        .filter(|j| j.state == JobState::Running && j.node.is_none())
+    {
         if let Some(gpu_ids) = &j.gpu_ids {
             for &idx in gpu_ids {
                 let name = j
@@ -115,6 +150,117 @@ fn print_gpu_allocation(info: &gflow::core::info::SchedulerInfo, jobs: &[gflow::
     }
 }
 
+fn print_disks(disks: &[gflow::utils::disk::DiskUsage]) {
+    use tabled::{builder::Builder, settings::Style};
+
+    if disks.is_empty() {
+        return;
+    }
+    let mut builder = Builder::default();
+    builder.push_record(["DIR", "PATH", "SPACE"]);
+    for disk in disks {
+        builder.push_record([
+            disk.kind.clone(),
+            disk.path.display().to_string(),
+            disk.summary(),
+        ]);
+    }
+    println!();
+    println!("Disk space:");
+    println!("{}", builder.build().with(Style::empty()));
+}
+
+fn print_labels(info: &gflow::core::info::SchedulerInfo) {
+    use gflow::utils::constraint::format_labels;
+    use tabled::{builder::Builder, settings::Style};
+
+    if !info.labels.is_empty() {
+        println!();
+        println!("Labels: {}", format_labels(&info.labels));
+    }
+
+    // GPUs with labels of their own
+    let gpus: Vec<_> = info
+        .gpus
+        .iter()
+        .filter(|gpu| gpu.labels != info.labels)
+        .collect();
+    if gpus.is_empty() {
+        return;
+    }
+    let mut builder = Builder::default();
+    builder.push_record(["GPU", "LABELS"]);
+    for gpu in gpus {
+        builder.push_record([gpu.index.to_string(), format_labels(&gpu.labels)]);
+    }
+    println!();
+    println!("GPU labels:");
+    println!("{}", builder.build().with(Style::empty()));
+}
+
+fn print_active_reservations(
+    reservations: &[gflow::core::reservation::GpuReservation],
+) -> Result<()> {
+    use gflow::core::reservation::GpuSpec;
+    use gflow::utils::timezone::format_system_time_short;
+    use tabled::{builder::Builder, settings::Style};
+
+    if reservations.is_empty() {
+        return Ok(());
+    }
+
+    let mut builder = Builder::default();
+    builder.push_record(["RESERVATION", "USER", "GPUS", "UNTIL"]);
+    for r in reservations {
+        let gpus = match &r.gpu_spec {
+            GpuSpec::Count(count) => count.to_string(),
+            GpuSpec::Indices(indices) => indices
+                .iter()
+                .map(|i| i.to_string())
+                .collect::<Vec<_>>()
+                .join(","),
+        };
+        builder.push_record([
+            r.id.to_string(),
+            r.holder().to_string(),
+            gpus,
+            format_system_time_short(r.end_time(), None)?,
+        ]);
+    }
+
+    println!();
+    println!("Active reservations:");
+    println!("{}", builder.build().with(Style::empty()));
+    Ok(())
+}
+
+fn print_nodes(nodes: &[gflow::core::node::Node]) -> Result<()> {
+    use gflow::utils::timezone::format_system_time_short;
+    use tabled::{builder::Builder, settings::Style};
+
+    if nodes.is_empty() {
+        return Ok(());
+    }
+
+    let mut builder = Builder::default();
+    builder.push_record(["NODE", "STATUS", "GPUS", "MEMORY", "LAST SEEN", "LABELS"]);
+    for node in nodes {
+        builder.push_record([
+            node.name.clone(),
+            node.status.to_string(),
+            node.gpus.to_string(),
+            gflow::utils::format_memory(node.total_memory_mb),
+            format_system_time_short(node.last_heartbeat, None)?,
+            gflow::utils::constraint::format_labels(&node.labels),
+        ]);
+    }
+
+    println!();
+    println!("Nodes:");
+    println!("{}", builder.build().with(Style::empty()));
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use gflow::core::job::JobBuilder;
@@ -131,22 +277,26 @@ mod tests {
                     available: true,
                     uuid: "GPU-0000".to_string(),
                     reason: None,
+                    labels: Default::default(),
                 },
                 gflow::core::info::GpuInfo {
                     index: 1,
                     available: false,
                     uuid: "GPU-0001".to_string(),
                     reason: None,
+                    labels: Default::default(),
                 },
                 gflow::core::info::GpuInfo {
                     index: 2,
                     available: false,
                     uuid: "GPU-0002".to_string(),
                     reason: Some("Unmanaged".to_string()),
+                    labels: Default::default(),
                 },
             ],
             allowed_gpu_indices: None,
             gpu_allocation_strategy: gflow::core::gpu_allocation::GpuAllocationStrategy::Sequential,
+            labels: Default::default(),
         };
         let jobs = vec![JobBuilder::new().build(), JobBuilder::new().build()];
 
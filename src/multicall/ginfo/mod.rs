@@ -7,6 +7,7 @@ use std::ffi::OsString;
 
 pub async fn run(argv: Vec<OsString>) -> Result<()> {
     let args = cli::GInfoCli::parse_from(argv);
+    gflow::utils::output::set_color_choice(args.color);
 
     tracing_subscriber::fmt()
         .with_max_level(args.verbosity)
@@ -25,6 +26,6 @@ pub async fn run(argv: Vec<OsString>) -> Result<()> {
         }
     }
 
-    commands::info::handle_info(&args.config).await?;
+    commands::info::handle_info(&args.config, args.json).await?;
     Ok(())
 }
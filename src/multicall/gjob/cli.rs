@@ -19,6 +19,18 @@ pub struct GJob {
     #[arg(long, global = true, help = "Path to the config file", hide = true)]
     pub config: Option<std::path::PathBuf>,
 
+    /// Talk to the daemon of this `[remotes.<name>]` entry (or set GFLOW_REMOTE)
+    #[arg(long, global = true, value_name = "NAME")]
+    pub remote: Option<String>,
+
+    /// Run even if gflowd declares this client too old (or set GFLOW_SKIP_VERSION_CHECK=1)
+    #[arg(long, global = true)]
+    pub skip_version_check: bool,
+
+    /// When to color output: auto (terminals, unless NO_COLOR is set), always or never
+    #[arg(long, global = true, value_enum, value_name = "WHEN", default_value_t)]
+    pub color: gflow::utils::output::ColorChoice,
+
     #[command(subcommand)]
     pub command: Commands,
 }
@@ -31,7 +43,12 @@ pub enum Commands {
     Attach {
         #[arg(help = "Job ID to attach to (supports @ for most recent job)", value_hint = clap::ValueHint::Other)]
         job: String,
+
+        #[arg(long = "ro", help = "Attach read-only (tmux attach -r)")]
+        read_only: bool,
     },
+    /// List the tmux sessions of running jobs
+    Sessions,
     /// View a job's log output
     #[command(visible_alias = "l")]
     Log {
@@ -56,6 +73,93 @@ pub enum Commands {
         )]
         last: Option<NonZeroUsize>,
     },
+    /// Print the paths of a job's log, run directory, job directory and artifacts
+    Path {
+        #[arg(help = "Job ID (supports @ for most recent job)", value_hint = clap::ValueHint::Other)]
+        job: String,
+    },
+    /// List the artifacts a job registered, or register one
+    Artifacts {
+        #[arg(help = "Job ID (supports @ for most recent job)", value_hint = clap::ValueHint::Other)]
+        job: String,
+
+        #[arg(
+            long,
+            value_name = "PATH",
+            help = "Register this file or directory as an output of the job",
+            value_hint = clap::ValueHint::AnyPath
+        )]
+        add: Option<std::path::PathBuf>,
+
+        #[arg(
+            long,
+            requires = "add",
+            help = "Label for the --add artifact, e.g. best-checkpoint",
+            value_hint = clap::ValueHint::Other
+        )]
+        label: Option<String>,
+
+        #[arg(long, conflicts_with = "add", help = "Print JSON instead of a table")]
+        json: bool,
+    },
+    /// Remove a finished job's GFLOW_JOB_DIR and print how much it held
+    Clean {
+        #[arg(help = "Job ID (supports @ for most recent job)", value_hint = clap::ValueHint::Other)]
+        job: String,
+    },
+    /// Add or remove a job's tags, or print them
+    Tag {
+        #[arg(help = "Job ID (supports @ for most recent job)", value_hint = clap::ValueHint::Other)]
+        job: String,
+
+        #[arg(
+            long,
+            value_delimiter = ',',
+            help = "Tags to add; repeat or separate with commas",
+            value_hint = clap::ValueHint::Other
+        )]
+        add: Vec<String>,
+
+        #[arg(
+            long,
+            value_delimiter = ',',
+            help = "Tags to remove; repeat or separate with commas",
+            value_hint = clap::ValueHint::Other
+        )]
+        remove: Vec<String>,
+    },
+    /// Wait until jobs reach a state, for scripts and pipelines
+    Wait {
+        #[arg(
+            required = true,
+            help = "Job IDs to wait for: IDs, ranges like \"1-3\", or @ for the most recent job",
+            value_hint = clap::ValueHint::Other
+        )]
+        jobs: Vec<String>,
+
+        #[arg(long = "for", value_enum, default_value_t, help = "State to wait for")]
+        condition: crate::multicall::gjob::commands::wait::WaitCondition,
+
+        #[arg(long, help = "Return as soon as one job reaches the state")]
+        any: bool,
+
+        #[arg(
+            long,
+            value_parser = gflow::utils::parse_duration,
+            help = "Give up after this long, e.g. 30m or 6h (exit status 124)",
+            value_hint = clap::ValueHint::Other
+        )]
+        timeout: Option<std::time::Duration>,
+
+        #[arg(
+            long,
+            value_parser = gflow::utils::parse_duration,
+            default_value = "10s",
+            help = "How often to check the jobs",
+            value_hint = clap::ValueHint::Other
+        )]
+        poll: std::time::Duration,
+    },
     /// Put a queued job on hold
     #[command(visible_alias = "h")]
     Hold {
@@ -74,6 +178,36 @@ pub enum Commands {
         )]
         job: String,
     },
+    /// Call a webhook once when a job ends
+    Notify {
+        #[arg(help = "Job ID to watch (supports @ for most recent job)", value_hint = clap::ValueHint::Other)]
+        job: String,
+
+        #[arg(
+            long,
+            value_name = "URL",
+            required_unless_present = "remove",
+            help = "Webhook URL to POST to when the job ends",
+            value_hint = clap::ValueHint::Url
+        )]
+        webhook: Option<String>,
+
+        #[arg(
+            long,
+            value_enum,
+            value_delimiter = ',',
+            conflicts_with = "remove",
+            help = "Only notify for these outcomes (default: all)"
+        )]
+        events: Vec<crate::multicall::gjob::commands::notify::NotifyEvent>,
+
+        #[arg(
+            long,
+            conflicts_with = "webhook",
+            help = "Remove the webhooks attached to the job"
+        )]
+        remove: bool,
+    },
     /// Update parameters for a queued or held job
     #[command(visible_alias = "u")]
     Update {
@@ -166,6 +300,12 @@ pub enum Commands {
 
         #[arg(long = "param", help = "Update parameter (KEY=VALUE, can be repeated)", value_hint = clap::ValueHint::Other)]
         params: Vec<String>,
+
+        #[arg(short = 'u', long, help = "Allow updating jobs of these users (comma-separated) instead of only your own", value_hint = clap::ValueHint::Other)]
+        user: Option<String>,
+
+        #[arg(long, conflicts_with = "user", help = "Allow updating any user's jobs")]
+        all_users: bool,
     },
     /// Show detailed information about a job
     #[command(visible_alias = "s")]
@@ -226,6 +366,114 @@ pub enum Commands {
         )]
         cascade: bool,
     },
+    /// Queue a job cancelled before it started again, as a new job
+    Restore {
+        #[arg(
+            help = "Cancelled job ID (supports @ for most recent job)",
+            required_unless_present = "last",
+            conflicts_with = "last",
+            value_hint = clap::ValueHint::Other
+        )]
+        job: Option<String>,
+
+        #[arg(long, help = "Restore your most recently cancelled job")]
+        last: bool,
+    },
+    /// Resubmit the failed and cancelled jobs of a workflow and everything downstream of them
+    RerunFailed {
+        #[arg(
+            help = "A job of the workflow (supports @); the jobs linked to it by dependencies are checked",
+            required_unless_present = "group",
+            conflicts_with = "group",
+            value_hint = clap::ValueHint::Other
+        )]
+        job: Option<String>,
+
+        #[arg(
+            long,
+            help = "Check the jobs of this group (UUID, or the ID of one of its jobs)",
+            value_hint = clap::ValueHint::Other
+        )]
+        group: Option<String>,
+
+        #[arg(
+            long,
+            help = "Show which jobs would be resubmitted without submitting them"
+        )]
+        dry_run: bool,
+    },
+    /// Show typical runtimes of earlier runs, by run-name prefix
+    Stats {
+        #[arg(
+            help = "Run name, name prefix, or glob such as 'train-*' (default: all)",
+            value_hint = clap::ValueHint::Other
+        )]
+        name: Option<String>,
+
+        #[arg(short, long, help = "Only show this user's runs", value_hint = clap::ValueHint::Username)]
+        user: Option<String>,
+
+        #[arg(long, help = "Print JSON instead of a table")]
+        json: bool,
+    },
+    /// List job groups (batch submissions) with their jobs' states
+    Groups {
+        #[arg(
+            short,
+            long,
+            help = "Only show these users' groups (comma-separated; default: your own)",
+            value_hint = clap::ValueHint::Username
+        )]
+        user: Option<String>,
+
+        #[arg(long, conflicts_with = "user", help = "Show every user's groups")]
+        all_users: bool,
+
+        #[arg(long, help = "Print JSON instead of a table")]
+        json: bool,
+    },
+    /// List the jobs of a job group
+    Group {
+        #[arg(
+            help = "Group UUID, or the ID of one of its jobs",
+            value_hint = clap::ValueHint::Other
+        )]
+        group: String,
+
+        #[arg(long, help = "Print JSON instead of a table")]
+        json: bool,
+    },
+    /// Draw the dependency graph of a job, a job group or a user's jobs
+    Dag {
+        #[arg(
+            help = "Show the jobs linked to this one by dependencies (supports @)",
+            conflicts_with_all = ["group", "user", "all_users"],
+            value_hint = clap::ValueHint::Other
+        )]
+        job: Option<String>,
+
+        #[arg(
+            long,
+            help = "Show the jobs of this group (UUID, or the ID of one of its jobs)",
+            conflicts_with_all = ["user", "all_users"],
+            value_hint = clap::ValueHint::Other
+        )]
+        group: Option<String>,
+
+        #[arg(
+            short,
+            long,
+            help = "Show these users' jobs (comma-separated; default: your own)",
+            value_hint = clap::ValueHint::Username
+        )]
+        user: Option<String>,
+
+        #[arg(long, conflicts_with = "user", help = "Show every user's jobs")]
+        all_users: bool,
+
+        #[arg(long, value_enum, default_value_t, help = "Output format")]
+        format: crate::multicall::gjob::commands::dag::DagFormat,
+    },
     /// Close tmux sessions for completed jobs (by default). Use --state to close sessions in other states.
     #[command(visible_alias = "close")]
     CloseSessions {
@@ -297,4 +545,204 @@ mod tests {
         assert!(message.contains("--first"));
         assert!(message.contains("--last"));
     }
+
+    #[test]
+    fn parses_stats_filters() {
+        let args = GJob::try_parse_from(["gjob", "stats", "train-*", "-u", "alice"])
+            .expect("should parse");
+
+        match args.command {
+            Commands::Stats { name, user, json } => {
+                assert_eq!(name.as_deref(), Some("train-*"));
+                assert_eq!(user.as_deref(), Some("alice"));
+                assert!(!json);
+            }
+            other => panic!("unexpected command: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn rerun_failed_needs_a_job_or_group() {
+        let args = GJob::try_parse_from(["gjob", "rerun-failed", "--group", "42", "--dry-run"])
+            .expect("should parse");
+        match args.command {
+            Commands::RerunFailed {
+                job,
+                group,
+                dry_run,
+            } => {
+                assert_eq!(job, None);
+                assert_eq!(group.as_deref(), Some("42"));
+                assert!(dry_run);
+            }
+            other => panic!("unexpected command: {other:?}"),
+        }
+
+        assert!(GJob::try_parse_from(["gjob", "rerun-failed"]).is_err());
+        assert!(GJob::try_parse_from(["gjob", "rerun-failed", "3", "--group", "42"]).is_err());
+    }
+
+    #[test]
+    fn notify_needs_a_webhook_or_remove() {
+        let args = GJob::try_parse_from([
+            "gjob",
+            "notify",
+            "7",
+            "--webhook",
+            "https://hooks.example/x",
+            "--events",
+            "finished,failed",
+        ])
+        .expect("should parse");
+        match args.command {
+            Commands::Notify {
+                job,
+                webhook,
+                events,
+                remove,
+            } => {
+                use crate::multicall::gjob::commands::notify::NotifyEvent;
+                assert_eq!(job, "7");
+                assert_eq!(webhook.as_deref(), Some("https://hooks.example/x"));
+                assert_eq!(events, vec![NotifyEvent::Finished, NotifyEvent::Failed]);
+                assert!(!remove);
+            }
+            other => panic!("unexpected command: {other:?}"),
+        }
+
+        assert!(GJob::try_parse_from(["gjob", "notify", "7", "--remove"]).is_ok());
+        assert!(GJob::try_parse_from(["gjob", "notify", "7"]).is_err());
+        assert!(GJob::try_parse_from([
+            "gjob",
+            "notify",
+            "7",
+            "--remove",
+            "--webhook",
+            "https://hooks.example/x"
+        ])
+        .is_err());
+        assert!(
+            GJob::try_parse_from(["gjob", "notify", "7", "--remove", "--events", "failed"])
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn artifacts_lists_or_adds() {
+        let args = GJob::try_parse_from([
+            "gjob",
+            "artifacts",
+            "7",
+            "--add",
+            "out/model.pt",
+            "--label",
+            "best",
+        ])
+        .expect("should parse");
+        match args.command {
+            Commands::Artifacts {
+                job,
+                add,
+                label,
+                json,
+            } => {
+                assert_eq!(job, "7");
+                assert_eq!(add, Some("out/model.pt".into()));
+                assert_eq!(label.as_deref(), Some("best"));
+                assert!(!json);
+            }
+            other => panic!("unexpected command: {other:?}"),
+        }
+
+        assert!(GJob::try_parse_from(["gjob", "artifacts", "7", "--json"]).is_ok());
+        assert!(GJob::try_parse_from(["gjob", "artifacts", "7", "--label", "best"]).is_err());
+        assert!(GJob::try_parse_from(["gjob", "artifacts", "7", "--add", "a", "--json"]).is_err());
+    }
+
+    #[test]
+    fn tag_takes_repeated_or_comma_separated_tags() {
+        let args = GJob::try_parse_from([
+            "gjob",
+            "tag",
+            "7",
+            "--add",
+            "exp42,baseline",
+            "--add",
+            "lr",
+            "--remove",
+            "old",
+        ])
+        .expect("should parse");
+        match args.command {
+            Commands::Tag { job, add, remove } => {
+                assert_eq!(job, "7");
+                assert_eq!(add, ["exp42", "baseline", "lr"]);
+                assert_eq!(remove, ["old"]);
+            }
+            other => panic!("unexpected command: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn wait_takes_several_jobs_and_durations() {
+        let args = GJob::try_parse_from([
+            "gjob",
+            "wait",
+            "101",
+            "102-103",
+            "--for",
+            "ended",
+            "--any",
+            "--timeout",
+            "6h",
+            "--quiet",
+        ])
+        .expect("should parse");
+        assert!(args.verbosity.is_silent());
+        match args.command {
+            Commands::Wait {
+                jobs,
+                condition,
+                any,
+                timeout,
+                poll,
+            } => {
+                assert_eq!(jobs, ["101", "102-103"]);
+                assert_eq!(
+                    condition,
+                    crate::multicall::gjob::commands::wait::WaitCondition::Ended
+                );
+                assert!(any);
+                assert_eq!(timeout, Some(std::time::Duration::from_secs(6 * 3600)));
+                assert_eq!(poll, std::time::Duration::from_secs(10));
+            }
+            other => panic!("unexpected command: {other:?}"),
+        }
+
+        assert!(GJob::try_parse_from(["gjob", "wait"]).is_err());
+        assert!(GJob::try_parse_from(["gjob", "wait", "1", "--timeout", "6"]).is_err());
+    }
+
+    #[test]
+    fn parses_dag_selection_and_format() {
+        let args = GJob::try_parse_from(["gjob", "dag", "--group", "42", "--format", "dot"])
+            .expect("should parse");
+
+        match args.command {
+            Commands::Dag {
+                job, group, format, ..
+            } => {
+                assert_eq!(job, None);
+                assert_eq!(group.as_deref(), Some("42"));
+                assert_eq!(
+                    format,
+                    crate::multicall::gjob::commands::dag::DagFormat::Dot
+                );
+            }
+            other => panic!("unexpected command: {other:?}"),
+        }
+
+        assert!(GJob::try_parse_from(["gjob", "dag", "42", "--group", "7"]).is_err());
+        assert!(GJob::try_parse_from(["gjob", "dag", "--group", "7", "-u", "alice"]).is_err());
+    }
 }
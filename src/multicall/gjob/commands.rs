@@ -1,32 +1,118 @@
 use super::cli::Commands;
+use crate::multicall::completion::{DynamicKind, DynamicSpec};
 use clap::CommandFactory;
 
+pub mod artifacts;
 pub mod attach;
+pub mod clean;
 pub mod close_sessions;
+pub mod dag;
+pub mod groups;
 pub mod hold;
 pub mod log;
+pub mod notify;
+pub mod path;
 pub mod redo;
 pub mod release;
+pub mod rerun_failed;
+pub mod restore;
+pub mod sessions;
 pub mod show;
+pub mod stats;
+pub mod tag;
 pub mod update;
+pub mod wait;
+
+const DYNAMIC_COMPLETION: DynamicSpec = DynamicSpec {
+    positional: None,
+    subcommands: &[
+        (
+            &["hold", "h", "release", "r", "notify", "update", "u", "wait"],
+            DynamicKind::Active,
+        ),
+        (
+            &[
+                "attach",
+                "a",
+                "log",
+                "l",
+                "path",
+                "artifacts",
+                "clean",
+                "tag",
+                "show",
+                "s",
+                "redo",
+                "restore",
+            ],
+            DynamicKind::Recent,
+        ),
+    ],
+    options: &[],
+};
 
 pub async fn handle_commands(
     config_path: &Option<std::path::PathBuf>,
+    verbosity: clap_verbosity_flag::Verbosity,
     command: Commands,
 ) -> anyhow::Result<()> {
     match command {
-        Commands::Attach { job } => {
-            attach::handle_attach(config_path, &job).await?;
+        Commands::Attach { job, read_only } => {
+            attach::handle_attach(config_path, &job, read_only).await?;
+        }
+        Commands::Sessions => {
+            sessions::handle_sessions(config_path).await?;
         }
         Commands::Log { job, first, last } => {
             log::handle_log(config_path, &job, first, last).await?;
         }
+        Commands::Path { job } => {
+            path::handle_path(config_path, &job).await?;
+        }
+        Commands::Clean { job } => {
+            clean::handle_clean(config_path, &job).await?;
+        }
+        Commands::Artifacts {
+            job,
+            add,
+            label,
+            json,
+        } => {
+            artifacts::handle_artifacts(config_path, &job, add, label, json).await?;
+        }
+        Commands::Tag { job, add, remove } => {
+            tag::handle_tag(config_path, &job, &add, &remove).await?;
+        }
+        Commands::Wait {
+            jobs,
+            condition,
+            any,
+            timeout,
+            poll,
+        } => {
+            let options = wait::WaitOptions {
+                condition,
+                any,
+                timeout,
+                poll,
+                quiet: verbosity.is_silent(),
+            };
+            wait::handle_wait(config_path, &jobs, options).await?;
+        }
         Commands::Hold { job } => {
             hold::handle_hold(config_path, job).await?;
         }
         Commands::Release { job } => {
             release::handle_release(config_path, job).await?;
         }
+        Commands::Notify {
+            job,
+            webhook,
+            events,
+            remove,
+        } => {
+            notify::handle_notify(config_path, &job, webhook, events, remove).await?;
+        }
         Commands::Update {
             job,
             command,
@@ -51,6 +137,8 @@ pub async fn handle_commands(
             max_retries,
             clear_max_retries,
             params,
+            user,
+            all_users,
         } => {
             let update_params = update::UpdateJobParams {
                 job_ids_str: job,
@@ -76,6 +164,8 @@ pub async fn handle_commands(
                 max_retries,
                 clear_max_retries,
                 params,
+                user,
+                all_users,
             };
             update::handle_update(config_path, update_params).await?;
         }
@@ -109,6 +199,38 @@ pub async fn handle_commands(
             )
             .await?;
         }
+        Commands::Restore { job, last } => {
+            restore::handle_restore(config_path, job, last).await?;
+        }
+        Commands::RerunFailed {
+            job,
+            group,
+            dry_run,
+        } => {
+            rerun_failed::handle_rerun_failed(config_path, job, group, dry_run).await?;
+        }
+        Commands::Stats { name, user, json } => {
+            stats::handle_stats(config_path, name, user, json).await?;
+        }
+        Commands::Groups {
+            user,
+            all_users,
+            json,
+        } => {
+            groups::handle_groups(config_path, user, all_users, json).await?;
+        }
+        Commands::Group { group, json } => {
+            groups::handle_group(config_path, &group, json).await?;
+        }
+        Commands::Dag {
+            job,
+            group,
+            user,
+            all_users,
+            format,
+        } => {
+            dag::handle_dag(config_path, job, group, user, all_users, format).await?;
+        }
         Commands::CloseSessions {
             jobs,
             state,
@@ -119,10 +241,11 @@ pub async fn handle_commands(
                 .await?;
         }
         Commands::Completion { shell } => {
-            crate::multicall::completion::handle_completion(
+            crate::multicall::completion::handle_dynamic_completion(
                 shell,
                 super::cli::GJob::command(),
                 "gjob",
+                &DYNAMIC_COMPLETION,
             )?;
         }
     }
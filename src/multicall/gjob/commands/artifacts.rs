@@ -0,0 +1,62 @@
+use crate::multicall::gjob::utils::resolve_job_id;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local};
+use gflow::core::job::JobArtifact;
+use gflow::utils::format_bytes;
+use std::path::PathBuf;
+use tabled::{builder::Builder, settings::style::Style};
+
+pub async fn handle_artifacts(
+    config_path: &Option<PathBuf>,
+    job: &str,
+    add: Option<PathBuf>,
+    label: Option<String>,
+    json: bool,
+) -> Result<()> {
+    let client = gflow::create_client(config_path)?;
+    let job_id = resolve_job_id(&client, job).await?;
+
+    if let Some(path) = add {
+        // Relative to where gjob runs, not to the job's run directory.
+        let path = std::path::absolute(&path)
+            .with_context(|| format!("Invalid path '{}'", path.display()))?;
+        let artifact = client
+            .register_job_artifact(job_id, &path, label.as_deref())
+            .await?;
+        println!(
+            "Registered {} ({}) as an artifact of job {job_id}.",
+            artifact.path.display(),
+            format_bytes(artifact.size_bytes)
+        );
+        return Ok(());
+    }
+
+    let artifacts = client.get_job_artifacts(job_id).await?;
+    if json {
+        println!("{}", serde_json::to_string_pretty(&artifacts)?);
+        return Ok(());
+    }
+    if artifacts.is_empty() {
+        println!("Job {job_id} has no artifacts.");
+        return Ok(());
+    }
+
+    let mut builder = Builder::default();
+    builder.push_record(["LABEL", "SIZE", "REGISTERED", "PATH"]);
+    for artifact in &artifacts {
+        builder.push_record(row_cells(artifact));
+    }
+    println!("{}", builder.build().with(Style::blank()));
+    Ok(())
+}
+
+fn row_cells(artifact: &JobArtifact) -> [String; 4] {
+    [
+        artifact.label.clone().unwrap_or_else(|| "-".to_string()),
+        format_bytes(artifact.size_bytes),
+        DateTime::<Local>::from(artifact.registered_at)
+            .format("%Y-%m-%d %H:%M")
+            .to_string(),
+        artifact.path.display().to_string(),
+    ]
+}
@@ -1,11 +1,74 @@
 use anyhow::Result;
-use gflow::tmux::{attach_to_session, is_session_exist};
+use gflow::config::ListenAddr;
+use gflow::core::executor::ExecutorKind;
+use gflow::core::job::{Job, JobState};
+use gflow::tmux::{attach_to_session, JobTarget};
+
+/// What `gjob attach` can do for a job.
+#[derive(Debug, PartialEq, Eq)]
+enum AttachTarget {
+    /// The session exists on this host.
+    Local(String),
+    /// The daemon runs elsewhere; the session is on its host.
+    Remote { host: String, session: String },
+    /// Nothing to attach to, and why.
+    Unavailable(String),
+}
+
+/// `local` is the job's session or window on this host, if it has one.
+fn attach_target(job: &Job, daemon: &ListenAddr, local: Option<JobTarget>) -> AttachTarget {
+    let job_id = job.id;
+    if job.executor == Some(ExecutorKind::Direct) {
+        return AttachTarget::Unavailable(format!(
+            "Job {job_id} runs without tmux (direct executor); view its output with `gjob log {job_id}`"
+        ));
+    }
+    let Some(session) = job.run_name.as_ref().map(ToString::to_string) else {
+        return AttachTarget::Unavailable(format!(
+            "Job {job_id} does not have an associated tmux session"
+        ));
+    };
+    if let Some(local) = local {
+        return AttachTarget::Local(local.as_str().to_string());
+    }
+
+    if let ListenAddr::Tcp { host, .. } = daemon {
+        if !daemon.is_local() && !job.state.is_final() {
+            return AttachTarget::Remote {
+                host: host.clone(),
+                session,
+            };
+        }
+    }
+
+    let reason = match job.state {
+        JobState::Queued | JobState::Hold => format!(
+            "Job {job_id} has not started yet ({}); its session '{session}' will exist once it runs",
+            job.state
+        ),
+        JobState::Running => format!(
+            "Tmux session '{session}' for job {job_id} does not exist on this host"
+        ),
+        state if job.auto_close_tmux => format!(
+            "Job {job_id} is {state} and its session '{session}' was closed automatically; \
+             view its output with `gjob log {job_id}`"
+        ),
+        state => format!(
+            "Job {job_id} is {state} and its session '{session}' no longer exists; \
+             view its output with `gjob log {job_id}`"
+        ),
+    };
+    AttachTarget::Unavailable(reason)
+}
 
 pub async fn handle_attach(
     config_path: &Option<std::path::PathBuf>,
     job_id_str: &str,
+    read_only: bool,
 ) -> Result<()> {
-    let client = gflow::create_client(config_path)?;
+    let mut config = gflow::config::load_config(config_path.as_ref())?;
+    config.use_selected_remote()?;
+    let client = gflow::Client::build(&config)?;
 
     // Resolve job ID (handle @ shorthand)
     let job_id = crate::multicall::gjob::utils::resolve_job_id(&client, job_id_str).await?;
@@ -15,33 +78,99 @@ pub async fn handle_attach(
         return Ok(());
     };
 
-    // Check if the job has a tmux session
-    let session_name = match job.run_name {
-        Some(ref name) => name,
-        None => {
-            eprintln!(
-                "Error: Job {} does not have an associated tmux session",
-                job_id
-            );
-            return Ok(());
+    let local = job
+        .run_name
+        .as_deref()
+        .and_then(|name| JobTarget::find(job.id, name));
+    match attach_target(&job, &config.daemon.listen_addr(), local) {
+        AttachTarget::Local(session) => {
+            println!("Attaching to tmux session '{session}' for job {job_id}...");
+            attach_to_session(&session, read_only)?;
         }
-    };
+        AttachTarget::Remote { host, session } => {
+            let flag = if read_only { " -r" } else { "" };
+            println!("Job {job_id} runs in tmux session '{session}' on {host}.");
+            println!("Attach there with: ssh -t {host} tmux attach -t {session}{flag}");
+        }
+        AttachTarget::Unavailable(reason) => eprintln!(
+            "{} {reason}",
+            gflow::utils::output::OutputStyle::stderr().error_label()
+        ),
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn job(state: JobState) -> Job {
+        Job {
+            id: 7,
+            state,
+            run_name: Some("gjob-7".into()),
+            ..Default::default()
+        }
+    }
+
+    fn local() -> ListenAddr {
+        ListenAddr::Tcp {
+            host: "localhost".to_string(),
+            port: 59000,
+        }
+    }
 
-    // Check if the tmux session exists
-    if !is_session_exist(session_name) {
-        eprintln!(
-            "Error: Tmux session '{}' for job {} does not exist",
-            session_name, job_id
+    #[test]
+    fn attaches_to_existing_sessions() {
+        assert_eq!(
+            attach_target(
+                &job(JobState::Running),
+                &local(),
+                Some(JobTarget::Session("gjob-7".to_string()))
+            ),
+            AttachTarget::Local("gjob-7".to_string())
+        );
+        assert_eq!(
+            attach_target(
+                &job(JobState::Running),
+                &local(),
+                Some(JobTarget::Window("=gflow:=7-gjob-7".to_string()))
+            ),
+            AttachTarget::Local("=gflow:=7-gjob-7".to_string())
+        );
+    }
+
+    #[test]
+    fn points_remote_jobs_at_the_daemon_host() {
+        let remote = ListenAddr::Tcp {
+            host: "gpu-node-3".to_string(),
+            port: 59000,
+        };
+        assert_eq!(
+            attach_target(&job(JobState::Running), &remote, None),
+            AttachTarget::Remote {
+                host: "gpu-node-3".to_string(),
+                session: "gjob-7".to_string()
+            }
         );
-        return Ok(());
     }
 
-    // Attach to the tmux session
-    println!(
-        "Attaching to tmux session '{}' for job {}...",
-        session_name, job_id
-    );
-    attach_to_session(session_name)?;
+    #[test]
+    fn points_closed_sessions_at_the_log() {
+        let mut finished = job(JobState::Finished);
+        finished.auto_close_tmux = true;
+        let AttachTarget::Unavailable(reason) = attach_target(&finished, &local(), None) else {
+            panic!("finished job should have nothing to attach to");
+        };
+        assert!(reason.contains("closed automatically"), "{reason}");
+        assert!(reason.contains("gjob log 7"), "{reason}");
 
-    Ok(())
+        let mut direct = job(JobState::Running);
+        direct.executor = Some(ExecutorKind::Direct);
+        let AttachTarget::Unavailable(reason) = attach_target(&direct, &local(), None) else {
+            panic!("direct job has no session");
+        };
+        assert!(reason.contains("direct executor"), "{reason}");
+    }
 }
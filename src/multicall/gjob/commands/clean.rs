@@ -0,0 +1,17 @@
+use crate::multicall::gjob::utils::resolve_job_id;
+use anyhow::Result;
+use gflow::utils::format_bytes;
+use std::path::PathBuf;
+
+/// Remove a finished job's `GFLOW_JOB_DIR` and report how much it held.
+pub async fn handle_clean(config_path: &Option<PathBuf>, job: &str) -> Result<()> {
+    let client = gflow::create_client(config_path)?;
+    let job_id = resolve_job_id(&client, job).await?;
+    let removed = client.remove_job_dir(job_id).await?;
+    println!(
+        "Removed {} ({}) of job {job_id}.",
+        removed.path.display(),
+        format_bytes(removed.bytes)
+    );
+    Ok(())
+}
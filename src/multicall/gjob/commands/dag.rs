@@ -0,0 +1,388 @@
+use super::groups::resolve_group_id;
+use crate::multicall::gjob::utils::resolve_job_id;
+use anyhow::{anyhow, bail, Result};
+use gflow::client::{JobFilter, UserScope};
+use gflow::core::job::{DependencyMode, Job, JobState};
+use gflow::utils::output::OutputStyle;
+use owo_colors::Style;
+use std::collections::{BTreeMap, BTreeSet, HashSet, VecDeque};
+use std::fmt::Write;
+
+/// How `gjob dag` prints the graph.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum DagFormat {
+    /// An indented tree with states in color
+    #[default]
+    Ascii,
+    /// Graphviz DOT, e.g. for `dot -Tpng`
+    Dot,
+}
+
+pub async fn handle_dag(
+    config_path: &Option<std::path::PathBuf>,
+    job: Option<String>,
+    group: Option<String>,
+    user: Option<String>,
+    all_users: bool,
+    format: DagFormat,
+) -> Result<()> {
+    let mut config = gflow::config::load_config(config_path.as_ref())?;
+    config.use_selected_remote()?;
+    let client = gflow::Client::build(&config)?;
+
+    let jobs = if let Some(job) = job {
+        let job_id = resolve_job_id(&client, &job).await?;
+        let all_jobs = client.list_jobs().await?;
+        if !all_jobs.iter().any(|job| job.id == job_id) {
+            bail!("Job {} not found", job_id);
+        }
+        connected_jobs(all_jobs, job_id)
+    } else if let Some(group) = group {
+        let group_id = resolve_group_id(&client, &group).await?;
+        client
+            .list_group_jobs(&group_id)
+            .await?
+            .ok_or_else(|| anyhow!("No jobs found with group_id '{}'", group_id))?
+    } else {
+        let scope = UserScope::from_args(
+            user.as_deref(),
+            all_users,
+            config.gqueue.default_user_filter(),
+        );
+        client
+            .find_jobs(&JobFilter {
+                user: scope.user_filter(),
+                ..Default::default()
+            })
+            .await?
+    };
+
+    if jobs.is_empty() {
+        println!("No jobs found.");
+        return Ok(());
+    }
+
+    let graph = Graph::new(&jobs)?;
+    match format {
+        DagFormat::Ascii => print!("{}", graph.render_ascii(&OutputStyle::stdout())),
+        DagFormat::Dot => print!("{}", graph.render_dot()),
+    }
+    Ok(())
+}
+
+/// `root` and every job linked to it through dependencies, in either direction.
+pub(super) fn connected_jobs(jobs: Vec<Job>, root: u32) -> Vec<Job> {
+    let mut neighbors: BTreeMap<u32, Vec<u32>> = BTreeMap::new();
+    for job in &jobs {
+        for dependency in job.dependency_ids_iter() {
+            neighbors.entry(job.id).or_default().push(dependency);
+            neighbors.entry(dependency).or_default().push(job.id);
+        }
+    }
+
+    let mut connected = HashSet::from([root]);
+    let mut queue = VecDeque::from([root]);
+    while let Some(id) = queue.pop_front() {
+        for &neighbor in neighbors.get(&id).into_iter().flatten() {
+            if connected.insert(neighbor) {
+                queue.push_back(neighbor);
+            }
+        }
+    }
+    jobs.into_iter()
+        .filter(|job| connected.contains(&job.id))
+        .collect()
+}
+
+/// The dependency graph of a set of jobs. Dependencies on jobs outside the
+/// set are kept as edges from nodes without a job.
+struct Graph<'a> {
+    jobs: BTreeMap<u32, &'a Job>,
+    /// Jobs waiting on each job, in ID order
+    dependents: BTreeMap<u32, Vec<u32>>,
+}
+
+impl<'a> Graph<'a> {
+    /// Fails when the jobs depend on each other in a cycle, which the daemon
+    /// refuses at submission but old state files may still hold.
+    fn new(jobs: &'a [Job]) -> Result<Self> {
+        let jobs: BTreeMap<u32, &Job> = jobs.iter().map(|job| (job.id, job)).collect();
+        let mut dependents: BTreeMap<u32, Vec<u32>> = BTreeMap::new();
+        for job in jobs.values() {
+            for dependency in job.dependency_ids_iter() {
+                dependents.entry(dependency).or_default().push(job.id);
+            }
+        }
+        for waiting in dependents.values_mut() {
+            waiting.sort_unstable();
+            waiting.dedup();
+        }
+
+        let graph = Self { jobs, dependents };
+        let cycle = graph.jobs_in_cycles();
+        if !cycle.is_empty() {
+            let ids: Vec<String> = cycle.iter().map(u32::to_string).collect();
+            bail!("Dependency cycle among jobs {}", ids.join(", "));
+        }
+        Ok(graph)
+    }
+
+    /// Dependencies of `job` that are in the graph.
+    fn dependencies(&self, job: &Job) -> BTreeSet<u32> {
+        job.dependency_ids_iter()
+            .filter(|id| self.jobs.contains_key(id))
+            .collect()
+    }
+
+    /// Jobs left over after repeatedly removing jobs with no dependencies left.
+    fn jobs_in_cycles(&self) -> BTreeSet<u32> {
+        let mut waiting_on: BTreeMap<u32, usize> = self
+            .jobs
+            .values()
+            .map(|job| (job.id, self.dependencies(job).len()))
+            .collect();
+        let mut ready: VecDeque<u32> = waiting_on
+            .iter()
+            .filter(|(_, waiting)| **waiting == 0)
+            .map(|(id, _)| *id)
+            .collect();
+        while let Some(id) = ready.pop_front() {
+            waiting_on.remove(&id);
+            for dependent in self.dependents.get(&id).into_iter().flatten() {
+                if let Some(waiting) = waiting_on.get_mut(dependent) {
+                    *waiting -= 1;
+                    if *waiting == 0 {
+                        ready.push_back(*dependent);
+                    }
+                }
+            }
+        }
+        waiting_on.into_keys().collect()
+    }
+
+    /// Each job under the jobs it depends on. A job with several
+    /// dependencies is drawn under the first and referred to elsewhere.
+    fn render_ascii(&self, style: &OutputStyle) -> String {
+        let mut out = String::new();
+        let mut drawn = HashSet::new();
+        for job in self.jobs.values() {
+            if self.dependencies(job).is_empty() {
+                self.render_ascii_node(job, "", "", style, &mut drawn, &mut out);
+            }
+        }
+        out
+    }
+
+    fn render_ascii_node(
+        &self,
+        job: &Job,
+        connector: &str,
+        prefix: &str,
+        style: &OutputStyle,
+        drawn: &mut HashSet<u32>,
+        out: &mut String,
+    ) {
+        drawn.insert(job.id);
+        let _ = writeln!(out, "{connector}{}", self.ascii_label(job, style));
+
+        let children: Vec<&Job> = self
+            .dependents
+            .get(&job.id)
+            .into_iter()
+            .flatten()
+            .filter_map(|id| self.jobs.get(id).copied())
+            .collect();
+        let (branch, edge, pipe, arrow) = if style.unicode {
+            ("├─ ", "╰─ ", "│  ", "→")
+        } else {
+            ("|- ", "`- ", "|  ", "->")
+        };
+        for (index, child) in children.iter().enumerate() {
+            let is_last = index + 1 == children.len();
+            let child_connector = format!("{prefix}{}", if is_last { edge } else { branch });
+            if drawn.contains(&child.id) {
+                let _ = writeln!(out, "{child_connector}{arrow} see job {}", child.id);
+            } else {
+                let child_prefix = format!("{prefix}{}", if is_last { "   " } else { pipe });
+                self.render_ascii_node(child, &child_connector, &child_prefix, style, drawn, out);
+            }
+        }
+    }
+
+    /// `ID NAME ST`, and which jobs it waits for when there are several.
+    fn ascii_label(&self, job: &Job, style: &OutputStyle) -> String {
+        let name = format!("{} {}", job.id, job.run_name.as_deref().unwrap_or("-"));
+        let mut label = format!(
+            "{} {}",
+            match terminal_style(job.state) {
+                Some(color) => style.paint(name, color),
+                None => name,
+            },
+            style.state(job.state)
+        );
+        let dependencies: Vec<String> =
+            job.dependency_ids_iter().map(|id| id.to_string()).collect();
+        if dependencies.len() > 1 {
+            let mode = match job.dependency_mode.unwrap_or(DependencyMode::All) {
+                DependencyMode::All => "all",
+                DependencyMode::Any => "any",
+            };
+            let _ = write!(label, " (needs {mode} of {})", dependencies.join(", "));
+        }
+        label
+    }
+
+    /// Edges point from a dependency to the job waiting on it; waiting on
+    /// any one of several dependencies is drawn dashed.
+    fn render_dot(&self) -> String {
+        let mut out = String::from("digraph gflow {\n    rankdir=LR;\n    node [shape=box];\n");
+        for job in self.jobs.values() {
+            let label = format!(
+                "{}: {}\n{}",
+                job.id,
+                job.run_name.as_deref().unwrap_or("-"),
+                job.state
+            );
+            let _ = write!(out, "    {} [label=\"{}\"", job.id, dot_escape(&label));
+            if let Some(color) = dot_color(job.state) {
+                let _ = write!(out, ", color={color}, fontcolor={color}");
+            }
+            out.push_str("];\n");
+        }
+        for (dependency, _) in self
+            .dependents
+            .iter()
+            .filter(|(id, _)| !self.jobs.contains_key(id))
+        {
+            let _ = writeln!(
+                out,
+                "    {dependency} [label=\"{dependency}\", style=dotted];"
+            );
+        }
+        for (dependency, dependents) in &self.dependents {
+            for dependent in dependents {
+                let any = self.jobs[dependent].dependency_mode == Some(DependencyMode::Any)
+                    && self.jobs[dependent].dependency_ids_iter().count() > 1;
+                let attributes = if any {
+                    " [style=dashed, label=\"any\"]"
+                } else {
+                    ""
+                };
+                let _ = writeln!(out, "    {dependency} -> {dependent}{attributes};");
+            }
+        }
+        out.push_str("}\n");
+        out
+    }
+}
+
+fn terminal_style(state: JobState) -> Option<Style> {
+    match state {
+        JobState::Finished => Some(Style::new().green()),
+        JobState::Failed | JobState::Timeout => Some(Style::new().red()),
+        JobState::Cancelled => Some(Style::new().dimmed()),
+        _ => None,
+    }
+}
+
+fn dot_color(state: JobState) -> Option<&'static str> {
+    match state {
+        JobState::Finished => Some("green"),
+        JobState::Failed | JobState::Timeout => Some("red"),
+        JobState::Cancelled => Some("gray"),
+        _ => None,
+    }
+}
+
+/// `text` for use inside a double-quoted DOT string.
+fn dot_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gflow::core::job::JobBuilder;
+
+    fn job(id: u32, name: &str, state: JobState, depends_on: &[u32]) -> Job {
+        let mut job = JobBuilder::new()
+            .command("true")
+            .run_name(Some(name.to_string()))
+            .build();
+        job.id = id;
+        job.state = state;
+        job.depends_on_ids = depends_on.iter().copied().collect();
+        job
+    }
+
+    fn pipeline() -> Vec<Job> {
+        let mut eval = job(4, "eval", JobState::Queued, &[2, 3]);
+        eval.dependency_mode = Some(DependencyMode::Any);
+        vec![
+            job(1, "prepare", JobState::Finished, &[]),
+            job(2, "train", JobState::Failed, &[1]),
+            job(3, "baseline", JobState::Running, &[1]),
+            eval,
+        ]
+    }
+
+    #[test]
+    fn ascii_tree_draws_shared_dependents_once() {
+        let jobs = pipeline();
+        let graph = Graph::new(&jobs).unwrap();
+        assert_eq!(
+            graph.render_ascii(&OutputStyle::default()),
+            "1 prepare CD\n\
+             |- 2 train F\n\
+             |  `- 4 eval PD (needs any of 2, 3)\n\
+             `- 3 baseline R\n   \
+             `- -> see job 4\n"
+        );
+    }
+
+    #[test]
+    fn dot_output_marks_states_modes_and_outside_jobs() {
+        let mut jobs = pipeline();
+        jobs.remove(0);
+        jobs[0].run_name = Some("say \"hi\"\\".into());
+        let dot = Graph::new(&jobs).unwrap().render_dot();
+
+        assert!(dot.starts_with("digraph gflow {\n"));
+        assert!(dot.contains(
+            "    2 [label=\"2: say \\\"hi\\\"\\\\\\nFailed\", color=red, fontcolor=red];\n"
+        ));
+        assert!(dot.contains("    1 [label=\"1\", style=dotted];\n"));
+        assert!(dot.contains("    1 -> 2;\n"));
+        assert!(dot.contains("    2 -> 4 [style=dashed, label=\"any\"];\n"));
+        assert!(dot.ends_with("}\n"));
+    }
+
+    #[test]
+    fn cycles_are_reported() {
+        let jobs = vec![
+            job(1, "a", JobState::Queued, &[3]),
+            job(2, "b", JobState::Queued, &[1]),
+            job(3, "c", JobState::Queued, &[2]),
+            job(4, "d", JobState::Queued, &[]),
+        ];
+        let error = Graph::new(&jobs).err().unwrap();
+        assert_eq!(error.to_string(), "Dependency cycle among jobs 1, 2, 3");
+    }
+
+    #[test]
+    fn connected_jobs_follow_dependencies_both_ways() {
+        let mut jobs = pipeline();
+        jobs.push(job(5, "other", JobState::Queued, &[]));
+        let ids: Vec<u32> = connected_jobs(jobs, 3).iter().map(|job| job.id).collect();
+        assert_eq!(ids, [1, 2, 3, 4]);
+    }
+}
@@ -0,0 +1,163 @@
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Local};
+use gflow::client::UserScope;
+use gflow::core::job::{Job, JobGroup, JobState};
+use gflow::utils::format_duration_compact;
+use gflow::utils::output::OutputStyle;
+use std::time::SystemTime;
+use tabled::{builder::Builder, settings::style::Style};
+
+pub async fn handle_groups(
+    config_path: &Option<std::path::PathBuf>,
+    user: Option<String>,
+    all_users: bool,
+    json: bool,
+) -> Result<()> {
+    let mut config = gflow::config::load_config(config_path.as_ref())?;
+    config.use_selected_remote()?;
+    let client = gflow::Client::build(&config)?;
+    let scope = UserScope::from_args(
+        user.as_deref(),
+        all_users,
+        config.gqueue.default_user_filter(),
+    );
+
+    let groups = client.list_groups(scope.user_filter()).await?;
+    if json {
+        println!("{}", serde_json::to_string_pretty(&groups)?);
+        return Ok(());
+    }
+    if groups.is_empty() {
+        println!("No job groups found.");
+        return Ok(());
+    }
+
+    let style = OutputStyle::stdout();
+    let mut builder = Builder::default();
+    builder.push_record([
+        "GROUP",
+        "OWNER",
+        "JOBS",
+        "STATES",
+        "LIMIT",
+        "SUBMITTED",
+        "LAST ACTIVITY",
+    ]);
+    for group in &groups {
+        builder.push_record([
+            group.id.to_string(),
+            group.owner.clone(),
+            group.total().to_string(),
+            state_counts(group, &style),
+            group
+                .max_concurrent
+                .map_or_else(|| "-".to_string(), |limit| limit.to_string()),
+            format_time(group.first_submitted_at),
+            format_time(group.last_activity_at),
+        ]);
+    }
+    println!("{}", builder.build().with(Style::blank()));
+    Ok(())
+}
+
+pub async fn handle_group(
+    config_path: &Option<std::path::PathBuf>,
+    group: &str,
+    json: bool,
+) -> Result<()> {
+    let client = gflow::create_client(config_path)?;
+    let group_id = resolve_group_id(&client, group).await?;
+
+    let summary = client.get_group(&group_id).await?;
+    let jobs = client.list_group_jobs(&group_id).await?;
+    let (Some(summary), Some(jobs)) = (summary, jobs) else {
+        return Err(anyhow!("No jobs found with group_id '{}'", group_id));
+    };
+
+    if json {
+        let output = serde_json::json!({ "group": summary, "jobs": jobs });
+        println!("{}", serde_json::to_string_pretty(&output)?);
+        return Ok(());
+    }
+
+    let style = OutputStyle::stdout();
+    let limit = summary
+        .max_concurrent
+        .map(|limit| format!(", at most {limit} running"))
+        .unwrap_or_default();
+    println!(
+        "Group {} ({}): {} job(s): {}{limit}",
+        summary.id,
+        summary.owner,
+        summary.total(),
+        state_counts(&summary, &style)
+    );
+    println!();
+
+    let mut builder = Builder::default();
+    builder.push_record(["JOBID", "NAME", "ST", "TIME", "PARAMETERS"]);
+    for job in &jobs {
+        builder.push_record(job_row(job, &style));
+    }
+    println!("{}", builder.build().with(Style::blank()));
+    Ok(())
+}
+
+/// The group UUID `group`, or the group of the job with ID `group`.
+pub(super) async fn resolve_group_id(client: &gflow::Client, group: &str) -> Result<String> {
+    let Ok(job_id) = group.parse::<u32>() else {
+        return Ok(group.to_string());
+    };
+    let job = client
+        .get_job(job_id)
+        .await?
+        .ok_or_else(|| anyhow!("Job {} not found", job_id))?;
+    job.group_id
+        .map(|group_id| group_id.to_string())
+        .ok_or_else(|| anyhow!("Job {} is not part of a group", job_id))
+}
+
+/// Member counts such as `2 R, 3 PD, 5 CD`, with states in their colors.
+fn state_counts(group: &JobGroup, style: &OutputStyle) -> String {
+    group
+        .jobs_by_state
+        .iter()
+        .map(|(&state, count)| format!("{count} {}", style.state(state)))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn job_row(job: &Job, style: &OutputStyle) -> [String; 5] {
+    let time = match job.state {
+        JobState::Queued | JobState::Hold => job.wait_time_as_of(SystemTime::now()),
+        _ => job.runtime(),
+    };
+    let parameters = job
+        .parameters
+        .iter()
+        .map(|(key, value)| format!("{key}={value}"))
+        .collect::<Vec<_>>()
+        .join(" ");
+    [
+        job.id.to_string(),
+        job.run_name.as_deref().unwrap_or("-").to_string(),
+        style.state(job.state),
+        time.map_or_else(|| "-".to_string(), format_duration_compact),
+        if parameters.is_empty() {
+            "-".to_string()
+        } else {
+            parameters
+        },
+    ]
+}
+
+fn format_time(time: Option<SystemTime>) -> String {
+    time.map_or_else(
+        || "-".to_string(),
+        |time| {
+            DateTime::<Local>::from(time)
+                .format("%Y-%m-%d %H:%M")
+                .to_string()
+        },
+    )
+}
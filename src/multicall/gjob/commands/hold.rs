@@ -1,5 +1,5 @@
 use anyhow::Result;
-use gflow::utils::parse_job_ids;
+use gflow::{client::JobAction, utils::parse_job_ids};
 
 pub async fn handle_hold(
     config_path: &Option<std::path::PathBuf>,
@@ -9,6 +9,16 @@ pub async fn handle_hold(
 
     let job_ids = parse_job_ids(&job_ids_str)?;
 
+    if job_ids.len() > 1 {
+        for result in client.apply_job_action(JobAction::Hold, &job_ids).await? {
+            match result.into_result(JobAction::Hold) {
+                Ok(job_id) => println!("Job {} put on hold.", job_id),
+                Err(error) => gflow::client::print_error(&error),
+            }
+        }
+        return Ok(());
+    }
+
     for &job_id in &job_ids {
         // Get the job from the daemon to check its state
         let Some(job) = gflow::client::get_job_or_warn(&client, job_id).await? else {
@@ -19,7 +29,7 @@ pub async fn handle_hold(
         if let Err(e) =
             gflow::utils::validate_job_state(&job, gflow::core::job::JobState::Queued, "held")
         {
-            eprintln!("Error: {}", e);
+            gflow::client::print_error(&e);
             continue;
         }
 
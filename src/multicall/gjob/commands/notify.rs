@@ -0,0 +1,74 @@
+use crate::multicall::gjob::utils::resolve_job_id;
+use anyhow::Result;
+use clap::ValueEnum;
+
+/// How a job ends, as chosen with `gjob notify --events`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum NotifyEvent {
+    /// The job finished successfully
+    Finished,
+    /// The job failed
+    Failed,
+    /// The job was cancelled
+    Cancelled,
+    /// The job hit its time limit
+    Timeout,
+}
+
+impl NotifyEvent {
+    /// The webhook event name the daemon sends for this outcome.
+    fn webhook_event(self) -> &'static str {
+        match self {
+            NotifyEvent::Finished => "job_completed",
+            NotifyEvent::Failed => "job_failed",
+            NotifyEvent::Cancelled => "job_cancelled",
+            NotifyEvent::Timeout => "job_timeout",
+        }
+    }
+}
+
+pub async fn handle_notify(
+    config_path: &Option<std::path::PathBuf>,
+    job: &str,
+    webhook: Option<String>,
+    events: Vec<NotifyEvent>,
+    remove: bool,
+) -> Result<()> {
+    let client = gflow::create_client(config_path)?;
+    let job_id = resolve_job_id(&client, job).await?;
+
+    if remove {
+        match client.remove_job_webhooks(job_id).await? {
+            0 => println!("Job {job_id} has no webhooks."),
+            1 => println!("Removed 1 webhook from job {job_id}."),
+            n => println!("Removed {n} webhooks from job {job_id}."),
+        }
+        return Ok(());
+    }
+
+    let Some(url) = webhook else {
+        anyhow::bail!("Pass --webhook <URL> or --remove");
+    };
+    let webhook_events: Vec<String> = events
+        .iter()
+        .map(|event| event.webhook_event().to_string())
+        .collect();
+    client
+        .add_job_webhook(job_id, &url, &webhook_events)
+        .await?;
+
+    if events.is_empty() {
+        println!("Job {job_id} will notify the webhook when it ends.");
+    } else {
+        let outcomes: Vec<String> = events
+            .iter()
+            .filter_map(|event| event.to_possible_value())
+            .map(|value| value.get_name().to_string())
+            .collect();
+        println!(
+            "Job {job_id} will notify the webhook if it ends as: {}.",
+            outcomes.join(", ")
+        );
+    }
+    Ok(())
+}
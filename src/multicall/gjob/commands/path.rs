@@ -0,0 +1,30 @@
+use crate::multicall::gjob::utils::resolve_job_id;
+use anyhow::Result;
+use std::path::PathBuf;
+
+/// Print a job's log, run directory, job directory and artifacts, one per line as
+/// `<kind>\t<path>`, artifacts followed by `\t<label>` when they have one.
+pub async fn handle_path(config_path: &Option<PathBuf>, job: &str) -> Result<()> {
+    let client = gflow::create_client(config_path)?;
+    let job_id = resolve_job_id(&client, job).await?;
+    let Some(job) = gflow::client::get_job_or_warn(&client, job_id).await? else {
+        return Ok(());
+    };
+
+    // Absent until the job has started.
+    if let Some(log) = client.get_job_log_path(job_id).await? {
+        println!("log\t{log}");
+    }
+    println!("run_dir\t{}", job.run_dir.display());
+    // Absent until the job has started here, and once it is removed.
+    if let Some(job_dir) = &job.job_dir {
+        println!("job_dir\t{}", job_dir.display());
+    }
+    for artifact in client.get_job_artifacts(job_id).await? {
+        match artifact.label {
+            Some(label) => println!("artifact\t{}\t{label}", artifact.path.display()),
+            None => println!("artifact\t{}", artifact.path.display()),
+        }
+    }
+    Ok(())
+}
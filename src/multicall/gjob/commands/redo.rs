@@ -1,7 +1,7 @@
 use anyhow::{anyhow, Context, Result};
-use gflow::client::Client;
-use gflow::core::job::{GpuSharingMode, Job, JobState, JobStateReason};
-use gflow::print_field;
+use gflow::client::{Client, ClientError};
+use gflow::core::job::{EnvSpec, GpuSharingMode, Job, JobState, JobStateReason};
+use gflow::{print_field, print_optional_field};
 use std::collections::{HashMap, HashSet, VecDeque};
 use std::path::PathBuf;
 use std::time::Duration;
@@ -42,7 +42,10 @@ pub async fn redo_job(
     let original_job = match client.get_job(original_job_id).await? {
         Some(job) => job,
         None => {
-            return Err(anyhow!("Job {} not found.", original_job_id));
+            return Err(ClientError::NotFound {
+                job_id: original_job_id,
+            }
+            .into());
         }
     };
 
@@ -97,6 +100,14 @@ pub(crate) fn validate_redo_source_job(original_job: &Job) -> Result<()> {
     }
 }
 
+/// The original job's environment, unless `--conda-env` overrides it.
+fn redo_environment(original_job: &Job, options: &RedoJobOptions) -> EnvSpec {
+    match &options.conda_env_override {
+        Some(conda_env) => EnvSpec::Conda(conda_env.clone().into()),
+        None => original_job.environment.clone(),
+    }
+}
+
 pub(crate) fn build_redo_job(original_job: &Job, options: &RedoJobOptions) -> Job {
     let mut builder = Job::builder();
 
@@ -111,12 +122,13 @@ pub(crate) fn build_redo_job(original_job: &Job, options: &RedoJobOptions) -> Jo
     builder = builder.gpu_sharing_mode(original_job.gpu_sharing_mode);
     builder = builder.priority(options.priority_override.unwrap_or(original_job.priority));
 
-    let conda_env = if let Some(ref override_env) = options.conda_env_override {
-        Some(override_env.clone())
-    } else {
-        original_job.conda_env.as_ref().map(|s| s.to_string())
-    };
-    builder = builder.conda_env(conda_env);
+    builder = builder.environment(redo_environment(original_job, options));
+    builder = builder.container_image(original_job.container_image.clone());
+    builder = builder.executor(original_job.executor);
+    builder = builder.interactive(original_job.interactive);
+    builder = builder.preemptible(original_job.preemptible);
+    builder = builder.keep_job_dir(original_job.keep_job_dir);
+    builder = builder.constraint(original_job.constraint.clone());
 
     let time_limit = options.time_limit_override.or(original_job.time_limit);
     builder = builder.time_limit(time_limit);
@@ -130,6 +142,8 @@ pub(crate) fn build_redo_job(original_job: &Job, options: &RedoJobOptions) -> Jo
         .gpu_memory_limit_mb_override
         .or(original_job.gpu_memory_limit_mb);
     builder = builder.gpu_memory_limit_mb(gpu_memory_limit_mb);
+    builder = builder.cpus(original_job.cpus);
+    builder = builder.min_free_disk_mb(original_job.min_free_disk_mb);
 
     let depends_on_ids = if options.clear_deps {
         Vec::new()
@@ -154,6 +168,7 @@ pub(crate) fn build_redo_job(original_job: &Job, options: &RedoJobOptions) -> Jo
     builder = builder.group_id_uuid(original_job.group_id);
     builder = builder.max_concurrent(original_job.max_concurrent);
     builder = builder.project(original_job.project.as_ref().map(|s| s.to_string()));
+    builder = builder.tags(original_job.tags.clone());
     builder = builder.notifications(original_job.notifications.clone());
     builder = builder.redone_from(Some(original_job.id));
     builder = builder.submitted_by(gflow::platform::get_current_username());
@@ -184,7 +199,7 @@ pub async fn handle_redo(
     let original_job = match client.get_job(job_id).await? {
         Some(job) => job,
         None => {
-            return Err(anyhow!("Job {} not found.", job_id));
+            return Err(ClientError::NotFound { job_id }.into());
         }
     };
 
@@ -193,7 +208,7 @@ pub async fn handle_redo(
     println!("Resubmitting job {} with parameters:", original_job.id);
 
     let time_limit_override = if let Some(ref time_str) = time_override {
-        Some(gflow::utils::parse_time_limit(time_str)?)
+        Some(gflow::utils::parse_time_limit(time_str).context(ClientError::invalid_time_limit())?)
     } else {
         None
     };
@@ -243,15 +258,12 @@ pub async fn handle_redo(
     let priority = options.priority_override.unwrap_or(original_job.priority);
     print_field!("Priority", "{}", priority);
 
-    // Apply conda environment (override or original)
-    let conda_env = if let Some(ref override_env) = options.conda_env_override {
-        Some(override_env.clone())
-    } else {
-        original_job.conda_env.as_ref().map(|s| s.to_string())
-    };
-    if let Some(ref env) = conda_env {
-        print_field!("CondaEnv", "{}", env);
+    // Apply environment (conda override or original)
+    let environment = redo_environment(&original_job, &options);
+    if !environment.is_none() {
+        print_field!("Environment", "{}", environment);
     }
+    print_optional_field!("Container", original_job.container_image);
 
     // Apply time limit (override or original)
     let time_limit = options.time_limit_override.or(original_job.time_limit);
@@ -266,6 +278,7 @@ pub async fn handle_redo(
     if let Some(memory_mb) = memory_limit_mb {
         print_field!("MemoryLimit", "{}", gflow::utils::format_memory(memory_mb));
     }
+    print_optional_field!("CPUs", original_job.cpus);
 
     // Apply per-GPU memory limit (override or original)
     let gpu_memory_limit_mb = options
@@ -395,9 +408,16 @@ async fn redo_with_cascade(
         builder = builder.gpu_sharing_mode(cascade_job.gpu_sharing_mode);
         builder = builder.gpu_memory_limit_mb(cascade_job.gpu_memory_limit_mb);
         builder = builder.priority(cascade_job.priority);
-        builder = builder.conda_env(cascade_job.conda_env.as_ref().map(|s| s.to_string()));
+        builder = builder.environment(cascade_job.environment.clone());
+        builder = builder.container_image(cascade_job.container_image.clone());
+        builder = builder.executor(cascade_job.executor);
+        builder = builder.interactive(cascade_job.interactive);
+        builder = builder.preemptible(cascade_job.preemptible);
+        builder = builder.keep_job_dir(cascade_job.keep_job_dir);
+        builder = builder.constraint(cascade_job.constraint.clone());
         builder = builder.time_limit(cascade_job.time_limit);
         builder = builder.memory_limit_mb(cascade_job.memory_limit_mb);
+        builder = builder.cpus(cascade_job.cpus);
         builder = builder.max_retries(cascade_job.max_retries);
 
         // Update dependencies to point to new job IDs
@@ -420,6 +440,7 @@ async fn redo_with_cascade(
         builder = builder.group_id_uuid(cascade_job.group_id);
         builder = builder.max_concurrent(cascade_job.max_concurrent);
         builder = builder.project(cascade_job.project.as_ref().map(|s| s.to_string()));
+        builder = builder.tags(cascade_job.tags.clone());
         builder = builder.notifications(cascade_job.notifications.clone());
 
         // Track that this job was redone from the original cascade job
@@ -488,6 +509,22 @@ mod tests {
         assert_eq!(redone_job.redone_from, Some(original_job.id));
     }
 
+    #[test]
+    fn build_redo_job_preserves_venv_environment() {
+        let original_job = JobBuilder::new()
+            .command("python train.py")
+            .environment(EnvSpec::Venv(PathBuf::from("/opt/venvs/ml")))
+            .submitted_by("alice")
+            .build();
+
+        let redone_job = build_redo_job(&original_job, &RedoJobOptions::default());
+        assert_eq!(
+            redone_job.environment,
+            EnvSpec::Venv(PathBuf::from("/opt/venvs/ml"))
+        );
+        assert_eq!(redone_job.conda_env, None);
+    }
+
     #[test]
     fn build_redo_job_applies_dependency_and_resource_overrides() {
         let original_job = JobBuilder::new()
@@ -1,5 +1,5 @@
 use anyhow::Result;
-use gflow::utils::parse_job_ids;
+use gflow::{client::JobAction, utils::parse_job_ids};
 
 pub async fn handle_release(
     config_path: &Option<std::path::PathBuf>,
@@ -9,6 +9,19 @@ pub async fn handle_release(
 
     let job_ids = parse_job_ids(&job_ids_str)?;
 
+    if job_ids.len() > 1 {
+        for result in client
+            .apply_job_action(JobAction::Release, &job_ids)
+            .await?
+        {
+            match result.into_result(JobAction::Release) {
+                Ok(job_id) => println!("Job {} released back to queue.", job_id),
+                Err(error) => gflow::client::print_error(&error),
+            }
+        }
+        return Ok(());
+    }
+
     for &job_id in &job_ids {
         // Get the job from the daemon to check its state
         let Some(job) = gflow::client::get_job_or_warn(&client, job_id).await? else {
@@ -19,7 +32,7 @@ pub async fn handle_release(
         if let Err(e) =
             gflow::utils::validate_job_state(&job, gflow::core::job::JobState::Hold, "released")
         {
-            eprintln!("Error: {}", e);
+            gflow::client::print_error(&e);
             continue;
         }
 
@@ -0,0 +1,227 @@
+use super::dag::connected_jobs;
+use super::groups::resolve_group_id;
+use super::redo::{build_redo_job, RedoJobOptions};
+use crate::multicall::gjob::utils::resolve_job_id;
+use anyhow::{anyhow, bail, Context, Result};
+use gflow::core::job::{Job, JobState};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::PathBuf;
+
+pub async fn handle_rerun_failed(
+    config_path: &Option<PathBuf>,
+    job: Option<String>,
+    group: Option<String>,
+    dry_run: bool,
+) -> Result<()> {
+    let client = gflow::create_client(config_path)?;
+
+    let jobs = if let Some(group) = group {
+        let group_id = resolve_group_id(&client, &group).await?;
+        client
+            .list_group_jobs(&group_id)
+            .await?
+            .ok_or_else(|| anyhow!("No jobs found with group_id '{}'", group_id))?
+    } else {
+        let job = job.context("A job ID or --group is required")?;
+        let job_id = resolve_job_id(&client, &job).await?;
+        let all_jobs = client.list_jobs().await?;
+        if !all_jobs.iter().any(|job| job.id == job_id) {
+            bail!("Job {} not found", job_id);
+        }
+        connected_jobs(all_jobs, job_id)
+    };
+
+    let plan = RerunPlan::new(&jobs);
+    for job in &plan.skipped {
+        gflow::utils::output::print_warning(format!(
+            "Job {} depends on a failed job but is still {}; cancel it to rerun it too",
+            job.id, job.state
+        ));
+    }
+    if plan.jobs.is_empty() {
+        println!("No failed or cancelled jobs to rerun.");
+        return Ok(());
+    }
+
+    if dry_run {
+        println!("Would rerun {} job(s):", plan.jobs.len());
+        for job in &plan.jobs {
+            println!("  {}", plan.describe(job));
+        }
+        return Ok(());
+    }
+
+    let responses = client
+        .add_jobs(plan.new_jobs())
+        .await
+        .context("Failed to submit jobs")?;
+    println!("Rerunning {} job(s):", responses.len());
+    for (job, response) in plan.jobs.iter().zip(&responses) {
+        println!(
+            "  Job {} → Job {} ({})",
+            job.id, response.id, response.run_name
+        );
+    }
+    Ok(())
+}
+
+/// The jobs to run again: failed, timed-out and cancelled jobs, and every
+/// finished job downstream of them.
+struct RerunPlan<'a> {
+    /// In job ID order, which has dependencies first
+    jobs: Vec<&'a Job>,
+    /// Downstream jobs left alone because they have not finished
+    skipped: Vec<&'a Job>,
+}
+
+impl<'a> RerunPlan<'a> {
+    fn new(jobs: &'a [Job]) -> Self {
+        let jobs: BTreeMap<u32, &Job> = jobs.iter().map(|job| (job.id, job)).collect();
+        let mut rerun: BTreeSet<u32> = jobs
+            .values()
+            .filter(|job| {
+                matches!(
+                    job.state,
+                    JobState::Failed | JobState::Timeout | JobState::Cancelled
+                )
+            })
+            .map(|job| job.id)
+            .collect();
+        let mut skipped = BTreeSet::new();
+
+        // A job's dependencies have lower IDs, so one pass in ID order sees
+        // every upstream job before its dependents.
+        for job in jobs.values() {
+            if rerun.contains(&job.id) {
+                continue;
+            }
+            if job.dependency_ids_iter().any(|id| rerun.contains(&id)) {
+                if job.state.is_final() {
+                    rerun.insert(job.id);
+                } else {
+                    skipped.insert(job.id);
+                }
+            }
+        }
+
+        Self {
+            jobs: rerun.iter().map(|id| jobs[id]).collect(),
+            skipped: skipped.iter().map(|id| jobs[id]).collect(),
+        }
+    }
+
+    fn position(&self, job_id: u32) -> Option<usize> {
+        self.jobs.iter().position(|job| job.id == job_id)
+    }
+
+    /// Copies of the jobs, each depending on the copies of the jobs it
+    /// depended on and still on any other, finished, jobs.
+    fn new_jobs(&self) -> Vec<Job> {
+        self.jobs
+            .iter()
+            .map(|job| {
+                let mut new_job = build_redo_job(job, &RedoJobOptions::default());
+                let (rerun, kept): (Vec<u32>, Vec<u32>) = job
+                    .dependency_ids_iter()
+                    .partition(|id| self.position(*id).is_some());
+                new_job.depends_on = None;
+                new_job.depends_on_ids = kept.into_iter().collect();
+                new_job.batch_depends_on = rerun
+                    .into_iter()
+                    .filter_map(|id| self.position(id))
+                    .collect();
+                new_job.dependency_mode = job.dependency_mode;
+                if new_job.depends_on_ids.len() == 1 && new_job.batch_depends_on.is_empty() {
+                    new_job.depends_on = new_job.depends_on_ids.first().copied();
+                }
+                new_job
+            })
+            .collect()
+    }
+
+    fn describe(&self, job: &Job) -> String {
+        let dependencies: Vec<String> = job
+            .dependency_ids_iter()
+            .map(|id| match self.position(id) {
+                Some(_) => format!("rerun of {id}"),
+                None => id.to_string(),
+            })
+            .collect();
+        let mut line = format!(
+            "Job {} ({}, {})",
+            job.id,
+            job.run_name.as_deref().unwrap_or("-"),
+            job.state
+        );
+        if !dependencies.is_empty() {
+            line.push_str(", after ");
+            line.push_str(&dependencies.join(", "));
+        }
+        line
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gflow::core::job::{DependencyMode, JobBuilder};
+    use std::collections::HashMap;
+
+    fn job(id: u32, state: JobState, depends_on: &[u32]) -> Job {
+        let mut job = JobBuilder::new()
+            .command(format!("step {id}"))
+            .gpus(1)
+            .parameters(HashMap::from([("lr".to_string(), "0.1".to_string())]))
+            .submitted_by("alice")
+            .run_dir("/tmp")
+            .build();
+        job.id = id;
+        job.state = state;
+        job.depends_on_ids = depends_on.iter().copied().collect();
+        job
+    }
+
+    #[test]
+    fn failed_jobs_and_their_dependents_are_rerun() {
+        let mut report = job(6, JobState::Cancelled, &[3, 5]);
+        report.dependency_mode = Some(DependencyMode::Any);
+        let jobs = vec![
+            job(1, JobState::Finished, &[]),
+            job(2, JobState::Finished, &[1]),
+            job(3, JobState::Failed, &[2]),
+            job(4, JobState::Cancelled, &[3]),
+            job(5, JobState::Finished, &[1]),
+            report,
+            job(7, JobState::Queued, &[4]),
+        ];
+
+        let plan = RerunPlan::new(&jobs);
+        let ids: Vec<u32> = plan.jobs.iter().map(|job| job.id).collect();
+        assert_eq!(ids, [3, 4, 6]);
+        let skipped: Vec<u32> = plan.skipped.iter().map(|job| job.id).collect();
+        assert_eq!(skipped, [7]);
+
+        let new_jobs = plan.new_jobs();
+        assert_eq!(new_jobs[0].depends_on_ids.as_slice(), [2]);
+        assert_eq!(new_jobs[0].depends_on, Some(2));
+        assert!(new_jobs[0].batch_depends_on.is_empty());
+        assert!(new_jobs[1].depends_on_ids.is_empty());
+        assert_eq!(new_jobs[1].depends_on, None);
+        assert_eq!(new_jobs[1].batch_depends_on, [0]);
+        assert_eq!(new_jobs[2].depends_on_ids.as_slice(), [5]);
+        assert_eq!(new_jobs[2].batch_depends_on, [0]);
+        assert_eq!(new_jobs[2].dependency_mode, Some(DependencyMode::Any));
+        for (new_job, old_job) in new_jobs.iter().zip(&plan.jobs) {
+            assert_eq!(new_job.redone_from, Some(old_job.id));
+            assert_eq!(new_job.state, JobState::Queued);
+            assert_eq!(new_job.command, old_job.command);
+            assert_eq!(new_job.gpus, 1);
+            assert_eq!(new_job.parameters, old_job.parameters);
+        }
+
+        assert_eq!(
+            plan.describe(plan.jobs[2]),
+            "Job 6 (-, Cancelled), after rerun of 3, 5"
+        );
+    }
+}
@@ -0,0 +1,133 @@
+use super::redo::{build_redo_job, RedoJobOptions};
+use anyhow::{anyhow, bail, Context, Result};
+use gflow::client::{Client, ClientError};
+use gflow::core::job::{Job, JobState};
+use std::path::PathBuf;
+
+pub async fn handle_restore(
+    config_path: &Option<PathBuf>,
+    job: Option<String>,
+    last: bool,
+) -> Result<()> {
+    let client = gflow::create_client(config_path)?;
+
+    let job_id = match job {
+        Some(job) => crate::multicall::gjob::utils::resolve_job_id(&client, &job).await?,
+        None if last => last_cancelled_job_id(&client).await?,
+        None => bail!("Give a job ID or --last"),
+    };
+    let original_job = client
+        .get_job(job_id)
+        .await?
+        .ok_or(ClientError::NotFound { job_id })?;
+    validate_restore_source_job(&original_job)?;
+
+    for dependency_id in original_job.all_dependency_ids() {
+        match client.get_job(dependency_id).await? {
+            Some(dependency) if ended_unsuccessfully(&dependency) => eprintln!(
+                "Warning: dependency {} is {}, so the restored job may never start",
+                dependency.id, dependency.state
+            ),
+            _ => {}
+        }
+    }
+
+    let response = client
+        .add_job(build_redo_job(&original_job, &RedoJobOptions::default()))
+        .await
+        .context("Failed to submit job")?;
+    println!(
+        "Restored job {} as job {} ({}).",
+        original_job.id, response.id, response.run_name
+    );
+    Ok(())
+}
+
+/// Only a job cancelled before it ever ran is restored; anything else is
+/// for `gjob redo`.
+fn validate_restore_source_job(job: &Job) -> Result<()> {
+    if job.state != JobState::Cancelled {
+        bail!(
+            "Job {} is {}; only cancelled jobs can be restored",
+            job.id,
+            job.state
+        );
+    }
+    if job.started_at.is_some() {
+        bail!(
+            "Job {} was cancelled after it started; use `gjob redo {}` to run it again",
+            job.id,
+            job.id
+        );
+    }
+    Ok(())
+}
+
+fn ended_unsuccessfully(job: &Job) -> bool {
+    job.state.is_final() && job.state != JobState::Finished
+}
+
+/// The invoking user's job that was most recently cancelled before it started.
+async fn last_cancelled_job_id(client: &Client) -> Result<u32> {
+    let username = gflow::platform::get_current_username();
+    let jobs = client
+        .list_jobs_with_query(
+            Some(JobState::Cancelled.to_string()),
+            Some(username.clone()),
+            None,
+            None,
+            None,
+            None,
+        )
+        .await?;
+    last_cancelled(&jobs)
+        .map(|job| job.id)
+        .ok_or_else(|| anyhow!("User '{username}' has no cancelled job to restore"))
+}
+
+fn last_cancelled(jobs: &[Job]) -> Option<&Job> {
+    jobs.iter()
+        .filter(|job| validate_restore_source_job(job).is_ok())
+        .max_by_key(|job| (job.finished_at, job.id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gflow::core::job::{JobBuilder, Tags};
+    use std::time::{Duration, SystemTime};
+
+    fn cancelled_job(id: u32, cancelled_secs_ago: u64) -> Job {
+        let mut job = JobBuilder::new()
+            .command("python train.py --lr {lr}")
+            .gpus(2)
+            .tags(Tags::new(["exp42"]).unwrap())
+            .submitted_by("alice")
+            .run_dir("/tmp")
+            .build();
+        job.id = id;
+        job.state = JobState::Cancelled;
+        job.finished_at = Some(SystemTime::now() - Duration::from_secs(cancelled_secs_ago));
+        job
+    }
+
+    #[test]
+    fn only_jobs_cancelled_before_starting_are_restored() {
+        let mut started = cancelled_job(3, 10);
+        started.started_at = Some(SystemTime::now() - Duration::from_secs(60));
+        let jobs = [cancelled_job(1, 30), cancelled_job(2, 20), started];
+
+        assert_eq!(last_cancelled(&jobs).map(|job| job.id), Some(2));
+        assert!(validate_restore_source_job(&jobs[2]).is_err());
+        let mut finished = cancelled_job(4, 0);
+        finished.state = JobState::Finished;
+        assert!(validate_restore_source_job(&finished).is_err());
+
+        let restored = build_redo_job(&jobs[1], &RedoJobOptions::default());
+        assert_eq!(restored.state, JobState::Queued);
+        assert_eq!(restored.redone_from, Some(2));
+        assert_eq!(restored.command, jobs[1].command);
+        assert_eq!(restored.gpus, 2);
+        assert_eq!(restored.tags, jobs[1].tags);
+    }
+}
@@ -0,0 +1,38 @@
+use anyhow::Result;
+use gflow::core::executor::ExecutorKind;
+use gflow::tmux::get_all_session_names;
+use tabled::{builder::Builder, settings::style::Style};
+
+pub async fn handle_sessions(config_path: &Option<std::path::PathBuf>) -> Result<()> {
+    let client = gflow::create_client(config_path)?;
+    let mut jobs = client
+        .list_jobs_with_query(Some("Running".to_string()), None, None, None, None, None)
+        .await?;
+
+    if jobs.is_empty() {
+        println!("No running jobs.");
+        return Ok(());
+    }
+    jobs.sort_by_key(|job| job.id);
+
+    let local_sessions = get_all_session_names();
+    let mut builder = Builder::default();
+    builder.push_record(["JOBID", "SESSION", "USER", "ON THIS HOST"]);
+    for job in jobs {
+        let (session, on_this_host) = match job.run_name.as_deref() {
+            _ if job.executor == Some(ExecutorKind::Direct) => ("-".to_string(), "direct"),
+            Some(name) if local_sessions.contains(name) => (name.to_string(), "yes"),
+            Some(name) => (name.to_string(), "no"),
+            None => ("-".to_string(), "-"),
+        };
+        builder.push_record([
+            job.id.to_string(),
+            session,
+            job.submitted_by.to_string(),
+            on_this_host.to_string(),
+        ]);
+    }
+
+    println!("{}", builder.build().with(Style::blank()));
+    Ok(())
+}
@@ -1,6 +1,6 @@
 use anyhow::Result;
-use gflow::core::job::Job;
-use gflow::utils::{parse_job_ids, substitute_parameters};
+use gflow::core::job::{Job, JobArtifact};
+use gflow::utils::{parse_job_ids, substitute_job_placeholders};
 use gflow::{print_field, print_optional_field};
 use std::path::PathBuf;
 use std::time::SystemTime;
@@ -21,35 +21,61 @@ pub async fn handle_show(config_path: &Option<PathBuf>, job_ids_str: String) ->
         };
 
         print_job_details(&job);
+        // Daemons that predate artifacts answer 404; show the job without them.
+        match client.get_job_artifacts(job_id).await {
+            Ok(artifacts) => print_artifacts(&artifacts),
+            Err(error) => tracing::debug!("Failed to get artifacts of job {job_id}: {error:#}"),
+        }
     }
     Ok(())
 }
 
+fn print_artifacts(artifacts: &[JobArtifact]) {
+    if artifacts.is_empty() {
+        return;
+    }
+    println!("\nArtifacts:");
+    for artifact in artifacts {
+        print_field!(
+            artifact.label.as_deref().unwrap_or("-"),
+            "{} ({})",
+            artifact.path.display(),
+            gflow::utils::format_bytes(artifact.size_bytes)
+        );
+    }
+}
+
 fn print_job_details(job: &Job) {
     println!("Job Details:");
     print_field!("ID", "{}", job.id);
-    print_field!("State", "{} ({})", job.state, job.state.short_form());
+    let style = gflow::utils::output::OutputStyle::stdout();
+    print_field!("State", "{} ({})", job.state, style.state(job.state));
     print_field!("Priority", "{}", job.priority);
     print_field!("SubmittedBy", "{}", job.submitted_by);
     if job.max_retries > 0 {
         print_field!("MaxRetries", "{}", job.max_retries);
     }
     print_optional_field!("GroupID", job.group_id);
+    if !job.tags.is_empty() {
+        print_field!("Tags", "{}", job.tags.iter().collect::<Vec<_>>().join(","));
+    }
 
     // Command or script
     print_optional_field!("Script", job.script, |s| s.display());
     if let Some(ref command) = job.command {
-        // Check if command contains parameters
-        let has_params = command.contains('{') && !job.parameters.is_empty();
-
-        if has_params {
-            print_field!("Command(template)", "{}", command);
-            match substitute_parameters(command, &job.parameters) {
-                Ok(substituted) => print_field!("Command(actual)", "{}", substituted),
-                Err(e) => print_field!("Command(actual)", "Error: {}", e),
+        // Show both forms when placeholders change the command
+        match substitute_job_placeholders(job, command) {
+            Ok(substituted) if substituted == command.as_str() => {
+                print_field!("Command", "{}", command)
+            }
+            Ok(substituted) => {
+                print_field!("Command(template)", "{}", command);
+                print_field!("Command(actual)", "{}", substituted);
+            }
+            Err(e) => {
+                print_field!("Command(template)", "{}", command);
+                print_field!("Command(actual)", "Error: {}", e);
             }
-        } else {
-            print_field!("Command", "{}", command);
         }
     }
 
@@ -79,10 +105,44 @@ fn print_job_details(job: &Job) {
         );
     }
     print_optional_field!("GPUIDs", job.gpu_ids, |ids| format_ids(ids));
+    if let Some(peak_mb) = job.peak_gpu_mb {
+        print_field!(
+            "PeakGPUMemory",
+            "{}",
+            format_peak(
+                peak_mb,
+                job.requested_gpu_memory_mb(),
+                job.gpu_memory_utilization()
+            )
+        );
+    }
+    print_optional_field!("Node", job.node);
     if let Some(memory_mb) = job.memory_limit_mb {
         print_field!("MemoryLimit", "{}", gflow::utils::format_memory(memory_mb));
     }
-    print_optional_field!("CondaEnv", job.conda_env);
+    if let Some(peak_mb) = job.peak_rss_mb {
+        print_field!(
+            "PeakMemory",
+            "{}",
+            format_peak(peak_mb, job.memory_limit_mb, job.memory_utilization())
+        );
+    }
+    print_optional_field!("CPUs", job.cpus);
+    print_optional_field!("CPUIDs", job.cpu_ids, |ids| {
+        gflow::core::cpu::format_cpu_list(ids)
+    });
+    if !job.environment.is_none() {
+        print_field!("Environment", "{}", job.environment);
+    }
+    print_optional_field!("Container", job.container_image);
+    print_optional_field!("Executor", job.executor);
+    if job.interactive {
+        print_field!("Interactive", "yes");
+    }
+    if job.preemptible {
+        print_field!("Preemptible", "yes (preempted {} times)", job.preemptions);
+    }
+    print_optional_field!("Constraint", job.constraint);
 
     // Working directory and run name
     println!("\nExecution:");
@@ -173,6 +233,26 @@ fn print_job_details(job: &Job) {
             print_field!("Started", "{}", format_time(started_at));
         }
     }
+    if let Some(progress) = job.progress.as_deref() {
+        let now = SystemTime::now();
+        let age = now.duration_since(progress.updated_at).unwrap_or_default();
+        let reported = format!(
+            "{progress} (reported {} ago)",
+            gflow::utils::format_duration_compact(age)
+        );
+        if progress.is_stale(now) {
+            print_field!(
+                "Progress",
+                "{}",
+                style.paint(
+                    format!("{reported}, stale"),
+                    owo_colors::Style::new().dimmed()
+                )
+            );
+        } else {
+            print_field!("Progress", "{}", reported);
+        }
+    }
 }
 
 /// Format a slice of u32 IDs as a comma-separated string
@@ -183,6 +263,18 @@ fn format_ids(ids: &[u32]) -> String {
         .join(",")
 }
 
+/// Peak memory, against the request when there is one, e.g. `3G (37% of 8G)`
+fn format_peak(peak_mb: u64, requested_mb: Option<u64>, utilization: Option<u64>) -> String {
+    let peak = gflow::utils::format_memory(peak_mb);
+    match requested_mb.zip(utilization) {
+        Some((requested_mb, percent)) => format!(
+            "{peak} ({percent}% of {})",
+            gflow::utils::format_memory(requested_mb)
+        ),
+        None => peak,
+    }
+}
+
 fn format_gpu_sharing_mode(mode: gflow::core::job::GpuSharingMode) -> &'static str {
     match mode {
         gflow::core::job::GpuSharingMode::Exclusive => "exclusive",
@@ -0,0 +1,51 @@
+use anyhow::Result;
+use chrono::{Local, TimeZone};
+use gflow::core::runtime_stats::{RuntimeStatsQuery, RuntimeSummary};
+use gflow::utils::format_duration_compact;
+use std::time::Duration;
+use tabled::{builder::Builder, settings::style::Style};
+
+pub async fn handle_stats(
+    config_path: &Option<std::path::PathBuf>,
+    name: Option<String>,
+    user: Option<String>,
+    json: bool,
+) -> Result<()> {
+    let client = gflow::create_client(config_path)?;
+    let rows = client
+        .get_runtime_stats(&RuntimeStatsQuery { name, user })
+        .await?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&rows)?);
+        return Ok(());
+    }
+    if rows.is_empty() {
+        println!("No runtime history found.");
+        return Ok(());
+    }
+
+    let mut builder = Builder::default();
+    builder.push_record(["USER", "NAME", "RUNS", "MEAN", "P50", "P95", "LAST SEEN"]);
+    for row in &rows {
+        builder.push_record(row_cells(row));
+    }
+    println!("{}", builder.build().with(Style::blank()));
+    Ok(())
+}
+
+fn row_cells(row: &RuntimeSummary) -> [String; 7] {
+    let secs = |secs: u64| format_duration_compact(Duration::from_secs(secs));
+    [
+        row.user.clone(),
+        row.name.clone(),
+        row.count.to_string(),
+        secs(row.mean_secs),
+        secs(row.p50_secs),
+        secs(row.p95_secs),
+        Local.timestamp_opt(row.last_seen, 0).single().map_or_else(
+            || row.last_seen.to_string(),
+            |dt| dt.format("%Y-%m-%d %H:%M").to_string(),
+        ),
+    ]
+}
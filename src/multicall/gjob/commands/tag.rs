@@ -0,0 +1,30 @@
+use crate::multicall::gjob::utils::resolve_job_id;
+use anyhow::Result;
+use std::path::PathBuf;
+
+pub async fn handle_tag(
+    config_path: &Option<PathBuf>,
+    job: &str,
+    add: &[String],
+    remove: &[String],
+) -> Result<()> {
+    let client = gflow::create_client(config_path)?;
+    let job_id = resolve_job_id(&client, job).await?;
+
+    let tags = if add.is_empty() && remove.is_empty() {
+        let job = client
+            .get_job(job_id)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Job {job_id} not found"))?;
+        job.tags.iter().map(str::to_string).collect()
+    } else {
+        client.update_job_tags(job_id, add, remove).await?
+    };
+
+    if tags.is_empty() {
+        println!("Job {job_id} has no tags.");
+    } else {
+        println!("Job {job_id}: {}", tags.join(", "));
+    }
+    Ok(())
+}
@@ -1,5 +1,5 @@
-use anyhow::{anyhow, Result};
-use gflow::client::UpdateJobRequest;
+use anyhow::{anyhow, Context, Result};
+use gflow::client::{ClientError, UpdateJobRequest, UserScope};
 use gflow::print_field;
 use gflow::utils::parse_job_ids;
 use std::collections::HashMap;
@@ -28,13 +28,22 @@ pub struct UpdateJobParams {
     pub max_retries: Option<u32>,
     pub clear_max_retries: bool,
     pub params: Vec<String>,
+    pub user: Option<String>,
+    pub all_users: bool,
 }
 
 pub async fn handle_update(
     config_path: &Option<std::path::PathBuf>,
     params: UpdateJobParams,
 ) -> Result<()> {
-    let client = gflow::create_client(config_path)?;
+    let mut config = gflow::config::load_config(config_path.as_ref())?;
+    config.use_selected_remote()?;
+    let client = gflow::Client::build(&config)?;
+    let scope = UserScope::from_args(
+        params.user.as_deref(),
+        params.all_users,
+        config.gqueue.default_user_filter(),
+    );
 
     let job_ids = parse_job_ids(&params.job_ids_str)?;
 
@@ -88,7 +97,9 @@ pub async fn handle_update(
 
     // Parse time limit
     let parsed_time_limit = if let Some(time_str) = &params.time_limit {
-        Some(Some(gflow::utils::parse_time_limit(time_str)?))
+        Some(Some(
+            gflow::utils::parse_time_limit(time_str).context(ClientError::invalid_time_limit())?,
+        ))
     } else if params.clear_time_limit {
         Some(None)
     } else {
@@ -171,6 +182,13 @@ pub async fn handle_update(
     };
 
     for &job_id in &job_ids {
+        if scope != UserScope::All {
+            if let Err(e) = client.get_job_in_scope(job_id, &scope).await {
+                gflow::client::print_error(&e);
+                continue;
+            }
+        }
+
         // Build update request
         let request = UpdateJobRequest {
             command: params.command.clone(),
@@ -199,7 +217,7 @@ pub async fn handle_update(
                 }
             }
             Err(e) => {
-                eprintln!("Error updating job {}: {}", job_id, e);
+                gflow::client::print_error(&e);
             }
         }
     }
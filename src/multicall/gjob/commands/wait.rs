@@ -0,0 +1,365 @@
+use anyhow::{bail, Result};
+use gflow::client::Client;
+use gflow::core::job::{Job, JobState};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// Exit status when a job ended without reaching the condition.
+pub const EXIT_MISSED: i32 = 1;
+/// Exit status when `--timeout` ran out first, as for `timeout(1)`.
+pub const EXIT_TIMED_OUT: i32 = 124;
+/// Exit status on Ctrl-C, as for a shell killed by SIGINT.
+pub const EXIT_INTERRUPTED: i32 = 130;
+
+/// Shortest `--poll` accepted, so a loop of waits cannot flood the daemon.
+const MIN_POLL: Duration = Duration::from_secs(1);
+
+/// The state `gjob wait --for` waits for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum WaitCondition {
+    /// Finished successfully
+    #[default]
+    Finished,
+    /// Ended in any way: finished, failed, cancelled or timed out
+    Ended,
+    /// Started running (or already ran)
+    Running,
+}
+
+/// Where one job stands against the condition
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Standing {
+    Waiting,
+    Reached,
+    /// Ended in a way that can never meet the condition
+    Missed,
+}
+
+impl WaitCondition {
+    fn standing(self, job: &Job) -> Standing {
+        let reached = match self {
+            Self::Finished => job.state == JobState::Finished,
+            Self::Ended => job.state.is_final(),
+            Self::Running => job.state == JobState::Running || job.started_at.is_some(),
+        };
+        if reached {
+            Standing::Reached
+        } else if job.state.is_final() {
+            Standing::Missed
+        } else {
+            Standing::Waiting
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct WaitOptions {
+    pub condition: WaitCondition,
+    /// Return once one job reaches the condition rather than all of them
+    pub any: bool,
+    pub timeout: Option<Duration>,
+    pub poll: Duration,
+    pub quiet: bool,
+}
+
+/// The last state seen of each job, `None` once it no longer exists
+#[derive(Debug, Default)]
+pub struct WaitReport {
+    states: BTreeMap<u32, Option<JobState>>,
+    reached: Vec<u32>,
+    missed: Vec<u32>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum WaitOutcome {
+    Done,
+    Missed,
+    TimedOut,
+}
+
+pub async fn handle_wait(
+    config_path: &Option<PathBuf>,
+    jobs: &[String],
+    options: WaitOptions,
+) -> Result<()> {
+    if options.poll < MIN_POLL {
+        bail!("--poll must be at least {}s", MIN_POLL.as_secs());
+    }
+    let client = gflow::create_client(config_path)?;
+    let mut job_ids = Vec::new();
+    for job in jobs {
+        match job.trim() {
+            job if job.starts_with('@') => {
+                job_ids.push(super::super::utils::resolve_job_id(&client, job).await?)
+            }
+            job => job_ids.extend(gflow::utils::parse_job_ids(job)?),
+        }
+    }
+    job_ids.sort_unstable();
+    job_ids.dedup();
+
+    let mut report = WaitReport::default();
+    let outcome = tokio::select! {
+        outcome = wait_for_jobs(&client, &job_ids, options, &mut report) => outcome?,
+        _ = tokio::signal::ctrl_c() => {
+            eprintln!("Interrupted.");
+            report.print();
+            std::process::exit(EXIT_INTERRUPTED);
+        }
+    };
+    if outcome != WaitOutcome::Done || !options.quiet {
+        report.print();
+    }
+    match outcome {
+        WaitOutcome::Done => Ok(()),
+        WaitOutcome::Missed => std::process::exit(EXIT_MISSED),
+        WaitOutcome::TimedOut => {
+            eprintln!("Timed out waiting for jobs.");
+            std::process::exit(EXIT_TIMED_OUT)
+        }
+    }
+}
+
+/// Poll each job still waiting every `options.poll` until the condition is
+/// settled: all jobs reached it, or with `any` one did, or some job can no
+/// longer reach it. Failed requests are retried, so a daemon restart does
+/// not end a long wait.
+pub async fn wait_for_jobs(
+    client: &Client,
+    job_ids: &[u32],
+    options: WaitOptions,
+    report: &mut WaitReport,
+) -> Result<WaitOutcome> {
+    let deadline = options.timeout.map(|timeout| Instant::now() + timeout);
+    let mut waiting = job_ids.to_vec();
+    let mut last_status = String::new();
+    loop {
+        let mut unreachable = false;
+        for &job_id in &waiting {
+            match client.get_job(job_id).await {
+                Ok(job) => {
+                    report.states.insert(job_id, job.as_ref().map(|j| j.state));
+                    match job.map_or(Standing::Missed, |job| options.condition.standing(&job)) {
+                        Standing::Waiting => {}
+                        Standing::Reached => report.reached.push(job_id),
+                        Standing::Missed => report.missed.push(job_id),
+                    }
+                }
+                Err(e) => {
+                    unreachable = true;
+                    if !options.quiet {
+                        eprintln!("Could not check job {job_id}, retrying: {e:#}");
+                    }
+                    break;
+                }
+            }
+        }
+        waiting.retain(|id| !report.reached.contains(id) && !report.missed.contains(id));
+
+        if options.any && !report.reached.is_empty() {
+            return Ok(WaitOutcome::Done);
+        }
+        if options.any && waiting.is_empty() || !options.any && !report.missed.is_empty() {
+            return Ok(WaitOutcome::Missed);
+        }
+        if waiting.is_empty() {
+            return Ok(WaitOutcome::Done);
+        }
+
+        if !options.quiet && !unreachable {
+            let status = report.status_line(job_ids.len(), waiting.len());
+            if status != last_status {
+                eprintln!("[{}] {status}", chrono::Local::now().format("%H:%M:%S"));
+                last_status = status;
+            }
+        }
+
+        let now = Instant::now();
+        if deadline.is_some_and(|deadline| deadline <= now) {
+            return Ok(WaitOutcome::TimedOut);
+        }
+        let wake = now + options.poll;
+        tokio::time::sleep_until(deadline.map_or(wake, |deadline| deadline.min(wake))).await;
+    }
+}
+
+impl WaitReport {
+    /// e.g. `Waiting for 2 of 3 jobs: 1 Queued, 1 Running, 1 Finished`
+    fn status_line(&self, total: usize, waiting: usize) -> String {
+        let mut counts: BTreeMap<JobState, usize> = BTreeMap::new();
+        for state in self.states.values().flatten() {
+            *counts.entry(*state).or_default() += 1;
+        }
+        let counts = counts
+            .iter()
+            .map(|(state, count)| format!("{count} {state}"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        format!("Waiting for {waiting} of {total} jobs: {counts}")
+    }
+
+    /// The last state seen of each job, one per line
+    fn print(&self) {
+        for (job_id, state) in &self.states {
+            match state {
+                Some(state) => println!("Job {job_id}: {state}"),
+                None => println!("Job {job_id}: not found"),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gflow::config::Config;
+    use std::time::SystemTime;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn client_for(server: &MockServer) -> Client {
+        let mut config = Config::default();
+        config.daemon.host = "127.0.0.1".to_string();
+        config.daemon.port = server.address().port();
+        Client::build(&config).unwrap()
+    }
+
+    fn options(condition: WaitCondition, any: bool) -> WaitOptions {
+        WaitOptions {
+            condition,
+            any,
+            timeout: Some(Duration::from_secs(5)),
+            poll: Duration::from_millis(10),
+            quiet: true,
+        }
+    }
+
+    /// Answer GET /jobs/{id} with each of `states` once, then the last for good.
+    async fn mount_states(server: &MockServer, id: u32, states: &[JobState]) {
+        for (i, state) in states.iter().enumerate() {
+            let mut job = Job::builder().command("true").submitted_by("alice").build();
+            job.id = id;
+            job.state = *state;
+            if *state != JobState::Queued {
+                job.started_at = Some(SystemTime::now());
+            }
+            let mock = Mock::given(method("GET"))
+                .and(path(format!("/jobs/{id}")))
+                .respond_with(ResponseTemplate::new(200).set_body_json(&job));
+            let mock = if i + 1 < states.len() {
+                mock.up_to_n_times(1).with_priority(1 + i as u8)
+            } else {
+                mock.with_priority(100)
+            };
+            mock.mount(server).await;
+        }
+    }
+
+    #[tokio::test]
+    async fn waits_until_every_job_finishes() {
+        let server = MockServer::start().await;
+        use JobState::*;
+        mount_states(&server, 1, &[Queued, Running, Finished]).await;
+        mount_states(&server, 2, &[Running, Running, Running, Finished]).await;
+
+        let mut report = WaitReport::default();
+        let outcome = wait_for_jobs(
+            &client_for(&server),
+            &[1, 2],
+            options(WaitCondition::Finished, false),
+            &mut report,
+        )
+        .await
+        .unwrap();
+        assert_eq!(outcome, WaitOutcome::Done);
+        assert_eq!(report.reached, [1, 2]);
+        assert_eq!(report.states[&2], Some(Finished));
+    }
+
+    #[tokio::test]
+    async fn a_failed_or_missing_job_ends_the_wait() {
+        let server = MockServer::start().await;
+        use JobState::*;
+        mount_states(&server, 1, &[Running, Failed]).await;
+        mount_states(&server, 2, &[Running]).await;
+        Mock::given(method("GET"))
+            .and(path("/jobs/3"))
+            .respond_with(ResponseTemplate::new(404))
+            .mount(&server)
+            .await;
+        let client = client_for(&server);
+
+        let mut report = WaitReport::default();
+        let outcome = wait_for_jobs(
+            &client,
+            &[1, 2],
+            options(WaitCondition::Finished, false),
+            &mut report,
+        )
+        .await
+        .unwrap();
+        assert_eq!(outcome, WaitOutcome::Missed);
+        assert_eq!(report.missed, [1]);
+        assert_eq!(report.states[&1], Some(Failed));
+
+        // Failing is still ending.
+        let mut report = WaitReport::default();
+        let outcome = wait_for_jobs(
+            &client,
+            &[1, 2],
+            options(WaitCondition::Ended, true),
+            &mut report,
+        )
+        .await
+        .unwrap();
+        assert_eq!(outcome, WaitOutcome::Done);
+
+        let mut report = WaitReport::default();
+        let outcome = wait_for_jobs(
+            &client,
+            &[3],
+            options(WaitCondition::Running, false),
+            &mut report,
+        )
+        .await
+        .unwrap();
+        assert_eq!(outcome, WaitOutcome::Missed);
+        assert_eq!(report.states[&3], None);
+    }
+
+    #[tokio::test]
+    async fn any_returns_at_the_first_job_and_timeout_stops_waiting() {
+        let server = MockServer::start().await;
+        use JobState::*;
+        mount_states(&server, 1, &[Queued]).await;
+        mount_states(&server, 2, &[Queued, Running]).await;
+        let client = client_for(&server);
+
+        let mut report = WaitReport::default();
+        let outcome = wait_for_jobs(
+            &client,
+            &[1, 2],
+            options(WaitCondition::Running, true),
+            &mut report,
+        )
+        .await
+        .unwrap();
+        assert_eq!(outcome, WaitOutcome::Done);
+        assert_eq!(report.reached, [2]);
+
+        let mut report = WaitReport::default();
+        let mut options = options(WaitCondition::Running, false);
+        options.timeout = Some(Duration::from_millis(50));
+        let outcome = wait_for_jobs(&client, &[1, 2], options, &mut report)
+            .await
+            .unwrap();
+        assert_eq!(outcome, WaitOutcome::TimedOut);
+        assert_eq!(report.states[&1], Some(Queued));
+        assert_eq!(
+            report.status_line(2, 1),
+            "Waiting for 1 of 2 jobs: 1 Queued, 1 Running"
+        );
+    }
+}
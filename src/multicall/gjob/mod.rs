@@ -8,11 +8,14 @@ use std::ffi::OsString;
 
 pub async fn run(argv: Vec<OsString>) -> Result<()> {
     let args = cli::GJob::parse_from(argv);
+    crate::multicall::select_remote(args.remote.as_deref());
+    crate::multicall::skip_version_check(args.skip_version_check);
+    gflow::utils::output::set_color_choice(args.color);
 
     tracing_subscriber::fmt()
         .with_max_level(args.verbosity)
         .init();
 
-    commands::handle_commands(&args.config, args.command).await?;
+    commands::handle_commands(&args.config, args.verbosity, args.command).await?;
     Ok(())
 }
@@ -1,3 +1,5 @@
+use super::commands::list::export::ExportFormat;
+use super::commands::list::report::ReportKind;
 use clap::Parser;
 use clap_complete::Shell;
 
@@ -18,6 +20,18 @@ pub struct GQueue {
 
     #[arg(long, global = true, help = "Path to the config file", hide = true)]
     pub config: Option<std::path::PathBuf>,
+
+    /// Talk to the daemon of this `[remotes.<name>]` entry (or set GFLOW_REMOTE)
+    #[arg(long, global = true, value_name = "NAME")]
+    pub remote: Option<String>,
+
+    /// Run even if gflowd declares this client too old (or set GFLOW_SKIP_VERSION_CHECK=1)
+    #[arg(long, global = true)]
+    pub skip_version_check: bool,
+
+    /// When to color output: auto (terminals, unless NO_COLOR is set), always or never
+    #[arg(long, global = true, value_enum, value_name = "WHEN", default_value_t)]
+    pub color: gflow::utils::output::ColorChoice,
 }
 
 #[derive(Debug, Parser)]
@@ -36,11 +50,25 @@ pub struct ListArgs {
         long,
         short = 'u',
         visible_alias = "users",
-        help = "Filter by a comma-separated list of users (default: current user; use 'all' to show all users)",
+        help = "Filter by a comma-separated list of users (default: current user, unless [gqueue] default_user_filter = false; use 'all' to show all users)",
         value_hint = clap::ValueHint::Other
     )]
     pub user: Option<String>,
 
+    #[arg(
+        long,
+        conflicts_with = "user",
+        help = "Show every user's jobs (same as --user all)"
+    )]
+    pub all_users: bool,
+
+    #[arg(
+        long,
+        conflicts_with_all = ["user", "all_users"],
+        help = "Show only your own jobs, even with [gqueue] default_user_filter = false"
+    )]
+    pub me: bool,
+
     #[arg(
         long,
         short = 'n',
@@ -51,7 +79,26 @@ pub struct ListArgs {
     )]
     pub limit: i32,
 
-    #[arg(long, short = 'a', help = "Show all jobs including completed ones")]
+    #[arg(
+        long,
+        help = "Show this page of the listing, counting from 1 (from the end with a negative --limit)",
+        value_parser = clap::value_parser!(u32).range(1..)
+    )]
+    pub page: Option<u32>,
+
+    #[arg(
+        long,
+        help = "Jobs per page (default: the --limit size, or 50)",
+        value_parser = clap::value_parser!(u32).range(1..)
+    )]
+    pub per_page: Option<u32>,
+
+    #[arg(
+        long,
+        short = 'a',
+        visible_alias = "history",
+        help = "Show all jobs including completed ones"
+    )]
     pub all: bool,
 
     #[arg(
@@ -69,10 +116,17 @@ pub struct ListArgs {
     )]
     pub since: Option<String>,
 
+    #[arg(
+        long,
+        help = "Show jobs submitted up to a specific time (same formats as --since)",
+        value_hint = clap::ValueHint::Other
+    )]
+    pub until: Option<String>,
+
     #[arg(
         long,
         short = 'r',
-        help = "Sort jobs by field (options: id, state, time, name, gpus, priority)",
+        help = "Sort jobs by field (options: id, state, time, name, gpus, priority, submitted, started, finished, wait)",
         default_value = "id"
     )]
     pub sort: String,
@@ -97,7 +151,7 @@ pub struct ListArgs {
     #[arg(
         long,
         short = 'N',
-        help = "Filter by a comma-separated list of job names",
+        help = "Filter by a comma-separated list of job names or glob patterns (e.g. '*resnet*', 'train-?')",
         value_hint = clap::ValueHint::Other
     )]
     pub names: Option<String>,
@@ -110,14 +164,37 @@ pub struct ListArgs {
     )]
     pub project: Option<String>,
 
+    #[arg(
+        long,
+        value_delimiter = ',',
+        help = "Show only jobs with this tag; repeat or separate with commas to require several",
+        value_hint = clap::ValueHint::Other
+    )]
+    pub tag: Vec<String>,
+
+    #[arg(
+        long,
+        short = 'S',
+        help = "Search commands, script paths, job names and parameter values for all of these terms; searches every state and lists the newest first",
+        value_hint = clap::ValueHint::Other
+    )]
+    pub search: Option<String>,
+
     #[arg(
         long,
         short = 'f',
-        help = "Specify a comma-separated list of fields to display",
+        help = "Comma-separated columns to display, each with an optional width, e.g. JOBID:6,NAME:30,USER,ST (default: [gqueue] format in the config)",
         value_hint = clap::ValueHint::Other
     )]
     pub format: Option<String>,
 
+    #[arg(
+        long,
+        value_enum,
+        help = "How SUBMITTED, STARTED and FINISHED are shown: relative, local (ISO 8601 in the configured timezone) or utc (default: [gqueue] time_style in the config, else relative)"
+    )]
+    pub time_style: Option<gflow::config::TimeStyle>,
+
     #[arg(
         long,
         short = 'g',
@@ -132,16 +209,55 @@ pub struct ListArgs {
     )]
     pub tree: bool,
 
+    #[arg(
+        long,
+        conflicts_with = "completed",
+        help = "Show only jobs held or waiting on something other than free resources, such as dependencies, group limits or reservations"
+    )]
+    pub blocked: bool,
+
     #[arg(long, short = 'T', help = "Show only jobs with active tmux sessions")]
     pub tmux: bool,
 
     #[arg(
         long,
         short = 'o',
-        help = "Output format (options: table, json, csv, yaml)",
-        default_value = "table"
+        help = "Output format (options: table, json, csv, yaml; default: table). With --export, the file to write (default: stdout)"
+    )]
+    pub output: Option<String>,
+
+    #[arg(
+        long,
+        conflicts_with_all = ["output", "export"],
+        help = "Same as --output json"
+    )]
+    pub json: bool,
+
+    #[arg(long, help = "Don't print the queue summary line above the table")]
+    pub no_summary: bool,
+
+    #[arg(
+        long,
+        help = "Print only the queue summary (jobs per state, GPUs in use and queued, longest wait, expected all-clear), from a single stats call",
+        conflicts_with_all = ["no_summary", "export", "report", "group", "tree"]
     )]
-    pub output: String,
+    pub summary_only: bool,
+
+    #[arg(
+        long,
+        value_enum,
+        help = "Export the selected jobs for reporting, one row per job",
+        conflicts_with_all = ["watch", "group", "tree"]
+    )]
+    pub export: Option<ExportFormat>,
+
+    #[arg(
+        long,
+        value_enum,
+        help = "Report on the selected jobs instead of listing them (rightsizing: jobs that peaked under half their requested memory). Looks at completed jobs unless --states is given",
+        conflicts_with_all = ["watch", "group", "tree", "export", "output"]
+    )]
+    pub report: Option<ReportKind>,
 
     #[arg(long, short = 'w', help = "Auto-refresh job list (default: every 2s)")]
     pub watch: bool,
@@ -167,4 +283,36 @@ mod tests {
         assert_eq!(args.list_args.user.as_deref(), Some("alice,bob"));
         assert_eq!(args.list_args.jobs.as_deref(), Some("1,2,3"));
     }
+
+    #[test]
+    fn export_takes_the_output_path() {
+        let args = GQueue::try_parse_from([
+            "gqueue",
+            "--history",
+            "--export",
+            "csv",
+            "--output",
+            "report.csv",
+            "--since",
+            "2w",
+            "--until",
+            "1w",
+        ])
+        .expect("should parse export options");
+
+        assert!(args.list_args.all);
+        assert_eq!(args.list_args.export, Some(ExportFormat::Csv));
+        assert_eq!(args.list_args.output.as_deref(), Some("report.csv"));
+        assert!(GQueue::try_parse_from(["gqueue", "--export", "jsonl", "--watch"]).is_err());
+    }
+
+    #[test]
+    fn summary_only_takes_json_but_not_no_summary() {
+        let args = GQueue::try_parse_from(["gqueue", "--summary-only", "--json"])
+            .expect("should parse summary options");
+        assert!(args.list_args.summary_only);
+        assert!(args.list_args.json);
+        assert!(GQueue::try_parse_from(["gqueue", "--summary-only", "--no-summary"]).is_err());
+        assert!(GQueue::try_parse_from(["gqueue", "--json", "-o", "csv"]).is_err());
+    }
 }
@@ -1,4 +1,5 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
+use gflow::client::UserScope;
 use std::path::PathBuf;
 
 pub mod list;
@@ -8,24 +9,67 @@ pub async fn handle_commands(
     config_path: &Option<PathBuf>,
     args: &super::cli::ListArgs,
 ) -> Result<()> {
-    let client = gflow::create_client(config_path)?;
+    let mut config = gflow::config::load_config(config_path.as_ref())?;
+    config.use_selected_remote()?;
+    let client = gflow::Client::build(&config)?;
+
+    let columns = match &args.format {
+        Some(format) => list::columns::parse_columns(format)?,
+        None => match &config.gqueue.format {
+            Some(format) => list::columns::parse_columns(format)
+                .context("Invalid `format` in the [gqueue] config section")?,
+            None => list::columns::parse_columns(list::columns::DEFAULT_FORMAT)?,
+        },
+    };
+
+    // With --export, --output names the file to write rather than a format.
+    let (output, export_path) = match args.export {
+        Some(_) => (None, args.output.as_ref().map(PathBuf::from)),
+        None if args.json => (Some("json".to_string()), None),
+        None => (args.output.clone(), None),
+    };
 
     let options = ListOptions {
-        user: args.user.clone(),
+        users: if args.me {
+            UserScope::current()
+        } else {
+            UserScope::from_args(
+                args.user.as_deref(),
+                args.all_users,
+                config.gqueue.default_user_filter(),
+            )
+        },
         states: args.states.clone(),
         jobs: args.jobs.clone(),
         names: args.names.clone(),
         project: args.project.clone(),
+        tags: args.tag.clone(),
+        search: args.search.clone(),
         sort: args.sort.clone(),
         limit: args.limit,
+        page: args.page.map(|page| page as usize),
+        per_page: args.per_page.map(|per_page| per_page as usize),
         all: args.all,
         completed: args.completed,
         since: args.since.clone(),
+        until: args.until.clone(),
         group: args.group,
         tree: args.tree,
-        format: args.format.clone(),
+        columns,
+        time_style: args
+            .time_style
+            .or(config.gqueue.time_style)
+            .unwrap_or_default(),
+        timezone: config.timezone.clone(),
+        priority_classes: config.priorities.clone(),
         tmux: args.tmux,
-        output: args.output.clone(),
+        blocked: args.blocked,
+        output: output.unwrap_or_else(|| "table".to_string()),
+        export: args.export,
+        export_path,
+        report: args.report,
+        summary: !args.no_summary,
+        summary_only: args.summary_only,
         watch: args.watch,
         interval: args.interval,
     };
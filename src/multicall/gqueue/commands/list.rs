@@ -1,38 +1,85 @@
-use anyhow::Result;
-use gflow::{client::Client, core::job::JobState, tmux::get_all_session_names};
-
+use anyhow::{Context, Result};
+use gflow::{
+    client::{Client, ClientError, JobFilter, UserScope},
+    config::TimeStyle,
+    core::job::JobState,
+    tmux::get_all_session_names,
+    utils::name_glob::NameGlobs,
+};
+
+pub mod columns;
 mod display;
+pub mod export;
 mod output;
+pub mod report;
+mod summary;
 mod tree;
 
-use display::{display_grouped_jobs, display_jobs_table};
+use columns::{Column, Field};
+use display::{display_grouped_jobs, display_jobs_table, CellContext};
+use export::{export_jobs, ExportFormat};
 use output::{output_csv, output_json, output_yaml, OutputFormat};
-#[cfg(test)]
-use std::collections::HashSet;
+use report::{print_report, ReportKind};
+use std::path::PathBuf;
+use summary::print_summary;
 use tree::display_jobs_tree;
 #[cfg(test)]
 use tree::{build_dependency_tree, JobNodeChild};
 
 pub struct ListOptions {
-    pub user: Option<String>,
+    pub users: UserScope,
     pub states: Option<String>,
     pub jobs: Option<String>,
     pub names: Option<String>,
     pub project: Option<String>,
+    /// Jobs must carry every one of these tags
+    pub tags: Vec<String>,
+    pub search: Option<String>,
     pub sort: String,
     pub limit: i32,
+    /// 1-based page of `per_page` jobs
+    pub page: Option<usize>,
+    pub per_page: Option<usize>,
     pub all: bool,
     pub completed: bool,
     pub since: Option<String>,
+    pub until: Option<String>,
     pub group: bool,
     pub tree: bool,
-    pub format: Option<String>,
+    pub columns: Vec<Column>,
+    pub time_style: TimeStyle,
+    /// Timezone for `TimeStyle::Local`
+    pub timezone: Option<String>,
+    /// `[priorities]`, shown by name in the PRIORITY column
+    pub priority_classes: std::collections::BTreeMap<String, u8>,
     pub tmux: bool,
+    /// Only jobs held or waiting on something other than free resources
+    pub blocked: bool,
     pub output: String,
+    pub export: Option<ExportFormat>,
+    pub export_path: Option<PathBuf>,
+    pub report: Option<ReportKind>,
+    /// Print the queue summary line above the table
+    pub summary: bool,
+    /// Print only the queue summary, without listing jobs
+    pub summary_only: bool,
     pub watch: bool,
     pub interval: u64,
 }
 
+/// Held, or queued for a reason other than waiting for GPUs, memory, CPU
+/// cores or disk space.
+fn is_blocked(job: &gflow::core::job::Job) -> bool {
+    match job.state {
+        JobState::Hold => true,
+        JobState::Queued => job
+            .reason
+            .as_deref()
+            .is_some_and(|reason| !reason.is_resource_wait()),
+        _ => false,
+    }
+}
+
 pub async fn handle_list(client: &Client, options: ListOptions) -> Result<()> {
     if options.watch {
         let interval = std::time::Duration::from_secs(options.interval);
@@ -53,45 +100,122 @@ pub async fn handle_list(client: &Client, options: ListOptions) -> Result<()> {
 }
 
 async fn display_once(client: &Client, options: &ListOptions) -> Result<()> {
-    let current_user = gflow::platform::get_current_username();
-    let user_filter = match options.user.as_deref().map(str::trim) {
-        None => Some(current_user.clone()),
-        Some("") => Some(current_user.clone()),
-        Some("all") | Some("*") => None,
-        Some(u) => Some(u.to_string()),
-    };
+    let output_format: OutputFormat = options.output.parse().map_err(|_| {
+        anyhow::anyhow!(
+            "Invalid output format '{}'. Valid options: table, json, csv, yaml",
+            options.output
+        )
+    })?;
+    let now = chrono::Utc::now().timestamp();
 
-    let states_filter = if options.completed {
+    // One stats call, however long the queue.
+    if options.summary_only {
+        let stats = client.get_scheduler_stats().await?;
+        return print_summary(&stats, output_format, now);
+    }
+    if options.summary
+        && output_format == OutputFormat::Table
+        && options.export.is_none()
+        && options.report.is_none()
+    {
+        // The listing is still worth showing when the summary is not.
+        if let Ok(stats) = client.get_scheduler_stats().await {
+            println!("{}", stats.queue_overview(now));
+            println!();
+        }
+    }
+
+    // Reports look at jobs that have ended unless told otherwise.
+    let states_filter =
+        if options.completed || (options.report.is_some() && options.states.is_none()) {
+            Some(
+                JobState::completed_states()
+                    .iter()
+                    .map(|s| s.to_string())
+                    .collect::<Vec<_>>()
+                    .join(","),
+            )
+        } else if let Some(ref states) = options.states {
+            Some(states.clone())
+        } else if options.all || options.search.is_some() {
+            None
+        } else {
+            Some(
+                JobState::active_states()
+                    .iter()
+                    .map(|s| s.to_string())
+                    .collect::<Vec<_>>()
+                    .join(","),
+            )
+        };
+
+    let created_after = if let Some(ref since_str) = options.since {
         Some(
-            JobState::completed_states()
-                .iter()
-                .map(|s| s.to_string())
-                .collect::<Vec<_>>()
-                .join(","),
+            gflow::utils::parse_since_time(since_str)
+                .context(ClientError::invalid_since_time("--since"))?,
         )
-    } else if let Some(ref states) = options.states {
-        Some(states.clone())
-    } else if options.all {
-        None
     } else {
-        Some(
-            JobState::active_states()
-                .iter()
-                .map(|s| s.to_string())
-                .collect::<Vec<_>>()
-                .join(","),
-        )
+        None
     };
 
-    let created_after = if let Some(ref since_str) = options.since {
-        Some(gflow::utils::parse_since_time(since_str)?)
+    let created_before = if let Some(ref until_str) = options.until {
+        Some(
+            gflow::utils::parse_since_time(until_str)
+                .context(ClientError::invalid_since_time("--until"))?,
+        )
     } else {
         None
     };
 
-    let mut jobs_vec = client
-        .list_jobs_with_query(states_filter, user_filter, None, None, created_after, None)
-        .await?;
+    let name_globs = options
+        .names
+        .as_deref()
+        .filter(|names| !names.trim().is_empty())
+        .map(str::parse::<NameGlobs>)
+        .transpose()
+        .context(ClientError::InvalidInput {
+            field: "--names".to_string(),
+            example: "names or globs such as train-*, *resnet* or ?exp-[0-9]".to_string(),
+        })?;
+
+    let window = PageWindow::from_options(options);
+    // The daemon can cut the page when it applies every filter and the order is its own.
+    let pages_on_server = created_before.is_none()
+        && options.jobs.is_none()
+        && options.project.is_none()
+        && !options.tmux
+        && !options.blocked
+        && options.report.is_none()
+        && !options.tree
+        && options.sort.eq_ignore_ascii_case("id");
+    let mut filter = JobFilter {
+        states: states_filter,
+        user: options.users.user_filter(),
+        created_after,
+        search: options.search.clone(),
+        name_glob: name_globs.as_ref().and(options.names.clone()),
+        tag: (!options.tags.is_empty()).then(|| options.tags.join(",")),
+        order: None,
+    };
+    let (mut jobs_vec, server_total) = match window {
+        Some(window) if pages_on_server => {
+            let newest_first = options.search.is_some() != window.from_end;
+            filter.order = Some(if newest_first { "desc" } else { "asc" }.to_string());
+            let (jobs, total) = client
+                .find_jobs_page(&filter, window.per_page, window.offset())
+                .await?;
+            (jobs, Some(total))
+        }
+        _ => (client.find_jobs(&filter).await?, None),
+    };
+
+    if let Some(until) = created_before {
+        jobs_vec.retain(|job| {
+            job.submitted_at
+                .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+                .is_some_and(|t| t.as_secs() as i64 <= until)
+        });
+    }
 
     if let Some(job_ids) = options.jobs.as_deref() {
         let job_ids_vec: Vec<u32> = job_ids
@@ -103,18 +227,18 @@ async fn display_once(client: &Client, options: &ListOptions) -> Result<()> {
         }
     }
 
-    if let Some(names_filter) = options.names.as_deref() {
-        let names_vec: Vec<String> = names_filter
-            .split(',')
-            .map(|s| s.trim().to_string())
-            .collect();
-        if !names_vec.is_empty() {
-            jobs_vec.retain(|job| {
-                job.run_name
-                    .as_ref()
-                    .is_some_and(|run_name| names_vec.iter().any(|n| n == run_name.as_str()))
-            });
-        }
+    // Daemons older than `name_glob` ignore it and return every name.
+    if let Some(globs) = name_globs {
+        jobs_vec.retain(|job| {
+            job.run_name
+                .as_ref()
+                .is_some_and(|run_name| globs.matches(run_name))
+        });
+    }
+
+    // Daemons older than `tag` ignore it as well.
+    if !options.tags.is_empty() {
+        jobs_vec.retain(|job| options.tags.iter().all(|tag| job.tags.contains(tag)));
     }
 
     if let Some(project_filter) = options.project.as_deref() {
@@ -124,6 +248,10 @@ async fn display_once(client: &Client, options: &ListOptions) -> Result<()> {
         }
     }
 
+    if options.blocked {
+        jobs_vec.retain(is_blocked);
+    }
+
     let tmux_sessions = get_all_session_names();
 
     if options.tmux {
@@ -134,45 +262,48 @@ async fn display_once(client: &Client, options: &ListOptions) -> Result<()> {
         });
     }
 
-    if jobs_vec.is_empty() {
+    if jobs_vec.is_empty() && server_total.unwrap_or(0) == 0 && options.export.is_none() {
         println!("No jobs found.");
         return Ok(());
     }
 
-    sort_jobs(&mut jobs_vec, &options.sort);
+    if options.search.is_some() && options.sort.eq_ignore_ascii_case("id") {
+        // Search results are ranked newest first.
+        jobs_vec.sort_by_key(|job| std::cmp::Reverse(job.id));
+    } else {
+        sort_jobs(&mut jobs_vec, &options.sort);
+    }
 
-    let output_format: OutputFormat = options.output.parse().map_err(|_| {
-        anyhow::anyhow!(
-            "Invalid output format '{}'. Valid options: table, json, csv, yaml",
-            options.output
-        )
-    })?;
+    if let Some(kind) = options.report {
+        print_report(&jobs_vec, kind);
+        return Ok(());
+    }
 
-    let effective_limit = if options.all { 0 } else { options.limit };
     let mut limit_message = None;
-    if effective_limit != 0 {
-        let total_jobs = jobs_vec.len();
-
-        if effective_limit > 0 {
-            let limit_usize = effective_limit as usize;
-            if jobs_vec.len() > limit_usize {
-                jobs_vec.truncate(limit_usize);
-                limit_message = Some(format!(
-                    "Showing first {} of {} jobs (use --all or -n 0 to show all)",
-                    effective_limit, total_jobs
-                ));
-            }
-        } else {
-            let limit_usize = (-effective_limit) as usize;
-            if jobs_vec.len() > limit_usize {
-                let start = jobs_vec.len() - limit_usize;
-                jobs_vec = jobs_vec.into_iter().skip(start).collect();
-                limit_message = Some(format!(
-                    "Showing last {} of {} jobs (use --all or -n 0 to show all)",
-                    limit_usize, total_jobs
-                ));
+    if let Some(window) = window {
+        let total = match server_total {
+            Some(total) => total,
+            None => {
+                let total = jobs_vec.len();
+                jobs_vec = window.apply(jobs_vec);
+                total
             }
+        };
+        if jobs_vec.is_empty() && options.export.is_none() {
+            println!(
+                "No jobs on page {}: {} jobs fit on {} pages of {}.",
+                window.page,
+                total,
+                total.div_ceil(window.per_page),
+                window.per_page
+            );
+            return Ok(());
         }
+        limit_message = window.message(jobs_vec.len(), total);
+    }
+
+    if let Some(format) = options.export {
+        return export_jobs(&jobs_vec, format, options.export_path.as_deref());
     }
 
     if let Some(msg) = limit_message {
@@ -182,14 +313,31 @@ async fn display_once(client: &Client, options: &ListOptions) -> Result<()> {
         }
     }
 
+    let mut cells = CellContext {
+        tmux_sessions,
+        now,
+        time_style: options.time_style,
+        timezone: options.timezone.clone(),
+        priority_classes: options.priority_classes.clone(),
+        style: gflow::utils::output::OutputStyle::stdout(),
+        ..Default::default()
+    };
+    let shows_estimates = options
+        .columns
+        .iter()
+        .any(|column| column.field == Field::EstStart);
+    if output_format == OutputFormat::Table && shows_estimates {
+        cells.estimates = client.get_start_estimates().await?;
+    }
+
     match output_format {
         OutputFormat::Table => {
             if options.group {
-                display_grouped_jobs(&jobs_vec, options.format.as_deref(), &tmux_sessions);
+                display_grouped_jobs(&jobs_vec, &options.columns, &cells);
             } else if options.tree {
-                display_jobs_tree(&jobs_vec, options.format.as_deref(), &tmux_sessions);
+                display_jobs_tree(&jobs_vec, &options.columns, &cells);
             } else {
-                display_jobs_table(&jobs_vec, options.format.as_deref(), &tmux_sessions);
+                display_jobs_table(&jobs_vec, &options.columns, &cells);
             }
         }
         OutputFormat::Json => output_json(&jobs_vec)?,
@@ -200,6 +348,82 @@ async fn display_once(client: &Client, options: &ListOptions) -> Result<()> {
     Ok(())
 }
 
+/// The part of the listing `-n`, `--page` and `--per-page` ask for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct PageWindow {
+    per_page: usize,
+    /// 1-based
+    page: usize,
+    /// Pages count back from the end of the listing, for a negative `-n`.
+    from_end: bool,
+    /// `--page` or `--per-page` was given, rather than just `-n`.
+    explicit: bool,
+}
+
+impl PageWindow {
+    const DEFAULT_PER_PAGE: usize = 50;
+
+    /// `None` when every job is shown.
+    fn from_options(options: &ListOptions) -> Option<Self> {
+        let limit = if options.all { 0 } else { options.limit };
+        let explicit = options.page.is_some() || options.per_page.is_some();
+        let per_page = options
+            .per_page
+            .or((limit != 0).then_some(limit.unsigned_abs() as usize))
+            .or(explicit.then_some(Self::DEFAULT_PER_PAGE))?;
+        Some(Self {
+            per_page,
+            page: options.page.unwrap_or(1).max(1),
+            from_end: limit < 0,
+            explicit,
+        })
+    }
+
+    /// Jobs before this page, from whichever end it counts.
+    fn offset(&self) -> usize {
+        (self.page - 1).saturating_mul(self.per_page)
+    }
+
+    /// This page of the whole, sorted listing.
+    fn apply(&self, jobs: Vec<gflow::core::job::Job>) -> Vec<gflow::core::job::Job> {
+        let (start, end) = self.range(jobs.len());
+        jobs.into_iter().skip(start).take(end - start).collect()
+    }
+
+    /// Where the page sits in a listing of `total` jobs, as 0-based start
+    /// and exclusive end.
+    fn range(&self, total: usize) -> (usize, usize) {
+        let offset = self.offset().min(total);
+        let shown = self.per_page.min(total - offset);
+        if self.from_end {
+            (total - offset - shown, total - offset)
+        } else {
+            (offset, offset + shown)
+        }
+    }
+
+    fn message(&self, shown: usize, total: usize) -> Option<String> {
+        if self.explicit {
+            let (start, _) = self.range(total);
+            return Some(format!(
+                "Showing jobs {}-{} of {} (page {} of {})",
+                start + 1,
+                start + shown,
+                total,
+                self.page,
+                total.div_ceil(self.per_page)
+            ));
+        }
+        if shown >= total {
+            return None;
+        }
+        let end = if self.from_end { "last" } else { "first" };
+        Some(format!(
+            "Showing {end} {shown} of {total} jobs (use --all or -n 0 to show all)"
+        ))
+    }
+}
+
 fn sort_jobs(jobs: &mut [gflow::core::job::Job], sort_field: &str) {
     match sort_field.to_lowercase().as_str() {
         "id" => jobs.sort_by_key(|j| j.id),
@@ -213,11 +437,18 @@ fn sort_jobs(jobs: &mut [gflow::core::job::Job], sort_field: &str) {
         }),
         "gpus" | "nodes" => jobs.sort_by_key(|j| j.gpus),
         "priority" => jobs.sort_by_key(|j| j.priority),
+        "submitted" => jobs.sort_by_key(|j| j.submitted_at),
+        "started" => jobs.sort_by_key(|j| j.started_at),
+        "finished" => jobs.sort_by_key(|j| j.finished_at),
+        "wait" => {
+            let now = std::time::SystemTime::now();
+            jobs.sort_by_cached_key(|j| j.wait_time_as_of(now))
+        }
         _ => {
-            eprintln!(
-                "Warning: Unknown sort field '{}', using default 'id'",
+            gflow::utils::output::print_warning(format_args!(
+                "Unknown sort field '{}', using default 'id'",
                 sort_field
-            );
+            ));
             jobs.sort_by_key(|j| j.id);
         }
     }
@@ -229,6 +460,10 @@ mod tests {
     use gflow::core::job::{GpuSharingMode, Job, JobState};
     use std::path::PathBuf;
 
+    fn default_columns() -> Vec<Column> {
+        columns::parse_columns(columns::DEFAULT_FORMAT).unwrap()
+    }
+
     fn create_test_job(id: u32, name: &str, depends_on: Option<u32>) -> Job {
         Job {
             id,
@@ -247,6 +482,12 @@ mod tests {
             run_name: Some(name.into()),
             project: None,
             notifications: gflow::core::job::JobNotifications::default(),
+            environment: gflow::core::job::EnvSpec::None,
+            container_image: None,
+            executor: None,
+            interactive: false,
+            preemptible: false,
+            preemptions: 0,
             state: JobState::Finished,
             gpu_ids: Some(smallvec::smallvec![0]),
             submitted_at: None,
@@ -264,6 +505,23 @@ mod tests {
             group_id: None,
             max_concurrent: None,
             reason: None,
+            node: None,
+            constraint: None,
+            min_free_disk_mb: None,
+            script_content: None,
+            submission_id: None,
+            force_queue: false,
+            as_user: false,
+            queued_at: None,
+            batch_depends_on: Vec::new(),
+            cpus: None,
+            cpu_ids: None,
+            peak_rss_mb: None,
+            peak_gpu_mb: None,
+            progress: None,
+            tags: Default::default(),
+            keep_job_dir: false,
+            job_dir: None,
         }
     }
 
@@ -296,6 +554,12 @@ mod tests {
             run_name: Some(name.into()),
             project: None,
             notifications: gflow::core::job::JobNotifications::default(),
+            environment: gflow::core::job::EnvSpec::None,
+            container_image: None,
+            executor: None,
+            interactive: false,
+            preemptible: false,
+            preemptions: 0,
             state,
             gpu_ids: Some(smallvec::smallvec![0]),
             submitted_at: None,
@@ -313,6 +577,23 @@ mod tests {
             group_id: None,
             max_concurrent: None,
             reason: None,
+            node: None,
+            constraint: None,
+            min_free_disk_mb: None,
+            script_content: None,
+            submission_id: None,
+            force_queue: false,
+            as_user: false,
+            queued_at: None,
+            batch_depends_on: Vec::new(),
+            cpus: None,
+            cpu_ids: None,
+            peak_rss_mb: None,
+            peak_gpu_mb: None,
+            progress: None,
+            tags: Default::default(),
+            keep_job_dir: false,
+            job_dir: None,
         }
     }
 
@@ -334,6 +615,12 @@ mod tests {
             run_name: Some(name.into()),
             project: None,
             notifications: gflow::core::job::JobNotifications::default(),
+            environment: gflow::core::job::EnvSpec::None,
+            container_image: None,
+            executor: None,
+            interactive: false,
+            preemptible: false,
+            preemptions: 0,
             state: JobState::Finished,
             gpu_ids: Some(smallvec::smallvec![0]),
             submitted_at: None,
@@ -351,6 +638,23 @@ mod tests {
             group_id: None,
             max_concurrent: None,
             reason: None,
+            node: None,
+            constraint: None,
+            min_free_disk_mb: None,
+            script_content: None,
+            submission_id: None,
+            force_queue: false,
+            as_user: false,
+            queued_at: None,
+            batch_depends_on: Vec::new(),
+            cpus: None,
+            cpu_ids: None,
+            peak_rss_mb: None,
+            peak_gpu_mb: None,
+            progress: None,
+            tags: Default::default(),
+            keep_job_dir: false,
+            job_dir: None,
         }
     }
 
@@ -366,7 +670,38 @@ mod tests {
             create_test_job_with_state(7, "job-7", JobState::Cancelled),
         ];
         println!();
-        display_jobs_tree(&jobs, None, &HashSet::new());
+        display_jobs_tree(&jobs, &default_columns(), &CellContext::default());
+    }
+
+    #[test]
+    fn blocked_jobs_wait_on_something_other_than_resources() {
+        use gflow::core::job::JobStateReason;
+        let queued_with = |reason: Option<JobStateReason>| {
+            let mut job = create_test_job_with_state(1, "job-1", JobState::Queued);
+            job.reason = reason.map(Box::new);
+            job
+        };
+        assert!(is_blocked(&queued_with(Some(JobStateReason::Dependency {
+            unfinished: 2
+        }))));
+        assert!(is_blocked(&queued_with(Some(JobStateReason::GroupLimit {
+            running: 4,
+            max: 4
+        }))));
+        assert!(!is_blocked(&queued_with(Some(
+            JobStateReason::WaitingForGpus { need: 4, free: 1 }
+        ))));
+        assert!(!is_blocked(&queued_with(None)));
+        assert!(is_blocked(&create_test_job_with_state(
+            2,
+            "job-2",
+            JobState::Hold
+        )));
+        assert!(!is_blocked(&create_test_job_with_state(
+            3,
+            "job-3",
+            JobState::Running
+        )));
     }
 
     #[test]
@@ -377,7 +712,7 @@ mod tests {
             create_test_job(3, "child-job-2", Some(1)),
         ];
         println!();
-        display_jobs_tree(&jobs, None, &HashSet::new());
+        display_jobs_tree(&jobs, &default_columns(), &CellContext::default());
     }
 
     #[test]
@@ -389,7 +724,7 @@ mod tests {
             create_test_job(4, "level-3-job", Some(3)),
         ];
         println!();
-        display_jobs_tree(&jobs, None, &HashSet::new());
+        display_jobs_tree(&jobs, &default_columns(), &CellContext::default());
     }
 
     #[test]
@@ -402,7 +737,7 @@ mod tests {
             create_test_job(5, "child-2-2", Some(3)),
         ];
         println!();
-        display_jobs_tree(&jobs, None, &HashSet::new());
+        display_jobs_tree(&jobs, &default_columns(), &CellContext::default());
     }
 
     #[test]
@@ -417,7 +752,7 @@ mod tests {
             // this in our current structure without modifying the data after creation
         ];
         println!();
-        display_jobs_tree(&jobs, None, &HashSet::new());
+        display_jobs_tree(&jobs, &default_columns(), &CellContext::default());
     }
 
     #[test]
@@ -428,7 +763,7 @@ mod tests {
             create_test_job(3, "job-3", Some(1)),
         ];
         println!();
-        display_jobs_tree(&jobs, None, &HashSet::new());
+        display_jobs_tree(&jobs, &default_columns(), &CellContext::default());
     }
 
     #[test]
@@ -524,7 +859,7 @@ mod tests {
             create_test_job(3, "job-3", Some(1)),
         ];
         println!();
-        display_jobs_tree(&jobs, None, &HashSet::new());
+        display_jobs_tree(&jobs, &default_columns(), &CellContext::default());
     }
 
     #[test]
@@ -539,14 +874,14 @@ mod tests {
             create_test_job(7, "deep-child", Some(4)),
         ];
         println!();
-        display_jobs_tree(&jobs, None, &HashSet::new());
+        display_jobs_tree(&jobs, &default_columns(), &CellContext::default());
     }
 
     #[test]
     fn test_empty_job_list() {
         let jobs: Vec<Job> = vec![];
         println!();
-        display_jobs_tree(&jobs, None, &HashSet::new());
+        display_jobs_tree(&jobs, &default_columns(), &CellContext::default());
     }
 
     #[test]
@@ -557,7 +892,7 @@ mod tests {
             create_test_job(3, "short", Some(1)),
         ];
         println!();
-        display_jobs_tree(&jobs, None, &HashSet::new());
+        display_jobs_tree(&jobs, &default_columns(), &CellContext::default());
     }
 
     #[test]
@@ -570,7 +905,7 @@ mod tests {
         ];
         println!();
         println!("Test: Redo relationship (job 3 is redone from job 1)");
-        display_jobs_tree(&jobs, None, &HashSet::new());
+        display_jobs_tree(&jobs, &default_columns(), &CellContext::default());
     }
 
     #[test]
@@ -584,7 +919,7 @@ mod tests {
         ];
         println!();
         println!("Test: Mixed dependencies and redo relationships");
-        display_jobs_tree(&jobs, None, &HashSet::new());
+        display_jobs_tree(&jobs, &default_columns(), &CellContext::default());
     }
 
     #[test]
@@ -597,7 +932,7 @@ mod tests {
         ];
         println!();
         println!("Test: Mixed dependencies and redo relationships");
-        display_jobs_tree(&jobs, None, &HashSet::new());
+        display_jobs_tree(&jobs, &default_columns(), &CellContext::default());
     }
 
     #[test]
@@ -618,7 +953,7 @@ mod tests {
         println!("Test: Job with both dependency and redo relationship (user's scenario)");
         println!("Job 165 depends on 163 AND is a redo of 164");
         println!("Expected: Job 165 appears once under 163, with '→ see job 165 below' reference under 164");
-        display_jobs_tree(&jobs, None, &HashSet::new());
+        display_jobs_tree(&jobs, &default_columns(), &CellContext::default());
     }
 
     #[test]
@@ -634,7 +969,7 @@ mod tests {
         println!();
         println!("Test: Repeated redo operations (chain of redos)");
         println!("100 -> 101 (redo of 100) -> 102 (redo of 101) -> 103 (redo of 102)");
-        display_jobs_tree(&jobs, None, &HashSet::new());
+        display_jobs_tree(&jobs, &default_columns(), &CellContext::default());
     }
 
     #[test]
@@ -650,7 +985,7 @@ mod tests {
         println!();
         println!("Test: Multiple redos of the same job");
         println!("Jobs 201, 202, 203 are all redos of job 200");
-        display_jobs_tree(&jobs, None, &HashSet::new());
+        display_jobs_tree(&jobs, &default_columns(), &CellContext::default());
     }
 
     #[test]
@@ -668,7 +1003,7 @@ mod tests {
         println!("Test: Redo job with its own dependencies");
         println!("300 -> 301 (depends on 300)");
         println!("302 (redo of 300) -> 303 (depends on 302)");
-        display_jobs_tree(&jobs, None, &HashSet::new());
+        display_jobs_tree(&jobs, &default_columns(), &CellContext::default());
     }
 
     #[test]
@@ -695,7 +1030,7 @@ mod tests {
         println!("400 -> 401 -> 402");
         println!("403 (redo of 401, depends on 400)");
         println!("404 (redo of 402, depends on 403)");
-        display_jobs_tree(&jobs, None, &HashSet::new());
+        display_jobs_tree(&jobs, &default_columns(), &CellContext::default());
     }
 
     #[test]
@@ -716,7 +1051,7 @@ mod tests {
         println!("500 -> 501");
         println!("502 (redo of 500, but depends on 501)");
         println!("Expected: 502 appears under 501, reference under 500");
-        display_jobs_tree(&jobs, None, &HashSet::new());
+        display_jobs_tree(&jobs, &default_columns(), &CellContext::default());
     }
 
     #[test]
@@ -742,6 +1077,52 @@ mod tests {
         println!("600 -> 601 -> 602");
         println!("603 and 604 are both redos of 602");
         println!("Expected: 602 appears under 601, 603 and 604 are root jobs with redo indicators");
-        display_jobs_tree(&jobs, None, &HashSet::new());
+        display_jobs_tree(&jobs, &default_columns(), &CellContext::default());
+    }
+
+    #[test]
+    fn page_window_counts_pages_from_either_end() {
+        let window = PageWindow {
+            per_page: 10,
+            page: 3,
+            from_end: false,
+            explicit: true,
+        };
+        assert_eq!(window.offset(), 20);
+        assert_eq!(window.range(25), (20, 25));
+        assert_eq!(window.range(15), (15, 15));
+        assert_eq!(
+            window.message(5, 25).as_deref(),
+            Some("Showing jobs 21-25 of 25 (page 3 of 3)")
+        );
+
+        let last = PageWindow {
+            from_end: true,
+            page: 2,
+            ..window
+        };
+        assert_eq!(last.range(25), (5, 15));
+        assert_eq!(last.range(12), (0, 2));
+        let ids: Vec<u32> = last
+            .apply(
+                (1..=25)
+                    .map(|id| create_test_job(id, "job", None))
+                    .collect(),
+            )
+            .iter()
+            .map(|job| job.id)
+            .collect();
+        assert_eq!(ids, (6..=15).collect::<Vec<_>>());
+
+        let first = PageWindow {
+            page: 1,
+            explicit: false,
+            ..window
+        };
+        assert_eq!(
+            first.message(10, 25).as_deref(),
+            Some("Showing first 10 of 25 jobs (use --all or -n 0 to show all)")
+        );
+        assert_eq!(first.message(8, 8), None);
     }
 }
@@ -0,0 +1,165 @@
+//! Table columns chosen with `--format` or `[gqueue] format`, e.g.
+//! `JOBID:6,NAME:30,USER,ST`. A column without `:<width>` is as wide as its content.
+
+use anyhow::{anyhow, bail, Context, Result};
+
+/// Columns shown when neither `--format` nor `[gqueue] format` is set
+pub const DEFAULT_FORMAT: &str = "JOBID,NAME,ST,TIME,NODES,NODELIST(REASON)";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Field {
+    JobId,
+    Name,
+    User,
+    State,
+    Gpus,
+    Cpus,
+    Nodes,
+    NodeList,
+    Time,
+    TimeLimit,
+    Memory,
+    Priority,
+    Project,
+    Tags,
+    Preempt,
+    EstStart,
+    Submitted,
+    Started,
+    Finished,
+    Wait,
+    Progress,
+}
+
+/// Every column by its header, in the order listed to users.
+const FIELDS: [(&str, Field); 21] = [
+    ("JOBID", Field::JobId),
+    ("NAME", Field::Name),
+    ("USER", Field::User),
+    ("ST", Field::State),
+    ("GPUS", Field::Gpus),
+    ("CPUS", Field::Cpus),
+    ("NODES", Field::Nodes),
+    ("NODELIST(REASON)", Field::NodeList),
+    ("TIME", Field::Time),
+    ("TIMELIMIT", Field::TimeLimit),
+    ("MEMORY", Field::Memory),
+    ("PRIORITY", Field::Priority),
+    ("PROJECT", Field::Project),
+    ("TAGS", Field::Tags),
+    ("PREEMPT", Field::Preempt),
+    ("EST_START", Field::EstStart),
+    ("SUBMITTED", Field::Submitted),
+    ("STARTED", Field::Started),
+    ("FINISHED", Field::Finished),
+    ("WAIT", Field::Wait),
+    ("PROGRESS", Field::Progress),
+];
+
+impl Field {
+    pub fn header(self) -> &'static str {
+        FIELDS
+            .iter()
+            .find(|(_, field)| *field == self)
+            .map(|(header, _)| *header)
+            .expect("every field has a header")
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Column {
+    pub field: Field,
+    /// Fixed width; longer cells are cut short with `…`
+    pub width: Option<usize>,
+}
+
+/// Parse a comma-separated `NAME[:WIDTH]` list; names ignore case.
+pub fn parse_columns(format: &str) -> Result<Vec<Column>> {
+    let columns = format
+        .split(',')
+        .map(str::trim)
+        .filter(|column| !column.is_empty())
+        .map(parse_column)
+        .collect::<Result<Vec<_>>>()?;
+    if columns.is_empty() {
+        bail!("No columns in format '{format}'");
+    }
+    Ok(columns)
+}
+
+fn parse_column(column: &str) -> Result<Column> {
+    let (name, width) = match column.rsplit_once(':') {
+        Some((name, width)) => {
+            let width = width
+                .trim()
+                .parse::<usize>()
+                .ok()
+                .filter(|width| *width > 0)
+                .with_context(|| {
+                    format!("Invalid width '{width}' for column {name}; expected a positive number")
+                })?;
+            (name.trim(), Some(width))
+        }
+        None => (column, None),
+    };
+
+    let field = FIELDS
+        .iter()
+        .find(|(header, _)| header.eq_ignore_ascii_case(name))
+        .map(|(_, field)| *field)
+        .ok_or_else(|| {
+            let valid: Vec<&str> = FIELDS.iter().map(|(header, _)| *header).collect();
+            anyhow!(
+                "Unknown column '{name}'. Valid columns: {}",
+                valid.join(", ")
+            )
+        })?;
+    Ok(Column { field, width })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn widths_are_optional_per_column() {
+        let columns = parse_columns("JOBID:6, name:30,USER,NODELIST(REASON)").unwrap();
+        assert_eq!(
+            columns,
+            vec![
+                Column {
+                    field: Field::JobId,
+                    width: Some(6)
+                },
+                Column {
+                    field: Field::Name,
+                    width: Some(30)
+                },
+                Column {
+                    field: Field::User,
+                    width: None
+                },
+                Column {
+                    field: Field::NodeList,
+                    width: None
+                },
+            ]
+        );
+        assert_eq!(columns[1].field.header(), "NAME");
+        assert_eq!(parse_columns(DEFAULT_FORMAT).unwrap().len(), 6);
+    }
+
+    #[test]
+    fn unknown_columns_list_the_valid_ones() {
+        let err = parse_columns("JOBID,OWNER").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Unknown column 'OWNER'. Valid columns: JOBID, NAME, USER, ST, GPUS, CPUS, NODES, \
+             NODELIST(REASON), TIME, TIMELIMIT, MEMORY, PRIORITY, PROJECT, TAGS, \
+             PREEMPT, EST_START, SUBMITTED, STARTED, FINISHED, WAIT, PROGRESS"
+        );
+        assert!(parse_columns("NAME:0").is_err());
+        assert!(parse_columns("NAME:wide").is_err());
+        assert!(parse_columns(" , ").is_err());
+    }
+}
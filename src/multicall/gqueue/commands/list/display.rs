@@ -1,85 +1,103 @@
+use super::columns::{Column, Field};
+use gflow::config::TimeStyle;
 use gflow::core::job::{GpuIds, JobState};
-use owo_colors::OwoColorize;
-use std::collections::HashSet;
-use tabled::{builder::Builder, settings::style::Style};
+use gflow::core::scheduler::StartEstimate;
+use gflow::utils::output::OutputStyle;
+use owo_colors::Style as TextStyle;
+use std::collections::{BTreeMap, HashSet};
+use std::time::SystemTime;
+use tabled::{
+    builder::Builder,
+    settings::{object::Columns, style::Style, Width},
+    Table,
+};
 
-pub(super) fn display_jobs_table(
-    jobs: &[gflow::core::job::Job],
-    format: Option<&str>,
-    tmux_sessions: &HashSet<String>,
-) {
-    if jobs.is_empty() {
-        println!("No jobs to display.");
-        return;
-    }
-
-    let format = format
-        .unwrap_or("JOBID,NAME,ST,TIME,NODES,NODELIST(REASON)")
-        .to_string();
-    let headers: Vec<&str> = format.split(',').collect();
-
-    // Build table using tabled Builder
-    let mut builder = Builder::default();
-
-    // Add header row
-    builder.push_record(headers.clone());
+/// What cells need besides the job itself.
+#[derive(Default)]
+pub(super) struct CellContext {
+    pub tmux_sessions: HashSet<String>,
+    /// Only fetched when `EST_START` is shown.
+    pub estimates: BTreeMap<u32, StartEstimate>,
+    /// Unix timestamp estimates and relative times are shown against.
+    pub now: i64,
+    /// How SUBMITTED/STARTED/FINISHED are shown
+    pub time_style: TimeStyle,
+    /// Timezone for `TimeStyle::Local`; the local one if unset
+    pub timezone: Option<String>,
+    /// `[priorities]`, whose names PRIORITY shows instead of their values
+    pub priority_classes: BTreeMap<String, u8>,
+    pub style: OutputStyle,
+}
 
-    // Add data rows
-    for job in jobs {
-        let row: Vec<String> = headers
-            .iter()
-            .map(|header| format_job_cell(job, header, tmux_sessions))
-            .collect();
-        builder.push_record(row);
+impl CellContext {
+    fn now_time(&self) -> SystemTime {
+        SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(self.now.max(0) as u64)
     }
+}
 
-    let mut table = builder.build();
-    table.with(Style::blank());
-
-    println!("{}", table);
+pub(super) fn display_jobs_table(
+    jobs: &[gflow::core::job::Job],
+    columns: &[Column],
+    cells: &CellContext,
+) {
+    let jobs: Vec<&gflow::core::job::Job> = jobs.iter().collect();
+    display_jobs_table_refs(&jobs, columns, cells);
 }
 
 /// Displays jobs in a standard table format (for references)
 fn display_jobs_table_refs(
     jobs: &[&gflow::core::job::Job],
-    format: Option<&str>,
-    tmux_sessions: &HashSet<String>,
+    columns: &[Column],
+    cells: &CellContext,
 ) {
     if jobs.is_empty() {
         println!("No jobs to display.");
         return;
     }
 
-    let format = format
-        .unwrap_or("JOBID,NAME,ST,TIME,NODES,NODELIST(REASON)")
-        .to_string();
-    let headers: Vec<&str> = format.split(',').collect();
+    println!("{}", render_jobs_table(jobs, columns, cells));
+}
 
+fn render_jobs_table(
+    jobs: &[&gflow::core::job::Job],
+    columns: &[Column],
+    cells: &CellContext,
+) -> Table {
     // Build table using tabled Builder
     let mut builder = Builder::default();
 
     // Add header row
-    builder.push_record(headers.clone());
+    builder.push_record(columns.iter().map(|column| column.field.header()));
 
     // Add data rows
     for job in jobs {
-        let row: Vec<String> = headers
+        let row: Vec<String> = columns
             .iter()
-            .map(|header| format_job_cell(job, header, tmux_sessions))
+            .map(|column| format_job_cell(job, column.field, cells))
             .collect();
         builder.push_record(row);
     }
 
+    finish_table(builder, columns)
+}
+
+/// Build the table with its style and the fixed column widths.
+pub(super) fn finish_table(builder: Builder, columns: &[Column]) -> Table {
     let mut table = builder.build();
     table.with(Style::blank());
-
-    println!("{}", table);
+    for (index, column) in columns.iter().enumerate() {
+        if let Some(width) = column.width {
+            table.modify(Columns::one(index), Width::truncate(width).suffix("…"));
+            table.modify(Columns::one(index), Width::increase(width));
+        }
+    }
+    table
 }
 
 pub(super) fn display_grouped_jobs(
     jobs: &[gflow::core::job::Job],
-    format: Option<&str>,
-    tmux_sessions: &HashSet<String>,
+    columns: &[Column],
+    cells: &CellContext,
 ) {
     use gflow::core::job::JobState;
 
@@ -107,26 +125,12 @@ pub(super) fn display_grouped_jobs(
             first = false;
 
             println!("{} ({})", state, state_jobs.len());
-            println!("{}", "─".repeat(60));
-            display_jobs_table_refs(state_jobs, format, tmux_sessions);
+            println!("{}", cells.style.rule(60));
+            display_jobs_table_refs(state_jobs, columns, cells);
         }
     }
 }
 
-/// Colorizes a job state string based on its state
-fn colorize_state(state: &JobState) -> String {
-    let short = state.short_form();
-    match state {
-        JobState::Running => short.green().bold().to_string(),
-        JobState::Finished => short.dimmed().to_string(),
-        JobState::Queued => short.italic().to_string(),
-        JobState::Hold => short.bold().to_string(),
-        JobState::Failed => short.red().bold().to_string(),
-        JobState::Timeout => short.underline().to_string(),
-        JobState::Cancelled => short.strikethrough().to_string(),
-    }
-}
-
 /// Computes the reason why a job is in its current state for display
 pub(super) fn get_job_reason_display(job: &gflow::core::job::Job) -> String {
     use gflow::core::job::JobStateReason;
@@ -161,56 +165,265 @@ fn format_gpu_ids(gpu_ids: Option<&GpuIds>) -> String {
     )
 }
 
+/// The job's user; jobs from before users were required may have none.
+pub(super) fn format_user(job: &gflow::core::job::Job) -> &str {
+    match job.submitted_by.trim() {
+        "" => "(unknown)",
+        user => user,
+    }
+}
+
+/// Formats the requested CPU cores, with the pinned ones while running,
+/// e.g. `4 (8-11)`
+fn format_cpus(job: &gflow::core::job::Job) -> String {
+    let Some(cpus) = job.cpus else {
+        return "-".to_string();
+    };
+    match job
+        .cpu_ids
+        .as_ref()
+        .filter(|_| job.state == JobState::Running)
+    {
+        Some(ids) if !ids.is_empty() => {
+            format!("{cpus} ({})", gflow::core::cpu::format_cpu_list(ids))
+        }
+        _ => cpus.to_string(),
+    }
+}
+
+const ISO_FORMAT: &str = "%Y-%m-%dT%H:%M:%S%:z";
+
+/// Formats a point in time as `cells.time_style` asks, `-` if unset
+fn format_time_cell(time: Option<SystemTime>, cells: &CellContext) -> String {
+    let Some(time) = time else {
+        return "-".to_string();
+    };
+    match cells.time_style {
+        TimeStyle::Relative => {
+            let secs = time
+                .duration_since(SystemTime::UNIX_EPOCH)
+                .map_or(0, |since_epoch| since_epoch.as_secs() as i64);
+            let ago = std::time::Duration::from_secs((cells.now - secs).max(0) as u64);
+            format!("{} ago", gflow::utils::format_duration_compact(ago))
+        }
+        TimeStyle::Local => {
+            gflow::utils::timezone::format_system_time(time, cells.timezone.as_deref(), ISO_FORMAT)
+                .unwrap_or_else(|_| gflow::utils::format_system_time(time))
+        }
+        TimeStyle::Utc => chrono::DateTime::<chrono::Utc>::from(time)
+            .format(ISO_FORMAT)
+            .to_string(),
+    }
+}
+
 /// Formats a job field value for display
 pub(super) fn format_job_cell(
     job: &gflow::core::job::Job,
-    header: &str,
-    tmux_sessions: &HashSet<String>,
+    field: Field,
+    cells: &CellContext,
 ) -> String {
-    match header {
-        "JOBID" => job.id.to_string(),
-        "NAME" => format_job_name_with_session_status(job, tmux_sessions),
-        "ST" => colorize_state(&job.state),
-        "NODES" => job.gpus.to_string(),
-        "MEMORY" => job
+    match field {
+        Field::JobId => job.id.to_string(),
+        Field::Name => format_job_name_with_session_status(job, cells),
+        Field::User => format_user(job).to_string(),
+        Field::State => cells.style.state(job.state),
+        Field::Gpus | Field::Nodes => job.gpus.to_string(),
+        Field::Cpus => format_cpus(job),
+        Field::Memory => job
             .memory_limit_mb
             .map_or_else(|| "-".to_string(), gflow::utils::format_memory),
-        "NODELIST(REASON)" => {
-            // For running jobs, show GPU IDs
+        Field::NodeList => {
+            // For running jobs, show GPU IDs, prefixed by the node for remote jobs
             // For queued/held/cancelled jobs, show pending reason
             match job.state {
-                JobState::Running => format_gpu_ids(job.gpu_ids.as_ref()),
+                JobState::Running => match &job.node {
+                    Some(node) => format!("{node}:{}", format_gpu_ids(job.gpu_ids.as_ref())),
+                    None => format_gpu_ids(job.gpu_ids.as_ref()),
+                },
                 JobState::Queued | JobState::Hold | JobState::Cancelled => {
                     get_job_reason_display(job)
                 }
                 _ => "-".to_string(),
             }
         }
-        "TIME" => gflow::utils::format_elapsed_time(job.started_at, job.finished_at),
-        "TIMELIMIT" => job
+        Field::Time => gflow::utils::format_elapsed_time(job.started_at, job.finished_at),
+        Field::EstStart => cells.estimates.get(&job.id).map_or_else(
+            || "-".to_string(),
+            |estimate| gflow::utils::format_start_estimate(estimate, cells.now),
+        ),
+        Field::TimeLimit => job
             .time_limit
             .map_or_else(|| "UNLIMITED".to_string(), gflow::utils::format_duration),
-        "USER" => job.submitted_by.to_string(),
-        "PROJECT" => job
+        Field::Priority => {
+            gflow::utils::parsers::priority_class_name(job.priority, &cells.priority_classes)
+                .map_or_else(|| job.priority.to_string(), str::to_string)
+        }
+        Field::Preempt => job.preemptions.to_string(),
+        Field::Project => job
             .project
             .as_ref()
             .map_or_else(|| "-".to_string(), |p| p.to_string()),
-        _ => String::new(),
+        Field::Tags if job.tags.is_empty() => "-".to_string(),
+        Field::Tags => job.tags.iter().collect::<Vec<_>>().join(","),
+        Field::Submitted => format_time_cell(job.submitted_at, cells),
+        Field::Started => format_time_cell(job.started_at, cells),
+        Field::Finished => format_time_cell(job.finished_at, cells),
+        Field::Wait => job
+            .wait_time_as_of(cells.now_time())
+            .map_or_else(|| "-".to_string(), gflow::utils::format_duration_compact),
+        Field::Progress => format_progress(job, cells),
+    }
+}
+
+/// The job's latest progress, dimmed and marked `*` once it is stale
+fn format_progress(job: &gflow::core::job::Job, cells: &CellContext) -> String {
+    match job.progress.as_deref() {
+        Some(progress) if progress.is_stale(cells.now_time()) => cells
+            .style
+            .paint(format!("{progress}*"), TextStyle::new().dimmed()),
+        Some(progress) => progress.to_string(),
+        None => "-".to_string(),
     }
 }
 
 /// Formats the job name with a visual indicator for tmux session status
-fn format_job_name_with_session_status(
-    job: &gflow::core::job::Job,
-    tmux_sessions: &HashSet<String>,
-) -> String {
+fn format_job_name_with_session_status(job: &gflow::core::job::Job, cells: &CellContext) -> String {
     let Some(name) = &job.run_name else {
         return "-".to_string();
     };
 
-    if tmux_sessions.contains(name.as_str()) {
-        format!("{} {}", name, "○".green())
+    if cells.tmux_sessions.contains(name.as_str()) {
+        let marker = if cells.style.unicode { "○" } else { "*" };
+        format!(
+            "{} {}",
+            name,
+            cells.style.paint(marker, TextStyle::new().green())
+        )
     } else {
         name.to_string()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::super::columns::parse_columns;
+    use super::*;
+    use gflow::core::job::Job;
+    use std::time::Duration;
+
+    #[test]
+    fn fixed_widths_pad_and_truncate_cells() {
+        let job = Job {
+            id: 7,
+            run_name: Some("train-a-very-long-model-name".into()),
+            submitted_at: Some(SystemTime::UNIX_EPOCH + Duration::from_secs(1_000)),
+            ..Default::default()
+        };
+        let cells = CellContext {
+            now: 1_000 + 90 * 60,
+            ..Default::default()
+        };
+        let columns = parse_columns("JOBID:5,NAME:10,SUBMITTED").unwrap();
+        let table = render_jobs_table(&[&job], &columns, &cells).to_string();
+        let lines: Vec<&str> = table.lines().map(str::trim_end).collect();
+        assert_eq!(lines[0], " JOBID   NAME         SUBMITTED");
+        assert_eq!(lines[1], " 7       train-a-v…   1h 30m ago");
+
+        let iso = CellContext {
+            time_style: TimeStyle::Local,
+            timezone: Some("UTC".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            format_job_cell(&job, Field::Submitted, &iso),
+            "1970-01-01T00:16:40+00:00"
+        );
+    }
+
+    #[test]
+    fn stale_progress_is_marked() {
+        let reported_at = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000);
+        let job = Job {
+            state: JobState::Running,
+            progress: gflow::core::job::JobProgress::parse("42 epoch 42/100", reported_at)
+                .map(Box::new),
+            ..Default::default()
+        };
+        let fresh = CellContext {
+            now: 1_000 + 60,
+            ..Default::default()
+        };
+        assert_eq!(
+            format_job_cell(&job, Field::Progress, &fresh),
+            "42% epoch 42/100"
+        );
+        let later = CellContext {
+            now: 1_000 + 3600,
+            ..Default::default()
+        };
+        assert_eq!(
+            format_job_cell(&job, Field::Progress, &later),
+            "42% epoch 42/100*"
+        );
+        assert_eq!(
+            format_job_cell(&Job::default(), Field::Progress, &later),
+            "-"
+        );
+    }
+
+    #[test]
+    fn priority_shows_the_name_of_a_configured_class() {
+        let cells = CellContext {
+            priority_classes: BTreeMap::from([("high".to_string(), 50)]),
+            ..Default::default()
+        };
+        let high = Job {
+            priority: 50,
+            ..Default::default()
+        };
+        let other = Job {
+            priority: 51,
+            ..Default::default()
+        };
+        assert_eq!(format_job_cell(&high, Field::Priority, &cells), "high");
+        assert_eq!(format_job_cell(&other, Field::Priority, &cells), "51");
+    }
+
+    #[test]
+    fn jobs_without_a_user_show_unknown() {
+        let cells = CellContext::default();
+        let job = Job {
+            submitted_by: "alice".into(),
+            ..Default::default()
+        };
+        assert_eq!(format_job_cell(&job, Field::User, &cells), "alice");
+        let job = Job {
+            submitted_by: "".into(),
+            ..Default::default()
+        };
+        assert_eq!(format_job_cell(&job, Field::User, &cells), "(unknown)");
+    }
+
+    #[test]
+    fn states_are_colored_only_when_the_style_allows() {
+        let job = Job {
+            id: 3,
+            state: JobState::Queued,
+            ..Default::default()
+        };
+        let columns = parse_columns("JOBID,ST").unwrap();
+        let plain = render_jobs_table(&[&job], &columns, &CellContext::default()).to_string();
+        assert!(!plain.contains('\u{1b}'));
+        assert!(plain.contains(" PD"));
+
+        let colored = CellContext {
+            style: OutputStyle {
+                color: true,
+                unicode: true,
+            },
+            ..Default::default()
+        };
+        let table = render_jobs_table(&[&job], &columns, &colored).to_string();
+        assert!(table.contains("\u{1b}[33mPD\u{1b}[0m"));
+    }
+}
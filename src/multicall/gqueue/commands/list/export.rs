@@ -0,0 +1,231 @@
+//! `gqueue --export`: one row per job for usage reports, written as it goes
+//! rather than collected into one string.
+
+use anyhow::{Context, Result};
+use gflow::core::job::Job;
+use serde::Serialize;
+use std::io::Write;
+use std::path::Path;
+use std::time::SystemTime;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ExportFormat {
+    Csv,
+    Jsonl,
+}
+
+const COLUMNS: [&str; 16] = [
+    "id",
+    "name",
+    "user",
+    "project",
+    "state",
+    "gpus",
+    "gpu_ids",
+    "submitted_at",
+    "started_at",
+    "finished_at",
+    "runtime_secs",
+    "priority",
+    "time_limit_secs",
+    "command",
+    "peak_rss_mb",
+    "peak_gpu_mb",
+];
+
+#[derive(Debug, Serialize)]
+struct ExportRow<'a> {
+    id: u32,
+    name: Option<&'a str>,
+    user: &'a str,
+    project: Option<&'a str>,
+    state: String,
+    gpus: u32,
+    gpu_ids: Vec<u32>,
+    submitted_at: Option<String>,
+    started_at: Option<String>,
+    finished_at: Option<String>,
+    runtime_secs: Option<u64>,
+    priority: u8,
+    time_limit_secs: Option<u64>,
+    command: Option<String>,
+    peak_rss_mb: Option<u64>,
+    peak_gpu_mb: Option<u64>,
+}
+
+fn rfc3339(time: Option<SystemTime>) -> Option<String> {
+    time.map(|t| {
+        chrono::DateTime::<chrono::Utc>::from(t).to_rfc3339_opts(chrono::SecondsFormat::Secs, true)
+    })
+}
+
+impl<'a> ExportRow<'a> {
+    fn from_job(job: &'a Job, now: SystemTime) -> Self {
+        // Running jobs count up to the time of the export.
+        let runtime_secs = job.started_at.map(|started| {
+            job.finished_at
+                .unwrap_or(now)
+                .duration_since(started)
+                .map_or(0, |elapsed| elapsed.as_secs())
+        });
+        Self {
+            id: job.id,
+            name: job.run_name.as_deref(),
+            user: &job.submitted_by,
+            project: job.project.as_deref(),
+            state: job.state.to_string(),
+            gpus: job.gpus,
+            gpu_ids: job
+                .gpu_ids
+                .as_ref()
+                .map_or_else(Vec::new, |ids| ids.to_vec()),
+            submitted_at: rfc3339(job.submitted_at),
+            started_at: rfc3339(job.started_at),
+            finished_at: rfc3339(job.finished_at),
+            runtime_secs,
+            priority: job.priority,
+            time_limit_secs: job.time_limit.map(|limit| limit.as_secs()),
+            command: job
+                .command
+                .as_ref()
+                .map(|command| command.to_string())
+                .or_else(|| {
+                    job.script
+                        .as_ref()
+                        .map(|script| script.display().to_string())
+                }),
+            peak_rss_mb: job.peak_rss_mb,
+            peak_gpu_mb: job.peak_gpu_mb,
+        }
+    }
+
+    fn csv_record(&self) -> [String; COLUMNS.len()] {
+        fn opt<T: ToString>(value: Option<T>) -> String {
+            value.map(|v| v.to_string()).unwrap_or_default()
+        }
+        [
+            self.id.to_string(),
+            opt(self.name),
+            self.user.to_string(),
+            opt(self.project),
+            self.state.clone(),
+            self.gpus.to_string(),
+            self.gpu_ids
+                .iter()
+                .map(|id| id.to_string())
+                .collect::<Vec<_>>()
+                .join(","),
+            opt(self.submitted_at.as_deref()),
+            opt(self.started_at.as_deref()),
+            opt(self.finished_at.as_deref()),
+            opt(self.runtime_secs),
+            self.priority.to_string(),
+            opt(self.time_limit_secs),
+            opt(self.command.as_deref()),
+            opt(self.peak_rss_mb),
+            opt(self.peak_gpu_mb),
+        ]
+    }
+}
+
+/// Write `jobs` to `path`, or to stdout without one.
+pub(super) fn export_jobs(jobs: &[Job], format: ExportFormat, path: Option<&Path>) -> Result<()> {
+    match path {
+        Some(path) => {
+            let file = std::fs::File::create(path)
+                .with_context(|| format!("Failed to create {}", path.display()))?;
+            write_jobs(jobs, format, std::io::BufWriter::new(file))
+                .with_context(|| format!("Failed to write {}", path.display()))?;
+            eprintln!("Exported {} jobs to {}", jobs.len(), path.display());
+            Ok(())
+        }
+        None => write_jobs(jobs, format, std::io::stdout().lock()),
+    }
+}
+
+fn write_jobs(jobs: &[Job], format: ExportFormat, mut out: impl Write) -> Result<()> {
+    let now = SystemTime::now();
+    match format {
+        ExportFormat::Csv => {
+            let mut wtr = csv::Writer::from_writer(out);
+            wtr.write_record(COLUMNS)?;
+            for job in jobs {
+                wtr.write_record(ExportRow::from_job(job, now).csv_record())?;
+            }
+            wtr.flush()?;
+        }
+        ExportFormat::Jsonl => {
+            for job in jobs {
+                serde_json::to_writer(&mut out, &ExportRow::from_job(job, now))?;
+                out.write_all(b"\n")?;
+            }
+            out.flush()?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gflow::core::job::{JobBuilder, JobState};
+    use std::time::Duration;
+
+    fn finished_job() -> Job {
+        let mut job = JobBuilder::new()
+            .command("python train.py --tags a,b\necho \"done\"")
+            .submitted_by("alice")
+            .gpus(2)
+            .build();
+        job.id = 7;
+        job.state = JobState::Finished;
+        job.gpu_ids = Some(smallvec::smallvec![0, 3]);
+        let start = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        job.submitted_at = Some(start);
+        job.started_at = Some(start + Duration::from_secs(5));
+        job.finished_at = Some(start + Duration::from_secs(95));
+        job.peak_rss_mb = Some(3072);
+        job
+    }
+
+    #[test]
+    fn csv_quotes_commands_with_commas_and_newlines() {
+        let mut out = Vec::new();
+        write_jobs(&[finished_job()], ExportFormat::Csv, &mut out).unwrap();
+
+        let mut reader = csv::Reader::from_reader(out.as_slice());
+        assert_eq!(reader.headers().unwrap(), COLUMNS.as_slice());
+        let rows: Vec<csv::StringRecord> = reader.records().map(Result::unwrap).collect();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(&rows[0][0], "7");
+        assert_eq!(&rows[0][6], "0,3");
+        assert_eq!(&rows[0][7], "2023-11-14T22:13:20Z");
+        assert_eq!(&rows[0][10], "90");
+        assert_eq!(&rows[0][13], "python train.py --tags a,b\necho \"done\"");
+        assert_eq!(&rows[0][14], "3072");
+        assert_eq!(&rows[0][15], "");
+    }
+
+    #[test]
+    fn jsonl_writes_one_object_per_line() {
+        let mut queued = JobBuilder::new()
+            .command("true")
+            .submitted_by("bob")
+            .build();
+        queued.id = 8;
+
+        let mut out = Vec::new();
+        write_jobs(&[finished_job(), queued], ExportFormat::Jsonl, &mut out).unwrap();
+
+        let lines: Vec<serde_json::Value> = String::from_utf8(out)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["gpu_ids"], serde_json::json!([0, 3]));
+        assert_eq!(lines[0]["runtime_secs"], 90);
+        assert_eq!(lines[1]["user"], "bob");
+        assert!(lines[1]["runtime_secs"].is_null());
+    }
+}
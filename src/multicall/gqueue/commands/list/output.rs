@@ -19,9 +19,17 @@ pub(super) struct JobOutput {
     pub(super) state: String,
     pub(super) time: String,
     pub(super) gpus: Vec<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) cpus: Option<u32>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub(super) cpu_ids: Vec<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) node: Option<String>,
     pub(super) user: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub(super) project: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub(super) tags: Vec<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub(super) submitted_at: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -50,8 +58,15 @@ impl JobOutput {
                 .gpu_ids
                 .as_ref()
                 .map_or_else(Vec::new, |ids| ids.to_vec()),
+            cpus: job.cpus,
+            cpu_ids: job
+                .cpu_ids
+                .as_ref()
+                .map_or_else(Vec::new, |ids| ids.to_vec()),
+            node: job.node.clone(),
             user: job.submitted_by.to_string(),
             project: job.project.as_ref().map(|s| s.to_string()),
+            tags: job.tags.iter().map(str::to_string).collect(),
             submitted_at: job.submitted_at.and_then(|t| {
                 chrono::DateTime::<chrono::Utc>::from(t)
                     .to_rfc3339_opts(chrono::SecondsFormat::Secs, true)
@@ -0,0 +1,99 @@
+//! `gqueue --report`: what the selected jobs say about their requests, rather
+//! than the jobs themselves.
+
+use gflow::core::job::Job;
+use gflow::utils::format_memory;
+use tabled::{builder::Builder, settings::style::Style};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ReportKind {
+    /// Jobs whose peak memory stayed under half of what they requested
+    Rightsizing,
+}
+
+/// Jobs that peaked under this share of their request (in percent) asked
+/// for too much.
+const RIGHTSIZING_THRESHOLD: u64 = 50;
+
+pub(super) fn print_report(jobs: &[Job], kind: ReportKind) {
+    match kind {
+        ReportKind::Rightsizing => print_rightsizing(jobs),
+    }
+}
+
+fn print_rightsizing(jobs: &[Job]) {
+    let jobs: Vec<&Job> = jobs.iter().filter(|job| over_requested(job)).collect();
+    if jobs.is_empty() {
+        println!("No jobs peaked under {RIGHTSIZING_THRESHOLD}% of the memory they requested.");
+        return;
+    }
+
+    let mut builder = Builder::default();
+    builder.push_record([
+        "JOBID",
+        "NAME",
+        "USER",
+        "MEMORY",
+        "PEAK",
+        "USED",
+        "GPU_MEMORY",
+        "GPU_PEAK",
+        "GPU_USED",
+    ]);
+    for job in jobs {
+        builder.push_record([
+            job.id.to_string(),
+            job.run_name.as_deref().unwrap_or("-").to_string(),
+            super::display::format_user(job).to_string(),
+            memory_cell(job.memory_limit_mb),
+            memory_cell(job.peak_rss_mb),
+            percent_cell(job.memory_utilization()),
+            memory_cell(job.requested_gpu_memory_mb()),
+            memory_cell(job.peak_gpu_mb),
+            percent_cell(job.gpu_memory_utilization()),
+        ]);
+    }
+    println!("{}", builder.build().with(Style::blank()));
+}
+
+/// Whether the job's host or GPU memory peaked well under its request.
+fn over_requested(job: &Job) -> bool {
+    let under = |percent: Option<u64>| percent.is_some_and(|p| p < RIGHTSIZING_THRESHOLD);
+    under(job.memory_utilization()) || under(job.gpu_memory_utilization())
+}
+
+fn memory_cell(memory_mb: Option<u64>) -> String {
+    memory_mb.map_or_else(|| "-".to_string(), format_memory)
+}
+
+fn percent_cell(percent: Option<u64>) -> String {
+    percent.map_or_else(|| "-".to_string(), |p| format!("{p}%"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flags_jobs_that_peaked_under_half_their_request() {
+        let job = |memory: Option<u64>, peak: Option<u64>| {
+            let mut job = Job::builder()
+                .command("python train.py")
+                .memory_limit_mb(memory)
+                .build();
+            job.peak_rss_mb = peak;
+            job
+        };
+        assert!(over_requested(&job(Some(8192), Some(2048))));
+        assert!(!over_requested(&job(Some(8192), Some(4096))));
+        // Nothing requested, or nothing measured
+        assert!(!over_requested(&job(None, Some(2048))));
+        assert!(!over_requested(&job(Some(8192), None)));
+
+        let mut gpu_job = job(None, None);
+        gpu_job.gpus = 1;
+        gpu_job.gpu_memory_limit_mb = Some(20480);
+        gpu_job.peak_gpu_mb = Some(4096);
+        assert!(over_requested(&gpu_job));
+    }
+}
@@ -0,0 +1,112 @@
+//! The queue at a glance: the line above the table and `--summary-only`.
+
+use super::output::OutputFormat;
+use anyhow::Result;
+use gflow::core::info::SchedulerStats;
+use gflow::core::job::JobState;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+#[derive(Debug, Serialize)]
+struct SummaryOutput {
+    /// Queued, Hold and Running, including those with no jobs
+    jobs: BTreeMap<String, usize>,
+    total_gpus: usize,
+    available_gpus: usize,
+    running_gpus: u32,
+    queued_gpus: u32,
+    longest_wait_secs: Option<u64>,
+    oldest_queued_at: Option<String>,
+    /// `None` when some running or queued job has no expected end
+    all_clear_at: Option<String>,
+    timestamp: String,
+}
+
+fn rfc3339(unix_secs: i64) -> Option<String> {
+    chrono::DateTime::from_timestamp(unix_secs, 0)
+        .map(|at| at.to_rfc3339_opts(chrono::SecondsFormat::Secs, true))
+}
+
+impl SummaryOutput {
+    fn new(stats: &SchedulerStats, now: i64) -> Self {
+        let queue = &stats.queue;
+        Self {
+            jobs: JobState::active_states()
+                .iter()
+                .map(|&state| (state.to_string(), stats.count(state)))
+                .collect(),
+            total_gpus: stats.total_gpus,
+            available_gpus: stats.available_gpus,
+            running_gpus: queue.running_gpus,
+            queued_gpus: queue.queued_gpus,
+            longest_wait_secs: queue
+                .oldest_queued_at
+                .map(|oldest| now.saturating_sub(oldest).max(0) as u64),
+            oldest_queued_at: queue.oldest_queued_at.and_then(rfc3339),
+            all_clear_at: queue.all_clear_at.and_then(rfc3339),
+            timestamp: rfc3339(now).unwrap_or_default(),
+        }
+    }
+}
+
+/// Print the summary of `stats` alone, in `format`.
+pub(super) fn print_summary(stats: &SchedulerStats, format: OutputFormat, now: i64) -> Result<()> {
+    match format {
+        OutputFormat::Table => println!("{}", stats.queue_overview(now)),
+        OutputFormat::Json => println!(
+            "{}",
+            serde_json::to_string_pretty(&SummaryOutput::new(stats, now))?
+        ),
+        OutputFormat::Yaml => println!(
+            "{}",
+            serde_yaml::to_string(&SummaryOutput::new(stats, now))?
+        ),
+        OutputFormat::Csv => {
+            anyhow::bail!("--summary-only prints table, json or yaml, not csv")
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn summary_output_lists_every_active_state() {
+        let stats: SchedulerStats = serde_json::from_value(serde_json::json!({
+            "version": "0.0.0",
+            "pid": 1,
+            "uptime_secs": 0,
+            "jobs_by_state": { "Running": 4, "Finished": 30 },
+            "total_gpus": 8,
+            "available_gpus": 1,
+            "gpus": [],
+            "total_memory_mb": 0,
+            "available_memory_mb": 0,
+            "active_reservations": [],
+            "persistence": {
+                "mode": "state",
+                "state_writable": true,
+                "journal_writable": false
+            },
+            "queue": {
+                "running_gpus": 7,
+                "queued_gpus": 0,
+                "oldest_queued_at": 1_700_000_000,
+                "all_clear_at": null
+            }
+        }))
+        .unwrap();
+
+        let output = serde_json::to_value(SummaryOutput::new(&stats, 1_700_003_600)).unwrap();
+        assert_eq!(
+            output["jobs"],
+            serde_json::json!({ "Queued": 0, "Hold": 0, "Running": 4 })
+        );
+        assert_eq!(output["running_gpus"], 7);
+        assert_eq!(output["longest_wait_secs"], 3600);
+        assert_eq!(output["oldest_queued_at"], "2023-11-14T22:13:20Z");
+        assert!(output["all_clear_at"].is_null());
+    }
+}
@@ -1,15 +1,49 @@
-use super::display::format_job_cell;
+use super::columns::{Column, Field};
+use super::display::{finish_table, format_job_cell, CellContext};
 use std::collections::{HashMap, HashSet};
-use tabled::{builder::Builder, settings::style::Style};
+use tabled::builder::Builder;
 
-const TREE_BRANCH: &str = "├─";
-const TREE_EDGE: &str = "╰─";
-const TREE_PIPE: &str = "│ ";
 const TREE_EMPTY: &str = "  ";
 
-// Tree rendering constants - dashed lines for redo relationships
-const TREE_BRANCH_DASHED: &str = "├┄";
-const TREE_EDGE_DASHED: &str = "╰┄";
+/// Line pieces the tree is drawn with; dashed ones mark redo relationships.
+struct TreeGlyphs {
+    branch: &'static str,
+    edge: &'static str,
+    pipe: &'static str,
+    branch_dashed: &'static str,
+    edge_dashed: &'static str,
+    arrow: &'static str,
+}
+
+const UNICODE_GLYPHS: TreeGlyphs = TreeGlyphs {
+    branch: "├─",
+    edge: "╰─",
+    pipe: "│ ",
+    branch_dashed: "├┄",
+    edge_dashed: "╰┄",
+    arrow: "→",
+};
+
+/// For terminals without UTF-8
+const ASCII_GLYPHS: TreeGlyphs = TreeGlyphs {
+    branch: "|-",
+    edge: "`-",
+    pipe: "| ",
+    branch_dashed: "|.",
+    edge_dashed: "`.",
+    arrow: "->",
+};
+
+impl TreeGlyphs {
+    fn connector(&self, relationship: RelationshipKind, is_last: bool) -> &'static str {
+        match (relationship, is_last) {
+            (RelationshipKind::Dependency, true) => self.edge,
+            (RelationshipKind::Dependency, false) => self.branch,
+            (RelationshipKind::Redo, true) => self.edge_dashed,
+            (RelationshipKind::Redo, false) => self.branch_dashed,
+        }
+    }
+}
 
 pub(super) struct JobNode {
     pub(super) job: gflow::core::job::Job,
@@ -30,8 +64,9 @@ pub(super) enum JobNodeChild {
 
 /// Context for rendering jobs with formatting and session information
 struct RenderContext<'a> {
-    headers: &'a [&'a str],
-    tmux_sessions: &'a HashSet<String>,
+    columns: &'a [Column],
+    cells: &'a CellContext,
+    glyphs: &'a TreeGlyphs,
 }
 
 #[derive(Clone, Copy)]
@@ -222,19 +257,14 @@ pub(super) fn build_dependency_tree(jobs: &[gflow::core::job::Job]) -> Vec<JobNo
 /// Displays jobs in a tree format showing dependency relationships
 pub(super) fn display_jobs_tree(
     jobs: &[gflow::core::job::Job],
-    format: Option<&str>,
-    tmux_sessions: &HashSet<String>,
+    columns: &[Column],
+    cells: &CellContext,
 ) {
     if jobs.is_empty() {
         println!("No jobs to display.");
         return;
     }
 
-    let format = format
-        .unwrap_or("JOBID,NAME,ST,TIME,NODES,NODELIST(REASON)")
-        .to_string();
-    let headers: Vec<&str> = format.split(',').collect();
-
     // Build dependency tree
     let tree = build_dependency_tree(jobs);
 
@@ -242,12 +272,17 @@ pub(super) fn display_jobs_tree(
     let mut builder = Builder::default();
 
     // Add header row
-    builder.push_record(headers.clone());
+    builder.push_record(columns.iter().map(|column| column.field.header()));
 
     // Create render context
     let ctx = RenderContext {
-        headers: &headers,
-        tmux_sessions,
+        columns,
+        cells,
+        glyphs: if cells.style.unicode {
+            &UNICODE_GLYPHS
+        } else {
+            &ASCII_GLYPHS
+        },
     };
 
     // Collect all tree rows
@@ -263,10 +298,7 @@ pub(super) fn display_jobs_tree(
         );
     }
 
-    let mut table = builder.build();
-    table.with(Style::blank());
-
-    println!("{}", table);
+    println!("{}", finish_table(builder, columns));
 }
 
 /// Collects job node and its children as table rows
@@ -281,34 +313,22 @@ fn collect_tree_rows(
 ) {
     let job = &node.job;
     let tree_prefix = if is_root {
-        String::new()
-    } else if relationship == RelationshipKind::Redo {
-        // Use dashed lines for redo relationships
-        if is_last {
-            TREE_EDGE_DASHED.to_string()
-        } else {
-            TREE_BRANCH_DASHED.to_string()
-        }
+        ""
     } else {
-        // Use solid lines for dependency relationships
-        if is_last {
-            TREE_EDGE.to_string()
-        } else {
-            TREE_BRANCH.to_string()
-        }
+        ctx.glyphs.connector(relationship, is_last)
     };
 
     // Build the row
     let row: Vec<String> = ctx
-        .headers
+        .columns
         .iter()
         .enumerate()
-        .map(|(idx, header)| {
-            if *header == "JOBID" && idx == 0 {
+        .map(|(idx, column)| {
+            if column.field == Field::JobId && idx == 0 {
                 // Add tree prefix to JOBID column
                 format!("{}{}{}", prefix, tree_prefix, job.id)
             } else {
-                format_job_cell(job, header, ctx.tmux_sessions)
+                format_job_cell(job, column.field, ctx.cells)
             }
         })
         .collect();
@@ -321,7 +341,7 @@ fn collect_tree_rows(
         let is_last_child = idx == child_count - 1;
 
         // Root nodes should not add any prefix to their children
-        // Non-root nodes add TREE_PIPE if not last, TREE_EMPTY if last (to maintain tree structure)
+        // Non-root nodes add a pipe if not last, TREE_EMPTY if last (to maintain tree structure)
         let child_prefix = if is_root {
             String::new()
         } else {
@@ -329,7 +349,7 @@ fn collect_tree_rows(
             if is_last {
                 format!("{}{}", prefix, TREE_EMPTY)
             } else {
-                format!("{}{}", prefix, TREE_PIPE)
+                format!("{}{}", prefix, ctx.glyphs.pipe)
             }
         };
 
@@ -347,27 +367,20 @@ fn collect_tree_rows(
             }
             JobNodeChild::Reference(job_id, child_relationship) => {
                 // Add a reference row - make it compact by using minimal spacing
-                let tree_prefix = if *child_relationship == RelationshipKind::Dependency {
-                    if is_last_child {
-                        TREE_EDGE
-                    } else {
-                        TREE_BRANCH
-                    }
-                } else if is_last_child {
-                    TREE_EDGE_DASHED
-                } else {
-                    TREE_BRANCH_DASHED
-                };
+                let tree_prefix = ctx.glyphs.connector(*child_relationship, is_last_child);
 
                 // Create a compact reference that doesn't cause large gaps
-                let reference_text = format!("{}{}→ see job {}", child_prefix, tree_prefix, job_id);
+                let reference_text = format!(
+                    "{}{}{} see job {}",
+                    child_prefix, tree_prefix, ctx.glyphs.arrow, job_id
+                );
 
                 let row: Vec<String> = ctx
-                    .headers
+                    .columns
                     .iter()
                     .enumerate()
-                    .map(|(idx, header)| {
-                        if *header == "JOBID" && idx == 0 {
+                    .map(|(idx, column)| {
+                        if column.field == Field::JobId && idx == 0 {
                             reference_text.clone()
                         } else {
                             // Use "-" for other columns to maintain table structure
@@ -1,20 +1,42 @@
 mod cli;
 mod commands;
 
+use crate::multicall::completion::{DynamicKind, DynamicOption, DynamicSpec};
 use anyhow::Result;
 use clap::{CommandFactory, Parser};
 use std::ffi::OsString;
 
+const DYNAMIC_COMPLETION: DynamicSpec = DynamicSpec {
+    positional: None,
+    subcommands: &[],
+    options: &[
+        DynamicOption {
+            long: "jobs",
+            short: Some('j'),
+            kind: DynamicKind::Recent,
+        },
+        DynamicOption {
+            long: "names",
+            short: Some('N'),
+            kind: DynamicKind::Names,
+        },
+    ],
+};
+
 pub async fn run(argv: Vec<OsString>) -> Result<()> {
     let args = cli::GQueue::parse_from(argv);
+    crate::multicall::select_remote(args.remote.as_deref());
+    crate::multicall::skip_version_check(args.skip_version_check);
+    gflow::utils::output::set_color_choice(args.color);
 
     if let Some(command) = args.command {
         match command {
             cli::Commands::Completion { shell } => {
-                crate::multicall::completion::handle_completion(
+                crate::multicall::completion::handle_dynamic_completion(
                     shell,
                     cli::GQueue::command(),
                     "gqueue",
+                    &DYNAMIC_COMPLETION,
                 )?;
                 return Ok(());
             }
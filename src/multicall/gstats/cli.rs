@@ -35,9 +35,47 @@ pub struct GStats {
 
 #[derive(Debug, Parser)]
 pub enum Commands {
+    /// Account GPU-hours, job counts, and success rates per user, project, or day
+    Report(ReportArgs),
+
     /// Generate shell completion scripts
     Completion {
         #[arg(value_enum)]
         shell: Shell,
     },
 }
+
+#[derive(Debug, clap::Args)]
+pub struct ReportArgs {
+    /// Group rows by user, project, or submission day (UTC)
+    #[arg(
+        long,
+        default_value = "user",
+        value_parser = clap::builder::PossibleValuesParser::new(["user", "project", "day"])
+    )]
+    pub by: String,
+
+    /// Only jobs submitted since (e.g. '7d', '30d', 'today', or a Unix timestamp)
+    #[arg(long, short = 't', value_hint = clap::ValueHint::Other)]
+    pub since: Option<String>,
+
+    /// Only jobs submitted before (same formats as --since)
+    #[arg(long, value_hint = clap::ValueHint::Other)]
+    pub until: Option<String>,
+
+    /// Report on the previous calendar week (Monday to Monday)
+    #[arg(long, conflicts_with_all = ["since", "until", "last_month"])]
+    pub last_week: bool,
+
+    /// Report on the previous calendar month
+    #[arg(long, conflicts_with_all = ["since", "until"])]
+    pub last_month: bool,
+
+    /// Only jobs of this user (default: all users)
+    #[arg(long, short = 'u', value_hint = clap::ValueHint::Other)]
+    pub user: Option<String>,
+
+    /// Print the report as JSON
+    #[arg(long)]
+    pub json: bool,
+}
@@ -0,0 +1,159 @@
+use anyhow::{Context, Result};
+use chrono::{Datelike, Days, Local, Months, NaiveDate, TimeZone};
+use gflow::core::report::{ReportGroupBy, ReportQuery, ReportRow, UsageReport};
+use gflow::utils::parse_since_time;
+use std::time::Duration;
+use tabled::{builder::Builder, settings::style::Style};
+
+use super::super::cli::ReportArgs;
+
+pub async fn handle_report(
+    config_path: &Option<std::path::PathBuf>,
+    args: &ReportArgs,
+) -> Result<()> {
+    let today = Local::now().date_naive();
+    let (since, until) = if args.last_week {
+        let (start, end) = previous_week(today);
+        (Some(local_midnight(start)?), Some(local_midnight(end)?))
+    } else if args.last_month {
+        let (start, end) = previous_month(today);
+        (Some(local_midnight(start)?), Some(local_midnight(end)?))
+    } else {
+        (
+            args.since.as_deref().map(parse_since_time).transpose()?,
+            args.until.as_deref().map(parse_since_time).transpose()?,
+        )
+    };
+
+    let query = ReportQuery {
+        group_by: args
+            .by
+            .parse()
+            .map_err(|_| anyhow::anyhow!("Invalid --by value '{}'", args.by))?,
+        since,
+        until,
+        user: args.user.clone(),
+    };
+
+    let client = gflow::create_client(config_path)?;
+    let report = client.get_report(&query).await?;
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        print_table(&report);
+    }
+    Ok(())
+}
+
+/// Monday to Monday of the week before the one containing `today`.
+fn previous_week(today: NaiveDate) -> (NaiveDate, NaiveDate) {
+    let this_monday = today - Days::new(today.weekday().num_days_from_monday().into());
+    (this_monday - Days::new(7), this_monday)
+}
+
+/// First day of the month before the one containing `today`, and of that month.
+fn previous_month(today: NaiveDate) -> (NaiveDate, NaiveDate) {
+    let this_month = today.with_day(1).expect("every month has a first day");
+    (this_month - Months::new(1), this_month)
+}
+
+fn local_midnight(date: NaiveDate) -> Result<i64> {
+    let midnight = date.and_hms_opt(0, 0, 0).expect("midnight is a valid time");
+    Local
+        .from_local_datetime(&midnight)
+        .earliest()
+        .map(|dt| dt.timestamp())
+        .with_context(|| format!("Midnight of {date} does not exist in the local time zone"))
+}
+
+fn format_ts(ts: i64) -> String {
+    Local
+        .timestamp_opt(ts, 0)
+        .single()
+        .map(|dt| dt.format("%Y-%m-%d %H:%M").to_string())
+        .unwrap_or_else(|| ts.to_string())
+}
+
+fn print_table(report: &UsageReport) {
+    let window = match (report.since, report.until) {
+        (Some(since), Some(until)) => format!("{} to {}", format_ts(since), format_ts(until)),
+        (Some(since), None) => format!("since {}", format_ts(since)),
+        (None, Some(until)) => format!("before {}", format_ts(until)),
+        (None, None) => "all time".to_string(),
+    };
+    println!("Usage by {}, {}", report.group_by, window);
+
+    if report.rows.is_empty() {
+        println!("No jobs found.");
+        return;
+    }
+
+    let key_header = match report.group_by {
+        ReportGroupBy::User => "USER",
+        ReportGroupBy::Project => "PROJECT",
+        ReportGroupBy::Day => "DAY",
+    };
+    let mut builder = Builder::default();
+    builder.push_record([
+        key_header,
+        "JOBS",
+        "DONE",
+        "FAILED",
+        "CANCELLED",
+        "SUCCESS",
+        "GPU-HOURS",
+        "AVG WAIT",
+    ]);
+    for row in report.rows.iter().chain([&report.total]) {
+        builder.push_record(row_cells(row));
+    }
+    println!("{}", builder.build().with(Style::blank()));
+}
+
+fn row_cells(row: &ReportRow) -> [String; 8] {
+    [
+        row.key.clone(),
+        row.jobs.to_string(),
+        row.completed.to_string(),
+        row.failed.to_string(),
+        row.cancelled.to_string(),
+        row.success_rate
+            .map_or("-".to_string(), |rate| format!("{rate:.1}%")),
+        format!("{:.1}", row.gpu_hours),
+        row.avg_wait_secs.map_or("-".to_string(), |secs| {
+            gflow::utils::format_duration(Duration::from_secs_f64(secs))
+        }),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn presets_cover_the_previous_calendar_period() {
+        // A Wednesday.
+        assert_eq!(
+            previous_week(date(2026, 10, 14)),
+            (date(2026, 10, 5), date(2026, 10, 12))
+        );
+        // Mondays belong to the week they start.
+        assert_eq!(
+            previous_week(date(2026, 10, 12)),
+            (date(2026, 10, 5), date(2026, 10, 12))
+        );
+        assert_eq!(
+            previous_month(date(2026, 3, 31)),
+            (date(2026, 2, 1), date(2026, 3, 1))
+        );
+        assert_eq!(
+            previous_month(date(2026, 1, 15)),
+            (date(2025, 12, 1), date(2026, 1, 1))
+        );
+    }
+}
@@ -1,8 +1,9 @@
 use anyhow::Result;
 use chrono::TimeZone;
 use gflow::client::UsageStats;
+use gflow::utils::output::OutputStyle;
 use gflow::utils::parse_since_time;
-use owo_colors::OwoColorize;
+use owo_colors::Style;
 use std::time::Duration;
 
 pub async fn handle_stats(
@@ -32,7 +33,7 @@ pub async fn handle_stats(
     match output {
         "json" => print_json(&stats)?,
         "csv" => print_csv(&stats),
-        _ => print_table(&stats),
+        _ => print_table(&stats, &OutputStyle::stdout()),
     }
 
     Ok(())
@@ -71,42 +72,66 @@ fn print_csv(stats: &UsageStats) {
     println!("success_rate,{:.1}", stats.success_rate);
 }
 
-fn print_table(stats: &UsageStats) {
+fn print_table(stats: &UsageStats, style: &OutputStyle) {
     let terminal_jobs =
         stats.completed_jobs + stats.failed_jobs + stats.cancelled_jobs + stats.timeout_jobs;
     let active_jobs = stats.running_jobs + stats.queued_jobs;
 
-    print_header(stats, active_jobs);
+    print_header(stats, active_jobs, style);
     println!();
 
-    print_section("Job Status");
-    print_kv("Total Jobs", stats.total_jobs.to_string().bold());
+    print_section("Job Status", style);
+    print_kv(
+        "Total Jobs",
+        style.paint(stats.total_jobs, Style::new().bold()),
+        style,
+    );
     if terminal_jobs > 0 {
         print_job_stat(
             "Completed",
             stats.completed_jobs,
             terminal_jobs,
             BarTone::Good,
+            style,
+        );
+        print_job_stat(
+            "Failed",
+            stats.failed_jobs,
+            terminal_jobs,
+            BarTone::Bad,
+            style,
         );
-        print_job_stat("Failed", stats.failed_jobs, terminal_jobs, BarTone::Bad);
         print_job_stat(
             "Cancelled",
             stats.cancelled_jobs,
             terminal_jobs,
             BarTone::Warn,
+            style,
         );
         if stats.timeout_jobs > 0 {
-            print_job_stat("Timeout", stats.timeout_jobs, terminal_jobs, BarTone::Bad);
+            print_job_stat(
+                "Timeout",
+                stats.timeout_jobs,
+                terminal_jobs,
+                BarTone::Bad,
+                style,
+            );
         }
     } else {
         print_kv(
             "Completed",
-            style_value(stats.completed_jobs, BarTone::Good),
+            style_value(stats.completed_jobs, BarTone::Good, style),
+            style,
+        );
+        print_kv(
+            "Failed",
+            style_value(stats.failed_jobs, BarTone::Bad, style),
+            style,
         );
-        print_kv("Failed", style_value(stats.failed_jobs, BarTone::Bad));
         print_kv(
             "Cancelled",
-            style_value(stats.cancelled_jobs, BarTone::Warn),
+            style_value(stats.cancelled_jobs, BarTone::Warn, style),
+            style,
         );
     }
     if active_jobs > 0 {
@@ -115,34 +140,43 @@ fn print_table(stats: &UsageStats) {
             stats.running_jobs,
             stats.total_jobs.max(1),
             BarTone::Info,
+            style,
         );
         print_job_stat(
             "Queued",
             stats.queued_jobs,
             stats.total_jobs.max(1),
             BarTone::Warn,
+            style,
         );
     }
 
     println!();
-    print_section("Efficiency");
+    print_section("Efficiency", style);
     print_kv(
         "Avg Wait Time",
         stats.avg_wait_secs.map_or("-".to_string(), format_secs),
+        style,
     );
     print_kv(
         "Avg Runtime",
         stats.avg_runtime_secs.map_or("-".to_string(), format_secs),
+        style,
+    );
+    print_kv(
+        "Total GPU-Hours",
+        format!("{:.1}h", stats.total_gpu_hours),
+        style,
     );
-    print_kv("Total GPU-Hours", format!("{:.1}h", stats.total_gpu_hours));
     let success_tone = tone_for_success_rate(stats.success_rate);
     print_kv(
         "Success Rate",
-        style_value(format!("{:.1}%", stats.success_rate), success_tone),
+        style_value(format!("{:.1}%", stats.success_rate), success_tone, style),
+        style,
     );
 
     println!();
-    print_section("GPU Usage");
+    print_section("GPU Usage", style);
     let gpu_pct = if stats.total_jobs > 0 {
         stats.jobs_with_gpus as f64 / stats.total_jobs as f64 * 100.0
     } else {
@@ -152,35 +186,48 @@ fn print_table(stats: &UsageStats) {
         "Jobs with GPUs",
         format!(
             "{} ({:.0}%)",
-            style_value(stats.jobs_with_gpus, BarTone::Info),
+            style_value(stats.jobs_with_gpus, BarTone::Info, style),
             gpu_pct
         ),
+        style,
+    );
+    print_kv(
+        "Avg GPUs/Job",
+        format!("{:.1}", stats.avg_gpus_per_job),
+        style,
     );
-    print_kv("Avg GPUs/Job", format!("{:.1}", stats.avg_gpus_per_job));
-    print_kv("Peak GPU Usage", stats.peak_gpu_usage);
+    print_kv("Peak GPU Usage", stats.peak_gpu_usage, style);
 
     if !stats.top_jobs.is_empty() {
         println!();
-        print_section("Top Jobs by Runtime");
-        print_top_jobs(stats);
+        print_section("Top Jobs by Runtime", style);
+        print_top_jobs(stats, style);
     }
 }
 
-fn print_section(title: &str) {
-    println!("{}", title.bold());
+fn print_section(title: &str, style: &OutputStyle) {
+    println!("{}", style.paint(title, Style::new().bold()));
 }
 
-fn print_kv(label: &str, value: impl std::fmt::Display) {
-    println!("  {:<16} {}", format!("{label}:").dimmed(), value);
+fn print_kv(label: &str, value: impl std::fmt::Display, style: &OutputStyle) {
+    println!(
+        "  {:<16} {}",
+        style.paint(format!("{label}:"), Style::new().dimmed()),
+        value
+    );
 }
 
-fn print_job_stat(label: &str, count: usize, total: usize, tone: BarTone) {
+fn print_job_stat(label: &str, count: usize, total: usize, tone: BarTone, style: &OutputStyle) {
     let pct = if total == 0 {
         0.0
     } else {
         count as f64 / total as f64 * 100.0
     };
-    print_kv(label, format!("{} ({:.0}%)", style_value(count, tone), pct));
+    print_kv(
+        label,
+        format!("{} ({:.0}%)", style_value(count, tone, style), pct),
+        style,
+    );
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -201,7 +248,7 @@ fn tone_for_success_rate(rate: f64) -> BarTone {
     }
 }
 
-fn print_header(stats: &UsageStats, active_jobs: usize) {
+fn print_header(stats: &UsageStats, active_jobs: usize, style: &OutputStyle) {
     let user_label = stats.user.as_deref().unwrap_or("all users");
 
     let window = stats
@@ -209,25 +256,28 @@ fn print_header(stats: &UsageStats, active_jobs: usize) {
         .map(format_since)
         .unwrap_or_else(|| "all time".to_string());
 
+    let dimmed = Style::new().dimmed();
     println!(
         "{}  {}  {}  {}",
-        "Usage Statistics".bold(),
-        format!("user: {user_label}").dimmed(),
-        format!("since: {window}").dimmed(),
-        format!("active: {active_jobs}").dimmed(),
+        style.paint("Usage Statistics", Style::new().bold()),
+        style.paint(format!("user: {user_label}"), dimmed),
+        style.paint(format!("since: {window}"), dimmed),
+        style.paint(format!("active: {active_jobs}"), dimmed),
     );
-    println!("{}", "─".repeat(72).dimmed());
+    println!("{}", style.paint(style.rule(72), dimmed));
 }
 
-fn style_value(value: impl std::fmt::Display, tone: BarTone) -> String {
-    match tone {
-        BarTone::Good | BarTone::Warn | BarTone::Bad | BarTone::Info => {
-            value.to_string().bold().to_string()
-        }
-    }
+fn style_value(value: impl std::fmt::Display, tone: BarTone, style: &OutputStyle) -> String {
+    let color = match tone {
+        BarTone::Good => Style::new().green(),
+        BarTone::Warn => Style::new().yellow(),
+        BarTone::Bad => Style::new().red(),
+        BarTone::Info => Style::new().cyan(),
+    };
+    style.paint(value, color.bold())
 }
 
-fn print_top_jobs(stats: &UsageStats) {
+fn print_top_jobs(stats: &UsageStats, style: &OutputStyle) {
     let runtime_width = stats
         .top_jobs
         .iter()
@@ -244,29 +294,32 @@ fn print_top_jobs(stats: &UsageStats) {
         .max(4);
     let table_width = 50 + runtime_width + gpu_width;
 
+    let bold = Style::new().bold();
     println!(
         "  {} {}  {}  {}  {}",
-        format!("{:<3}", "#").bold(),
-        format!("{:>6}", "JOBID").bold(),
-        format!("{:<34}", "NAME").bold(),
-        format!("{:>runtime_width$}", "RUNTIME").bold(),
-        format!("{:>gpu_width$}", "GPUS").bold(),
+        style.paint(format!("{:<3}", "#"), bold),
+        style.paint(format!("{:>6}", "JOBID"), bold),
+        style.paint(format!("{:<34}", "NAME"), bold),
+        style.paint(format!("{:>runtime_width$}", "RUNTIME"), bold),
+        style.paint(format!("{:>gpu_width$}", "GPUS"), bold),
+    );
+    println!(
+        "  {}",
+        style.paint(style.rule(table_width), Style::new().dimmed())
     );
-    println!("  {}", "─".repeat(table_width).dimmed());
 
     for (index, job) in stats.top_jobs.iter().enumerate() {
-        let rank = format!("{:<3}", format!("{}.", index + 1))
-            .bold()
-            .to_string();
-        let job_id = format!("{:>6}", job.id).bold().to_string();
+        let rank = style.paint(format!("{:<3}", format!("{}.", index + 1)), bold);
+        let job_id = style.paint(format!("{:>6}", job.id), bold);
         let name = format!(
             "{:<34}",
             truncate_for_cell(job.name.as_deref().unwrap_or("<unnamed>"), 34)
         );
-        let runtime = format!("{:>runtime_width$}", format_secs(job.runtime_secs))
-            .bold()
-            .to_string();
-        let gpus = format!("{:>gpu_width$}", job.gpus).bold().to_string();
+        let runtime = style.paint(
+            format!("{:>runtime_width$}", format_secs(job.runtime_secs)),
+            bold,
+        );
+        let gpus = style.paint(format!("{:>gpu_width$}", job.gpus), bold);
 
         println!("  {} {}  {}  {}  {}", rank, job_id, name, runtime, gpus);
     }
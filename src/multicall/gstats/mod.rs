@@ -10,6 +10,10 @@ pub async fn run(argv: Vec<OsString>) -> Result<()> {
 
     if let Some(command) = args.command {
         match command {
+            cli::Commands::Report(report_args) => {
+                commands::report::handle_report(&args.config, &report_args).await?;
+                return Ok(());
+            }
             cli::Commands::Completion { shell } => {
                 crate::multicall::completion::handle_completion(
                     shell,
@@ -0,0 +1,473 @@
+use anyhow::Result;
+use gflow::client::Client;
+use gflow::core::info::SchedulerStats;
+use gflow::core::job::{Job, JobState};
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::widgets::TableState;
+use std::collections::{HashMap, VecDeque};
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+/// Maximum number of entries kept in the recent events pane.
+const MAX_EVENTS: usize = 100;
+/// Number of trailing log lines shown in the log pane.
+const LOG_TAIL_LINES: usize = 200;
+/// How long to wait for a key press before re-checking the refresh deadline.
+const INPUT_POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Mode {
+    Normal,
+    Filter,
+    ConfirmCancel(u32),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Action {
+    None,
+    Quit,
+    Cancel(u32),
+    Hold(u32),
+    Release(u32),
+    ToggleLog(u32),
+}
+
+pub(super) struct LogPane {
+    pub(super) job_id: u32,
+    pub(super) path: Option<PathBuf>,
+    pub(super) lines: Vec<String>,
+}
+
+pub(super) struct App {
+    pub(super) jobs: Vec<Job>,
+    pub(super) stats: Option<SchedulerStats>,
+    /// Last refresh error; the previous snapshot stays on screen while this is set.
+    pub(super) error: Option<String>,
+    /// Transient feedback from the last user action.
+    pub(super) status: Option<String>,
+    pub(super) table: TableState,
+    pub(super) filter: String,
+    pub(super) mode: Mode,
+    pub(super) log: Option<LogPane>,
+    pub(super) events: VecDeque<String>,
+    last_states: HashMap<u32, JobState>,
+}
+
+impl App {
+    pub(super) fn new() -> Self {
+        Self {
+            jobs: Vec::new(),
+            stats: None,
+            error: None,
+            status: None,
+            table: TableState::default(),
+            filter: String::new(),
+            mode: Mode::Normal,
+            log: None,
+            events: VecDeque::new(),
+            last_states: HashMap::new(),
+        }
+    }
+
+    /// Jobs shown in the queue table after applying the `/` filter.
+    pub(super) fn visible_jobs(&self) -> Vec<&Job> {
+        self.jobs
+            .iter()
+            .filter(|job| matches_filter(job, &self.filter))
+            .collect()
+    }
+
+    pub(super) fn selected_job_id(&self) -> Option<u32> {
+        let jobs = self.visible_jobs();
+        self.table
+            .selected()
+            .and_then(|idx| jobs.get(idx))
+            .map(|job| job.id)
+    }
+
+    fn move_selection(&mut self, delta: isize) {
+        let len = self.visible_jobs().len();
+        if len == 0 {
+            self.table.select(None);
+            return;
+        }
+        let current = self.table.selected().unwrap_or(0) as isize;
+        let next = (current + delta).clamp(0, len as isize - 1);
+        self.table.select(Some(next as usize));
+    }
+
+    fn clamp_selection(&mut self) {
+        let len = self.visible_jobs().len();
+        match self.table.selected() {
+            _ if len == 0 => self.table.select(None),
+            Some(idx) if idx >= len => self.table.select(Some(len - 1)),
+            None => self.table.select(Some(0)),
+            _ => {}
+        }
+    }
+
+    pub(super) fn handle_key(&mut self, key: KeyEvent) -> Action {
+        if key.kind != KeyEventKind::Press {
+            return Action::None;
+        }
+        if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+            return Action::Quit;
+        }
+
+        match self.mode {
+            Mode::Filter => {
+                match key.code {
+                    KeyCode::Enter => self.mode = Mode::Normal,
+                    KeyCode::Esc => {
+                        self.filter.clear();
+                        self.mode = Mode::Normal;
+                    }
+                    KeyCode::Backspace => {
+                        self.filter.pop();
+                    }
+                    KeyCode::Char(c) => self.filter.push(c),
+                    _ => {}
+                }
+                self.clamp_selection();
+                Action::None
+            }
+            Mode::ConfirmCancel(job_id) => {
+                self.mode = Mode::Normal;
+                if matches!(key.code, KeyCode::Char('y') | KeyCode::Char('Y')) {
+                    Action::Cancel(job_id)
+                } else {
+                    self.status = Some(format!("Cancel of job {job_id} aborted"));
+                    Action::None
+                }
+            }
+            Mode::Normal => match key.code {
+                KeyCode::Char('q') | KeyCode::Esc => {
+                    if self.log.is_some() && key.code == KeyCode::Esc {
+                        self.log = None;
+                        Action::None
+                    } else {
+                        Action::Quit
+                    }
+                }
+                KeyCode::Down | KeyCode::Char('j') => {
+                    self.move_selection(1);
+                    Action::None
+                }
+                KeyCode::Up | KeyCode::Char('k') => {
+                    self.move_selection(-1);
+                    Action::None
+                }
+                KeyCode::Char('/') => {
+                    self.mode = Mode::Filter;
+                    Action::None
+                }
+                KeyCode::Char('c') => {
+                    if let Some(job_id) = self.selected_job_id() {
+                        self.mode = Mode::ConfirmCancel(job_id);
+                    }
+                    Action::None
+                }
+                KeyCode::Char('h') => self.selected_job_id().map_or(Action::None, Action::Hold),
+                KeyCode::Char('r') => self.selected_job_id().map_or(Action::None, Action::Release),
+                KeyCode::Char('l') => self
+                    .selected_job_id()
+                    .map_or(Action::None, Action::ToggleLog),
+                _ => Action::None,
+            },
+        }
+    }
+
+    /// Replace the job snapshot and record state transitions as recent events.
+    ///
+    /// `departed` carries the latest known version of jobs that dropped out of the
+    /// active set since the previous refresh (e.g. finished or cancelled).
+    pub(super) fn apply_snapshot(&mut self, jobs: Vec<Job>, departed: Vec<Job>, now: &str) {
+        let first_snapshot = self.last_states.is_empty() && self.events.is_empty();
+        let mut next_states = HashMap::with_capacity(jobs.len());
+
+        for job in jobs.iter().chain(departed.iter()) {
+            let previous = self.last_states.get(&job.id).copied();
+            if !first_snapshot {
+                if let Some(message) = describe_transition(job, previous) {
+                    self.push_event(format!("{now} {message}"));
+                }
+            }
+        }
+        for job in &jobs {
+            next_states.insert(job.id, job.state);
+        }
+
+        self.last_states = next_states;
+        self.jobs = jobs;
+        self.clamp_selection();
+    }
+
+    /// Ids of jobs that were active in the previous snapshot but are missing from `jobs`.
+    pub(super) fn departed_ids(&self, jobs: &[Job]) -> Vec<u32> {
+        let mut ids: Vec<u32> = self
+            .last_states
+            .keys()
+            .filter(|id| !jobs.iter().any(|job| job.id == **id))
+            .copied()
+            .collect();
+        ids.sort_unstable();
+        ids
+    }
+
+    pub(super) fn push_event(&mut self, message: String) {
+        if self.events.len() == MAX_EVENTS {
+            self.events.pop_back();
+        }
+        self.events.push_front(message);
+    }
+}
+
+pub(super) fn matches_filter(job: &Job, filter: &str) -> bool {
+    let filter = filter.trim();
+    if filter.is_empty() {
+        return true;
+    }
+    let needle = filter.to_lowercase();
+    job.id.to_string() == needle
+        || job.submitted_by.to_lowercase().contains(&needle)
+        || job
+            .run_name
+            .as_ref()
+            .is_some_and(|name| name.to_lowercase().contains(&needle))
+}
+
+fn describe_transition(job: &Job, previous: Option<JobState>) -> Option<String> {
+    let name = job.run_name.as_deref().unwrap_or("-");
+    match previous {
+        None => Some(format!("job {} ({name}) submitted [{}]", job.id, job.state)),
+        Some(prev) if prev != job.state => {
+            Some(format!("job {} ({name}) {} -> {}", job.id, prev, job.state))
+        }
+        Some(_) => None,
+    }
+}
+
+pub(super) async fn run(client: Client, interval: Duration) -> Result<()> {
+    let mut terminal = ratatui::init();
+    let result = event_loop(&mut terminal, &client, interval).await;
+    ratatui::restore();
+    result
+}
+
+async fn event_loop(
+    terminal: &mut ratatui::DefaultTerminal,
+    client: &Client,
+    interval: Duration,
+) -> Result<()> {
+    let mut app = App::new();
+    let mut next_refresh = Instant::now();
+
+    loop {
+        if Instant::now() >= next_refresh {
+            refresh(&mut app, client).await;
+            next_refresh = Instant::now() + interval;
+        }
+
+        terminal.draw(|frame| super::ui::render(frame, &mut app))?;
+
+        if !event::poll(INPUT_POLL_INTERVAL)? {
+            continue;
+        }
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+
+        match app.handle_key(key) {
+            Action::None => {}
+            Action::Quit => return Ok(()),
+            Action::Cancel(job_id) => {
+                app.status = Some(action_status(
+                    client.cancel_job(job_id).await,
+                    "Cancelled",
+                    job_id,
+                ));
+                next_refresh = Instant::now();
+            }
+            Action::Hold(job_id) => {
+                app.status = Some(action_status(client.hold_job(job_id).await, "Held", job_id));
+                next_refresh = Instant::now();
+            }
+            Action::Release(job_id) => {
+                app.status = Some(action_status(
+                    client.release_job(job_id).await,
+                    "Released",
+                    job_id,
+                ));
+                next_refresh = Instant::now();
+            }
+            Action::ToggleLog(job_id) => {
+                if app.log.as_ref().is_some_and(|log| log.job_id == job_id) {
+                    app.log = None;
+                } else {
+                    let path = client.get_job_log_path(job_id).await.ok().flatten();
+                    let mut pane = LogPane {
+                        job_id,
+                        path: path.map(PathBuf::from),
+                        lines: Vec::new(),
+                    };
+                    read_log_tail(&mut pane);
+                    app.log = Some(pane);
+                }
+            }
+        }
+    }
+}
+
+fn action_status(result: Result<()>, verb: &str, job_id: u32) -> String {
+    match result {
+        Ok(()) => format!("{verb} job {job_id}"),
+        Err(e) => format!("Job {job_id}: {e}"),
+    }
+}
+
+/// Fetch one snapshot from the daemon. Errors keep the previous data on screen.
+async fn refresh(app: &mut App, client: &Client) {
+    let fetched = tokio::try_join!(
+        client.get_scheduler_stats(),
+        client.list_jobs_with_query(
+            Some("Queued,Hold,Running".to_string()),
+            None,
+            None,
+            None,
+            None,
+            None,
+        )
+    );
+
+    match fetched {
+        Ok((stats, jobs)) => {
+            let mut departed = Vec::new();
+            for job_id in app.departed_ids(&jobs) {
+                if let Ok(Some(job)) = client.get_job(job_id).await {
+                    departed.push(job);
+                }
+            }
+            let now = chrono::Local::now().format("%H:%M:%S").to_string();
+            app.apply_snapshot(jobs, departed, &now);
+            app.stats = Some(stats);
+            app.error = None;
+        }
+        Err(e) => {
+            app.error = Some(e.to_string().lines().next().unwrap_or_default().to_string());
+        }
+    }
+
+    if let Some(pane) = app.log.as_mut() {
+        read_log_tail(pane);
+    }
+}
+
+fn read_log_tail(pane: &mut LogPane) {
+    let Some(path) = &pane.path else {
+        pane.lines = vec![format!("Log for job {} is not available.", pane.job_id)];
+        return;
+    };
+    pane.lines = match std::fs::read(path) {
+        Ok(bytes) => {
+            let text = String::from_utf8_lossy(&bytes);
+            let lines: Vec<&str> = text.lines().collect();
+            let start = lines.len().saturating_sub(LOG_TAIL_LINES);
+            lines[start..].iter().map(|l| l.to_string()).collect()
+        }
+        Err(e) => vec![format!("Failed to read {}: {e}", path.display())],
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gflow::core::job::JobBuilder;
+
+    fn job(id: u32, user: &str, name: &str, state: JobState) -> Job {
+        let mut job = JobBuilder::new()
+            .submitted_by(user)
+            .run_name(Some(name.to_string()))
+            .build();
+        job.id = id;
+        job.state = state;
+        job
+    }
+
+    fn press(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    #[test]
+    fn filter_matches_id_user_and_name() {
+        let j = job(12, "alice", "train-resnet", JobState::Queued);
+        assert!(matches_filter(&j, ""));
+        assert!(matches_filter(&j, "12"));
+        assert!(matches_filter(&j, "ALI"));
+        assert!(matches_filter(&j, "resnet"));
+        assert!(!matches_filter(&j, "1"));
+        assert!(!matches_filter(&j, "bob"));
+    }
+
+    #[test]
+    fn snapshot_records_transitions_after_first_refresh() {
+        let mut app = App::new();
+        app.apply_snapshot(vec![job(1, "a", "x", JobState::Queued)], vec![], "00:00:00");
+        assert!(app.events.is_empty());
+
+        let jobs = vec![
+            job(1, "a", "x", JobState::Running),
+            job(2, "a", "y", JobState::Queued),
+        ];
+        app.apply_snapshot(jobs, vec![], "00:00:02");
+        assert_eq!(app.events.len(), 2);
+        assert!(app
+            .events
+            .iter()
+            .any(|e| e.contains("job 1 (x) Queued -> Running")));
+
+        let remaining = vec![job(2, "a", "y", JobState::Queued)];
+        assert_eq!(app.departed_ids(&remaining), vec![1]);
+        app.apply_snapshot(
+            remaining,
+            vec![job(1, "a", "x", JobState::Finished)],
+            "00:00:04",
+        );
+        assert!(app.events[0].contains("Running -> Finished"));
+    }
+
+    #[test]
+    fn cancel_requires_confirmation() {
+        let mut app = App::new();
+        app.apply_snapshot(vec![job(7, "a", "x", JobState::Running)], vec![], "t");
+
+        assert_eq!(app.handle_key(press(KeyCode::Char('c'))), Action::None);
+        assert_eq!(app.mode, Mode::ConfirmCancel(7));
+        assert_eq!(app.handle_key(press(KeyCode::Char('n'))), Action::None);
+        assert_eq!(app.mode, Mode::Normal);
+
+        app.handle_key(press(KeyCode::Char('c')));
+        assert_eq!(app.handle_key(press(KeyCode::Char('y'))), Action::Cancel(7));
+    }
+
+    #[test]
+    fn filter_mode_narrows_selection() {
+        let mut app = App::new();
+        app.apply_snapshot(
+            vec![
+                job(1, "alice", "x", JobState::Queued),
+                job(2, "bob", "y", JobState::Queued),
+            ],
+            vec![],
+            "t",
+        );
+        app.handle_key(press(KeyCode::Char('/')));
+        for c in "bob".chars() {
+            app.handle_key(press(KeyCode::Char(c)));
+        }
+        app.handle_key(press(KeyCode::Enter));
+
+        assert_eq!(app.visible_jobs().len(), 1);
+        assert_eq!(app.selected_job_id(), Some(2));
+        assert_eq!(app.handle_key(press(KeyCode::Char('h'))), Action::Hold(2));
+    }
+}
@@ -0,0 +1,32 @@
+use clap::Parser;
+use clap_complete::Shell;
+
+#[derive(Debug, Parser)]
+#[command(
+    name = "gtop",
+    author,
+    version=gflow::build_info::version(),
+    about = "Interactive terminal dashboard for the gflow scheduler."
+)]
+#[command(styles=gflow::utils::STYLES)]
+pub struct GTop {
+    #[command(subcommand)]
+    pub command: Option<Commands>,
+
+    /// Refresh interval in seconds
+    #[arg(long, short = 'n', default_value_t = 2, value_parser = clap::value_parser!(u64).range(1..))]
+    pub interval: u64,
+
+    #[arg(long, global = true, help = "Path to the config file", hide = true)]
+    pub config: Option<std::path::PathBuf>,
+}
+
+#[derive(Debug, Parser)]
+pub enum Commands {
+    /// Generate shell completion scripts
+    Completion {
+        /// The shell to generate completions for
+        #[arg(value_enum)]
+        shell: Shell,
+    },
+}
@@ -0,0 +1,28 @@
+mod app;
+mod cli;
+mod ui;
+
+use anyhow::Result;
+use clap::{CommandFactory, Parser};
+use std::ffi::OsString;
+use std::time::Duration;
+
+pub async fn run(argv: Vec<OsString>) -> Result<()> {
+    let args = cli::GTop::parse_from(argv);
+
+    if let Some(command) = args.command {
+        match command {
+            cli::Commands::Completion { shell } => {
+                crate::multicall::completion::handle_completion(
+                    shell,
+                    cli::GTop::command(),
+                    "gtop",
+                )?;
+                return Ok(());
+            }
+        }
+    }
+
+    let client = gflow::create_client_or_default(&args.config)?;
+    app::run(client, Duration::from_secs(args.interval)).await
+}
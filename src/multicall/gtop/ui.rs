@@ -0,0 +1,257 @@
+use super::app::{App, Mode};
+use gflow::core::job::JobState;
+use gflow::utils::{format_duration, format_elapsed_time, format_memory};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style, Stylize};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Cell, Gauge, List, ListItem, Paragraph, Row, Table, Wrap};
+use ratatui::Frame;
+use std::collections::HashMap;
+use std::time::Duration;
+
+pub(super) fn render(frame: &mut Frame, app: &mut App) {
+    let [header, banner, resources, jobs, bottom, footer] = Layout::vertical([
+        Constraint::Length(1),
+        Constraint::Length(1),
+        Constraint::Length(4),
+        Constraint::Min(6),
+        Constraint::Percentage(30),
+        Constraint::Length(1),
+    ])
+    .areas(frame.area());
+
+    render_header(frame, app, header);
+    render_banner(frame, app, banner);
+
+    let [gpu_area, memory_area] =
+        Layout::horizontal([Constraint::Min(20), Constraint::Length(32)]).areas(resources);
+    render_gpus(frame, app, gpu_area);
+    render_memory(frame, app, memory_area);
+
+    render_jobs(frame, app, jobs);
+    if app.log.is_some() {
+        render_log(frame, app, bottom);
+    } else {
+        render_events(frame, app, bottom);
+    }
+    render_footer(frame, app, footer);
+}
+
+fn render_header(frame: &mut Frame, app: &App, area: Rect) {
+    let line = match &app.stats {
+        Some(stats) => Line::from(vec![
+            Span::styled(" gtop ", Style::new().bold().reversed()),
+            Span::raw(format!(
+                "  gflowd v{}  pid {}  up {}  {}  GPUs {}/{} free",
+                stats.version,
+                stats.pid,
+                format_duration(Duration::from_secs(stats.uptime_secs)),
+                stats.queue_summary(),
+                stats.available_gpus,
+                stats.total_gpus,
+            )),
+        ]),
+        None => Line::from(vec![
+            Span::styled(" gtop ", Style::new().bold().reversed()),
+            Span::raw("  waiting for gflowd..."),
+        ]),
+    };
+    frame.render_widget(Paragraph::new(line), area);
+}
+
+fn render_banner(frame: &mut Frame, app: &App, area: Rect) {
+    let line = if let Some(error) = &app.error {
+        let note = if app.stats.is_some() {
+            " (showing last snapshot)"
+        } else {
+            ""
+        };
+        Line::styled(
+            format!(" Daemon unreachable: {error}{note}"),
+            Style::new().fg(Color::Red),
+        )
+    } else if let Some(status) = &app.status {
+        Line::styled(format!(" {status}"), Style::new().fg(Color::Yellow))
+    } else {
+        Line::default()
+    };
+    frame.render_widget(Paragraph::new(line), area);
+}
+
+fn render_gpus(frame: &mut Frame, app: &App, area: Rect) {
+    let block = Block::bordered().title(" GPUs ");
+    let Some(stats) = &app.stats else {
+        frame.render_widget(Paragraph::new("-").block(block), area);
+        return;
+    };
+
+    let mut owners: HashMap<u32, u32> = HashMap::new();
+    for job in app.jobs.iter().filter(|j| j.state == JobState::Running) {
+        for gpu in job.gpu_ids.iter().flatten() {
+            owners.insert(*gpu, job.id);
+        }
+    }
+
+    let cells: Vec<Span> = stats
+        .gpus
+        .iter()
+        .flat_map(|gpu| {
+            let (label, style) = if gpu.available {
+                ("idle".to_string(), Style::new().black().on_green())
+            } else if let Some(job_id) = owners.get(&gpu.index) {
+                (format!("#{job_id}"), Style::new().black().on_yellow())
+            } else {
+                ("busy".to_string(), Style::new().white().on_red())
+            };
+            [
+                Span::styled(format!(" {}:{label} ", gpu.index), style),
+                Span::raw(" "),
+            ]
+        })
+        .collect();
+
+    let paragraph = if cells.is_empty() {
+        Paragraph::new("No GPUs detected")
+    } else {
+        Paragraph::new(Line::from(cells)).wrap(Wrap { trim: false })
+    };
+    frame.render_widget(paragraph.block(block), area);
+}
+
+fn render_memory(frame: &mut Frame, app: &App, area: Rect) {
+    let block = Block::bordered().title(" Memory ");
+    let (ratio, label) = match &app.stats {
+        Some(stats) if stats.total_memory_mb > 0 => {
+            let used = stats
+                .total_memory_mb
+                .saturating_sub(stats.available_memory_mb);
+            (
+                used as f64 / stats.total_memory_mb as f64,
+                format!(
+                    "{} / {}",
+                    format_memory(used),
+                    format_memory(stats.total_memory_mb)
+                ),
+            )
+        }
+        _ => (0.0, "-".to_string()),
+    };
+    let gauge = Gauge::default()
+        .block(block)
+        .gauge_style(Style::new().fg(Color::Cyan))
+        .ratio(ratio.clamp(0.0, 1.0))
+        .label(label);
+    frame.render_widget(gauge, area);
+}
+
+fn state_style(state: JobState) -> Style {
+    match state {
+        JobState::Running => Style::new().fg(Color::Green),
+        JobState::Queued => Style::new().fg(Color::Yellow),
+        JobState::Hold => Style::new().fg(Color::Magenta),
+        _ => Style::new().fg(Color::DarkGray),
+    }
+}
+
+fn render_jobs(frame: &mut Frame, app: &mut App, area: Rect) {
+    let visible = app.visible_jobs();
+    let title = if app.filter.is_empty() {
+        format!(" Jobs ({}) ", visible.len())
+    } else {
+        format!(" Jobs ({}) filter: {} ", visible.len(), app.filter)
+    };
+
+    let header = Row::new(["ID", "NAME", "USER", "ST", "GPUS", "NODES", "TIME"])
+        .style(Style::new().add_modifier(Modifier::BOLD));
+    let rows: Vec<Row> = visible
+        .iter()
+        .map(|job| {
+            let nodes = job
+                .gpu_ids
+                .as_ref()
+                .filter(|ids| !ids.is_empty())
+                .map(|ids| {
+                    ids.iter()
+                        .map(|id| id.to_string())
+                        .collect::<Vec<_>>()
+                        .join(",")
+                })
+                .unwrap_or_else(|| "-".to_string());
+            Row::new([
+                Cell::from(job.id.to_string()),
+                Cell::from(job.run_name.as_deref().unwrap_or("-").to_string()),
+                Cell::from(job.submitted_by.to_string()),
+                Cell::from(job.state.short_form()).style(state_style(job.state)),
+                Cell::from(job.gpus.to_string()),
+                Cell::from(nodes),
+                Cell::from(format_elapsed_time(job.started_at, job.finished_at)),
+            ])
+        })
+        .collect();
+
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Length(6),
+            Constraint::Min(12),
+            Constraint::Length(10),
+            Constraint::Length(3),
+            Constraint::Length(5),
+            Constraint::Length(10),
+            Constraint::Length(10),
+        ],
+    )
+    .header(header)
+    .block(Block::bordered().title(title))
+    .row_highlight_style(Style::new().reversed())
+    .highlight_symbol("> ");
+
+    frame.render_stateful_widget(table, area, &mut app.table);
+}
+
+fn render_events(frame: &mut Frame, app: &App, area: Rect) {
+    let items: Vec<ListItem> = app
+        .events
+        .iter()
+        .map(|event| ListItem::new(event.as_str()))
+        .collect();
+    frame.render_widget(
+        List::new(items).block(Block::bordered().title(" Recent events ")),
+        area,
+    );
+}
+
+fn render_log(frame: &mut Frame, app: &App, area: Rect) {
+    let Some(pane) = &app.log else {
+        return;
+    };
+    let inner_height = area.height.saturating_sub(2) as usize;
+    let start = pane.lines.len().saturating_sub(inner_height);
+    let lines: Vec<Line> = pane.lines[start..]
+        .iter()
+        .map(|l| Line::raw(l.as_str()))
+        .collect();
+    let title = format!(" Log: job {} (l/Esc to close) ", pane.job_id);
+    frame.render_widget(
+        Paragraph::new(lines).block(Block::bordered().title(title)),
+        area,
+    );
+}
+
+fn render_footer(frame: &mut Frame, app: &App, area: Rect) {
+    let line = match app.mode {
+        Mode::Normal => Line::from(
+            " q quit  j/k move  c cancel  h hold  r release  l log  / filter".dark_gray(),
+        ),
+        Mode::Filter => Line::from(vec![
+            Span::styled(" filter: ", Style::new().bold()),
+            Span::raw(app.filter.as_str()),
+            Span::raw("_  (Enter apply, Esc clear)"),
+        ]),
+        Mode::ConfirmCancel(job_id) => Line::styled(
+            format!(" Cancel job {job_id}? [y/N]"),
+            Style::new().fg(Color::Red).bold(),
+        ),
+    };
+    frame.render_widget(Paragraph::new(line), area);
+}
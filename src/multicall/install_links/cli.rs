@@ -0,0 +1,27 @@
+use clap::Parser;
+use std::path::PathBuf;
+
+#[derive(Debug, Parser)]
+#[command(
+    name = "install-links",
+    bin_name = "gflow install-links",
+    author,
+    version=gflow::build_info::version(),
+    about = "Links gbatch, gqueue and the other tools to this gflow binary, so one binary serves them all.",
+    after_help = "Where a symlink cannot be made, a hard link is made instead, and failing that the binary is copied; \
+        run it again with --force after upgrading gflow to refresh those."
+)]
+#[command(styles=gflow::utils::STYLES)]
+pub struct InstallLinksCli {
+    /// Directory for the links [default: ~/.local/bin]
+    #[arg(long, value_hint = clap::ValueHint::DirPath)]
+    pub dir: Option<PathBuf>,
+
+    /// Remove the links that point at this binary instead
+    #[arg(long)]
+    pub uninstall: bool,
+
+    /// Replace files with a tool's name that are not links to this binary
+    #[arg(long, conflicts_with = "uninstall")]
+    pub force: bool,
+}
@@ -0,0 +1,294 @@
+mod cli;
+
+use super::TOOLS;
+use anyhow::{bail, Context, Result};
+use clap::Parser;
+use std::ffi::OsString;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// How a tool's link was made, best first
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LinkKind {
+    Symlink,
+    HardLink,
+    /// A copy of the binary, which goes stale when gflow is upgraded
+    Copy,
+}
+
+/// What happened to one tool's file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Outcome {
+    Linked(LinkKind),
+    /// Already pointed at this binary
+    UpToDate,
+    /// A file that does not point at this binary, left alone
+    Kept,
+    Removed,
+}
+
+pub async fn run(argv: Vec<OsString>) -> Result<()> {
+    let args = cli::InstallLinksCli::parse_from(argv);
+    let exe = std::env::current_exe()
+        .and_then(fs::canonicalize)
+        .context("Failed to locate the gflow binary")?;
+    let dir = match args.dir {
+        Some(dir) => dir,
+        None => dirs::home_dir()
+            .context("Cannot find the home directory; pass --dir")?
+            .join(".local")
+            .join("bin"),
+    };
+
+    if args.uninstall {
+        let outcomes = uninstall(&exe, &dir)?;
+        for (path, outcome) in &outcomes {
+            match outcome {
+                Outcome::Removed => println!("Removed {}", path.display()),
+                _ => eprintln!(
+                    "Kept {}: it is not a link to {}",
+                    path.display(),
+                    exe.display()
+                ),
+            }
+        }
+        if !outcomes
+            .iter()
+            .any(|(_, outcome)| *outcome == Outcome::Removed)
+        {
+            println!("No links to {} in {}", exe.display(), dir.display());
+        }
+        return Ok(());
+    }
+
+    let outcomes = install(&exe, &dir, args.force)?;
+    for (path, outcome) in &outcomes {
+        let path = path.display();
+        match outcome {
+            Outcome::Linked(LinkKind::Symlink) => println!("Linked {path} -> {}", exe.display()),
+            Outcome::Linked(LinkKind::HardLink) => {
+                println!("Hard-linked {path} to {}", exe.display())
+            }
+            Outcome::Linked(LinkKind::Copy) => println!("Copied {} to {path}", exe.display()),
+            Outcome::UpToDate => println!("{path} is up to date"),
+            Outcome::Kept | Outcome::Removed => eprintln!(
+                "Skipped {path}: it is not a link to {}; use --force to replace it",
+                exe.display()
+            ),
+        }
+    }
+
+    if outcomes.iter().any(|(_, outcome)| {
+        matches!(
+            outcome,
+            Outcome::Linked(LinkKind::HardLink | LinkKind::Copy)
+        )
+    }) {
+        eprintln!(
+            "Warning: hard links and copies do not follow upgrades; run `gflow install-links --force` again after upgrading gflow"
+        );
+    }
+    let on_path = std::env::var_os("PATH").is_some_and(|path| {
+        std::env::split_paths(&path)
+            .any(|entry| fs::canonicalize(entry).ok() == fs::canonicalize(&dir).ok())
+    });
+    if !on_path {
+        eprintln!(
+            "Note: {} is not in PATH; add it to run the tools by name",
+            dir.display()
+        );
+    }
+
+    let skipped = outcomes
+        .iter()
+        .filter(|(_, outcome)| *outcome == Outcome::Kept)
+        .count();
+    if skipped > 0 {
+        bail!("{skipped} tool(s) were not linked");
+    }
+    Ok(())
+}
+
+fn tool_path(dir: &Path, tool: &str) -> PathBuf {
+    dir.join(format!("{tool}{}", std::env::consts::EXE_SUFFIX))
+}
+
+/// Create or refresh a link to `exe` in `dir` for each tool. A file with a
+/// tool's name is replaced only when it is a symlink to some `gflow` binary
+/// (e.g. one since moved), or with `force`.
+fn install(exe: &Path, dir: &Path, force: bool) -> Result<Vec<(PathBuf, Outcome)>> {
+    fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    TOOLS
+        .iter()
+        .map(|tool| {
+            let path = tool_path(dir, tool);
+            let outcome = install_one(exe, &path, force)
+                .with_context(|| format!("Failed to link {}", path.display()))?;
+            Ok((path, outcome))
+        })
+        .collect()
+}
+
+fn install_one(exe: &Path, path: &Path, force: bool) -> io::Result<Outcome> {
+    match fs::symlink_metadata(path) {
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+        Err(e) => return Err(e),
+        Ok(_) if points_at(path, exe) => return Ok(Outcome::UpToDate),
+        Ok(_) if force || is_symlink_to_namesake(path, exe) => fs::remove_file(path)?,
+        Ok(_) => return Ok(Outcome::Kept),
+    }
+    make_link(exe, path).map(Outcome::Linked)
+}
+
+/// Remove each tool's file in `dir` that points at `exe`; other files with
+/// a tool's name are reported as kept.
+fn uninstall(exe: &Path, dir: &Path) -> Result<Vec<(PathBuf, Outcome)>> {
+    let mut outcomes = Vec::new();
+    for tool in TOOLS {
+        let path = tool_path(dir, tool);
+        if fs::symlink_metadata(&path).is_err() {
+            continue;
+        }
+        if points_at(&path, exe) {
+            fs::remove_file(&path)
+                .with_context(|| format!("Failed to remove {}", path.display()))?;
+            outcomes.push((path, Outcome::Removed));
+        } else {
+            outcomes.push((path, Outcome::Kept));
+        }
+    }
+    Ok(outcomes)
+}
+
+/// Whether running `path` runs `exe`: a symlink to it, a hard link to it or
+/// a copy of it.
+fn points_at(path: &Path, exe: &Path) -> bool {
+    if fs::canonicalize(path).is_ok_and(|target| target == exe) {
+        return true;
+    }
+    let (Ok(file), Ok(binary)) = (fs::metadata(path), fs::metadata(exe)) else {
+        return false;
+    };
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        if file.dev() == binary.dev() && file.ino() == binary.ino() {
+            return true;
+        }
+    }
+    if !file.is_file() || file.len() != binary.len() {
+        return false;
+    }
+    match (fs::read(path), fs::read(exe)) {
+        (Ok(file), Ok(binary)) => file == binary,
+        _ => false,
+    }
+}
+
+/// A symlink to a file named like `exe`, such as one left by an install
+/// that has since moved.
+fn is_symlink_to_namesake(path: &Path, exe: &Path) -> bool {
+    fs::read_link(path).is_ok_and(|target| target.file_name() == exe.file_name())
+}
+
+/// Link `path` to `exe`, falling back to a hard link and then a copy where
+/// symlinks cannot be made, as on Windows without the privilege for them.
+fn make_link(exe: &Path, path: &Path) -> io::Result<LinkKind> {
+    let symlink_error = match symlink(exe, path) {
+        Ok(()) => return Ok(LinkKind::Symlink),
+        Err(e) => e,
+    };
+    if fs::hard_link(exe, path).is_ok() {
+        eprintln!(
+            "Warning: cannot symlink {} ({symlink_error}); made a hard link instead",
+            path.display()
+        );
+        return Ok(LinkKind::HardLink);
+    }
+    fs::copy(exe, path)?;
+    eprintln!(
+        "Warning: cannot link {} ({symlink_error}); copied the binary instead",
+        path.display()
+    );
+    Ok(LinkKind::Copy)
+}
+
+#[cfg(unix)]
+fn symlink(target: &Path, link: &Path) -> io::Result<()> {
+    std::os::unix::fs::symlink(target, link)
+}
+
+#[cfg(windows)]
+fn symlink(target: &Path, link: &Path) -> io::Result<()> {
+    std::os::windows::fs::symlink_file(target, link)
+}
+
+#[cfg(not(any(unix, windows)))]
+fn symlink(_target: &Path, _link: &Path) -> io::Result<()> {
+    Err(io::ErrorKind::Unsupported.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(unix)]
+    #[test]
+    fn links_are_refreshed_and_only_ours_are_removed() {
+        let tmp = tempfile::tempdir().unwrap();
+        let bin = tmp.path().join("bin");
+        fs::create_dir(&bin).unwrap();
+        fs::write(bin.join("gflow"), b"multicall").unwrap();
+        let exe = fs::canonicalize(bin.join("gflow")).unwrap();
+        let dir = tmp.path().join("links");
+        let outcomes_of = |outcomes: Vec<(PathBuf, Outcome)>| -> Vec<Outcome> {
+            outcomes.into_iter().map(|(_, outcome)| outcome).collect()
+        };
+
+        let outcomes = outcomes_of(install(&exe, &dir, false).unwrap());
+        assert_eq!(
+            outcomes,
+            vec![Outcome::Linked(LinkKind::Symlink); TOOLS.len()]
+        );
+        assert_eq!(fs::read_link(dir.join("gjob")).unwrap(), exe);
+        let outcomes = outcomes_of(install(&exe, &dir, false).unwrap());
+        assert_eq!(outcomes, vec![Outcome::UpToDate; TOOLS.len()]);
+
+        // A foreign file is kept unless forced; a link to a moved gflow is
+        // refreshed; a copy of the binary counts as ours.
+        fs::remove_file(dir.join("gbatch")).unwrap();
+        fs::write(dir.join("gbatch"), b"someone else's gbatch").unwrap();
+        fs::remove_file(dir.join("gjob")).unwrap();
+        std::os::unix::fs::symlink(tmp.path().join("old/gflow"), dir.join("gjob")).unwrap();
+        fs::remove_file(dir.join("gtop")).unwrap();
+        fs::copy(&exe, dir.join("gtop")).unwrap();
+        let outcomes = install(&exe, &dir, false).unwrap();
+        let outcome = |tool: &str| {
+            outcomes
+                .iter()
+                .find(|(path, _)| path.ends_with(tool))
+                .unwrap()
+                .1
+        };
+        assert_eq!(outcome("gbatch"), Outcome::Kept);
+        assert_eq!(outcome("gjob"), Outcome::Linked(LinkKind::Symlink));
+        assert_eq!(outcome("gtop"), Outcome::UpToDate);
+
+        let outcomes = outcomes_of(uninstall(&exe, &dir).unwrap());
+        assert_eq!(outcomes.len(), TOOLS.len());
+        assert_eq!(outcomes.iter().filter(|o| **o == Outcome::Kept).count(), 1);
+        assert_eq!(
+            fs::read(dir.join("gbatch")).unwrap(),
+            b"someone else's gbatch"
+        );
+        assert!(fs::symlink_metadata(dir.join("gtop")).is_err());
+        assert!(fs::symlink_metadata(dir.join("gqueue")).is_err());
+
+        let outcomes = outcomes_of(install(&exe, &dir, true).unwrap());
+        assert_eq!(
+            outcomes,
+            vec![Outcome::Linked(LinkKind::Symlink); TOOLS.len()]
+        );
+    }
+}
@@ -148,7 +148,7 @@ fn queue_group_outputs(
 
 fn job_reason_label(job: &Job) -> String {
     if let Some(reason) = job.reason.as_deref() {
-        return reason.to_string();
+        return reason.label().to_string();
     }
 
     match job.state {
@@ -11,7 +11,7 @@ use gflow::client::UpdateJobRequest;
 use gflow::core::gpu_allocation::GpuAllocationStrategy;
 use gflow::core::info::{GpuInfo, SchedulerInfo};
 use gflow::core::job::{JobBuilder, JobState, JobStateReason};
-use gflow::core::reservation::{GpuReservation, GpuSpec, ReservationStatus};
+use gflow::core::reservation::{GpuReservation, GpuSpec, ReservationKind, ReservationStatus};
 use schemars::schema_for;
 use serde_json::Value;
 use std::collections::HashMap;
@@ -216,16 +216,19 @@ fn queue_pressure_summarizes_gpu_pressure_and_groups() {
                 index: 0,
                 available: false,
                 reason: Some("running gflow job".to_string()),
+                labels: Default::default(),
             },
             GpuInfo {
                 uuid: "gpu-1".to_string(),
                 index: 1,
                 available: true,
                 reason: None,
+                labels: Default::default(),
             },
         ],
         allowed_gpu_indices: None,
         gpu_allocation_strategy: GpuAllocationStrategy::Sequential,
+        labels: Default::default(),
     };
 
     let mut running = JobBuilder::new()
@@ -265,6 +268,8 @@ fn queue_pressure_summarizes_gpu_pressure_and_groups() {
         status: ReservationStatus::Active,
         created_at: SystemTime::now() - Duration::from_secs(120),
         cancelled_at: None,
+        kind: ReservationKind::User,
+        recurrence: None,
     };
 
     let output = build_queue_pressure_output(info, vec![running, queued, held], vec![reservation]);
@@ -277,7 +282,7 @@ fn queue_pressure_summarizes_gpu_pressure_and_groups() {
     assert_eq!(output.held_jobs, 1);
     assert_eq!(output.queued_requested_gpus, 2);
     assert_eq!(output.running_allocated_gpus, 1);
-    assert_eq!(output.blocked_reasons.get("Resources"), Some(&1));
+    assert_eq!(output.blocked_reasons.get("WaitingForGPUs"), Some(&1));
     assert_eq!(output.blocked_reasons.get("JobHeldUser"), Some(&1));
     assert_eq!(output.reservations_total, 1);
     assert_eq!(output.reservations_active, 1);
@@ -96,7 +96,7 @@ fn retry_hints_for_job(job: &Job, log_excerpt: Option<&str>) -> Vec<String> {
             Some(reason) if reason.contains("Memory") => {
                 hints.push("lower memory request or wait for memory pressure to clear".to_string())
             }
-            Some(reason) if reason.contains("Gpu") || reason.contains("Resources") => hints.push(
+            Some(reason) if reason.contains("GPU") || reason.contains("Resources") => hints.push(
                 "check get_queue_pressure for GPU availability, reservations, and running jobs"
                     .to_string(),
             ),
@@ -181,7 +181,7 @@ fn apply_update_preview(mut job: Job, request: UpdateJobRequest) -> (Job, Vec<St
         updated_fields.push("gpus".to_string());
     }
     if let Some(conda_env) = request.conda_env {
-        job.conda_env = conda_env.map(CompactString::from);
+        job.set_conda_env(conda_env);
         updated_fields.push("conda_env".to_string());
     }
     if let Some(priority) = request.priority {
@@ -1,7 +1,12 @@
-use std::ffi::OsString;
+use std::ffi::{OsStr, OsString};
+use std::future::Future;
+use std::path::Path;
+use std::pin::Pin;
 
 mod completion;
 
+pub mod config;
+pub mod doctor;
 pub mod gbatch;
 pub mod gcancel;
 pub mod gctl;
@@ -10,7 +15,54 @@ pub mod ginfo;
 pub mod gjob;
 pub mod gqueue;
 pub mod gstats;
+pub mod gtop;
+pub mod install_links;
 pub mod mcp;
+pub mod remote;
+
+type Run = fn(Vec<OsString>) -> Pin<Box<dyn Future<Output = anyhow::Result<()>>>>;
+
+/// Every command the binary runs, by the name it is called as: `gflow
+/// <name>`, or `<name>` itself through a link made by `gflow install-links`.
+const COMMANDS: &[(&str, Run)] = &[
+    ("config", |argv| Box::pin(config::run(argv))),
+    ("doctor", |argv| Box::pin(doctor::run(argv))),
+    ("gbatch", |argv| Box::pin(gbatch::run(argv))),
+    ("gcancel", |argv| Box::pin(gcancel::run(argv))),
+    ("gctl", |argv| Box::pin(gctl::run(argv))),
+    ("gflowd", |argv| Box::pin(gflowd::run(argv))),
+    ("ginfo", |argv| Box::pin(ginfo::run(argv))),
+    ("gjob", |argv| Box::pin(gjob::run(argv))),
+    ("gqueue", |argv| Box::pin(gqueue::run(argv))),
+    ("gstats", |argv| Box::pin(gstats::run(argv))),
+    ("gtop", |argv| Box::pin(gtop::run(argv))),
+    ("install-links", |argv| Box::pin(install_links::run(argv))),
+    ("mcp", |argv| Box::pin(mcp::run(argv))),
+    ("remote", |argv| Box::pin(remote::run(argv))),
+];
+
+/// The commands installed as programs of their own, each also built as a
+/// `[[bin]]` wrapper.
+pub const TOOLS: &[&str] = &[
+    "gbatch", "gcancel", "gctl", "gflowd", "ginfo", "gjob", "gqueue", "gstats", "gtop",
+];
+
+fn command(name: &str) -> Option<Run> {
+    COMMANDS
+        .iter()
+        .find(|(command, _)| *command == name)
+        .map(|(_, run)| *run)
+}
+
+/// The tool a program path names, e.g. `gbatch` for `~/.local/bin/gbatch`
+/// or `gbatch.exe`, so a link to the binary runs as that tool.
+pub fn tool_for_program(program: &OsStr) -> Option<&'static str> {
+    let name = Path::new(program).file_name()?.to_str()?;
+    let name = name
+        .strip_suffix(std::env::consts::EXE_SUFFIX)
+        .unwrap_or(name);
+    TOOLS.iter().copied().find(|tool| *tool == name)
+}
 
 pub async fn dispatch(argv: Vec<OsString>) -> anyhow::Result<()> {
     let Some(program) = argv.first() else {
@@ -18,28 +70,79 @@ pub async fn dispatch(argv: Vec<OsString>) -> anyhow::Result<()> {
         return Ok(());
     };
 
-    match program.to_string_lossy().as_ref() {
-        "gbatch" => gbatch::run(argv).await,
-        "gcancel" => gcancel::run(argv).await,
-        "gctl" => gctl::run(argv).await,
-        "gflowd" => gflowd::run(argv).await,
-        "ginfo" => ginfo::run(argv).await,
-        "gjob" => gjob::run(argv).await,
-        "mcp" => mcp::run(argv).await,
-        "gqueue" => gqueue::run(argv).await,
-        "gstats" => gstats::run(argv).await,
-        _ => {
+    if argv
+        .get(1)
+        .is_some_and(|arg| arg == completion::DYNAMIC_COMPLETE_ARG)
+    {
+        return completion::run_dynamic_complete(&argv[2..]).await;
+    }
+
+    let program = program.to_string_lossy().into_owned();
+    match command(&program) {
+        Some(run) => run(argv).await,
+        None => {
             print_top_level_help();
+            let names: Vec<_> = COMMANDS.iter().map(|(name, _)| *name).collect();
             anyhow::bail!(
-                "Unknown command '{}'. Expected one of: gbatch, gcancel, gctl, gflowd, ginfo, gjob, mcp, gqueue, gstats",
-                program.to_string_lossy()
+                "Unknown command '{program}'. Expected one of: {}",
+                names.join(", ")
             );
         }
     }
 }
 
+/// Hand `--remote` on to the client through `GFLOW_REMOTE`, which
+/// [`gflow::create_client`] and nested commands read.
+pub(crate) fn select_remote(remote: Option<&str>) {
+    if let Some(remote) = remote {
+        std::env::set_var(gflow::config::REMOTE_ENV, remote);
+    }
+}
+
+/// Hand `--skip-version-check` on to the client through
+/// `GFLOW_SKIP_VERSION_CHECK`, which [`gflow::Client`] reads.
+pub(crate) fn skip_version_check(skip: bool) {
+    if skip {
+        std::env::set_var(gflow::core::version::SKIP_VERSION_CHECK_ENV, "1");
+    }
+}
+
 pub fn print_top_level_help() {
-    eprintln!(
-        "gflow (multi-call)\n\nUsage:\n  gflow __multicall <command> [args...]\n  gflow <command> [args...]\n\nCommands:\n  gbatch\n  gcancel\n  gctl\n  gflowd\n  ginfo\n  gjob\n  mcp\n  gqueue\n  gstats\n"
+    let mut help = String::from(
+        "gflow (multi-call)\n\nUsage:\n  gflow __multicall <command> [args...]\n  gflow <command> [args...]\n\nCommands:\n",
     );
+    for (name, _) in COMMANDS {
+        help.push_str("  ");
+        help.push_str(name);
+        help.push('\n');
+    }
+    eprintln!("{help}");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeSet;
+
+    #[test]
+    fn every_tool_is_dispatched_and_built() {
+        for tool in TOOLS {
+            assert!(command(tool).is_some(), "{tool} is not dispatched");
+            let program = format!("/usr/local/bin/{tool}{}", std::env::consts::EXE_SUFFIX);
+            assert_eq!(tool_for_program(OsStr::new(&program)), Some(*tool));
+        }
+        assert_eq!(tool_for_program(OsStr::new("gflow")), None);
+        assert_eq!(tool_for_program(OsStr::new("install-links")), None);
+
+        // Each `[[bin]]` wrapper but gflow itself is a tool, and the reverse.
+        let manifest: toml::Value = toml::from_str(include_str!("../../Cargo.toml")).unwrap();
+        let bins: BTreeSet<&str> = manifest["bin"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|bin| bin["name"].as_str().unwrap())
+            .filter(|name| *name != "gflow")
+            .collect();
+        assert_eq!(bins, TOOLS.iter().copied().collect());
+    }
 }
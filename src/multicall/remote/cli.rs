@@ -0,0 +1,52 @@
+use clap::Parser;
+use std::path::PathBuf;
+
+#[derive(Debug, Parser)]
+#[command(
+    name = "remote",
+    bin_name = "gflow remote",
+    author,
+    version=gflow::build_info::version(),
+    about = "Manages daemons on other machines that CLI tools reach with --remote."
+)]
+#[command(styles=gflow::utils::STYLES)]
+pub struct RemoteCli {
+    #[command(subcommand)]
+    pub command: Commands,
+
+    #[arg(long, global = true, help = "Path to the config file", hide = true)]
+    pub config: Option<PathBuf>,
+}
+
+#[derive(Debug, Parser)]
+pub enum Commands {
+    /// List configured remotes; `*` marks the one GFLOW_REMOTE selects
+    #[command(visible_alias = "ls")]
+    List,
+    /// Add a remote to the config file
+    Add {
+        /// Name used with --remote
+        name: String,
+        /// Daemon URL, e.g. http://gpu1:59000
+        host: String,
+        /// API token for this daemon
+        #[arg(long)]
+        token: Option<String>,
+        /// PEM CA certificate(s) trusted for an https:// daemon
+        #[arg(long, value_hint = clap::ValueHint::FilePath)]
+        tls_ca: Option<PathBuf>,
+        /// Connect to an https:// daemon without verifying its certificate
+        #[arg(long)]
+        insecure_skip_verify: bool,
+        /// The remote sees this machine's files at the same paths, so
+        /// gbatch passes script paths instead of uploading scripts
+        #[arg(long)]
+        shared_fs: bool,
+    },
+    /// Remove a remote from the config file
+    #[command(visible_alias = "rm")]
+    Remove {
+        /// Name of the remote
+        name: String,
+    },
+}
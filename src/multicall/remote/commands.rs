@@ -0,0 +1,85 @@
+use crate::multicall::config::write_atomically;
+use anyhow::{Context, Result};
+use gflow::config::{config_file_path, load_config, set_remote, RemoteConfig, REMOTE_ENV};
+use std::path::{Path, PathBuf};
+
+fn target_path(config_path: &Option<PathBuf>) -> Result<PathBuf> {
+    config_file_path(config_path.as_ref()).map(|(path, _)| path)
+}
+
+pub(super) fn handle_list(config_path: &Option<PathBuf>) -> Result<()> {
+    use tabled::{builder::Builder, settings::Style};
+
+    let config = load_config(config_path.as_ref())?;
+    if config.remotes.is_empty() {
+        println!("No remotes configured; add one with `gflow remote add <name> <url>`");
+        return Ok(());
+    }
+
+    let selected = std::env::var(REMOTE_ENV).ok();
+    let mut builder = Builder::default();
+    builder.push_record(["", "NAME", "HOST", "SHARED_FS", "TOKEN"]);
+    for (name, remote) in &config.remotes {
+        let marker = if selected.as_deref() == Some(name.as_str()) {
+            "*"
+        } else {
+            ""
+        };
+        builder.push_record([
+            marker.to_string(),
+            name.clone(),
+            remote.host.clone(),
+            if remote.shared_fs { "yes" } else { "no" }.to_string(),
+            if remote.token.is_some() { "set" } else { "-" }.to_string(),
+        ]);
+    }
+    println!("{}", builder.build().with(Style::empty()));
+    Ok(())
+}
+
+pub(super) fn handle_add(
+    config_path: &Option<PathBuf>,
+    name: &str,
+    remote: &RemoteConfig,
+) -> Result<()> {
+    if name.is_empty()
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-'))
+    {
+        anyhow::bail!("Invalid remote name '{name}': use letters, digits, '_' and '-'");
+    }
+    let path = target_path(config_path)?;
+    edit(&path, name, Some(remote))?;
+    println!("Added remote '{name}' to {}", path.display());
+    if !remote.shared_fs {
+        println!("  (gbatch uploads job scripts to it; pass --shared-fs if it sees this machine's files)");
+    }
+    Ok(())
+}
+
+pub(super) fn handle_remove(config_path: &Option<PathBuf>, name: &str) -> Result<()> {
+    let path = target_path(config_path)?;
+    edit(&path, name, None)?;
+    println!("Removed remote '{name}' from {}", path.display());
+    Ok(())
+}
+
+fn edit(path: &Path, name: &str, remote: Option<&RemoteConfig>) -> Result<()> {
+    let content = if path.exists() {
+        std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?
+    } else {
+        String::new()
+    };
+    let updated = match set_remote(path, &content, name, remote) {
+        Ok(updated) => updated,
+        Err(errors) => {
+            for error in &errors {
+                eprintln!("error: {error}");
+            }
+            anyhow::bail!("Refusing to write {}", path.display());
+        }
+    };
+    write_atomically(path, &updated)
+}
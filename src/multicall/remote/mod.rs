@@ -0,0 +1,32 @@
+mod cli;
+mod commands;
+
+use anyhow::Result;
+use clap::Parser;
+use std::ffi::OsString;
+
+pub async fn run(argv: Vec<OsString>) -> Result<()> {
+    let args = cli::RemoteCli::parse_from(argv);
+
+    match args.command {
+        cli::Commands::List => commands::handle_list(&args.config),
+        cli::Commands::Add {
+            name,
+            host,
+            token,
+            tls_ca,
+            insecure_skip_verify,
+            shared_fs,
+        } => {
+            let remote = gflow::config::RemoteConfig {
+                host,
+                token,
+                tls_ca,
+                insecure_skip_verify,
+                shared_fs,
+            };
+            commands::handle_add(&args.config, &name, &remote)
+        }
+        cli::Commands::Remove { name } => commands::handle_remove(&args.config, &name),
+    }
+}
@@ -19,7 +19,57 @@ pub fn get_runtime_dir() -> anyhow::Result<PathBuf> {
         .map(|p| p.join("gflow"))
 }
 
-fn get_log_dir() -> anyhow::Result<PathBuf> {
+/// PID file written by the running daemon.
+pub fn get_pid_file_path() -> anyhow::Result<PathBuf> {
+    Ok(get_runtime_dir()?.join("gflowd.pid"))
+}
+
+/// Scripts `gbatch --stdin` saved for jobs submitted from standard input.
+pub fn get_scripts_dir() -> anyhow::Result<PathBuf> {
+    Ok(get_data_dir()?.join("scripts"))
+}
+
+/// Save `content` as an executable script in the scripts directory, named
+/// `<prefix>-<timestamp>-<uuid>.sh`. The daemon keeps it for as long as a job
+/// refers to it.
+pub fn save_script(prefix: &str, content: &str) -> anyhow::Result<PathBuf> {
+    use anyhow::Context;
+
+    let scripts_dir = get_scripts_dir()?;
+    std::fs::create_dir_all(&scripts_dir).with_context(|| {
+        format!(
+            "Failed to create scripts directory {}",
+            scripts_dir.display()
+        )
+    })?;
+    let timestamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    let script_path = scripts_dir.join(format!(
+        "{prefix}-{timestamp}-{}.sh",
+        uuid::Uuid::new_v4().simple()
+    ));
+
+    std::fs::write(&script_path, content)
+        .with_context(|| format!("Failed to write script {}", script_path.display()))?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&script_path, std::fs::Permissions::from_mode(0o755))?;
+    }
+
+    Ok(script_path)
+}
+
+/// Submissions `gbatch --spool` could not deliver, one JSON file each.
+pub fn get_spool_dir() -> anyhow::Result<PathBuf> {
+    Ok(get_data_dir()?.join("spool"))
+}
+
+/// Directory job logs are written to.
+pub fn get_log_dir() -> anyhow::Result<PathBuf> {
     Ok(get_data_dir()?.join("logs"))
 }
 
@@ -63,6 +113,54 @@ pub fn prepare_log_file_path(job_id: u32) -> anyhow::Result<PathBuf> {
     Ok(log_path)
 }
 
+/// File a running job may write its progress to, exported to it as
+/// `GFLOW_PROGRESS_FILE`.
+pub fn get_progress_file_path(job_id: u32) -> anyhow::Result<PathBuf> {
+    Ok(get_runtime_dir()?.join("progress").join(job_id.to_string()))
+}
+
+/// Returns the progress file path for a job about to start, creating its
+/// directory and removing progress left from an earlier run.
+pub fn prepare_progress_file_path(job_id: u32) -> anyhow::Result<PathBuf> {
+    prepare_job_file(get_progress_file_path(job_id)?)
+}
+
+/// Directory of the files running jobs list their artifacts in, one per job
+/// and named by its ID.
+pub fn get_artifacts_dir() -> anyhow::Result<PathBuf> {
+    Ok(get_runtime_dir()?.join("artifacts"))
+}
+
+/// File a running job may append artifact paths to, exported to it as
+/// `GFLOW_ARTIFACTS_FILE`.
+pub fn get_artifacts_file_path(job_id: u32) -> anyhow::Result<PathBuf> {
+    Ok(get_artifacts_dir()?.join(job_id.to_string()))
+}
+
+/// Returns the artifacts file path for a job about to start, creating its
+/// directory and removing lines left from an earlier run.
+pub fn prepare_artifacts_file_path(job_id: u32) -> anyhow::Result<PathBuf> {
+    prepare_job_file(get_artifacts_file_path(job_id)?)
+}
+
+/// Scratch directory the daemon creates for a job when it starts, exported
+/// to it as `GFLOW_JOB_DIR`.
+pub fn get_job_dir(job_id: u32) -> anyhow::Result<PathBuf> {
+    Ok(get_data_dir()?.join("jobs").join(job_id.to_string()))
+}
+
+fn prepare_job_file(path: PathBuf) -> anyhow::Result<PathBuf> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    match std::fs::remove_file(&path) {
+        Ok(()) => {}
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+        Err(e) => return Err(e.into()),
+    }
+    Ok(path)
+}
+
 pub fn get_daemon_log_file_path() -> anyhow::Result<PathBuf> {
     Ok(get_log_dir()?.join("daemon.log"))
 }
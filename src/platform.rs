@@ -1,7 +1,38 @@
 use std::env;
+use std::ffi::CStr;
 
+/// Name of the effective user, so `sudo -u` and `su` submit as the user they
+/// switch to. Falls back to `$USER` where the uid has no passwd entry, as in
+/// some containers.
 pub fn get_current_username() -> String {
-    env::var("USER")
-        .or_else(|_| env::var("USERNAME"))
-        .unwrap_or_else(|_| "unknown".to_string())
+    let env_user = |name| env::var(name).ok().filter(|user| !user.trim().is_empty());
+    effective_username()
+        .or_else(|| env_user("USER"))
+        .or_else(|| env_user("USERNAME"))
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+/// The passwd entry's name for the effective uid.
+fn effective_username() -> Option<String> {
+    let uid = unsafe { libc::geteuid() };
+    let mut buf = vec![0 as libc::c_char; 1024];
+    loop {
+        let mut passwd: libc::passwd = unsafe { std::mem::zeroed() };
+        let mut entry = std::ptr::null_mut();
+        let rc =
+            unsafe { libc::getpwuid_r(uid, &mut passwd, buf.as_mut_ptr(), buf.len(), &mut entry) };
+        if rc == libc::ERANGE && buf.len() < 1 << 20 {
+            buf.resize(buf.len() * 4, 0);
+            continue;
+        }
+        if rc != 0 || entry.is_null() || passwd.pw_name.is_null() {
+            return None;
+        }
+        let name = unsafe { CStr::from_ptr(passwd.pw_name) };
+        return name
+            .to_str()
+            .ok()
+            .filter(|name| !name.is_empty())
+            .map(str::to_owned);
+    }
 }
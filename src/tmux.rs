@@ -1,5 +1,22 @@
+use serde::{Deserialize, Serialize};
 use std::path::Path;
-use tmux_interface::{KillSession, NewSession, PipePane, SendKeys, Tmux};
+use tmux_interface::{KillSession, KillWindow, NewSession, NewWindow, PipePane, SendKeys, Tmux};
+
+/// Session holding the jobs' windows in [`TmuxMode::WindowsInSharedSession`].
+pub const SHARED_SESSION_NAME: &str = "gflow";
+
+/// How the tmux executor lays out jobs (`[daemon] tmux_mode`).
+#[derive(
+    Deserialize, Serialize, Debug, Clone, Copy, Default, PartialEq, Eq, schemars::JsonSchema,
+)]
+#[serde(rename_all = "kebab-case")]
+pub enum TmuxMode {
+    /// A session per job, named after its run name
+    #[default]
+    SessionPerJob,
+    /// A window per job, named `<job_id>-<run_name>`, in one `gflow` session
+    WindowsInSharedSession,
+}
 
 /// A tmux session
 pub struct TmuxSession {
@@ -38,6 +55,55 @@ impl TmuxSession {
         Ok(Self { name })
     }
 
+    /// Open a window for a job in the shared session, creating the session
+    /// if it does not exist yet. The returned target names the window.
+    pub fn create_window(window: &str) -> anyhow::Result<Self> {
+        let error = |message: String| {
+            anyhow::anyhow!(
+                "Failed to create tmux window '{}' in session '{}': {}",
+                window,
+                SHARED_SESSION_NAME,
+                message
+            )
+        };
+        // Start the session with the job's window rather than an empty one.
+        // Another job may create it first, so fall back to a new window.
+        let created = !is_session_exist(SHARED_SESSION_NAME)
+            && Tmux::with_command(
+                NewSession::new()
+                    .detached()
+                    .session_name(SHARED_SESSION_NAME)
+                    .window_name(window),
+            )
+            .output()
+            .is_ok_and(|output| output.success());
+        if !created {
+            let output = Tmux::with_command(
+                NewWindow::new()
+                    .detached()
+                    .target_window(format!("={SHARED_SESSION_NAME}:"))
+                    .window_name(window),
+            )
+            .output()
+            .map_err(|e| error(e.to_string()))?;
+            if !output.success() {
+                let stderr = String::from_utf8_lossy(&output.stderr()).trim().to_string();
+                return Err(error(if stderr.is_empty() {
+                    "tmux returned a non-zero exit status".to_string()
+                } else {
+                    stderr
+                }));
+            }
+        }
+
+        // Allow the window's shell to initialize
+        std::thread::sleep(std::time::Duration::from_secs(1));
+
+        Ok(Self {
+            name: window_target(window),
+        })
+    }
+
     /// Send a command to the tmux session
     pub fn send_command(&self, command: &str) {
         self.try_send_command(command).ok();
@@ -133,6 +199,89 @@ pub fn normalize_session_name(name: &str) -> String {
     normalized.trim_matches('_').to_string()
 }
 
+/// Name of a job's window in the shared session.
+pub fn job_window_name(job_id: u32, run_name: &str) -> String {
+    format!("{job_id}-{run_name}")
+}
+
+/// Target of a window in the shared session. `=` asks tmux for exact names,
+/// so job 1's window is never taken for job 12's.
+pub fn window_target(window: &str) -> String {
+    format!("={SHARED_SESSION_NAME}:={window}")
+}
+
+/// Where a job's shell runs in tmux.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum JobTarget {
+    /// Its own session, named after the run name
+    Session(String),
+    /// A window of the shared session, as a [`window_target`]
+    Window(String),
+}
+
+impl JobTarget {
+    /// The job's session, or else its window in the shared session. Jobs keep
+    /// being found after `tmux_mode` changes.
+    pub fn find(job_id: u32, run_name: &str) -> Option<Self> {
+        if is_session_exist(run_name) {
+            return Some(Self::Session(run_name.to_string()));
+        }
+        let window = job_window_name(job_id, run_name);
+        is_window_exist(&window).then(|| Self::Window(window_target(&window)))
+    }
+
+    /// Target for `send-keys`, `pipe-pane` and `attach-session`.
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Session(name) | Self::Window(name) => name,
+        }
+    }
+
+    /// PID of the shell in the session or window's pane.
+    pub fn pane_pid(&self) -> Option<u32> {
+        let output = Tmux::with_command(
+            tmux_interface::DisplayMessage::new()
+                .target_pane(self.as_str())
+                .print()
+                .message("#{pane_pid}"),
+        )
+        .output()
+        .ok()?;
+        if !output.success() {
+            return None;
+        }
+        String::from_utf8_lossy(&output.stdout())
+            .trim()
+            .parse()
+            .ok()
+    }
+
+    /// Process group of the command in the foreground of the pane, which is
+    /// what a signal for the job should reach rather than the shell typed
+    /// into. `None` when the shell itself is in the foreground.
+    pub fn foreground_process_group(&self) -> Option<u32> {
+        let shell = self.pane_pid()?;
+        let stat = std::fs::read_to_string(format!("/proc/{shell}/stat")).ok()?;
+        terminal_process_group(&stat).filter(|&group| group != shell)
+    }
+
+    /// Close the session or window, stopping pipe-pane first.
+    pub fn kill(&self) -> anyhow::Result<()> {
+        match self {
+            Self::Session(name) => kill_session(name),
+            Self::Window(target) => kill_window(target),
+        }
+    }
+}
+
+/// The foreground process group of the terminal (`tpgid`) from a
+/// /proc/<pid>/stat line, whose command name may contain parentheses.
+fn terminal_process_group(stat: &str) -> Option<u32> {
+    let (_, fields) = stat.rsplit_once(')')?;
+    let group: i32 = fields.split_whitespace().nth(5)?.parse().ok()?;
+    u32::try_from(group).ok().filter(|&group| group > 0)
+}
+
 pub fn is_session_exist(name: &str) -> bool {
     Tmux::with_command(tmux_interface::HasSession::new().target_session(name))
         .output()
@@ -157,6 +306,24 @@ pub fn get_all_session_names() -> std::collections::HashSet<String> {
         .unwrap_or_else(|_| std::collections::HashSet::new())
 }
 
+/// Whether the shared session has a window named `window`.
+pub fn is_window_exist(window: &str) -> bool {
+    Tmux::with_command(
+        tmux_interface::ListWindows::new()
+            .target_session(format!("={SHARED_SESSION_NAME}"))
+            .format("#{window_name}"),
+    )
+    .output()
+    .map(|output| {
+        output.success()
+            && String::from_utf8_lossy(&output.stdout())
+                .lines()
+                .any(|line| line == window)
+    })
+    .unwrap_or(false)
+}
+
+/// Send Ctrl-C to a session or, given a [`window_target`], to a window.
 pub fn send_ctrl_c(name: &str) -> anyhow::Result<()> {
     Tmux::with_command(SendKeys::new().target_pane(name).key("C-c"))
         .output()
@@ -212,6 +379,21 @@ pub fn kill_session(name: &str) -> anyhow::Result<()> {
         .map_err(|e| anyhow::anyhow!("Failed to kill tmux session: {}", e))
 }
 
+/// Kill a job's window in the shared session, given its [`window_target`].
+/// tmux ends the session with its last window.
+pub fn kill_window(target: &str) -> anyhow::Result<()> {
+    Tmux::with_command(PipePane::new().target_pane(target))
+        .output()
+        .ok();
+
+    std::thread::sleep(std::time::Duration::from_secs(1));
+
+    Tmux::with_command(KillWindow::new().target_window(target))
+        .output()
+        .map(|_| ())
+        .map_err(|e| anyhow::anyhow!("Failed to kill tmux window: {}", e))
+}
+
 /// Kill multiple tmux sessions in batch using a single tmux command
 /// This is much faster than killing sessions sequentially
 /// Returns a vector of tuples: (session_name, result)
@@ -277,11 +459,35 @@ pub fn kill_sessions_batch(names: &[String]) -> Vec<(String, anyhow::Result<()>)
     results
 }
 
-pub fn attach_to_session(name: &str) -> anyhow::Result<()> {
-    Tmux::with_command(tmux_interface::AttachSession::new().target_session(name))
-        .output()
-        .map_err(|e| anyhow::anyhow!("Failed to attach to tmux session: {}", e))?;
+fn attach_command(name: &str, read_only: bool) -> std::process::Command {
+    let mut command = std::process::Command::new("tmux");
+    command.args(["attach-session", "-t", name]);
+    if read_only {
+        command.arg("-r");
+    }
+    command
+}
+
+/// Replace the current process with `tmux attach-session`, read-only with
+/// `read_only`. Only returns if tmux could not be started.
+pub fn attach_to_session(name: &str, read_only: bool) -> anyhow::Result<()> {
+    use std::os::unix::process::CommandExt;
+
+    Err(anyhow::anyhow!(
+        "Failed to attach to tmux session: {}",
+        attach_command(name, read_only).exec()
+    ))
+}
 
+/// Attach to a session and return once the client detaches or the session ends.
+pub fn attach_and_wait(name: &str) -> anyhow::Result<()> {
+    let status = attach_command(name, false)
+        .status()
+        .map_err(|e| anyhow::anyhow!("Failed to attach to tmux session: {}", e))?;
+    // tmux also exits non-zero when the session is killed while attached.
+    if !status.success() && is_session_exist(name) {
+        anyhow::bail!("tmux attach-session -t {} exited with {}", name, status);
+    }
     Ok(())
 }
 
@@ -330,6 +536,54 @@ mod tests {
             .unwrap();
     }
 
+    #[test]
+    fn terminal_process_group_is_read_from_stat() {
+        let stat = "4242 (bash (x)) S 4200 4242 4242 34817 4250 4194560 0 0";
+        assert_eq!(terminal_process_group(stat), Some(4250));
+        // No terminal
+        let stat = "4242 (gflowd) S 1 4242 4242 0 -1 4194560 0 0";
+        assert_eq!(terminal_process_group(stat), None);
+    }
+
+    #[test]
+    fn test_foreground_process_group_of_a_pane() {
+        let tmux_usable = Command::new("tmux")
+            .arg("start-server")
+            .output()
+            .map(|output| output.status.success())
+            .unwrap_or(false);
+
+        if !tmux_usable {
+            eprintln!("Skipping test_foreground_process_group_of_a_pane: tmux not usable");
+            return;
+        }
+
+        let session_name = format!(
+            "gflow-test-fg-{}-{}",
+            std::process::id(),
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_millis()
+        );
+        let session = TmuxSession::create(session_name.clone()).unwrap();
+        let target = JobTarget::Session(session_name.clone());
+        let shell = target.pane_pid().unwrap();
+
+        session.send_command("sleep 30");
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(5);
+        let mut group = None;
+        while group.is_none() && std::time::Instant::now() < deadline {
+            std::thread::sleep(std::time::Duration::from_millis(50));
+            group = target.foreground_process_group();
+        }
+        assert!(group.is_some_and(|group| group != shell));
+
+        Tmux::with_command(KillSession::new().target_session(&session_name))
+            .output()
+            .unwrap();
+    }
+
     #[test]
     fn test_tmux_session_create_reports_duplicate_session() {
         let tmux_usable = Command::new("tmux")
@@ -364,6 +618,83 @@ mod tests {
             .unwrap();
     }
 
+    #[test]
+    fn test_job_windows_in_shared_session() {
+        let tmux_usable = Command::new("tmux")
+            .arg("start-server")
+            .output()
+            .map(|output| output.status.success())
+            .unwrap_or(false);
+
+        if !tmux_usable {
+            eprintln!("Skipping test_job_windows_in_shared_session: tmux not usable");
+            return;
+        }
+
+        let run_name = format!(
+            "gflow-test-win-{}-{}",
+            std::process::id(),
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_millis()
+        );
+        let window = job_window_name(7, &run_name);
+        let session = TmuxSession::create_window(&window).unwrap();
+        assert_eq!(session.name, window_target(&window));
+        assert!(is_window_exist(&window));
+        assert_eq!(
+            JobTarget::find(7, &run_name),
+            Some(JobTarget::Window(window_target(&window)))
+        );
+        // Only the exact window name matches.
+        assert_eq!(JobTarget::find(7, &run_name[..run_name.len() - 1]), None);
+
+        JobTarget::Window(window_target(&window)).kill().unwrap();
+        assert!(!is_window_exist(&window));
+        assert_eq!(JobTarget::find(7, &run_name), None);
+    }
+
+    #[test]
+    fn jobs_given_the_same_name_find_their_own_sessions() {
+        let tmux_usable = Command::new("tmux")
+            .arg("start-server")
+            .output()
+            .map(|output| output.status.success())
+            .unwrap_or(false);
+
+        if !tmux_usable {
+            eprintln!("Skipping jobs_given_the_same_name_find_their_own_sessions: tmux not usable");
+            return;
+        }
+
+        // Run names as the daemon gives them: the job ID, then the `--name`.
+        let name = format!(
+            "gflow-test-same-{}-{}",
+            std::process::id(),
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_millis()
+        );
+        let finished = format!("gjob-1-{name}");
+        let running = format!("gjob-2-{name}");
+        TmuxSession::create(finished.clone()).unwrap();
+        TmuxSession::create(running.clone()).unwrap();
+
+        let target = JobTarget::find(2, &running).unwrap();
+        assert_eq!(target, JobTarget::Session(running.clone()));
+        target.kill().unwrap();
+        assert!(!is_session_exist(&running));
+        // The finished job's session, left open for inspection, is untouched.
+        assert_eq!(
+            JobTarget::find(1, &finished),
+            Some(JobTarget::Session(finished.clone()))
+        );
+
+        kill_session(&finished).unwrap();
+    }
+
     #[test]
     fn normalize_session_name_replaces_tmux_target_delimiters() {
         assert_eq!(
@@ -1,3 +1,8 @@
+pub mod constraint;
+pub mod disk;
+pub mod interpolation;
+pub mod name_glob;
+pub mod output;
 pub mod parameter_sweep;
 pub mod parsers;
 pub mod timezone;
@@ -8,14 +13,14 @@ use clap::builder::{
     Styles,
 };
 use compact_str::CompactString;
-use regex::Regex;
 use std::collections::HashMap;
 use std::time::{Duration, SystemTime};
 
 // Re-export parser functions for backward compatibility
 pub use parameter_sweep::{generate_param_combinations, parse_param_spec};
 pub use parsers::{
-    parse_gpu_indices, parse_job_ids, parse_memory_limit, parse_since_time, parse_time_limit,
+    parse_duration, parse_gpu_indices, parse_job_ids, parse_memory_limit, parse_priority,
+    parse_since_time, parse_time_limit,
 };
 
 /// Trait for types that can provide parameter lookups
@@ -29,7 +34,8 @@ impl ParameterLookup for HashMap<CompactString, CompactString> {
     }
 }
 
-/// Substitute {param_name} patterns in command with actual values.
+/// Substitute {param_name} patterns in command with actual values, and
+/// `{{`/`}}` with literal braces (see [`interpolation`]).
 ///
 /// # Examples
 ///
@@ -50,28 +56,24 @@ pub fn substitute_parameters<P: ParameterLookup + ?Sized>(
     command: &str,
     parameters: &P,
 ) -> Result<String> {
-    let re = Regex::new(r"\{([a-zA-Z_][a-zA-Z0-9_]*)\}").unwrap();
-    let mut result = command.to_string();
-    let mut missing_params = Vec::new();
+    interpolation::expand(command, |name| {
+        parameters.get_param(name).map(|value| value.to_string())
+    })
+}
 
-    for cap in re.captures_iter(command) {
-        let param_name = &cap[1];
-        if let Some(value) = parameters.get_param(param_name) {
-            let pattern = format!("{{{}}}", param_name);
-            result = result.replace(&pattern, value.as_str());
-        } else {
-            missing_params.push(param_name.to_string());
+/// `command` of `job` as it runs: its parameters, then `{job_id}` and
+/// `{run_name}`, filled in.
+pub fn substitute_job_placeholders(job: &crate::core::job::Job, command: &str) -> Result<String> {
+    interpolation::expand(command, |name| {
+        if let Some(value) = job.parameters.get_param(name) {
+            return Some(value.to_string());
         }
-    }
-
-    if !missing_params.is_empty() {
-        return Err(anyhow!(
-            "Missing parameter values: {}",
-            missing_params.join(", ")
-        ));
-    }
-
-    Ok(result)
+        match name {
+            "job_id" => Some(job.id.to_string()),
+            "run_name" => Some(job.run_name.as_deref().unwrap_or_default().to_string()),
+            _ => None,
+        }
+    })
 }
 
 /// Format duration for display in HH:MM:SS format.
@@ -158,6 +160,36 @@ pub fn format_memory(memory_mb: u64) -> String {
     }
 }
 
+/// Format a size in bytes for display (e.g., `"512B"`, `"1.5K"`, `"3G"`).
+///
+/// # Examples
+///
+/// ```
+/// use gflow::utils::format_bytes;
+///
+/// assert_eq!(format_bytes(512), "512B");
+/// assert_eq!(format_bytes(1536), "1.5K");
+/// assert_eq!(format_bytes(3 << 30), "3G");
+/// ```
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["K", "M", "G", "T", "P"];
+    if bytes < 1024 {
+        return format!("{bytes}B");
+    }
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    let value = format!("{value:.1}");
+    format!(
+        "{}{}",
+        value.strip_suffix(".0").unwrap_or(&value),
+        UNITS[unit]
+    )
+}
+
 /// Normalize a project value by trimming surrounding whitespace and treating blank values as None.
 pub fn normalize_project(project: Option<&str>) -> Option<String> {
     project
@@ -205,6 +237,32 @@ pub fn validate_project_policy(
     Ok(normalized)
 }
 
+/// Check `priority` against the limit `[priority_limits]` sets for `user`.
+///
+/// The error lists the configured classes `user` may still use.
+pub fn validate_priority_limit(
+    priority: u8,
+    user: &str,
+    limits: &crate::config::PriorityLimitsConfig,
+    classes: &std::collections::BTreeMap<String, u8>,
+) -> std::result::Result<(), crate::core::api_error::ApiError> {
+    let Some(max) = limits.max_for(user) else {
+        return Ok(());
+    };
+    if priority <= max {
+        return Ok(());
+    }
+
+    let mut allowed: Vec<_> = classes.iter().filter(|(_, &value)| value <= max).collect();
+    allowed.sort_by_key(|(_, &value)| value);
+    Err(crate::core::api_error::ApiErrorKind::PriorityTooHigh {
+        priority,
+        max,
+        allowed: allowed.into_iter().map(|(name, _)| name.clone()).collect(),
+    }
+    .into())
+}
+
 pub const STYLES: Styles = Styles::styled()
     .header(AnsiColor::Green.on_default().effects(Effects::BOLD))
     .usage(AnsiColor::Green.on_default().effects(Effects::BOLD))
@@ -276,6 +334,44 @@ pub fn format_duration_compact(duration: Duration) -> String {
     }
 }
 
+/// Format a start estimate for tables, in local time.
+///
+/// Starts no later than `now` (a Unix timestamp) read `now`, later ones `~14:32`
+/// today or `~10-21 09:00` on another day.
+///
+/// # Examples
+///
+/// ```
+/// use gflow::core::scheduler::StartEstimate;
+/// use gflow::utils::format_start_estimate;
+///
+/// let at = StartEstimate::At { at: 1000, jobs_ahead: 0 };
+/// assert_eq!(format_start_estimate(&at, 1000), "now");
+/// assert_eq!(format_start_estimate(&StartEstimate::Unknown, 1000), "UNKNOWN");
+/// ```
+pub fn format_start_estimate(estimate: &crate::core::scheduler::StartEstimate, now: i64) -> String {
+    use crate::core::scheduler::StartEstimate;
+    use chrono::{Local, TimeZone};
+
+    match estimate {
+        StartEstimate::At { at, .. } if *at <= now => "now".to_string(),
+        StartEstimate::At { at, .. } => {
+            match (
+                Local.timestamp_opt(*at, 0).single(),
+                Local.timestamp_opt(now, 0).single(),
+            ) {
+                (Some(at), Some(now)) if at.date_naive() == now.date_naive() => {
+                    at.format("~%H:%M").to_string()
+                }
+                (Some(at), _) => at.format("~%m-%d %H:%M").to_string(),
+                (None, _) => at.to_string(),
+            }
+        }
+        StartEstimate::Unknown => "UNKNOWN".to_string(),
+        StartEstimate::Never { .. } => "NEVER".to_string(),
+    }
+}
+
 /// Validates that a job is in the expected state.
 /// Returns an error with a user-friendly message if the state doesn't match.
 ///
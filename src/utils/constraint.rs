@@ -0,0 +1,220 @@
+//! Labels on GPUs and nodes, and the `gbatch --constraint` expressions that
+//! select them.
+//!
+//! A constraint is a comma-separated list of `key=value` terms that must all
+//! match; `|` lists alternatives for one key:
+//!
+//! ```
+//! use gflow::utils::constraint::{Constraint, Labels};
+//!
+//! let constraint: Constraint = "vram=48g,arch=ampere|hopper".parse().unwrap();
+//! let labels: Labels = [("arch", "hopper"), ("vram", "48g"), ("nvlink", "true")]
+//!     .into_iter()
+//!     .map(|(k, v)| (k.to_string(), v.to_string()))
+//!     .collect();
+//! assert!(constraint.matches(&labels));
+//! ```
+
+use anyhow::{bail, Result};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+/// `key = value` labels of a GPU or node
+pub type Labels = BTreeMap<String, String>;
+
+/// Labels that must match for a job to be placed, see the module docs.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Constraint {
+    /// Each key with the values it may take, in the order written
+    terms: Vec<(String, Vec<String>)>,
+}
+
+impl Constraint {
+    /// Whether every key is labelled with one of its allowed values.
+    pub fn matches(&self, labels: &Labels) -> bool {
+        self.terms.iter().all(|(key, values)| {
+            labels
+                .get(key)
+                .is_some_and(|label| values.iter().any(|value| value == label))
+        })
+    }
+}
+
+impl FromStr for Constraint {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let mut terms: Vec<(String, Vec<String>)> = Vec::new();
+        for term in s.split(',') {
+            let Some((key, values)) = term.split_once('=') else {
+                bail!(
+                    "Invalid constraint term '{}': expected key=value",
+                    term.trim()
+                );
+            };
+            let key = key.trim();
+            validate_label_part("key", key)?;
+            if terms.iter().any(|(existing, _)| existing == key) {
+                bail!("Constraint key '{key}' appears twice; use {key}=a|b to allow either value");
+            }
+            let values = values
+                .split('|')
+                .map(|value| {
+                    let value = value.trim();
+                    validate_label_part("value", value)?;
+                    Ok(value.to_string())
+                })
+                .collect::<Result<Vec<_>>>()?;
+            terms.push((key.to_string(), values));
+        }
+        Ok(Self { terms })
+    }
+}
+
+impl fmt::Display for Constraint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, (key, values)) in self.terms.iter().enumerate() {
+            if i > 0 {
+                write!(f, ",")?;
+            }
+            write!(f, "{key}={}", values.join("|"))?;
+        }
+        Ok(())
+    }
+}
+
+impl TryFrom<String> for Constraint {
+    type Error = anyhow::Error;
+
+    fn try_from(s: String) -> Result<Self> {
+        s.parse()
+    }
+}
+
+impl From<Constraint> for String {
+    fn from(constraint: Constraint) -> Self {
+        constraint.to_string()
+    }
+}
+
+/// Parse a `key=value` label.
+pub fn parse_label(s: &str) -> Result<(String, String)> {
+    let Some((key, value)) = s.split_once('=') else {
+        bail!("Invalid label '{s}': expected key=value");
+    };
+    let (key, value) = (key.trim(), value.trim());
+    validate_label(key, value)?;
+    Ok((key.to_string(), value.to_string()))
+}
+
+/// `key=value` pairs joined by commas, as `gbatch --constraint` takes them.
+pub fn format_labels(labels: &Labels) -> String {
+    labels
+        .iter()
+        .map(|(key, value)| format!("{key}={value}"))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Check that a label can be matched by a constraint.
+pub fn validate_label(key: &str, value: &str) -> Result<()> {
+    validate_label_part("key", key)?;
+    validate_label_part("value", value)
+}
+
+/// Keys and values are non-empty and made of letters, digits, `_`, `-` and `.`.
+fn validate_label_part(what: &str, s: &str) -> Result<()> {
+    if s.is_empty() {
+        bail!("Label {what} must not be empty");
+    }
+    if let Some(c) = s
+        .chars()
+        .find(|c| !(c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.')))
+    {
+        bail!("Invalid character '{c}' in label {what} '{s}'");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn labels(pairs: &[(&str, &str)]) -> Labels {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_and_display_round_trip() {
+        let constraint: Constraint = " vram = 48g , arch=ampere|hopper".parse().unwrap();
+        assert_eq!(constraint.to_string(), "vram=48g,arch=ampere|hopper");
+        assert_eq!(
+            constraint.to_string().parse::<Constraint>().unwrap(),
+            constraint
+        );
+    }
+
+    #[test]
+    fn test_all_keys_must_match() {
+        let constraint: Constraint = "vram=48g,arch=ampere".parse().unwrap();
+        assert!(constraint.matches(&labels(&[("vram", "48g"), ("arch", "ampere")])));
+        assert!(!constraint.matches(&labels(&[("vram", "48g"), ("arch", "hopper")])));
+        assert!(!constraint.matches(&labels(&[("vram", "48g")])));
+    }
+
+    #[test]
+    fn test_alternatives_within_a_key() {
+        let constraint: Constraint = "arch=ampere|hopper,nvlink=true".parse().unwrap();
+        assert!(constraint.matches(&labels(&[("arch", "hopper"), ("nvlink", "true")])));
+        assert!(constraint.matches(&labels(&[("arch", "ampere"), ("nvlink", "true")])));
+        assert!(!constraint.matches(&labels(&[("arch", "turing"), ("nvlink", "true")])));
+    }
+
+    #[test]
+    fn test_rejects_malformed_constraints() {
+        for bad in [
+            "",
+            "arch",
+            "arch=",
+            "=ampere",
+            "arch=ampere,",
+            "arch=ampere||hopper",
+            "arch=amp ere",
+            "arch=ampere,arch=hopper",
+            "arch=a=b",
+        ] {
+            assert!(
+                bad.parse::<Constraint>().is_err(),
+                "{bad:?} should not parse"
+            );
+        }
+    }
+
+    #[test]
+    fn test_serde_uses_the_string_form() {
+        let constraint: Constraint = "arch=ampere|hopper".parse().unwrap();
+        let json = serde_json::to_string(&constraint).unwrap();
+        assert_eq!(json, "\"arch=ampere|hopper\"");
+        assert_eq!(
+            serde_json::from_str::<Constraint>(&json).unwrap(),
+            constraint
+        );
+        assert!(serde_json::from_str::<Constraint>("\"arch\"").is_err());
+    }
+
+    #[test]
+    fn test_parse_label() {
+        assert_eq!(
+            parse_label("vram=48g").unwrap(),
+            ("vram".to_string(), "48g".to_string())
+        );
+        assert!(parse_label("vram").is_err());
+        assert!(parse_label("vram=48g|80g").is_err());
+        assert!(parse_label("vram=").is_err());
+    }
+}
@@ -0,0 +1,165 @@
+use serde::{Deserialize, Serialize};
+use std::ffi::CString;
+use std::os::unix::ffi::OsStrExt;
+use std::path::{Path, PathBuf};
+
+/// Free and total space of the filesystem holding a path, in MB.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DiskSpace {
+    pub free_mb: u64,
+    pub total_mb: u64,
+}
+
+/// Free space of a directory the daemon watches, as shown by `ginfo`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, utoipa::ToSchema)]
+pub struct DiskUsage {
+    /// What the directory is for: `data_dir` or `run_dir`
+    pub kind: String,
+    #[schema(value_type = String)]
+    pub path: PathBuf,
+    /// `None` when the filesystem could not be asked
+    pub free_mb: Option<u64>,
+    pub total_mb: Option<u64>,
+}
+
+impl DiskUsage {
+    pub fn of(kind: &str, path: &Path) -> Self {
+        let space = disk_space(path);
+        Self {
+            kind: kind.to_string(),
+            path: path.to_path_buf(),
+            free_mb: space.map(|s| s.free_mb),
+            total_mb: space.map(|s| s.total_mb),
+        }
+    }
+
+    /// e.g. `12G free / 2000G total`, or `unknown`.
+    pub fn summary(&self) -> String {
+        match (self.free_mb, self.total_mb) {
+            (Some(free_mb), Some(total_mb)) => format!(
+                "{} free / {} total",
+                crate::utils::format_memory(free_mb),
+                crate::utils::format_memory(total_mb)
+            ),
+            _ => "unknown".to_string(),
+        }
+    }
+}
+
+/// Space of the filesystem holding `path`, as seen by an unprivileged user.
+///
+/// Returns `None` when `statvfs` fails or reports values that make no sense,
+/// as some network filesystems do; callers treat that as unknown.
+pub fn disk_space(path: &Path) -> Option<DiskSpace> {
+    let path = CString::new(path.as_os_str().as_bytes()).ok()?;
+    let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+    if unsafe { libc::statvfs(path.as_ptr(), &mut stat) } != 0 {
+        return None;
+    }
+    let fragment_size = if stat.f_frsize > 0 {
+        stat.f_frsize
+    } else {
+        stat.f_bsize
+    };
+    space_from_blocks(
+        fragment_size as u64,
+        stat.f_blocks as u64,
+        stat.f_bavail as u64,
+    )
+}
+
+fn space_from_blocks(block_size: u64, blocks: u64, available: u64) -> Option<DiskSpace> {
+    if block_size == 0 || blocks == 0 || available > blocks {
+        return None;
+    }
+    let to_mb = |count: u64| (u128::from(count) * u128::from(block_size) / (1024 * 1024)) as u64;
+    Some(DiskSpace {
+        free_mb: to_mb(available),
+        total_mb: to_mb(blocks),
+    })
+}
+
+/// Remove `dir` and everything under it; returns the bytes of the files
+/// removed. Symlinks are removed rather than followed, so nothing outside
+/// `dir` is touched, and a symlink in place of `dir` only loses the link.
+pub fn remove_dir_tree(dir: &Path) -> std::io::Result<u64> {
+    let metadata = std::fs::symlink_metadata(dir)?;
+    if !metadata.is_dir() {
+        std::fs::remove_file(dir)?;
+        return Ok(if metadata.is_file() {
+            metadata.len()
+        } else {
+            0
+        });
+    }
+    let mut size = 0;
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        // Neither of these follows a symlink.
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            size += remove_dir_tree(&entry.path())?;
+            continue;
+        }
+        if file_type.is_file() {
+            size += entry.metadata()?.len();
+        }
+        std::fs::remove_file(entry.path())?;
+    }
+    std::fs::remove_dir(dir)?;
+    Ok(size)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn odd_statvfs_values_are_unknown() {
+        assert_eq!(space_from_blocks(0, 100, 10), None);
+        assert_eq!(space_from_blocks(4096, 0, 0), None);
+        assert_eq!(space_from_blocks(4096, 100, 200), None);
+        assert_eq!(
+            space_from_blocks(4096, 1024 * 1024, 256 * 1024),
+            Some(DiskSpace {
+                free_mb: 1024,
+                total_mb: 4096
+            })
+        );
+    }
+
+    #[test]
+    fn missing_paths_are_unknown() {
+        assert_eq!(disk_space(Path::new("/nonexistent/gflow/run_dir")), None);
+        assert!(disk_space(Path::new("/")).is_some());
+
+        let usage = DiskUsage::of("run_dir", Path::new("/nonexistent/gflow/run_dir"));
+        assert_eq!(usage.summary(), "unknown");
+    }
+
+    #[test]
+    fn removing_a_tree_never_follows_symlinks_out_of_it() {
+        let root = tempfile::tempdir().unwrap();
+        let outside = root.path().join("outside");
+        std::fs::create_dir(&outside).unwrap();
+        std::fs::write(outside.join("keep.txt"), [0u8; 100]).unwrap();
+
+        let dir = root.path().join("jobs/7");
+        std::fs::create_dir_all(dir.join("ckpt")).unwrap();
+        std::fs::write(dir.join("out.txt"), [0u8; 10]).unwrap();
+        std::fs::write(dir.join("ckpt/a.pt"), [0u8; 5]).unwrap();
+        std::os::unix::fs::symlink(&outside, dir.join("link")).unwrap();
+        std::os::unix::fs::symlink(outside.join("keep.txt"), dir.join("ckpt/file")).unwrap();
+
+        assert_eq!(remove_dir_tree(&dir).unwrap(), 15);
+        assert!(!dir.exists());
+        assert_eq!(std::fs::read(outside.join("keep.txt")).unwrap().len(), 100);
+
+        // A link in place of the directory goes, not what it points to.
+        std::os::unix::fs::symlink(&outside, &dir).unwrap();
+        assert_eq!(remove_dir_tree(&dir).unwrap(), 0);
+        assert!(std::fs::symlink_metadata(&dir).is_err());
+        assert!(outside.join("keep.txt").exists());
+        assert!(remove_dir_tree(&dir).is_err());
+    }
+}
@@ -0,0 +1,269 @@
+//! `{...}` placeholders in job commands:
+//!
+//! - `{name}`: a job parameter, or [`JOB_PLACEHOLDERS`] such as `{job_id}`,
+//!   filled in when the job starts
+//! - `{env:VAR}` and `{env:VAR:-default}`: the submitting shell's
+//!   environment, filled in by `gbatch` at submission
+//! - `{{` and `}}`: a literal brace
+//!
+//! Any other brace is literal, so shell code such as `awk '{print $1}'` or
+//! `cp a.{txt,bak}` is left alone.
+
+use anyhow::{anyhow, bail, Result};
+
+/// Placeholders every job has, known only once the daemon accepted it
+pub const JOB_PLACEHOLDERS: [&str; 2] = ["job_id", "run_name"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Token<'a> {
+    Text(&'a str),
+    /// `{{` or `}}`
+    Brace(char),
+    Name(&'a str),
+    Env {
+        var: &'a str,
+        default: Option<&'a str>,
+    },
+}
+
+fn is_identifier(s: &str) -> bool {
+    let mut chars = s.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+fn tokenize(template: &str) -> Result<Vec<Token<'_>>> {
+    let mut tokens = Vec::new();
+    let mut text_start = 0;
+    let mut i = 0;
+    let bytes = template.as_bytes();
+    while i < bytes.len() {
+        let token = match bytes[i] {
+            b'{' | b'}' if bytes.get(i + 1) == Some(&bytes[i]) => {
+                Some((Token::Brace(bytes[i] as char), 2))
+            }
+            b'{' => match template[i + 1..].find('}') {
+                Some(len) => {
+                    let inner = &template[i + 1..i + 1 + len];
+                    placeholder(inner)?.map(|token| (token, len + 2))
+                }
+                None => None,
+            },
+            _ => None,
+        };
+        match token {
+            Some((token, len)) => {
+                if text_start < i {
+                    tokens.push(Token::Text(&template[text_start..i]));
+                }
+                tokens.push(token);
+                i += len;
+                text_start = i;
+            }
+            None => i += 1,
+        }
+    }
+    if text_start < template.len() {
+        tokens.push(Token::Text(&template[text_start..]));
+    }
+    Ok(tokens)
+}
+
+/// The placeholder between a pair of braces, or `None` when they are
+/// literal.
+fn placeholder(inner: &str) -> Result<Option<Token<'_>>> {
+    if is_identifier(inner) {
+        return Ok(Some(Token::Name(inner)));
+    }
+    let Some(spec) = inner.strip_prefix("env:") else {
+        return Ok(None);
+    };
+    let (var, default) = match spec.split_once(":-") {
+        Some((var, default)) => (var, Some(default)),
+        None => (spec, None),
+    };
+    if !is_identifier(var) {
+        bail!("Invalid placeholder '{{{inner}}}': expected {{env:VAR}} or {{env:VAR:-default}}");
+    }
+    Ok(Some(Token::Env { var, default }))
+}
+
+/// `value` with its braces doubled, so it reads back as literal text.
+fn escape(value: &str) -> String {
+    value.replace('{', "{{").replace('}', "}}")
+}
+
+/// Fill in `{env:VAR}` and `{env:VAR:-default}` from `env`, keeping every
+/// other placeholder and escape as written. Fails on a variable that is
+/// unset and has no default.
+pub fn expand_env(template: &str, env: impl Fn(&str) -> Option<String>) -> Result<String> {
+    let mut result = String::with_capacity(template.len());
+    for token in tokenize(template)? {
+        match token {
+            Token::Text(text) => result.push_str(text),
+            Token::Brace(brace) => {
+                result.push(brace);
+                result.push(brace);
+            }
+            Token::Name(name) => {
+                result.push('{');
+                result.push_str(name);
+                result.push('}');
+            }
+            Token::Env { var, default } => {
+                let value = env(var).or_else(|| default.map(str::to_string)).ok_or_else(|| {
+                    anyhow!(
+                        "Environment variable {var} is not set; use {{env:{var}:-default}} for a fallback"
+                    )
+                })?;
+                result.push_str(&escape(&value));
+            }
+        }
+    }
+    Ok(result)
+}
+
+/// Fill in every `{name}` from `lookup` and turn `{{` and `}}` into single
+/// braces. Fails naming the placeholders `lookup` does not know, and on
+/// `{env:...}`, which is only expanded at submission.
+pub fn expand(template: &str, lookup: impl Fn(&str) -> Option<String>) -> Result<String> {
+    let mut result = String::with_capacity(template.len());
+    let mut unknown = Vec::new();
+    for token in tokenize(template)? {
+        match token {
+            Token::Text(text) => result.push_str(text),
+            Token::Brace(brace) => result.push(brace),
+            Token::Name(name) => match lookup(name) {
+                Some(value) => result.push_str(&value),
+                None if !unknown.contains(&name) => unknown.push(name),
+                None => {}
+            },
+            Token::Env { var, .. } => {
+                bail!("{{env:{var}}} is only expanded by gbatch at submission")
+            }
+        }
+    }
+    if !unknown.is_empty() {
+        return Err(unknown_placeholders(&unknown));
+    }
+    Ok(result)
+}
+
+/// The `{name}` placeholders in `template`, in order, without repeats.
+pub fn placeholder_names(template: &str) -> Result<Vec<&str>> {
+    let mut names = Vec::new();
+    for token in tokenize(template)? {
+        if let Token::Name(name) = token {
+            if !names.contains(&name) {
+                names.push(name);
+            }
+        }
+    }
+    Ok(names)
+}
+
+/// Fails naming each of `names` that is neither in `known` nor one of
+/// [`JOB_PLACEHOLDERS`].
+pub fn check_placeholders(template: &str, known: impl Fn(&str) -> bool) -> Result<Vec<&str>> {
+    let names = placeholder_names(template)?;
+    let unknown: Vec<_> = names
+        .iter()
+        .copied()
+        .filter(|name| !known(name) && !JOB_PLACEHOLDERS.contains(name))
+        .collect();
+    if !unknown.is_empty() {
+        return Err(unknown_placeholders(&unknown));
+    }
+    Ok(names)
+}
+
+fn unknown_placeholders(names: &[&str]) -> anyhow::Error {
+    let names: Vec<_> = names.iter().map(|name| format!("{{{name}}}")).collect();
+    anyhow!(
+        "Unknown placeholder(s) {}; give a value with --param, or write {{{{ and }}}} for literal braces",
+        names.join(", ")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn lookup<'a>(values: &'a [(&str, &str)]) -> impl Fn(&str) -> Option<String> + 'a {
+        move |name| {
+            values
+                .iter()
+                .find(|(key, _)| *key == name)
+                .map(|(_, value)| value.to_string())
+        }
+    }
+
+    #[test]
+    fn names_are_filled_in_and_escapes_unescaped() {
+        let values = [("lr", "0.1"), ("job_id", "42")];
+        let expand = |template| expand(template, lookup(&values)).unwrap();
+
+        assert_eq!(
+            expand("python train.py --lr {lr} --out /results/{job_id}"),
+            "python train.py --lr 0.1 --out /results/42"
+        );
+        assert_eq!(expand("{lr}{lr}"), "0.10.1");
+        assert_eq!(expand("echo ${{HOME}} {{lr}}"), "echo ${HOME} {lr}");
+        assert_eq!(expand("{{{lr}}}"), "{0.1}");
+        // Braces that are not placeholders stay as they are.
+        for literal in [
+            "awk '{print $1}'",
+            "cp a.{txt,bak} .",
+            "find . -exec rm {} +",
+            "{ echo; }",
+            "unclosed {lr",
+            "lone } brace",
+            "{1x}",
+        ] {
+            assert_eq!(expand(literal), literal);
+        }
+    }
+
+    #[test]
+    fn unknown_placeholders_are_errors() {
+        let values = [("lr", "0.1")];
+        let error = expand("{lr} {seed} {model} {seed}", lookup(&values)).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Unknown placeholder(s) {seed}, {model}; give a value with --param, or write {{ and }} for literal braces"
+        );
+        assert!(expand("{env:HOME}", lookup(&values)).is_err());
+
+        let known = |name: &str| name == "lr";
+        assert_eq!(
+            check_placeholders("{lr} {job_id} {run_name} {{seed}}", known).unwrap(),
+            ["lr", "job_id", "run_name"]
+        );
+        assert!(check_placeholders("{seed}", known).is_err());
+        assert!(check_placeholders("{env:1X}", known).is_err());
+    }
+
+    #[test]
+    fn environment_is_expanded_and_escaped_eagerly() {
+        let env: HashMap<&str, &str> = HashMap::from([("USER", "alice"), ("ODD", "{lr} }")]);
+        let expand_env = |template| expand_env(template, |var| env.get(var).map(|v| v.to_string()));
+
+        assert_eq!(
+            expand_env("--out /results/{env:USER}/{job_id} {{x}}").unwrap(),
+            "--out /results/alice/{job_id} {{x}}"
+        );
+        assert_eq!(expand_env("{env:MISSING:-/tmp}").unwrap(), "/tmp");
+        assert_eq!(expand_env("{env:MISSING:-}").unwrap(), "");
+        assert_eq!(expand_env("{env:USER:-bob}").unwrap(), "alice");
+        let error = expand_env("{env:MISSING}").unwrap_err();
+        assert!(error.to_string().contains("MISSING is not set"), "{error}");
+
+        // A value's braces are kept literal rather than read as placeholders.
+        let expanded = expand_env("echo {env:ODD}").unwrap();
+        assert_eq!(expanded, "echo {{lr}} }}");
+        assert_eq!(expand(&expanded, |_| None).unwrap(), "echo {lr} }");
+    }
+}
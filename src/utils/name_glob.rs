@@ -0,0 +1,143 @@
+//! Shell-style glob patterns for job names, as taken by `gqueue --names`.
+//!
+//! `*` matches any run of characters, `?` one character, `[a-z0-9]` one of a
+//! set (`[!...]` or `[^...]` negates it) and `\` escapes the next character.
+//! A pattern without these matches only that exact name:
+//!
+//! ```
+//! use gflow::utils::name_glob::NameGlobs;
+//!
+//! let globs: NameGlobs = "train-*,?exp-[0-9]".parse().unwrap();
+//! assert!(globs.matches("train-resnet-1"));
+//! assert!(globs.matches("aexp-7"));
+//! assert!(!globs.matches("eval-resnet-1"));
+//! ```
+
+use anyhow::{bail, Context, Result};
+use regex::Regex;
+use std::str::FromStr;
+
+/// A comma-separated list of glob patterns; a name matches if any pattern does.
+#[derive(Debug, Clone)]
+pub struct NameGlobs {
+    patterns: Vec<Regex>,
+}
+
+impl NameGlobs {
+    pub fn matches(&self, name: &str) -> bool {
+        self.patterns.iter().any(|pattern| pattern.is_match(name))
+    }
+}
+
+impl FromStr for NameGlobs {
+    type Err = anyhow::Error;
+
+    fn from_str(list: &str) -> Result<Self> {
+        let patterns = list
+            .split(',')
+            .map(str::trim)
+            .filter(|pattern| !pattern.is_empty())
+            .map(glob_to_regex)
+            .collect::<Result<Vec<_>>>()?;
+        if patterns.is_empty() {
+            bail!("Empty name pattern '{list}'");
+        }
+        Ok(Self { patterns })
+    }
+}
+
+/// The anchored regex for one glob `pattern`.
+fn glob_to_regex(pattern: &str) -> Result<Regex> {
+    let invalid = |position: usize, problem: &str| {
+        anyhow::anyhow!("Invalid name pattern '{pattern}' at position {position}: {problem}")
+    };
+
+    let mut regex = String::from("^");
+    let mut chars = pattern.chars().enumerate().peekable();
+    while let Some((position, c)) = chars.next() {
+        match c {
+            '*' => regex.push_str(".*"),
+            '?' => regex.push('.'),
+            '\\' => {
+                let (_, escaped) = chars
+                    .next()
+                    .ok_or_else(|| invalid(position, "nothing left to escape after '\\'"))?;
+                regex.push_str(&regex::escape(&escaped.to_string()));
+            }
+            '[' => {
+                regex.push('[');
+                if chars.next_if(|&(_, c)| c == '!' || c == '^').is_some() {
+                    regex.push('^');
+                }
+                let mut empty = true;
+                loop {
+                    match chars.next() {
+                        None => return Err(invalid(position, "'[' is never closed")),
+                        // A leading `]` is part of the set, as in shells.
+                        Some((_, ']')) if !empty => break,
+                        Some((_, '-'))
+                            if !empty && chars.peek().is_some_and(|&(_, c)| c != ']') =>
+                        {
+                            regex.push('-')
+                        }
+                        Some((_, c)) => regex.push_str(&regex::escape(&c.to_string())),
+                    }
+                    empty = false;
+                }
+                regex.push(']');
+            }
+            c => regex.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    regex.push('$');
+
+    Regex::new(&regex).with_context(|| format!("Invalid name pattern '{pattern}'"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn globs(list: &str) -> NameGlobs {
+        list.parse().unwrap()
+    }
+
+    #[test]
+    fn wildcards_sets_and_escapes() {
+        assert!(globs("*resnet*").matches("train-resnet-1"));
+        assert!(globs("train-resnet-1").matches("train-resnet-1"));
+        assert!(!globs("train-resnet").matches("train-resnet-1"));
+        assert!(globs("?exp-[0-9]").matches("bexp-4"));
+        assert!(!globs("?exp-[0-9]").matches("exp-4"));
+        assert!(!globs("run-[!0-9]").matches("run-3"));
+        assert!(globs("run-[]x]").matches("run-]"));
+        assert!(globs(r"lr-3e-4\*").matches("lr-3e-4*"));
+        assert!(!globs(r"lr-3e-4\*").matches("lr-3e-4x"));
+        // Regex syntax is literal.
+        assert!(globs("a.b+(c)").matches("a.b+(c)"));
+        assert!(!globs("a.b").matches("axb"));
+    }
+
+    #[test]
+    fn any_pattern_in_the_list_may_match() {
+        let list = globs("eval-*, train-?");
+        assert!(list.matches("eval-1"));
+        assert!(list.matches("train-2"));
+        assert!(!list.matches("train-22"));
+    }
+
+    #[test]
+    fn invalid_patterns_name_the_position() {
+        let err = "ok-*,exp-[0-9".parse::<NameGlobs>().unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid name pattern 'exp-[0-9' at position 4: '[' is never closed"
+        );
+        let err = r"trailing\".parse::<NameGlobs>().unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            r"Invalid name pattern 'trailing\' at position 8: nothing left to escape after '\'"
+        );
+        assert!(" , ".parse::<NameGlobs>().is_err());
+    }
+}
@@ -0,0 +1,176 @@
+//! Terminal output shared by the CLIs: whether to use color, and whether the
+//! terminal can show box-drawing characters.
+//!
+//! Color follows `--color` ([`set_color_choice`]). With `auto`, the default,
+//! output is colored only when it goes to a terminal and `NO_COLOR` is unset.
+
+use crate::core::job::JobState;
+use owo_colors::{OwoColorize, Style};
+use std::fmt::Display;
+use std::io::IsTerminal;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+/// When to color output, as taken by `--color`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum ColorChoice {
+    /// Color terminals unless `NO_COLOR` is set
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+static COLOR_CHOICE: AtomicU8 = AtomicU8::new(ColorChoice::Auto as u8);
+
+/// Set the choice later [`OutputStyle::stdout`] and [`OutputStyle::stderr`] calls follow.
+pub fn set_color_choice(choice: ColorChoice) {
+    COLOR_CHOICE.store(choice as u8, Ordering::Relaxed);
+}
+
+pub fn color_choice() -> ColorChoice {
+    match COLOR_CHOICE.load(Ordering::Relaxed) {
+        value if value == ColorChoice::Always as u8 => ColorChoice::Always,
+        value if value == ColorChoice::Never as u8 => ColorChoice::Never,
+        _ => ColorChoice::Auto,
+    }
+}
+
+/// How to render text for one output stream.
+///
+/// The default is plain ASCII, which is also what tests render with unless
+/// they force a setting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct OutputStyle {
+    pub color: bool,
+    /// Box-drawing characters may be used
+    pub unicode: bool,
+}
+
+impl OutputStyle {
+    pub fn stdout() -> Self {
+        Self::detect(std::io::stdout().is_terminal())
+    }
+
+    pub fn stderr() -> Self {
+        Self::detect(std::io::stderr().is_terminal())
+    }
+
+    fn detect(is_terminal: bool) -> Self {
+        let env = |name: &str| std::env::var(name).ok();
+        Self {
+            color: color_enabled(color_choice(), is_terminal, env("NO_COLOR").as_deref()),
+            unicode: locale_is_utf8(env),
+        }
+    }
+
+    /// `text` in `style`, or unchanged without color.
+    pub fn paint(&self, text: impl Display, style: Style) -> String {
+        if self.color {
+            text.style(style).to_string()
+        } else {
+            text.to_string()
+        }
+    }
+
+    /// The short form of `state` (`R`, `PD`, ...) in its color.
+    pub fn state(&self, state: JobState) -> String {
+        let style = match state {
+            JobState::Running => Style::new().green().bold(),
+            JobState::Queued => Style::new().yellow(),
+            JobState::Hold => Style::new().magenta(),
+            JobState::Finished => Style::new().dimmed(),
+            JobState::Failed => Style::new().red().bold(),
+            JobState::Timeout => Style::new().red().underline(),
+            JobState::Cancelled => Style::new().strikethrough(),
+        };
+        self.paint(state.short_form(), style)
+    }
+
+    pub fn error_label(&self) -> String {
+        self.paint("error:", Style::new().red().bold())
+    }
+
+    pub fn warning_label(&self) -> String {
+        self.paint("warning:", Style::new().yellow().bold())
+    }
+
+    /// A horizontal line `width` characters wide.
+    pub fn rule(&self, width: usize) -> String {
+        (if self.unicode { "─" } else { "-" }).repeat(width)
+    }
+}
+
+/// Print `message` to stderr after a `warning:` label.
+pub fn print_warning(message: impl Display) {
+    eprintln!("{} {message}", OutputStyle::stderr().warning_label());
+}
+
+fn color_enabled(choice: ColorChoice, is_terminal: bool, no_color: Option<&str>) -> bool {
+    match choice {
+        ColorChoice::Always => true,
+        ColorChoice::Never => false,
+        ColorChoice::Auto => is_terminal && no_color.is_none_or(str::is_empty),
+    }
+}
+
+/// Whether the locale in `LC_ALL`, `LC_CTYPE` or `LANG` (the first one set)
+/// uses UTF-8. Windows terminals are assumed to when none is set.
+fn locale_is_utf8(env: impl Fn(&str) -> Option<String>) -> bool {
+    ["LC_ALL", "LC_CTYPE", "LANG"]
+        .into_iter()
+        .filter_map(env)
+        .find(|locale| !locale.is_empty())
+        .map_or(cfg!(windows), |locale| {
+            let locale = locale.to_ascii_lowercase();
+            locale.contains("utf-8") || locale.contains("utf8")
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn auto_colors_terminals_unless_no_color_is_set() {
+        assert!(color_enabled(ColorChoice::Auto, true, None));
+        assert!(color_enabled(ColorChoice::Auto, true, Some("")));
+        assert!(!color_enabled(ColorChoice::Auto, true, Some("1")));
+        assert!(!color_enabled(ColorChoice::Auto, false, None));
+        assert!(color_enabled(ColorChoice::Always, false, Some("1")));
+        assert!(!color_enabled(ColorChoice::Never, true, None));
+    }
+
+    #[test]
+    fn box_drawing_needs_a_utf8_locale() {
+        let locale = |vars: &'static [(&'static str, &'static str)]| {
+            locale_is_utf8(move |name| {
+                vars.iter()
+                    .find(|(var, _)| *var == name)
+                    .map(|(_, value)| value.to_string())
+            })
+        };
+        assert!(locale(&[("LANG", "en_US.UTF-8")]));
+        assert!(locale(&[("LC_ALL", "zh_CN.utf8"), ("LANG", "C")]));
+        assert!(!locale(&[("LC_ALL", "C"), ("LANG", "en_US.UTF-8")]));
+        assert!(!locale(&[("LC_ALL", ""), ("LANG", "POSIX")]));
+        assert_eq!(locale(&[]), cfg!(windows));
+    }
+
+    #[test]
+    fn forced_styles_render_states_and_rules() {
+        let plain = OutputStyle::default();
+        assert_eq!(plain.state(JobState::Running), "R");
+        assert_eq!(plain.warning_label(), "warning:");
+        assert_eq!(plain.rule(3), "---");
+
+        let fancy = OutputStyle {
+            color: true,
+            unicode: true,
+        };
+        assert_eq!(fancy.state(JobState::Running), "\u{1b}[32;1mR\u{1b}[0m");
+        assert_eq!(fancy.state(JobState::Queued), "\u{1b}[33mPD\u{1b}[0m");
+        assert_eq!(fancy.state(JobState::Hold), "\u{1b}[35mH\u{1b}[0m");
+        assert_eq!(fancy.state(JobState::Failed), "\u{1b}[31;1mF\u{1b}[0m");
+        assert_eq!(fancy.rule(3), "───");
+    }
+}
@@ -1,5 +1,6 @@
 use anyhow::{anyhow, Context, Result};
 use range_parser::parse;
+use std::collections::BTreeMap;
 use std::time::{Duration, SystemTime};
 
 // Time constants (in seconds)
@@ -70,6 +71,50 @@ pub fn parse_time_limit(time_str: &str) -> Result<Duration> {
     }
 }
 
+/// Parse a duration written with units, such as `gjob wait --timeout`.
+///
+/// Supported formats: numbers each followed by `s`, `m`, `h` or `d`, e.g.
+/// `"10s"`, `"6h"` or `"1h30m"`.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use gflow::utils::parsers::parse_duration;
+///
+/// assert_eq!(parse_duration("10s").unwrap(), Duration::from_secs(10));
+/// assert_eq!(parse_duration("1h30m").unwrap(), Duration::from_secs(5400));
+/// assert!(parse_duration("90").is_err());
+/// ```
+pub fn parse_duration(duration_str: &str) -> Result<Duration> {
+    let input = duration_str.trim().to_lowercase();
+    let invalid = || anyhow!("Invalid duration '{duration_str}'; use e.g. 10s, 5m, 6h or 1h30m");
+    let mut seconds = 0u64;
+    let mut rest = input.as_str();
+    if rest.is_empty() {
+        return Err(invalid());
+    }
+    while !rest.is_empty() {
+        let digits = rest
+            .find(|c: char| !c.is_ascii_digit())
+            .ok_or_else(invalid)?;
+        let value: u64 = rest[..digits].parse().map_err(|_| invalid())?;
+        let unit = match rest[digits..].chars().next() {
+            Some('s') => 1,
+            Some('m') => SECONDS_PER_MINUTE,
+            Some('h') => SECONDS_PER_HOUR,
+            Some('d') => SECONDS_PER_DAY,
+            _ => return Err(invalid()),
+        };
+        seconds = value
+            .checked_mul(unit)
+            .and_then(|part| seconds.checked_add(part))
+            .ok_or_else(invalid)?;
+        rest = &rest[digits + 1..];
+    }
+    Ok(Duration::from_secs(seconds))
+}
+
 /// Parse memory limit string into megabytes.
 ///
 /// Supported formats:
@@ -356,6 +401,37 @@ pub fn parse_reservation_duration(duration_str: &str) -> Result<u64> {
     Ok(total_secs)
 }
 
+/// Parse a comma-separated list of weekdays for a recurring reservation.
+///
+/// # Examples
+///
+/// ```
+/// use chrono::Weekday;
+/// use gflow::utils::parsers::parse_weekdays;
+///
+/// assert_eq!(parse_weekdays("tue").unwrap(), vec![Weekday::Tue]);
+/// assert_eq!(
+///     parse_weekdays("Mon, wednesday,fri").unwrap(),
+///     vec![Weekday::Mon, Weekday::Wed, Weekday::Fri]
+/// );
+/// assert!(parse_weekdays("tue,funday").is_err());
+/// ```
+pub fn parse_weekdays(days: &str) -> Result<Vec<chrono::Weekday>> {
+    let mut weekdays = Vec::new();
+    for day in days.split(',').map(str::trim).filter(|day| !day.is_empty()) {
+        let weekday: chrono::Weekday = day
+            .parse()
+            .map_err(|_| anyhow!("Invalid weekday '{}' (use mon, tue, ..., sun)", day))?;
+        if !weekdays.contains(&weekday) {
+            weekdays.push(weekday);
+        }
+    }
+    if weekdays.is_empty() {
+        anyhow::bail!("Specify at least one weekday");
+    }
+    Ok(weekdays)
+}
+
 /// Parse range specification (start:stop or start:stop:step).
 /// Returns a vector of stringified values.
 ///
@@ -486,6 +562,61 @@ pub fn parse_array_spec(spec: &str) -> Result<Vec<u32>> {
     }
 }
 
+/// Parse a priority: a number from 0 to 255 or the name of a class from
+/// `[priorities]`.
+///
+/// # Examples
+///
+/// ```
+/// use std::collections::BTreeMap;
+/// use gflow::utils::parsers::parse_priority;
+///
+/// let classes = BTreeMap::from([("high".to_string(), 50)]);
+/// assert_eq!(parse_priority("20", &classes).unwrap(), 20);
+/// assert_eq!(parse_priority("high", &classes).unwrap(), 50);
+/// assert!(parse_priority("urgent", &classes).is_err());
+/// ```
+pub fn parse_priority(value: &str, classes: &BTreeMap<String, u8>) -> Result<u8> {
+    let value = value.trim();
+    if value.chars().all(|c| c.is_ascii_digit()) {
+        return value
+            .parse::<u8>()
+            .map_err(|_| anyhow!("Invalid priority '{value}': expected 0-255"));
+    }
+    if let Some(&priority) = classes.get(value) {
+        return Ok(priority);
+    }
+    if classes.is_empty() {
+        return Err(anyhow!(
+            "Invalid priority '{value}': expected 0-255 (no priority classes are configured in [priorities])"
+        ));
+    }
+    Err(anyhow!(
+        "Unknown priority class '{value}'; expected 0-255 or one of: {}",
+        format_priority_classes(classes)
+    ))
+}
+
+/// The class configured for `priority`, if any; the first by name when
+/// several share it.
+pub fn priority_class_name(priority: u8, classes: &BTreeMap<String, u8>) -> Option<&str> {
+    classes
+        .iter()
+        .find(|(_, &value)| value == priority)
+        .map(|(name, _)| name.as_str())
+}
+
+/// `low (1), normal (10), high (50)`, lowest priority first.
+pub fn format_priority_classes(classes: &BTreeMap<String, u8>) -> String {
+    let mut classes: Vec<_> = classes.iter().collect();
+    classes.sort_by_key(|(_, &priority)| priority);
+    classes
+        .iter()
+        .map(|(name, priority)| format!("{name} ({priority})"))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -532,6 +663,22 @@ mod tests {
         assert!(parse_time_limit("1:abc").is_err());
     }
 
+    // Tests for parse_duration
+    #[test]
+    fn test_parse_duration_units() {
+        assert_eq!(parse_duration("10s").unwrap(), Duration::from_secs(10));
+        assert_eq!(parse_duration("5m").unwrap(), Duration::from_secs(300));
+        assert_eq!(parse_duration(" 6H ").unwrap(), Duration::from_secs(21600));
+        assert_eq!(parse_duration("1d2h").unwrap(), Duration::from_secs(93600));
+    }
+
+    #[test]
+    fn test_parse_duration_invalid() {
+        for invalid in ["", "10", "h", "1x", "1h30", "-5s", "99999999999999999999d"] {
+            assert!(parse_duration(invalid).is_err(), "{invalid:?} was accepted");
+        }
+    }
+
     // Tests for parse_memory_limit
     #[test]
     fn test_parse_memory_limit_megabytes() {
@@ -847,4 +994,22 @@ mod tests {
         assert!(parse_array_spec("5-1").is_err());
         assert!(parse_array_spec("abc").is_err());
     }
+
+    #[test]
+    fn test_parse_priority_numbers_and_classes() {
+        let classes = BTreeMap::from([
+            ("low".to_string(), 1),
+            ("normal".to_string(), 10),
+            ("high".to_string(), 50),
+        ]);
+        assert_eq!(parse_priority("0", &classes).unwrap(), 0);
+        assert_eq!(parse_priority(" high ", &classes).unwrap(), 50);
+        assert!(parse_priority("256", &classes).is_err());
+        let error = parse_priority("urgent", &classes).unwrap_err().to_string();
+        assert!(error.contains("low (1), normal (10), high (50)"), "{error}");
+        assert!(parse_priority("high", &BTreeMap::new()).is_err());
+
+        assert_eq!(priority_class_name(10, &classes), Some("normal"));
+        assert_eq!(priority_class_name(11, &classes), None);
+    }
 }
@@ -0,0 +1,101 @@
+//! Which config file and daemon address the CLI ends up using, across
+//! `--config`, `GFLOW_CONFIG`, the default location and `GFLOW_SCHEDULER_URL`.
+
+use std::process::{Command, Output};
+
+struct Env {
+    root: tempfile::TempDir,
+}
+
+impl Env {
+    /// A home with a default config (port 59001) and two other files,
+    /// `env.toml` (port 59002) and `flag.toml` (port 59003).
+    fn new() -> Self {
+        let root = tempfile::tempdir().unwrap();
+        let default_dir = root.path().join("config/gflow");
+        std::fs::create_dir_all(&default_dir).unwrap();
+        for (path, port) in [
+            (default_dir.join("gflow.toml"), 59001),
+            (root.path().join("env.toml"), 59002),
+            (root.path().join("flag.toml"), 59003),
+        ] {
+            std::fs::write(path, format!("[daemon]\nport = {port}\n")).unwrap();
+        }
+        Self { root }
+    }
+
+    fn path(&self, name: &str) -> String {
+        self.root.path().join(name).display().to_string()
+    }
+
+    fn gflow(&self, args: &[&str], env: &[(&str, &str)]) -> Output {
+        let root = self.root.path();
+        Command::new(env!("CARGO_BIN_EXE_gflow"))
+            .env_clear()
+            .env("HOME", root)
+            .env("XDG_CONFIG_HOME", root.join("config"))
+            .env("XDG_DATA_HOME", root.join("data"))
+            .env("XDG_RUNTIME_DIR", root.join("run"))
+            .envs(env.iter().copied())
+            .args(args)
+            .current_dir(root)
+            .output()
+            .unwrap()
+    }
+
+    fn port(&self, flag: Option<&str>, env: &[(&str, &str)]) -> String {
+        let mut args = vec!["config", "get", "daemon.port"];
+        if let Some(flag) = flag {
+            args.extend(["--config", flag]);
+        }
+        let output = self.gflow(&args, env);
+        assert!(output.status.success(), "{output:?}");
+        String::from_utf8(output.stdout).unwrap().trim().to_string()
+    }
+}
+
+#[test]
+fn config_flag_beats_env_beats_default() {
+    let env = Env::new();
+    let env_file = env.path("env.toml");
+    let flag_file = env.path("flag.toml");
+    let missing = env.path("missing.toml");
+
+    assert_eq!(env.port(None, &[]), "59001");
+    assert_eq!(env.port(None, &[("GFLOW_CONFIG", "")]), "59001");
+    assert_eq!(env.port(None, &[("GFLOW_CONFIG", &env_file)]), "59002");
+    assert_eq!(env.port(Some(&flag_file), &[]), "59003");
+    assert_eq!(
+        env.port(Some(&flag_file), &[("GFLOW_CONFIG", &env_file)]),
+        "59003"
+    );
+    // A named file that is missing does not fall back to the default file.
+    assert_eq!(env.port(None, &[("GFLOW_CONFIG", &missing)]), "59000");
+    // `GFLOW_DAEMON__*` still applies on top of whichever file won.
+    assert_eq!(
+        env.port(
+            None,
+            &[("GFLOW_CONFIG", &env_file), ("GFLOW_DAEMON__PORT", "59004")]
+        ),
+        "59004"
+    );
+}
+
+#[test]
+fn scheduler_url_overrides_only_the_address() {
+    let env = Env::new();
+    let stderr = |output: Output| String::from_utf8(output.stderr).unwrap();
+
+    // Nothing listens on these ports, so the error names the address used.
+    let output = env.gflow(&["gqueue"], &[]);
+    assert!(stderr(output).contains(":59001"));
+    let output = env.gflow(
+        &["gqueue"],
+        &[("GFLOW_SCHEDULER_URL", "http://127.0.0.1:9")],
+    );
+    assert!(!output.status.success());
+    assert!(stderr(output).contains("http://127.0.0.1:9"));
+
+    let output = env.gflow(&["gqueue"], &[("GFLOW_SCHEDULER_URL", "localhost")]);
+    assert!(stderr(output).contains("Invalid GFLOW_SCHEDULER_URL"));
+}
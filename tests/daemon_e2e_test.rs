@@ -1,5 +1,8 @@
+use gflow::client::{JobAction, JobFilter, UpdateJobRequest};
 use gflow::config::{Config, DaemonConfig};
-use gflow::core::job::{JobBuilder, JobState};
+use gflow::core::api_error::ApiErrorKind;
+use gflow::core::executor::ExecutorKind;
+use gflow::core::job::{EnvSpec, JobBuilder, JobState, JobStateReason};
 use gflow::tmux::{get_all_session_names, is_session_exist};
 use reqwest::StatusCode;
 use serde_json::Value;
@@ -202,9 +205,35 @@ impl TestSandbox {
             daemon: DaemonConfig {
                 host: "127.0.0.1".to_string(),
                 port: self.port,
+                listen: None,
+                extra_listen: Vec::new(),
+                readonly_bind: None,
+                tls_cert: None,
+                tls_key: None,
+                tls_ca: None,
+                insecure_skip_verify: false,
+                require_auth: false,
+                anonymous_read: true,
+                token: None,
                 gpus: None,
                 gpu_allocation_strategy: Default::default(),
+                preemption_priority: None,
+                labels: Default::default(),
+                gpu_labels: Default::default(),
                 gpu_poll_interval_secs: 10,
+                default_shell: "bash".to_string(),
+                executor: Default::default(),
+                tmux_mode: Default::default(),
+                state_save_interval_ms: 1000,
+                state_backups: 5,
+                state_backup_interval_secs: 3600,
+                enforce_limits: false,
+                min_free_disk: None,
+                min_client_version: None,
+                duplicate_run_names: Default::default(),
+                allow_as_user: false,
+                job_dir_retention_secs: None,
+                logging: Default::default(),
             },
             ..Default::default()
         }
@@ -266,7 +295,7 @@ impl TestSandbox {
         }
     }
 
-    fn run_gflow<I, S>(&self, args: I) -> CommandResult
+    fn gflow_command<I, S>(&self, args: I) -> Command
     where
         I: IntoIterator<Item = S>,
         S: AsRef<OsStr>,
@@ -280,7 +309,38 @@ impl TestSandbox {
         command.env("XDG_RUNTIME_DIR", &self.runtime_dir);
         command.env("GFLOW_DISABLE_DEV_AUTO", "1");
         command.args(args);
-        CommandResult::from_output(command.output().unwrap())
+        command
+    }
+
+    fn run_gflow<I, S>(&self, args: I) -> CommandResult
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<OsStr>,
+    {
+        CommandResult::from_output(self.gflow_command(args).output().unwrap())
+    }
+
+    fn run_gflow_with_stdin<I, S>(&self, args: I, input: &str) -> CommandResult
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<OsStr>,
+    {
+        use std::io::Write;
+
+        let mut child = self
+            .gflow_command(args)
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::piped())
+            .spawn()
+            .unwrap();
+        child
+            .stdin
+            .take()
+            .unwrap()
+            .write_all(input.as_bytes())
+            .unwrap();
+        CommandResult::from_output(child.wait_with_output().unwrap())
     }
 
     fn start_daemon(&mut self) {
@@ -442,6 +502,10 @@ async fn wait_for_tmux_session(name: &str, should_exist: bool, timeout: Duration
     );
 }
 
+fn read_pid_file(path: &Path) -> Option<u32> {
+    std::fs::read_to_string(path).ok()?.trim().parse().ok()
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn daemon_lifecycle_reload_and_health_endpoint() {
     let Some(mut sandbox) = TestSandbox::new() else {
@@ -455,6 +519,8 @@ async fn daemon_lifecycle_reload_and_health_endpoint() {
     assert_eq!(health["status"], "ok");
     let original_pid = health["pid"].as_u64().unwrap() as u32;
     assert!(process_running(original_pid));
+    let pid_file = sandbox.runtime_dir.join("gflow").join("gflowd.pid");
+    assert_eq!(read_pid_file(&pid_file), Some(original_pid));
 
     let status = sandbox.run_gflow(["gflowd", "status"]);
     status.assert_success("gflowd status while running");
@@ -469,6 +535,7 @@ async fn daemon_lifecycle_reload_and_health_endpoint() {
     assert_eq!(reloaded_health["status"], "ok");
     let new_pid = reloaded_health["pid"].as_u64().unwrap() as u32;
     assert_ne!(original_pid, new_pid);
+    assert_eq!(read_pid_file(&pid_file), Some(new_pid));
 
     let start = Instant::now();
     while start.elapsed() < Duration::from_secs(15) {
@@ -498,6 +565,8 @@ async fn daemon_lifecycle_reload_and_health_endpoint() {
         );
     }
 
+    assert!(!pid_file.exists(), "PID file should be removed after down");
+
     let status = sandbox.run_gflow(["gflowd", "status"]);
     status.assert_success("gflowd status after down");
     assert!(status.stdout.contains("Status: Not running"));
@@ -621,40 +690,1789 @@ async fn cancelling_missing_job_returns_client_error() {
     let client = gflow::Client::build(&sandbox.client_config()).unwrap();
     let err = client.cancel_job(u32::MAX).await.unwrap_err();
     assert!(err.to_string().contains("Failed to cancel job"));
-    assert!(err.to_string().contains("404"));
+    let rejected = err.downcast_ref::<gflow::client::DaemonRejected>().unwrap();
+    assert_eq!(rejected.status, StatusCode::NOT_FOUND);
 
     sandbox.stop_daemon();
 }
 
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
-async fn health_reports_recovery_mode_for_corrupt_state() {
+async fn stdin_scripts_are_saved_in_managed_scripts_dir() {
+    let Some(mut sandbox) = TestSandbox::new() else {
+        return;
+    };
+
+    sandbox.start_daemon();
+    wait_for_health_status(&sandbox.base_url(), StatusCode::OK, Duration::from_secs(15)).await;
+
+    let script = "#!/bin/bash\n# GFLOW --priority=3\necho from-heredoc\n";
+    sandbox
+        .run_gflow_with_stdin(["gbatch", "--stdin"], script)
+        .assert_success("gbatch --stdin");
+    sandbox
+        .run_gflow_with_stdin(["gbatch", "-"], script)
+        .assert_success("gbatch -");
+
+    let client = gflow::Client::build(&sandbox.client_config()).unwrap();
+    let scripts_dir = sandbox.data_home.join("gflow/scripts");
+    let mut saved = Vec::new();
+    for id in [1, 2] {
+        let job = client.get_job(id).await.unwrap().unwrap();
+        assert_eq!(job.priority, 3);
+        let path = *job.script.expect("stdin job should have a script");
+        assert!(path.starts_with(&scripts_dir), "{}", path.display());
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), script);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+            assert_ne!(mode & 0o111, 0, "script should be executable");
+        }
+        saved.push(path);
+    }
+    assert_ne!(saved[0], saved[1]);
+
+    let show = sandbox.run_gflow(["gjob", "show", "1"]);
+    show.assert_success("gjob show");
+    assert!(
+        show.stdout.contains(&saved[0].display().to_string()),
+        "stdout:\n{}",
+        show.stdout
+    );
+
+    let empty = sandbox.run_gflow_with_stdin(["gbatch", "--stdin"], "  \n");
+    assert!(!empty.status.success());
+    assert!(
+        empty.stderr.contains("No content provided via stdin"),
+        "stderr:\n{}",
+        empty.stderr
+    );
+
+    sandbox.stop_daemon();
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn scripts_are_uploaded_to_remotes_without_shared_filesystem() {
+    let Some(mut sandbox) = TestSandbox::new() else {
+        return;
+    };
+
+    let host = sandbox.base_url();
+    sandbox
+        .run_gflow(["remote", "add", "lab", host.as_str()])
+        .assert_success("gflow remote add lab");
+    sandbox
+        .run_gflow(["remote", "add", "shared", host.as_str(), "--shared-fs"])
+        .assert_success("gflow remote add shared");
+    let list = sandbox.run_gflow(["remote", "list"]);
+    list.assert_success("gflow remote list");
+    assert!(list.stdout.contains("lab"), "stdout:\n{}", list.stdout);
+    assert!(list.stdout.contains("shared"), "stdout:\n{}", list.stdout);
+
+    sandbox.start_daemon();
+    wait_for_health_status(&sandbox.base_url(), StatusCode::OK, Duration::from_secs(15)).await;
+
+    let script = "#!/bin/bash\n# GFLOW --priority=3\necho uploaded\n";
+    let script_path = sandbox.work_dir.join("job.sh");
+    std::fs::write(&script_path, script).unwrap();
+
+    sandbox
+        .run_gflow(["gbatch", "--remote", "lab", "job.sh"])
+        .assert_success("gbatch --remote lab");
+    let by_env = sandbox
+        .gflow_command(["gbatch", "job.sh"])
+        .env("GFLOW_REMOTE", "shared")
+        .output()
+        .unwrap();
+    CommandResult::from_output(by_env).assert_success("GFLOW_REMOTE=shared gbatch");
+    sandbox
+        .run_gflow_with_stdin(["gbatch", "--remote", "lab", "--stdin"], script)
+        .assert_success("gbatch --remote lab --stdin");
+
+    let client = gflow::Client::build(&sandbox.client_config()).unwrap();
+    let scripts_dir = sandbox.data_home.join("gflow/scripts");
+    let uploaded = *client.get_job(1).await.unwrap().unwrap().script.unwrap();
+    assert!(uploaded.starts_with(&scripts_dir), "{}", uploaded.display());
+    assert_eq!(std::fs::read_to_string(&uploaded).unwrap(), script);
+    let shared = client.get_job(2).await.unwrap().unwrap();
+    assert_eq!(shared.script.as_deref(), Some(&script_path));
+    assert_eq!(shared.priority, 3);
+    let from_stdin = *client.get_job(3).await.unwrap().unwrap().script.unwrap();
+    assert!(
+        from_stdin.starts_with(&scripts_dir),
+        "{}",
+        from_stdin.display()
+    );
+    assert_ne!(from_stdin, uploaded);
+    assert_eq!(
+        std::fs::read_dir(&scripts_dir).unwrap().count(),
+        2,
+        "only the daemon should have saved scripts"
+    );
+
+    let queue = sandbox.run_gflow(["gqueue", "--remote", "lab", "-a"]);
+    queue.assert_success("gqueue --remote lab");
+    let unknown = sandbox.run_gflow(["gqueue", "--remote", "nowhere"]);
+    assert!(!unknown.status.success());
+    assert!(
+        unknown.stderr.contains("Unknown remote 'nowhere'"),
+        "stderr:\n{}",
+        unknown.stderr
+    );
+
+    sandbox
+        .run_gflow(["remote", "remove", "lab"])
+        .assert_success("gflow remote remove");
+    let list = sandbox.run_gflow(["remote", "list"]);
+    assert!(!list.stdout.contains("lab"), "stdout:\n{}", list.stdout);
+
+    sandbox.stop_daemon();
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn venv_without_activate_script_fails_job_at_schedule_time() {
+    let Some(mut sandbox) = TestSandbox::new() else {
+        return;
+    };
+
+    sandbox.start_daemon();
+    wait_for_health_status(&sandbox.base_url(), StatusCode::OK, Duration::from_secs(15)).await;
+
+    let venv = sandbox.work_dir.join("missing-venv");
+    let venv_arg = venv.display().to_string();
+    let submit = sandbox.run_gflow(["gbatch", "--venv", venv_arg.as_str(), "echo", "hi"]);
+    submit.assert_success("gbatch --venv");
+    assert!(
+        submit.stderr.contains("bin/activate does not exist"),
+        "stderr:\n{}",
+        submit.stderr
+    );
+
+    let client = gflow::Client::build(&sandbox.client_config()).unwrap();
+    let job = wait_for_job_state(&client, 1, JobState::Failed, Duration::from_secs(15)).await;
+    assert_eq!(job.environment, EnvSpec::Venv(venv.clone()));
+    match job.reason.as_deref() {
+        Some(JobStateReason::SystemError(message)) => {
+            assert!(message.contains("no activation script"), "{message}")
+        }
+        other => panic!("unexpected reason: {other:?}"),
+    }
+
+    let show = sandbox.run_gflow(["gjob", "show", "1"]);
+    show.assert_success("gjob show");
+    assert!(
+        show.stdout
+            .contains(&format!("Environment=venv:{}", venv.display())),
+        "stdout:\n{}",
+        show.stdout
+    );
+
+    sandbox.stop_daemon();
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn direct_executor_runs_and_cancels_jobs_without_tmux_sessions() {
     let Some(mut sandbox) = TestSandbox::new() else {
         return;
     };
 
-    std::fs::create_dir_all(sandbox.data_dir()).unwrap();
     std::fs::write(
-        sandbox.data_dir().join("state.json"),
-        b"{ definitely-not-json",
+        sandbox.config_home.join("gflow/gflow.toml"),
+        format!(
+            "[daemon]\nhost = \"127.0.0.1\"\nport = {}\nexecutor = \"direct\"\n",
+            sandbox.port
+        ),
     )
     .unwrap();
 
     sandbox.start_daemon();
+    wait_for_health_status(&sandbox.base_url(), StatusCode::OK, Duration::from_secs(15)).await;
+
+    let client = gflow::Client::build(&sandbox.client_config()).unwrap();
+    sandbox
+        .run_gflow(["gbatch", "echo", "direct-ok"])
+        .assert_success("gbatch echo");
+    let finished =
+        wait_for_job_state(&client, 1, JobState::Finished, Duration::from_secs(15)).await;
+    assert_eq!(finished.executor, Some(ExecutorKind::Direct));
+    assert!(!is_session_exist(finished.run_name.as_deref().unwrap()));
+    wait_for_log_contains(&sandbox.log_path(1), "direct-ok", Duration::from_secs(5)).await;
+
+    sandbox
+        .run_gflow(["gbatch", "sleep", "300"])
+        .assert_success("gbatch sleep");
+    wait_for_job_state(&client, 2, JobState::Running, Duration::from_secs(15)).await;
+    let pid = read_pid_file(&sandbox.runtime_dir.join("gflow/direct/2.pid"))
+        .expect("direct job should record its PID");
+    assert!(process_running(pid));
+
+    sandbox
+        .run_gflow(["gcancel", "2"])
+        .assert_success("gcancel");
+    wait_for_job_state(&client, 2, JobState::Cancelled, Duration::from_secs(15)).await;
+    let start = Instant::now();
+    while process_running(pid) && start.elapsed() < Duration::from_secs(15) {
+        tokio::time::sleep(Duration::from_millis(200)).await;
+    }
+    assert!(!process_running(pid), "cancelled job should be interrupted");
+
+    sandbox.stop_daemon();
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn sigterm_with_wait_running_drains_running_jobs_before_exiting() {
+    let Some(mut sandbox) = TestSandbox::new() else {
+        return;
+    };
+
+    std::fs::write(
+        sandbox.config_home.join("gflow/gflow.toml"),
+        format!(
+            "[daemon]\nhost = \"127.0.0.1\"\nport = {}\nexecutor = \"direct\"\n",
+            sandbox.port
+        ),
+    )
+    .unwrap();
 
+    sandbox
+        .run_gflow(["gflowd", "up", "--wait-running=60"])
+        .assert_success("gflowd up --wait-running");
+    sandbox.daemon_started = true;
     let health =
         wait_for_health_status(&sandbox.base_url(), StatusCode::OK, Duration::from_secs(15)).await;
-    assert_eq!(health["status"], "recovery");
-    assert_eq!(health["mode"], "journal");
-    assert!(health["pid"].as_u64().is_some());
-    assert!(health["detail"]
-        .as_str()
+    assert!(health["shutdown"].is_null(), "{health}");
+    let pid = health["pid"].as_u64().unwrap() as libc::pid_t;
+
+    let client = gflow::Client::build(&sandbox.client_config()).unwrap();
+    sandbox
+        .run_gflow(["gbatch", "sleep", "3"])
+        .assert_success("gbatch sleep");
+    wait_for_job_state(&client, 1, JobState::Running, Duration::from_secs(15)).await;
+
+    unsafe {
+        libc::kill(pid, libc::SIGTERM);
+    }
+    let start = Instant::now();
+    loop {
+        let (_, health) = get_health(&sandbox.base_url()).await.unwrap();
+        if health["shutdown"] == "draining" {
+            break;
+        }
+        assert!(
+            start.elapsed() < Duration::from_secs(5),
+            "daemon did not start draining: {health}"
+        );
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+
+    // Submissions are still accepted while draining, but nothing new starts.
+    sandbox
+        .run_gflow(["gbatch", "echo", "after-shutdown"])
+        .assert_success("gbatch while draining");
+    tokio::time::sleep(Duration::from_secs(1)).await;
+    assert_eq!(
+        client.get_job(2).await.unwrap().unwrap().state,
+        JobState::Queued
+    );
+
+    wait_for_health_unreachable(&sandbox.base_url(), Duration::from_secs(20)).await;
+
+    // The job's outcome was saved before the daemon exited.
+    sandbox.stop_daemon();
+    sandbox.start_daemon();
+    wait_for_health_status(&sandbox.base_url(), StatusCode::OK, Duration::from_secs(15)).await;
+    assert_eq!(
+        client.get_job(1).await.unwrap().unwrap().state,
+        JobState::Finished
+    );
+
+    sandbox.stop_daemon();
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn spooled_submissions_are_sent_once_daemon_is_up() {
+    let Some(mut sandbox) = TestSandbox::new() else {
+        return;
+    };
+
+    let spooled = sandbox.run_gflow(["gbatch", "--spool", "--name", "first", "sleep", "30"]);
+    spooled.assert_success("gbatch --spool with the daemon down");
+    assert!(
+        spooled.stdout.contains("spooled 1 job(s)"),
+        "stdout:\n{}",
+        spooled.stdout
+    );
+    sandbox
+        .run_gflow(["gbatch", "--spool", "--param", "n=1,2", "echo {n}"])
+        .assert_success("second gbatch --spool");
+    let spool_dir = sandbox.data_home.join("gflow/spool");
+    let spooled_files = std::fs::read_dir(&spool_dir)
         .unwrap()
-        .contains("entered recovery mode"));
+        .filter(|entry| {
+            let path = entry.as_ref().unwrap().path();
+            path.extension().is_some_and(|ext| ext == "json")
+        })
+        .count();
+    assert_eq!(spooled_files, 2);
 
-    let backup = health["state_backup"].as_str().unwrap();
+    sandbox.start_daemon();
+    wait_for_health_status(&sandbox.base_url(), StatusCode::OK, Duration::from_secs(15)).await;
+
+    let flush = sandbox.run_gflow(["gbatch", "flush-spool"]);
+    flush.assert_success("gbatch flush-spool");
+    for line in [
+        "Submitted spooled batch job 1 (",
+        "Submitted spooled batch job 2 (",
+        "Submitted spooled batch job 3 (",
+    ] {
+        assert!(flush.stdout.contains(line), "stdout:\n{}", flush.stdout);
+    }
+
+    let client = gflow::Client::build(&sandbox.client_config()).unwrap();
+    let job = client.get_job(1).await.unwrap().unwrap();
+    assert_eq!(job.run_dir, sandbox.work_dir);
+    let job = client.get_job(3).await.unwrap().unwrap();
+    assert_eq!(job.parameters.get("n").map(|v| v.as_str()), Some("2"));
+
+    let empty = sandbox.run_gflow(["gbatch", "flush-spool"]);
+    empty.assert_success("gbatch flush-spool with nothing spooled");
+    assert!(empty.stdout.contains("No spooled submissions"));
+
+    sandbox.stop_daemon();
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn resubmitting_with_same_idempotency_key_creates_one_job() {
+    let Some(mut sandbox) = TestSandbox::new() else {
+        return;
+    };
+
+    sandbox.start_daemon();
+    wait_for_health_status(&sandbox.base_url(), StatusCode::OK, Duration::from_secs(15)).await;
+
+    let job = JobBuilder::new()
+        .command("sleep 30")
+        .submitted_by("tester")
+        .run_dir(sandbox.root.clone())
+        .build();
+    let http = reqwest::Client::new();
+    let submit = |path: &str, body: Value, key: &str| {
+        http.post(format!("{}{path}", sandbox.base_url()))
+            .header(gflow::client::IDEMPOTENCY_KEY_HEADER, key)
+            .json(&body)
+            .send()
+    };
+
+    let single = serde_json::to_value(&job).unwrap();
+    let first: Value = submit("/jobs", single.clone(), "key-a")
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let retried = submit("/jobs", single.clone(), "key-a").await.unwrap();
+    assert_eq!(retried.status(), StatusCode::CREATED);
+    assert_eq!(retried.json::<Value>().await.unwrap(), first);
+
+    let batch = Value::Array(vec![single.clone(), single.clone()]);
+    let first_batch: Value = submit("/jobs/batch", batch.clone(), "key-b")
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let retried_batch: Value = submit("/jobs/batch", batch, "key-b")
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(retried_batch, first_batch);
+
+    // A fresh key is a new submission.
+    let other: Value = submit("/jobs", single, "key-c")
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_ne!(other["id"], first["id"]);
+
+    let client = gflow::Client::build(&sandbox.client_config()).unwrap();
+    assert_eq!(client.list_jobs().await.unwrap().len(), 4);
+
+    sandbox.stop_daemon();
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn resending_a_submission_id_returns_the_original_job() {
+    let Some(mut sandbox) = TestSandbox::new() else {
+        return;
+    };
+
+    sandbox.start_daemon();
+    wait_for_health_status(&sandbox.base_url(), StatusCode::OK, Duration::from_secs(15)).await;
+
+    let mut job = JobBuilder::new()
+        .command("sleep 30")
+        .submitted_by("tester")
+        .run_dir(sandbox.root.clone())
+        .build();
+    job.submission_id = Some(uuid::Uuid::new_v4());
+    let http = reqwest::Client::new();
+    let submit = |path: &str, body: Value| {
+        http.post(format!("{}{path}", sandbox.base_url()))
+            .json(&body)
+            .send()
+    };
+
+    let single = serde_json::to_value(&job).unwrap();
+    let first = submit("/jobs", single.clone()).await.unwrap();
+    assert_eq!(first.status(), StatusCode::CREATED);
+    let first: Value = first.json().await.unwrap();
+    let resent = submit("/jobs", single.clone()).await.unwrap();
+    assert_eq!(resent.status(), StatusCode::OK);
+    let resent: Value = resent.json().await.unwrap();
+    assert_eq!(resent["id"], first["id"]);
+    assert_eq!(resent["run_name"], first["run_name"]);
+
+    let mut changed = single.clone();
+    changed["command"] = "sleep 60".into();
+    let conflict = submit("/jobs", changed).await.unwrap();
+    assert_eq!(conflict.status(), StatusCode::CONFLICT);
+
+    // In a batch, resent jobs keep their IDs and only new ones are created.
+    let mut new_job = job.clone();
+    new_job.submission_id = Some(uuid::Uuid::new_v4());
+    let batch = serde_json::json!([serde_json::to_value(&new_job).unwrap(), single]);
+    let created = submit("/jobs/batch", batch.clone()).await.unwrap();
+    assert_eq!(created.status(), StatusCode::CREATED);
+    let created: Value = created.json().await.unwrap();
+    assert_eq!(created[1]["id"], first["id"]);
+    assert_ne!(created[0]["id"], first["id"]);
+    let resent_batch = submit("/jobs/batch", batch).await.unwrap();
+    assert_eq!(resent_batch.status(), StatusCode::OK);
+    let resent_batch: Value = resent_batch.json().await.unwrap();
+    assert_eq!(resent_batch[0]["id"], created[0]["id"]);
+    assert_eq!(resent_batch[1]["id"], created[1]["id"]);
+
+    let client = gflow::Client::build(&sandbox.client_config()).unwrap();
+    assert_eq!(client.list_jobs().await.unwrap().len(), 2);
+
+    sandbox.stop_daemon();
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn pipelines_are_submitted_as_one_batch_or_not_at_all() {
+    let Some(mut sandbox) = TestSandbox::new() else {
+        return;
+    };
+
+    sandbox.start_daemon();
+    wait_for_health_status(&sandbox.base_url(), StatusCode::OK, Duration::from_secs(15)).await;
+
+    let pipeline = sandbox.work_dir.join("flow.yaml");
+    std::fs::write(
+        &pipeline,
+        "params:\n  dataset: cifar10\nsteps:\n  \
+         eval:\n    command: sleep 30 # {dataset}\n    depends_on: [train, prepare]\n  \
+         train:\n    command: sleep 30 # {dataset}\n    depends_on: [prepare]\n  \
+         prepare:\n    command: sleep 30\n",
+    )
+    .unwrap();
+    let submit = sandbox.run_gflow(["gbatch", "--pipeline", "flow.yaml"]);
+    submit.assert_success("gbatch --pipeline");
+    assert!(submit.stdout.contains("Submitted 3 jobs as group"));
+
+    let client = gflow::Client::build(&sandbox.client_config()).unwrap();
+    let jobs = client.list_jobs().await.unwrap();
+    let names: Vec<_> = jobs.iter().map(|job| job.run_name.as_deref()).collect();
+    assert_eq!(
+        names,
+        [
+            Some("gjob-1-prepare"),
+            Some("gjob-2-train"),
+            Some("gjob-3-eval")
+        ]
+    );
+    assert!(jobs[0].depends_on_ids.is_empty());
+    assert_eq!(jobs[1].depends_on_ids.as_slice(), [jobs[0].id]);
+    assert_eq!(jobs[2].depends_on_ids.as_slice(), [jobs[1].id, jobs[0].id]);
+    assert_eq!(
+        jobs[1]
+            .parameters
+            .get("dataset")
+            .map(|value| value.as_str()),
+        Some("cifar10")
+    );
+    assert!(jobs[0].group_id.is_some());
+    assert!(jobs.iter().all(|job| job.group_id == jobs[0].group_id));
+
+    // A cycle is caught before anything is sent.
+    std::fs::write(
+        &pipeline,
+        "steps:\n  a:\n    command: true\n    depends_on: [b]\n  \
+         b:\n    command: true\n    depends_on: [a]\n",
+    )
+    .unwrap();
+    let cycle = sandbox.run_gflow(["gbatch", "--pipeline", "flow.yaml"]);
+    assert!(!cycle.status.success());
+    assert!(cycle.stderr.contains("cycle: a, b"), "{}", cycle.stderr);
+
+    // The daemon refuses the whole batch when any job in it is invalid.
+    let job = JobBuilder::new()
+        .command("sleep 30")
+        .submitted_by("tester")
+        .run_dir(sandbox.root.clone())
+        .build();
+    let mut forward = job.clone();
+    forward.batch_depends_on = vec![1];
+    let mut missing = job.clone();
+    missing.depends_on = Some(999);
+    missing.depends_on_ids = vec![999].into();
+    let http = reqwest::Client::new();
+    for batch in [vec![forward, job.clone()], vec![job.clone(), missing]] {
+        let response = http
+            .post(format!("{}/jobs/batch", sandbox.base_url()))
+            .json(&batch)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+    assert_eq!(client.list_jobs().await.unwrap().len(), 3);
+
+    sandbox.stop_daemon();
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn gbatch_quiet_and_verbose_report_the_submission() {
+    let Some(mut sandbox) = TestSandbox::new() else {
+        return;
+    };
+
+    sandbox.start_daemon();
+    wait_for_health_status(&sandbox.base_url(), StatusCode::OK, Duration::from_secs(15)).await;
+
+    let quiet = sandbox.run_gflow(["gbatch", "-q", "-n", "quiet", "sleep", "30"]);
+    quiet.assert_success("gbatch -q");
+    assert_eq!(quiet.stdout, "1\n");
+
+    let waiting = sandbox.run_gflow([
+        "gbatch",
+        "-v",
+        "-n",
+        "waiting",
+        "--depends-on",
+        "1",
+        "sleep",
+        "30",
+    ]);
+    waiting.assert_success("gbatch -v --depends-on");
     assert!(
-        Path::new(backup).exists(),
-        "backup path should exist: {backup}"
+        waiting.stdout.starts_with("Submitted batch job 2 ("),
+        "{}",
+        waiting.stdout
+    );
+    assert!(waiting.stdout.contains("  Depends on:     1 ("));
+    assert!(waiting
+        .stdout
+        .contains("  Queue position: waiting on dependencies"));
+    assert!(waiting.stdout.contains("  Free GPUs:      "));
+
+    // Outranks job 1 even if that is still queued.
+    let urgent = sandbox.run_gflow(["gbatch", "-v", "-n", "urgent", "-p", "50", "sleep", "30"]);
+    urgent.assert_success("gbatch -v -p 50");
+    assert!(
+        urgent.stdout.contains("  Priority:       50"),
+        "{}",
+        urgent.stdout
+    );
+    assert!(
+        urgent.stdout.contains("  Queue position: 1\n"),
+        "{}",
+        urgent.stdout
+    );
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn project_file_sets_gbatch_defaults_below_flags() {
+    let Some(mut sandbox) = TestSandbox::new() else {
+        return;
+    };
+
+    sandbox.start_daemon();
+    wait_for_health_status(&sandbox.base_url(), StatusCode::OK, Duration::from_secs(15)).await;
+
+    std::fs::write(
+        sandbox.work_dir.join(".gflow.toml"),
+        "[gbatch]\npriority = 42\ntime = \"30\"\n",
+    )
+    .unwrap();
+
+    let show = sandbox.run_gflow(["gbatch", "--show-effective-config", "-p", "7"]);
+    show.assert_success("gbatch --show-effective-config");
+    let rows: Vec<Vec<&str>> = show
+        .stdout
+        .lines()
+        .map(|line| line.split_whitespace().collect())
+        .collect();
+    assert!(
+        rows.contains(&vec!["priority", "7", "command", "line"]),
+        "{}",
+        show.stdout
+    );
+    assert!(
+        rows.iter().any(|row| row[..3] == ["time", "30", "project"]),
+        "{}",
+        show.stdout
+    );
+    assert!(
+        rows.contains(&vec!["gpus", "0", "builtin"]),
+        "{}",
+        show.stdout
+    );
+
+    let defaulted = sandbox.run_gflow(["gbatch", "-q", "-n", "defaulted", "sleep", "30"]);
+    defaulted.assert_success("gbatch with project defaults");
+    let flagged = sandbox.run_gflow(["gbatch", "-q", "-n", "flagged", "-p", "7", "sleep", "30"]);
+    flagged.assert_success("gbatch -p 7 with project defaults");
+
+    let client = gflow::Client::build(&sandbox.client_config()).unwrap();
+    let defaulted = client.get_job(1).await.unwrap().unwrap();
+    assert_eq!(defaulted.priority, 42);
+    assert_eq!(defaulted.time_limit, Some(Duration::from_secs(30 * 60)));
+    let flagged = client.get_job(2).await.unwrap().unwrap();
+    assert_eq!(flagged.priority, 7);
+
+    std::fs::write(sandbox.work_dir.join(".gflow.toml"), "[gbatch]\ngpu = 1\n").unwrap();
+    let invalid = sandbox.run_gflow(["gbatch", "sleep", "30"]);
+    assert!(!invalid.status.success());
+    assert!(
+        invalid
+            .stderr
+            .contains(".gflow.toml:2: unknown key 'gbatch.gpu'"),
+        "{}",
+        invalid.stderr
+    );
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn rerun_failed_resubmits_only_the_failed_part_of_a_pipeline() {
+    let Some(mut sandbox) = TestSandbox::new() else {
+        return;
+    };
+
+    std::fs::write(
+        sandbox.config_home.join("gflow/gflow.toml"),
+        format!(
+            "[daemon]\nhost = \"127.0.0.1\"\nport = {}\nexecutor = \"direct\"\n",
+            sandbox.port
+        ),
+    )
+    .unwrap();
+    sandbox.start_daemon();
+    wait_for_health_status(&sandbox.base_url(), StatusCode::OK, Duration::from_secs(15)).await;
+
+    std::fs::write(
+        sandbox.work_dir.join("flow.yaml"),
+        "steps:\n  a:\n    command: echo {step}\n  \
+         b:\n    command: 'false'\n    depends_on: [a]\n  \
+         c:\n    command: echo done\n    depends_on: [b]\n\
+         params:\n  step: first\n",
+    )
+    .unwrap();
+    sandbox
+        .run_gflow(["gbatch", "--pipeline", "flow.yaml"])
+        .assert_success("gbatch --pipeline");
+
+    let client = gflow::Client::build(&sandbox.client_config()).unwrap();
+    wait_for_job_state(&client, 1, JobState::Finished, Duration::from_secs(15)).await;
+    wait_for_job_state(&client, 2, JobState::Failed, Duration::from_secs(15)).await;
+    let cancelled =
+        wait_for_job_state(&client, 3, JobState::Cancelled, Duration::from_secs(15)).await;
+    let group = cancelled.group_id.unwrap().to_string();
+
+    let plan = sandbox.run_gflow([
+        "gjob",
+        "rerun-failed",
+        "--group",
+        group.as_str(),
+        "--dry-run",
+    ]);
+    plan.assert_success("gjob rerun-failed --dry-run");
+    assert!(
+        plan.stdout.contains("Would rerun 2 job(s):"),
+        "{}",
+        plan.stdout
+    );
+    assert!(plan
+        .stdout
+        .contains("Job 3 (gjob-3-c, Cancelled), after rerun of 2"));
+    assert_eq!(client.list_jobs().await.unwrap().len(), 3);
+
+    let rerun = sandbox.run_gflow(["gjob", "rerun-failed", "3"]);
+    rerun.assert_success("gjob rerun-failed");
+    assert!(rerun.stdout.contains("Job 2 → Job 4"), "{}", rerun.stdout);
+    assert!(rerun.stdout.contains("Job 3 → Job 5"), "{}", rerun.stdout);
+
+    let jobs = client.list_jobs().await.unwrap();
+    assert_eq!(jobs.len(), 5);
+    assert_eq!(jobs[3].redone_from, Some(2));
+    assert_eq!(jobs[3].depends_on_ids.as_slice(), [1]);
+    assert_eq!(jobs[3].command.as_deref(), Some("false"));
+    assert_eq!(jobs[4].redone_from, Some(3));
+    assert_eq!(jobs[4].depends_on_ids.as_slice(), [4]);
+    assert_eq!(
+        jobs[4].parameters.get("step").map(|value| value.as_str()),
+        Some("first")
+    );
+    assert_eq!(jobs[4].group_id, cancelled.group_id);
+
+    sandbox.stop_daemon();
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn refused_requests_carry_error_codes() {
+    let Some(mut sandbox) = TestSandbox::new() else {
+        return;
+    };
+
+    sandbox.start_daemon();
+    wait_for_health_status(&sandbox.base_url(), StatusCode::OK, Duration::from_secs(15)).await;
+
+    let client = gflow::Client::build(&sandbox.client_config()).unwrap();
+    let kind = |error: anyhow::Error| {
+        error
+            .downcast_ref::<gflow::client::DaemonRejected>()
+            .unwrap_or_else(|| panic!("not a daemon error: {error:#}"))
+            .error
+            .kind
+            .clone()
+    };
+    let job = |command: &str| {
+        JobBuilder::new()
+            .command(command)
+            .submitted_by("tester")
+            .run_dir(sandbox.root.clone())
+            .build()
+    };
+
+    assert_eq!(
+        kind(client.cancel_job(999).await.unwrap_err()),
+        ApiErrorKind::JobNotFound { id: 999 }
+    );
+
+    // Too many GPUs to ever start, so it is still queued when held.
+    let mut held = job("true");
+    held.gpus = 64;
+    held.force_queue = true;
+    let held = client.add_job(held).await.unwrap().id;
+    client.hold_job(held).await.unwrap();
+    assert_eq!(
+        kind(client.finish_job(held).await.unwrap_err()),
+        ApiErrorKind::InvalidTransition {
+            id: held,
+            from: JobState::Hold,
+            to: JobState::Finished,
+            allowed: vec![JobState::Queued, JobState::Cancelled],
+        }
+    );
+    client.cancel_job(held).await.unwrap();
+
+    let cancelled = client.add_job(job("sleep 30")).await.unwrap().id;
+    client.cancel_job(cancelled).await.unwrap();
+    let transition = |to| ApiErrorKind::invalid_transition(cancelled, JobState::Cancelled, to);
+    assert_eq!(
+        kind(client.finish_job(cancelled).await.unwrap_err()),
+        transition(JobState::Finished)
+    );
+    assert_eq!(
+        kind(client.fail_job(cancelled).await.unwrap_err()),
+        transition(JobState::Failed)
+    );
+    assert_eq!(
+        kind(client.hold_job(cancelled).await.unwrap_err()),
+        transition(JobState::Hold)
+    );
+    assert_eq!(
+        kind(client.release_job(cancelled).await.unwrap_err()),
+        transition(JobState::Queued)
+    );
+    assert_eq!(
+        kind(
+            client
+                .update_job(cancelled, UpdateJobRequest::default())
+                .await
+                .unwrap_err()
+        ),
+        ApiErrorKind::JobNotUpdatable {
+            id: cancelled,
+            state: JobState::Cancelled
+        }
+    );
+
+    let mut orphan = job("true");
+    orphan.depends_on_ids = vec![999].into();
+    assert_eq!(
+        kind(client.add_job(orphan.clone()).await.unwrap_err()),
+        ApiErrorKind::DependencyMissing { id: 999 }
+    );
+    assert_eq!(
+        kind(client.add_jobs(vec![orphan]).await.unwrap_err()),
+        ApiErrorKind::DependencyMissing { id: 999 }
+    );
+    let too_many = vec![job("true"); 1001];
+    assert_eq!(
+        kind(client.add_jobs(too_many).await.unwrap_err()),
+        ApiErrorKind::BatchTooLarge { max: 1000 }
+    );
+
+    // `first` waits on a job that never gets its GPUs, `second` on `first`;
+    // making `first` wait on `second` closes a loop.
+    let mut waiting = job("true");
+    waiting.gpus = 64;
+    waiting.force_queue = true;
+    let waiting = client.add_job(waiting).await.unwrap().id;
+    let mut first = job("true");
+    first.depends_on_ids = vec![waiting].into();
+    let first = client.add_job(first).await.unwrap().id;
+    let mut second = job("true");
+    second.depends_on_ids = vec![first].into();
+    let second = client.add_job(second).await.unwrap().id;
+    let update = UpdateJobRequest {
+        depends_on_ids: Some(vec![second]),
+        ..Default::default()
+    };
+    assert_eq!(
+        kind(client.update_job(first, update).await.unwrap_err()),
+        ApiErrorKind::CircularDependency {
+            id: first,
+            dependency: second
+        }
+    );
+
+    assert_eq!(
+        kind(client.cancel_reservation(42).await.unwrap_err()),
+        ApiErrorKind::ReservationNotFound { id: 42 }
+    );
+
+    // The wire format, including `error` for older clients.
+    let response = reqwest::Client::new()
+        .post(format!("{}/jobs/999/hold", sandbox.base_url()))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["code"], "job_not_found");
+    assert_eq!(body["details"]["id"], 999);
+    assert_eq!(body["message"], "Job 999 not found");
+    assert_eq!(body["error"], body["message"]);
+
+    sandbox.stop_daemon();
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn bulk_actions_report_each_job_and_cli_uses_them() {
+    let Some(mut sandbox) = TestSandbox::new() else {
+        return;
+    };
+
+    sandbox.start_daemon();
+    wait_for_health_status(&sandbox.base_url(), StatusCode::OK, Duration::from_secs(15)).await;
+
+    let client = gflow::Client::build(&sandbox.client_config()).unwrap();
+    // Too many GPUs to ever start, so they stay queued.
+    let mut ids = Vec::new();
+    for _ in 0..4 {
+        let mut job = JobBuilder::new()
+            .command("true")
+            .gpus(64)
+            .submitted_by("tester")
+            .run_dir(sandbox.root.clone())
+            .build();
+        job.force_queue = true;
+        ids.push(client.add_job(job).await.unwrap().id);
+    }
+    let state_of = |id: u32| {
+        let client = &client;
+        async move { client.get_job(id).await.unwrap().unwrap().state }
+    };
+
+    let results = client
+        .apply_job_action(JobAction::Hold, &[ids[0], 999, ids[1], ids[0]])
+        .await
+        .unwrap();
+    let outcome: Vec<_> = results
+        .iter()
+        .map(|result| (result.id, result.error.as_ref().map(|e| e.kind.clone())))
+        .collect();
+    assert_eq!(
+        outcome,
+        vec![
+            (ids[0], None),
+            (999, Some(ApiErrorKind::JobNotFound { id: 999 })),
+            (ids[1], None),
+            (ids[0], None),
+        ]
+    );
+    assert_eq!(state_of(ids[0]).await, JobState::Hold);
+    assert_eq!(state_of(ids[1]).await, JobState::Hold);
+    assert_eq!(state_of(ids[2]).await, JobState::Queued);
+
+    let results = client
+        .apply_job_action(JobAction::Finish, &[ids[0]])
+        .await
+        .unwrap();
+    assert_eq!(
+        results[0].error.as_ref().unwrap().kind,
+        ApiErrorKind::invalid_transition(ids[0], JobState::Hold, JobState::Finished)
+    );
+
+    let release = sandbox.run_gflow(["gjob", "release", &format!("{},{}", ids[0], ids[1])]);
+    release.assert_success("gjob release");
+    assert!(
+        release
+            .stdout
+            .contains(&format!("Job {} released back to queue.", ids[1])),
+        "{}",
+        release.stdout
+    );
+    assert_eq!(state_of(ids[0]).await, JobState::Queued);
+
+    let listed: Vec<String> = ids.iter().map(u32::to_string).collect();
+    let cancel = sandbox.run_gflow(["gcancel", "--all-users", &listed.join(",")]);
+    cancel.assert_success("gcancel");
+    for &id in &ids {
+        assert!(
+            cancel.stdout.contains(&format!("Job {id} cancelled.")),
+            "{}",
+            cancel.stdout
+        );
+        assert_eq!(state_of(id).await, JobState::Cancelled);
+    }
+
+    // gcancel reports the jobs it changed, but fails if any was left alone.
+    let partial = sandbox.run_gflow(["gcancel", "--all-users", &format!("{},999", ids[0])]);
+    assert!(!partial.status.success());
+    assert!(partial
+        .stdout
+        .contains(&format!("Job {} cancelled.", ids[0])));
+    assert!(
+        partial.stderr.contains("Job 999 not found"),
+        "{}",
+        partial.stderr
+    );
+
+    sandbox.stop_daemon();
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn daemon_filters_jobs_by_search_terms_and_name_globs() {
+    let Some(mut sandbox) = TestSandbox::new() else {
+        return;
+    };
+
+    sandbox.start_daemon();
+    wait_for_health_status(&sandbox.base_url(), StatusCode::OK, Duration::from_secs(15)).await;
+
+    let client = gflow::Client::build(&sandbox.client_config()).unwrap();
+    let submit = |command: &str, lr: &str, user: &str| {
+        let job = JobBuilder::new()
+            .command(command)
+            .parameters(std::collections::HashMap::from([(
+                "lr".to_string(),
+                lr.to_string(),
+            )]))
+            .submitted_by(user)
+            .run_dir(sandbox.root.clone())
+            .build();
+        let client = &client;
+        async move { client.add_job(job).await.unwrap().id }
+    };
+    let first = submit("echo cosine --lr {lr}", "3e-4", "alice").await;
+    let _other_lr = submit("echo cosine --lr {lr}", "1e-3", "alice").await;
+    let _linear = submit("echo linear --lr {lr}", "3e-4", "alice").await;
+    let second = submit("echo COSINE --lr {lr}", "3e-4", "alice").await;
+    let _other_user = submit("echo cosine --lr {lr}", "3e-4", "bob").await;
+
+    let ids = |jobs: Vec<gflow::core::job::Job>| jobs.iter().map(|job| job.id).collect::<Vec<_>>();
+    let find = |filter: JobFilter| {
+        let client = &client;
+        async move { ids(client.find_jobs(&filter).await.unwrap()) }
+    };
+    assert_eq!(
+        find(JobFilter {
+            user: Some("alice".into()),
+            search: Some("cosine --lr 3e-4".into()),
+            ..JobFilter::default()
+        })
+        .await,
+        vec![second, first]
+    );
+    assert!(find(JobFilter {
+        states: Some("Hold".into()),
+        search: Some("cosine".into()),
+        ..JobFilter::default()
+    })
+    .await
+    .is_empty());
+
+    let named = |name: &str| {
+        JobBuilder::new()
+            .command("echo named")
+            .run_name(Some(name.to_string()))
+            .submitted_by("alice")
+            .run_dir(sandbox.root.clone())
+            .build()
+    };
+    let resnet = client.add_job(named("train-resnet-1")).await.unwrap().id;
+    let vit = client.add_job(named("train-vit-2")).await.unwrap().id;
+    client.add_job(named("eval-resnet-1")).await.unwrap();
+    let glob = |pattern: &str| JobFilter {
+        name_glob: Some(pattern.to_string()),
+        ..JobFilter::default()
+    };
+    // Run names are stored as `gjob-<id>-<name>-<id>`.
+    assert_eq!(find(glob("*-train-*")).await, vec![resnet, vit]);
+    assert_eq!(
+        find(glob("*vit*,gjob-?-train-resnet-*")).await,
+        vec![resnet, vit]
+    );
+
+    // The limit applies to the jobs that matched.
+    let response = reqwest::Client::new()
+        .get(format!(
+            "{}/jobs?name_glob=*-train-*&limit=1",
+            sandbox.base_url()
+        ))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.headers()["x-total-count"], "2");
+    let limited: Vec<Value> = response.json().await.unwrap();
+    assert_eq!(limited.len(), 1);
+    assert_eq!(limited[0]["id"], resnet);
+
+    let (page, total) = client
+        .find_jobs_page(&glob("*-train-*"), 1, 1)
+        .await
+        .unwrap();
+    assert_eq!((ids(page), total), (vec![vit], 2));
+    let everyone = JobFilter {
+        order: Some("desc".into()),
+        ..JobFilter::default()
+    };
+    let (page, total) = client.find_jobs_page(&everyone, 2, 0).await.unwrap();
+    assert_eq!(page.len(), 2);
+    assert_eq!(total, 8);
+
+    let invalid = client.find_jobs(&glob("train-[0-9")).await.unwrap_err();
+    let rejected = invalid
+        .downcast_ref::<gflow::client::DaemonRejected>()
+        .unwrap();
+    assert_eq!(rejected.error.kind, ApiErrorKind::InvalidRequest);
+    assert!(rejected
+        .error
+        .message
+        .contains("'train-[0-9' at position 6"));
+
+    sandbox.stop_daemon();
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn polled_endpoints_answer_unchanged_requests_with_not_modified() {
+    let Some(mut sandbox) = TestSandbox::new() else {
+        return;
+    };
+
+    sandbox.start_daemon();
+    wait_for_health_status(&sandbox.base_url(), StatusCode::OK, Duration::from_secs(15)).await;
+
+    let http = reqwest::Client::new();
+    let base = sandbox.base_url();
+    let etag_of = |path: &'static str| {
+        let http = &http;
+        let base = &base;
+        async move {
+            let response = http.get(format!("{base}{path}")).send().await.unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+            response.headers()["etag"].clone()
+        }
+    };
+    let poll = |path: &'static str, etag: reqwest::header::HeaderValue| {
+        let http = &http;
+        let base = &base;
+        async move {
+            http.get(format!("{base}{path}"))
+                .header("if-none-match", etag)
+                .send()
+                .await
+                .unwrap()
+        }
+    };
+
+    for path in ["/jobs", "/info", "/stats"] {
+        let etag = etag_of(path).await;
+        let unchanged = poll(path, etag.clone()).await;
+        assert_eq!(unchanged.status(), StatusCode::NOT_MODIFIED, "{path}");
+        assert_eq!(unchanged.headers()["etag"], etag);
+        assert!(unchanged.bytes().await.unwrap().is_empty());
+    }
+
+    let before = etag_of("/jobs").await;
+    let client = gflow::Client::build(&sandbox.client_config()).unwrap();
+    let job = JobBuilder::new()
+        .command("echo polled")
+        .submitted_by("alice")
+        .run_dir(sandbox.root.clone())
+        .build();
+    client.add_job(job).await.unwrap();
+    let changed = poll("/jobs", before.clone()).await;
+    assert_eq!(changed.status(), StatusCode::OK);
+    assert_ne!(changed.headers()["etag"], before);
+
+    // Polls racing submissions see either the old listing or a new one.
+    let submitters = (0..4).map(|i| {
+        let client = client.clone();
+        let root = sandbox.root.clone();
+        tokio::spawn(async move {
+            for j in 0..5 {
+                let job = JobBuilder::new()
+                    .command(format!("echo {i}-{j}"))
+                    .submitted_by("alice")
+                    .run_dir(root.clone())
+                    .build();
+                client.add_job(job).await.unwrap();
+            }
+        })
+    });
+    let submitters: Vec<_> = submitters.collect();
+    let mut etag = etag_of("/jobs").await;
+    for _ in 0..20 {
+        let response = poll("/jobs", etag.clone()).await;
+        match response.status() {
+            StatusCode::NOT_MODIFIED => {}
+            StatusCode::OK => {
+                etag = response.headers()["etag"].clone();
+                let jobs: Vec<Value> = response.json().await.unwrap();
+                assert!(!jobs.is_empty());
+            }
+            status => panic!("unexpected {status}"),
+        }
+    }
+    for submitter in submitters {
+        submitter.await.unwrap();
+    }
+    assert_eq!(client.list_jobs().await.unwrap().len(), 21);
+
+    sandbox.stop_daemon();
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn versioned_api_serves_openapi_spec_and_keeps_legacy_aliases() {
+    let Some(mut sandbox) = TestSandbox::new() else {
+        return;
+    };
+
+    sandbox.start_daemon();
+    wait_for_health_status(&sandbox.base_url(), StatusCode::OK, Duration::from_secs(15)).await;
+
+    let http = reqwest::Client::new();
+    let base = sandbox.base_url();
+    let spec: Value = http
+        .get(format!("{base}/api/v1/openapi.json"))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert!(spec["openapi"].as_str().unwrap().starts_with("3."));
+    assert!(spec["paths"]["/jobs/{id}"].is_object());
+
+    let job = JobBuilder::new()
+        .command("sleep 30")
+        .submitted_by("tester")
+        .run_dir(sandbox.root.clone())
+        .build();
+    let created = http
+        .post(format!("{base}/api/v1/jobs"))
+        .json(&job)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(created.status(), StatusCode::CREATED);
+    let id = created.json::<Value>().await.unwrap()["id"]
+        .as_u64()
+        .unwrap();
+
+    for path in [format!("/api/v1/jobs/{id}"), format!("/jobs/{id}")] {
+        let response = http.get(format!("{base}{path}")).send().await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK, "GET {path}");
+        assert_eq!(response.json::<Value>().await.unwrap()["id"], id);
+    }
+    let cancelled = http
+        .post(format!("{base}/api/v1/jobs/{id}/cancel"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(cancelled.status(), StatusCode::OK);
+
+    sandbox.stop_daemon();
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn batch_submissions_survive_a_killed_daemon() {
+    let Some(mut sandbox) = TestSandbox::new() else {
+        return;
+    };
+
+    // A save interval far beyond the test's runtime leaves the flush done
+    // before replying to the batch as the only write.
+    std::fs::write(
+        sandbox.config_home.join("gflow/gflow.toml"),
+        format!(
+            "[daemon]\nhost = \"127.0.0.1\"\nport = {}\nstate_save_interval_ms = 600000\n",
+            sandbox.port
+        ),
+    )
+    .unwrap();
+
+    sandbox.start_daemon();
+    let health =
+        wait_for_health_status(&sandbox.base_url(), StatusCode::OK, Duration::from_secs(15)).await;
+    let pid = health["pid"].as_u64().unwrap() as u32;
+
+    let client = gflow::Client::build(&sandbox.client_config()).unwrap();
+    let jobs = (0..3)
+        .map(|_| {
+            JobBuilder::new()
+                .command("sleep 30")
+                .submitted_by("tester")
+                .run_dir(sandbox.root.clone())
+                .build()
+        })
+        .collect();
+    let submitted: Vec<u32> = client
+        .add_jobs(jobs)
+        .await
+        .unwrap()
+        .iter()
+        .map(|response| response.id)
+        .collect();
+    assert_eq!(submitted.len(), 3);
+
+    let kill = Command::new("kill")
+        .args(["-KILL", &pid.to_string()])
+        .output()
+        .unwrap();
+    assert!(kill.status.success(), "failed to kill gflowd {pid}");
+    let start = Instant::now();
+    while process_running(pid) && start.elapsed() < Duration::from_secs(10) {
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    assert!(!process_running(pid), "gflowd {pid} survived SIGKILL");
+    wait_for_health_unreachable(&sandbox.base_url(), Duration::from_secs(10)).await;
+
+    // The daemon's tmux session outlives it; restart replaces the stale one.
+    sandbox
+        .run_gflow(["gflowd", "restart"])
+        .assert_success("gflowd restart");
+    wait_for_health_status(&sandbox.base_url(), StatusCode::OK, Duration::from_secs(15)).await;
+
+    let mut recovered: Vec<u32> = client
+        .list_jobs()
+        .await
+        .unwrap()
+        .iter()
+        .map(|job| job.id)
+        .collect();
+    recovered.sort();
+    assert_eq!(recovered, submitted);
+
+    sandbox.stop_daemon();
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn restore_refuses_to_reuse_job_ids_unless_forced() {
+    let Some(mut sandbox) = TestSandbox::new() else {
+        return;
+    };
+
+    sandbox.start_daemon();
+    wait_for_health_status(&sandbox.base_url(), StatusCode::OK, Duration::from_secs(15)).await;
+
+    let client = gflow::Client::build(&sandbox.client_config()).unwrap();
+    let job = || {
+        JobBuilder::new()
+            .command("sleep 30")
+            .submitted_by("tester")
+            .run_dir(sandbox.root.clone())
+            .build()
+    };
+    client.add_job(job()).await.unwrap();
+    sandbox
+        .run_gflow(["gflowd", "backup", "now"])
+        .assert_success("gflowd backup now");
+    client.add_job(job()).await.unwrap();
+    client.add_job(job()).await.unwrap();
+
+    let listed = sandbox.run_gflow(["gflowd", "backup", "list"]);
+    listed.assert_success("gflowd backup list");
+    let backup = listed
+        .stdout
+        .lines()
+        .find_map(|line| {
+            line.split_whitespace()
+                .next()
+                .filter(|name| name.starts_with("state-"))
+        })
+        .expect("a backup is listed")
+        .to_string();
+
+    let refused = sandbox.run_gflow(["gflowd", "restore", &backup]);
+    assert!(!refused.status.success(), "restore reused job IDs");
+    assert!(refused.stderr.contains("--force"), "{}", refused.stderr);
+    assert_eq!(client.list_jobs().await.unwrap().len(), 3);
+
+    sandbox
+        .run_gflow(["gflowd", "restore", &backup, "--force"])
+        .assert_success("gflowd restore --force");
+    let ids: Vec<u32> = client
+        .list_jobs()
+        .await
+        .unwrap()
+        .iter()
+        .map(|job| job.id)
+        .collect();
+    assert_eq!(ids, [1]);
+    // Forced: numbering resumes where the backup left off.
+    assert_eq!(client.add_job(job()).await.unwrap().id, 2);
+
+    sandbox.stop_daemon();
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn cli_works_over_unix_socket() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let Some(mut sandbox) = TestSandbox::new() else {
+        return;
+    };
+
+    // The socket directory does not exist yet; gflowd creates it.
+    let socket_path = sandbox.runtime_dir.join("gflow-sock").join("gflow.sock");
+    std::fs::write(
+        sandbox.config_home.join("gflow/gflow.toml"),
+        format!("[daemon]\nlisten = \"unix:{}\"\n", socket_path.display()),
+    )
+    .unwrap();
+    let mut config = sandbox.client_config();
+    config.daemon.listen = Some(gflow::config::ListenAddr::Unix(socket_path.clone()));
+    let client = gflow::Client::build(&config).unwrap();
+
+    sandbox.start_daemon();
+    let start = Instant::now();
+    while client.get_health().await.ok() != Some(StatusCode::OK) {
+        assert!(
+            start.elapsed() < Duration::from_secs(15),
+            "daemon did not come up on {}",
+            socket_path.display()
+        );
+        tokio::time::sleep(Duration::from_millis(200)).await;
+    }
+
+    let mode = |path: &Path| std::fs::metadata(path).unwrap().permissions().mode() & 0o777;
+    assert_eq!(mode(&socket_path), 0o600);
+    assert_eq!(mode(socket_path.parent().unwrap()), 0o700);
+    assert!(
+        get_health(&sandbox.base_url()).await.is_err(),
+        "TCP port should not be served when listening on a Unix socket"
+    );
+
+    let status = sandbox.run_gflow(["gflowd", "status"]);
+    status.assert_success("gflowd status over Unix socket");
+    assert!(status.stdout.contains("Status: Running"));
+    assert!(status
+        .stdout
+        .contains(&format!("Address: unix:{}", socket_path.display())));
+
+    let submit = sandbox.run_gflow(["gbatch", "echo", "over-socket"]);
+    submit.assert_success("gbatch over Unix socket");
+    let queue = sandbox.run_gflow(["gqueue", "-a"]);
+    queue.assert_success("gqueue over Unix socket");
+    assert!(queue.stdout.contains("gjob-1"), "stdout:\n{}", queue.stdout);
+    let reload = sandbox.run_gflow(["gctl", "reload-config"]);
+    reload.assert_success("gctl reload-config over Unix socket");
+
+    sandbox.stop_daemon();
+    assert!(!socket_path.exists(), "socket should be removed after down");
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn health_reports_recovery_mode_for_corrupt_state() {
+    let Some(mut sandbox) = TestSandbox::new() else {
+        return;
+    };
+
+    std::fs::create_dir_all(sandbox.data_dir()).unwrap();
+    std::fs::write(
+        sandbox.data_dir().join("state.json"),
+        b"{ definitely-not-json",
+    )
+    .unwrap();
+
+    sandbox.start_daemon();
+
+    let health =
+        wait_for_health_status(&sandbox.base_url(), StatusCode::OK, Duration::from_secs(15)).await;
+    assert_eq!(health["status"], "recovery");
+    assert_eq!(health["mode"], "journal");
+    assert!(health["pid"].as_u64().is_some());
+    assert!(health["detail"]
+        .as_str()
+        .unwrap()
+        .contains("entered recovery mode"));
+
+    let backup = health["state_backup"].as_str().unwrap();
+    assert!(
+        Path::new(backup).exists(),
+        "backup path should exist: {backup}"
+    );
+
+    // Journal recovery still accepts jobs, so the daemon is live and ready.
+    let client = reqwest::Client::new();
+    let healthz = client
+        .get(format!("{}/healthz", sandbox.base_url()))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(healthz.status(), StatusCode::OK);
+
+    let readyz = client
+        .get(format!("{}/readyz", sandbox.base_url()))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(readyz.status(), StatusCode::OK);
+    let readyz: Value = readyz.json().await.unwrap();
+    assert_eq!(readyz["mode"], "journal");
+
+    let startupz: Value = client
+        .get(format!("{}/startupz", sandbox.base_url()))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(startupz["status"], "complete");
+    assert_eq!(startupz["jobs_loaded"], 0);
+
+    sandbox.stop_daemon();
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn require_auth_enforces_tokens_and_job_ownership() {
+    let Some(mut sandbox) = TestSandbox::new() else {
+        return;
+    };
+
+    std::fs::write(
+        sandbox.config_home.join("gflow/gflow.toml"),
+        format!(
+            "[daemon]\nhost = \"127.0.0.1\"\nport = {}\nrequire_auth = true\n",
+            sandbox.port
+        ),
+    )
+    .unwrap();
+    let create_token = |args: &[&str]| {
+        let result = sandbox.run_gflow(["gflowd", "token", "create"].iter().chain(args));
+        result.assert_success("gflowd token create");
+        result
+            .stdout
+            .lines()
+            .find(|line| line.starts_with("gflow_"))
+            .expect("token should be printed")
+            .to_string()
+    };
+    let alice_token = create_token(&["alice"]);
+    let bob_token = create_token(&["bob"]);
+    let admin_token = create_token(&["root", "--admin"]);
+
+    sandbox.start_daemon();
+    wait_for_health_status(&sandbox.base_url(), StatusCode::OK, Duration::from_secs(15)).await;
+
+    let config = sandbox.client_config();
+    let alice = gflow::Client::build_with_token(&config, Some(&alice_token)).unwrap();
+    let bob = gflow::Client::build_with_token(&config, Some(&bob_token)).unwrap();
+    let admin = gflow::Client::build_with_token(&config, Some(&admin_token)).unwrap();
+    let anonymous = gflow::Client::build_with_token(&config, None).unwrap();
+
+    let job = || {
+        JobBuilder::new()
+            .submitted_by("bob")
+            .run_dir(&sandbox.work_dir)
+            .command("sleep 30")
+            .auto_close_tmux(true)
+            .build()
+    };
+
+    // Mutations need a token; reads stay open with the default anonymous_read.
+    let err = anonymous.add_job(job()).await.unwrap_err();
+    assert!(err.to_string().contains("Authentication required"), "{err}");
+    let raw = reqwest::Client::new()
+        .post(format!("{}/jobs/1/cancel", sandbox.base_url()))
+        .bearer_auth("gflow_bogus_token")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(raw.status(), StatusCode::UNAUTHORIZED);
+    assert_eq!(raw.json::<Value>().await.unwrap()["code"], "unauthorized");
+    anonymous.list_jobs().await.unwrap();
+
+    // The token's user wins over the claimed submitted_by.
+    let response = alice.add_job(job()).await.unwrap();
+    let submitted = anonymous.get_job(response.id).await.unwrap().unwrap();
+    assert_eq!(submitted.submitted_by, "alice");
+
+    let err = bob.cancel_job(response.id).await.unwrap_err();
+    let rejected = err.downcast_ref::<gflow::client::DaemonRejected>().unwrap();
+    assert_eq!(rejected.status, StatusCode::FORBIDDEN);
+    assert_eq!(rejected.error.kind, ApiErrorKind::Forbidden);
+    let err = alice.set_allowed_gpus(None).await.unwrap_err();
+    assert!(err.to_string().contains("not an admin"), "{err}");
+    let versioned = reqwest::Client::new()
+        .post(format!("{}/api/v1/gpus", sandbox.base_url()))
+        .bearer_auth(&alice_token)
+        .json(&serde_json::json!({ "allowed_indices": null }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(versioned.status(), StatusCode::FORBIDDEN);
+
+    admin.cancel_job(response.id).await.unwrap();
+    wait_for_job_state(
+        &anonymous,
+        response.id,
+        JobState::Cancelled,
+        Duration::from_secs(10),
+    )
+    .await;
+
+    // The job reports its own completion with the GFLOW_JOB_TOKEN it was given.
+    let quick = JobBuilder::new()
+        .submitted_by("alice")
+        .run_dir(&sandbox.work_dir)
+        .command("echo authenticated")
+        .auto_close_tmux(true)
+        .build();
+    let response = alice.add_job(quick).await.unwrap();
+    wait_for_job_state(
+        &anonymous,
+        response.id,
+        JobState::Finished,
+        Duration::from_secs(20),
+    )
+    .await;
+
+    sandbox.stop_daemon();
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn serves_https_on_tcp_and_plain_http_on_extra_unix_socket() {
+    let Some(mut sandbox) = TestSandbox::new() else {
+        return;
+    };
+
+    let cert = rcgen::generate_simple_self_signed(vec!["localhost".to_string()]).unwrap();
+    let cert_path = sandbox.root.join("cert.pem");
+    let key_path = sandbox.root.join("key.pem");
+    std::fs::write(&cert_path, cert.cert.pem()).unwrap();
+    std::fs::write(&key_path, cert.signing_key.serialize_pem()).unwrap();
+    let socket_path = sandbox.runtime_dir.join("gflow.sock");
+    std::fs::write(
+        sandbox.config_home.join("gflow/gflow.toml"),
+        format!(
+            "[daemon]\nhost = \"localhost\"\nport = {}\nextra_listen = [\"unix:{}\"]\ntls_cert = \"{}\"\ntls_key = \"{}\"\n",
+            sandbox.port,
+            socket_path.display(),
+            cert_path.display(),
+            key_path.display()
+        ),
+    )
+    .unwrap();
+
+    sandbox.start_daemon();
+
+    let mut config = sandbox.client_config();
+    config.daemon.host = "localhost".to_string();
+    config.daemon.tls_ca = Some(cert_path.clone());
+    let https = gflow::Client::build(&config).unwrap();
+    let start = Instant::now();
+    while https.get_health().await.ok() != Some(StatusCode::OK) {
+        assert!(
+            start.elapsed() < Duration::from_secs(15),
+            "daemon did not come up over HTTPS"
+        );
+        tokio::time::sleep(Duration::from_millis(200)).await;
+    }
+    assert!(
+        get_health(&format!("http://localhost:{}", sandbox.port))
+            .await
+            .is_err(),
+        "plain HTTP must not be served on the TLS port"
+    );
+
+    // Without the CA the self-signed certificate is rejected, unless verification is skipped.
+    config.daemon.tls_ca = None;
+    config.daemon.insecure_skip_verify = true;
+    let insecure = gflow::Client::build(&config).unwrap();
+    assert_eq!(insecure.get_health().await.unwrap(), StatusCode::OK);
+    config.daemon.insecure_skip_verify = false;
+    config.daemon.tls_ca = Some(sandbox.root.join("other-ca.pem"));
+    std::fs::write(
+        sandbox.root.join("other-ca.pem"),
+        rcgen::generate_simple_self_signed(vec!["localhost".to_string()])
+            .unwrap()
+            .cert
+            .pem(),
+    )
+    .unwrap();
+    let untrusted = gflow::Client::build(&config).unwrap();
+    assert!(untrusted.get_health().await.is_err());
+
+    let mut unix_config = sandbox.client_config();
+    unix_config.daemon.listen = Some(gflow::config::ListenAddr::Unix(socket_path));
+    let unix = gflow::Client::build(&unix_config).unwrap();
+    assert_eq!(unix.get_health().await.unwrap(), StatusCode::OK);
+
+    let status = sandbox.run_gflow(["gflowd", "status"]);
+    status.assert_success("gflowd status over HTTPS");
+    assert!(
+        status.stdout.contains("Status: Running"),
+        "stdout:\n{}",
+        status.stdout
+    );
+
+    sandbox.stop_daemon();
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn readonly_bind_serves_only_read_endpoints() {
+    let Some(mut sandbox) = TestSandbox::new() else {
+        return;
+    };
+
+    let readonly_port = pick_unused_port();
+    std::fs::write(
+        sandbox.config_home.join("gflow/gflow.toml"),
+        format!(
+            "[daemon]\nhost = \"127.0.0.1\"\nport = {}\nreadonly_bind = \"127.0.0.1:{readonly_port}\"\n",
+            sandbox.port
+        ),
+    )
+    .unwrap();
+
+    sandbox.start_daemon();
+    wait_for_health_status(&sandbox.base_url(), StatusCode::OK, Duration::from_secs(15)).await;
+
+    let submit = sandbox.run_gflow(["gbatch", "sleep", "30"]);
+    submit.assert_success("gbatch on the main port");
+
+    let readonly_url = format!("http://127.0.0.1:{readonly_port}");
+    let http = reqwest::Client::new();
+    for path in [
+        "/jobs",
+        "/jobs/1",
+        "/info",
+        "/info/stats",
+        "/health",
+        "/stats",
+        "/report?group_by=day",
+        "/jobs/estimates",
+        "/stats/runtimes?name=train-*",
+        "/api/v1/jobs",
+        "/api/v1/openapi.json",
+    ] {
+        let response = http
+            .get(format!("{readonly_url}{path}"))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK, "GET {path}");
+    }
+
+    // Mutating routes do not exist on this listener: 404, or 405 where a GET
+    // route shares the path.
+    let assert_unreachable = |status: StatusCode, what: &str| {
+        assert!(
+            matches!(
+                status,
+                StatusCode::NOT_FOUND | StatusCode::METHOD_NOT_ALLOWED
+            ),
+            "{what} returned {status}"
+        );
+    };
+    for path in [
+        "/jobs",
+        "/jobs/batch",
+        "/jobs/1/cancel",
+        "/jobs/1/finish",
+        "/jobs/1/hold",
+        "/gpus",
+        "/config/reload",
+        "/reservations",
+        "/api/v1/jobs/1/cancel",
+        "/api/v1/gpus",
+    ] {
+        let response = http
+            .post(format!("{readonly_url}{path}"))
+            .json(&Value::Null)
+            .send()
+            .await
+            .unwrap();
+        assert_unreachable(response.status(), &format!("POST {path}"));
+    }
+    let patch = http
+        .patch(format!("{readonly_url}/jobs/1"))
+        .json(&serde_json::json!({ "priority": 1 }))
+        .send()
+        .await
+        .unwrap();
+    assert_unreachable(patch.status(), "PATCH /jobs/1");
+    let debug = http
+        .get(format!("{readonly_url}/debug/state"))
+        .send()
+        .await
+        .unwrap();
+    assert_unreachable(debug.status(), "GET /debug/state");
+
+    // CLI read commands work unchanged when pointed at the read-only port.
+    let dashboard_config = sandbox.root.join("dashboard.toml");
+    std::fs::write(
+        &dashboard_config,
+        format!("[daemon]\nhost = \"127.0.0.1\"\nport = {readonly_port}\n"),
+    )
+    .unwrap();
+    let config_arg = dashboard_config.display().to_string();
+    let queue = sandbox.run_gflow(["gqueue", "-a", "--config", &config_arg]);
+    queue.assert_success("gqueue against the read-only port");
+    assert!(queue.stdout.contains("gjob-1"), "stdout:\n{}", queue.stdout);
+    sandbox
+        .run_gflow(["ginfo", "--config", &config_arg])
+        .assert_success("ginfo against the read-only port");
+    let client = gflow::Client::build(&sandbox.client_config()).unwrap();
+    let job = client.get_job(1).await.unwrap().unwrap();
+    assert_ne!(job.state, JobState::Cancelled);
+    sandbox.run_gflow(["gcancel", "1", "--config", &config_arg]);
+    let job = client.get_job(1).await.unwrap().unwrap();
+    assert_ne!(
+        job.state,
+        JobState::Cancelled,
+        "gcancel must not work on the read-only port"
     );
 
     sandbox.stop_daemon();
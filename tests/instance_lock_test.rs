@@ -0,0 +1,115 @@
+//! Two daemons started on one data directory: the second refuses to run,
+//! unless it is told to take over.
+
+use std::path::PathBuf;
+use std::process::{Child, Command, Stdio};
+use std::time::{Duration, Instant};
+
+struct Env {
+    root: tempfile::TempDir,
+}
+
+impl Env {
+    fn new() -> Self {
+        let root = tempfile::tempdir().unwrap();
+        let config_dir = root.path().join("config/gflow");
+        std::fs::create_dir_all(&config_dir).unwrap();
+        let port = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        std::fs::write(
+            config_dir.join("gflow.toml"),
+            format!("[daemon]\nport = {port}\n"),
+        )
+        .unwrap();
+        Self { root }
+    }
+
+    fn gflowd(&self, args: &[&str]) -> Command {
+        let root = self.root.path();
+        let mut command = Command::new(env!("CARGO_BIN_EXE_gflow"));
+        command
+            .env_clear()
+            .env("PATH", std::env::var_os("PATH").unwrap_or_default())
+            .env("HOME", root)
+            .env("XDG_CONFIG_HOME", root.join("config"))
+            .env("XDG_DATA_HOME", root.join("data"))
+            .env("XDG_RUNTIME_DIR", root.join("run"))
+            .arg("gflowd")
+            .args(args)
+            .current_dir(root)
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped());
+        command
+    }
+
+    fn lock_file(&self) -> PathBuf {
+        self.root.path().join("data/gflow/gflowd.lock")
+    }
+
+    /// Start a daemon and wait until it holds the lock.
+    fn start(&self, args: &[&str]) -> Child {
+        let mut child = self.gflowd(args).stderr(Stdio::null()).spawn().unwrap();
+        let pid = child.id().to_string();
+        let deadline = Instant::now() + Duration::from_secs(30);
+        while std::fs::read_to_string(self.lock_file())
+            .unwrap_or_default()
+            .trim()
+            != pid
+        {
+            if let Some(status) = child.try_wait().unwrap() {
+                panic!("gflowd exited with {status} before taking the lock");
+            }
+            assert!(Instant::now() < deadline, "gflowd never took the lock");
+            std::thread::sleep(Duration::from_millis(100));
+        }
+        child
+    }
+}
+
+/// Wait for `child` to exit, killing it if it takes too long.
+fn wait_exit(child: &mut Child) -> bool {
+    let deadline = Instant::now() + Duration::from_secs(30);
+    while Instant::now() < deadline {
+        if child.try_wait().unwrap().is_some() {
+            return true;
+        }
+        std::thread::sleep(Duration::from_millis(100));
+    }
+    child.kill().ok();
+    false
+}
+
+#[test]
+fn second_daemon_refuses_unless_taking_over() {
+    let env = Env::new();
+    let mut first = env.start(&[]);
+
+    let second = env.gflowd(&[]).output().unwrap();
+    assert!(!second.status.success());
+    let stderr = String::from_utf8_lossy(&second.stderr);
+    assert!(
+        stderr.contains(&format!("gflowd (PID {}) is already running", first.id())),
+        "{stderr}"
+    );
+
+    let mut third = env.start(&["--takeover"]);
+    assert!(wait_exit(&mut first), "the first daemon did not shut down");
+
+    // After a crash the lock is free again, though the file still names the
+    // dead daemon.
+    third.kill().unwrap();
+    third.wait().unwrap();
+    let stale = std::fs::read_to_string(env.lock_file()).unwrap();
+    assert_eq!(stale.trim(), third.id().to_string());
+    let mut fourth = env.start(&[]);
+
+    // A clean shutdown leaves the file empty.
+    unsafe {
+        libc::kill(fourth.id() as libc::pid_t, libc::SIGTERM);
+    }
+    assert!(wait_exit(&mut fourth), "the last daemon did not shut down");
+    assert_eq!(std::fs::read_to_string(env.lock_file()).unwrap(), "");
+}